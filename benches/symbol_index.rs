@@ -0,0 +1,108 @@
+//! Symbol index cold-load benchmarks
+//!
+//! Compares loading a 100k-symbol synthetic index from `symbol_index.jsonl`
+//! against its binary copy, `symbol_index.bin`.
+//!
+//! Run with: cargo bench --bench symbol_index
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::io::Write;
+use std::time::Duration;
+
+use semfora_engine::cache::{symbol_bin, CacheDir, SymbolIndexEntry};
+use semfora_engine::schema::FrameworkEntryPoint;
+
+/// Symbols in the synthetic index
+const SYMBOLS: usize = 100_000;
+
+/// Symbols per synthetic source file
+const SYMBOLS_PER_FILE: usize = 25;
+
+/// Build a cache holding a synthetic index and its binary copy
+fn setup_index() -> (tempfile::TempDir, CacheDir) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache = CacheDir {
+        root: temp_dir.path().join("cache"),
+        repo_root: temp_dir.path().to_path_buf(),
+        repo_hash: "bench_symbol_index".to_string(),
+    };
+    std::fs::create_dir_all(&cache.root).unwrap();
+
+    let entries: Vec<SymbolIndexEntry> = (0..SYMBOLS)
+        .map(|i| {
+            let file = i / SYMBOLS_PER_FILE;
+            SymbolIndexEntry {
+                symbol: format!("handle_request_{}", i),
+                hash: format!("{:08x}:{:08x}", file, i),
+                semantic_hash: String::new(),
+                kind: ["function", "class", "method"][i % 3].to_string(),
+                module: format!("services.module{}", file % 200),
+                file: format!("src/services/module{}/file{}.ts", file % 200, file),
+                lines: format!("{}-{}", (i % 25) * 20 + 1, (i % 25) * 20 + 18),
+                risk: ["low", "medium", "high"][i % 3].to_string(),
+                cognitive_complexity: i % 17,
+                max_nesting: i % 5,
+                is_escape_local: false,
+                framework_entry_point: if i % 50 == 0 {
+                    FrameworkEntryPoint::ExpressRoute
+                } else {
+                    FrameworkEntryPoint::None
+                },
+                is_exported: i % 2 == 0,
+                decorators: String::new(),
+                arity: i % 4,
+                is_async: i % 3 == 0,
+                return_type: if i % 2 == 0 {
+                    "Promise<Response>".to_string()
+                } else {
+                    String::new()
+                },
+                ext_package: String::new(),
+                base_classes: String::new(),
+                feature_flags: String::new(),
+                is_deprecated: false,
+                deprecation_message: String::new(),
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: 0,
+                sql_tables: String::new(),
+                partial_of: String::new(),
+                parent: String::new(),
+                is_generated: false,
+            }
+        })
+        .collect();
+
+    let mut file = std::fs::File::create(cache.symbol_index_path()).unwrap();
+    for entry in &entries {
+        writeln!(file, "{}", serde_json::to_string(entry).unwrap()).unwrap();
+    }
+    drop(file);
+
+    let fingerprint = symbol_bin::TextFingerprint::of(&cache).unwrap();
+    symbol_bin::write(&cache, &entries, fingerprint).unwrap();
+    (temp_dir, cache)
+}
+
+fn bench_cold_load(c: &mut Criterion) {
+    let (_temp_dir, cache) = setup_index();
+    assert_eq!(symbol_bin::load(&cache).map(|e| e.len()), Some(SYMBOLS));
+
+    let mut group = c.benchmark_group("symbol_index_cold_load");
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(10));
+
+    group.bench_function("jsonl_100k", |b| {
+        b.iter(|| black_box(cache.load_text_symbol_entries().unwrap()))
+    });
+    group.bench_function("binary_100k", |b| {
+        b.iter(|| black_box(symbol_bin::load(&cache).unwrap()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_cold_load);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+//! Build script: embeds the compiled security pattern database
+//!
+//! Writes `$OUT_DIR/embedded_patterns.bin`, which
+//! `PatternDatabase::load_embedded` includes in the binary, and sets
+//! `SEMFORA_PATTERNS_SIZE` to its length.
+//!
+//! The database comes from `SECURITY_PATTERNS_PATH`, a file written by
+//! `semfora-security-compiler compile`. A build script can't call into the
+//! crate it builds, so without one the file is left empty and
+//! `load_embedded` compiles the manually curated patterns
+//! (`patterns::manual::all_patterns`) on first use instead.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=SECURITY_PATTERNS_PATH");
+
+    let bytes = match env::var("SECURITY_PATTERNS_PATH") {
+        Ok(path) if !path.is_empty() => {
+            println!("cargo:rerun-if-changed={}", path);
+            fs::read(&path).unwrap_or_else(|e| {
+                panic!("Failed to read SECURITY_PATTERNS_PATH '{}': {}", path, e)
+            })
+        }
+        _ => Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(out_dir.join("embedded_patterns.bin"), &bytes)
+        .expect("Failed to write embedded_patterns.bin");
+    println!("cargo:rustc-env=SEMFORA_PATTERNS_SIZE={}", bytes.len());
+}
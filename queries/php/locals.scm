@@ -0,0 +1,101 @@
+; PHP locals.scm - Variable and reference tracking
+; Adapted from nvim-treesitter (MIT License)
+
+; SCOPES
+(program) @local.scope
+
+(class_declaration
+  body: (_) @local.scope)
+
+(trait_declaration
+  body: (_) @local.scope)
+
+(interface_declaration
+  body: (_) @local.scope)
+
+(enum_declaration
+  body: (_) @local.scope)
+
+(compound_statement) @local.scope
+
+; Functions
+(function_definition) @local.scope
+
+(method_declaration) @local.scope
+
+(anonymous_function) @local.scope
+
+(arrow_function) @local.scope
+
+; loops
+(foreach_statement) @local.scope
+
+(for_statement) @local.scope
+
+(while_statement) @local.scope
+
+(do_statement) @local.scope
+
+; try/catch
+(try_statement) @local.scope
+
+(catch_clause) @local.scope
+
+; DEFINITIONS
+(namespace_definition
+  name: (namespace_name) @local.definition.namespace)
+
+(class_declaration
+  name: (name) @local.definition.type)
+
+(interface_declaration
+  name: (name) @local.definition.type)
+
+(trait_declaration
+  name: (name) @local.definition.type)
+
+(enum_declaration
+  name: (name) @local.definition.enum)
+
+(function_definition
+  name: (name) @local.definition.function)
+
+(method_declaration
+  name: (name) @local.definition.method)
+
+(assignment_expression
+  left: (variable_name
+    (name) @local.definition.var))
+
+(simple_parameter
+  name: (variable_name
+    (name) @local.definition.parameter))
+
+(property_promotion_parameter
+  name: (variable_name
+    (name) @local.definition.field))
+
+(catch_clause
+  name: (variable_name
+    (name) @local.definition.parameter))
+
+(property_element
+  name: (variable_name
+    (name) @local.definition.field))
+
+(const_element
+  (name) @local.definition.constant)
+
+; REFERENCES
+(variable_name
+  (name) @local.reference)
+
+(member_access_expression
+  name: (name) @local.reference)
+
+(class_constant_access_expression
+  (name) @local.reference)
+
+(scoped_property_access_expression
+  name: (variable_name
+    (name) @local.reference))
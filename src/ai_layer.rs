@@ -0,0 +1,609 @@
+//! AI layer: proposed edits staged by agents
+//!
+//! Agents stage proposed file contents (the `stage_ai_edit` MCP tool) before
+//! writing them to disk. Each staged file is parsed and its symbols are placed
+//! in the [`LayerKind::AI`] overlay, which shadows the Base/Branch/Working
+//! layers. Query handlers given the layer through
+//! [`CommandContext::ai_layer`](crate::commands::CommandContext) answer as if
+//! the staged files had been written:
+//!
+//! - symbols of a staged file replace the indexed symbols of that file
+//! - symbols the edit removes are tombstoned in the overlay
+//! - calls made by staged symbols are resolved against the indexed symbols
+//!
+//! The layer lives only in memory. It is never written to shard files or the
+//! layer cache (`CacheDir::save_layer` skips the AI layer).
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::analysis::{calculate_cognitive_complexity, max_nesting_depth};
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::error::{McpDiffError, Result};
+use crate::lang::Lang;
+use crate::overlay::{
+    compute_symbol_hash, extract_file_hash, is_stale_ai_entry, LayerKind, Overlay, SymbolState,
+};
+use crate::parsing::parse_and_extract;
+use crate::schema::{SemanticSummary, SymbolInfo};
+use crate::shard::extract_module_name;
+
+/// A proposed file content staged in the AI layer
+#[derive(Debug, Clone)]
+pub struct StagedFile {
+    /// Repository-relative path
+    pub path: String,
+    /// Path as recorded in the symbol index (the input of symbol hashes)
+    pub index_file: String,
+    /// Module the file belongs to
+    pub module: String,
+    /// Proposed content
+    pub content: String,
+    /// Semantic summary of the proposed content
+    pub summary: SemanticSummary,
+    /// Hashes of the staged symbols, parallel to `summary.symbols`
+    pub hashes: Vec<String>,
+    /// Hashes of indexed symbols the edit removes
+    pub removed: Vec<String>,
+    /// Number of staged symbols that are not in the index
+    pub added: usize,
+    /// When the content was staged (Unix timestamp)
+    pub staged_at: u64,
+}
+
+/// Result of staging one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageOutcome {
+    pub path: String,
+    pub symbols: usize,
+    pub added: usize,
+    pub modified: usize,
+    pub removed: usize,
+}
+
+/// Staged edits and the AI overlay built from them
+#[derive(Debug, Clone)]
+pub struct AiLayer {
+    files: BTreeMap<String, StagedFile>,
+    overlay: Overlay,
+}
+
+impl Default for AiLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiLayer {
+    /// Create an empty AI layer
+    pub fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            overlay: Overlay::new(LayerKind::AI),
+        }
+    }
+
+    /// Check if nothing is staged
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Staged files, ordered by path
+    pub fn files(&self) -> impl Iterator<Item = &StagedFile> {
+        self.files.values()
+    }
+
+    /// The AI overlay (staged symbols and tombstones)
+    pub fn overlay(&self) -> &Overlay {
+        &self.overlay
+    }
+
+    /// Stage proposed content for `path`, replacing any earlier staged content
+    ///
+    /// `path` may be repository-relative or absolute within the repository.
+    /// The file does not need to exist on disk.
+    pub fn stage(&mut self, cache: &CacheDir, path: &str, content: String) -> Result<StageOutcome> {
+        let rel = relative_path(&cache.repo_root, path)?;
+        let full_path = cache.repo_root.join(&rel);
+        let lang = Lang::from_path(&full_path)?;
+
+        // Reuse the indexed path so staged hashes line up with indexed ones
+        let indexed: Vec<SymbolIndexEntry> = cache
+            .load_all_symbol_entries()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| Path::new(&e.file).ends_with(&rel))
+            .collect();
+        let index_file = indexed
+            .first()
+            .map(|e| e.file.clone())
+            .unwrap_or_else(|| full_path.to_string_lossy().to_string());
+        let module = indexed
+            .first()
+            .map(|e| e.module.clone())
+            .unwrap_or_else(|| extract_module_name(&index_file));
+
+        let summary = parse_and_extract(Path::new(&index_file), &content, lang)?;
+        let hashes: Vec<String> = summary
+            .symbols
+            .iter()
+            .map(|symbol| compute_symbol_hash(symbol, &index_file))
+            .collect();
+
+        let removed: Vec<String> = indexed
+            .iter()
+            .filter(|e| !hashes.contains(&e.hash))
+            .map(|e| e.hash.clone())
+            .collect();
+        let added = hashes
+            .iter()
+            .filter(|h| !indexed.iter().any(|e| &e.hash == *h))
+            .count();
+
+        let outcome = StageOutcome {
+            path: rel.clone(),
+            symbols: hashes.len(),
+            added,
+            modified: hashes.len() - added,
+            removed: removed.len(),
+        };
+
+        self.files.insert(
+            rel.clone(),
+            StagedFile {
+                path: rel,
+                index_file,
+                module,
+                content,
+                summary,
+                hashes,
+                removed,
+                added,
+                staged_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            },
+        );
+        self.rebuild_overlay();
+
+        Ok(outcome)
+    }
+
+    /// Drop staged edits: one file, or everything when `path` is `None`
+    ///
+    /// Returns the paths that were unstaged.
+    pub fn clear(&mut self, repo_root: &Path, path: Option<&str>) -> Vec<String> {
+        let cleared = match path {
+            Some(path) => relative_path(repo_root, path)
+                .ok()
+                .and_then(|rel| self.files.remove(&rel))
+                .map(|file| vec![file.path])
+                .unwrap_or_default(),
+            None => std::mem::take(&mut self.files).into_keys().collect(),
+        };
+        self.rebuild_overlay();
+        cleared
+    }
+
+    /// Drop staged edits older than `max_age_secs` whose file was never
+    /// written to the working tree (see [`is_stale_ai_entry`])
+    ///
+    /// Returns the paths that were unstaged.
+    pub fn prune_stale(&mut self, max_age_secs: u64) -> Vec<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let stale: Vec<String> = self
+            .files
+            .values()
+            .filter(|f| {
+                is_stale_ai_entry(
+                    f.staged_at,
+                    Some(Path::new(&f.index_file)),
+                    max_age_secs,
+                    now,
+                )
+            })
+            .map(|f| f.path.clone())
+            .collect();
+        for path in &stale {
+            self.files.remove(path);
+        }
+        if !stale.is_empty() {
+            self.rebuild_overlay();
+        }
+        stale
+    }
+
+    /// Rebuild the overlay from the staged files
+    fn rebuild_overlay(&mut self) {
+        let mut overlay = Overlay::new(LayerKind::AI);
+        for file in self.files.values() {
+            for (symbol, hash) in file.summary.symbols.iter().zip(&file.hashes) {
+                overlay.upsert(
+                    hash.clone(),
+                    SymbolState::active_at(symbol.clone(), PathBuf::from(&file.index_file))
+                        .with_created_at(file.staged_at),
+                );
+            }
+            for hash in &file.removed {
+                overlay.delete(hash);
+            }
+        }
+        self.overlay = overlay;
+    }
+
+    /// Staged file an indexed path refers to
+    pub fn staged_file(&self, file: &str) -> Option<&StagedFile> {
+        self.files
+            .values()
+            .find(|f| f.index_file == file || Path::new(file).ends_with(&f.path))
+    }
+
+    /// Check if an indexed entry is replaced by the AI layer
+    pub fn shadows(&self, entry: &SymbolIndexEntry) -> bool {
+        self.overlay.symbols.contains_key(&entry.hash) || self.staged_file(&entry.file).is_some()
+    }
+
+    /// Look up a hash in the AI layer
+    ///
+    /// `None` means the layer does not touch the symbol; `Some(None)` means the
+    /// staged edit removes it.
+    pub fn lookup(&self, hash: &str) -> Option<Option<SymbolIndexEntry>> {
+        if self.overlay.is_deleted(hash) {
+            return Some(None);
+        }
+        self.staged_entries()
+            .into_iter()
+            .find(|e| e.hash == hash)
+            .map(Some)
+    }
+
+    /// Staged symbols as index entries
+    pub fn staged_entries(&self) -> Vec<SymbolIndexEntry> {
+        self.files
+            .values()
+            .flat_map(|file| {
+                file.summary
+                    .symbols
+                    .iter()
+                    .zip(&file.hashes)
+                    .map(move |(symbol, hash)| staged_entry(file, symbol, hash))
+            })
+            .collect()
+    }
+
+    /// Replace the entries of staged files with their staged symbols
+    pub fn apply_to_entries(&self, entries: Vec<SymbolIndexEntry>) -> Vec<SymbolIndexEntry> {
+        let mut merged: Vec<SymbolIndexEntry> =
+            entries.into_iter().filter(|e| !self.shadows(e)).collect();
+        merged.extend(self.staged_entries());
+        merged
+    }
+
+    /// Apply the layer to symbol search results
+    ///
+    /// Shadowed results are dropped and matching staged symbols are put first,
+    /// keeping at most `limit` results.
+    pub fn apply_to_search(
+        &self,
+        results: &mut Vec<SymbolIndexEntry>,
+        query: &str,
+        module_filter: Option<&str>,
+        kind_filter: Option<&str>,
+        risk_filter: Option<&str>,
+        limit: usize,
+    ) {
+        let query_lower = query.to_lowercase();
+        let mut staged: Vec<SymbolIndexEntry> = self
+            .staged_entries()
+            .into_iter()
+            .filter(|e| {
+                crate::cache::symbol_matches_query(
+                    e,
+                    &query_lower,
+                    module_filter,
+                    kind_filter,
+                    risk_filter,
+                )
+            })
+            .collect();
+        results.retain(|e| !self.shadows(e));
+        staged.append(results);
+        staged.truncate(limit);
+        *results = staged;
+    }
+
+    /// Replace the call graph edges of staged files
+    ///
+    /// `entries` must already have the layer applied; calls of staged symbols
+    /// are resolved against them by name, preferring symbols in the same file.
+    pub fn apply_to_call_graph(
+        &self,
+        call_graph: &mut HashMap<String, Vec<String>>,
+        entries: &[SymbolIndexEntry],
+    ) {
+        // Every indexed symbol of a staged file is replaced, including the
+        // ones the edit removes
+        let staged_files: Vec<String> = self
+            .files
+            .values()
+            .map(|f| file_hash(&f.index_file))
+            .collect();
+        call_graph.retain(|caller, _| !staged_files.iter().any(|f| extract_file_hash(caller) == f));
+
+        let mut by_name: HashMap<String, Vec<&str>> = HashMap::new();
+        for entry in entries {
+            for alias in crate::detectors::rust::impl_member_aliases(&entry.symbol) {
+                by_name.entry(alias).or_default().push(entry.hash.as_str());
+            }
+            by_name
+                .entry(entry.symbol.clone())
+                .or_default()
+                .push(entry.hash.as_str());
+        }
+
+        for file in self.files.values() {
+            let same_file = file_hash(&file.index_file);
+            for (symbol, hash) in file.summary.symbols.iter().zip(&file.hashes) {
+                let mut callees: Vec<String> = Vec::new();
+                for call in &symbol.calls {
+                    let name = match &call.object {
+                        Some(object) => format!("{}.{}", object, call.name),
+                        None => call.name.clone(),
+                    };
+                    let callee = resolve_call(&by_name, &name, &same_file)
+                        .unwrap_or_else(|| format!("ext:{}", name));
+                    if !callees.contains(&callee) {
+                        callees.push(callee);
+                    }
+                }
+                call_graph.insert(hash.clone(), callees);
+            }
+        }
+    }
+}
+
+/// Index entry for a staged symbol
+fn staged_entry(file: &StagedFile, symbol: &SymbolInfo, hash: &str) -> SymbolIndexEntry {
+    SymbolIndexEntry {
+        symbol: symbol.name.clone(),
+        hash: hash.to_string(),
+        semantic_hash: crate::overlay::extract_semantic_hash(hash).to_string(),
+        kind: format!("{:?}", symbol.kind).to_lowercase(),
+        module: file.module.clone(),
+        file: file.index_file.clone(),
+        lines: format!("{}-{}", symbol.start_line, symbol.end_line),
+        risk: format!("{:?}", symbol.behavioral_risk).to_lowercase(),
+        cognitive_complexity: calculate_cognitive_complexity(&symbol.control_flow),
+        max_nesting: max_nesting_depth(&symbol.control_flow),
+        is_escape_local: symbol.is_escape_local,
+        framework_entry_point: symbol.framework_entry_point,
+        is_exported: symbol.is_exported,
+        decorators: symbol.decorators.join(","),
+        arity: symbol.arguments.len() + symbol.props.len(),
+        is_async: symbol.is_async,
+        return_type: symbol.return_type.clone().unwrap_or_default(),
+        ext_package: String::new(),
+        base_classes: symbol.base_classes.join(","),
+        feature_flags: symbol.feature_flags.join(","),
+        is_deprecated: symbol.is_deprecated,
+        deprecation_message: symbol.deprecation_message.clone().unwrap_or_default(),
+        awaits_count: symbol.awaits_count,
+        io_calls_outside_try: symbol.io_calls_outside_try,
+        has_empty_catch: symbol.has_empty_catch,
+        rethrows: symbol.rethrows,
+        panic_points: symbol.panic_points.len(),
+        sql_tables: symbol.embedded_sql_tables(),
+        partial_of: symbol.partial_of.clone().unwrap_or_default(),
+        parent: file
+            .summary
+            .parent_of(symbol)
+            .map_or_else(String::new, |parent| {
+                crate::overlay::compute_symbol_hash(parent, &file.index_file)
+            }),
+        is_generated: file.summary.is_generated,
+    }
+}
+
+/// Resolve a call name to a symbol hash (exact name, `Type::method`, then method name)
+fn resolve_call(
+    by_name: &HashMap<String, Vec<&str>>,
+    name: &str,
+    same_file: &str,
+) -> Option<String> {
+    let hashes_of = |name: &str| by_name.get(name).cloned().unwrap_or_default();
+    if !by_name.contains_key(name) {
+        let same_file_prefix = format!("{}:", same_file);
+        if let Some(hash) = crate::shard::resolve_type_path_call(name, &same_file_prefix, hashes_of)
+        {
+            return Some(hash.to_string());
+        }
+    }
+    let method = name.rsplit_once('.').map(|(_, method)| method);
+    [Some(name), method]
+        .into_iter()
+        .flatten()
+        .find_map(|name| by_name.get(name))
+        .and_then(|hashes| {
+            hashes
+                .iter()
+                .find(|h| extract_file_hash(h) == same_file)
+                .or_else(|| hashes.first())
+        })
+        .map(|h| h.to_string())
+}
+
+/// File part of the symbol hashes of a file
+fn file_hash(index_file: &str) -> String {
+    format!("{:08x}", crate::schema::fnv1a_hash(index_file) as u32)
+}
+
+/// Repository-relative form of `path` with `/` separators
+fn relative_path(repo_root: &Path, path: &str) -> Result<String> {
+    let path = Path::new(path);
+    let rel = if path.is_absolute() {
+        path.strip_prefix(repo_root)
+            .map_err(|_| McpDiffError::FileNotFound {
+                path: format!(
+                    "{} (outside repository {})",
+                    path.display(),
+                    repo_root.display()
+                ),
+            })?
+    } else {
+        path
+    };
+    let rel = rel
+        .components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if rel.is_empty() {
+        return Err(McpDiffError::FileNotFound {
+            path: "empty path".to_string(),
+        });
+    }
+    Ok(rel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::OutputFormat;
+    use crate::commands::{run_get_callers, CommandContext};
+    use crate::mcp_server::helpers::generate_index_internal;
+    use std::sync::Arc;
+
+    fn indexed_repo() -> (tempfile::TempDir, CacheDir) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("billing.ts"),
+            "function round(value: number) {\n  return Math.round(value);\n}\n\nexport function charge(amount: number) {\n  return round(amount * 2);\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            src.join("checkout.ts"),
+            "export function checkout() {\n  return 1;\n}\n\nexport function legacyTotal() {\n  return 2;\n}\n",
+        )
+        .unwrap();
+        generate_index_internal(dir.path(), 10, &[]).unwrap();
+        let cache = CacheDir::for_repo(dir.path()).unwrap();
+        (dir, cache)
+    }
+
+    fn hash_of(cache: &CacheDir, name: &str) -> String {
+        cache
+            .load_all_symbol_entries()
+            .unwrap()
+            .into_iter()
+            .find(|e| e.symbol == name)
+            .unwrap_or_else(|| panic!("{name} not indexed"))
+            .hash
+    }
+
+    fn caller_names(dir: &Path, hash: &str, ai_layer: Option<Arc<AiLayer>>) -> Vec<String> {
+        let ctx =
+            CommandContext::from_cli(OutputFormat::Json, false, false).with_ai_layer(ai_layer);
+        let output = run_get_callers(Some(&dir.to_path_buf()), hash, 1, false, 20, &ctx).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        json["callers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_staged_caller_shows_up_until_cleared() {
+        let (dir, cache) = indexed_repo();
+        let charge = hash_of(&cache, "charge");
+        assert!(caller_names(dir.path(), &charge, None).is_empty());
+
+        let mut layer = AiLayer::new();
+        let outcome = layer
+            .stage(
+                &cache,
+                "src/checkout.ts",
+                "import { charge } from './billing';\n\nexport function checkout() {\n  return charge(1);\n}\n"
+                    .to_string(),
+            )
+            .unwrap();
+        assert_eq!(outcome.modified, 1);
+        assert_eq!(outcome.removed, 1, "legacyTotal is dropped by the edit");
+
+        let staged = Arc::new(layer.clone());
+        assert_eq!(
+            caller_names(dir.path(), &charge, Some(staged.clone())),
+            vec!["checkout"]
+        );
+        let legacy = hash_of(&cache, "legacyTotal");
+        assert!(matches!(staged.lookup(&legacy), Some(None)));
+
+        // Nothing staged reaches the index on disk
+        assert!(caller_names(dir.path(), &charge, None).is_empty());
+
+        layer.clear(&cache.repo_root, None);
+        assert!(layer.is_empty());
+        assert!(layer.overlay().is_empty());
+        assert!(caller_names(dir.path(), &charge, Some(Arc::new(layer))).is_empty());
+    }
+
+    #[test]
+    fn test_search_prefers_staged_symbols() {
+        let (_dir, cache) = indexed_repo();
+        let mut layer = AiLayer::new();
+        layer
+            .stage(
+                &cache,
+                "src/refunds.ts",
+                "export function chargeBack() {\n  return 0;\n}\n".to_string(),
+            )
+            .unwrap();
+
+        let mut results = cache
+            .search_symbols("charge", None, None, None, 20)
+            .unwrap();
+        layer.apply_to_search(&mut results, "charge", None, None, None, 20);
+        let names: Vec<&str> = results.iter().map(|e| e.symbol.as_str()).collect();
+        assert_eq!(names, vec!["chargeBack", "charge"]);
+        assert!(cache
+            .load_all_symbol_entries()
+            .unwrap()
+            .iter()
+            .all(|e| e.symbol != "chargeBack"));
+    }
+
+    #[test]
+    fn test_prune_drops_old_unwritten_edits() {
+        let (_dir, cache) = indexed_repo();
+        let mut layer = AiLayer::new();
+        for path in ["src/checkout.ts", "src/refunds.ts", "src/fresh.ts"] {
+            layer
+                .stage(
+                    &cache,
+                    path,
+                    "export function proposed() {\n  return 0;\n}\n".to_string(),
+                )
+                .unwrap();
+        }
+        for path in ["src/checkout.ts", "src/refunds.ts"] {
+            layer.files.get_mut(path).unwrap().staged_at = 0;
+        }
+        layer.rebuild_overlay();
+
+        // checkout.ts exists on disk; fresh.ts was staged just now
+        assert_eq!(layer.prune_stale(3600), vec!["src/refunds.ts"]);
+        let paths: Vec<&str> = layer.files().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/checkout.ts", "src/fresh.ts"]);
+        assert_eq!(layer.overlay().active_count(), 2);
+        assert!(layer.prune_stale(3600).is_empty());
+    }
+}
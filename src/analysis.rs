@@ -8,6 +8,7 @@ use crate::schema::{RiskLevel, SemanticSummary, SymbolKind};
 use crate::utils::truncate_to_char_boundary;
 use crate::Result;
 use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -69,7 +70,11 @@ pub struct SymbolComplexity {
 pub fn calculate_cognitive_complexity(control_flow: &[crate::schema::ControlFlowChange]) -> usize {
     let mut complexity = 0;
 
-    for cf in control_flow {
+    // Await/yield don't add structural complexity
+    for cf in control_flow
+        .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
+    {
         // Base increment for the control structure
         let base = 1;
         // Nesting penalty
@@ -84,6 +89,7 @@ pub fn calculate_cognitive_complexity(control_flow: &[crate::schema::ControlFlow
 pub fn max_nesting_depth(control_flow: &[crate::schema::ControlFlowChange]) -> usize {
     control_flow
         .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
         .map(|cf| cf.nesting_depth)
         .max()
         .unwrap_or(0)
@@ -205,6 +211,160 @@ pub struct RepoAnalysis {
     pub total_lines: usize,
     pub avg_complexity: f64,
     pub high_risk_percentage: f64,
+    /// Resolved calls between modules (caller module -> callee module -> count),
+    /// including calls within a module
+    pub module_calls: HashMap<String, HashMap<String, usize>>,
+    /// Lines of code behind each kind of technical debt
+    pub debt: DebtLines,
+}
+
+/// Lines of code carrying each kind of technical debt
+///
+/// Complexity, coupling and test coverage are measured by [`analyze_repo`];
+/// duplicates only by [`add_duplicate_debt`], since clustering compares
+/// every pair of functions.
+#[derive(Debug, Clone, Default)]
+pub struct DebtLines {
+    /// Lines in functions duplicating another (the first of a cluster excluded)
+    pub duplicate: usize,
+    /// Lines in symbols above the "complex" cognitive complexity rating (> 10)
+    pub complex: usize,
+    /// Lines in symbols with more than 10 resolved callees
+    pub coupled: usize,
+    /// Lines in production functions no test is linked to
+    pub untested: usize,
+    /// Lines in production functions (0 when the index has no test links)
+    pub testable: usize,
+    /// Symbol hash -> lines, for every symbol carrying any kind of debt
+    pub affected: HashMap<String, usize>,
+}
+
+impl DebtLines {
+    fn absorb(&mut self, other: DebtLines) {
+        self.duplicate += other.duplicate;
+        self.complex += other.complex;
+        self.coupled += other.coupled;
+        self.untested += other.untested;
+        self.testable += other.testable;
+        self.affected.extend(other.affected);
+    }
+}
+
+/// Estimated remediation effort per line of code carrying debt
+pub const REMEDIATION_HOURS_PER_LOC: f64 = 0.5;
+
+/// Aggregate technical debt of a repository
+///
+/// Each component is the percentage of the relevant code carrying that kind
+/// of debt (0 = none, 100 = all of it); `overall_score` is their mean.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TechDebtReport {
+    /// 0-100, lower is better
+    pub overall_score: f64,
+    /// Percentage of lines in duplicated functions
+    pub duplicate_debt: f64,
+    /// Percentage of lines in complex symbols
+    pub complexity_debt: f64,
+    /// Percentage of lines in symbols with high fan-out
+    pub coupling_debt: f64,
+    /// Percentage of production function lines without a linked test
+    pub test_coverage_debt: f64,
+    /// Lines carrying at least one kind of debt
+    pub affected_loc: usize,
+    /// `affected_loc` × [`REMEDIATION_HOURS_PER_LOC`]
+    pub remediation_hours: f64,
+}
+
+impl RepoAnalysis {
+    /// Quantify the repository's technical debt from the measured [`DebtLines`]
+    pub fn tech_debt_score(&self) -> TechDebtReport {
+        let percent = |part: usize, whole: usize| {
+            if whole == 0 {
+                0.0
+            } else {
+                round1((part as f64 / whole as f64 * 100.0).min(100.0))
+            }
+        };
+        let debt = &self.debt;
+        let duplicate_debt = percent(debt.duplicate, self.total_lines);
+        let complexity_debt = percent(debt.complex, self.total_lines);
+        let coupling_debt = percent(debt.coupled, self.total_lines);
+        let test_coverage_debt = percent(debt.untested, debt.testable);
+        let affected_loc: usize = debt.affected.values().sum();
+
+        TechDebtReport {
+            overall_score: round1(
+                (duplicate_debt + complexity_debt + coupling_debt + test_coverage_debt) / 4.0,
+            ),
+            duplicate_debt,
+            complexity_debt,
+            coupling_debt,
+            test_coverage_debt,
+            affected_loc,
+            remediation_hours: round1(affected_loc as f64 * REMEDIATION_HOURS_PER_LOC),
+        }
+    }
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Measure duplicate debt from the index's function signatures
+///
+/// Every function in a duplicate cluster except its first counts, exact and
+/// near copies alike; divergent matches don't.
+pub fn add_duplicate_debt(analysis: &mut RepoAnalysis, cache: &CacheDir) -> Result<()> {
+    let signatures = crate::cache::load_function_signatures(cache)?;
+    let clusters = crate::duplicate::DuplicateDetector::default().find_all_clusters(&signatures);
+    for duplicate in clusters
+        .iter()
+        .flat_map(|cluster| &cluster.duplicates)
+        .filter(|d| d.kind != crate::duplicate::DuplicateKind::Divergent)
+    {
+        let symbol = &duplicate.symbol;
+        let loc = symbol.end_line.saturating_sub(symbol.start_line) + 1;
+        analysis.debt.duplicate += loc;
+        analysis.debt.affected.insert(symbol.hash.clone(), loc);
+    }
+    Ok(())
+}
+
+/// Direction of the calls between a coupled module pair, seen from `module_a`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CouplingKind {
+    /// Only `module_b` calls into `module_a`
+    AfferentOnly,
+    /// Only `module_a` calls into `module_b`
+    EfferentOnly,
+    /// Both modules call into each other
+    Bidirectional,
+}
+
+impl CouplingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CouplingKind::AfferentOnly => "afferent_only",
+            CouplingKind::EfferentOnly => "efferent_only",
+            CouplingKind::Bidirectional => "bidirectional",
+        }
+    }
+}
+
+/// Coupling between two modules that call into each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingReport {
+    /// First module of the pair (alphabetically)
+    pub module_a: String,
+    /// Second module of the pair
+    pub module_b: String,
+    /// Calls from either module into the other
+    pub shared_call_count: usize,
+    /// `shared_call_count / (calls_a + calls_b)`, where `calls_x` counts every
+    /// resolved call made from module x (0 = loosely coupled, 1 = the pair
+    /// only calls each other)
+    pub coupling_score: f64,
+    pub kind: CouplingKind,
 }
 
 /// Analyze complexity from a call graph
@@ -282,6 +442,81 @@ pub fn analyze_call_graph(
     analysis
 }
 
+/// Find module pairs that call into each other.
+///
+/// Every pair with at least one call between the two modules is reported,
+/// sorted by `coupling_score` descending. Bidirectional pairs with a high
+/// shared call count are an architectural smell: neither module can change
+/// without the other.
+pub fn analyze_coupling(analysis: &RepoAnalysis) -> Vec<CouplingReport> {
+    let calls_between = |from: &str, to: &str| {
+        analysis
+            .module_calls
+            .get(from)
+            .and_then(|callees| callees.get(to))
+            .copied()
+            .unwrap_or(0)
+    };
+    let total_calls = |module: &str| -> usize {
+        analysis
+            .module_calls
+            .get(module)
+            .map(|callees| callees.values().sum())
+            .unwrap_or(0)
+    };
+
+    let mut pairs: Vec<(&str, &str)> = analysis
+        .module_calls
+        .iter()
+        .flat_map(|(caller, callees)| {
+            callees
+                .keys()
+                .filter(move |callee| *callee != caller)
+                .map(move |callee| {
+                    if caller < callee {
+                        (caller.as_str(), callee.as_str())
+                    } else {
+                        (callee.as_str(), caller.as_str())
+                    }
+                })
+        })
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+
+    let mut reports: Vec<CouplingReport> = pairs
+        .into_iter()
+        .map(|(a, b)| {
+            let a_to_b = calls_between(a, b);
+            let b_to_a = calls_between(b, a);
+            let shared_call_count = a_to_b + b_to_a;
+            let kind = match (a_to_b > 0, b_to_a > 0) {
+                (true, true) => CouplingKind::Bidirectional,
+                (true, false) => CouplingKind::EfferentOnly,
+                _ => CouplingKind::AfferentOnly,
+            };
+            CouplingReport {
+                module_a: a.to_string(),
+                module_b: b.to_string(),
+                shared_call_count,
+                coupling_score: shared_call_count as f64 / (total_calls(a) + total_calls(b)) as f64,
+                kind,
+            }
+        })
+        .collect();
+
+    reports.sort_by(|a, b| {
+        b.coupling_score
+            .partial_cmp(&a.coupling_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.shared_call_count.cmp(&a.shared_call_count))
+            .then_with(|| a.module_a.cmp(&b.module_a))
+            .then_with(|| a.module_b.cmp(&b.module_b))
+    });
+
+    reports
+}
+
 /// Detect cycles in call graph using DFS
 fn detect_cycles(graph: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
     let mut cycles = Vec::new();
@@ -582,6 +817,13 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
         }
     }
 
+    // Production symbols with a linked test; an index without test links
+    // doesn't measure coverage
+    let test_links = crate::test_links::TestLinkIndex::load(&cache).ok();
+    let tested: Option<std::collections::HashSet<&str>> = test_links
+        .as_ref()
+        .map(|links| links.tested_by.keys().map(String::as_str).collect());
+
     // Group entries by module
     let mut module_entries: HashMap<String, Vec<&crate::cache::SymbolIndexEntry>> = HashMap::new();
     for entry in &symbol_entries {
@@ -640,7 +882,7 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
     let total_modules = module_entries.len();
     let processed_modules = std::sync::atomic::AtomicUsize::new(0);
     eprintln!("Analyzing {} modules...", total_modules);
-    let module_results: Vec<(ModuleMetrics, Vec<SymbolComplexity>)> = module_entries
+    let module_results: Vec<(ModuleMetrics, Vec<SymbolComplexity>, DebtLines)> = module_entries
         .par_iter()
         .map(|(module_name, entries)| {
             let current = processed_modules.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
@@ -660,6 +902,7 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
             let mut files_seen = std::collections::HashSet::new();
             let mut module_cc_sum = 0usize;
             let mut complex_symbols_local: Vec<SymbolComplexity> = Vec::new();
+            let mut debt = DebtLines::default();
 
             for entry in entries {
                 files_seen.insert(&entry.file);
@@ -718,6 +961,29 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
                     complex_symbols_local.push(sym_complexity);
                 }
 
+                let complex = entry.cognitive_complexity > 10;
+                let coupled = fan_out_map.get(&entry.hash).is_some_and(|&fo| fo > 10);
+                let untested = match &tested {
+                    Some(tested)
+                        if matches!(entry.kind.as_str(), "function" | "method")
+                            && !crate::search::is_test_file(&entry.file) =>
+                    {
+                        debt.testable += loc;
+                        !tested.contains(entry.hash.as_str())
+                    }
+                    _ => false,
+                };
+                for (carries, lines) in [
+                    (complex, &mut debt.complex),
+                    (coupled, &mut debt.coupled),
+                    (untested, &mut debt.untested),
+                ] {
+                    if carries {
+                        *lines += loc;
+                        debt.affected.insert(entry.hash.clone(), loc);
+                    }
+                }
+
                 module_metrics.symbols += 1;
             }
 
@@ -727,12 +993,13 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
                     module_cc_sum as f64 / module_metrics.symbols as f64;
             }
 
-            (module_metrics, complex_symbols_local)
+            (module_metrics, complex_symbols_local, debt)
         })
         .collect();
 
     // Merge results
-    for (module_metrics, complex_symbols_local) in module_results {
+    for (module_metrics, complex_symbols_local, debt) in module_results {
+        analysis.debt.absorb(debt);
         analysis.total_symbols += module_metrics.symbols;
         analysis.total_lines += module_metrics.total_loc;
         analysis.modules.push(module_metrics);
@@ -758,6 +1025,27 @@ pub fn analyze_repo(repo_path: &Path) -> Result<RepoAnalysis> {
     });
     analysis.complex_symbols.truncate(20);
 
+    // Aggregate resolved calls per module pair
+    let hash_to_module: HashMap<&str, &str> = symbol_entries
+        .iter()
+        .map(|e| (e.hash.as_str(), e.module.as_str()))
+        .collect();
+    for (caller, callees) in &call_graph {
+        let Some(&caller_module) = hash_to_module.get(caller.as_str()) else {
+            continue;
+        };
+        for callee in callees {
+            if let Some(&callee_module) = hash_to_module.get(callee.as_str()) {
+                *analysis
+                    .module_calls
+                    .entry(caller_module.to_string())
+                    .or_default()
+                    .entry(callee_module.to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
     // Analyze call graph
     analysis.call_graph = analyze_call_graph(&call_graph, &symbol_names);
 
@@ -820,9 +1108,359 @@ pub fn analyze_module(repo_path: &Path, module_name: &str) -> Result<ModuleMetri
     Ok(metrics)
 }
 
+// ============================================================================
+// Python package resolution
+// ============================================================================
+
+/// Where a name imported by a Python file is defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonImportTarget {
+    /// Defining file, as it appears in the summaries
+    pub file: String,
+    /// Name imported from the module; `None` when the module itself is bound
+    pub name: Option<String>,
+}
+
+/// Indexed Python files grouped into packages
+///
+/// Dotted module paths are relative to a package root: a source root from
+/// `pyproject.toml` / `setup.cfg` (or a conventional `src/` layout), else the
+/// directory above the outermost `__init__.py` package holding the file.
+#[derive(Debug, Clone, Default)]
+pub struct PythonPackageIndex {
+    /// Repo-relative normalized path -> path as indexed
+    files: HashMap<String, String>,
+    /// Repo-relative source roots, deepest first
+    source_roots: Vec<String>,
+    /// Prefix stripped from indexed paths (`<repo>/`)
+    repo_prefix: String,
+}
+
+impl PythonPackageIndex {
+    /// Index `files`; `source_roots` are relative to `repo_root`
+    pub fn new(
+        files: impl IntoIterator<Item = String>,
+        repo_root: Option<&Path>,
+        source_roots: Vec<String>,
+    ) -> Self {
+        let mut index = Self {
+            repo_prefix: repo_root
+                .map(|root| format!("{}/", root.to_string_lossy().trim_end_matches('/')))
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        for file in files {
+            index.files.insert(index.key(&file), file);
+        }
+
+        index.source_roots = source_roots
+            .iter()
+            .map(|root| crate::module_usage::normalize_path(root.trim_start_matches("./")))
+            .filter(|root| !root.is_empty())
+            .collect();
+        // `src/<package>/__init__.py` without packaging config
+        let src_layout = index.files.keys().any(|key| {
+            key.starts_with("src/")
+                && key.matches('/').count() == 2
+                && key.ends_with("/__init__.py")
+        });
+        if index.source_roots.is_empty() && src_layout {
+            index.source_roots.push("src".to_string());
+        }
+        index
+            .source_roots
+            .sort_by_key(|root| std::cmp::Reverse(root.len()));
+        index
+    }
+
+    /// Index the Python files among `summaries`
+    pub fn from_summaries(summaries: &[SemanticSummary], repo_root: Option<&Path>) -> Self {
+        let files = summaries
+            .iter()
+            .filter(|s| is_python_file(&s.file))
+            .map(|s| s.file.clone());
+        let source_roots = repo_root.map(python_source_roots).unwrap_or_default();
+        Self::new(files, repo_root, source_roots)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Repo-relative normalized form of an indexed path
+    fn key(&self, file: &str) -> String {
+        let file = file.replace('\\', "/");
+        let relative = file.strip_prefix(&self.repo_prefix).unwrap_or(&file);
+        crate::module_usage::normalize_path(relative.trim_start_matches("./"))
+    }
+
+    /// Directory a file's dotted module path is relative to
+    pub fn package_root(&self, file: &str) -> String {
+        let key = self.key(file);
+        if let Some(root) = self
+            .source_roots
+            .iter()
+            .find(|root| key.starts_with(&format!("{}/", root)))
+        {
+            return root.clone();
+        }
+        let mut root = parent_dir(&key);
+        while self.files.contains_key(&join_path(root, "__init__.py")) {
+            if root.is_empty() {
+                break;
+            }
+            root = parent_dir(root);
+        }
+        root.to_string()
+    }
+
+    /// Dotted module path of an indexed file (`pkg.core.models`)
+    pub fn module_path(&self, file: &str) -> Option<String> {
+        let key = self.key(file);
+        let root = self.package_root(file);
+        let relative = if root.is_empty() {
+            key.as_str()
+        } else {
+            key.strip_prefix(&format!("{}/", root))?
+        };
+        let relative = relative.strip_suffix(".py")?;
+        let module = relative.strip_suffix("/__init__").unwrap_or(relative);
+        Some(module.replace('/', "."))
+    }
+
+    /// Indexed file a module imported by `importer` refers to
+    ///
+    /// Relative modules (`.`, `..core`) are resolved from the importer's
+    /// package; absolute ones from its package root, then the source roots.
+    pub fn resolve(&self, importer: &str, module: &str) -> Option<&str> {
+        let rest = module.trim_start_matches('.');
+        let dots = module.len() - rest.len();
+
+        let bases: Vec<String> = if dots > 0 {
+            let mut base = parent_dir(&self.key(importer)).to_string();
+            for _ in 1..dots {
+                if base.is_empty() {
+                    return None;
+                }
+                base = parent_dir(&base).to_string();
+            }
+            vec![base]
+        } else {
+            let mut bases = vec![self.package_root(importer)];
+            bases.extend(self.source_roots.iter().cloned());
+            bases.push(String::new());
+            bases
+        };
+
+        let rest = rest.replace('.', "/");
+        bases.iter().find_map(|base| {
+            let module_dir = join_path(base, &rest);
+            let candidates = if rest.is_empty() {
+                vec![join_path(base, "__init__.py")]
+            } else {
+                vec![
+                    format!("{}.py", module_dir),
+                    join_path(&module_dir, "__init__.py"),
+                ]
+            };
+            candidates
+                .iter()
+                .find_map(|candidate| self.files.get(candidate))
+                .map(String::as_str)
+        })
+    }
+
+    /// Repo-local names bound by a file's imports (local name -> target)
+    pub fn import_targets(&self, summary: &SemanticSummary) -> HashMap<String, PythonImportTarget> {
+        let mut targets = HashMap::new();
+        if !self.files.contains_key(&self.key(&summary.file)) {
+            return targets;
+        }
+
+        for import in &summary.imports {
+            for imported in &import.names {
+                let local = imported.alias.as_ref().unwrap_or(&imported.name);
+                if import.is_namespace {
+                    // `import pkg.mod [as m]`
+                    if let Some(file) = self.resolve(&summary.file, &imported.name) {
+                        targets.insert(
+                            local.clone(),
+                            PythonImportTarget {
+                                file: file.to_string(),
+                                name: None,
+                            },
+                        );
+                    }
+                    continue;
+                }
+
+                // `from . import utils` binds a submodule when one exists
+                let separator = if import.source.ends_with('.') {
+                    ""
+                } else {
+                    "."
+                };
+                let submodule = format!("{}{}{}", import.source, separator, imported.name);
+                let target = match self.resolve(&summary.file, &submodule) {
+                    Some(file) => Some((file, None)),
+                    None => self
+                        .resolve(&summary.file, &import.source)
+                        .map(|file| (file, Some(imported.name.clone()))),
+                };
+                if let Some((file, name)) = target {
+                    targets.insert(
+                        local.clone(),
+                        PythonImportTarget {
+                            file: file.to_string(),
+                            name,
+                        },
+                    );
+                }
+            }
+        }
+        targets
+    }
+}
+
+/// Name and defining file a call goes to through a Python file's imports
+///
+/// `helper()` after `from .utils import helper` and `utils.helper()` after
+/// `from . import utils` both look up `helper` in `utils.py`.
+pub fn resolve_python_call<'a>(
+    targets: &'a HashMap<String, PythonImportTarget>,
+    call_name: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    if let Some(target) = targets.get(call_name) {
+        return target
+            .name
+            .as_deref()
+            .map(|name| (name, target.file.as_str()));
+    }
+    let (object, method) = call_name.rsplit_once('.')?;
+    let root_object = object.split('.').next().unwrap_or(object);
+    let target = targets.get(object).or_else(|| targets.get(root_object))?;
+    Some((method, target.file.as_str()))
+}
+
+/// Source roots declared by a repository's Python packaging config
+///
+/// Reads `[tool.setuptools]` (`package-dir`, `packages.find.where`),
+/// `[tool.poetry] packages.from` and hatch wheel `packages` from
+/// `pyproject.toml`, and `package_dir` / `packages.find.where` from `setup.cfg`.
+pub fn python_source_roots(repo_root: &Path) -> Vec<String> {
+    let mut roots: Vec<String> = Vec::new();
+
+    if let Some(pyproject) = std::fs::read_to_string(repo_root.join("pyproject.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+    {
+        let tool = pyproject.get("tool");
+        let at = |path: &[&str]| path.iter().try_fold(tool?, |value, key| value.get(*key));
+        if let Some(dir) = at(&["setuptools", "package-dir", ""]).and_then(|v| v.as_str()) {
+            roots.push(dir.to_string());
+        }
+        if let Some(wheres) = at(&["setuptools", "packages", "find", "where"]) {
+            let wheres = wheres
+                .as_array()
+                .cloned()
+                .unwrap_or_else(|| vec![wheres.clone()]);
+            roots.extend(wheres.iter().filter_map(|w| w.as_str()).map(String::from));
+        }
+        for package in at(&["poetry", "packages"])
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(from) = package.get("from").and_then(|f| f.as_str()) {
+                roots.push(from.to_string());
+            }
+        }
+        for package in at(&["hatch", "build", "targets", "wheel", "packages"])
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.as_str())
+        {
+            roots.push(parent_dir(package.trim_end_matches('/')).to_string());
+        }
+    }
+
+    if let Ok(setup_cfg) = std::fs::read_to_string(repo_root.join("setup.cfg")) {
+        roots.extend(setup_cfg_source_roots(&setup_cfg));
+    }
+
+    roots.retain(|root| !matches!(root.trim_matches('/'), "" | "."));
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// `package_dir = =src` and `[options.packages.find] where = src`
+fn setup_cfg_source_roots(text: &str) -> Vec<String> {
+    let mut roots = Vec::new();
+    let mut section = "";
+    let mut key = "";
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            section = name;
+            key = "";
+            continue;
+        }
+        // Continuation lines are indented and belong to the previous key
+        let value = if line.starts_with([' ', '\t']) {
+            trimmed
+        } else {
+            match trimmed.split_once('=') {
+                Some((k, v)) => {
+                    key = k.trim();
+                    v.trim()
+                }
+                None => continue,
+            }
+        };
+        match (section, key) {
+            ("options", "package_dir") => {
+                // `=src` maps the root package
+                if let Some(dir) = value.strip_prefix('=') {
+                    roots.push(dir.trim().to_string());
+                }
+            }
+            ("options.packages.find", "where") if !value.is_empty() => {
+                roots.push(value.to_string())
+            }
+            _ => {}
+        }
+    }
+    roots
+}
+
+fn is_python_file(path: &str) -> bool {
+    path.ends_with(".py")
+}
+
+/// Parent of a `/`-separated path (`""` at the top)
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else if name.is_empty() {
+        dir.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::{Import, ImportedName};
 
     #[test]
     fn test_complexity_score() {
@@ -857,6 +1495,92 @@ mod tests {
         assert!(!cycles.is_empty());
     }
 
+    fn module_calls(edges: &[(&str, &str, usize)]) -> HashMap<String, HashMap<String, usize>> {
+        let mut calls: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        for (from, to, count) in edges {
+            calls
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string(), *count);
+        }
+        calls
+    }
+
+    #[test]
+    fn test_analyze_coupling() {
+        let analysis = RepoAnalysis {
+            module_calls: module_calls(&[
+                ("api", "db", 6),
+                ("db", "api", 2),
+                ("api", "api", 2),
+                ("ui", "api", 1),
+                ("ui", "ui", 9),
+            ]),
+            ..Default::default()
+        };
+
+        let reports = analyze_coupling(&analysis);
+        assert_eq!(reports.len(), 2);
+
+        // api <-> db: 8 shared calls out of 8 (api) + 2 (db)
+        assert_eq!(reports[0].module_a, "api");
+        assert_eq!(reports[0].module_b, "db");
+        assert_eq!(reports[0].shared_call_count, 8);
+        assert_eq!(reports[0].kind, CouplingKind::Bidirectional);
+        assert!((reports[0].coupling_score - 8.0 / 10.0).abs() < f64::EPSILON);
+
+        // ui only calls into api, so api sees it as afferent coupling
+        assert_eq!(reports[1].module_a, "api");
+        assert_eq!(reports[1].module_b, "ui");
+        assert_eq!(reports[1].kind, CouplingKind::AfferentOnly);
+        assert!((reports[1].coupling_score - 1.0 / 18.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_analyze_coupling_ignores_internal_calls() {
+        let analysis = RepoAnalysis {
+            module_calls: module_calls(&[("core", "core", 12)]),
+            ..Default::default()
+        };
+        assert!(analyze_coupling(&analysis).is_empty());
+    }
+
+    #[test]
+    fn test_tech_debt_score() {
+        let analysis = RepoAnalysis {
+            total_lines: 200,
+            debt: DebtLines {
+                duplicate: 20,
+                complex: 50,
+                coupled: 0,
+                untested: 30,
+                testable: 120,
+                // The complex symbol is also untested: its lines count once
+                affected: HashMap::from([
+                    ("dup".to_string(), 20),
+                    ("complex".to_string(), 50),
+                    ("untested".to_string(), 10),
+                ]),
+            },
+            ..Default::default()
+        };
+
+        let report = analysis.tech_debt_score();
+        assert_eq!(report.duplicate_debt, 10.0);
+        assert_eq!(report.complexity_debt, 25.0);
+        assert_eq!(report.coupling_debt, 0.0);
+        assert_eq!(report.test_coverage_debt, 25.0);
+        assert_eq!(report.overall_score, 15.0);
+        assert_eq!(report.affected_loc, 80);
+        assert_eq!(report.remediation_hours, 40.0);
+
+        // An empty repo carries no debt
+        assert_eq!(
+            RepoAnalysis::default().tech_debt_score(),
+            TechDebtReport::default()
+        );
+    }
+
     #[test]
     fn test_instability() {
         let mut metrics = ModuleMetrics::default();
@@ -868,6 +1592,168 @@ mod tests {
         metrics.efferent_coupling = 10;
         assert!((metrics.instability() - 1.0).abs() < 0.01);
     }
+
+    fn python_index(files: &[&str], source_roots: &[&str]) -> PythonPackageIndex {
+        PythonPackageIndex::new(
+            files.iter().map(|f| format!("/repo/{}", f)),
+            Some(Path::new("/repo")),
+            source_roots.iter().map(|r| r.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_python_src_layout_package() {
+        let index = python_index(
+            &[
+                "src/mypkg/__init__.py",
+                "src/mypkg/core/__init__.py",
+                "src/mypkg/core/models.py",
+                "tests/test_models.py",
+                "scripts/tool.py",
+            ],
+            &[],
+        );
+
+        assert_eq!(index.package_root("/repo/src/mypkg/core/models.py"), "src");
+        assert_eq!(
+            index
+                .module_path("/repo/src/mypkg/core/models.py")
+                .as_deref(),
+            Some("mypkg.core.models")
+        );
+        assert_eq!(
+            index
+                .module_path("/repo/src/mypkg/core/__init__.py")
+                .as_deref(),
+            Some("mypkg.core")
+        );
+        assert_eq!(
+            index.module_path("/repo/scripts/tool.py").as_deref(),
+            Some("tool")
+        );
+        // Tests outside the package import it by its dotted path
+        assert_eq!(
+            index.resolve("/repo/tests/test_models.py", "mypkg.core.models"),
+            Some("/repo/src/mypkg/core/models.py")
+        );
+        assert_eq!(
+            index.resolve("/repo/tests/test_models.py", "mypkg.core"),
+            Some("/repo/src/mypkg/core/__init__.py")
+        );
+        assert_eq!(
+            index.resolve("/repo/tests/test_models.py", "requests"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_python_configured_source_root() {
+        // Namespace package (no __init__.py) under a configured root
+        let index = python_index(&["lib/acme/billing/invoice.py"], &["lib"]);
+        assert_eq!(
+            index
+                .module_path("/repo/lib/acme/billing/invoice.py")
+                .as_deref(),
+            Some("acme.billing.invoice")
+        );
+    }
+
+    #[test]
+    fn test_python_relative_import_resolution() {
+        let index = python_index(
+            &[
+                "pkg/__init__.py",
+                "pkg/utils.py",
+                "pkg/core/__init__.py",
+                "pkg/core/models.py",
+                "pkg/core/service.py",
+            ],
+            &[],
+        );
+        let importer = "/repo/pkg/core/service.py";
+
+        assert_eq!(
+            index.resolve(importer, ".models"),
+            Some("/repo/pkg/core/models.py")
+        );
+        assert_eq!(
+            index.resolve(importer, "."),
+            Some("/repo/pkg/core/__init__.py")
+        );
+        assert_eq!(
+            index.resolve(importer, "..utils"),
+            Some("/repo/pkg/utils.py")
+        );
+        assert_eq!(index.resolve(importer, ".missing"), None);
+        assert_eq!(index.resolve(importer, "...."), None);
+
+        let summary = SemanticSummary {
+            file: importer.to_string(),
+            imports: vec![
+                Import {
+                    source: ".".to_string(),
+                    names: vec![ImportedName {
+                        name: "models".to_string(),
+                        alias: None,
+                    }],
+                    ..Default::default()
+                },
+                Import {
+                    source: "..utils".to_string(),
+                    names: vec![ImportedName {
+                        name: "slugify".to_string(),
+                        alias: Some("slug".to_string()),
+                    }],
+                    ..Default::default()
+                },
+                Import {
+                    source: "os".to_string(),
+                    names: vec![ImportedName {
+                        name: "os".to_string(),
+                        alias: None,
+                    }],
+                    is_namespace: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let targets = index.import_targets(&summary);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(
+            resolve_python_call(&targets, "slug"),
+            Some(("slugify", "/repo/pkg/utils.py"))
+        );
+        assert_eq!(
+            resolve_python_call(&targets, "models.User"),
+            Some(("User", "/repo/pkg/core/models.py"))
+        );
+        assert_eq!(resolve_python_call(&targets, "os.getcwd"), None);
+    }
+
+    #[test]
+    fn test_python_source_roots_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[tool.setuptools.packages.find]\nwhere = [\"src\"]\n\n[tool.poetry]\npackages = [{ include = \"app\", from = \"lib\" }]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("setup.cfg"),
+            "[metadata]\nname = demo\n\n[options]\npackage_dir =\n    =python\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            python_source_roots(dir.path()),
+            vec!["lib".to_string(), "python".to_string(), "src".to_string()]
+        );
+        assert_eq!(
+            setup_cfg_source_roots("[options.packages.find]\nwhere = src\n"),
+            vec!["src".to_string()]
+        );
+    }
 }
 
 #[test]
@@ -6,7 +6,9 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{McpDiffError, Result};
 use crate::{encode_toon, extract, generate_repo_overview, Lang};
 
 /// Approximate token count from text
@@ -20,7 +22,7 @@ pub fn estimate_tokens(text: &str) -> usize {
 }
 
 /// Detailed token breakdown for a file
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenMetrics {
     /// Path to the file
     pub file: String,
@@ -76,7 +78,7 @@ impl TokenMetrics {
 }
 
 /// Aggregate metrics for a repository
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepoTokenMetrics {
     /// Individual file metrics
     pub files: Vec<TokenMetrics>,
@@ -157,6 +159,43 @@ impl RepoTokenMetrics {
         }
     }
 
+    /// Write the metrics as JSON, for a later [`RepoTokenMetrics::compare`]
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| McpDiffError::Serialization(e.to_string()))?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read metrics written by [`RepoTokenMetrics::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).map_err(|e| McpDiffError::IoError {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        serde_json::from_str(&json)
+            .map_err(|e| McpDiffError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Compare against `baseline`, failing metrics that dropped by more than
+    /// `max_regression` percent of their baseline value
+    pub fn compare(&self, baseline: &RepoTokenMetrics, max_regression: f64) -> BenchmarkComparison {
+        BenchmarkComparison {
+            compression: MetricDelta {
+                baseline: baseline.total_compression,
+                current: self.total_compression,
+            },
+            token_savings: MetricDelta {
+                baseline: baseline.total_token_savings,
+                current: self.total_token_savings,
+            },
+            max_regression,
+        }
+    }
+
     /// Generate a human-readable report
     pub fn report(&self) -> String {
         let mut output = String::new();
@@ -254,6 +293,100 @@ impl RepoTokenMetrics {
     }
 }
 
+/// A metric's baseline and current value
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl MetricDelta {
+    /// Change from the baseline in percentage points
+    pub fn delta_points(&self) -> f64 {
+        (self.current - self.baseline) * 100.0
+    }
+
+    /// Change relative to the baseline, in percent (negative is worse)
+    pub fn change_pct(&self) -> f64 {
+        if self.baseline == 0.0 {
+            0.0
+        } else {
+            (self.current - self.baseline) / self.baseline.abs() * 100.0
+        }
+    }
+
+    /// Whether the metric dropped by more than `max_regression` percent
+    pub fn regressed(&self, max_regression: f64) -> bool {
+        -self.change_pct() > max_regression
+    }
+}
+
+/// Result of `benchmark --compare`
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkComparison {
+    /// Byte compression ratio
+    pub compression: MetricDelta,
+
+    /// Token savings ratio
+    pub token_savings: MetricDelta,
+
+    /// Largest tolerated drop, in percent of the baseline value
+    pub max_regression: f64,
+}
+
+impl BenchmarkComparison {
+    /// Names of the metrics that regressed past the threshold
+    pub fn regressions(&self) -> Vec<&'static str> {
+        [
+            ("compression", self.compression),
+            ("token_savings", self.token_savings),
+        ]
+        .into_iter()
+        .filter(|(_, delta)| delta.regressed(self.max_regression))
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Generate a human-readable report
+    pub fn report(&self) -> String {
+        let mut output = String::new();
+        output.push_str("───────────────────────────────────────────────────────\n");
+        output.push_str("  COMPARISON WITH BASELINE\n");
+        output.push_str("───────────────────────────────────────────────────────\n");
+
+        let regressions = self.regressions();
+        for (label, name, delta) in [
+            ("Compression:  ", "compression", self.compression),
+            ("Token savings:", "token_savings", self.token_savings),
+        ] {
+            output.push_str(&format!(
+                "  {} {:.1}% → {:.1}% ({:+.1} pts, {:+.1}%){}\n",
+                label,
+                delta.baseline * 100.0,
+                delta.current * 100.0,
+                delta.delta_points(),
+                delta.change_pct(),
+                if regressions.contains(&name) {
+                    " REGRESSED"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        output.push_str(&format!(
+            "  Result: {} (max regression {}%)\n",
+            if regressions.is_empty() {
+                "PASS"
+            } else {
+                "FAIL"
+            },
+            self.max_regression
+        ));
+        output
+    }
+}
+
 /// Analyze a repository and generate token metrics
 pub fn analyze_repo_tokens(dir_path: &Path) -> Result<RepoTokenMetrics> {
     let files = collect_source_files(dir_path, 10)?;
@@ -525,6 +658,68 @@ mod tests {
         assert!(tokens >= 20 && tokens <= 35);
     }
 
+    fn metrics(compression: f64, token_savings: f64) -> RepoTokenMetrics {
+        RepoTokenMetrics {
+            total_compression: compression,
+            total_token_savings: token_savings,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_metrics_save_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench/baseline.json");
+        let files = vec![TokenMetrics::new(
+            "src/main.rs",
+            "fn main() {\n    println!(\"Hello, world!\");\n}",
+            "symbol: main",
+        )];
+        let saved = RepoTokenMetrics::from_files(files, "overview");
+
+        saved.save(&path).unwrap();
+        let loaded = RepoTokenMetrics::load(&path).unwrap();
+
+        assert_eq!(loaded.files.len(), 1);
+        assert_eq!(loaded.files[0].file, "src/main.rs");
+        assert_eq!(loaded.total_source_tokens, saved.total_source_tokens);
+        assert_eq!(loaded.total_compression, saved.total_compression);
+        assert_eq!(loaded.total_token_savings, saved.total_token_savings);
+        assert!(loaded.compare(&saved, 5.0).regressions().is_empty());
+    }
+
+    #[test]
+    fn test_compare_trips_on_regression() {
+        let baseline = metrics(0.80, 0.80);
+
+        // 0.80 -> 0.78 is a 2.5% drop: within the threshold
+        let comparison = metrics(0.78, 0.82).compare(&baseline, 5.0);
+        assert!(comparison.regressions().is_empty());
+        assert!(comparison.report().contains("Result: PASS"));
+
+        // 0.80 -> 0.70 is a 12.5% drop
+        let comparison = metrics(0.70, 0.82).compare(&baseline, 5.0);
+        assert_eq!(comparison.regressions(), vec!["compression"]);
+        assert!((comparison.compression.change_pct() + 12.5).abs() < 1e-9);
+        assert!(comparison.report().contains("REGRESSED"));
+        assert!(comparison.report().contains("Result: FAIL"));
+
+        // Improvements never trip
+        let comparison = metrics(0.95, 0.95).compare(&baseline, 0.0);
+        assert!(comparison.regressions().is_empty());
+    }
+
+    #[test]
+    fn test_load_rejects_malformed_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            RepoTokenMetrics::load(&path),
+            Err(McpDiffError::Serialization(_))
+        ));
+    }
+
     #[test]
     fn test_token_metrics() {
         let source = "fn main() {\n    println!(\"Hello, world!\");\n}";
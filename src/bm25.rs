@@ -16,16 +16,35 @@
 //!
 //! - k1 = 1.2 (term frequency saturation)
 //! - b = 0.75 (document length normalization)
+//!
+//! # Topic Clustering
+//!
+//! [`Bm25Index::cluster_topics`] groups modules by vocabulary: each module
+//! becomes a TF-IDF vector over the terms of its symbols, the vectors are
+//! clustered with k-means (cosine similarity), and every cluster is labeled
+//! with its highest-weight terms.
 
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::installer::config::{SearchConfig, SemforaConfig};
+use crate::profile::{Phase, PhaseTimer};
+use crate::stemmer;
+use crate::utils::{is_word_char, split_scripts, ScriptClass};
 
 /// BM25 parameters
 const K1: f64 = 1.2;
 const B: f64 = 0.75;
 
+/// Maximum k-means iterations for topic clustering
+const KMEANS_MAX_ITERATIONS: usize = 50;
+
+/// Terms used to label a topic cluster
+const TOPIC_LABEL_TERMS: usize = 5;
+
 /// A document in the BM25 index (represents a symbol)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bm25Document {
@@ -94,6 +113,22 @@ pub struct Bm25SearchResult {
     pub matched_terms: Vec<String>,
 }
 
+/// A group of modules sharing vocabulary, from [`Bm25Index::cluster_topics`]
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicCluster {
+    /// Cluster index (clusters are ordered largest first)
+    pub id: usize,
+    /// Top terms of the cluster, comma-separated
+    pub label: String,
+    /// Modules assigned to this cluster, sorted
+    pub modules: Vec<String>,
+    /// Mean cosine similarity of member modules to the cluster centroid (0.0-1.0)
+    pub cohesion_score: f64,
+}
+
+/// Sparse term vector: term id -> weight
+type TermVector = HashMap<usize, f64>;
+
 impl Default for Bm25Index {
     fn default() -> Self {
         Self::new()
@@ -279,6 +314,114 @@ impl Bm25Index {
         terms.into_iter().map(|(t, _)| t).collect()
     }
 
+    /// Cluster modules into `num_topics` topics by the terms of their symbols
+    ///
+    /// Modules are TF-IDF vectors (one "document" per module) clustered with
+    /// k-means on cosine similarity. Initialization is deterministic, so the
+    /// same index always yields the same topics. Fewer clusters are returned
+    /// when there are fewer modules than `num_topics`.
+    pub fn cluster_topics(&self, num_topics: usize) -> Vec<TopicCluster> {
+        // (1) Group term frequencies by module
+        let mut terms: Vec<&String> = self.inverted_index.keys().collect();
+        terms.sort();
+        let mut module_tf: std::collections::BTreeMap<&str, TermVector> =
+            std::collections::BTreeMap::new();
+        for (term_id, term) in terms.iter().enumerate() {
+            for entry in &self.inverted_index[*term] {
+                if let Some(doc) = self.documents.get(&entry.doc_id) {
+                    *module_tf
+                        .entry(doc.module.as_str())
+                        .or_default()
+                        .entry(term_id)
+                        .or_insert(0.0) += entry.tf as f64;
+                }
+            }
+        }
+        let modules: Vec<&str> = module_tf.keys().copied().collect();
+        let k = num_topics.min(modules.len());
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // (2) TF-IDF per module, L2-normalized
+        let mut module_df: HashMap<usize, f64> = HashMap::new();
+        for tf in module_tf.values() {
+            for term_id in tf.keys() {
+                *module_df.entry(*term_id).or_insert(0.0) += 1.0;
+            }
+        }
+        let n = modules.len() as f64;
+        let vectors: Vec<TermVector> = module_tf
+            .values()
+            .map(|tf| {
+                let total: f64 = tf.values().sum();
+                let weighted = tf
+                    .iter()
+                    .map(|(term_id, count)| {
+                        // Terms found in every module (symbol kinds, common
+                        // verbs) keep a small positive weight
+                        let idf = ((1.0 + n) / module_df[term_id]).ln();
+                        (*term_id, count / total * idf)
+                    })
+                    .collect();
+                normalized(weighted)
+            })
+            .collect();
+
+        // (3) k-means
+        let (assignments, centroids) = kmeans(&vectors, k);
+
+        // (4) Label clusters by their top centroid terms
+        let mut clusters: Vec<TopicCluster> = centroids
+            .iter()
+            .enumerate()
+            .filter_map(|(cluster, centroid)| {
+                let members: Vec<usize> = (0..vectors.len())
+                    .filter(|&i| assignments[i] == cluster)
+                    .collect();
+                if members.is_empty() {
+                    return None;
+                }
+                let cohesion = members
+                    .iter()
+                    .map(|&i| cosine(&vectors[i], centroid))
+                    .sum::<f64>()
+                    / members.len() as f64;
+
+                let mut top: Vec<(&usize, &f64)> = centroid.iter().collect();
+                top.sort_by(|a, b| {
+                    b.1.partial_cmp(a.1)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then_with(|| a.0.cmp(b.0))
+                });
+                let label = top
+                    .iter()
+                    .take(TOPIC_LABEL_TERMS)
+                    .map(|(term_id, _)| terms[**term_id].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                Some(TopicCluster {
+                    id: 0,
+                    label,
+                    modules: members.iter().map(|&i| modules[i].to_string()).collect(),
+                    cohesion_score: cohesion,
+                })
+            })
+            .collect();
+
+        clusters.sort_by(|a, b| {
+            b.modules
+                .len()
+                .cmp(&a.modules.len())
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        for (id, cluster) in clusters.iter_mut().enumerate() {
+            cluster.id = id;
+        }
+        clusters
+    }
+
     /// Save index to a file
     pub fn save(&self, path: &Path) -> std::io::Result<()> {
         let file = std::fs::File::create(path)?;
@@ -293,6 +436,153 @@ impl Bm25Index {
         let index: Self = serde_json::from_str(&content)?;
         Ok(index)
     }
+
+    /// Load an index previously written with [`init_bm25_sqlite`]
+    pub fn load_sqlite(path: &Path) -> std::io::Result<Self> {
+        let to_io = |e: rusqlite::Error| std::io::Error::new(std::io::ErrorKind::Other, e);
+        let conn = Connection::open(path).map_err(to_io)?;
+
+        let mut index = Self::new();
+        let mut stmt = conn
+            .prepare("SELECT doc_id, symbol, file, lines, kind, module, risk, doc_length FROM bm25_documents")
+            .map_err(to_io)?;
+        let documents = stmt
+            .query_map([], |row| {
+                Ok(Bm25Document {
+                    hash: row.get(0)?,
+                    symbol: row.get(1)?,
+                    file: row.get(2)?,
+                    lines: row.get(3)?,
+                    kind: row.get(4)?,
+                    module: row.get(5)?,
+                    risk: row.get(6)?,
+                    doc_length: row.get::<_, i64>(7)? as u32,
+                })
+            })
+            .map_err(to_io)?;
+        for doc in documents {
+            let doc = doc.map_err(to_io)?;
+            index.documents.insert(doc.hash.clone(), doc);
+        }
+
+        let mut stmt = conn
+            .prepare("SELECT term, doc_id, tf FROM bm25_terms")
+            .map_err(to_io)?;
+        let postings = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    TermEntry {
+                        doc_id: row.get(1)?,
+                        tf: row.get::<_, i64>(2)? as u32,
+                    },
+                ))
+            })
+            .map_err(to_io)?;
+        for posting in postings {
+            let (term, entry) = posting.map_err(to_io)?;
+            index.inverted_index.entry(term).or_default().push(entry);
+        }
+
+        index.finalize();
+        Ok(index)
+    }
+}
+
+/// Scale a vector to unit length
+fn normalized(mut vector: TermVector) -> TermVector {
+    let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|w| *w /= norm);
+    }
+    vector
+}
+
+/// Cosine similarity of two vectors
+fn cosine(a: &TermVector, b: &TermVector) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let dot: f64 = small
+        .iter()
+        .filter_map(|(term_id, w)| large.get(term_id).map(|v| w * v))
+        .sum();
+    let norm_a = a.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norm_b = b.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Spherical k-means over unit vectors with farthest-point initialization
+///
+/// Returns the cluster of each vector and the cluster centroids.
+fn kmeans(vectors: &[TermVector], k: usize) -> (Vec<usize>, Vec<TermVector>) {
+    // Seed with the vector richest in terms, then repeatedly with the vector
+    // least similar to any existing seed
+    let first = (0..vectors.len())
+        .max_by(|&a, &b| vectors[a].len().cmp(&vectors[b].len()).then(b.cmp(&a)))
+        .unwrap_or(0);
+    let mut centroids = vec![vectors[first].clone()];
+    while centroids.len() < k {
+        let next = (0..vectors.len())
+            .map(|i| {
+                let closest = centroids
+                    .iter()
+                    .map(|c| cosine(&vectors[i], c))
+                    .fold(f64::NEG_INFINITY, f64::max);
+                (i, closest)
+            })
+            .min_by(|a, b| {
+                a.1.partial_cmp(&b.1)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.0.cmp(&b.0))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        centroids.push(vectors[next].clone());
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, vector) in vectors.iter().enumerate() {
+            let best = (0..centroids.len())
+                .map(|c| (c, cosine(vector, &centroids[c])))
+                .fold((0, f64::NEG_INFINITY), |best, cur| {
+                    if cur.1 > best.1 {
+                        cur
+                    } else {
+                        best
+                    }
+                })
+                .0;
+            if assignments[i] != best {
+                assignments[i] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = TermVector::new();
+            for (i, vector) in vectors.iter().enumerate() {
+                if assignments[i] == c {
+                    for (term_id, w) in vector {
+                        *sum.entry(*term_id).or_insert(0.0) += w;
+                    }
+                }
+            }
+            // An emptied cluster keeps its previous centroid
+            if !sum.is_empty() {
+                *centroid = normalized(sum);
+            }
+        }
+    }
+
+    (assignments, centroids)
 }
 
 pub fn init_bm25_sqlite(conn: &Connection) -> rusqlite::Result<()> {
@@ -315,13 +605,22 @@ pub fn init_bm25_sqlite(conn: &Connection) -> rusqlite::Result<()> {
         );
         CREATE TABLE IF NOT EXISTS bm25_meta (
             total_docs INTEGER,
-            avg_doc_length REAL
+            avg_doc_length REAL,
+            tokenizer_version TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_bm25_terms_term ON bm25_terms(term);
         CREATE INDEX IF NOT EXISTS idx_bm25_terms_doc ON bm25_terms(doc_id);
         CREATE UNIQUE INDEX IF NOT EXISTS idx_bm25_terms_unique ON bm25_terms(term, doc_id);
         "#,
     )?;
+
+    // Indexes written before the tokenizer was versioned lack the column
+    if conn
+        .prepare("SELECT tokenizer_version FROM bm25_meta LIMIT 0")
+        .is_err()
+    {
+        conn.execute_batch("ALTER TABLE bm25_meta ADD COLUMN tokenizer_version TEXT")?;
+    }
     Ok(())
 }
 
@@ -342,17 +641,32 @@ pub fn write_bm25_meta(
     avg_doc_length: f64,
 ) -> rusqlite::Result<()> {
     conn.execute(
-        "INSERT INTO bm25_meta (total_docs, avg_doc_length) VALUES (?, ?)",
-        params![total_docs as i64, avg_doc_length],
+        "INSERT INTO bm25_meta (total_docs, avg_doc_length, tokenizer_version) VALUES (?, ?, ?)",
+        params![total_docs as i64, avg_doc_length, tokenizer().version()],
     )?;
     Ok(())
 }
 
+/// Tokenizer version the index at `path` was built with
+///
+/// `None` when there is no index or it predates tokenizer versioning.
+pub fn read_bm25_tokenizer_version(path: &Path) -> Option<String> {
+    let conn = Connection::open(path).ok()?;
+    conn.query_row(
+        "SELECT tokenizer_version FROM bm25_meta LIMIT 1",
+        [],
+        |row| row.get::<_, Option<String>>(0),
+    )
+    .ok()
+    .flatten()
+}
+
 pub fn search_sqlite(
     path: &Path,
     query: &str,
     limit: usize,
 ) -> std::io::Result<Vec<Bm25SearchResult>> {
+    let _timer = PhaseTimer::start(Phase::Bm25Search);
     let conn =
         Connection::open(path).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
     let (total_docs, avg_doc_length): (f64, f64) = conn
@@ -450,59 +764,162 @@ pub fn search_sqlite(
     Ok(results)
 }
 
-/// Tokenize text into searchable terms
+/// Version of the term extraction rules
 ///
-/// This function:
-/// - Converts to lowercase
-/// - Splits on camelCase and snake_case boundaries
-/// - Removes very short terms (< 2 chars)
-/// - Removes common stop words
-pub fn tokenize(text: &str) -> Vec<String> {
-    let mut terms = Vec::new();
-    let mut seen = std::collections::HashSet::new();
+/// Bump whenever [`Tokenizer::tokenize`] changes the terms it produces, so
+/// indexes built by older versions are rebuilt instead of silently missing
+/// matches.
+pub const TOKENIZER_VERSION: u32 = 2;
+
+/// Words with little search value, dropped unless removed via config
+#[rustfmt::skip]
+const DEFAULT_STOPWORDS: &[&str] = &[
+    // Common English words
+    "the", "a", "an", "is", "are", "was", "be", "to", "of", "and",
+    "in", "it", "for", "on", "with", "as", "at", "by", "or", "if",
+    // Very common code terms
+    "fn", "let", "var", "const", "mut", "pub", "self", "impl",
+    // Accessor vocabulary shared by most symbols
+    "get", "set", "data", "value",
+    // Single characters
+    "i", "j", "k", "n", "x", "y", "e", "t", "s",
+];
+
+/// Term extraction shared by indexing and querying
+///
+/// Both sides must use the same tokenizer or queries stop matching; the
+/// process-wide instance from [`tokenizer`] is built once from the
+/// `[search]` section of the semfora config.
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    stopwords: HashSet<String>,
+}
 
-    // Split on whitespace, punctuation, and underscores
-    for word in text.split(|c: char| !c.is_alphanumeric()) {
-        if word.is_empty() {
-            continue;
+impl Tokenizer {
+    /// Build a tokenizer from the default stopwords adjusted by `config`
+    pub fn new(config: &SearchConfig) -> Self {
+        let mut stopwords: HashSet<String> =
+            DEFAULT_STOPWORDS.iter().map(|w| w.to_string()).collect();
+        stopwords.extend(config.stopwords_add.iter().map(|w| w.to_lowercase()));
+        for word in &config.stopwords_remove {
+            stopwords.remove(&word.to_lowercase());
         }
+        Self { stopwords }
+    }
+
+    /// Identifier stored in the index meta
+    ///
+    /// Combines [`TOKENIZER_VERSION`] with a hash of the stopword list, since
+    /// editing the list changes the terms as much as a code change does.
+    pub fn version(&self) -> String {
+        let mut words: Vec<&str> = self.stopwords.iter().map(String::as_str).collect();
+        words.sort_unstable();
+        format!(
+            "{}-{:016x}",
+            TOKENIZER_VERSION,
+            crate::schema::fnv1a_hash(&words.join(","))
+        )
+    }
+
+    /// Tokenize text into searchable terms
+    ///
+    /// This function:
+    /// - Converts to lowercase
+    /// - Splits on camelCase and snake_case boundaries
+    /// - Splits where the script changes (`getUser用户` → `get`, `user`, `用户`)
+    /// - Indexes CJK runs whole plus as overlapping bigrams, since they have no
+    ///   word separators (`获取用户` → `获取用户`, `获取`, `取用`, `用户`)
+    /// - Keeps emoji and combining characters as part of terms
+    /// - Removes very short terms (< 2 chars, except CJK and emoji)
+    /// - Removes stop words
+    /// - Reduces English words to their Porter stem (`authenticated` → `authent`)
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut terms = Vec::new();
+        let mut seen = HashSet::new();
+        let mut add = |term: String, terms: &mut Vec<String>| {
+            if seen.insert(term.clone()) {
+                terms.push(term);
+            }
+        };
 
-        // Handle camelCase
-        let mut current = String::new();
-        let chars = word.chars();
+        // Split on whitespace, punctuation, and underscores
+        for word in text.split(|c: char| !is_word_char(c)) {
+            if word.is_empty() {
+                continue;
+            }
+
+            let runs = split_scripts(word);
+            for &(class, run) in &runs {
+                match class {
+                    ScriptClass::Cjk => {
+                        add(run.to_string(), &mut terms);
+                        let chars: Vec<char> = run.chars().collect();
+                        if chars.len() > 2 {
+                            for pair in chars.windows(2) {
+                                add(pair.iter().collect(), &mut terms);
+                            }
+                        }
+                    }
+                    ScriptClass::Emoji => add(run.to_string(), &mut terms),
+                    ScriptClass::Other => {
+                        // Handle camelCase
+                        let mut current = String::new();
+
+                        for c in run.chars() {
+                            if c.is_uppercase() && !current.is_empty() {
+                                // Start of new word
+                                if let Some(term) = self.word_term(&current) {
+                                    add(term, &mut terms);
+                                }
+                                current = String::new();
+                            }
+                            current.push(c);
+                        }
 
-        for c in chars {
-            if c.is_uppercase() && !current.is_empty() {
-                // Start of new word
-                if current.len() >= 2 && !is_stop_word(&current) {
-                    let lower = current.to_lowercase();
-                    if seen.insert(lower.clone()) {
-                        terms.push(lower);
+                        // Don't forget the last segment
+                        if let Some(term) = self.word_term(&current) {
+                            add(term, &mut terms);
+                        }
                     }
                 }
-                current = String::new();
             }
-            current.push(c);
-        }
 
-        // Don't forget the last segment
-        if current.len() >= 2 && !is_stop_word(&current) {
-            let lower = current.to_lowercase();
-            if seen.insert(lower.clone()) {
-                terms.push(lower);
+            // Also add the full word if it's different from segments
+            if let Some(term) = self.word_term(word) {
+                add(term, &mut terms);
             }
         }
 
-        // Also add the full word if it's different from segments
-        let lower_word = word.to_lowercase();
-        if lower_word.len() >= 2 && !is_stop_word(&lower_word) {
-            if seen.insert(lower_word.clone()) {
-                terms.push(lower_word);
-            }
+        terms
+    }
+
+    /// Stemmed term for a single word, or `None` if it is too short or a
+    /// stop word
+    fn word_term(&self, word: &str) -> Option<String> {
+        let lower = word.to_lowercase();
+        if lower.chars().count() < 2 || self.stopwords.contains(&lower) {
+            return None;
         }
+        Some(stemmer::stem(&lower))
     }
+}
 
-    terms
+static TOKENIZER: OnceLock<Tokenizer> = OnceLock::new();
+
+/// The tokenizer configured for this process
+///
+/// An unreadable config falls back to the default stopwords rather than
+/// failing every search.
+pub fn tokenizer() -> &'static Tokenizer {
+    TOKENIZER.get_or_init(|| {
+        let config = SemforaConfig::load().map(|c| c.search).unwrap_or_default();
+        Tokenizer::new(&config)
+    })
+}
+
+/// Tokenize text with the process-wide [`tokenizer`]
+pub fn tokenize(text: &str) -> Vec<String> {
+    tokenizer().tokenize(text)
 }
 
 pub fn extract_terms_from_file_path(file_path: &str) -> Vec<String> {
@@ -580,7 +997,7 @@ pub fn extract_terms_from_symbol(
     terms.extend(extract_terms_from_file_path(file_path));
 
     // Add kind as a term
-    terms.push(kind.to_lowercase());
+    terms.extend(tokenize(kind));
 
     // Extract from TOON content if available
     if let Some(content) = toon_content {
@@ -594,20 +1011,6 @@ pub fn extract_terms_from_symbol(
     terms
 }
 
-/// Check if a word is a stop word (common words with little semantic value)
-fn is_stop_word(word: &str) -> bool {
-    matches!(
-        word.to_lowercase().as_str(),
-        // Common programming terms that don't add search value
-        "the" | "a" | "an" | "is" | "are" | "was" | "be" | "to" | "of" | "and" |
-        "in" | "it" | "for" | "on" | "with" | "as" | "at" | "by" | "or" | "if" |
-        // Very common code terms
-        "fn" | "let" | "var" | "const" | "mut" | "pub" | "self" | "impl" |
-        // Single characters
-        "i" | "j" | "k" | "n" | "x" | "y" | "e" | "t" | "s"
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -615,15 +1018,15 @@ mod tests {
     #[test]
     fn test_tokenize_camel_case() {
         let terms = tokenize("handleUserAuthentication");
-        assert!(terms.contains(&"handle".to_string()));
+        assert!(terms.contains(&"handl".to_string()));
         assert!(terms.contains(&"user".to_string()));
-        assert!(terms.contains(&"authentication".to_string()));
+        assert!(terms.contains(&"authent".to_string()));
     }
 
     #[test]
     fn test_tokenize_snake_case() {
         let terms = tokenize("handle_user_auth");
-        assert!(terms.contains(&"handle".to_string()));
+        assert!(terms.contains(&"handl".to_string()));
         assert!(terms.contains(&"user".to_string()));
         assert!(terms.contains(&"auth".to_string()));
     }
@@ -638,6 +1041,59 @@ mod tests {
         assert!(terms.contains(&"test".to_string()));
     }
 
+    #[test]
+    fn test_tokenize_cjk_bigrams() {
+        let terms = tokenize("获取用户信息");
+        for term in ["获取用户信息", "获取", "用户", "户信", "信息"] {
+            assert!(
+                terms.contains(&term.to_string()),
+                "missing {term}: {terms:?}"
+            );
+        }
+
+        // Script boundaries split mixed identifiers
+        let terms = tokenize("loadUser用户_name");
+        for term in ["load", "user", "用户", "name"] {
+            assert!(
+                terms.contains(&term.to_string()),
+                "missing {term}: {terms:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tokenize_emoji_and_combining_characters() {
+        let terms = tokenize("launch🚀Rocket");
+        assert!(terms.contains(&"launch".to_string()));
+        assert!(terms.contains(&"🚀".to_string()));
+        assert!(terms.contains(&"rocket".to_string()));
+
+        // Decomposed "café" stays one term instead of splitting at U+0301
+        let terms = tokenize("cafe\u{301}_menu");
+        assert!(terms.contains(&"cafe\u{301}".to_string()));
+        assert!(!terms.contains(&"cafe".to_string()));
+    }
+
+    #[test]
+    fn test_bm25_search_cjk_query() {
+        let mut index = Bm25Index::new();
+        add_symbol(
+            &mut index,
+            "users",
+            "获取用户信息",
+            &tokenize("获取用户信息")
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+        );
+        add_symbol(&mut index, "orders", "create_order", &["create", "order"]);
+        index.finalize();
+
+        let results = index.search("用户", 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].symbol, "获取用户信息");
+    }
+
     #[test]
     fn test_bm25_search() {
         let mut index = Bm25Index::new();
@@ -684,4 +1140,193 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results[0].symbol, "authenticate_user");
     }
+
+    fn add_symbol(index: &mut Bm25Index, module: &str, symbol: &str, terms: &[&str]) {
+        index.add_document(
+            Bm25Document {
+                hash: format!("{}::{}", module, symbol),
+                symbol: symbol.to_string(),
+                file: format!("src/{}.rs", module),
+                lines: "1-10".to_string(),
+                kind: "function".to_string(),
+                module: module.to_string(),
+                risk: "low".to_string(),
+                doc_length: 0,
+            },
+            terms.iter().map(|t| t.to_string()).collect(),
+        );
+    }
+
+    fn topic_index() -> Bm25Index {
+        let mut index = Bm25Index::new();
+        add_symbol(
+            &mut index,
+            "auth",
+            "login",
+            &["login", "user", "token", "function"],
+        );
+        add_symbol(
+            &mut index,
+            "auth",
+            "logout",
+            &["logout", "token", "session", "function"],
+        );
+        add_symbol(
+            &mut index,
+            "session",
+            "refresh",
+            &["refresh", "session", "token", "function"],
+        );
+        add_symbol(
+            &mut index,
+            "db",
+            "query",
+            &["query", "sql", "connection", "function"],
+        );
+        add_symbol(
+            &mut index,
+            "db",
+            "migrate",
+            &["migrate", "sql", "schema", "function"],
+        );
+        add_symbol(
+            &mut index,
+            "storage",
+            "insert",
+            &["insert", "sql", "connection", "function"],
+        );
+        index.finalize();
+        index
+    }
+
+    #[test]
+    fn test_cluster_topics_groups_modules_by_vocabulary() {
+        let clusters = topic_index().cluster_topics(2);
+        assert_eq!(clusters.len(), 2);
+
+        let mut groups: Vec<Vec<String>> = clusters.iter().map(|c| c.modules.clone()).collect();
+        groups.sort();
+        assert_eq!(groups, vec![vec!["auth", "session"], vec!["db", "storage"]]);
+
+        let db = clusters
+            .iter()
+            .find(|c| c.modules.contains(&"db".to_string()))
+            .unwrap();
+        assert!(db.label.starts_with("sql"), "label: {}", db.label);
+        assert!(db.label.split(", ").count() <= TOPIC_LABEL_TERMS);
+        assert!(clusters
+            .iter()
+            .all(|c| c.cohesion_score > 0.0 && c.cohesion_score <= 1.0 + 1e-9));
+        assert_eq!(
+            clusters.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_cluster_topics_caps_at_module_count() {
+        let index = topic_index();
+        assert_eq!(index.cluster_topics(10).len(), 4);
+        assert!(index.cluster_topics(0).is_empty());
+        assert!(Bm25Index::new().cluster_topics(3).is_empty());
+
+        let single = index.cluster_topics(1);
+        assert_eq!(single[0].modules.len(), 4);
+    }
+
+    /// Small corpus indexed the same way shard generation does it
+    fn fixture_index() -> Bm25Index {
+        let mut index = Bm25Index::new();
+        for (module, symbol) in [
+            ("auth", "authenticate"),
+            ("guards", "isAuthenticated"),
+            ("errors", "AuthenticationError"),
+            ("store", "getData"),
+            ("config", "setValue"),
+            ("accounts", "loadUser"),
+            ("reports", "getReportData"),
+            ("charts", "renderChart"),
+        ] {
+            let terms =
+                extract_terms_from_symbol(symbol, &format!("src/{}.rs", module), "function", None);
+            let terms: Vec<&str> = terms.iter().map(String::as_str).collect();
+            add_symbol(&mut index, module, symbol, &terms);
+        }
+        index.finalize();
+        index
+    }
+
+    #[test]
+    fn test_bm25_search_matches_word_family() {
+        let index = fixture_index();
+        for query in ["authenticate", "authenticated", "authentication"] {
+            let mut symbols: Vec<String> = index
+                .search(query, 10)
+                .into_iter()
+                .map(|r| r.symbol)
+                .collect();
+            symbols.sort();
+            assert_eq!(
+                symbols,
+                vec!["AuthenticationError", "authenticate", "isAuthenticated"],
+                "query {:?}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_bm25_search_ignores_stopword_heavy_terms() {
+        let results = fixture_index().search("get user data", 10);
+        assert_eq!(results[0].symbol, "loadUser", "results: {:?}", results);
+    }
+
+    #[test]
+    fn test_tokenizer_stopwords_config() {
+        let config = SearchConfig {
+            stopwords_add: vec!["Handler".to_string()],
+            stopwords_remove: vec!["get".to_string()],
+            ..SearchConfig::default()
+        };
+        let custom = Tokenizer::new(&config);
+        let terms = custom.tokenize("getRequestHandler");
+        assert!(terms.contains(&"get".to_string()), "terms: {:?}", terms);
+        assert!(
+            !terms.contains(&"handler".to_string()),
+            "terms: {:?}",
+            terms
+        );
+
+        let default = Tokenizer::new(&SearchConfig::default());
+        assert!(!default
+            .tokenize("getRequestHandler")
+            .contains(&"get".to_string()));
+        assert_ne!(custom.version(), default.version());
+        assert_eq!(
+            default.version(),
+            Tokenizer::new(&SearchConfig::default()).version()
+        );
+    }
+
+    #[test]
+    fn test_bm25_meta_records_tokenizer_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("index.sqlite");
+
+        // An index from before tokenizer versioning
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch("CREATE TABLE bm25_meta (total_docs INTEGER, avg_doc_length REAL)")
+            .unwrap();
+        conn.execute("INSERT INTO bm25_meta VALUES (1, 1.0)", [])
+            .unwrap();
+        assert_eq!(read_bm25_tokenizer_version(&path), None);
+
+        init_bm25_sqlite(&conn).unwrap();
+        clear_bm25_sqlite(&conn).unwrap();
+        write_bm25_meta(&conn, 1, 1.0).unwrap();
+        assert_eq!(
+            read_bm25_tokenizer_version(&path),
+            Some(tokenizer().version())
+        );
+    }
 }
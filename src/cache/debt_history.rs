@@ -0,0 +1,138 @@
+//! History of technical debt scores.
+//!
+//! Each `analyze --debt-score` run records its [`TechDebtReport`] in
+//! `debt_history.json` beside the index, so later runs can show whether debt
+//! is going up or down. A run against an index generation that already has a
+//! score replaces it rather than adding a point, so re-running the report
+//! doesn't flatten the trend.
+//!
+//! Like the index manifests, the history survives [`CacheDir::clear`]; only
+//! the newest [`DEBT_HISTORY_LEN`] scores are kept.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::TechDebtReport;
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+
+use super::CacheDir;
+
+/// Number of scores kept per cache
+pub const DEBT_HISTORY_LEN: usize = 20;
+
+/// A recorded debt score
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DebtScoreEntry {
+    /// When the score was computed (RFC 3339)
+    pub recorded_at: String,
+    /// Manifest of the index generation scored, if one was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<String>,
+    pub overall_score: f64,
+    pub duplicate_debt: f64,
+    pub complexity_debt: f64,
+    pub coupling_debt: f64,
+    pub test_coverage_debt: f64,
+    pub remediation_hours: f64,
+}
+
+impl CacheDir {
+    /// Path of the debt score history
+    pub fn debt_history_path(&self) -> PathBuf {
+        self.root.join("debt_history.json")
+    }
+
+    /// Recorded debt scores, oldest first (empty when none were recorded)
+    pub fn load_debt_history(&self) -> Vec<DebtScoreEntry> {
+        fs::read_to_string(self.debt_history_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Record a debt score for the current index generation
+    ///
+    /// Returns the scores recorded before it for earlier generations, oldest
+    /// first.
+    pub fn record_debt_score(&self, report: &TechDebtReport) -> Result<Vec<DebtScoreEntry>> {
+        let generation = self
+            .current_manifest_path()
+            .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()));
+        let mut history = self.load_debt_history();
+        if generation.is_some() {
+            history.retain(|entry| entry.generation != generation);
+        }
+        let previous = history.clone();
+
+        history.push(DebtScoreEntry {
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            generation,
+            overall_score: report.overall_score,
+            duplicate_debt: report.duplicate_debt,
+            complexity_debt: report.complexity_debt,
+            coupling_debt: report.coupling_debt,
+            test_coverage_debt: report.test_coverage_debt,
+            remediation_hours: report.remediation_hours,
+        });
+        let excess = history.len().saturating_sub(DEBT_HISTORY_LEN);
+        history.drain(..excess);
+
+        let content = serde_json::to_string_pretty(&history)
+            .map_err(|e| McpDiffError::Serialization(e.to_string()))?;
+        fs::create_dir_all(&self.root)?;
+        let temp = self.root.join("debt_history.json.tmp");
+        fs::write(&temp, content)?;
+        fs_utils::atomic_rename(&temp, &self.debt_history_path())?;
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(overall_score: f64) -> TechDebtReport {
+        TechDebtReport {
+            overall_score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_debt_score_keeps_one_score_per_generation() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = CacheDir {
+            root: temp.path().join("cache"),
+            repo_root: temp.path().to_path_buf(),
+            repo_hash: "0123456789abcdef".to_string(),
+        };
+        let generation = |name: &str| {
+            fs::create_dir_all(cache.manifests_dir()).unwrap();
+            fs::write(cache.manifests_dir().join(format!("{name}.json")), "{}").unwrap();
+        };
+
+        generation("20260101T000000.000Z-000");
+        assert!(cache.record_debt_score(&report(40.0)).unwrap().is_empty());
+        // Re-running against the same index replaces its score
+        assert!(cache.record_debt_score(&report(41.0)).unwrap().is_empty());
+
+        generation("20260102T000000.000Z-000");
+        let previous = cache.record_debt_score(&report(35.0)).unwrap();
+        assert_eq!(previous.len(), 1);
+        assert_eq!(previous[0].overall_score, 41.0);
+
+        let scores: Vec<f64> = cache
+            .load_debt_history()
+            .iter()
+            .map(|e| e.overall_score)
+            .collect();
+        assert_eq!(scores, [41.0, 35.0]);
+
+        // The history outlives clearing the index
+        cache.clear().unwrap();
+        assert_eq!(cache.load_debt_history().len(), 2);
+    }
+}
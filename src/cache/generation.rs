@@ -0,0 +1,630 @@
+//! Read generations: snapshot isolation for queries during a partial reindex.
+//!
+//! A partial reindex replaces a few module shards, their symbol shards and
+//! the symbol index. Rewriting them in place lets a concurrent query read
+//! the new symbol index but an old module shard, or return a hash whose
+//! shard has not been written yet. Instead each partial reindex writes the
+//! files it replaces side by side under versioned names
+//! (`modules/api@g3.toon`, `symbols/<hash>@g3.toon`, `symbol_index@g3.jsonl`),
+//! records them in a [`Generation`] manifest (`generations/3.json`) and then
+//! swaps `generations/CURRENT` to it in a single rename.
+//!
+//! [`CacheDir`] path accessors resolve through the generation current when
+//! they are called. A request that opens several files pins one generation
+//! with [`CacheDir::pin_generation`]: until the pin drops, every lookup for
+//! that cache on the thread resolves the pinned generation, so the request
+//! sees one consistent index however many reindexes finish meanwhile.
+//!
+//! After each publish, superseded generations are collected once nothing in
+//! this process holds them and [`GRACE_PERIOD`] has passed since they were
+//! replaced, which covers readers in other processes. A full index write
+//! starts by dropping all generations ([`reset`]) and writes the plain
+//! names again.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+
+use super::{sanitize_filename, CacheDir};
+
+/// How long a superseded generation stays readable for other processes
+pub const GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// File in `generations/` naming the current generation, as `<id> <created_at>`
+///
+/// The timestamp tells a generation apart from an earlier one with the same
+/// id that was dropped by [`reset`].
+const CURRENT_FILE: &str = "CURRENT";
+
+/// Shards replaced since the last full index write
+///
+/// Each generation carries every replacement since the last full write, so
+/// resolving a path never needs more than the one manifest. Generation 0 is
+/// the plain layout a full write leaves behind.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Generation {
+    pub id: u64,
+
+    /// When the generation was published (Unix milliseconds)
+    pub created_at: u64,
+
+    /// Replaced module shards: shard name (see [`CacheDir::list_modules`])
+    /// -> file in `modules/`, or `None` when the module no longer exists
+    #[serde(default)]
+    pub modules: BTreeMap<String, Option<String>>,
+
+    /// Replaced symbol shards: symbol hash -> file in `symbols/`
+    #[serde(default)]
+    pub symbols: BTreeMap<String, String>,
+
+    /// Replacement symbol index in the cache root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_index: Option<String>,
+}
+
+impl Generation {
+    /// Start the generation after this one, keeping its replacements
+    pub fn next(&self) -> Self {
+        Self {
+            id: self.id + 1,
+            created_at: 0,
+            ..self.clone()
+        }
+    }
+
+    /// Versioned file name of a module shard written for this generation
+    fn module_file(&self, module_name: &str) -> String {
+        format!("{}@g{}.toon", sanitize_filename(module_name), self.id)
+    }
+
+    /// Replace a module's shard, returning the file in `modules/` to write
+    pub fn replace_module(&mut self, module_name: &str) -> String {
+        let file = self.module_file(module_name);
+        self.modules
+            .insert(sanitize_filename(module_name), Some(file.clone()));
+        file
+    }
+
+    /// Mark a module as removed in this generation
+    pub fn remove_module(&mut self, module_name: &str) {
+        self.modules.insert(sanitize_filename(module_name), None);
+    }
+
+    /// Replace a symbol's shard, returning the file in `symbols/` to write
+    pub fn replace_symbol(&mut self, symbol_hash: &str) -> String {
+        let file = format!("{}@g{}.toon", symbol_hash, self.id);
+        self.symbols.insert(symbol_hash.to_string(), file.clone());
+        file
+    }
+
+    /// Replace the symbol index, returning the file in the cache root to write
+    pub fn replace_symbol_index(&mut self) -> String {
+        let file = format!("symbol_index@g{}.jsonl", self.id);
+        self.symbol_index = Some(file.clone());
+        file
+    }
+
+    /// Symbol index file in the cache root
+    pub(crate) fn symbol_index_file(&self) -> &str {
+        self.symbol_index.as_deref().unwrap_or("symbol_index.jsonl")
+    }
+
+    /// File in `modules/` holding a module, if this generation replaced it
+    ///
+    /// A removed module resolves to a versioned name that is never written,
+    /// so it reads as missing while older generations still see its shard.
+    pub(crate) fn module_override(&self, module_name: &str) -> Option<String> {
+        self.modules
+            .get(&sanitize_filename(module_name))
+            .map(|file| {
+                file.clone()
+                    .unwrap_or_else(|| self.module_file(module_name))
+            })
+    }
+
+    /// Every versioned file this generation reads, relative to the cache root
+    fn files(&self) -> impl Iterator<Item = String> + '_ {
+        let modules = self
+            .modules
+            .values()
+            .flatten()
+            .map(|file| format!("modules/{}", file));
+        let symbols = self
+            .symbols
+            .values()
+            .map(|file| format!("symbols/{}", file));
+        let index = self.symbol_index.iter().flat_map(|file| {
+            let bin = Path::new(file).with_extension("bin");
+            [file.clone(), bin.to_string_lossy().into_owned()]
+        });
+        modules.chain(symbols).chain(index)
+    }
+}
+
+/// Generation a versioned file name was written for (`api@g3.toon` -> 3)
+pub(crate) fn version_of(file_name: &str) -> Option<u64> {
+    let (_, rest) = file_name.rsplit_once("@g")?;
+    let digits = rest.split('.').next()?;
+    digits.parse().ok()
+}
+
+/// Generations loaded by this process, by cache root
+///
+/// Pins and callers of [`current`] share these `Arc`s, so a strong count
+/// above one means someone still reads that generation.
+static LOADED: Lazy<Mutex<HashMap<PathBuf, BTreeMap<u64, Arc<Generation>>>>> =
+    Lazy::new(Mutex::default);
+
+thread_local! {
+    /// Generations pinned by this thread, by cache root
+    static PINNED: RefCell<HashMap<PathBuf, Arc<Generation>>> = RefCell::new(HashMap::new());
+}
+
+fn loaded() -> std::sync::MutexGuard<'static, HashMap<PathBuf, BTreeMap<u64, Arc<Generation>>>> {
+    LOADED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn manifest_path(root: &Path, id: u64) -> PathBuf {
+    root.join("generations").join(format!("{}.json", id))
+}
+
+fn load_manifest(path: &Path) -> Result<Generation> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| McpDiffError::CacheCorrupt {
+        path: path.to_path_buf(),
+        message: format!("not a generation manifest: {}", e),
+    })
+}
+
+/// The generation `generations/CURRENT` names, if any has been published
+pub(crate) fn current(root: &Path) -> Option<Arc<Generation>> {
+    let pointer = root.join("generations").join(CURRENT_FILE);
+    let pointer = fs::read_to_string(pointer).ok()?;
+    let mut parts = pointer.split_whitespace();
+    let id: u64 = parts.next()?.parse().ok()?;
+    let created_at: u64 = parts.next()?.parse().ok()?;
+
+    if let Some(generation) = loaded()
+        .get(root)
+        .and_then(|gens| gens.get(&id))
+        .filter(|generation| generation.created_at == created_at)
+    {
+        return Some(Arc::clone(generation));
+    }
+
+    let generation = match load_manifest(&manifest_path(root, id)) {
+        Ok(generation) => Arc::new(generation),
+        Err(e) => {
+            tracing::warn!("Ignoring generation {} of {}: {}", id, root.display(), e);
+            return None;
+        }
+    };
+    loaded()
+        .entry(root.to_path_buf())
+        .or_default()
+        .insert(id, Arc::clone(&generation));
+    Some(generation)
+}
+
+/// The generation reads of `root` resolve: the pinned one, else the current one
+pub(crate) fn resolve(root: &Path) -> Option<Arc<Generation>> {
+    PINNED
+        .with(|pinned| pinned.borrow().get(root).cloned())
+        .or_else(|| current(root))
+}
+
+/// Run `f` with this thread's reads of `root` resolving `generation`
+///
+/// Lets a writer fill in a generation it has not published yet through the
+/// usual [`CacheDir`] accessors.
+pub(crate) fn with_generation<R>(root: &Path, generation: &Generation, f: impl FnOnce() -> R) -> R {
+    struct Restore<'a> {
+        root: &'a Path,
+        previous: Option<Arc<Generation>>,
+    }
+    impl Drop for Restore<'_> {
+        fn drop(&mut self) {
+            PINNED.with(|pinned| {
+                let mut pinned = pinned.borrow_mut();
+                match self.previous.take() {
+                    Some(previous) => pinned.insert(self.root.to_path_buf(), previous),
+                    None => pinned.remove(self.root),
+                };
+            });
+        }
+    }
+
+    let previous = PINNED.with(|pinned| {
+        pinned
+            .borrow_mut()
+            .insert(root.to_path_buf(), Arc::new(generation.clone()))
+    });
+    let _restore = Restore { root, previous };
+    f()
+}
+
+/// Make `generation` current, then collect superseded generations
+///
+/// Callers hold the index lock and have written every file `generation`
+/// names.
+pub fn publish(cache: &CacheDir, mut generation: Generation) -> Result<Arc<Generation>> {
+    let dir = cache.generations_dir();
+    fs::create_dir_all(&dir)?;
+    generation.created_at = now_ms();
+
+    let content = serde_json::to_string(&generation)
+        .map_err(|e| McpDiffError::Serialization(e.to_string()))?;
+    let temp = dir.join(format!("{}.json.tmp", generation.id));
+    fs::write(&temp, content)?;
+    fs_utils::atomic_rename(&temp, &manifest_path(&cache.root, generation.id))?;
+
+    let temp = dir.join("CURRENT.tmp");
+    fs::write(
+        &temp,
+        format!("{} {}", generation.id, generation.created_at),
+    )?;
+    fs_utils::atomic_rename(&temp, &dir.join(CURRENT_FILE))?;
+
+    let generation = Arc::new(generation);
+    loaded()
+        .entry(cache.root.clone())
+        .or_default()
+        .insert(generation.id, Arc::clone(&generation));
+
+    if let Err(e) = collect_garbage(cache) {
+        tracing::warn!("Failed to collect old index generations: {}", e);
+    }
+    Ok(generation)
+}
+
+/// Drop every generation, going back to the plain layout
+///
+/// Modules the current generation removed lose their plain shards too, so
+/// they don't reappear. Used before a full index write.
+pub fn reset(cache: &CacheDir) -> Result<()> {
+    if let Some(generation) = current(&cache.root) {
+        for (module, file) in &generation.modules {
+            if file.is_none() {
+                let _ = fs::remove_file(cache.modules_dir().join(format!("{}.toon", module)));
+            }
+        }
+    }
+
+    let dir = cache.generations_dir();
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    for path in versioned_files(cache) {
+        fs::remove_file(path)?;
+    }
+    loaded().remove(&cache.root);
+    Ok(())
+}
+
+/// Versioned shard and index files in the cache
+fn versioned_files(cache: &CacheDir) -> Vec<PathBuf> {
+    [cache.root.clone(), cache.modules_dir(), cache.symbols_dir()]
+        .iter()
+        .flat_map(|dir| fs::read_dir(dir).into_iter().flatten())
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(version_of)
+                .is_some()
+        })
+        .collect()
+}
+
+/// Remove superseded generations nobody reads any more, with the files
+/// only they referenced
+///
+/// A generation is kept while this process holds it (a pin or a
+/// [`CacheDir`] lookup in progress) and for [`GRACE_PERIOD`] after it was
+/// replaced. Returns how many generations were removed.
+pub fn collect_garbage(cache: &CacheDir) -> Result<usize> {
+    let Some(current) = current(&cache.root) else {
+        return Ok(0);
+    };
+
+    let mut manifests: BTreeMap<u64, Generation> = BTreeMap::new();
+    for entry in fs::read_dir(cache.generations_dir())?.flatten() {
+        let path = entry.path();
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok());
+        if let (Some(id), true) = (id, path.extension().is_some_and(|ext| ext == "json")) {
+            // An unreadable manifest is kept rather than guessed at
+            if let Ok(generation) = load_manifest(&path) {
+                manifests.insert(id, generation);
+            }
+        }
+    }
+
+    let held: HashSet<u64> = loaded()
+        .get(&cache.root)
+        .map(|gens| {
+            gens.iter()
+                .filter(|(_, generation)| Arc::strong_count(generation) > 1)
+                .map(|(id, _)| *id)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let now = now_ms();
+    let grace = GRACE_PERIOD.as_millis() as u64;
+    let ids: Vec<u64> = manifests.keys().copied().collect();
+    let expired: Vec<u64> = ids
+        .iter()
+        .enumerate()
+        .filter(|(i, id)| {
+            // A generation stops being current when its successor is published
+            let replaced_at = ids
+                .get(i + 1)
+                .and_then(|next| manifests.get(next))
+                .map_or(current.created_at, |next| next.created_at);
+            **id < current.id && !held.contains(id) && now.saturating_sub(replaced_at) >= grace
+        })
+        .map(|(_, id)| *id)
+        .collect();
+    if expired.is_empty() {
+        return Ok(0);
+    }
+
+    for id in &expired {
+        manifests.remove(id);
+        fs::remove_file(manifest_path(&cache.root, *id))?;
+    }
+    if let Some(gens) = loaded().get_mut(&cache.root) {
+        gens.retain(|id, _| !expired.contains(id));
+    }
+
+    // Files of generations still being written (newer than current) are left alone
+    let referenced: HashSet<String> = manifests
+        .values()
+        .chain(std::iter::once(current.as_ref()))
+        .flat_map(Generation::files)
+        .collect();
+    for path in versioned_files(cache) {
+        let Ok(relative) = path.strip_prefix(&cache.root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let version = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(version_of);
+        if version.is_some_and(|v| v <= current.id) && !referenced.contains(&relative) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(expired.len())
+}
+
+/// One generation of a cache held for the current thread
+///
+/// See [`CacheDir::pin_generation`]. Dropping the pin releases the
+/// generation for garbage collection.
+#[must_use = "the generation is released when the pin is dropped"]
+pub struct GenerationPin {
+    /// `None` when the thread already had this cache pinned
+    root: Option<PathBuf>,
+    id: u64,
+    /// Pins belong to the thread that took them
+    _not_send: PhantomData<*const ()>,
+}
+
+impl GenerationPin {
+    /// The pinned generation (0 for the plain layout)
+    pub fn generation_id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for GenerationPin {
+    fn drop(&mut self) {
+        if let Some(root) = self.root.take() {
+            PINNED.with(|pinned| pinned.borrow_mut().remove(&root));
+        }
+    }
+}
+
+impl CacheDir {
+    /// Directory holding the generation manifests
+    pub fn generations_dir(&self) -> PathBuf {
+        self.root.join("generations")
+    }
+
+    /// Generation reads currently resolve (0 for the plain layout)
+    pub fn generation_id(&self) -> u64 {
+        resolve(&self.root).map_or(0, |g| g.id)
+    }
+
+    /// Pin the current generation for this thread until the pin drops
+    ///
+    /// Reindexes publishing newer generations meanwhile are invisible to
+    /// reads on this thread, and the pinned generation's files are not
+    /// collected. Pinning is re-entrant: an inner pin keeps the outer one's
+    /// generation.
+    pub fn pin_generation(&self) -> GenerationPin {
+        let (root, id) = PINNED.with(|pinned| {
+            let mut pinned = pinned.borrow_mut();
+            if let Some(generation) = pinned.get(&self.root) {
+                return (None, generation.id);
+            }
+            let generation = current(&self.root).unwrap_or_default();
+            let id = generation.id;
+            pinned.insert(self.root.clone(), generation);
+            (Some(self.root.clone()), id)
+        });
+        GenerationPin {
+            root,
+            id,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cache(dir: &Path) -> CacheDir {
+        let cache = CacheDir {
+            root: dir.join("cache"),
+            repo_root: dir.to_path_buf(),
+            repo_hash: "test".to_string(),
+        };
+        cache.init().unwrap();
+        cache
+    }
+
+    /// Publish a generation replacing module `api` with `content`
+    fn publish_api(cache: &CacheDir, content: &str) -> Arc<Generation> {
+        let mut next = current(&cache.root).unwrap_or_default().next();
+        let file = next.replace_module("api");
+        fs::write(cache.modules_dir().join(file), content).unwrap();
+        publish(cache, next).unwrap()
+    }
+
+    #[test]
+    fn test_version_of() {
+        assert_eq!(version_of("src.api@g3.toon"), Some(3));
+        assert_eq!(version_of("symbol_index@g12.jsonl"), Some(12));
+        assert_eq!(version_of("src.api.toon"), None);
+        assert_eq!(version_of("symbol_index.jsonl"), None);
+    }
+
+    #[test]
+    fn test_paths_resolve_through_current_generation() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = test_cache(temp.path());
+        fs::write(cache.module_path("api"), "plain").unwrap();
+        fs::write(cache.module_path("old"), "plain").unwrap();
+        assert_eq!(cache.generation_id(), 0);
+
+        let mut next = Generation::default().next();
+        let file = next.replace_module("api");
+        fs::write(cache.modules_dir().join(file), "g1").unwrap();
+        next.remove_module("old");
+        next.replace_symbol("h1");
+        publish(&cache, next).unwrap();
+
+        assert_eq!(cache.generation_id(), 1);
+        assert_eq!(fs::read_to_string(cache.module_path("api")).unwrap(), "g1");
+        assert!(!cache.module_path("old").exists());
+        assert_eq!(cache.list_modules(), vec!["api".to_string()]);
+        assert!(cache.symbol_path("h1").ends_with("symbols/h1@g1.toon"));
+        assert!(cache.symbol_path("h2").ends_with("symbols/h2.toon"));
+    }
+
+    #[test]
+    fn test_pin_hides_later_generations() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = test_cache(temp.path());
+        publish_api(&cache, "first");
+
+        let pin = cache.pin_generation();
+        assert_eq!(pin.generation_id(), 1);
+        publish_api(&cache, "second");
+        assert_eq!(
+            fs::read_to_string(cache.module_path("api")).unwrap(),
+            "first"
+        );
+
+        // Re-entrant: the inner pin keeps the outer generation
+        let inner = cache.pin_generation();
+        assert_eq!(inner.generation_id(), 1);
+        drop(inner);
+        assert_eq!(cache.generation_id(), 1);
+
+        // Other threads see the newest generation
+        let other = cache.clone();
+        let seen = std::thread::spawn(move || fs::read_to_string(other.module_path("api")))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(seen, "second");
+
+        drop(pin);
+        assert_eq!(
+            fs::read_to_string(cache.module_path("api")).unwrap(),
+            "second"
+        );
+    }
+
+    #[test]
+    fn test_collect_garbage_spares_pinned_and_current() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = test_cache(temp.path());
+        publish_api(&cache, "first");
+        let pin = cache.pin_generation();
+        publish_api(&cache, "second");
+        publish_api(&cache, "third");
+
+        // Age every generation past the grace period
+        for id in 1..=3 {
+            let path = manifest_path(&cache.root, id);
+            let mut generation = load_manifest(&path).unwrap();
+            generation.created_at -= GRACE_PERIOD.as_millis() as u64 + 1;
+            fs::write(&path, serde_json::to_string(&generation).unwrap()).unwrap();
+        }
+        // Generation 2 is unpinned and replaced; 1 is pinned, 3 is current
+        assert_eq!(collect_garbage(&cache).unwrap(), 1);
+        assert!(cache.modules_dir().join("api@g1.toon").exists());
+        assert!(!cache.modules_dir().join("api@g2.toon").exists());
+        assert!(cache.modules_dir().join("api@g3.toon").exists());
+
+        drop(pin);
+        assert_eq!(collect_garbage(&cache).unwrap(), 1);
+        assert!(!cache.modules_dir().join("api@g1.toon").exists());
+        assert!(!manifest_path(&cache.root, 1).exists());
+        assert_eq!(
+            fs::read_to_string(cache.module_path("api")).unwrap(),
+            "third"
+        );
+    }
+
+    #[test]
+    fn test_reset_returns_to_plain_layout() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = test_cache(temp.path());
+        fs::write(cache.module_path("api"), "plain").unwrap();
+        fs::write(cache.module_path("gone"), "plain").unwrap();
+        let mut next = Generation::default().next();
+        next.remove_module("gone");
+        publish(&cache, next).unwrap();
+        publish_api(&cache, "g2");
+
+        reset(&cache).unwrap();
+        assert_eq!(cache.generation_id(), 0);
+        assert!(!cache.generations_dir().exists());
+        assert!(!cache.module_path("gone").exists());
+        assert_eq!(
+            fs::read_to_string(cache.module_path("api")).unwrap(),
+            "plain"
+        );
+        assert_eq!(cache.list_modules(), vec!["api".to_string()]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Advisory lock serializing index writes.
+//!
+//! Two processes writing the same cache (a pre-commit hook running
+//! `semfora-engine index` while the MCP server refreshes, say) interleave
+//! shard files and corrupt the index. Every index writer therefore holds an
+//! [`IndexLock`] for the cache while it clears and rewrites it.
+//!
+//! The lock file sits next to the cache directory (`<hash>.lock`) rather than
+//! inside it, so clearing or quarantining the cache can't delete a lock that
+//! another process is waiting on. The OS releases the lock when the holder
+//! exits, so a crashed indexer never leaves the cache locked.
+//!
+//! Acquisition is re-entrant per thread: code already holding the lock (e.g.
+//! `ensure_fresh_index` calling into index generation) passes straight
+//! through. Other threads and processes wait.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::error::{McpDiffError, Result};
+
+use super::CacheDir;
+
+/// Environment variable overriding how long to wait for another indexer
+pub const INDEX_LOCK_TIMEOUT_ENV: &str = "SEMFORA_INDEX_LOCK_TIMEOUT_SECS";
+
+/// Default wait for another indexer to finish
+pub const DEFAULT_INDEX_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay between attempts while waiting for the lock
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Lock files held by this thread
+    static HELD: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Exclusive hold on a cache's index lock, released on drop
+#[derive(Debug)]
+pub struct IndexLock {
+    path: PathBuf,
+    /// `None` when this thread already held the lock
+    file: Option<File>,
+}
+
+impl IndexLock {
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            let _ = file.unlock();
+            HELD.with(|held| held.borrow_mut().remove(&self.path));
+        }
+    }
+}
+
+/// How long to wait for the lock: `SEMFORA_INDEX_LOCK_TIMEOUT_SECS`, or
+/// [`DEFAULT_INDEX_LOCK_TIMEOUT`] when unset or invalid
+///
+/// `0` fails immediately if another indexer holds the lock.
+pub fn index_lock_timeout() -> Duration {
+    parse_lock_timeout(std::env::var(INDEX_LOCK_TIMEOUT_ENV).ok().as_deref())
+}
+
+fn parse_lock_timeout(value: Option<&str>) -> Duration {
+    let Some(value) = value.map(str::trim) else {
+        return DEFAULT_INDEX_LOCK_TIMEOUT;
+    };
+    match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            tracing::warn!(
+                "Ignoring {}={:?}: expected a number of seconds",
+                INDEX_LOCK_TIMEOUT_ENV,
+                value
+            );
+            DEFAULT_INDEX_LOCK_TIMEOUT
+        }
+    }
+}
+
+impl CacheDir {
+    /// Path of the lock file guarding index writes to this cache
+    pub fn index_lock_path(&self) -> PathBuf {
+        let mut name = self.root.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        self.root.with_file_name(name)
+    }
+
+    /// Take the index lock, waiting up to [`index_lock_timeout`]
+    pub fn lock_index(&self) -> Result<IndexLock> {
+        self.lock_index_within(index_lock_timeout())
+    }
+
+    /// Take the index lock, waiting up to `timeout` for another indexer
+    ///
+    /// Fails with [`McpDiffError::IndexLocked`] if the lock is still held
+    /// when `timeout` runs out.
+    pub fn lock_index_within(&self, timeout: Duration) -> Result<IndexLock> {
+        let path = self.index_lock_path();
+        if HELD.with(|held| held.borrow().contains(&path)) {
+            return Ok(IndexLock { path, file: None });
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| McpDiffError::IoError {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
+
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) if start.elapsed() < timeout => {
+                    std::thread::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed())));
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(McpDiffError::IndexLocked {
+                        repo: self.repo_root.clone(),
+                        waited_ms: start.elapsed().as_millis() as u64,
+                    });
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(McpDiffError::IoError {
+                        path,
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        HELD.with(|held| held.borrow_mut().insert(path.clone()));
+        Ok(IndexLock {
+            path,
+            file: Some(file),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_in(dir: &Path) -> CacheDir {
+        CacheDir {
+            root: dir.join("cache").join("0123456789abcdef"),
+            repo_root: dir.join("repo"),
+            repo_hash: "0123456789abcdef".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lock_file_sits_beside_cache() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = cache_in(temp.path());
+        assert_eq!(
+            cache.index_lock_path(),
+            temp.path().join("cache").join("0123456789abcdef.lock")
+        );
+
+        let lock = cache.lock_index_within(Duration::ZERO).unwrap();
+        assert!(lock.path().exists());
+        // Clearing the cache leaves the lock in place
+        fs::create_dir_all(&cache.root).unwrap();
+        cache.clear().unwrap();
+        assert!(lock.path().exists());
+    }
+
+    #[test]
+    fn test_lock_excludes_other_threads_until_dropped() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = cache_in(temp.path());
+        let lock = cache.lock_index_within(Duration::ZERO).unwrap();
+
+        let contender = cache.clone();
+        let err = std::thread::spawn(move || {
+            contender
+                .lock_index_within(Duration::from_millis(100))
+                .unwrap_err()
+        })
+        .join()
+        .unwrap();
+        assert!(matches!(err, McpDiffError::IndexLocked { .. }), "{err}");
+        assert!(err.to_string().contains("in progress"));
+
+        // A waiter gets the lock once the holder releases it
+        let waiter = cache.clone();
+        let handle =
+            std::thread::spawn(move || waiter.lock_index_within(Duration::from_secs(10)).is_ok());
+        std::thread::sleep(Duration::from_millis(100));
+        drop(lock);
+        assert!(handle.join().unwrap());
+    }
+
+    #[test]
+    fn test_lock_is_reentrant_on_one_thread() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = cache_in(temp.path());
+        let outer = cache.lock_index_within(Duration::ZERO).unwrap();
+        let inner = cache.lock_index_within(Duration::ZERO).unwrap();
+        drop(inner);
+
+        // Dropping the inner guard keeps the outer hold
+        let contender = cache.clone();
+        let still_held =
+            std::thread::spawn(move || contender.lock_index_within(Duration::ZERO).is_err())
+                .join()
+                .unwrap();
+        assert!(still_held);
+        drop(outer);
+    }
+
+    #[test]
+    fn test_parse_lock_timeout() {
+        assert_eq!(parse_lock_timeout(None), DEFAULT_INDEX_LOCK_TIMEOUT);
+        assert_eq!(parse_lock_timeout(Some(" 5 ")), Duration::from_secs(5));
+        assert_eq!(parse_lock_timeout(Some("0")), Duration::ZERO);
+        assert_eq!(parse_lock_timeout(Some("soon")), DEFAULT_INDEX_LOCK_TIMEOUT);
+    }
+}
@@ -0,0 +1,443 @@
+//! Index manifests: a machine-readable record of each index generation.
+//!
+//! Every full index generation ends by writing an [`IndexManifest`] to
+//! `manifests/` in the cache: per-file content hashes and, per module, the
+//! hash of every symbol. Comparing two manifests ([`diff_manifests`]) shows
+//! exactly how the index changed, e.g. across an engine upgrade.
+//!
+//! [`CacheDir::clear`] keeps the manifests, so a rebuilt index can still be
+//! compared with the one it replaced. Only the newest
+//! `SEMFORA_MANIFEST_HISTORY` manifests (default
+//! [`DEFAULT_MANIFEST_HISTORY`]) are retained.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+use crate::schema::{fnv1a_hash, SCHEMA_VERSION};
+
+use super::{CacheDir, SymbolIndexEntry};
+
+/// Version of the manifest format itself
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Environment variable overriding how many manifests each cache keeps
+pub const MANIFEST_HISTORY_ENV: &str = "SEMFORA_MANIFEST_HISTORY";
+
+/// Default number of manifests kept per cache
+pub const DEFAULT_MANIFEST_HISTORY: usize = 5;
+
+/// Record of one index generation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexManifest {
+    /// Manifest format version ([`MANIFEST_VERSION`])
+    pub manifest_version: u32,
+
+    /// Index schema version the generation was written with
+    pub schema_version: String,
+
+    /// Engine version that wrote the index
+    pub engine_version: String,
+
+    /// When the index was generated (RFC 3339)
+    pub generated_at: String,
+
+    /// Totals across the index
+    pub stats: ManifestStats,
+
+    /// Symbols per module
+    pub modules: BTreeMap<String, ModuleManifest>,
+
+    /// Content hash of every indexed file, keyed by repo-relative path
+    pub files: BTreeMap<String, String>,
+}
+
+/// Totals recorded in a manifest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestStats {
+    pub files: usize,
+    pub modules: usize,
+    pub symbols: usize,
+}
+
+/// Symbols of one module in a manifest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleManifest {
+    pub symbol_count: usize,
+
+    /// Symbol hash keyed by `file::name` (`file::name#2` for the second
+    /// symbol of that name in the file, and so on)
+    pub symbols: BTreeMap<String, String>,
+}
+
+impl IndexManifest {
+    /// Build the manifest of an index from its symbol entries and the files
+    /// it was generated from
+    ///
+    /// File paths are recorded relative to `repo_root`, so manifests of two
+    /// checkouts of a repository can be compared.
+    pub fn build(repo_root: &Path, entries: &[SymbolIndexEntry], files: &[String]) -> Self {
+        let files: BTreeMap<String, String> = files
+            .iter()
+            .filter_map(|file| {
+                let path = Path::new(file);
+                let path = if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    repo_root.join(path)
+                };
+                let content = fs::read_to_string(&path).ok()?;
+                Some((
+                    relative_to(repo_root, file),
+                    format!("{:016x}", fnv1a_hash(&content)),
+                ))
+            })
+            .collect();
+
+        let mut modules: BTreeMap<String, ModuleManifest> = BTreeMap::new();
+        for entry in entries {
+            let module = modules.entry(entry.module.clone()).or_default();
+            let base = format!("{}::{}", relative_to(repo_root, &entry.file), entry.symbol);
+            let mut key = base.clone();
+            let mut occurrence = 1;
+            while module.symbols.contains_key(&key) {
+                occurrence += 1;
+                key = format!("{}#{}", base, occurrence);
+            }
+            module.symbols.insert(key, entry.hash.clone());
+        }
+        for module in modules.values_mut() {
+            module.symbol_count = module.symbols.len();
+        }
+
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            schema_version: SCHEMA_VERSION.to_string(),
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            stats: ManifestStats {
+                files: files.len(),
+                modules: modules.len(),
+                symbols: entries.len(),
+            },
+            modules,
+            files,
+        }
+    }
+
+    /// Read a manifest file
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Err(McpDiffError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| McpDiffError::CacheCorrupt {
+            path: path.to_path_buf(),
+            message: format!("not an index manifest: {}", e),
+        })
+    }
+}
+
+/// `file` relative to `repo_root`, with `/` separators
+fn relative_to(repo_root: &Path, file: &str) -> String {
+    Path::new(file)
+        .strip_prefix(repo_root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| file.to_string())
+        .replace('\\', "/")
+}
+
+/// How many manifests each cache keeps: `SEMFORA_MANIFEST_HISTORY`, or
+/// [`DEFAULT_MANIFEST_HISTORY`] when unset or invalid
+///
+/// At least one (the current index's) is always kept.
+pub fn manifest_history() -> usize {
+    parse_manifest_history(std::env::var(MANIFEST_HISTORY_ENV).ok().as_deref())
+}
+
+fn parse_manifest_history(value: Option<&str>) -> usize {
+    let Some(value) = value.map(str::trim) else {
+        return DEFAULT_MANIFEST_HISTORY;
+    };
+    match value.parse::<usize>() {
+        Ok(count) => count.max(1),
+        Err(_) => {
+            tracing::warn!(
+                "Ignoring {}={:?}: expected a number of manifests",
+                MANIFEST_HISTORY_ENV,
+                value
+            );
+            DEFAULT_MANIFEST_HISTORY
+        }
+    }
+}
+
+impl CacheDir {
+    /// Directory holding the manifests of recent index generations
+    pub fn manifests_dir(&self) -> PathBuf {
+        self.root.join("manifests")
+    }
+
+    /// Manifest files, oldest first
+    pub fn list_manifests(&self) -> Vec<PathBuf> {
+        let mut manifests: Vec<PathBuf> = fs::read_dir(self.manifests_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        // Names are timestamps, so they sort chronologically
+        manifests.sort();
+        manifests
+    }
+
+    /// Manifest of the current index, if one was written
+    pub fn current_manifest_path(&self) -> Option<PathBuf> {
+        self.list_manifests().pop()
+    }
+
+    /// Manifest of the generation before the current one, if retained
+    pub fn previous_manifest_path(&self) -> Option<PathBuf> {
+        let mut manifests = self.list_manifests();
+        manifests.pop();
+        manifests.pop()
+    }
+
+    /// Write the manifest of a new generation, dropping all but the newest
+    /// `keep` manifests
+    pub fn save_manifest(&self, manifest: &IndexManifest, keep: usize) -> Result<PathBuf> {
+        let dir = self.manifests_dir();
+        fs::create_dir_all(&dir)?;
+
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+        let mut attempt = 0;
+        let mut path = dir.join(format!("{}-{:03}.json", stamp, attempt));
+        while path.exists() {
+            attempt += 1;
+            path = dir.join(format!("{}-{:03}.json", stamp, attempt));
+        }
+
+        let content = serde_json::to_string_pretty(manifest)
+            .map_err(|e| McpDiffError::Serialization(e.to_string()))?;
+        let temp = dir.join("manifest.json.tmp");
+        fs::write(&temp, content)?;
+        fs_utils::atomic_rename(&temp, &path)?;
+
+        let manifests = self.list_manifests();
+        let excess = manifests.len().saturating_sub(keep.max(1));
+        for old in &manifests[..excess] {
+            fs::remove_file(old)?;
+        }
+        Ok(path)
+    }
+}
+
+/// How the index changed between two manifests
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ManifestDiff {
+    pub files_added: Vec<String>,
+    pub files_removed: Vec<String>,
+    /// Files whose content hash changed
+    pub files_changed: Vec<String>,
+    /// Modules with symbol changes, by name
+    pub modules: Vec<ModuleDiff>,
+    pub before: ManifestStats,
+    pub after: ManifestStats,
+}
+
+/// Symbol changes within one module
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModuleDiff {
+    pub module: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Symbols present in both whose hash changed
+    pub rehashed: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Whether the two generations are identical
+    pub fn is_empty(&self) -> bool {
+        self.files_added.is_empty()
+            && self.files_removed.is_empty()
+            && self.files_changed.is_empty()
+            && self.modules.is_empty()
+    }
+}
+
+/// Compare two generations, `before` being the older
+pub fn diff_manifests(before: &IndexManifest, after: &IndexManifest) -> ManifestDiff {
+    let (files_added, files_removed, files_changed) = diff_maps(&before.files, &after.files);
+
+    let empty = ModuleManifest::default();
+    let names: BTreeSet<&String> = before.modules.keys().chain(after.modules.keys()).collect();
+    let modules = names
+        .into_iter()
+        .filter_map(|name| {
+            let old = before.modules.get(name).unwrap_or(&empty);
+            let new = after.modules.get(name).unwrap_or(&empty);
+            let (added, removed, rehashed) = diff_maps(&old.symbols, &new.symbols);
+            (!added.is_empty() || !removed.is_empty() || !rehashed.is_empty()).then(|| ModuleDiff {
+                module: name.clone(),
+                added,
+                removed,
+                rehashed,
+            })
+        })
+        .collect();
+
+    ManifestDiff {
+        files_added,
+        files_removed,
+        files_changed,
+        modules,
+        before: before.stats,
+        after: after.stats,
+    }
+}
+
+/// Keys only in `after`, keys only in `before`, and keys whose value changed
+fn diff_maps(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let added = after
+        .keys()
+        .filter(|key| !before.contains_key(*key))
+        .cloned()
+        .collect();
+    let removed = before
+        .keys()
+        .filter(|key| !after.contains_key(*key))
+        .cloned()
+        .collect();
+    let changed = after
+        .iter()
+        .filter(|(key, value)| before.get(*key).is_some_and(|old| old != *value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    (added, removed, changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(module: &str, file: &str, symbol: &str, hash: &str) -> SymbolIndexEntry {
+        serde_json::from_value(serde_json::json!({
+            "s": symbol, "h": hash, "k": "fn", "m": module, "f": file, "l": "1-3", "r": "low"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_records_files_and_symbols() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("a.ts"), "export function a() {}\n").unwrap();
+        let file = temp.path().join("a.ts").to_string_lossy().to_string();
+
+        let manifest = IndexManifest::build(
+            temp.path(),
+            &[
+                entry("root", &file, "a", "h1"),
+                entry("root", &file, "a", "h2"),
+            ],
+            &[file.clone(), "missing.ts".to_string()],
+        );
+
+        assert_eq!(manifest.manifest_version, MANIFEST_VERSION);
+        assert_eq!(manifest.files.keys().collect::<Vec<_>>(), vec!["a.ts"]);
+        let root = &manifest.modules["root"];
+        assert_eq!(root.symbol_count, 2);
+        assert_eq!(root.symbols["a.ts::a"], "h1");
+        assert_eq!(root.symbols["a.ts::a#2"], "h2");
+        assert_eq!(
+            manifest.stats,
+            ManifestStats {
+                files: 1,
+                modules: 1,
+                symbols: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_manifests() {
+        let mut before = IndexManifest::default();
+        before.files.insert("a.ts".into(), "1".into());
+        before.files.insert("b.ts".into(), "2".into());
+        let mut module = ModuleManifest::default();
+        module.symbols.insert("a.ts::a".into(), "h1".into());
+        module.symbols.insert("b.ts::b".into(), "h2".into());
+        before.modules.insert("root".into(), module);
+
+        let mut after = before.clone();
+        after.files.remove("b.ts");
+        after.files.insert("a.ts".into(), "3".into());
+        after.files.insert("c.ts".into(), "4".into());
+        let module = after.modules.get_mut("root").unwrap();
+        module.symbols.remove("b.ts::b");
+        module.symbols.insert("a.ts::a".into(), "h3".into());
+        let mut other = ModuleManifest::default();
+        other.symbols.insert("c.ts::c".into(), "h4".into());
+        after.modules.insert("other".into(), other);
+
+        let diff = diff_manifests(&before, &after);
+        assert_eq!(diff.files_added, vec!["c.ts"]);
+        assert_eq!(diff.files_removed, vec!["b.ts"]);
+        assert_eq!(diff.files_changed, vec!["a.ts"]);
+        assert_eq!(
+            diff.modules,
+            vec![
+                ModuleDiff {
+                    module: "other".into(),
+                    added: vec!["c.ts::c".into()],
+                    ..Default::default()
+                },
+                ModuleDiff {
+                    module: "root".into(),
+                    removed: vec!["b.ts::b".into()],
+                    rehashed: vec!["a.ts::a".into()],
+                    ..Default::default()
+                },
+            ]
+        );
+        assert!(diff_manifests(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_save_manifest_keeps_newest() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = CacheDir {
+            root: temp.path().join("cache"),
+            repo_root: temp.path().to_path_buf(),
+            repo_hash: "0123456789abcdef".to_string(),
+        };
+
+        let saved: Vec<PathBuf> = (0..4)
+            .map(|_| cache.save_manifest(&IndexManifest::default(), 3).unwrap())
+            .collect();
+
+        assert_eq!(cache.list_manifests(), saved[1..].to_vec());
+        assert_eq!(cache.current_manifest_path().as_ref(), saved.last());
+        assert_eq!(cache.previous_manifest_path().as_ref(), saved.get(2));
+    }
+
+    #[test]
+    fn test_parse_manifest_history() {
+        assert_eq!(parse_manifest_history(None), DEFAULT_MANIFEST_HISTORY);
+        assert_eq!(parse_manifest_history(Some(" 10 ")), 10);
+        assert_eq!(parse_manifest_history(Some("0")), 1);
+        assert_eq!(
+            parse_manifest_history(Some("many")),
+            DEFAULT_MANIFEST_HISTORY
+        );
+    }
+}
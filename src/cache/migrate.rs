@@ -0,0 +1,215 @@
+//! Schema migrations for cached indexes.
+//!
+//! Every index records the `SCHEMA_VERSION` it was written with in
+//! `repo_overview.toon`. When the engine is upgraded, [`migrate_cache`] walks
+//! the [`MIGRATIONS`] registered between the stored version and the current
+//! one, then stamps the cache with the current version.
+//!
+//! Migrations that cannot convert artifacts in place remove them instead; the
+//! cache then reports `rebuild_required` and the next index generation
+//! recreates it from source.
+
+use crate::error::{McpDiffError, Result};
+use crate::schema::SCHEMA_VERSION;
+
+use super::CacheDir;
+
+/// A migration step, applied to a cache written by an older schema
+pub type MigrationFn = fn(&CacheDir) -> Result<()>;
+
+/// Registered migrations, keyed by the schema version they upgrade to
+/// (see [`schema_version_number`]) and kept in ascending order.
+pub static MIGRATIONS: &[(u32, MigrationFn)] = &[(200, migrate_to_2_0), (201, migrate_to_2_1)];
+
+/// Outcome of migrating a cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Schema version found in the cache (None if no index exists)
+    pub from_version: Option<String>,
+    /// Schema version the cache was migrated to
+    pub to_version: String,
+    /// Migrations applied, in order
+    pub applied: Vec<u32>,
+    /// The index was removed and must be regenerated from source
+    pub rebuild_required: bool,
+}
+
+impl MigrationReport {
+    /// Whether the cache was already at the target version (or had no index)
+    pub fn is_noop(&self) -> bool {
+        self.applied.is_empty() && !self.rebuild_required
+    }
+}
+
+/// Numeric form of a `MAJOR.MINOR` schema version (`"2.1"` -> `201`)
+pub fn schema_version_number(version: &str) -> Option<u32> {
+    let (major, minor) = version.trim().split_once('.')?;
+    let major: u32 = major.parse().ok()?;
+    let minor: u32 = minor.parse().ok()?;
+    (minor < 100).then_some(major * 100 + minor)
+}
+
+/// Inverse of [`schema_version_number`] (`201` -> `"2.1"`)
+pub fn format_schema_version(number: u32) -> String {
+    format!("{}.{}", number / 100, number % 100)
+}
+
+/// Whether the cache holds an index written by a different schema version
+pub fn needs_migration(cache: &CacheDir) -> bool {
+    cache
+        .stored_schema_version()
+        .is_some_and(|version| version != SCHEMA_VERSION)
+}
+
+/// Migrate the cache to the current `SCHEMA_VERSION`.
+pub fn migrate_cache(cache: &CacheDir) -> Result<MigrationReport> {
+    migrate_with(cache, MIGRATIONS, SCHEMA_VERSION)
+}
+
+fn migrate_with(
+    cache: &CacheDir,
+    migrations: &[(u32, MigrationFn)],
+    target: &str,
+) -> Result<MigrationReport> {
+    let from_version = cache.stored_schema_version();
+    let mut report = MigrationReport {
+        from_version: from_version.clone(),
+        to_version: target.to_string(),
+        ..Default::default()
+    };
+
+    let Some(from_version) = from_version else {
+        return Ok(report);
+    };
+    if from_version == target {
+        return Ok(report);
+    }
+
+    let target_number =
+        schema_version_number(target).ok_or_else(|| McpDiffError::ExtractionFailure {
+            message: format!("Invalid schema version: {}", target),
+        })?;
+
+    match schema_version_number(&from_version) {
+        Some(from) if from < target_number => {
+            for (version, migrate) in migrations
+                .iter()
+                .filter(|(version, _)| *version > from && *version <= target_number)
+            {
+                migrate(cache)?;
+                report.applied.push(*version);
+            }
+        }
+        // Written by a newer engine or unreadable: there is no path back
+        _ => cache.clear()?,
+    }
+
+    if cache.exists() {
+        cache.set_stored_schema_version(target)?;
+    } else {
+        report.rebuild_required = true;
+    }
+
+    Ok(report)
+}
+
+/// 2.0 introduced layered indexes (SEM-45). Layer state from older builds is
+/// not tied to the current base index, so drop it and let it rebuild.
+fn migrate_to_2_0(cache: &CacheDir) -> Result<()> {
+    cache.clear_layers()
+}
+
+/// 2.1 switched symbol hashes to `file_hash:semantic_hash`. The old hashes
+/// are baked into every shard and the call graph, so nothing can be kept.
+fn migrate_to_2_1(cache: &CacheDir) -> Result<()> {
+    cache.clear()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn cache_with_version(version: &str) -> (TempDir, CacheDir) {
+        let temp = TempDir::new().unwrap();
+        let cache = CacheDir {
+            root: temp.path().join("cache"),
+            repo_root: temp.path().to_path_buf(),
+            repo_hash: "test".to_string(),
+        };
+        fs::create_dir_all(&cache.root).unwrap();
+        fs::write(
+            cache.repo_overview_path(),
+            format!(
+                "_type: repo_overview\nschema_version: \"{}\"\nfiles: 3\n",
+                version
+            ),
+        )
+        .unwrap();
+        (temp, cache)
+    }
+
+    fn keep(_: &CacheDir) -> Result<()> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_version_number() {
+        assert_eq!(schema_version_number("2.1"), Some(201));
+        assert_eq!(schema_version_number("10.12"), Some(1012));
+        assert_eq!(schema_version_number("2"), None);
+        assert_eq!(schema_version_number("two.one"), None);
+        assert_eq!(format_schema_version(201), "2.1");
+        assert_eq!(
+            schema_version_number(SCHEMA_VERSION),
+            MIGRATIONS.last().map(|m| m.0)
+        );
+    }
+
+    #[test]
+    fn test_migrate_applies_pending_migrations_in_place() {
+        let (_temp, cache) = cache_with_version("1.0");
+        let migrations: &[(u32, MigrationFn)] =
+            &[(100, keep), (200, keep), (201, keep), (300, keep)];
+
+        let report = migrate_with(&cache, migrations, "2.1").unwrap();
+
+        assert_eq!(report.from_version.as_deref(), Some("1.0"));
+        assert_eq!(report.applied, vec![200, 201]);
+        assert!(!report.rebuild_required);
+        assert_eq!(cache.stored_schema_version().as_deref(), Some("2.1"));
+        let overview = fs::read_to_string(cache.repo_overview_path()).unwrap();
+        assert!(overview.ends_with("files: 3\n"));
+    }
+
+    #[test]
+    fn test_migrate_requiring_rebuild_clears_cache() {
+        let (_temp, cache) = cache_with_version("2.0");
+
+        let report = migrate_cache(&cache).unwrap();
+
+        assert_eq!(report.applied, vec![201]);
+        assert!(report.rebuild_required);
+        assert!(!cache.root.exists());
+    }
+
+    #[test]
+    fn test_migrate_from_newer_schema_clears_cache() {
+        let (_temp, cache) = cache_with_version("9.0");
+        assert!(needs_migration(&cache));
+
+        let report = migrate_cache(&cache).unwrap();
+
+        assert!(report.applied.is_empty());
+        assert!(report.rebuild_required);
+    }
+
+    #[test]
+    fn test_migrate_current_version_is_noop() {
+        let (_temp, cache) = cache_with_version(SCHEMA_VERSION);
+        assert!(!needs_migration(&cache));
+        assert!(migrate_cache(&cache).unwrap().is_noop());
+        assert!(cache.exists());
+    }
+}
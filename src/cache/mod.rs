@@ -3,9 +3,22 @@
 //! Provides XDG-compliant cache directory management and repo hashing
 //! for storing sharded semantic IR that can be queried by AI agents.
 
+pub mod debt_history;
+pub mod generation;
+pub mod lock;
+pub mod manifest;
+pub mod migrate;
 pub mod signatures;
-
+pub mod symbol_bin;
+pub mod verify;
+
+pub use debt_history::DebtScoreEntry;
+pub use generation::{Generation, GenerationPin};
+pub use lock::IndexLock;
+pub use manifest::{diff_manifests, IndexManifest, ManifestDiff};
+pub use migrate::{migrate_cache, MigrationReport, MIGRATIONS};
 pub use signatures::load_function_signatures;
+pub use verify::{verify_cache, ArtifactKind, ArtifactState, ArtifactStatus, RepairPlan};
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -21,15 +34,20 @@ use crate::error::Result;
 use crate::fs_utils;
 use crate::git;
 use crate::overlay::{LayerKind, LayeredIndex, Overlay};
+use crate::profile::{Phase, PhaseTimer};
 use crate::schema::{fnv1a_hash, FrameworkEntryPoint, SCHEMA_VERSION};
+use crate::settings::RepoSettings;
+use crate::shard::ShardNamingStrategy;
 
 /// Normalize symbol kind aliases for filtering
 /// Maps shorthand forms (fn, struct) to full names (function, class)
 pub fn normalize_kind(kind: &str) -> &str {
     match kind {
         "fn" | "func" | "method" => "function",
-        "struct" | "record" => "class", // C# structs/records stored as class
-        "interface" => "trait",         // Stored as trait in index
+        "struct" => "class",    // C# structs stored as class
+        "record" => "type",     // C# records stored as type
+        "interface" => "trait", // Stored as trait in index
+        "config_key" | "key" => "configkey",
         other => other,
     }
 }
@@ -67,6 +85,32 @@ fn match_glob_pattern(symbol: &str, pattern: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Check an index entry against a symbol search (`query_lower` is lowercased)
+///
+/// The query matches symbol names case-insensitively: empty or `*` matches
+/// everything, `*`/`?` make it a glob, anything else is a substring match.
+pub fn symbol_matches_query(
+    entry: &SymbolIndexEntry,
+    query_lower: &str,
+    module_filter: Option<&str>,
+    kind_filter: Option<&str>,
+    risk_filter: Option<&str>,
+) -> bool {
+    let symbol_lower = entry.symbol.to_lowercase();
+    let matches = if query_lower.is_empty() || query_lower == "*" {
+        true
+    } else if query_lower.contains('*') || query_lower.contains('?') {
+        match_glob_pattern(&symbol_lower, query_lower)
+    } else {
+        symbol_lower.contains(query_lower)
+    };
+
+    matches
+        && module_filter.is_none_or(|m| entry.module == m)
+        && kind_filter.is_none_or(|k| entry.kind == normalize_kind(k))
+        && risk_filter.is_none_or(|r| entry.risk == r)
+}
+
 /// Split a string by comma while respecting quoted strings.
 /// Handles entries like: "foo","bar","ext:baz(a, b, c).unwrap"
 pub fn split_respecting_quotes(s: &str) -> Vec<String> {
@@ -117,6 +161,28 @@ pub struct CacheMeta {
     /// Git SHA this index was created at (for incremental indexing)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub indexed_sha: Option<String>,
+
+    /// Identity of the repository the cache was built from
+    /// (see [`compute_repo_fingerprint`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_fingerprint: Option<String>,
+
+    /// Shard naming strategy, when not the default (kept by regenerations)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard_naming: Option<ShardNamingStrategy>,
+
+    /// Repository the cache was built from (lets `cache list` name it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_path: Option<PathBuf>,
+
+    /// Entries in the symbol index when it was last written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_count: Option<usize>,
+
+    /// Index settings from the layered config the cache was built with
+    /// (see [`RepoSettings::index_fingerprint`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings_fingerprint: Option<String>,
 }
 
 /// Information about a source file for staleness detection
@@ -216,6 +282,46 @@ impl Default for IndexingStatus {
     }
 }
 
+impl IndexingStatus {
+    /// Status for an index written before every file could be processed.
+    ///
+    /// The index is usable but only covers `files_indexed` of `files_total`
+    /// files; generation resumes from the checkpoint on the next run.
+    pub fn partial(
+        files_indexed: usize,
+        files_total: usize,
+        modules_ready: Vec<String>,
+        modules_pending: Vec<String>,
+    ) -> Self {
+        let percent = (files_indexed * 100)
+            .checked_div(files_total)
+            .map_or(100, |p| p.min(100) as u8);
+
+        Self {
+            in_progress: true,
+            files_indexed,
+            files_total,
+            percent,
+            eta_seconds: None,
+            modules_ready,
+            modules_pending,
+        }
+    }
+
+    /// Whether this status describes an incomplete (partial) index
+    pub fn is_partial(&self) -> bool {
+        self.in_progress && self.files_indexed < self.files_total
+    }
+
+    /// Note surfaced by tools that operate on a partial index
+    pub fn coverage_note(&self) -> String {
+        format!(
+            "index_coverage: {}% ({}/{} files indexed, generation in progress)",
+            self.percent, self.files_indexed, self.files_total
+        )
+    }
+}
+
 impl CacheMeta {
     /// Create a new cache metadata entry
     pub fn new(source_files: Vec<SourceFileInfo>) -> Self {
@@ -225,6 +331,11 @@ impl CacheMeta {
             source_files,
             indexing_status: None,
             indexed_sha: None,
+            repo_fingerprint: None,
+            shard_naming: None,
+            repo_path: None,
+            symbol_count: None,
+            settings_fingerprint: None,
         }
     }
 
@@ -368,10 +479,12 @@ impl CacheDir {
         self.root.join("modules")
     }
 
-    /// Path to a specific module file
+    /// Path to a specific module file, as of the current generation
     pub fn module_path(&self, module_name: &str) -> PathBuf {
-        self.modules_dir()
-            .join(format!("{}.toon", sanitize_filename(module_name)))
+        let file = generation::resolve(&self.root)
+            .and_then(|g| g.module_override(module_name))
+            .unwrap_or_else(|| format!("{}.toon", sanitize_filename(module_name)));
+        self.modules_dir().join(file)
     }
 
     /// Path to symbols directory
@@ -379,9 +492,12 @@ impl CacheDir {
         self.root.join("symbols")
     }
 
-    /// Path to a specific symbol file
+    /// Path to a specific symbol file, as of the current generation
     pub fn symbol_path(&self, symbol_hash: &str) -> PathBuf {
-        self.symbols_dir().join(format!("{}.toon", symbol_hash))
+        let file = generation::resolve(&self.root)
+            .and_then(|g| g.symbols.get(symbol_hash).cloned())
+            .unwrap_or_else(|| format!("{}.toon", symbol_hash));
+        self.symbols_dir().join(file)
     }
 
     /// Path to graphs directory
@@ -404,6 +520,31 @@ impl CacheDir {
         self.graphs_dir().join("module_graph.toon")
     }
 
+    /// Path to the per-file import summary behind module usage queries
+    pub fn module_usage_path(&self) -> PathBuf {
+        self.graphs_dir().join("module_usage.json")
+    }
+
+    /// Path to the test-to-production links
+    pub fn test_links_path(&self) -> PathBuf {
+        self.graphs_dir().join("test_links.json")
+    }
+
+    /// Path to the hot spot rankings
+    pub fn hotspots_path(&self) -> PathBuf {
+        self.graphs_dir().join("hotspots.json")
+    }
+
+    /// Path to the file and symbol ownership
+    pub fn ownership_path(&self) -> PathBuf {
+        self.graphs_dir().join("ownership.json")
+    }
+
+    /// Path to the cached git blame results (kept across index rebuilds)
+    pub fn blame_cache_path(&self) -> PathBuf {
+        self.root.join("blame_cache.json")
+    }
+
     /// Path to diffs directory
     pub fn diffs_dir(&self) -> PathBuf {
         self.root.join("diffs")
@@ -472,6 +613,139 @@ impl CacheDir {
         Ok(())
     }
 
+    /// Schema version recorded in repo_overview.toon, if an index exists
+    pub fn stored_schema_version(&self) -> Option<String> {
+        let content = fs::read_to_string(self.repo_overview_path()).ok()?;
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix("schema_version:"))
+            .map(|version| version.trim().trim_matches('"').to_string())
+    }
+
+    /// Rewrite the schema version recorded in repo_overview.toon (atomic write)
+    pub fn set_stored_schema_version(&self, version: &str) -> Result<()> {
+        let path = self.repo_overview_path();
+        let content = fs::read_to_string(&path)?;
+        let mut updated: String = content
+            .lines()
+            .map(|line| {
+                if line.starts_with("schema_version:") {
+                    format!("schema_version: \"{}\"", version)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.ends_with('\n') {
+            updated.push('\n');
+        }
+
+        let temp = self.root.join("repo_overview.toon.tmp");
+        fs::write(&temp, updated)?;
+        fs_utils::atomic_rename(&temp, &path)?;
+        Ok(())
+    }
+
+    /// Path to meta.json (CacheMeta for the sharded index)
+    pub fn meta_path(&self) -> PathBuf {
+        self.root.join("meta.json")
+    }
+
+    /// Load the index metadata, if present and readable
+    pub fn load_meta(&self) -> Option<CacheMeta> {
+        let content = fs::read_to_string(self.meta_path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save the index metadata (atomic write)
+    pub fn save_meta(&self, meta: &CacheMeta) -> Result<()> {
+        let path = self.meta_path();
+        let temp = self.root.join("meta.json.tmp");
+        let content = serde_json::to_string_pretty(meta).map_err(|e| {
+            crate::error::McpDiffError::ExtractionFailure {
+                message: format!("Failed to serialize cache meta: {}", e),
+            }
+        })?;
+        fs::write(&temp, content)?;
+        fs_utils::atomic_rename(&temp, &path)?;
+        Ok(())
+    }
+
+    /// Whether this cache was built from the repository now at `repo_root`.
+    ///
+    /// A cache without meta, or written before fingerprints were recorded,
+    /// is assumed to match.
+    pub fn fingerprint_matches(&self) -> bool {
+        match self.load_meta().and_then(|m| m.repo_fingerprint) {
+            Some(stored) => stored == compute_repo_fingerprint(&self.repo_root),
+            None => true,
+        }
+    }
+
+    /// Whether the index settings in the layered config (extensions, depth,
+    /// exclusions) are the ones this cache was built with.
+    ///
+    /// A cache written before settings were recorded is compared against
+    /// the defaults, so adding a config file triggers a rebuild.
+    pub fn settings_match(&self) -> bool {
+        let stored = self
+            .load_meta()
+            .and_then(|m| m.settings_fingerprint)
+            .unwrap_or_else(|| RepoSettings::default().index_fingerprint());
+        stored == RepoSettings::load_or_default(&self.repo_root).index_fingerprint()
+    }
+
+    /// Record the repository fingerprint, path, shard naming, symbol count
+    /// and index settings of a freshly written index in its metadata
+    pub fn record_index_identity(
+        &self,
+        shard_naming: ShardNamingStrategy,
+        symbol_count: usize,
+    ) -> Result<()> {
+        let mut meta = self
+            .load_meta()
+            .unwrap_or_else(|| CacheMeta::new(Vec::new()));
+        meta.repo_fingerprint = Some(compute_repo_fingerprint(&self.repo_root));
+        meta.shard_naming =
+            (shard_naming != ShardNamingStrategy::default()).then_some(shard_naming);
+        meta.repo_path = Some(self.repo_root.clone());
+        meta.symbol_count = Some(symbol_count);
+        meta.settings_fingerprint =
+            Some(RepoSettings::load_or_default(&self.repo_root).index_fingerprint());
+        self.save_meta(&meta)
+    }
+
+    /// Update the symbol count recorded in the metadata, if there is any
+    fn record_symbol_count(&self, symbol_count: usize) -> Result<()> {
+        match self.load_meta() {
+            Some(mut meta) => {
+                meta.symbol_count = Some(symbol_count);
+                self.save_meta(&meta)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Shard naming strategy the index was written with
+    pub fn shard_naming(&self) -> ShardNamingStrategy {
+        self.load_meta()
+            .and_then(|m| m.shard_naming)
+            .unwrap_or_default()
+    }
+
+    /// Indexing status of a partial index, or None if the index is complete
+    pub fn partial_status(&self) -> Option<IndexingStatus> {
+        self.load_meta()?
+            .indexing_status
+            .filter(IndexingStatus::is_partial)
+    }
+
+    /// Path to the checkpoint directory used to resume time-boxed indexing
+    pub fn checkpoint_dir(&self) -> PathBuf {
+        self.root.join("checkpoint")
+    }
+
     /// Path to status_hash file (hash of git status when last indexed)
     pub fn status_hash_path(&self) -> PathBuf {
         self.root.join("status_hash")
@@ -618,42 +892,30 @@ impl CacheDir {
 
     // ========== Utility methods ==========
 
-    /// List all module names in the cache
+    /// List all module names in the cache, as of the current generation
     pub fn list_modules(&self) -> Vec<String> {
-        fs::read_dir(self.modules_dir())
-            .into_iter()
-            .flatten()
-            .filter_map(|entry| entry.ok())
-            .filter_map(|entry| {
-                let path = entry.path();
-                if path.extension().map(|e| e == "toon").unwrap_or(false) {
-                    path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s.to_string())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        let mut modules = list_shard_stems(&self.modules_dir());
+        if let Some(generation) = generation::resolve(&self.root) {
+            modules.retain(|module| !generation.modules.contains_key(module));
+            modules.extend(
+                generation
+                    .modules
+                    .iter()
+                    .filter(|(_, file)| file.is_some())
+                    .map(|(module, _)| module.clone()),
+            );
+        }
+        modules
     }
 
-    /// List all symbol hashes in the cache
+    /// List all symbol hashes in the cache, as of the current generation
     pub fn list_symbols(&self) -> Vec<String> {
-        fs::read_dir(self.symbols_dir())
-            .into_iter()
-            .flatten()
-            .filter_map(|entry| entry.ok())
-            .filter_map(|entry| {
-                let path = entry.path();
-                if path.extension().map(|e| e == "toon").unwrap_or(false) {
-                    path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s.to_string())
-                } else {
-                    None
-                }
-            })
-            .collect()
+        let mut symbols = list_shard_stems(&self.symbols_dir());
+        if let Some(generation) = generation::resolve(&self.root) {
+            symbols.retain(|hash| !generation.symbols.contains_key(hash));
+            symbols.extend(generation.symbols.keys().cloned());
+        }
+        symbols
     }
 
     /// Get cache size in bytes
@@ -662,7 +924,35 @@ impl CacheDir {
     }
 
     /// Clear the cache
+    ///
+    /// Index manifests and the debt score history are kept, so the next
+    /// generation can be compared with this one (see [`manifest`],
+    /// [`debt_history`]). [`CacheDir::purge`] removes them too.
     pub fn clear(&self) -> Result<()> {
+        let kept = [
+            self.manifests_dir(),
+            self.debt_history_path(),
+            self.blame_cache_path(),
+        ];
+        if !kept.iter().any(|path| path.exists()) {
+            return self.purge();
+        }
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if kept.contains(&path) {
+                continue;
+            }
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the cache entirely, index manifests included
+    pub fn purge(&self) -> Result<()> {
         if self.root.exists() {
             fs::remove_dir_all(&self.root)?;
         }
@@ -681,7 +971,7 @@ impl CacheDir {
             return Ok(std::collections::HashMap::new());
         }
 
-        let content = fs::read_to_string(&path)?;
+        let content = verify::read_artifact(&path)?;
         let mut graph = std::collections::HashMap::new();
 
         // Parse TOON format call graph
@@ -725,13 +1015,7 @@ impl CacheDir {
         module_name: &str,
     ) -> Result<Vec<crate::schema::SemanticSummary>> {
         let path = self.module_path(module_name);
-        if !path.exists() {
-            return Err(crate::McpDiffError::FileNotFound {
-                path: path.display().to_string(),
-            });
-        }
-
-        let content = fs::read_to_string(&path)?;
+        let content = verify::read_artifact(&path)?;
         let mut summaries = Vec::new();
         let mut current_summary: Option<crate::schema::SemanticSummary> = None;
 
@@ -839,9 +1123,17 @@ impl CacheDir {
 
     // ========== Query-Driven API (v1) ==========
 
-    /// Path to the symbol index file (JSONL format)
+    /// Path to the symbol index file (JSONL format), as of the current generation
     pub fn symbol_index_path(&self) -> PathBuf {
-        self.root.join("symbol_index.jsonl")
+        match generation::resolve(&self.root) {
+            Some(generation) => self.root.join(generation.symbol_index_file()),
+            None => self.root.join("symbol_index.jsonl"),
+        }
+    }
+
+    /// Path to the binary copy of the symbol index (see [`symbol_bin`])
+    pub fn symbol_index_bin_path(&self) -> PathBuf {
+        self.symbol_index_path().with_extension("bin")
     }
 
     /// Check if symbol index exists
@@ -849,6 +1141,11 @@ impl CacheDir {
         self.symbol_index_path().exists()
     }
 
+    /// Path to the content hashes of the indexed files
+    pub fn file_hashes_path(&self) -> PathBuf {
+        self.root.join("file_hashes.json")
+    }
+
     /// Path to the function signature index file (JSONL format)
     /// Used for duplicate function detection
     pub fn signature_index_path(&self) -> PathBuf {
@@ -892,6 +1189,33 @@ impl CacheDir {
         rows.next().ok().flatten().is_some()
     }
 
+    /// Check if the BM25 index was built by a different tokenizer
+    ///
+    /// Its terms no longer match what queries tokenize to, so it must be
+    /// rebuilt even though the rest of the index is current.
+    pub fn bm25_needs_rebuild(&self) -> bool {
+        self.has_bm25_index()
+            && crate::bm25::read_bm25_tokenizer_version(&self.bm25_index_path()).as_deref()
+                != Some(crate::bm25::tokenizer().version().as_str())
+    }
+
+    /// Check the BM25 index can be queried
+    ///
+    /// Fails with `FileNotFound` when no index was built and `CacheCorrupt`
+    /// when index.sqlite is not a readable database.
+    pub fn require_bm25_index(&self) -> Result<()> {
+        let path = self.bm25_index_path();
+        if path.exists() {
+            verify::check_sqlite_header(&path)?;
+        }
+        if !self.has_bm25_index() {
+            return Err(crate::McpDiffError::FileNotFound {
+                path: "BM25 index not found. Run `semfora index generate` first.".to_string(),
+            });
+        }
+        Ok(())
+    }
+
     /// Path to the module registry SQLite database (stored in index.sqlite)
     /// Used for conflict-aware module naming at scale
     pub fn module_registry_path(&self) -> PathBuf {
@@ -902,23 +1226,84 @@ impl CacheDir {
     ///
     /// This removes all existing entries for the file and adds new ones.
     /// Used by the file watcher to keep the index up-to-date in real-time.
+    ///
+    /// Once a partial reindex has published a generation (see
+    /// [`generation`]), the updated index is published as the next one
+    /// rather than rewritten in place, so pinned readers keep theirs.
     pub fn update_symbol_index_for_file(
         &self,
         file_path: &str,
         new_entries: Vec<SymbolIndexEntry>,
     ) -> Result<()> {
-        use std::io::{BufRead, Write};
+        if generation::current(&self.root).is_none() {
+            return self.rewrite_symbol_index_for_file(
+                &self.symbol_index_path(),
+                file_path,
+                new_entries,
+            );
+        }
+
+        let _lock = self.lock_index()?;
+        let current = generation::current(&self.root).unwrap_or_default();
+        let source = self.root.join(current.symbol_index_file());
+        let mut next = current.next();
+        next.replace_symbol_index();
+        generation::with_generation(&self.root, &next, || {
+            self.rewrite_symbol_index_for_file(&source, file_path, new_entries)
+        })?;
+        generation::publish(self, next)?;
+        Ok(())
+    }
+
+    /// Replace the symbol index (and its binary copy) with `entries`
+    pub fn write_symbol_index(&self, entries: &[SymbolIndexEntry]) -> Result<()> {
+        use std::io::Write;
 
         let index_path = self.symbol_index_path();
+        let temp_path = index_path.with_extension("jsonl.tmp");
+        {
+            let mut file = std::io::BufWriter::new(fs::File::create(&temp_path)?);
+            for entry in entries {
+                let json = serde_json::to_string(entry).map_err(|e| {
+                    crate::McpDiffError::ExtractionFailure {
+                        message: format!("Failed to serialize symbol index entry: {}", e),
+                    }
+                })?;
+                writeln!(file, "{}", json)?;
+            }
+            file.flush()?;
+        }
+        fs_utils::atomic_rename(&temp_path, &index_path)?;
+
+        if let Some(fingerprint) = symbol_bin::TextFingerprint::of(self) {
+            if let Err(e) = symbol_bin::write(self, entries, fingerprint) {
+                tracing::debug!("[CACHE] Failed to write symbol_index.bin: {}", e);
+            }
+        }
+        self.record_symbol_count(entries.len())
+    }
+
+    /// Write `index_path` without `file_path`'s entries, plus `new_entries`,
+    /// to the symbol index
+    fn rewrite_symbol_index_for_file(
+        &self,
+        index_path: &Path,
+        file_path: &str,
+        new_entries: Vec<SymbolIndexEntry>,
+    ) -> Result<()> {
+        use std::io::{BufRead, Write};
+
         tracing::debug!(
             "[CACHE] update_symbol_index_for_file: file_path={}, cache={}",
             file_path,
             index_path.display()
         );
 
+        let previous = symbol_bin::TextFingerprint::of(self);
+
         // Read existing entries, filtering out the ones for this file
         let mut entries: Vec<SymbolIndexEntry> = if index_path.exists() {
-            let file = fs::File::open(&index_path)?;
+            let file = fs::File::open(index_path)?;
             let reader = std::io::BufReader::new(file);
             let all_entries: Vec<_> = reader
                 .lines()
@@ -951,9 +1336,10 @@ impl CacheDir {
         };
 
         // Add new entries
-        entries.extend(new_entries);
+        entries.extend(new_entries.iter().cloned());
 
         // Write back atomically (temp file + rename)
+        let index_path = self.symbol_index_path();
         let temp_path = index_path.with_extension("jsonl.tmp");
         {
             let mut file = fs::File::create(&temp_path)?;
@@ -976,7 +1362,11 @@ impl CacheDir {
             entries.len()
         );
 
-        Ok(())
+        if let Err(e) = symbol_bin::update_file(self, file_path, &new_entries, &entries, previous) {
+            tracing::debug!("[CACHE] Failed to update symbol_index.bin: {}", e);
+        }
+
+        self.record_symbol_count(entries.len())
     }
 
     /// Search symbol index with filters
@@ -991,6 +1381,7 @@ impl CacheDir {
     ) -> Result<Vec<SymbolIndexEntry>> {
         use std::io::BufRead;
 
+        let _timer = PhaseTimer::start(Phase::IndexLookup);
         let index_path = self.symbol_index_path();
         if !index_path.exists() {
             return Err(crate::McpDiffError::FileNotFound {
@@ -998,9 +1389,25 @@ impl CacheDir {
             });
         }
 
+        let query_lower = query.to_lowercase();
+        if let Some(entries) = symbol_bin::load(self) {
+            return Ok(entries
+                .into_iter()
+                .filter(|entry| {
+                    symbol_matches_query(
+                        entry,
+                        &query_lower,
+                        module_filter,
+                        kind_filter,
+                        risk_filter,
+                    )
+                })
+                .take(limit)
+                .collect());
+        }
+
         let file = fs::File::open(&index_path)?;
         let reader = std::io::BufReader::new(file);
-        let query_lower = query.to_lowercase();
         let mut results = Vec::new();
 
         for line in reader.lines() {
@@ -1014,42 +1421,16 @@ impl CacheDir {
                 Err(_) => continue, // Skip malformed lines
             };
 
-            // Match query against symbol name (case-insensitive)
-            // Supports: empty/"*" = match all, *foo* = glob pattern, foo = substring
-            let symbol_lower = entry.symbol.to_lowercase();
-            let matches = if query_lower.is_empty() || query_lower == "*" {
-                // Empty or "*" matches all symbols
-                true
-            } else if query_lower.contains('*') || query_lower.contains('?') {
-                // Glob pattern matching
-                match_glob_pattern(&symbol_lower, &query_lower)
-            } else {
-                // Default: substring matching (original behavior)
-                symbol_lower.contains(&query_lower)
-            };
-
-            if !matches {
+            if !symbol_matches_query(
+                &entry,
+                &query_lower,
+                module_filter,
+                kind_filter,
+                risk_filter,
+            ) {
                 continue;
             }
 
-            // Apply optional filters
-            if let Some(m) = module_filter {
-                if entry.module != m {
-                    continue;
-                }
-            }
-            if let Some(k) = kind_filter {
-                let normalized_kind = normalize_kind(k);
-                if entry.kind != normalized_kind {
-                    continue;
-                }
-            }
-            if let Some(r) = risk_filter {
-                if entry.risk != r {
-                    continue;
-                }
-            }
-
             results.push(entry);
 
             if results.len() >= limit {
@@ -1070,6 +1451,7 @@ impl CacheDir {
     ) -> Result<Vec<SymbolIndexEntry>> {
         use std::io::BufRead;
 
+        let _timer = PhaseTimer::start(Phase::IndexLookup);
         let index_path = self.symbol_index_path();
         if !index_path.exists() {
             return Err(crate::McpDiffError::FileNotFound {
@@ -1077,6 +1459,30 @@ impl CacheDir {
             });
         }
 
+        let in_module = |entry: &SymbolIndexEntry| {
+            // Must match module
+            if entry.module != module {
+                return false;
+            }
+
+            // Apply optional filters
+            if let Some(k) = kind_filter {
+                if entry.kind != normalize_kind(k) {
+                    return false;
+                }
+            }
+            if let Some(r) = risk_filter {
+                if entry.risk != r {
+                    return false;
+                }
+            }
+            true
+        };
+
+        if let Some(entries) = symbol_bin::load(self) {
+            return Ok(entries.into_iter().filter(in_module).take(limit).collect());
+        }
+
         let file = fs::File::open(&index_path)?;
         let reader = std::io::BufReader::new(file);
         let mut results = Vec::new();
@@ -1092,24 +1498,10 @@ impl CacheDir {
                 Err(_) => continue,
             };
 
-            // Must match module
-            if entry.module != module {
+            if !in_module(&entry) {
                 continue;
             }
 
-            // Apply optional filters
-            if let Some(k) = kind_filter {
-                let normalized_kind = normalize_kind(k);
-                if entry.kind != normalized_kind {
-                    continue;
-                }
-            }
-            if let Some(r) = risk_filter {
-                if entry.risk != r {
-                    continue;
-                }
-            }
-
             results.push(entry);
 
             if results.len() >= limit {
@@ -1124,32 +1516,58 @@ impl CacheDir {
     ///
     /// Returns all entries from the symbol index without filtering.
     /// Use this for batch analysis operations.
+    ///
+    /// Reads the binary copy when it is current. Otherwise the text index is
+    /// parsed and the binary copy regenerated from it for the next load.
+    ///
+    /// Malformed lines are skipped; an index with no readable entry at all is
+    /// reported as [`McpDiffError::CacheCorrupt`](crate::McpDiffError::CacheCorrupt).
     pub fn load_all_symbol_entries(&self) -> Result<Vec<SymbolIndexEntry>> {
-        use std::io::BufRead;
+        let _timer = PhaseTimer::start(Phase::IndexLookup);
+        if let Some(entries) = symbol_bin::load(self) {
+            return Ok(entries);
+        }
 
-        let index_path = self.symbol_index_path();
-        if !index_path.exists() {
-            return Err(crate::McpDiffError::FileNotFound {
-                path: index_path.display().to_string(),
-            });
+        let fingerprint = symbol_bin::TextFingerprint::of(self);
+        let entries = self.load_text_symbol_entries()?;
+        if let Some(fingerprint) = fingerprint {
+            if let Err(e) = symbol_bin::write(self, &entries, fingerprint) {
+                tracing::debug!("[CACHE] Failed to write symbol_index.bin: {}", e);
+            }
         }
+        Ok(entries)
+    }
 
-        let file = fs::File::open(&index_path)?;
-        let reader = std::io::BufReader::new(file);
+    /// Files whose symbols are flagged as generated code
+    pub fn generated_files(&self) -> Result<std::collections::HashSet<String>> {
+        Ok(self
+            .load_all_symbol_entries()?
+            .into_iter()
+            .filter(|e| e.is_generated)
+            .map(|e| e.file)
+            .collect())
+    }
+
+    /// Load all symbol index entries from the text index, ignoring the binary copy
+    pub fn load_text_symbol_entries(&self) -> Result<Vec<SymbolIndexEntry>> {
+        let index_path = self.symbol_index_path();
+        let content = verify::read_artifact(&index_path)?;
         let mut results = Vec::new();
+        let mut malformed = 0;
 
-        for line in reader.lines() {
-            let line = line?;
+        for line in content.lines() {
             if line.trim().is_empty() {
                 continue;
             }
 
-            let entry: SymbolIndexEntry = match serde_json::from_str(&line) {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
+            match serde_json::from_str::<SymbolIndexEntry>(line) {
+                Ok(entry) => results.push(entry),
+                Err(_) => malformed += 1,
+            }
+        }
 
-            results.push(entry);
+        if results.is_empty() && malformed > 0 {
+            return Err(verify::corrupt(&index_path, "no readable entries"));
         }
 
         Ok(results)
@@ -1260,7 +1678,7 @@ impl CacheDir {
             ]),
             Some("fn") | Some("function") | Some("method") => None, // Functions exist in all languages
             Some("struct") => Some(vec!["rs".to_string(), "go".to_string(), "cs".to_string()]),
-            Some("trait") => Some(vec!["rs".to_string()]),
+            Some("trait") => Some(vec!["rs".to_string(), "php".to_string()]),
             Some("enum") => Some(vec![
                 "rs".to_string(),
                 "ts".to_string(),
@@ -1275,6 +1693,7 @@ impl CacheDir {
                 "java".to_string(),
                 "kt".to_string(),
                 "cs".to_string(),
+                "php".to_string(),
             ]),
             Some("interface") => Some(vec![
                 "ts".to_string(),
@@ -1283,6 +1702,7 @@ impl CacheDir {
                 "kt".to_string(),
                 "cs".to_string(),
                 "go".to_string(),
+                "php".to_string(),
             ]),
             _ => None,
         }
@@ -1497,8 +1917,6 @@ impl CacheDir {
     ///
     /// Returns None if layer directory doesn't exist or AI layer is requested.
     pub fn load_layer(&self, kind: LayerKind) -> Result<Option<Overlay>> {
-        use std::io::BufRead;
-
         let layer_dir = match self.layer_dir(kind) {
             Some(d) => d,
             None => return Ok(None), // AI layer - not persisted
@@ -1511,10 +1929,9 @@ impl CacheDir {
         // Load layer metadata
         let meta_path = layer_dir.join("meta.json");
         let meta: crate::overlay::LayerMeta = if meta_path.exists() {
-            let json = fs::read_to_string(&meta_path)?;
-            serde_json::from_str(&json).map_err(|e| crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to deserialize {} layer meta: {}", kind, e),
-            })?
+            let json = verify::read_artifact(&meta_path)?;
+            serde_json::from_str(&json)
+                .map_err(|e| verify::corrupt(&meta_path, format!("{} layer meta: {}", kind, e)))?
         } else {
             crate::overlay::LayerMeta::new(kind)
         };
@@ -1523,18 +1940,13 @@ impl CacheDir {
         let mut symbols = std::collections::HashMap::new();
         let symbols_path = layer_dir.join("symbols.jsonl");
         if symbols_path.exists() {
-            let file = fs::File::open(&symbols_path)?;
-            let reader = std::io::BufReader::new(file);
-            for line in reader.lines() {
-                let line = line?;
+            let content = verify::read_artifact(&symbols_path)?;
+            for line in content.lines() {
                 if line.trim().is_empty() {
                     continue;
                 }
-                let entry: SymbolEntry = serde_json::from_str(&line).map_err(|e| {
-                    crate::McpDiffError::ExtractionFailure {
-                        message: format!("Failed to deserialize symbol entry: {}", e),
-                    }
-                })?;
+                let entry: SymbolEntry =
+                    serde_json::from_str(line).map_err(|e| verify::corrupt(&symbols_path, e))?;
                 symbols.insert(entry.hash, entry.state);
             }
         }
@@ -1543,7 +1955,7 @@ impl CacheDir {
         let mut deleted = std::collections::HashSet::new();
         let deleted_path = layer_dir.join("deleted.txt");
         if deleted_path.exists() {
-            let content = fs::read_to_string(&deleted_path)?;
+            let content = verify::read_artifact(&deleted_path)?;
             for line in content.lines() {
                 let hash = line.trim();
                 if !hash.is_empty() {
@@ -1556,19 +1968,13 @@ impl CacheDir {
         let mut moves = Vec::new();
         let moves_path = layer_dir.join("moves.jsonl");
         if moves_path.exists() {
-            let file = fs::File::open(&moves_path)?;
-            let reader = std::io::BufReader::new(file);
-            for line in reader.lines() {
-                let line = line?;
+            let content = verify::read_artifact(&moves_path)?;
+            for line in content.lines() {
                 if line.trim().is_empty() {
                     continue;
                 }
                 let file_move: crate::overlay::FileMove =
-                    serde_json::from_str(&line).map_err(|e| {
-                        crate::McpDiffError::ExtractionFailure {
-                            message: format!("Failed to deserialize file move: {}", e),
-                        }
-                    })?;
+                    serde_json::from_str(line).map_err(|e| verify::corrupt(&moves_path, e))?;
                 moves.push(file_move);
             }
         }
@@ -1928,6 +2334,37 @@ impl CacheDir {
         let module_graph_content = Self::encode_module_graph(&module_graph);
         std::fs::write(self.module_graph_path(), &module_graph_content)?;
 
+        // Write the import summary, keeping module names from the symbol index
+        let file_to_module: std::collections::HashMap<String, String> = self
+            .load_all_symbol_entries()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| (e.file, e.module))
+            .collect();
+        crate::module_usage::ModuleUsageIndex::build(summaries, &file_to_module).write(self)?;
+        crate::test_links::TestLinkIndex::build(summaries, Some(&self.repo_root)).write(self)?;
+        crate::hotspots::HotspotIndex::build(
+            &self.load_all_symbol_entries().unwrap_or_default(),
+            &self.load_call_graph()?,
+            Some(&self.repo_root),
+            crate::hotspots::churn_commits(),
+            false,
+        )
+        .write(self)?;
+        // Blame again only if the index being refreshed was built with it
+        let mut blame_cache = crate::ownership::OwnershipIndex::load(self)
+            .is_ok_and(|ownership| ownership.blame)
+            .then(|| crate::ownership::BlameCache::load(self));
+        crate::ownership::OwnershipIndex::build(
+            &self.load_all_symbol_entries().unwrap_or_default(),
+            &self.repo_root,
+            blame_cache.as_mut(),
+        )
+        .write(self)?;
+        if let Some(blame_cache) = &blame_cache {
+            blame_cache.write(self)?;
+        }
+
         Ok((
             call_graph_entries,
             import_graph_entries,
@@ -1956,6 +2393,9 @@ impl CacheDir {
             // Index from symbols array
             for symbol in &summary.symbols {
                 let hash = compute_symbol_hash(symbol, &summary.file);
+                for alias in crate::detectors::rust::impl_member_aliases(&symbol.name) {
+                    lookup.entry(alias).or_default().push(hash.clone());
+                }
                 lookup.entry(symbol.name.clone()).or_default().push(hash);
             }
         }
@@ -1971,12 +2411,28 @@ impl CacheDir {
 
     /// Resolve a call name to a symbol hash
     /// When multiple symbols have the same name, prefers same-file matches (local scope)
+    /// Python calls through a repo-local import prefer the imported file
     fn resolve_call_to_hash(
         call_name: &str,
         lookup: &std::collections::HashMap<String, Vec<String>>,
         same_file_prefix: &str,
         import_sources: &std::collections::HashMap<String, String>,
+        python_targets: Option<
+            &std::collections::HashMap<String, crate::analysis::PythonImportTarget>,
+        >,
     ) -> String {
+        if let Some((name, file)) =
+            python_targets.and_then(|t| crate::analysis::resolve_python_call(t, call_name))
+        {
+            let file_prefix = format!("{:08x}:", crate::schema::fnv1a_hash(file) as u32);
+            if let Some(hash) = lookup
+                .get(name)
+                .and_then(|matches| matches.iter().find(|hash| hash.starts_with(&file_prefix)))
+            {
+                return hash.clone();
+            }
+        }
+
         let find_best_match = |matches: &[String]| -> Option<String> {
             if matches.is_empty() {
                 return None;
@@ -1999,6 +2455,19 @@ impl CacheDir {
             }
         }
 
+        // `Type::method` resolves to a method in the file defining `Type`
+        let hashes_of = |name: &str| {
+            lookup
+                .get(name)
+                .map(|matches| matches.iter().map(String::as_str).collect())
+                .unwrap_or_default()
+        };
+        if let Some(resolved) =
+            crate::shard::resolve_type_path_call(call_name, same_file_prefix, hashes_of)
+        {
+            return resolved.to_string();
+        }
+
         // If call has an object (e.g., "playerEntity.GetGoldAmount"), try just the method name
         if let Some(dot_pos) = call_name.rfind('.') {
             let method_name = &call_name[dot_pos + 1..];
@@ -2049,6 +2518,20 @@ impl CacheDir {
         // Build symbol lookup for resolving call names to hashes (before parallel phase)
         let symbol_lookup = Self::build_symbol_lookup_from_summaries(summaries);
 
+        // Repo-local names bound by each Python file's imports
+        let python_packages =
+            crate::analysis::PythonPackageIndex::from_summaries(summaries, Some(&self.repo_root));
+        let python_imports: HashMap<&str, HashMap<String, crate::analysis::PythonImportTarget>> =
+            if python_packages.is_empty() {
+                HashMap::new()
+            } else {
+                summaries
+                    .iter()
+                    .map(|s| (s.file.as_str(), python_packages.import_targets(s)))
+                    .filter(|(_, targets)| !targets.is_empty())
+                    .collect()
+            };
+
         // Progress and stats tracking
         let processed = AtomicUsize::new(0);
         let total_symbols_from_vec = AtomicUsize::new(0);
@@ -2090,6 +2573,7 @@ impl CacheDir {
                     } else {
                         format!("{}:", caller_file_hash)
                     };
+                    let python_targets = python_imports.get(summary.file.as_str());
 
                     // Process each symbol in the file
                     for symbol in &summary.symbols {
@@ -2108,6 +2592,7 @@ impl CacheDir {
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 )
                             } else {
                                 Self::resolve_call_to_hash(
@@ -2115,6 +2600,7 @@ impl CacheDir {
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 )
                             };
                             if seen.insert(resolved.clone()) {
@@ -2133,6 +2619,7 @@ impl CacheDir {
                                         &symbol_lookup,
                                         &same_file_prefix,
                                         &summary.import_sources,
+                                        python_targets,
                                     );
                                     if seen.insert(resolved.clone()) {
                                         calls.push(resolved);
@@ -2161,6 +2648,7 @@ impl CacheDir {
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 )
                             } else {
                                 Self::resolve_call_to_hash(
@@ -2168,6 +2656,7 @@ impl CacheDir {
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 )
                             };
                             if seen.insert(resolved.clone()) {
@@ -2185,6 +2674,7 @@ impl CacheDir {
                                         &symbol_lookup,
                                         &same_file_prefix,
                                         &summary.import_sources,
+                                        python_targets,
                                     );
                                     if seen.insert(resolved.clone()) {
                                         calls.push(resolved);
@@ -2212,6 +2702,7 @@ impl CacheDir {
                                         &symbol_lookup,
                                         &same_file_prefix,
                                         &summary.import_sources,
+                                        python_targets,
                                     );
                                     if seen.insert(resolved.clone()) {
                                         calls.push(resolved);
@@ -2631,6 +3122,57 @@ pub struct SymbolIndexEntry {
     /// Only populated for class/interface/struct nodes
     #[serde(rename = "bc", default, skip_serializing_if = "String::is_empty")]
     pub base_classes: String,
+
+    /// Feature flags gating this symbol (comma-separated), e.g. "beta,new_checkout"
+    #[serde(rename = "ff", default, skip_serializing_if = "String::is_empty")]
+    pub feature_flags: String,
+
+    /// Whether this symbol is marked deprecated
+    #[serde(rename = "dep", default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_deprecated: bool,
+
+    /// Deprecation note (empty when the marker has none)
+    #[serde(rename = "depm", default, skip_serializing_if = "String::is_empty")]
+    pub deprecation_message: String,
+
+    /// Number of `await` expressions in the body
+    #[serde(rename = "aw", default, skip_serializing_if = "is_zero_usize")]
+    pub awaits_count: usize,
+
+    /// Number of I/O calls made outside any try block
+    #[serde(rename = "iot", default, skip_serializing_if = "is_zero_usize")]
+    pub io_calls_outside_try: usize,
+
+    /// Whether a catch/except block swallows errors
+    #[serde(rename = "ec", default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_empty_catch: bool,
+
+    /// Whether a catch/except block throws
+    #[serde(rename = "rth", default, skip_serializing_if = "std::ops::Not::not")]
+    pub rethrows: bool,
+
+    /// Number of places the body can panic or throw
+    #[serde(rename = "pp", default, skip_serializing_if = "is_zero_usize")]
+    pub panic_points: usize,
+
+    /// Tables touched by SQL embedded in string literals (comma-separated),
+    /// e.g. "users,orders"
+    #[serde(rename = "sqt", default, skip_serializing_if = "String::is_empty")]
+    pub sql_tables: String,
+
+    /// Qualified name of the partial type this symbol is one part of,
+    /// e.g. "Shop.OrderService"
+    #[serde(rename = "pof", default, skip_serializing_if = "String::is_empty")]
+    pub partial_of: String,
+
+    /// Hash of the function this closure, callback or nested function is
+    /// defined in
+    #[serde(rename = "par", default, skip_serializing_if = "String::is_empty")]
+    pub parent: String,
+
+    /// Whether the symbol's file is generated code
+    #[serde(rename = "gen", default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_generated: bool,
 }
 
 fn is_zero_usize(v: &usize) -> bool {
@@ -2697,6 +3239,45 @@ pub fn compute_repo_hash(repo_path: &Path) -> String {
     format!("{:016x}", fnv1a_hash(&canonical.to_string_lossy()))
 }
 
+/// Compute a fingerprint identifying which repository lives at a path.
+///
+/// Unlike [`compute_repo_hash`], this tells apart two different repositories
+/// checked out at the same path over time. For git repositories it combines
+/// the work tree root with the root commit SHA(s); otherwise it combines the
+/// path with the names of the top-level entries as a content sample.
+pub fn compute_repo_fingerprint(repo_root: &Path) -> String {
+    let toplevel = git::git_command_optional(&["rev-parse", "--show-toplevel"], Some(repo_root));
+    let roots =
+        git::git_command_optional(&["rev-list", "--max-parents=0", "HEAD"], Some(repo_root));
+
+    let identity = match (toplevel, roots) {
+        (Some(toplevel), Some(roots)) if !roots.is_empty() => {
+            let mut roots: Vec<&str> = roots.lines().collect();
+            roots.sort_unstable();
+            format!("git:{}:{}", toplevel, roots.join(","))
+        }
+        _ => {
+            let mut names: Vec<String> = fs::read_dir(repo_root)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .filter(|name| !name.starts_with('.'))
+                        .collect()
+                })
+                .unwrap_or_default();
+            names.sort_unstable();
+            format!(
+                "dir:{}:{}",
+                fs_utils::normalize_path(repo_root).display(),
+                names.join("/")
+            )
+        }
+    };
+
+    format!("{:016x}", fnv1a_hash(&identity))
+}
+
 /// Get the git remote URL for a repository
 fn get_git_remote_url(repo_path: &Path) -> Option<String> {
     let output = Command::new("git")
@@ -2716,7 +3297,7 @@ fn get_git_remote_url(repo_path: &Path) -> Option<String> {
 }
 
 /// Sanitize a string for use as a filename
-fn sanitize_filename(name: &str) -> String {
+pub(crate) fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
@@ -2728,6 +3309,29 @@ fn sanitize_filename(name: &str) -> String {
         .collect()
 }
 
+/// Stems of the plain `.toon` shards in a directory
+///
+/// Versioned shards written for a generation (`name@g3.toon`) are skipped;
+/// they are listed through the generation that names them.
+fn list_shard_stems(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().map(|e| e == "toon").unwrap_or(false) {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .filter(|s| generation::version_of(s).is_none())
+                    .map(|s| s.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Calculate total size of a directory
 fn dir_size(path: &Path) -> u64 {
     fs::read_dir(path)
@@ -2745,8 +3349,104 @@ fn dir_size(path: &Path) -> u64 {
         .sum()
 }
 
+/// Count the files directly inside a directory
+fn file_count(path: &Path) -> usize {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .count()
+}
+
+/// Storage details of one cached repository, for `cache list` and `cache info`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheRepoInfo {
+    /// Cache directory name (hash of the repository identity)
+    pub repo_hash: String,
+
+    /// Cache directory
+    pub cache_path: PathBuf,
+
+    /// Repository the cache was built from (empty for caches written before
+    /// the path was recorded)
+    pub repo_path: PathBuf,
+
+    /// Seconds since the index was last written
+    pub index_age_secs: u64,
+
+    /// Symbols in the index, as recorded when it was written
+    pub symbol_count: usize,
+
+    /// Module and symbol shard files
+    pub shard_count: usize,
+
+    /// Total size of the cache directory in bytes
+    pub cache_size_bytes: u64,
+
+    /// Total size of the cache directory in MB
+    pub cache_size_mb: f64,
+
+    /// Schema version of the stored index (`unknown` if there is no index)
+    pub schema_version: String,
+
+    /// Branch the repository is on now, if it is a git checkout
+    pub git_branch: Option<String>,
+}
+
+impl CacheRepoInfo {
+    /// Read the details of the cache at `cache_path`
+    fn read(cache_path: PathBuf) -> Self {
+        let repo_hash = cache_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let cache = CacheDir {
+            root: cache_path.clone(),
+            repo_root: PathBuf::new(),
+            repo_hash: repo_hash.clone(),
+        };
+        let meta = cache.load_meta();
+        let repo_path = meta
+            .as_ref()
+            .and_then(|m| m.repo_path.clone())
+            .unwrap_or_default();
+
+        // repo_overview.toon is rewritten by every full index; quarantined
+        // or partial caches fall back to the directory itself
+        let index_age_secs = fs::metadata(cache.repo_overview_path())
+            .or_else(|_| fs::metadata(&cache_path))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age.as_secs())
+            .unwrap_or(0);
+
+        let git_branch = (repo_path.is_dir() && git::is_git_repo(Some(&repo_path)))
+            .then(|| git::get_current_branch(Some(&repo_path)).ok())
+            .flatten();
+
+        let cache_size_bytes = dir_size(&cache_path);
+
+        Self {
+            repo_hash,
+            repo_path,
+            index_age_secs,
+            symbol_count: meta.and_then(|m| m.symbol_count).unwrap_or(0),
+            shard_count: file_count(&cache.modules_dir()) + file_count(&cache.symbols_dir()),
+            cache_size_bytes,
+            cache_size_mb: cache_size_bytes as f64 / (1024.0 * 1024.0),
+            schema_version: cache
+                .stored_schema_version()
+                .unwrap_or_else(|| "unknown".to_string()),
+            git_branch,
+            cache_path,
+        }
+    }
+}
+
 /// List all cached repositories
-pub fn list_cached_repos() -> Vec<(String, PathBuf, u64)> {
+pub fn list_cached_repos() -> Vec<CacheRepoInfo> {
     let cache_base = get_cache_base_dir();
 
     fs::read_dir(&cache_base)
@@ -2754,15 +3454,21 @@ pub fn list_cached_repos() -> Vec<(String, PathBuf, u64)> {
         .flatten()
         .filter_map(|entry| entry.ok())
         .filter(|entry| entry.path().is_dir())
-        .map(|entry| {
-            let path = entry.path();
-            let hash = entry.file_name().to_string_lossy().to_string();
-            let size = dir_size(&path);
-            (hash, path, size)
-        })
+        .filter(|entry| entry.file_name().to_str().is_some_and(is_repo_cache_name))
+        .map(|entry| CacheRepoInfo::read(entry.path()))
         .collect()
 }
 
+/// Whether a directory in the cache base holds a repository cache
+///
+/// Repository caches are named by [`compute_repo_hash`], possibly with a
+/// quarantine suffix; anything else (such as the shared security pattern
+/// store) isn't one.
+fn is_repo_cache_name(name: &str) -> bool {
+    let hash = name.split_once(".corrupt-").map_or(name, |(hash, _)| hash);
+    hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
 /// Prune caches older than the specified number of days
 pub fn prune_old_caches(days: u32) -> Result<usize> {
     let cache_base = get_cache_base_dir();
@@ -2778,9 +3484,18 @@ pub fn prune_old_caches(days: u32) -> Result<usize> {
             continue;
         }
 
-        // Check the modification time of repo_overview.toon as proxy for last use
-        let overview_path = path.join("repo_overview.toon");
-        if let Ok(metadata) = fs::metadata(&overview_path) {
+        // Check the modification time of repo_overview.toon as proxy for last
+        // use; quarantined caches may have lost theirs, so use the directory
+        let is_quarantined = entry
+            .file_name()
+            .to_str()
+            .is_some_and(verify::is_quarantined);
+        let marker = if is_quarantined {
+            path.clone()
+        } else {
+            path.join("repo_overview.toon")
+        };
+        if let Ok(metadata) = fs::metadata(&marker) {
             if let Ok(modified) = metadata.modified() {
                 if modified < cutoff {
                     fs::remove_dir_all(&path)?;
@@ -2853,6 +3568,16 @@ mod tests {
         assert!(match_glob_pattern("foo.bar.baz", "foo*baz"));
     }
 
+    #[test]
+    fn test_is_repo_cache_name() {
+        assert!(is_repo_cache_name("0123456789abcdef"));
+        assert!(is_repo_cache_name(
+            "0123456789abcdef.corrupt-20240101T000000"
+        ));
+        assert!(!is_repo_cache_name("security"));
+        assert!(!is_repo_cache_name("abc123"));
+    }
+
     #[test]
     fn test_cache_base_dir() {
         let base = get_cache_base_dir();
@@ -3317,6 +4042,53 @@ mod tests {
         assert_eq!(meta.merge_base, Some("merge_base".to_string()));
     }
 
+    #[test]
+    fn test_cache_repo_info_reads_recorded_metadata() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_root = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo_root).unwrap();
+        let cache = CacheDir {
+            root: temp_dir.path().join("0123456789abcdef"),
+            repo_root: repo_root.clone(),
+            repo_hash: "0123456789abcdef".to_string(),
+        };
+        fs::create_dir_all(cache.modules_dir()).unwrap();
+        fs::create_dir_all(cache.symbols_dir()).unwrap();
+        fs::write(cache.module_path("api"), "module: api\n").unwrap();
+        fs::write(cache.symbol_path("h1"), "symbol: a\n").unwrap();
+        fs::write(cache.symbol_path("h2"), "symbol: b\n").unwrap();
+        fs::write(cache.repo_overview_path(), "schema_version: \"2.1\"\n").unwrap();
+        cache
+            .record_index_identity(ShardNamingStrategy::default(), 42)
+            .unwrap();
+
+        let info = CacheRepoInfo::read(cache.root.clone());
+
+        assert_eq!(info.repo_hash, "0123456789abcdef");
+        assert_eq!(info.repo_path, repo_root);
+        assert_eq!(info.symbol_count, 42);
+        assert_eq!(info.shard_count, 3);
+        assert_eq!(info.schema_version, "2.1");
+        assert_eq!(info.git_branch, None);
+        assert!(info.index_age_secs < 60);
+        assert!(info.cache_size_bytes > 0);
+    }
+
+    #[test]
+    fn test_cache_repo_info_without_meta() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let info = CacheRepoInfo::read(temp_dir.path().to_path_buf());
+
+        assert!(info.repo_path.as_os_str().is_empty());
+        assert_eq!(info.symbol_count, 0);
+        assert_eq!(info.shard_count, 0);
+        assert_eq!(info.schema_version, "unknown");
+    }
+
     // ========================================================================
     // Layer Cache Error Scenario Tests
     // ========================================================================
@@ -3353,16 +4125,16 @@ mod tests {
             "Should fail to load layer with corrupted symbols.jsonl"
         );
 
-        // Verify it's a deserialization error
+        // Verify it's reported as a corrupt artifact
         match result {
-            Err(crate::McpDiffError::ExtractionFailure { message }) => {
+            Err(crate::McpDiffError::CacheCorrupt { path, .. }) => {
                 assert!(
-                    message.contains("deserialize") || message.contains("symbol entry"),
-                    "Error should indicate symbol deserialization issue: {}",
-                    message
+                    path.ends_with("symbols.jsonl"),
+                    "Error should name the corrupt symbol file: {}",
+                    path.display()
                 );
             }
-            Err(e) => panic!("Expected ExtractionFailure error, got: {:?}", e),
+            Err(e) => panic!("Expected CacheCorrupt error, got: {:?}", e),
             Ok(_) => panic!("Should have failed"),
         }
     }
@@ -3404,16 +4176,16 @@ mod tests {
             "Should fail to load layer with corrupted moves.jsonl"
         );
 
-        // Verify it's a deserialization error
+        // Verify it's reported as a corrupt artifact
         match result {
-            Err(crate::McpDiffError::ExtractionFailure { message }) => {
+            Err(crate::McpDiffError::CacheCorrupt { path, .. }) => {
                 assert!(
-                    message.contains("deserialize") || message.contains("file move"),
-                    "Error should indicate file move deserialization issue: {}",
-                    message
+                    path.ends_with("moves.jsonl"),
+                    "Error should name the corrupt file move file: {}",
+                    path.display()
                 );
             }
-            Err(e) => panic!("Expected ExtractionFailure error, got: {:?}", e),
+            Err(e) => panic!("Expected CacheCorrupt error, got: {:?}", e),
             Ok(_) => panic!("Should have failed"),
         }
     }
@@ -4788,6 +5560,7 @@ mod tests {
                 arg_type: Some("string".to_string()),
                 default_value: None,
             }],
+            param_types: vec![Some("string".to_string())],
             props: Vec::new(),
             return_type: Some("void".to_string()),
             calls: Vec::new(),
@@ -4799,6 +5572,22 @@ mod tests {
             framework_entry_point: crate::schema::FrameworkEntryPoint::None,
             is_async: false,
             base_classes: Vec::new(),
+            doc_comment: None,
+            feature_flags: Vec::new(),
+            generic_params: Vec::new(),
+            lifetime_params: Vec::new(),
+            implements: Vec::new(),
+            is_deprecated: false,
+            deprecation_message: None,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: Vec::new(),
+            embedded_queries: Vec::new(),
+            partial_of: None,
+            parent_symbol: None,
+            nested_id: None,
         };
 
         let hash1 = compute_symbol_hash(&symbol, "/path/to/file.ts");
@@ -4990,6 +5779,18 @@ export { formatName, processData };
                 return_type: symbol.return_type.clone().unwrap_or_default(),
                 ext_package: String::new(),
                 base_classes: symbol.base_classes.join(","),
+                feature_flags: symbol.feature_flags.join(","),
+                is_deprecated: symbol.is_deprecated,
+                deprecation_message: symbol.deprecation_message.clone().unwrap_or_default(),
+                awaits_count: symbol.awaits_count,
+                io_calls_outside_try: symbol.io_calls_outside_try,
+                has_empty_catch: symbol.has_empty_catch,
+                rethrows: symbol.rethrows,
+                panic_points: symbol.panic_points.len(),
+                sql_tables: symbol.embedded_sql_tables(),
+                partial_of: symbol.partial_of.clone().unwrap_or_default(),
+                parent: summary.parent_hash(symbol),
+                is_generated: summary.is_generated,
             });
         }
 
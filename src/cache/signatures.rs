@@ -3,11 +3,10 @@
 //! This module provides unified function signature loading used by both
 //! CLI commands and MCP tools.
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
 use crate::duplicate::FunctionSignature;
+use crate::error::Result;
 
+use super::verify::{corrupt, read_artifact};
 use super::CacheDir;
 
 /// Load function signatures from the cache.
@@ -24,7 +23,9 @@ use super::CacheDir;
 /// # Returns
 ///
 /// * `Ok(Vec<FunctionSignature>)` - Successfully loaded signatures (may be empty)
-/// * `Err(String)` - If the signature file cannot be opened/read
+/// * `Err(McpDiffError::CacheCorrupt)` - If the file can't be decoded or has no
+///   readable signature at all
+/// * `Err(McpDiffError::Io)` - If the signature file cannot be read
 ///
 /// # Example
 ///
@@ -35,7 +36,7 @@ use super::CacheDir;
 /// let signatures = load_function_signatures(&cache)?;
 /// println!("Loaded {} signatures", signatures.len());
 /// ```
-pub fn load_function_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignature>, String> {
+pub fn load_function_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignature>> {
     let sig_path = cache.signature_index_path();
 
     // Return empty if file doesn't exist (graceful degradation)
@@ -43,19 +44,16 @@ pub fn load_function_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignatur
         return Ok(Vec::new());
     }
 
-    let file =
-        File::open(&sig_path).map_err(|e| format!("Failed to open signature index: {}", e))?;
-    let reader = BufReader::new(file);
+    let content = read_artifact(&sig_path)?;
 
     let mut signatures = Vec::new();
-    for (line_num, line_result) in reader.lines().enumerate() {
-        let line = line_result.map_err(|e| format!("Failed to read line: {}", e))?;
-
+    let mut malformed = 0;
+    for (line_num, line) in content.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
 
-        match serde_json::from_str::<FunctionSignature>(&line) {
+        match serde_json::from_str::<FunctionSignature>(line) {
             Ok(sig) => signatures.push(sig),
             Err(e) => {
                 // Log malformed lines for debugging but continue processing
@@ -64,16 +62,22 @@ pub fn load_function_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignatur
                     line_num + 1,
                     e
                 );
+                malformed += 1;
             }
         }
     }
 
+    if signatures.is_empty() && malformed > 0 {
+        return Err(corrupt(&sig_path, "no readable signatures"));
+    }
+
     Ok(signatures)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs::File;
     use std::io::Write;
     use std::path::PathBuf;
     use tempfile::tempdir;
@@ -0,0 +1,679 @@
+//! Compact binary copy of the symbol index for fast cold-start loads.
+//!
+//! `symbol_index.jsonl` stays the source of truth; `symbol_index.bin` holds
+//! the same entries in a form that decodes several times faster than JSON.
+//! Readers use it only when its header matches both the current
+//! [`SCHEMA_VERSION`] and the text index it was written from (by size and
+//! modification time), and fall back to the text index otherwise.
+//!
+//! # Layout
+//!
+//! ```text
+//! header   "SFSI" | format: u8 | schema: u8 len + bytes
+//!          | text_len: u64 | text_mtime_ns: u64 | segments: u32
+//! segment  body_len: u32 | body
+//! body     replaced files | string table | entries
+//! ```
+//!
+//! Integers inside a body are LEB128 varints and every string field of an
+//! entry is an index into the segment's string table, so the kinds, modules,
+//! files and risk levels shared by many symbols are stored once.
+//!
+//! Segments are applied in order: each one first drops the entries of its
+//! replaced files, then appends its own entries. A full write is a single
+//! segment; a single-file reindex appends one, which reproduces exactly the
+//! order [`CacheDir::update_symbol_index_for_file`] leaves in the text index.
+//! Once [`MAX_SEGMENTS`] have accumulated the file is rewritten in one piece.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::time::UNIX_EPOCH;
+
+use crate::error::Result;
+use crate::fs_utils;
+use crate::schema::{FrameworkEntryPoint, SCHEMA_VERSION};
+
+use super::{CacheDir, SymbolIndexEntry};
+
+/// Magic bytes at the start of `symbol_index.bin`
+const MAGIC: &[u8; 4] = b"SFSI";
+
+/// Version of the binary layout, bumped whenever it changes
+pub const FORMAT_VERSION: u8 = 8;
+
+/// Appended segments tolerated before the file is rewritten in one piece
+pub const MAX_SEGMENTS: u32 = 32;
+
+const FLAG_ESCAPE_LOCAL: u8 = 1;
+const FLAG_EXPORTED: u8 = 1 << 1;
+const FLAG_ASYNC: u8 = 1 << 2;
+const FLAG_DEPRECATED: u8 = 1 << 3;
+const FLAG_EMPTY_CATCH: u8 = 1 << 4;
+const FLAG_RETHROWS: u8 = 1 << 5;
+const FLAG_GENERATED: u8 = 1 << 6;
+
+/// Size and modification time of the text index a binary copy was written from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFingerprint {
+    len: u64,
+    mtime_ns: u64,
+}
+
+impl TextFingerprint {
+    /// Fingerprint the current `symbol_index.jsonl`, if it exists
+    pub fn of(cache: &CacheDir) -> Option<Self> {
+        let meta = fs::metadata(cache.symbol_index_path()).ok()?;
+        let mtime_ns = meta
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+        Some(Self {
+            len: meta.len(),
+            mtime_ns,
+        })
+    }
+}
+
+/// Load the symbol index from its binary copy
+///
+/// Returns `None` when the binary copy is missing, was written by another
+/// format or schema version, no longer matches the text index, or can't be
+/// decoded; callers then read the text index instead.
+pub fn load(cache: &CacheDir) -> Option<Vec<SymbolIndexEntry>> {
+    let bytes = fs::read(cache.symbol_index_bin_path()).ok()?;
+    let header = Header::decode(&bytes)?;
+    if Some(header.fingerprint) != TextFingerprint::of(cache) {
+        return None;
+    }
+    decode_segments(&bytes[header.len..], header.segments)
+}
+
+/// Write the whole symbol index as a single segment
+///
+/// `fingerprint` identifies the text index `entries` were read from or
+/// written to; take it before reading so a concurrent rewrite of the text
+/// index leaves the binary copy stale rather than wrong. Returns the number
+/// of bytes written.
+pub fn write(
+    cache: &CacheDir,
+    entries: &[SymbolIndexEntry],
+    fingerprint: TextFingerprint,
+) -> Result<u64> {
+    let path = cache.symbol_index_bin_path();
+    let mut bytes = Header {
+        fingerprint,
+        segments: 1,
+        len: 0,
+    }
+    .encode();
+    push_segment(&mut bytes, &[], entries);
+
+    let tmp_path = path.with_extension("bin.tmp");
+    if let Err(e) =
+        fs::write(&tmp_path, &bytes).and_then(|_| fs_utils::atomic_rename(&tmp_path, &path))
+    {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(bytes.len() as u64)
+}
+
+/// Bring the binary copy up to date after one file was reindexed
+///
+/// `previous` fingerprints the text index before it was rewritten and
+/// `entries` is the complete index afterwards. When the binary copy still
+/// matched `previous`, a segment replacing `file` is appended; otherwise, or
+/// once [`MAX_SEGMENTS`] is reached, the whole index is rewritten.
+pub fn update_file(
+    cache: &CacheDir,
+    file: &str,
+    new_entries: &[SymbolIndexEntry],
+    entries: &[SymbolIndexEntry],
+    previous: Option<TextFingerprint>,
+) -> Result<()> {
+    let Some(current) = TextFingerprint::of(cache) else {
+        return Ok(());
+    };
+    let path = cache.symbol_index_bin_path();
+    let header = fs::read(&path)
+        .ok()
+        .and_then(|bytes| Header::decode(&bytes));
+
+    match header {
+        Some(header) if Some(header.fingerprint) == previous && header.segments < MAX_SEGMENTS => {
+            let mut segment = Vec::new();
+            push_segment(&mut segment, &[file], new_entries);
+
+            let mut out = fs::OpenOptions::new().write(true).open(&path)?;
+            out.seek(SeekFrom::End(0))?;
+            out.write_all(&segment)?;
+            // The header goes last: a crash before this leaves the copy stale
+            let header = Header {
+                fingerprint: current,
+                segments: header.segments + 1,
+                len: header.len,
+            };
+            out.seek(SeekFrom::Start(0))?;
+            out.write_all(&header.encode())?;
+            Ok(())
+        }
+        _ => write(cache, entries, current).map(|_| ()),
+    }
+}
+
+/// Decoded fixed-size header
+struct Header {
+    fingerprint: TextFingerprint,
+    segments: u32,
+    /// Encoded length in bytes
+    len: usize,
+}
+
+impl Header {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + SCHEMA_VERSION.len());
+        out.extend_from_slice(MAGIC);
+        out.push(FORMAT_VERSION);
+        out.push(SCHEMA_VERSION.len() as u8);
+        out.extend_from_slice(SCHEMA_VERSION.as_bytes());
+        out.extend_from_slice(&self.fingerprint.len.to_le_bytes());
+        out.extend_from_slice(&self.fingerprint.mtime_ns.to_le_bytes());
+        out.extend_from_slice(&self.segments.to_le_bytes());
+        out
+    }
+
+    /// Decode the header, rejecting other formats and schema versions
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC || reader.byte()? != FORMAT_VERSION {
+            return None;
+        }
+        let schema_len = reader.byte()? as usize;
+        if reader.take(schema_len)? != SCHEMA_VERSION.as_bytes() {
+            return None;
+        }
+        let len = u64::from_le_bytes(reader.take(8)?.try_into().ok()?);
+        let mtime_ns = u64::from_le_bytes(reader.take(8)?.try_into().ok()?);
+        let segments = u32::from_le_bytes(reader.take(4)?.try_into().ok()?);
+        Some(Self {
+            fingerprint: TextFingerprint { len, mtime_ns },
+            segments,
+            len: reader.pos,
+        })
+    }
+}
+
+/// Append one length-prefixed segment to `out`
+fn push_segment(out: &mut Vec<u8>, replaced: &[&str], entries: &[SymbolIndexEntry]) {
+    let mut strings = StringTable::default();
+    let mut records = Vec::with_capacity(entries.len() * 24);
+    put_varint(&mut records, entries.len() as u64);
+    // Entry point names outlive the table that borrows them
+    let entry_points: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            if entry.framework_entry_point.is_none() {
+                return String::new();
+            }
+            serde_json::to_value(entry.framework_entry_point)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default()
+        })
+        .collect();
+    for (entry, fep) in entries.iter().zip(&entry_points) {
+        for field in [
+            &entry.symbol,
+            &entry.hash,
+            &entry.kind,
+            &entry.module,
+            &entry.file,
+            &entry.lines,
+            &entry.risk,
+            fep,
+            &entry.decorators,
+            &entry.return_type,
+            &entry.ext_package,
+            &entry.base_classes,
+            &entry.feature_flags,
+            &entry.deprecation_message,
+            &entry.sql_tables,
+            &entry.partial_of,
+            &entry.parent,
+        ] {
+            put_varint(&mut records, strings.intern(field) as u64);
+        }
+        put_varint(&mut records, entry.cognitive_complexity as u64);
+        put_varint(&mut records, entry.max_nesting as u64);
+        put_varint(&mut records, entry.arity as u64);
+        put_varint(&mut records, entry.awaits_count as u64);
+        put_varint(&mut records, entry.io_calls_outside_try as u64);
+        put_varint(&mut records, entry.panic_points as u64);
+        let mut flags = 0;
+        if entry.is_escape_local {
+            flags |= FLAG_ESCAPE_LOCAL;
+        }
+        if entry.is_exported {
+            flags |= FLAG_EXPORTED;
+        }
+        if entry.is_async {
+            flags |= FLAG_ASYNC;
+        }
+        if entry.is_deprecated {
+            flags |= FLAG_DEPRECATED;
+        }
+        if entry.has_empty_catch {
+            flags |= FLAG_EMPTY_CATCH;
+        }
+        if entry.rethrows {
+            flags |= FLAG_RETHROWS;
+        }
+        if entry.is_generated {
+            flags |= FLAG_GENERATED;
+        }
+        records.push(flags);
+    }
+
+    let mut body = Vec::with_capacity(records.len() + strings.bytes);
+    put_varint(&mut body, replaced.len() as u64);
+    for file in replaced {
+        put_str(&mut body, file);
+    }
+    put_varint(&mut body, strings.values.len() as u64);
+    for value in &strings.values {
+        put_str(&mut body, value);
+    }
+    body.extend_from_slice(&records);
+
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&body);
+}
+
+/// Apply `count` segments in order and return the resulting entries
+fn decode_segments(bytes: &[u8], count: u32) -> Option<Vec<SymbolIndexEntry>> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut entries: Vec<SymbolIndexEntry> = Vec::new();
+    let mut entry_points: HashMap<String, FrameworkEntryPoint> = HashMap::new();
+
+    for _ in 0..count {
+        let body_len = u32::from_le_bytes(reader.take(4)?.try_into().ok()?) as usize;
+        let mut body = Reader {
+            bytes: reader.take(body_len)?,
+            pos: 0,
+        };
+
+        let replaced = (0..body.varint()?)
+            .map(|_| body.str())
+            .collect::<Option<Vec<_>>>()?;
+        if !replaced.is_empty() {
+            entries.retain(|entry| !replaced.contains(&entry.file.as_str()));
+        }
+
+        let strings = (0..body.varint()?)
+            .map(|_| body.str())
+            .collect::<Option<Vec<_>>>()?;
+        let string = |id: u64| strings.get(id as usize).map(|s| s.to_string());
+
+        let count = body.varint()? as usize;
+        entries.reserve(count);
+        for _ in 0..count {
+            let symbol = string(body.varint()?)?;
+            let hash = string(body.varint()?)?;
+            let kind = string(body.varint()?)?;
+            let module = string(body.varint()?)?;
+            let file = string(body.varint()?)?;
+            let lines = string(body.varint()?)?;
+            let risk = string(body.varint()?)?;
+            let fep = strings.get(body.varint()? as usize)?;
+            let framework_entry_point = if fep.is_empty() {
+                FrameworkEntryPoint::None
+            } else if let Some(known) = entry_points.get(*fep) {
+                *known
+            } else {
+                let parsed: FrameworkEntryPoint =
+                    serde_json::from_value(serde_json::Value::String(fep.to_string())).ok()?;
+                entry_points.insert(fep.to_string(), parsed);
+                parsed
+            };
+            let decorators = string(body.varint()?)?;
+            let return_type = string(body.varint()?)?;
+            let ext_package = string(body.varint()?)?;
+            let base_classes = string(body.varint()?)?;
+            let feature_flags = string(body.varint()?)?;
+            let deprecation_message = string(body.varint()?)?;
+            let sql_tables = string(body.varint()?)?;
+            let partial_of = string(body.varint()?)?;
+            let parent = string(body.varint()?)?;
+            let cognitive_complexity = body.varint()? as usize;
+            let max_nesting = body.varint()? as usize;
+            let arity = body.varint()? as usize;
+            let awaits_count = body.varint()? as usize;
+            let io_calls_outside_try = body.varint()? as usize;
+            let panic_points = body.varint()? as usize;
+            let flags = body.byte()?;
+
+            entries.push(SymbolIndexEntry {
+                symbol,
+                hash,
+                // Not stored in the text index either
+                semantic_hash: String::new(),
+                kind,
+                module,
+                file,
+                lines,
+                risk,
+                cognitive_complexity,
+                max_nesting,
+                is_escape_local: flags & FLAG_ESCAPE_LOCAL != 0,
+                framework_entry_point,
+                is_exported: flags & FLAG_EXPORTED != 0,
+                decorators,
+                arity,
+                is_async: flags & FLAG_ASYNC != 0,
+                return_type,
+                ext_package,
+                base_classes,
+                feature_flags,
+                is_deprecated: flags & FLAG_DEPRECATED != 0,
+                deprecation_message,
+                awaits_count,
+                io_calls_outside_try,
+                has_empty_catch: flags & FLAG_EMPTY_CATCH != 0,
+                rethrows: flags & FLAG_RETHROWS != 0,
+                panic_points,
+                sql_tables,
+                partial_of,
+                parent,
+                is_generated: flags & FLAG_GENERATED != 0,
+            });
+        }
+        if body.pos != body.bytes.len() {
+            return None;
+        }
+    }
+
+    (reader.pos == bytes.len()).then_some(entries)
+}
+
+/// Strings of one segment, each stored once
+#[derive(Default)]
+struct StringTable<'a> {
+    ids: HashMap<&'a str, u32>,
+    values: Vec<&'a str>,
+    bytes: usize,
+}
+
+impl<'a> StringTable<'a> {
+    fn intern(&mut self, value: &'a str) -> u32 {
+        if let Some(id) = self.ids.get(value) {
+            return *id;
+        }
+        let id = self.values.len() as u32;
+        self.ids.insert(value, id);
+        self.values.push(value);
+        self.bytes += value.len() + 1;
+        id
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_str(out: &mut Vec<u8>, value: &str) {
+    put_varint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+/// Bounds-checked cursor; every read returns `None` past the end
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let slice = self.bytes.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn str(&mut self) -> Option<&'a str> {
+        let len = self.varint()? as usize;
+        std::str::from_utf8(self.take(len)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn test_cache(root: &Path) -> CacheDir {
+        let cache = CacheDir {
+            root: root.join("cache"),
+            repo_root: root.to_path_buf(),
+            repo_hash: "test".to_string(),
+        };
+        fs::create_dir_all(&cache.root).unwrap();
+        cache
+    }
+
+    /// Entries exercising every field, with values shared across entries
+    fn sample_entries(files: usize, per_file: usize) -> Vec<SymbolIndexEntry> {
+        let mut entries = Vec::new();
+        for f in 0..files {
+            for i in 0..per_file {
+                entries.push(SymbolIndexEntry {
+                    symbol: format!("sym_{}_{}", f, i),
+                    hash: format!("{:08x}:{:08x}", f, i),
+                    semantic_hash: String::new(),
+                    kind: ["function", "class", "component"][i % 3].to_string(),
+                    module: format!("mod{}", f % 4),
+                    file: format!("src/file{}.ts", f),
+                    lines: format!("{}-{}", i * 10 + 1, i * 10 + 9),
+                    risk: ["low", "medium", "high"][i % 3].to_string(),
+                    cognitive_complexity: i * 3,
+                    max_nesting: i % 4,
+                    is_escape_local: i % 5 == 0,
+                    framework_entry_point: if i % 4 == 0 {
+                        FrameworkEntryPoint::ExpressRoute
+                    } else {
+                        FrameworkEntryPoint::None
+                    },
+                    is_exported: i % 2 == 0,
+                    decorators: if i % 3 == 0 {
+                        "@Get,@Auth".into()
+                    } else {
+                        String::new()
+                    },
+                    arity: i % 6,
+                    is_async: i % 2 == 1,
+                    return_type: if i % 2 == 0 {
+                        "Promise<User>".into()
+                    } else {
+                        String::new()
+                    },
+                    ext_package: if f == 0 {
+                        "react".into()
+                    } else {
+                        String::new()
+                    },
+                    base_classes: if i == 1 {
+                        "Base,Mixin".into()
+                    } else {
+                        String::new()
+                    },
+                    feature_flags: if i == 2 { "beta".into() } else { String::new() },
+                    is_deprecated: i == 3,
+                    deprecation_message: if i == 3 {
+                        "use save_v2".into()
+                    } else {
+                        String::new()
+                    },
+                    awaits_count: i % 3,
+                    io_calls_outside_try: i % 2,
+                    has_empty_catch: i == 4,
+                    rethrows: i == 5,
+                    panic_points: i % 4,
+                    sql_tables: if i == 3 {
+                        "users".into()
+                    } else {
+                        String::new()
+                    },
+                    partial_of: if i == 2 {
+                        "Shop.OrderService".into()
+                    } else {
+                        String::new()
+                    },
+                    parent: if i == 5 {
+                        format!("{:08x}:{:08x}", f, 4)
+                    } else {
+                        String::new()
+                    },
+                    is_generated: i == 6,
+                });
+            }
+        }
+        entries
+    }
+
+    fn write_text(cache: &CacheDir, entries: &[SymbolIndexEntry]) {
+        let mut file = fs::File::create(cache.symbol_index_path()).unwrap();
+        for entry in entries {
+            writeln!(file, "{}", serde_json::to_string(entry).unwrap()).unwrap();
+        }
+    }
+
+    fn debug_all(entries: &[SymbolIndexEntry]) -> Vec<String> {
+        entries.iter().map(|e| format!("{:?}", e)).collect()
+    }
+
+    #[test]
+    fn test_binary_index_matches_text_index() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        write_text(&cache, &sample_entries(6, 7));
+
+        // First load parses the text index and writes the binary copy
+        assert!(load(&cache).is_none());
+        let from_text = cache.load_all_symbol_entries().unwrap();
+        let from_binary = load(&cache).expect("binary copy written lazily");
+        assert_eq!(debug_all(&from_binary), debug_all(&from_text));
+        assert_eq!(
+            debug_all(&cache.load_text_symbol_entries().unwrap()),
+            debug_all(&from_text)
+        );
+
+        // Queries answer identically with and without the binary copy
+        let search = |cache: &CacheDir| {
+            debug_all(&cache.search_symbols("sym_3", None, None, None, 5).unwrap())
+        };
+        let filtered = |cache: &CacheDir| {
+            debug_all(
+                &cache
+                    .search_symbols("", Some("mod1"), Some("fn"), Some("low"), 100)
+                    .unwrap(),
+            )
+        };
+        let module = |cache: &CacheDir| {
+            debug_all(
+                &cache
+                    .list_module_symbols("mod2", Some("struct"), None, 100)
+                    .unwrap(),
+            )
+        };
+        let answers = (search(&cache), filtered(&cache), module(&cache));
+        fs::remove_file(cache.symbol_index_bin_path()).unwrap();
+        assert_eq!((search(&cache), filtered(&cache), module(&cache)), answers);
+        assert!(!answers.0.is_empty() && !answers.1.is_empty() && !answers.2.is_empty());
+    }
+
+    #[test]
+    fn test_single_file_update_appends_a_segment() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        let entries = sample_entries(3, 4);
+        write_text(&cache, &entries);
+        write(&cache, &entries, TextFingerprint::of(&cache).unwrap()).unwrap();
+        let full_len = fs::metadata(cache.symbol_index_bin_path()).unwrap().len();
+
+        let mut replacement = sample_entries(1, 2);
+        for entry in &mut replacement {
+            entry.file = "src/file1.ts".to_string();
+            entry.symbol.push_str("_new");
+        }
+        cache
+            .update_symbol_index_for_file("src/file1.ts", replacement)
+            .unwrap();
+
+        let bytes = fs::read(cache.symbol_index_bin_path()).unwrap();
+        let header = Header::decode(&bytes).unwrap();
+        assert_eq!(header.segments, 2);
+        assert!(bytes.len() as u64 > full_len);
+
+        let from_binary = load(&cache).expect("binary copy kept current");
+        let from_text = cache.load_text_symbol_entries().unwrap();
+        assert_eq!(debug_all(&from_binary), debug_all(&from_text));
+        assert_eq!(
+            from_binary
+                .iter()
+                .filter(|e| e.file == "src/file1.ts")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_stale_or_foreign_binary_is_ignored() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        let entries = sample_entries(2, 3);
+        write_text(&cache, &entries);
+        write(&cache, &entries, TextFingerprint::of(&cache).unwrap()).unwrap();
+        assert!(load(&cache).is_some());
+
+        // The text index changed underneath the binary copy
+        write_text(&cache, &entries[..4]);
+        assert!(load(&cache).is_none());
+        assert_eq!(cache.load_all_symbol_entries().unwrap().len(), 4);
+        assert_eq!(load(&cache).unwrap().len(), 4);
+
+        // Another format version
+        let mut bytes = fs::read(cache.symbol_index_bin_path()).unwrap();
+        bytes[4] = FORMAT_VERSION + 1;
+        fs::write(cache.symbol_index_bin_path(), &bytes).unwrap();
+        assert!(load(&cache).is_none());
+
+        // Truncated body
+        bytes[4] = FORMAT_VERSION;
+        bytes.truncate(bytes.len() - 3);
+        fs::write(cache.symbol_index_bin_path(), &bytes).unwrap();
+        assert!(load(&cache).is_none());
+    }
+}
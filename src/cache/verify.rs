@@ -0,0 +1,626 @@
+//! Integrity checks and recovery for cached index artifacts.
+//!
+//! Cache readers distinguish three failure modes: an artifact that is missing
+//! ([`McpDiffError::FileNotFound`]), one whose bytes can't be decoded
+//! ([`McpDiffError::CacheCorrupt`]), and one written by an incompatible schema
+//! ([`McpDiffError::CacheIncompatible`]).
+//!
+//! [`verify_cache`] walks every artifact and reports its state, [`RepairPlan`]
+//! removes and rebuilds only what is broken, and [`quarantine_cache`] moves an
+//! unusable cache aside so a fresh index can be generated in its place.
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::duplicate::FunctionSignature;
+use crate::error::{McpDiffError, Result};
+use crate::hotspots::HotspotIndex;
+use crate::module_usage::ModuleUsageIndex;
+use crate::overlay::{FileMove, LayerMeta};
+use crate::ownership::OwnershipIndex;
+use crate::schema::SCHEMA_VERSION;
+use crate::test_links::TestLinkIndex;
+
+use super::{migrate, CacheDir, CacheMeta, LayeredIndexMeta, SymbolEntry, SymbolIndexEntry};
+
+/// Header every SQLite database file starts with
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+
+/// Bytes read from the end of the symbol index by [`check_essentials`]
+const TAIL_CHECK_BYTES: u64 = 64 * 1024;
+
+/// Kind of cached artifact, which decides how it is rebuilt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// repo_overview.toon
+    Overview,
+    /// meta.json
+    Meta,
+    /// modules/*.toon
+    Module,
+    /// symbols/*.toon
+    Symbol,
+    /// symbol_index.jsonl
+    SymbolIndex,
+    /// signature_index.jsonl
+    SignatureIndex,
+    /// graphs/*.toon
+    Graph,
+    /// index.sqlite (BM25 index and module registry)
+    SearchIndex,
+    /// layers/**
+    Layer,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Overview => "overview",
+            Self::Meta => "meta",
+            Self::Module => "module",
+            Self::Symbol => "symbol",
+            Self::SymbolIndex => "symbol_index",
+            Self::SignatureIndex => "signature_index",
+            Self::Graph => "graph",
+            Self::SearchIndex => "search_index",
+            Self::Layer => "layer",
+        }
+    }
+}
+
+/// State of a cached artifact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactState {
+    Ok,
+    Missing,
+    Corrupt,
+    Incompatible,
+}
+
+impl ArtifactState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Missing => "missing",
+            Self::Corrupt => "corrupt",
+            Self::Incompatible => "incompatible",
+        }
+    }
+}
+
+/// Verification result for a single artifact
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactStatus {
+    /// Path relative to the cache root
+    pub path: String,
+    pub kind: ArtifactKind,
+    pub state: ArtifactState,
+    /// Why the artifact is not usable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ArtifactStatus {
+    fn new(cache: &CacheDir, path: &Path, kind: ArtifactKind, check: Result<()>) -> Self {
+        let (state, detail) = match check {
+            Ok(()) => (ArtifactState::Ok, None),
+            Err(McpDiffError::FileNotFound { .. }) => (ArtifactState::Missing, None),
+            Err(McpDiffError::CacheIncompatible {
+                found, expected, ..
+            }) => (
+                ArtifactState::Incompatible,
+                Some(format!("schema {}, expected {}", found, expected)),
+            ),
+            Err(McpDiffError::CacheCorrupt { message, .. }) => {
+                (ArtifactState::Corrupt, Some(message))
+            }
+            Err(e) => (ArtifactState::Corrupt, Some(e.to_string())),
+        };
+        Self {
+            path: path
+                .strip_prefix(&cache.root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            kind,
+            state,
+            detail,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.state == ArtifactState::Ok
+    }
+}
+
+/// Read a text artifact, distinguishing missing files from undecodable ones
+pub fn read_artifact(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => McpDiffError::FileNotFound {
+            path: path.display().to_string(),
+        },
+        _ => McpDiffError::Io(e),
+    })?;
+    String::from_utf8(bytes).map_err(|e| corrupt(path, e.utf8_error()))
+}
+
+/// Build a [`McpDiffError::CacheCorrupt`] for `path`
+pub fn corrupt(path: &Path, message: impl std::fmt::Display) -> McpDiffError {
+    McpDiffError::CacheCorrupt {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    }
+}
+
+/// Check every cached artifact and report its state, sorted by path
+///
+/// The overview and symbol index are required and reported as missing when
+/// absent; every other artifact is only checked if it exists.
+pub fn verify_cache(cache: &CacheDir) -> Vec<ArtifactStatus> {
+    let mut statuses = Vec::new();
+    let mut check = |path: PathBuf, kind: ArtifactKind, result: Result<()>| {
+        statuses.push(ArtifactStatus::new(cache, &path, kind, result));
+    };
+
+    check(
+        cache.repo_overview_path(),
+        ArtifactKind::Overview,
+        check_overview(cache),
+    );
+    let meta_path = cache.meta_path();
+    if meta_path.exists() {
+        check(
+            meta_path.clone(),
+            ArtifactKind::Meta,
+            check_meta(&meta_path),
+        );
+    }
+    for path in files_in(&cache.modules_dir()) {
+        let result = read_artifact(&path).map(|_| ());
+        check(path, ArtifactKind::Module, result);
+    }
+    for path in files_in(&cache.symbols_dir()) {
+        let result = read_artifact(&path).map(|_| ());
+        check(path, ArtifactKind::Symbol, result);
+    }
+
+    let symbol_index = cache.symbol_index_path();
+    let result = check_jsonl::<SymbolIndexEntry>(&symbol_index);
+    check(symbol_index, ArtifactKind::SymbolIndex, result);
+
+    let signature_index = cache.signature_index_path();
+    if signature_index.exists() {
+        let result = check_jsonl::<FunctionSignature>(&signature_index);
+        check(signature_index, ArtifactKind::SignatureIndex, result);
+    }
+    for path in files_in(&cache.graphs_dir()) {
+        let result = if path == cache.module_usage_path() {
+            parse_json::<ModuleUsageIndex>(&path).map(|_| ())
+        } else if path == cache.test_links_path() {
+            parse_json::<TestLinkIndex>(&path).map(|_| ())
+        } else if path == cache.hotspots_path() {
+            parse_json::<HotspotIndex>(&path).map(|_| ())
+        } else if path == cache.ownership_path() {
+            parse_json::<OwnershipIndex>(&path).map(|_| ())
+        } else {
+            check_graph(&path)
+        };
+        check(path, ArtifactKind::Graph, result);
+    }
+    let search_index = cache.index_db_path();
+    if search_index.exists() {
+        let result = check_sqlite(&search_index);
+        check(search_index, ArtifactKind::SearchIndex, result);
+    }
+
+    let layers_dir = cache.layers_dir();
+    for path in files_in(&layers_dir) {
+        let result = check_layer_file(&path);
+        check(path, ArtifactKind::Layer, result);
+    }
+    for dir in dirs_in(&layers_dir) {
+        for path in files_in(&dir) {
+            let result = check_layer_file(&path);
+            check(path, ArtifactKind::Layer, result);
+        }
+    }
+
+    statuses.sort_by(|a, b| a.path.cmp(&b.path));
+    statuses
+}
+
+/// Cheap integrity check run before serving queries from the cache
+///
+/// Covers the artifacts every query depends on without reading whole shards:
+/// the overview, meta.json, the tail of the symbol index (where truncation
+/// shows up) and the search index header. Missing artifacts are not errors
+/// here; the caller regenerates those anyway.
+pub fn check_essentials(cache: &CacheDir) -> Result<()> {
+    let overview = cache.repo_overview_path();
+    if overview.exists() {
+        check_overview(cache)?;
+    }
+    let meta_path = cache.meta_path();
+    if meta_path.exists() {
+        check_meta(&meta_path)?;
+    }
+    let symbol_index = cache.symbol_index_path();
+    if symbol_index.exists() {
+        check_jsonl_tail::<SymbolIndexEntry>(&symbol_index)?;
+    }
+    let search_index = cache.index_db_path();
+    if search_index.exists() {
+        check_sqlite_header(&search_index)?;
+    }
+    Ok(())
+}
+
+/// Move the cache aside to `<root>.corrupt-<timestamp>` and return the new path
+///
+/// The quarantined copy is kept for inspection; `cache prune` removes it once
+/// it is old enough.
+pub fn quarantine_cache(cache: &CacheDir) -> Result<PathBuf> {
+    let name = cache
+        .root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| cache.repo_hash.clone());
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3f");
+    let target = cache
+        .root
+        .with_file_name(format!("{}.corrupt-{}", name, stamp));
+    fs::rename(&cache.root, &target).map_err(|e| McpDiffError::IoError {
+        path: cache.root.clone(),
+        message: format!("failed to quarantine cache: {}", e),
+    })?;
+    Ok(target)
+}
+
+/// Whether a cache directory name marks a quarantined cache
+pub fn is_quarantined(name: &str) -> bool {
+    name.contains(".corrupt-")
+}
+
+/// What it takes to bring a cache back to a usable state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairPlan {
+    /// Broken artifacts to delete before rebuilding
+    pub remove: Vec<PathBuf>,
+    /// The overview was written by another schema version
+    pub migrate: bool,
+    /// Overlay layers are broken (they are rebuilt lazily once cleared)
+    pub clear_layers: bool,
+    /// Only graphs are broken; they can be rebuilt from the symbol index
+    pub regenerate_graphs: bool,
+    /// Index shards are broken and the index must be regenerated from source
+    pub full_rebuild: bool,
+}
+
+impl RepairPlan {
+    /// Plan the cheapest repair for the broken artifacts in `statuses`
+    pub fn from_statuses(cache: &CacheDir, statuses: &[ArtifactStatus]) -> Self {
+        let mut plan = Self::default();
+        for status in statuses.iter().filter(|s| !s.is_ok()) {
+            match (status.kind, status.state) {
+                (ArtifactKind::Overview, ArtifactState::Incompatible) => plan.migrate = true,
+                (ArtifactKind::Layer, _) => plan.clear_layers = true,
+                (ArtifactKind::Graph, _) => plan.regenerate_graphs = true,
+                _ => plan.full_rebuild = true,
+            }
+            // Layers are cleared as a whole and an outdated overview is migrated
+            let remove = match status.state {
+                ArtifactState::Ok | ArtifactState::Missing => false,
+                ArtifactState::Incompatible => status.kind != ArtifactKind::Overview,
+                ArtifactState::Corrupt => true,
+            };
+            if remove && status.kind != ArtifactKind::Layer {
+                plan.remove.push(cache.root.join(&status.path));
+            }
+        }
+        // A full rebuild rewrites the graphs too
+        plan.regenerate_graphs &= !plan.full_rebuild;
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Remove broken artifacts and apply every repair that doesn't need
+    /// source analysis. Returns whether a full index rebuild is still needed.
+    pub fn apply(&self, cache: &CacheDir) -> Result<bool> {
+        for path in &self.remove {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        if self.clear_layers {
+            cache.clear_layers()?;
+        }
+        let mut full_rebuild = self.full_rebuild;
+        if self.migrate {
+            full_rebuild |= migrate::migrate_cache(cache)?.rebuild_required;
+        }
+        if self.regenerate_graphs && !full_rebuild {
+            cache.regenerate_graphs()?;
+        }
+        Ok(full_rebuild)
+    }
+}
+
+fn check_overview(cache: &CacheDir) -> Result<()> {
+    let path = cache.repo_overview_path();
+    let content = read_artifact(&path)?;
+    let version = content
+        .lines()
+        .find_map(|line| line.strip_prefix("schema_version:"))
+        .map(|version| version.trim().trim_matches('"').to_string())
+        .ok_or_else(|| corrupt(&path, "no schema_version"))?;
+    if version != SCHEMA_VERSION {
+        return Err(McpDiffError::CacheIncompatible {
+            path,
+            found: version,
+            expected: SCHEMA_VERSION.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn check_meta(path: &Path) -> Result<()> {
+    let meta: CacheMeta = parse_json(path)?;
+    if !meta.is_compatible() {
+        return Err(McpDiffError::CacheIncompatible {
+            path: path.to_path_buf(),
+            found: meta.schema_version,
+            expected: SCHEMA_VERSION.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn check_layer_file(path: &Path) -> Result<()> {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let in_layer = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .is_some_and(|n| n != "layers");
+    match name {
+        "meta.json" if in_layer => parse_json::<LayerMeta>(path).map(|_| ()),
+        "meta.json" => {
+            let meta: LayeredIndexMeta = parse_json(path)?;
+            if meta.schema_version != SCHEMA_VERSION {
+                return Err(McpDiffError::CacheIncompatible {
+                    path: path.to_path_buf(),
+                    found: meta.schema_version,
+                    expected: SCHEMA_VERSION.to_string(),
+                });
+            }
+            Ok(())
+        }
+        "symbols.jsonl" => check_jsonl::<SymbolEntry>(path),
+        "moves.jsonl" => check_jsonl::<FileMove>(path),
+        _ => read_artifact(path).map(|_| ()),
+    }
+}
+
+/// Graph lines look like `hash: [callee, ...]`; a missing bracket means the
+/// file was cut short
+fn check_graph(path: &Path) -> Result<()> {
+    let content = read_artifact(path)?;
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(pos) = line.find(": [") {
+            if !line[pos..].ends_with(']') {
+                return Err(corrupt(
+                    path,
+                    format!("line {}: unterminated list", number + 1),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = read_artifact(path)?;
+    serde_json::from_str(&content).map_err(|e| corrupt(path, e))
+}
+
+fn check_jsonl<T: DeserializeOwned>(path: &Path) -> Result<()> {
+    let content = read_artifact(path)?;
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        serde_json::from_str::<T>(line)
+            .map_err(|e| corrupt(path, format!("line {}: {}", number + 1, e)))?;
+    }
+    Ok(())
+}
+
+/// Check the last complete line of a JSON Lines file
+fn check_jsonl_tail<T: DeserializeOwned>(path: &Path) -> Result<()> {
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_CHECK_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    // Skip the (possibly partial) first line of the window
+    if start > 0 {
+        match bytes.iter().position(|&b| b == b'\n') {
+            Some(pos) => bytes.drain(..=pos),
+            None => return Ok(()),
+        };
+    }
+    let tail = String::from_utf8(bytes).map_err(|e| corrupt(path, e.utf8_error()))?;
+    match tail.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => serde_json::from_str::<T>(line)
+            .map(|_| ())
+            .map_err(|e| corrupt(path, format!("last line: {}", e))),
+        None => Ok(()),
+    }
+}
+
+pub(super) fn check_sqlite_header(path: &Path) -> Result<()> {
+    let mut header = [0u8; 16];
+    let mut file = fs::File::open(path)?;
+    match file.read_exact(&mut header) {
+        Ok(()) if header == SQLITE_MAGIC => Ok(()),
+        // SQLite creates empty files for new connections
+        Err(_) if file.metadata()?.len() == 0 => Ok(()),
+        _ => Err(corrupt(path, "not a SQLite database")),
+    }
+}
+
+fn check_sqlite(path: &Path) -> Result<()> {
+    check_sqlite_header(path)?;
+    let conn = rusqlite::Connection::open(path).map_err(|e| corrupt(path, e))?;
+    let result: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| corrupt(path, e))?;
+    if result != "ok" {
+        return Err(corrupt(path, result));
+    }
+    Ok(())
+}
+
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    entries_in(dir, |p| p.is_file() && !is_temp_file(p))
+}
+
+fn dirs_in(dir: &Path) -> Vec<PathBuf> {
+    entries_in(dir, |p| p.is_dir())
+}
+
+fn entries_in(dir: &Path, keep: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| keep(path))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Leftovers of interrupted atomic writes are not artifacts
+fn is_temp_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "tmp")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn test_cache(root: &Path) -> CacheDir {
+        let cache = CacheDir {
+            root: root.join("cache"),
+            repo_root: root.to_path_buf(),
+            repo_hash: "test".to_string(),
+        };
+        fs::create_dir_all(cache.modules_dir()).unwrap();
+        fs::write(
+            cache.repo_overview_path(),
+            format!(
+                "_type: repo_overview\nschema_version: \"{}\"\n",
+                SCHEMA_VERSION
+            ),
+        )
+        .unwrap();
+        fs::write(
+            cache.module_path("api"),
+            "_type: module_shard\nmodule: api\n",
+        )
+        .unwrap();
+        fs::write(cache.symbol_index_path(), "").unwrap();
+        cache
+    }
+
+    fn state_of(statuses: &[ArtifactStatus], path: &str) -> ArtifactState {
+        statuses.iter().find(|s| s.path == path).unwrap().state
+    }
+
+    #[test]
+    fn test_verify_healthy_cache() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        let statuses = verify_cache(&cache);
+        assert!(statuses.iter().all(|s| s.is_ok()), "{:?}", statuses);
+        assert!(check_essentials(&cache).is_ok());
+        assert!(RepairPlan::from_statuses(&cache, &statuses).is_empty());
+    }
+
+    #[test]
+    fn test_verify_distinguishes_failures() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        fs::write(cache.module_path("api"), [0x5f, 0xff, 0xfe, 0x00]).unwrap();
+        fs::write(cache.index_db_path(), b"garbage bytes, not a database").unwrap();
+        fs::write(
+            cache.repo_overview_path(),
+            "_type: repo_overview\nschema_version: \"99.0\"\n",
+        )
+        .unwrap();
+        fs::remove_file(cache.symbol_index_path()).unwrap();
+
+        let statuses = verify_cache(&cache);
+        assert_eq!(
+            state_of(&statuses, "modules/api.toon"),
+            ArtifactState::Corrupt
+        );
+        assert_eq!(state_of(&statuses, "index.sqlite"), ArtifactState::Corrupt);
+        assert_eq!(
+            state_of(&statuses, "repo_overview.toon"),
+            ArtifactState::Incompatible
+        );
+        assert_eq!(
+            state_of(&statuses, "symbol_index.jsonl"),
+            ArtifactState::Missing
+        );
+
+        let plan = RepairPlan::from_statuses(&cache, &statuses);
+        assert!(plan.migrate && plan.full_rebuild);
+        assert_eq!(
+            plan.remove,
+            vec![cache.index_db_path(), cache.module_path("api")]
+        );
+    }
+
+    #[test]
+    fn test_truncated_symbol_index_is_corrupt() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        fs::write(cache.symbol_index_path(), "{\"s\":\"main\",\"h\":\"ab").unwrap();
+
+        assert!(matches!(
+            check_essentials(&cache),
+            Err(McpDiffError::CacheCorrupt { .. })
+        ));
+        assert!(cache
+            .load_all_symbol_entries()
+            .unwrap_err()
+            .is_cache_damage());
+    }
+
+    #[test]
+    fn test_quarantine_moves_cache_aside() {
+        let dir = tempdir().unwrap();
+        let cache = test_cache(dir.path());
+        let target = quarantine_cache(&cache).unwrap();
+        assert!(!cache.root.exists());
+        assert!(target.join("repo_overview.toon").exists());
+        assert!(is_quarantined(
+            &target.file_name().unwrap().to_string_lossy()
+        ));
+    }
+}
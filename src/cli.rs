@@ -3,8 +3,20 @@
 //! This module defines the command-line interface for semfora-engine using
 //! a subcommand-based structure for better organization and discoverability.
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCompleter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::commands::completions::complete_modules;
+use crate::commands::fail_on::FailOnRule;
+use crate::extract::ExtractionLevel;
+use crate::lang::Lang;
+use crate::search::FacetFilter;
+use crate::settings::RepoSettings;
+use crate::shard::ShardNamingStrategy;
 
 /// Semantic code analyzer with TOON output
 #[derive(Parser, Debug)]
@@ -27,8 +39,30 @@ pub struct Cli {
     pub verbose: bool,
 
     /// Show progress percentage during long operations
-    #[arg(long, global = true)]
+    #[arg(long, global = true, overrides_with = "no_progress")]
     pub progress: bool,
+
+    /// Never show progress, even when `--progress-format` is given
+    #[arg(long, global = true, overrides_with = "progress")]
+    pub no_progress: bool,
+
+    /// How progress is reported on stderr (`json` implies `--progress`)
+    #[arg(long, default_value = "text", value_enum, global = true)]
+    pub progress_format: ProgressFormat,
+
+    /// How file paths are written in output (relative to the repo root, or absolute)
+    #[arg(long, default_value = "relative", value_enum, global = true)]
+    pub paths: PathStyle,
+
+    /// Print every command and argument as JSON (names, types, defaults,
+    /// value hints, environment overrides) and exit
+    #[arg(long)]
+    pub json_help: bool,
+
+    /// Config-file defaults of the repository the command works on, for
+    /// commands that take them (filled in by [`Cli::parse_args`])
+    #[arg(skip)]
+    pub settings: Option<Arc<RepoSettings>>,
 }
 
 // ============================================
@@ -87,6 +121,9 @@ pub enum Commands {
     /// Run token efficiency benchmark
     Benchmark(BenchmarkArgs),
 
+    /// Print a shell completion script (`source <(semfora-engine completions bash)`)
+    Completions(CompletionsArgs),
+
     /// Start the MCP server (for AI coding assistants)
     Serve(ServeArgs),
 }
@@ -126,6 +163,21 @@ pub struct AnalyzeArgs {
     #[arg(long, value_name = "REF")]
     pub target_ref: Option<String>,
 
+    /// Scope to one workspace package (name or path): diffs keep only its
+    /// files, and directory analysis defaults to its directory
+    #[arg(long, value_name = "NAME")]
+    pub package: Option<String>,
+
+    /// Diff directly against the base ref instead of its merge-base with the target
+    /// (by default only changes unique to the target branch are shown, like a PR diff)
+    #[arg(long)]
+    pub no_merge_base: bool,
+
+    /// In a shallow clone, fetch missing history (up to COMMITS commits, default 200)
+    /// when the base ref or merge-base isn't available
+    #[arg(long, value_name = "COMMITS", num_args = 0..=1, default_missing_value = "200")]
+    pub auto_deepen: Option<usize>,
+
     /// Maximum number of files to show in diff output (pagination)
     #[arg(long, value_name = "N")]
     pub limit: Option<usize>,
@@ -134,7 +186,7 @@ pub struct AnalyzeArgs {
     #[arg(long, value_name = "N")]
     pub offset: Option<usize>,
 
-    /// Maximum directory depth for recursive scan (default: 10)
+    /// Maximum directory depth for recursive scan (default: 10, or `index.max_depth`)
     #[arg(long, default_value = "10")]
     pub max_depth: usize,
 
@@ -170,6 +222,22 @@ pub struct AnalyzeArgs {
     #[arg(long, requires = "shard")]
     pub incremental: bool,
 
+    /// Write each file's TOON summary next to it (`foo.rs` -> `foo.rs.toon`)
+    /// instead of printing one aggregate
+    #[arg(
+        long,
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "shard", "stdin", "blob_ref"]
+    )]
+    pub mirror: bool,
+
+    /// Extension appended to mirrored file names (default: toon)
+    #[arg(long, value_name = "EXT", default_value = "toon", requires = "mirror")]
+    pub mirror_ext: String,
+
+    /// Mirror into this directory instead, preserving the source layout
+    #[arg(long, value_name = "DIR", requires = "mirror")]
+    pub output_dir: Option<PathBuf>,
+
     /// Analyze AI token counts
     #[arg(long, value_enum)]
     pub analyze_tokens: Option<TokenAnalysisMode>,
@@ -178,9 +246,91 @@ pub struct AnalyzeArgs {
     #[arg(long, requires = "analyze_tokens")]
     pub compare_compact: bool,
 
+    /// Token budget for the compact token report; lower-priority sections
+    /// (high-risk symbols, then all symbols, then imports) are dropped to fit
+    #[arg(long, requires = "analyze_tokens")]
+    pub token_budget: Option<usize>,
+
     /// Print the parsed AST (for debugging)
     #[arg(long)]
     pub print_ast: bool,
+
+    /// Analyze a git blob without checking it out: `<ref>:<path>`
+    /// (e.g. `stash@{0}:src/lib.rs`) or a blob SHA. For a ref or blob SHA
+    /// without a path, PATH names the file (and its language).
+    #[arg(
+        long = "ref",
+        value_name = "SPEC",
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling"]
+    )]
+    pub blob_ref: Option<String>,
+
+    /// Report tightly-coupled module pairs from the semantic index
+    /// (use --limit to cap the number of pairs)
+    #[arg(long)]
+    pub coupling: bool,
+
+    /// Score the technical debt recorded in the semantic index (duplicates,
+    /// complexity, coupling, untested code), with the trend across index
+    /// generations
+    #[arg(
+        long,
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling", "blob_ref", "stdin"]
+    )]
+    pub debt_score: bool,
+
+    /// Cluster modules into N topics by the vocabulary of their symbols
+    /// (from the BM25 index)
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling", "blob_ref", "stdin"]
+    )]
+    pub topics: Option<usize>,
+
+    /// List the HTTP routes (Express, Next.js, NestJS, FastAPI) declared
+    /// under PATH, with method, path and handler
+    #[arg(
+        long,
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling", "blob_ref", "stdin", "topics"]
+    )]
+    pub routes: bool,
+
+    /// Semantically diff this file against --file-b, without git (e.g. a
+    /// file and its generated counterpart, or two candidate implementations)
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "file_b",
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling", "blob_ref", "stdin", "topics", "routes"]
+    )]
+    pub file_a: Option<PathBuf>,
+
+    /// The file compared against --file-a
+    #[arg(long, value_name = "FILE", requires = "file_a")]
+    pub file_b: Option<PathBuf>,
+
+    /// Analyze source read from stdin (requires --lang). PATH is the repo
+    /// checked for duplicates; the snippet is never written to the index.
+    #[arg(
+        long,
+        requires = "lang",
+        conflicts_with_all = ["diff", "uncommitted", "commit", "all_commits", "coupling", "blob_ref"]
+    )]
+    pub stdin: bool,
+
+    /// Language of the --stdin source, as an extension or name (e.g. ts, python)
+    #[arg(long, value_name = "LANG", requires = "stdin")]
+    pub lang: Option<String>,
+
+    /// Print only the subtree at this RFC 6901 JSON Pointer
+    /// (e.g. `/symbols/0/name`; requires `--format json`)
+    #[arg(long, visible_alias = "json-pointer", value_name = "POINTER")]
+    pub select: Option<String>,
+
+    /// Write the result to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
 }
 
 // ============================================
@@ -191,7 +341,11 @@ pub struct AnalyzeArgs {
 #[derive(Args, Debug)]
 pub struct SearchArgs {
     /// Search query (searches both symbol names and code semantically)
-    #[arg(value_name = "QUERY")]
+    #[arg(
+        value_name = "QUERY",
+        required_unless_present_any = ["flag", "deprecated", "panics", "sql", "facets"],
+        default_value = ""
+    )]
     pub query: String,
 
     /// Only show exact symbol name matches
@@ -211,18 +365,59 @@ pub struct SearchArgs {
     pub kind: Option<String>,
 
     /// Filter by module name
-    #[arg(long, value_name = "MODULE")]
+    #[arg(long, value_name = "MODULE", add = ArgValueCompleter::new(complete_modules))]
     pub module: Option<String>,
 
+    /// Leave out symbols in this module and its submodules (repeatable)
+    #[arg(long = "exclude-module", value_name = "MODULE")]
+    pub exclude_modules: Vec<String>,
+
     /// Filter by risk level (high, medium, low)
     #[arg(long, value_name = "RISK")]
     pub risk: Option<String>,
 
+    /// Only show results from this file (relative to the repo root, or absolute)
+    #[arg(long, value_name = "PATH")]
+    pub in_file: Option<String>,
+
+    /// Only show results from this workspace package (name or path)
+    #[arg(long, value_name = "NAME")]
+    pub package: Option<String>,
+
+    /// Only show results under paths matching this glob, relative to the repo
+    /// root (repeatable; `**` spans directories, a `!` prefix excludes)
+    #[arg(long = "path-glob", value_name = "GLOB")]
+    pub path_globs: Vec<String>,
+
+    /// List symbols gated by this feature flag (QUERY then filters by name)
+    #[arg(long, value_name = "FLAG")]
+    pub flag: Option<String>,
+
+    /// List symbols marked deprecated (QUERY then filters by name)
+    #[arg(long, conflicts_with = "flag")]
+    pub deprecated: bool,
+
+    /// List symbols that can panic or throw (`.unwrap()`, `panic!`, `throw`,
+    /// `raise`) with each panic point (QUERY then filters by name)
+    #[arg(long, conflicts_with_all = ["flag", "deprecated"])]
+    pub panics: bool,
+
+    /// List symbols with SQL string literals touching this table, with each
+    /// query (QUERY then filters by name)
+    #[arg(long, value_name = "TABLE", conflicts_with_all = ["flag", "deprecated", "panics"])]
+    pub sql: Option<String>,
+
+    /// Filter by symbol facet, e.g. `io_calls_outside_try>0` or `has_empty_catch`
+    /// (repeatable; facets: is_async, awaits_count, io_calls_outside_try,
+    /// has_empty_catch, rethrows, panic_points)
+    #[arg(long = "facet", value_name = "FACET")]
+    pub facets: Vec<FacetFilter>,
+
     /// Include source code snippets in output
     #[arg(long)]
     pub include_source: bool,
 
-    /// Limit number of results (default: 20)
+    /// Limit number of results (default: 20, or `search.limit`)
     #[arg(long, default_value = "20")]
     pub limit: usize,
 
@@ -245,6 +440,22 @@ pub struct SearchArgs {
     /// Include local variables that escape their scope
     #[arg(long)]
     pub include_escape_refs: bool,
+
+    /// Never fall back to a plain-text search when the index finds nothing
+    #[arg(long)]
+    pub no_fallback: bool,
+
+    /// Fall back to a plain-text search below this many indexed hits
+    #[arg(long, value_name = "N", default_value = "1")]
+    pub fallback_min: usize,
+
+    /// Maximum plain-text matches a fallback adds
+    #[arg(long, value_name = "N", default_value = "10")]
+    pub fallback_limit: usize,
+
+    /// Write the result to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
 }
 
 // ============================================
@@ -334,11 +545,26 @@ pub enum QueryType {
         /// Include git context (branch, last commit) in output (default: true)
         #[arg(long, default_value = "true")]
         include_git_context: bool,
+
+        /// Trim the overview to fit this many tokens (keeps entry-point and
+        /// high-risk modules longest)
+        #[arg(long)]
+        max_tokens: Option<usize>,
+
+        /// Append the largest, most-called and most-changed code (see `query hotspots`)
+        #[arg(long)]
+        hotspots: bool,
+
+        /// Tally the risk breakdown over symbols of at least this many lines
+        /// instead of files, so tiny functions don't skew it
+        #[arg(long, value_name = "N")]
+        min_lines: Option<usize>,
     },
 
     /// Get a specific module's details
     Module {
         /// Module name
+        #[arg(add = ArgValueCompleter::new(complete_modules))]
         name: String,
 
         /// Show only symbols (not full details)
@@ -447,13 +673,25 @@ pub enum QueryType {
         path: Option<PathBuf>,
 
         /// Filter to a specific module
-        #[arg(long)]
+        #[arg(long, add = ArgValueCompleter::new(complete_modules))]
         module: Option<String>,
 
+        /// Leave out callers and callees in this module and its submodules (repeatable)
+        #[arg(
+            long = "exclude-module",
+            value_name = "MODULE",
+            add = ArgValueCompleter::new(complete_modules)
+        )]
+        exclude_modules: Vec<String>,
+
         /// Filter to edges involving this symbol (name or hash)
         #[arg(long)]
         symbol: Option<String>,
 
+        /// Filter to edges whose caller is in this workspace package (name or path)
+        #[arg(long, value_name = "NAME")]
+        package: Option<String>,
+
         /// Export to SQLite file
         #[arg(long, value_name = "PATH")]
         export: Option<String>,
@@ -509,6 +747,160 @@ pub enum QueryType {
         include_escape_refs: bool,
     },
 
+    /// Show which application symbols consume a protobuf/OpenAPI contract element
+    ContractUsages {
+        /// Message, service, rpc or schema name, OpenAPI operationId, or path (e.g. /users/{id})
+        target: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum consumers to show per contract element
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Show which modules import a module (or, with --unused, modules nothing imports)
+    ModuleUsages {
+        /// Module name (e.g. legacy.payments) or its directory (src/legacy/payments)
+        #[arg(
+            required_unless_present = "unused",
+            add = ArgValueCompleter::new(complete_modules)
+        )]
+        module: Option<String>,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// List modules with no inbound imports and no entry points instead
+        #[arg(long, conflicts_with = "module")]
+        unused: bool,
+
+        /// With --unused, also list modules made up of generated code
+        #[arg(long, requires = "unused")]
+        include_generated: bool,
+
+        /// Maximum modules to show
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Show the public API of a module: exported functions, constants and types
+    ApiSurface {
+        /// Module name (from `query overview`)
+        #[arg(add = ArgValueCompleter::new(complete_modules))]
+        module: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// List exported types and their members too
+        #[arg(long)]
+        include_types: bool,
+    },
+
+    /// List what a file imports
+    Imports {
+        /// File path (e.g. src/auth.ts)
+        #[arg(long)]
+        file: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// List the symbols a module exports
+    Exports {
+        /// Module name (e.g. auth or src.auth) or its directory
+        #[arg(long, add = ArgValueCompleter::new(complete_modules))]
+        module: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Show the React render tree under a component, or the components calling a hook
+    Components {
+        /// Component at the root of the render tree (name or symbol hash)
+        #[arg(required_unless_present = "hook")]
+        root: Option<String>,
+
+        /// List components calling this hook instead: `useTheme` or `useContext(ThemeContext)`
+        #[arg(long, conflicts_with = "root")]
+        hook: Option<String>,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum render depth below the root
+        #[arg(long, default_value = "5")]
+        depth: usize,
+    },
+
+    /// Show the tests covering a symbol or file, or what a test symbol/file exercises
+    TestsFor {
+        /// Symbol hash, production file, or test file
+        target: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum entries to show per list
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Show the commits that changed a symbol, following renames
+    SymbolHistory {
+        /// Symbol hash
+        hash: String,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum commits to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show the largest, most-called and most-changed code
+    Hotspots {
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum entries to show per ranking
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Rank generated code too (default: off)
+        #[arg(long)]
+        include_generated: bool,
+    },
+
+    /// Summarize CODEOWNERS coverage: files and symbols per owner, and the
+    /// files no rule owns
+    Owners {
+        /// Module name, file or directory (defaults to the whole repository)
+        target: Option<String>,
+
+        /// Path to repository (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Maximum unowned files to list
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
     /// List supported languages
     Languages,
 }
@@ -542,9 +934,17 @@ pub struct ValidateArgs {
     pub line: Option<usize>,
 
     /// Module name for module-level validation
-    #[arg(long)]
+    #[arg(long, add = ArgValueCompleter::new(complete_modules))]
     pub module: Option<String>,
 
+    /// Leave out symbols in this module and its submodules (repeatable)
+    #[arg(long = "exclude-module", value_name = "MODULE")]
+    pub exclude_modules: Vec<String>,
+
+    /// Only report duplicate clusters involving this workspace package (name or path)
+    #[arg(long, value_name = "NAME")]
+    pub package: Option<String>,
+
     /// Include source snippet in output
     #[arg(long)]
     pub include_source: bool,
@@ -553,14 +953,29 @@ pub struct ValidateArgs {
     #[arg(long)]
     pub duplicates: bool,
 
-    /// Similarity threshold for duplicate detection (default: 0.90)
+    /// List pairs of files that import each other (JavaScript/TypeScript)
+    #[arg(long)]
+    pub cycles: bool,
+
+    /// Similarity threshold for duplicate detection (default: 0.90, or `duplicates.threshold`)
     #[arg(long, default_value = "0.90")]
     pub threshold: f64,
 
-    /// Include boilerplate functions in duplicate detection
+    /// Include boilerplate functions in duplicate detection (default: off,
+    /// or `duplicates.include_boilerplate`)
     #[arg(long)]
     pub include_boilerplate: bool,
 
+    /// Include generated code (protobuf output, codegen clients) in
+    /// validation and duplicate detection (default: off)
+    #[arg(long)]
+    pub include_generated: bool,
+
+    /// Strip layout from call names in signatures loaded from an index built
+    /// before duplicate detection ignored whitespace (saves a reindex)
+    #[arg(long)]
+    pub normalize_whitespace: bool,
+
     /// Filter by symbol kind
     #[arg(long)]
     pub kind: Option<String>,
@@ -581,9 +996,26 @@ pub struct ValidateArgs {
     #[arg(long, default_value = "3")]
     pub min_lines: usize,
 
-    /// Sort clusters by: similarity (default), size, or count
+    /// Sort clusters by: similarity (default), size, count, or savings
     #[arg(long, default_value = "similarity")]
     pub sort_by: String,
+
+    /// Report duplicates as consolidation opportunities ranked by estimated savings
+    #[arg(long)]
+    pub report: bool,
+
+    /// Exit with code 2 when a rule trips (repeatable), e.g.
+    /// `complexity:cyclomatic>15`, `impact:callers>50`, `duplicates:new`
+    #[arg(long = "fail-on", value_name = "RULE")]
+    pub fail_on: Vec<FailOnRule>,
+
+    /// Base ref for `duplicates:new` (defaults to the detected base branch)
+    #[arg(long, value_name = "REF")]
+    pub base: Option<String>,
+
+    /// Write the result to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
 }
 
 // ============================================
@@ -615,13 +1047,30 @@ pub enum IndexOperation {
         #[arg(long)]
         incremental: bool,
 
-        /// Maximum directory depth
+        /// Maximum directory depth (default: 10, or `index.max_depth`)
         #[arg(long, default_value = "10")]
         max_depth: usize,
 
-        /// Filter by file extension
+        /// Filter by file extension (default: `index.extensions`, else all)
         #[arg(long = "ext")]
         extensions: Vec<String>,
+
+        /// Extraction detail: `signatures` indexes names/kinds/signatures only
+        /// (fast first pass); a later `full` run enriches it
+        #[arg(long, value_enum, default_value = "full")]
+        level: ExtractionLevel,
+
+        /// How module shards are named: `module-path` (shortest unique dotted
+        /// path), `content-hash` (hash of the full path) or `hybrid` (path
+        /// prefix plus hash, collision resistant). Defaults to the existing
+        /// index's strategy, else `module-path`
+        #[arg(long, value_enum)]
+        shard_naming: Option<ShardNamingStrategy>,
+
+        /// Attribute each symbol to the git author of most of its lines (one
+        /// `git blame` per file, cached by file content between runs)
+        #[arg(long)]
+        with_blame: bool,
     },
 
     /// Check if the index is fresh or stale
@@ -641,6 +1090,14 @@ pub enum IndexOperation {
         #[arg(value_name = "PATH")]
         path: Option<String>,
     },
+
+    /// Fold committed overlay layers into the branch/base layer and drop
+    /// stale tombstones (requires a clean working tree)
+    Compact {
+        /// Repository path (default: current directory)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
 }
 
 // ============================================
@@ -661,6 +1118,13 @@ pub enum CacheOperation {
     /// Show cache information
     Info,
 
+    /// List every cached repository with its index age, size and symbol count
+    List {
+        /// Sort order: largest, oldest or most symbols first
+        #[arg(long, value_enum, default_value = "size")]
+        sort_by: CacheSortKey,
+    },
+
     /// Clear the cache for the current directory
     Clear,
 
@@ -669,6 +1133,80 @@ pub enum CacheOperation {
         /// Number of days
         days: u32,
     },
+
+    /// Upgrade the index to the current schema version
+    Migrate(MigrateArgs),
+
+    /// Check every cached artifact and report missing, corrupt or
+    /// schema-incompatible files
+    Verify(CacheVerifyArgs),
+
+    /// Rebuild only the cached artifacts that fail verification
+    Repair(CacheRepairArgs),
+
+    /// Compare two index generations by their manifests: files and symbols
+    /// added, removed or changed
+    Diff(CacheDiffArgs),
+}
+
+/// Sort keys for `cache list`
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum CacheSortKey {
+    /// Largest cache first (default)
+    #[default]
+    Size,
+    /// Oldest index first
+    Age,
+    /// Most symbols first
+    Symbols,
+}
+
+/// Arguments for `cache migrate`
+#[derive(Args, Debug)]
+pub struct MigrateArgs {
+    /// Repository path (defaults to current directory)
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Don't regenerate the index when a migration had to discard it
+    /// (the next query regenerates it instead)
+    #[arg(long)]
+    pub no_rebuild: bool,
+}
+
+/// Arguments for `cache verify`
+#[derive(Args, Debug)]
+pub struct CacheVerifyArgs {
+    /// Repository path (defaults to current directory)
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+}
+
+/// Arguments for `cache repair`
+#[derive(Args, Debug)]
+pub struct CacheRepairArgs {
+    /// Repository path (defaults to current directory)
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+}
+
+/// Arguments for `cache diff`
+#[derive(Args, Debug)]
+pub struct CacheDiffArgs {
+    /// Older manifest (from `manifests/` in a cache directory)
+    #[arg(required_unless_present = "previous", conflicts_with = "previous")]
+    pub manifest_a: Option<PathBuf>,
+
+    /// Newer manifest (defaults to the current index's manifest)
+    pub manifest_b: Option<PathBuf>,
+
+    /// Compare the current index with the generation before it
+    #[arg(long)]
+    pub previous: bool,
+
+    /// Repository path (defaults to current directory)
+    #[arg(long)]
+    pub path: Option<PathBuf>,
 }
 
 // ============================================
@@ -682,6 +1220,10 @@ pub struct SecurityArgs {
     /// Security operation to perform
     #[command(subcommand)]
     pub operation: SecurityOperation,
+
+    /// Write the result to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH", global = true)]
+    pub output: Option<PathBuf>,
 }
 
 /// Security operations (internal use only)
@@ -706,11 +1248,16 @@ pub enum SecurityOperation {
         url: Option<String>,
         #[arg(long)]
         file: Option<PathBuf>,
+        /// Expected SHA-256 of the database (default: fetched from <url>.sha256)
+        #[arg(long)]
+        checksum: Option<String>,
         #[arg(long)]
         force: bool,
     },
     /// Show security pattern statistics
     Stats,
+    /// Restore the pattern database replaced by the last update
+    Rollback,
 }
 
 // ============================================
@@ -740,6 +1287,16 @@ pub struct TestArgs {
     #[arg(long, default_value = "300")]
     pub timeout: u64,
 
+    /// Only run tests that call code changed since REF (default: HEAD), using the call graph
+    #[arg(
+        long,
+        value_name = "REF",
+        num_args = 0..=1,
+        default_missing_value = "HEAD",
+        conflicts_with = "filter"
+    )]
+    pub changed: Option<String>,
+
     /// Path to project directory
     #[arg(long)]
     pub path: Option<PathBuf>,
@@ -751,9 +1308,15 @@ pub struct TestArgs {
 
 /// Arguments for the lint command
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct LintArgs {
+    /// Operation to run (`scan` when omitted)
     #[command(subcommand)]
-    pub operation: LintOperation,
+    pub operation: Option<LintOperation>,
+
+    /// Apply automatic fixes in the current directory (shorthand for `lint fix`)
+    #[arg(long)]
+    pub fix: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -783,6 +1346,10 @@ pub enum LintOperation {
         /// Only show fixable issues
         #[arg(long)]
         fixable_only: bool,
+
+        /// Only detect and run linters for these languages (e.g. `rust,go`)
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<Lang>,
     },
 
     /// Apply automatic fixes
@@ -802,6 +1369,10 @@ pub enum LintOperation {
         /// Only apply safe fixes
         #[arg(long)]
         safe_only: bool,
+
+        /// Only detect and run linters for these languages (e.g. `rust,go`)
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<Lang>,
     },
 
     /// Run type checkers only
@@ -824,6 +1395,10 @@ pub enum LintOperation {
         /// Path to project directory
         #[arg(value_name = "PATH")]
         path: Option<PathBuf>,
+
+        /// Only detect linters for these languages (e.g. `rust,go`)
+        #[arg(long = "lang", value_name = "LANG", value_delimiter = ',')]
+        languages: Vec<Lang>,
     },
 
     /// Get recommendations for missing linters
@@ -950,8 +1525,8 @@ pub struct UninstallArgs {
     #[arg(long)]
     pub keep_cache: bool,
 
-    /// Skip confirmation prompts
-    #[arg(long, short)]
+    /// Skip confirmation prompts (no `-f`: that's the global `--format`)
+    #[arg(long)]
     pub force: bool,
 }
 
@@ -971,7 +1546,16 @@ pub struct ConfigArgs {
 #[derive(Subcommand, Debug)]
 pub enum ConfigOperation {
     /// Show current configuration
-    Show,
+    Show {
+        /// Show the analysis defaults in effect for a repository, merged from
+        /// the global and repo config files, with the source of each value
+        #[arg(long)]
+        effective: bool,
+
+        /// Repository to show effective defaults for (default: current directory)
+        #[arg(value_name = "PATH", requires = "effective")]
+        path: Option<PathBuf>,
+    },
 
     /// Set a configuration value
     Set {
@@ -995,6 +1579,45 @@ pub struct BenchmarkArgs {
     /// Path to directory to benchmark
     #[arg(value_name = "PATH")]
     pub path: Option<PathBuf>,
+
+    /// Save the metrics as JSON to this file, for a later `--compare`
+    #[arg(long, value_name = "FILE")]
+    pub save: Option<PathBuf>,
+
+    /// Compare against metrics saved with `--save`; exits with code 2 when
+    /// compression or token savings regressed more than `--max-regression`
+    #[arg(long, value_name = "FILE")]
+    pub compare: Option<PathBuf>,
+
+    /// Largest tolerated drop in compression or token savings, in percent
+    /// of the baseline value
+    #[arg(long, value_name = "PCT", default_value = "5.0", requires = "compare")]
+    pub max_regression: f64,
+
+    /// Write the result to this file (atomically) instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+// ============================================
+// Completions Subcommand
+// ============================================
+
+/// Arguments for the completions command
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to print the completion script for
+    #[arg(value_enum)]
+    pub shell: CompletionShell,
+}
+
+/// Shells `completions` can print a script for
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
 }
 
 // ============================================
@@ -1015,6 +1638,15 @@ pub struct ServeArgs {
     /// Disable git polling for branch/commit changes
     #[arg(long)]
     pub no_git_poll: bool,
+
+    /// Seconds between checks for committed layers to compact (0 disables)
+    #[arg(long, value_name = "SECS", default_value = "300")]
+    pub compact_interval: u64,
+
+    /// Milliseconds of quiet before file changes are applied as one batch
+    /// (a branch switch touching many files triggers a single update)
+    #[arg(long, value_name = "MS", default_value = "100")]
+    pub watch_debounce_ms: u64,
 }
 
 // ============================================
@@ -1041,6 +1673,31 @@ pub enum OutputFormat {
     Toon,
     /// JSON - standard JSON output for machine parsing
     Json,
+    /// CSV - one row per duplicate match (`validate --duplicates` only)
+    Csv,
+    /// Review bundle - summary and inline findings for review bots
+    /// (`analyze --diff` only)
+    ReviewJson,
+}
+
+/// Progress report options
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum ProgressFormat {
+    /// A single status line redrawn in place (default)
+    #[default]
+    Text,
+    /// Newline-delimited JSON events: phase, current, total, percent
+    Json,
+}
+
+/// File path style options
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum PathStyle {
+    /// Relative to the repository root, with `/` separators (default)
+    #[default]
+    Relative,
+    /// Absolute paths on this machine
+    Absolute,
 }
 
 // ============================================
@@ -1050,7 +1707,175 @@ pub enum OutputFormat {
 impl Cli {
     /// Parse CLI arguments from command line
     pub fn parse_args() -> Self {
-        Self::parse()
+        let matches = Self::command().subcommand_required(false).get_matches();
+        if matches.get_flag("json_help") {
+            print!("{}", crate::cli_schema::json_help());
+            std::process::exit(0);
+        }
+        if matches.subcommand().is_none() {
+            // Let clap report the missing subcommand as it usually does
+            Self::command().get_matches();
+        }
+        let mut cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        let csv_supported = matches!(&cli.command, Commands::Validate(args) if args.duplicates);
+        if cli.format == OutputFormat::Csv && !csv_supported {
+            Self::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "`--format csv` is only supported by `validate --duplicates`",
+                )
+                .exit();
+        }
+        let review_supported = match &cli.command {
+            Commands::Analyze(args) => args.diff.is_some() && !args.all_commits,
+            _ => false,
+        };
+        if cli.format == OutputFormat::ReviewJson && !review_supported {
+            Self::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "`--format review-json` is only supported by `analyze --diff`",
+                )
+                .exit();
+        }
+
+        // Flags left at their defaults take the repository's configured ones
+        if let Some(path) = cli.command.settings_path() {
+            let settings = RepoSettings::load(&path).unwrap_or_else(|e| {
+                Self::command()
+                    .error(ErrorKind::InvalidValue, e.to_string())
+                    .exit()
+            });
+            if let Some((_, sub_matches)) = matches.subcommand() {
+                cli.command.apply_settings(&settings, sub_matches);
+            }
+            cli.settings = Some(Arc::new(settings));
+        }
+        cli
+    }
+
+    /// Whether progress should be shown, reconciling the progress flags
+    pub fn show_progress(&self) -> bool {
+        !self.no_progress && (self.progress || self.progress_format == ProgressFormat::Json)
+    }
+}
+
+impl PathStyle {
+    /// Render `file` for output, given the repository root it belongs to
+    ///
+    /// `file` may be relative to `repo_root` (with or without a leading
+    /// `./`) or absolute. Relative output uses `/` separators on every
+    /// platform so the same checkout renders identically on any machine;
+    /// files outside `repo_root` keep their absolute path.
+    pub fn render(self, file: &str, repo_root: &Path) -> String {
+        let path = Path::new(file);
+        let path = path.strip_prefix(".").unwrap_or(path);
+        match self {
+            PathStyle::Absolute => {
+                if path.is_absolute() {
+                    path.display().to_string()
+                } else {
+                    repo_root.join(path).display().to_string()
+                }
+            }
+            PathStyle::Relative => {
+                if !path.is_absolute() {
+                    return to_slash(path);
+                }
+                if let Ok(relative) = path.strip_prefix(repo_root) {
+                    return to_slash(relative);
+                }
+                // The root or the file may have been reached through a symlink
+                let canonical_root = repo_root.canonicalize();
+                let canonical_file = path.canonicalize();
+                match (canonical_root, canonical_file) {
+                    (Ok(root), Ok(file)) => file
+                        .strip_prefix(&root)
+                        .map(to_slash)
+                        .unwrap_or_else(|_| path.display().to_string()),
+                    _ => path.display().to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Join the components of a relative path with `/` (`.` for the root itself)
+fn to_slash(path: &Path) -> String {
+    let joined = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if joined.is_empty() {
+        ".".to_string()
+    } else {
+        joined
+    }
+}
+
+impl Commands {
+    /// Path whose config files supply this command's defaults, for commands
+    /// whose flags take them
+    fn settings_path(&self) -> Option<PathBuf> {
+        let path = match self {
+            Commands::Analyze(args) => args.path.clone(),
+            Commands::Validate(args) => args.path.clone(),
+            Commands::Search(_) => None,
+            Commands::Index(IndexArgs {
+                operation: IndexOperation::Generate { path, .. },
+            }) => path.clone(),
+            _ => return None,
+        };
+        path.or_else(|| std::env::current_dir().ok())
+    }
+
+    /// Replace the defaults of flags not given on the command line with the
+    /// configured ones
+    fn apply_settings(&mut self, settings: &RepoSettings, matches: &ArgMatches) {
+        let defaulted = |matches: &ArgMatches, id: &str| {
+            matches.value_source(id) != Some(ValueSource::CommandLine)
+        };
+        match self {
+            Commands::Analyze(args) => {
+                if defaulted(matches, "max_depth") {
+                    args.max_depth = settings.max_depth;
+                }
+                if defaulted(matches, "extensions") {
+                    args.extensions = settings.extensions.clone();
+                }
+            }
+            Commands::Search(args) if defaulted(matches, "limit") => {
+                args.limit = settings.search_limit;
+            }
+            Commands::Validate(args) => {
+                if defaulted(matches, "threshold") {
+                    args.threshold = settings.duplicate_threshold;
+                }
+                if defaulted(matches, "include_boilerplate") {
+                    args.include_boilerplate = settings.include_boilerplate;
+                }
+            }
+            Commands::Index(IndexArgs {
+                operation:
+                    IndexOperation::Generate {
+                        max_depth,
+                        extensions,
+                        ..
+                    },
+            }) => {
+                let Some((_, matches)) = matches.subcommand() else {
+                    return;
+                };
+                if defaulted(matches, "max_depth") {
+                    *max_depth = settings.max_depth;
+                }
+                if defaulted(matches, "extensions") {
+                    *extensions = settings.extensions.clone();
+                }
+            }
+            _ => {}
+        }
     }
 }
 
@@ -1072,7 +1897,17 @@ impl AnalyzeArgs {
 impl SearchArgs {
     /// Determine the search mode based on flags
     pub fn search_mode(&self) -> SearchMode {
-        if self.raw {
+        if self.flag.is_some() {
+            SearchMode::Flag
+        } else if self.deprecated {
+            SearchMode::Deprecated
+        } else if self.panics {
+            SearchMode::Panics
+        } else if self.sql.is_some() {
+            SearchMode::Sql
+        } else if !self.facets.is_empty() {
+            SearchMode::Facet
+        } else if self.raw {
             SearchMode::Raw
         } else if self.symbols && !self.related {
             SearchMode::SymbolsOnly
@@ -1098,7 +1933,16 @@ impl SearchArgs {
             raw: false,
             kind,
             module,
+            exclude_modules: Vec::new(),
             risk,
+            in_file: None,
+            package: None,
+            path_globs: Vec::new(),
+            flag: None,
+            deprecated: false,
+            panics: false,
+            sql: None,
+            facets: Vec::new(),
             include_source: false,
             limit,
             file_types: None,
@@ -1106,6 +1950,10 @@ impl SearchArgs {
             merge_threshold: 3,
             symbol_scope: SymbolScope::Functions,
             include_escape_refs: false,
+            no_fallback: false,
+            fallback_min: 1,
+            fallback_limit: 10,
+            output: None,
         }
     }
 
@@ -1124,7 +1972,16 @@ impl SearchArgs {
             raw: false,
             kind,
             module,
+            exclude_modules: Vec::new(),
             risk: None,
+            in_file: None,
+            package: None,
+            path_globs: Vec::new(),
+            flag: None,
+            deprecated: false,
+            panics: false,
+            sql: None,
+            facets: Vec::new(),
             include_source,
             limit,
             file_types: None,
@@ -1132,6 +1989,10 @@ impl SearchArgs {
             merge_threshold: 3,
             symbol_scope: SymbolScope::Functions,
             include_escape_refs: false,
+            no_fallback: false,
+            fallback_min: 1,
+            fallback_limit: 10,
+            output: None,
         }
     }
 
@@ -1150,7 +2011,16 @@ impl SearchArgs {
             raw: true,
             kind: None,
             module: None,
+            exclude_modules: Vec::new(),
             risk: None,
+            in_file: None,
+            package: None,
+            path_globs: Vec::new(),
+            flag: None,
+            deprecated: false,
+            panics: false,
+            sql: None,
+            facets: Vec::new(),
             include_source: false,
             limit,
             file_types,
@@ -1158,6 +2028,10 @@ impl SearchArgs {
             merge_threshold,
             symbol_scope: SymbolScope::Functions,
             include_escape_refs: false,
+            no_fallback: false,
+            fallback_min: 1,
+            fallback_limit: 10,
+            output: None,
         }
     }
 
@@ -1176,7 +2050,16 @@ impl SearchArgs {
             raw: false,
             kind,
             module,
+            exclude_modules: Vec::new(),
             risk,
+            in_file: None,
+            package: None,
+            path_globs: Vec::new(),
+            flag: None,
+            deprecated: false,
+            panics: false,
+            sql: None,
+            facets: Vec::new(),
             include_source: true,
             limit,
             file_types: None,
@@ -1184,6 +2067,10 @@ impl SearchArgs {
             merge_threshold: 3,
             symbol_scope: SymbolScope::Functions,
             include_escape_refs: false,
+            no_fallback: false,
+            fallback_min: 1,
+            fallback_limit: 10,
+            output: None,
         }
     }
 }
@@ -1239,6 +2126,16 @@ pub enum SearchMode {
     SemanticOnly,
     /// Raw regex search
     Raw,
+    /// Symbols gated by a feature flag
+    Flag,
+    /// Symbols marked deprecated
+    Deprecated,
+    /// Symbols that can panic or throw
+    Panics,
+    /// Symbols with embedded SQL touching a table
+    Sql,
+    /// Symbols matching facet filters
+    Facet,
 }
 
 // ============================================
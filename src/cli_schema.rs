@@ -0,0 +1,229 @@
+//! Machine-readable CLI schema (`--json-help`)
+//!
+//! Describes every command and argument of [`Cli`] as JSON, for wrappers and
+//! editor integrations that would otherwise hand-maintain the argument list.
+//! It's generated from the clap definition itself, so it can't drift from
+//! what the binary accepts.
+//!
+//! Commands and arguments are listed in definition order and every object
+//! has the same keys in the same order (absent values are `null` or empty),
+//! so the output only changes when the CLI does. Hidden commands and
+//! arguments are left out.
+
+use std::any::TypeId;
+use std::path::PathBuf;
+
+use clap::builder::ValueHint;
+use clap::{Arg, ArgAction, Command, CommandFactory};
+use serde_json::{json, Value};
+
+use crate::cli::Cli;
+
+/// Version of the schema's own layout, bumped when keys change
+pub const CLI_SCHEMA_VERSION: u32 = 1;
+
+/// The `--json-help` output: the whole CLI as pretty-printed JSON
+pub fn json_help() -> String {
+    let mut value = command_schema(&Cli::command());
+    value["schema_version"] = json!(CLI_SCHEMA_VERSION);
+    value["version"] = json!(env!("CARGO_PKG_VERSION"));
+    serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+}
+
+/// A command, its arguments and its subcommands
+pub fn command_schema(command: &Command) -> Value {
+    json!({
+        "name": command.get_name(),
+        "about": command.get_about().map(|about| about.to_string()),
+        "aliases": command.get_visible_aliases().collect::<Vec<_>>(),
+        "args": command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .map(arg_schema)
+            .collect::<Vec<_>>(),
+        "subcommands": command
+            .get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+            .map(command_schema)
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// One argument: how it's spelled, what it takes and what it defaults to
+fn arg_schema(arg: &Arg) -> Value {
+    let takes_value = arg.get_action().takes_values();
+    json!({
+        "name": arg.get_id().as_str(),
+        "long": arg.get_long(),
+        "short": arg.get_short().map(String::from),
+        "positional": arg.is_positional(),
+        "type": value_type(arg),
+        "value_name": takes_value
+            .then(|| arg.get_value_names())
+            .flatten()
+            .map(|names| names.iter().map(|n| n.as_str()).collect::<Vec<_>>().join(" ")),
+        "required": arg.is_required_set(),
+        "multiple": matches!(arg.get_action(), ArgAction::Append | ArgAction::Count)
+            || arg.get_num_args().is_some_and(|n| n.max_values() > 1),
+        "default": arg
+            .get_default_values()
+            .iter()
+            .map(|v| v.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        "possible_values": arg
+            .get_possible_values()
+            .iter()
+            .filter(|v| takes_value && !v.is_hide_set())
+            .map(|v| v.get_name())
+            .collect::<Vec<_>>(),
+        "value_hint": takes_value.then(|| value_hint(arg.get_value_hint())).flatten(),
+        "env": arg.get_env().map(|env| env.to_string_lossy().into_owned()),
+        "global": arg.is_global_set(),
+        "help": arg.get_help().map(|help| help.to_string()),
+    })
+}
+
+/// JSON-ish type of the values an argument takes
+fn value_type(arg: &Arg) -> &'static str {
+    match arg.get_action() {
+        ArgAction::SetTrue | ArgAction::SetFalse => return "flag",
+        ArgAction::Count => return "count",
+        action if !action.takes_values() => return "flag",
+        _ => {}
+    }
+    if !arg.get_possible_values().is_empty() {
+        return "enum";
+    }
+    let parser = arg.get_value_parser().type_id();
+    if parser == TypeId::of::<PathBuf>() {
+        "path"
+    } else if parser == TypeId::of::<bool>() {
+        "boolean"
+    } else if [
+        TypeId::of::<usize>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<i32>(),
+    ]
+    .into_iter()
+    .any(|id| parser == id)
+    {
+        "integer"
+    } else if parser == TypeId::of::<f64>() || parser == TypeId::of::<f32>() {
+        "number"
+    } else {
+        // Strings, and custom parsers (`FacetFilter`, `FailOnRule`, ...)
+        // that read one
+        "string"
+    }
+}
+
+/// Snake-case name of a value hint, `None` when there isn't one
+fn value_hint(hint: ValueHint) -> Option<&'static str> {
+    Some(match hint {
+        ValueHint::AnyPath => "any_path",
+        ValueHint::FilePath => "file_path",
+        ValueHint::DirPath => "dir_path",
+        ValueHint::ExecutablePath => "executable_path",
+        ValueHint::CommandName => "command_name",
+        ValueHint::CommandString => "command_string",
+        ValueHint::CommandWithArguments => "command_with_arguments",
+        ValueHint::Username => "username",
+        ValueHint::Hostname => "hostname",
+        ValueHint::Url => "url",
+        ValueHint::EmailAddress => "email_address",
+        ValueHint::Other => "other",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Commands;
+    use clap::Subcommand;
+
+    fn find<'a>(items: &'a Value, name: &str) -> Option<&'a Value> {
+        items.as_array()?.iter().find(|item| item["name"] == name)
+    }
+
+    /// Every visible command and argument is in the schema with its
+    /// description and type, so a new one can't slip in without them
+    fn check_command(command: &Command, schema: &Value, path: &str) {
+        assert!(schema["about"].is_string(), "`{}` has no description", path);
+        for arg in command.get_arguments().filter(|a| !a.is_hide_set()) {
+            let id = arg.get_id().as_str();
+            let entry = find(&schema["args"], id)
+                .unwrap_or_else(|| panic!("`{} {}` missing from the schema", path, id));
+            assert!(entry["help"].is_string(), "`{} {}` has no help", path, id);
+            assert!(entry["type"].is_string(), "`{} {}` has no type", path, id);
+        }
+        for sub in command.get_subcommands().filter(|s| !s.is_hide_set()) {
+            let name = sub.get_name();
+            let entry = find(&schema["subcommands"], name)
+                .unwrap_or_else(|| panic!("`{} {}` missing from the schema", path, name));
+            check_command(sub, entry, &format!("{} {}", path, name));
+        }
+    }
+
+    #[test]
+    fn test_cli_definition_is_consistent() {
+        // Completions build the whole command tree, which panics on
+        // conflicting definitions (e.g. a short flag taken twice)
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_schema_covers_every_command_and_arg() {
+        let schema: Value = serde_json::from_str(&json_help()).unwrap();
+        assert_eq!(schema["schema_version"], CLI_SCHEMA_VERSION);
+
+        let variants = Commands::augment_subcommands(Command::new("commands"));
+        for variant in variants.get_subcommands().filter(|s| !s.is_hide_set()) {
+            assert!(
+                find(&schema["subcommands"], variant.get_name()).is_some(),
+                "`{}` missing from the schema",
+                variant.get_name()
+            );
+        }
+        check_command(&Cli::command(), &schema, "semfora");
+    }
+
+    #[test]
+    fn test_schema_describes_args() {
+        let schema: Value = serde_json::from_str(&json_help()).unwrap();
+        let format = find(&schema["args"], "format").unwrap();
+        assert_eq!(format["type"], "enum");
+        assert_eq!(format["default"], json!(["text"]));
+        assert_eq!(format["global"], true);
+        assert!(format["possible_values"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("json")));
+
+        let query = find(&schema["subcommands"], "query").unwrap();
+        assert_eq!(query["aliases"], json!(["q"]));
+        let callers = find(&query["subcommands"], "callers").unwrap();
+        let hash = find(&callers["args"], "hash").unwrap();
+        assert_eq!(hash["positional"], true);
+        assert_eq!(hash["required"], true);
+        assert_eq!(hash["type"], "string");
+        let depth = find(&callers["args"], "depth").unwrap();
+        assert_eq!(depth["type"], "integer");
+        assert_eq!(depth["long"], "depth");
+        assert_eq!(depth["default"], json!(["1"]));
+        let path = find(&callers["args"], "path").unwrap();
+        assert_eq!(path["type"], "path");
+        assert_eq!(path["value_hint"], "any_path");
+        let source = find(&callers["args"], "source").unwrap();
+        assert_eq!(source["type"], "flag");
+        assert_eq!(source["value_name"], Value::Null);
+        assert_eq!(source["possible_values"], json!([]));
+
+        // Stable: generating it again gives the same bytes
+        assert_eq!(json_help(), json_help());
+    }
+}
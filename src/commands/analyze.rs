@@ -8,25 +8,74 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rayon::prelude::*;
 
+use crate::analysis::{
+    add_duplicate_debt, analyze_coupling, analyze_repo, calculate_cognitive_complexity,
+    max_nesting_depth, CouplingKind, REMEDIATION_HOURS_PER_LOC,
+};
+use crate::cache::load_function_signatures;
 use crate::cli::{AnalyzeArgs, OutputFormat, TokenAnalysisMode};
+use crate::contracts::diff_breaking_changes;
+use crate::diff_warnings::diff_warnings;
 use crate::error::{McpDiffError, Result};
 use crate::git::{
-    detect_base_branch, get_changed_files, get_commit_changed_files, get_commits_since,
-    get_file_at_ref, get_merge_base, get_repo_root, get_staged_changes, get_uncommitted_changes,
-    get_unstaged_changes, ChangeType, ChangedFile,
+    detect_base_branch, ensure_ref, get_blob_text, get_changed_files, get_commit_changed_files,
+    get_commits_since, get_file_at_ref, get_repo_root, get_staged_changes, get_unstaged_changes,
+    get_untracked_changes, get_working_tree_changes, git_command_optional, merge_base_with_deepen,
+    ChangeType, ChangedFile,
+};
+use crate::mcp_server::formatting::{
+    format_diff_output_paginated, format_diff_summary, format_file_diff, format_surface_delta,
 };
-use crate::mcp_server::formatting::{format_diff_output_paginated, format_diff_summary};
+use crate::module_usage::mark_cyclic_imports;
 use crate::parsing::{parse_and_extract, parse_and_extract_with_options};
+use crate::review::review_bundle;
+use crate::schema::{RouteInfo, SemanticDiff, SymbolKind};
+use crate::security::patterns::embedded::load_embedded_patterns;
 use crate::tokens::{format_analysis_compact, format_analysis_report, TokenAnalyzer};
+use crate::workspace::resolve_package;
 use crate::{
-    encode_toon, encode_toon_directory, fs_utils, generate_repo_overview, is_test_file, CacheDir,
-    Lang, SemanticSummary, ShardWriter,
+    encode_toon, encode_toon_directory, fs_utils, generate_repo_overview, is_test_file, Bm25Index,
+    CacheDir, DuplicateDetector, FunctionSignature, Lang, SemanticSummary, ShardWriter,
 };
 
-use super::CommandContext;
+use super::{write_atomic, CommandContext};
 
 /// Run the analyze command
 pub fn run_analyze(ctx: &CommandContext, args: &AnalyzeArgs) -> Result<String> {
+    let Some(ref pointer) = args.select else {
+        return run_analysis(ctx, args);
+    };
+    if ctx.format != OutputFormat::Json {
+        return Err(McpDiffError::QueryError {
+            message: "--select requires --format json".to_string(),
+        });
+    }
+    select_json_pointer(&run_analysis(ctx, args)?, pointer)
+}
+
+/// Apply an RFC 6901 JSON Pointer to JSON output, keeping only that subtree
+pub fn select_json_pointer(output: &str, pointer: &str) -> Result<String> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(McpDiffError::QueryError {
+            message: format!(
+                "Invalid JSON pointer `{}`: must be empty or start with `/`",
+                pointer
+            ),
+        });
+    }
+    let value: serde_json::Value =
+        serde_json::from_str(output).map_err(|e| McpDiffError::QueryError {
+            message: format!("--select needs a single JSON document: {}", e),
+        })?;
+    let selected = value
+        .pointer(pointer)
+        .ok_or_else(|| McpDiffError::QueryError {
+            message: format!("JSON pointer `{}` does not resolve in the output", pointer),
+        })?;
+    serde_json::to_string_pretty(selected).map_err(|e| McpDiffError::Serialization(e.to_string()))
+}
+
+fn run_analysis(ctx: &CommandContext, args: &AnalyzeArgs) -> Result<String> {
     // Determine what kind of analysis to perform
     if args.uncommitted {
         let base_ref = args.base.clone().unwrap_or_else(|| "HEAD".to_string());
@@ -51,7 +100,49 @@ pub fn run_analyze(ctx: &CommandContext, args: &AnalyzeArgs) -> Result<String> {
     }
 
     // File or directory analysis
-    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let path = match (&args.path, &args.package) {
+        (Some(path), _) => path.clone(),
+        (None, Some(package)) => {
+            let repo_root = PathBuf::from(get_repo_root(None)?);
+            repo_root.join(resolve_package(&repo_root, package)?.path)
+        }
+        (None, None) => PathBuf::from("."),
+    };
+
+    if args.stdin {
+        let mut source = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)?;
+        let lang = args.lang.as_deref().unwrap_or_default();
+        return run_analyze_snippet(ctx, &source, lang, &path);
+    }
+
+    if let Some(ref spec) = args.blob_ref {
+        return run_blob(ctx, args, spec);
+    }
+
+    if args.coupling {
+        return run_coupling(ctx, args, &path);
+    }
+
+    if args.debt_score {
+        return run_debt_score(ctx, &path);
+    }
+
+    if let Some(num_topics) = args.topics {
+        return run_topics(ctx, &path, num_topics);
+    }
+
+    if args.routes {
+        return run_routes(ctx, args, &path);
+    }
+
+    if let (Some(file_a), Some(file_b)) = (&args.file_a, &args.file_b) {
+        return run_file_diff(ctx, file_a, file_b);
+    }
+
+    if args.mirror {
+        return run_mirror(ctx, args, &path);
+    }
 
     if path.is_file() {
         run_single_file(ctx, args, &path)
@@ -72,6 +163,65 @@ pub fn run_analyze(ctx: &CommandContext, args: &AnalyzeArgs) -> Result<String> {
     }
 }
 
+/// Semantic diff between two files on disk, with no git involved
+///
+/// Each file is parsed in the language of its own extension, so `file_a`
+/// may be e.g. a `.js` build output of a `.ts` source.
+pub fn run_file_diff(ctx: &CommandContext, file_a: &Path, file_b: &Path) -> Result<String> {
+    let summarize = |file_path: &Path| -> Result<SemanticSummary> {
+        if !file_path.is_file() {
+            return Err(McpDiffError::FileNotFound {
+                path: file_path.display().to_string(),
+            });
+        }
+        let lang = Lang::from_path(file_path)?;
+        let source = fs::read_to_string(file_path)?;
+        parse_and_extract(file_path, &source, lang)
+    };
+    let before = summarize(file_a)?;
+    let after = summarize(file_b)?;
+    let diff = SemanticDiff::from_summaries(Some(&before), &after);
+
+    let name_a = file_a.display().to_string();
+    let name_b = file_b.display().to_string();
+    if ctx.format == OutputFormat::Toon {
+        return Ok(format_file_diff(&name_a, &name_b, &diff));
+    }
+
+    let json_value = serde_json::json!({
+        "_type": "file_diff",
+        "file_a": name_a,
+        "file_b": name_b,
+        "risk_before": diff.risk_before.as_str(),
+        "risk_after": diff.risk_after.as_str(),
+        "risk_change": diff.risk_change,
+        "deltas": diff.deltas,
+    });
+
+    if ctx.format == OutputFormat::Json {
+        return Ok(serde_json::to_string_pretty(&json_value).unwrap_or_default());
+    }
+
+    let mut output = String::new();
+    output.push_str("═══════════════════════════════════════════════════════\n");
+    output.push_str("  FILE DIFF\n");
+    output.push_str("═══════════════════════════════════════════════════════\n\n");
+    output.push_str(&format!("a: {}\n", name_a));
+    output.push_str(&format!("b: {}\n", name_b));
+    output.push_str(&format!(
+        "risk: {} -> {}\n\n",
+        diff.risk_before.as_str(),
+        diff.risk_after.as_str()
+    ));
+    if diff.deltas.is_empty() {
+        output.push_str("No semantic differences.\n");
+    }
+    for delta in &diff.deltas {
+        output.push_str(&format!("  {}\n", format_surface_delta(delta)));
+    }
+    Ok(output)
+}
+
 /// Resolve the base ref for diff operations
 fn resolve_base_ref(args: &AnalyzeArgs, diff_ref: &str) -> Result<String> {
     if let Some(ref base) = args.base {
@@ -85,6 +235,586 @@ fn resolve_base_ref(args: &AnalyzeArgs, diff_ref: &str) -> Result<String> {
     detect_base_branch(None)
 }
 
+/// Analyze a git blob (`--ref`) without checking it out
+fn run_blob(ctx: &CommandContext, args: &AnalyzeArgs, spec: &str) -> Result<String> {
+    let path_hint = args
+        .path
+        .as_ref()
+        .map(|p| p.to_string_lossy().replace('\\', "/"));
+
+    let (blob_spec, display_path) = match (spec.split_once(':'), path_hint) {
+        (Some((_, path)), hint) => (spec.to_string(), hint.unwrap_or_else(|| path.to_string())),
+        (None, Some(path)) => {
+            // A bare blob SHA keeps PATH as a name only; any other ref is
+            // looked up at PATH
+            let is_blob = git_command_optional(&["cat-file", "-t", spec], None).as_deref()
+                == Some("blob");
+            if is_blob {
+                (spec.to_string(), path)
+            } else {
+                (format!("{}:{}", spec, path), path)
+            }
+        }
+        (None, None) => {
+            return Err(McpDiffError::GitError {
+                message: format!(
+                    "Cannot detect the language of {}; pass the file path (e.g. `analyze src/lib.rs --ref {}`)",
+                    spec, spec
+                ),
+            })
+        }
+    };
+
+    let display_path = PathBuf::from(display_path);
+    let lang = Lang::from_path(&display_path)?;
+    let source = get_blob_text(&blob_spec, None)?;
+
+    if ctx.verbose {
+        eprintln!("Read {} bytes from {}", source.len(), blob_spec);
+    }
+
+    analyze_source(ctx, args, &display_path, lang, &source, source.len() as u64)
+}
+
+/// Languages accepted for in-memory snippets (`--stdin`, `analyze_snippet`)
+const SNIPPET_LANGS: &[Lang] = &[
+    Lang::TypeScript,
+    Lang::Tsx,
+    Lang::JavaScript,
+    Lang::Jsx,
+    Lang::Python,
+    Lang::Rust,
+    Lang::Go,
+    Lang::Java,
+    Lang::Kotlin,
+    Lang::CSharp,
+    Lang::C,
+    Lang::Cpp,
+    Lang::Bash,
+];
+
+/// Minimum CVE pattern similarity reported for snippets
+const SNIPPET_CVE_THRESHOLD: f32 = 0.75;
+
+/// Duplicate matches reported per snippet symbol
+const SNIPPET_MAX_DUPLICATES: usize = 5;
+
+/// Resolve a snippet language from an extension (`ts`) or name (`typescript`)
+pub fn resolve_snippet_lang(name: &str) -> Result<Lang> {
+    let wanted = name.trim().trim_start_matches('.').to_lowercase();
+    SNIPPET_LANGS
+        .iter()
+        .copied()
+        .find(|lang| lang.name() == wanted || lang.extensions().contains(&wanted.as_str()))
+        .ok_or_else(|| McpDiffError::UnsupportedLanguage {
+            extension: format!(
+                "{} (supported: {})",
+                name,
+                SNIPPET_LANGS
+                    .iter()
+                    .map(|l| format!("{} ({})", l.name(), l.extensions()[0]))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+}
+
+/// Analyze source that isn't on disk (stdin or an MCP `content` parameter)
+///
+/// The snippet is parsed in memory and never written to the cache. Each
+/// function gets risk and complexity, CVE pattern matches, and — when
+/// `repo_path` has an index — its closest duplicates among indexed functions.
+pub fn run_analyze_snippet(
+    ctx: &CommandContext,
+    source: &str,
+    lang: &str,
+    repo_path: &Path,
+) -> Result<String> {
+    let lang = resolve_snippet_lang(lang)?;
+    let snippet_path = PathBuf::from(format!("<stdin>.{}", lang.extensions()[0]));
+    let summary = parse_and_extract(&snippet_path, source, lang)?;
+
+    // Read-only: signatures come from an existing index, nothing is written
+    let cache = CacheDir::for_repo(repo_path).ok().filter(|c| c.exists());
+    let indexed = cache
+        .as_ref()
+        .map(|c| load_function_signatures(c).unwrap_or_default())
+        .unwrap_or_default();
+    let repo_root = cache.as_ref().map(|c| c.repo_root.clone());
+
+    let detector = DuplicateDetector::default();
+    let pattern_db = load_embedded_patterns();
+    let snippet_file = snippet_path.to_string_lossy().to_string();
+
+    // Locals and fields show up as variables; report the declarations that own them
+    let symbols: Vec<serde_json::Value> = summary
+        .symbols
+        .iter()
+        .filter(|sym| sym.kind != SymbolKind::Variable)
+        .map(|sym| {
+            let mut entry = serde_json::json!({
+                "name": sym.name,
+                "kind": sym.kind.as_str(),
+                "lines": format!("{}-{}", sym.start_line, sym.end_line),
+                "risk": sym.behavioral_risk.as_str(),
+                "cognitive_complexity": calculate_cognitive_complexity(&sym.control_flow),
+                "max_nesting": max_nesting_depth(&sym.control_flow),
+            });
+            if !matches!(sym.kind, SymbolKind::Function | SymbolKind::Method) {
+                return entry;
+            }
+
+            let signature = FunctionSignature::from_symbol_info(
+                sym,
+                &format!("snippet:{}", sym.name),
+                &snippet_file,
+                "",
+                None,
+            );
+
+            if cache.is_some() {
+                let mut duplicates = detector.find_duplicates(&signature, &indexed);
+                duplicates.sort_by(|a, b| {
+                    b.similarity
+                        .partial_cmp(&a.similarity)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                entry["duplicates"] = duplicates
+                    .iter()
+                    .take(SNIPPET_MAX_DUPLICATES)
+                    .map(|d| {
+                        let file = repo_root
+                            .as_ref()
+                            .and_then(|root| Path::new(&d.symbol.file).strip_prefix(root).ok())
+                            .map(|p| p.to_string_lossy().replace('\\', "/"))
+                            .unwrap_or_else(|| d.symbol.file.clone());
+                        serde_json::json!({
+                            "finding": format!(
+                                "{:.0}% similar to {}:{}",
+                                d.similarity * 100.0,
+                                file,
+                                d.symbol.name
+                            ),
+                            "symbol": d.symbol.name,
+                            "file": file,
+                            "line": d.symbol.start_line,
+                            "hash": d.symbol.hash,
+                            "similarity": (d.similarity * 100.0).round() / 100.0,
+                            "kind": format!("{:?}", d.kind).to_lowercase(),
+                        })
+                    })
+                    .collect();
+            }
+
+            let cve_matches =
+                detector.match_cve_patterns(&signature, &pattern_db, SNIPPET_CVE_THRESHOLD);
+            if !cve_matches.is_empty() {
+                entry["cve_matches"] = cve_matches
+                    .iter()
+                    .map(|m| {
+                        serde_json::json!({
+                            "cve_id": m.cve_id,
+                            "severity": m.severity.to_string(),
+                            "similarity": (m.similarity * 100.0).round() / 100.0,
+                            "description": m.description,
+                        })
+                    })
+                    .collect();
+            }
+            entry
+        })
+        .collect();
+
+    let findings = serde_json::json!({
+        "_type": "snippet_analysis",
+        "language": lang.name(),
+        "index_available": cache.is_some(),
+        "symbols": symbols,
+    });
+
+    let output = match ctx.format {
+        OutputFormat::Json => {
+            let mut json_value = findings;
+            json_value["summary"] = serde_json::to_value(&summary).unwrap_or_default();
+            serde_json::to_string_pretty(&json_value).unwrap_or_default()
+        }
+        OutputFormat::Toon => format!(
+            "{}\n---\n{}",
+            encode_toon(&summary),
+            super::encode_toon(&findings)
+        ),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            let mut text = String::new();
+            text.push_str("═══════════════════════════════════════════\n");
+            text.push_str("  SNIPPET ANALYSIS\n");
+            text.push_str("═══════════════════════════════════════════\n\n");
+            text.push_str(&format!("language: {}\n", lang.name()));
+            if cache.is_none() {
+                text.push_str("duplicates: skipped (no index for this repository)\n");
+            }
+            for sym in findings["symbols"].as_array().into_iter().flatten() {
+                text.push_str(&format!(
+                    "\n{} ({}) L{}\n  risk: {} | complexity: {} | nesting: {}\n",
+                    sym["name"].as_str().unwrap_or_default(),
+                    sym["kind"].as_str().unwrap_or_default(),
+                    sym["lines"].as_str().unwrap_or_default(),
+                    sym["risk"].as_str().unwrap_or_default(),
+                    sym["cognitive_complexity"],
+                    sym["max_nesting"]
+                ));
+                for dup in sym["duplicates"].as_array().into_iter().flatten() {
+                    text.push_str(&format!(
+                        "  duplicate: {}\n",
+                        dup["finding"].as_str().unwrap_or_default()
+                    ));
+                }
+                for cve in sym["cve_matches"].as_array().into_iter().flatten() {
+                    text.push_str(&format!(
+                        "  ⚠ {} [{}]: {}\n",
+                        cve["cve_id"].as_str().unwrap_or_default(),
+                        cve["severity"].as_str().unwrap_or_default(),
+                        cve["description"].as_str().unwrap_or_default()
+                    ));
+                }
+            }
+            text.push('\n');
+            text.push_str(&encode_toon(&summary));
+            text
+        }
+    };
+
+    Ok(format!("{}\n", output))
+}
+
+/// Report module pairs that call into each other, from the semantic index
+fn run_coupling(ctx: &CommandContext, args: &AnalyzeArgs, repo_path: &Path) -> Result<String> {
+    let cache = CacheDir::for_repo(repo_path)?;
+    if !cache.exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!(
+                "semantic index for {} (run `semfora index generate` first)",
+                repo_path.display()
+            ),
+        });
+    }
+
+    let analysis = analyze_repo(repo_path)?;
+    let reports = analyze_coupling(&analysis);
+    let total = reports.len();
+    let shown = &reports[..args.limit.unwrap_or(20).min(total)];
+
+    let json_value = serde_json::json!({
+        "_type": "module_coupling",
+        "total_pairs": total,
+        "showing": shown.len(),
+        "pairs": shown.iter().map(|r| serde_json::json!({
+            "module_a": r.module_a,
+            "module_b": r.module_b,
+            "kind": r.kind.as_str(),
+            "shared_call_count": r.shared_call_count,
+            "coupling_score": (r.coupling_score * 1000.0).round() / 1000.0,
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════════════════\n");
+            output.push_str("  MODULE COUPLING\n");
+            output.push_str("═══════════════════════════════════════════════════════\n\n");
+
+            if shown.is_empty() {
+                output.push_str("No calls between modules found.\n");
+            } else {
+                output.push_str(&format!("pairs: {} (showing {})\n\n", total, shown.len()));
+                for r in shown {
+                    let arrow = match r.kind {
+                        CouplingKind::Bidirectional => "<->",
+                        CouplingKind::EfferentOnly => "-->",
+                        CouplingKind::AfferentOnly => "<--",
+                    };
+                    output.push_str(&format!(
+                        "  {} {} {}  score: {:.3}  shared_calls: {}{}\n",
+                        r.module_a,
+                        arrow,
+                        r.module_b,
+                        r.coupling_score,
+                        r.shared_call_count,
+                        if r.kind == CouplingKind::Bidirectional {
+                            "  ⚠ bidirectional"
+                        } else {
+                            ""
+                        }
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Score the repository's technical debt and record it for the trend
+fn run_debt_score(ctx: &CommandContext, repo_path: &Path) -> Result<String> {
+    let cache = CacheDir::for_repo(repo_path)?;
+    if !cache.exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!(
+                "semantic index for {} (run `semfora index generate` first)",
+                repo_path.display()
+            ),
+        });
+    }
+
+    let mut analysis = analyze_repo(repo_path)?;
+    add_duplicate_debt(&mut analysis, &cache)?;
+    let report = analysis.tech_debt_score();
+    let previous = cache.record_debt_score(&report)?;
+
+    let mut json_value = serde_json::json!({
+        "_type": "tech_debt",
+        "overall_score": report.overall_score,
+        "duplicate_debt": report.duplicate_debt,
+        "complexity_debt": report.complexity_debt,
+        "coupling_debt": report.coupling_debt,
+        "test_coverage_debt": report.test_coverage_debt,
+        "test_coverage_measured": analysis.debt.testable > 0,
+        "affected_loc": report.affected_loc,
+        "remediation_hours": report.remediation_hours,
+        "hours_per_loc": REMEDIATION_HOURS_PER_LOC,
+    });
+    let trend = previous.last().map(|last| {
+        let change = ((report.overall_score - last.overall_score) * 10.0).round() / 10.0;
+        let direction = match change {
+            c if c < 0.0 => "improving",
+            c if c > 0.0 => "worsening",
+            _ => "unchanged",
+        };
+        serde_json::json!({
+            "previous_score": last.overall_score,
+            "change": change,
+            "direction": direction,
+            "history": previous.iter().rev().take(10).rev().map(|entry| serde_json::json!({
+                "recorded_at": entry.recorded_at,
+                "overall_score": entry.overall_score,
+            })).collect::<Vec<_>>(),
+        })
+    });
+    if let Some(trend) = &trend {
+        json_value["trend"] = trend.clone();
+    }
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════════════════\n");
+            output.push_str("  TECHNICAL DEBT\n");
+            output.push_str("═══════════════════════════════════════════════════════\n\n");
+            output.push_str(&format!(
+                "overall score: {:.1} / 100 (lower is better)\n\n",
+                report.overall_score
+            ));
+            output.push_str(&format!(
+                "  duplicates:     {:>5.1}\n",
+                report.duplicate_debt
+            ));
+            output.push_str(&format!(
+                "  complexity:     {:>5.1}\n",
+                report.complexity_debt
+            ));
+            output.push_str(&format!(
+                "  coupling:       {:>5.1}\n",
+                report.coupling_debt
+            ));
+            output.push_str(&format!(
+                "  test coverage:  {:>5.1}{}\n\n",
+                report.test_coverage_debt,
+                if analysis.debt.testable > 0 {
+                    ""
+                } else {
+                    "  (not measured: index has no test links)"
+                }
+            ));
+            output.push_str(&format!(
+                "remediation: ~{:.1} hours ({} lines affected x {} h/line)\n",
+                report.remediation_hours, report.affected_loc, REMEDIATION_HOURS_PER_LOC
+            ));
+            if let Some(trend) = &trend {
+                output.push_str(&format!(
+                    "trend: {} -> {:.1} ({:+.1}, {})\n",
+                    trend["previous_score"],
+                    report.overall_score,
+                    trend["change"].as_f64().unwrap_or(0.0),
+                    trend["direction"].as_str().unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Cluster the repository's modules into topics from the BM25 index
+pub fn run_topics(ctx: &CommandContext, repo_path: &Path, num_topics: usize) -> Result<String> {
+    let cache = CacheDir::for_repo(repo_path)?;
+    let missing = || McpDiffError::FileNotFound {
+        path: format!(
+            "BM25 index for {} (run `semfora index generate` first)",
+            repo_path.display()
+        ),
+    };
+    if !cache.exists() {
+        return Err(missing());
+    }
+    cache
+        .require_bm25_index()
+        .map_err(|e| if e.is_cache_damage() { e } else { missing() })?;
+
+    let index = Bm25Index::load_sqlite(&cache.bm25_index_path())?;
+    let topics = index.cluster_topics(num_topics);
+
+    let json_value = serde_json::json!({
+        "_type": "module_topics",
+        "requested": num_topics,
+        "topics": topics.iter().map(|t| serde_json::json!({
+            "id": t.id,
+            "label": t.label,
+            "cohesion_score": (t.cohesion_score * 1000.0).round() / 1000.0,
+            "modules": t.modules,
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════════════════\n");
+            output.push_str("  MODULE TOPICS\n");
+            output.push_str("═══════════════════════════════════════════════════════\n\n");
+
+            if topics.is_empty() {
+                output.push_str("No modules in the index.\n");
+            }
+            for t in &topics {
+                output.push_str(&format!(
+                    "[{}] {}  cohesion: {:.3}\n    {}\n\n",
+                    t.id,
+                    t.label,
+                    t.cohesion_score,
+                    t.modules.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// List the HTTP routes declared in a file or directory
+///
+/// Files are parsed fresh, so no index is needed. Routes are ordered by file
+/// and line.
+fn run_routes(ctx: &CommandContext, args: &AnalyzeArgs, path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: path.display().to_string(),
+        });
+    }
+    let files = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        collect_files(path, args.max_depth, args, ctx)
+    };
+
+    let mut routes: Vec<(String, RouteInfo)> = files
+        .par_iter()
+        .flat_map_iter(|file_path| {
+            let summary = Lang::from_path(file_path).ok().and_then(|lang| {
+                let source = fs::read_to_string(file_path).ok()?;
+                parse_and_extract_string(file_path, &source, lang).ok()
+            });
+            let relative = file_path
+                .strip_prefix(path)
+                .ok()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(file_path)
+                .display()
+                .to_string();
+            summary
+                .map(|s| s.routes)
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |route| (relative.clone(), route))
+        })
+        .collect();
+    routes.sort_by(|(file_a, a), (file_b, b)| (file_a, a.line).cmp(&(file_b, b.line)));
+
+    let json_value = serde_json::json!({
+        "_type": "routes",
+        "total": routes.len(),
+        "routes": routes.iter().map(|(file, r)| serde_json::json!({
+            "method": r.method,
+            "path": r.path,
+            "handler": r.handler,
+            "file": file,
+            "line": r.line,
+            "framework": r.framework,
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════════════════\n");
+            output.push_str("  HTTP ROUTES\n");
+            output.push_str("═══════════════════════════════════════════════════════\n\n");
+
+            if routes.is_empty() {
+                output.push_str("No routes found.\n");
+            } else {
+                output.push_str(&format!("routes: {}\n\n", routes.len()));
+            }
+            let path_width = routes.iter().map(|(_, r)| r.path.len()).max().unwrap_or(0);
+            for (file, r) in &routes {
+                output.push_str(&format!(
+                    "  {:<7} {:<path_width$}  {}  ({}:{}, {})\n",
+                    r.method,
+                    r.path,
+                    r.handler.as_deref().unwrap_or("(inline)"),
+                    file,
+                    r.line,
+                    r.framework,
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
 /// Large file thresholds (matching MCP constants)
 const VERY_LARGE_FILE_BYTES: u64 = 500_000;
 const LARGE_FILE_LINES: usize = 3000;
@@ -117,15 +847,53 @@ fn run_single_file(ctx: &CommandContext, args: &AnalyzeArgs, file_path: &Path) -
         eprintln!("Read {} bytes from {}", source.len(), file_path.display());
     }
 
-    // Large file detection
     let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+    let cwd = std::env::current_dir()?;
+    let dir = file_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let display_path = PathBuf::from(render_path(ctx, file_path, &output_root(dir, &cwd)));
+    analyze_source(ctx, args, &display_path, lang, &source, file_size)
+}
+
+/// Repository root that analyzed paths are rendered against
+///
+/// The enclosing git work tree, or `fallback` outside of one.
+fn output_root(dir: &Path, fallback: &Path) -> PathBuf {
+    get_repo_root(Some(dir))
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            std::path::absolute(fallback).unwrap_or_else(|_| fallback.to_path_buf())
+        })
+}
+
+/// Render a path on disk per `--paths`, relative to `root`
+fn render_path(ctx: &CommandContext, path: &Path, root: &Path) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    ctx.display_path(&absolute.to_string_lossy(), root)
+}
+
+/// Analyze source text attributed to `file_path`
+///
+/// Shared by files on disk and git blobs (`--ref`), which have no file of
+/// their own.
+fn analyze_source(
+    ctx: &CommandContext,
+    args: &AnalyzeArgs,
+    file_path: &Path,
+    lang: Lang,
+    source: &str,
+    file_size: u64,
+) -> Result<String> {
+    // Large file detection
     let line_count = source.lines().count();
     let has_focus = args.start_line.is_some() && args.end_line.is_some();
 
     // For very large files without focus, return metadata with navigation hints
     if (file_size > VERY_LARGE_FILE_BYTES || line_count > LARGE_FILE_LINES) && !has_focus {
         // Do a quick parse to get symbol count for the hint
-        let symbol_hint = if let Ok(summary) = parse_and_extract(file_path, &source, lang) {
+        let symbol_hint = if let Ok(summary) = parse_and_extract(file_path, source, lang) {
             format!(
                 "\nsymbols_found: {}\nhigh_risk_count: {}\n",
                 summary.symbols.len(),
@@ -179,7 +947,7 @@ fn run_single_file(ctx: &CommandContext, args: &AnalyzeArgs, file_path: &Path) -
 
         lines[start_idx..end_idx].join("\n")
     } else {
-        source.clone()
+        source.to_string()
     };
 
     let summary =
@@ -241,21 +1009,24 @@ fn run_single_file(ctx: &CommandContext, args: &AnalyzeArgs, file_path: &Path) -
             // Token analysis if requested
             if let Some(mode) = args.analyze_tokens {
                 let analyzer = TokenAnalyzer::new();
-                let analysis = analyzer.analyze(&source, &json_pretty, &json_compact, &toon_output);
+                let analysis = analyzer.analyze(source, &json_pretty, &json_compact, &toon_output);
 
                 let report = match mode {
                     TokenAnalysisMode::Full => {
                         format_analysis_report(&analysis, args.compare_compact)
                     }
-                    TokenAnalysisMode::Compact => {
-                        format_analysis_compact(&analysis, args.compare_compact)
-                    }
+                    TokenAnalysisMode::Compact => format_analysis_compact(
+                        &analysis,
+                        args.compare_compact,
+                        &summary,
+                        args.token_budget,
+                    ),
                 };
                 eprintln!("{}", report);
             }
 
             let mut output = match ctx.format {
-                OutputFormat::Text => {
+                OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                     // Human-readable text format
                     let mut text = String::new();
                     text.push_str("═══════════════════════════════════════════\n");
@@ -330,7 +1101,7 @@ fn run_directory(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> R
         });
     }
 
-    let files = collect_files(dir_path, args.max_depth, args);
+    let files = collect_files(dir_path, args.max_depth, args, ctx);
 
     if files.is_empty() {
         return Ok(format!(
@@ -347,7 +1118,7 @@ fn run_directory(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> R
     let total_lines_atomic = AtomicUsize::new(0);
     let verbose = ctx.verbose;
 
-    let summaries: Vec<SemanticSummary> = files
+    let mut summaries: Vec<SemanticSummary> = files
         .par_iter()
         .filter_map(|file_path| {
             let lang = match Lang::from_path(file_path) {
@@ -393,7 +1164,15 @@ fn run_directory(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> R
     let all_source_len = all_source_len_atomic.load(Ordering::Relaxed);
     let total_lines = total_lines_atomic.load(Ordering::Relaxed);
 
-    let dir_str = dir_path.display().to_string();
+    mark_cyclic_imports(&mut summaries);
+    let root = output_root(dir_path, dir_path);
+    for summary in &mut summaries {
+        summary.file = render_path(ctx, Path::new(&summary.file), &root);
+        for file in &mut summary.cyclic_imports {
+            *file = render_path(ctx, Path::new(file.as_str()), &root);
+        }
+    }
+    let dir_str = render_path(ctx, dir_path, &root);
     let overview = generate_repo_overview(&summaries, &dir_str);
 
     let output = if args.summary_only {
@@ -439,6 +1218,97 @@ fn run_directory(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> R
     Ok(output)
 }
 
+/// Write each file's TOON summary to `<file>.<ext>` (`--mirror`)
+///
+/// With `--output-dir` the summaries go to the same relative paths under that
+/// directory instead of next to their sources. Every summary is written
+/// atomically; the result lists the files written.
+fn run_mirror(ctx: &CommandContext, args: &AnalyzeArgs, path: &Path) -> Result<String> {
+    let (base, files) = if path.is_file() {
+        Lang::from_path(path)?;
+        let dir = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        (dir.to_path_buf(), vec![path.to_path_buf()])
+    } else if path.is_dir() {
+        let files = collect_files(path, args.max_depth, args, ctx);
+        (path.to_path_buf(), files)
+    } else {
+        return Err(McpDiffError::FileNotFound {
+            path: path.display().to_string(),
+        });
+    };
+    // Summaries name their file as `analyze <file>` would
+    let root = output_root(&base, &std::env::current_dir()?);
+    let ext = args.mirror_ext.trim_start_matches('.');
+
+    let written = files
+        .par_iter()
+        .map(|file| {
+            let lang = Lang::from_path(file)?;
+            let source = fs::read_to_string(file)?;
+            let mut summary = match parse_and_extract_string(file, &source, lang) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    if ctx.verbose {
+                        eprintln!("Failed to analyze {}: {}", file.display(), e);
+                    }
+                    return Ok(None);
+                }
+            };
+            summary.file = render_path(ctx, file, &root);
+
+            let relative = file.strip_prefix(&base).unwrap_or(file);
+            let mirror = match &args.output_dir {
+                Some(dir) => dir.join(relative),
+                None => file.clone(),
+            };
+            let mut file_name = mirror.file_name().unwrap_or_default().to_os_string();
+            file_name.push(format!(".{}", ext));
+            let mirror = mirror.with_file_name(file_name);
+
+            write_atomic(&mirror, &encode_toon(&summary))?;
+            Ok(Some((summary.file, render_path(ctx, &mirror, &root))))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut written: Vec<(String, String)> = written.into_iter().flatten().collect();
+    written.sort();
+
+    if ctx.verbose {
+        eprintln!("Mirrored {} of {} files", written.len(), files.len());
+    }
+
+    let json_value = serde_json::json!({
+        "_type": "analyze_mirror",
+        "extension": ext,
+        "output_dir": args.output_dir.as_ref().map(|dir| render_path(ctx, dir, &root)),
+        "files_written": written.len(),
+        "files": written.iter().map(|(source, mirror)| serde_json::json!({
+            "source": source,
+            "mirror": mirror,
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("files_written: {}\n", written.len()));
+            for (source, mirror) in &written {
+                output.push_str(&format!("  {} -> {}\n", source, mirror));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
 /// Generate sharded index for large repositories
 fn run_shard(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> Result<String> {
     if !dir_path.exists() {
@@ -471,7 +1341,7 @@ fn run_shard(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> Resul
     }
 
     // Full reindex
-    let files = collect_files(dir_path, args.max_depth, args);
+    let files = collect_files(dir_path, args.max_depth, args, ctx);
 
     if files.is_empty() {
         return Ok(format!(
@@ -530,6 +1400,7 @@ fn run_shard(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> Resul
     }
 
     // Write sharded output
+    let _lock = CacheDir::for_repo(&canonical_path)?.lock_index()?;
     let mut writer = ShardWriter::new(&canonical_path)?;
     writer.add_summaries(summaries.clone());
     let stats = writer.write_all(&canonical_path.display().to_string())?;
@@ -548,15 +1419,20 @@ fn run_shard(ctx: &CommandContext, args: &AnalyzeArgs, dir_path: &Path) -> Resul
 }
 
 /// Analyze uncommitted changes
+///
+/// Covers staged, unstaged and untracked files. Files no longer in the
+/// working tree are reported under `removed` with their summary at HEAD.
 fn run_uncommitted(ctx: &CommandContext, _args: &AnalyzeArgs, _base_ref: &str) -> Result<String> {
     let repo_root = PathBuf::from(get_repo_root(None)?);
 
     let staged = get_staged_changes(None)?;
     let unstaged = get_unstaged_changes(None)?;
+    let untracked = get_untracked_changes(None)?;
 
     let mut all_changes: Vec<ChangedFile> = Vec::new();
     all_changes.extend(staged.into_iter());
     all_changes.extend(unstaged.into_iter());
+    all_changes.extend(untracked.into_iter());
 
     // Deduplicate by path
     all_changes.sort_by(|a, b| a.path.cmp(&b.path));
@@ -566,16 +1442,17 @@ fn run_uncommitted(ctx: &CommandContext, _args: &AnalyzeArgs, _base_ref: &str) -
         return Ok("uncommitted_changes: 0\n".to_string());
     }
 
+    // A staged file deleted since is gone too, whatever its staged status
+    let (present, removed): (Vec<ChangedFile>, Vec<ChangedFile>) = all_changes
+        .into_iter()
+        .partition(|change| repo_root.join(&change.path).exists());
+
     let verbose = ctx.verbose;
-    let summaries: Vec<SemanticSummary> = all_changes
+    let summaries: Vec<SemanticSummary> = present
         .par_iter()
         .filter_map(|change| {
             let file_path = repo_root.join(&change.path);
 
-            if !file_path.exists() {
-                return None;
-            }
-
             let lang = match Lang::from_path(&file_path) {
                 Ok(l) => l,
                 Err(_) => return None,
@@ -596,7 +1473,24 @@ fn run_uncommitted(ctx: &CommandContext, _args: &AnalyzeArgs, _base_ref: &str) -
         .collect();
 
     let overview = generate_repo_overview(&summaries, "uncommitted");
-    let output = encode_toon_directory(&overview, &summaries);
+    let mut output = encode_toon_directory(&overview, &summaries);
+
+    if !removed.is_empty() {
+        output.push_str(&format!("\nremoved[{}]:\n", removed.len()));
+        for change in &removed {
+            output.push_str(&format!("  {}\n", change.path));
+            let file_path = repo_root.join(&change.path);
+            let base_summary = Lang::from_path(&file_path).ok().and_then(|lang| {
+                let source = get_file_at_ref(&change.path, "HEAD", Some(&repo_root)).ok()??;
+                parse_and_extract_string(&file_path, &source, lang).ok()
+            });
+            if let Some(summary) = base_summary {
+                for line in encode_toon(&summary).lines() {
+                    output.push_str(&format!("    {}\n", line));
+                }
+            }
+        }
+    }
 
     Ok(output)
 }
@@ -615,17 +1509,55 @@ fn run_diff_branch(ctx: &CommandContext, args: &AnalyzeArgs, base_ref: &str) ->
     let target_ref = args.target_ref.as_deref().unwrap_or("HEAD");
 
     // Handle special case for uncommitted changes (WORKING target)
-    let (changed_files, display_target) = if target_ref.eq_ignore_ascii_case("WORKING") {
-        let files = get_uncommitted_changes(base_ref, Some(&repo_root))?;
-        (files, "WORKING (uncommitted)")
+    let working = target_ref.eq_ignore_ascii_case("WORKING");
+    ensure_ref(base_ref, args.auto_deepen, Some(&repo_root))?;
+    let (changed_files, display_target, diff_base) = if working {
+        let diff_base = resolve_diff_base(base_ref, "HEAD", &repo_root, args)?;
+        let files = get_working_tree_changes(&diff_base, Some(&repo_root))?;
+        (files, "WORKING (uncommitted)", diff_base)
     } else {
         // Normal comparison between refs
-        let merge_base = get_merge_base(base_ref, target_ref, Some(&repo_root))
-            .unwrap_or_else(|_| base_ref.to_string());
-        let files = get_changed_files(&merge_base, target_ref, Some(&repo_root))?;
-        (files, target_ref)
+        ensure_ref(target_ref, args.auto_deepen, Some(&repo_root))?;
+        let diff_base = resolve_diff_base(base_ref, target_ref, &repo_root, args)?;
+        let files = get_changed_files(&diff_base, target_ref, Some(&repo_root))?;
+        (files, target_ref, diff_base)
+    };
+
+    let changed_files = match args.package {
+        Some(ref name) => {
+            let package = resolve_package(&repo_root, name)?;
+            changed_files
+                .into_iter()
+                .filter(|f| {
+                    package.contains(&f.path)
+                        || f.old_path
+                            .as_deref()
+                            .is_some_and(|old| package.contains(old))
+                })
+                .collect()
+        }
+        None => changed_files,
     };
 
+    if ctx.format == OutputFormat::ReviewJson {
+        let breaking = diff_breaking_changes(
+            &repo_root,
+            &diff_base,
+            (!working).then_some(target_ref),
+            &changed_files,
+        );
+        return Ok(review_bundle(
+            &repo_root,
+            base_ref,
+            display_target,
+            &diff_base,
+            (!working).then_some(target_ref),
+            &changed_files,
+            &breaking,
+        )
+        .to_json());
+    }
+
     if changed_files.is_empty() {
         return Ok(format!(
             "_type: analyze_diff\nbase: \"{}\"\ntarget: \"{}\"\ntotal_files: 0\n_note: No files changed.\n",
@@ -637,15 +1569,40 @@ fn run_diff_branch(ctx: &CommandContext, args: &AnalyzeArgs, base_ref: &str) ->
     let limit = args.limit.unwrap_or(20).min(100); // Default 20, max 100
     let offset = args.offset.unwrap_or(0);
 
+    // Protobuf/OpenAPI changes that break existing clients
+    let breaking = diff_breaking_changes(
+        &repo_root,
+        &diff_base,
+        (!working).then_some(target_ref),
+        &changed_files,
+    );
+    // New deprecated calls and I/O calls moved out of try blocks
+    let warnings = diff_warnings(
+        &repo_root,
+        &diff_base,
+        (!working).then_some(target_ref),
+        &changed_files,
+    );
+
     // Choose output format based on options
     let output = if args.summary_only {
-        format_diff_summary(&repo_root, base_ref, display_target, &changed_files)
+        format_diff_summary(
+            &repo_root,
+            base_ref,
+            display_target,
+            &changed_files,
+            &breaking,
+            &warnings,
+        )
     } else {
         format_diff_output_paginated(
             &repo_root,
             base_ref,
             display_target,
+            &diff_base,
             &changed_files,
+            &breaking,
+            &warnings,
             offset,
             limit,
         )
@@ -663,6 +1620,31 @@ fn run_diff_branch(ctx: &CommandContext, args: &AnalyzeArgs, base_ref: &str) ->
     Ok(output)
 }
 
+/// Resolve the ref the diff should start from
+///
+/// By default this is the merge-base of `base_ref` and `target_ref`, so commits
+/// that only exist on the base branch (e.g. after a rebase or force-push) are not
+/// reported as changes. Falls back to `base_ref` when the histories are
+/// unrelated. In a shallow clone a missing merge-base is an error (or is
+/// fetched with `--auto-deepen`) rather than silently diffing against the
+/// wrong commit.
+fn resolve_diff_base(
+    base_ref: &str,
+    target_ref: &str,
+    repo_root: &Path,
+    args: &AnalyzeArgs,
+) -> Result<String> {
+    if args.no_merge_base {
+        return Ok(base_ref.to_string());
+    }
+
+    match merge_base_with_deepen(base_ref, target_ref, args.auto_deepen, Some(repo_root)) {
+        Ok(merge_base) => Ok(merge_base),
+        Err(e @ McpDiffError::ShallowClone { .. }) => Err(e),
+        Err(_) => Ok(base_ref.to_string()),
+    }
+}
+
 /// Analyze a single commit
 fn run_single_commit(ctx: &CommandContext, _args: &AnalyzeArgs, sha: &str) -> Result<String> {
     let changed_files = get_commit_changed_files(sha, None)?;
@@ -715,7 +1697,8 @@ fn run_single_commit(ctx: &CommandContext, _args: &AnalyzeArgs, sha: &str) -> Re
 }
 
 /// Analyze all commits since base
-fn run_all_commits(_ctx: &CommandContext, _args: &AnalyzeArgs, base_ref: &str) -> Result<String> {
+fn run_all_commits(_ctx: &CommandContext, args: &AnalyzeArgs, base_ref: &str) -> Result<String> {
+    ensure_ref(base_ref, args.auto_deepen, None)?;
     let commits = get_commits_since(base_ref, None)?;
 
     if commits.is_empty() {
@@ -748,9 +1731,17 @@ fn parse_and_extract_string(file_path: &Path, source: &str, lang: Lang) -> Resul
     parse_and_extract(file_path, source, lang)
 }
 
-/// Collect files for analysis
-fn collect_files(dir_path: &Path, max_depth: usize, args: &AnalyzeArgs) -> Vec<PathBuf> {
-    collect_files_recursive(dir_path, max_depth, 0, args)
+/// Collect files for analysis, skipping paths the repository config excludes
+fn collect_files(
+    dir_path: &Path,
+    max_depth: usize,
+    args: &AnalyzeArgs,
+    ctx: &CommandContext,
+) -> Vec<PathBuf> {
+    let settings = ctx.repo_settings(dir_path);
+    let mut files = collect_files_recursive(dir_path, max_depth, 0, args);
+    files.retain(|file| !settings.is_excluded(file));
+    files
 }
 
 /// Recursively collect files
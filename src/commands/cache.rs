@@ -1,16 +1,33 @@
 //! Cache command handler - Manage the semantic cache
 
-use crate::cache::{get_cache_base_dir, list_cached_repos, prune_old_caches, CacheDir};
-use crate::cli::{CacheArgs, CacheOperation, OutputFormat};
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use crate::cache::manifest::ModuleDiff;
+use crate::cache::migrate::format_schema_version;
+use crate::cache::{
+    diff_manifests, get_cache_base_dir, list_cached_repos, migrate_cache, prune_old_caches,
+    verify_cache, ArtifactStatus, CacheDir, CacheRepoInfo, IndexManifest, RepairPlan,
+};
+use crate::cli::{
+    CacheArgs, CacheDiffArgs, CacheOperation, CacheRepairArgs, CacheSortKey, CacheVerifyArgs,
+    MigrateArgs, OutputFormat,
+};
 use crate::commands::CommandContext;
 use crate::error::{McpDiffError, Result};
+use crate::mcp_server::helpers::generate_index_internal;
 
 /// Run the cache command
 pub fn run_cache(args: &CacheArgs, ctx: &CommandContext) -> Result<String> {
     match &args.operation {
         CacheOperation::Info => run_cache_info(ctx),
+        CacheOperation::List { sort_by } => run_cache_list(*sort_by, ctx),
         CacheOperation::Clear => run_cache_clear(ctx),
         CacheOperation::Prune { days } => run_cache_prune(*days, ctx),
+        CacheOperation::Migrate(migrate_args) => run_migrate(migrate_args, ctx),
+        CacheOperation::Verify(verify_args) => run_verify(verify_args, ctx),
+        CacheOperation::Repair(repair_args) => run_repair(repair_args, ctx),
+        CacheOperation::Diff(diff_args) => run_cache_diff(diff_args, ctx),
     }
 }
 
@@ -21,15 +38,15 @@ fn run_cache_info(ctx: &CommandContext) -> Result<String> {
 
     let mut output = String::new();
 
-    let total_size: u64 = cached_repos.iter().map(|(_, _, s)| *s).sum();
+    let total_size: u64 = cached_repos.iter().map(|r| r.cache_size_bytes).sum();
     let repos: Vec<serde_json::Value> = cached_repos
         .iter()
-        .map(|(hash, path, size)| {
+        .map(|repo| {
             serde_json::json!({
-                "hash": hash,
-                "path": path.to_string_lossy(),
-                "size_bytes": size,
-                "size_mb": *size as f64 / (1024.0 * 1024.0)
+                "hash": repo.repo_hash,
+                "path": repo.cache_path.to_string_lossy(),
+                "size_bytes": repo.cache_size_bytes,
+                "size_mb": repo.cache_size_mb
             })
         })
         .collect();
@@ -50,7 +67,7 @@ fn run_cache_info(ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════════════════\n");
             output.push_str("  SEMFORA CACHE INFO\n");
             output.push_str("═══════════════════════════════════════════════════════\n\n");
@@ -68,13 +85,12 @@ fn run_cache_info(ctx: &CommandContext) -> Result<String> {
                 ));
 
                 output.push_str("repos:\n");
-                for (hash, path, size) in &cached_repos {
-                    output.push_str(&format!("  - hash: {}\n", hash));
-                    output.push_str(&format!("    path: {}\n", path.display()));
+                for repo in &cached_repos {
+                    output.push_str(&format!("  - hash: {}\n", repo.repo_hash));
+                    output.push_str(&format!("    path: {}\n", repo.cache_path.display()));
                     output.push_str(&format!(
                         "    size: {} bytes ({:.2} MB)\n",
-                        size,
-                        *size as f64 / (1024.0 * 1024.0)
+                        repo.cache_size_bytes, repo.cache_size_mb
                     ));
                 }
             }
@@ -84,6 +100,87 @@ fn run_cache_info(ctx: &CommandContext) -> Result<String> {
     Ok(output)
 }
 
+/// List cached repositories with their storage details
+fn run_cache_list(sort_by: CacheSortKey, ctx: &CommandContext) -> Result<String> {
+    let mut cached_repos = list_cached_repos();
+    sort_cached_repos(&mut cached_repos, sort_by);
+
+    let mut output = String::new();
+
+    let total_mb: f64 = cached_repos.iter().map(|r| r.cache_size_mb).sum();
+    let json_value = serde_json::json!({
+        "_type": "cache_list",
+        "cache_base": get_cache_base_dir().to_string_lossy(),
+        "sort_by": format!("{:?}", sort_by).to_lowercase(),
+        "count": cached_repos.len(),
+        "total_size_mb": total_mb,
+        "repos": cached_repos
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            if cached_repos.is_empty() {
+                output.push_str("No cached repositories found.\n");
+                return Ok(output);
+            }
+
+            output.push_str(&format!(
+                "{:<10} {:>6} {:>9} {:>7} {:>7}  {:<15} REPOSITORY\n",
+                "SIZE", "AGE", "SYMBOLS", "SHARDS", "SCHEMA", "BRANCH"
+            ));
+            for repo in &cached_repos {
+                let repo_path = if repo.repo_path.as_os_str().is_empty() {
+                    format!("(unknown, cache {})", repo.repo_hash)
+                } else {
+                    repo.repo_path.display().to_string()
+                };
+                output.push_str(&format!(
+                    "{:<10} {:>6} {:>9} {:>7} {:>7}  {:<15} {}\n",
+                    format!("{:.2} MB", repo.cache_size_mb),
+                    format_age(repo.index_age_secs),
+                    repo.symbol_count,
+                    repo.shard_count,
+                    repo.schema_version,
+                    repo.git_branch.as_deref().unwrap_or("-"),
+                    repo_path
+                ));
+            }
+            output.push_str(&format!(
+                "\n{} cached repos, {:.2} MB total\n",
+                cached_repos.len(),
+                total_mb
+            ));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Order the listing so the entries most worth pruning come first
+fn sort_cached_repos(repos: &mut [CacheRepoInfo], sort_by: CacheSortKey) {
+    match sort_by {
+        CacheSortKey::Size => repos.sort_by_key(|r| Reverse(r.cache_size_bytes)),
+        CacheSortKey::Age => repos.sort_by_key(|r| Reverse(r.index_age_secs)),
+        CacheSortKey::Symbols => repos.sort_by_key(|r| Reverse(r.symbol_count)),
+    }
+}
+
+/// Render an age in seconds with its largest unit (`45s`, `12m`, `5h`, `3d`)
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
 /// Clear the cache for the current directory
 fn run_cache_clear(ctx: &CommandContext) -> Result<String> {
     let current_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
@@ -96,7 +193,7 @@ fn run_cache_clear(ctx: &CommandContext) -> Result<String> {
 
     let json_value = if cache.exists() {
         let size = cache.size();
-        cache.clear()?;
+        cache.purge()?;
         serde_json::json!({
             "_type": "cache_clear",
             "cleared": true,
@@ -120,7 +217,7 @@ fn run_cache_clear(ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             if json_value
                 .get("cleared")
                 .and_then(|v| v.as_bool())
@@ -164,7 +261,7 @@ fn run_cache_prune(days: u32, ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!("Pruning caches older than {} days...\n", days));
             if pruned_count == 0 {
                 output.push_str("No caches pruned.\n");
@@ -176,3 +273,375 @@ fn run_cache_prune(days: u32, ctx: &CommandContext) -> Result<String> {
 
     Ok(output)
 }
+
+/// Upgrade the cached index to the current schema version.
+///
+/// Applies the registered migrations between the stored and current schema
+/// versions. If a migration had to discard the index, it is regenerated
+/// unless `--no-rebuild` is given.
+pub fn run_migrate(args: &MigrateArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = repo_dir_or_current(&args.path)?;
+
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let report = migrate_cache(&cache)?;
+
+    let rebuilt = if report.rebuild_required && !args.no_rebuild {
+        let result = generate_index_internal(&repo_dir, 10, &[])
+            .map_err(|message| McpDiffError::ExtractionFailure { message })?;
+        Some(result.files_analyzed)
+    } else {
+        None
+    };
+
+    let applied: Vec<String> = report
+        .applied
+        .iter()
+        .map(|v| format_schema_version(*v))
+        .collect();
+
+    let json_value = serde_json::json!({
+        "_type": "cache_migrate",
+        "path": repo_dir.to_string_lossy(),
+        "from_version": report.from_version,
+        "to_version": report.to_version,
+        "applied": applied,
+        "rebuild_required": report.rebuild_required,
+        "rebuilt": rebuilt.is_some(),
+        "files_indexed": rebuilt
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => match &report
+            .from_version
+        {
+            None => {
+                output.push_str(&format!("No index exists for: {}\n", repo_dir.display()));
+            }
+            Some(from) if report.is_noop() => {
+                output.push_str(&format!("Index is up to date (schema {}).\n", from));
+            }
+            Some(from) => {
+                output.push_str(&format!(
+                    "Migrated index schema {} -> {}\n",
+                    from, report.to_version
+                ));
+                if !applied.is_empty() {
+                    output.push_str(&format!("applied: {}\n", applied.join(", ")));
+                }
+                match rebuilt {
+                    Some(files) => {
+                        output.push_str(&format!("Index regenerated ({} files).\n", files))
+                    }
+                    None if report.rebuild_required => output
+                        .push_str("Index discarded; run `semfora index generate` to rebuild it.\n"),
+                    None => {}
+                }
+            }
+        },
+    }
+
+    Ok(output)
+}
+
+/// Check every cached artifact and report its status.
+///
+/// JSON and TOON list every artifact; text output lists only the broken ones.
+pub fn run_verify(args: &CacheVerifyArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = repo_dir_or_current(&args.path)?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let statuses = if cache.exists() {
+        verify_cache(&cache)
+    } else {
+        Vec::new()
+    };
+    let broken: Vec<&ArtifactStatus> = statuses.iter().filter(|s| !s.is_ok()).collect();
+
+    let json_value = serde_json::json!({
+        "_type": "cache_verify",
+        "path": repo_dir.to_string_lossy(),
+        "cache_dir": cache.root.to_string_lossy(),
+        "exists": cache.exists(),
+        "healthy": cache.exists() && broken.is_empty(),
+        "checked": statuses.len(),
+        "broken": broken.len(),
+        "artifacts": statuses
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            if !cache.exists() {
+                output.push_str(&format!("No index exists for: {}\n", repo_dir.display()));
+                return Ok(output);
+            }
+            output.push_str(&format!("cache_dir: {}\n", cache.root.display()));
+            output.push_str(&format!(
+                "checked: {} artifacts, {} broken\n",
+                statuses.len(),
+                broken.len()
+            ));
+            push_artifact_lines(&mut output, &broken);
+            if broken.is_empty() {
+                output.push_str("Cache is healthy.\n");
+            } else {
+                output.push_str("Run `semfora cache repair` to rebuild the broken artifacts.\n");
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Rebuild only the cached artifacts that fail verification.
+///
+/// Broken files are removed first. Overlay layers are cleared, an outdated
+/// overview is migrated and broken graphs are rebuilt from the symbol index;
+/// the index is only regenerated from source when one of its shards is broken.
+pub fn run_repair(args: &CacheRepairArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = repo_dir_or_current(&args.path)?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    if !cache.exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!(
+                "No index exists for {}. Run `semfora index generate` first.",
+                repo_dir.display()
+            ),
+        });
+    }
+
+    let broken: Vec<ArtifactStatus> = verify_cache(&cache)
+        .into_iter()
+        .filter(|s| !s.is_ok())
+        .collect();
+    let plan = RepairPlan::from_statuses(&cache, &broken);
+
+    let mut actions = Vec::new();
+    if !plan.remove.is_empty() {
+        actions.push(format!("removed {} broken artifacts", plan.remove.len()));
+    }
+    if plan.clear_layers {
+        actions.push("cleared overlay layers".to_string());
+    }
+    if plan.migrate {
+        actions.push("migrated index schema".to_string());
+    }
+    if plan.regenerate_graphs {
+        actions.push("regenerated graphs".to_string());
+    }
+
+    let full_rebuild = plan.apply(&cache)?;
+    let files_indexed = if full_rebuild {
+        let result = generate_index_internal(&repo_dir, 10, &[])
+            .map_err(|message| McpDiffError::ExtractionFailure { message })?;
+        if let Ok(sha) = crate::git::git_command(&["rev-parse", "HEAD"], Some(&cache.repo_root)) {
+            let _ = cache.set_indexed_sha(&sha);
+        }
+        actions.push(format!(
+            "regenerated index ({} files)",
+            result.files_analyzed
+        ));
+        Some(result.files_analyzed)
+    } else {
+        None
+    };
+
+    let remaining: Vec<ArtifactStatus> = verify_cache(&cache)
+        .into_iter()
+        .filter(|s| !s.is_ok())
+        .collect();
+
+    let json_value = serde_json::json!({
+        "_type": "cache_repair",
+        "path": repo_dir.to_string_lossy(),
+        "repaired": broken,
+        "actions": actions,
+        "files_indexed": files_indexed,
+        "remaining": remaining
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            if broken.is_empty() {
+                output.push_str("Cache is healthy, nothing to repair.\n");
+                return Ok(output);
+            }
+            output.push_str(&format!("broken: {} artifacts\n", broken.len()));
+            push_artifact_lines(&mut output, &broken.iter().collect::<Vec<_>>());
+            for action in &actions {
+                output.push_str(&format!("  - {}\n", action));
+            }
+            if remaining.is_empty() {
+                output.push_str("Cache repaired.\n");
+            } else {
+                output.push_str(&format!(
+                    "{} artifacts are still broken; run `semfora cache clear` and reindex.\n",
+                    remaining.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Compare two index generations by their manifests.
+///
+/// `manifest_b` defaults to the current index's manifest; `--previous`
+/// compares it with the generation before.
+pub fn run_cache_diff(args: &CacheDiffArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = repo_dir_or_current(&args.path)?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+
+    // Without a manifest A, clap has required --previous
+    let manifest_a = match &args.manifest_a {
+        Some(path) => path.clone(),
+        None => cache
+            .previous_manifest_path()
+            .ok_or_else(|| McpDiffError::QueryError {
+                message: "No earlier index generation is retained; regenerate the index first"
+                    .to_string(),
+            })?,
+    };
+    let manifest_b = match &args.manifest_b {
+        Some(path) => path.clone(),
+        None => cache
+            .current_manifest_path()
+            .ok_or_else(|| McpDiffError::QueryError {
+                message: format!(
+                    "No index manifest for {}; run `semfora-engine index generate` first",
+                    repo_dir.display()
+                ),
+            })?,
+    };
+
+    let diff = diff_manifests(
+        &IndexManifest::load(&manifest_a)?,
+        &IndexManifest::load(&manifest_b)?,
+    );
+    let count = |select: fn(&ModuleDiff) -> usize| diff.modules.iter().map(select).sum::<usize>();
+    let total = |before: usize, after: usize| {
+        serde_json::json!({
+            "before": before,
+            "after": after,
+            "delta": after as i64 - before as i64
+        })
+    };
+
+    let json_value = serde_json::json!({
+        "_type": "cache_diff",
+        "manifest_a": manifest_a.to_string_lossy(),
+        "manifest_b": manifest_b.to_string_lossy(),
+        "identical": diff.is_empty(),
+        "files_added": diff.files_added,
+        "files_removed": diff.files_removed,
+        "files_changed": diff.files_changed,
+        "symbols_added": count(|m| m.added.len()),
+        "symbols_removed": count(|m| m.removed.len()),
+        "symbols_rehashed": count(|m| m.rehashed.len()),
+        "modules": diff.modules,
+        "totals": {
+            "files": total(diff.before.files, diff.after.files),
+            "modules": total(diff.before.modules, diff.after.modules),
+            "symbols": total(diff.before.symbols, diff.after.symbols)
+        }
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════════════════\n");
+            output.push_str("  INDEX DIFF\n");
+            output.push_str("═══════════════════════════════════════════════════════\n\n");
+            output.push_str(&format!("a: {}\n", manifest_a.display()));
+            output.push_str(&format!("b: {}\n", manifest_b.display()));
+            output.push_str(&format!(
+                "symbols: {} -> {}, files: {} -> {}, modules: {} -> {}\n\n",
+                diff.before.symbols,
+                diff.after.symbols,
+                diff.before.files,
+                diff.after.files,
+                diff.before.modules,
+                diff.after.modules
+            ));
+
+            if diff.is_empty() {
+                output.push_str("No changes between the two index generations.\n");
+                return Ok(output);
+            }
+
+            for (label, files) in [
+                ("added", &diff.files_added),
+                ("removed", &diff.files_removed),
+                ("changed", &diff.files_changed),
+            ] {
+                for file in files {
+                    output.push_str(&format!("  {:<8} {}\n", label, file));
+                }
+            }
+
+            for module in &diff.modules {
+                output.push_str(&format!("\n{}:\n", module.module));
+                for (sign, symbols) in [
+                    ("+", &module.added),
+                    ("-", &module.removed),
+                    ("~", &module.rehashed),
+                ] {
+                    for symbol in symbols {
+                        output.push_str(&format!("  {} {}\n", sign, symbol));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn push_artifact_lines(output: &mut String, statuses: &[&ArtifactStatus]) {
+    for status in statuses {
+        output.push_str(&format!("  {} {}", status.state.as_str(), status.path));
+        if let Some(ref detail) = status.detail {
+            output.push_str(&format!(" ({})", detail));
+        }
+        output.push('\n');
+    }
+}
+
+fn repo_dir_or_current(path: &Option<PathBuf>) -> Result<PathBuf> {
+    match path {
+        Some(path) => Ok(path.clone()),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        }),
+    }
+}
@@ -8,10 +8,12 @@ use crate::cli::{CommitArgs, OutputFormat};
 use crate::commands::CommandContext;
 use crate::error::{McpDiffError, Result};
 use crate::git::{
-    get_current_branch, get_last_commit, get_remote_url, get_staged_changes, get_unstaged_changes,
-    is_git_repo, ChangeType, ChangedFile,
+    get_base_branch_status, get_current_branch, get_last_commit, get_remote_url,
+    get_staged_changes, get_unstaged_changes, get_untracked_changes, is_git_repo, ChangeType,
+    ChangedFile,
 };
 use crate::parsing::parse_and_extract;
+use crate::utils::truncate_to_char_boundary;
 use crate::Lang;
 
 /// Run the commit command - prepare information for commit message
@@ -72,10 +74,17 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
         get_unstaged_changes(Some(&repo_dir))?
     };
 
+    // Get untracked files (unless staged_only)
+    let untracked_changes = if args.staged {
+        Vec::new()
+    } else {
+        get_untracked_changes(Some(&repo_dir))?
+    };
+
     // If no changes at all, return early
-    if staged_changes.is_empty() && unstaged_changes.is_empty() {
+    if staged_changes.is_empty() && unstaged_changes.is_empty() && untracked_changes.is_empty() {
         return Ok(format!(
-            "{}note: No changes to commit.\n\nstaged_changes: (none)\nunstaged_changes: (none)\n",
+            "{}note: No changes to commit.\n\nstaged_changes: (none)\nunstaged_changes: (none)\nuntracked_changes: (none)\n",
             super::toon_header("prep_commit")
         ));
     }
@@ -99,12 +108,31 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
         include_complexity,
         include_all_metrics,
     );
+    let untracked_files = analyze_changed_files(
+        &untracked_changes,
+        &repo_dir,
+        show_diff_stats,
+        include_complexity,
+        include_all_metrics,
+    );
+
+    let staged_symbol_count: usize = staged_files.iter().map(|f| f.symbols.len()).sum();
+    let unstaged_symbol_count: usize = unstaged_files.iter().map(|f| f.symbols.len()).sum();
+    let untracked_symbol_count: usize = untracked_files.iter().map(|f| f.symbols.len()).sum();
+
+    // Base branch context is optional: shallow clones and repos without a
+    // main branch still get the rest of the report
+    let base_status = get_base_branch_status(Some(&repo_dir));
+    let shallow_fetch = match &base_status {
+        Err(McpDiffError::ShallowClone { fetch_command, .. }) => Some(fetch_command.clone()),
+        _ => None,
+    };
 
     // Format output
     let mut output = String::new();
 
     // Build JSON value for all formats
-    let json_value = serde_json::json!({
+    let mut json_value = serde_json::json!({
         "_type": "prep_commit",
         "_note": "Information for commit message. This tool DOES NOT commit.",
         "git_context": {
@@ -115,11 +143,29 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
         },
         "summary": {
             "staged_files": staged_files.len(),
-            "unstaged_files": unstaged_files.len()
+            "staged_symbols": staged_symbol_count,
+            "unstaged_files": unstaged_files.len(),
+            "unstaged_symbols": unstaged_symbol_count,
+            "untracked_files": untracked_files.len(),
+            "untracked_symbols": untracked_symbol_count
         },
         "staged_changes": staged_files,
-        "unstaged_changes": unstaged_files
+        "unstaged_changes": unstaged_files,
+        "untracked_changes": untracked_files
     });
+    if let Some(git_context) = json_value["git_context"].as_object_mut() {
+        if let Ok(status) = &base_status {
+            git_context.insert("base_branch".into(), status.base_branch.clone().into());
+            git_context.insert("commits_ahead".into(), status.commits_ahead.into());
+        }
+        if let Some(fetch_command) = &shallow_fetch {
+            git_context.insert("shallow_clone".into(), true.into());
+            git_context.insert(
+                "_shallow_note".into(),
+                format!("base branch history not fetched; run `{}`", fetch_command).into(),
+            );
+        }
+    }
 
     match ctx.format {
         OutputFormat::Json => {
@@ -128,7 +174,7 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&super::toon_header("prep_commit"));
             output
                 .push_str("_note: Information for commit message. This tool DOES NOT commit.\n\n");
@@ -144,23 +190,36 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
             }
             if let Some(ref m) = last_commit_message {
                 let truncated = if m.len() > 60 {
-                    format!("{}...", &m[..57])
+                    format!("{}...", truncate_to_char_boundary(m, 57))
                 } else {
                     m.clone()
                 };
                 output.push_str(&format!("  last_message: \"{}\"\n", truncated));
             }
+            if let Ok(status) = &base_status {
+                output.push_str(&format!("  base_branch: \"{}\"\n", status.base_branch));
+                output.push_str(&format!("  commits_ahead: {}\n", status.commits_ahead));
+            }
+            if let Some(fetch_command) = &shallow_fetch {
+                output.push_str("  shallow_clone: true\n");
+                output.push_str(&format!(
+                    "  _shallow_note: \"base branch history not fetched; run `{}`\"\n",
+                    fetch_command
+                ));
+            }
             output.push('\n');
 
             // Summary
-            let staged_symbol_count: usize = staged_files.iter().map(|f| f.symbols.len()).sum();
-            let unstaged_symbol_count: usize = unstaged_files.iter().map(|f| f.symbols.len()).sum();
-
             output.push_str("summary:\n");
             output.push_str(&format!("  staged_files: {}\n", staged_files.len()));
             output.push_str(&format!("  staged_symbols: {}\n", staged_symbol_count));
             output.push_str(&format!("  unstaged_files: {}\n", unstaged_files.len()));
             output.push_str(&format!("  unstaged_symbols: {}\n", unstaged_symbol_count));
+            output.push_str(&format!("  untracked_files: {}\n", untracked_files.len()));
+            output.push_str(&format!(
+                "  untracked_symbols: {}\n",
+                untracked_symbol_count
+            ));
 
             if show_diff_stats {
                 let staged_insertions: usize = staged_files.iter().map(|f| f.insertions).sum();
@@ -197,8 +256,23 @@ pub fn run_commit(args: &CommitArgs, ctx: &CommandContext) -> Result<String> {
                     include_complexity,
                     include_all_metrics,
                 );
+                output.push('\n');
+            } else {
+                output.push_str("unstaged_changes: (none)\n\n");
+            }
+
+            // Untracked files (new, not yet added)
+            if !untracked_files.is_empty() {
+                output.push_str(&format!("untracked_changes[{}]:\n", untracked_files.len()));
+                format_file_list(
+                    &untracked_files,
+                    &mut output,
+                    show_diff_stats,
+                    include_complexity,
+                    include_all_metrics,
+                );
             } else {
-                output.push_str("unstaged_changes: (none)\n");
+                output.push_str("untracked_changes: (none)\n");
             }
         }
     }
@@ -258,11 +332,16 @@ fn analyze_changed_files(
             let file_path = repo_dir.join(&changed_file.path);
             let change_type_str = format!("{:?}", changed_file.change_type);
 
-            // Get diff stats
-            let (insertions, deletions) = if show_diff_stats {
-                get_diff_stats(repo_dir, &changed_file.path)
-            } else {
+            // Get diff stats; an untracked file is all insertions
+            let (insertions, deletions) = if !show_diff_stats {
                 (0, 0)
+            } else if changed_file.change_type == ChangeType::Untracked {
+                let lines = fs::read_to_string(&file_path)
+                    .map(|s| s.lines().count())
+                    .unwrap_or(0);
+                (lines, 0)
+            } else {
+                get_diff_stats(repo_dir, &changed_file.path)
             };
 
             // Skip deleted files
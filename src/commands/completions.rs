@@ -0,0 +1,101 @@
+//! Completions command handler - Shell completion scripts
+//!
+//! The printed script registers the binary as its own completer: each time
+//! the shell completes, it runs the binary with [`COMPLETE_ENV`] set, which
+//! [`complete_from_env`] answers from the `Cli` definition. Values like
+//! `--module` complete from the index of the current directory's repository
+//! ([`complete_modules`]); without an index they complete like any other
+//! free-form value.
+
+use std::ffi::OsStr;
+
+use clap::CommandFactory;
+use clap_complete::env::Shells;
+use clap_complete::{CompleteEnv, CompletionCandidate};
+
+use crate::cache::CacheDir;
+use crate::cli::{Cli, CompletionShell, CompletionsArgs};
+use crate::error::{McpDiffError, Result};
+
+/// Set by the completion script when it asks the binary for completions
+pub const COMPLETE_ENV: &str = "SEMFORA_COMPLETE";
+
+/// Binary the script completes when it can't tell how it was invoked
+const DEFAULT_BIN: &str = "semfora-engine";
+
+/// Print the completion script for a shell
+pub fn run_completions(args: &CompletionsArgs) -> Result<String> {
+    let shell = match args.shell {
+        CompletionShell::Bash => "bash",
+        CompletionShell::Zsh => "zsh",
+        CompletionShell::Fish => "fish",
+        CompletionShell::Powershell => "powershell",
+    };
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(shell)
+        .ok_or_else(|| McpDiffError::ConfigError {
+            message: format!("no completion support for {}", shell),
+        })?;
+
+    // Complete the name the binary was run as, by calling this very binary
+    let bin = std::env::args_os()
+        .next()
+        .as_deref()
+        .and_then(|arg| std::path::Path::new(arg).file_name())
+        .and_then(OsStr::to_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| DEFAULT_BIN.to_string());
+    let exe = std::env::current_exe().map_err(|e| McpDiffError::IoError {
+        path: std::path::PathBuf::from(&bin),
+        message: e.to_string(),
+    })?;
+
+    let mut script = Vec::new();
+    completer
+        .write_registration(
+            COMPLETE_ENV,
+            Cli::command().get_name(),
+            &bin,
+            &exe.to_string_lossy(),
+            &mut script,
+        )
+        .map_err(|e| McpDiffError::IoError {
+            path: exe.clone(),
+            message: e.to_string(),
+        })?;
+    Ok(String::from_utf8_lossy(&script).into_owned())
+}
+
+/// Answer a completion request from the script and exit, if this run is one
+///
+/// Must run before anything is written to stdout.
+pub fn complete_from_env() {
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_ENV)
+        .complete();
+}
+
+/// Module names from the index of the current directory's repository
+///
+/// Completes nothing when the repository isn't indexed.
+pub fn complete_modules(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let Some(cache) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| CacheDir::for_repo(&dir).ok())
+        .filter(CacheDir::exists)
+    else {
+        return Vec::new();
+    };
+
+    let mut modules = cache.list_modules();
+    modules.sort();
+    modules
+        .into_iter()
+        .filter(|module| module.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
@@ -0,0 +1,30 @@
+//! Config command handler
+//!
+//! `config show`, `set` and `reset` manage the global config file through the
+//! installer. `config show --effective` is handled here: it prints the
+//! analysis defaults in effect for a repository (see [`crate::settings`]).
+
+use std::path::Path;
+
+use crate::cli::OutputFormat;
+use crate::commands::CommandContext;
+use crate::error::{McpDiffError, Result};
+use crate::settings::RepoSettings;
+
+/// Print the merged global and repo settings for `path` (default: the
+/// current directory), with the source of each value
+pub fn run_config_effective(path: Option<&Path>, ctx: &CommandContext) -> Result<String> {
+    let path = match path {
+        Some(p) => p.to_path_buf(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let settings = RepoSettings::load(&path)?;
+
+    Ok(match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&settings.to_json()).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&settings.to_json()),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => settings.display(),
+    })
+}
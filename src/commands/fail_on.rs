@@ -0,0 +1,347 @@
+//! `validate --fail-on` rules for CI gating
+//!
+//! A rule is `<category>:<condition>`:
+//!
+//! - `complexity:<metric><op><n>` - metric is `cognitive`, `cyclomatic`, or
+//!   `nesting`. The index stores one complexity score per symbol, so
+//!   `cyclomatic` is checked against the same value as `cognitive`.
+//! - `impact:callers<op><n>` - number of distinct direct callers
+//! - `duplicates:new` - a duplicate involving a symbol added or modified since
+//!   the merge base with `--base`
+//!
+//! `<op>` is one of `>`, `>=`, `<`, `<=`, `==`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+const CATEGORIES: &str = "complexity, duplicates, impact";
+const COMPLEXITY_METRICS: &str = "cognitive, cyclomatic, nesting";
+const OPERATORS: &str = ">, >=, <, <=, ==";
+
+/// Symbol metric compared against a threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleMetric {
+    Cognitive,
+    Cyclomatic,
+    Nesting,
+    Callers,
+}
+
+impl RuleMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleMetric::Cognitive => "cognitive",
+            RuleMetric::Cyclomatic => "cyclomatic",
+            RuleMetric::Nesting => "nesting",
+            RuleMetric::Callers => "callers",
+        }
+    }
+
+    fn category(&self) -> &'static str {
+        match self {
+            RuleMetric::Callers => "impact",
+            _ => "complexity",
+        }
+    }
+}
+
+/// Comparison operator of a threshold rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+impl Comparison {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Eq => "==",
+        }
+    }
+
+    /// Whether `value <op> threshold` holds
+    pub fn holds(&self, value: usize, threshold: usize) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Ge => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Le => value <= threshold,
+            Comparison::Eq => value == threshold,
+        }
+    }
+}
+
+/// A parsed `--fail-on` rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailOnRule {
+    /// A symbol metric crosses a threshold
+    Threshold {
+        metric: RuleMetric,
+        op: Comparison,
+        value: usize,
+    },
+    /// A duplicate introduced relative to the base ref
+    NewDuplicates,
+}
+
+impl fmt::Display for FailOnRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailOnRule::Threshold { metric, op, value } => write!(
+                f,
+                "{}:{}{}{}",
+                metric.category(),
+                metric.as_str(),
+                op.as_str(),
+                value
+            ),
+            FailOnRule::NewDuplicates => write!(f, "duplicates:new"),
+        }
+    }
+}
+
+impl FromStr for FailOnRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        if rule.is_empty() {
+            return Err(format!(
+                "empty rule; expected `<category>:<condition>` (categories: {})",
+                CATEGORIES
+            ));
+        }
+
+        let Some((category, condition)) = rule.split_once(':') else {
+            return Err(format!(
+                "`{}` has no category; expected `<category>:<condition>` (categories: {})",
+                rule, CATEGORIES
+            ));
+        };
+        let condition = condition.trim();
+
+        match category.trim() {
+            "complexity" => {
+                let (metric, op, value) = parse_threshold(rule, condition)?;
+                let metric = match metric {
+                    "cognitive" => RuleMetric::Cognitive,
+                    "cyclomatic" => RuleMetric::Cyclomatic,
+                    "nesting" => RuleMetric::Nesting,
+                    other => {
+                        return Err(format!(
+                            "unknown complexity metric `{}` in `{}`; expected one of: {}",
+                            other, rule, COMPLEXITY_METRICS
+                        ))
+                    }
+                };
+                Ok(FailOnRule::Threshold { metric, op, value })
+            }
+            "impact" => {
+                let (metric, op, value) = parse_threshold(rule, condition)?;
+                if metric != "callers" {
+                    return Err(format!(
+                        "unknown impact metric `{}` in `{}`; expected: callers",
+                        metric, rule
+                    ));
+                }
+                Ok(FailOnRule::Threshold {
+                    metric: RuleMetric::Callers,
+                    op,
+                    value,
+                })
+            }
+            "duplicates" => match condition {
+                "new" => Ok(FailOnRule::NewDuplicates),
+                other => Err(format!(
+                    "unknown duplicates condition `{}` in `{}`; expected: new",
+                    other, rule
+                )),
+            },
+            other => Err(format!(
+                "unknown rule category `{}` in `{}`; expected one of: {}",
+                other, rule, CATEGORIES
+            )),
+        }
+    }
+}
+
+/// Split `<metric><op><n>` into its parts
+fn parse_threshold<'a>(
+    rule: &str,
+    condition: &'a str,
+) -> Result<(&'a str, Comparison, usize), String> {
+    let Some(op_start) = condition.find(['>', '<', '=', '!']) else {
+        return Err(format!(
+            "`{}` has no comparison; expected e.g. `{}>15` (operators: {})",
+            rule, rule, OPERATORS
+        ));
+    };
+    let metric = condition[..op_start].trim();
+    if metric.is_empty() {
+        return Err(format!("`{}` has no metric before the operator", rule));
+    }
+
+    let rest = &condition[op_start..];
+    let (op, threshold) = [
+        (">=", Comparison::Ge),
+        ("<=", Comparison::Le),
+        ("==", Comparison::Eq),
+        (">", Comparison::Gt),
+        ("<", Comparison::Lt),
+    ]
+    .iter()
+    .find_map(|(token, op)| rest.strip_prefix(token).map(|t| (*op, t.trim())))
+    .ok_or_else(|| {
+        let token: String = rest.chars().take_while(|c| "<>=!".contains(*c)).collect();
+        format!(
+            "unknown operator `{}` in `{}`; expected one of: {}",
+            token, rule, OPERATORS
+        )
+    })?;
+
+    if threshold.is_empty() {
+        return Err(format!(
+            "`{}` has no threshold after `{}`",
+            rule,
+            op.as_str()
+        ));
+    }
+    let value = threshold.parse::<usize>().map_err(|_| {
+        format!(
+            "threshold `{}` in `{}` is not a non-negative integer",
+            threshold, rule
+        )
+    })?;
+
+    Ok((metric, op, value))
+}
+
+/// A symbol that tripped a `--fail-on` rule
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleViolation {
+    /// The rule as written in canonical form
+    pub rule: String,
+    pub symbol: String,
+    pub hash: String,
+    pub file: String,
+    pub lines: String,
+    /// Measured value for threshold rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<usize>,
+    /// Human-readable reason
+    pub detail: String,
+}
+
+/// Render the final `violations[N]:` section of a text/TOON report
+pub fn format_violations(violations: &[RuleViolation]) -> String {
+    let mut output = format!("violations[{}]:\n", violations.len());
+    for v in violations {
+        output.push_str(&format!(
+            "  - {} {} ({}:{}) {}\n",
+            v.rule, v.symbol, v.file, v.lines, v.detail
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(rule: &str) -> Result<FailOnRule, String> {
+        rule.parse()
+    }
+
+    #[test]
+    fn test_parse_valid_rules() {
+        assert_eq!(
+            parse("complexity:cyclomatic>15").unwrap(),
+            FailOnRule::Threshold {
+                metric: RuleMetric::Cyclomatic,
+                op: Comparison::Gt,
+                value: 15
+            }
+        );
+        assert_eq!(
+            parse(" impact:callers >= 50 ").unwrap(),
+            FailOnRule::Threshold {
+                metric: RuleMetric::Callers,
+                op: Comparison::Ge,
+                value: 50
+            }
+        );
+        assert_eq!(parse("duplicates:new").unwrap(), FailOnRule::NewDuplicates);
+        assert_eq!(
+            parse("complexity:nesting==4").unwrap().to_string(),
+            "complexity:nesting==4"
+        );
+        assert_eq!(
+            parse("impact:callers > 50").unwrap().to_string(),
+            "impact:callers>50"
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_rules() {
+        let cases = [
+            ("", "empty rule"),
+            ("cyclomatic>15", "has no category"),
+            ("speed:cyclomatic>15", "unknown rule category `speed`"),
+            (
+                "complexity:halstead>15",
+                "unknown complexity metric `halstead`",
+            ),
+            ("impact:callees>5", "unknown impact metric `callees`"),
+            ("complexity:cyclomatic", "has no comparison"),
+            ("complexity:>15", "has no metric"),
+            ("complexity:cyclomatic=>15", "unknown operator `=>`"),
+            ("complexity:cyclomatic!=15", "unknown operator `!=`"),
+            ("complexity:cyclomatic>", "has no threshold"),
+            ("complexity:cyclomatic>-1", "threshold `-1`"),
+            ("complexity:cyclomatic>>15", "threshold `>15`"),
+            ("complexity:cyclomatic>1.5", "threshold `1.5`"),
+            ("duplicates:old", "unknown duplicates condition `old`"),
+        ];
+        for (rule, expected) in cases {
+            let err = parse(rule).expect_err(rule);
+            assert!(err.contains(expected), "{rule}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_comparison_holds() {
+        assert!(Comparison::Gt.holds(16, 15));
+        assert!(!Comparison::Gt.holds(15, 15));
+        assert!(Comparison::Ge.holds(15, 15));
+        assert!(Comparison::Lt.holds(2, 3));
+        assert!(Comparison::Le.holds(3, 3));
+        assert!(Comparison::Eq.holds(3, 3));
+    }
+
+    #[test]
+    fn test_format_violations() {
+        let output = format_violations(&[RuleViolation {
+            rule: "complexity:cyclomatic>15".to_string(),
+            symbol: "parse".to_string(),
+            hash: "abc".to_string(),
+            file: "src/parse.ts".to_string(),
+            lines: "1-40".to_string(),
+            value: Some(22),
+            detail: "cyclomatic=22".to_string(),
+        }]);
+        assert_eq!(
+            output,
+            "violations[1]:\n  - complexity:cyclomatic>15 parse (src/parse.ts:1-40) cyclomatic=22\n"
+        );
+        assert_eq!(format_violations(&[]), "violations[0]:\n");
+    }
+}
@@ -7,15 +7,20 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::cache::CacheDir;
-use crate::cli::{IndexArgs, IndexOperation, OutputFormat};
-use crate::commands::CommandContext;
+use crate::cli::{IndexArgs, IndexOperation, OutputFormat, ProgressFormat};
+use crate::commands::{CommandContext, ProgressSink};
 use crate::error::{McpDiffError, Result};
-use crate::indexing::{analyze_files_parallel, IndexingProgressCallback};
-use crate::shard::{ShardProgressCallback, ShardWriter};
+use crate::extract::ExtractionLevel;
+use crate::indexing::{analyze_files_parallel_with_level, IndexingProgressCallback};
+use crate::overlay::CompactionStats;
+use crate::ownership::OwnershipIndex;
+use crate::server::ServerState;
+use crate::shard::{ShardNamingStrategy, ShardProgressCallback, ShardWriter};
 use crate::Lang;
 
 struct ProgressState {
     last_line_len: usize,
+    last_current: usize,
     last_size_check: Instant,
     last_mem_check: Instant,
     last_size_bytes: u64,
@@ -28,15 +33,20 @@ struct ProgressState {
 
 struct ProgressReporter {
     cache_root: PathBuf,
+    format: ProgressFormat,
+    sink: Option<ProgressSink>,
     state: Mutex<ProgressState>,
 }
 
 impl ProgressReporter {
-    fn new(cache_root: PathBuf) -> Self {
+    fn new(cache_root: PathBuf, format: ProgressFormat, sink: Option<ProgressSink>) -> Self {
         Self {
             cache_root,
+            format,
+            sink,
             state: Mutex::new(ProgressState {
                 last_line_len: 0,
+                last_current: 0,
                 last_size_check: Instant::now()
                     .checked_sub(Duration::from_secs(5))
                     .unwrap_or_else(Instant::now),
@@ -71,6 +81,22 @@ impl ProgressReporter {
             }
             state.current_step = step.to_string();
             state.step_start = now;
+            state.last_current = 0;
+        }
+
+        if let Some(sink) = &self.sink {
+            sink.report(step, current, total);
+            return;
+        }
+
+        if self.format == ProgressFormat::Json {
+            // Parallel workers can report out of order; keep each phase monotonic
+            if current < state.last_current {
+                return;
+            }
+            state.last_current = current;
+            super::emit_progress_event(step, current, total);
+            return;
         }
 
         if now.duration_since(state.last_mem_check) >= Duration::from_millis(500) {
@@ -148,7 +174,9 @@ impl ProgressReporter {
             })
             .to_string(),
         );
-        eprintln!();
+        if self.format == ProgressFormat::Text && self.sink.is_none() {
+            eprintln!();
+        }
     }
 }
 
@@ -239,12 +267,18 @@ pub fn run_index(args: &IndexArgs, ctx: &CommandContext) -> Result<String> {
             incremental,
             max_depth,
             extensions,
+            level,
+            shard_naming,
+            with_blame,
         } => run_generate(
             path.clone(),
             *force,
             *incremental,
             *max_depth,
             extensions.clone(),
+            *level,
+            *shard_naming,
+            *with_blame,
             ctx,
         ),
         IndexOperation::Check {
@@ -252,16 +286,21 @@ pub fn run_index(args: &IndexArgs, ctx: &CommandContext) -> Result<String> {
             max_age,
         } => run_check(*auto_refresh, *max_age, ctx),
         IndexOperation::Export { path } => run_export(path.clone(), ctx),
+        IndexOperation::Compact { path } => run_compact(path.clone(), ctx),
     }
 }
 
 /// Generate or refresh the semantic index
+#[allow(clippy::too_many_arguments)]
 fn run_generate(
     path: Option<PathBuf>,
     force: bool,
     incremental: bool,
     max_depth: usize,
     extensions: Vec<String>,
+    level: ExtractionLevel,
+    shard_naming: Option<ShardNamingStrategy>,
+    with_blame: bool,
     ctx: &CommandContext,
 ) -> Result<String> {
     let repo_dir =
@@ -269,6 +308,11 @@ fn run_generate(
 
     let cache = CacheDir::for_repo(&repo_dir)?;
 
+    // A cache built from a different repository at this path is never reused
+    let foreign = cache.exists() && !cache.fingerprint_matches();
+    // So is one built under different index settings in the config
+    let force = force || foreign || (cache.exists() && !cache.settings_match());
+
     // Check if we should skip (unless force)
     if !force && cache.exists() {
         // Check freshness
@@ -292,24 +336,44 @@ fn run_generate(
         }
     }
 
-    if incremental && cache.exists() {
+    if incremental && cache.exists() && !foreign {
         // For incremental, just check if anything changed
         // For now, we just regenerate if forced or no cache exists
         return Ok("Incremental mode: Index exists. Use --force to regenerate.".to_string());
     }
 
-    run_full_index(&repo_dir, &cache, max_depth, &extensions, ctx, force)
+    run_full_index(
+        &repo_dir,
+        &cache,
+        max_depth,
+        &extensions,
+        level,
+        shard_naming,
+        with_blame,
+        ctx,
+        force,
+    )
 }
 
 /// Run full index generation
+#[allow(clippy::too_many_arguments)]
 fn run_full_index(
     repo_dir: &std::path::Path,
     cache: &CacheDir,
     max_depth: usize,
     extensions: &[String],
+    level: ExtractionLevel,
+    shard_naming: Option<ShardNamingStrategy>,
+    with_blame: bool,
     ctx: &CommandContext,
     force: bool,
 ) -> Result<String> {
+    // Regenerating keeps the naming the index was written with
+    let shard_naming = shard_naming.unwrap_or_else(|| cache.shard_naming());
+
+    // Another indexer writing this cache would interleave shards with ours
+    let _lock = cache.lock_index()?;
+
     // Clear existing cache
     if cache.exists() {
         let progress_path = cache.root.join("progress.json");
@@ -318,8 +382,12 @@ fn run_full_index(
         }
     }
 
-    let reporter = if ctx.progress {
-        Some(Arc::new(ProgressReporter::new(cache.root.clone())))
+    let reporter = if ctx.progress || ctx.progress_sink.is_some() {
+        Some(Arc::new(ProgressReporter::new(
+            cache.root.clone(),
+            ctx.progress_format,
+            ctx.progress_sink.clone(),
+        )))
     } else {
         None
     };
@@ -329,7 +397,9 @@ fn run_full_index(
     }
 
     // Collect files
-    let files = collect_files(repo_dir, max_depth, extensions)?;
+    let mut files = collect_files(repo_dir, max_depth, extensions)?;
+    let settings = ctx.repo_settings(repo_dir);
+    files.retain(|file| !settings.is_excluded(file));
 
     if let Some(reporter) = &reporter {
         reporter.update("Collecting files", 1, 1);
@@ -345,6 +415,8 @@ fn run_full_index(
 
     // Create shard writer (takes repo path)
     let mut writer = ShardWriter::new(repo_dir)?;
+    writer.set_naming_strategy(shard_naming);
+    writer.set_with_blame(with_blame);
 
     // Process files in parallel (DEDUP-102: fixes the parallelism bug)
     // Previously used sequential for loop, now uses Rayon par_iter()
@@ -357,7 +429,7 @@ fn run_full_index(
         None
     };
 
-    let result = analyze_files_parallel(&files, progress_cb, ctx.verbose);
+    let result = analyze_files_parallel_with_level(&files, progress_cb, ctx.verbose, level);
     let summaries = result.summaries;
     let errors = result.errors;
 
@@ -386,11 +458,14 @@ fn run_full_index(
         "_type": "index_generate",
         "action": "generate",
         "path": repo_dir.to_string_lossy(),
+        "level": level.as_str(),
         "files_found": files.len(),
         "files_processed": summaries.len(),
         "errors": errors,
         "modules": stats.modules_written,
-        "symbols": stats.symbols_written
+        "symbols": stats.symbols_written,
+        "shard_naming": shard_naming.as_str(),
+        "shard_name_collisions": stats.collision_count
     });
 
     match ctx.format {
@@ -400,14 +475,22 @@ fn run_full_index(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("Index generation complete:\n");
             output.push_str(&format!("  path: {}\n", repo_dir.display()));
+            output.push_str(&format!("  level: {}\n", level.as_str()));
             output.push_str(&format!("  files_found: {}\n", files.len()));
             output.push_str(&format!("  files_processed: {}\n", summaries.len()));
             output.push_str(&format!("  errors: {}\n", errors));
             output.push_str(&format!("  modules: {}\n", stats.modules_written));
             output.push_str(&format!("  symbols: {}\n", stats.symbols_written));
+            output.push_str(&format!("  shard_naming: {}\n", shard_naming.as_str()));
+            if stats.collision_count > 0 {
+                output.push_str(&format!(
+                    "  shard_name_collisions: {}\n",
+                    stats.collision_count
+                ));
+            }
         }
     }
 
@@ -424,7 +507,17 @@ fn run_check(auto_refresh: bool, max_age: u64, ctx: &CommandContext) -> Result<S
 
     if !cache.exists() {
         if auto_refresh {
-            return run_full_index(&repo_dir, &cache, 10, &[], ctx, false);
+            return run_full_index(
+                &repo_dir,
+                &cache,
+                10,
+                &[],
+                ExtractionLevel::Full,
+                None,
+                false,
+                ctx,
+                false,
+            );
         }
         return Ok("No index found. Run `semfora index generate` to create one.".to_string());
     }
@@ -433,7 +526,17 @@ fn run_check(auto_refresh: bool, max_age: u64, ctx: &CommandContext) -> Result<S
     let meta_path = cache.root.join("meta.json");
     if !meta_path.exists() {
         if auto_refresh {
-            return run_full_index(&repo_dir, &cache, 10, &[], ctx, false);
+            return run_full_index(
+                &repo_dir,
+                &cache,
+                10,
+                &[],
+                ExtractionLevel::Full,
+                None,
+                false,
+                ctx,
+                false,
+            );
         }
         return Ok(
             "Index metadata not found. Run `semfora index generate` to regenerate.".to_string(),
@@ -460,7 +563,19 @@ fn run_check(auto_refresh: bool, max_age: u64, ctx: &CommandContext) -> Result<S
 
     if is_stale && auto_refresh {
         eprintln!("Index is stale. Refreshing...");
-        return run_full_index(&repo_dir, &cache, 10, &[], ctx, false);
+        // Keep blame authors if the stale index had them
+        let with_blame = OwnershipIndex::load(&cache).is_ok_and(|ownership| ownership.blame);
+        return run_full_index(
+            &repo_dir,
+            &cache,
+            10,
+            &[],
+            ExtractionLevel::Full,
+            None,
+            with_blame,
+            ctx,
+            false,
+        );
     }
 
     let mut output = String::new();
@@ -480,7 +595,7 @@ fn run_check(auto_refresh: bool, max_age: u64, ctx: &CommandContext) -> Result<S
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!("path: {}\n", repo_dir.display()));
             output.push_str(&format!("indexed_at: {}\n", indexed_at));
             output.push_str(&format!(
@@ -520,7 +635,7 @@ fn run_export(path: Option<String>, ctx: &CommandContext) -> Result<String> {
     }
 
     let exporter = SqliteExporter::new();
-    let stats = exporter.export(&cache, &output_path, None, false)?;
+    let stats = exporter.export(&cache, &output_path, ctx.export_progress(), false)?;
 
     let mut output = String::new();
 
@@ -539,7 +654,7 @@ fn run_export(path: Option<String>, ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("Export complete:\n");
             output.push_str(&format!("  path: {}\n", output_path.display()));
             output.push_str(&format!("  nodes: {}\n", stats.nodes_inserted));
@@ -551,6 +666,78 @@ fn run_export(path: Option<String>, ctx: &CommandContext) -> Result<String> {
     Ok(output)
 }
 
+/// Compact the persisted overlay layers
+fn run_compact(path: Option<PathBuf>, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p,
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+
+    if !crate::server::is_worktree_clean(&repo_dir) {
+        return Err(McpDiffError::GitError {
+            message: "Working tree has uncommitted changes; commit or stash them before compacting"
+                .to_string(),
+        });
+    }
+
+    let Some(index) = cache.load_layered_index()? else {
+        return Ok(
+            "No cached layers to compact. Layers are persisted by `semfora serve`.".to_string(),
+        );
+    };
+
+    let state = ServerState::with_index(repo_dir.clone(), index);
+    state.set_cache_dir(cache);
+    let stats = state.compact(crate::server::compaction_target(&repo_dir))?;
+
+    Ok(format_compaction(&stats, ctx.format))
+}
+
+/// Render compaction results with before/after layer statistics
+pub(crate) fn format_compaction(stats: &CompactionStats, format: OutputFormat) -> String {
+    let mut json_value = serde_json::to_value(stats).unwrap_or_default();
+    json_value["_type"] = serde_json::json!("index_compact");
+
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            let mut output = String::new();
+            if stats.is_noop() {
+                output.push_str("Nothing to compact.\n");
+            } else {
+                output.push_str(&format!("Compacted layers into {}:\n", stats.target));
+                output.push_str(&format!("  symbols merged: {}\n", stats.symbols_merged));
+                output.push_str(&format!(
+                    "  tombstones dropped: {}\n",
+                    stats.tombstones_dropped
+                ));
+                output.push_str(&format!("  moves folded: {}\n", stats.moves_folded));
+                output.push_str(&format!(
+                    "  affected files: {}\n",
+                    stats.affected_files.len()
+                ));
+            }
+            for (label, layer_stats) in [("before", &stats.before), ("after", &stats.after)] {
+                output.push_str(&format!(
+                    "  {}: base {}, branch {}, working {}, ai {}, deleted {}, moves {}\n",
+                    label,
+                    layer_stats.base_symbols,
+                    layer_stats.branch_symbols,
+                    layer_stats.working_symbols,
+                    layer_stats.ai_symbols,
+                    layer_stats.total_deleted,
+                    layer_stats.total_moves
+                ));
+            }
+            output
+        }
+    }
+}
+
 // ============================================
 // Helper Functions
 // ============================================
@@ -1,15 +1,48 @@
 //! Lint command handler - Run linters, formatters, and type checkers
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use crate::cli::{LintArgs, LintOperation, OutputFormat};
 use crate::commands::CommandContext;
 use crate::error::Result;
-use crate::lint::{detect_linters, get_recommendations, DetectedLinter};
+use crate::lang::Lang;
+use crate::line_symbols::LineSymbolIndex;
+use crate::lint::{
+    detect_linters_for, get_recommendations, DetectedLinter, LintIssue, LintResults,
+    LintRunOptions, LintSeverity, Linter,
+};
 
 /// Run the lint command
 pub fn run_lint(args: &LintArgs, ctx: &CommandContext) -> Result<String> {
-    match &args.operation {
+    let default_operation;
+    let operation = match &args.operation {
+        Some(operation) => operation,
+        None => {
+            default_operation = if args.fix {
+                LintOperation::Fix {
+                    path: None,
+                    linter: None,
+                    dry_run: false,
+                    safe_only: false,
+                    languages: Vec::new(),
+                }
+            } else {
+                LintOperation::Scan {
+                    path: None,
+                    linter: None,
+                    severity: None,
+                    limit: 100,
+                    file: None,
+                    fixable_only: false,
+                    languages: Vec::new(),
+                }
+            };
+            &default_operation
+        }
+    };
+
+    match operation {
         LintOperation::Scan {
             path,
             linter,
@@ -17,14 +50,25 @@ pub fn run_lint(args: &LintArgs, ctx: &CommandContext) -> Result<String> {
             limit,
             file,
             fixable_only,
-        } => run_lint_scan(path, linter, severity, *limit, file, *fixable_only, ctx),
+            languages,
+        } => run_lint_scan(
+            path,
+            linter,
+            severity,
+            *limit,
+            file,
+            *fixable_only,
+            languages,
+            ctx,
+        ),
 
         LintOperation::Fix {
             path,
             linter,
             dry_run,
             safe_only,
-        } => run_lint_fix(path, linter, *dry_run, *safe_only, ctx),
+            languages,
+        } => run_lint_fix(path, linter, *dry_run, *safe_only, languages, ctx),
 
         LintOperation::Typecheck {
             path,
@@ -32,13 +76,19 @@ pub fn run_lint(args: &LintArgs, ctx: &CommandContext) -> Result<String> {
             limit,
         } => run_typecheck(path, checker, *limit, ctx),
 
-        LintOperation::Detect { path } => run_detect_linters(path, ctx),
+        LintOperation::Detect { path, languages } => run_detect_linters(path, languages, ctx),
 
         LintOperation::Recommend { path } => run_lint_recommend(path, ctx),
     }
 }
 
+/// `--lang` values as a run filter; none given means every language
+fn language_filter(languages: &[Lang]) -> Option<Vec<Lang>> {
+    (!languages.is_empty()).then(|| languages.to_vec())
+}
+
 /// Scan for lint issues
+#[allow(clippy::too_many_arguments)]
 fn run_lint_scan(
     path: &Option<PathBuf>,
     linter: &Option<String>,
@@ -46,6 +96,7 @@ fn run_lint_scan(
     limit: usize,
     _file: &Option<String>,
     fixable_only: bool,
+    languages: &[Lang],
     ctx: &CommandContext,
 ) -> Result<String> {
     use crate::lint::{run_lint, LintRunOptions, LintSeverity, Linter};
@@ -62,6 +113,7 @@ fn run_lint_scan(
 
     let options = LintRunOptions {
         linter: target_linter,
+        linters: None,
         path_filter: None,
         severity_filter,
         limit: Some(limit),
@@ -69,10 +121,16 @@ fn run_lint_scan(
         fix: false,
         dry_run: false,
         safe_only: false,
+        languages: language_filter(languages),
     };
 
     // Run linters
-    let results = run_lint(&project_dir, &options)?;
+    let mut results = run_lint(&project_dir, &options)?;
+    if let Some(mut symbols) = LineSymbolIndex::for_repo(&project_dir) {
+        for issue in &mut results.issues {
+            issue.symbol = symbols.resolve(&issue.file, issue.line);
+        }
+    }
 
     let mut output = String::new();
 
@@ -91,16 +149,20 @@ fn run_lint_scan(
             "warnings": l.warning_count,
             "duration_ms": l.duration_ms,
         })).collect::<Vec<_>>(),
-        "issues": results.issues.iter().map(|i| serde_json::json!({
-            "file": i.file,
-            "line": i.line,
-            "column": i.column,
-            "severity": i.severity.as_str(),
-            "rule": i.rule,
-            "message": i.message,
-            "linter": i.linter.as_str(),
-            "fix": i.fix,
-        })).collect::<Vec<_>>(),
+        "issues": results.issues.iter().map(|i| {
+            let mut issue = serde_json::json!({
+                "file": i.file,
+                "line": i.line,
+                "column": i.column,
+                "severity": i.severity.as_str(),
+                "rule": i.rule,
+                "message": i.message,
+                "linter": i.linter.as_str(),
+                "fix": i.fix,
+            });
+            i.symbol.insert_into(&mut issue);
+            issue
+        }).collect::<Vec<_>>(),
     });
 
     match ctx.format {
@@ -110,7 +172,7 @@ fn run_lint_scan(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             let status = if results.success { "pass" } else { "fail" };
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str(&format!("  LINT SCAN: {}\n", status.to_uppercase()));
@@ -160,6 +222,7 @@ fn run_lint_scan(
                         "  {}:{}{} {} [{}] {}\n",
                         issue.line, col, "", severity_code, issue.rule, issue.message
                     ));
+                    output.push_str(&format!("    {}\n", issue.symbol));
                 }
             }
         }
@@ -174,6 +237,7 @@ fn run_lint_fix(
     linter: &Option<String>,
     dry_run: bool,
     safe_only: bool,
+    languages: &[Lang],
     ctx: &CommandContext,
 ) -> Result<String> {
     use crate::lint::{run_lint, LintRunOptions, Linter};
@@ -187,6 +251,7 @@ fn run_lint_fix(
 
     let options = LintRunOptions {
         linter: target_linter,
+        linters: None,
         path_filter: None,
         severity_filter: None,
         limit: None,
@@ -194,22 +259,53 @@ fn run_lint_fix(
         fix: true,
         dry_run,
         safe_only,
+        languages: language_filter(languages),
     };
 
     // Run linters in fix mode
-    let results = run_lint(&project_dir, &options)?;
+    let mut results = run_lint(&project_dir, &options)?;
+    if let Some(mut symbols) = LineSymbolIndex::for_repo(&project_dir) {
+        for issue in &mut results.issues {
+            issue.symbol = symbols.resolve(&issue.file, issue.line);
+        }
+    }
 
     let mut output = String::new();
 
     let mode = if dry_run { "dry-run" } else { "fix" };
+    // In a dry run nothing changed: the fixable issues are still listed
+    let manual_count = if dry_run {
+        results.issues.len().saturating_sub(results.fixed_count)
+    } else {
+        results.issues.len()
+    };
 
     let json_value = serde_json::json!({
         "_type": "lint_fix",
         "path": project_dir.to_string_lossy(),
         "mode": mode,
         "success": results.success,
+        "fixed": results.fixed_count,
+        "manual": manual_count,
         "linters_run": results.linters.iter().map(|l| l.linter.as_str()).collect::<Vec<_>>(),
+        "linters": results.linters.iter().map(|l| serde_json::json!({
+            "linter": l.linter.as_str(),
+            "fixed": l.fixed_count,
+            "remaining": l.issues.len(),
+        })).collect::<Vec<_>>(),
         "duration_ms": results.duration_ms,
+        "remaining_issues": results.issues.iter().map(|i| {
+            let mut issue = serde_json::json!({
+                "file": i.file,
+                "line": i.line,
+                "severity": i.severity.as_str(),
+                "rule": i.rule,
+                "message": i.message,
+                "linter": i.linter.as_str(),
+            });
+            i.symbol.insert_into(&mut issue);
+            issue
+        }).collect::<Vec<_>>(),
     });
 
     match ctx.format {
@@ -219,18 +315,44 @@ fn run_lint_fix(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str(&format!("  LINT FIX ({})\n", mode.to_uppercase()));
             output.push_str("═══════════════════════════════════════════\n\n");
             output.push_str(&format!("path: {}\n", project_dir.display()));
-            output.push_str(&format!("duration: {}ms\n\n", results.duration_ms));
+            let fixed_label = if dry_run { "would fix" } else { "fixed" };
+            output.push_str(&format!(
+                "{}: {} | manual: {} | duration: {}ms\n\n",
+                fixed_label, results.fixed_count, manual_count, results.duration_ms
+            ));
 
             if !results.linters.is_empty() {
                 output.push_str("linters run:\n");
                 for l in &results.linters {
                     let status_icon = if l.success { "✓" } else { "✗" };
-                    output.push_str(&format!("  {} {}\n", status_icon, l.linter.display_name()));
+                    output.push_str(&format!(
+                        "  {} {} ({}: {}, remaining: {})\n",
+                        status_icon,
+                        l.linter.display_name(),
+                        fixed_label,
+                        l.fixed_count,
+                        l.issues.len()
+                    ));
+                }
+            }
+
+            if !dry_run && !results.issues.is_empty() {
+                output.push_str("\nneeds manual fix:\n");
+                for issue in &results.issues {
+                    output.push_str(&format!(
+                        "  {}:{} {} [{}] {}\n",
+                        issue.file,
+                        issue.line,
+                        issue.severity.code(),
+                        issue.rule,
+                        issue.message
+                    ));
+                    output.push_str(&format!("    {}\n", issue.symbol));
                 }
             }
 
@@ -243,6 +365,201 @@ fn run_lint_fix(
     Ok(output)
 }
 
+/// Most frequent rules listed in `lint_results`
+const TOP_RULES: usize = 5;
+
+/// Run linters (checking, or fixing with `options.fix`) and report the
+/// outcome as `lint_results`
+///
+/// Errors are always listed; warnings, info and hints only with
+/// `include_warnings`, so the default report stays small. `top_rules` names
+/// the most frequent rules across every issue, and in fix mode `fixed` counts
+/// what each linter resolved. `options.limit` caps each section separately.
+pub fn run_lint_results(
+    dir: &Path,
+    options: &LintRunOptions,
+    include_warnings: bool,
+    ctx: &CommandContext,
+) -> Result<String> {
+    // Limited per section below, so warnings can't crowd out errors
+    let run_options = LintRunOptions {
+        limit: None,
+        ..options.clone()
+    };
+    let mut results = crate::lint::run_lint(dir, &run_options)?;
+    if let Some(mut symbols) = LineSymbolIndex::for_repo(dir) {
+        for issue in &mut results.issues {
+            issue.symbol = symbols.resolve(&issue.file, issue.line);
+        }
+    }
+
+    let mode = match (options.fix, options.dry_run) {
+        (false, _) => "scan",
+        (true, false) => "fix",
+        (true, true) => "dry-run",
+    };
+    let json_value = lint_results_json(dir, mode, &results, include_warnings, options.limit);
+
+    let output = match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            format_lint_results_text(&json_value)
+        }
+    };
+    Ok(output)
+}
+
+/// The `lint_results` report for `results`
+fn lint_results_json(
+    dir: &Path,
+    mode: &str,
+    results: &LintResults,
+    include_warnings: bool,
+    limit: Option<usize>,
+) -> serde_json::Value {
+    let limit = limit.unwrap_or(usize::MAX);
+    let (errors, warnings): (Vec<&LintIssue>, Vec<&LintIssue>) = results
+        .issues
+        .iter()
+        .partition(|issue| issue.severity == LintSeverity::Error);
+
+    let issue_json = |issue: &LintIssue, with_severity: bool| {
+        let mut value = serde_json::json!({
+            "file": issue.file,
+            "line": issue.line,
+            "rule": issue.rule,
+            "message": issue.message,
+            "linter": issue.linter.as_str(),
+        });
+        if with_severity {
+            value["severity"] = serde_json::json!(issue.severity.as_str());
+        }
+        if let Some(ref fix) = issue.fix {
+            value["fix"] = serde_json::json!(fix);
+        }
+        issue.symbol.insert_into(&mut value);
+        value
+    };
+
+    let mut rule_counts: HashMap<(&str, Linter), usize> = HashMap::new();
+    for issue in &results.issues {
+        *rule_counts
+            .entry((issue.rule.as_str(), issue.linter))
+            .or_default() += 1;
+    }
+    let mut top_rules: Vec<_> = rule_counts.into_iter().collect();
+    top_rules.sort_by(|((rule_a, _), count_a), ((rule_b, _), count_b)| {
+        count_b.cmp(count_a).then_with(|| rule_a.cmp(rule_b))
+    });
+    top_rules.truncate(TOP_RULES);
+
+    let mut value = serde_json::json!({
+        "_type": "lint_results",
+        "path": dir.to_string_lossy(),
+        "mode": mode,
+        "success": errors.is_empty(),
+        "error_count": errors.len(),
+        "warning_count": warnings.len(),
+        "files_with_issues": results.files_with_issues,
+        "duration_ms": results.duration_ms,
+        "linters": results.linters.iter().map(|l| serde_json::json!({
+            "linter": l.linter.as_str(),
+            "success": l.success,
+            "errors": l.error_count,
+            "warnings": l.warning_count,
+        })).collect::<Vec<_>>(),
+        "top_rules": top_rules.iter().map(|((rule, linter), count)| serde_json::json!({
+            "rule": rule,
+            "linter": linter.as_str(),
+            "count": count,
+        })).collect::<Vec<_>>(),
+        "errors": errors.iter().take(limit).map(|i| issue_json(i, false)).collect::<Vec<_>>(),
+    });
+
+    if include_warnings {
+        value["warnings"] = warnings
+            .iter()
+            .take(limit)
+            .map(|i| issue_json(i, true))
+            .collect();
+    } else if !warnings.is_empty() {
+        value["hint"] = serde_json::json!(format!(
+            "{} warnings not shown; pass include_warnings: true to list them",
+            warnings.len()
+        ));
+    }
+
+    if mode != "scan" {
+        value["fixed_count"] = serde_json::json!(results.fixed_count);
+        value["fixed"] = results
+            .linters
+            .iter()
+            .filter(|l| l.fixed_count > 0)
+            .map(|l| serde_json::json!({"linter": l.linter.as_str(), "count": l.fixed_count}))
+            .collect();
+    }
+
+    value
+}
+
+/// Plain-text rendering of a `lint_results` report
+fn format_lint_results_text(report: &serde_json::Value) -> String {
+    let mut output = String::new();
+    let status = if report["success"] == true {
+        "pass"
+    } else {
+        "fail"
+    };
+    output.push_str("═══════════════════════════════════════════\n");
+    output.push_str(&format!("  LINT RESULTS: {}\n", status.to_uppercase()));
+    output.push_str("═══════════════════════════════════════════\n\n");
+    output.push_str(&format!(
+        "path: {}\nmode: {} | errors: {} | warnings: {}",
+        report["path"].as_str().unwrap_or_default(),
+        report["mode"].as_str().unwrap_or_default(),
+        report["error_count"],
+        report["warning_count"]
+    ));
+    if let Some(fixed) = report.get("fixed_count") {
+        output.push_str(&format!(" | fixed: {}", fixed));
+    }
+    output.push('\n');
+
+    let empty = Vec::new();
+    let rows = |key: &str| report.get(key).and_then(|v| v.as_array()).unwrap_or(&empty);
+    if !rows("top_rules").is_empty() {
+        output.push_str("\ntop rules:\n");
+        for rule in rows("top_rules") {
+            output.push_str(&format!(
+                "  {} ({}): {}\n",
+                rule["rule"].as_str().unwrap_or_default(),
+                rule["linter"].as_str().unwrap_or_default(),
+                rule["count"]
+            ));
+        }
+    }
+    for section in ["errors", "warnings"] {
+        if rows(section).is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n{}:\n", section));
+        for issue in rows(section) {
+            output.push_str(&format!(
+                "  {}:{} [{}] {}\n",
+                issue["file"].as_str().unwrap_or_default(),
+                issue["line"],
+                issue["rule"].as_str().unwrap_or_default(),
+                issue["message"].as_str().unwrap_or_default()
+            ));
+        }
+    }
+    if let Some(hint) = report.get("hint").and_then(|v| v.as_str()) {
+        output.push_str(&format!("\n{}\n", hint));
+    }
+    output
+}
+
 /// Run type checkers only
 fn run_typecheck(
     path: &Option<PathBuf>,
@@ -270,7 +587,7 @@ fn run_typecheck(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("Type checking not yet implemented.\n");
         }
     }
@@ -279,12 +596,16 @@ fn run_typecheck(
 }
 
 /// Detect available linters
-fn run_detect_linters(path: &Option<PathBuf>, ctx: &CommandContext) -> Result<String> {
+fn run_detect_linters(
+    path: &Option<PathBuf>,
+    languages: &[Lang],
+    ctx: &CommandContext,
+) -> Result<String> {
     let project_dir = path
         .clone()
         .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
 
-    let detected = detect_linters(&project_dir);
+    let detected = detect_linters_for(&project_dir, language_filter(languages).as_deref());
 
     let mut output = String::new();
 
@@ -319,7 +640,7 @@ fn run_detect_linters(path: &Option<PathBuf>, ctx: &CommandContext) -> Result<St
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  DETECTED LINTERS\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -416,7 +737,7 @@ fn run_lint_recommend(path: &Option<PathBuf>, ctx: &CommandContext) -> Result<St
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  LINTER RECOMMENDATIONS\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -442,3 +763,59 @@ fn run_lint_recommend(path: &Option<PathBuf>, ctx: &CommandContext) -> Result<St
 
     Ok(output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(file: &str, severity: LintSeverity, rule: &str) -> LintIssue {
+        LintIssue {
+            file: file.to_string(),
+            line: 1,
+            column: None,
+            end_line: None,
+            end_column: None,
+            severity,
+            rule: rule.to_string(),
+            message: format!("{} violated", rule),
+            linter: Linter::ESLint,
+            fix: None,
+            symbol: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_lint_results_sections_and_top_rules() {
+        let mut issues = vec![issue("a.ts", LintSeverity::Error, "no-undef")];
+        for rule in ["semi", "semi", "semi", "quotes", "quotes", "a", "b", "c"] {
+            issues.push(issue("b.ts", LintSeverity::Warning, rule));
+        }
+        let results = LintResults {
+            issues,
+            ..LintResults::default()
+        };
+
+        let report = lint_results_json(Path::new("."), "scan", &results, false, None);
+        assert_eq!(report["_type"], "lint_results");
+        assert_eq!(report["error_count"], 1);
+        assert_eq!(report["warning_count"], 8);
+        assert_eq!(report["errors"][0]["rule"], "no-undef");
+        assert!(report.get("warnings").is_none());
+        assert!(report.get("fixed").is_none());
+        let top: Vec<_> = report["top_rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| (r["rule"].as_str().unwrap(), r["count"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(
+            top,
+            [("semi", 3), ("quotes", 2), ("a", 1), ("b", 1), ("c", 1)]
+        );
+
+        let report = lint_results_json(Path::new("."), "fix", &results, true, Some(2));
+        assert_eq!(report["warnings"].as_array().unwrap().len(), 2);
+        assert_eq!(report["warnings"][0]["severity"], "warning");
+        assert_eq!(report["fixed_count"], 0);
+    }
+}
@@ -14,13 +14,20 @@
 //! - `security` - CVE scanning and pattern management (internal use only)
 //! - `test` - Run or detect tests
 //! - `commit` - Prepare commit information
+//! - `config` - Show the analysis defaults in effect for a repository
+//! - `completions` - Print shell completion scripts
 //!
 //! All command handlers take their respective `Args` struct from `cli.rs`
-//! and a shared `CommandContext` for output format and verbosity.
+//! and a shared `CommandContext` for output format and verbosity. Report
+//! commands pass their result through [`CommandContext::emit`], which writes
+//! it to the `--output` file when one was given.
 
 pub mod analyze;
 pub mod cache;
 pub mod commit;
+pub mod completions;
+pub mod config;
+pub mod fail_on;
 pub mod index;
 pub mod lint;
 pub mod query;
@@ -34,16 +41,19 @@ pub mod trace;
 pub mod validate;
 
 // Re-export command handlers for easy access
-pub use analyze::run_analyze;
-pub use cache::run_cache;
+pub use analyze::{run_analyze, run_analyze_snippet, run_file_diff, run_topics};
+pub use cache::{run_cache, run_migrate};
 pub use commit::run_commit;
+pub use completions::run_completions;
+pub use config::run_config_effective;
 pub use index::run_index;
-pub use lint::run_lint;
+pub use lint::{run_lint, run_lint_results};
 pub use query::{
-    run_file_symbols, run_get_callers, run_get_callgraph, run_get_source, run_get_symbol,
-    run_overview, run_query,
+    run_api_surface, run_component_tree, run_file_symbols, run_get_callers, run_get_callgraph,
+    run_get_source, run_get_symbol, run_hook_usage, run_module_usages, run_overview, run_query,
+    run_query_exports, run_query_imports, run_symbol_history, run_unused_modules,
 };
-pub use search::run_search;
+pub use search::{run_search, run_semantic_grep};
 // Security not re-exported - internal use only
 // pub use security::run_security;
 pub use serve::run_serve;
@@ -51,7 +61,18 @@ pub use test::run_test;
 pub use trace::run_trace;
 pub use validate::{run_duplicates, run_validate};
 
-use crate::cli::OutputFormat;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::ai_layer::AiLayer;
+use crate::cli::{OutputFormat, PathStyle, ProgressFormat};
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+use crate::profile::{Phase, PhaseTimer};
+use crate::settings::RepoSettings;
+use crate::sqlite_export::{ExportProgress, ProgressCallback};
 
 /// Shared context passed to all command handlers
 #[derive(Debug, Clone)]
@@ -62,6 +83,41 @@ pub struct CommandContext {
     pub verbose: bool,
     /// Show progress during long operations
     pub progress: bool,
+    /// File the result is written to instead of stdout (`--output`)
+    pub output: Option<PathBuf>,
+    /// Staged AI edits that queries answer as if applied (MCP only)
+    pub ai_layer: Option<Arc<AiLayer>>,
+    /// How file paths are written in output (`--paths`)
+    pub paths: PathStyle,
+    /// How progress is reported on stderr (`--progress-format`)
+    pub progress_format: ProgressFormat,
+    /// Receives progress instead of stderr (MCP progress notifications)
+    pub progress_sink: Option<ProgressSink>,
+    /// Config-file defaults for the repository the command works on; loaded
+    /// on demand by [`CommandContext::repo_settings`] when not set
+    pub settings: Option<Arc<RepoSettings>>,
+}
+
+/// Receiver for `(phase, current, total)` progress updates
+///
+/// Updates may come from several threads and, within a phase, out of order.
+#[derive(Clone)]
+pub struct ProgressSink(Arc<dyn Fn(&str, usize, usize) + Send + Sync>);
+
+impl ProgressSink {
+    pub fn new(report: impl Fn(&str, usize, usize) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(report))
+    }
+
+    pub fn report(&self, phase: &str, current: usize, total: usize) {
+        (self.0)(phase, current, total)
+    }
+}
+
+impl fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressSink")
+    }
 }
 
 impl Default for CommandContext {
@@ -70,15 +126,45 @@ impl Default for CommandContext {
             format: OutputFormat::Text,
             verbose: false,
             progress: true,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         }
     }
 }
 
 /// Encode a JSON value as proper TOON using the rtoon library
 pub fn encode_toon(value: &serde_json::Value) -> String {
+    let _timer = PhaseTimer::start(Phase::Formatting);
     rtoon::encode_default(value).unwrap_or_else(|e| format!("TOON encoding error: {}", e))
 }
 
+/// Write `contents` to a temporary file next to `path` and rename it into
+/// place, so `path` never holds a partial write
+pub(crate) fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| McpDiffError::FileNotFound {
+            path: format!("{} (not a file path)", path.display()),
+        })?
+        .to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    if let Err(e) =
+        fs::write(&tmp_path, contents).and_then(|_| fs_utils::atomic_rename(&tmp_path, path))
+    {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
 /// Package version from Cargo.toml
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -91,6 +177,41 @@ pub fn toon_header(type_name: &str) -> String {
     format!("_type: {}\nversion: {}\n", type_name, VERSION)
 }
 
+/// Whether `module` is one of the `--exclude-module` modules or nested in one
+///
+/// Names compare case-insensitively; excluding `generated` also excludes
+/// `generated.client`, but not `generated_docs`.
+pub fn is_excluded_module(module: &str, excluded: &[String]) -> bool {
+    excluded.iter().any(|name| {
+        module
+            .get(..name.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+            && module
+                .get(name.len()..)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Write one progress event to stderr as a line of JSON
+///
+/// Used by `--progress-format json`. Each line carries the phase name, the
+/// current and total counts within that phase, and the percentage.
+pub fn emit_progress_event(phase: &str, current: usize, total: usize) {
+    let percent = if total == 0 {
+        0.0
+    } else {
+        (current as f64 / total as f64 * 1000.0).round() / 10.0
+    };
+    let event = serde_json::json!({
+        "event": "progress",
+        "phase": phase,
+        "current": current,
+        "total": total,
+        "percent": percent,
+    });
+    eprintln!("{}", event);
+}
+
 impl CommandContext {
     /// Create a new CommandContext from CLI args
     pub fn from_cli(format: OutputFormat, verbose: bool, progress: bool) -> Self {
@@ -98,6 +219,106 @@ impl CommandContext {
             format,
             verbose,
             progress,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        }
+    }
+
+    /// Set the file the command result is written to
+    pub fn with_output(mut self, output: Option<PathBuf>) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Set how file paths are written in output
+    pub fn with_paths(mut self, paths: PathStyle) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Set how progress is reported on stderr
+    pub fn with_progress_format(mut self, progress_format: ProgressFormat) -> Self {
+        self.progress_format = progress_format;
+        self
+    }
+
+    /// Send progress to `sink` instead of stderr
+    pub fn with_progress_sink(mut self, sink: Option<ProgressSink>) -> Self {
+        self.progress_sink = sink;
+        self
+    }
+
+    /// Use `settings` as the repository's config-file defaults
+    pub fn with_settings(mut self, settings: Option<Arc<RepoSettings>>) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Config-file defaults for the repository at `repo_root`
+    pub fn repo_settings(&self, repo_root: &Path) -> Arc<RepoSettings> {
+        match &self.settings {
+            Some(settings) => Arc::clone(settings),
+            None => Arc::new(RepoSettings::load_or_default(repo_root)),
+        }
+    }
+
+    /// Report progress to the progress sink, if there is one
+    pub fn report_progress(&self, phase: &str, current: usize, total: usize) {
+        if let Some(sink) = &self.progress_sink {
+            sink.report(phase, current, total);
+        }
+    }
+
+    /// Progress callback for a SQLite export, if progress is enabled
+    pub fn export_progress(&self) -> Option<ProgressCallback> {
+        if let Some(sink) = self.progress_sink.clone() {
+            return Some(Box::new(move |p: ExportProgress| {
+                sink.report(p.phase.as_str(), p.current, p.total)
+            }));
+        }
+        if !self.progress {
+            return None;
+        }
+        Some(match self.progress_format {
+            ProgressFormat::Json => Box::new(|p: ExportProgress| {
+                emit_progress_event(p.phase.as_str(), p.current, p.total)
+            }),
+            ProgressFormat::Text => Box::new(|p: ExportProgress| {
+                eprintln!("Export: {}", p.message);
+            }),
+        })
+    }
+
+    /// Render a file path from a summary or index for output
+    pub fn display_path(&self, file: &str, repo_root: &Path) -> String {
+        self.paths.render(file, repo_root)
+    }
+
+    /// Answer queries with staged AI edits applied
+    pub fn with_ai_layer(mut self, ai_layer: Option<Arc<AiLayer>>) -> Self {
+        self.ai_layer = ai_layer;
+        self
+    }
+
+    /// Deliver a command result
+    ///
+    /// Without `--output` the result is returned for printing to stdout.
+    /// With it, the result is written to a temporary file next to the target
+    /// and renamed into place, so the target never holds a partial report,
+    /// and an empty string is returned.
+    pub fn emit(&self, result: String) -> Result<String> {
+        let Some(path) = &self.output else {
+            return Ok(result);
+        };
+
+        write_atomic(path, &result)?;
+        if self.verbose {
+            eprintln!("Wrote {} bytes to {}", result.len(), path.display());
         }
+        Ok(String::new())
     }
 }
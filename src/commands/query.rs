@@ -1,14 +1,28 @@
 //! Query command handler - Query the semantic index for symbols, source, callers, etc.
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
 use crate::cache::{CacheDir, SymbolIndexEntry};
 use crate::cli::{OutputFormat, QueryArgs, QueryType, SymbolScope};
 use crate::commands::toon_parser::read_cached_file;
-use crate::commands::CommandContext;
+use crate::commands::{is_excluded_module, CommandContext};
+use crate::components::{Component, ComponentIndex, RenderNode};
+use crate::contracts::find_contract_usages;
 use crate::error::{McpDiffError, Result};
-use crate::git::{get_current_branch, get_last_commit};
+use crate::git::{get_current_branch, get_last_commit, get_line_range_history};
+use crate::hotspots::{churn_commits, FileHotspot, HotspotIndex, SymbolHotspot};
+use crate::lang::Lang;
+use crate::module_usage::ModuleUsageIndex;
+use crate::ownership::OwnershipIndex;
+use crate::parsing::parse_and_extract;
+use crate::profile::{Phase, PhaseTimer};
+use crate::schema::{SymbolInfo, SymbolKind};
+use crate::test_links::{FileRef, SymbolLink, TestLinkIndex};
+use crate::tokens::TokenAnalyzer;
+use crate::utils::truncate_to_char_boundary;
+use crate::workspace::{repo_relative, resolve_package};
 
 /// Run the query command
 pub fn run_query(args: &QueryArgs, ctx: &CommandContext) -> Result<String> {
@@ -19,12 +33,18 @@ pub fn run_query(args: &QueryArgs, ctx: &CommandContext) -> Result<String> {
             max_modules,
             exclude_test_dirs,
             include_git_context,
+            max_tokens,
+            hotspots,
+            min_lines,
         } => run_overview(
             path.as_ref(),
             *modules,
             *max_modules,
             *exclude_test_dirs,
             *include_git_context,
+            *max_tokens,
+            *hotspots,
+            *min_lines,
             ctx,
         ),
         QueryType::Module {
@@ -92,7 +112,9 @@ pub fn run_query(args: &QueryArgs, ctx: &CommandContext) -> Result<String> {
         QueryType::Callgraph {
             path,
             module,
+            exclude_modules,
             symbol,
+            package,
             export,
             stats_only,
             limit,
@@ -101,7 +123,9 @@ pub fn run_query(args: &QueryArgs, ctx: &CommandContext) -> Result<String> {
         } => run_get_callgraph(
             path.as_ref(),
             module.as_deref(),
+            exclude_modules,
             symbol.as_deref(),
+            package.as_deref(),
             export.as_deref(),
             *stats_only,
             *limit,
@@ -129,19 +153,83 @@ pub fn run_query(args: &QueryArgs, ctx: &CommandContext) -> Result<String> {
             *include_escape_refs,
             ctx,
         ),
+        QueryType::ContractUsages {
+            target,
+            path,
+            limit,
+        } => run_contract_usages(path.as_ref(), target, *limit, ctx),
+        QueryType::ModuleUsages {
+            module,
+            path,
+            unused,
+            include_generated,
+            limit,
+        } => match module {
+            Some(module) if !*unused => run_module_usages(path.as_ref(), module, *limit, ctx),
+            _ => run_unused_modules(path.as_ref(), *limit, *include_generated, ctx),
+        },
+        QueryType::ApiSurface {
+            module,
+            path,
+            include_types,
+        } => run_api_surface(path.as_ref(), module, *include_types, ctx),
+        QueryType::Imports { file, path } => {
+            run_query_imports(file, &repo_cache(path.as_ref())?, ctx)
+        }
+        QueryType::Exports { module, path } => {
+            run_query_exports(module, &repo_cache(path.as_ref())?, ctx)
+        }
+        QueryType::Components {
+            root,
+            hook,
+            path,
+            depth,
+        } => match hook {
+            Some(hook) => run_hook_usage(path.as_ref(), hook, ctx),
+            None => run_component_tree(
+                path.as_ref(),
+                root.as_deref().unwrap_or_default(),
+                *depth,
+                ctx,
+            ),
+        },
+        QueryType::TestsFor {
+            target,
+            path,
+            limit,
+        } => run_tests_for(path.as_ref(), target, *limit, ctx),
+        QueryType::SymbolHistory { hash, path, limit } => {
+            run_symbol_history(path.as_ref(), hash, *limit, ctx)
+        }
+        QueryType::Hotspots {
+            path,
+            limit,
+            include_generated,
+        } => run_hotspots(path.as_ref(), *limit, *include_generated, ctx),
+        QueryType::Owners {
+            target,
+            path,
+            limit,
+        } => run_owners(path.as_ref(), target.as_deref(), *limit, ctx),
         QueryType::Languages => run_list_languages(ctx),
     }
 }
 
 /// Get repository overview (DEDUP-201: unified CLI/MCP handler)
 ///
-/// If path is None, uses the current directory.
+/// If path is None, uses the current directory. With `min_lines`, the risk
+/// breakdown counts indexed symbols spanning at least that many lines rather
+/// than files; the index itself is unchanged.
+#[allow(clippy::too_many_arguments)]
 pub fn run_overview(
     path: Option<&PathBuf>,
     include_modules: bool,
     max_modules: usize,
     exclude_test_dirs: bool,
     include_git_context: bool,
+    max_tokens: Option<usize>,
+    include_hotspots: bool,
+    min_lines: Option<usize>,
     ctx: &CommandContext,
 ) -> Result<String> {
     let repo_dir = match path {
@@ -176,17 +264,110 @@ pub fn run_overview(
     };
 
     // Filter modules based on flags
-    let filtered_content =
+    let mut filtered_content =
         filter_overview_content(&content, include_modules, max_modules, exclude_test_dirs);
 
-    match ctx.format {
+    if let Some(min_lines) = min_lines {
+        let entries = cache.load_all_symbol_entries()?;
+        filtered_content = replace_risk_breakdown(
+            &filtered_content,
+            symbol_risk_breakdown(&entries, min_lines),
+            min_lines,
+        );
+    }
+
+    // Hot spots are ranked at index time; an index predating them says so
+    let hotspots = include_hotspots.then(|| match HotspotIndex::load(&cache) {
+        Ok(index) => hotspots_json(&index.truncated(OVERVIEW_HOTSPOTS), &cache, ctx),
+        Err(e) => serde_json::json!(format!("unavailable ({})", e)),
+    });
+
+    let render = |content: &str| {
+        render_overview(content, git_context.as_ref(), hotspots.as_ref(), ctx.format)
+    };
+    Ok(match max_tokens {
+        Some(budget) => fit_overview_to_budget(&filtered_content, budget, render),
+        None => render(&filtered_content),
+    })
+}
+
+/// Hot spots per ranking appended to the overview
+const OVERVIEW_HOTSPOTS: usize = 5;
+
+/// `(high, medium, low)` counts of the non-variable symbols spanning at least
+/// `min_lines` lines
+fn symbol_risk_breakdown(entries: &[SymbolIndexEntry], min_lines: usize) -> (usize, usize, usize) {
+    let mut breakdown = (0, 0, 0);
+    for entry in entries {
+        if !SymbolScope::Functions.matches_kind(&entry.kind) {
+            continue;
+        }
+        let (start, end) = crate::test_selection::parse_lines(&entry.lines);
+        if end.saturating_sub(start) + 1 < min_lines {
+            continue;
+        }
+        match entry.risk.as_str() {
+            "high" => breakdown.0 += 1,
+            "medium" => breakdown.1 += 1,
+            _ => breakdown.2 += 1,
+        }
+    }
+    breakdown
+}
+
+/// Swap the file-level `risk_breakdown` line of overview content for a
+/// symbol-level one, noting the line threshold it was tallied with
+fn replace_risk_breakdown(
+    content: &str,
+    (high, medium, low): (usize, usize, usize),
+    min_lines: usize,
+) -> String {
+    let replacement = format!(
+        "risk_breakdown: \"high:{},medium:{},low:{}\"\nrisk_min_lines: {}",
+        high, medium, low, min_lines
+    );
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !replaced && line.starts_with("risk_breakdown:") {
+                replaced = true;
+                replacement.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(replacement);
+    }
+    let mut output = lines.join("\n");
+    if content.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+/// Render filtered overview content in the requested format
+fn render_overview(
+    content: &str,
+    git_context: Option<&serde_json::Value>,
+    hotspots: Option<&serde_json::Value>,
+    format: OutputFormat,
+) -> String {
+    match format {
         OutputFormat::Json => {
             // Convert TOON to JSON structure
-            let mut json = toon_to_json_overview(&filtered_content);
-            if let (Some(ctx), Some(obj)) = (git_context.as_ref(), json.as_object_mut()) {
-                obj.insert("git_context".to_string(), ctx.clone());
+            let mut json = toon_to_json_overview(content);
+            if let Some(obj) = json.as_object_mut() {
+                if let Some(ctx) = git_context {
+                    obj.insert("git_context".to_string(), ctx.clone());
+                }
+                if let Some(hotspots) = hotspots {
+                    obj.insert("hotspots".to_string(), hotspots.clone());
+                }
             }
-            Ok(serde_json::to_string_pretty(&json).unwrap_or_default())
+            serde_json::to_string_pretty(&json).unwrap_or_default()
         }
         OutputFormat::Toon => {
             // Return TOON format with git context prepended
@@ -201,10 +382,19 @@ pub fn run_overview(
                 }
                 output.push('\n');
             }
-            output.push_str(&filtered_content);
-            Ok(output)
+            output.push_str(content);
+            if let Some(hotspots) = hotspots {
+                if !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str(&super::encode_toon(
+                    &serde_json::json!({ "hotspots": hotspots }),
+                ));
+                output.push('\n');
+            }
+            output
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             // Human-readable text format with header
             let mut output = String::new();
             output.push_str("═══════════════════════════════════════════\n");
@@ -219,10 +409,190 @@ pub fn run_overview(
                 }
                 output.push('\n');
             }
-            output.push_str(&filtered_content);
-            Ok(output)
+            output.push_str(content);
+            if let Some(hotspots) = hotspots {
+                if !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push('\n');
+                output.push_str(&format_hotspots_text(hotspots));
+            }
+            output
+        }
+    }
+}
+
+/// Overview sections dropped (in order) when modules alone don't fit the budget
+const OPTIONAL_OVERVIEW_SECTIONS: &[&str] = &[
+    "indexing_status:",
+    "tech_debt_score:",
+    "generated:",
+    "packages[",
+    "patterns[",
+    "database:",
+    "risk_min_lines:",
+    "risk_breakdown:",
+    "framework:",
+    "modules[",
+];
+
+/// Trim overview content until the rendered output fits `max_tokens`
+///
+/// Modules are dropped first, keeping entry-point modules, then high-risk,
+/// then medium-risk ones. If that isn't enough, optional sections go, then
+/// entry points from the end of the list, and finally trailing lines.
+fn fit_overview_to_budget(
+    content: &str,
+    max_tokens: usize,
+    render: impl Fn(&str) -> String,
+) -> String {
+    let analyzer = TokenAnalyzer::new();
+    let fits = |output: &str| analyzer.count_tokens(output) <= max_tokens;
+
+    let output = render(content);
+    if fits(&output) {
+        return output;
+    }
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let entry_points: Vec<String> = lines
+        .iter()
+        .find_map(|l| l.strip_prefix("entry_points["))
+        .and_then(|rest| rest.split_once(": "))
+        .map(|(_, list)| list.split(',').map(|e| e.trim().to_string()).collect())
+        .unwrap_or_default();
+
+    // Modules, as (line index, priority): lower priority values are kept longer
+    let header = lines.iter().position(|l| l.starts_with("modules["));
+    let mut modules: Vec<(usize, u8)> = Vec::new();
+    if let Some(header) = header {
+        for (i, line) in lines.iter().enumerate().skip(header + 1) {
+            if !line.starts_with("  ") || !line.contains(',') {
+                break;
+            }
+            let fields: Vec<&str> = line.trim().split(',').collect();
+            let risk = fields.last().copied().unwrap_or("");
+            let priority = if module_has_entry_point(fields[0], &entry_points) {
+                0
+            } else {
+                match risk {
+                    "high" => 1,
+                    "medium" => 2,
+                    _ => 3,
+                }
+            };
+            modules.push((i, priority));
+        }
+    }
+    let mut ranked = modules.clone();
+    ranked.sort_by_key(|&(i, priority)| (priority, i));
+
+    // Largest number of modules that fits (output size grows with each one)
+    let with_modules = |keep: usize| -> String {
+        let kept: HashSet<usize> = ranked.iter().take(keep).map(|&(i, _)| i).collect();
+        let dropped = modules.len() - keep;
+        let mut trimmed = Vec::with_capacity(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            if Some(i) == header {
+                trimmed.push(budget_module_header(line, keep, dropped));
+            } else if modules.iter().any(|&(m, _)| m == i) {
+                if kept.contains(&i) {
+                    trimmed.push(line.clone());
+                }
+            } else {
+                trimmed.push(line.clone());
+            }
+        }
+        trimmed.join("\n")
+    };
+    let (mut low, mut high) = (0, modules.len());
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if fits(&render(&with_modules(mid))) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    let trimmed = with_modules(low);
+    let output = render(&trimmed);
+    if low > 0 || fits(&output) {
+        return output;
+    }
+
+    // Not even the summary fits: drop optional sections, then entry points
+    lines = trimmed.lines().map(String::from).collect();
+    for prefix in OPTIONAL_OVERVIEW_SECTIONS {
+        if let Some(start) = lines.iter().position(|l| l.starts_with(prefix)) {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| !l.starts_with("  "))
+                .map_or(lines.len(), |n| start + 1 + n);
+            lines.drain(start..end);
+        }
+        let output = render(&lines.join("\n"));
+        if fits(&output) {
+            return output;
+        }
+    }
+
+    if let Some(idx) = lines.iter().position(|l| l.starts_with("entry_points[")) {
+        let mut kept = entry_points.clone();
+        while !kept.is_empty() {
+            kept.pop();
+            lines[idx] = format!("entry_points[{}]: {}", kept.len(), kept.join(","));
+            let output = render(&lines.join("\n"));
+            if fits(&output) {
+                return output;
+            }
+        }
+        lines.remove(idx);
+    }
+
+    while lines.len() > 1 {
+        lines.pop();
+        let output = render(&lines.join("\n"));
+        if fits(&output) {
+            return output;
         }
     }
+    render(&lines.join("\n"))
+}
+
+/// Whether a module contains one of the overview's entry points
+///
+/// Entry points are file paths (optionally prefixed with an HTTP method);
+/// the module matches when its dotted name appears as consecutive path
+/// components.
+fn module_has_entry_point(module: &str, entry_points: &[String]) -> bool {
+    let module_parts: Vec<&str> = module.split('.').collect();
+    entry_points.iter().any(|entry| {
+        let path = entry.rsplit(' ').next().unwrap_or(entry);
+        let components: Vec<&str> = path.split('/').collect();
+        components
+            .windows(module_parts.len())
+            .any(|window| window == module_parts.as_slice())
+    })
+}
+
+/// Rewrite a `modules[N]{notes}:` header after trimming to the token budget
+fn budget_module_header(header: &str, shown: usize, dropped: usize) -> String {
+    if dropped == 0 {
+        return header.to_string();
+    }
+    let notes = header
+        .split_once('{')
+        .and_then(|(_, rest)| rest.split_once('}'))
+        .map(|(notes, _)| notes)
+        .filter(|notes| !notes.contains("name,"))
+        .unwrap_or("");
+    let budget_note = format!("budget:{}", dropped);
+    let notes = if notes.is_empty() {
+        budget_note
+    } else {
+        format!("{} {}", notes, budget_note)
+    };
+    format!("modules[{}]{{{}}}:", shown, notes)
 }
 
 /// Build git context information
@@ -231,7 +601,7 @@ fn build_git_context(repo_dir: &std::path::Path) -> Option<serde_json::Value> {
     let commit = get_last_commit(Some(repo_dir)).map(|c| {
         let msg = format!("{} {}", c.short_sha, c.subject);
         if msg.len() > 60 {
-            format!("{}...", &msg[..57])
+            format!("{}...", truncate_to_char_boundary(&msg, 57))
         } else {
             msg
         }
@@ -433,9 +803,30 @@ fn toon_to_json_overview(content: &str) -> serde_json::Value {
     result.insert("_type".to_string(), serde_json::json!("repo_overview"));
 
     let mut modules = Vec::new();
-
-    for line in content.lines() {
-        let line = line.trim();
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+
+        // Package rows: name,path,files,symbols,loc,high,medium,low
+        if !raw_line.starts_with(' ') {
+            in_packages = line.starts_with("packages[");
+        } else if in_packages {
+            let parts: Vec<&str> = line.split(',').collect();
+            if parts.len() >= 8 {
+                let count = |i: usize| parts[i].trim().parse::<usize>().unwrap_or(0);
+                packages.push(serde_json::json!({
+                    "name": parts[0],
+                    "path": parts[1],
+                    "files": count(2),
+                    "symbols": count(3),
+                    "loc": count(4),
+                    "risk": {"high": count(5), "medium": count(6), "low": count(7)}
+                }));
+            }
+            continue;
+        }
 
         if line.starts_with("_type:") {
             // Skip, already added
@@ -453,6 +844,42 @@ fn toon_to_json_overview(content: &str) -> serde_json::Value {
                     serde_json::json!(val.trim().trim_matches('"')),
                 );
             }
+        } else if let Some(val) = line.strip_prefix("risk_breakdown:") {
+            let mut breakdown = serde_json::Map::new();
+            for part in val.trim().trim_matches('"').split(',') {
+                if let Some((level, count)) = part.split_once(':') {
+                    if let Ok(count) = count.trim().parse::<usize>() {
+                        breakdown.insert(level.trim().to_string(), serde_json::json!(count));
+                    }
+                }
+            }
+            result.insert(
+                "risk_breakdown".to_string(),
+                serde_json::Value::Object(breakdown),
+            );
+        } else if let Some(val) = line.strip_prefix("generated:") {
+            let mut generated = serde_json::Map::new();
+            for part in val.trim().trim_matches('"').split(',') {
+                if let Some((key, value)) = part.split_once(':') {
+                    let value = match value.trim().parse::<usize>() {
+                        Ok(count) => serde_json::json!(count),
+                        Err(_) => serde_json::json!(value.trim().parse::<f64>().unwrap_or(0.0)),
+                    };
+                    generated.insert(key.trim().to_string(), value);
+                }
+            }
+            result.insert(
+                "generated".to_string(),
+                serde_json::Value::Object(generated),
+            );
+        } else if let Some(val) = line.strip_prefix("risk_min_lines:") {
+            if let Ok(min_lines) = val.trim().parse::<usize>() {
+                result.insert("risk_min_lines".to_string(), serde_json::json!(min_lines));
+            }
+        } else if let Some(val) = line.strip_prefix("tech_debt_score:") {
+            if let Ok(score) = val.trim().parse::<f64>() {
+                result.insert("tech_debt_score".to_string(), serde_json::json!(score));
+            }
         } else if line.starts_with("patterns[") {
             // Parse patterns array
             if let Some(colon_idx) = line.find(':') {
@@ -482,6 +909,9 @@ fn toon_to_json_overview(content: &str) -> serde_json::Value {
     if !modules.is_empty() {
         result.insert("modules".to_string(), serde_json::json!(modules));
     }
+    if !packages.is_empty() {
+        result.insert("packages".to_string(), serde_json::json!(packages));
+    }
 
     serde_json::Value::Object(result)
 }
@@ -506,7 +936,7 @@ fn run_get_module(name: &str, ctx: &CommandContext) -> Result<String> {
     match ctx.format {
         OutputFormat::Json => Ok(cached.as_json()),
         OutputFormat::Toon => Ok(cached.as_toon()),
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             // Human-readable text format
             let mut output = String::new();
             output.push_str("═══════════════════════════════════════════\n");
@@ -620,7 +1050,7 @@ fn run_list_module_symbols(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str(&format!("  MODULE SYMBOLS: {}\n", module_name));
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -662,14 +1092,18 @@ pub fn run_get_symbol(
 
     // File+line mode: find symbol at specific location
     if let (Some(file_path), Some(line_num)) = (file, line) {
-        let symbol = find_symbol_by_location(&cache, file_path, line_num)?;
+        let symbol = find_symbol_by_location(&cache, ctx, file_path, line_num)?;
         results.push(symbol);
     } else if let Some(hash_str) = hash {
         // Hash mode: handle comma-separated hashes for batch queries
         let hashes: Vec<&str> = hash_str.split(',').map(|s| s.trim()).collect();
 
         for h in &hashes {
-            if let Some(symbol) = load_symbol_from_cache(&cache, h)? {
+            if let Some(mut symbol) = load_symbol(&cache, ctx, h)? {
+                // Symbol shards don't repeat the hash they're stored under
+                if symbol.hash.is_empty() {
+                    symbol.hash = h.to_string();
+                }
                 results.push(symbol);
             }
         }
@@ -685,8 +1119,20 @@ pub fn run_get_symbol(
         });
     }
 
+    let links = TestLinkIndex::load(&cache).ok();
+    let ownership = OwnershipIndex::load(&cache).ok();
+    let mut entries = cache.load_all_symbol_entries().unwrap_or_default();
+    if let Some(ai_layer) = &ctx.ai_layer {
+        entries = ai_layer.apply_to_entries(entries);
+    }
+    let nesting: Vec<SymbolNesting> = results
+        .iter()
+        .map(|symbol| SymbolNesting::of(symbol, &entries))
+        .collect();
+
     let json_value = if results.len() == 1 {
-        let mut val = serde_json::to_value(&results[0]).unwrap_or_default();
+        let mut val = symbol_json(&results[0], links.as_ref(), ownership.as_ref());
+        nesting[0].add_to_json(&mut val);
         if let Some(obj) = val.as_object_mut() {
             obj.insert("_type".to_string(), serde_json::json!("symbol"));
         }
@@ -694,7 +1140,15 @@ pub fn run_get_symbol(
     } else {
         serde_json::json!({
             "_type": "symbols",
-            "symbols": results,
+            "symbols": results
+                .iter()
+                .zip(&nesting)
+                .map(|(entry, nesting)| {
+                    let mut val = symbol_json(entry, links.as_ref(), ownership.as_ref());
+                    nesting.add_to_json(&mut val);
+                    val
+                })
+                .collect::<Vec<_>>(),
             "count": results.len()
         })
     };
@@ -708,22 +1162,55 @@ pub fn run_get_symbol(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  SYMBOL DETAILS\n");
             output.push_str("═══════════════════════════════════════════\n\n");
 
-            for symbol in &results {
+            for (symbol, nesting) in results.iter().zip(&nesting) {
                 output.push_str(&format!("## {} ({})\n", symbol.symbol, symbol.kind));
                 output.push_str(&format!("hash: {}\n", symbol.hash));
                 output.push_str(&format!("file: {}\n", symbol.file));
                 output.push_str(&format!("lines: {}\n", symbol.lines));
+                if let Some(parent) = &nesting.parent {
+                    output.push_str(&format!("parent: {} ({})\n", parent.symbol, parent.hash));
+                }
+                if !nesting.children.is_empty() {
+                    let children: Vec<String> = nesting
+                        .children
+                        .iter()
+                        .map(|c| format!("{} L{} ({})", c.symbol, c.lines, c.hash))
+                        .collect();
+                    output.push_str(&format!("children: {}\n", children.join(", ")));
+                }
                 output.push_str(&format!("module: {}\n", symbol.module));
                 output.push_str(&format!("risk: {}\n", symbol.risk));
+                if let Some(ownership) = &ownership {
+                    let owners = ownership.owners_of(&symbol.file);
+                    if !owners.is_empty() {
+                        output.push_str(&format!("owners: {}\n", owners.join(", ")));
+                    }
+                    if let Some(author) = ownership.authors.get(&symbol.hash) {
+                        output.push_str(&format!("author: {}\n", author.describe()));
+                    }
+                }
+                if let Some(links) = &links {
+                    if let Some(refs) = links.tested_by.get(&symbol.hash) {
+                        output.push_str(&format!("tested_by: {}\n", format_file_refs(refs)));
+                    }
+                    if let Some(targets) = links.tests_symbols.get(&symbol.hash) {
+                        let names: Vec<String> = targets
+                            .symbols
+                            .iter()
+                            .map(|s| format!("{} ({})", s.name, s.file))
+                            .collect();
+                        output.push_str(&format!("tests_symbols: {}\n", names.join(", ")));
+                    }
+                }
 
                 if include_source {
                     if let Some(source) =
-                        get_source_for_symbol(&cache, &symbol.file, &symbol.lines, context)
+                        symbol_source(&cache, ctx, &symbol.file, &symbol.lines, context)
                     {
                         output.push_str("\n__source__:\n");
                         output.push_str(&source);
@@ -737,33 +1224,175 @@ pub fn run_get_symbol(
     Ok(output)
 }
 
+/// Put each nested symbol right after the function defining it, children in
+/// source order; files without nested symbols keep their order
+fn nest_order(symbols: Vec<SymbolIndexEntry>) -> Vec<SymbolIndexEntry> {
+    fn visit(
+        idx: usize,
+        symbols: &[SymbolIndexEntry],
+        children: &HashMap<&str, Vec<usize>>,
+        order: &mut Vec<usize>,
+    ) {
+        order.push(idx);
+        for &child in children
+            .get(symbols[idx].hash.as_str())
+            .into_iter()
+            .flatten()
+        {
+            visit(child, symbols, children, order);
+        }
+    }
+
+    if symbols.iter().all(|s| s.parent.is_empty()) {
+        return symbols;
+    }
+    let hashes: HashSet<&str> = symbols.iter().map(|s| s.hash.as_str()).collect();
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (idx, symbol) in symbols.iter().enumerate() {
+        if hashes.contains(symbol.parent.as_str()) {
+            children
+                .entry(symbol.parent.as_str())
+                .or_default()
+                .push(idx);
+        } else {
+            roots.push(idx);
+        }
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by_key(|&idx| start_line(&symbols[idx].lines));
+    }
+
+    let mut order = Vec::with_capacity(symbols.len());
+    for root in roots {
+        visit(root, &symbols, &children, &mut order);
+    }
+    let mut slots: Vec<Option<SymbolIndexEntry>> = symbols.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|idx| slots[idx].take())
+        .collect()
+}
+
+/// Where a symbol sits among the closures, callbacks and nested functions of its file
+#[derive(Default)]
+struct SymbolNesting {
+    /// Function the symbol is defined in
+    parent: Option<SymbolIndexEntry>,
+    /// Symbols defined directly in it, in source order
+    children: Vec<SymbolIndexEntry>,
+}
+
+impl SymbolNesting {
+    fn of(symbol: &SymbolIndexEntry, entries: &[SymbolIndexEntry]) -> Self {
+        let parent = (!symbol.parent.is_empty())
+            .then(|| entries.iter().find(|e| e.hash == symbol.parent).cloned())
+            .flatten();
+        let mut children: Vec<SymbolIndexEntry> = entries
+            .iter()
+            .filter(|e| !e.parent.is_empty() && e.parent == symbol.hash)
+            .cloned()
+            .collect();
+        children.sort_by_key(|e| start_line(&e.lines));
+        Self { parent, children }
+    }
+
+    fn add_to_json(&self, val: &mut serde_json::Value) {
+        let Some(obj) = val.as_object_mut() else {
+            return;
+        };
+        if let Some(parent) = &self.parent {
+            obj.insert("parent_name".to_string(), serde_json::json!(parent.symbol));
+        }
+        if !self.children.is_empty() {
+            let children: Vec<serde_json::Value> = self
+                .children
+                .iter()
+                .map(|c| serde_json::json!({"name": c.symbol, "hash": c.hash, "lines": c.lines}))
+                .collect();
+            obj.insert("children".to_string(), serde_json::json!(children));
+        }
+    }
+}
+
+/// First line of a `start-end` range (0 if unparseable)
+fn start_line(lines: &str) -> usize {
+    lines
+        .split('-')
+        .next()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Symbol entry as JSON, with its test links and owners when the index has them
+fn symbol_json(
+    entry: &SymbolIndexEntry,
+    links: Option<&TestLinkIndex>,
+    ownership: Option<&OwnershipIndex>,
+) -> serde_json::Value {
+    let mut val = serde_json::to_value(entry).unwrap_or_default();
+    if let (Some(ownership), Some(obj)) = (ownership, val.as_object_mut()) {
+        let owners = ownership.owners_of(&entry.file);
+        if !owners.is_empty() {
+            obj.insert("owners".to_string(), serde_json::json!(owners));
+        }
+        if let Some(author) = ownership.authors.get(&entry.hash) {
+            obj.insert("author".to_string(), serde_json::json!(author));
+        }
+    }
+    if let (Some(links), Some(obj)) = (links, val.as_object_mut()) {
+        if let Some(refs) = links.tested_by.get(&entry.hash) {
+            obj.insert("tested_by".to_string(), serde_json::json!(refs));
+        }
+        if let Some(targets) = links.tests_symbols.get(&entry.hash) {
+            obj.insert(
+                "tests_symbols".to_string(),
+                serde_json::json!(targets.symbols),
+            );
+        }
+    }
+    val
+}
+
+/// `file [confidence], ...`
+fn format_file_refs(refs: &[FileRef]) -> String {
+    refs.iter()
+        .map(|r| format!("{} [{}]", r.file, r.confidence.as_str()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Find symbol at a specific file:line location
 fn find_symbol_by_location(
     cache: &CacheDir,
+    ctx: &CommandContext,
     file_path: &str,
     line: usize,
 ) -> Result<SymbolIndexEntry> {
-    let entries = cache
+    let mut entries = cache
         .load_all_symbol_entries()
         .map_err(|e| McpDiffError::FileNotFound {
             path: format!("Failed to load symbol index: {}", e),
         })?;
+    if let Some(ai_layer) = &ctx.ai_layer {
+        entries = ai_layer.apply_to_entries(entries);
+    }
 
+    // Innermost symbol, so a line in a callback resolves to the callback
     entries
         .into_iter()
-        .find(|e| {
+        .filter_map(|e| {
             // Check if file matches (allow partial path matching)
             if !e.file.ends_with(file_path) && !file_path.ends_with(&e.file) {
-                return false;
+                return None;
             }
             // Check if line is within range
-            if let Some((start, end)) = e.lines.split_once('-') {
-                if let (Ok(s), Ok(en)) = (start.parse::<usize>(), end.parse::<usize>()) {
-                    return line >= s && line <= en;
-                }
-            }
-            false
+            let (start, end) = e.lines.split_once('-')?;
+            let (s, en) = (start.parse::<usize>().ok()?, end.parse::<usize>().ok()?);
+            (line >= s && line <= en).then_some((en - s, e))
         })
+        .min_by_key(|(span, _)| *span)
+        .map(|(_, e)| e)
         .ok_or_else(|| McpDiffError::FileNotFound {
             path: format!("No symbol found at {}:{}", file_path, line),
         })
@@ -884,7 +1513,7 @@ fn run_batch_source(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!(
                 "Batch source: {} requested, {} found\n",
                 hashes.len(),
@@ -929,7 +1558,10 @@ fn format_file_source(
         });
     }
 
-    let content = fs::read_to_string(&file_path)?;
+    let content = {
+        let _timer = PhaseTimer::start(Phase::FilesystemIo);
+        fs::read_to_string(&file_path)?
+    };
     let lines: Vec<&str> = content.lines().collect();
 
     // Calculate range with context
@@ -969,7 +1601,7 @@ fn format_file_source(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!("file: {}\n", file));
             output.push_str(&format!("range: {}-{}\n", actual_start, actual_end));
             output.push_str("---\n");
@@ -1014,13 +1646,19 @@ pub fn run_get_callers(
     let cache = CacheDir::for_repo(&repo_dir)?;
 
     // Load call graph
-    let call_graph = cache.load_call_graph()?;
+    let mut call_graph = cache.load_call_graph()?;
     if call_graph.is_empty() {
         return Err(McpDiffError::FileNotFound {
             path: "Call graph not found or empty. Run `semfora index generate` first.".to_string(),
         });
     }
 
+    let mut entries = cache.load_all_symbol_entries().unwrap_or_default();
+    if let Some(ai_layer) = &ctx.ai_layer {
+        entries = ai_layer.apply_to_entries(entries);
+        ai_layer.apply_to_call_graph(&mut call_graph, &entries);
+    }
+
     // Build reverse call graph (callee -> callers)
     let mut reverse_graph: HashMap<String, Vec<String>> = HashMap::new();
     for (caller, callees) in &call_graph {
@@ -1038,15 +1676,24 @@ pub fn run_get_callers(
     // Load symbol entries for resolution
     let mut symbol_names: HashMap<String, String> = HashMap::new();
     let mut target_entry: Option<SymbolIndexEntry> = None;
-    if let Ok(entries) = cache.load_all_symbol_entries() {
-        for entry in entries {
-            if entry.hash == hash {
-                target_entry = Some(entry.clone());
-            }
-            symbol_names.insert(entry.hash.clone(), entry.symbol.clone());
+    for entry in &entries {
+        if entry.hash == hash {
+            target_entry = Some(entry.clone());
         }
+        symbol_names.insert(entry.hash.clone(), entry.symbol.clone());
     }
 
+    // Parts of a partial type are separate symbols; callers of any part are
+    // callers of the type
+    let parts: Vec<(String, String)> = match &target_entry {
+        Some(target) if !target.partial_of.is_empty() => entries
+            .iter()
+            .filter(|e| e.partial_of == target.partial_of && e.kind == target.kind)
+            .map(|e| (e.hash.clone(), e.file.clone()))
+            .collect(),
+        _ => Vec::new(),
+    };
+
     // Get target name and framework entry point
     let target_name = symbol_names
         .get(hash)
@@ -1062,6 +1709,12 @@ pub fn run_get_callers(
     let mut all_callers: Vec<(String, String, usize)> = Vec::new(); // (hash, name, depth)
     let mut visited: HashSet<String> = HashSet::new();
     let mut current_level: Vec<String> = vec![hash.to_string()];
+    current_level.extend(
+        parts
+            .iter()
+            .map(|(h, _)| h.clone())
+            .filter(|h| h.as_str() != hash),
+    );
 
     for current_depth in 1..=depth {
         let mut next_level: Vec<String> = Vec::new();
@@ -1114,6 +1767,10 @@ pub fn run_get_callers(
         "depth": depth,
         "callers": callers_json,
         "count": all_callers.len(),
+        "parts": parts
+            .iter()
+            .map(|(h, f)| serde_json::json!({ "hash": h, "file": f }))
+            .collect::<Vec<_>>(),
         "framework_entry_point": fep_str,
         "is_framework_entry_point": !target_fep.is_none()
     });
@@ -1129,6 +1786,12 @@ pub fn run_get_callers(
             output.push_str(&format!("target: {} ({})\n", target_name, hash));
             output.push_str(&format!("depth: {}\n", depth));
             output.push_str(&format!("total_callers: {}\n", all_callers.len()));
+            if !parts.is_empty() {
+                output.push_str(&format!("parts[{}]{{hash,file}}:\n", parts.len()));
+                for (h, f) in &parts {
+                    output.push_str(&format!("  {},{}\n", h, f));
+                }
+            }
 
             if all_callers.is_empty() {
                 if !target_fep.is_none() {
@@ -1154,9 +1817,9 @@ pub fn run_get_callers(
             if include_source && !all_callers.is_empty() {
                 output.push_str("\n__caller_sources__:\n");
                 for (caller_hash, caller_name, _) in all_callers.iter().take(5) {
-                    if let Some(symbol) = load_symbol_from_cache(&cache, caller_hash)? {
+                    if let Some(symbol) = load_symbol(&cache, ctx, caller_hash)? {
                         if let Some(source) =
-                            get_source_for_symbol(&cache, &symbol.file, &symbol.lines, 1)
+                            symbol_source(&cache, ctx, &symbol.file, &symbol.lines, 1)
                         {
                             output
                                 .push_str(&format!("--- {} ({}) ---\n", caller_name, caller_hash));
@@ -1169,12 +1832,18 @@ pub fn run_get_callers(
                 }
             }
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  CALLERS\n");
             output.push_str("═══════════════════════════════════════════\n\n");
             output.push_str(&format!("target: {} ({})\n", target_name, hash));
             output.push_str(&format!("depth: {}\n", depth));
+            if !parts.is_empty() {
+                output.push_str(&format!("parts[{}]:\n", parts.len()));
+                for (h, f) in &parts {
+                    output.push_str(&format!("  {} ({})\n", f, h));
+                }
+            }
             output.push_str(&format!("callers[{}]:\n", all_callers.len()));
 
             if all_callers.is_empty() {
@@ -1191,9 +1860,9 @@ pub fn run_get_callers(
                     output.push_str(&format!("  [d{}] {} ({})\n", d, caller_name, caller_hash));
 
                     if include_source {
-                        if let Some(symbol) = load_symbol_from_cache(&cache, caller_hash)? {
+                        if let Some(symbol) = load_symbol(&cache, ctx, caller_hash)? {
                             if let Some(source) =
-                                get_source_for_symbol(&cache, &symbol.file, &symbol.lines, 1)
+                                symbol_source(&cache, ctx, &symbol.file, &symbol.lines, 1)
                             {
                                 output.push_str(&format!(
                                     "       # {}:{}\n",
@@ -1219,7 +1888,9 @@ pub fn run_get_callers(
 pub fn run_get_callgraph(
     path: Option<&PathBuf>,
     module: Option<&str>,
+    exclude_modules: &[String],
     symbol: Option<&str>,
+    package: Option<&str>,
     export: Option<&str>,
     stats_only: bool,
     limit: usize,
@@ -1252,13 +1923,58 @@ pub fn run_get_callgraph(
 
     let call_graph = filter_escape_edges(call_graph, include_escape_refs);
 
+    let entries = cache.load_all_symbol_entries().unwrap_or_default();
+
+    // Callers inside the --package workspace package
+    let package_hashes: Option<HashSet<String>> = match package {
+        Some(name) => {
+            let package = resolve_package(&cache.repo_root, name)?;
+            Some(
+                entries
+                    .iter()
+                    .filter(|e| package.contains(&repo_relative(&cache.repo_root, &e.file)))
+                    .map(|e| e.hash.clone())
+                    .collect(),
+            )
+        }
+        None => None,
+    };
+    let call_graph: HashMap<String, Vec<String>> = match package_hashes {
+        Some(ref hashes) => call_graph
+            .into_iter()
+            .filter(|(caller, _)| hashes.contains(caller))
+            .collect(),
+        None => call_graph,
+    };
+
+    // Drop callers and callees in --exclude-module modules
+    let call_graph = if exclude_modules.is_empty() {
+        call_graph
+    } else {
+        let excluded: HashSet<&str> = entries
+            .iter()
+            .filter(|e| is_excluded_module(&e.module, exclude_modules))
+            .map(|e| e.hash.as_str())
+            .collect();
+        call_graph
+            .into_iter()
+            .filter(|(caller, _)| !excluded.contains(caller.as_str()))
+            .filter_map(|(caller, callees)| {
+                let kept: Vec<String> = callees
+                    .into_iter()
+                    .filter(|c| {
+                        let edge = crate::schema::CallGraphEdge::decode(c);
+                        !excluded.contains(edge.callee.as_str())
+                    })
+                    .collect();
+                (!kept.is_empty()).then_some((caller, kept))
+            })
+            .collect()
+    };
+
     // Build hash-to-name mapping for symbol resolution
-    let hash_to_name: HashMap<String, String> = cache
-        .load_all_symbol_entries()
-        .unwrap_or_default()
-        .into_iter()
-        .map(|e| (e.hash, e.symbol))
-        .collect();
+    let hash_to_name: HashMap<String, String> =
+        entries.into_iter().map(|e| (e.hash, e.symbol)).collect();
 
     // Resolve symbol filter to matching hashes (enables name-based lookup)
     let resolved_symbol_hashes: Option<HashSet<String>> = if let Some(sym) = symbol {
@@ -1338,6 +2054,7 @@ pub fn run_get_callgraph(
             "avg_callees_per_caller": total_calls as f64 / total_edges.max(1) as f64,
             "module_filter": module,
             "symbol_filter": symbol,
+            "package_filter": package,
             "top_callers_by_fan_out": top_callers
         });
 
@@ -1350,7 +2067,7 @@ pub fn run_get_callgraph(
             OutputFormat::Toon => {
                 output = super::encode_toon(&json_value);
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                 output.push_str("═══════════════════════════════════════════\n");
                 output.push_str("  CALL GRAPH SUMMARY\n");
                 output.push_str("═══════════════════════════════════════════\n\n");
@@ -1366,6 +2083,9 @@ pub fn run_get_callgraph(
                 if let Some(s) = symbol {
                     output.push_str(&format!("symbol_filter: {}\n", s));
                 }
+                if let Some(p) = package {
+                    output.push_str(&format!("package_filter: {}\n", p));
+                }
 
                 if !caller_stats.is_empty() {
                     output.push_str("\ntop_callers_by_fan_out:\n");
@@ -1447,7 +2167,7 @@ pub fn run_get_callgraph(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  CALL GRAPH\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -1542,7 +2262,7 @@ fn run_export_sqlite(
     path: &str,
     cache: &CacheDir,
     include_escape_refs: bool,
-    _ctx: &CommandContext,
+    ctx: &CommandContext,
 ) -> Result<String> {
     use crate::sqlite_export::{default_export_path, SqliteExporter};
 
@@ -1555,7 +2275,12 @@ fn run_export_sqlite(
     eprintln!("Exporting call graph to: {}", output_path.display());
 
     let exporter = SqliteExporter::new();
-    let stats = exporter.export(cache, &output_path, None, include_escape_refs)?;
+    let stats = exporter.export(
+        cache,
+        &output_path,
+        ctx.export_progress(),
+        include_escape_refs,
+    )?;
 
     Ok(format!(
         "Export complete:\n  Path: {}\n  Nodes: {}\n  Edges: {}\n  Size: {} bytes",
@@ -1625,7 +2350,7 @@ pub fn run_file_symbols(
                 "symbols": [],
                 "hint": "File may not be indexed or path doesn't match."
             }).to_string()),
-            OutputFormat::Toon | OutputFormat::Text => Ok(format!(
+            OutputFormat::Toon | OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => Ok(format!(
                 "{}file: \"{}\"\nshowing: 0\nsymbols: (none)\nhint: File may not be indexed or path doesn't match.\n",
                 super::toon_header("file_symbols"),
                 file_path
@@ -1633,22 +2358,58 @@ pub fn run_file_symbols(
         };
     }
 
+    let symbols = nest_order(symbols);
+
+    let ownership = OwnershipIndex::load(&cache).unwrap_or_default();
+    let owners = ownership.owners_of(&symbols[0].file);
+    let author = |sym: &SymbolIndexEntry| ownership.authors.get(&sym.hash);
+    let has_authors = symbols.iter().any(|sym| author(sym).is_some());
+
+    // Closures, callbacks and nested functions under the function defining them
+    let parent_name = |sym: &SymbolIndexEntry| {
+        symbols
+            .iter()
+            .find(|p| !sym.parent.is_empty() && p.hash == sym.parent)
+            .map_or("", |p| p.symbol.as_str())
+    };
+    let depth = |sym: &SymbolIndexEntry| {
+        let mut depth = 0;
+        let mut parent = sym.parent.as_str();
+        while let Some(p) = symbols
+            .iter()
+            .find(|p| !parent.is_empty() && p.hash == parent)
+        {
+            depth += 1;
+            parent = p.parent.as_str();
+        }
+        depth
+    };
+    let has_nesting = symbols.iter().any(|sym| !sym.parent.is_empty());
+
     // Build JSON representation
     let symbols_json: Vec<serde_json::Value> = symbols
         .iter()
         .map(|sym| {
-            serde_json::json!({
+            let mut val = serde_json::json!({
                 "name": sym.symbol,
                 "hash": sym.hash,
                 "kind": sym.kind,
                 "lines": sym.lines,
                 "risk": sym.risk,
                 "module": sym.module
-            })
+            });
+            if !sym.parent.is_empty() {
+                val["parent"] = serde_json::json!(sym.parent);
+                val["parent_name"] = serde_json::json!(parent_name(sym));
+            }
+            if let Some(author) = author(sym) {
+                val["author"] = serde_json::json!(author);
+            }
+            val
         })
         .collect();
 
-    let json_value = serde_json::json!({
+    let mut json_value = serde_json::json!({
         "_type": "file_symbols",
         "file": file_path,
         "count": symbols.len(),
@@ -1656,6 +2417,9 @@ pub fn run_file_symbols(
         "risk_filter": risk_filter,
         "symbols": symbols_json
     });
+    if !owners.is_empty() {
+        json_value["owners"] = serde_json::json!(owners);
+    }
 
     let mut output = String::new();
 
@@ -1667,17 +2431,30 @@ pub fn run_file_symbols(
             // Compact TOON format with key columns
             output.push_str(&super::toon_header("file_symbols"));
             output.push_str(&format!("file: \"{}\"\n", file_path));
+            if !owners.is_empty() {
+                output.push_str(&format!("owners: \"{}\"\n", owners.join(", ")));
+            }
             output.push_str(&format!("showing: {}\n", symbols.len()));
             output.push_str(&format!(
-                "symbols[{}]{{name,hash,kind,lines,risk}}:\n",
-                symbols.len()
+                "symbols[{}]{{name,hash,kind,lines,risk{}{}}}:\n",
+                symbols.len(),
+                if has_nesting { ",parent" } else { "" },
+                if has_authors { ",author" } else { "" }
             ));
 
             for sym in &symbols {
                 output.push_str(&format!(
-                    "  {},{},{},{},{}\n",
+                    "  {},{},{},{},{}",
                     sym.symbol, sym.hash, sym.kind, sym.lines, sym.risk
                 ));
+                if has_nesting {
+                    output.push_str(&format!(",{}", parent_name(sym)));
+                }
+                if has_authors {
+                    let name = author(sym).map_or("", |a| a.author.as_str());
+                    output.push_str(&format!(",{}", name));
+                }
+                output.push('\n');
             }
 
             // Include source if requested
@@ -1693,18 +2470,28 @@ pub fn run_file_symbols(
                 }
             }
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str(&format!("  FILE: {}\n", file_path));
             output.push_str("═══════════════════════════════════════════\n\n");
+            if !owners.is_empty() {
+                output.push_str(&format!("owners: {}\n", owners.join(", ")));
+            }
             output.push_str(&format!("symbols[{}]:\n", symbols.len()));
 
             for sym in &symbols {
+                let indent = "  ".repeat(depth(sym));
                 output.push_str(&format!(
-                    "  {} ({}) L{} [{}]\n",
-                    sym.symbol, sym.kind, sym.lines, sym.risk
+                    "  {}{} ({}) L{} [{}]\n",
+                    indent, sym.symbol, sym.kind, sym.lines, sym.risk
                 ));
-                output.push_str(&format!("    hash: {}\n", sym.hash));
+                output.push_str(&format!("    {}hash: {}\n", indent, sym.hash));
+                if !sym.parent.is_empty() {
+                    output.push_str(&format!("    {}parent: {}\n", indent, parent_name(sym)));
+                }
+                if let Some(author) = author(sym) {
+                    output.push_str(&format!("    author: {}\n", author.describe()));
+                }
 
                 if include_source {
                     if let Some(source) =
@@ -1724,21 +2511,1638 @@ pub fn run_file_symbols(
 }
 
 /// List supported languages
-fn run_list_languages(ctx: &CommandContext) -> Result<String> {
-    // All supported languages with their extensions
-    let languages = vec![
-        ("TypeScript", vec!["ts", "mts", "cts"]),
-        ("Tsx", vec!["tsx"]),
-        ("JavaScript", vec!["js", "mjs", "cjs"]),
-        ("Jsx", vec!["jsx"]),
-        ("Rust", vec!["rs"]),
-        ("Python", vec!["py", "pyi"]),
+/// Show the application symbols that consume a contract element
+///
+/// Consumers are call sites whose callee name matches the generated-code name
+/// of the protobuf/OpenAPI element (see `contracts::find_contract_usages`).
+pub fn run_contract_usages(
+    path: Option<&PathBuf>,
+    target: &str,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let usages = find_contract_usages(&cache, target)?;
+
+    let json_value = serde_json::json!({
+        "_type": "contract_usages",
+        "target": target,
+        "elements": usages.iter().map(|u| serde_json::json!({
+            "name": u.element.name,
+            "kind": u.element.kind,
+            "file": u.element.file,
+            "matched_names": u.element.consumer_names,
+            "consumers": u.consumers.iter().take(limit).map(|c| serde_json::json!({
+                "symbol": c.symbol,
+                "hash": c.hash,
+                "file": c.file,
+                "lines": c.lines,
+                "via": c.via,
+            })).collect::<Vec<_>>(),
+            "consumer_count": u.consumers.len(),
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("contract_usages"));
+            output.push_str(&format!("target: \"{}\"\n", target));
+            if usages.is_empty() {
+                output
+                    .push_str("elements: (none - no indexed .proto or OpenAPI element matches)\n");
+            }
+            for usage in &usages {
+                output.push_str(&format!(
+                    "\n{} {} ({})\n",
+                    usage.element.kind, usage.element.name, usage.element.file
+                ));
+                if usage.consumers.is_empty() {
+                    output.push_str("consumers: (none)\n");
+                    continue;
+                }
+                output.push_str(&format!(
+                    "consumers[{}]{{symbol,hash,file,lines,via}}:\n",
+                    usage.consumers.len()
+                ));
+                for c in usage.consumers.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {},{},{},{},{}\n",
+                        c.symbol, c.hash, c.file, c.lines, c.via
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  CONTRACT USAGES\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!("target: {}\n", target));
+            if usages.is_empty() {
+                output.push_str("  (no indexed .proto or OpenAPI element matches)\n");
+            }
+            for usage in &usages {
+                output.push_str(&format!(
+                    "\n{} {} ({})\n",
+                    usage.element.kind, usage.element.name, usage.element.file
+                ));
+                if usage.consumers.is_empty() {
+                    output.push_str("  (no consumers found)\n");
+                }
+                for c in usage.consumers.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {} ({}:{}) via {}\n",
+                        c.symbol, c.file, c.lines, c.via
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Show the modules importing from `module` (see `module_usage`)
+pub fn run_module_usages(
+    path: Option<&PathBuf>,
+    module: &str,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let index = ModuleUsageIndex::load(&cache)?;
+    let module = resolve_module_name(&index, module)?;
+    let importers = index.usages(&module);
+    let statements: usize = importers.iter().map(|i| i.count).sum();
+
+    let json_value = serde_json::json!({
+        "_type": "module_usages",
+        "module": module,
+        "importer_count": importers.len(),
+        "import_count": statements,
+        "importers": importers.iter().take(limit).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("module_usages"));
+            output.push_str(&format!("module: \"{}\"\n", module));
+            output.push_str(&format!("import_count: {}\n", statements));
+            if importers.is_empty() {
+                output.push_str("importers: (none - no other module imports this one)\n");
+            } else {
+                output.push_str(&format!(
+                    "importers[{}]{{module,count,names,via}}:\n",
+                    importers.len()
+                ));
+                for importer in importers.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {},{},\"{}\",{}\n",
+                        importer.module,
+                        importer.count,
+                        join_set(&importer.names),
+                        join_set(&importer.via)
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  MODULE USAGES\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!("module: {}\n", module));
+            output.push_str(&format!(
+                "imported by {} module(s) in {} statement(s)\n",
+                importers.len(),
+                statements
+            ));
+            for importer in importers.iter().take(limit) {
+                output.push_str(&format!(
+                    "\n{} ({} import{})\n",
+                    importer.module,
+                    importer.count,
+                    if importer.count == 1 { "" } else { "s" }
+                ));
+                output.push_str(&format!("  names: {}\n", join_set(&importer.names)));
+                if !importer.via.is_empty() {
+                    output.push_str(&format!("  via: {}\n", join_set(&importer.via)));
+                }
+                if importer.dynamic_only {
+                    output.push_str("  dynamic import() only\n");
+                }
+                for file in &importer.files {
+                    output.push_str(&format!("  {}\n", file));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Cache directory of the repository at `path` (defaults to the current directory)
+fn repo_cache(path: Option<&PathBuf>) -> Result<CacheDir> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    CacheDir::for_repo(&repo_dir)
+}
+
+/// List the names a file imports (its `added_dependencies`), with the
+/// package or path each comes from when known
+///
+/// The index keeps only a capped dependency list per file, so the file is
+/// parsed again from source.
+pub fn run_query_imports(file: &str, cache: &CacheDir, ctx: &CommandContext) -> Result<String> {
+    let relative = file.trim_start_matches("./");
+    let full_path = cache.repo_root.join(relative);
+    let source = fs::read_to_string(&full_path).map_err(|_| McpDiffError::FileNotFound {
+        path: full_path.display().to_string(),
+    })?;
+    let lang = Lang::from_path(&full_path)?;
+    let summary = parse_and_extract(&full_path, &source, lang)?;
+    let imports: Vec<(&str, Option<&str>)> = summary
+        .added_dependencies
+        .iter()
+        .map(|name| {
+            let source = summary
+                .import_sources
+                .get(name)
+                .or_else(|| {
+                    summary
+                        .imports
+                        .iter()
+                        .find(|import| {
+                            import
+                                .names
+                                .iter()
+                                .any(|n| n.alias.as_deref().unwrap_or(&n.name) == name.as_str())
+                        })
+                        .map(|import| &import.source)
+                })
+                .map(String::as_str);
+            (name.as_str(), source)
+        })
+        .collect();
+    let display = ctx.display_path(relative, &cache.repo_root);
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            let json_value = serde_json::json!({
+                "_type": "file_imports",
+                "file": display,
+                "count": imports.len(),
+                "imports": imports
+                    .iter()
+                    .map(|(name, source)| serde_json::json!({"name": name, "source": source}))
+                    .collect::<Vec<_>>(),
+                "local_imports": summary.local_imports,
+            });
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("file_imports"));
+            output.push_str(&format!("file: \"{}\"\n", display));
+            if imports.is_empty() {
+                output.push_str("imports: (none)\n");
+            } else {
+                output.push_str(&format!("imports[{}]{{name,source}}:\n", imports.len()));
+                for (name, source) in &imports {
+                    output.push_str(&format!("  {},{}\n", name, source.unwrap_or("")));
+                }
+            }
+            if !summary.local_imports.is_empty() {
+                output.push_str(&format!(
+                    "local_imports[{}]: {}\n",
+                    summary.local_imports.len(),
+                    summary.local_imports.join(",")
+                ));
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str(&format!("  IMPORTS: {}\n", display));
+            output.push_str("═══════════════════════════════════════════\n\n");
+            if imports.is_empty() {
+                output.push_str("no imports\n");
+            }
+            for (name, source) in &imports {
+                match source {
+                    Some(source) => output.push_str(&format!("  {}  (from {})\n", name, source)),
+                    None => output.push_str(&format!("  {}\n", name)),
+                }
+            }
+            if !summary.local_imports.is_empty() {
+                output.push_str("\nLocal files:\n");
+                for local in &summary.local_imports {
+                    output.push_str(&format!("  {}\n", local));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// List the symbols a module exports
+///
+/// `module` is resolved like [`resolve_module_name`], so `auth` finds
+/// `src.auth`.
+pub fn run_query_exports(module: &str, cache: &CacheDir, ctx: &CommandContext) -> Result<String> {
+    let entries = cache
+        .load_all_symbol_entries()
+        .map_err(|e| McpDiffError::FileNotFound {
+            path: format!("Failed to load symbol index: {}", e),
+        })?;
+    let modules = entries.iter().map(|e| e.module.as_str()).collect();
+    let module = match_module_name(&modules, module)?;
+    let mut exports: Vec<SymbolIndexEntry> = entries
+        .into_iter()
+        .filter(|e| e.module == module && e.is_exported && !e.is_escape_local)
+        .collect();
+    exports.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then_with(|| symbol_start(a).cmp(&symbol_start(b)))
+    });
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            let json_value = serde_json::json!({
+                "_type": "module_exports",
+                "module": module,
+                "count": exports.len(),
+                "exports": exports
+                    .iter()
+                    .map(|e| serde_json::json!({
+                        "name": e.symbol,
+                        "kind": e.kind,
+                        "hash": e.hash,
+                        "file": ctx.display_path(&e.file, &cache.repo_root),
+                        "lines": e.lines,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("module_exports"));
+            output.push_str(&format!("module: \"{}\"\n", module));
+            if exports.is_empty() {
+                output.push_str("exports: (none)\n");
+            } else {
+                output.push_str(&format!(
+                    "exports[{}]{{name,kind,hash,file,lines}}:\n",
+                    exports.len()
+                ));
+                for e in &exports {
+                    output.push_str(&format!(
+                        "  {},{},{},{},{}\n",
+                        e.symbol,
+                        e.kind,
+                        e.hash,
+                        ctx.display_path(&e.file, &cache.repo_root),
+                        e.lines
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str(&format!("  EXPORTS: {}\n", module));
+            output.push_str("═══════════════════════════════════════════\n\n");
+            if exports.is_empty() {
+                output.push_str("no exported symbols\n");
+            }
+            for e in &exports {
+                output.push_str(&format!(
+                    "  {} {}  [{}:{}]\n",
+                    e.kind,
+                    e.symbol,
+                    ctx.display_path(&e.file, &cache.repo_root),
+                    e.lines
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Show the tests linked to a symbol or file, or what a test exercises
+/// (see `test_links`)
+///
+/// Production targets list the test files covering them; test symbols and
+/// test files list the production files and symbols they exercise.
+pub fn run_tests_for(
+    path: Option<&PathBuf>,
+    target: &str,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let links = TestLinkIndex::load(&cache)?;
+
+    let mut resolved = target.to_string();
+    let mut tests: Vec<FileRef> = Vec::new();
+    let mut covers: Vec<FileRef> = Vec::new();
+    let mut symbols: Vec<SymbolLink> = Vec::new();
+    let role = if let Some(targets) = links.tests_symbols.get(target) {
+        resolved = format!("{} ({})", targets.name, targets.file);
+        symbols = targets.symbols.clone();
+        "test"
+    } else if let Some(refs) = links.tested_by.get(target) {
+        tests = refs.clone();
+        "production"
+    } else if let Some((file, refs)) = links.tests_for_file(target) {
+        resolved = file.to_string();
+        tests = refs.to_vec();
+        "production"
+    } else if links.is_test_file(target) || crate::search::is_test_file(target) {
+        covers = links.sources_for_test_file(target);
+        symbols = links.symbols_for_test_file(target);
+        "test"
+    } else {
+        "production"
+    };
+
+    let json_value = serde_json::json!({
+        "_type": "tests_for",
+        "target": resolved,
+        "role": role,
+        "tests": tests.iter().take(limit).collect::<Vec<_>>(),
+        "covers": covers.iter().take(limit).collect::<Vec<_>>(),
+        "symbols": symbols.iter().take(limit).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("tests_for"));
+            output.push_str(&format!("target: \"{}\"\n", resolved));
+            output.push_str(&format!("role: {}\n", role));
+            if role == "production" {
+                if tests.is_empty() {
+                    output.push_str("tests: (none - no linked tests)\n");
+                } else {
+                    output.push_str(&format!("tests[{}]{{file,confidence}}:\n", tests.len()));
+                    for r in tests.iter().take(limit) {
+                        output.push_str(&format!("  {},{}\n", r.file, r.confidence.as_str()));
+                    }
+                }
+            } else {
+                if !covers.is_empty() {
+                    output.push_str(&format!("covers[{}]{{file,confidence}}:\n", covers.len()));
+                    for r in covers.iter().take(limit) {
+                        output.push_str(&format!("  {},{}\n", r.file, r.confidence.as_str()));
+                    }
+                }
+                if symbols.is_empty() {
+                    output.push_str("symbols: (none - no calls into indexed code)\n");
+                } else {
+                    output.push_str(&format!(
+                        "symbols[{}]{{name,file,hash,confidence}}:\n",
+                        symbols.len()
+                    ));
+                    for s in symbols.iter().take(limit) {
+                        output.push_str(&format!(
+                            "  {},{},{},{}\n",
+                            s.name,
+                            s.file,
+                            s.hash,
+                            s.confidence.as_str()
+                        ));
+                    }
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  TESTS FOR\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!("target: {} ({})\n", resolved, role));
+            if role == "production" {
+                if tests.is_empty() {
+                    output.push_str("\nno linked tests\n");
+                }
+                for r in tests.iter().take(limit) {
+                    output.push_str(&format!("  {} [{}]\n", r.file, r.confidence.as_str()));
+                }
+            } else {
+                if !covers.is_empty() {
+                    output.push_str("\ncovers:\n");
+                }
+                for r in covers.iter().take(limit) {
+                    output.push_str(&format!("  {} [{}]\n", r.file, r.confidence.as_str()));
+                }
+                if !symbols.is_empty() {
+                    output.push_str("\nexercises:\n");
+                }
+                for s in symbols.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {} ({}) {} [{}]\n",
+                        s.name,
+                        s.file,
+                        s.hash,
+                        s.confidence.as_str()
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Show the commits that changed a symbol, newest first
+///
+/// Follows the symbol's lines through `git log -L`, so the history carries on
+/// across function and file renames. Lines come from the index, so they refer
+/// to the file as last indexed.
+pub fn run_symbol_history(
+    path: Option<&PathBuf>,
+    hash: &str,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let symbol = load_symbol(&cache, ctx, hash)?.ok_or_else(|| McpDiffError::FileNotFound {
+        path: format!("Symbol not found: {}", hash),
+    })?;
+
+    let (start, end) = symbol
+        .lines
+        .split_once('-')
+        .and_then(|(s, e)| Some((s.parse::<usize>().ok()?, e.parse::<usize>().ok()?)))
+        .ok_or_else(|| McpDiffError::QueryError {
+            message: format!("symbol {} has no line range in the index", hash),
+        })?;
+    let history = get_line_range_history(&symbol.file, start, end, Some(&cache.repo_root))?;
+
+    let commits: Vec<serde_json::Value> = history
+        .iter()
+        .take(limit)
+        .map(|c| {
+            serde_json::json!({
+                "sha": c.commit.sha,
+                "short_sha": c.commit.short_sha,
+                "date": c.commit.date,
+                "author": c.commit.author,
+                "subject": c.commit.subject,
+                "file": c.path,
+                "change": if c.introduced { "added" } else { "modified" },
+            })
+        })
+        .collect();
+    let json_value = serde_json::json!({
+        "_type": "symbol_history",
+        "symbol": symbol.symbol,
+        "hash": hash,
+        "file": symbol.file,
+        "lines": symbol.lines,
+        "count": history.len(),
+        "commits": commits,
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("symbol_history"));
+            output.push_str(&format!("symbol: \"{}\"\n", symbol.symbol));
+            output.push_str(&format!("file: \"{}\"\n", symbol.file));
+            output.push_str(&format!("lines: \"{}\"\n", symbol.lines));
+            if history.is_empty() {
+                output.push_str("commits: (none - not committed yet)\n");
+            } else {
+                output.push_str(&format!(
+                    "commits[{}]{{sha,date,author,change,file,subject}}:\n",
+                    history.len()
+                ));
+                for c in history.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {},{},\"{}\",{},{},\"{}\"\n",
+                        c.commit.short_sha,
+                        c.commit.date,
+                        c.commit.author,
+                        if c.introduced { "added" } else { "modified" },
+                        c.path,
+                        c.commit.subject
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  SYMBOL HISTORY\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!(
+                "symbol: {} ({}:{})\n\n",
+                symbol.symbol, symbol.file, symbol.lines
+            ));
+            if history.is_empty() {
+                output.push_str("  (none - not committed yet)\n");
+            }
+            for c in history.iter().take(limit) {
+                let date = c.commit.date.get(..10).unwrap_or(&c.commit.date);
+                output.push_str(&format!(
+                    "  {} {} {}{}\n",
+                    c.commit.short_sha,
+                    date,
+                    c.commit.subject,
+                    if c.introduced { " [added]" } else { "" }
+                ));
+                if c.path != symbol.file {
+                    output.push_str(&format!("          in {}\n", c.path));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// List modules with no inbound imports and no entry points
+pub fn run_unused_modules(
+    path: Option<&PathBuf>,
+    limit: usize,
+    include_generated: bool,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let index = ModuleUsageIndex::load(&cache)?;
+    let unused = index.unused_modules(include_generated);
+
+    let json_value = serde_json::json!({
+        "_type": "unused_modules",
+        "count": unused.len(),
+        "modules": unused.iter().take(limit).collect::<Vec<_>>(),
+    });
+
+    let mut output = String::new();
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("unused_modules"));
+            if unused.is_empty() {
+                output.push_str("modules: (none)\n");
+            } else {
+                output.push_str(&format!(
+                    "modules[{}]{{module,files,confidence,reason}}:\n",
+                    unused.len()
+                ));
+                for m in unused.iter().take(limit) {
+                    output.push_str(&format!(
+                        "  {},{},{},\"{}\"\n",
+                        m.module, m.files, m.confidence, m.reason
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  UNUSED MODULES\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            if unused.is_empty() {
+                output.push_str("  (none - every module is imported or an entry point)\n");
+            }
+            for m in unused.iter().take(limit) {
+                output.push_str(&format!(
+                    "  {} ({} file{}) [{}] {}\n",
+                    m.module,
+                    m.files,
+                    if m.files == 1 { "" } else { "s" },
+                    m.confidence,
+                    m.reason
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// An exported symbol listed by [`run_api_surface`]
+struct ApiEntry {
+    name: String,
+    kind: String,
+    file: String,
+    lines: String,
+    /// Parameters (arguments, or props for components), as `name: type`
+    params: Vec<String>,
+    returns: Option<String>,
+    /// Fields and members of a type
+    members: Vec<String>,
+}
+
+impl ApiEntry {
+    fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "name": self.name,
+            "kind": self.kind,
+            "file": self.file,
+            "lines": self.lines,
+        });
+        if !self.params.is_empty() {
+            value["params"] = serde_json::json!(self.params);
+        }
+        if let Some(ref returns) = self.returns {
+            value["returns"] = serde_json::json!(returns);
+        }
+        if !self.members.is_empty() {
+            value["members"] = serde_json::json!(self.members);
+        }
+        value
+    }
+}
+
+/// Which section of the API surface a symbol kind belongs to
+fn api_group(kind: &str) -> Option<&'static str> {
+    match SymbolKind::from_str(kind) {
+        SymbolKind::Function
+        | SymbolKind::Component
+        | SymbolKind::Method
+        | SymbolKind::Rpc
+        | SymbolKind::Macro => Some("functions"),
+        SymbolKind::Class
+        | SymbolKind::Interface
+        | SymbolKind::Trait
+        | SymbolKind::Struct
+        | SymbolKind::Enum
+        | SymbolKind::TypeAlias
+        | SymbolKind::Type
+        | SymbolKind::Contract
+        | SymbolKind::Entity
+        | SymbolKind::Repository => Some("types"),
+        SymbolKind::Variable => Some("constants"),
+        SymbolKind::Module | SymbolKind::ConfigKey => None,
+    }
+}
+
+/// First line of an index entry
+fn symbol_start(entry: &SymbolIndexEntry) -> usize {
+    crate::test_selection::parse_lines(&entry.lines).0
+}
+
+/// `name: type`, or just `name` when the type is unknown
+fn typed_name(name: &str, ty: Option<&str>) -> String {
+    match ty.map(|t| t.trim_start_matches(':').trim()) {
+        Some(t) if !t.is_empty() => format!("{}: {}", name, t),
+        _ => name.to_string(),
+    }
+}
+
+/// Parsed symbols of the given files, keyed by file, name and start line
+fn symbols_by_location(
+    repo_root: &std::path::Path,
+    files: &std::collections::BTreeSet<&str>,
+) -> std::collections::HashMap<(String, String, usize), SymbolInfo> {
+    let mut symbols = std::collections::HashMap::new();
+    for file in files {
+        let full_path = repo_root.join(file);
+        let Ok(source) = fs::read_to_string(&full_path) else {
+            continue;
+        };
+        let Ok(lang) = Lang::from_path(&full_path) else {
+            continue;
+        };
+        let Ok(summary) = parse_and_extract(&full_path, &source, lang) else {
+            continue;
+        };
+        for symbol in summary.symbols {
+            let key = (file.to_string(), symbol.name.clone(), symbol.start_line);
+            symbols.insert(key, symbol);
+        }
+    }
+    symbols
+}
+
+/// Show the public API of a module: its exported functions, constants and
+/// (with `include_types`) types, with parameters and return types
+pub fn run_api_surface(
+    path: Option<&PathBuf>,
+    module: &str,
+    include_types: bool,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let mut entries = cache.list_module_symbols(module, None, None, usize::MAX)?;
+    if entries.is_empty() && !cache.module_path(module).exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!("Module '{}' not found", module),
+        });
+    }
+    let total = entries.len();
+    entries.retain(|e| e.is_exported && !e.is_escape_local);
+    entries.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then_with(|| symbol_start(a).cmp(&symbol_start(b)))
+    });
+
+    // Parameters and return types aren't in the index; read them from source
+    let files: std::collections::BTreeSet<&str> = entries.iter().map(|e| e.file.as_str()).collect();
+    let parsed = symbols_by_location(&cache.repo_root, &files);
+
+    let mut groups: std::collections::BTreeMap<&str, Vec<ApiEntry>> =
+        std::collections::BTreeMap::new();
+    for entry in &entries {
+        let Some(group) = api_group(&entry.kind) else {
+            continue;
+        };
+        let key = (
+            entry.file.clone(),
+            entry.symbol.clone(),
+            symbol_start(entry),
+        );
+        let symbol = parsed.get(&key);
+        let mut api = ApiEntry {
+            name: entry.symbol.clone(),
+            kind: entry.kind.clone(),
+            file: ctx.display_path(&entry.file, &cache.repo_root),
+            lines: entry.lines.clone(),
+            params: Vec::new(),
+            returns: None,
+            members: Vec::new(),
+        };
+        if let Some(symbol) = symbol {
+            let props = symbol
+                .props
+                .iter()
+                .map(|p| typed_name(&p.name, p.prop_type.as_deref()));
+            if group == "types" {
+                api.members = props.collect();
+            } else if !symbol.arguments.is_empty() {
+                api.params = symbol
+                    .arguments
+                    .iter()
+                    .map(|a| typed_name(&a.name, a.arg_type.as_deref()))
+                    .collect();
+            } else {
+                api.params = props.collect();
+            }
+            api.returns = symbol.return_type.clone();
+        }
+        groups.entry(group).or_default().push(api);
+    }
+
+    let section = |name: &str| groups.get(name).map(Vec::as_slice).unwrap_or_default();
+    let functions = section("functions");
+    let constants = section("constants");
+    let types = section("types");
+    let exported = functions.len() + constants.len() + types.len();
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            let list =
+                |entries: &[ApiEntry]| entries.iter().map(ApiEntry::to_json).collect::<Vec<_>>();
+            let mut json_value = serde_json::json!({
+                "_type": "api_surface",
+                "module": module,
+                "exported": exported,
+                "internal": total - entries.len(),
+                "functions": list(functions),
+                "constants": list(constants),
+            });
+            if include_types {
+                json_value["types"] = serde_json::json!(list(types));
+            } else {
+                json_value["types_omitted"] = serde_json::json!(types.len());
+            }
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("api_surface"));
+            output.push_str(&format!("module: \"{}\"\n", module));
+            output.push_str(&format!(
+                "exported: {} | internal: {}\n",
+                exported,
+                total - entries.len()
+            ));
+            if !functions.is_empty() {
+                output.push_str(&format!(
+                    "functions[{}]{{name,params,returns,file,lines}}:\n",
+                    functions.len()
+                ));
+                for f in functions {
+                    output.push_str(&format!(
+                        "  {},\"{}\",\"{}\",{},{}\n",
+                        f.name,
+                        f.params.join(", "),
+                        f.returns.as_deref().unwrap_or(""),
+                        f.file,
+                        f.lines
+                    ));
+                }
+            }
+            if !constants.is_empty() {
+                output.push_str(&format!(
+                    "constants[{}]{{name,file,lines}}:\n",
+                    constants.len()
+                ));
+                for c in constants {
+                    output.push_str(&format!("  {},{},{}\n", c.name, c.file, c.lines));
+                }
+            }
+            if include_types && !types.is_empty() {
+                output.push_str(&format!(
+                    "types[{}]{{name,kind,members,file,lines}}:\n",
+                    types.len()
+                ));
+                for t in types {
+                    output.push_str(&format!(
+                        "  {},{},\"{}\",{},{}\n",
+                        t.name,
+                        t.kind,
+                        t.members.join(", "),
+                        t.file,
+                        t.lines
+                    ));
+                }
+            } else if !types.is_empty() {
+                output.push_str(&format!("types: {} (omitted)\n", types.len()));
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str(&format!("  API SURFACE: {}\n", module));
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!(
+                "{} exported, {} internal\n",
+                exported,
+                total - entries.len()
+            ));
+            if !functions.is_empty() {
+                output.push_str("\nFunctions:\n");
+                for f in functions {
+                    let returns = f
+                        .returns
+                        .as_ref()
+                        .map(|r| format!(" -> {}", r))
+                        .unwrap_or_default();
+                    output.push_str(&format!(
+                        "  {}({}){}  [{}:{}]\n",
+                        f.name,
+                        f.params.join(", "),
+                        returns,
+                        f.file,
+                        f.lines
+                    ));
+                }
+            }
+            if !constants.is_empty() {
+                output.push_str("\nConstants:\n");
+                for c in constants {
+                    output.push_str(&format!("  {}  [{}:{}]\n", c.name, c.file, c.lines));
+                }
+            }
+            if include_types && !types.is_empty() {
+                output.push_str("\nTypes:\n");
+                for t in types {
+                    let members = if t.members.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {{ {} }}", t.members.join(", "))
+                    };
+                    output.push_str(&format!(
+                        "  {} {}{}  [{}:{}]\n",
+                        t.kind, t.name, members, t.file, t.lines
+                    ));
+                }
+            } else if !types.is_empty() {
+                output.push_str(&format!(
+                    "\n{} types omitted (use --include-types)\n",
+                    types.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Message returned when the index has no React components
+const NO_COMPONENTS: &str = "no React components indexed";
+
+/// React components of a repository, or `None` when none are indexed
+fn load_components(path: Option<&PathBuf>) -> Result<Option<(CacheDir, ComponentIndex)>> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let index = ComponentIndex::load(&cache)?;
+    Ok((!index.is_empty()).then_some((cache, index)))
+}
+
+/// The "no React components indexed" result in the requested format
+fn no_components(type_name: &str, ctx: &CommandContext) -> String {
+    match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+            "_type": type_name,
+            "components": 0,
+            "message": NO_COMPONENTS,
+        }))
+        .unwrap_or_default(),
+        OutputFormat::Toon => format!(
+            "{}message: \"{}\"\n",
+            super::toon_header(type_name),
+            NO_COMPONENTS
+        ),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            format!("{}\n", NO_COMPONENTS)
+        }
+    }
+}
+
+/// Show the React render tree under `root`, down to `depth` levels
+pub fn run_component_tree(
+    path: Option<&PathBuf>,
+    root: &str,
+    depth: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let Some((cache, index)) = load_components(path)? else {
+        return Ok(no_components("component_tree", ctx));
+    };
+    let root_component = index.find(root).ok_or_else(|| McpDiffError::FileNotFound {
+        path: format!("Component '{}' not found", root),
+    })?;
+    let tree = index.render_tree(root_component, depth);
+    let nodes = tree.flatten();
+    let components = index.components();
+    let note = |node: &RenderNode| {
+        if node.cycle {
+            "cycle"
+        } else if node.truncated {
+            "truncated"
+        } else {
+            ""
+        }
+    };
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            fn to_json(
+                node: &RenderNode,
+                components: &[Component],
+                file: &dyn Fn(&str) -> String,
+            ) -> serde_json::Value {
+                let c = &components[node.component];
+                let mut value = serde_json::json!({
+                    "name": c.name,
+                    "hash": c.hash,
+                    "file": file(&c.file),
+                    "line": c.start_line,
+                    "risk": c.risk,
+                });
+                if node.cycle {
+                    value["cycle"] = serde_json::json!(true);
+                }
+                if node.truncated {
+                    value["truncated"] = serde_json::json!(true);
+                }
+                if !node.children.is_empty() {
+                    value["children"] = node
+                        .children
+                        .iter()
+                        .map(|child| to_json(child, components, file))
+                        .collect();
+                }
+                value
+            }
+            let file = |f: &str| ctx.display_path(f, &cache.repo_root);
+            let json_value = serde_json::json!({
+                "_type": "component_tree",
+                "root": components[root_component].name,
+                "depth": depth,
+                "nodes": nodes.len(),
+                "tree": to_json(&tree, components, &file),
+            });
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("component_tree"));
+            output.push_str(&format!("root: {}\n", components[root_component].name));
+            output.push_str(&format!("depth: {}\n", depth));
+            output.push_str(&format!(
+                "nodes[{}]{{depth,name,file,line,risk,note}}:\n",
+                nodes.len()
+            ));
+            for (level, node) in &nodes {
+                let c = &components[node.component];
+                output.push_str(&format!(
+                    "  {},{},{},{},{},{}\n",
+                    level,
+                    c.name,
+                    ctx.display_path(&c.file, &cache.repo_root),
+                    c.start_line,
+                    c.risk,
+                    note(node)
+                ));
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str(&format!(
+                "  COMPONENT TREE: {}\n",
+                components[root_component].name
+            ));
+            output.push_str("═══════════════════════════════════════════\n\n");
+            for (level, node) in &nodes {
+                let c = &components[node.component];
+                let note = match note(node) {
+                    "" => String::new(),
+                    note => format!(" ({})", note),
+                };
+                output.push_str(&format!(
+                    "{}{}  [{}:{}] {}{}\n",
+                    "  ".repeat(*level),
+                    c.name,
+                    ctx.display_path(&c.file, &cache.repo_root),
+                    c.start_line,
+                    c.risk,
+                    note
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Show the components calling a hook
+///
+/// `hook` is a hook name (`useTheme`) or a call with its first argument
+/// (`useContext(ThemeContext)`), which matches only calls passing it.
+pub fn run_hook_usage(path: Option<&PathBuf>, hook: &str, ctx: &CommandContext) -> Result<String> {
+    let Some((cache, index)) = load_components(path)? else {
+        return Ok(no_components("hook_usage", ctx));
+    };
+    let (name, argument) = match hook.trim().split_once('(') {
+        Some((name, rest)) => {
+            let argument = rest.trim_end_matches(')').trim();
+            (name.trim(), (!argument.is_empty()).then_some(argument))
+        }
+        None => (hook.trim(), None),
+    };
+    let users = index.hook_users(name, argument);
+    let calls: usize = users.iter().map(|(_, calls)| calls.len()).sum();
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            let json_value = serde_json::json!({
+                "_type": "hook_usage",
+                "hook": name,
+                "argument": argument,
+                "components": users.len(),
+                "calls": calls,
+                "users": users.iter().map(|(c, calls)| serde_json::json!({
+                    "name": c.name,
+                    "hash": c.hash,
+                    "file": ctx.display_path(&c.file, &cache.repo_root),
+                    "line": c.start_line,
+                    "risk": c.risk,
+                    "calls": calls.iter().map(|call| serde_json::json!({
+                        "line": call.location.line,
+                        "argument": call.argument,
+                    })).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            });
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("hook_usage"));
+            output.push_str(&format!("hook: \"{}\"\n", hook.trim()));
+            output.push_str(&format!("components: {} | calls: {}\n", users.len(), calls));
+            if calls > 0 {
+                output.push_str(&format!(
+                    "users[{}]{{name,argument,file,line,risk}}:\n",
+                    calls
+                ));
+            }
+            for (c, calls) in &users {
+                for call in calls {
+                    output.push_str(&format!(
+                        "  {},{},{},{},{}\n",
+                        c.name,
+                        call.argument.as_deref().unwrap_or(""),
+                        ctx.display_path(&c.file, &cache.repo_root),
+                        call.location.line,
+                        c.risk
+                    ));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str(&format!("  HOOK USAGE: {}\n", hook.trim()));
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!("{} components, {} calls\n\n", users.len(), calls));
+            for (c, calls) in &users {
+                for call in calls {
+                    let argument = call
+                        .argument
+                        .as_ref()
+                        .map(|a| format!("({})", a))
+                        .unwrap_or_default();
+                    output.push_str(&format!(
+                        "  {}  {}{}  [{}:{}] {}\n",
+                        c.name,
+                        name,
+                        argument,
+                        ctx.display_path(&c.file, &cache.repo_root),
+                        call.location.line,
+                        c.risk
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Match a module name or directory path against the indexed modules
+///
+/// Accepts `legacy.payments`, `src/legacy/payments` or any unique dotted
+/// suffix/prefix match of an indexed module name.
+fn resolve_module_name(index: &ModuleUsageIndex, module: &str) -> Result<String> {
+    match_module_name(&index.modules(), module)
+}
+
+/// [`resolve_module_name`] against an arbitrary set of module names
+fn match_module_name(modules: &std::collections::BTreeSet<&str>, module: &str) -> Result<String> {
+    let dotted = module.trim_matches('/').replace(['/', '\\'], ".");
+    if modules.contains(dotted.as_str()) {
+        return Ok(dotted);
+    }
+
+    let matches: Vec<&str> = modules
+        .iter()
+        .copied()
+        .filter(|m| dotted.ends_with(&format!(".{}", m)) || m.ends_with(&format!(".{}", dotted)))
+        .collect();
+    match matches.as_slice() {
+        [only] => Ok(only.to_string()),
+        [] => Err(McpDiffError::FileNotFound {
+            path: format!("module '{}' is not in the index", module),
+        }),
+        many => Err(McpDiffError::QueryError {
+            message: format!("module '{}' is ambiguous: {}", module, many.join(", ")),
+        }),
+    }
+}
+
+/// Show the largest, most-called and most-changed code
+///
+/// The stored rankings leave generated code out; `include_generated` ranks
+/// the index again with it.
+pub fn run_hotspots(
+    path: Option<&PathBuf>,
+    limit: usize,
+    include_generated: bool,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let index = if include_generated {
+        HotspotIndex::build(
+            &cache.load_all_symbol_entries()?,
+            &cache.load_call_graph()?,
+            Some(&cache.repo_root),
+            churn_commits(),
+            true,
+        )
+    } else {
+        HotspotIndex::load(&cache)?
+    }
+    .truncated(limit);
+    let hotspots = hotspots_json(&index, &cache, ctx);
+
+    Ok(match ctx.format {
+        OutputFormat::Json => {
+            let mut json_value = serde_json::json!({ "_type": "hotspots" });
+            if let (Some(obj), Some(fields)) = (json_value.as_object_mut(), hotspots.as_object()) {
+                obj.extend(fields.clone());
+            }
+            serde_json::to_string_pretty(&json_value).unwrap_or_default()
+        }
+        OutputFormat::Toon => {
+            let mut output = super::toon_header("hotspots");
+            output.push_str(&super::encode_toon(&hotspots));
+            output.push('\n');
+            output
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            format_hotspots_text(&hotspots)
+        }
+    })
+}
+
+/// Hot spot rankings as JSON, with paths rendered per `--paths`
+fn hotspots_json(
+    index: &HotspotIndex,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+) -> serde_json::Value {
+    let symbols = |list: &[SymbolHotspot]| {
+        list.iter()
+            .map(|h| {
+                serde_json::json!({
+                    "symbol": h.symbol,
+                    "kind": h.kind,
+                    "module": h.module,
+                    "file": ctx.display_path(&h.file, &cache.repo_root),
+                    "risk": h.risk,
+                    "value": h.value,
+                    "hash": h.hash,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let files = |list: &[FileHotspot]| {
+        list.iter()
+            .map(|h| {
+                serde_json::json!({
+                    "module": h.module,
+                    "file": ctx.display_path(&h.file, &cache.repo_root),
+                    "risk": h.risk,
+                    "value": h.value,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    serde_json::json!({
+        "largest": symbols(&index.largest),
+        "most_called": symbols(&index.most_called),
+        "most_changed": files(&index.most_changed),
+        "churn_window": index.churn_window,
+    })
+}
+
+/// Human-readable hot spot rankings (or the reason they are unavailable)
+fn format_hotspots_text(hotspots: &serde_json::Value) -> String {
+    let mut output = String::new();
+    output.push_str("═══════════════════════════════════════════\n");
+    output.push_str("  HOT SPOTS\n");
+    output.push_str("═══════════════════════════════════════════\n");
+    if let Some(reason) = hotspots.as_str() {
+        output.push_str(&format!("\n{}\n", reason));
+        return output;
+    }
+
+    let sections = [
+        ("largest", "LARGEST", "lines"),
+        ("most_called", "MOST CALLED", "callers"),
+        ("most_changed", "MOST CHANGED", "commits"),
+    ];
+    for (key, title, unit) in sections {
+        let entries = hotspots[key].as_array().cloned().unwrap_or_default();
+        output.push_str(&format!("\n{} ({})\n", title, unit));
+        if key == "most_changed" {
+            match hotspots["churn_window"].as_u64() {
+                Some(window) => {
+                    output.push_str(&format!("  over the last {} commits\n", window));
+                }
+                None => {
+                    output.push_str("  (no git history)\n");
+                    continue;
+                }
+            }
+        }
+        if entries.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for e in &entries {
+            let name = match e["symbol"].as_str() {
+                Some(symbol) => format!("{} ({}) ", symbol, e["kind"].as_str().unwrap_or("")),
+                None => String::new(),
+            };
+            output.push_str(&format!(
+                "  {:>5}  {}{} [{}] {}\n",
+                e["value"],
+                name,
+                e["module"].as_str().unwrap_or(""),
+                e["risk"].as_str().unwrap_or(""),
+                e["file"].as_str().unwrap_or("")
+            ));
+        }
+    }
+    output
+}
+
+/// Summarize who owns a module, file or directory (default: the repository)
+///
+/// Counts indexed files and symbols per CODEOWNERS owner, lists the files no
+/// rule owns and, for indexes built with `--with-blame`, the top symbol
+/// authors.
+pub fn run_owners(
+    path: Option<&PathBuf>,
+    target: Option<&str>,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let repo_dir = match path {
+        Some(p) => p.clone(),
+        None => std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?,
+    };
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let ownership = OwnershipIndex::load(&cache)?;
+    let entries: Vec<SymbolIndexEntry> = cache
+        .load_all_symbol_entries()?
+        .into_iter()
+        .filter(|e| !e.is_escape_local)
+        .filter(|e| target.map_or(true, |t| in_owners_target(e, t, &cache.repo_root)))
+        .collect();
+    if entries.is_empty() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!("No indexed files match: {}", target.unwrap_or(".")),
+        });
+    }
+
+    // file -> symbol count
+    let mut files: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    let mut authors: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    for entry in &entries {
+        *files.entry(&entry.file).or_insert(0) += 1;
+        if let Some(author) = ownership.authors.get(&entry.hash) {
+            *authors.entry(&author.author).or_insert(0) += 1;
+        }
+    }
+
+    // owner -> (files, symbols)
+    let mut owners: std::collections::BTreeMap<&str, (usize, usize)> =
+        std::collections::BTreeMap::new();
+    let mut unowned: Vec<String> = Vec::new();
+    for (file, symbols) in &files {
+        let file_owners = ownership.owners_of(file);
+        if file_owners.is_empty() {
+            unowned.push(ctx.display_path(file, &cache.repo_root));
+        }
+        for owner in file_owners {
+            let counts = owners.entry(owner).or_insert((0, 0));
+            counts.0 += 1;
+            counts.1 += symbols;
+        }
+    }
+    let mut owners: Vec<(&str, (usize, usize))> = owners.into_iter().collect();
+    owners.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+    let mut authors: Vec<(&str, usize)> = authors.into_iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    authors.truncate(10);
+
+    let owned = files.len() - unowned.len();
+    let unowned_count = unowned.len();
+    unowned.truncate(limit);
+
+    let mut json_value = serde_json::json!({
+        "_type": "owners",
+        "target": target.unwrap_or("."),
+        "codeowners": ownership.codeowners,
+        "files": files.len(),
+        "owned_files": owned,
+        "coverage": (owned as f64 * 1000.0 / files.len() as f64).round() / 10.0,
+        "owners": owners.iter().map(|(owner, (files, symbols))| serde_json::json!({
+            "owner": owner,
+            "files": files,
+            "symbols": symbols,
+        })).collect::<Vec<_>>(),
+        "unowned_count": unowned_count,
+        "unowned_files": unowned,
+    });
+    if ownership.blame {
+        json_value["authors"] = authors
+            .iter()
+            .map(|(author, symbols)| serde_json::json!({ "author": author, "symbols": symbols }))
+            .collect();
+    }
+
+    Ok(match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            format_owners_text(&json_value)
+        }
+    })
+}
+
+/// Whether an entry belongs to an `owners` target: its module, its file or a
+/// directory above it
+fn in_owners_target(entry: &SymbolIndexEntry, target: &str, repo_root: &std::path::Path) -> bool {
+    if entry.module == target {
+        return true;
+    }
+    let file = std::path::Path::new(&entry.file);
+    let file = file
+        .strip_prefix(repo_root)
+        .unwrap_or(file)
+        .to_string_lossy();
+    let file = file.trim_start_matches("./");
+    let target = target.trim_start_matches("./").trim_end_matches('/');
+    file == target || file.starts_with(&format!("{}/", target))
+}
+
+/// Human-readable ownership summary
+fn format_owners_text(summary: &serde_json::Value) -> String {
+    let mut output = String::new();
+    output.push_str("═══════════════════════════════════════════\n");
+    output.push_str(&format!(
+        "  OWNERS: {}\n",
+        summary["target"].as_str().unwrap_or(".")
+    ));
+    output.push_str("═══════════════════════════════════════════\n\n");
+
+    match summary["codeowners"].as_str() {
+        Some(codeowners) => output.push_str(&format!("codeowners: {}\n", codeowners)),
+        None => output.push_str("codeowners: (no CODEOWNERS file found)\n"),
+    }
+    output.push_str(&format!(
+        "coverage: {}/{} files ({}%)\n",
+        summary["owned_files"], summary["files"], summary["coverage"]
+    ));
+
+    output.push_str("\nOWNERS (files, symbols)\n");
+    let owners = summary["owners"].as_array().cloned().unwrap_or_default();
+    if owners.is_empty() {
+        output.push_str("  (none)\n");
+    }
+    for owner in &owners {
+        output.push_str(&format!(
+            "  {:>5} {:>6}  {}\n",
+            owner["files"].as_u64().unwrap_or(0),
+            owner["symbols"].as_u64().unwrap_or(0),
+            owner["owner"].as_str().unwrap_or("")
+        ));
+    }
+
+    output.push_str(&format!("\nUNOWNED FILES ({})\n", summary["unowned_count"]));
+    let unowned = summary["unowned_files"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if unowned.is_empty() {
+        output.push_str("  (none)\n");
+    }
+    for file in &unowned {
+        output.push_str(&format!("  {}\n", file.as_str().unwrap_or("")));
+    }
+    let unowned_count = summary["unowned_count"].as_u64().unwrap_or(0) as usize;
+    if unowned_count > unowned.len() {
+        output.push_str(&format!(
+            "  ... and {} more\n",
+            unowned_count - unowned.len()
+        ));
+    }
+
+    if let Some(authors) = summary["authors"].as_array() {
+        output.push_str("\nTOP AUTHORS (symbols)\n");
+        if authors.is_empty() {
+            output.push_str("  (none)\n");
+        }
+        for author in authors {
+            output.push_str(&format!(
+                "  {:>5}  {}\n",
+                author["symbols"].as_u64().unwrap_or(0),
+                author["author"].as_str().unwrap_or("")
+            ));
+        }
+    }
+    output
+}
+
+fn join_set(set: &std::collections::BTreeSet<String>) -> String {
+    set.iter().cloned().collect::<Vec<_>>().join(",")
+}
+
+fn run_list_languages(ctx: &CommandContext) -> Result<String> {
+    // All supported languages with their extensions
+    let languages = vec![
+        ("TypeScript", vec!["ts", "mts", "cts"]),
+        ("Tsx", vec!["tsx", "mdx"]),
+        ("JavaScript", vec!["js", "mjs", "cjs"]),
+        ("Jsx", vec!["jsx"]),
+        ("Rust", vec!["rs"]),
+        ("Python", vec!["py", "pyi"]),
         ("Go", vec!["go"]),
         ("Java", vec!["java"]),
         ("C", vec!["c", "h"]),
         ("Cpp", vec!["cpp", "cc", "cxx", "hpp", "hxx", "hh"]),
         ("CSharp", vec!["cs"]),
         ("Kotlin", vec!["kt", "kts"]),
+        ("Php", vec!["php", "phtml"]),
         ("Html", vec!["html", "htm"]),
         ("Css", vec!["css"]),
         ("Scss", vec!["scss", "sass"]),
@@ -1755,6 +4159,8 @@ fn run_list_languages(ctx: &CommandContext) -> Result<String> {
         ("Bash", vec!["sh", "bash", "zsh", "fish"]),
         ("Gradle", vec!["gradle"]),
         ("Dockerfile", vec!["dockerfile"]),
+        ("Protobuf", vec!["proto"]),
+        ("GraphQL", vec!["graphql", "gql"]),
     ];
 
     let json_value = serde_json::json!({
@@ -1775,7 +4181,7 @@ fn run_list_languages(ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  SUPPORTED LANGUAGES\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -1888,6 +4294,71 @@ fn symbol_from_json(sym: &serde_json::Value, module_name: &str) -> SymbolIndexEn
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string(),
+        feature_flags: sym
+            .get("feature_flags")
+            .or_else(|| sym.get("ff"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        is_deprecated: sym
+            .get("is_deprecated")
+            .or_else(|| sym.get("dep"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        deprecation_message: sym
+            .get("deprecation_message")
+            .or_else(|| sym.get("depm"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        awaits_count: sym
+            .get("awaits_count")
+            .or_else(|| sym.get("aw"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        io_calls_outside_try: sym
+            .get("io_calls_outside_try")
+            .or_else(|| sym.get("iot"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        has_empty_catch: sym
+            .get("has_empty_catch")
+            .or_else(|| sym.get("ec"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        rethrows: sym
+            .get("rethrows")
+            .or_else(|| sym.get("rth"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        panic_points: sym
+            .get("panic_points")
+            .or_else(|| sym.get("pp"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        sql_tables: sym
+            .get("sql_tables")
+            .or_else(|| sym.get("sqt"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        partial_of: sym
+            .get("partial_of")
+            .or_else(|| sym.get("pof"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        parent: sym
+            .get("parent")
+            .or_else(|| sym.get("par"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        is_generated: sym
+            .get("is_generated")
+            .or_else(|| sym.get("gen"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
     }
 }
 
@@ -1902,36 +4373,21 @@ fn load_symbol_from_cache(cache: &CacheDir, hash: &str) -> Result<Option<SymbolI
         return Ok(Some(entry));
     }
 
-    // Fall back to searching through modules
-    let modules_dir = cache.modules_dir();
-    if modules_dir.exists() {
-        for entry in fs::read_dir(&modules_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path
-                .extension()
-                .map(|e| e == "toon" || e == "json")
-                .unwrap_or(false)
-            {
-                // Use toon_parser to handle both formats
-                if let Ok(cached) = read_cached_file(&path) {
-                    if let Some(symbols) = cached.json.get("symbols").and_then(|s| s.as_array()) {
-                        for sym in symbols {
-                            let sym_hash = sym
-                                .get("hash")
-                                .or_else(|| sym.get("h"))
-                                .and_then(|h| h.as_str())
-                                .unwrap_or("");
-                            if sym_hash == hash
-                                || sym_hash.contains(hash)
-                                || hash.contains(sym_hash)
-                            {
-                                let module_name =
-                                    path.file_stem().and_then(|s| s.to_str()).unwrap_or("?");
-                                return Ok(Some(symbol_from_json(sym, module_name)));
-                            }
-                        }
-                    }
+    // Fall back to searching through modules (of the same generation)
+    for module_name in cache.list_modules() {
+        // Use toon_parser to handle both formats
+        let Ok(cached) = read_cached_file(&cache.module_path(&module_name)) else {
+            continue;
+        };
+        if let Some(symbols) = cached.json.get("symbols").and_then(|s| s.as_array()) {
+            for sym in symbols {
+                let sym_hash = sym
+                    .get("hash")
+                    .or_else(|| sym.get("h"))
+                    .and_then(|h| h.as_str())
+                    .unwrap_or("");
+                if sym_hash == hash || sym_hash.contains(hash) || hash.contains(sym_hash) {
+                    return Ok(Some(symbol_from_json(sym, &module_name)));
                 }
             }
         }
@@ -1940,6 +4396,32 @@ fn load_symbol_from_cache(cache: &CacheDir, hash: &str) -> Result<Option<SymbolI
     Ok(None)
 }
 
+/// Load a symbol, preferring its staged version when an AI layer is applied
+fn load_symbol(
+    cache: &CacheDir,
+    ctx: &CommandContext,
+    hash: &str,
+) -> Result<Option<SymbolIndexEntry>> {
+    if let Some(staged) = ctx.ai_layer.as_ref().and_then(|ai| ai.lookup(hash)) {
+        return Ok(staged);
+    }
+    load_symbol_from_cache(cache, hash)
+}
+
+/// Get source for a symbol, from the staged content if its file is staged
+fn symbol_source(
+    cache: &CacheDir,
+    ctx: &CommandContext,
+    file: &str,
+    lines: &str,
+    context: usize,
+) -> Option<String> {
+    match ctx.ai_layer.as_ref().and_then(|ai| ai.staged_file(file)) {
+        Some(staged) => source_snippet(&staged.content, lines, context),
+        None => get_source_for_symbol(cache, file, lines, context),
+    }
+}
+
 /// Helper to get source for a symbol
 fn get_source_for_symbol(
     cache: &CacheDir,
@@ -1947,12 +4429,17 @@ fn get_source_for_symbol(
     lines: &str,
     context: usize,
 ) -> Option<String> {
+    let file_path = cache.repo_root.join(file);
+    let content = fs::read_to_string(&file_path).ok()?;
+    source_snippet(&content, lines, context)
+}
+
+/// Lines `start-end` of `content` with `context` lines around, target lines marked
+fn source_snippet(content: &str, lines: &str, context: usize) -> Option<String> {
     let parts: Vec<&str> = lines.split('-').collect();
     let start: usize = parts.first()?.parse().ok()?;
     let end: usize = parts.get(1).unwrap_or(&parts[0]).parse().ok()?;
 
-    let file_path = cache.repo_root.join(file);
-    let content = fs::read_to_string(&file_path).ok()?;
     let all_lines: Vec<&str> = content.lines().collect();
 
     let start_with_ctx = start.saturating_sub(context + 1);
@@ -1976,3 +4463,74 @@ fn get_source_for_symbol(
 
     Some(snippet)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overview_with_modules(count: usize) -> String {
+        let mut lines = vec![
+            "_type: repo_overview".to_string(),
+            "schema_version: \"2.1\"".to_string(),
+            "framework: \"express\"".to_string(),
+            format!("modules[{}]:", count),
+        ];
+        for i in 0..count {
+            let risk = if i % 10 == 0 { "high" } else { "low" };
+            lines.push(format!("  module{},\"module{} module\",3,{}", i, i, risk));
+        }
+        lines.push("files: 300".to_string());
+        lines.push("risk_breakdown: \"high:10,medium:0,low:90\"".to_string());
+        lines.push("entry_points[1]: src/module57/index.ts".to_string());
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_fit_overview_to_budget_keeps_entry_points() {
+        let content = overview_with_modules(100);
+        let analyzer = TokenAnalyzer::new();
+        assert!(analyzer.count_tokens(&content) > 400);
+
+        let output = fit_overview_to_budget(&content, 250, |c| c.to_string());
+
+        assert!(analyzer.count_tokens(&output) <= 250);
+        assert!(output.contains("entry_points[1]: src/module57/index.ts"));
+        // The entry-point module outranks high-risk ones, which outrank the rest
+        assert!(output.contains("  module57,"));
+        assert!(output.contains("  module0,"));
+        assert!(!output.contains("  module1,"), "{}", output);
+        assert!(output.contains("{budget:"));
+    }
+
+    #[test]
+    fn test_fit_overview_to_budget_tiny_budget_still_fits() {
+        let content = overview_with_modules(100);
+        let analyzer = TokenAnalyzer::new();
+
+        let output = fit_overview_to_budget(&content, 40, |c| c.to_string());
+
+        assert!(analyzer.count_tokens(&output) <= 40, "{}", output);
+        assert!(output.starts_with("_type: repo_overview"));
+        assert!(output.contains("entry_points"), "{}", output);
+        assert!(!output.contains("modules["));
+    }
+
+    #[test]
+    fn test_fit_overview_within_budget_is_unchanged() {
+        let content = overview_with_modules(3);
+        let output = fit_overview_to_budget(&content, 10_000, |c| c.to_string());
+        assert_eq!(output, content);
+    }
+
+    #[test]
+    fn test_module_has_entry_point() {
+        let entries = vec![
+            "src/api/server.ts".to_string(),
+            "GET app/api/users/route.ts".to_string(),
+        ];
+        assert!(module_has_entry_point("api", &entries));
+        assert!(module_has_entry_point("api.users", &entries));
+        assert!(!module_has_entry_point("users.api", &entries));
+        assert!(!module_has_entry_point("ap", &entries));
+    }
+}
@@ -3,23 +3,48 @@
 //! This module implements the "magic" search that runs BOTH symbol matching AND
 //! semantic search by default, presenting results in categorized sections.
 
-use crate::cache::CacheDir;
-use crate::cli::{OutputFormat, SearchArgs, SearchMode};
-use crate::commands::CommandContext;
+use crate::cache::{normalize_kind, CacheDir, SymbolIndexEntry};
+use crate::cli::{OutputFormat, PathStyle, SearchArgs, SearchMode, SymbolScope};
+use crate::commands::{is_excluded_module, CommandContext};
+use crate::duplicate::boilerplate::matches_glob;
 use crate::error::{McpDiffError, Result};
+use crate::git::get_repo_root;
+use crate::lang::Lang;
+use crate::parsing::parse_and_extract;
+use crate::profile::{Phase, PhaseTimer};
 use crate::ripgrep::{RipgrepSearcher, SearchOptions};
+use crate::schema::{EmbeddedQuery, PanicPoint, SemanticSummary, SymbolInfo};
+use crate::semantic_grep::{semantic_grep, SemanticPattern};
+use crate::settings::RepoSettings;
+use crate::shard::extract_module_name;
 use crate::truncate_to_char_boundary;
-use std::collections::HashSet;
+use crate::workspace::{repo_relative, resolve_package, Workspace};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Run the search command with hybrid search by default
 pub fn run_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
     let mode = args.search_mode();
 
+    // An unknown package is an error rather than an empty result
+    if let Some(ref package) = args.package {
+        let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+            path: format!("current directory: {}", e),
+        })?;
+        resolve_package(&CacheDir::for_repo(&repo_dir)?.repo_root, package)?;
+    }
+
     match mode {
         SearchMode::Hybrid => run_hybrid_search(args, ctx),
         SearchMode::SymbolsOnly => run_symbol_search(args, ctx),
         SearchMode::SemanticOnly => run_semantic_search(args, ctx),
         SearchMode::Raw => run_raw_search(args, ctx),
+        SearchMode::Flag => run_flag_search(args, ctx),
+        SearchMode::Deprecated => run_deprecated_search(args, ctx),
+        SearchMode::Panics => run_panic_search(args, ctx),
+        SearchMode::Sql => run_sql_search(args, ctx),
+        SearchMode::Facet => run_facet_search(args, ctx),
     }
 }
 
@@ -33,10 +58,10 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
     let mut output = String::new();
 
     // Try to get symbol matches
-    let symbol_results = get_symbol_matches(&cache, args);
+    let symbol_results = get_symbol_matches(&cache, args, ctx);
 
     // Try to get semantic matches
-    let semantic_results = get_semantic_matches(&cache, args);
+    let semantic_results = get_semantic_matches(&cache, args, ctx);
 
     let symbol_count = symbol_results
         .as_ref()
@@ -47,11 +72,20 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         .map(|r| r.results.len())
         .unwrap_or(0);
 
+    // Too few indexed hits for text that isn't a symbol name: the query is
+    // likely a string literal, config key or comment, which only ripgrep sees
+    let raw_fallback = (!args.no_fallback
+        && symbol_count + related_count < args.fallback_min
+        && !looks_like_symbol_name(&args.query))
+    .then(|| get_raw_fallback_matches(&cache, args, ctx));
+    let fallback_count = raw_fallback.as_ref().map(Vec::len).unwrap_or(0);
+
     // Generate contextual hint for AI based on what was found
     let hint: Option<&str> = match (symbol_count > 0, related_count > 0) {
         (true, true) => None, // Both found results - no hint needed
         (true, false) => Some("Exact symbol matches found. No semantic matches - try get_symbol(hash) for details."),
         (false, true) => Some("No exact symbol name matches, but BM25 found related code. Use hashes from related_code for get_symbol/get_source."),
+        (false, false) if fallback_count > 0 => Some("No indexed matches; raw_fallback lists plain-text matches from the source. Use mode='raw' for regex patterns or more results."),
         (false, false) => Some("No results. Try: 1) get_overview to find module names, 2) get_file(module) to explore, 3) simpler single-word queries."),
     };
 
@@ -73,7 +107,7 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         .unwrap_or(&empty_suggestions);
 
     // Dynamic field ordering: show non-empty results first
-    let json_value = if symbol_count > 0 || related_count == 0 {
+    let mut json_value = if symbol_count > 0 || related_count == 0 {
         // Symbol results first (has results, or both empty)
         let mut obj = serde_json::json!({
             "_type": "hybrid_search",
@@ -104,6 +138,11 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         }
         obj
     };
+    echo_path_scope(&mut json_value, &args.path_globs);
+    if let Some(ref matches) = raw_fallback {
+        json_value["raw_fallback"] = serde_json::json!(matches);
+        json_value["raw_fallback_count"] = serde_json::json!(fallback_count);
+    }
 
     match ctx.format {
         OutputFormat::Json => {
@@ -112,8 +151,10 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
-            output.push_str(&format!("query: \"{}\"\n\n", args.query));
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("query: \"{}\"\n", args.query));
+            output.push_str(&path_scope_line(&args.path_globs));
+            output.push('\n');
 
             // Symbol matches section
             output.push_str("═══════════════════════════════════════════\n");
@@ -125,8 +166,12 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
                 } else {
                     for entry in &results.results {
                         output.push_str(&format!(
-                            "• {} ({})      {}:{}\n",
-                            entry.symbol, entry.kind, entry.file, entry.lines
+                            "• {} ({})      {}:{}{}\n",
+                            entry.symbol,
+                            entry.kind,
+                            entry.file,
+                            entry.lines,
+                            generated_tag(entry.generated)
                         ));
                         if ctx.verbose {
                             output.push_str(&format!("  hash: {}\n", entry.hash));
@@ -163,8 +208,11 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
                             entry.symbol, desc, entry.score
                         ));
                         output.push_str(&format!(
-                            "  {}:{} ({})\n",
-                            entry.file, entry.lines, entry.kind
+                            "  {}:{} ({}){}\n",
+                            entry.file,
+                            entry.lines,
+                            entry.kind,
+                            generated_tag(entry.generated)
                         ));
                     }
                 }
@@ -183,6 +231,25 @@ fn run_hybrid_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
                 output.push_str("(no BM25 index - run `semfora index generate` first)\n");
             }
 
+            if let Some(ref matches) = raw_fallback {
+                output.push_str("\n═══════════════════════════════════════════\n");
+                output.push_str(&format!(
+                    "RAW TEXT MATCHES ({}) [raw_fallback]\n",
+                    matches.len()
+                ));
+                output.push_str("───────────────────────────────────────────\n");
+                if matches.is_empty() {
+                    output.push_str("(no plain-text matches either)\n");
+                } else {
+                    for m in matches {
+                        output.push_str(&format!(
+                            "• {}:{}:{}: {}\n",
+                            m.file, m.line, m.column, m.content
+                        ));
+                    }
+                }
+            }
+
             // Include source snippets if requested
             if args.include_source {
                 output.push_str("\n═══════════════════════════════════════════\n");
@@ -215,6 +282,7 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         path: format!("current directory: {}", e),
     })?;
     let cache = CacheDir::for_repo(&repo_dir)?;
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
 
     // Use fallback-aware search
     let search_result = cache.search_symbols_with_fallback(
@@ -222,16 +290,21 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
         args.module.as_deref(),
         args.kind.as_deref(),
         args.risk.as_deref(),
-        args.limit,
+        scope.fetch_limit(args.limit),
     )?;
 
     let mut output = String::new();
 
     if search_result.fallback_used {
         // Ripgrep fallback results
-        let ripgrep_results = search_result.ripgrep_results.unwrap_or_default();
+        let mut ripgrep_results = search_result.ripgrep_results.unwrap_or_default();
+        ripgrep_results.retain(|entry| scope.contains_text(&entry.file));
+        ripgrep_results.truncate(args.limit);
+        for entry in &mut ripgrep_results {
+            entry.file = ctx.display_path(&entry.file, &cache.repo_root);
+        }
 
-        let json_value = serde_json::json!({
+        let mut json_value = serde_json::json!({
             "_type": "symbol_search",
             "query": args.query,
             "results": ripgrep_results,
@@ -239,6 +312,7 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
             "fallback": true,
             "note": "Using ripgrep fallback (no semantic index). Run `semfora index generate` to create index."
         });
+        echo_path_scope(&mut json_value, &args.path_globs);
 
         match ctx.format {
             OutputFormat::Json => {
@@ -247,9 +321,10 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
             OutputFormat::Toon => {
                 output = super::encode_toon(&json_value);
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                 output.push_str("_note: Using ripgrep fallback (no semantic index)\n");
                 output.push_str(&format!("query: \"{}\"\n", args.query));
+                output.push_str(&path_scope_line(&args.path_globs));
                 output.push_str(&format!("results[{}]:\n", ripgrep_results.len()));
                 for entry in &ripgrep_results {
                     let content_preview = if entry.content.len() > 60 {
@@ -270,18 +345,34 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
     } else {
         // Normal indexed search results
         let mut results = search_result.indexed_results.unwrap_or_default();
+        if let Some(ai_layer) = &ctx.ai_layer {
+            ai_layer.apply_to_search(
+                &mut results,
+                &args.query,
+                args.module.as_deref(),
+                args.kind.as_deref(),
+                args.risk.as_deref(),
+                args.limit,
+            );
+        }
         let symbol_scope = args.symbol_scope.for_kind(args.kind.as_deref());
         results.retain(|entry| symbol_scope.matches_kind(&entry.kind));
         if !args.include_escape_refs {
             results.retain(|entry| !entry.is_escape_local);
         }
+        results.retain(|entry| scope.contains_symbol(&entry.file, &entry.module));
+        results.truncate(args.limit);
+        for entry in &mut results {
+            entry.file = ctx.display_path(&entry.file, &cache.repo_root);
+        }
 
-        let json_value = serde_json::json!({
+        let mut json_value = serde_json::json!({
             "_type": "symbol_search",
             "query": args.query,
             "results": results,
             "count": results.len()
         });
+        echo_path_scope(&mut json_value, &args.path_globs);
 
         match ctx.format {
             OutputFormat::Json => {
@@ -290,13 +381,20 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
             OutputFormat::Toon => {
                 output = super::encode_toon(&json_value);
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                 output.push_str(&format!("query: \"{}\"\n", args.query));
+                output.push_str(&path_scope_line(&args.path_globs));
                 output.push_str(&format!("results[{}]:\n", results.len()));
                 for entry in &results {
                     output.push_str(&format!(
-                        "  {} ({}) - {} [{}] {}:{}\n",
-                        entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                        "  {} ({}) - {} [{}] {}:{}{}\n",
+                        entry.symbol,
+                        entry.kind,
+                        entry.module,
+                        entry.risk,
+                        entry.file,
+                        entry.lines,
+                        generated_tag(entry.is_generated)
                     ));
                 }
             }
@@ -306,23 +404,493 @@ fn run_symbol_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String>
     Ok(output)
 }
 
-/// Semantic-only search (BM25 natural language matching)
-fn run_semantic_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
-    use crate::bm25::search_sqlite;
+/// Feature flag search: every symbol gated by `--flag`
+fn run_flag_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+        path: format!("current directory: {}", e),
+    })?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let flag = args.flag.as_deref().unwrap_or_default();
+
+    let mut results = cache.load_all_symbol_entries()?;
+    results.retain(|e| e.feature_flags.split(',').any(|f| f == flag));
+    let (results, total) = narrow_listing(results, args, &cache, ctx);
+
+    let mut output = String::new();
+
+    let json_value = serde_json::json!({
+        "_type": "flag_search",
+        "flag": flag,
+        "results": results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("flag: \"{}\"\n", flag));
+            output.push_str(&format!("results[{}]:\n", results.len()));
+            for entry in &results {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                ));
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise --limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
 
+    Ok(output)
+}
+
+/// Deprecated symbol search: every symbol marked deprecated, with its note
+fn run_deprecated_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
     let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
         path: format!("current directory: {}", e),
     })?;
     let cache = CacheDir::for_repo(&repo_dir)?;
 
-    if !cache.has_bm25_index() {
+    let mut results = cache.load_all_symbol_entries()?;
+    results.retain(|e| e.is_deprecated);
+    let (results, total) = narrow_listing(results, args, &cache, ctx);
+
+    let mut output = String::new();
+
+    let json_value = serde_json::json!({
+        "_type": "deprecated_search",
+        "results": results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("deprecated[{}]:\n", results.len()));
+            for entry in &results {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                ));
+                if !entry.deprecation_message.is_empty() {
+                    output.push_str(&format!("    note: {}\n", entry.deprecation_message));
+                }
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise --limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Panic search: symbols that can panic or throw, with each panic point
+///
+/// The index only keeps a count per symbol, so the listed files are
+/// re-extracted for the points themselves.
+fn run_panic_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+        path: format!("current directory: {}", e),
+    })?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+
+    let mut results = cache.load_all_symbol_entries()?;
+    results.retain(|e| e.panic_points > 0);
+    // Listings show display paths; keep the indexed ones for re-extraction
+    let indexed_files: HashMap<String, String> = results
+        .iter()
+        .map(|e| (e.hash.clone(), e.file.clone()))
+        .collect();
+    let (results, total) = narrow_listing(results, args, &cache, ctx);
+
+    let points: Vec<Vec<PanicPoint>> = reextract_symbols(&results, &indexed_files, &cache)
+        .into_iter()
+        .map(|sym| sym.map(|sym| sym.panic_points).unwrap_or_default())
+        .collect();
+
+    let mut output = String::new();
+
+    let json_results: Vec<serde_json::Value> = results
+        .iter()
+        .zip(&points)
+        .map(|(entry, points)| {
+            let mut value = serde_json::to_value(entry).unwrap_or_default();
+            value["points"] = serde_json::json!(points);
+            value
+        })
+        .collect();
+    let json_value = serde_json::json!({
+        "_type": "panic_search",
+        "results": json_results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("panics[{}]:\n", results.len()));
+            for (entry, points) in results.iter().zip(&points) {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                ));
+                for point in points {
+                    output.push_str(&format!(
+                        "    line {}: {}{}\n",
+                        point.location.line,
+                        point.kind.as_str(),
+                        if point.in_try { " (in try)" } else { "" }
+                    ));
+                }
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise --limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Embedded SQL search: symbols whose SQL string literals touch `--sql`,
+/// with those queries
+///
+/// The index keeps the tables per symbol; the queries come from
+/// re-extracting the listed files.
+fn run_sql_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+        path: format!("current directory: {}", e),
+    })?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+    let table = args.sql.as_deref().unwrap_or_default();
+
+    let mut results = cache.load_all_symbol_entries()?;
+    results.retain(|e| {
+        e.sql_tables
+            .split(',')
+            .any(|t| t.eq_ignore_ascii_case(table))
+    });
+    let indexed_files: HashMap<String, String> = results
+        .iter()
+        .map(|e| (e.hash.clone(), e.file.clone()))
+        .collect();
+    let (results, total) = narrow_listing(results, args, &cache, ctx);
+
+    let queries: Vec<Vec<EmbeddedQuery>> = reextract_symbols(&results, &indexed_files, &cache)
+        .into_iter()
+        .map(|sym| {
+            let mut queries = sym.map(|sym| sym.embedded_queries).unwrap_or_default();
+            queries.retain(|q| q.tables.iter().any(|t| t.eq_ignore_ascii_case(table)));
+            queries
+        })
+        .collect();
+
+    let mut output = String::new();
+
+    let json_results: Vec<serde_json::Value> = results
+        .iter()
+        .zip(&queries)
+        .map(|(entry, queries)| {
+            let mut value = serde_json::to_value(entry).unwrap_or_default();
+            value["queries"] = serde_json::json!(queries);
+            value
+        })
+        .collect();
+    let json_value = serde_json::json!({
+        "_type": "sql_search",
+        "table": table,
+        "results": json_results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("table: \"{}\"\n", table));
+            output.push_str(&format!("results[{}]:\n", results.len()));
+            for (entry, queries) in results.iter().zip(&queries) {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                ));
+                for query in queries {
+                    output.push_str(&format!(
+                        "    line {}: {}\n",
+                        query.location.line, query.query
+                    ));
+                }
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise --limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// The freshly extracted symbol behind each index entry, for details the
+/// index doesn't keep
+///
+/// `indexed_files` maps entry hashes to indexed paths, since listings show
+/// display paths. Each file is extracted once; `None` when it can't be read
+/// or the symbol is no longer there.
+fn reextract_symbols(
+    entries: &[SymbolIndexEntry],
+    indexed_files: &HashMap<String, String>,
+    cache: &CacheDir,
+) -> Vec<Option<SymbolInfo>> {
+    let mut summaries: HashMap<String, Option<SemanticSummary>> = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let file = indexed_files.get(&entry.hash)?;
+            let summary = summaries
+                .entry(file.clone())
+                .or_insert_with(|| extract_file(&cache.repo_root.join(file)));
+            let start_line = entry
+                .lines
+                .split('-')
+                .next()
+                .and_then(|l| l.parse::<usize>().ok());
+            summary
+                .as_ref()?
+                .symbols
+                .iter()
+                .find(|sym| sym.name == entry.symbol && Some(sym.start_line) == start_line)
+                .cloned()
+        })
+        .collect()
+}
+
+/// Extract a file's semantic summary, or `None` if it can't be read or parsed
+fn extract_file(path: &Path) -> Option<SemanticSummary> {
+    let lang = Lang::from_path(path).ok()?;
+    let source = std::fs::read_to_string(path).ok()?;
+    parse_and_extract(path, &source, lang).ok()
+}
+
+/// Facet search: symbols matching every `--facet` filter
+fn run_facet_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
+    let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+        path: format!("current directory: {}", e),
+    })?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+
+    let results = cache.load_all_symbol_entries()?;
+    let (results, total) = narrow_listing(results, args, &cache, ctx);
+    let facets: Vec<String> = args.facets.iter().map(|f| f.to_string()).collect();
+
+    let mut output = String::new();
+
+    let json_value = serde_json::json!({
+        "_type": "facet_search",
+        "facets": facets,
+        "results": results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("facets: {}\n", facets.join(", ")));
+            output.push_str(&format!("results[{}]:\n", results.len()));
+            for entry in &results {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    entry.symbol, entry.kind, entry.module, entry.risk, entry.file, entry.lines
+                ));
+                output.push_str(&format!(
+                    "    async:{} awaits:{} io_outside_try:{} empty_catch:{} rethrows:{} panic_points:{}\n",
+                    entry.is_async,
+                    entry.awaits_count,
+                    entry.io_calls_outside_try,
+                    entry.has_empty_catch,
+                    entry.rethrows,
+                    entry.panic_points
+                ));
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise --limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Apply the name/kind/module/risk/file/facet filters shared by the index
+/// listings (`--flag`, `--deprecated`, `--panics`, `--sql`, `--facet`), sort by location and cut
+/// to `--limit`.
+///
+/// Returns the page and the number of matches before the limit.
+fn narrow_listing(
+    mut results: Vec<SymbolIndexEntry>,
+    args: &SearchArgs,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+) -> (Vec<SymbolIndexEntry>, usize) {
+    // The query narrows by symbol name when given
+    if !args.query.is_empty() {
+        let query_lower = args.query.to_lowercase();
+        results.retain(|e| e.symbol.to_lowercase().contains(&query_lower));
+    }
+    if let Some(ref kind_filter) = args.kind {
+        let kind_lower = kind_filter.to_lowercase();
+        results.retain(|e| e.kind.to_lowercase() == kind_lower);
+    }
+    if let Some(ref module_filter) = args.module {
+        let module_lower = module_filter.to_lowercase();
+        results.retain(|e| e.module.to_lowercase() == module_lower);
+    }
+    if let Some(ref risk_filter) = args.risk {
+        let risk_lower = risk_filter.to_lowercase();
+        results.retain(|e| e.risk == risk_lower);
+    }
+    results.retain(|e| args.facets.iter().all(|f| f.matches(e)));
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
+    results.retain(|e| scope.contains_symbol(&e.file, &e.module));
+    results.sort_by(|a, b| a.file.cmp(&b.file).then_with(|| a.lines.cmp(&b.lines)));
+    let total = results.len();
+    results.truncate(args.limit);
+    for entry in &mut results {
+        entry.file = ctx.display_path(&entry.file, &cache.repo_root);
+    }
+
+    (results, total)
+}
+
+/// Semantic grep: symbols matching a `calls:`, `mutates:`, `imports:` or
+/// `returns:` pattern, answered from the index rather than source text
+pub fn run_semantic_grep(
+    repo_path: &Path,
+    pattern: &str,
+    kind: Option<&str>,
+    limit: usize,
+    ctx: &CommandContext,
+) -> Result<String> {
+    let parsed: SemanticPattern = pattern
+        .parse()
+        .map_err(|message| McpDiffError::QueryError { message })?;
+
+    let cache = CacheDir::for_repo(repo_path)?;
+    if !cache.exists() {
         return Err(McpDiffError::FileNotFound {
-            path: "BM25 index not found. Run `semfora index generate` first.".to_string(),
+            path: "No index found. Run `semfora index generate` first.".to_string(),
         });
     }
 
+    let kind = kind.map(normalize_kind);
+    let symbol_scope = SymbolScope::Functions.for_kind(kind);
+    let mut results = semantic_grep(&cache, &parsed, |e| {
+        kind.is_none_or(|k| e.kind.eq_ignore_ascii_case(k)) && symbol_scope.matches_kind(&e.kind)
+    })?;
+    let total = results.len();
+    results.truncate(limit);
+    for m in &mut results {
+        m.file = ctx.display_path(&m.file, &cache.repo_root);
+    }
+
+    let mut output = String::new();
+
+    let json_value = serde_json::json!({
+        "_type": "semantic_grep",
+        "pattern": pattern.trim(),
+        "results": results,
+        "count": results.len(),
+        "total": total
+    });
+
+    match ctx.format {
+        OutputFormat::Json => {
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output = super::encode_toon(&json_value);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str(&format!("pattern: \"{}\"\n", pattern.trim()));
+            output.push_str(&format!("results[{}]:\n", results.len()));
+            for m in &results {
+                output.push_str(&format!(
+                    "  {} ({}) - {} [{}] {}:{}\n",
+                    m.symbol, m.kind, m.module, m.matched, m.file, m.lines
+                ));
+            }
+            if total > results.len() {
+                output.push_str(&format!(
+                    "({} more - raise the limit to see all)\n",
+                    total - results.len()
+                ));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Semantic-only search (BM25 natural language matching)
+fn run_semantic_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
+    use crate::bm25::search_sqlite;
+
+    let repo_dir = std::env::current_dir().map_err(|e| McpDiffError::FileNotFound {
+        path: format!("current directory: {}", e),
+    })?;
+    let cache = CacheDir::for_repo(&repo_dir)?;
+
+    cache.require_bm25_index()?;
+
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
     let bm25_path = cache.bm25_index_path();
-    let mut results = search_sqlite(&bm25_path, &args.query, args.limit * 2).map_err(|e| {
+    let fetch_limit = scope.fetch_limit(args.limit * 2);
+    let mut results = search_sqlite(&bm25_path, &args.query, fetch_limit).map_err(|e| {
         McpDiffError::GitError {
             message: format!("Failed to search BM25 index: {}", e),
         }
@@ -343,30 +911,46 @@ fn run_semantic_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String
         let escape_hashes = load_escape_local_hashes(&cache);
         results.retain(|r| !escape_hashes.contains(&r.hash));
     }
+    results.retain(|r| scope.contains_symbol(&r.file, &r.module));
 
     results.truncate(args.limit);
+    let generated = cache.generated_files().unwrap_or_default();
+    let generated: Vec<bool> = results
+        .iter()
+        .map(|r| generated.contains(&r.file))
+        .collect();
+    for r in &mut results {
+        r.file = ctx.display_path(&r.file, &cache.repo_root);
+    }
 
     let mut output = String::new();
 
     let suggestions: Vec<String> = Vec::new();
 
-    let json_value = serde_json::json!({
+    let mut json_value = serde_json::json!({
         "_type": "semantic_search",
         "query": args.query,
-        "results": results.iter().map(|r| serde_json::json!({
-            "symbol": r.symbol,
-            "kind": r.kind,
-            "hash": r.hash,
-            "file": r.file,
-            "lines": r.lines,
-            "module": r.module,
-            "risk": r.risk,
-            "score": r.score,
-            "matched_terms": r.matched_terms
-        })).collect::<Vec<_>>(),
+        "results": results.iter().zip(&generated).map(|(r, generated)| {
+            let mut result = serde_json::json!({
+                "symbol": r.symbol,
+                "kind": r.kind,
+                "hash": r.hash,
+                "file": r.file,
+                "lines": r.lines,
+                "module": r.module,
+                "risk": r.risk,
+                "score": r.score,
+                "matched_terms": r.matched_terms
+            });
+            if *generated {
+                result["generated"] = serde_json::json!(true);
+            }
+            result
+        }).collect::<Vec<_>>(),
         "count": results.len(),
         "related_terms": suggestions
     });
+    echo_path_scope(&mut json_value, &args.path_globs);
 
     match ctx.format {
         OutputFormat::Json => {
@@ -375,12 +959,18 @@ fn run_semantic_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!("query: \"{}\"\n", args.query));
+            output.push_str(&path_scope_line(&args.path_globs));
             output.push_str(&format!("results[{}]:\n", results.len()));
 
-            for result in &results {
-                output.push_str(&format!("\n## {} ({})\n", result.symbol, result.kind));
+            for (result, generated) in results.iter().zip(&generated) {
+                output.push_str(&format!(
+                    "\n## {} ({}){}\n",
+                    result.symbol,
+                    result.kind,
+                    generated_tag(*generated)
+                ));
                 output.push_str(&format!("hash: {}\n", result.hash));
                 output.push_str(&format!("file: {}\n", result.file));
                 output.push_str(&format!("lines: {}\n", result.lines));
@@ -432,17 +1022,28 @@ fn run_raw_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
         options = options.with_file_types(file_types);
     }
 
+    if !args.path_globs.is_empty() {
+        options = options.with_globs(glob_root(&repo_dir), args.path_globs.clone());
+    }
+
+    // A file root walks just that file, a package root just that package
+    let search_root = match (&args.in_file, &args.package) {
+        (Some(file), _) => repo_dir.join(file),
+        (None, Some(package)) => repo_dir.join(resolve_package(&repo_dir, package)?.path),
+        (None, None) => repo_dir.clone(),
+    };
+
     let searcher = RipgrepSearcher::new();
     let mut output = String::new();
 
     if args.merge_threshold > 0 {
-        match searcher.search_merged(&repo_dir, &options) {
+        match searcher.search_merged(&search_root, &options) {
             Ok(blocks) => {
-                let json_value = serde_json::json!({
+                let mut json_value = serde_json::json!({
                     "_type": "raw_search",
                     "pattern": args.query,
                     "blocks": blocks.iter().map(|b| serde_json::json!({
-                        "file": b.file.strip_prefix(&repo_dir).unwrap_or(&b.file).to_string_lossy(),
+                        "file": ctx.display_path(&b.file.to_string_lossy(), &repo_dir),
                         "start_line": b.start_line,
                         "end_line": b.end_line,
                         "lines": b.lines.iter().map(|l| serde_json::json!({
@@ -453,6 +1054,7 @@ fn run_raw_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
                     })).collect::<Vec<_>>(),
                     "count": blocks.len()
                 });
+                echo_path_scope(&mut json_value, &args.path_globs);
 
                 match ctx.format {
                     OutputFormat::Json => {
@@ -461,15 +1063,13 @@ fn run_raw_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
                     OutputFormat::Toon => {
                         output = super::encode_toon(&json_value);
                     }
-                    OutputFormat::Text => {
+                    OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                         output.push_str(&format!("pattern: \"{}\"\n", args.query));
+                        output.push_str(&path_scope_line(&args.path_globs));
                         output.push_str(&format!("blocks[{}]:\n", blocks.len()));
                         for block in &blocks {
-                            let relative_file = block
-                                .file
-                                .strip_prefix(&repo_dir)
-                                .unwrap_or(&block.file)
-                                .to_string_lossy();
+                            let relative_file =
+                                ctx.display_path(&block.file.to_string_lossy(), &repo_dir);
                             output.push_str(&format!(
                                 "\n--- {}:{}-{} ---\n",
                                 relative_file, block.start_line, block.end_line
@@ -492,19 +1092,20 @@ fn run_raw_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
             }
         }
     } else {
-        match searcher.search(&repo_dir, &options) {
+        match searcher.search(&search_root, &options) {
             Ok(matches) => {
-                let json_value = serde_json::json!({
+                let mut json_value = serde_json::json!({
                     "_type": "raw_search",
                     "pattern": args.query,
                     "matches": matches.iter().map(|m| serde_json::json!({
-                        "file": m.file.strip_prefix(&repo_dir).unwrap_or(&m.file).to_string_lossy(),
+                        "file": ctx.display_path(&m.file.to_string_lossy(), &repo_dir),
                         "line": m.line,
                         "column": m.column,
                         "content": m.content.trim()
                     })).collect::<Vec<_>>(),
                     "count": matches.len()
                 });
+                echo_path_scope(&mut json_value, &args.path_globs);
 
                 match ctx.format {
                     OutputFormat::Json => {
@@ -513,15 +1114,13 @@ fn run_raw_search(args: &SearchArgs, ctx: &CommandContext) -> Result<String> {
                     OutputFormat::Toon => {
                         output = super::encode_toon(&json_value);
                     }
-                    OutputFormat::Text => {
+                    OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
                         output.push_str(&format!("pattern: \"{}\"\n", args.query));
+                        output.push_str(&path_scope_line(&args.path_globs));
                         output.push_str(&format!("matches[{}]:\n", matches.len()));
                         for m in &matches {
-                            let relative_file = m
-                                .file
-                                .strip_prefix(&repo_dir)
-                                .unwrap_or(&m.file)
-                                .to_string_lossy();
+                            let relative_file =
+                                ctx.display_path(&m.file.to_string_lossy(), &repo_dir);
                             output.push_str(&format!(
                                 "  {}:{}:{}: {}\n",
                                 relative_file,
@@ -558,6 +1157,8 @@ struct SymbolEntry {
     lines: String,
     module: String,
     risk: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    generated: bool,
 }
 
 /// Symbol search results
@@ -577,6 +1178,8 @@ struct SemanticEntry {
     risk: String,
     score: f32,
     matched_terms: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    generated: bool,
 }
 
 /// Semantic search results with suggestions
@@ -585,15 +1188,30 @@ struct SemanticSearchResults {
     suggestions: Vec<String>,
 }
 
+/// Plain-text match added when the index finds nothing for a hybrid query
+#[derive(Debug, Clone, serde::Serialize)]
+struct RawFallbackEntry {
+    file: String,
+    line: u64,
+    column: u64,
+    content: String,
+    source: &'static str,
+}
+
 /// Get symbol matches from the index
-fn get_symbol_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SymbolSearchResults> {
+fn get_symbol_matches(
+    cache: &CacheDir,
+    args: &SearchArgs,
+    ctx: &CommandContext,
+) -> Option<SymbolSearchResults> {
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
     let search_result = cache
         .search_symbols_with_fallback(
             &args.query,
             args.module.as_deref(),
             args.kind.as_deref(),
             args.risk.as_deref(),
-            args.limit / 2, // Half limit for hybrid
+            scope.fetch_limit(args.limit / 2), // Half limit for hybrid
         )
         .ok()?;
 
@@ -602,20 +1220,32 @@ fn get_symbol_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SymbolSearc
         let ripgrep = search_result.ripgrep_results.unwrap_or_default();
         let results: Vec<SymbolEntry> = ripgrep
             .iter()
+            .filter(|r| scope.contains_text(&r.file))
             .take(args.limit / 2)
             .map(|r| SymbolEntry {
                 symbol: extract_symbol_name(&r.content),
                 kind: "unknown".to_string(),
                 hash: String::new(),
-                file: r.file.clone(),
+                file: ctx.display_path(&r.file, &cache.repo_root),
                 lines: r.line.to_string(),
                 module: "unknown".to_string(),
                 risk: "unknown".to_string(),
+                generated: false,
             })
             .collect();
         Some(SymbolSearchResults { results })
     } else {
         let mut indexed = search_result.indexed_results.unwrap_or_default();
+        if let Some(ai_layer) = &ctx.ai_layer {
+            ai_layer.apply_to_search(
+                &mut indexed,
+                &args.query,
+                args.module.as_deref(),
+                args.kind.as_deref(),
+                args.risk.as_deref(),
+                args.limit / 2,
+            );
+        }
         let symbol_scope = args.symbol_scope.for_kind(args.kind.as_deref());
         indexed.retain(|entry| symbol_scope.matches_kind(&entry.kind));
         if !args.include_escape_refs {
@@ -623,14 +1253,17 @@ fn get_symbol_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SymbolSearc
         }
         let results: Vec<SymbolEntry> = indexed
             .iter()
+            .filter(|e| scope.contains_symbol(&e.file, &e.module))
+            .take(args.limit / 2)
             .map(|e| SymbolEntry {
                 symbol: e.symbol.clone(),
                 kind: e.kind.clone(),
                 hash: e.hash.clone(),
-                file: e.file.clone(),
+                file: ctx.display_path(&e.file, &cache.repo_root),
                 lines: e.lines.clone(),
                 module: e.module.clone(),
                 risk: e.risk.clone(),
+                generated: e.is_generated,
             })
             .collect();
         Some(SymbolSearchResults { results })
@@ -638,15 +1271,20 @@ fn get_symbol_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SymbolSearc
 }
 
 /// Get semantic matches from the BM25 index
-fn get_semantic_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SemanticSearchResults> {
+fn get_semantic_matches(
+    cache: &CacheDir,
+    args: &SearchArgs,
+    ctx: &CommandContext,
+) -> Option<SemanticSearchResults> {
     use crate::bm25::search_sqlite;
 
     if !cache.has_bm25_index() {
         return None;
     }
 
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
     let bm25_path = cache.bm25_index_path();
-    let mut results = search_sqlite(&bm25_path, &args.query, args.limit).ok()?;
+    let mut results = search_sqlite(&bm25_path, &args.query, scope.fetch_limit(args.limit)).ok()?;
 
     // Apply filters
     if let Some(ref kind_filter) = args.kind {
@@ -663,10 +1301,20 @@ fn get_semantic_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SemanticS
         let escape_hashes = load_escape_local_hashes(cache);
         results.retain(|r| !escape_hashes.contains(&r.hash));
     }
+    results.retain(|r| scope.contains_symbol(&r.file, &r.module));
+
+    // Staged AI edits replace their files; BM25 does not score staged content
+    if let Some(ai_layer) = &ctx.ai_layer {
+        results.retain(|r| {
+            !ai_layer.overlay().symbols.contains_key(&r.hash)
+                && ai_layer.staged_file(&r.file).is_none()
+        });
+    }
 
     results.truncate(args.limit / 2); // Half limit for hybrid
 
     let suggestions: Vec<String> = Vec::new();
+    let generated = cache.generated_files().unwrap_or_default();
 
     let semantic_results: Vec<SemanticEntry> = results
         .iter()
@@ -674,12 +1322,13 @@ fn get_semantic_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SemanticS
             symbol: r.symbol.clone(),
             kind: r.kind.clone(),
             hash: r.hash.clone(),
-            file: r.file.clone(),
+            file: ctx.display_path(&r.file, &cache.repo_root),
             lines: r.lines.clone(),
             module: r.module.clone(),
             risk: r.risk.clone(),
             score: r.score as f32,
             matched_terms: r.matched_terms.clone(),
+            generated: generated.contains(&r.file),
         })
         .collect();
 
@@ -689,6 +1338,249 @@ fn get_semantic_matches(cache: &CacheDir, args: &SearchArgs) -> Option<SemanticS
     })
 }
 
+/// Literal ripgrep matches for the query, capped at `--fallback-limit`
+///
+/// Reads source files directly, so it works whether or not an index exists.
+fn get_raw_fallback_matches(
+    cache: &CacheDir,
+    args: &SearchArgs,
+    ctx: &CommandContext,
+) -> Vec<RawFallbackEntry> {
+    let scope = FileScope::new(args, &cache.repo_root, ctx);
+    let file_types = args
+        .file_types
+        .as_ref()
+        .map(|types| types.split(',').map(|t| t.trim().to_string()).collect());
+    // The query is text, not a pattern: match it literally
+    let matches = match cache.search_with_ripgrep(
+        &regex::escape(args.query.trim()),
+        file_types,
+        scope.fetch_limit(args.fallback_limit),
+    ) {
+        Ok(matches) => matches,
+        Err(e) => {
+            tracing::warn!("Raw search fallback failed: {}", e);
+            return Vec::new();
+        }
+    };
+
+    matches
+        .into_iter()
+        .filter(|m| scope.contains_text(&m.file))
+        .take(args.fallback_limit)
+        .map(|m| RawFallbackEntry {
+            file: ctx.display_path(&m.file, &cache.repo_root),
+            line: m.line,
+            column: m.column,
+            content: m.content.trim().to_string(),
+            source: "raw_fallback",
+        })
+        .collect()
+}
+
+/// Whether a query reads as a symbol name rather than free text
+///
+/// `loadConfig`, `MAX_RETRIES`, `cache::CacheDir` and `Parser` are symbol
+/// names; `timeout`, `retry budget exceeded` and `db.pool.size` are text an
+/// index search can miss.
+fn looks_like_symbol_name(query: &str) -> bool {
+    let query = query.trim();
+    let is_identifier = |part: &str| {
+        let mut chars = part.chars();
+        chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_')
+    };
+    if query.is_empty() || !query.split("::").all(is_identifier) {
+        return false;
+    }
+    // A lone lowercase word could be either; treat it as text
+    query.contains("::") || query.contains('_') || query.chars().any(char::is_uppercase)
+}
+
+/// The `--in-file`, `--package`, `--path-glob` and `--exclude-module` scope
+/// of a search, less the paths the repository config excludes
+///
+/// Paths are compared in repo-relative form, so the `./src/a.ts`, absolute
+/// and repo-relative paths the index may hold all match `src/a.ts`.
+struct FileScope<'a> {
+    file: Option<String>,
+    /// Directory of the `--package` workspace package
+    package_dir: Option<String>,
+    path_globs: PathGlobs,
+    excluded_modules: &'a [String],
+    settings: Arc<RepoSettings>,
+    repo_root: &'a Path,
+}
+
+impl<'a> FileScope<'a> {
+    fn new(args: &'a SearchArgs, repo_root: &'a Path, ctx: &CommandContext) -> Self {
+        let file = args
+            .in_file
+            .as_deref()
+            .map(|file| PathStyle::Relative.render(file, repo_root));
+        let package_dir = args.package.as_deref().map(|name| {
+            Workspace::detect(repo_root)
+                .and_then(|w| w.find(name).map(|p| p.path.clone()))
+                .unwrap_or_else(|| name.trim_end_matches('/').to_string())
+        });
+        Self {
+            file,
+            package_dir,
+            path_globs: PathGlobs::new(&args.path_globs, repo_root),
+            excluded_modules: &args.exclude_modules,
+            settings: ctx.repo_settings(repo_root),
+            repo_root,
+        }
+    }
+
+    /// Results to request from an index search that stops at its limit:
+    /// all of them when the scope will narrow them afterwards
+    fn fetch_limit(&self, limit: usize) -> usize {
+        if self.file.is_some()
+            || self.package_dir.is_some()
+            || !self.path_globs.is_empty()
+            || !self.excluded_modules.is_empty()
+            || !self.settings.exclude.is_empty()
+        {
+            usize::MAX
+        } else {
+            limit
+        }
+    }
+
+    fn contains(&self, file: &str) -> bool {
+        let in_file = self
+            .file
+            .as_ref()
+            .is_none_or(|scope| PathStyle::Relative.render(file, self.repo_root) == *scope);
+        let in_package = self.package_dir.as_ref().is_none_or(|dir| {
+            repo_relative(self.repo_root, file)
+                .strip_prefix(dir.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+        });
+        in_file
+            && in_package
+            && self.path_globs.contains(file, self.repo_root)
+            && !self.settings.is_excluded(file)
+    }
+
+    /// Whether an indexed symbol in `module` stays in scope
+    fn contains_symbol(&self, file: &str, module: &str) -> bool {
+        self.contains(file) && !is_excluded_module(module, self.excluded_modules)
+    }
+
+    /// Whether a plain-text match stays in scope; it has no indexed module,
+    /// so the module is taken from its path
+    fn contains_text(&self, file: &str) -> bool {
+        self.contains(file)
+            && (self.excluded_modules.is_empty()
+                || !is_excluded_module(
+                    &extract_module_name(&repo_relative(self.repo_root, file)),
+                    self.excluded_modules,
+                ))
+    }
+}
+
+/// The `--path-glob` patterns of a search
+///
+/// Globs are relative to the repository root: the git work tree when there
+/// is one, so a search run from a subdirectory scopes like one run from the
+/// top. They follow ripgrep's `--glob` rules, which raw search hands them to:
+/// a glob without a `/` matches a file name at any depth, a leading `**/`
+/// also matches at the root, and a `!` glob that matches a directory
+/// excludes everything under it.
+struct PathGlobs {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    root: PathBuf,
+}
+
+impl PathGlobs {
+    fn new(globs: &[String], repo_dir: &Path) -> Self {
+        let root = if globs.is_empty() {
+            repo_dir.to_path_buf()
+        } else {
+            glob_root(repo_dir)
+        };
+        let (exclude, include) = globs.iter().cloned().partition(|g| g.starts_with('!'));
+        Self {
+            include,
+            exclude: exclude.iter().map(|g| g[1..].to_string()).collect(),
+            root,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `file`, as the index under `repo_root` holds it, is in scope
+    fn contains(&self, file: &str, repo_root: &Path) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let relative = repo_relative(repo_root, file);
+        let path = repo_root.join(&relative);
+        let relative = path
+            .strip_prefix(&self.root)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or(relative);
+
+        let included =
+            self.include.is_empty() || self.include.iter().any(|g| path_glob_matches(g, &relative));
+        // The path itself or any directory above it
+        let excluded = relative
+            .match_indices('/')
+            .map(|(i, _)| &relative[..i])
+            .chain([relative.as_str()])
+            .any(|path| self.exclude.iter().any(|g| path_glob_matches(g, path)));
+        included && !excluded
+    }
+}
+
+/// The directory `--path-glob` patterns are relative to
+fn glob_root(repo_dir: &Path) -> PathBuf {
+    get_repo_root(Some(repo_dir))
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| repo_dir.to_path_buf())
+}
+
+/// Match one glob against a root-relative path, see [`PathGlobs`]
+fn path_glob_matches(glob: &str, path: &str) -> bool {
+    let glob = glob.trim_start_matches('/');
+    let name = path.rsplit('/').next().unwrap_or(path);
+    matches_glob(glob, path)
+        || (!glob.contains('/') && matches_glob(glob, name))
+        || glob
+            .strip_prefix("**/")
+            .is_some_and(|rest| path_glob_matches(rest, path))
+}
+
+/// Add the `--path-glob` scope after the query in a JSON/TOON header
+fn echo_path_scope(json: &mut serde_json::Value, globs: &[String]) {
+    if let (false, Some(header)) = (globs.is_empty(), json.as_object_mut()) {
+        header.shift_insert(2, "path_scope".to_string(), serde_json::json!(globs));
+    }
+}
+
+/// The `--path-glob` scope as a text-output header line
+fn path_scope_line(globs: &[String]) -> String {
+    if globs.is_empty() {
+        String::new()
+    } else {
+        format!("path_scope: {}\n", globs.join(", "))
+    }
+}
+
+/// Text-output marker for hits in generated code
+fn generated_tag(generated: bool) -> &'static str {
+    if generated {
+        " [generated]"
+    } else {
+        ""
+    }
+}
+
 fn load_escape_local_hashes(cache: &CacheDir) -> HashSet<String> {
     cache
         .load_all_symbol_entries()
@@ -740,8 +1632,11 @@ fn get_source_snippet(cache: &CacheDir, file: &str, lines: &str, context: usize)
     let end: usize = parts.get(1).unwrap_or(&parts[0]).parse().ok()?;
 
     // Read file
-    let file_path = cache.root.parent()?.join(file);
-    let content = std::fs::read_to_string(&file_path).ok()?;
+    let file_path = cache.repo_root.join(file);
+    let content = {
+        let _timer = PhaseTimer::start(Phase::FilesystemIo);
+        std::fs::read_to_string(&file_path).ok()?
+    };
     let all_lines: Vec<&str> = content.lines().collect();
 
     // Calculate range with context
@@ -9,7 +9,6 @@ use crate::duplicate::DuplicateDetector;
 use crate::error::{McpDiffError, Result};
 use crate::security::patterns::embedded::{load_embedded_patterns, pattern_stats};
 use crate::security::{CVEMatch, Severity};
-use crate::FunctionSignature;
 
 /// Run the security command
 pub fn run_security(args: &SecurityArgs, ctx: &CommandContext) -> Result<String> {
@@ -28,23 +27,25 @@ pub fn run_security(args: &SecurityArgs, ctx: &CommandContext) -> Result<String>
             *limit,
             ctx,
         ),
-        SecurityOperation::Update { url, file, force } => run_update_patterns(
+        SecurityOperation::Update {
+            url,
+            file,
+            checksum,
+            force,
+        } => run_update_patterns(
             url.as_deref(),
             file.as_ref().map(|p| p.as_path()),
+            checksum.as_deref(),
             *force,
             ctx,
         ),
         SecurityOperation::Stats => run_pattern_stats(ctx),
+        SecurityOperation::Rollback => run_rollback_patterns(ctx),
     }
 }
 
 // load_signatures removed - now uses crate::cache::load_function_signatures (DEDUP-105)
 
-/// Wrapper to load signatures with crate error type
-fn load_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignature>> {
-    load_function_signatures(cache).map_err(|e| McpDiffError::FileNotFound { path: e })
-}
-
 /// Scan for CVE vulnerability patterns
 fn run_cve_scan(
     module_filter: Option<&str>,
@@ -66,13 +67,15 @@ fn run_cve_scan(
     }
 
     // Load function signatures from index
-    let signatures = load_signatures(&cache)?;
+    let mut signatures = load_function_signatures(&cache)?;
+    let settings = ctx.repo_settings(&cache.repo_root);
+    signatures.retain(|sig| !settings.is_excluded(&sig.file));
 
     // Load pattern database (embedded at build time)
     let pattern_db = load_embedded_patterns();
 
     if pattern_db.is_empty() {
-        return Ok("No security patterns available.\nRun semfora-security-compiler to generate patterns, then rebuild with --features embedded-patterns.".to_string());
+        return Ok("No security patterns available.\nRun `semfora security update`, or rebuild with SECURITY_PATTERNS_PATH pointing at a database from semfora-security-compiler.".to_string());
     }
 
     if ctx.verbose {
@@ -147,6 +150,7 @@ fn run_cve_scan(
         "patterns_checked": pattern_db.len(),
         "matches": all_matches.iter().map(|m| serde_json::json!({
             "function": m.function,
+            "symbol_hash": m.symbol_hash,
             "file": m.file,
             "line": m.line,
             "cve_id": m.cve_id,
@@ -167,7 +171,7 @@ fn run_cve_scan(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  CVE VULNERABILITY SCAN\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -192,6 +196,9 @@ fn run_cve_scan(
                         m.similarity * 100.0
                     ));
                     output.push_str(&format!("function: {}\n", m.function));
+                    if let Some(ref hash) = m.symbol_hash {
+                        output.push_str(&format!("symbol_hash: {}\n", hash));
+                    }
                     output.push_str(&format!("file: {}:{}\n", m.file, m.line));
                     output.push_str(&format!("cwes: {}\n", m.cwe_ids.join(", ")));
                     output.push_str(&format!("description: {}\n", m.description));
@@ -211,66 +218,19 @@ fn run_cve_scan(
 fn run_update_patterns(
     url: Option<&str>,
     file_path: Option<&std::path::Path>,
+    checksum: Option<&str>,
     force: bool,
     ctx: &CommandContext,
 ) -> Result<String> {
     use crate::security::patterns::{fetch_pattern_updates, update_patterns_from_file};
 
-    let mut output = String::new();
-
-    if let Some(path) = file_path {
+    let result = if let Some(path) = file_path {
         // Load from file
         if ctx.verbose {
             eprintln!("Loading patterns from: {}", path.display());
         }
 
-        match update_patterns_from_file(path) {
-            Ok(result) => {
-                let json_value = serde_json::json!({
-                    "_type": "pattern_update",
-                    "success": true,
-                    "updated": result.updated,
-                    "previous_version": result.previous_version,
-                    "current_version": result.current_version,
-                    "pattern_count": result.pattern_count,
-                    "message": result.message
-                });
-
-                match ctx.format {
-                    OutputFormat::Json => {
-                        output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
-                    }
-                    OutputFormat::Toon => {
-                        output = super::encode_toon(&json_value);
-                    }
-                    OutputFormat::Text => {
-                        output.push_str("Security patterns updated successfully.\n\n");
-                        output.push_str(&format!("version: {}\n", result.current_version));
-                        output.push_str(&format!("patterns: {}\n", result.pattern_count));
-                        output.push_str(&format!("message: {}\n", result.message));
-                    }
-                }
-            }
-            Err(e) => {
-                let json_value = serde_json::json!({
-                    "_type": "pattern_update",
-                    "success": false,
-                    "error": e.to_string()
-                });
-
-                match ctx.format {
-                    OutputFormat::Json => {
-                        output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
-                    }
-                    OutputFormat::Toon => {
-                        output = super::encode_toon(&json_value);
-                    }
-                    OutputFormat::Text => {
-                        output.push_str(&format!("Failed to update patterns: {}\n", e));
-                    }
-                }
-            }
-        }
+        update_patterns_from_file(path, checksum)
     } else {
         // Fetch from URL (async - needs runtime)
         // For CLI, we'll use a blocking approach
@@ -286,56 +246,66 @@ fn run_update_patterns(
             }
         }
 
-        match rt.block_on(fetch_pattern_updates(url, force)) {
+        rt.block_on(fetch_pattern_updates(url, checksum, force))
+    };
+
+    Ok(format_update_result("pattern_update", result, ctx))
+}
+
+/// Restore the pattern database replaced by the last update
+fn run_rollback_patterns(ctx: &CommandContext) -> Result<String> {
+    use crate::security::patterns::rollback_patterns;
+
+    Ok(format_update_result(
+        "pattern_rollback",
+        rollback_patterns(),
+        ctx,
+    ))
+}
+
+/// Render the outcome of an update or rollback
+fn format_update_result(
+    kind: &str,
+    result: Result<crate::security::patterns::PatternUpdateResult>,
+    ctx: &CommandContext,
+) -> String {
+    let json_value = match &result {
+        Ok(result) => serde_json::json!({
+            "_type": kind,
+            "success": true,
+            "updated": result.updated,
+            "previous_version": result.previous_version,
+            "current_version": result.current_version,
+            "pattern_count": result.pattern_count,
+            "message": result.message
+        }),
+        Err(e) => serde_json::json!({
+            "_type": kind,
+            "success": false,
+            "error": e.to_string()
+        }),
+    };
+
+    match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => match result {
             Ok(result) => {
-                let json_value = serde_json::json!({
-                    "_type": "pattern_update",
-                    "success": true,
-                    "updated": result.updated,
-                    "previous_version": result.previous_version,
-                    "current_version": result.current_version,
-                    "pattern_count": result.pattern_count,
-                    "message": result.message
-                });
-
-                match ctx.format {
-                    OutputFormat::Json => {
-                        output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
-                    }
-                    OutputFormat::Toon => {
-                        output = super::encode_toon(&json_value);
-                    }
-                    OutputFormat::Text => {
-                        output.push_str("Security patterns updated successfully.\n\n");
-                        output.push_str(&format!("version: {}\n", result.current_version));
-                        output.push_str(&format!("patterns: {}\n", result.pattern_count));
-                        output.push_str(&format!("message: {}\n", result.message));
-                    }
+                let mut output = String::new();
+                if kind == "pattern_update" && result.updated {
+                    output.push_str("Security patterns updated successfully.\n\n");
                 }
+                output.push_str(&format!("version: {}\n", result.current_version));
+                output.push_str(&format!("patterns: {}\n", result.pattern_count));
+                output.push_str(&format!("message: {}\n", result.message));
+                output
             }
-            Err(e) => {
-                let json_value = serde_json::json!({
-                    "_type": "pattern_update",
-                    "success": false,
-                    "error": e.to_string()
-                });
-
-                match ctx.format {
-                    OutputFormat::Json => {
-                        output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
-                    }
-                    OutputFormat::Toon => {
-                        output = super::encode_toon(&json_value);
-                    }
-                    OutputFormat::Text => {
-                        output.push_str(&format!("Failed to update patterns: {}\n", e));
-                    }
-                }
+            Err(e) if kind == "pattern_rollback" => {
+                format!("Failed to roll back patterns: {}\n", e)
             }
-        }
+            Err(e) => format!("Failed to update patterns: {}\n", e),
+        },
     }
-
-    Ok(output)
 }
 
 /// Show security pattern statistics
@@ -352,7 +322,9 @@ fn run_pattern_stats(ctx: &CommandContext) -> Result<String> {
         "pattern_count": stats.pattern_count,
         "cwe_count": stats.cwe_count,
         "language_count": stats.language_count,
-        "source": format!("{:?}", stats.source)
+        "source": format!("{:?}", stats.source),
+        "update": stats.update,
+        "rollback_available": stats.rollback_available
     });
 
     match ctx.format {
@@ -362,7 +334,7 @@ fn run_pattern_stats(ctx: &CommandContext) -> Result<String> {
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  SECURITY PATTERN STATISTICS\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -378,6 +350,15 @@ fn run_pattern_stats(ctx: &CommandContext) -> Result<String> {
             output.push_str(&format!("cwes_covered: {}\n", stats.cwe_count));
             output.push_str(&format!("languages: {}\n", stats.language_count));
             output.push_str(&format!("source: {:?}\n", stats.source));
+            if let Some(ref update) = stats.update {
+                output.push_str(&format!("installed_from: {}\n", update.source));
+                output.push_str(&format!("checksum: {}\n", update.checksum));
+                output.push_str(&format!("fetched_at: {}\n", update.fetched_at));
+            }
+            output.push_str(&format!(
+                "rollback_available: {}\n",
+                stats.rollback_available
+            ));
         }
     }
 
@@ -7,7 +7,8 @@
 //!
 //! The server automatically maintains a fresh index with:
 //! - FileWatcher: Automatically updates Working layer on file changes
-//! - GitPoller: Polls for Base/Branch layer updates (git state changes)
+//! - GitPoller: Polls for Base/Branch layer updates (git state changes) and
+//!   compacts committed layers once HEAD advances
 //! - Thread-safe access: Concurrent reads, exclusive writes
 //!
 //! This ensures the index stays fresh throughout long-running sessions.
@@ -18,7 +19,9 @@ use std::sync::Arc;
 use crate::cli::ServeArgs;
 use crate::error::McpDiffError;
 use crate::mcp_server::McpDiffServer;
-use crate::server::{init_event_emitter, FileWatcher, GitPoller, ServerState};
+use crate::server::{
+    init_event_emitter, FileWatcher, GitPoller, PollerConfig, ServerState, WatcherConfig,
+};
 
 use rmcp::transport::stdio;
 use rmcp::ServiceExt;
@@ -76,7 +79,11 @@ async fn run_serve_async(args: &ServeArgs) -> crate::Result<()> {
 
     // Start background services for automatic layer updates (unless disabled)
     let _watcher_handle = if !args.no_watch {
-        let file_watcher = FileWatcher::new(repo_path.clone());
+        let config = WatcherConfig {
+            debounce_duration: std::time::Duration::from_millis(args.watch_debounce_ms),
+            ..WatcherConfig::default()
+        };
+        let file_watcher = FileWatcher::with_config(repo_path.clone(), config);
         let handle = file_watcher.start(Arc::clone(&server_state)).map_err(|e| {
             McpDiffError::ConfigError {
                 message: format!("Failed to start file watcher: {}", e),
@@ -90,7 +97,12 @@ async fn run_serve_async(args: &ServeArgs) -> crate::Result<()> {
     };
 
     let _poller_handle = if !args.no_git_poll {
-        let git_poller = GitPoller::new(repo_path.clone());
+        let config = PollerConfig {
+            compact_interval: (args.compact_interval > 0)
+                .then(|| std::time::Duration::from_secs(args.compact_interval)),
+            ..PollerConfig::default()
+        };
+        let git_poller = GitPoller::with_config(repo_path.clone(), config);
         let handle =
             git_poller
                 .start(Arc::clone(&server_state))
@@ -1,13 +1,19 @@
 //! Test command handler - Run or detect tests
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::cache::CacheDir;
 use crate::cli::{OutputFormat, TestArgs};
 use crate::commands::CommandContext;
 use crate::error::{McpDiffError, Result};
+use crate::line_symbols::LineSymbolIndex;
+use crate::test_links::TestLinkIndex;
 use crate::test_runner::{
-    detect_all_frameworks, run_tests, run_tests_with_framework, TestFramework, TestRunOptions,
+    detect_all_frameworks, detect_framework, run_tests, run_tests_with_framework, TestFailure,
+    TestFramework, TestRunOptions,
 };
+use crate::test_selection::{filter_for_framework, select_changed_tests, TestSelection};
+use crate::utils::truncate_to_char_boundary;
 
 /// Run the test command
 pub fn run_test(args: &TestArgs, ctx: &CommandContext) -> Result<String> {
@@ -46,7 +52,7 @@ fn run_detect_tests(project_dir: &PathBuf, ctx: &CommandContext) -> Result<Strin
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str(&format!("path: {}\n", project_dir.display()));
             output.push_str(&format!("frameworks_detected: {}\n\n", frameworks.len()));
 
@@ -76,36 +82,75 @@ fn run_execute_tests(
     project_dir: &PathBuf,
     ctx: &CommandContext,
 ) -> Result<String> {
-    let options = TestRunOptions {
+    let mut options = TestRunOptions {
         filter: args.filter.clone(),
         verbose: args.test_verbose,
         timeout_secs: Some(args.timeout),
         ..Default::default()
     };
 
+    let forced_framework = match args.framework {
+        Some(ref framework_name) => Some(parse_framework_name(framework_name)?),
+        None => None,
+    };
+
+    // Diff-aware selection: narrow the run to tests reaching changed code
+    let mut selection_json = None;
+    if let Some(ref base) = args.changed {
+        match select_changed_tests(project_dir, base) {
+            Ok(selection) => {
+                if selection.tests.is_empty() {
+                    return Ok(format_empty_selection(&selection, ctx));
+                }
+                let framework = forced_framework.unwrap_or_else(|| detect_framework(project_dir));
+                let (filter, extra_args) = filter_for_framework(framework, &selection.tests);
+                options.filter = filter;
+                options.extra_args.extend(extra_args);
+                selection_json = Some(serde_json::json!({
+                    "mode": "changed",
+                    "base": selection.base,
+                    "changed_symbols": selection.changed_symbols.len(),
+                    "selected": selection.tests.iter().map(|t| t.name.clone()).collect::<Vec<_>>()
+                }));
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warning: could not determine which tests cover the changes ({}); running all tests",
+                    e
+                );
+                selection_json = Some(serde_json::json!({
+                    "mode": "all",
+                    "base": base,
+                    "fallback": e.to_string()
+                }));
+            }
+        }
+    }
+
     if ctx.verbose {
         eprintln!("Running tests in: {}", project_dir.display());
-        if let Some(ref filter) = args.filter {
+        if let Some(ref filter) = options.filter {
             eprintln!("Filter: {}", filter);
         }
     }
 
-    let results = if let Some(ref framework_name) = args.framework {
+    let results = if let Some(framework) = forced_framework {
         // Force specific framework
-        let framework = parse_framework_name(framework_name)?;
         run_tests_with_framework(project_dir, framework, &options)
     } else {
         // Auto-detect
         run_tests(project_dir, &options)
     };
 
-    let results = results.map_err(|e| McpDiffError::GitError {
+    let mut results = results.map_err(|e| McpDiffError::GitError {
         message: format!("Test execution failed: {}", e),
     })?;
+    attach_production_symbols(project_dir, &mut results.failures);
+    attach_failing_symbols(project_dir, &mut results.failures);
 
     let mut output = String::new();
 
-    let json_value = serde_json::json!({
+    let mut json_value = serde_json::json!({
         "_type": "test_results",
         "framework": format!("{:?}", results.framework),
         "passed": results.passed,
@@ -114,13 +159,21 @@ fn run_execute_tests(
         "total": results.total,
         "duration_ms": results.duration_ms,
         "success": results.failed == 0,
-        "failures": results.failures.iter().map(|f| serde_json::json!({
-            "name": f.name,
-            "message": f.message,
-            "file": f.file,
-            "line": f.line
-        })).collect::<Vec<_>>()
+        "failures": results.failures.iter().map(|f| {
+            let mut failure = serde_json::json!({
+                "name": f.name,
+                "message": f.message,
+                "file": f.file,
+                "line": f.line,
+                "production_symbols": f.production_symbols
+            });
+            f.symbol.insert_into(&mut failure);
+            failure
+        }).collect::<Vec<_>>()
     });
+    if let Some(selection) = selection_json {
+        json_value["selection"] = selection;
+    }
 
     match ctx.format {
         OutputFormat::Json => {
@@ -129,7 +182,7 @@ fn run_execute_tests(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             let status = if results.failed == 0 {
                 "✓ PASSED"
             } else {
@@ -141,6 +194,18 @@ fn run_execute_tests(
             output.push_str("═══════════════════════════════════════════\n\n");
 
             output.push_str(&format!("framework: {:?}\n", results.framework));
+            if let Some(selection) = json_value.get("selection") {
+                if let Some(reason) = selection["fallback"].as_str() {
+                    output.push_str(&format!("selection: all tests (fallback: {})\n", reason));
+                } else {
+                    output.push_str(&format!(
+                        "selection: {} tests covering {} changed symbols since {}\n",
+                        selection["selected"].as_array().map_or(0, |a| a.len()),
+                        selection["changed_symbols"],
+                        selection["base"].as_str().unwrap_or_default()
+                    ));
+                }
+            }
             output.push_str(&format!(
                 "duration: {:.2}s\n\n",
                 results.duration_ms as f64 / 1000.0
@@ -161,6 +226,7 @@ fn run_execute_tests(
                     if let Some(ref file) = failure.file {
                         if let Some(line) = failure.line {
                             output.push_str(&format!("  at {}:{}\n", file, line));
+                            output.push_str(&format!("  {}\n", failure.symbol));
                         } else {
                             output.push_str(&format!("  at {}\n", file));
                         }
@@ -168,12 +234,20 @@ fn run_execute_tests(
                     if !failure.message.is_empty() {
                         // Truncate long messages
                         let msg = if failure.message.len() > 200 {
-                            format!("{}...", &failure.message[..200])
+                            format!("{}...", truncate_to_char_boundary(&failure.message, 200))
                         } else {
                             failure.message.clone()
                         };
                         output.push_str(&format!("  {}\n", msg));
                     }
+                    for symbol in &failure.production_symbols {
+                        output.push_str(&format!(
+                            "  exercises: {} ({}) [{}]\n",
+                            symbol.name,
+                            symbol.file,
+                            symbol.confidence.as_str()
+                        ));
+                    }
                 }
             }
         }
@@ -182,20 +256,86 @@ fn run_execute_tests(
     Ok(output)
 }
 
+/// Point each failure at the production symbols its test exercises
+///
+/// Needs an index with test links; failures are left as they are otherwise.
+fn attach_production_symbols(project_dir: &Path, failures: &mut [TestFailure]) {
+    if failures.is_empty() {
+        return;
+    }
+    let Ok(links) = CacheDir::for_repo(project_dir).and_then(|cache| TestLinkIndex::load(&cache))
+    else {
+        return;
+    };
+    for failure in failures {
+        failure.production_symbols = links.probable_symbols(&failure.name, failure.file.as_deref());
+    }
+}
+
+/// Point each failure with a known file and line at the symbol containing it
+fn attach_failing_symbols(project_dir: &Path, failures: &mut [TestFailure]) {
+    if !failures
+        .iter()
+        .any(|f| f.file.is_some() && f.line.is_some())
+    {
+        return;
+    }
+    let Some(mut symbols) = LineSymbolIndex::for_repo(project_dir) else {
+        return;
+    };
+    for failure in failures {
+        if let (Some(file), Some(line)) = (&failure.file, failure.line) {
+            failure.symbol = symbols.resolve(file, line);
+        }
+    }
+}
+
+/// Report a diff-aware selection that matched no tests
+fn format_empty_selection(selection: &TestSelection, ctx: &CommandContext) -> String {
+    let message = if selection.changed_files.is_empty() {
+        format!("No changes since {}", selection.base)
+    } else {
+        "No tests reach the changed code".to_string()
+    };
+
+    let json_value = serde_json::json!({
+        "_type": "test_selection",
+        "base": selection.base,
+        "changed_files": selection.changed_files,
+        "changed_symbols": selection.changed_symbols,
+        "selected": 0,
+        "message": message
+    });
+
+    match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            let mut output = String::new();
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  TEST SELECTION: nothing to run\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+            output.push_str(&format!("base: {}\n", selection.base));
+            output.push_str(&format!(
+                "changed_files: {}\n",
+                selection.changed_files.len()
+            ));
+            output.push_str(&format!(
+                "changed_symbols: {}\n",
+                selection.changed_symbols.len()
+            ));
+            output.push_str(&format!("\n{}.\n", message));
+            output
+        }
+    }
+}
+
 /// Parse framework name string to enum
 fn parse_framework_name(name: &str) -> Result<TestFramework> {
-    match name.to_lowercase().as_str() {
-        "pytest" | "python" => Ok(TestFramework::Pytest),
-        "cargo" | "rust" => Ok(TestFramework::Cargo),
-        "npm" | "node" => Ok(TestFramework::Npm),
-        "vitest" => Ok(TestFramework::Vitest),
-        "jest" => Ok(TestFramework::Jest),
-        "go" | "golang" => Ok(TestFramework::Go),
-        _ => Err(McpDiffError::GitError {
-            message: format!(
-                "Unknown test framework: '{}'. Supported: pytest, cargo, npm, vitest, jest, go",
-                name
-            ),
-        }),
-    }
+    TestFramework::from_name(name).ok_or_else(|| McpDiffError::GitError {
+        message: format!(
+            "Unknown test framework: '{}'. Supported: pytest, cargo, npm, vitest, jest, go",
+            name
+        ),
+    })
 }
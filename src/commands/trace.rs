@@ -43,7 +43,7 @@ pub fn run_trace(options: TraceOptions, ctx: &CommandContext) -> Result<String>
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
             output.push_str("═══════════════════════════════════════════\n");
             output.push_str("  TRACE\n");
             output.push_str("═══════════════════════════════════════════\n\n");
@@ -1,23 +1,35 @@
 //! Validate command handler - Quality audits (complexity, duplicates, impact)
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
-use crate::cache::{load_function_signatures, CacheDir};
+use crate::cache::{load_function_signatures, CacheDir, SymbolIndexEntry};
 use crate::cli::{OutputFormat, SymbolScope, ValidateArgs};
-use crate::commands::CommandContext;
-use crate::duplicate::DuplicateKind;
+use crate::commands::fail_on::{format_violations, FailOnRule, RuleMetric, RuleViolation};
+use crate::commands::{is_excluded_module, CommandContext};
+use crate::duplicate::report::{rank_consolidation_opportunities, ConsolidationOpportunity};
+use crate::duplicate::{DuplicateKind, DuplicateMatch};
 use crate::error::{McpDiffError, Result};
+use crate::git::{
+    detect_base_branch, get_changed_line_ranges, get_oldest_line_time, get_untracked_files,
+    is_git_repo, merge_base_with_deepen,
+};
 use crate::mcp_server::helpers::{
     find_symbol_by_hash, find_symbol_by_location, format_batch_validation_results,
     format_validation_result, validate_single_symbol, validate_symbols_batch,
 };
+use crate::module_usage::{FileImports, ModuleUsageIndex};
 use crate::normalize_kind;
-use crate::{DuplicateDetector, FunctionSignature};
+use crate::ownership::OwnershipIndex;
+use crate::schema::{CallGraphEdge, RefKind};
+use crate::test_selection::{parse_lines, relative_to};
+use crate::workspace::{repo_relative, resolve_package, Workspace};
+use crate::DuplicateDetector;
 
 /// Run the validate command - unified validation with auto scope detection
 ///
-/// Scope priority: symbol_hash > file_path+line > file_path > module > duplicates
+/// Scope priority: symbol_hash > file_path+line > file_path > module > duplicates;
+/// `--cycles` lists import cycles instead
 pub fn run_validate(args: &ValidateArgs, ctx: &CommandContext) -> Result<String> {
     // Use provided path or current directory
     let repo_dir = match &args.path {
@@ -34,24 +46,52 @@ pub fn run_validate(args: &ValidateArgs, ctx: &CommandContext) -> Result<String>
         });
     }
 
+    let report = run_validate_scope(args, &cache, ctx)?;
+    if args.fail_on.is_empty() {
+        return Ok(report);
+    }
+
+    let violations = evaluate_fail_on(args, &cache, ctx)?;
+    let output = append_violations(report, args, &violations, ctx);
+    if violations.is_empty() {
+        Ok(output)
+    } else {
+        Err(McpDiffError::ValidationFailed {
+            count: violations.len(),
+            report: output,
+        })
+    }
+}
+
+/// Run the validation for the scope selected by `args`
+fn run_validate_scope(
+    args: &ValidateArgs,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+) -> Result<String> {
+    // `--cycles` is a shorthand that skips symbol validation
+    if args.cycles {
+        return run_import_cycles(cache, ctx);
+    }
+
     // Scope detection (in order of priority):
     // 1. symbol_hash → single symbol validation
     if let Some(ref hash) = args.symbol_hash {
-        return run_validate_symbol_by_hash(args, &cache, hash, ctx);
+        return run_validate_symbol_by_hash(args, cache, hash, ctx);
     }
 
     // 2. file_path + line → single symbol at location
     // 3. file_path only → file-level validation
     if let Some(ref file_path) = args.file_path {
         if let Some(line) = args.line {
-            return run_validate_symbol_by_location(args, &cache, file_path, line, ctx);
+            return run_validate_symbol_by_location(args, cache, file_path, line, ctx);
         }
-        return run_validate_file(args, &cache, file_path, ctx);
+        return run_validate_file(args, cache, file_path, ctx);
     }
 
     // 4. module → module-level validation
     if let Some(ref module_name) = args.module {
-        return run_validate_module(args, &cache, module_name, ctx);
+        return run_validate_module(args, cache, module_name, ctx);
     }
 
     // 5. duplicates flag or legacy target-based routing
@@ -59,13 +99,13 @@ pub fn run_validate(args: &ValidateArgs, ctx: &CommandContext) -> Result<String>
         if let Some(ref target) = args.target {
             // Check if target looks like a hash (for single symbol duplicate check)
             if target.contains(':') || target.len() >= 16 {
-                return run_check_duplicates(target, args.threshold, &cache, ctx);
+                return run_check_duplicates(target, args.threshold, cache, ctx);
             }
         }
     }
 
     // Default: run full duplicate scan with optional file/module filter
-    run_find_duplicates(args, &cache, ctx)
+    run_find_duplicates(args, cache, ctx)
 }
 
 /// Validate a single symbol by hash (DEDUP-304)
@@ -106,25 +146,9 @@ fn run_validate_file(
     args: &ValidateArgs,
     cache: &CacheDir,
     file_path: &str,
-    _ctx: &CommandContext,
+    ctx: &CommandContext,
 ) -> Result<String> {
-    let all_entries = cache
-        .load_all_symbol_entries()
-        .map_err(|e| McpDiffError::GitError {
-            message: format!("Failed to load symbol index: {}", e),
-        })?;
-
-    let mut entries: Vec<_> = all_entries
-        .into_iter()
-        .filter(|e| e.file.ends_with(file_path) || file_path.ends_with(&e.file))
-        .collect();
-
-    if let Some(ref kind) = args.kind {
-        let normalized = normalize_kind(kind);
-        entries.retain(|e| e.kind.eq_ignore_ascii_case(normalized));
-    }
-    let symbol_scope = args.symbol_scope.for_kind(args.kind.as_deref());
-    entries.retain(|e| symbol_scope.matches_kind(&e.kind));
+    let entries = load_filtered_entries(args, cache, ctx, |e| in_file(e, file_path))?;
 
     if entries.is_empty() {
         return Err(McpDiffError::FileNotFound {
@@ -133,7 +157,10 @@ fn run_validate_file(
     }
 
     let results = validate_symbols_batch(cache, &entries, args.threshold);
-    let output = format_batch_validation_results(&results, file_path);
+    let mut output = format_batch_validation_results(&results, file_path);
+    output.push_str(&cycle_warnings(cache, |file, _| {
+        file.ends_with(file_path) || file_path.ends_with(file)
+    }));
 
     Ok(output)
 }
@@ -143,18 +170,104 @@ fn run_validate_module(
     args: &ValidateArgs,
     cache: &CacheDir,
     module_name: &str,
-    _ctx: &CommandContext,
+    ctx: &CommandContext,
 ) -> Result<String> {
+    let mut entries = load_filtered_entries(args, cache, ctx, |e| in_module(e, module_name))?;
+    entries.truncate(args.limit.min(500));
+
+    if entries.is_empty() {
+        return Err(McpDiffError::FileNotFound {
+            path: format!("No symbols found in module: {}", module_name),
+        });
+    }
+
+    let results = validate_symbols_batch(cache, &entries, args.threshold);
+    let mut output = format_batch_validation_results(&results, &format!("module:{}", module_name));
+    output.push_str(&cycle_warnings(cache, |_, imports| {
+        imports.module.eq_ignore_ascii_case(module_name) || imports.module.ends_with(module_name)
+    }));
+
+    Ok(output)
+}
+
+/// Warnings for the import cycles touching files selected by `in_scope`
+fn cycle_warnings(cache: &CacheDir, in_scope: impl Fn(&str, &FileImports) -> bool) -> String {
+    let Ok(usage) = ModuleUsageIndex::load(cache) else {
+        return String::new();
+    };
+    let warnings: Vec<String> = usage
+        .import_cycles()
+        .into_iter()
+        .filter(|(a, b)| [a, b].iter().any(|f| in_scope(f, &usage.files[f.as_str()])))
+        .map(|(a, b)| {
+            format!(
+                "import cycle: {} <-> {}",
+                relative_to(&cache.repo_root, &a),
+                relative_to(&cache.repo_root, &b)
+            )
+        })
+        .collect();
+    if warnings.is_empty() {
+        return String::new();
+    }
+    let mut output = format!("\nwarnings[{}]:\n", warnings.len());
+    for warning in warnings {
+        output.push_str(&format!("  - {}\n", warning));
+    }
+    output
+}
+
+/// List every pair of files importing each other (`validate --cycles`)
+fn run_import_cycles(cache: &CacheDir, ctx: &CommandContext) -> Result<String> {
+    let cycles: Vec<(String, String)> = ModuleUsageIndex::load(cache)?
+        .import_cycles()
+        .into_iter()
+        .map(|(a, b)| {
+            (
+                relative_to(&cache.repo_root, &a),
+                relative_to(&cache.repo_root, &b),
+            )
+        })
+        .collect();
+
+    let json_value = serde_json::json!({
+        "_type": "import_cycles",
+        "count": cycles.len(),
+        "cycles": cycles
+            .iter()
+            .map(|(a, b)| serde_json::json!({ "a": a, "b": b }))
+            .collect::<Vec<_>>(),
+    });
+
+    let output = match ctx.format {
+        OutputFormat::Json => serde_json::to_string_pretty(&json_value).unwrap_or_default(),
+        OutputFormat::Toon => super::encode_toon(&json_value),
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            let mut text = format!("import cycles: {}\n", cycles.len());
+            for (a, b) in &cycles {
+                text.push_str(&format!("  {} <-> {}\n", a, b));
+            }
+            text
+        }
+    };
+    Ok(output)
+}
+
+/// Index entries matching `filter`, narrowed by `--kind`, `--symbol-scope`,
+/// `--exclude-module` and the paths the repository config excludes
+fn load_filtered_entries(
+    args: &ValidateArgs,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+    filter: impl Fn(&SymbolIndexEntry) -> bool,
+) -> Result<Vec<SymbolIndexEntry>> {
     let all_entries = cache
         .load_all_symbol_entries()
         .map_err(|e| McpDiffError::GitError {
             message: format!("Failed to load symbol index: {}", e),
         })?;
 
-    let mut entries: Vec<_> = all_entries
-        .into_iter()
-        .filter(|e| e.module.eq_ignore_ascii_case(module_name) || e.module.ends_with(module_name))
-        .collect();
+    let mut entries: Vec<_> = all_entries.into_iter().filter(|e| filter(e)).collect();
 
     if let Some(ref kind) = args.kind {
         let normalized = normalize_kind(kind);
@@ -162,28 +275,26 @@ fn run_validate_module(
     }
     let symbol_scope = args.symbol_scope.for_kind(args.kind.as_deref());
     entries.retain(|e| symbol_scope.matches_kind(&e.kind));
-
-    entries.truncate(args.limit.min(500));
-
-    if entries.is_empty() {
-        return Err(McpDiffError::FileNotFound {
-            path: format!("No symbols found in module: {}", module_name),
-        });
+    entries.retain(|e| !is_excluded_module(&e.module, &args.exclude_modules));
+    if !args.include_generated {
+        entries.retain(|e| !e.is_generated);
     }
+    let settings = ctx.repo_settings(&cache.repo_root);
+    entries.retain(|e| !settings.is_excluded(&e.file));
 
-    let results = validate_symbols_batch(cache, &entries, args.threshold);
-    let output = format_batch_validation_results(&results, &format!("module:{}", module_name));
-
-    Ok(output)
+    Ok(entries)
 }
 
-// load_signatures removed - now uses crate::cache::load_function_signatures (DEDUP-105)
+fn in_file(entry: &SymbolIndexEntry, file_path: &str) -> bool {
+    entry.file.ends_with(file_path) || file_path.ends_with(&entry.file)
+}
 
-/// Wrapper to load signatures with crate error type
-fn load_signatures(cache: &CacheDir) -> Result<Vec<FunctionSignature>> {
-    load_function_signatures(cache).map_err(|e| McpDiffError::FileNotFound { path: e })
+fn in_module(entry: &SymbolIndexEntry, module_name: &str) -> bool {
+    entry.module.eq_ignore_ascii_case(module_name) || entry.module.ends_with(module_name)
 }
 
+// load_signatures removed - now uses crate::cache::load_function_signatures (DEDUP-105)
+
 /// Extract a short module name from a file path (fallback for old cached data)
 /// e.g., "/home/user/project/src/Presentation/Nop.Web/Factories/ProductModelFactory.cs"
 ///    -> "Nop.Web.Factories"
@@ -232,8 +343,15 @@ fn run_find_duplicates(
         });
     }
 
+    let package = match args.package {
+        Some(ref name) => Some(resolve_package(&cache.repo_root, name)?),
+        None => None,
+    };
+
     eprintln!("Loading function signatures...");
-    let mut signatures = load_signatures(cache)?;
+    ctx.report_progress("Loading signatures", 0, 1);
+    let mut signatures = load_function_signatures(cache)?;
+    ctx.report_progress("Loading signatures", 1, 1);
 
     // Filter by target if specified (file path or module name)
     if let Some(ref target) = args.target {
@@ -248,6 +366,20 @@ fn run_find_duplicates(
             });
         }
     }
+    signatures.retain(|sig| !is_excluded_module(&sig.module, &args.exclude_modules));
+    if !args.include_generated {
+        let generated = cache.generated_files()?;
+        signatures.retain(|sig| !generated.contains(&sig.file));
+    }
+    let settings = ctx.repo_settings(&cache.repo_root);
+    signatures.retain(|sig| !settings.is_excluded(&sig.file));
+
+    for sig in &mut signatures {
+        sig.file = ctx.display_path(&sig.file, &cache.repo_root);
+        if args.normalize_whitespace {
+            sig.normalize_whitespace();
+        }
+    }
 
     // Filter by minimum lines only for duplicate detection (DEDUP-207)
     if args.duplicates {
@@ -263,7 +395,8 @@ fn run_find_duplicates(
                 "message": "No function signatures found in index."
             })
             .to_string()),
-            OutputFormat::Toon | OutputFormat::Text => {
+            OutputFormat::Csv => Ok(duplicates_csv(std::iter::empty())),
+            OutputFormat::Toon | OutputFormat::Text | OutputFormat::ReviewJson => {
                 Ok("No function signatures found in index.".to_string())
             }
         };
@@ -278,9 +411,44 @@ fn run_find_duplicates(
     let detector =
         DuplicateDetector::new(args.threshold).with_boilerplate_exclusion(exclude_boilerplate);
 
-    let mut clusters = detector.find_all_clusters(&signatures);
+    let mut clusters = detector.find_all_clusters_with_progress(&signatures, |done, total| {
+        ctx.report_progress("Finding duplicates", done, total)
+    });
+    if let Some(ref package) = package {
+        clusters.retain(|c| {
+            cluster_files(c).any(|f| package.contains(&repo_relative(&cache.repo_root, f)))
+        });
+    }
     let total_clusters = clusters.len();
 
+    // Consolidation scoring never counts boilerplate, even with --include-boilerplate
+    let boilerplate_hashes: HashSet<String> = signatures
+        .iter()
+        .filter(|sig| sig.boilerplate_category.is_some())
+        .map(|sig| sig.symbol_hash.clone())
+        .collect();
+
+    if args.report {
+        let opportunities =
+            rank_consolidation_opportunities(&clusters, &boilerplate_hashes, get_module_name);
+        return Ok(format_consolidation_report(
+            args,
+            signatures.len(),
+            total_clusters,
+            &opportunities,
+            ctx,
+        ));
+    }
+
+    let savings: HashMap<String, ConsolidationOpportunity> = if args.sort_by == "savings" {
+        rank_consolidation_opportunities(&clusters, &boilerplate_hashes, get_module_name)
+            .into_iter()
+            .map(|o| (o.primary.hash.clone(), o))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
     // Sort clusters by specified criteria (DEDUP-207)
     match args.sort_by.as_str() {
         "size" => {
@@ -295,6 +463,17 @@ fn run_find_duplicates(
             // Sort by number of duplicates, most first
             clusters.sort_by(|a, b| b.duplicates.len().cmp(&a.duplicates.len()));
         }
+        "savings" => {
+            // Sort by estimated consolidation savings, boilerplate-only clusters last
+            let score = |c: &crate::duplicate::DuplicateCluster| {
+                savings.get(&c.primary.hash).map_or(0.0, |o| o.score)
+            };
+            clusters.sort_by(|a, b| {
+                score(b)
+                    .partial_cmp(&score(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
         _ => {
             // Default: sort by highest similarity in cluster
             clusters.sort_by(|a, b| {
@@ -317,7 +496,51 @@ fn run_find_duplicates(
 
     // Apply pagination with offset and limit (DEDUP-207)
     let limit = args.limit.min(200);
-    let paginated: Vec<_> = clusters.into_iter().skip(args.offset).take(limit).collect();
+    let mut paginated: Vec<_> = clusters.into_iter().skip(args.offset).take(limit).collect();
+
+    // Copy-paste origin for divergent clusters, blaming only the page shown
+    if is_git_repo(Some(&cache.repo_root)) {
+        for cluster in &mut paginated {
+            cluster.annotate_likely_original(|s| {
+                get_oldest_line_time(&s.file, s.start_line, s.end_line, Some(&cache.repo_root))
+            });
+        }
+    }
+
+    // Teams involved in each cluster, keyed by the primary's hash
+    let ownership = OwnershipIndex::load(cache).unwrap_or_default();
+    let owners: HashMap<String, Vec<String>> = paginated
+        .iter()
+        .map(|c| (c.primary.hash.clone(), cluster_owners(c, &ownership)))
+        .collect();
+    let owners_line = |c: &crate::duplicate::DuplicateCluster| {
+        owners
+            .get(&c.primary.hash)
+            .filter(|o| !o.is_empty())
+            .map(|o| o.join(", "))
+    };
+
+    // Workspace packages each cluster spans, keyed by the primary's hash
+    let workspace = Workspace::detect(&cache.repo_root);
+    let packages: HashMap<String, Vec<String>> = match workspace {
+        Some(ref workspace) => paginated
+            .iter()
+            .map(|c| {
+                let packages = cluster_packages(c, workspace, &cache.repo_root);
+                (c.primary.hash.clone(), packages)
+            })
+            .collect(),
+        None => HashMap::new(),
+    };
+    let packages_line = |c: &crate::duplicate::DuplicateCluster| {
+        let packages = packages.get(&c.primary.hash).filter(|p| !p.is_empty())?;
+        let cross = if packages.len() > 1 {
+            " (cross-package)"
+        } else {
+            ""
+        };
+        Some(format!("{}{}", packages.join(", "), cross))
+    };
 
     let mut output = String::new();
 
@@ -332,23 +555,50 @@ fn run_find_duplicates(
                 "sort_by": args.sort_by,
                 "total_signatures": signatures.len(),
                 "filter": args.target,
+                "package": args.package,
                 "clusters": total_clusters,
                 "offset": args.offset,
                 "limit": limit,
                 "showing": paginated.len(),
                 "total_duplicates": paginated.iter().map(|c| c.duplicates.len()).sum::<usize>(),
-                "cluster_details": paginated.iter().map(|c| serde_json::json!({
-                    "primary": c.primary.name,
-                    "primary_file": c.primary.file,
-                    "primary_hash": c.primary.hash,
-                    "duplicate_count": c.duplicates.len(),
-                    "duplicates": c.duplicates.iter().take(5).map(|d| serde_json::json!({
-                        "name": d.symbol.name,
-                        "file": d.symbol.file,
-                        "similarity": d.similarity,
-                        "kind": format!("{:?}", d.kind)
-                    })).collect::<Vec<_>>()
-                })).collect::<Vec<_>>()
+                "cluster_details": paginated.iter().map(|c| {
+                    let mut detail = serde_json::json!({
+                        "primary": c.primary.name,
+                        "primary_file": c.primary.file,
+                        "primary_hash": c.primary.hash,
+                        "duplicate_count": c.duplicates.len(),
+                        "duplicates": c.duplicates.iter().take(5).map(|d| serde_json::json!({
+                            "name": d.symbol.name,
+                            "file": d.symbol.file,
+                            "similarity": d.similarity,
+                            "kind": format!("{:?}", d.kind)
+                        })).collect::<Vec<_>>()
+                    });
+                    if let Some(original) = &c.likely_original {
+                        detail["likely_original"] = serde_json::json!({
+                            "name": original.symbol.name,
+                            "file": original.symbol.file,
+                            "hash": original.symbol.hash,
+                            "reason": original.reason.as_str()
+                        });
+                    }
+                    if let Some(owners) = owners.get(&c.primary.hash).filter(|o| !o.is_empty()) {
+                        detail["owners"] = serde_json::json!(owners);
+                    }
+                    if let Some(packages) = packages.get(&c.primary.hash).filter(|p| !p.is_empty()) {
+                        detail["packages"] = serde_json::json!(packages);
+                        detail["cross_package"] = serde_json::json!(packages.len() > 1);
+                    }
+                    if let Some(opp) = savings.get(&c.primary.hash) {
+                        detail["savings"] = serde_json::json!({
+                            "score": (opp.score * 10.0).round() / 10.0,
+                            "duplicated_lines": opp.duplicated_lines,
+                            "modules": opp.modules.len(),
+                            "action": opp.suggested_action()
+                        });
+                    }
+                    detail
+                }).collect::<Vec<_>>()
             });
             output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
         }
@@ -400,6 +650,27 @@ fn run_find_duplicates(
                 ));
                 output.push_str(&format!("  hash: {}\n", cluster.primary.hash));
                 output.push_str(&format!("  duplicates: {}\n", cluster.duplicates.len()));
+                if let Some(original) = &cluster.likely_original {
+                    output.push_str(&format!(
+                        "  likely_original: {}@{} ({})\n",
+                        original.symbol.name,
+                        get_module_name(&original.symbol),
+                        original.reason.as_str()
+                    ));
+                }
+                if let Some(owners) = owners_line(cluster) {
+                    output.push_str(&format!("  owners: {}\n", owners));
+                }
+                if let Some(packages) = packages_line(cluster) {
+                    output.push_str(&format!("  packages: {}\n", packages));
+                }
+                if let Some(opp) = savings.get(&cluster.primary.hash) {
+                    output.push_str(&format!(
+                        "  savings: {:.1} | duplicated_lines: {}\n",
+                        opp.score, opp.duplicated_lines
+                    ));
+                    output.push_str(&format!("  action: {}\n", opp.suggested_action()));
+                }
 
                 // Group duplicates by module (actual index module names)
                 let mut by_module: BTreeMap<String, Vec<&crate::duplicate::DuplicateMatch>> =
@@ -491,7 +762,20 @@ fn run_find_duplicates(
                 output.push_str("\n");
             }
         }
-        OutputFormat::Text => {
+        OutputFormat::Csv => {
+            // Spreadsheet export: every match of the page, not just the top few
+            output = duplicates_csv(paginated.iter().flat_map(|c| {
+                c.duplicates.iter().map(|d| {
+                    (
+                        c.primary.hash.as_str(),
+                        c.primary.name.as_str(),
+                        c.primary.file.as_str(),
+                        d,
+                    )
+                })
+            }));
+        }
+        OutputFormat::Text | OutputFormat::ReviewJson => {
             // Human-readable format for terminal (DEDUP-207 enhanced)
             output.push_str("═══════════════════════════════════════════\n");
             if let Some(ref target) = args.target {
@@ -536,6 +820,24 @@ fn run_find_duplicates(
                     cluster.primary.name, primary_module
                 ));
                 output.push_str(&format!("  hash: {}\n", cluster.primary.hash));
+                if let Some(original) = &cluster.likely_original {
+                    output.push_str(&format!(
+                        "Likely original: {} ({}, {})\n",
+                        original.symbol.name,
+                        original.symbol.file,
+                        original.reason.as_str()
+                    ));
+                }
+                if let Some(owners) = owners_line(cluster) {
+                    output.push_str(&format!("Owners: {}\n", owners));
+                }
+                if let Some(packages) = packages_line(cluster) {
+                    output.push_str(&format!("Packages: {}\n", packages));
+                }
+                if let Some(opp) = savings.get(&cluster.primary.hash) {
+                    output.push_str(&format!("Savings: {:.1}\n", opp.score));
+                    output.push_str(&format!("Action: {}\n", opp.suggested_action()));
+                }
 
                 // Group by module (actual index module names)
                 let mut by_module: BTreeMap<String, Vec<&crate::duplicate::DuplicateMatch>> =
@@ -594,6 +896,216 @@ fn run_find_duplicates(
     Ok(output)
 }
 
+/// Render duplicate matches as CSV, one row per (primary, duplicate) pair
+///
+/// Rows are `(primary_hash, primary_name, primary_file, match)`.
+fn duplicates_csv<'a>(
+    rows: impl Iterator<Item = (&'a str, &'a str, &'a str, &'a DuplicateMatch)>,
+) -> String {
+    let mut output = String::from(
+        "primary_hash,primary_name,primary_file,duplicate_hash,duplicate_name,duplicate_file,similarity,kind\n",
+    );
+    for (hash, name, file, dup) in rows {
+        let fields = [
+            hash.to_string(),
+            name.to_string(),
+            file.to_string(),
+            dup.symbol.hash.clone(),
+            dup.symbol.name.clone(),
+            dup.symbol.file.clone(),
+            format!("{:.4}", dup.similarity),
+            format!("{:?}", dup.kind).to_lowercase(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Files of a cluster's members, primary first
+fn cluster_files(cluster: &crate::duplicate::DuplicateCluster) -> impl Iterator<Item = &String> {
+    std::iter::once(&cluster.primary.file).chain(cluster.duplicates.iter().map(|d| &d.symbol.file))
+}
+
+/// Workspace packages of a cluster's files, primary first, without repeats
+fn cluster_packages(
+    cluster: &crate::duplicate::DuplicateCluster,
+    workspace: &Workspace,
+    repo_root: &Path,
+) -> Vec<String> {
+    let mut packages: Vec<String> = Vec::new();
+    for file in cluster_files(cluster) {
+        if let Some(package) = workspace.package_of(&repo_relative(repo_root, file)) {
+            if !packages.contains(&package.name) {
+                packages.push(package.name.clone());
+            }
+        }
+    }
+    packages
+}
+
+/// CODEOWNERS owners of a cluster's files, primary first, without repeats
+fn cluster_owners(
+    cluster: &crate::duplicate::DuplicateCluster,
+    ownership: &OwnershipIndex,
+) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+    for file in cluster_files(cluster) {
+        for owner in ownership.owners_of(file) {
+            if !owners.contains(owner) {
+                owners.push(owner.clone());
+            }
+        }
+    }
+    owners
+}
+
+/// Format duplicate clusters as consolidation opportunities ranked by savings
+fn format_consolidation_report(
+    args: &ValidateArgs,
+    total_signatures: usize,
+    total_clusters: usize,
+    opportunities: &[ConsolidationOpportunity],
+    ctx: &CommandContext,
+) -> String {
+    let limit = args.limit.min(200);
+    let page: Vec<_> = opportunities.iter().skip(args.offset).take(limit).collect();
+    let total_duplicated_lines: usize = opportunities.iter().map(|o| o.duplicated_lines).sum();
+    let showing_start = args.offset + 1;
+    let showing_end = args.offset + page.len();
+
+    let mut output = String::new();
+    match ctx.format {
+        OutputFormat::Json => {
+            let json_value = serde_json::json!({
+                "_type": "duplicate_consolidation_report",
+                "threshold": args.threshold,
+                "boilerplate_excluded": !args.include_boilerplate,
+                "min_lines": args.min_lines,
+                "total_signatures": total_signatures,
+                "filter": args.target,
+                "clusters": total_clusters,
+                "opportunities": opportunities.len(),
+                "total_duplicated_lines": total_duplicated_lines,
+                "offset": args.offset,
+                "limit": limit,
+                "showing": page.len(),
+                "opportunity_details": page.iter().enumerate().map(|(i, o)| serde_json::json!({
+                    "rank": args.offset + i + 1,
+                    "primary": o.primary.name,
+                    "primary_file": o.primary.file,
+                    "primary_hash": o.primary.hash,
+                    "module": o.primary_module,
+                    "copies": o.copies,
+                    "duplicated_lines": o.duplicated_lines,
+                    "modules": o.modules,
+                    "min_similarity": o.min_similarity,
+                    "max_similarity": o.max_similarity,
+                    "score": (o.score * 10.0).round() / 10.0,
+                    "action": o.suggested_action()
+                })).collect::<Vec<_>>()
+            });
+            output = serde_json::to_string_pretty(&json_value).unwrap_or_default();
+        }
+        OutputFormat::Toon => {
+            output.push_str(&super::toon_header("duplicate_consolidation"));
+            output.push_str(&format!(
+                "threshold: {:.0}% | clusters: {} | opportunities: {} | showing: {}-{} | min_lines: {}\n",
+                args.threshold * 100.0,
+                total_clusters,
+                opportunities.len(),
+                showing_start,
+                showing_end,
+                args.min_lines
+            ));
+            output.push_str(&format!(
+                "total_duplicated_lines: {}\n",
+                total_duplicated_lines
+            ));
+            if showing_end < opportunities.len() {
+                output.push_str(&format!(
+                    "hint: use --offset {} for next page\n",
+                    showing_end
+                ));
+            }
+            if page.is_empty() {
+                output.push_str("result: No consolidation opportunities found above threshold.\n");
+                return output;
+            }
+            output.push('\n');
+
+            for (i, opp) in page.iter().enumerate() {
+                output.push_str(&format!(
+                    "[{}] {} ({}:{})\n",
+                    args.offset + i + 1,
+                    opp.primary.name,
+                    opp.primary_module,
+                    opp.primary.start_line
+                ));
+                output.push_str(&format!("  action: {}\n", opp.suggested_action()));
+                output.push_str(&format!(
+                    "  savings: {:.1} | hash: {}\n",
+                    opp.score, opp.primary.hash
+                ));
+                if opp.modules.len() > 1 {
+                    output.push_str(&format!("  modules: {}\n", opp.modules.join(", ")));
+                }
+            }
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            output.push_str("═══════════════════════════════════════════\n");
+            output.push_str("  DUPLICATE CONSOLIDATION REPORT\n");
+            output.push_str("═══════════════════════════════════════════\n\n");
+
+            output.push_str(&format!("Threshold: {:.0}%\n", args.threshold * 100.0));
+            output.push_str(&format!("Signatures analyzed: {}\n", total_signatures));
+            output.push_str(&format!("Clusters found: {}\n", total_clusters));
+            output.push_str(&format!(
+                "Consolidation opportunities: {}\n",
+                opportunities.len()
+            ));
+            output.push_str(&format!("Duplicated lines: {}\n\n", total_duplicated_lines));
+
+            if page.is_empty() {
+                output.push_str("No consolidation opportunities found above threshold.\n");
+                return output;
+            }
+
+            for (i, opp) in page.iter().enumerate() {
+                output.push_str(&format!(
+                    "{}. {} ({}) — savings {:.1}\n",
+                    args.offset + i + 1,
+                    opp.primary.name,
+                    opp.primary_module,
+                    opp.score
+                ));
+                output.push_str(&format!("   → {}\n", opp.suggested_action()));
+            }
+
+            if showing_end < opportunities.len() {
+                output.push_str(&format!(
+                    "\n... showing {} of {} opportunities (use --offset {} for next page)\n",
+                    page.len(),
+                    opportunities.len(),
+                    showing_end
+                ));
+            }
+        }
+    }
+
+    output
+}
+
 /// Check duplicates for a specific symbol by hash
 fn run_check_duplicates(
     hash: &str,
@@ -602,7 +1114,10 @@ fn run_check_duplicates(
     ctx: &CommandContext,
 ) -> Result<String> {
     // Load all signatures
-    let signatures = load_signatures(cache)?;
+    let mut signatures = load_function_signatures(cache)?;
+    for sig in &mut signatures {
+        sig.file = ctx.display_path(&sig.file, &cache.repo_root);
+    }
 
     // Find the signature with matching hash
     let target_sig = signatures
@@ -648,7 +1163,17 @@ fn run_check_duplicates(
         OutputFormat::Toon => {
             output = super::encode_toon(&json_value);
         }
-        OutputFormat::Text => {
+        OutputFormat::Csv => {
+            output = duplicates_csv(duplicates.iter().map(|d| {
+                (
+                    target_sig.symbol_hash.as_str(),
+                    target_sig.name.as_str(),
+                    target_sig.file.as_str(),
+                    d,
+                )
+            }));
+        }
+        OutputFormat::Text | OutputFormat::ReviewJson => {
             output.push_str(&format!("symbol: {}\n", target_sig.name));
             output.push_str(&format!("hash: {}\n", target_sig.symbol_hash));
             output.push_str(&format!("file: {}\n", target_sig.file));
@@ -688,7 +1213,9 @@ pub fn run_duplicates(
     symbol_hash: Option<&str>,
     threshold: f64,
     module_filter: Option<&str>,
+    exclude_modules: &[String],
     exclude_boilerplate: bool,
+    include_generated: bool,
     min_lines: usize,
     sort_by: &str,
     limit: usize,
@@ -720,20 +1247,282 @@ pub fn run_duplicates(
         target: module_filter.map(String::from),
         threshold,
         duplicates: true,
+        cycles: false,
         include_boilerplate: !exclude_boilerplate,
+        include_generated,
+        normalize_whitespace: false,
         min_lines,
         limit,
         offset,
         sort_by: sort_by.to_string(),
+        report: false,
+        fail_on: Vec::new(),
+        base: None,
         // Not used for duplicates
         symbol_hash: None,
         file_path: None,
         line: None,
         module: None,
+        exclude_modules: exclude_modules.to_vec(),
+        package: None,
         include_source: false,
         kind: None,
         symbol_scope: SymbolScope::Functions,
+        output: None,
     };
 
     run_find_duplicates(&args, &cache, ctx)
 }
+
+// ============================================================================
+// --fail-on rules (CI gating)
+// ============================================================================
+
+/// Check every `--fail-on` rule against the symbols in the validation scope
+fn evaluate_fail_on(
+    args: &ValidateArgs,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+) -> Result<Vec<RuleViolation>> {
+    let entries = fail_on_scope(args, cache, ctx)?;
+    let needs_callers = args.fail_on.iter().any(|rule| {
+        matches!(
+            rule,
+            FailOnRule::Threshold {
+                metric: RuleMetric::Callers,
+                ..
+            }
+        )
+    });
+    let caller_counts = if needs_callers {
+        direct_caller_counts(cache)?
+    } else {
+        HashMap::new()
+    };
+
+    let mut violations = Vec::new();
+    for rule in &args.fail_on {
+        match rule {
+            FailOnRule::Threshold { metric, op, value } => {
+                for entry in &entries {
+                    let actual = match metric {
+                        // The index keeps a single complexity score
+                        RuleMetric::Cognitive | RuleMetric::Cyclomatic => {
+                            entry.cognitive_complexity
+                        }
+                        RuleMetric::Nesting => entry.max_nesting,
+                        RuleMetric::Callers => caller_counts.get(&entry.hash).copied().unwrap_or(0),
+                    };
+                    if op.holds(actual, *value) {
+                        violations.push(rule_violation(
+                            rule,
+                            entry,
+                            cache,
+                            Some(actual),
+                            format!("{}={}", metric.as_str(), actual),
+                        ));
+                    }
+                }
+            }
+            FailOnRule::NewDuplicates => {
+                for (entry, dup) in new_duplicates(args, cache, &entries)? {
+                    let detail = format!(
+                        "{:.0}% similar to {} ({})",
+                        dup.similarity * 100.0,
+                        dup.symbol.name,
+                        relative_to(&cache.repo_root, &dup.symbol.file)
+                    );
+                    violations.push(rule_violation(rule, entry, cache, None, detail));
+                }
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+fn rule_violation(
+    rule: &FailOnRule,
+    entry: &SymbolIndexEntry,
+    cache: &CacheDir,
+    value: Option<usize>,
+    detail: String,
+) -> RuleViolation {
+    RuleViolation {
+        rule: rule.to_string(),
+        symbol: entry.symbol.clone(),
+        hash: entry.hash.clone(),
+        file: relative_to(&cache.repo_root, &entry.file),
+        lines: entry.lines.clone(),
+        value,
+        detail,
+    }
+}
+
+/// Symbols covered by the validation scope (same priority as `run_validate`)
+fn fail_on_scope(
+    args: &ValidateArgs,
+    cache: &CacheDir,
+    ctx: &CommandContext,
+) -> Result<Vec<SymbolIndexEntry>> {
+    if let Some(ref hash) = args.symbol_hash {
+        let entry =
+            find_symbol_by_hash(cache, hash).map_err(|e| McpDiffError::GitError { message: e })?;
+        return Ok(vec![entry]);
+    }
+
+    if let Some(ref file_path) = args.file_path {
+        if let Some(line) = args.line {
+            let entry = find_symbol_by_location(cache, file_path, line)
+                .map_err(|e| McpDiffError::GitError { message: e })?;
+            return Ok(vec![entry]);
+        }
+        return load_filtered_entries(args, cache, ctx, |e| in_file(e, file_path));
+    }
+
+    if let Some(ref module_name) = args.module {
+        return load_filtered_entries(args, cache, ctx, |e| in_module(e, module_name));
+    }
+
+    match args.target {
+        Some(ref target) => {
+            let target_lower = target
+                .replace('/', std::path::MAIN_SEPARATOR_STR)
+                .to_lowercase();
+            load_filtered_entries(args, cache, ctx, |e| {
+                e.hash == *target || e.file.to_lowercase().contains(&target_lower)
+            })
+        }
+        None => load_filtered_entries(args, cache, ctx, |_| true),
+    }
+}
+
+/// Number of distinct direct callers per symbol hash
+fn direct_caller_counts(cache: &CacheDir) -> Result<HashMap<String, usize>> {
+    let call_graph = cache.load_call_graph()?;
+
+    let mut callers: HashMap<String, HashSet<&str>> = HashMap::new();
+    for (caller, callees) in &call_graph {
+        for callee in callees {
+            let edge = CallGraphEdge::decode(callee);
+            if edge.edge_kind == RefKind::None {
+                callers.entry(edge.callee).or_default().insert(caller);
+            }
+        }
+    }
+
+    Ok(callers
+        .into_iter()
+        .map(|(hash, callers)| (hash, callers.len()))
+        .collect())
+}
+
+/// Duplicates of symbols added or modified since the merge base with `--base`
+///
+/// Each changed symbol is paired with its closest match above the threshold.
+/// Duplicates between two untouched symbols predate the branch and don't count.
+fn new_duplicates<'a>(
+    args: &ValidateArgs,
+    cache: &CacheDir,
+    entries: &'a [SymbolIndexEntry],
+) -> Result<Vec<(&'a SymbolIndexEntry, DuplicateMatch)>> {
+    let cwd = Some(cache.repo_root.as_path());
+    if !is_git_repo(cwd) {
+        return Err(McpDiffError::NotGitRepo);
+    }
+    let base = match args.base {
+        Some(ref base) => base.clone(),
+        None => detect_base_branch(cwd)?,
+    };
+    let merge_base = merge_base_with_deepen(&base, "HEAD", None, cwd)?;
+
+    let mut changed_ranges = get_changed_line_ranges(&merge_base, cwd)?;
+    for path in get_untracked_files(cwd)? {
+        // Untracked files are new in their entirety
+        changed_ranges.push((path, vec![(1, usize::MAX)]));
+    }
+    let changed_ranges: HashMap<String, Vec<(usize, usize)>> = changed_ranges.into_iter().collect();
+
+    let changed: Vec<&SymbolIndexEntry> = entries
+        .iter()
+        .filter(|entry| {
+            changed_ranges
+                .get(&relative_to(&cache.repo_root, &entry.file))
+                .is_some_and(|ranges| {
+                    let (start, end) = parse_lines(&entry.lines);
+                    ranges.iter().any(|&(lo, hi)| lo <= end && start <= hi)
+                })
+        })
+        .collect();
+    if changed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut signatures = load_function_signatures(cache)?;
+    signatures.retain(|sig| sig.line_count >= args.min_lines);
+    if args.normalize_whitespace {
+        for sig in &mut signatures {
+            sig.normalize_whitespace();
+        }
+    }
+    let detector = DuplicateDetector::new(args.threshold)
+        .with_boilerplate_exclusion(!args.include_boilerplate);
+
+    let mut found = Vec::new();
+    for entry in changed {
+        let Some(signature) = signatures.iter().find(|sig| sig.symbol_hash == entry.hash) else {
+            continue;
+        };
+        let closest = detector
+            .find_duplicates(signature, &signatures)
+            .into_iter()
+            .filter(|m| m.similarity >= args.threshold)
+            .max_by(|a, b| {
+                a.similarity
+                    .partial_cmp(&b.similarity)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        if let Some(closest) = closest {
+            found.push((entry, closest));
+        }
+    }
+
+    Ok(found)
+}
+
+/// Attach the `--fail-on` outcome to the validation report
+///
+/// JSON reports gain `fail_on` and `violations` fields; text and TOON reports
+/// end with a `violations[N]:` section.
+fn append_violations(
+    report: String,
+    args: &ValidateArgs,
+    violations: &[RuleViolation],
+    ctx: &CommandContext,
+) -> String {
+    match ctx.format {
+        OutputFormat::Json => {
+            let mut value = match serde_json::from_str::<serde_json::Value>(&report) {
+                Ok(value @ serde_json::Value::Object(_)) => value,
+                // Symbol, file, and module validation only render TOON
+                _ => serde_json::json!({ "_type": "validation", "report": report }),
+            };
+            value["fail_on"] = serde_json::json!(args
+                .fail_on
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<_>>());
+            value["violations"] = serde_json::to_value(violations).unwrap_or_default();
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        }
+        OutputFormat::Toon | OutputFormat::Text | OutputFormat::Csv | OutputFormat::ReviewJson => {
+            let mut output = report;
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            output.push('\n');
+            output.push_str(&format_violations(violations));
+            output
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! React component structure: render tree and hook usage
+//!
+//! The index records which symbols are components, but not what they render
+//! or which hooks they call. [`ComponentIndex::load`] re-reads the files that
+//! define components and attributes each JSX element and hook call to the
+//! innermost component around it.
+//!
+//! JSX tags are resolved against component names, preferring a component
+//! defined in the rendering file; tags naming nothing indexed (`<div>`,
+//! library components) stay out of the tree. Member tags (`<Icons.Home />`)
+//! fall back to their last segment.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::detectors::javascript::frameworks::react::{collect_hook_calls, collect_jsx_elements};
+use crate::error::Result;
+use crate::lang::Lang;
+use crate::schema::{HookCall, JsxElement, SymbolKind};
+use crate::test_selection::parse_lines;
+
+/// An indexed React component with what its body renders and calls
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub name: String,
+    pub hash: String,
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub risk: String,
+    /// JSX elements with a PascalCase tag, in source order
+    pub renders: Vec<JsxElement>,
+    /// Hook calls, in source order
+    pub hooks: Vec<HookCall>,
+}
+
+/// A component in a render tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderNode {
+    /// Position in [`ComponentIndex::components`]
+    pub component: usize,
+    /// Rendered children, each component once, in first-render order
+    pub children: Vec<RenderNode>,
+    /// The component is also one of its own ancestors; not expanded again
+    pub cycle: bool,
+    /// The component renders children beyond the depth limit
+    pub truncated: bool,
+}
+
+impl RenderNode {
+    fn leaf(component: usize) -> Self {
+        Self {
+            component,
+            children: Vec::new(),
+            cycle: false,
+            truncated: false,
+        }
+    }
+
+    /// The tree in pre-order, with each node's depth below this one
+    pub fn flatten(&self) -> Vec<(usize, &RenderNode)> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![(0, self)];
+        while let Some((depth, node)) = stack.pop() {
+            nodes.push((depth, node));
+            stack.extend(node.children.iter().rev().map(|c| (depth + 1, c)));
+        }
+        nodes
+    }
+}
+
+/// Components of a repository, searchable by name
+#[derive(Debug, Default)]
+pub struct ComponentIndex {
+    components: Vec<Component>,
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl ComponentIndex {
+    /// Build the index from the symbol index and the component files on disk
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        let entries = cache.load_all_symbol_entries()?;
+        Ok(Self::from_entries(entries, |file| {
+            fs::read_to_string(cache.repo_root.join(file)).ok()
+        }))
+    }
+
+    /// Build the index from symbol entries, reading sources through `read`
+    ///
+    /// Only components in JavaScript/TypeScript files are kept. A file that
+    /// can't be read or parsed keeps its components, with nothing rendered.
+    pub fn from_entries(
+        entries: impl IntoIterator<Item = SymbolIndexEntry>,
+        read: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut by_file: BTreeMap<String, Vec<SymbolIndexEntry>> = BTreeMap::new();
+        for entry in entries {
+            if entry.is_escape_local || SymbolKind::from_str(&entry.kind) != SymbolKind::Component {
+                continue;
+            }
+            let is_react_file = matches!(
+                Lang::from_path(Path::new(&entry.file)),
+                Ok(Lang::TypeScript | Lang::Tsx | Lang::JavaScript | Lang::Jsx)
+            );
+            if is_react_file {
+                by_file.entry(entry.file.clone()).or_default().push(entry);
+            }
+        }
+
+        let mut index = Self::default();
+        for (file, entries) in by_file {
+            let first = index.components.len();
+            for entry in entries {
+                let (start_line, end_line) = parse_lines(&entry.lines);
+                index.components.push(Component {
+                    name: entry.symbol,
+                    hash: entry.hash,
+                    file: entry.file,
+                    start_line,
+                    end_line,
+                    risk: entry.risk,
+                    renders: Vec::new(),
+                    hooks: Vec::new(),
+                });
+            }
+            index.components[first..].sort_by_key(|c| (c.start_line, c.name.clone()));
+
+            let Some((elements, hooks)) = read(&file).and_then(|s| parse_react(&file, &s)) else {
+                continue;
+            };
+            let in_file = first..index.components.len();
+            for element in elements {
+                if !element.tag.starts_with(|c: char| c.is_ascii_uppercase()) {
+                    continue;
+                }
+                if let Some(owner) = index.innermost(in_file.clone(), element.location.line) {
+                    index.components[owner].renders.push(element);
+                }
+            }
+            for hook in hooks {
+                if let Some(owner) = index.innermost(in_file.clone(), hook.location.line) {
+                    index.components[owner].hooks.push(hook);
+                }
+            }
+        }
+
+        for (i, component) in index.components.iter().enumerate() {
+            index
+                .by_name
+                .entry(component.name.clone())
+                .or_default()
+                .push(i);
+        }
+        index
+    }
+
+    /// All components, grouped by file
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Find a component by name or symbol hash
+    ///
+    /// A name defined in several files resolves to the first by path.
+    pub fn find(&self, name_or_hash: &str) -> Option<usize> {
+        self.by_name
+            .get(name_or_hash)
+            .and_then(|c| c.first().copied())
+            .or_else(|| self.components.iter().position(|c| c.hash == name_or_hash))
+    }
+
+    /// Components rendered directly by `component`, each once
+    pub fn children_of(&self, component: usize) -> Vec<usize> {
+        let from = &self.components[component];
+        let mut children = Vec::new();
+        for element in &from.renders {
+            if let Some(child) = self.resolve_tag(&element.tag, &from.file) {
+                if !children.contains(&child) {
+                    children.push(child);
+                }
+            }
+        }
+        children
+    }
+
+    /// The render tree under `root`, at most `depth` levels deep
+    ///
+    /// A component rendering one of its ancestors (or itself) appears once
+    /// more, marked as a cycle, without being expanded again.
+    pub fn render_tree(&self, root: usize, depth: usize) -> RenderNode {
+        let mut ancestors = Vec::new();
+        self.expand(root, depth, &mut ancestors)
+    }
+
+    /// Components calling `hook`, optionally only with `argument` as its
+    /// first argument, with the matching calls
+    pub fn hook_users(
+        &self,
+        hook: &str,
+        argument: Option<&str>,
+    ) -> Vec<(&Component, Vec<&HookCall>)> {
+        self.components
+            .iter()
+            .filter_map(|component| {
+                let calls: Vec<&HookCall> = component
+                    .hooks
+                    .iter()
+                    .filter(|h| h.name == hook)
+                    .filter(|h| argument.is_none() || h.argument.as_deref() == argument)
+                    .collect();
+                (!calls.is_empty()).then_some((component, calls))
+            })
+            .collect()
+    }
+
+    fn expand(&self, component: usize, depth: usize, ancestors: &mut Vec<usize>) -> RenderNode {
+        let mut node = RenderNode::leaf(component);
+        let children = self.children_of(component);
+        if depth == 0 {
+            node.truncated = !children.is_empty();
+            return node;
+        }
+
+        ancestors.push(component);
+        for child in children {
+            if ancestors.contains(&child) {
+                node.children.push(RenderNode {
+                    cycle: true,
+                    ..RenderNode::leaf(child)
+                });
+            } else {
+                node.children.push(self.expand(child, depth - 1, ancestors));
+            }
+        }
+        ancestors.pop();
+        node
+    }
+
+    /// Component a JSX tag refers to, preferring one in `from_file`
+    fn resolve_tag(&self, tag: &str, from_file: &str) -> Option<usize> {
+        let candidates = self
+            .by_name
+            .get(tag)
+            .or_else(|| self.by_name.get(tag.rsplit('.').next()?))?;
+        candidates
+            .iter()
+            .copied()
+            .find(|&c| self.components[c].file == from_file)
+            .or_else(|| candidates.first().copied())
+    }
+
+    /// Smallest component in `range` whose lines include `line`
+    fn innermost(&self, range: std::ops::Range<usize>, line: usize) -> Option<usize> {
+        range
+            .filter(|&i| {
+                let c = &self.components[i];
+                c.start_line <= line && line <= c.end_line
+            })
+            .min_by_key(|&i| self.components[i].end_line - self.components[i].start_line)
+    }
+}
+
+/// JSX elements and hook calls of a source file
+fn parse_react(file: &str, source: &str) -> Option<(Vec<JsxElement>, Vec<HookCall>)> {
+    let lang = Lang::from_path(Path::new(file)).ok()?;
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&lang.tree_sitter_language()).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+    Some((
+        collect_jsx_elements(&root, source),
+        collect_hook_calls(&root, source),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::FrameworkEntryPoint;
+
+    fn component(name: &str, file: &str, lines: &str) -> SymbolIndexEntry {
+        SymbolIndexEntry {
+            symbol: name.to_string(),
+            hash: format!("hash_{}", name),
+            semantic_hash: String::new(),
+            kind: "component".to_string(),
+            module: "src".to_string(),
+            file: file.to_string(),
+            lines: lines.to_string(),
+            risk: "low".to_string(),
+            cognitive_complexity: 0,
+            max_nesting: 0,
+            is_escape_local: false,
+            framework_entry_point: FrameworkEntryPoint::None,
+            is_exported: true,
+            decorators: String::new(),
+            arity: 0,
+            is_async: false,
+            return_type: String::new(),
+            ext_package: String::new(),
+            base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
+        }
+    }
+
+    #[test]
+    fn test_render_tree_marks_cycles_and_truncation() {
+        let source = "\
+function Tree() {
+    return <Node><Leaf /></Node>;
+}
+function Node() {
+    return <div><Tree /><Leaf /></div>;
+}
+function Leaf() {
+    return <span />;
+}
+";
+        let index = ComponentIndex::from_entries(
+            vec![
+                component("Tree", "src/tree.tsx", "1-3"),
+                component("Node", "src/tree.tsx", "4-6"),
+                component("Leaf", "src/tree.tsx", "7-9"),
+            ],
+            |_| Some(source.to_string()),
+        );
+        let tree = index.find("Tree").unwrap();
+        let node = index.find("Node").unwrap();
+        let leaf = index.find("Leaf").unwrap();
+
+        let root = index.render_tree(tree, 5);
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].component, node);
+        assert_eq!(root.children[1].component, leaf);
+        let node_children = &root.children[0].children;
+        assert_eq!(node_children[0].component, tree);
+        assert!(node_children[0].cycle);
+        assert!(node_children[0].children.is_empty());
+
+        let shallow = index.render_tree(tree, 1);
+        assert!(shallow.children[0].truncated);
+        assert!(!shallow.children[1].truncated, "Leaf renders no components");
+        assert_eq!(shallow.flatten().len(), 3);
+    }
+}
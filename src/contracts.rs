@@ -0,0 +1,433 @@
+//! Cross-service contract tracking
+//!
+//! Ties together the protobuf and OpenAPI detectors to answer two questions
+//! about the contracts checked into a repository:
+//!
+//! - **What breaks?** [`breaking_changes`] compares two versions of a contract
+//!   file and lists changes that break existing clients (used by `analyze --diff`).
+//! - **Who consumes it?** [`find_contract_usages`] maps a contract element to the
+//!   application symbols that call its generated code, by matching the element's
+//!   names against the callees recorded in the call graph.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::detectors::openapi::{self, OpenApiDoc};
+use crate::detectors::protobuf::{self, ProtoFile};
+use crate::error::Result;
+use crate::git::{get_file_at_ref, ChangeType, ChangedFile};
+use crate::lang::Lang;
+
+/// A parsed contract document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContractDoc {
+    Protobuf(ProtoFile),
+    OpenApi(OpenApiDoc),
+}
+
+impl ContractDoc {
+    /// Parse `source` as a contract, based on the file's language.
+    ///
+    /// Returns `None` for files that are not protobuf or OpenAPI documents.
+    pub fn parse(path: &Path, source: &str) -> Option<Self> {
+        match Lang::from_path(path).ok()? {
+            Lang::Protobuf => Some(Self::Protobuf(protobuf::parse(source))),
+            lang @ (Lang::Yaml | Lang::Json) => openapi::parse(source, lang).map(Self::OpenApi),
+            _ => None,
+        }
+    }
+
+    /// An empty document of the same format (used for deleted files)
+    fn empty_like(&self) -> Self {
+        match self {
+            Self::Protobuf(_) => Self::Protobuf(ProtoFile::default()),
+            Self::OpenApi(_) => Self::OpenApi(OpenApiDoc::default()),
+        }
+    }
+}
+
+/// A contract change that breaks existing clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakingChange {
+    /// Contract file (relative to the repo root)
+    pub file: String,
+    /// Changed element (e.g. `User.email`, `GET /users/{id}`)
+    pub element: String,
+    /// What changed (e.g. `field removed (#2)`)
+    pub reason: String,
+}
+
+/// Breaking changes between two versions of a contract file.
+///
+/// `before`/`after` are `None` when the file did not exist on that side.
+/// New contract files never break clients; deleted ones remove every element.
+pub fn breaking_changes(
+    file: &str,
+    before: Option<&str>,
+    after: Option<&str>,
+) -> Vec<BreakingChange> {
+    let path = Path::new(file);
+    let Some(old) = before.and_then(|source| ContractDoc::parse(path, source)) else {
+        return Vec::new();
+    };
+    let new = after
+        .and_then(|source| ContractDoc::parse(path, source))
+        .unwrap_or_else(|| old.empty_like());
+
+    let changes = match (&old, &new) {
+        (ContractDoc::Protobuf(old), ContractDoc::Protobuf(new)) => {
+            protobuf::breaking_changes(old, new)
+        }
+        (ContractDoc::OpenApi(old), ContractDoc::OpenApi(new)) => {
+            openapi::breaking_changes(old, new)
+        }
+        _ => Vec::new(),
+    };
+
+    changes
+        .into_iter()
+        .map(|(element, reason)| BreakingChange {
+            file: file.to_string(),
+            element,
+            reason,
+        })
+        .collect()
+}
+
+/// Breaking contract changes across a set of changed files.
+///
+/// `base_ref` is read from git. `target_ref` is read from git as well, or from
+/// the working tree when it is `None`.
+pub fn diff_breaking_changes(
+    repo_root: &Path,
+    base_ref: &str,
+    target_ref: Option<&str>,
+    changed_files: &[ChangedFile],
+) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    for changed in changed_files {
+        if !is_contract_candidate(&changed.path) || changed.change_type == ChangeType::Added {
+            continue;
+        }
+
+        let old_path = changed.old_path.as_deref().unwrap_or(&changed.path);
+        let before = get_file_at_ref(old_path, base_ref, Some(repo_root))
+            .ok()
+            .flatten();
+        let after = if changed.change_type == ChangeType::Deleted {
+            None
+        } else {
+            match target_ref {
+                Some(target) => get_file_at_ref(&changed.path, target, Some(repo_root))
+                    .ok()
+                    .flatten(),
+                None => fs::read_to_string(repo_root.join(&changed.path)).ok(),
+            }
+        };
+
+        changes.extend(breaking_changes(
+            &changed.path,
+            before.as_deref(),
+            after.as_deref(),
+        ));
+    }
+
+    changes
+}
+
+/// Cheap extension check before reading a file from git
+fn is_contract_candidate(path: &str) -> bool {
+    matches!(
+        Lang::from_path(Path::new(path)),
+        Ok(Lang::Protobuf | Lang::Yaml | Lang::Json)
+    )
+}
+
+// ============================================================================
+// Contract usages
+// ============================================================================
+
+/// A contract element matched by a `contract-usages` query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractElement {
+    /// Element name (message, rpc, schema, path or operation)
+    pub name: String,
+    /// Element kind (`message`, `enum`, `service`, `rpc`, `path`, `operation`, `schema`)
+    pub kind: &'static str,
+    /// Contract file (as recorded in the index)
+    pub file: String,
+    /// Names generated code is expected to use for this element
+    pub consumer_names: Vec<String>,
+}
+
+/// An application symbol that calls into a contract element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractConsumer {
+    pub symbol: String,
+    pub hash: String,
+    pub file: String,
+    pub lines: String,
+    /// The callee name that matched the contract element
+    pub via: String,
+}
+
+/// A contract element together with its consumers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractUsage {
+    pub element: ContractElement,
+    pub consumers: Vec<ContractConsumer>,
+}
+
+/// Find the application symbols that consume a contract element.
+///
+/// `target` is a message, enum, service, rpc or schema name, an OpenAPI
+/// `operationId`, or an OpenAPI path (starting with `/`). Consumers are found
+/// by matching the element's generated-code names against call graph callees,
+/// ignoring case and underscores (`GetUser` matches `getUser` and `get_user`).
+pub fn find_contract_usages(cache: &CacheDir, target: &str) -> Result<Vec<ContractUsage>> {
+    let entries = cache.load_all_symbol_entries()?;
+
+    let contract_files: BTreeSet<&str> = entries
+        .iter()
+        .filter(|e| e.kind == "contract" || e.kind == "rpc")
+        .map(|e| e.file.as_str())
+        .collect();
+
+    let mut elements = Vec::new();
+    for file in contract_files {
+        let path = if Path::new(file).is_absolute() {
+            Path::new(file).to_path_buf()
+        } else {
+            cache.repo_root.join(file)
+        };
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if let Some(doc) = ContractDoc::parse(&path, &source) {
+            elements.extend(matching_elements(&doc, file, target));
+        }
+    }
+
+    if elements.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let call_graph = cache.load_call_graph()?;
+    let by_hash: HashMap<&str, &SymbolIndexEntry> =
+        entries.iter().map(|e| (e.hash.as_str(), e)).collect();
+
+    let usages = elements
+        .into_iter()
+        .map(|element| {
+            let wanted: HashSet<String> = element
+                .consumer_names
+                .iter()
+                .map(|n| normalize_name(n))
+                .collect();
+
+            let mut consumers: Vec<ContractConsumer> = Vec::new();
+            let mut seen = HashSet::new();
+            for (caller, callees) in &call_graph {
+                let Some(caller_entry) = by_hash.get(caller.as_str()) else {
+                    continue;
+                };
+                // Only application code counts as a consumer
+                if caller_entry.kind == "contract" || caller_entry.kind == "rpc" {
+                    continue;
+                }
+                for callee in callees {
+                    let name = callee_name(callee, &by_hash);
+                    if wanted.contains(&normalize_name(&name)) && seen.insert(caller.clone()) {
+                        consumers.push(ContractConsumer {
+                            symbol: caller_entry.symbol.clone(),
+                            hash: caller.clone(),
+                            file: caller_entry.file.clone(),
+                            lines: caller_entry.lines.clone(),
+                            via: name,
+                        });
+                    }
+                }
+            }
+            consumers.sort_by(|a, b| (&a.file, &a.symbol).cmp(&(&b.file, &b.symbol)));
+
+            ContractUsage { element, consumers }
+        })
+        .collect();
+
+    Ok(usages)
+}
+
+/// Contract elements in `doc` that match `target`
+fn matching_elements(doc: &ContractDoc, file: &str, target: &str) -> Vec<ContractElement> {
+    let mut elements = Vec::new();
+    let element = |name: &str, kind, consumer_names: Vec<String>| ContractElement {
+        name: name.to_string(),
+        kind,
+        file: file.to_string(),
+        consumer_names,
+    };
+
+    match doc {
+        ContractDoc::Protobuf(proto) => {
+            for message in &proto.messages {
+                if name_matches(&message.name, target) {
+                    elements.push(element(
+                        &message.name,
+                        "message",
+                        vec![short_name(&message.name).to_string()],
+                    ));
+                }
+            }
+            for proto_enum in &proto.enums {
+                if name_matches(&proto_enum.name, target) {
+                    elements.push(element(
+                        &proto_enum.name,
+                        "enum",
+                        vec![short_name(&proto_enum.name).to_string()],
+                    ));
+                }
+            }
+            for service in &proto.services {
+                if name_matches(&service.name, target) {
+                    // Generated clients are named after the service
+                    let mut names = vec![
+                        service.name.clone(),
+                        format!("{}Client", service.name),
+                        format!("{}Stub", service.name),
+                    ];
+                    names.extend(service.rpcs.iter().map(|r| r.name.clone()));
+                    elements.push(element(&service.name, "service", names));
+                }
+                for rpc in &service.rpcs {
+                    let qualified = format!("{}.{}", service.name, rpc.name);
+                    if rpc.name == target || qualified == target {
+                        elements.push(element(&qualified, "rpc", vec![rpc.name.clone()]));
+                    }
+                }
+            }
+        }
+        ContractDoc::OpenApi(api) => {
+            let wanted_path = target.trim_end_matches('/');
+            for path in &api.paths {
+                let operation_ids: Vec<String> = path
+                    .operations
+                    .iter()
+                    .filter_map(|op| op.operation_id.clone())
+                    .collect();
+                if target.starts_with('/') && path.path.trim_end_matches('/') == wanted_path {
+                    elements.push(element(&path.path, "path", operation_ids));
+                    continue;
+                }
+                for op in &path.operations {
+                    if op.operation_id.as_deref() == Some(target) {
+                        elements.push(element(
+                            &format!("{} {}", op.method, path.path),
+                            "operation",
+                            vec![target.to_string()],
+                        ));
+                    }
+                }
+            }
+            for schema in &api.schemas {
+                if schema.name == target {
+                    elements.push(element(&schema.name, "schema", vec![schema.name.clone()]));
+                }
+            }
+        }
+    }
+
+    elements
+}
+
+/// Match a possibly nested name (`Outer.Inner`) against a query
+fn name_matches(name: &str, target: &str) -> bool {
+    name == target || short_name(name) == target
+}
+
+fn short_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Callee name for a call graph entry: the symbol name for resolved hashes,
+/// the method name for external calls (`ext:pkg:client.getUser` -> `getUser`)
+fn callee_name(callee: &str, by_hash: &HashMap<&str, &SymbolIndexEntry>) -> String {
+    if let Some(entry) = by_hash.get(callee) {
+        return entry.symbol.clone();
+    }
+    let name = callee.strip_prefix("ext:").unwrap_or(callee);
+    let name = name.rsplit(':').next().unwrap_or(name);
+    let name = name.trim_start_matches("new ");
+    short_name(name).to_string()
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO_V1: &str = r#"
+syntax = "proto3";
+message User {
+  string id = 1;
+  string email = 2;
+}
+service UserService {
+  rpc GetUser(User) returns (User);
+}
+"#;
+
+    #[test]
+    fn test_breaking_changes_for_modified_and_deleted_proto() {
+        let v2 = PROTO_V1.replace("string email = 2;", "string email = 3;");
+        let changes = breaking_changes("api/user.proto", Some(PROTO_V1), Some(&v2));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].element, "User.email");
+        assert_eq!(changes[0].reason, "field renumbered (2 -> 3)");
+
+        let deleted = breaking_changes("api/user.proto", Some(PROTO_V1), None);
+        let elements: Vec<&str> = deleted.iter().map(|c| c.element.as_str()).collect();
+        assert_eq!(elements, vec!["User", "UserService"]);
+
+        assert!(breaking_changes("api/user.proto", None, Some(PROTO_V1)).is_empty());
+    }
+
+    #[test]
+    fn test_breaking_changes_ignores_plain_config() {
+        let changes = breaking_changes("config.yaml", Some("name: a\n"), Some("other: b\n"));
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_callee_name_and_normalization() {
+        let by_hash = HashMap::new();
+        assert_eq!(callee_name("ext:grpc:client.getUser", &by_hash), "getUser");
+        assert_eq!(
+            callee_name("ext:new GetUserRequest", &by_hash),
+            "GetUserRequest"
+        );
+        assert_eq!(normalize_name("get_user"), normalize_name("GetUser"));
+    }
+
+    #[test]
+    fn test_matching_elements_for_rpc_and_path() {
+        let doc = ContractDoc::Protobuf(protobuf::parse(PROTO_V1));
+        let rpc = matching_elements(&doc, "user.proto", "GetUser");
+        assert_eq!(rpc.len(), 1);
+        assert_eq!(rpc[0].name, "UserService.GetUser");
+
+        let api = "openapi: 3.0.0\npaths:\n  /users/{id}:\n    get:\n      operationId: getUser\n      responses:\n        '200':\n          description: ok\n";
+        let doc = ContractDoc::OpenApi(openapi::parse(api, Lang::Yaml).unwrap());
+        let path = matching_elements(&doc, "openapi.yaml", "/users/{id}/");
+        assert_eq!(path[0].kind, "path");
+        assert_eq!(path[0].consumer_names, vec!["getUser"]);
+    }
+}
@@ -5,13 +5,19 @@
 
 use tree_sitter::Tree;
 
-use crate::detectors::generic::extract_with_grammar;
+use crate::detectors::generic::extract_with_grammar_at_level;
 use crate::detectors::grammar::{CPP_GRAMMAR, C_GRAMMAR};
 use crate::error::Result;
+use crate::extract::ExtractionLevel;
 use crate::schema::SemanticSummary;
 
 /// Extract semantic information from a C/C++ source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // Select grammar based on file extension
     let is_cpp = summary.file.ends_with(".cpp")
         || summary.file.ends_with(".cc")
@@ -39,5 +45,5 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Resu
     // - Control flow: if, for, while, do, switch (+ for_range_loop, try in C++)
     // - Calls: call_expression
     // - Risk calculation
-    extract_with_grammar(summary, source, tree, grammar)
+    extract_with_grammar_at_level(summary, source, tree, grammar, level)
 }
@@ -1,12 +1,27 @@
 //! Config file detector (JSON, YAML, TOML)
+//!
+//! Every key becomes a symbol named by its dotted path (`database.url`), so
+//! `search` finds where a setting is defined. Arrays (including TOML
+//! `[[array tables]]`) are not descended into: their elements have no stable
+//! name.
+//!
+//! OpenAPI documents are handed to the `openapi` detector instead.
 
 use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
+use crate::detectors::openapi::{self, Value, ValueKind};
 use crate::error::Result;
 use crate::lang::Lang;
-use crate::schema::SemanticSummary;
+use crate::schema::{SemanticSummary, SymbolInfo, SymbolKind};
 use tree_sitter::{Node, Tree};
 
+/// Most keys indexed per file, so generated files (lock files) don't flood the index
+const MAX_CONFIG_KEYS: usize = 500;
+
 pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: Lang) -> Result<()> {
+    if openapi::is_openapi(source, tree, lang) {
+        return openapi::extract(summary, source, tree, lang);
+    }
+
     let root = tree.root_node();
 
     match lang {
@@ -16,6 +31,29 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: L
         _ => {}
     }
 
+    let mut keys = Vec::new();
+    match lang {
+        Lang::Json | Lang::Yaml => {
+            if let Some(document) = openapi::document_value(source, tree, lang) {
+                collect_document_keys(&document, "", &mut keys);
+            }
+        }
+        Lang::Toml => collect_toml_keys(&root, source, &mut keys),
+        _ => {}
+    }
+    keys.truncate(MAX_CONFIG_KEYS);
+    summary.symbols.extend(
+        keys.into_iter()
+            .map(|(name, start_line, end_line)| SymbolInfo {
+                name,
+                kind: SymbolKind::ConfigKey,
+                start_line,
+                end_line,
+                is_exported: true,
+                ..Default::default()
+            }),
+    );
+
     generate_insertions(summary);
     summary.extraction_complete = true;
     Ok(())
@@ -88,6 +126,106 @@ fn extract_toml_structure(summary: &mut SemanticSummary, source: &str, root: &No
     });
 }
 
+/// A config key: dotted path and line range
+type ConfigKey = (String, usize, usize);
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Keys of a JSON/YAML mapping and its nested mappings
+fn collect_document_keys(value: &Value, prefix: &str, keys: &mut Vec<ConfigKey>) {
+    let ValueKind::Map(entries) = &value.kind else {
+        return;
+    };
+    for (key, value) in entries {
+        if keys.len() >= MAX_CONFIG_KEYS {
+            return;
+        }
+        let path = join_key(prefix, key);
+        keys.push((path.clone(), value.start_line, value.end_line));
+        collect_document_keys(value, &path, keys);
+    }
+}
+
+/// Keys of a TOML document: tables, pairs and inline tables
+fn collect_toml_keys(root: &Node, source: &str, keys: &mut Vec<ConfigKey>) {
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        match child.kind() {
+            "pair" => collect_toml_pair(&child, source, "", keys),
+            "table" => {
+                let Some(header) = child.named_child(0) else {
+                    continue;
+                };
+                let mut prefix = String::new();
+                for part in toml_key_parts(&header, source) {
+                    prefix = join_key(&prefix, &part);
+                    // `[a.b]` also defines `a`, unless an earlier table did
+                    if !keys.iter().any(|(name, _, _)| *name == prefix) {
+                        keys.push((
+                            prefix.clone(),
+                            child.start_position().row + 1,
+                            child.end_position().row + 1,
+                        ));
+                    }
+                }
+                let mut pairs = child.walk();
+                for pair in child.named_children(&mut pairs) {
+                    if pair.kind() == "pair" {
+                        collect_toml_pair(&pair, source, &prefix, keys);
+                    }
+                }
+            }
+            // `[[name]]` elements are array items, which aren't indexed
+            _ => {}
+        }
+    }
+}
+
+fn collect_toml_pair(pair: &Node, source: &str, prefix: &str, keys: &mut Vec<ConfigKey>) {
+    let Some(key) = pair.named_child(0) else {
+        return;
+    };
+    let path = toml_key_parts(&key, source)
+        .iter()
+        .fold(prefix.to_string(), |path, part| join_key(&path, part));
+    keys.push((
+        path.clone(),
+        pair.start_position().row + 1,
+        pair.end_position().row + 1,
+    ));
+
+    if let Some(value) = pair.named_child(1).filter(|v| v.kind() == "inline_table") {
+        let mut cursor = value.walk();
+        for inner in value.named_children(&mut cursor) {
+            if inner.kind() == "pair" {
+                collect_toml_pair(&inner, source, &path, keys);
+            }
+        }
+    }
+}
+
+/// Segments of a bare, quoted or dotted TOML key
+fn toml_key_parts(key: &Node, source: &str) -> Vec<String> {
+    match key.kind() {
+        "dotted_key" => {
+            let mut cursor = key.walk();
+            key.named_children(&mut cursor)
+                .flat_map(|part| toml_key_parts(&part, source))
+                .collect()
+        }
+        "quoted_key" => vec![get_node_text(key, source)
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string()],
+        _ => vec![get_node_text(key, source)],
+    }
+}
+
 fn is_meaningful_key(key: &str) -> bool {
     matches!(
         key,
@@ -184,3 +322,118 @@ fn generate_insertions(summary: &mut SemanticSummary) {
             .push(format!("config with {}", key_summary));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn config_keys(file: &str, source: &str, lang: Lang) -> Vec<(String, usize, usize)> {
+        let mut parser = Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary {
+            file: file.to_string(),
+            ..Default::default()
+        };
+        extract(&mut summary, source, &tree, lang).unwrap();
+        summary
+            .symbols
+            .iter()
+            .inspect(|s| assert_eq!(s.kind, SymbolKind::ConfigKey))
+            .map(|s| (s.name.clone(), s.start_line, s.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_yaml_keys_use_dotted_paths() {
+        let source = "\
+app:
+  name: demo
+database:
+  url: postgres://localhost/app
+  pool:
+    max: 5
+servers:
+  - host: a.example.com
+";
+        let keys = config_keys("config.yaml", source, Lang::Yaml);
+        assert_eq!(
+            keys,
+            vec![
+                ("app".to_string(), 1, 2),
+                ("app.name".to_string(), 2, 2),
+                ("database".to_string(), 3, 6),
+                ("database.url".to_string(), 4, 4),
+                ("database.pool".to_string(), 5, 6),
+                ("database.pool.max".to_string(), 6, 6),
+                // Array items are skipped
+                ("servers".to_string(), 7, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toml_keys_use_dotted_paths() {
+        let source = r#"title = "demo"
+server.port = 8080
+
+[database]
+url = "postgres://localhost/app"
+pool = { max = 5 }
+
+[database.replica]
+"read.only" = true
+
+[[bin]]
+name = "cli"
+"#;
+        let keys = config_keys("settings.toml", source, Lang::Toml);
+        let names: Vec<&str> = keys.iter().map(|(name, _, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "title",
+                "server.port",
+                "database",
+                "database.url",
+                "database.pool",
+                "database.pool.max",
+                "database.replica",
+                "database.replica.read.only",
+            ]
+        );
+
+        let line = |name: &str| keys.iter().find(|(n, _, _)| n == name).unwrap().1;
+        assert_eq!(line("server.port"), 2);
+        assert_eq!(line("database"), 4);
+        assert_eq!(line("database.url"), 5);
+        assert_eq!(line("database.pool.max"), 6);
+        assert_eq!(line("database.replica"), 8);
+        assert_eq!(line("database.replica.read.only"), 9);
+    }
+
+    #[test]
+    fn test_json_keys_and_limit() {
+        let keys = config_keys(
+            "package.json",
+            "{\n  \"scripts\": {\n    \"build\": \"tsc\"\n  }\n}\n",
+            Lang::Json,
+        );
+        assert_eq!(
+            keys,
+            vec![
+                ("scripts".to_string(), 2, 4),
+                ("scripts.build".to_string(), 3, 3)
+            ]
+        );
+
+        let huge: String = (0..MAX_CONFIG_KEYS + 10)
+            .map(|i| format!("key{} = {}\n", i, i))
+            .collect();
+        assert_eq!(
+            config_keys("huge.toml", &huge, Lang::Toml).len(),
+            MAX_CONFIG_KEYS
+        );
+    }
+}
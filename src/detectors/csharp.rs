@@ -8,24 +8,459 @@
 //!
 //! - **Symbols**: class_declaration, struct_declaration, record_declaration,
 //!   interface_declaration, enum_declaration, method_declaration,
-//!   constructor_declaration, local_function_statement
+//!   constructor_declaration, local_function_statement, indexer_declaration,
+//!   operator_declaration, conversion_operator_declaration
 //! - **Imports**: using_directive
 //! - **State changes**: local_declaration_statement, field_declaration,
 //!   property_declaration, assignment_expression
 //! - **Control flow**: if, for, foreach, while, do, switch, switch_expression, try
-//! - **Calls**: invocation_expression
-//! - **Async**: await_expression
+//! - **Calls**: invocation_expression, object_creation_expression
+//! - **Async**: await_expression, `await foreach`, `await using`
+//!
+//! Records (`record`, `record class`, `record struct`) get the `type` symbol
+//! kind, with their members as props: the primary constructor parameters of
+//! a positional record, or the properties of a nominal one. The primary
+//! constructor parameters of a class or struct become its arguments.
+//!
+//! Each part of a `partial` type is its own symbol, linked to the others by
+//! `partial_of` (the namespace-qualified type name). Parts hash apart because
+//! `SymbolInfo::to_symbol_id` folds their file into the semantic hash.
 
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
-use crate::detectors::generic::extract_with_grammar;
-use crate::detectors::grammar::CSHARP_GRAMMAR;
+use crate::detectors::common::{get_node_text, visit_all};
+use crate::detectors::generic::{extract_call, extract_with_grammar_at_level};
+use crate::detectors::grammar::{csharp_is_exported, CSHARP_GRAMMAR};
 use crate::error::Result;
-use crate::schema::SemanticSummary;
+use crate::extract::ExtractionLevel;
+use crate::schema::{
+    Argument, ControlFlowChange, ControlFlowKind, Location, Prop, SemanticSummary, SymbolInfo,
+    SymbolKind,
+};
+
+/// Members the generic extractor doesn't see as symbols
+const MEMBER_KINDS: &[&str] = &[
+    "indexer_declaration",
+    "operator_declaration",
+    "conversion_operator_declaration",
+];
+
+/// Type declarations that can be `partial`
+const TYPE_KINDS: &[&str] = &[
+    "class_declaration",
+    "struct_declaration",
+    "interface_declaration",
+    "record_declaration",
+];
+
+/// Statements that a leading `await` token makes asynchronous
+const AWAIT_STATEMENT_KINDS: &[&str] = &[
+    "foreach_statement",
+    "using_statement",
+    "local_declaration_statement",
+];
 
 /// Extract semantic information from a C# source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
-    extract_with_grammar(summary, source, tree, &CSHARP_GRAMMAR)
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
+    extract_with_grammar_at_level(summary, source, tree, &CSHARP_GRAMMAR, level)?;
+    let root = tree.root_node();
+    apply_record_types(summary, &root, source);
+    apply_primary_constructors(summary, &root, source);
+    apply_partial_types(summary, &root, source);
+    extract_members(summary, &root, source, level);
+    if level == ExtractionLevel::Full {
+        apply_await_statements(summary, &root);
+    }
+    Ok(())
+}
+
+/// Give classes and structs their primary constructor parameters as arguments
+fn apply_primary_constructors(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if !matches!(node.kind(), "class_declaration" | "struct_declaration") {
+            return;
+        }
+        let mut cursor = node.walk();
+        let Some(params) = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "parameter_list")
+        else {
+            return;
+        };
+        let arguments = parameter_arguments(&params, source);
+        if let Some(symbol) = find_declared_symbol(&mut summary.symbols, node, source) {
+            symbol.arguments = arguments;
+        }
+    });
+}
+
+/// Link the parts of partial types through their qualified name
+fn apply_partial_types(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if !TYPE_KINDS.contains(&node.kind()) || !has_modifier(node, source, "partial") {
+            return;
+        }
+        let qualified_name = qualified_type_name(node, source);
+        if let Some(symbol) = find_declared_symbol(&mut summary.symbols, node, source) {
+            symbol.partial_of = qualified_name;
+        }
+    });
+}
+
+/// Add indexers and operators as function symbols
+///
+/// Their calls were attributed to the enclosing type, so they move over.
+fn extract_members(
+    summary: &mut SemanticSummary,
+    root: &Node,
+    source: &str,
+    level: ExtractionLevel,
+) {
+    visit_all(root, |node| {
+        if !MEMBER_KINDS.contains(&node.kind()) {
+            return;
+        }
+        let Some(name) = member_name(node, source) else {
+            return;
+        };
+        let start = node.start_position();
+        let end = node.end_position();
+        let lines = start.row + 1..=end.row + 1;
+
+        let mut calls = Vec::new();
+        if level == ExtractionLevel::Full {
+            let owner_node = std::iter::successors(node.parent(), |n| n.parent())
+                .find(|n| TYPE_KINDS.contains(&n.kind()));
+            let owner = owner_node
+                .and_then(|owner| find_declared_symbol(&mut summary.symbols, &owner, source));
+            if let Some(owner) = owner {
+                let (inside, outside) = std::mem::take(&mut owner.calls)
+                    .into_iter()
+                    .partition(|call| lines.contains(&call.location.line));
+                owner.calls = outside;
+                calls = inside;
+            }
+            // Calls the owner's deduplication folded into an earlier one
+            visit_all(node, |child| {
+                if !CSHARP_GRAMMAR.call_nodes.contains(&child.kind()) {
+                    return;
+                }
+                if let Some(call) = extract_call(child, source, &CSHARP_GRAMMAR) {
+                    if !calls
+                        .iter()
+                        .any(|c| c.name == call.name && c.is_awaited == call.is_awaited)
+                    {
+                        calls.push(call);
+                    }
+                }
+            });
+        }
+
+        let parameters = node.child_by_field_name("parameters");
+        summary.symbols.push(SymbolInfo {
+            name,
+            kind: SymbolKind::Function,
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            is_exported: csharp_is_exported(node, source),
+            arguments: parameters
+                .map(|p| parameter_arguments(&p, source))
+                .unwrap_or_default(),
+            return_type: node
+                .child_by_field_name("type")
+                .map(|t| get_node_text(&t, source)),
+            calls,
+            ..Default::default()
+        });
+    });
+}
+
+/// Symbol name of an indexer or operator: `this[]`, `operator +`,
+/// `implicit operator int`
+fn member_name(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "indexer_declaration" => Some("this[]".to_string()),
+        "operator_declaration" => {
+            let operator = node.child_by_field_name("operator")?;
+            Some(format!("operator {}", get_node_text(&operator, source)))
+        }
+        "conversion_operator_declaration" => {
+            let target = node.child_by_field_name("type")?;
+            let mut cursor = node.walk();
+            let conversion = node
+                .children(&mut cursor)
+                .find(|child| matches!(child.kind(), "implicit" | "explicit"))?;
+            Some(format!(
+                "{} operator {}",
+                conversion.kind(),
+                get_node_text(&target, source)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Record `await foreach` and `await using` as awaits
+///
+/// The calls producing the awaited collection or resource are awaited; the
+/// statement body is not.
+fn apply_await_statements(summary: &mut SemanticSummary, root: &Node) {
+    let mut awaited_calls: Vec<Location> = Vec::new();
+    visit_all(root, |node| {
+        if !is_await_statement(node) {
+            return;
+        }
+        let pos = node.start_position();
+        summary.control_flow_changes.push(ControlFlowChange {
+            kind: ControlFlowKind::Await,
+            location: Location::new(pos.row + 1, pos.column),
+            nesting_depth: nesting_depth(node),
+        });
+
+        let awaited = match node.kind() {
+            "foreach_statement" => node.child_by_field_name("right").into_iter().collect(),
+            "using_statement" => {
+                let body = node.child_by_field_name("body");
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor)
+                    .filter(|child| body.is_none_or(|b| child.id() != b.id()))
+                    .collect()
+            }
+            _ => vec![*node],
+        };
+        for part in awaited {
+            visit_all(&part, |child| {
+                if CSHARP_GRAMMAR.call_nodes.contains(&child.kind()) {
+                    let pos = child.start_position();
+                    awaited_calls.push(Location::new(pos.row + 1, pos.column));
+                }
+            });
+        }
+    });
+    if awaited_calls.is_empty() {
+        return;
+    }
+
+    summary
+        .control_flow_changes
+        .sort_by_key(|cf| (cf.location.line, cf.location.column));
+    let calls = summary
+        .symbols
+        .iter_mut()
+        .flat_map(|symbol| symbol.calls.iter_mut())
+        .chain(summary.calls.iter_mut());
+    for call in calls {
+        if awaited_calls.contains(&call.location) {
+            call.is_awaited = true;
+        }
+    }
+}
+
+/// Whether a node is an `await foreach` or `await using` statement
+pub(crate) fn is_await_statement(node: &Node) -> bool {
+    if !AWAIT_STATEMENT_KINDS.contains(&node.kind()) {
+        return false;
+    }
+    let mut cursor = node.walk();
+    let has_await = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "await");
+    has_await
+}
+
+/// Number of control flow structures around `node`
+fn nesting_depth(node: &Node) -> usize {
+    let mut depth = 0;
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        let kind = parent.kind();
+        if CSHARP_GRAMMAR.control_flow_nodes.contains(&kind)
+            || CSHARP_GRAMMAR.try_nodes.contains(&kind)
+        {
+            depth += 1;
+        }
+        current = parent.parent();
+    }
+    depth
+}
+
+/// Namespace-qualified name of a type declaration, e.g. `Shop.Cart.Line`
+fn qualified_type_name(node: &Node, source: &str) -> Option<String> {
+    let mut parts = vec![get_node_text(&node.child_by_field_name("name")?, source)];
+    let mut in_namespace = false;
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        let kind = parent.kind();
+        if TYPE_KINDS.contains(&kind) || kind.ends_with("namespace_declaration") {
+            in_namespace |= kind.ends_with("namespace_declaration");
+            if let Some(name) = parent.child_by_field_name("name") {
+                parts.push(get_node_text(&name, source));
+            }
+        }
+        if parent.parent().is_none() && !in_namespace {
+            // A file-scoped namespace is a sibling of the types it contains
+            let mut cursor = parent.walk();
+            let namespace = parent
+                .children(&mut cursor)
+                .find(|child| child.kind() == "file_scoped_namespace_declaration")
+                .and_then(|ns| ns.child_by_field_name("name"));
+            if let Some(name) = namespace {
+                parts.push(get_node_text(&name, source));
+            }
+        }
+        current = parent.parent();
+    }
+    parts.reverse();
+    Some(parts.join("."))
+}
+
+/// Whether a declaration has the given modifier keyword
+fn has_modifier(node: &Node, source: &str, modifier: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "modifier" && get_node_text(&child, source) == modifier);
+    found
+}
+
+/// The symbol the generic extractor created for a type declaration
+fn find_declared_symbol<'a>(
+    symbols: &'a mut [SymbolInfo],
+    node: &Node,
+    source: &str,
+) -> Option<&'a mut SymbolInfo> {
+    let name = get_node_text(&node.child_by_field_name("name")?, source);
+    let line = node.start_position().row + 1;
+    symbols
+        .iter_mut()
+        .find(|symbol| symbol.name == name && symbol.start_line == line)
+}
+
+/// Arguments from a parameter list (`(...)` or an indexer's `[...]`)
+fn parameter_arguments(list: &Node, source: &str) -> Vec<Argument> {
+    let mut cursor = list.walk();
+    list.named_children(&mut cursor)
+        .filter(|param| param.kind() == "parameter")
+        .filter_map(|param| parameter_prop(&param, source))
+        .map(|prop| Argument {
+            name: prop.name,
+            arg_type: prop.prop_type,
+            default_value: prop.default_value,
+        })
+        .collect()
+}
+
+/// Give record declarations the `type` kind and their members as props
+///
+/// The generic extractor treats a record as a class without properties.
+fn apply_record_types(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if node.kind() != "record_declaration" {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = get_node_text(&name, source);
+        let line = node.start_position().row + 1;
+        let props = record_props(node, source);
+
+        for symbol in &mut summary.symbols {
+            if symbol.name == name && symbol.start_line == line {
+                symbol.kind = SymbolKind::Type;
+                symbol.props = props.clone();
+            }
+        }
+        if summary.symbol.as_deref() == Some(name.as_str()) && summary.start_line == Some(line) {
+            summary.symbol_kind = Some(SymbolKind::Type);
+            summary.props = props;
+        }
+    });
+}
+
+/// Members of a record: primary constructor parameters, then body properties
+fn record_props(record: &Node, source: &str) -> Vec<Prop> {
+    let mut props = Vec::new();
+    let mut cursor = record.walk();
+    for child in record.children(&mut cursor) {
+        if child.kind() == "parameter_list" {
+            let mut params = child.walk();
+            for param in child.named_children(&mut params) {
+                if param.kind() == "parameter" {
+                    props.extend(parameter_prop(&param, source));
+                }
+            }
+        }
+    }
+
+    if let Some(body) = record.child_by_field_name("body") {
+        let mut members = body.walk();
+        for member in body.named_children(&mut members) {
+            if member.kind() == "property_declaration" {
+                props.extend(property_prop(&member, source));
+            }
+        }
+    }
+    props
+}
+
+/// A parameter as a prop; it is required unless it has a default
+fn parameter_prop(param: &Node, source: &str) -> Option<Prop> {
+    let name = param.child_by_field_name("name")?;
+    let prop_type = param.child_by_field_name("type");
+    // The default value is the one named child that isn't the type, name or an attribute
+    let mut cursor = param.walk();
+    let default_value = param
+        .named_children(&mut cursor)
+        .find(|child| {
+            child.id() != name.id()
+                && prop_type.is_none_or(|t| child.id() != t.id())
+                && !matches!(child.kind(), "attribute_list" | "modifier")
+        })
+        .map(|value| get_node_text(&value, source));
+
+    Some(Prop {
+        name: get_node_text(&name, source),
+        prop_type: prop_type.map(|t| get_node_text(&t, source)),
+        required: default_value.is_none(),
+        default_value,
+    })
+}
+
+/// A property of a nominal record; only `required` properties must be set
+///
+/// Static and expression-bodied (`=> ...`) properties are computed rather
+/// than stored, so they are not members.
+fn property_prop(property: &Node, source: &str) -> Option<Prop> {
+    let name = property.child_by_field_name("name")?;
+    let mut required = false;
+    let mut cursor = property.walk();
+    for child in property.children(&mut cursor) {
+        match child.kind() {
+            "arrow_expression_clause" => return None,
+            "modifier" => match get_node_text(&child, source).as_str() {
+                "static" => return None,
+                "required" => required = true,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    Some(Prop {
+        name: get_node_text(&name, source),
+        prop_type: property
+            .child_by_field_name("type")
+            .map(|t| get_node_text(&t, source)),
+        default_value: property
+            .child_by_field_name("value")
+            .map(|v| get_node_text(&v, source)),
+        required,
+    })
 }
 
 #[cfg(test)]
@@ -72,7 +507,7 @@ namespace MyApp
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         // Should detect the class and methods
         assert!(!summary.symbols.is_empty(), "Should detect symbols");
@@ -102,7 +537,7 @@ public class ApiService
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         // Should detect symbols
         assert!(!summary.symbols.is_empty(), "Should detect async method");
@@ -128,10 +563,78 @@ namespace MyApp.Models
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+
+        let symbol = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
+
+        // Positional record: primary constructor parameters become props
+        let user = symbol("User");
+        assert_eq!(user.kind, SymbolKind::Type);
+        let props: Vec<_> = user
+            .props
+            .iter()
+            .map(|p| (p.name.as_str(), p.prop_type.as_deref(), p.required))
+            .collect();
+        assert_eq!(
+            props,
+            vec![("Name", Some("string"), true), ("Age", Some("int"), true)]
+        );
+
+        // Nominal record: properties become props
+        let address = symbol("Address");
+        assert_eq!(address.kind, SymbolKind::Type);
+        let names: Vec<_> = address.props.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Street", "City"]);
+    }
+
+    #[test]
+    fn test_csharp_record_defaults_and_computed_members() {
+        let source = r#"
+public readonly record struct Point(double X, double Y = 0);
+
+public record Config
+{
+    public required string Host { get; init; }
+    public int Port { get; init; } = 8080;
+    public string Url => $"{Host}:{Port}";
+    public static Config Default { get; } = new() { Host = "localhost" };
+}
+
+public class NotARecord
+{
+    public string Name { get; set; }
+}
+"#;
+        let tree = parse_source(source);
+        let mut summary = SemanticSummary {
+            file: "/test/Config.cs".to_string(),
+            ..Default::default()
+        };
+
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+
+        let symbol = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
 
-        // Should detect record types
-        assert!(!summary.symbols.is_empty(), "Should detect record types");
+        let point = symbol("Point");
+        assert_eq!(point.kind, SymbolKind::Type);
+        assert!(point.props[0].required);
+        assert!(!point.props[1].required);
+        assert_eq!(point.props[1].default_value.as_deref(), Some("0"));
+
+        let config = symbol("Config");
+        let props: Vec<_> = config
+            .props
+            .iter()
+            .map(|p| (p.name.as_str(), p.required, p.default_value.as_deref()))
+            .collect();
+        assert_eq!(
+            props,
+            vec![("Host", true, None), ("Port", false, Some("8080"))]
+        );
+
+        let class = symbol("NotARecord");
+        assert_eq!(class.kind, SymbolKind::Class);
+        assert!(class.props.is_empty());
     }
 
     #[test]
@@ -172,7 +675,7 @@ public class Calculator
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         // Should detect control flow
         assert!(
@@ -223,7 +726,7 @@ public class TestController : Controller
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         println!("\n=== Extracted Symbols ===");
         for sym in &summary.symbols {
@@ -263,7 +766,7 @@ public class Matcher
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         // Should detect switch expression
         assert!(
@@ -292,7 +795,7 @@ public class QuestMachine
             ..Default::default()
         };
 
-        extract(&mut summary, source, &tree).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
 
         // Find the RegisterActionTemplates method
         let method = summary
@@ -330,4 +833,201 @@ public class QuestMachine
             call_names
         );
     }
+
+    /// .NET 8 syntax the generic extractor misses
+    const MODERN_SOURCE: &str = r#"
+namespace Shop;
+
+public partial class Cart(IPricing pricing, int capacity = 10)
+{
+    public int Total => Items.Sum(i => pricing.Price(i));
+
+    public string this[int index] => Items[index].Describe();
+
+    public static Cart operator +(Cart a, Cart b) => Merge(a, b);
+
+    public static implicit operator int(Cart c) => c.Count();
+
+    public string Label() => Format(Total);
+
+    public async Task DrainAsync(IAsyncEnumerable<Item> feed)
+    {
+        await foreach (var item in feed.WithCancellation(Token()))
+        {
+            Process(item);
+        }
+        await using var conn = OpenConnection();
+        if (conn.IsOpen)
+        {
+            await using (var tx = conn.BeginTransaction())
+            {
+                Commit(tx);
+            }
+        }
+    }
+}
+"#;
+
+    fn extract_modern(file: &str, source: &str) -> SemanticSummary {
+        let tree = parse_source(source);
+        let mut summary = SemanticSummary {
+            file: file.to_string(),
+            ..Default::default()
+        };
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary
+    }
+
+    fn call_names(symbol: &SymbolInfo) -> Vec<&str> {
+        symbol.calls.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_csharp_expression_bodied_members() {
+        let summary = extract_modern("/src/Shop/Cart.cs", MODERN_SOURCE);
+        let symbol = |name: &str| {
+            summary
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("symbol {name} not extracted"))
+        };
+
+        assert!(call_names(symbol("Total")).contains(&"Sum"));
+        assert!(call_names(symbol("Label")).contains(&"Format"));
+
+        let indexer = symbol("this[]");
+        assert_eq!(indexer.kind, SymbolKind::Function);
+        assert!(indexer.is_exported);
+        assert_eq!(indexer.return_type.as_deref(), Some("string"));
+        assert_eq!(indexer.arguments[0].name, "index");
+        assert_eq!(call_names(indexer), vec!["Describe"]);
+
+        let plus = symbol("operator +");
+        let params: Vec<_> = plus.arguments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(params, vec!["a", "b"]);
+        assert!(call_names(plus).contains(&"Merge"));
+
+        let conversion = symbol("implicit operator int");
+        assert_eq!(conversion.return_type.as_deref(), Some("int"));
+        assert_eq!(call_names(conversion), vec!["Count"]);
+
+        // The members' calls no longer count as the class's
+        let class = symbol("Cart");
+        assert!(!call_names(class).contains(&"Merge"));
+        assert!(!call_names(class).contains(&"Describe"));
+    }
+
+    #[test]
+    fn test_csharp_primary_constructor_arguments() {
+        let source = r#"
+public class Cart(IPricing pricing, int capacity = 10) { }
+
+public struct Money(decimal amount) { }
+
+public class Plain { }
+"#;
+        let summary = extract_modern("/src/Cart.cs", source);
+        let symbol = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
+
+        let args: Vec<_> = symbol("Cart")
+            .arguments
+            .iter()
+            .map(|a| {
+                (
+                    a.name.as_str(),
+                    a.arg_type.as_deref(),
+                    a.default_value.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                ("pricing", Some("IPricing"), None),
+                ("capacity", Some("int"), Some("10"))
+            ]
+        );
+        assert_eq!(symbol("Money").arguments.len(), 1);
+        assert!(symbol("Plain").arguments.is_empty());
+    }
+
+    #[test]
+    fn test_csharp_await_foreach_and_using() {
+        let summary = extract_modern("/src/Shop/Cart.cs", MODERN_SOURCE);
+
+        let awaits: Vec<_> = summary
+            .control_flow_changes
+            .iter()
+            .filter(|cf| cf.kind == ControlFlowKind::Await)
+            .map(|cf| (cf.location.line, cf.nesting_depth))
+            .collect();
+        assert_eq!(awaits, vec![(18, 0), (22, 0), (25, 1)]);
+
+        let drain = summary
+            .symbols
+            .iter()
+            .find(|s| s.name == "DrainAsync")
+            .unwrap();
+        let awaited = |name: &str| {
+            drain
+                .calls
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("call {name} not extracted"))
+                .is_awaited
+        };
+        // The collection and resources are awaited, the bodies are not
+        assert!(awaited("WithCancellation"));
+        assert!(awaited("Token"));
+        assert!(awaited("OpenConnection"));
+        assert!(awaited("BeginTransaction"));
+        assert!(!awaited("Process"));
+        assert!(!awaited("Commit"));
+    }
+
+    #[test]
+    fn test_csharp_partial_types_are_distinct_but_linked() {
+        let main = extract_modern("src/Shop/Cart.cs", MODERN_SOURCE);
+        let audit_source = r#"
+namespace Shop
+{
+    public partial class Cart
+    {
+        public void Audit() => Log();
+
+        private partial class Line { }
+    }
+
+    public class Receipt { }
+}
+"#;
+        let audit = extract_modern("src/Shop/Cart.Audit.cs", audit_source);
+        let symbol = |summary: &SemanticSummary, name: &str| {
+            summary
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .cloned()
+                .unwrap()
+        };
+
+        let part_a = symbol(&main, "Cart");
+        let part_b = symbol(&audit, "Cart");
+        assert_eq!(part_a.partial_of.as_deref(), Some("Shop.Cart"));
+        assert_eq!(part_b.partial_of.as_deref(), Some("Shop.Cart"));
+        assert_eq!(
+            symbol(&audit, "Line").partial_of.as_deref(),
+            Some("Shop.Cart.Line")
+        );
+        assert_eq!(symbol(&audit, "Receipt").partial_of, None);
+
+        // Same namespace, name and kind, but each part keeps its own hashes
+        let id_a = part_a.to_symbol_id("Shop", "src/Shop/Cart.cs");
+        let mut part_b = part_b;
+        part_b.arguments = part_a.arguments.clone();
+        let id_b = part_b.to_symbol_id("Shop", "src/Shop/Cart.Audit.cs");
+        assert_ne!(id_a.semantic_hash, id_b.semantic_hash);
+        assert_ne!(id_a.hash, id_b.hash);
+    }
 }
@@ -0,0 +1,424 @@
+//! Deprecation marker detection
+//!
+//! Flags symbols marked deprecated (with the marker's note, if any) so planned
+//! removals can be tracked with `search --deprecated` and new calls into them
+//! reported by `analyze --diff`. Runs after the language detector, once
+//! symbols and their line ranges are known.
+//!
+//! # Recognized Patterns
+//!
+//! - **Rust**: `#[deprecated]`, `#[deprecated = "note"]` and
+//!   `#[deprecated(since = "1.2", note = "note")]`
+//! - **JavaScript/TypeScript**: a `/** ... @deprecated note */` doc comment
+//! - **Java**: `@Deprecated`, with the note taken from a Javadoc `@deprecated` tag
+//! - **Python**: `warnings.warn("note", DeprecationWarning)` inside a body
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::detectors::common::{find_containing_symbol_by_line, get_node_text, visit_all};
+use crate::lang::{Lang, LangFamily};
+use crate::schema::SemanticSummary;
+
+/// `#[deprecated]` in any of its forms
+static RUST_DEPRECATED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#\[\s*deprecated\b").expect("valid regex"));
+
+/// Note of a Rust `#[deprecated]` attribute (`= "..."` or `note = "..."`)
+static RUST_NOTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:^#\[\s*deprecated|\bnote)\s*=\s*"((?:[^"\\]|\\.)*)""#).expect("valid regex")
+});
+
+/// A deprecation marker on the declaration starting at `line`
+struct Marker {
+    /// First line of the attributes/comments attached to the declaration
+    first_line: usize,
+    /// Line the declaration itself starts on (`None` = the symbol containing
+    /// the marker, as for Python's runtime warnings)
+    item_line: Option<usize>,
+    message: Option<String>,
+}
+
+/// Flag the symbols carrying a deprecation marker
+pub fn extract(summary: &mut SemanticSummary, root: &Node, source: &str, lang: Lang) {
+    let markers = match lang.family() {
+        LangFamily::Rust => rust_markers(root, source),
+        LangFamily::JavaScript => doc_comment_markers(root, source, "comment"),
+        LangFamily::Java => {
+            let mut markers = java_annotation_markers(root, source);
+            markers.extend(doc_comment_markers(root, source, "block_comment"));
+            markers
+        }
+        LangFamily::Python => python_markers(root, source),
+        _ => return,
+    };
+
+    for marker in markers {
+        let idx = match marker.item_line {
+            // Only the declaration the marker is attached to, never the
+            // symbol around it (e.g. a deprecated const inside a module)
+            Some(line) => find_containing_symbol_by_line(line, &summary.symbols)
+                .filter(|&idx| summary.symbols[idx].start_line >= marker.first_line),
+            None => find_containing_symbol_by_line(marker.first_line, &summary.symbols),
+        };
+        let Some(idx) = idx else {
+            continue;
+        };
+        let symbol = &mut summary.symbols[idx];
+        symbol.is_deprecated = true;
+        if symbol.deprecation_message.is_none() {
+            symbol.deprecation_message = marker.message;
+        }
+    }
+}
+
+/// Note of a Rust `#[deprecated]` attribute, if it has one
+pub fn rust_note(attribute: &str) -> Option<String> {
+    RUST_NOTE
+        .captures(attribute)
+        .map(|c| c[1].replace("\\\"", "\""))
+        .filter(|note| !note.is_empty())
+}
+
+/// Note following the `@deprecated` tag of a JSDoc/Javadoc comment.
+///
+/// Returns `None` when the comment has no `@deprecated` tag, and
+/// `Some(None)` when the tag carries no note.
+pub fn doc_comment_note(comment: &str) -> Option<Option<String>> {
+    let body = comment
+        .trim()
+        .trim_start_matches("/**")
+        .trim_end_matches("*/");
+    let mut lines = body
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*').trim());
+
+    let first = lines.by_ref().find_map(|line| {
+        let (_, rest) = line.split_once("@deprecated")?;
+        // `@deprecatedSince` and friends are other tags
+        (!rest.starts_with(|c: char| c.is_alphanumeric())).then_some(rest)
+    })?;
+    let mut note = vec![first.trim()];
+    // The note runs until a blank line or the next tag
+    note.extend(lines.take_while(|line| !line.is_empty() && !line.starts_with('@')));
+
+    let note = note
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((!note.is_empty()).then_some(note))
+}
+
+fn rust_markers(root: &Node, source: &str) -> Vec<Marker> {
+    let mut markers = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "attribute_item" {
+            return;
+        }
+        let text = get_node_text(node, source);
+        if !RUST_DEPRECATED.is_match(&text) {
+            return;
+        }
+
+        // Attributes and doc comments stacked above the item belong to it
+        let mut first = *node;
+        while let Some(prev) = first.prev_named_sibling().filter(|n| {
+            matches!(
+                n.kind(),
+                "attribute_item" | "line_comment" | "block_comment"
+            )
+        }) {
+            first = prev;
+        }
+        let mut item = node.next_named_sibling();
+        while let Some(n) = item.filter(|n| {
+            matches!(
+                n.kind(),
+                "attribute_item" | "line_comment" | "block_comment"
+            )
+        }) {
+            item = n.next_named_sibling();
+        }
+        let Some(item) = item else {
+            return;
+        };
+
+        markers.push(Marker {
+            first_line: first.start_position().row + 1,
+            item_line: Some(item.start_position().row + 1),
+            message: rust_note(&text),
+        });
+    });
+
+    markers
+}
+
+/// `/** @deprecated */` comments (node kind `comment_kind`) on a declaration
+fn doc_comment_markers(root: &Node, source: &str, comment_kind: &str) -> Vec<Marker> {
+    let mut markers = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != comment_kind {
+            return;
+        }
+        let text = get_node_text(node, source);
+        if !text.starts_with("/**") {
+            return;
+        }
+        let Some(message) = doc_comment_note(&text) else {
+            return;
+        };
+
+        let mut item = node.next_named_sibling();
+        while let Some(n) = item.filter(|n| n.kind() == comment_kind) {
+            item = n.next_named_sibling();
+        }
+        let Some(item) = item else {
+            return;
+        };
+
+        markers.push(Marker {
+            first_line: node.start_position().row + 1,
+            item_line: Some(item.start_position().row + 1),
+            message,
+        });
+    });
+
+    markers
+}
+
+/// `@Deprecated` annotations; their notes live in the Javadoc
+fn java_annotation_markers(root: &Node, source: &str) -> Vec<Marker> {
+    let mut markers = Vec::new();
+
+    visit_all(root, |node| {
+        if !matches!(node.kind(), "marker_annotation" | "annotation") {
+            return;
+        }
+        let is_deprecated = node.child_by_field_name("name").is_some_and(|name| {
+            matches!(
+                get_node_text(&name, source).as_str(),
+                "Deprecated" | "java.lang.Deprecated"
+            )
+        });
+        if !is_deprecated {
+            return;
+        }
+        // annotation -> modifiers -> declaration
+        let Some(declaration) = node
+            .parent()
+            .filter(|p| p.kind() == "modifiers")
+            .and_then(|m| m.parent())
+        else {
+            return;
+        };
+
+        markers.push(Marker {
+            first_line: declaration.start_position().row + 1,
+            item_line: Some(declaration.start_position().row + 1),
+            message: None,
+        });
+    });
+
+    markers
+}
+
+/// `warnings.warn(..., DeprecationWarning)` calls, attributed to their body
+fn python_markers(root: &Node, source: &str) -> Vec<Marker> {
+    let mut markers = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "call" {
+            return;
+        }
+        let is_warn = node.child_by_field_name("function").is_some_and(|f| {
+            matches!(get_node_text(&f, source).as_str(), "warnings.warn" | "warn")
+        });
+        let Some(args) = node.child_by_field_name("arguments").filter(|_| is_warn) else {
+            return;
+        };
+
+        let mut cursor = args.walk();
+        let args: Vec<Node> = args.named_children(&mut cursor).collect();
+        let is_deprecation = |n: &Node| get_node_text(n, source).ends_with("DeprecationWarning");
+        let category = args.iter().any(|arg| match arg.kind() {
+            "keyword_argument" => {
+                arg.child_by_field_name("name")
+                    .is_some_and(|n| get_node_text(&n, source) == "category")
+                    && arg
+                        .child_by_field_name("value")
+                        .is_some_and(|v| is_deprecation(&v))
+            }
+            "identifier" | "attribute" => is_deprecation(arg),
+            _ => false,
+        });
+        if !category {
+            return;
+        }
+
+        let message = args
+            .first()
+            .filter(|arg| arg.kind() == "string")
+            .map(|arg| python_string(&get_node_text(arg, source)))
+            .filter(|note| !note.is_empty());
+        markers.push(Marker {
+            first_line: node.start_position().row + 1,
+            item_line: None,
+            message,
+        });
+    });
+
+    markers
+}
+
+/// Contents of a Python string literal, without prefix or quotes
+fn python_string(literal: &str) -> String {
+    literal
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .trim_matches(|c| c == '"' || c == '\'')
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn summarize(source: &str, lang: Lang, path: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn deprecation_of(summary: &SemanticSummary, name: &str) -> (bool, Option<String>) {
+        let symbol = summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("symbol {name} not extracted"));
+        (symbol.is_deprecated, symbol.deprecation_message.clone())
+    }
+
+    #[test]
+    fn test_rust_deprecated_attribute() {
+        let source = r#"
+/// Saves the record.
+#[deprecated(since = "0.4.0", note = "use save_v2 instead")]
+pub fn save() -> u32 {
+    1
+}
+
+#[deprecated]
+pub fn legacy() -> u32 {
+    2
+}
+
+pub fn save_v2() -> u32 {
+    3
+}
+"#;
+        let summary = summarize(source, Lang::Rust, "/src/lib.rs");
+        assert_eq!(
+            deprecation_of(&summary, "save"),
+            (true, Some("use save_v2 instead".to_string()))
+        );
+        assert_eq!(deprecation_of(&summary, "legacy"), (true, None));
+        assert_eq!(deprecation_of(&summary, "save_v2"), (false, None));
+    }
+
+    #[test]
+    fn test_js_deprecated_jsdoc() {
+        let source = r#"
+/**
+ * Formats a date.
+ * @deprecated Use formatDateTime instead.
+ * @param {Date} d
+ */
+export function formatDate(d) {
+  return d.toISOString();
+}
+
+/** Formats a date and time. */
+export function formatDateTime(d) {
+  return d.toISOString();
+}
+"#;
+        let summary = summarize(source, Lang::JavaScript, "/src/format.js");
+        assert_eq!(
+            deprecation_of(&summary, "formatDate"),
+            (true, Some("Use formatDateTime instead.".to_string()))
+        );
+        assert_eq!(deprecation_of(&summary, "formatDateTime"), (false, None));
+    }
+
+    #[test]
+    fn test_java_deprecated_annotation() {
+        let source = r#"
+public class Store {
+    /**
+     * @deprecated use {@link #saveAll} instead
+     */
+    @Deprecated
+    public void save() {}
+
+    public void saveAll() {}
+}
+"#;
+        let summary = summarize(source, Lang::Java, "/src/Store.java");
+        assert_eq!(
+            deprecation_of(&summary, "save"),
+            (true, Some("use {@link #saveAll} instead".to_string()))
+        );
+        assert_eq!(deprecation_of(&summary, "saveAll"), (false, None));
+    }
+
+    #[test]
+    fn test_python_deprecation_warning() {
+        let source = r#"
+import warnings
+
+def old_api():
+    warnings.warn("old_api is going away", DeprecationWarning, stacklevel=2)
+    return new_api()
+
+def new_api():
+    warnings.warn("slow path", RuntimeWarning)
+    return 1
+"#;
+        let summary = summarize(source, Lang::Python, "/src/api.py");
+        assert_eq!(
+            deprecation_of(&summary, "old_api"),
+            (true, Some("old_api is going away".to_string()))
+        );
+        assert_eq!(deprecation_of(&summary, "new_api"), (false, None));
+    }
+
+    #[test]
+    fn test_doc_comment_note() {
+        assert_eq!(doc_comment_note("/** Plain docs. */"), None);
+        assert_eq!(doc_comment_note("/** @deprecated */"), Some(None));
+        assert_eq!(
+            doc_comment_note("/**\n * @deprecated since 2.0,\n *   use bar\n * @see bar\n */"),
+            Some(Some("since 2.0, use bar".to_string()))
+        );
+        assert_eq!(doc_comment_note("/** @deprecatedSince 2.0 */"), None);
+    }
+
+    #[test]
+    fn test_rust_note() {
+        assert_eq!(
+            rust_note(r#"#[deprecated = "gone"]"#),
+            Some("gone".to_string())
+        );
+        assert_eq!(
+            rust_note(r#"#[deprecated(since = "1.0", note = "use \"b\"")]"#),
+            Some("use \"b\"".to_string())
+        );
+        assert_eq!(rust_note("#[deprecated]"), None);
+    }
+}
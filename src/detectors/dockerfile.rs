@@ -113,6 +113,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Res
                             name: pair.clone(),
                             state_type: "env".to_string(),
                             initializer: String::new(),
+                            is_atomic: false,
                         });
 
                         // Security check for secrets
@@ -143,6 +144,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Res
                         name: args.to_string(),
                         state_type: "arg".to_string(),
                         initializer: String::new(),
+                        is_atomic: false,
                     });
 
                     summary.symbols.push(SymbolInfo {
@@ -176,6 +178,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Res
                             name: format!("port:{}", port),
                             state_type: "expose".to_string(),
                             initializer: String::new(),
+                            is_atomic: false,
                         });
                     }
 
@@ -196,6 +199,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Res
                         name: format!("user:{}", user),
                         state_type: "user".to_string(),
                         initializer: String::new(),
+                        is_atomic: false,
                     });
 
                     summary.symbols.push(SymbolInfo {
@@ -245,6 +249,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Res
                         name: format!("workdir:{}", args),
                         state_type: "workdir".to_string(),
                         initializer: String::new(),
+                        is_atomic: false,
                     });
 
                     summary.symbols.push(SymbolInfo {
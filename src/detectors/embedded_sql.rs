@@ -0,0 +1,213 @@
+//! Embedded SQL detection
+//!
+//! Records SQL statements written as string literals so that data access can
+//! be reviewed with `search --sql <table>`. Runs after the language detector,
+//! once symbols and their line ranges are known.
+//!
+//! # Heuristics
+//!
+//! A string literal is a query when, case-insensitively, it starts with
+//! `SELECT`, `INSERT`, `UPDATE`, `DELETE` or `CREATE` and has the clause that
+//! statement needs (`FROM`, `INTO`, `SET`, `FROM`, or an object keyword such
+//! as `TABLE`), which keeps UI strings like "Select a file" out. Tables are
+//! the names following `FROM`, `JOIN`, `INTO`, `UPDATE` and `TABLE`; queries
+//! assembled by concatenation only contribute their literal parts.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::detectors::common::{
+    find_containing_symbol_by_line, get_node_text, normalize_whitespace, truncate_to_char_boundary,
+    visit_all,
+};
+use crate::lang::{Lang, LangFamily};
+use crate::schema::{EmbeddedQuery, Location, SemanticSummary};
+
+/// String literal node kinds across the supported grammars
+const STRING_KINDS: &[&str] = &[
+    "string",
+    "string_literal",
+    "raw_string_literal",
+    "template_string",
+    "interpreted_string_literal",
+    "verbatim_string_literal",
+    "interpolated_string_expression",
+    "text_block",
+];
+
+/// Longest query text kept for display
+const MAX_QUERY_LEN: usize = 200;
+
+/// Leading statement keyword and the clause it needs to count as SQL
+static STATEMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)^(?:select\s.*\bfrom\b|insert\s+(?:or\s+\w+\s+)?into\b|update\s.*\bset\b|delete\s+from\b|create\s+(?:or\s+replace\s+)?(?:temp(?:orary)?\s+|unique\s+)?(?:table|index|view)\b)",
+    )
+    .expect("valid regex")
+});
+
+/// A table name after a keyword that introduces one
+static TABLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?i)\b(?:from|join|into|update|table(?:\s+if\s+not\s+exists)?)\s+([`"\[]?[a-z_][\w$]*[`"\]]?(?:\.[`"\[]?[a-z_][\w$]*[`"\]]?)*)"#,
+    )
+    .expect("valid regex")
+});
+
+/// Attach embedded SQL queries to the symbols containing them
+pub fn extract(summary: &mut SemanticSummary, root: &Node, source: &str, lang: Lang) {
+    if matches!(
+        lang.family(),
+        LangFamily::Markup
+            | LangFamily::Config
+            | LangFamily::Hcl
+            | LangFamily::Dockerfile
+            | LangFamily::Protobuf
+            | LangFamily::GraphQL
+    ) {
+        return;
+    }
+    let symbols = &mut summary.symbols;
+
+    visit_all(root, |node| {
+        if !STRING_KINDS.contains(&node.kind())
+            || node
+                .parent()
+                .is_some_and(|p| STRING_KINDS.contains(&p.kind()))
+        {
+            return;
+        }
+        let Some(query) = parse_query(&get_node_text(node, source)) else {
+            return;
+        };
+        let pos = node.start_position();
+        if let Some(idx) = find_containing_symbol_by_line(pos.row + 1, symbols) {
+            symbols[idx].embedded_queries.push(EmbeddedQuery {
+                location: Location::new(pos.row + 1, pos.column),
+                ..query
+            });
+        }
+    });
+}
+
+/// Parse a string literal (with its quotes) as a query, if it is one
+fn parse_query(literal: &str) -> Option<EmbeddedQuery> {
+    // Prefixes such as r#", f", @" and $" come before the first quote
+    let start = literal.find(['"', '\'', '`'])?;
+    let text = normalize_whitespace(
+        literal[start..].trim_matches(|c| matches!(c, '"' | '\'' | '`' | '#')),
+    );
+    if !STATEMENT.is_match(&text) {
+        return None;
+    }
+
+    let mut tables: Vec<String> = Vec::new();
+    for capture in TABLE.captures_iter(&text) {
+        let table = capture[1].replace(['`', '"', '[', ']'], "");
+        if !tables.iter().any(|t| t.eq_ignore_ascii_case(&table)) {
+            tables.push(table);
+        }
+    }
+
+    let query = if text.len() > MAX_QUERY_LEN {
+        format!("{}...", truncate_to_char_boundary(&text, MAX_QUERY_LEN - 3))
+    } else {
+        text
+    };
+    Some(EmbeddedQuery {
+        location: Location::default(),
+        query,
+        tables,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn summarize(source: &str, lang: Lang, path: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn queries_of(summary: &SemanticSummary, name: &str) -> Vec<EmbeddedQuery> {
+        summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("symbol {name} not extracted"))
+            .embedded_queries
+            .clone()
+    }
+
+    #[test]
+    fn test_inline_select_captures_table() {
+        let source = r#"
+export async function findUsers(db) {
+  const rows = await db.query("SELECT * FROM users WHERE active = 1");
+  return rows;
+}
+
+export function label() {
+  return "Select a file to upload";
+}
+"#;
+        let summary = summarize(source, Lang::JavaScript, "/src/users.js");
+
+        let queries = queries_of(&summary, "findUsers");
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query, "SELECT * FROM users WHERE active = 1");
+        assert_eq!(queries[0].tables, vec!["users"]);
+        assert_eq!(queries[0].location.line, 3);
+        assert!(queries_of(&summary, "label").is_empty());
+    }
+
+    #[test]
+    fn test_multiline_query_tables() {
+        let source = r#"
+def monthly_totals(conn):
+    return conn.execute("""
+        select o.id, sum(l.amount)
+        from orders o
+        join line_items l on l.order_id = o.id
+        group by o.id
+    """)
+
+def archive(conn):
+    conn.execute('INSERT INTO "audit"."events" (kind) VALUES (?)', ("archive",))
+    conn.execute("update Orders set archived = 1")
+"#;
+        let summary = summarize(source, Lang::Python, "/app/reports.py");
+
+        let totals = queries_of(&summary, "monthly_totals");
+        assert_eq!(totals.len(), 1);
+        assert!(totals[0]
+            .query
+            .starts_with("select o.id, sum(l.amount) from orders"));
+        assert_eq!(totals[0].tables, vec!["orders", "line_items"]);
+
+        let archive = queries_of(&summary, "archive");
+        let tables: Vec<_> = archive.iter().map(|q| q.tables.clone()).collect();
+        assert_eq!(tables, vec![vec!["audit.events"], vec!["Orders"]]);
+    }
+
+    #[test]
+    fn test_parse_query_forms() {
+        let tables = |literal: &str| parse_query(literal).map(|q| q.tables);
+
+        assert_eq!(
+            tables(r##"r#"CREATE TABLE IF NOT EXISTS sessions (id TEXT)"#"##),
+            Some(vec!["sessions".to_string()])
+        );
+        assert_eq!(
+            tables("`DELETE FROM carts WHERE user_id = ${id}`"),
+            Some(vec!["carts".to_string()])
+        );
+        assert_eq!(tables("\"Update your profile\""), None);
+        assert_eq!(tables("\"selection\""), None);
+    }
+}
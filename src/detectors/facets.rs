@@ -0,0 +1,457 @@
+//! Async and error-handling facets
+//!
+//! Aggregates per-symbol facets that make questions like "async functions that
+//! do I/O outside any try block" or "functions that swallow errors" searchable
+//! (`search --facet`). Runs after the language detector, once symbols, their
+//! calls and their line ranges are known.
+//!
+//! - `awaits_count`: `await` expressions in the body, plus C# `await foreach`
+//!   and `await using` statements
+//! - `io_calls_outside_try`: I/O calls ([`Call::check_is_io`]) not inside a try block
+//! - `has_empty_catch`: a catch/except block with no calls or state changes
+//!   (e.g. `catch {}`, `except: pass`)
+//! - `rethrows`: a catch/except block that throws
+//! - `panic_points`: where the body can panic or throw — Rust `.unwrap()`,
+//!   `.expect()` and `panic!`-style macros, `throw` and Python `raise` — each
+//!   marked `in_try` when a try block around it catches it
+//!
+//! Catch blocks are recognized for JavaScript/TypeScript, Python, Java and C#.
+//!
+//! [`Call::check_is_io`]: crate::schema::Call::check_is_io
+
+use tree_sitter::Node;
+
+use crate::detectors::common::{find_containing_symbol_by_line, visit_all};
+use crate::lang::{Lang, LangFamily};
+use crate::schema::{Location, PanicKind, PanicPoint, RefKind, SemanticSummary};
+
+/// Rust macros that panic unconditionally
+const PANIC_MACROS: &[&str] = &["panic", "unreachable", "todo", "unimplemented"];
+
+/// Node kinds the facets are computed from, per language
+struct FacetKinds {
+    awaits: &'static [&'static str],
+    catches: &'static [&'static str],
+    throws: &'static [&'static str],
+    /// Try statements; a throw inside one's body is caught
+    tries: &'static [&'static str],
+    /// Calls and state changes; a catch block with none of these is empty
+    effects: &'static [&'static str],
+}
+
+impl FacetKinds {
+    fn for_lang(lang: Lang) -> Option<Self> {
+        let kinds = match lang.family() {
+            LangFamily::JavaScript => Self {
+                awaits: &["await_expression"],
+                catches: &["catch_clause"],
+                throws: &["throw_statement"],
+                tries: &["try_statement"],
+                effects: &[
+                    "call_expression",
+                    "new_expression",
+                    "assignment_expression",
+                    "augmented_assignment_expression",
+                    "update_expression",
+                ],
+            },
+            LangFamily::Python => Self {
+                awaits: &["await"],
+                catches: &["except_clause"],
+                throws: &["raise_statement"],
+                tries: &["try_statement"],
+                effects: &["call", "assignment", "augmented_assignment"],
+            },
+            LangFamily::Java => Self {
+                awaits: &[],
+                catches: &["catch_clause"],
+                throws: &["throw_statement"],
+                tries: &["try_statement", "try_with_resources_statement"],
+                effects: &[
+                    "method_invocation",
+                    "object_creation_expression",
+                    "assignment_expression",
+                    "update_expression",
+                ],
+            },
+            LangFamily::CSharp => Self {
+                awaits: &["await_expression"],
+                catches: &["catch_clause"],
+                throws: &["throw_statement", "throw_expression"],
+                tries: &["try_statement"],
+                effects: &[
+                    "invocation_expression",
+                    "object_creation_expression",
+                    "assignment_expression",
+                    "prefix_unary_expression",
+                    "postfix_unary_expression",
+                ],
+            },
+            LangFamily::Rust => Self {
+                awaits: &["await_expression"],
+                catches: &[],
+                throws: &[],
+                tries: &[],
+                effects: &[],
+            },
+            _ => return None,
+        };
+        Some(kinds)
+    }
+}
+
+/// Compute the async and error-handling facets of every symbol
+pub fn extract(summary: &mut SemanticSummary, root: &Node, source: &str, lang: Lang) {
+    for symbol in &mut summary.symbols {
+        symbol.io_calls_outside_try = symbol
+            .calls
+            .iter()
+            .filter(|c| c.ref_kind == RefKind::None && c.is_io && !c.in_try)
+            .count();
+    }
+
+    let Some(kinds) = FacetKinds::for_lang(lang) else {
+        return;
+    };
+    let symbols = &mut summary.symbols;
+
+    visit_all(root, |node| {
+        // Python's `await` keyword token shares its kind with the expression
+        if !node.is_named() {
+            return;
+        }
+        let kind = node.kind();
+        let is_await = kinds.awaits.contains(&kind)
+            || (lang.family() == LangFamily::CSharp
+                && crate::detectors::csharp::is_await_statement(node));
+        let is_catch = kinds.catches.contains(&kind);
+        let panic_kind = panic_kind(node, &kinds, source, lang);
+        if !is_await && !is_catch && panic_kind.is_none() {
+            return;
+        }
+        let Some(idx) = find_containing_symbol_by_line(node.start_position().row + 1, symbols)
+        else {
+            return;
+        };
+
+        if let Some(kind) = panic_kind {
+            let pos = node.start_position();
+            symbols[idx].panic_points.push(PanicPoint {
+                location: Location::new(pos.row + 1, pos.column),
+                kind,
+                in_try: in_try_body(node, kinds.tries),
+            });
+            return;
+        }
+        if is_await {
+            symbols[idx].awaits_count += 1;
+            return;
+        }
+        let body = catch_body(node);
+        if contains_kind(&body, kinds.throws) {
+            symbols[idx].rethrows = true;
+        } else if !contains_kind(&body, kinds.effects) {
+            symbols[idx].has_empty_catch = true;
+        }
+    });
+}
+
+/// What kind of panic point `node` is, if it is one
+fn panic_kind(node: &Node, kinds: &FacetKinds, source: &str, lang: Lang) -> Option<PanicKind> {
+    if kinds.throws.contains(&node.kind()) {
+        return Some(if lang.family() == LangFamily::Python {
+            PanicKind::Raise
+        } else {
+            PanicKind::Throw
+        });
+    }
+    if lang.family() != LangFamily::Rust {
+        return None;
+    }
+    let text = |n: Node| n.utf8_text(source.as_bytes()).unwrap_or("");
+    match node.kind() {
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            if function.kind() != "field_expression" {
+                return None;
+            }
+            match text(function.child_by_field_name("field")?) {
+                "unwrap" => Some(PanicKind::Unwrap),
+                "expect" => Some(PanicKind::Expect),
+                _ => None,
+            }
+        }
+        "macro_invocation" => {
+            let name = text(node.child_by_field_name("macro")?);
+            let name = name.rsplit("::").next().unwrap_or(name);
+            PANIC_MACROS.contains(&name).then_some(PanicKind::Panic)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `node` is inside the body of a try statement (as opposed to its
+/// catch or finally blocks)
+fn in_try_body(node: &Node, tries: &[&str]) -> bool {
+    let mut child = *node;
+    while let Some(parent) = child.parent() {
+        if tries.contains(&parent.kind())
+            && parent
+                .child_by_field_name("body")
+                .is_some_and(|body| body.id() == child.id())
+        {
+            return true;
+        }
+        child = parent;
+    }
+    false
+}
+
+/// The block run when a catch/except clause matches
+fn catch_body<'a>(catch: &Node<'a>) -> Node<'a> {
+    if let Some(body) = catch.child_by_field_name("body") {
+        return body;
+    }
+    let mut cursor = catch.walk();
+    let block = catch
+        .named_children(&mut cursor)
+        .filter(|c| c.kind() == "block")
+        .last();
+    block.unwrap_or(*catch)
+}
+
+/// Whether `node` or any of its descendants is one of `kinds`
+fn contains_kind(node: &Node, kinds: &[&str]) -> bool {
+    let mut found = false;
+    visit_all(node, |n| {
+        found = found || kinds.contains(&n.kind());
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{RiskLevel, SymbolInfo};
+    use std::path::Path;
+
+    fn summarize(source: &str, lang: Lang, path: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn symbol<'a>(summary: &'a SemanticSummary, name: &str) -> &'a SymbolInfo {
+        summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("symbol {name} not extracted"))
+    }
+
+    #[test]
+    fn test_typescript_facets() {
+        let source = r#"
+export async function loadUser(id: string) {
+  const res = await fetch(`/users/${id}`);
+  return await res.json();
+}
+
+export async function saveUser(user: User) {
+  try {
+    await fetch('/users', { method: 'POST' });
+  } catch (e) {
+  }
+}
+
+export function parseConfig(text: string) {
+  try {
+    return JSON.parse(text);
+  } catch (e) {
+    throw new ConfigError(e);
+  }
+}
+"#;
+        let summary = summarize(source, Lang::TypeScript, "/src/users.ts");
+
+        let load = symbol(&summary, "loadUser");
+        assert!(load.is_async);
+        assert_eq!(load.awaits_count, 2);
+        assert_eq!(load.io_calls_outside_try, 1);
+        assert!(!load.has_empty_catch);
+
+        let save = symbol(&summary, "saveUser");
+        assert_eq!(save.awaits_count, 1);
+        assert_eq!(save.io_calls_outside_try, 0);
+        assert!(save.has_empty_catch);
+        assert!(!save.rethrows);
+
+        let parse = symbol(&summary, "parseConfig");
+        assert_eq!(parse.awaits_count, 0);
+        assert!(!parse.has_empty_catch);
+        assert!(parse.rethrows);
+    }
+
+    #[test]
+    fn test_python_facets() {
+        let source = r#"
+async def sync_orders(client):
+    await client.refresh()
+    data = open("orders.json")
+    try:
+        return parse(data)
+    except ValueError:
+        pass
+
+def load_settings(path):
+    try:
+        return read(path)
+    except OSError as e:
+        raise SettingsError(path) from e
+"#;
+        let summary = summarize(source, Lang::Python, "/src/orders.py");
+
+        let sync = symbol(&summary, "sync_orders");
+        assert!(sync.is_async);
+        assert_eq!(sync.awaits_count, 1);
+        assert_eq!(sync.io_calls_outside_try, 1);
+        assert!(sync.has_empty_catch);
+        assert!(!sync.rethrows);
+
+        let load = symbol(&summary, "load_settings");
+        assert_eq!(load.io_calls_outside_try, 0);
+        assert!(!load.has_empty_catch);
+        assert!(load.rethrows);
+    }
+
+    #[test]
+    fn test_csharp_facets() {
+        let source = r#"
+public class OrderService
+{
+    public async Task SyncAsync()
+    {
+        await _client.RefreshAsync();
+        await foreach (var order in _client.StreamAsync())
+        {
+            Queue(order);
+        }
+        try
+        {
+            Process();
+        }
+        catch (Exception)
+        {
+        }
+    }
+
+    public void Save(Order order)
+    {
+        try
+        {
+            _repo.Save(order);
+        }
+        catch (DbException ex)
+        {
+            _log.Error(ex);
+            throw;
+        }
+    }
+}
+"#;
+        let summary = summarize(source, Lang::CSharp, "/src/OrderService.cs");
+
+        let sync = symbol(&summary, "SyncAsync");
+        assert_eq!(sync.awaits_count, 2);
+        assert!(sync.has_empty_catch);
+        assert!(!sync.rethrows);
+
+        let save = symbol(&summary, "Save");
+        assert_eq!(save.awaits_count, 0);
+        assert!(!save.has_empty_catch);
+        assert!(save.rethrows);
+    }
+
+    #[test]
+    fn test_rust_panic_points() {
+        let source = r#"
+pub fn load(path: &str) -> Config {
+    let text = std::fs::read_to_string(path).unwrap();
+    let config = toml::from_str(&text).expect("valid config");
+    if config.version == 0 {
+        panic!("unsupported version");
+    }
+    config
+}
+
+pub fn load_or_default(path: &str) -> Config {
+    std::fs::read_to_string(path)
+        .map(|text| parse(&text))
+        .unwrap_or_default()
+}
+"#;
+        let summary = summarize(source, Lang::Rust, "/src/config.rs");
+
+        let load = symbol(&summary, "load");
+        let points: Vec<_> = load
+            .panic_points
+            .iter()
+            .map(|p| (p.location.line, p.kind, p.in_try))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                (3, PanicKind::Unwrap, false),
+                (4, PanicKind::Expect, false),
+                (6, PanicKind::Panic, false),
+            ]
+        );
+        assert!(symbol(&summary, "load_or_default").panic_points.is_empty());
+
+        // Three uncaught points weigh the symbol's risk by the cap of 2
+        assert_eq!(crate::schema::panic_weight(&load.panic_points), 2);
+        let mut without = load.clone();
+        without.panic_points.clear();
+        assert_eq!(without.calculate_risk(), RiskLevel::Low);
+        assert_eq!(load.calculate_risk(), RiskLevel::Medium);
+    }
+
+    #[test]
+    fn test_javascript_throw_points() {
+        let source = r#"
+export function parse(text) {
+  if (!text) {
+    throw new Error("empty");
+  }
+  return JSON.parse(text);
+}
+
+export function parseSafe(text) {
+  try {
+    if (!text) throw new Error("empty");
+    return JSON.parse(text);
+  } catch (e) {
+    return null;
+  }
+}
+"#;
+        let summary = summarize(source, Lang::JavaScript, "/src/parse.js");
+
+        let parse = symbol(&summary, "parse");
+        assert_eq!(parse.panic_points.len(), 1);
+        assert_eq!(parse.panic_points[0].location.line, 4);
+        assert_eq!(parse.panic_points[0].kind, PanicKind::Throw);
+        assert!(!parse.panic_points[0].in_try);
+
+        let safe = symbol(&summary, "parseSafe");
+        assert_eq!(safe.panic_points.len(), 1);
+        assert_eq!(safe.panic_points[0].location.line, 11);
+        assert!(safe.panic_points[0].in_try);
+
+        // A caught throw counts half as much as an uncaught one
+        assert_eq!(crate::schema::panic_weight(&parse.panic_points), 1);
+        let doubled = [safe.panic_points[0].clone(), safe.panic_points[0].clone()];
+        assert_eq!(crate::schema::panic_weight(&doubled), 1);
+    }
+}
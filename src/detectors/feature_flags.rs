@@ -0,0 +1,255 @@
+//! Feature flag detection
+//!
+//! Records which feature flags gate each symbol so that flag cleanups can be
+//! planned with `search --flag <name>`. Runs after the language detector, once
+//! symbols and their line ranges are known.
+//!
+//! # Recognized Patterns
+//!
+//! - **Rust**: `#[cfg(feature = "x")]` / `#[cfg_attr(feature = "x", ...)]` on an
+//!   item, `#![cfg(feature = "x")]` for the whole file, and `cfg!(feature = "x")`
+//!   checks inside a body
+//! - **JavaScript/TypeScript**: flag SDK calls whose first argument is a string
+//!   literal, e.g. `flags.isEnabled('x')`, `ldClient.variation('x', false)`,
+//!   `useFeatureFlag('x')`
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tree_sitter::Node;
+
+use crate::detectors::common::{find_containing_symbol_by_line, get_node_text, visit_all};
+use crate::lang::{Lang, LangFamily};
+use crate::schema::SemanticSummary;
+
+/// `feature = "name"` inside a `cfg` predicate
+static CFG_FEATURE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\bfeature\s*=\s*"([^"]+)""#).expect("valid regex"));
+
+/// Flag-checking methods of common SDKs (Unleash, LaunchDarkly, GrowthBook,
+/// Statsig, Split, OpenFeature, PostHog, Flipper-style wrappers)
+const FLAG_METHODS: &[&str] = &[
+    "isEnabled",
+    "isFeatureEnabled",
+    "isOn",
+    "variation",
+    "boolVariation",
+    "stringVariation",
+    "getFeatureValue",
+    "getFeatureFlag",
+    "getBooleanValue",
+    "checkGate",
+    "getTreatment",
+];
+
+/// Standalone flag-checking functions and React hooks
+const FLAG_FUNCTIONS: &[&str] = &[
+    "isEnabled",
+    "isFeatureEnabled",
+    "useFlag",
+    "useFeatureFlag",
+    "useFeatureFlagEnabled",
+    "useFeatureIsOn",
+    "useGate",
+];
+
+/// Attach feature flags to the symbols they gate
+pub fn extract(summary: &mut SemanticSummary, root: &Node, source: &str, lang: Lang) {
+    let checks = match lang.family() {
+        LangFamily::Rust => rust_flag_checks(root, source),
+        LangFamily::JavaScript => js_flag_checks(root, source),
+        _ => return,
+    };
+
+    for (flag, line) in checks {
+        let targets: Vec<usize> = match line {
+            Some(line) => find_containing_symbol_by_line(line, &summary.symbols)
+                .into_iter()
+                .collect(),
+            // File-level gate applies to every symbol
+            None => (0..summary.symbols.len()).collect(),
+        };
+        for idx in targets {
+            let flags = &mut summary.symbols[idx].feature_flags;
+            if !flags.contains(&flag) {
+                flags.push(flag.clone());
+            }
+        }
+    }
+}
+
+/// Flag names referenced by a `cfg` predicate
+pub fn cfg_features(text: &str) -> Vec<String> {
+    CFG_FEATURE
+        .captures_iter(text)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// (flag, line) pairs for Rust; a `None` line gates the whole file
+fn rust_flag_checks(root: &Node, source: &str) -> Vec<(String, Option<usize>)> {
+    let mut checks = Vec::new();
+
+    visit_all(root, |node| {
+        let line = match node.kind() {
+            // Outer attribute: gates the item that follows it
+            "attribute_item" => {
+                let mut item = node.next_named_sibling();
+                while let Some(n) = item.filter(|n| n.kind() == "attribute_item") {
+                    item = n.next_named_sibling();
+                }
+                match item {
+                    Some(item) => Some(item.start_position().row + 1),
+                    None => return,
+                }
+            }
+            "inner_attribute_item" => None,
+            "macro_invocation" => {
+                let is_cfg = node
+                    .child_by_field_name("macro")
+                    .is_some_and(|m| get_node_text(&m, source) == "cfg");
+                if !is_cfg {
+                    return;
+                }
+                Some(node.start_position().row + 1)
+            }
+            _ => return,
+        };
+
+        let text = get_node_text(node, source);
+        let is_cfg = node.kind() == "macro_invocation"
+            || text.contains("cfg(")
+            || text.contains("cfg_attr(");
+        if !is_cfg {
+            return;
+        }
+        for flag in cfg_features(&text) {
+            checks.push((flag, line));
+        }
+    });
+
+    checks
+}
+
+/// (flag, line) pairs for JavaScript/TypeScript flag SDK calls
+fn js_flag_checks(root: &Node, source: &str) -> Vec<(String, Option<usize>)> {
+    let mut checks = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "call_expression" {
+            return;
+        }
+        let Some(function) = node.child_by_field_name("function") else {
+            return;
+        };
+        let is_flag_call = match function.kind() {
+            "member_expression" => function
+                .child_by_field_name("property")
+                .is_some_and(|p| FLAG_METHODS.contains(&get_node_text(&p, source).as_str())),
+            "identifier" => FLAG_FUNCTIONS.contains(&get_node_text(&function, source).as_str()),
+            _ => false,
+        };
+        if !is_flag_call {
+            return;
+        }
+
+        let flag = node
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0))
+            .and_then(|arg| string_literal(&arg, source));
+        if let Some(flag) = flag {
+            checks.push((flag, Some(node.start_position().row + 1)));
+        }
+    });
+
+    checks
+}
+
+/// Contents of a string literal (or a template literal without substitutions)
+fn string_literal(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "string" => {}
+        "template_string" if node.named_child_count() <= 1 => {
+            if node
+                .named_child(0)
+                .is_some_and(|c| c.kind() == "template_substitution")
+            {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+    let text = get_node_text(node, source);
+    let flag = text.trim_matches(|c| c == '\'' || c == '"' || c == '`');
+    (!flag.is_empty()).then(|| flag.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn summarize(source: &str, lang: Lang, path: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn flags_of(summary: &SemanticSummary, name: &str) -> Vec<String> {
+        summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("symbol {name} not extracted"))
+            .feature_flags
+            .clone()
+    }
+
+    #[test]
+    fn test_rust_cfg_feature() {
+        let source = r#"
+#[cfg(feature = "beta")]
+pub fn beta_only() -> u32 {
+    1
+}
+
+pub fn always() -> u32 {
+    if cfg!(feature = "metrics") {
+        record();
+    }
+    2
+}
+"#;
+        let summary = summarize(source, Lang::Rust, "/src/lib.rs");
+        assert_eq!(flags_of(&summary, "beta_only"), vec!["beta"]);
+        assert_eq!(flags_of(&summary, "always"), vec!["metrics"]);
+    }
+
+    #[test]
+    fn test_js_flag_sdk_call() {
+        let source = r#"
+export function renderCheckout(flags) {
+  if (flags.isEnabled('beta')) {
+    return newCheckout();
+  }
+  return oldCheckout();
+}
+
+export function unrelated(cache) {
+  return cache.get('beta');
+}
+"#;
+        let summary = summarize(source, Lang::JavaScript, "/src/checkout.js");
+        assert_eq!(flags_of(&summary, "renderCheckout"), vec!["beta"]);
+        assert!(flags_of(&summary, "unrelated").is_empty());
+    }
+
+    #[test]
+    fn test_cfg_features() {
+        assert_eq!(
+            cfg_features(r#"#[cfg(all(feature = "a", not(feature="b")))]"#),
+            vec!["a", "b"]
+        );
+        assert!(cfg_features("#[cfg(test)]").is_empty());
+    }
+}
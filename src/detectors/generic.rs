@@ -22,6 +22,7 @@ use crate::detectors::common::{
 use crate::detectors::grammar::LangGrammar;
 use crate::detectors::variable_refs;
 use crate::error::Result;
+use crate::extract::ExtractionLevel;
 use crate::lang::Lang;
 use crate::schema::{
     Call, ControlFlowChange, ControlFlowKind, FrameworkEntryPoint, Location, RefKind, RiskLevel,
@@ -39,16 +40,31 @@ pub fn extract_with_grammar(
     source: &str,
     tree: &Tree,
     grammar: &LangGrammar,
+) -> Result<()> {
+    extract_with_grammar_at_level(summary, source, tree, grammar, ExtractionLevel::Full)
+}
+
+/// Extract semantic information at the given level of detail
+///
+/// `ExtractionLevel::Signatures` only extracts symbols and imports.
+pub fn extract_with_grammar_at_level(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    grammar: &LangGrammar,
+    level: ExtractionLevel,
 ) -> Result<()> {
     let root = tree.root_node();
 
     // Extract all semantic information
     extract_symbols(summary, &root, source, grammar);
     extract_imports(summary, &root, source, grammar);
-    extract_state_changes(summary, &root, source, grammar);
-    extract_control_flow(summary, &root, source, grammar);
-    extract_calls(summary, &root, source, grammar);
-    extract_variable_references(summary, &root, source, grammar);
+    if level == ExtractionLevel::Full {
+        extract_state_changes(summary, &root, source, grammar);
+        extract_control_flow(summary, &root, source, grammar);
+        extract_calls(summary, &root, source, grammar);
+        extract_variable_references(summary, &root, source, grammar);
+    }
 
     // Calculate derived metrics
     calculate_complexity(summary);
@@ -99,6 +115,7 @@ fn extract_symbols(
             is_default_export: false,
             hash: None,
             arguments: Vec::new(),
+            param_types: Vec::new(),
             props: Vec::new(),
             return_type: None,
             calls: Vec::new(),
@@ -110,6 +127,22 @@ fn extract_symbols(
             framework_entry_point: FrameworkEntryPoint::None,
             is_async: candidate.is_async,
             base_classes: Vec::new(),
+            doc_comment: None,
+            feature_flags: Vec::new(),
+            generic_params: Vec::new(),
+            lifetime_params: Vec::new(),
+            implements: Vec::new(),
+            is_deprecated: false,
+            deprecation_message: None,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: Vec::new(),
+            embedded_queries: Vec::new(),
+            partial_of: None,
+            parent_symbol: None,
+            nested_id: None,
         };
         summary.symbols.push(symbol_info);
     }
@@ -268,6 +301,7 @@ fn is_class_field(node: &Node, grammar: &LangGrammar) -> bool {
 /// Looks for decorator nodes in:
 /// 1. Preceding siblings (Python @decorator, C# \[Attribute\])
 /// 2. Child nodes (some grammars nest attributes within the declaration)
+/// 3. A `modifiers` child (Java and Kotlin annotations sit beside `public`)
 fn extract_decorators(node: &Node, source: &str, grammar: &LangGrammar) -> Vec<String> {
     let mut decorators = Vec::new();
 
@@ -289,10 +323,18 @@ fn extract_decorators(node: &Node, source: &str, grammar: &LangGrammar) -> Vec<S
         }
         prev = sibling.prev_sibling();
     }
+    // Reverse so they're in source order (we collected backwards from prev_sibling)
+    decorators.reverse();
 
     // Also check direct children (some grammars nest attributes within declaration)
     let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
+    let mut children: Vec<Node> = node.children(&mut cursor).collect();
+    if let Some(modifiers) = children.iter().position(|c| c.kind() == "modifiers") {
+        let modifiers = children.remove(modifiers);
+        let mut inner_cursor = modifiers.walk();
+        children.splice(0..0, modifiers.children(&mut inner_cursor));
+    }
+    for child in children {
         let child_kind = child.kind();
         if grammar.decorator_nodes.contains(&child_kind) {
             // For attribute_list nodes, extract each attribute
@@ -303,6 +345,16 @@ fn extract_decorators(node: &Node, source: &str, grammar: &LangGrammar) -> Vec<S
                         if let Some(text) = extract_decorator_text(&attr, source) {
                             decorators.push(text);
                         }
+                    } else if attr.kind() == "attribute_group" {
+                        // PHP groups attributes: `#[Get('/'), Middleware('auth')]`
+                        let mut group_cursor = attr.walk();
+                        for grouped in attr.children(&mut group_cursor) {
+                            if grouped.kind() == "attribute" {
+                                if let Some(text) = extract_decorator_text(&grouped, source) {
+                                    decorators.push(text);
+                                }
+                            }
+                        }
                     }
                 }
             } else if let Some(text) = extract_decorator_text(&child, source) {
@@ -311,8 +363,6 @@ fn extract_decorators(node: &Node, source: &str, grammar: &LangGrammar) -> Vec<S
         }
     }
 
-    // Reverse so they're in source order (we collected backwards from prev_sibling)
-    decorators.reverse();
     decorators
 }
 
@@ -322,11 +372,14 @@ fn extract_decorators(node: &Node, source: &str, grammar: &LangGrammar) -> Vec<S
 /// Works for any language where the grammar places an `async` keyword node
 /// as a direct child of the function definition node (Python, JS/TS).
 /// For JS, `async` appears as a named child with kind "async";
-/// for Python (tree-sitter-python ≥0.23) it is an anonymous first child.
+/// for Python (tree-sitter-python ≥0.23) it is an anonymous first child;
+/// Rust wraps it in a `function_modifiers` child.
 pub fn is_async_node(node: &Node) -> bool {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        if child.kind() == "async" {
+        if child.kind() == "async"
+            || (child.kind() == "function_modifiers" && is_async_node(&child))
+        {
             return true;
         }
     }
@@ -561,24 +614,31 @@ fn extract_state_changes(
     grammar: &LangGrammar,
 ) {
     visit_all(root, |node| {
-        let kind = node.kind();
-
-        // Variable declarations
-        if grammar.var_declaration_nodes.contains(&kind) {
-            if let Some(state_change) = extract_var_declaration(node, source, grammar) {
-                summary.state_changes.push(state_change);
-            }
-        }
-
-        // Assignments
-        if grammar.assignment_nodes.contains(&kind) {
-            if let Some(state_change) = extract_assignment(node, source, grammar) {
-                summary.state_changes.push(state_change);
-            }
+        if let Some(state_change) = state_change_for_node(node, source, grammar) {
+            summary.state_changes.push(state_change);
         }
     });
 }
 
+/// State change recorded for a variable declaration or assignment node
+///
+/// Language detectors that refine state changes after extraction walk the tree
+/// in the same order and use this to line nodes up with `summary.state_changes`.
+pub(crate) fn state_change_for_node(
+    node: &Node,
+    source: &str,
+    grammar: &LangGrammar,
+) -> Option<StateChange> {
+    let kind = node.kind();
+    if grammar.var_declaration_nodes.contains(&kind) {
+        extract_var_declaration(node, source, grammar)
+    } else if grammar.assignment_nodes.contains(&kind) {
+        extract_assignment(node, source, grammar)
+    } else {
+        None
+    }
+}
+
 fn extract_var_declaration(
     node: &Node,
     source: &str,
@@ -615,6 +675,7 @@ fn extract_var_declaration(
         name,
         state_type,
         initializer,
+        is_atomic: false,
     })
 }
 
@@ -635,6 +696,7 @@ fn extract_assignment(node: &Node, source: &str, grammar: &LangGrammar) -> Optio
         name,
         state_type: "_".to_string(),
         initializer,
+        is_atomic: false,
     })
 }
 
@@ -648,7 +710,7 @@ fn find_identifier_child<'a>(node: &'a Node<'a>) -> Option<Node<'a>> {
     None
 }
 
-fn compress_initializer(init: &str) -> String {
+pub(crate) fn compress_initializer(init: &str) -> String {
     // Use the common utility if available, otherwise simple truncation
     if init.len() <= 60 {
         init.to_string()
@@ -702,6 +764,15 @@ fn collect_control_flow_recursive(
                     location,
                     nesting_depth: current_depth,
                 });
+            } else if grammar.await_nodes.contains(&kind) {
+                results.push(ControlFlowChange {
+                    kind: ControlFlowKind::Await,
+                    location: Location::new(
+                        current_node.start_position().row + 1,
+                        current_node.start_position().column,
+                    ),
+                    nesting_depth: current_depth,
+                });
             }
 
             let is_control_flow =
@@ -825,7 +896,8 @@ const CONSTRUCTOR_NODE_TYPES: &[&str] = &[
     "new_expression",             // JavaScript, TypeScript
 ];
 
-fn extract_call(node: &Node, source: &str, grammar: &LangGrammar) -> Option<Call> {
+/// Extract a single call node
+pub(crate) fn extract_call(node: &Node, source: &str, grammar: &LangGrammar) -> Option<Call> {
     let node_kind = node.kind();
 
     // Get the function/type name - constructor nodes have different structure
@@ -884,6 +956,7 @@ fn extract_call(node: &Node, source: &str, grammar: &LangGrammar) -> Option<Call
         in_try,
         is_hook,
         is_io,
+        is_macro: false,
         ref_kind: RefKind::None,
         location,
     })
@@ -917,6 +990,7 @@ fn grammar_name_to_lang(name: &str) -> Option<Lang> {
         "c" => Some(Lang::C),
         "cpp" => Some(Lang::Cpp),
         "kotlin" => Some(Lang::Kotlin),
+        "php" => Some(Lang::Php),
         "bash" => Some(Lang::Bash),
         "gradle" => Some(Lang::Gradle),
         "hcl" => Some(Lang::Hcl),
@@ -954,7 +1028,12 @@ fn determine_risk(summary: &mut SemanticSummary) {
     let mut complexity: usize = 0;
     let mut max_depth: usize = 0;
 
-    for cf in &summary.control_flow_changes {
+    // Await points are counted by risk scoring, not structural complexity
+    for cf in summary
+        .control_flow_changes
+        .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
+    {
         // Base complexity for each control flow construct
         complexity += 1;
 
@@ -1061,6 +1140,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rust_await_control_flow() {
+        let source = r#"
+async fn sync_all(client: &Client) -> Result<()> {
+    let items = client.fetch().await;
+    for item in items {
+        client.store(item).await;
+    }
+    Ok(())
+}
+"#;
+        let tree = parse_source(source, Lang::Rust);
+        let path = PathBuf::from("/test/sync.rs");
+        let summary = extract(&path, source, &tree, Lang::Rust).unwrap();
+
+        let awaits: Vec<_> = summary
+            .control_flow_changes
+            .iter()
+            .filter(|cf| cf.kind == ControlFlowKind::Await)
+            .collect();
+        assert_eq!(awaits.len(), 2);
+        assert_eq!(awaits[0].nesting_depth, 0);
+        assert_eq!(
+            awaits[1].nesting_depth, 1,
+            "second await is inside the for loop"
+        );
+    }
+
     // =============================================================================
     // Call Graph Tests - Symbol-Level Call Attribution
     // =============================================================================
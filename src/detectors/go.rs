@@ -7,26 +7,40 @@
 //! - State changes (variable declarations)
 //! - Control flow (if, for, switch, select)
 //! - Function calls
+//! - Interfaces each type satisfies (from interfaces declared in the same file)
+//! - `//go:generate` directives, recorded as macro calls
+
+use std::collections::{HashMap, HashSet};
 
 use tree_sitter::{Node, Tree};
 
 use crate::detectors::common::get_node_text;
-use crate::detectors::generic::extract_with_grammar;
+use crate::detectors::generic::extract_with_grammar_at_level;
 use crate::detectors::grammar::GO_GRAMMAR;
 use crate::error::Result;
-use crate::schema::{FrameworkEntryPoint, RiskLevel, SemanticSummary, SymbolInfo, SymbolKind};
+use crate::extract::ExtractionLevel;
+use crate::schema::{
+    Call, FrameworkEntryPoint, Location, RiskLevel, SemanticSummary, SymbolInfo, SymbolKind,
+};
 
 /// Extract semantic information from a Go source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // Use the generic extractor for most semantic extraction
     // This handles: imports, state_changes, control_flow, calls, and risk calculation
-    extract_with_grammar(summary, source, tree, &GO_GRAMMAR)?;
+    extract_with_grammar_at_level(summary, source, tree, &GO_GRAMMAR, level)?;
 
     // Go has a unique type declaration structure: type_declaration > type_spec > (struct_type | interface_type)
     // The generic extractor won't find these, so we do Go-specific symbol extraction
     // that merges with what the generic extractor already found
     let root = tree.root_node();
     find_go_type_symbols(summary, &root, source);
+    mark_interface_implementations(summary, &root, source);
+    collect_generate_directives(summary, &root, source);
 
     Ok(())
 }
@@ -82,6 +96,7 @@ fn find_go_type_symbols(summary: &mut SemanticSummary, root: &Node, source: &str
                 is_default_export: false,
                 hash: None,
                 arguments: Vec::new(),
+                param_types: Vec::new(),
                 props: Vec::new(),
                 return_type: None,
                 calls: Vec::new(),
@@ -93,6 +108,22 @@ fn find_go_type_symbols(summary: &mut SemanticSummary, root: &Node, source: &str
                 framework_entry_point: FrameworkEntryPoint::None,
                 is_async: false,
                 base_classes: Vec::new(),
+                doc_comment: None,
+                feature_flags: Vec::new(),
+                generic_params: Vec::new(),
+                lifetime_params: Vec::new(),
+                implements: Vec::new(),
+                is_deprecated: false,
+                deprecation_message: None,
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: Vec::new(),
+                embedded_queries: Vec::new(),
+                partial_of: None,
+                parent_symbol: None,
+                nested_id: None,
             };
             summary.symbols.push(symbol_info);
         }
@@ -182,6 +213,264 @@ fn determine_type_kind(type_spec: &Node) -> SymbolKind {
     SymbolKind::Struct // Default to struct for type aliases
 }
 
+// ============================================================================
+// Interface Satisfaction
+// ============================================================================
+// Go interfaces are satisfied implicitly: a type implements an interface when
+// its methods cover every method the interface declares. Only interfaces
+// declared in the same file can be checked, since other files' declarations
+// aren't available here.
+
+/// Record on each type symbol the same-file interfaces its methods satisfy
+fn mark_interface_implementations(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let interfaces = collect_interface_method_sets(root, source);
+    if interfaces.is_empty() {
+        return;
+    }
+    let methods = collect_receiver_method_sets(root, source);
+
+    for symbol in &mut summary.symbols {
+        if symbol.kind != SymbolKind::Struct {
+            continue;
+        }
+        let Some(own) = methods.get(&symbol.name) else {
+            continue;
+        };
+        for (interface, required) in &interfaces {
+            if required.is_subset(own) && !symbol.implements.contains(interface) {
+                symbol.implements.push(interface.clone());
+            }
+        }
+    }
+}
+
+/// Method signatures required by each non-empty interface declared in the file
+///
+/// Embedded interfaces declared in the same file contribute their methods.
+/// An interface embedding anything else (`io.Reader`, a type union) is
+/// skipped, since its full method set is unknown.
+fn collect_interface_method_sets(root: &Node, source: &str) -> Vec<(String, HashSet<String>)> {
+    // name -> (declared method signatures, embedded type names)
+    let mut declared: HashMap<String, (HashSet<String>, Vec<String>)> = HashMap::new();
+    let mut order = Vec::new();
+
+    for type_spec in type_specs(root) {
+        let (Some(name_node), Some(type_node)) = (
+            type_spec.child_by_field_name("name"),
+            type_spec.child_by_field_name("type"),
+        ) else {
+            continue;
+        };
+        if type_node.kind() != "interface_type" {
+            continue;
+        }
+
+        let mut methods = HashSet::new();
+        let mut embedded = Vec::new();
+        let mut cursor = type_node.walk();
+        for element in type_node.named_children(&mut cursor) {
+            match element.kind() {
+                "method_elem" => methods.extend(method_signature(&element, source)),
+                "type_elem" => embedded.push(normalize_type(&get_node_text(&element, source))),
+                _ => {}
+            }
+        }
+
+        let name = get_node_text(&name_node, source);
+        order.push(name.clone());
+        declared.insert(name, (methods, embedded));
+    }
+
+    order
+        .into_iter()
+        .filter_map(|name| {
+            let methods = resolve_interface(&name, &declared, &mut Vec::new())?;
+            (!methods.is_empty()).then_some((name, methods))
+        })
+        .collect()
+}
+
+/// Full method set of `name`, following embedded interfaces
+fn resolve_interface(
+    name: &str,
+    declared: &HashMap<String, (HashSet<String>, Vec<String>)>,
+    visiting: &mut Vec<String>,
+) -> Option<HashSet<String>> {
+    if visiting.iter().any(|v| v == name) {
+        return None;
+    }
+    let (methods, embedded) = declared.get(name)?;
+    visiting.push(name.to_string());
+    let mut all = methods.clone();
+    for inner in embedded {
+        all.extend(resolve_interface(inner, declared, visiting)?);
+    }
+    visiting.pop();
+    Some(all)
+}
+
+/// Method signatures declared on each receiver type, pointer or value
+fn collect_receiver_method_sets(root: &Node, source: &str) -> HashMap<String, HashSet<String>> {
+    let mut sets: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut cursor = root.walk();
+
+    for child in root.children(&mut cursor) {
+        if child.kind() != "method_declaration" {
+            continue;
+        }
+        let Some(receiver) = child
+            .child_by_field_name("receiver")
+            .and_then(|list| list.named_child(0))
+            .and_then(|param| param.child_by_field_name("type"))
+        else {
+            continue;
+        };
+        // `*Stack[T]` -> `Stack`
+        let receiver = get_node_text(&receiver, source);
+        let type_name = receiver
+            .trim_start_matches('*')
+            .split('[')
+            .next()
+            .unwrap_or_default()
+            .trim();
+        if let Some(signature) = method_signature(&child, source) {
+            sets.entry(type_name.to_string())
+                .or_default()
+                .insert(signature);
+        }
+    }
+
+    sets
+}
+
+/// All `type_spec` nodes of top-level type declarations
+fn type_specs<'a>(root: &Node<'a>) -> Vec<Node<'a>> {
+    let mut specs = Vec::new();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() == "type_declaration" {
+            let mut inner_cursor = child.walk();
+            specs.extend(
+                child
+                    .children(&mut inner_cursor)
+                    .filter(|inner| inner.kind() == "type_spec"),
+            );
+        }
+    }
+    specs
+}
+
+/// Comparable signature of a method declaration or interface method,
+/// e.g. `Read([]byte)(int,error)`
+///
+/// Parameter names are dropped, so `Read(p []byte) (n int, err error)`
+/// matches the interface's `Read([]byte) (int, error)`.
+fn method_signature(node: &Node, source: &str) -> Option<String> {
+    let name = get_node_text(&node.child_by_field_name("name")?, source);
+    let params = node
+        .child_by_field_name("parameters")
+        .map(|list| parameter_types(&list, source))
+        .unwrap_or_default();
+    let result = match node.child_by_field_name("result") {
+        Some(list) if list.kind() == "parameter_list" => {
+            format!("({})", parameter_types(&list, source))
+        }
+        Some(ty) => format!("({})", normalize_type(&get_node_text(&ty, source))),
+        None => String::new(),
+    };
+    Some(format!("{}({}){}", name, params, result))
+}
+
+/// Comma-separated parameter types of a parameter list, names dropped
+fn parameter_types(list: &Node, source: &str) -> String {
+    let mut types = Vec::new();
+    let mut cursor = list.walk();
+    for param in list.named_children(&mut cursor) {
+        let Some(type_node) = param.child_by_field_name("type") else {
+            continue;
+        };
+        let mut ty = normalize_type(&get_node_text(&type_node, source));
+        if param.kind() == "variadic_parameter_declaration" {
+            ty = format!("...{}", ty);
+        }
+        // `a, b int` declares two parameters of one type
+        let mut name_cursor = param.walk();
+        let count = param
+            .children_by_field_name("name", &mut name_cursor)
+            .count()
+            .max(1);
+        types.extend(std::iter::repeat_n(ty, count));
+    }
+    types.join(",")
+}
+
+/// Type text with whitespace removed, so formatting differences don't matter
+fn normalize_type(text: &str) -> String {
+    text.split_whitespace().collect()
+}
+
+// ============================================================================
+// go:generate Directives
+// ============================================================================
+
+/// Record `//go:generate` directives as macro calls
+///
+/// Each directive is attached to the declaration that follows it, so the
+/// generator shows up in the call graph next to the code it produces.
+/// Directives after the last declaration are kept on the file summary.
+fn collect_generate_directives(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() != "comment" {
+            continue;
+        }
+        let text = get_node_text(&child, source);
+        let Some(name) = text
+            .strip_prefix("//go:generate")
+            .filter(|command| command.starts_with(char::is_whitespace))
+            .and_then(generator_name)
+        else {
+            continue;
+        };
+
+        let line = child.start_position().row + 1;
+        let call = Call {
+            name,
+            is_macro: true,
+            location: Location::new(line, child.start_position().column),
+            ..Default::default()
+        };
+        let next_symbol = summary
+            .symbols
+            .iter_mut()
+            .filter(|symbol| symbol.start_line > line)
+            .min_by_key(|symbol| symbol.start_line);
+        match next_symbol {
+            Some(symbol) => symbol.calls.push(call),
+            None => summary.calls.push(call),
+        }
+    }
+}
+
+/// The generator a `go:generate` command runs
+///
+/// This is the command itself (`stringer -type=Pill` -> `stringer`), or the
+/// program for `go run` (`go run ./cmd/gen` -> `./cmd/gen`).
+fn generator_name(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+    if program == "go" && words.next() == Some("run") {
+        // Flags may take values, so look for something shaped like a package
+        // or file rather than the first non-flag word
+        let is_target =
+            |word: &&str| !word.starts_with('-') && (word.ends_with(".go") || word.contains('/'));
+        if let Some(target) = words.find(is_target) {
+            return Some(target.to_string());
+        }
+    }
+    Some(program.to_string())
+}
+
 /// Calculate a score for symbol prioritization
 fn calculate_symbol_score(
     name: &str,
@@ -232,6 +521,100 @@ fn calculate_symbol_score(
 mod tests {
     use super::*;
 
+    fn extract_source(source: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary {
+            file: "server.go".to_string(),
+            ..Default::default()
+        };
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary
+    }
+
+    fn symbol<'a>(summary: &'a SemanticSummary, name: &str) -> &'a SymbolInfo {
+        summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("no symbol {name}"))
+    }
+
+    #[test]
+    fn test_interface_implementations() {
+        let summary = extract_source(
+            r#"package server
+
+type Handler interface {
+    ServeHTTP(ResponseWriter, *Request)
+}
+
+type Closer interface {
+    Close() error
+}
+
+type ReadCloser interface {
+    Closer
+    Read(p []byte) (n int, err error)
+}
+
+type Server struct{}
+
+func (s *Server) ServeHTTP(w ResponseWriter, r *Request) {}
+
+type File struct{}
+
+func (f File) Read(buf []byte) (int, error) { return 0, nil }
+func (f File) Close() error { return nil }
+
+type Broken struct{}
+
+func (b *Broken) ServeHTTP(w ResponseWriter) {}
+"#,
+        );
+
+        assert_eq!(symbol(&summary, "Server").implements, vec!["Handler"]);
+        assert_eq!(
+            symbol(&summary, "File").implements,
+            vec!["Closer", "ReadCloser"]
+        );
+        // Same method name, different signature
+        assert!(symbol(&summary, "Broken").implements.is_empty());
+        assert!(symbol(&summary, "Handler").implements.is_empty());
+    }
+
+    #[test]
+    fn test_go_generate_directives() {
+        let summary = extract_source(
+            r#"package server
+
+//go:generate stringer -type=Pill
+type Pill int
+
+//go:generate go run -tags gen ./cmd/gen
+func Load() {}
+
+// go:generate with a space is an ordinary comment
+func Other() {}
+"#,
+        );
+
+        let calls = &symbol(&summary, "Pill").calls;
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "stringer");
+        assert!(calls[0].is_macro);
+
+        let load = symbol(&summary, "Load");
+        assert!(load
+            .calls
+            .iter()
+            .any(|c| c.name == "./cmd/gen" && c.is_macro));
+        assert!(!symbol(&summary, "Other").calls.iter().any(|c| c.is_macro));
+    }
+
     #[test]
     fn test_extract_filename_stem() {
         assert_eq!(extract_filename_stem("/path/to/server.go"), "server");
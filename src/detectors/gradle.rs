@@ -6,18 +6,24 @@
 
 use tree_sitter::Tree;
 
-use crate::detectors::generic::extract_with_grammar;
+use crate::detectors::generic::extract_with_grammar_at_level;
 use crate::detectors::grammar::GRADLE_GRAMMAR;
 use crate::error::Result;
+use crate::extract::ExtractionLevel;
 use crate::schema::SemanticSummary;
 
 /// Extract semantic information from a Gradle build file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // The generic extractor handles Gradle/Groovy semantics:
     // - Symbols: method_declaration, closure
     // - Imports: import_declaration
     // - State changes: variable_declaration, assignment
     // - Control flow: if_statement, for_statement, while_statement
     // - Calls: method_call_expression
-    extract_with_grammar(summary, source, tree, &GRADLE_GRAMMAR)
+    extract_with_grammar_at_level(summary, source, tree, &GRADLE_GRAMMAR, level)
 }
@@ -746,6 +746,83 @@ pub static KOTLIN_GRAMMAR: LangGrammar = LangGrammar {
     decorator_nodes: &["annotation"],
 };
 
+// =============================================================================
+// PHP Grammar
+// =============================================================================
+
+/// PHP: members without `private` or `protected` are public; classes and
+/// functions are always visible
+pub fn php_is_exported(node: &Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "visibility_modifier" {
+            return child.utf8_text(source.as_bytes()).unwrap_or("") == "public";
+        }
+    }
+    true
+}
+
+pub static PHP_GRAMMAR: LangGrammar = LangGrammar {
+    name: "php",
+    function_nodes: &["function_definition", "method_declaration"],
+    class_nodes: &["class_declaration"],
+    // Interfaces are told apart from traits in detectors/php.rs
+    interface_nodes: &["interface_declaration", "trait_declaration"],
+    enum_nodes: &["enum_declaration"],
+    control_flow_nodes: &[
+        "if_statement",
+        "switch_statement",
+        "match_expression",
+        "for_statement",
+        "foreach_statement",
+        "while_statement",
+        "do_statement",
+    ],
+    try_nodes: &["try_statement"],
+    var_declaration_nodes: &[], // PHP variables are declared by assignment
+    assignment_nodes: &[
+        "assignment_expression",
+        "augmented_assignment_expression",
+        "reference_assignment_expression",
+    ],
+    // Constants and properties name their elements, not the declaration
+    module_var_nodes: &[],
+    field_nodes: &[],
+    local_scope_nodes: &[
+        "function_definition",
+        "method_declaration",
+        "anonymous_function",
+        "arrow_function",
+        "compound_statement",
+        "for_statement",
+        "foreach_statement",
+        "while_statement",
+        "do_statement",
+        "if_statement",
+        "switch_statement",
+        "try_statement",
+    ],
+    call_nodes: &[
+        "function_call_expression",
+        "member_call_expression",
+        "nullsafe_member_call_expression",
+        "scoped_call_expression",
+        "object_creation_expression",
+    ],
+    await_nodes: &[],
+    import_nodes: &[], // `use` declarations are resolved in detectors/php.rs
+    name_field: "name",
+    value_field: "right",
+    type_field: "type",
+    body_field: "body",
+    params_field: "parameters",
+    condition_field: "condition",
+    is_exported: php_is_exported,
+    uppercase_is_export: false,
+    visibility_modifiers: &["public", "protected", "private"],
+    decorator_nodes: &["attribute_list"],
+};
+
 // =============================================================================
 // Shell/Bash Grammar
 // =============================================================================
@@ -902,6 +979,7 @@ pub fn get_grammar(lang_name: &str) -> Option<&'static LangGrammar> {
         "c" => Some(&C_GRAMMAR),
         "cpp" | "c++" | "cc" | "cxx" => Some(&CPP_GRAMMAR),
         "kotlin" | "kt" | "kts" => Some(&KOTLIN_GRAMMAR),
+        "php" => Some(&PHP_GRAMMAR),
         "bash" | "sh" | "shell" => Some(&BASH_GRAMMAR),
         "gradle" | "groovy" => Some(&GRADLE_GRAMMAR),
         "hcl" | "tf" | "terraform" => Some(&HCL_GRAMMAR),
@@ -923,6 +1001,7 @@ mod tests {
         assert!(get_grammar("typescript").is_some());
         assert!(get_grammar("c").is_some());
         assert!(get_grammar("cpp").is_some());
+        assert!(get_grammar("php").is_some());
         assert!(get_grammar("unknown").is_none());
     }
 
@@ -939,6 +1018,7 @@ mod tests {
             &TYPESCRIPT_GRAMMAR,
             &C_GRAMMAR,
             &CPP_GRAMMAR,
+            &PHP_GRAMMAR,
         ];
 
         for grammar in grammars {
@@ -175,6 +175,7 @@ fn extract_block_symbol(node: &Node, source: &str) -> Option<SymbolInfo> {
         is_default_export: false,
         hash: None,
         arguments: Vec::new(),
+        param_types: Vec::new(),
         props: Vec::new(),
         return_type: None,
         calls: Vec::new(),
@@ -186,6 +187,22 @@ fn extract_block_symbol(node: &Node, source: &str) -> Option<SymbolInfo> {
         framework_entry_point: FrameworkEntryPoint::None,
         is_async: false,
         base_classes: Vec::new(),
+        doc_comment: None,
+        feature_flags: Vec::new(),
+        generic_params: Vec::new(),
+        lifetime_params: Vec::new(),
+        implements: Vec::new(),
+        is_deprecated: false,
+        deprecation_message: None,
+        awaits_count: 0,
+        io_calls_outside_try: 0,
+        has_empty_catch: false,
+        rethrows: false,
+        panic_points: Vec::new(),
+        embedded_queries: Vec::new(),
+        partial_of: None,
+        parent_symbol: None,
+        nested_id: None,
     })
 }
 
@@ -255,6 +272,7 @@ fn extract_function_call(node: &Node, source: &str) -> Option<Call> {
                 in_try: false,
                 is_hook: false,
                 is_io: false,
+                is_macro: false,
                 ref_kind: RefKind::None,
                 location: crate::schema::Location {
                     line: node.start_position().row + 1,
@@ -310,6 +328,7 @@ fn extract_attribute_state_change(node: &Node, source: &str) -> Option<StateChan
         name: name?,
         state_type: "hcl".to_string(),
         initializer,
+        is_atomic: false,
     })
 }
 
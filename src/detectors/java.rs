@@ -3,16 +3,69 @@
 //! Extracts semantic information from Java source files using the generic extractor.
 //! Java's class/interface/enum declarations are first-class AST nodes, so the generic
 //! extractor handles them well.
+//!
+//! Spring and JPA annotations are then mapped onto the summary: request
+//! mappings and `@Scheduled` methods become framework entry points,
+//! `@Transactional` methods get an atomic state change, and `@Entity` /
+//! `@Repository` types get their own symbol kinds.
+
+use std::collections::HashSet;
 
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
-use crate::detectors::generic::extract_with_grammar;
+use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
+use crate::detectors::generic::{
+    compress_initializer, extract_with_grammar_at_level, state_change_for_node,
+};
 use crate::detectors::grammar::JAVA_GRAMMAR;
 use crate::error::Result;
-use crate::schema::SemanticSummary;
+use crate::extract::ExtractionLevel;
+use crate::schema::{FrameworkEntryPoint, ScheduleEntry, SemanticSummary, StateChange, SymbolKind};
+
+/// Mutating methods of `java.util.concurrent.atomic` types
+const ATOMIC_METHODS: &[&str] = &[
+    "set",
+    "lazySet",
+    "setPlain",
+    "setRelease",
+    "getAndSet",
+    "compareAndSet",
+    "weakCompareAndSet",
+    "compareAndExchange",
+    "incrementAndGet",
+    "decrementAndGet",
+    "getAndIncrement",
+    "getAndDecrement",
+    "addAndGet",
+    "getAndAdd",
+    "updateAndGet",
+    "getAndUpdate",
+    "accumulateAndGet",
+    "getAndAccumulate",
+    "increment",
+    "decrement",
+    "add",
+    "accumulate",
+    "reset",
+];
+
+/// Spring MVC annotations mapping a method to HTTP requests
+const SPRING_MAPPINGS: &[&str] = &[
+    "RequestMapping",
+    "GetMapping",
+    "PostMapping",
+    "PutMapping",
+    "DeleteMapping",
+    "PatchMapping",
+];
 
 /// Extract semantic information from a Java source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // The generic extractor handles everything for Java:
     // - Symbols: class_declaration, interface_declaration, enum_declaration, method_declaration
     // - Imports: import_declaration
@@ -20,5 +73,426 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Resu
     // - Control flow: if, for, enhanced_for, while, do, switch, try
     // - Calls: method_invocation
     // - Risk calculation
-    extract_with_grammar(summary, source, tree, &JAVA_GRAMMAR)
+    extract_with_grammar_at_level(summary, source, tree, &JAVA_GRAMMAR, level)?;
+    apply_spring_annotations(summary, &tree.root_node(), source);
+    if level == ExtractionLevel::Full {
+        mark_atomic_state(summary, &tree.root_node(), source);
+        mark_transactional(summary, &tree.root_node(), source);
+    }
+    Ok(())
+}
+
+/// Map Spring and JPA annotations onto symbols
+///
+/// Request mappings and `@Scheduled` methods are invoked by Spring, so they
+/// become entry points rather than dead code; `@Scheduled` methods are also
+/// listed in `summary.schedules`. `@Entity` and `@Repository` types get the
+/// `entity` and `repository` symbol kinds.
+fn apply_spring_annotations(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut handlers = 0;
+    for symbol in &mut summary.symbols {
+        let annotated = |names: &[&str]| {
+            symbol
+                .decorators
+                .iter()
+                .any(|d| names.contains(&simple_name(d)))
+        };
+        let is_type = matches!(
+            symbol.kind,
+            SymbolKind::Class | SymbolKind::Interface | SymbolKind::Trait
+        );
+        if is_type && annotated(&["Entity"]) {
+            symbol.kind = SymbolKind::Entity;
+        } else if is_type && annotated(&["Repository"]) {
+            symbol.kind = SymbolKind::Repository;
+        } else if !is_type && annotated(SPRING_MAPPINGS) {
+            symbol.framework_entry_point = FrameworkEntryPoint::SpringRoute;
+            handlers += 1;
+        } else if !is_type && annotated(&["Scheduled"]) {
+            symbol.framework_entry_point = FrameworkEntryPoint::SpringScheduled;
+        }
+    }
+
+    visit_all(root, |node| {
+        if node.kind() != "method_declaration" {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        for annotation in annotations(node, source) {
+            if simple_name(&annotation.name) == "Scheduled" {
+                summary.schedules.push(ScheduleEntry {
+                    handler: get_node_text(&name, source),
+                    trigger: annotation
+                        .arguments
+                        .trim_start_matches('(')
+                        .trim_end_matches(')')
+                        .to_string(),
+                    line: annotation.line,
+                });
+            }
+        }
+    });
+
+    if handlers > 0 {
+        summary.framework_entry_point = FrameworkEntryPoint::SpringRoute;
+        push_unique_insertion(
+            &mut summary.insertions,
+            format!("{} Spring request handlers", handlers),
+            "Spring request",
+        );
+    } else if !summary.schedules.is_empty() && summary.framework_entry_point.is_none() {
+        summary.framework_entry_point = FrameworkEntryPoint::SpringScheduled;
+    }
+}
+
+/// Record `@Transactional` methods as atomic state changes
+///
+/// Everything the method writes commits or rolls back together, so the
+/// method (and the file) gets a `@Transactional` state change of type
+/// `transaction`, with the annotation's arguments as its initializer.
+fn mark_transactional(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut transactional = Vec::new();
+    visit_all(root, |node| {
+        if node.kind() != "method_declaration" {
+            return;
+        }
+        for annotation in annotations(node, source) {
+            if simple_name(&annotation.name) == "Transactional" {
+                transactional.push((node.start_position().row + 1, annotation.arguments));
+            }
+        }
+    });
+
+    for (line, arguments) in transactional {
+        let change = StateChange {
+            name: "@Transactional".to_string(),
+            state_type: "transaction".to_string(),
+            initializer: compress_initializer(&arguments),
+            is_atomic: true,
+        };
+        if let Some(symbol) = summary
+            .symbols
+            .iter_mut()
+            .find(|s| s.start_line <= line && line <= s.end_line && s.kind == SymbolKind::Function)
+        {
+            symbol.state_changes.push(change.clone());
+        }
+        summary.state_changes.push(change);
+    }
+}
+
+/// An annotation on a declaration
+struct Annotation {
+    /// Name as written (`Scheduled`, `org.springframework.stereotype.Repository`)
+    name: String,
+    /// Arguments with their parentheses; empty for marker annotations
+    arguments: String,
+    /// Line of the annotation (1-indexed)
+    line: usize,
+}
+
+/// Annotations in a declaration's `modifiers`
+///
+/// Covers both `marker_annotation` (`@Entity`) and `annotation`
+/// (`@Scheduled(cron = "...")`) nodes.
+fn annotations(node: &Node, source: &str) -> Vec<Annotation> {
+    let mut cursor = node.walk();
+    let Some(modifiers) = node.children(&mut cursor).find(|c| c.kind() == "modifiers") else {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    modifiers
+        .children(&mut cursor)
+        .filter(|a| matches!(a.kind(), "annotation" | "marker_annotation"))
+        .filter_map(|a| {
+            Some(Annotation {
+                name: get_node_text(&a.child_by_field_name("name")?, source),
+                arguments: a
+                    .child_by_field_name("arguments")
+                    .map(|args| get_node_text(&args, source))
+                    .unwrap_or_default(),
+                line: a.start_position().row + 1,
+            })
+        })
+        .collect()
+}
+
+/// `Entity` for both `Entity` and `jakarta.persistence.Entity`
+fn simple_name(annotation: &str) -> &str {
+    annotation.rsplit('.').next().unwrap_or(annotation)
+}
+
+/// Flag synchronized state changes and record atomic operations
+///
+/// Declarations and assignments inside a `synchronized` block or method, and
+/// declarations of `java.util.concurrent.atomic` types, are atomic. Mutating
+/// calls on those variables (`hits.incrementAndGet()`) aren't assignments, so
+/// they're added as state changes of type `atomic`.
+fn mark_atomic_state(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut atomic_vars: HashSet<String> = HashSet::new();
+    visit_all(root, |node| {
+        if !matches!(
+            node.kind(),
+            "field_declaration" | "local_variable_declaration"
+        ) {
+            return;
+        }
+        let is_atomic_type = node
+            .child_by_field_name("type")
+            .is_some_and(|t| is_atomic_type(&get_node_text(&t, source)));
+        if !is_atomic_type {
+            return;
+        }
+        let mut cursor = node.walk();
+        for declarator in node.children_by_field_name("declarator", &mut cursor) {
+            if let Some(name) = declarator.child_by_field_name("name") {
+                atomic_vars.insert(get_node_text(&name, source));
+            }
+        }
+    });
+
+    let mut index = 0;
+    let mut atomic_ops = Vec::new();
+    visit_all(root, |node| {
+        if node.kind() == "method_invocation" {
+            if let Some(op) = atomic_operation(node, source, &atomic_vars) {
+                atomic_ops.push(op);
+            }
+            return;
+        }
+        if state_change_for_node(node, source, &JAVA_GRAMMAR).is_none() {
+            return;
+        }
+        let is_atomic_type = node
+            .child_by_field_name("type")
+            .is_some_and(|t| is_atomic_type(&get_node_text(&t, source)));
+        if let Some(change) = summary.state_changes.get_mut(index) {
+            change.is_atomic = is_atomic_type || is_synchronized(node, source);
+        }
+        index += 1;
+    });
+
+    summary.state_changes.extend(atomic_ops);
+}
+
+/// `AtomicInteger`, `AtomicReference<T>`, `LongAdder`, or a qualified
+/// `java.util.concurrent.atomic` type
+fn is_atomic_type(type_text: &str) -> bool {
+    let simple = type_text
+        .split('<')
+        .next()
+        .unwrap_or(type_text)
+        .rsplit('.')
+        .next()
+        .unwrap_or(type_text);
+    simple.starts_with("Atomic")
+        || matches!(
+            simple,
+            "LongAdder" | "DoubleAdder" | "LongAccumulator" | "DoubleAccumulator"
+        )
+        || type_text.contains("java.util.concurrent.atomic.")
+}
+
+/// Inside a `synchronized` block, or a method declared `synchronized`
+fn is_synchronized(node: &Node, source: &str) -> bool {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        match n.kind() {
+            "synchronized_statement" => return true,
+            "method_declaration" | "constructor_declaration" => {
+                let mut cursor = n.walk();
+                let modifiers = n
+                    .children(&mut cursor)
+                    .find(|c| c.kind() == "modifiers")
+                    .map(|m| get_node_text(&m, source))
+                    .unwrap_or_default();
+                return modifiers.split_whitespace().any(|m| m == "synchronized");
+            }
+            // Lambdas and nested classes may run outside the lock
+            "lambda_expression" | "class_body" => return false,
+            _ => {}
+        }
+        current = n.parent();
+    }
+    false
+}
+
+/// `hits.incrementAndGet()` on a known atomic variable as a state change
+fn atomic_operation(
+    node: &Node,
+    source: &str,
+    atomic_vars: &HashSet<String>,
+) -> Option<StateChange> {
+    let method = get_node_text(&node.child_by_field_name("name")?, source);
+    if !ATOMIC_METHODS.contains(&method.as_str()) {
+        return None;
+    }
+    let object = get_node_text(&node.child_by_field_name("object")?, source);
+    if !atomic_vars.contains(object.trim_start_matches("this.")) {
+        return None;
+    }
+    let arguments = node
+        .child_by_field_name("arguments")
+        .map(|a| get_node_text(&a, source))
+        .unwrap_or_default();
+    Some(StateChange {
+        name: object,
+        state_type: "atomic".to_string(),
+        initializer: compress_initializer(&format!("{}{}", method, arguments)),
+        is_atomic: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract_java(source: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary
+    }
+
+    #[test]
+    fn test_spring_annotations() {
+        let summary = extract_java(
+            r#"
+@RestController
+@RequestMapping("/users")
+public class UserController {
+    @GetMapping("/{id}")
+    public User get(Long id) { return repo.findById(id); }
+
+    @Transactional(readOnly = false)
+    @PostMapping
+    public User create(User user) { return repo.save(user); }
+
+    @Scheduled(cron = "0 0 * * * *")
+    public void cleanup() { repo.deleteStale(); }
+
+    private void helper() {}
+}
+
+@jakarta.persistence.Entity
+class User {}
+
+@Repository
+interface UserRepository {}
+"#,
+        );
+        let symbol = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(symbol("get").decorators, vec!["GetMapping"]);
+        assert_eq!(
+            symbol("create").decorators,
+            vec!["Transactional", "PostMapping"]
+        );
+        assert_eq!(
+            symbol("get").framework_entry_point,
+            FrameworkEntryPoint::SpringRoute
+        );
+        assert_eq!(
+            symbol("create").framework_entry_point,
+            FrameworkEntryPoint::SpringRoute
+        );
+        assert_eq!(
+            symbol("cleanup").framework_entry_point,
+            FrameworkEntryPoint::SpringScheduled
+        );
+        assert!(symbol("helper").framework_entry_point.is_none());
+        assert_eq!(symbol("UserController").kind, SymbolKind::Class);
+        assert_eq!(symbol("User").kind, SymbolKind::Entity);
+        assert_eq!(symbol("UserRepository").kind, SymbolKind::Repository);
+        assert_eq!(
+            summary.framework_entry_point,
+            FrameworkEntryPoint::SpringRoute
+        );
+
+        assert_eq!(
+            summary.schedules,
+            vec![ScheduleEntry {
+                handler: "cleanup".to_string(),
+                trigger: "cron = \"0 0 * * * *\"".to_string(),
+                line: 12,
+            }]
+        );
+
+        let transaction = StateChange {
+            name: "@Transactional".to_string(),
+            state_type: "transaction".to_string(),
+            initializer: "(readOnly = false)".to_string(),
+            is_atomic: true,
+        };
+        assert_eq!(symbol("create").state_changes, vec![transaction.clone()]);
+        assert!(summary.state_changes.contains(&transaction));
+        assert!(symbol("get").state_changes.is_empty());
+    }
+
+    #[test]
+    fn test_mark_atomic_state() {
+        let source = r#"
+import java.util.concurrent.atomic.AtomicInteger;
+
+class Counter {
+    private final AtomicInteger hits = new AtomicInteger();
+    private int total;
+    private int misses;
+    private final java.util.List<Integer> items = new java.util.ArrayList<>();
+
+    void record() {
+        hits.incrementAndGet();
+        items.add(1);
+        synchronized (this) {
+            total = total + 1;
+        }
+        misses = misses + 1;
+    }
+
+    synchronized void reset() {
+        misses = 0;
+    }
+}
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+
+        let flags = |name: &str| -> Vec<bool> {
+            summary
+                .state_changes
+                .iter()
+                .filter(|s| s.name == name)
+                .map(|s| s.is_atomic)
+                .collect()
+        };
+        assert_eq!(flags("total"), vec![false, true]);
+        assert_eq!(flags("misses"), vec![false, false, true]);
+        assert!(
+            flags("hits").iter().all(|a| *a),
+            "{:?}",
+            summary.state_changes
+        );
+        assert!(
+            summary
+                .state_changes
+                .iter()
+                .any(|s| s.name == "hits" && s.state_type == "atomic"),
+            "incrementAndGet not recorded"
+        );
+        assert!(
+            flags("items").iter().all(|a| !a),
+            "List.add recorded as atomic"
+        );
+    }
 }
@@ -10,10 +10,12 @@ use crate::detectors::common::{
     find_containing_symbol_by_line, get_node_text, visit_all, visit_with_nesting_depth,
 };
 use crate::error::Result;
+use crate::extract::ExtractionLevel;
 use crate::lang::Lang;
 use crate::schema::{
-    Argument, Call, ControlFlowChange, ControlFlowKind, FrameworkEntryPoint, Location, Prop,
-    RefKind, RiskLevel, SemanticSummary, SymbolInfo, SymbolKind,
+    anonymous_symbol_name, Argument, Call, ControlFlowChange, ControlFlowKind, FrameworkEntryPoint,
+    Import, ImportKind, ImportedName, Location, Prop, RefKind, RiskLevel, SemanticSummary,
+    SymbolInfo, SymbolKind,
 };
 use crate::toon::is_meaningful_call;
 
@@ -33,6 +35,7 @@ pub fn extract_core(
     root: &Node,
     source: &str,
     lang: Lang,
+    level: ExtractionLevel,
 ) -> Result<()> {
     // Extract symbols (functions, classes, exports)
     find_primary_symbol(summary, root, source, lang);
@@ -40,6 +43,11 @@ pub fn extract_core(
     // Extract imports
     extract_imports(summary, root, source);
 
+    // Signature-level summaries stop here
+    if level == ExtractionLevel::Signatures {
+        return Ok(());
+    }
+
     // Extract control flow
     extract_control_flow(summary, root);
 
@@ -85,7 +93,16 @@ fn find_primary_symbol(summary: &mut SemanticSummary, root: &Node, source: &str,
     let mut candidates: Vec<SymbolCandidate> = Vec::new();
     let filename_stem = extract_filename_stem(&summary.file);
 
-    collect_symbol_candidates(root, source, &filename_stem, lang, &mut candidates);
+    let default_name = default_export_name(&summary.file);
+
+    collect_symbol_candidates(
+        root,
+        source,
+        &filename_stem,
+        &default_name,
+        lang,
+        &mut candidates,
+    );
 
     // Sort by score (highest first)
     candidates.sort_by(|a, b| b.score.cmp(&a.score));
@@ -108,6 +125,7 @@ fn find_primary_symbol(summary: &mut SemanticSummary, root: &Node, source: &str,
                 is_default_export: candidate.is_default_export,
                 hash: None,
                 arguments: candidate.arguments.clone(),
+                param_types: Vec::new(),
                 props: candidate.props.clone(),
                 return_type: if candidate.returns_jsx {
                     Some("JSX.Element".to_string())
@@ -123,6 +141,22 @@ fn find_primary_symbol(summary: &mut SemanticSummary, root: &Node, source: &str,
                 framework_entry_point: FrameworkEntryPoint::None,
                 is_async: candidate.is_async,
                 base_classes: candidate.extends_classes.clone(),
+                doc_comment: None,
+                feature_flags: Vec::new(),
+                generic_params: Vec::new(),
+                lifetime_params: Vec::new(),
+                implements: Vec::new(),
+                is_deprecated: false,
+                deprecation_message: None,
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: Vec::new(),
+                embedded_queries: Vec::new(),
+                partial_of: None,
+                parent_symbol: None,
+                nested_id: None,
             };
 
             summary.symbols.push(symbol_info);
@@ -147,6 +181,136 @@ fn find_primary_symbol(summary: &mut SemanticSummary, root: &Node, source: &str,
             summary.return_type = Some("JSX.Element".to_string());
         }
     }
+
+    collect_nested_symbols(summary, root, source);
+}
+
+/// Function node kinds a closure or callback can be
+const NESTED_FUNCTION_KINDS: &[&str] = &[
+    "arrow_function",
+    "function_expression",
+    "function",
+    "generator_function",
+    "function_declaration",
+    "generator_function_declaration",
+];
+
+/// Node kinds whose body other functions can be nested in
+const FUNCTION_SCOPE_KINDS: &[&str] = &[
+    "arrow_function",
+    "function_expression",
+    "function",
+    "generator_function",
+    "function_declaration",
+    "generator_function_declaration",
+    "method_definition",
+];
+
+/// Add closures, callbacks and nested functions with at least
+/// [`nested::min_statements`] statements as symbols of their own
+///
+/// A function counts when it is defined inside another function or passed as
+/// a call argument (`app.post('/x', async (req, res) => {...})`). Components
+/// wrapped in `forwardRef`/`memo` are the wrapper's own symbol and are skipped.
+///
+/// [`nested::min_statements`]: crate::detectors::nested::min_statements
+fn collect_nested_symbols(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let min_statements = crate::detectors::nested::min_statements();
+
+    visit_all(root, |node| {
+        if !NESTED_FUNCTION_KINDS.contains(&node.kind()) {
+            return;
+        }
+        let is_callback = node
+            .parent()
+            .is_some_and(|p| p.kind() == "arguments" && !is_component_wrapper_call(&p, source));
+        if !is_callback && !has_function_ancestor(node) {
+            return;
+        }
+        let Some(body) = node.child_by_field_name("body") else {
+            return;
+        };
+        if body.kind() != "statement_block" || count_statements(&body) < min_statements {
+            return;
+        }
+
+        let start_line = node.start_position().row + 1;
+        let name =
+            nested_function_name(node, source).unwrap_or_else(|| anonymous_symbol_name(start_line));
+
+        let mut arguments = Vec::new();
+        let mut props = Vec::new();
+        if let Some(params) = node.child_by_field_name("parameters") {
+            extract_parameters(&params, source, &mut arguments, &mut props);
+        } else if let Some(param) = node.child_by_field_name("parameter") {
+            arguments.push(Argument {
+                name: get_node_text(&param, source),
+                arg_type: None,
+                default_value: None,
+            });
+        }
+
+        summary.symbols.push(SymbolInfo {
+            name,
+            kind: SymbolKind::Function,
+            start_line,
+            end_line: node.end_position().row + 1,
+            arguments,
+            props,
+            is_async: crate::detectors::generic::is_async_node(node),
+            ..Default::default()
+        });
+    });
+}
+
+/// Whether `node` is inside the body of another function or method
+fn has_function_ancestor(node: &Node) -> bool {
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        if FUNCTION_SCOPE_KINDS.contains(&p.kind()) {
+            return true;
+        }
+        parent = p.parent();
+    }
+    false
+}
+
+/// Whether `arguments` belongs to a `forwardRef(...)`/`memo(...)` call
+fn is_component_wrapper_call(arguments: &Node, source: &str) -> bool {
+    arguments
+        .parent()
+        .and_then(|call| call.child_by_field_name("function"))
+        .is_some_and(|func| {
+            let text = get_node_text(&func, source);
+            text == "forwardRef"
+                || text == "memo"
+                || text.ends_with(".forwardRef")
+                || text.ends_with(".memo")
+        })
+}
+
+/// Number of statements directly in a `statement_block`
+fn count_statements(block: &Node) -> usize {
+    let mut cursor = block.walk();
+    block
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() != "comment")
+        .count()
+}
+
+/// Name a nested function is declared or assigned under, if any
+fn nested_function_name(node: &Node, source: &str) -> Option<String> {
+    if let Some(name) = node.child_by_field_name("name") {
+        return Some(get_node_text(&name, source));
+    }
+    let parent = node.parent()?;
+    let name = match parent.kind() {
+        "variable_declarator" => parent.child_by_field_name("name")?,
+        "pair" => parent.child_by_field_name("key")?,
+        "assignment_expression" => parent.child_by_field_name("left")?,
+        _ => return None,
+    };
+    Some(get_node_text(&name, source))
 }
 
 // Re-export shared extract_filename_stem for backwards compatibility
@@ -157,6 +321,7 @@ fn collect_symbol_candidates(
     root: &Node,
     source: &str,
     filename_stem: &str,
+    default_name: &str,
     lang: Lang,
     candidates: &mut Vec<SymbolCandidate>,
 ) {
@@ -193,6 +358,7 @@ fn collect_symbol_candidates(
                         let mut inner_cursor = child.walk();
                         for inner in child.children(&mut inner_cursor) {
                             if inner.kind() == "function_declaration"
+                                || inner.kind() == "generator_function_declaration"
                                 || inner.kind() == "class_declaration"
                             {
                                 if let Some(mut candidate) = extract_candidate_from_declaration(
@@ -209,6 +375,24 @@ fn collect_symbol_candidates(
                                 }
                                 break;
                             }
+                            // Handle: export default () => {} or export default function() {}
+                            if is_default
+                                && matches!(
+                                    inner.kind(),
+                                    "arrow_function"
+                                        | "function_expression"
+                                        | "generator_function"
+                                        | "class"
+                                )
+                            {
+                                let mut candidate =
+                                    extract_anonymous_default(&inner, source, default_name, lang);
+                                candidate.is_exported = true;
+                                candidate.is_default_export = true;
+                                candidate.score = calculate_symbol_score(&candidate, filename_stem);
+                                candidates.push(candidate);
+                                break;
+                            }
                             // Handle: export default memo(Component) or export default forwardRef(...)
                             if inner.kind() == "call_expression" && is_default {
                                 if let Some(candidate) =
@@ -262,7 +446,10 @@ fn collect_symbol_candidates(
                     }
                 }
             }
-            "function_declaration" | "class_declaration" | "lexical_declaration" => {
+            "function_declaration"
+            | "generator_function_declaration"
+            | "class_declaration"
+            | "lexical_declaration" => {
                 if let Some(mut candidate) =
                     extract_candidate_from_declaration(&child, source, filename_stem, lang)
                 {
@@ -483,6 +670,82 @@ pub fn extract_default_export_call(
     None
 }
 
+/// Extract a symbol from an anonymous default export
+///
+/// Handles: `export default () => {}`, `export default function() {}`,
+/// `export default class {}`. The symbol takes its own name if the
+/// expression has one, otherwise `default_name`.
+fn extract_anonymous_default(
+    node: &Node,
+    source: &str,
+    default_name: &str,
+    lang: Lang,
+) -> SymbolCandidate {
+    let name = node
+        .child_by_field_name("name")
+        .map(|n| get_node_text(&n, source))
+        .unwrap_or_else(|| default_name.to_string());
+
+    if node.kind() == "class" {
+        return SymbolCandidate {
+            name,
+            kind: SymbolKind::Class,
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            extends_classes: extract_js_base_classes(node, source),
+            ..Default::default()
+        };
+    }
+
+    let mut arguments = Vec::new();
+    let mut props = Vec::new();
+    if let Some(params) = node.child_by_field_name("parameters") {
+        extract_parameters(&params, source, &mut arguments, &mut props);
+    } else if let Some(param) = node.child_by_field_name("parameter") {
+        arguments.push(Argument {
+            name: get_node_text(&param, source),
+            arg_type: None,
+            default_value: None,
+        });
+    }
+
+    SymbolCandidate {
+        name,
+        kind: SymbolKind::Function,
+        returns_jsx: lang.supports_jsx() && returns_jsx(node),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        arguments,
+        props,
+        is_async: crate::detectors::generic::is_async_node(node),
+        ..Default::default()
+    }
+}
+
+/// Name for an anonymous default export, derived from its file
+///
+/// `homepage.tsx` → `Homepage`, `user-profile.ts` → `UserProfile`. Index
+/// files take their directory's name (`Button/index.tsx` → `Button`).
+pub fn default_export_name(file_path: &str) -> String {
+    let path = std::path::Path::new(file_path);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let stem = if stem == "index" {
+        path.parent()
+            .and_then(|p| p.file_name())
+            .and_then(|s| s.to_str())
+            .unwrap_or(stem)
+    } else {
+        stem
+    };
+
+    let name = to_pascal_case(stem);
+    if name.is_empty() {
+        "default".to_string()
+    } else {
+        name
+    }
+}
+
 /// Convert string to PascalCase for component naming
 pub fn to_pascal_case(s: &str) -> String {
     s.split(|c: char| c == '-' || c == '_' || c == '.')
@@ -505,7 +768,7 @@ fn extract_candidate_from_declaration(
     lang: Lang,
 ) -> Option<SymbolCandidate> {
     match node.kind() {
-        "function_declaration" => {
+        "function_declaration" | "generator_function_declaration" => {
             let name_node = node.child_by_field_name("name")?;
             let name = get_node_text(&name_node, source);
 
@@ -849,6 +1112,111 @@ pub fn extract_imports(summary: &mut SemanticSummary, root: &Node, source: &str)
             }
         }
     }
+
+    extract_import_records(summary, root, source);
+}
+
+/// Record import statements with their names for module usage tracking
+///
+/// Covers `import ... from`, re-exports (`export { x } from`, `export * from`),
+/// `require('...')` and dynamic `import('...')`.
+fn extract_import_records(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        let kind = match child.kind() {
+            "import_statement" => ImportKind::Static,
+            "export_statement" => ImportKind::ReExport,
+            _ => continue,
+        };
+        let Some(module) = child.child_by_field_name("source") else {
+            continue;
+        };
+        let mut import = Import {
+            source: unquote(&get_node_text(&module, source)),
+            kind,
+            ..Default::default()
+        };
+        collect_imported_names(&child, source, &mut import);
+        summary.imports.push(import);
+    }
+
+    visit_all(root, |node| {
+        if node.kind() != "call_expression" {
+            return;
+        }
+        let Some(function) = node.child_by_field_name("function") else {
+            return;
+        };
+        let kind = match function.kind() {
+            "import" => ImportKind::Dynamic,
+            "identifier" if get_node_text(&function, source) == "require" => ImportKind::Static,
+            _ => return,
+        };
+        let Some(arg) = node
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0))
+        else {
+            return;
+        };
+        let module = match arg.kind() {
+            "string" => unquote(&get_node_text(&arg, source)),
+            // import(`./pages/${name}`): keep the literal prefix
+            "template_string" if kind == ImportKind::Dynamic => {
+                let text = get_node_text(&arg, source);
+                let text = text.trim_matches('`');
+                match text.split_once("${") {
+                    Some((prefix, _)) => format!("{}*", prefix),
+                    None => text.to_string(),
+                }
+            }
+            _ => return,
+        };
+        summary.imports.push(Import {
+            source: module,
+            is_namespace: true,
+            kind,
+            ..Default::default()
+        });
+    });
+}
+
+/// Fill in the names bound or re-exported by an import/export statement
+fn collect_imported_names(statement: &Node, source: &str, import: &mut Import) {
+    // `export * from './x'` has no clause
+    if import.kind == ImportKind::ReExport {
+        let mut cursor = statement.walk();
+        import.is_namespace = !statement
+            .named_children(&mut cursor)
+            .any(|c| c.kind() == "export_clause");
+    }
+
+    visit_all(statement, |node| match node.kind() {
+        "import_specifier" | "export_specifier" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                import.names.push(ImportedName {
+                    name: get_node_text(&name, source),
+                    alias: node
+                        .child_by_field_name("alias")
+                        .map(|a| get_node_text(&a, source)),
+                });
+            }
+        }
+        "namespace_import" | "namespace_export" => import.is_namespace = true,
+        "identifier" if node.parent().is_some_and(|p| p.kind() == "import_clause") => {
+            import.is_default = true;
+            import.names.push(ImportedName {
+                name: "default".to_string(),
+                alias: Some(get_node_text(node, source)),
+            });
+        }
+        _ => {}
+    });
+}
+
+/// Strip the quotes from a string literal
+fn unquote(text: &str) -> String {
+    text.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
 }
 
 /// Check if an import path is local (starts with . or ..)
@@ -956,7 +1324,7 @@ const JS_CONTROL_FLOW_KINDS: &[&str] = &[
 /// and attribute them to symbols based on line ranges
 pub fn extract_control_flow(summary: &mut SemanticSummary, root: &Node) {
     // Collect all control flow items with their line numbers
-    let mut all_cf: Vec<(ControlFlowChange, usize)> = Vec::new();
+    let mut all_cf: Vec<(ControlFlowChange, usize, Option<usize>)> = Vec::new();
 
     visit_with_nesting_depth(
         root,
@@ -967,6 +1335,8 @@ pub fn extract_control_flow(summary: &mut SemanticSummary, root: &Node) {
                 "while_statement" => Some(ControlFlowKind::While),
                 "switch_statement" => Some(ControlFlowKind::Switch),
                 "try_statement" => Some(ControlFlowKind::Try),
+                "await_expression" => Some(ControlFlowKind::Await),
+                "yield_expression" => Some(ControlFlowKind::Yield),
                 _ => None,
             };
 
@@ -978,7 +1348,7 @@ pub fn extract_control_flow(summary: &mut SemanticSummary, root: &Node) {
                     location: Location::new(line, node.start_position().column),
                     nesting_depth: nesting,
                 };
-                all_cf.push((cf, line));
+                all_cf.push((cf, line, callback_span_end(node)));
             }
         },
         JS_CONTROL_FLOW_KINDS,
@@ -989,8 +1359,8 @@ pub fn extract_control_flow(summary: &mut SemanticSummary, root: &Node) {
         std::collections::HashMap::new();
     let mut file_level_cf: Vec<ControlFlowChange> = Vec::new();
 
-    for (cf, line) in all_cf {
-        if let Some(symbol_idx) = find_containing_symbol_by_line(line, &summary.symbols) {
+    for (cf, line, end_line) in all_cf {
+        if let Some(symbol_idx) = find_owning_symbol(line, end_line, &summary.symbols) {
             cf_by_symbol.entry(symbol_idx).or_default().push(cf);
         } else {
             // Control flow is at file level (not inside any symbol)
@@ -1024,7 +1394,7 @@ pub fn extract_calls(summary: &mut SemanticSummary, root: &Node, source: &str) {
     });
 
     // Collect all calls first
-    let mut all_calls: Vec<(Call, usize)> = Vec::new(); // (call, line_number)
+    let mut all_calls: Vec<(Call, usize, Option<usize>)> = Vec::new(); // (call, line_number, callback span)
 
     visit_all(root, |node| {
         if node.kind() == "call_expression" {
@@ -1059,11 +1429,12 @@ pub fn extract_calls(summary: &mut SemanticSummary, root: &Node, source: &str) {
                     in_try,
                     is_hook: false,
                     is_io,
+                    is_macro: false,
                     ref_kind: RefKind::None,
                     location: Location::new(line, node.start_position().column),
                 };
 
-                all_calls.push((call, line));
+                all_calls.push((call, line, callback_span_end(node)));
             }
         }
     });
@@ -1073,8 +1444,8 @@ pub fn extract_calls(summary: &mut SemanticSummary, root: &Node, source: &str) {
         std::collections::HashMap::new();
     let mut file_level_calls: Vec<Call> = Vec::new();
 
-    for (call, line) in all_calls {
-        if let Some(symbol_idx) = find_containing_symbol_by_line(line, &summary.symbols) {
+    for (call, line, end_line) in all_calls {
+        if let Some(symbol_idx) = find_owning_symbol(line, end_line, &summary.symbols) {
             calls_by_symbol.entry(symbol_idx).or_default().push(call);
         } else {
             // Call is at file level (not inside any symbol)
@@ -1093,6 +1464,43 @@ pub fn extract_calls(summary: &mut SemanticSummary, root: &Node, source: &str) {
     summary.calls = file_level_calls;
 }
 
+/// Last line of `node` if a function starts inside it on its first line
+/// (`app.post('/x', async (req, res) => {`), so that function's symbol is not
+/// mistaken for the one `node` belongs to
+fn callback_span_end(node: &Node) -> Option<usize> {
+    fn hosts_function(node: &Node, row: usize) -> bool {
+        let mut cursor = node.walk();
+        let hosts = node.children(&mut cursor).any(|child| {
+            child.start_position().row == row
+                && (NESTED_FUNCTION_KINDS.contains(&child.kind()) || hosts_function(&child, row))
+        });
+        hosts
+    }
+
+    let row = node.start_position().row;
+    (node.end_position().row > row && hosts_function(node, row))
+        .then(|| node.end_position().row + 1)
+}
+
+/// Innermost symbol containing `line`, skipping symbols that start on it and
+/// end by `span_end` (see [`callback_span_end`])
+fn find_owning_symbol(
+    line: usize,
+    span_end: Option<usize>,
+    symbols: &[SymbolInfo],
+) -> Option<usize> {
+    let Some(span_end) = span_end else {
+        return find_containing_symbol_by_line(line, symbols);
+    };
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.start_line <= line && s.end_line >= line)
+        .filter(|(_, s)| !(s.start_line == line && s.end_line <= span_end))
+        .min_by_key(|(_, s)| s.end_line - s.start_line)
+        .map(|(idx, _)| idx)
+}
+
 /// Extract call name and object
 fn extract_call_name(func_node: &Node, source: &str) -> (String, Option<String>) {
     match func_node.kind() {
@@ -1161,6 +1569,66 @@ mod tests {
         assert_eq!(to_pascal_case("button"), "Button");
     }
 
+    #[test]
+    fn test_default_export_name() {
+        assert_eq!(default_export_name("src/pages/homepage.tsx"), "Homepage");
+        assert_eq!(default_export_name("src/user-profile.ts"), "UserProfile");
+        assert_eq!(
+            default_export_name("src/components/Button/index.tsx"),
+            "Button"
+        );
+        assert_eq!(default_export_name("index.js"), "Index");
+    }
+
+    #[test]
+    fn test_anonymous_default_exports() {
+        let cases = [
+            (
+                "/app/pages/homepage.tsx",
+                "export default () => {\n    return <div>Home</div>;\n};\n",
+                "Homepage",
+                SymbolKind::Component,
+            ),
+            (
+                "/app/api/user-profile.ts",
+                "export default async function (req, res) {\n    res.send(await load(req));\n}\n",
+                "UserProfile",
+                SymbolKind::Function,
+            ),
+            (
+                "/app/models/account.js",
+                "export default class extends Base {}\n",
+                "Account",
+                SymbolKind::Class,
+            ),
+        ];
+
+        for (file, source, name, kind) in cases {
+            let lang = Lang::from_path(std::path::Path::new(file)).unwrap();
+            let tree = parse_source(source, lang);
+            let summary = extract(&PathBuf::from(file), source, &tree, lang).unwrap();
+
+            assert_eq!(summary.symbol.as_deref(), Some(name), "{file}");
+            let symbol = summary.symbols.iter().find(|s| s.name == name).unwrap();
+            assert_eq!(symbol.kind, kind, "{file}");
+            assert!(symbol.is_exported && symbol.is_default_export, "{file}");
+        }
+    }
+
+    #[test]
+    fn test_anonymous_default_export_arguments() {
+        let source = "export default async function (req, res) {\n    res.send('ok');\n}\n";
+        let tree = parse_source(source, Lang::JavaScript);
+        let path = PathBuf::from("/api/health.js");
+        let summary = extract(&path, source, &tree, Lang::JavaScript).unwrap();
+
+        let symbol = &summary.symbols[0];
+        assert_eq!(symbol.name, "Health");
+        assert!(symbol.is_async);
+        let args: Vec<_> = symbol.arguments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(args, ["req", "res"]);
+    }
+
     // ==========================================================================
     // Call Attribution Tests
     // ==========================================================================
@@ -1217,6 +1685,69 @@ export function processData(data) {
         );
     }
 
+    /// Test that callbacks and nested functions get their own calls
+    #[test]
+    fn test_nested_call_attribution() {
+        let source = r#"
+app.post('/users', async (req, res) => {
+    const user = await createUser(req.body);
+    notifyTeam(user);
+    res.json(user);
+});
+
+export function register(router) {
+    loadRoutes();
+    router.get('/health', function health(req, res) {
+        const status = checkHealth();
+        recordMetric(status);
+        res.json(status);
+    });
+    schedule(() => cleanupRoutes());
+}
+"#;
+        let tree = parse_source(source, Lang::JavaScript);
+        let path = PathBuf::from("/test/routes.js");
+        let summary = extract(&path, source, &tree, Lang::JavaScript).unwrap();
+
+        let names = |calls: &[Call]| {
+            calls
+                .iter()
+                .filter(|c| c.ref_kind == RefKind::None)
+                .map(|c| c.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let find = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
+
+        let handler = find("<anon@L2>");
+        assert_eq!(handler.parent_symbol, None);
+        assert!(handler.nested_id.is_some());
+        assert_eq!(
+            names(&handler.calls),
+            vec!["createUser", "notifyTeam", "json"]
+        );
+        assert_eq!(names(&summary.calls), vec!["post"]);
+
+        let health = find("health");
+        assert_eq!(health.parent_symbol.as_deref(), Some("register"));
+        assert_eq!(
+            names(&health.calls),
+            vec!["checkHealth", "recordMetric", "json"]
+        );
+
+        // One-statement callbacks stay part of their parent
+        let register = find("register");
+        assert_eq!(
+            names(&register.calls),
+            vec!["loadRoutes", "get", "schedule", "cleanupRoutes"]
+        );
+        let functions = summary
+            .symbols
+            .iter()
+            .filter(|s| s.kind == SymbolKind::Function)
+            .count();
+        assert_eq!(functions, 3);
+    }
+
     /// Test that CommonJS exports have calls attributed to symbols
     #[test]
     fn test_commonjs_call_attribution() {
@@ -1326,7 +1857,7 @@ export default {
         };
 
         // Call extract_vue_sfc directly
-        super::super::extract_vue_sfc(&mut summary, source).unwrap();
+        super::super::extract_vue_sfc(&mut summary, source, ExtractionLevel::Full).unwrap();
 
         // Vue Options API may not extract individual methods as symbols,
         // but should detect Vue patterns and have calls at file level
@@ -1381,7 +1912,7 @@ function logAction(action: string) {
             ..Default::default()
         };
 
-        super::super::extract_vue_sfc(&mut summary, source).unwrap();
+        super::super::extract_vue_sfc(&mut summary, source, ExtractionLevel::Full).unwrap();
 
         // Should have symbols
         assert!(
@@ -1440,7 +1971,7 @@ export default defineComponent({
             ..Default::default()
         };
 
-        super::super::extract_vue_sfc(&mut summary, source).unwrap();
+        super::super::extract_vue_sfc(&mut summary, source, ExtractionLevel::Full).unwrap();
 
         // Should detect Vue insertions (Composition API is detected)
         assert!(
@@ -1575,4 +2106,50 @@ export const globalReducer = (state = initialState, action: GlobalActions) => {
             "globalReducer should have control flow (switch statement), but has none"
         );
     }
+
+    #[test]
+    fn test_await_and_yield_control_flow() {
+        let source = r#"
+export async function loadAll(ids: string[]) {
+    const users = await fetchUsers(ids);
+    if (users.length > 0) {
+        await saveAll(users);
+    }
+    return users;
+}
+
+export function* pages(total: number) {
+    for (let page = 0; page < total; page++) {
+        yield page;
+    }
+}
+"#;
+        let tree = parse_source(source, Lang::TypeScript);
+        let path = PathBuf::from("/test/loader.ts");
+        let summary = extract(&path, source, &tree, Lang::TypeScript).unwrap();
+
+        let kinds = |name: &str| -> Vec<ControlFlowKind> {
+            summary
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .control_flow
+                .iter()
+                .map(|cf| cf.kind)
+                .collect()
+        };
+        assert_eq!(
+            kinds("loadAll"),
+            vec![
+                ControlFlowKind::Await,
+                ControlFlowKind::If,
+                ControlFlowKind::Await
+            ]
+        );
+        assert_eq!(
+            kinds("pages"),
+            vec![ControlFlowKind::For, ControlFlowKind::Yield]
+        );
+    }
 }
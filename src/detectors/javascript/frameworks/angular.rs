@@ -6,13 +6,17 @@
 //! - Module decorators (@NgModule)
 //! - Directive decorators (@Directive)
 //! - Pipe decorators (@Pipe)
-//! - Input/Output property decorators
+//! - Input/Output properties, recorded as component props
+//! - Constructor-injected services, recorded as dependencies
+//! - Template usages (`templateUrl`, inline template event bindings)
 //! - Lifecycle hooks
 
+use once_cell::sync::Lazy;
+use regex::Regex;
 use tree_sitter::Node;
 
 use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
-use crate::schema::SemanticSummary;
+use crate::schema::{Call, Location, Prop, SemanticSummary, SymbolKind};
 
 /// Enhance semantic summary with Angular-specific information
 ///
@@ -27,6 +31,10 @@ pub fn enhance(summary: &mut SemanticSummary, root: &Node, source: &str) {
     // Detect dependency injection
     detect_dependency_injection(summary, root, source);
 
+    // Inputs/outputs as props, template bindings as usages
+    extract_inputs_outputs(summary, root, source);
+    extract_template_usages(summary, root, source);
+
     // Detect common patterns
     detect_common_patterns(summary, source);
 }
@@ -304,8 +312,13 @@ fn detect_lifecycle_hooks(summary: &mut SemanticSummary, source: &str) {
 // =============================================================================
 
 /// Detect dependency injection patterns
+///
+/// Each constructor parameter's type (or its `@Inject()` token) is an
+/// injected service: it's added to the file's dependencies and as a call from
+/// the class, so the service's callers include everything that injects it.
 fn detect_dependency_injection(summary: &mut SemanticSummary, root: &Node, source: &str) {
     let mut injected_count = 0;
+    let mut injected: Vec<(Option<String>, String, Location)> = Vec::new();
 
     // Count constructor parameters (constructor injection)
     visit_all(root, |node| {
@@ -314,12 +327,39 @@ fn detect_dependency_injection(summary: &mut SemanticSummary, root: &Node, sourc
                 if get_node_text(&name, source) == "constructor" {
                     if let Some(params) = node.child_by_field_name("parameters") {
                         injected_count += count_constructor_params(&params);
+
+                        let class = enclosing_class_name(node, source);
+                        let mut cursor = params.walk();
+                        for param in params.children(&mut cursor) {
+                            if let Some(service) = injected_service(&param, source) {
+                                let location = Location::new(
+                                    param.start_position().row + 1,
+                                    param.start_position().column,
+                                );
+                                injected.push((class.clone(), service, location));
+                            }
+                        }
                     }
                 }
             }
         }
     });
 
+    for (class, service, location) in injected {
+        if !summary.added_dependencies.contains(&service) {
+            summary.added_dependencies.push(service.clone());
+        }
+        push_class_call(
+            summary,
+            class.as_deref(),
+            Call {
+                name: service,
+                location,
+                ..Default::default()
+            },
+        );
+    }
+
     // Check for inject() function (newer pattern)
     if source.contains("inject(") {
         push_unique_insertion(
@@ -338,6 +378,32 @@ fn detect_dependency_injection(summary: &mut SemanticSummary, root: &Node, sourc
     }
 }
 
+/// Service a constructor parameter injects: its `@Inject(TOKEN)` token, or
+/// its type without type arguments (`Store<AppState>` injects `Store`)
+fn injected_service(param: &Node, source: &str) -> Option<String> {
+    if param.kind() != "required_parameter" && param.kind() != "optional_parameter" {
+        return None;
+    }
+
+    let mut cursor = param.walk();
+    let token = param
+        .children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .map(|decorator| get_node_text(&decorator, source))
+        .find_map(|decorator| {
+            let token = decorator.strip_prefix("@Inject(")?.strip_suffix(')')?;
+            Some(token.trim().to_string())
+        });
+    if token.is_some() {
+        return token;
+    }
+
+    let type_node = param.child_by_field_name("type")?.named_child(0)?;
+    let type_text = get_node_text(&type_node, source);
+    let service = type_text.split('<').next().unwrap_or_default().trim();
+    (!service.is_empty()).then(|| service.to_string())
+}
+
 /// Count constructor parameters
 fn count_constructor_params(params: &Node) -> usize {
     let mut count = 0;
@@ -353,6 +419,259 @@ fn count_constructor_params(params: &Node) -> usize {
     count
 }
 
+// =============================================================================
+// Input/Output Extraction
+// =============================================================================
+
+/// Record `@Input()`/`@Output()` fields, and `input()`/`output()` signals,
+/// as props of their class
+fn extract_inputs_outputs(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut found: Vec<(Option<String>, Prop)> = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() == "public_field_definition" {
+            if let Some(prop) = field_prop(node, source) {
+                found.push((enclosing_class_name(node, source), prop));
+            }
+        }
+    });
+
+    for (class, prop) in found {
+        // The primary symbol's props are the file's props
+        if class.is_some() && class == summary.symbol && !summary.props.contains(&prop) {
+            summary.props.push(prop.clone());
+        }
+        if let Some(idx) = class_symbol(summary, class.as_deref()) {
+            let symbol = &mut summary.symbols[idx];
+            if !symbol.props.contains(&prop) {
+                symbol.props.push(prop);
+            }
+        }
+    }
+}
+
+/// The prop a class field declares, if it's an input or output
+fn field_prop(field: &Node, source: &str) -> Option<Prop> {
+    let name = get_node_text(&field.child_by_field_name("name")?, source);
+    let annotated_type = field
+        .child_by_field_name("type")
+        .and_then(|t| t.named_child(0))
+        .map(|t| get_node_text(&t, source));
+    let value = field.child_by_field_name("value");
+
+    let mut cursor = field.walk();
+    let decorator = field
+        .children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .map(|decorator| get_node_text(&decorator, source))
+        .find(|decorator| decorator.starts_with("@Input") || decorator.starts_with("@Output"));
+
+    if let Some(decorator) = decorator {
+        if decorator.starts_with("@Output") {
+            // `@Output() saved = new EventEmitter<User>();`
+            let prop_type = annotated_type.or_else(|| {
+                let value = get_node_text(&value?, source);
+                let constructed = value.strip_prefix("new ")?;
+                Some(constructed.split('(').next()?.trim().to_string())
+            });
+            return Some(Prop {
+                name,
+                prop_type,
+                default_value: None,
+                required: false,
+            });
+        }
+        return Some(Prop {
+            name,
+            prop_type: annotated_type,
+            default_value: value.map(|v| get_node_text(&v, source)),
+            required: decorator.contains("required: true"),
+        });
+    }
+
+    // Signals: `name = input<string>('')`, `input.required<User>()`,
+    // `saved = output<User>()`
+    let value = value.filter(|v| v.kind() == "call_expression")?;
+    let function = get_node_text(&value.child_by_field_name("function")?, source);
+    let (required, has_default) = match function.as_str() {
+        "input" | "model" => (false, true),
+        "input.required" | "model.required" => (true, false),
+        "output" => (false, false),
+        _ => return None,
+    };
+    let type_argument = value
+        .child_by_field_name("type_arguments")
+        .and_then(|t| t.named_child(0))
+        .map(|t| get_node_text(&t, source));
+    let default_value = has_default
+        .then(|| value.child_by_field_name("arguments")?.named_child(0))
+        .flatten()
+        .map(|v| get_node_text(&v, source));
+
+    Some(Prop {
+        name,
+        prop_type: annotated_type.or(type_argument),
+        default_value,
+        required,
+    })
+}
+
+// =============================================================================
+// Template Usage Extraction
+// =============================================================================
+
+/// An event binding in a template: `(click)="save()"`
+static EVENT_BINDING: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:^|\s)\(([\w.\-]+)\)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid regex")
+});
+
+/// A call in an event handler: `save(` or `form.reset(`
+static HANDLER_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"([A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*)*)\s*\(").expect("valid regex")
+});
+
+/// Record `@Component` templates as usages: `templateUrl` as a local import
+/// of the template file, and inline template event bindings as calls from
+/// the component class
+fn extract_template_usages(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut template_urls: Vec<String> = Vec::new();
+    let mut handler_calls: Vec<(Option<String>, Call)> = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "decorator" || !get_node_text(node, source).starts_with("@Component") {
+            return;
+        }
+        let class = decorated_class_name(node, source);
+
+        visit_all(node, |pair| {
+            if pair.kind() != "pair" {
+                return;
+            }
+            let (Some(key), Some(value)) = (
+                pair.child_by_field_name("key"),
+                pair.child_by_field_name("value"),
+            ) else {
+                return;
+            };
+            let value_text = get_node_text(&value, source);
+            let value_text = value_text.trim_matches(|c| c == '\'' || c == '"' || c == '`');
+
+            match get_node_text(&key, source).trim_matches(|c| c == '\'' || c == '"') {
+                "templateUrl" => {
+                    template_urls.push(value_text.trim_start_matches("./").to_string());
+                }
+                "template" => {
+                    let first_line = value.start_position().row + 1;
+                    for (line, name) in template_event_calls(value_text) {
+                        let (object, name) = match name.rsplit_once('.') {
+                            Some((object, method)) => {
+                                (Some(object.to_string()), method.to_string())
+                            }
+                            None => (None, name),
+                        };
+                        handler_calls.push((
+                            class.clone(),
+                            Call {
+                                name,
+                                object,
+                                location: Location::new(first_line + line, 0),
+                                ..Default::default()
+                            },
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        });
+    });
+
+    for url in template_urls {
+        if !summary.local_imports.contains(&url) {
+            summary.local_imports.push(url);
+        }
+    }
+    for (class, call) in handler_calls {
+        push_class_call(summary, class.as_deref(), call);
+    }
+}
+
+/// Calls made by a template's event bindings, with the line (0-based,
+/// within the template) each is on: `(click)="save(item)"` calls `save`,
+/// `(done)="form.reset(); close()"` calls `form.reset` and `close`
+fn template_event_calls(template: &str) -> Vec<(usize, String)> {
+    let mut calls = Vec::new();
+
+    for binding in EVENT_BINDING.captures_iter(template) {
+        let Some(handler) = binding.get(2).or_else(|| binding.get(3)) else {
+            continue;
+        };
+        let line = template[..handler.start()].matches('\n').count();
+        for call in HANDLER_CALL.captures_iter(handler.as_str()) {
+            calls.push((line, call[1].to_string()));
+        }
+    }
+
+    calls
+}
+
+// =============================================================================
+// Class Helpers
+// =============================================================================
+
+/// Name of the class a node is declared in
+fn enclosing_class_name(node: &Node, source: &str) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if matches!(
+            parent.kind(),
+            "class_declaration" | "abstract_class_declaration" | "class"
+        ) {
+            return parent
+                .child_by_field_name("name")
+                .map(|name| get_node_text(&name, source));
+        }
+        current = parent.parent();
+    }
+    None
+}
+
+/// Name of the class a decorator is applied to (decorators of an exported
+/// class belong to the `export` statement)
+fn decorated_class_name(decorator: &Node, source: &str) -> Option<String> {
+    let parent = decorator.parent()?;
+    let class = if parent.kind() == "export_statement" {
+        parent.child_by_field_name("declaration")?
+    } else {
+        parent
+    };
+    let name = class.child_by_field_name("name")?;
+    Some(get_node_text(&name, source))
+}
+
+/// Index of the class symbol named `class`, if it was extracted
+fn class_symbol(summary: &SemanticSummary, class: Option<&str>) -> Option<usize> {
+    let class = class?;
+    summary
+        .symbols
+        .iter()
+        .position(|s| s.name == class && s.kind == SymbolKind::Class)
+}
+
+/// Attach a call to the class it's made from, or to the file when the class
+/// isn't a symbol
+fn push_class_call(summary: &mut SemanticSummary, class: Option<&str>, call: Call) {
+    let calls = match class_symbol(summary, class) {
+        Some(idx) => &mut summary.symbols[idx].calls,
+        None => &mut summary.calls,
+    };
+    if !calls
+        .iter()
+        .any(|c| c.name == call.name && c.object == call.object)
+    {
+        calls.push(call);
+    }
+}
+
 // =============================================================================
 // Common Pattern Detection
 // =============================================================================
@@ -494,4 +813,139 @@ mod tests {
             Some(0)
         );
     }
+
+    fn extract_file(path: &str, source: &str) -> SemanticSummary {
+        let lang = crate::lang::Lang::TypeScript;
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(std::path::Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn class<'a>(summary: &'a SemanticSummary, name: &str) -> &'a crate::schema::SymbolInfo {
+        summary
+            .symbols
+            .iter()
+            .find(|s| s.name == name && s.kind == SymbolKind::Class)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_constructor_injection() {
+        let source = r#"import { Component, Inject } from '@angular/core';
+import { UserService } from './user.service';
+
+@Component({ selector: 'app-users', templateUrl: './users.component.html' })
+export class UsersComponent {
+    constructor(
+        private userService: UserService,
+        private store: Store<AppState>,
+        @Inject(API_URL) private apiUrl: string,
+    ) {}
+}
+"#;
+        let summary = extract_file("/repo/src/app/users.component.ts", source);
+
+        for service in ["UserService", "Store", "API_URL"] {
+            assert!(
+                summary.added_dependencies.iter().any(|d| d == service),
+                "{} not a dependency: {:?}",
+                service,
+                summary.added_dependencies
+            );
+            assert!(
+                class(&summary, "UsersComponent")
+                    .calls
+                    .iter()
+                    .any(|c| c.name == service),
+                "{} not called from the component",
+                service
+            );
+        }
+        assert!(summary
+            .insertions
+            .contains(&"3 injected dependencies".to_string()));
+        assert!(summary
+            .local_imports
+            .contains(&"users.component.html".to_string()));
+    }
+
+    #[test]
+    fn test_input_output_props() {
+        let source = r#"import { Component, Input, Output, EventEmitter, input, output } from '@angular/core';
+
+@Component({ selector: 'app-user-card', template: '<div></div>' })
+export class UserCardComponent {
+    @Input() user: User;
+    @Input({ required: true }) id!: string;
+    @Input() size = 'md';
+    @Output() saved = new EventEmitter<User>();
+    label = input<string>('');
+    mode = input.required<Mode>();
+    closed = output<void>();
+    private cache = new Map();
+}
+"#;
+        let summary = extract_file("/repo/src/app/user-card.component.ts", source);
+
+        let props: Vec<_> = class(&summary, "UserCardComponent")
+            .props
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.prop_type.as_deref(),
+                    p.default_value.as_deref(),
+                    p.required,
+                )
+            })
+            .collect();
+        assert_eq!(
+            props,
+            vec![
+                ("user", Some("User"), None, false),
+                ("id", Some("string"), None, true),
+                ("size", None, Some("'md'"), false),
+                ("saved", Some("EventEmitter<User>"), None, false),
+                ("label", Some("string"), Some("''"), false),
+                ("mode", Some("Mode"), None, true),
+                ("closed", Some("void"), None, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_event_bindings() {
+        let source = r#"import { Component } from '@angular/core';
+
+@Component({
+    selector: 'app-editor',
+    template: `
+        <input [(ngModel)]="name">
+        <button (click)="save(name)">Save</button>
+        <form (ngSubmit)="form.reset(); close()"></form>
+    `,
+})
+export class EditorComponent {
+    save(name: string) {}
+    close() {}
+}
+"#;
+        let summary = extract_file("/repo/src/app/editor.component.ts", source);
+
+        let calls: Vec<_> = class(&summary, "EditorComponent")
+            .calls
+            .iter()
+            .map(|c| (c.object.as_deref(), c.name.as_str(), c.location.line))
+            .collect();
+        assert!(calls.contains(&(None, "save", 7)), "{:?}", calls);
+        assert!(calls.contains(&(Some("form"), "reset", 8)), "{:?}", calls);
+        assert!(calls.contains(&(None, "close", 8)), "{:?}", calls);
+        assert!(!calls.iter().any(|(_, name, _)| *name == "ngModel"));
+
+        assert_eq!(
+            template_event_calls(r#"<a (click)='open()' href="x()">"#),
+            vec![(0, "open".to_string())]
+        );
+    }
 }
@@ -10,7 +10,7 @@
 use tree_sitter::Node;
 
 use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
-use crate::schema::{FrameworkEntryPoint, SemanticSummary, SymbolKind};
+use crate::schema::{FrameworkEntryPoint, RouteInfo, SemanticSummary, SymbolKind};
 
 /// Enhance semantic summary with Express-specific information
 ///
@@ -93,6 +93,9 @@ const HTTP_METHODS: &[&str] = &[
 /// app.get('/users', handler);
 /// router.post('/api/data', middleware, handler);
 /// ```
+///
+/// Registrations with a literal path and a handler are also recorded in
+/// `summary.routes`.
 pub fn extract_route_handlers(summary: &mut SemanticSummary, root: &Node, source: &str) {
     let mut routes: Vec<(String, String)> = Vec::new(); // (method, path)
 
@@ -101,6 +104,9 @@ pub fn extract_route_handlers(summary: &mut SemanticSummary, root: &Node, source
             if let Some((method, path)) = extract_route_info(node, source) {
                 routes.push((method, path));
             }
+            if let Some(route) = route_definition(node, source) {
+                summary.routes.push(route);
+            }
         }
     });
 
@@ -153,6 +159,60 @@ fn extract_route_info(node: &Node, source: &str) -> Option<(String, String)> {
     None
 }
 
+/// Route declared by `app.get('/path', ..., handler)`
+///
+/// Requires a string path starting with `/` (or `*`) and at least one
+/// handler, which rules out `app.get('setting')` and map lookups. The handler
+/// is the last argument, unwrapped from wrappers like `asyncHandler(fn)`;
+/// inline functions have no handler name.
+fn route_definition(node: &Node, source: &str) -> Option<RouteInfo> {
+    let func = node.child_by_field_name("function")?;
+    if func.kind() != "member_expression" {
+        return None;
+    }
+    let property = func.child_by_field_name("property")?;
+    let method = get_node_text(&property, source);
+    if !HTTP_METHODS.contains(&method.as_str()) {
+        return None;
+    }
+
+    let args = node.child_by_field_name("arguments")?;
+    let mut cursor = args.walk();
+    let args: Vec<Node> = args.named_children(&mut cursor).collect();
+    let (path_arg, handlers) = args.split_first()?;
+    let handler = handlers.last()?;
+    if !matches!(path_arg.kind(), "string" | "template_string") {
+        return None;
+    }
+    let path = get_node_text(path_arg, source);
+    let path = path.trim_matches(|c| matches!(c, '"' | '\'' | '`'));
+    if !path.starts_with('/') && path != "*" {
+        return None;
+    }
+
+    Some(RouteInfo::new(
+        "express",
+        &method,
+        path,
+        handler_name(handler, source),
+        node.start_position().row + 1,
+    ))
+}
+
+/// Name of a route handler expression, if it refers to a named function
+fn handler_name(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" | "member_expression" => Some(get_node_text(node, source)),
+        "call_expression" => {
+            let args = node.child_by_field_name("arguments")?;
+            let mut cursor = args.walk();
+            let last = args.named_children(&mut cursor).last()?;
+            handler_name(&last, source)
+        }
+        _ => None,
+    }
+}
+
 /// Count routes by HTTP method
 fn count_methods(routes: &[(String, String)]) -> Vec<(String, usize)> {
     let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
@@ -404,6 +464,46 @@ mod tests {
         assert!(!is_route_file("const x = 1;"));
     }
 
+    fn routes_in(source: &str) -> Vec<RouteInfo> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_javascript::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract_route_handlers(&mut summary, &tree.root_node(), source);
+        summary.routes
+    }
+
+    #[test]
+    fn test_route_definitions() {
+        let source = r#"
+const app = express();
+app.get('/users', listUsers);
+router.post("/users/:id", auth, asyncHandler(users.update));
+app.delete(`/users/:id`, (req, res) => res.send());
+app.get('title');
+cache.get(key, fallback);
+"#;
+        let routes: Vec<_> = routes_in(source)
+            .into_iter()
+            .map(|r| (r.method, r.path, r.handler, r.line))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("GET".into(), "/users".into(), Some("listUsers".into()), 3),
+                (
+                    "POST".into(),
+                    "/users/:id".into(),
+                    Some("users.update".into()),
+                    4
+                ),
+                ("DELETE".into(), "/users/:id".into(), None, 5),
+            ]
+        );
+    }
+
     #[test]
     fn test_is_middleware_file() {
         assert!(is_middleware_file(
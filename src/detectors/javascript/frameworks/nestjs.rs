@@ -7,18 +7,29 @@
 //! - HTTP method decorators (@Get, @Post, @Put, @Delete, etc.)
 //! - Bootstrap function detection (main.ts)
 //! - Dependency injection patterns
+//! - Route manifest entries for controller handlers
 
-use crate::detectors::common::push_unique_insertion;
-use crate::schema::{FrameworkEntryPoint, SemanticSummary, SymbolKind};
+use tree_sitter::Node;
+
+use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
+use crate::schema::{FrameworkEntryPoint, RouteInfo, SemanticSummary, SymbolKind};
+
+/// HTTP method decorators on controller methods
+const HTTP_DECORATORS: &[&str] = &[
+    "Get", "Post", "Put", "Delete", "Patch", "Head", "Options", "All",
+];
 
 /// Enhance semantic summary with NestJS-specific information
 ///
 /// This is called when NestJS is detected in the file.
-pub fn enhance(summary: &mut SemanticSummary, source: &str) {
+pub fn enhance(summary: &mut SemanticSummary, root: &Node, source: &str) {
     let file_lower = summary.file.to_lowercase();
 
     // Detect NestJS patterns
     detect_decorators(summary, source);
+    if source.contains("@Controller(") {
+        extract_routes(summary, root, source);
+    }
     detect_bootstrap(summary, &file_lower, source);
     detect_module_patterns(summary, source);
 
@@ -155,6 +166,128 @@ fn mark_http_handlers(summary: &mut SemanticSummary, source: &str) {
     }
 }
 
+/// Record the handlers of `@Controller('prefix')` classes in `summary.routes`
+///
+/// Each method decorated with `@Get(':id')` (or another HTTP decorator)
+/// becomes a route at `/prefix/:id`, handled by `Class.method`.
+fn extract_routes(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut routes = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "class_declaration" {
+            return;
+        }
+        // Decorators of an exported class belong to the export statement
+        let mut decorators = Vec::new();
+        for owner in std::iter::once(*node).chain(
+            node.parent()
+                .filter(|parent| parent.kind() == "export_statement"),
+        ) {
+            let mut cursor = owner.walk();
+            decorators.extend(owner.children_by_field_name("decorator", &mut cursor));
+        }
+        let Some(prefix) = decorators
+            .iter()
+            .find_map(|d| match decorator_call(d, source) {
+                Some((name, path)) if name == "Controller" => Some(path.unwrap_or_default()),
+                _ => None,
+            })
+        else {
+            return;
+        };
+        let (Some(class_name), Some(body)) = (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("body"),
+        ) else {
+            return;
+        };
+        let class_name = get_node_text(&class_name, source);
+
+        // Method decorators precede the method in the class body
+        let mut pending: Vec<Node> = Vec::new();
+        let mut cursor = body.walk();
+        for member in body.named_children(&mut cursor) {
+            match member.kind() {
+                "decorator" => pending.push(member),
+                "method_definition" => {
+                    let method_name = member
+                        .child_by_field_name("name")
+                        .map(|n| get_node_text(&n, source))
+                        .unwrap_or_default();
+                    for decorator in pending.drain(..) {
+                        let Some((name, path)) = decorator_call(&decorator, source) else {
+                            continue;
+                        };
+                        if HTTP_DECORATORS.contains(&name.as_str()) {
+                            routes.push(RouteInfo::new(
+                                "nestjs",
+                                &name,
+                                join_route_path(&prefix, path.as_deref().unwrap_or("")),
+                                Some(format!("{}.{}", class_name, method_name)),
+                                member.start_position().row + 1,
+                            ));
+                        }
+                    }
+                }
+                _ => pending.clear(),
+            }
+        }
+    });
+
+    summary.routes.extend(routes);
+}
+
+/// Name and path argument of a decorator call like `@Get(':id')`
+///
+/// The path is the first argument when it is a string, or the `path`
+/// property of an options object (`@Controller({ path: 'users' })`).
+fn decorator_call(decorator: &Node, source: &str) -> Option<(String, Option<String>)> {
+    let call = decorator.named_child(0)?;
+    if call.kind() != "call_expression" {
+        return None;
+    }
+    let name = get_node_text(&call.child_by_field_name("function")?, source);
+    let args = call.child_by_field_name("arguments")?;
+    let mut cursor = args.walk();
+    let path = args
+        .named_children(&mut cursor)
+        .next()
+        .and_then(|arg| match arg.kind() {
+            "string" => Some(string_value(&arg, source)),
+            "object" => {
+                let mut cursor = arg.walk();
+                let path = arg
+                    .named_children(&mut cursor)
+                    .filter(|pair| pair.kind() == "pair")
+                    .find(|pair| {
+                        pair.child_by_field_name("key")
+                            .is_some_and(|k| string_value(&k, source) == "path")
+                    })
+                    .and_then(|pair| pair.child_by_field_name("value"))
+                    .filter(|value| value.kind() == "string");
+                path.map(|value| string_value(&value, source))
+            }
+            _ => None,
+        });
+    Some((name, path))
+}
+
+fn string_value(node: &Node, source: &str) -> String {
+    get_node_text(node, source)
+        .trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+        .to_string()
+}
+
+/// Join a controller prefix and handler path: `users` + `:id` is `/users/:id`
+fn join_route_path(prefix: &str, path: &str) -> String {
+    let segments: Vec<&str> = [prefix, path]
+        .iter()
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
 /// Detect NestJS bootstrap function (main.ts)
 fn detect_bootstrap(summary: &mut SemanticSummary, file_lower: &str, source: &str) {
     // main.ts with NestFactory.create pattern
@@ -220,6 +353,72 @@ fn detect_module_patterns(summary: &mut SemanticSummary, source: &str) {
 mod tests {
     use super::*;
 
+    fn enhance_source(summary: &mut SemanticSummary, source: &str) {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        enhance(summary, &tree.root_node(), source);
+    }
+
+    #[test]
+    fn test_controller_routes() {
+        let mut summary = SemanticSummary::default();
+        let source = r#"
+@Controller('users')
+export class UsersController {
+    @Get()
+    findAll() { }
+
+    @Get(':id')
+    @UseGuards(AuthGuard)
+    findOne() { }
+
+    @Post('/')
+    create() { }
+
+    helper() { }
+}
+
+@Controller({ path: '/admin/' })
+class AdminController {
+    @Delete('users/:id')
+    remove() { }
+}
+"#;
+
+        enhance_source(&mut summary, source);
+
+        let routes: Vec<_> = summary
+            .routes
+            .iter()
+            .map(|r| {
+                (
+                    r.method.as_str(),
+                    r.path.as_str(),
+                    r.handler.as_deref(),
+                    r.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("GET", "/users", Some("UsersController.findAll"), 5),
+                ("GET", "/users/:id", Some("UsersController.findOne"), 9),
+                ("POST", "/users", Some("UsersController.create"), 12),
+                (
+                    "DELETE",
+                    "/admin/users/:id",
+                    Some("AdminController.remove"),
+                    20
+                ),
+            ]
+        );
+        assert!(summary.routes.iter().all(|r| r.framework == "nestjs"));
+    }
+
     #[test]
     fn test_controller_detection() {
         let mut summary = SemanticSummary::default();
@@ -234,7 +433,7 @@ mod tests {
             }
         "#;
 
-        enhance(&mut summary, source);
+        enhance_source(&mut summary, source);
 
         assert_eq!(
             summary.framework_entry_point,
@@ -260,7 +459,7 @@ mod tests {
             }
         "#;
 
-        enhance(&mut summary, source);
+        enhance_source(&mut summary, source);
 
         assert_eq!(
             summary.framework_entry_point,
@@ -284,7 +483,7 @@ mod tests {
             bootstrap();
         "#;
 
-        enhance(&mut summary, source);
+        enhance_source(&mut summary, source);
 
         assert_eq!(
             summary.framework_entry_point,
@@ -305,7 +504,7 @@ mod tests {
             export class AppModule { }
         "#;
 
-        enhance(&mut summary, source);
+        enhance_source(&mut summary, source);
 
         assert_eq!(
             summary.framework_entry_point,
@@ -9,7 +9,11 @@
 //! - Config files (next.config.js)
 
 use crate::detectors::common::push_unique_insertion;
-use crate::schema::{FrameworkEntryPoint, SemanticSummary, SymbolKind};
+use crate::schema::{FrameworkEntryPoint, RouteInfo, SemanticSummary, SymbolKind};
+
+/// HTTP methods an App Router route handler can export
+const ROUTE_HANDLER_METHODS: &[&str] =
+    &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
 
 /// Enhance semantic summary with Next.js-specific information
 ///
@@ -21,6 +25,7 @@ pub fn enhance(summary: &mut SemanticSummary, source: &str) {
     detect_app_router_patterns(summary, &file_lower, source);
     detect_pages_router_patterns(summary, &file_lower, source);
     detect_api_routes(summary, &file_lower, source);
+    extract_routes(summary);
     detect_middleware(summary, &file_lower, source);
     detect_config_files(summary, &file_lower, source);
     detect_server_client_components(summary, source);
@@ -281,6 +286,49 @@ fn detect_api_routes(summary: &mut SemanticSummary, file_lower: &str, _source: &
     }
 }
 
+/// Record route handlers in `summary.routes`
+///
+/// An App Router `route.ts` serves each exported HTTP method function at the
+/// path of its directory. A Pages Router file under `pages/api/` serves every
+/// method from its default export.
+fn extract_routes(summary: &mut SemanticSummary) {
+    let file = summary.file.replace('\\', "/");
+    let file_lower = file.to_lowercase();
+    let Some(path) = extract_route_path(&file) else {
+        return;
+    };
+
+    if file_lower.contains("/app/")
+        && (file_lower.ends_with("/route.ts") || file_lower.ends_with("/route.js"))
+    {
+        let routes: Vec<RouteInfo> = summary
+            .symbols
+            .iter()
+            .filter(|s| s.is_exported && ROUTE_HANDLER_METHODS.contains(&s.name.as_str()))
+            .map(|s| {
+                RouteInfo::new(
+                    "nextjs",
+                    &s.name,
+                    path.clone(),
+                    Some(s.name.clone()),
+                    s.start_line,
+                )
+            })
+            .collect();
+        summary.routes.extend(routes);
+    } else if file_lower.contains("/pages/api/") {
+        let handler = summary.symbols.iter().find(|s| s.is_default_export);
+        let route = RouteInfo::new(
+            "nextjs",
+            "ALL",
+            path,
+            handler.map(|s| s.name.clone()),
+            handler.map_or(1, |s| s.start_line),
+        );
+        summary.routes.push(route);
+    }
+}
+
 // =============================================================================
 // Middleware Detection
 // =============================================================================
@@ -480,6 +528,8 @@ pub fn extract_route_path(file_path: &str) -> Option<String> {
         let route = after_pages
             .replace(".tsx", "")
             .replace(".jsx", "")
+            .replace(".ts", "")
+            .replace(".js", "")
             .replace("/index", "")
             .replace("index", "");
 
@@ -525,4 +575,66 @@ mod tests {
             Some("/about".to_string())
         );
     }
+
+    fn extract_file(path: &str, source: &str) -> SemanticSummary {
+        let lang = crate::lang::Lang::TypeScript;
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(std::path::Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    #[test]
+    fn test_route_handler_routes() {
+        let source = "import { NextResponse } from 'next/server';\n\nexport async function GET() {\n    return NextResponse.json([]);\n}\n\nexport async function POST() {\n    return NextResponse.json({});\n}\n\nfunction helper() {}\n";
+        let summary = extract_file("/repo/app/api/users/[id]/route.ts", source);
+
+        let routes: Vec<_> = summary
+            .routes
+            .iter()
+            .map(|r| {
+                (
+                    r.method.as_str(),
+                    r.path.as_str(),
+                    r.handler.as_deref(),
+                    r.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("GET", "/api/users/[id]", Some("GET"), 3),
+                ("POST", "/api/users/[id]", Some("POST"), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pages_api_route() {
+        let source = "import type { NextApiRequest, NextApiResponse } from 'next';\n\nexport default function handler(req: NextApiRequest, res: NextApiResponse) {\n    res.status(200).json({});\n}\n";
+        let summary = extract_file("/repo/pages/api/users/index.ts", source);
+
+        assert_eq!(summary.routes.len(), 1, "{:?}", summary.routes);
+        let route = &summary.routes[0];
+        assert_eq!(route.method, "ALL");
+        assert_eq!(route.path, "/api/users");
+        assert_eq!(route.handler.as_deref(), Some("handler"));
+        assert_eq!(route.line, 3);
+    }
+
+    #[test]
+    fn test_anonymous_default_page_is_entry_point() {
+        let source = "import Link from 'next/link';\n\nexport default function () {\n    return <Link href=\"/\">Home</Link>;\n}\n";
+        let lang = crate::lang::Lang::Tsx;
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let path = std::path::PathBuf::from("/repo/pages/about.tsx");
+        let summary = crate::extract::extract(&path, source, &tree, lang).unwrap();
+
+        let about = summary.symbols.iter().find(|s| s.name == "About").unwrap();
+        assert!(about.is_default_export);
+        assert_eq!(about.framework_entry_point, FrameworkEntryPoint::NextPage);
+    }
 }
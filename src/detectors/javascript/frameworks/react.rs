@@ -13,8 +13,10 @@ use tree_sitter::Node;
 
 use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
 use crate::schema::{
-    Call, FrameworkEntryPoint, SemanticSummary, StateChange, SymbolInfo, SymbolKind,
+    Call, FrameworkEntryPoint, HookCall, JsxElement, Location, SemanticSummary, StateChange,
+    SymbolInfo, SymbolKind,
 };
+use crate::utils::truncate_to_char_boundary;
 
 /// Enhance semantic summary with React-specific information
 ///
@@ -128,6 +130,7 @@ fn extract_hook_state(summary: &mut SemanticSummary, node: &Node, func_name: &st
                                     name: name.clone(),
                                     state_type: infer_type(&init),
                                     initializer: init,
+                                    is_atomic: false,
                                 });
 
                                 summary
@@ -563,7 +566,7 @@ fn truncate_deps(deps: &str) -> String {
         if parts.len() > 3 {
             format!("{}, {} more", parts[..2].join(", "), parts.len() - 2)
         } else {
-            format!("{}...", &deps[..27])
+            format!("{}...", truncate_to_char_boundary(deps, 27))
         }
     } else {
         deps.to_string()
@@ -649,6 +652,93 @@ pub fn extract_jsx_insertions(summary: &mut SemanticSummary, root: &Node, source
     detect_list_pattern(&jsx_tags, summary);
 }
 
+/// Collect every JSX element with its tag, props and location
+///
+/// Fragments (`<>...</>`) have no tag and are skipped.
+pub fn collect_jsx_elements(root: &Node, source: &str) -> Vec<JsxElement> {
+    let mut elements = Vec::new();
+    visit_all(root, |node| {
+        let (tag_owner, is_self_closing) = match node.kind() {
+            "jsx_self_closing_element" => (Some(*node), true),
+            "jsx_element" => (
+                node.child(0).filter(|c| c.kind() == "jsx_opening_element"),
+                false,
+            ),
+            _ => return,
+        };
+        let Some(tag_owner) = tag_owner else {
+            return;
+        };
+        let Some(tag) = tag_owner.child_by_field_name("name") else {
+            return;
+        };
+
+        let mut props = Vec::new();
+        let mut cursor = tag_owner.walk();
+        for attr in tag_owner.children(&mut cursor) {
+            if attr.kind() != "jsx_attribute" {
+                continue;
+            }
+            let Some(name) = attr.child(0) else {
+                continue;
+            };
+            let value = attr
+                .child(attr.child_count().saturating_sub(1))
+                .filter(|v| v.id() != name.id())
+                .map(|v| get_node_text(&v, source));
+            props.push((get_node_text(&name, source), value));
+        }
+
+        elements.push(JsxElement {
+            tag: get_node_text(&tag, source),
+            props,
+            is_self_closing,
+            location: Location::new(node.start_position().row + 1, node.start_position().column),
+        });
+    });
+    elements
+}
+
+/// Collect hook calls (`useX(...)`, including `React.useX(...)`)
+///
+/// The first argument is kept when it names something, as the context object
+/// of `useContext(ThemeContext)` does; literals and inline functions are not.
+pub fn collect_hook_calls(root: &Node, source: &str) -> Vec<HookCall> {
+    let mut hooks = Vec::new();
+    visit_all(root, |node| {
+        if node.kind() != "call_expression" {
+            return;
+        }
+        let Some(func) = node.child_by_field_name("function") else {
+            return;
+        };
+        let name = match func.kind() {
+            "identifier" => get_node_text(&func, source),
+            "member_expression" => func
+                .child_by_field_name("property")
+                .map(|p| get_node_text(&p, source))
+                .unwrap_or_default(),
+            _ => return,
+        };
+        if !Call::check_is_hook(&name) {
+            return;
+        }
+
+        let argument = node
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0))
+            .filter(|arg| matches!(arg.kind(), "identifier" | "member_expression"))
+            .map(|arg| get_node_text(&arg, source));
+
+        hooks.push(HookCall {
+            name,
+            argument,
+            location: Location::new(node.start_position().row + 1, node.start_position().column),
+        });
+    });
+    hooks
+}
+
 /// Detect header container pattern
 fn detect_header_pattern(jsx_tags: &[String], summary: &mut SemanticSummary) {
     if jsx_tags.iter().any(|t| t == "header" || t == "Header") {
@@ -842,6 +932,12 @@ mod tests {
 
         // Empty deps
         assert_eq!(truncate_deps(""), "");
+
+        // Multi-byte names are cut on a char boundary (byte 27 is inside 状)
+        assert_eq!(
+            truncate_deps("用户名称信息, 用户状态"),
+            "用户名称信息, 用户..."
+        );
     }
 
     #[test]
@@ -1134,4 +1230,49 @@ mod tests {
             summary.insertions
         );
     }
+
+    #[test]
+    fn test_collect_jsx_elements_and_hooks() {
+        let source = r#"
+function Shell() {
+    const theme = useContext(ThemeContext);
+    const [open, setOpen] = React.useState(false);
+    useTheme();
+    return (
+        <Layout title="Home">
+            <Sidebar collapsed />
+            <>
+                <Icons.Home />
+            </>
+        </Layout>
+    );
+}
+"#;
+        let tree = parse_tsx(source);
+        let root = tree.root_node();
+
+        let elements = collect_jsx_elements(&root, source);
+        let tags: Vec<&str> = elements.iter().map(|e| e.tag.as_str()).collect();
+        assert_eq!(tags, vec!["Layout", "Sidebar", "Icons.Home"]);
+        assert_eq!(
+            elements[0].props,
+            vec![("title".to_string(), Some("\"Home\"".to_string()))]
+        );
+        assert!(elements[1].is_self_closing);
+        assert_eq!(elements[1].location.line, 8);
+
+        let hooks = collect_hook_calls(&root, source);
+        let hooks: Vec<(&str, Option<&str>)> = hooks
+            .iter()
+            .map(|h| (h.name.as_str(), h.argument.as_deref()))
+            .collect();
+        assert_eq!(
+            hooks,
+            vec![
+                ("useContext", Some("ThemeContext")),
+                ("useState", None),
+                ("useTheme", None),
+            ]
+        );
+    }
 }
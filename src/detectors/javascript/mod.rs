@@ -45,6 +45,7 @@ use tree_sitter::{Parser, Tree};
 use crate::detectors::common::push_unique_insertion;
 use crate::detectors::variable_refs;
 use crate::error::Result;
+use crate::extract::ExtractionLevel;
 use crate::lang::Lang;
 use crate::schema::SemanticSummary;
 
@@ -56,14 +57,22 @@ pub use frameworks::{detect_frameworks, FrameworkContext};
 /// 1. Runs core extraction for symbols, imports, control flow, calls
 /// 2. Detects which frameworks are in use
 /// 3. Applies framework-specific enhancements
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: Lang) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    lang: Lang,
+    level: ExtractionLevel,
+) -> Result<()> {
     let root = tree.root_node();
 
     // Phase 1: Core JavaScript/TypeScript extraction
-    core::extract_core(summary, &root, source, lang)?;
+    core::extract_core(summary, &root, source, lang, level)?;
 
     // Phase 1b: Variable references (include escaping locals for JS/TS/TSX)
-    variable_refs::extract_variable_references(summary, &root, source, Some(lang), true);
+    if level == ExtractionLevel::Full {
+        variable_refs::extract_variable_references(summary, &root, source, Some(lang), true);
+    }
 
     // Phase 2: Detect frameworks from imports and patterns
     let frameworks = detect_frameworks(summary, source);
@@ -90,7 +99,7 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: L
     }
 
     if frameworks.is_nestjs {
-        frameworks::nestjs::enhance(summary, source);
+        frameworks::nestjs::enhance(summary, &root, source);
     }
 
     if frameworks.is_redux {
@@ -108,7 +117,11 @@ pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: L
 /// 3. Parse the script content with the appropriate grammar
 /// 4. Run standard JS/TS extraction on the script
 /// 5. Apply Vue-specific enhancements
-pub fn extract_vue_sfc(summary: &mut SemanticSummary, source: &str) -> Result<()> {
+pub fn extract_vue_sfc(
+    summary: &mut SemanticSummary,
+    source: &str,
+    level: ExtractionLevel,
+) -> Result<()> {
     // Extract the script section from the SFC
     let Some(sfc_script) = frameworks::vue::extract_sfc_script(source) else {
         // No script section - this is a template-only component
@@ -144,14 +157,16 @@ pub fn extract_vue_sfc(summary: &mut SemanticSummary, source: &str) -> Result<()
 
     // Run standard extraction on the script content
     let root = tree.root_node();
-    core::extract_core(summary, &root, &sfc_script.content, sfc_script.lang)?;
-    variable_refs::extract_variable_references(
-        summary,
-        &root,
-        &sfc_script.content,
-        Some(sfc_script.lang),
-        true,
-    );
+    core::extract_core(summary, &root, &sfc_script.content, sfc_script.lang, level)?;
+    if level == ExtractionLevel::Full {
+        variable_refs::extract_variable_references(
+            summary,
+            &root,
+            &sfc_script.content,
+            Some(sfc_script.lang),
+            true,
+        );
+    }
 
     // Detect frameworks in the script
     let frameworks = detect_frameworks(summary, &sfc_script.content);
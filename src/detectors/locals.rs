@@ -238,6 +238,7 @@ pub fn get_locals_query(lang: Lang) -> Option<LocalsQuery> {
         Lang::C => include_str!("../../queries/c/locals.scm"),
         Lang::Cpp => include_str!("../../queries/cpp/locals.scm"),
         Lang::Kotlin => include_str!("../../queries/kotlin/locals.scm"),
+        Lang::Php => include_str!("../../queries/php/locals.scm"),
         Lang::Bash | Lang::Dockerfile => include_str!("../../queries/bash/locals.scm"),
         Lang::Gradle => include_str!("../../queries/groovy/locals.scm"),
         Lang::Hcl => include_str!("../../queries/hcl/locals.scm"),
@@ -251,7 +252,9 @@ pub fn get_locals_query(lang: Lang) -> Option<LocalsQuery> {
         | Lang::Json
         | Lang::Yaml
         | Lang::Toml
-        | Lang::Xml => return None,
+        | Lang::Xml
+        | Lang::Protobuf
+        | Lang::GraphQL => return None,
     };
 
     LocalsQuery::new(&lang.tree_sitter_language(), query_src)
@@ -527,6 +530,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_php_locals_query_loads() {
+        let query = get_locals_query(Lang::Php);
+        assert!(query.is_some(), "PHP locals.scm should load successfully");
+    }
+
     #[test]
     fn test_config_languages_return_none() {
         assert!(get_locals_query(Lang::Json).is_none());
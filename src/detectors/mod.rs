@@ -21,13 +21,16 @@
 //!
 //! - `javascript`: JS, TS, JSX, TSX (with React/component detection)
 //! - `rust`: Rust
-//! - `python`: Python (with decorator detection)
+//! - `python`: Python (with decorator and FastAPI route detection)
 //! - `go`: Go
 //! - `java`: Java
 //! - `csharp`: C# (.NET)
+//! - `php`: PHP (with Laravel controller, route, Eloquent and Artisan detection)
 //! - `c_family`: C, C++
 //! - `markup`: HTML, CSS, Markdown
 //! - `config`: JSON, YAML, TOML
+//! - `openapi`: OpenAPI/Swagger documents (YAML, JSON)
+//! - `protobuf`: Protocol Buffers (.proto)
 //!
 //! # Symbol Selection Heuristics
 //!
@@ -53,6 +56,12 @@
 //! - Modifiers: async, unsafe, partial, static
 //! - Frameworks: ASP.NET Core, Entity Framework, Unity (via boilerplate detection)
 //!
+//! ## IMPLEMENTED: PHP
+//! - Parser: tree-sitter-php
+//! - Targets: classes, interfaces, traits, enums, methods (with visibility), namespaces
+//! - Control flow: if, switch, match, loops, try/catch
+//! - Frameworks: Laravel (controllers, routes, Eloquent, Artisan, middleware boilerplate)
+//!
 //! ## Priority 1: Kotlin - HIGH PRIORITY
 //! TODO(SEM-XX): Enhance Kotlin detector (kotlin.rs)
 //! - Add data class and copy semantics
@@ -70,13 +79,6 @@
 //! - SwiftUI View boilerplate
 //! - Async/await task trees
 //!
-//! ## Priority 4: PHP - MEDIUM PRIORITY
-//! TODO(SEM-XX): Add PHP detector (php.rs)
-//! - Parser: tree-sitter-php
-//! - Focus: Framework-aware semantics
-//! - Frameworks: Laravel (controllers, providers, middleware, Eloquent)
-//! - High ROI due to extreme boilerplate density
-//!
 //! ## Priority 5: Ruby - LOW PRIORITY
 //! TODO(SEM-XX): Add Ruby detector (ruby.rs)
 //! - Parser: tree-sitter-ruby
@@ -97,7 +99,11 @@ pub mod c_family;
 pub mod common;
 pub mod config;
 pub mod csharp;
+pub mod deprecation;
 pub mod dockerfile;
+pub mod embedded_sql;
+pub mod facets;
+pub mod feature_flags;
 pub mod generic;
 pub mod go;
 pub mod gradle;
@@ -105,6 +111,7 @@ pub mod grammar;
 pub mod hcl;
 pub mod java;
 pub mod locals;
+pub mod nested;
 pub mod variable_refs;
 // JavaScript is now a directory module with framework support in:
 //   - javascript/core.rs: Generic JS/TS extraction
@@ -112,12 +119,15 @@ pub mod variable_refs;
 pub mod javascript;
 pub mod kotlin;
 pub mod markup;
+pub mod openapi;
+pub mod php;
+pub mod protobuf;
 pub mod python;
 pub mod rust;
 pub mod shell;
 
 // Re-export key types for convenience
-pub use generic::extract_with_grammar;
+pub use generic::{extract_with_grammar, extract_with_grammar_at_level};
 pub use grammar::{get_grammar, LangGrammar};
 
 /// Extract the filename stem from a file path (lowercase)
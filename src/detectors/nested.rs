@@ -0,0 +1,200 @@
+//! Nested functions, closures and callbacks
+//!
+//! Language detectors extract significant closures and callbacks (e.g. the
+//! handler in `app.post('/x', async (req, res) => {...})`) as symbols of their
+//! own, so their calls and control flow are attributed to them rather than to
+//! the function they are defined in. Anonymous ones are named
+//! [`anonymous_symbol_name`] (`<anon@L42>`).
+//!
+//! Runs after the language detector and links each nested symbol to its parent:
+//!
+//! - `parent_symbol`: name of the innermost function containing it
+//! - `nested_id`: `{parent path}/{name:kind:arity}#{ordinal}`, where the
+//!   ordinal counts earlier siblings with the same `name:kind:arity`. Folded
+//!   into the symbol hash, so it stays stable as long as the parent chain and
+//!   sibling order do. Anonymous symbols use `<anon>` as their name here,
+//!   and get a `nested_id` even at the top level to keep them apart.
+//!
+//! [`anonymous_symbol_name`]: crate::schema::anonymous_symbol_name
+
+use std::collections::HashMap;
+
+use crate::schema::{SemanticSummary, SymbolInfo, SymbolKind};
+
+/// Closures and callbacks with fewer statements than this stay part of the
+/// function they are defined in unless [`NESTED_MIN_STATEMENTS_ENV`] says otherwise
+pub const DEFAULT_NESTED_MIN_STATEMENTS: usize = 3;
+
+/// Minimum body statements for a closure or callback to become a symbol
+pub const NESTED_MIN_STATEMENTS_ENV: &str = "SEMFORA_NESTED_MIN_STATEMENTS";
+
+/// Minimum body statements for a closure or callback to become a symbol
+pub fn min_statements() -> usize {
+    parse_min_statements(std::env::var(NESTED_MIN_STATEMENTS_ENV).ok().as_deref())
+}
+
+fn parse_min_statements(value: Option<&str>) -> usize {
+    value
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_NESTED_MIN_STATEMENTS)
+}
+
+/// Whether symbols of this kind have a body and can be nested in one another
+fn is_callable(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Function | SymbolKind::Method | SymbolKind::Component
+    )
+}
+
+/// Set `parent_symbol` and `nested_id` on the symbols nested in another
+pub fn link_nested_symbols(summary: &mut SemanticSummary) {
+    let symbols = &summary.symbols;
+
+    // Parents before their children, siblings in source order
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    order.sort_by_key(|&i| {
+        (
+            symbols[i].start_line,
+            std::cmp::Reverse(symbols[i].end_line),
+        )
+    });
+
+    let mut links: Vec<(usize, Option<usize>, String)> = Vec::new();
+    let mut paths: Vec<Option<String>> = vec![None; symbols.len()];
+    let mut ordinals: HashMap<String, usize> = HashMap::new();
+
+    for &idx in &order {
+        let symbol = &symbols[idx];
+        if !is_callable(symbol.kind) {
+            continue;
+        }
+        let parent = innermost_container(symbols, idx)
+            .filter(|&p| is_callable(symbols[p].kind) || symbol.is_anonymous());
+        if parent.is_none() && !symbol.is_anonymous() {
+            continue;
+        }
+
+        let base = match parent {
+            Some(p) => {
+                let parent_path = paths[p]
+                    .clone()
+                    .unwrap_or_else(|| symbols[p].identity_key());
+                format!("{}/{}", parent_path, symbol.identity_key())
+            }
+            None => symbol.identity_key(),
+        };
+        let ordinal = ordinals.entry(base.clone()).or_insert(0);
+        let nested_id = format!("{}#{}", base, ordinal);
+        *ordinal += 1;
+
+        paths[idx] = Some(nested_id.clone());
+        links.push((idx, parent, nested_id));
+    }
+
+    for (idx, parent, nested_id) in links {
+        let parent_name = parent.map(|p| summary.symbols[p].name.clone());
+        let symbol = &mut summary.symbols[idx];
+        symbol.parent_symbol = parent_name;
+        symbol.nested_id = Some(nested_id);
+    }
+}
+
+/// Index of the smallest non-variable symbol strictly containing `symbols[idx]`
+fn innermost_container(symbols: &[SymbolInfo], idx: usize) -> Option<usize> {
+    let inner = &symbols[idx];
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(i, s)| {
+            *i != idx
+                && s.kind != SymbolKind::Variable
+                && s.start_line <= inner.start_line
+                && s.end_line >= inner.end_line
+                && (s.start_line, s.end_line) != (inner.start_line, inner.end_line)
+        })
+        .min_by_key(|(_, s)| s.end_line - s.start_line)
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::anonymous_symbol_name;
+
+    fn symbol(name: &str, kind: SymbolKind, start_line: usize, end_line: usize) -> SymbolInfo {
+        SymbolInfo {
+            name: name.to_string(),
+            kind,
+            start_line,
+            end_line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_min_statements() {
+        assert_eq!(parse_min_statements(None), DEFAULT_NESTED_MIN_STATEMENTS);
+        assert_eq!(parse_min_statements(Some(" 5 ")), 5);
+        assert_eq!(
+            parse_min_statements(Some("many")),
+            DEFAULT_NESTED_MIN_STATEMENTS
+        );
+    }
+
+    #[test]
+    fn test_link_nested_symbols() {
+        let mut summary = SemanticSummary {
+            symbols: vec![
+                symbol("outer", SymbolKind::Function, 1, 20),
+                symbol(&anonymous_symbol_name(3), SymbolKind::Function, 3, 8),
+                symbol(&anonymous_symbol_name(10), SymbolKind::Function, 10, 15),
+                symbol("helper", SymbolKind::Function, 11, 14),
+                symbol("Other", SymbolKind::Class, 22, 30),
+                symbol("method", SymbolKind::Function, 23, 29),
+            ],
+            ..Default::default()
+        };
+        link_nested_symbols(&mut summary);
+        let s = &summary.symbols;
+
+        assert_eq!(s[0].parent_symbol, None);
+        assert_eq!(s[0].nested_id, None);
+        assert_eq!(s[1].parent_symbol.as_deref(), Some("outer"));
+        assert_eq!(
+            s[1].nested_id.as_deref(),
+            Some("outer:function:0/<anon>:function:0#0")
+        );
+        assert_eq!(
+            s[2].nested_id.as_deref(),
+            Some("outer:function:0/<anon>:function:0#1")
+        );
+        assert_eq!(s[3].parent_symbol.as_deref(), Some(s[2].name.as_str()));
+        assert_eq!(
+            s[3].nested_id.as_deref(),
+            Some("outer:function:0/<anon>:function:0#1/helper:function:0#0")
+        );
+
+        // Methods aren't nested in their class
+        assert_eq!(s[5].parent_symbol, None);
+        assert_eq!(summary.parent_of(&s[3]).map(|p| p.start_line), Some(10));
+    }
+
+    #[test]
+    fn test_anonymous_hash_ignores_line() {
+        let mut a = symbol(&anonymous_symbol_name(3), SymbolKind::Function, 3, 8);
+        let mut b = symbol(&anonymous_symbol_name(7), SymbolKind::Function, 7, 12);
+        a.nested_id = Some("outer:function:0/<anon>:function:0#0".to_string());
+        b.nested_id = a.nested_id.clone();
+        assert_eq!(
+            a.to_symbol_id("app", "app.ts").hash,
+            b.to_symbol_id("app", "app.ts").hash
+        );
+
+        b.nested_id = Some("outer:function:0/<anon>:function:0#1".to_string());
+        assert_ne!(
+            a.to_symbol_id("app", "app.ts").hash,
+            b.to_symbol_id("app", "app.ts").hash
+        );
+    }
+}
@@ -0,0 +1,645 @@
+//! OpenAPI / Swagger contract extractor
+//!
+//! Recognizes OpenAPI documents (YAML or JSON with a top-level `openapi` or
+//! `swagger` key) and extracts their contract:
+//! - Paths (e.g. `/users/{id}`) as `SymbolKind::Contract` symbols
+//! - Operations as `SymbolKind::Rpc` symbols, named by `operationId` when present
+//! - Schema components (`components.schemas` / `definitions`) as
+//!   `SymbolKind::Contract` symbols with their properties as arguments
+
+use std::collections::HashMap;
+
+use tree_sitter::{Node, Tree};
+
+use crate::detectors::common::get_node_text;
+use crate::error::Result;
+use crate::lang::Lang;
+use crate::schema::{Argument, SemanticSummary, SymbolInfo, SymbolKind};
+
+/// HTTP methods that introduce an operation under a path item
+const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// A parsed OpenAPI document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenApiDoc {
+    /// `info.title`, if present
+    pub title: Option<String>,
+    pub paths: Vec<ApiPath>,
+    pub schemas: Vec<ApiSchema>,
+}
+
+/// A path item and its operations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiPath {
+    pub path: String,
+    pub operations: Vec<ApiOperation>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// An operation (HTTP method) on a path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiOperation {
+    /// Upper-case HTTP method
+    pub method: String,
+    pub operation_id: Option<String>,
+    /// Declared response codes (e.g. `200`, `404`, `default`)
+    pub responses: Vec<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl ApiOperation {
+    /// Display name: `operationId` when present, otherwise `METHOD /path`
+    pub fn display_name(&self, path: &str) -> String {
+        self.operation_id
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.method, path))
+    }
+}
+
+/// A schema component
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiSchema {
+    pub name: String,
+    /// Property name and type (`$ref` targets are reduced to the schema name)
+    pub properties: Vec<(String, Option<String>)>,
+    pub required: Vec<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Whether `tree` holds an OpenAPI document
+pub fn is_openapi(source: &str, tree: &Tree, lang: Lang) -> bool {
+    document_value(source, tree, lang)
+        .is_some_and(|doc| doc.get("openapi").is_some() || doc.get("swagger").is_some())
+}
+
+/// Extract semantic information from an OpenAPI document
+pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree, lang: Lang) -> Result<()> {
+    let Some(doc) = parse_tree(source, tree, lang) else {
+        return Ok(());
+    };
+
+    for path in &doc.paths {
+        summary.symbols.push(SymbolInfo {
+            name: path.path.clone(),
+            kind: SymbolKind::Contract,
+            start_line: path.start_line,
+            end_line: path.end_line,
+            is_exported: true,
+            ..Default::default()
+        });
+
+        for op in &path.operations {
+            summary.symbols.push(SymbolInfo {
+                name: op.display_name(&path.path),
+                kind: SymbolKind::Rpc,
+                start_line: op.start_line,
+                end_line: op.end_line,
+                is_exported: true,
+                decorators: vec![format!("{} {}", op.method, path.path)],
+                return_type: (!op.responses.is_empty()).then(|| op.responses.join("|")),
+                ..Default::default()
+            });
+        }
+    }
+
+    for schema in &doc.schemas {
+        summary.symbols.push(SymbolInfo {
+            name: schema.name.clone(),
+            kind: SymbolKind::Contract,
+            start_line: schema.start_line,
+            end_line: schema.end_line,
+            is_exported: true,
+            arguments: schema
+                .properties
+                .iter()
+                .map(|(name, ty)| Argument {
+                    name: name.clone(),
+                    arg_type: ty.clone(),
+                    default_value: None,
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    summary.symbol = doc
+        .title
+        .clone()
+        .or_else(|| doc.paths.first().map(|p| p.path.clone()));
+    summary.symbol_kind = Some(SymbolKind::Contract);
+    summary.public_surface_changed = true;
+
+    let op_count: usize = doc.paths.iter().map(|p| p.operations.len()).sum();
+    summary.insertions.push(format!(
+        "OpenAPI contract: {} paths ({} operations), {} schemas",
+        doc.paths.len(),
+        op_count,
+        doc.schemas.len()
+    ));
+
+    summary.extraction_complete = true;
+    Ok(())
+}
+
+/// Parse OpenAPI source text, returning `None` if it is not an OpenAPI document
+pub fn parse(source: &str, lang: Lang) -> Option<OpenApiDoc> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&lang.tree_sitter_language()).ok()?;
+    let tree = parser.parse(source, None)?;
+    parse_tree(source, &tree, lang)
+}
+
+fn parse_tree(source: &str, tree: &Tree, lang: Lang) -> Option<OpenApiDoc> {
+    let root = document_value(source, tree, lang)?;
+    if root.get("openapi").is_none() && root.get("swagger").is_none() {
+        return None;
+    }
+
+    let mut doc = OpenApiDoc {
+        title: root
+            .get("info")
+            .and_then(|info| info.get("title"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    for (path, item) in root.get("paths").map(Value::entries).unwrap_or_default() {
+        let operations = item
+            .entries()
+            .into_iter()
+            .filter(|(method, _)| HTTP_METHODS.contains(&method.to_lowercase().as_str()))
+            .map(|(method, op)| ApiOperation {
+                method: method.to_uppercase(),
+                operation_id: op
+                    .get("operationId")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                responses: op
+                    .get("responses")
+                    .map(|r| r.entries().into_iter().map(|(code, _)| code).collect())
+                    .unwrap_or_default(),
+                start_line: op.start_line,
+                end_line: op.end_line,
+            })
+            .collect();
+
+        doc.paths.push(ApiPath {
+            path,
+            operations,
+            start_line: item.start_line,
+            end_line: item.end_line,
+        });
+    }
+
+    // OpenAPI 3 keeps schemas under components, Swagger 2 under definitions
+    let schemas = root
+        .get("components")
+        .and_then(|c| c.get("schemas"))
+        .or_else(|| root.get("definitions"));
+    for (name, schema) in schemas.map(Value::entries).unwrap_or_default() {
+        doc.schemas.push(ApiSchema {
+            name,
+            properties: schema
+                .get("properties")
+                .map(Value::entries)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(prop, value)| (prop, schema_type(&value)))
+                .collect(),
+            required: schema
+                .get("required")
+                .map(|r| {
+                    r.items()
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            start_line: schema.start_line,
+            end_line: schema.end_line,
+        });
+    }
+
+    Some(doc)
+}
+
+/// Classify contract changes between two versions of an OpenAPI document
+///
+/// Returns `(element, reason)` pairs for every change that breaks existing
+/// clients: removed paths, operations, response codes, schemas and schema
+/// properties, and properties that became required.
+pub fn breaking_changes(before: &OpenApiDoc, after: &OpenApiDoc) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+
+    let after_paths: HashMap<&str, &ApiPath> =
+        after.paths.iter().map(|p| (p.path.as_str(), p)).collect();
+    for path in &before.paths {
+        let Some(new) = after_paths.get(path.path.as_str()) else {
+            changes.push((path.path.clone(), "path removed".to_string()));
+            continue;
+        };
+        for op in &path.operations {
+            let element = format!("{} {}", op.method, path.path);
+            let Some(new_op) = new.operations.iter().find(|o| o.method == op.method) else {
+                changes.push((element, "operation removed".to_string()));
+                continue;
+            };
+            for code in &op.responses {
+                if !new_op.responses.contains(code) {
+                    changes.push((element.clone(), format!("response {} removed", code)));
+                }
+            }
+            if op.operation_id.is_some() && new_op.operation_id != op.operation_id {
+                changes.push((
+                    element,
+                    format!(
+                        "operationId changed ({} -> {})",
+                        op.operation_id.as_deref().unwrap_or_default(),
+                        new_op.operation_id.as_deref().unwrap_or("none")
+                    ),
+                ));
+            }
+        }
+    }
+
+    let after_schemas: HashMap<&str, &ApiSchema> =
+        after.schemas.iter().map(|s| (s.name.as_str(), s)).collect();
+    for schema in &before.schemas {
+        let Some(new) = after_schemas.get(schema.name.as_str()) else {
+            changes.push((schema.name.clone(), "schema removed".to_string()));
+            continue;
+        };
+        for (prop, ty) in &schema.properties {
+            let element = format!("{}.{}", schema.name, prop);
+            match new.properties.iter().find(|(p, _)| p == prop) {
+                None => changes.push((element, "property removed".to_string())),
+                Some((_, new_ty)) if new_ty != ty => changes.push((
+                    element,
+                    format!(
+                        "property type changed ({} -> {})",
+                        ty.as_deref().unwrap_or("any"),
+                        new_ty.as_deref().unwrap_or("any")
+                    ),
+                )),
+                Some(_) => {}
+            }
+        }
+        for prop in &new.required {
+            if !schema.required.contains(prop) {
+                changes.push((
+                    format!("{}.{}", schema.name, prop),
+                    "property became required".to_string(),
+                ));
+            }
+        }
+    }
+
+    changes
+}
+
+fn schema_type(value: &Value) -> Option<String> {
+    if let Some(reference) = value.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().map(str::to_string);
+    }
+    let ty = value.get("type").and_then(Value::as_str)?;
+    if ty == "array" {
+        let items = value.get("items").and_then(schema_type);
+        return Some(format!("{}[]", items.as_deref().unwrap_or("any")));
+    }
+    Some(ty.to_string())
+}
+
+// ============================================================================
+// Document model
+// ============================================================================
+
+/// Minimal document tree shared by the YAML and JSON grammars
+///
+/// Also used by the `config` detector to index config keys.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Value {
+    pub(crate) kind: ValueKind,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) enum ValueKind {
+    Map(Vec<(String, Value)>),
+    Seq(Vec<Value>),
+    Scalar(String),
+    #[default]
+    Null,
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match &self.kind {
+            ValueKind::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            ValueKind::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn entries(&self) -> Vec<(String, Value)> {
+        match &self.kind {
+            ValueKind::Map(entries) => entries.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn items(&self) -> &[Value] {
+        match &self.kind {
+            ValueKind::Seq(items) => items,
+            _ => &[],
+        }
+    }
+}
+
+/// Top-level mapping of a YAML or JSON document
+pub(crate) fn document_value(source: &str, tree: &Tree, lang: Lang) -> Option<Value> {
+    let root = tree.root_node();
+    let value = match lang {
+        Lang::Yaml => yaml_value(&root, source),
+        Lang::Json => json_value(&root, source),
+        _ => return None,
+    };
+    matches!(value.kind, ValueKind::Map(_)).then_some(value)
+}
+
+fn node_value(node: &Node, kind: ValueKind) -> Value {
+    Value {
+        kind,
+        start_line: node.start_position().row + 1,
+        end_line: end_line(node),
+    }
+}
+
+/// Last line of a node, not counting a trailing newline it swallowed
+fn end_line(node: &Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row
+    } else {
+        end.row + 1
+    }
+}
+
+fn unquote(text: &str) -> String {
+    let trimmed = text.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = trimmed
+            .strip_prefix(quote)
+            .and_then(|t| t.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    trimmed.to_string()
+}
+
+fn yaml_value(node: &Node, source: &str) -> Value {
+    match node.kind() {
+        "block_mapping" | "flow_mapping" => {
+            let mut entries = Vec::new();
+            let mut cursor = node.walk();
+            for pair in node.named_children(&mut cursor) {
+                if pair.kind() != "block_mapping_pair" && pair.kind() != "flow_pair" {
+                    continue;
+                }
+                let Some(key) = pair.child_by_field_name("key") else {
+                    continue;
+                };
+                let mut value = pair
+                    .child_by_field_name("value")
+                    .map(|v| yaml_value(&v, source))
+                    .unwrap_or_default();
+                // Lines cover the whole pair so symbols include their key
+                value.start_line = pair.start_position().row + 1;
+                value.end_line = end_line(&pair);
+                entries.push((unquote(&get_node_text(&key, source)), value));
+            }
+            node_value(node, ValueKind::Map(entries))
+        }
+        "block_sequence" | "flow_sequence" => {
+            let mut cursor = node.walk();
+            let items = node
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() != "comment")
+                .map(|item| {
+                    if item.kind() == "block_sequence_item" {
+                        item.named_child(0)
+                            .map(|v| yaml_value(&v, source))
+                            .unwrap_or_default()
+                    } else {
+                        yaml_value(&item, source)
+                    }
+                })
+                .collect();
+            node_value(node, ValueKind::Seq(items))
+        }
+        "plain_scalar" | "double_quote_scalar" | "single_quote_scalar" | "block_scalar" => {
+            node_value(
+                node,
+                ValueKind::Scalar(unquote(&get_node_text(node, source))),
+            )
+        }
+        _ => {
+            // stream, document, block_node, flow_node: descend into the content
+            let mut cursor = node.walk();
+            let content = node
+                .named_children(&mut cursor)
+                .find(|c| !matches!(c.kind(), "comment" | "tag" | "anchor"));
+            match content {
+                Some(child) => yaml_value(&child, source),
+                None => node_value(node, ValueKind::Null),
+            }
+        }
+    }
+}
+
+fn json_value(node: &Node, source: &str) -> Value {
+    match node.kind() {
+        "object" => {
+            let mut entries = Vec::new();
+            let mut cursor = node.walk();
+            for pair in node.named_children(&mut cursor) {
+                if pair.kind() != "pair" {
+                    continue;
+                }
+                let Some(key) = pair.child_by_field_name("key") else {
+                    continue;
+                };
+                let mut value = pair
+                    .child_by_field_name("value")
+                    .map(|v| json_value(&v, source))
+                    .unwrap_or_default();
+                value.start_line = pair.start_position().row + 1;
+                value.end_line = end_line(&pair);
+                entries.push((unquote(&get_node_text(&key, source)), value));
+            }
+            node_value(node, ValueKind::Map(entries))
+        }
+        "array" => {
+            let mut cursor = node.walk();
+            let items = node
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() != "comment")
+                .map(|item| json_value(&item, source))
+                .collect();
+            node_value(node, ValueKind::Seq(items))
+        }
+        "string" | "number" | "true" | "false" => node_value(
+            node,
+            ValueKind::Scalar(unquote(&get_node_text(node, source))),
+        ),
+        "null" => node_value(node, ValueKind::Null),
+        _ => {
+            let mut cursor = node.walk();
+            let content = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() != "comment");
+            match content {
+                Some(child) => json_value(&child, source),
+                None => node_value(node, ValueKind::Null),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r#"openapi: 3.0.3
+info:
+  title: Pet Store
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: A list of pets
+    post:
+      operationId: createPet
+      responses:
+        "201":
+          description: Created
+  /pets/{petId}:
+    get:
+      operationId: showPetById
+      responses:
+        '200':
+          description: A pet
+        '404':
+          description: Not found
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+        tags:
+          type: array
+          items:
+            $ref: '#/components/schemas/Tag'
+    Tag:
+      type: object
+      properties:
+        label:
+          type: string
+"#;
+
+    #[test]
+    fn test_parse_openapi_yaml() {
+        let doc = parse(PETSTORE, Lang::Yaml).unwrap();
+
+        assert_eq!(doc.title.as_deref(), Some("Pet Store"));
+        let paths: Vec<&str> = doc.paths.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["/pets", "/pets/{petId}"]);
+
+        let list = &doc.paths[0].operations[0];
+        assert_eq!(list.method, "GET");
+        assert_eq!(list.operation_id.as_deref(), Some("listPets"));
+        assert_eq!(list.responses, vec!["200"]);
+        assert_eq!(doc.paths[0].operations[1].responses, vec!["201"]);
+        assert_eq!(doc.paths[1].operations[0].responses, vec!["200", "404"]);
+        assert_eq!(doc.paths[1].start_line, 17);
+
+        let pet = &doc.schemas[0];
+        assert_eq!(pet.name, "Pet");
+        assert_eq!(pet.required, vec!["id", "name"]);
+        assert!(pet
+            .properties
+            .contains(&("tags".to_string(), Some("Tag[]".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_openapi_json() {
+        let source = r#"{
+  "openapi": "3.1.0",
+  "paths": {
+    "/health": { "get": { "responses": { "200": { "description": "ok" } } } }
+  }
+}"#;
+        let doc = parse(source, Lang::Json).unwrap();
+        assert_eq!(doc.paths[0].path, "/health");
+        assert_eq!(
+            doc.paths[0].operations[0].display_name("/health"),
+            "GET /health"
+        );
+    }
+
+    #[test]
+    fn test_non_openapi_document_is_ignored() {
+        assert!(parse("name: app\nversion: 1\n", Lang::Yaml).is_none());
+    }
+
+    #[test]
+    fn test_breaking_path_and_response_changes() {
+        let before = parse(PETSTORE, Lang::Yaml).unwrap();
+
+        let removed_response =
+            PETSTORE.replace("        '404':\n          description: Not found\n", "");
+        let after = parse(&removed_response, Lang::Yaml).unwrap();
+        assert_eq!(
+            breaking_changes(&before, &after),
+            vec![(
+                "GET /pets/{petId}".to_string(),
+                "response 404 removed".to_string()
+            )]
+        );
+
+        let removed_path = PETSTORE.replace("/pets/{petId}:", "/animals/{petId}:");
+        let after = parse(&removed_path, Lang::Yaml).unwrap();
+        assert_eq!(
+            breaking_changes(&before, &after),
+            vec![("/pets/{petId}".to_string(), "path removed".to_string())]
+        );
+
+        // A new optional property is compatible
+        let extended = PETSTORE.replace(
+            "        label:\n",
+            "        color:\n          type: string\n        label:\n",
+        );
+        assert!(breaking_changes(&before, &parse(&extended, Lang::Yaml).unwrap()).is_empty());
+    }
+}
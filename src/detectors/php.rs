@@ -0,0 +1,752 @@
+//! PHP language detector
+//!
+//! Extracts semantic information from PHP source files using the generic extractor,
+//! then fills in what the generic pass can't tell apart: interfaces from traits,
+//! `extends` / `implements` clauses, the file's namespace and its `use` imports.
+//!
+//! Laravel conventions are then mapped onto the summary: controller actions,
+//! route closures in `routes/*.php`, Eloquent models and Artisan command
+//! handlers are invoked by the framework, so they become entry points rather
+//! than dead code.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tree_sitter::{Node, Tree};
+
+use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
+use crate::detectors::generic::extract_with_grammar_at_level;
+use crate::detectors::grammar::PHP_GRAMMAR;
+use crate::error::Result;
+use crate::extract::ExtractionLevel;
+use crate::schema::{
+    anonymous_symbol_name, FrameworkEntryPoint, Import, ImportedName, SemanticSummary, SymbolInfo,
+    SymbolKind,
+};
+
+/// `Route::` methods that register a handler
+const ROUTE_METHODS: &[&str] = &[
+    "get", "post", "put", "patch", "delete", "options", "any", "match", "fallback",
+];
+
+/// Base classes that make a class an Eloquent model
+const ELOQUENT_BASES: &[&str] = &["Model", "Authenticatable", "Pivot", "MorphPivot"];
+
+/// Eloquent relationship builders; a model method returning one is a relation
+const ELOQUENT_RELATIONS: &[&str] = &[
+    "hasOne",
+    "hasMany",
+    "belongsTo",
+    "belongsToMany",
+    "hasOneThrough",
+    "hasManyThrough",
+    "morphTo",
+    "morphOne",
+    "morphMany",
+    "morphToMany",
+    "morphedByMany",
+];
+
+/// Model methods Eloquent calls by name
+const ELOQUENT_HOOKS: &[&str] = &["boot", "booted", "casts", "newFactory"];
+
+/// Extract semantic information from a PHP source file
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
+    let root = tree.root_node();
+
+    // Route closures are registered before the generic pass so the calls
+    // inside them are attributed to the closure rather than the file
+    let routes = if is_routes_file(&summary.file) {
+        extract_route_closures(summary, &root, source)
+    } else {
+        0
+    };
+
+    // The generic extractor handles the rest:
+    // - Symbols: class, interface, trait, enum, method and function declarations
+    // - State changes: assignment_expression
+    // - Control flow: if, switch, match, for, foreach, while, do, try
+    // - Calls: function, member, static and constructor calls
+    // - Risk calculation
+    extract_with_grammar_at_level(summary, source, tree, &PHP_GRAMMAR, level)?;
+    refine_type_declarations(summary, &root, source);
+    extract_namespace(summary, &root, source);
+    extract_use_imports(summary, &root, source);
+    apply_laravel_conventions(summary, routes);
+    Ok(())
+}
+
+/// Whether a file lives under a Laravel `routes/` directory
+fn is_routes_file(file: &str) -> bool {
+    Path::new(file)
+        .parent()
+        .is_some_and(|dir| dir.components().any(|c| c.as_os_str() == "routes"))
+}
+
+/// Add route and console command closures as anonymous function symbols
+///
+/// `Route::get('/users', function () {...})`, chained registrations such as
+/// `Route::middleware('auth')->post(...)` and `$router->get(...)` become
+/// `LaravelRoute` entry points; `Artisan::command('name', function () {...})`
+/// becomes an `ArtisanCommand`. Returns the number of route closures found.
+fn extract_route_closures(summary: &mut SemanticSummary, root: &Node, source: &str) -> usize {
+    let mut routes = 0;
+    visit_all(root, |node| {
+        let entry_point = match node.kind() {
+            "scoped_call_expression" | "member_call_expression" => {
+                let method = node
+                    .child_by_field_name("name")
+                    .map(|n| get_node_text(&n, source))
+                    .unwrap_or_default();
+                match call_root(node, source).as_deref() {
+                    Some("Route" | "$router") if ROUTE_METHODS.contains(&method.as_str()) => {
+                        FrameworkEntryPoint::LaravelRoute
+                    }
+                    Some("Artisan") if method == "command" => FrameworkEntryPoint::ArtisanCommand,
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+        let Some(arguments) = node.child_by_field_name("arguments") else {
+            return;
+        };
+        let mut cursor = arguments.walk();
+        for argument in arguments.named_children(&mut cursor) {
+            let Some(closure) = argument.named_child(0) else {
+                continue;
+            };
+            if !matches!(closure.kind(), "anonymous_function" | "arrow_function") {
+                continue;
+            }
+            let start_line = closure.start_position().row + 1;
+            if entry_point == FrameworkEntryPoint::LaravelRoute {
+                routes += 1;
+            }
+            summary.symbols.push(SymbolInfo {
+                name: anonymous_symbol_name(start_line),
+                kind: SymbolKind::Function,
+                start_line,
+                end_line: closure.end_position().row + 1,
+                framework_entry_point: entry_point,
+                ..Default::default()
+            });
+        }
+    });
+    routes
+}
+
+/// Facade or variable a (possibly chained) call starts from
+///
+/// `Route::middleware('auth')->get(...)` starts from `Route`,
+/// `$router->get(...)` from `$router`.
+fn call_root(node: &Node, source: &str) -> Option<String> {
+    let mut current = *node;
+    loop {
+        match current.kind() {
+            "scoped_call_expression" => {
+                let scope = get_node_text(&current.child_by_field_name("scope")?, source);
+                return Some(simple_name(&scope).to_string());
+            }
+            "member_call_expression" => current = current.child_by_field_name("object")?,
+            "variable_name" => return Some(get_node_text(&current, source)),
+            _ => return None,
+        }
+    }
+}
+
+/// Last segment of a (possibly qualified) PHP name: `\App\Models\User` -> `User`
+fn simple_name(name: &str) -> &str {
+    name.rsplit('\\').next().unwrap_or(name)
+}
+
+/// Tell interfaces from traits and record `extends` / `implements` clauses
+///
+/// The generic pass gives interfaces and traits the same kind; interfaces
+/// get `SymbolKind::Interface` here, and every type gets its parent class
+/// and interfaces as base classes.
+fn refine_type_declarations(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut bases: HashMap<(String, usize), Vec<String>> = HashMap::new();
+    let mut interfaces: Vec<(String, usize)> = Vec::new();
+    visit_all(root, |node| {
+        let kind = node.kind();
+        if !matches!(
+            kind,
+            "class_declaration" | "interface_declaration" | "enum_declaration"
+        ) {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let key = (get_node_text(&name, source), node.start_position().row + 1);
+
+        let mut names = Vec::new();
+        let mut cursor = node.walk();
+        for clause in node.children(&mut cursor) {
+            if !matches!(clause.kind(), "base_clause" | "class_interface_clause") {
+                continue;
+            }
+            let mut inner = clause.walk();
+            for base in clause.named_children(&mut inner) {
+                if matches!(base.kind(), "name" | "qualified_name") {
+                    names.push(
+                        get_node_text(&base, source)
+                            .trim_start_matches('\\')
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        if kind == "interface_declaration" {
+            interfaces.push(key.clone());
+        }
+        if !names.is_empty() {
+            bases.insert(key, names);
+        }
+    });
+
+    for symbol in &mut summary.symbols {
+        let key = (symbol.name.clone(), symbol.start_line);
+        if interfaces.contains(&key) {
+            symbol.kind = SymbolKind::Interface;
+        }
+        if symbol.base_classes.is_empty() {
+            if let Some(names) = bases.remove(&key) {
+                symbol.base_classes = names;
+            }
+        }
+    }
+}
+
+/// Record `namespace App\Http\Controllers;` as a module symbol
+fn extract_namespace(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if node.kind() != "namespace_definition" {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        summary.symbols.push(SymbolInfo {
+            name: get_node_text(&name, source),
+            kind: SymbolKind::Module,
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            is_exported: true,
+            ..Default::default()
+        });
+    });
+}
+
+/// Record `use` imports with the names they bring into scope
+///
+/// `use Illuminate\Http\{Request, Response as HttpResponse};` becomes one
+/// import of `Illuminate\Http` naming `Request` and `Response` (aliased
+/// `HttpResponse`); the fully qualified names are added as dependencies.
+/// Trait uses inside a class body are a different node and aren't imports.
+fn extract_use_imports(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if node.kind() != "namespace_use_declaration" {
+            return;
+        }
+        // Group imports share a prefix: `use App\Models\{User, Post};`
+        let (prefix, clauses) = match node.child_by_field_name("body") {
+            Some(group) => {
+                let mut cursor = node.walk();
+                let prefix = node
+                    .children(&mut cursor)
+                    .find(|c| c.kind() == "namespace_name")
+                    .map(|n| get_node_text(&n, source))
+                    .unwrap_or_default();
+                (prefix, group)
+            }
+            None => (String::new(), *node),
+        };
+
+        let mut cursor = clauses.walk();
+        for clause in clauses.named_children(&mut cursor) {
+            if clause.kind() != "namespace_use_clause" {
+                continue;
+            }
+            let mut inner = clause.walk();
+            let Some(path) = clause
+                .named_children(&mut inner)
+                .find(|c| matches!(c.kind(), "qualified_name" | "name"))
+            else {
+                continue;
+            };
+            let path = get_node_text(&path, source);
+            let full = match prefix.as_str() {
+                "" => path,
+                prefix => format!("{}\\{}", prefix, path),
+            };
+            let full = full.trim_start_matches('\\').to_string();
+            let alias = clause
+                .child_by_field_name("alias")
+                .map(|a| get_node_text(&a, source));
+            push_import(summary, &full, alias);
+        }
+    });
+}
+
+/// Add one imported name, merging names imported from the same namespace
+fn push_import(summary: &mut SemanticSummary, full: &str, alias: Option<String>) {
+    let (namespace, name) = full.rsplit_once('\\').unwrap_or(("", full));
+    let name = ImportedName {
+        name: name.to_string(),
+        alias,
+    };
+    match summary.imports.iter_mut().find(|i| i.source == namespace) {
+        Some(import) => import.names.push(name),
+        None => summary.imports.push(Import {
+            source: namespace.to_string(),
+            names: vec![name],
+            ..Default::default()
+        }),
+    }
+    if !summary.added_dependencies.iter().any(|d| d == full) {
+        summary.added_dependencies.push(full.to_string());
+    }
+}
+
+/// What Laravel uses a class for, judging by its name and parent class
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LaravelRole {
+    Controller,
+    Model,
+    Command,
+}
+
+fn laravel_role(class: &SymbolInfo) -> Option<LaravelRole> {
+    let extends = |names: &[&str]| {
+        class
+            .base_classes
+            .iter()
+            .any(|b| names.contains(&simple_name(b)))
+    };
+    if class.name.ends_with("Controller") || extends(&["Controller"]) {
+        Some(LaravelRole::Controller)
+    } else if extends(ELOQUENT_BASES) {
+        Some(LaravelRole::Model)
+    } else if extends(&["Command"]) {
+        Some(LaravelRole::Command)
+    } else {
+        None
+    }
+}
+
+/// Map Laravel conventions onto symbols
+///
+/// Public methods of controllers are routed to, so they become
+/// `LaravelController` entry points. Eloquent models, and the relations,
+/// scopes and accessors Eloquent calls by name, become `EloquentModel`;
+/// the `handle` method of an Artisan command becomes `ArtisanCommand`.
+fn apply_laravel_conventions(summary: &mut SemanticSummary, routes: usize) {
+    let classes: Vec<(usize, usize, LaravelRole)> = summary
+        .symbols
+        .iter()
+        .filter(|s| s.kind == SymbolKind::Class)
+        .filter_map(|s| laravel_role(s).map(|role| (s.start_line, s.end_line, role)))
+        .collect();
+
+    let mut actions = 0;
+    let mut models = 0;
+    for symbol in &mut summary.symbols {
+        if symbol.kind == SymbolKind::Class {
+            if laravel_role(symbol) == Some(LaravelRole::Model) {
+                symbol.framework_entry_point = FrameworkEntryPoint::EloquentModel;
+                models += 1;
+            }
+            continue;
+        }
+        if symbol.kind != SymbolKind::Function || !symbol.framework_entry_point.is_none() {
+            continue;
+        }
+        let Some(&(_, _, role)) = classes
+            .iter()
+            .find(|(start, end, _)| symbol.start_line > *start && symbol.end_line <= *end)
+        else {
+            continue;
+        };
+        match role {
+            LaravelRole::Controller if is_controller_action(symbol) => {
+                symbol.framework_entry_point = FrameworkEntryPoint::LaravelController;
+                actions += 1;
+            }
+            LaravelRole::Model if is_eloquent_hook(symbol) => {
+                symbol.framework_entry_point = FrameworkEntryPoint::EloquentModel;
+            }
+            LaravelRole::Command if symbol.name == "handle" => {
+                symbol.framework_entry_point = FrameworkEntryPoint::ArtisanCommand;
+            }
+            _ => {}
+        }
+    }
+
+    if actions > 0 {
+        push_unique_insertion(
+            &mut summary.insertions,
+            format!("{} Laravel controller actions", actions),
+            "Laravel controller",
+        );
+    }
+    if routes > 0 {
+        push_unique_insertion(
+            &mut summary.insertions,
+            format!("{} Laravel route closures", routes),
+            "Laravel route",
+        );
+    }
+
+    if summary.framework_entry_point.is_none() {
+        summary.framework_entry_point = if actions > 0 {
+            FrameworkEntryPoint::LaravelController
+        } else if routes > 0 {
+            FrameworkEntryPoint::LaravelRoute
+        } else if summary
+            .symbols
+            .iter()
+            .any(|s| s.framework_entry_point == FrameworkEntryPoint::ArtisanCommand)
+        {
+            FrameworkEntryPoint::ArtisanCommand
+        } else if models > 0 {
+            FrameworkEntryPoint::EloquentModel
+        } else {
+            FrameworkEntryPoint::None
+        };
+    }
+}
+
+/// Public controller methods are actions; magic methods other than
+/// `__invoke` (single-action controllers) are not
+fn is_controller_action(symbol: &SymbolInfo) -> bool {
+    symbol.is_exported && (!symbol.name.starts_with("__") || symbol.name == "__invoke")
+}
+
+/// Model methods Eloquent calls by name: relations, `scopeActive`-style
+/// query scopes, `getNameAttribute`-style accessors and mutators, and boot hooks
+fn is_eloquent_hook(symbol: &SymbolInfo) -> bool {
+    let name = symbol.name.as_str();
+    let is_scope = name
+        .strip_prefix("scope")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()));
+    let is_accessor = (name.starts_with("get") || name.starts_with("set"))
+        && name.ends_with("Attribute")
+        && name.len() > "getAttribute".len();
+    is_scope
+        || is_accessor
+        || ELOQUENT_HOOKS.contains(&name)
+        || symbol
+            .calls
+            .iter()
+            .any(|c| ELOQUENT_RELATIONS.contains(&c.name.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duplicate::boilerplate::{classify_boilerplate, BoilerplateCategory};
+    use crate::schema::ControlFlowKind;
+
+    fn extract_php(file: &str, source: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_php::LANGUAGE_PHP.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary {
+            file: file.to_string(),
+            ..Default::default()
+        };
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary
+    }
+
+    #[test]
+    fn test_classes_methods_and_imports() {
+        let summary = extract_php(
+            "src/Billing/Invoice.php",
+            r#"<?php
+namespace App\Billing;
+
+use App\Models\User;
+use Illuminate\Support\{Collection, Str as Strings};
+use function App\Support\money;
+
+interface Payable { public function total(): int; }
+
+trait HasNumber { public function number() { return Strings::uuid(); } }
+
+#[Deprecated]
+final class Invoice extends Document implements Payable, \JsonSerializable
+{
+    use HasNumber;
+
+    public function total(): int
+    {
+        try {
+            return match ($this->status) {
+                'paid' => 0,
+                default => $this->sum(),
+            };
+        } catch (\Exception $e) {
+            report($e);
+        }
+    }
+
+    protected function sum() { return 1; }
+
+    private function audit() {}
+
+    function implicit() {}
+}
+"#,
+        );
+        let symbol = |name: &str| summary.symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(symbol("App\\Billing").kind, SymbolKind::Module);
+        assert_eq!(symbol("Payable").kind, SymbolKind::Interface);
+        assert_eq!(symbol("HasNumber").kind, SymbolKind::Trait);
+        assert_eq!(symbol("Invoice").kind, SymbolKind::Class);
+        assert_eq!(
+            symbol("Invoice").base_classes,
+            vec!["Document", "Payable", "JsonSerializable"]
+        );
+        assert_eq!(symbol("Invoice").decorators, vec!["Deprecated"]);
+
+        // Visibility
+        assert!(!symbol("sum").is_exported);
+        assert!(!symbol("audit").is_exported);
+        assert!(symbol("implicit").is_exported);
+        let total = summary
+            .symbols
+            .iter()
+            .find(|s| s.name == "total" && s.start_line > 10)
+            .unwrap();
+        assert!(total.is_exported);
+        assert!(total.calls.iter().any(|c| c.name == "report"));
+
+        // Control flow, including match and try/catch
+        let kinds: Vec<_> = summary
+            .control_flow_changes
+            .iter()
+            .map(|c| c.kind)
+            .collect();
+        assert!(kinds.contains(&ControlFlowKind::Try));
+        assert!(kinds.contains(&ControlFlowKind::Match));
+
+        // Imports
+        assert_eq!(
+            summary.added_dependencies,
+            vec![
+                "App\\Models\\User",
+                "Illuminate\\Support\\Collection",
+                "Illuminate\\Support\\Str",
+                "App\\Support\\money",
+            ]
+        );
+        let support = summary
+            .imports
+            .iter()
+            .find(|i| i.source == "Illuminate\\Support")
+            .unwrap();
+        assert_eq!(
+            support.names,
+            vec![
+                ImportedName {
+                    name: "Collection".to_string(),
+                    alias: None,
+                },
+                ImportedName {
+                    name: "Str".to_string(),
+                    alias: Some("Strings".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_laravel_controller_and_model() {
+        let controller = extract_php(
+            "app/Http/Controllers/UserController.php",
+            r#"<?php
+namespace App\Http\Controllers;
+
+use App\Models\User;
+use Illuminate\Http\Request;
+
+class UserController extends Controller
+{
+    public function __construct(private UserService $users) {}
+
+    public function index()
+    {
+        return view('users.index', ['users' => User::all()]);
+    }
+
+    public function store(Request $request)
+    {
+        return User::create($request->validated());
+    }
+
+    private function authorizeUser() {}
+}
+"#,
+        );
+        let symbol = |name: &str| controller.symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            symbol("index").framework_entry_point,
+            FrameworkEntryPoint::LaravelController
+        );
+        assert_eq!(
+            symbol("store").framework_entry_point,
+            FrameworkEntryPoint::LaravelController
+        );
+        assert!(symbol("__construct").framework_entry_point.is_none());
+        assert!(symbol("authorizeUser").framework_entry_point.is_none());
+        assert_eq!(
+            controller.framework_entry_point,
+            FrameworkEntryPoint::LaravelController
+        );
+        assert!(controller
+            .insertions
+            .contains(&"2 Laravel controller actions".to_string()));
+
+        // Duplicate detection treats thin actions as expected duplicates
+        for action in ["index", "store"] {
+            assert_eq!(
+                classify_boilerplate(symbol(action), None),
+                Some(BoilerplateCategory::LaravelController),
+                "{}",
+                action
+            );
+        }
+
+        let model = extract_php(
+            "app/Models/User.php",
+            r#"<?php
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Model;
+
+class User extends Model
+{
+    protected $fillable = ['name', 'email'];
+
+    public function posts()
+    {
+        return $this->hasMany(Post::class);
+    }
+
+    public function scopeActive($query)
+    {
+        return $query->where('active', true);
+    }
+
+    public function getNameAttribute($value)
+    {
+        return ucfirst($value);
+    }
+
+    public function isAdmin() { return $this->role === 'admin'; }
+}
+"#,
+        );
+        let symbol = |name: &str| model.symbols.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            symbol("User").framework_entry_point,
+            FrameworkEntryPoint::EloquentModel
+        );
+        for hook in ["posts", "scopeActive", "getNameAttribute"] {
+            assert_eq!(
+                symbol(hook).framework_entry_point,
+                FrameworkEntryPoint::EloquentModel,
+                "{}",
+                hook
+            );
+        }
+        assert!(symbol("isAdmin").framework_entry_point.is_none());
+        assert_eq!(
+            classify_boilerplate(symbol("posts"), None),
+            Some(BoilerplateCategory::EloquentModel)
+        );
+        assert_eq!(
+            model.framework_entry_point,
+            FrameworkEntryPoint::EloquentModel
+        );
+    }
+
+    #[test]
+    fn test_laravel_routes_and_commands() {
+        let routes = extract_php(
+            "routes/web.php",
+            r#"<?php
+use Illuminate\Support\Facades\Route;
+
+Route::get('/', function () {
+    return view('welcome');
+});
+
+Route::middleware('auth')->post('/logout', fn () => auth()->logout());
+
+Route::get('/users', [UserController::class, 'index']);
+"#,
+        );
+        let closures: Vec<_> = routes
+            .symbols
+            .iter()
+            .filter(|s| s.framework_entry_point == FrameworkEntryPoint::LaravelRoute)
+            .collect();
+        assert_eq!(closures.len(), 2);
+        assert_eq!(closures[0].name, "<anon@L4>");
+        assert!(closures[0].calls.iter().any(|c| c.name == "view"));
+        assert!(closures[1].calls.iter().any(|c| c.name == "logout"));
+        assert_eq!(
+            routes.framework_entry_point,
+            FrameworkEntryPoint::LaravelRoute
+        );
+
+        // Outside routes/ the same calls register nothing
+        let elsewhere = extract_php(
+            "app/helpers.php",
+            "<?php\nRoute::get('/', function () {});\n",
+        );
+        assert!(elsewhere.symbols.is_empty());
+
+        let console = extract_php(
+            "routes/console.php",
+            "<?php\nArtisan::command('inspire', function () {\n    $this->comment('hi');\n});\n",
+        );
+        assert_eq!(
+            console.symbols[0].framework_entry_point,
+            FrameworkEntryPoint::ArtisanCommand
+        );
+
+        let command = extract_php(
+            "app/Console/Commands/PruneUsers.php",
+            r#"<?php
+class PruneUsers extends Command
+{
+    protected $signature = 'users:prune';
+
+    public function handle() { User::stale()->delete(); }
+}
+"#,
+        );
+        let handle = command.symbols.iter().find(|s| s.name == "handle").unwrap();
+        assert_eq!(
+            handle.framework_entry_point,
+            FrameworkEntryPoint::ArtisanCommand
+        );
+        assert_eq!(
+            command.framework_entry_point,
+            FrameworkEntryPoint::ArtisanCommand
+        );
+    }
+}
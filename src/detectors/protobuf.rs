@@ -0,0 +1,784 @@
+//! Protocol Buffers contract extractor
+//!
+//! Extracts the service contract described by a `.proto` file:
+//! - Messages and their fields (name, type, field number)
+//! - Enums and their values
+//! - Services and their RPC methods
+//!
+//! Messages, enums and services become `SymbolKind::Contract` symbols and RPC
+//! methods become `SymbolKind::Rpc` symbols, so search and get_symbol work on
+//! them like on any other code symbol.
+//!
+//! Uses a small token-based parser (there is no tree-sitter grammar for
+//! protobuf compatible with tree-sitter 0.25). The Tree parameter is kept for
+//! API consistency but is not used.
+
+use std::collections::HashMap;
+
+use tree_sitter::Tree;
+
+use crate::error::Result;
+use crate::schema::{Argument, SemanticSummary, SymbolInfo, SymbolKind};
+
+/// A parsed `.proto` file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoFile {
+    /// Declared package, if any
+    pub package: Option<String>,
+    /// Imported files
+    pub imports: Vec<String>,
+    /// Messages, including nested ones (named `Outer.Inner`)
+    pub messages: Vec<ProtoMessage>,
+    /// Enums, including nested ones (named `Outer.Inner`)
+    pub enums: Vec<ProtoEnum>,
+    /// Services
+    pub services: Vec<ProtoService>,
+}
+
+/// A message definition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoMessage {
+    pub name: String,
+    pub fields: Vec<ProtoField>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// A message field
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoField {
+    pub name: String,
+    /// Field type, including `repeated`/`optional` labels and map types
+    pub field_type: String,
+    /// Field number (the wire identifier)
+    pub number: u32,
+}
+
+/// An enum definition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoEnum {
+    pub name: String,
+    pub values: Vec<(String, i64)>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// A service definition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoService {
+    pub name: String,
+    pub rpcs: Vec<ProtoRpc>,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// An RPC method of a service
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtoRpc {
+    pub name: String,
+    /// Request message type (prefixed with `stream ` for client streaming)
+    pub request: String,
+    /// Response message type (prefixed with `stream ` for server streaming)
+    pub response: String,
+    pub line: usize,
+}
+
+/// Extract semantic information from a `.proto` file
+pub fn extract(summary: &mut SemanticSummary, source: &str, _tree: &Tree) -> Result<()> {
+    let proto = parse(source);
+
+    for message in &proto.messages {
+        summary.symbols.push(SymbolInfo {
+            name: message.name.clone(),
+            kind: SymbolKind::Contract,
+            start_line: message.start_line,
+            end_line: message.end_line,
+            is_exported: true,
+            // Field numbers are kept as the argument default, mirroring `= N`
+            arguments: message
+                .fields
+                .iter()
+                .map(|f| Argument {
+                    name: f.name.clone(),
+                    arg_type: Some(f.field_type.clone()),
+                    default_value: Some(f.number.to_string()),
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    for proto_enum in &proto.enums {
+        summary.symbols.push(SymbolInfo {
+            name: proto_enum.name.clone(),
+            kind: SymbolKind::Contract,
+            start_line: proto_enum.start_line,
+            end_line: proto_enum.end_line,
+            is_exported: true,
+            arguments: proto_enum
+                .values
+                .iter()
+                .map(|(name, number)| Argument {
+                    name: name.clone(),
+                    arg_type: None,
+                    default_value: Some(number.to_string()),
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    for service in &proto.services {
+        summary.symbols.push(SymbolInfo {
+            name: service.name.clone(),
+            kind: SymbolKind::Contract,
+            start_line: service.start_line,
+            end_line: service.end_line,
+            is_exported: true,
+            ..Default::default()
+        });
+
+        for rpc in &service.rpcs {
+            summary.symbols.push(SymbolInfo {
+                name: rpc.name.clone(),
+                kind: SymbolKind::Rpc,
+                start_line: rpc.line,
+                end_line: rpc.line,
+                is_exported: true,
+                arguments: vec![Argument {
+                    name: "request".to_string(),
+                    arg_type: Some(rpc.request.clone()),
+                    default_value: None,
+                }],
+                return_type: Some(rpc.response.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    // Services are the most significant part of the contract, then messages
+    let primary = proto
+        .services
+        .first()
+        .map(|s| s.name.clone())
+        .or_else(|| proto.messages.first().map(|m| m.name.clone()))
+        .or_else(|| proto.enums.first().map(|e| e.name.clone()));
+    if let Some(name) = primary {
+        summary.symbol = Some(name);
+        summary.symbol_kind = Some(SymbolKind::Contract);
+        summary.public_surface_changed = true;
+    }
+
+    summary
+        .added_dependencies
+        .extend(proto.imports.iter().cloned());
+
+    let rpc_count: usize = proto.services.iter().map(|s| s.rpcs.len()).sum();
+    summary.insertions.push(format!(
+        "protobuf contract{}: {} messages, {} enums, {} services ({} rpcs)",
+        proto
+            .package
+            .as_ref()
+            .map(|p| format!(" {}", p))
+            .unwrap_or_default(),
+        proto.messages.len(),
+        proto.enums.len(),
+        proto.services.len(),
+        rpc_count
+    ));
+
+    summary.extraction_complete = true;
+    Ok(())
+}
+
+/// Parse a `.proto` source into its contract elements
+///
+/// Unknown constructs (options, extensions, reserved ranges) are skipped.
+pub fn parse(source: &str) -> ProtoFile {
+    let tokens = tokenize(source);
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        file: ProtoFile::default(),
+    };
+    parser.parse_body(None);
+    parser.file
+}
+
+/// Classify contract changes between two versions of a `.proto` file
+///
+/// Returns `(element, reason)` pairs for every change that breaks existing
+/// clients: removed or renumbered fields, field type changes, removed
+/// messages, enum values, services and RPC methods, and changed RPC signatures.
+pub fn breaking_changes(before: &ProtoFile, after: &ProtoFile) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+
+    let after_messages: HashMap<&str, &ProtoMessage> = after
+        .messages
+        .iter()
+        .map(|m| (m.name.as_str(), m))
+        .collect();
+    for message in &before.messages {
+        let Some(new) = after_messages.get(message.name.as_str()) else {
+            changes.push((message.name.clone(), "message removed".to_string()));
+            continue;
+        };
+        for field in &message.fields {
+            let element = format!("{}.{}", message.name, field.name);
+            let by_number = new.fields.iter().find(|f| f.number == field.number);
+            let by_name = new.fields.iter().find(|f| f.name == field.name);
+            match (by_name, by_number) {
+                (Some(named), _) if named.number != field.number => changes.push((
+                    element,
+                    format!("field renumbered ({} -> {})", field.number, named.number),
+                )),
+                (Some(named), _) if named.field_type != field.field_type => changes.push((
+                    element,
+                    format!(
+                        "field type changed ({} -> {})",
+                        field.field_type, named.field_type
+                    ),
+                )),
+                (Some(_), _) => {}
+                (None, Some(reused)) if reused.field_type != field.field_type => changes.push((
+                    element,
+                    format!(
+                        "field number {} reused by {} with type {}",
+                        field.number, reused.name, reused.field_type
+                    ),
+                )),
+                // Renamed in place: same number and type stay wire-compatible
+                (None, Some(_)) => {}
+                (None, None) => {
+                    changes.push((element, format!("field removed (#{})", field.number)))
+                }
+            }
+        }
+    }
+
+    let after_enums: HashMap<&str, &ProtoEnum> =
+        after.enums.iter().map(|e| (e.name.as_str(), e)).collect();
+    for proto_enum in &before.enums {
+        let Some(new) = after_enums.get(proto_enum.name.as_str()) else {
+            changes.push((proto_enum.name.clone(), "enum removed".to_string()));
+            continue;
+        };
+        for (name, number) in &proto_enum.values {
+            let element = format!("{}.{}", proto_enum.name, name);
+            match new.values.iter().find(|(n, _)| n == name) {
+                None => changes.push((element, format!("enum value removed (= {})", number))),
+                Some((_, new_number)) if new_number != number => changes.push((
+                    element,
+                    format!("enum value renumbered ({} -> {})", number, new_number),
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+
+    let after_services: HashMap<&str, &ProtoService> = after
+        .services
+        .iter()
+        .map(|s| (s.name.as_str(), s))
+        .collect();
+    for service in &before.services {
+        let Some(new) = after_services.get(service.name.as_str()) else {
+            changes.push((service.name.clone(), "service removed".to_string()));
+            continue;
+        };
+        for rpc in &service.rpcs {
+            let element = format!("{}.{}", service.name, rpc.name);
+            match new.rpcs.iter().find(|r| r.name == rpc.name) {
+                None => changes.push((element, "rpc removed".to_string())),
+                Some(new_rpc) if new_rpc.request != rpc.request => changes.push((
+                    element,
+                    format!(
+                        "rpc request changed ({} -> {})",
+                        rpc.request, new_rpc.request
+                    ),
+                )),
+                Some(new_rpc) if new_rpc.response != rpc.response => changes.push((
+                    element,
+                    format!(
+                        "rpc response changed ({} -> {})",
+                        rpc.response, new_rpc.response
+                    ),
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+
+    changes
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    line: usize,
+}
+
+/// Split source into identifiers, numbers, string literals and punctuation,
+/// dropping comments
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+    let mut line = 1;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token {
+                text: chars[start..i.min(chars.len())].iter().collect(),
+                line,
+            });
+        } else if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '-'))
+            {
+                i += 1;
+            }
+            tokens.push(Token {
+                text: chars[start..i].iter().collect(),
+                line,
+            });
+        } else {
+            tokens.push(Token {
+                text: c.to_string(),
+                line,
+            });
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    file: ProtoFile,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).map(|t| t.text.as_str())
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|t| t.line)
+            .unwrap_or(1)
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(token.text.as_str())
+    }
+
+    /// Skip to just past the next `;` or balanced `{ ... }` block
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token {
+                "{" => depth += 1,
+                "}" if depth <= 1 => return,
+                "}" => depth -= 1,
+                ";" if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse top-level or message-body statements until the closing `}`
+    fn parse_body(&mut self, scope: Option<&str>) {
+        while let Some(token) = self.peek() {
+            match token {
+                "}" => {
+                    self.pos += 1;
+                    return;
+                }
+                "syntax" | "edition" | "option" | "reserved" | "extensions" | "extend" => {
+                    self.skip_statement()
+                }
+                "package" => {
+                    self.pos += 1;
+                    self.file.package = self.next().map(str::to_string);
+                    self.skip_statement();
+                }
+                "import" => {
+                    self.pos += 1;
+                    if matches!(self.peek(), Some("public" | "weak")) {
+                        self.pos += 1;
+                    }
+                    if let Some(path) = self.next() {
+                        self.file
+                            .imports
+                            .push(path.trim_matches(['"', '\'']).to_string());
+                    }
+                    self.skip_statement();
+                }
+                "message" => self.parse_message(scope),
+                "enum" => self.parse_enum(scope),
+                "service" => self.parse_service(),
+                ";" => self.pos += 1,
+                _ => self.skip_statement(),
+            }
+        }
+    }
+
+    fn parse_message(&mut self, scope: Option<&str>) {
+        let start_line = self.line();
+        self.pos += 1;
+        let Some(name) = self.next().map(str::to_string) else {
+            return;
+        };
+        let name = qualify(scope, &name);
+        if self.next() != Some("{") {
+            return;
+        }
+
+        let index = self.file.messages.len();
+        self.file.messages.push(ProtoMessage {
+            name: name.clone(),
+            start_line,
+            ..Default::default()
+        });
+
+        let mut fields = Vec::new();
+        self.parse_message_body(&name, &mut fields);
+
+        let message = &mut self.file.messages[index];
+        message.fields = fields;
+        message.end_line = self.tokens[self.pos.saturating_sub(1)].line;
+    }
+
+    fn parse_message_body(&mut self, name: &str, fields: &mut Vec<ProtoField>) {
+        while let Some(token) = self.peek() {
+            match token {
+                "}" => {
+                    self.pos += 1;
+                    return;
+                }
+                "message" => self.parse_message(Some(name)),
+                "enum" => self.parse_enum(Some(name)),
+                "oneof" => {
+                    // oneof members are regular fields of the enclosing message
+                    self.pos += 2;
+                    if self.peek() == Some("{") {
+                        self.pos += 1;
+                        self.parse_message_body(name, fields);
+                    }
+                }
+                "option" | "reserved" | "extensions" | "extend" => self.skip_statement(),
+                ";" => self.pos += 1,
+                _ => {
+                    if let Some(field) = self.parse_field() {
+                        fields.push(field);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse `[label] type name = number [options];`
+    fn parse_field(&mut self) -> Option<ProtoField> {
+        let start = self.pos;
+        let mut type_parts: Vec<String> = Vec::new();
+        while let Some(token) = self.peek() {
+            if token == "=" || token == ";" || token == "{" || token == "}" {
+                break;
+            }
+            type_parts.push(token.to_string());
+            self.pos += 1;
+        }
+
+        if self.peek() != Some("=") || type_parts.len() < 2 {
+            self.pos = start;
+            self.skip_statement();
+            return None;
+        }
+        self.pos += 1;
+        let number = self.next().and_then(|n| n.parse().ok());
+        self.skip_statement();
+
+        let name = type_parts.pop()?;
+        Some(ProtoField {
+            name,
+            field_type: join_type(&type_parts),
+            number: number?,
+        })
+    }
+
+    fn parse_enum(&mut self, scope: Option<&str>) {
+        let start_line = self.line();
+        self.pos += 1;
+        let Some(name) = self.next().map(str::to_string) else {
+            return;
+        };
+        if self.next() != Some("{") {
+            return;
+        }
+
+        let mut values = Vec::new();
+        while let Some(token) = self.peek() {
+            match token {
+                "}" => {
+                    self.pos += 1;
+                    break;
+                }
+                "option" | "reserved" => self.skip_statement(),
+                ";" => self.pos += 1,
+                _ => {
+                    let value_name = self.next().unwrap_or_default().to_string();
+                    if self.peek() == Some("=") {
+                        self.pos += 1;
+                        if let Some(number) = self.next().and_then(|n| n.parse().ok()) {
+                            values.push((value_name, number));
+                        }
+                    }
+                    self.skip_statement();
+                }
+            }
+        }
+
+        self.file.enums.push(ProtoEnum {
+            name: qualify(scope, &name),
+            values,
+            start_line,
+            end_line: self.tokens[self.pos.saturating_sub(1)].line,
+        });
+    }
+
+    fn parse_service(&mut self) {
+        let start_line = self.line();
+        self.pos += 1;
+        let Some(name) = self.next().map(str::to_string) else {
+            return;
+        };
+        if self.next() != Some("{") {
+            return;
+        }
+
+        let mut rpcs = Vec::new();
+        while let Some(token) = self.peek() {
+            match token {
+                "}" => {
+                    self.pos += 1;
+                    break;
+                }
+                "rpc" => {
+                    let line = self.line();
+                    self.pos += 1;
+                    let rpc_name = self.next().unwrap_or_default().to_string();
+                    let request = self.parse_rpc_type();
+                    if self.peek() == Some("returns") {
+                        self.pos += 1;
+                    }
+                    let response = self.parse_rpc_type();
+                    self.skip_statement();
+                    rpcs.push(ProtoRpc {
+                        name: rpc_name,
+                        request,
+                        response,
+                        line,
+                    });
+                }
+                ";" => self.pos += 1,
+                _ => self.skip_statement(),
+            }
+        }
+
+        self.file.services.push(ProtoService {
+            name,
+            rpcs,
+            start_line,
+            end_line: self.tokens[self.pos.saturating_sub(1)].line,
+        });
+    }
+
+    /// Parse `( [stream] Type )`
+    fn parse_rpc_type(&mut self) -> String {
+        if self.peek() != Some("(") {
+            return String::new();
+        }
+        self.pos += 1;
+        let mut parts = Vec::new();
+        while let Some(token) = self.next() {
+            if token == ")" {
+                break;
+            }
+            parts.push(token.to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+fn qualify(scope: Option<&str>, name: &str) -> String {
+    match scope {
+        Some(scope) => format!("{}.{}", scope, name),
+        None => name.to_string(),
+    }
+}
+
+/// Join type tokens, keeping `map<K, V>` compact
+fn join_type(parts: &[String]) -> String {
+    let mut out = String::new();
+    for part in parts {
+        let glue = !out.is_empty()
+            && !matches!(part.as_str(), "<" | ">" | ",")
+            && !out.ends_with('<')
+            && !out.ends_with(' ');
+        if glue {
+            out.push(' ');
+        }
+        out.push_str(part);
+        if part == "," {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_PROTO: &str = r#"
+syntax = "proto3";
+
+package users.v1;
+
+import "google/protobuf/timestamp.proto";
+
+// A user account
+message User {
+  string id = 1;
+  string email = 2;
+  repeated string roles = 3;
+  map<string, string> labels = 4;
+  oneof contact {
+    string phone = 5;
+    string pager = 6;
+  }
+
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    ACTIVE = 1;
+  }
+  Status status = 7 [deprecated = true];
+}
+
+message GetUserRequest {
+  string id = 1;
+}
+
+service UserService {
+  rpc GetUser(GetUserRequest) returns (User);
+  rpc WatchUsers(GetUserRequest) returns (stream User) {
+    option idempotency_level = NO_SIDE_EFFECTS;
+  }
+}
+"#;
+
+    #[test]
+    fn test_parse_proto_file() {
+        let proto = parse(USER_PROTO);
+
+        assert_eq!(proto.package.as_deref(), Some("users.v1"));
+        assert_eq!(proto.imports, vec!["google/protobuf/timestamp.proto"]);
+
+        let user = proto.messages.iter().find(|m| m.name == "User").unwrap();
+        let fields: Vec<(&str, &str, u32)> = user
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.field_type.as_str(), f.number))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", "string", 1),
+                ("email", "string", 2),
+                ("roles", "repeated string", 3),
+                ("labels", "map<string, string>", 4),
+                ("phone", "string", 5),
+                ("pager", "string", 6),
+                ("status", "Status", 7),
+            ]
+        );
+        assert_eq!(user.start_line, 9);
+        assert_eq!(user.end_line, 24);
+
+        assert_eq!(proto.enums[0].name, "User.Status");
+        assert_eq!(proto.enums[0].values[1], ("ACTIVE".to_string(), 1));
+
+        let service = &proto.services[0];
+        assert_eq!(service.name, "UserService");
+        assert_eq!(service.rpcs.len(), 2);
+        assert_eq!(service.rpcs[0].request, "GetUserRequest");
+        assert_eq!(service.rpcs[1].response, "stream User");
+    }
+
+    #[test]
+    fn test_breaking_field_changes() {
+        let before = parse(USER_PROTO);
+        let after = parse(
+            &USER_PROTO
+                .replace("string email = 2;", "")
+                .replace("repeated string roles = 3;", "repeated string roles = 8;")
+                .replace("rpc GetUser(GetUserRequest) returns (User);", ""),
+        );
+
+        let changes = breaking_changes(&before, &after);
+        let elements: Vec<&str> = changes.iter().map(|(e, _)| e.as_str()).collect();
+        assert!(elements.contains(&"User.email"));
+        assert!(elements.contains(&"User.roles"));
+        assert!(elements.contains(&"UserService.GetUser"));
+        assert_eq!(changes.len(), 3);
+
+        // Adding a field is not breaking
+        let extended =
+            parse(&USER_PROTO.replace("string id = 1;", "string id = 1;\n  int64 age = 9;"));
+        assert!(breaking_changes(&before, &extended).is_empty());
+    }
+}
@@ -0,0 +1,539 @@
+//! FastAPI Framework Detector
+//!
+//! Specialized extraction for FastAPI applications including:
+//! - Route handlers registered with `@app.get(...)`, `@router.post(...)`, etc.
+//!   (recorded in `summary.routes`, with `APIRouter(prefix=...)` applied)
+//! - Dependency injection via `Depends(provider)`
+//!
+//! Route handlers are invoked by the framework, so they are marked as
+//! `FrameworkEntryPoint::ApiRoute` to keep them out of dead-code reports.
+//! Providers passed to `Depends()` are never called directly either; they are
+//! recorded as calls from the handler that declares them so the call graph
+//! links handlers to their dependencies.
+
+use std::collections::HashMap;
+
+use tree_sitter::Node;
+
+use crate::detectors::common::{get_node_text, push_unique_insertion, visit_all};
+use crate::schema::{Call, FrameworkEntryPoint, Location, RouteInfo, SemanticSummary};
+
+/// Names whose import marks a file as using FastAPI
+const FASTAPI_IMPORTS: &[&str] = &["FastAPI", "APIRouter", "Depends"];
+
+/// Route registration methods on `FastAPI` and `APIRouter` instances
+const ROUTE_METHODS: &[&str] = &[
+    "get",
+    "post",
+    "put",
+    "delete",
+    "patch",
+    "options",
+    "head",
+    "trace",
+    "api_route",
+    "websocket",
+];
+
+/// Enhance semantic summary with FastAPI-specific information
+///
+/// This is called when FastAPI is detected in the file.
+pub fn enhance(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    mark_route_handlers(summary, root, source);
+    extract_dependencies(summary, root, source);
+}
+
+/// Check whether the file imports FastAPI, APIRouter or Depends
+///
+/// Matches `from fastapi import ...` (including submodules such as
+/// `fastapi.params`) and plain `import fastapi`.
+pub fn is_fastapi(root: &Node, source: &str) -> bool {
+    let mut found = false;
+    visit_all(root, |node| {
+        if found {
+            return;
+        }
+        match node.kind() {
+            "import_from_statement" => {
+                let Some(module) = node.child_by_field_name("module_name") else {
+                    return;
+                };
+                let module = get_node_text(&module, source);
+                if module != "fastapi" && !module.starts_with("fastapi.") {
+                    return;
+                }
+                let mut cursor = node.walk();
+                found = node
+                    .children_by_field_name("name", &mut cursor)
+                    .any(|name| FASTAPI_IMPORTS.contains(&imported_name(&name, source).as_str()));
+            }
+            "import_statement" => {
+                let mut cursor = node.walk();
+                found = node
+                    .children_by_field_name("name", &mut cursor)
+                    .any(|name| imported_name(&name, source) == "fastapi");
+            }
+            _ => {}
+        }
+    });
+    found
+}
+
+/// Check whether a decorator (as recorded in `SymbolInfo::decorators`)
+/// registers a route, e.g. `router.get("/items/{id}")`
+pub fn is_route_decorator(decorator: &str) -> bool {
+    let decorator = decorator.trim().trim_start_matches('@');
+    let Some((target, _)) = decorator.split_once('(') else {
+        return false;
+    };
+    target
+        .rsplit_once('.')
+        .is_some_and(|(_, method)| ROUTE_METHODS.contains(&method))
+}
+
+// =============================================================================
+// Route Handler Detection
+// =============================================================================
+
+/// Mark functions decorated with a route registration as API routes
+fn mark_route_handlers(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut handlers: Vec<(String, usize)> = Vec::new(); // (name, def line)
+    let prefixes = router_prefixes(root, source);
+    let mut routes: Vec<RouteInfo> = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "decorated_definition" {
+            return;
+        }
+        let Some(definition) = node.child_by_field_name("definition") else {
+            return;
+        };
+        if definition.kind() != "function_definition" {
+            return;
+        }
+        let mut cursor = node.walk();
+        let is_route = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "decorator")
+            .any(|decorator| is_route_decorator(&get_node_text(&decorator, source)));
+        if let (true, Some(name)) = (is_route, definition.child_by_field_name("name")) {
+            let name = get_node_text(&name, source);
+            let line = definition.start_position().row + 1;
+            let mut cursor = node.walk();
+            for decorator in node.children(&mut cursor) {
+                for (method, path) in decorator_routes(&decorator, source, &prefixes) {
+                    routes.push(RouteInfo::new(
+                        "fastapi",
+                        &method,
+                        path,
+                        Some(name.clone()),
+                        line,
+                    ));
+                }
+            }
+            handlers.push((name, line));
+        }
+    });
+
+    summary.routes.extend(routes);
+    if handlers.is_empty() {
+        return;
+    }
+
+    for symbol in &mut summary.symbols {
+        let is_handler = handlers.iter().any(|(name, line)| {
+            *name == symbol.name && symbol.start_line <= *line && *line <= symbol.end_line
+        });
+        if is_handler {
+            symbol.framework_entry_point = FrameworkEntryPoint::ApiRoute;
+        }
+    }
+
+    summary.framework_entry_point = FrameworkEntryPoint::ApiRoute;
+    push_unique_insertion(
+        &mut summary.insertions,
+        format!("{} FastAPI route handlers", handlers.len()),
+        "FastAPI route",
+    );
+}
+
+/// `prefix=` of routers created in this file, by variable name
+///
+/// `router = APIRouter(prefix="/items")` maps `router` to `/items`.
+fn router_prefixes(root: &Node, source: &str) -> HashMap<String, String> {
+    let mut prefixes = HashMap::new();
+    visit_all(root, |node| {
+        if node.kind() != "assignment" {
+            return;
+        }
+        let (Some(target), Some(value)) = (
+            node.child_by_field_name("left"),
+            node.child_by_field_name("right"),
+        ) else {
+            return;
+        };
+        let is_router = value.kind() == "call"
+            && value
+                .child_by_field_name("function")
+                .is_some_and(|f| get_node_text(&f, source).ends_with("APIRouter"));
+        if !is_router || target.kind() != "identifier" {
+            return;
+        }
+        if let Some(prefix) = value
+            .child_by_field_name("arguments")
+            .and_then(|args| keyword_argument(&args, "prefix", source))
+            .and_then(|prefix| string_literal(&prefix, source))
+        {
+            prefixes.insert(get_node_text(&target, source), prefix);
+        }
+    });
+    prefixes
+}
+
+/// The (method, path) pairs a decorator like `@router.get("/{id}")` registers
+///
+/// `api_route` registers each of its `methods=[...]` (GET by default).
+fn decorator_routes(
+    decorator: &Node,
+    source: &str,
+    prefixes: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let Some(call) = decorator.named_child(0).filter(|c| c.kind() == "call") else {
+        return Vec::new();
+    };
+    let Some(function) = call
+        .child_by_field_name("function")
+        .filter(|f| f.kind() == "attribute")
+    else {
+        return Vec::new();
+    };
+    let (Some(object), Some(method), Some(args)) = (
+        function.child_by_field_name("object"),
+        function.child_by_field_name("attribute"),
+        call.child_by_field_name("arguments"),
+    ) else {
+        return Vec::new();
+    };
+    let method = get_node_text(&method, source);
+    if !ROUTE_METHODS.contains(&method.as_str()) {
+        return Vec::new();
+    }
+
+    let mut cursor = args.walk();
+    let path = args
+        .named_children(&mut cursor)
+        .next()
+        .filter(|arg| arg.kind() == "string")
+        .or_else(|| keyword_argument(&args, "path", source))
+        .and_then(|path| string_literal(&path, source))
+        .unwrap_or_default();
+    let prefix = prefixes
+        .get(&get_node_text(&object, source))
+        .map(String::as_str)
+        .unwrap_or("");
+    let path = format!("{}{}", prefix.trim_end_matches('/'), path);
+    let path = if path.is_empty() {
+        "/".to_string()
+    } else {
+        path
+    };
+
+    let methods = match method.as_str() {
+        "api_route" => {
+            let methods: Vec<String> = keyword_argument(&args, "methods", source)
+                .map(|list| {
+                    let mut cursor = list.walk();
+                    list.named_children(&mut cursor)
+                        .filter_map(|m| string_literal(&m, source))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if methods.is_empty() {
+                vec!["GET".to_string()]
+            } else {
+                methods
+            }
+        }
+        _ => vec![method],
+    };
+    methods.into_iter().map(|m| (m, path.clone())).collect()
+}
+
+/// Value of the keyword argument `name` in an argument list
+fn keyword_argument<'a>(args: &Node<'a>, name: &str, source: &str) -> Option<Node<'a>> {
+    let mut cursor = args.walk();
+    let found = args
+        .named_children(&mut cursor)
+        .filter(|arg| arg.kind() == "keyword_argument")
+        .find(|arg| {
+            arg.child_by_field_name("name")
+                .is_some_and(|n| get_node_text(&n, source) == name)
+        });
+    found.and_then(|arg| arg.child_by_field_name("value"))
+}
+
+/// Contents of a plain string literal
+fn string_literal(node: &Node, source: &str) -> Option<String> {
+    if node.kind() != "string" {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let content = node
+        .named_children(&mut cursor)
+        .filter(|c| c.kind() == "string_content")
+        .map(|c| get_node_text(&c, source))
+        .collect();
+    Some(content)
+}
+
+// =============================================================================
+// Dependency Injection
+// =============================================================================
+
+/// Record `Depends(provider)` as a call to `provider`
+///
+/// The call is attributed to the innermost symbol containing it (typically
+/// the handler whose parameter declares the dependency), or to the file when
+/// it appears at module level, e.g. `APIRouter(dependencies=[Depends(auth)])`.
+fn extract_dependencies(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut providers: Vec<Call> = Vec::new();
+
+    visit_all(root, |node| {
+        if node.kind() != "call" {
+            return;
+        }
+        let is_depends = node
+            .child_by_field_name("function")
+            .is_some_and(|f| get_node_text(&f, source) == "Depends");
+        if !is_depends {
+            return;
+        }
+        if let Some(call) = node
+            .child_by_field_name("arguments")
+            .and_then(|args| provider_call(&args, source))
+        {
+            providers.push(call);
+        }
+    });
+
+    for call in providers {
+        let line = call.location.line;
+        let owner = summary
+            .symbols
+            .iter_mut()
+            .filter(|s| s.start_line <= line && line <= s.end_line)
+            .min_by_key(|s| s.end_line - s.start_line);
+        let calls = match owner {
+            Some(symbol) => &mut symbol.calls,
+            None => &mut summary.calls,
+        };
+        if !calls
+            .iter()
+            .any(|c| c.name == call.name && c.object == call.object)
+        {
+            calls.push(call);
+        }
+    }
+}
+
+/// The provider passed to `Depends(...)`, as a call
+///
+/// `Depends()` without a provider (class dependencies resolved from the type
+/// annotation) yields nothing.
+fn provider_call(args: &Node, source: &str) -> Option<Call> {
+    let mut cursor = args.walk();
+    let provider = args.named_children(&mut cursor).find(|child| {
+        matches!(child.kind(), "identifier" | "attribute")
+            || (child.kind() == "keyword_argument"
+                && child
+                    .child_by_field_name("name")
+                    .is_some_and(|n| get_node_text(&n, source) == "dependency"))
+    })?;
+    let provider = if provider.kind() == "keyword_argument" {
+        provider.child_by_field_name("value")?
+    } else {
+        provider
+    };
+
+    let (name, object) = match provider.kind() {
+        "identifier" => (get_node_text(&provider, source), None),
+        "attribute" => (
+            get_node_text(&provider.child_by_field_name("attribute")?, source),
+            Some(get_node_text(
+                &provider.child_by_field_name("object")?,
+                source,
+            )),
+        ),
+        _ => return None,
+    };
+
+    let position = provider.start_position();
+    Some(Call {
+        name,
+        object,
+        location: Location::new(position.row + 1, position.column),
+        ..Default::default()
+    })
+}
+
+/// Name bound by an import clause entry (`x`, `x.y` or `x as y` -> `x`)
+fn imported_name(node: &Node, source: &str) -> String {
+    let name = match node.kind() {
+        "aliased_import" => node.child_by_field_name("name").unwrap_or(*node),
+        _ => *node,
+    };
+    get_node_text(&name, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::extract;
+    use crate::lang::Lang;
+    use crate::schema::SymbolInfo;
+    use std::path::Path;
+
+    const APP: &str = r#"
+from fastapi import APIRouter, Depends, HTTPException
+
+from . import deps
+
+router = APIRouter(dependencies=[Depends(verify_token)])
+
+
+def get_db():
+    return SessionLocal()
+
+
+@router.get("/items/{item_id}")
+async def read_item(item_id: int, db=Depends(get_db), user=Depends(deps.current_user)):
+    item = db.get(item_id)
+    if item is None:
+        raise HTTPException(status_code=404)
+    return item
+
+
+def helper():
+    return 1
+"#;
+
+    fn summarize(source: &str) -> SemanticSummary {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&Lang::Python.tree_sitter_language())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        extract(
+            Path::new("/app/routes/items.py"),
+            source,
+            &tree,
+            Lang::Python,
+        )
+        .unwrap()
+    }
+
+    fn symbol<'a>(summary: &'a SemanticSummary, name: &str) -> &'a SymbolInfo {
+        summary.symbols.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_route_handlers_are_entry_points() {
+        let summary = summarize(APP);
+        assert_eq!(
+            symbol(&summary, "read_item").framework_entry_point,
+            FrameworkEntryPoint::ApiRoute
+        );
+        assert_eq!(
+            symbol(&summary, "helper").framework_entry_point,
+            FrameworkEntryPoint::None
+        );
+        assert_eq!(summary.framework_entry_point, FrameworkEntryPoint::ApiRoute);
+    }
+
+    #[test]
+    fn test_routes_recorded() {
+        let source = r#"
+from fastapi import APIRouter, FastAPI
+
+app = FastAPI()
+router = APIRouter(prefix="/users")
+
+
+@app.get("/health")
+def health():
+    return "ok"
+
+
+@router.post("/{user_id}", status_code=201)
+async def update_user(user_id: int):
+    return user_id
+
+
+@router.api_route(path="/", methods=["GET", "HEAD"])
+def list_users():
+    return []
+"#;
+        let summary = summarize(source);
+        let routes: Vec<_> = summary
+            .routes
+            .iter()
+            .map(|r| {
+                (
+                    r.method.as_str(),
+                    r.path.as_str(),
+                    r.handler.as_deref(),
+                    r.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                ("GET", "/health", Some("health"), 9),
+                ("POST", "/users/{user_id}", Some("update_user"), 14),
+                ("GET", "/users/", Some("list_users"), 19),
+                ("HEAD", "/users/", Some("list_users"), 19),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_depends_recorded_as_calls() {
+        let summary = summarize(APP);
+        let calls = &symbol(&summary, "read_item").calls;
+        assert!(calls
+            .iter()
+            .any(|c| c.name == "get_db" && c.object.is_none()));
+        assert!(calls
+            .iter()
+            .any(|c| c.name == "current_user" && c.object.as_deref() == Some("deps")));
+        // Module-level dependencies belong to the file
+        assert!(summary.calls.iter().any(|c| c.name == "verify_token"));
+    }
+
+    #[test]
+    fn test_requires_fastapi_import() {
+        let source = APP.replace("from fastapi import", "from starlette import");
+        let summary = summarize(&source);
+        assert_eq!(
+            symbol(&summary, "read_item").framework_entry_point,
+            FrameworkEntryPoint::None
+        );
+        assert!(!symbol(&summary, "read_item")
+            .calls
+            .iter()
+            .any(|c| c.name == "get_db"));
+    }
+
+    #[test]
+    fn test_is_route_decorator() {
+        assert!(is_route_decorator("app.get(\"/items\")"));
+        assert!(is_route_decorator(
+            "@router.post(\"/items\", status_code=201)"
+        ));
+        assert!(is_route_decorator("api.v1.websocket(\"/ws\")"));
+        assert!(!is_route_decorator("app.middleware(\"http\")"));
+        assert!(!is_route_decorator("cache.get"));
+        assert!(!is_route_decorator("dataclass"));
+    }
+}
@@ -0,0 +1,43 @@
+//! Python Framework Detection and Enhancement
+//!
+//! This module provides framework detection and specialized extractors for
+//! popular Python frameworks, applied after core extraction.
+//!
+//! # Supported Frameworks
+//!
+//! - **FastAPI**: Route handlers (`@app.get`, `@router.post`), `Depends()`
+//!   dependency injection
+
+pub mod fastapi;
+
+use tree_sitter::Node;
+
+use crate::schema::SemanticSummary;
+
+/// Framework detection context
+///
+/// Tracks which frameworks are detected in a file based on its imports.
+#[derive(Debug, Default)]
+pub struct FrameworkContext {
+    /// FastAPI detected (imports FastAPI, APIRouter or Depends)
+    pub is_fastapi: bool,
+}
+
+/// Detect which frameworks a Python file uses
+pub fn detect_frameworks(root: &Node, source: &str) -> FrameworkContext {
+    FrameworkContext {
+        is_fastapi: fastapi::is_fastapi(root, source),
+    }
+}
+
+/// Apply the enhancers for every detected framework
+pub fn enhance(
+    summary: &mut SemanticSummary,
+    root: &Node,
+    source: &str,
+    frameworks: &FrameworkContext,
+) {
+    if frameworks.is_fastapi {
+        fastapi::enhance(summary, root, source);
+    }
+}
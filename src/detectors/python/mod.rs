@@ -1,15 +1,21 @@
 //! Python language detector
 //!
 //! Extracts semantic information from Python source files using the generic extractor.
-//! Python-specific features like decorator detection are handled in a second pass.
+//! Python-specific features like decorator detection are handled in a second pass,
+//! followed by framework-specific enhancements (see [`frameworks`]).
+
+pub mod frameworks;
 
 use tree_sitter::{Node, Tree};
 
-use crate::detectors::common::get_node_text;
-use crate::detectors::generic::extract_with_grammar;
+use crate::detectors::common::{get_node_text, visit_all};
+use crate::detectors::generic::extract_with_grammar_at_level;
 use crate::detectors::grammar::PYTHON_GRAMMAR;
 use crate::error::Result;
-use crate::schema::{FrameworkEntryPoint, RiskLevel, SemanticSummary};
+use crate::extract::ExtractionLevel;
+use crate::schema::{
+    Argument, FrameworkEntryPoint, Import, ImportedName, RiskLevel, SemanticSummary,
+};
 
 /// Walk the tree and collect base class names for every class definition.
 /// Returns a map of `class_name -> Vec<base_class_name>`.
@@ -64,14 +70,25 @@ fn collect_base_classes(root: &Node, source: &str) -> std::collections::HashMap<
 }
 
 /// Extract semantic information from a Python source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // Use the generic extractor for core semantic extraction
     // This handles: symbols, imports, state_changes, control_flow, calls, risk
-    extract_with_grammar(summary, source, tree, &PYTHON_GRAMMAR)?;
+    extract_with_grammar_at_level(summary, source, tree, &PYTHON_GRAMMAR, level)?;
 
     // Python-specific: detect decorated definitions and improve symbol scoring
     let root = tree.root_node();
     enhance_python_symbols(summary, &root, source);
+    extract_signatures(summary, &root, source);
+    extract_import_records(summary, &root, source);
+
+    // Framework-specific enhancements (FastAPI routes and dependencies)
+    let frameworks = frameworks::detect_frameworks(&root, source);
+    frameworks::enhance(summary, &root, source, &frameworks);
 
     Ok(())
 }
@@ -217,6 +234,153 @@ fn is_test_file_path(path: &str) -> bool {
     false
 }
 
+/// Record import statements with the names they bind
+///
+/// `from .utils import helper as h` keeps the module as written (`.utils`) with
+/// `helper` aliased to `h`; a wildcard import has no names. `import pkg.mod as m`
+/// is a namespace import whose single name is the module itself.
+fn extract_import_records(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| match node.kind() {
+        "import_statement" => {
+            let mut cursor = node.walk();
+            for name in node.children_by_field_name("name", &mut cursor) {
+                let imported = imported_name(&name, source);
+                summary.imports.push(Import {
+                    source: imported.name.clone(),
+                    names: vec![imported],
+                    is_namespace: true,
+                    ..Default::default()
+                });
+            }
+        }
+        "import_from_statement" => {
+            let Some(module) = node.child_by_field_name("module_name") else {
+                return;
+            };
+            let mut import = Import {
+                source: get_node_text(&module, source),
+                ..Default::default()
+            };
+            let mut cursor = node.walk();
+            import.names = node
+                .children_by_field_name("name", &mut cursor)
+                .map(|name| imported_name(&name, source))
+                .collect();
+            import.is_namespace = import.names.is_empty();
+            summary.imports.push(import);
+        }
+        _ => {}
+    });
+}
+
+/// Name (and alias) of a `dotted_name` or `aliased_import` node
+fn imported_name(node: &Node, source: &str) -> ImportedName {
+    match node.kind() {
+        "aliased_import" => ImportedName {
+            name: node
+                .child_by_field_name("name")
+                .map(|n| get_node_text(&n, source))
+                .unwrap_or_default(),
+            alias: node
+                .child_by_field_name("alias")
+                .map(|a| get_node_text(&a, source)),
+        },
+        _ => ImportedName {
+            name: get_node_text(node, source),
+            alias: None,
+        },
+    }
+}
+
+/// Fill in function arguments with their type annotations and return types
+///
+/// PEP 484 annotations become `param_types` (`name: str` → `Some("str")`, an
+/// untyped argument → `None`) and `return_type` (`-> str`). A leading `self`
+/// or `cls` is not an argument.
+fn extract_signatures(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut functions = Vec::new();
+    collect_functions(*root, &mut functions);
+
+    for sym in &mut summary.symbols {
+        let Some(function) = functions.iter().find(|f| {
+            let line = f.start_position().row + 1;
+            sym.start_line <= line
+                && line <= sym.end_line
+                && f.child_by_field_name("name")
+                    .is_some_and(|n| get_node_text(&n, source) == sym.name)
+        }) else {
+            continue;
+        };
+
+        let mut arguments: Vec<Argument> = Vec::new();
+        if let Some(params) = function.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for (i, param) in params.named_children(&mut cursor).enumerate() {
+                let Some(arg) = parse_parameter(&param, source) else {
+                    continue;
+                };
+                if i == 0 && (arg.name == "self" || arg.name == "cls") {
+                    continue;
+                }
+                arguments.push(arg);
+            }
+        }
+
+        sym.param_types = arguments.iter().map(|a| a.arg_type.clone()).collect();
+        sym.arguments = arguments;
+        sym.return_type = function
+            .child_by_field_name("return_type")
+            .map(|t| get_node_text(&t, source));
+    }
+
+    // The primary symbol's hash is computed from the summary; keep its arity
+    // in step with the symbol's so call edges resolve to the indexed hash
+    if let Some(primary) = summary.symbols.iter().find(|s| {
+        summary.symbol.as_deref() == Some(s.name.as_str())
+            && summary.start_line == Some(s.start_line)
+    }) {
+        summary.arguments = primary.arguments.clone();
+    }
+}
+
+fn collect_functions<'a>(node: Node<'a>, functions: &mut Vec<Node<'a>>) {
+    if node.kind() == "function_definition" {
+        functions.push(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_functions(child, functions);
+    }
+}
+
+/// An argument from a parameter node; `None` for `*` and `/` separators
+fn parse_parameter(param: &Node, source: &str) -> Option<Argument> {
+    let field = |name: &str| {
+        param
+            .child_by_field_name(name)
+            .map(|n| get_node_text(&n, source))
+    };
+    let (name, arg_type, default_value) = match param.kind() {
+        "identifier" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+            (get_node_text(param, source), None, None)
+        }
+        // `name: T`, `*args: T`, `**kwargs: T`
+        "typed_parameter" => (
+            get_node_text(&param.named_child(0)?, source),
+            field("type"),
+            None,
+        ),
+        "default_parameter" => (field("name")?, None, field("value")),
+        "typed_default_parameter" => (field("name")?, field("type"), field("value")),
+        _ => return None,
+    };
+    Some(Argument {
+        name,
+        arg_type,
+        default_value,
+    })
+}
+
 /// Check if a decorated definition has important decorators
 fn has_important_decorator(node: &Node, source: &str) -> bool {
     let mut cursor = node.walk();
@@ -267,4 +431,87 @@ mod tests {
         let normal_score = calculate_basic_score("create_user", "user");
         assert!(normal_score > test_score);
     }
+
+    #[test]
+    fn test_extract_type_annotations() {
+        let source = "\
+def load(path: str, retries, *args: int, limit: int = 10, **opts) -> dict[str, int]:
+    pass
+
+class Store:
+    def get(self, key: str):
+        pass
+";
+        let mut summary = SemanticSummary {
+            file: "store.py".to_string(),
+            ..Default::default()
+        };
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+
+        let load = summary.symbols.iter().find(|s| s.name == "load").unwrap();
+        let names: Vec<&str> = load.arguments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["path", "retries", "*args", "limit", "**opts"]);
+        assert_eq!(
+            load.param_types,
+            vec![
+                Some("str".to_string()),
+                None,
+                Some("int".to_string()),
+                Some("int".to_string()),
+                None
+            ]
+        );
+        assert_eq!(load.arguments[3].default_value.as_deref(), Some("10"));
+        assert_eq!(load.return_type.as_deref(), Some("dict[str, int]"));
+
+        let get = summary.symbols.iter().find(|s| s.name == "get").unwrap();
+        assert_eq!(get.param_types, vec![Some("str".to_string())]);
+        assert_eq!(get.return_type, None);
+    }
+
+    #[test]
+    fn test_extract_import_records() {
+        let source = "from . import utils\nfrom ..core.models import User as U, Order\nfrom .helpers import *\nimport pkg.mod as m, os\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract_import_records(&mut summary, &tree.root_node(), source);
+
+        let imports: Vec<(&str, Vec<(&str, Option<&str>)>, bool)> = summary
+            .imports
+            .iter()
+            .map(|i| {
+                (
+                    i.source.as_str(),
+                    i.names
+                        .iter()
+                        .map(|n| (n.name.as_str(), n.alias.as_deref()))
+                        .collect(),
+                    i.is_namespace,
+                )
+            })
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                (".", vec![("utils", None)], false),
+                (
+                    "..core.models",
+                    vec![("User", Some("U")), ("Order", None)],
+                    false
+                ),
+                (".helpers", vec![], true),
+                ("pkg.mod", vec![("pkg.mod", Some("m"))], true),
+                ("os", vec![("os", None)], true),
+            ]
+        );
+    }
 }
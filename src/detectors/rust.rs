@@ -4,21 +4,918 @@
 //! Rust's struct/enum/trait declarations are first-class AST nodes, so the generic
 //! extractor handles them well.
 
-use tree_sitter::Tree;
+use tree_sitter::{Node, Tree};
 
-use crate::detectors::generic::extract_with_grammar;
-use crate::detectors::grammar::RUST_GRAMMAR;
+use std::collections::HashSet;
+
+use crate::detectors::common::{get_node_text, get_node_text_normalized, visit_all};
+use crate::detectors::generic::{
+    compress_initializer, extract_with_grammar_at_level, state_change_for_node,
+};
+use crate::detectors::grammar::{rust_is_exported, RUST_GRAMMAR};
 use crate::error::Result;
-use crate::schema::SemanticSummary;
+use crate::extract::ExtractionLevel;
+use crate::schema::{Import, ImportedName, SemanticSummary, StateChange, SymbolInfo, SymbolKind};
+
+/// Atomic read-modify-write and store methods (`AtomicUsize::fetch_add`, ...)
+const ATOMIC_METHODS: &[&str] = &[
+    "store",
+    "swap",
+    "fetch_add",
+    "fetch_sub",
+    "fetch_and",
+    "fetch_nand",
+    "fetch_or",
+    "fetch_xor",
+    "fetch_max",
+    "fetch_min",
+    "fetch_update",
+    "compare_exchange",
+    "compare_exchange_weak",
+];
+
+/// Items that take generic parameters and a `where` clause
+const GENERIC_ITEMS: &[&str] = &[
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "trait_item",
+    "type_item",
+];
+
+/// Keywords that open an item inside macro arguments
+const ITEM_KEYWORDS: &[&str] = &[
+    "pub", "static", "ref", "const", "mut", "fn", "struct", "enum", "type", "impl", "trait",
+    "crate",
+];
+
+/// Memory orderings; every atomic operation takes one, which tells
+/// `AtomicBool::swap` apart from `slice::swap`
+const ORDERINGS: &[&str] = &["Relaxed", "Release", "Acquire", "AcqRel", "SeqCst"];
 
 /// Extract semantic information from a Rust source file
-pub fn extract(summary: &mut SemanticSummary, source: &str, tree: &Tree) -> Result<()> {
+pub fn extract(
+    summary: &mut SemanticSummary,
+    source: &str,
+    tree: &Tree,
+    level: ExtractionLevel,
+) -> Result<()> {
     // The generic extractor handles everything for Rust:
     // - Symbols: function_item, struct_item, enum_item, trait_item
     // - Imports: use_declaration
     // - State changes: let_declaration, assignment_expression
-    // - Control flow: if, for, while, match, loop
+    // - Control flow: if, for, while, match, loop, .await
     // - Calls: call_expression
     // - Risk calculation
-    extract_with_grammar(summary, source, tree, &RUST_GRAMMAR)
+    extract_with_grammar_at_level(summary, source, tree, &RUST_GRAMMAR, level)?;
+    extract_use_records(summary, &tree.root_node(), source);
+    extract_generic_params(summary, &tree.root_node(), source);
+    extract_lifetime_params(summary, &tree.root_node(), source);
+    qualify_impl_members(summary, &tree.root_node(), source);
+    extract_item_macros(summary, &tree.root_node(), source);
+    if level == ExtractionLevel::Full {
+        mark_atomic_state(summary, &tree.root_node(), source);
+    }
+    Ok(())
+}
+
+/// Record each symbol's generic parameters and bounds
+///
+/// `where` predicates are folded into the parameter they constrain, so
+/// `fn f<T>() where T: Clone` and `fn f<T: Clone>()` both record `T: Clone`
+/// and moving a bound between the two isn't reported as a change.
+fn extract_generic_params(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if !GENERIC_ITEMS.contains(&node.kind()) {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let params = generic_params(node, source);
+        if params.is_empty() {
+            return;
+        }
+        let name = get_node_text(&name, source);
+        let line = node.start_position().row + 1;
+        if let Some(symbol) = summary
+            .symbols
+            .iter_mut()
+            .find(|s| s.name == name && s.start_line <= line && line <= s.end_line)
+        {
+            symbol.generic_params = params;
+        }
+    });
+}
+
+/// Record the named lifetimes each function's signature uses
+///
+/// Lifetimes come from the signature only (generics, parameters, return
+/// type, `where` clause), including ones declared on the surrounding impl:
+/// `fn get(&self) -> &'a str` in `impl<'a> Parser<'a>` uses `'a`.
+fn extract_lifetime_params(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if !matches!(node.kind(), "function_item" | "function_signature_item") {
+            return;
+        }
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let lifetimes = signature_lifetimes(node, source);
+        if lifetimes.is_empty() {
+            return;
+        }
+        let name = get_node_text(&name, source);
+        let line = node.start_position().row + 1;
+        if let Some(symbol) = summary
+            .symbols
+            .iter_mut()
+            .find(|s| s.name == name && s.start_line <= line && line <= s.end_line)
+        {
+            symbol.lifetime_params = lifetimes;
+        }
+    });
+}
+
+/// Named lifetimes in a function's signature, in order of first use
+fn signature_lifetimes(function: &Node, source: &str) -> Vec<String> {
+    let mut lifetimes: Vec<String> = Vec::new();
+    let mut cursor = function.walk();
+    for child in function.children(&mut cursor) {
+        if function.child_by_field_name("body") == Some(child) {
+            continue;
+        }
+        visit_all(&child, |node| {
+            if node.kind() != "lifetime" {
+                return;
+            }
+            let lifetime = get_node_text(node, source);
+            if lifetime != "'static" && lifetime != "'_" && !lifetimes.contains(&lifetime) {
+                lifetimes.push(lifetime);
+            }
+        });
+    }
+    lifetimes
+}
+
+/// Qualify trait impl members and record associated types
+///
+/// Methods and constants of `impl Shape for Square` are renamed
+/// `<Square as Shape>::area`, so two impls of one trait don't index as two
+/// indistinguishable `area` symbols. Attribute-wrapped impls (`#[async_trait]`)
+/// are the same `impl_item` and get the same treatment. Associated types
+/// (`type Unit = f64;` in an impl, `type Unit;` in a trait) become type alias
+/// symbols.
+fn qualify_impl_members(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        let trait_name = match node.kind() {
+            "impl_item" => node
+                .child_by_field_name("trait")
+                .map(|t| get_node_text_normalized(&t, source)),
+            "trait_item" => None,
+            _ => return,
+        };
+        let Some(body) = node.child_by_field_name("body") else {
+            return;
+        };
+        let qualify = |name: &str| match (&trait_name, node.child_by_field_name("type")) {
+            (Some(trait_name), Some(ty)) => format!(
+                "<{} as {}>::{}",
+                get_node_text_normalized(&ty, source),
+                trait_name,
+                name
+            ),
+            _ => name.to_string(),
+        };
+
+        let mut cursor = body.walk();
+        for item in body.named_children(&mut cursor) {
+            let Some(name) = item.child_by_field_name("name") else {
+                continue;
+            };
+            let name = get_node_text(&name, source);
+            let line = item.start_position().row + 1;
+            match item.kind() {
+                "function_item" | "const_item" if trait_name.is_some() => {
+                    rename_symbol(summary, &name, line, qualify(&name))
+                }
+                "type_item" | "associated_type" => summary.symbols.push(SymbolInfo {
+                    name: qualify(&name),
+                    kind: SymbolKind::TypeAlias,
+                    start_line: line,
+                    end_line: item.end_position().row + 1,
+                    // Trait members share the trait's visibility
+                    is_exported: rust_is_exported(&item, source)
+                        || (node.kind() == "trait_item" && rust_is_exported(node, source)),
+                    ..Default::default()
+                }),
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Rename the symbol `name` declared at `line`, keeping the primary symbol in step
+fn rename_symbol(summary: &mut SemanticSummary, name: &str, line: usize, qualified: String) {
+    let Some(symbol) = summary
+        .symbols
+        .iter_mut()
+        .find(|s| s.name == name && s.start_line <= line && line <= s.end_line)
+    else {
+        return;
+    };
+    if summary.symbol.as_deref() == Some(name) && summary.start_line == Some(symbol.start_line) {
+        summary.symbol = Some(qualified.clone());
+    }
+    symbol.name = qualified;
+}
+
+/// Names a trait impl member can be called by
+///
+/// `<Square as Shape>::area` is reachable as `Square::area` and, through a
+/// receiver, as `area`. Other names have no aliases.
+pub fn impl_member_aliases(name: &str) -> Vec<String> {
+    let Some((qualifier, member)) = name
+        .strip_prefix('<')
+        .and_then(|rest| rest.rsplit_once(">::"))
+    else {
+        return Vec::new();
+    };
+    let Some((ty, _)) = qualifier.split_once(" as ") else {
+        return Vec::new();
+    };
+    // `Wrapper<T>` and `crate::shapes::Square` are called as `Wrapper::`, `Square::`
+    let ty = ty
+        .split('<')
+        .next()
+        .unwrap_or(ty)
+        .trim_start_matches(['&', '*']);
+    let ty = ty.rsplit("::").next().unwrap_or(ty);
+    vec![format!("{}::{}", ty, member), member.to_string()]
+}
+
+/// Record declarative macros and their item-position invocations
+///
+/// Items a macro generates aren't expanded, but an opaque symbol spanning the
+/// invocation (`make_getter!(answer)`, `lazy_static!`) keeps line-based lookups
+/// from landing between symbols.
+fn extract_item_macros(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        let name = match node.kind() {
+            "macro_definition" => node
+                .child_by_field_name("name")
+                .map(|name| get_node_text(&name, source)),
+            "macro_invocation" if is_item_position(node) => invocation_name(node, source),
+            _ => None,
+        };
+        if let Some(name) = name {
+            summary.symbols.push(SymbolInfo {
+                name,
+                kind: SymbolKind::Macro,
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+                is_exported: node.kind() == "macro_definition" && has_macro_export(node, source),
+                ..Default::default()
+            });
+        }
+    });
+}
+
+/// Whether a macro invocation stands where an item would
+fn is_item_position(node: &Node) -> bool {
+    let mut parent = node.parent();
+    // `make_getter!(answer);` parses as an expression statement
+    if parent.is_some_and(|p| p.kind() == "expression_statement") {
+        parent = parent.and_then(|p| p.parent());
+    }
+    parent.is_some_and(|p| matches!(p.kind(), "source_file" | "declaration_list"))
+}
+
+/// `make_getter!(answer)` named after the first identifier in the arguments
+/// (`lazy_static!(TABLE)` skips `static ref`), or `my_macro!` without one
+fn invocation_name(node: &Node, source: &str) -> Option<String> {
+    let path = get_node_text(&node.child_by_field_name("macro")?, source);
+    let path = path.rsplit("::").next().unwrap_or(&path);
+    let tree = node
+        .named_children(&mut node.walk())
+        .find(|c| c.kind() == "token_tree");
+    let first = tree.and_then(|tree| {
+        tree.named_children(&mut tree.walk())
+            .filter(|c| c.kind() == "identifier")
+            .map(|c| get_node_text(&c, source))
+            .find(|word| !ITEM_KEYWORDS.contains(&word.as_str()))
+    });
+    Some(match first {
+        Some(first) => format!("{}!({})", path, first),
+        None => format!("{}!", path),
+    })
+}
+
+/// Whether a `macro_rules!` definition carries `#[macro_export]`
+fn has_macro_export(node: &Node, source: &str) -> bool {
+    let mut sibling = node.prev_named_sibling();
+    while let Some(attr) = sibling.filter(|s| s.kind() == "attribute_item") {
+        if get_node_text(&attr, source).contains("macro_export") {
+            return true;
+        }
+        sibling = attr.prev_named_sibling();
+    }
+    false
+}
+
+/// Render an item's generic parameters as `T: Clone + Send`, `'a`, ...
+fn generic_params(item: &Node, source: &str) -> Vec<String> {
+    // (name, bounds, default)
+    let mut params: Vec<(String, Vec<String>, Option<String>)> = Vec::new();
+
+    if let Some(list) = item.child_by_field_name("type_parameters") {
+        let mut cursor = list.walk();
+        for param in list.named_children(&mut cursor) {
+            match param.kind() {
+                "type_parameter" | "lifetime_parameter" => {
+                    let Some(name) = param.child_by_field_name("name") else {
+                        continue;
+                    };
+                    params.push((
+                        get_node_text_normalized(&name, source),
+                        trait_bounds(param.child_by_field_name("bounds"), source),
+                        param
+                            .child_by_field_name("default_type")
+                            .map(|d| get_node_text_normalized(&d, source)),
+                    ));
+                }
+                "const_parameter" => {
+                    params.push((get_node_text_normalized(&param, source), Vec::new(), None));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut cursor = item.walk();
+    let where_clause = item
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "where_clause");
+    if let Some(where_clause) = where_clause {
+        let mut cursor = where_clause.walk();
+        for predicate in where_clause.named_children(&mut cursor) {
+            let Some(left) = predicate.child_by_field_name("left") else {
+                continue;
+            };
+            let left = get_node_text_normalized(&left, source);
+            let bounds = trait_bounds(predicate.child_by_field_name("bounds"), source);
+            match params.iter_mut().find(|(name, _, _)| *name == left) {
+                Some((_, existing, _)) => {
+                    for bound in bounds {
+                        if !existing.contains(&bound) {
+                            existing.push(bound);
+                        }
+                    }
+                }
+                None => params.push((left, bounds, None)),
+            }
+        }
+    }
+
+    params
+        .into_iter()
+        .map(|(name, bounds, default)| {
+            let mut rendered = name;
+            if !bounds.is_empty() {
+                rendered = format!("{}: {}", rendered, bounds.join(" + "));
+            }
+            if let Some(default) = default {
+                rendered = format!("{} = {}", rendered, default);
+            }
+            rendered
+        })
+        .collect()
+}
+
+/// The individual bounds of a `trait_bounds` node
+fn trait_bounds(bounds: Option<Node>, source: &str) -> Vec<String> {
+    let Some(bounds) = bounds else {
+        return Vec::new();
+    };
+    let mut cursor = bounds.walk();
+    bounds
+        .named_children(&mut cursor)
+        .map(|b| get_node_text_normalized(&b, source))
+        .collect()
+}
+
+/// Flag synchronized state changes and record atomic operations
+///
+/// A declaration or assignment is atomic when it goes through a lock
+/// (`*count.lock().unwrap() += 1`, `let mut guard = cache.write().unwrap()`)
+/// or assigns through a guard bound that way. `Atomic*` stores and
+/// read-modify-write calls aren't assignments, so they're added as state
+/// changes of type `atomic`.
+fn mark_atomic_state(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    let mut guards: HashSet<String> = HashSet::new();
+    visit_all(root, |node| {
+        if node.kind() != "let_declaration" {
+            return;
+        }
+        let is_lock = node
+            .child_by_field_name("value")
+            .is_some_and(|v| acquires_lock(&get_node_text(&v, source)));
+        if let (true, Some(pattern)) = (is_lock, node.child_by_field_name("pattern")) {
+            guards.insert(place_root(&get_node_text(&pattern, source)).to_string());
+        }
+    });
+
+    let mut index = 0;
+    let mut atomic_ops = Vec::new();
+    visit_all(root, |node| {
+        if node.kind() == "call_expression" {
+            if let Some(op) = atomic_operation(node, source) {
+                atomic_ops.push(op);
+            }
+            return;
+        }
+        if state_change_for_node(node, source, &RUST_GRAMMAR).is_none() {
+            return;
+        }
+        let text = get_node_text(node, source);
+        let target = node
+            .child_by_field_name("left")
+            .map(|left| get_node_text(&left, source))
+            .unwrap_or_default();
+        if let Some(change) = summary.state_changes.get_mut(index) {
+            change.is_atomic = acquires_lock(&text) || guards.contains(place_root(&target));
+        }
+        index += 1;
+    });
+
+    summary.state_changes.extend(atomic_ops);
+}
+
+/// Whether an expression takes a `Mutex`/`RwLock` write lock
+fn acquires_lock(text: &str) -> bool {
+    [".lock()", ".write()", ".try_lock()", ".try_write()"]
+        .iter()
+        .any(|call| text.contains(call))
+}
+
+/// Variable at the root of a place expression: `*guard.items[0]` -> `guard`
+fn place_root(place: &str) -> &str {
+    let place = place
+        .trim_start_matches(['*', '&'])
+        .trim_start_matches("mut ");
+    let end = place
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(place.len());
+    &place[..end]
+}
+
+/// `counter.fetch_add(1, Ordering::SeqCst)` as an atomic state change
+fn atomic_operation(node: &Node, source: &str) -> Option<StateChange> {
+    let function = node.child_by_field_name("function")?;
+    if function.kind() != "field_expression" {
+        return None;
+    }
+    let method = get_node_text(&function.child_by_field_name("field")?, source);
+    if !ATOMIC_METHODS.contains(&method.as_str()) {
+        return None;
+    }
+    let arguments = get_node_text(&node.child_by_field_name("arguments")?, source);
+    let has_ordering = arguments
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|word| ORDERINGS.contains(&word));
+    if !has_ordering {
+        return None;
+    }
+    let receiver = function.child_by_field_name("value")?;
+    Some(StateChange {
+        name: get_node_text(&receiver, source),
+        state_type: "atomic".to_string(),
+        initializer: compress_initializer(&format!("{}{}", method, arguments)),
+        is_atomic: true,
+    })
+}
+
+/// Record `use` declarations with the names they bring into scope
+///
+/// `use billing::invoice::{total, Invoice as Inv}` becomes one import of
+/// `billing::invoice` naming `total` and `Invoice` (aliased `Inv`); a glob
+/// import (`use billing::*`) has no names.
+fn extract_use_records(summary: &mut SemanticSummary, root: &Node, source: &str) {
+    visit_all(root, |node| {
+        if node.kind() != "use_declaration" {
+            return;
+        }
+        if let Some(argument) = node.child_by_field_name("argument") {
+            collect_use_tree(&argument, "", source, &mut summary.imports);
+        }
+    });
+}
+
+/// Flatten a use tree below `prefix` into imports
+fn collect_use_tree(node: &Node, prefix: &str, source: &str, imports: &mut Vec<Import>) {
+    let join = |path: &str| match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}::{}", prefix, path),
+    };
+    let mut push = |path: String, name: String, alias: Option<String>| match imports
+        .iter_mut()
+        .find(|i| i.source == path && !i.is_namespace)
+    {
+        Some(import) => import.names.push(ImportedName { name, alias }),
+        None => imports.push(Import {
+            source: path,
+            names: vec![ImportedName { name, alias }],
+            ..Default::default()
+        }),
+    };
+
+    match node.kind() {
+        "identifier" | "self" | "crate" | "super" => {
+            push(prefix.to_string(), get_node_text(node, source), None)
+        }
+        "scoped_identifier" => {
+            let path = node
+                .child_by_field_name("path")
+                .map(|p| get_node_text(&p, source))
+                .unwrap_or_default();
+            if let Some(name) = node.child_by_field_name("name") {
+                push(join(&path), get_node_text(&name, source), None);
+            }
+        }
+        "use_as_clause" => {
+            let (Some(path), Some(alias)) = (
+                node.child_by_field_name("path"),
+                node.child_by_field_name("alias"),
+            ) else {
+                return;
+            };
+            let alias = Some(get_node_text(&alias, source));
+            match path.kind() {
+                "scoped_identifier" => {
+                    let parent = path
+                        .child_by_field_name("path")
+                        .map(|p| get_node_text(&p, source))
+                        .unwrap_or_default();
+                    if let Some(name) = path.child_by_field_name("name") {
+                        push(join(&parent), get_node_text(&name, source), alias);
+                    }
+                }
+                _ => push(prefix.to_string(), get_node_text(&path, source), alias),
+            }
+        }
+        "scoped_use_list" => {
+            let path = node
+                .child_by_field_name("path")
+                .map(|p| join(&get_node_text(&p, source)))
+                .unwrap_or_else(|| prefix.to_string());
+            if let Some(list) = node.child_by_field_name("list") {
+                collect_use_tree(&list, &path, source, imports);
+            }
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            for item in node.named_children(&mut cursor) {
+                collect_use_tree(&item, prefix, source, imports);
+            }
+        }
+        "use_wildcard" => {
+            let mut cursor = node.walk();
+            let path = node
+                .named_children(&mut cursor)
+                .next()
+                .map(|p| join(&get_node_text(&p, source)))
+                .unwrap_or_else(|| prefix.to_string());
+            imports.push(Import {
+                source: path,
+                is_namespace: true,
+                ..Default::default()
+            });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_atomic_state() {
+        let source = r#"
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+
+fn record(hits: &AtomicUsize, total: &Mutex<u64>, cache: &RwLock<Vec<u64>>, items: &mut Vec<u64>) {
+    hits.fetch_add(1, Ordering::SeqCst);
+    *total.lock().unwrap() += 1;
+    let mut guard = cache.write().unwrap();
+    *guard = Vec::new();
+    let mut count = 0;
+    count = 2;
+    items.swap(0, 1);
+}
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+
+        let atomic = |name: &str| {
+            summary
+                .state_changes
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("no state change for {name}"))
+                .is_atomic
+        };
+        assert!(atomic("hits"));
+        assert!(atomic("guard"));
+        assert!(atomic("*guard"));
+        assert!(!atomic("count"));
+        assert!(
+            !summary.state_changes.iter().any(|s| s.name == "items"),
+            "slice swap recorded as atomic"
+        );
+        let hits = summary.state_changes.iter().find(|s| s.name == "hits");
+        assert_eq!(hits.unwrap().state_type, "atomic");
+    }
+
+    const SHAPES: &str = r#"use async_trait::async_trait;
+
+pub trait Shape {
+    const SIDES: u32;
+    type Unit;
+    fn area(&self) -> f64;
+}
+
+pub struct Square {
+    side: f64,
+}
+
+pub struct Circle {
+    radius: f64,
+}
+
+impl Shape for Square {
+    const SIDES: u32 = 4;
+    type Unit = f64;
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Shape for Circle {
+    const SIDES: u32 = 0;
+    type Unit = f64;
+    fn area(&self) -> f64 {
+        3.14 * self.radius * self.radius
+    }
+}
+
+impl Square {
+    pub fn new(side: f64) -> Self {
+        Square { side }
+    }
+}
+
+#[async_trait]
+pub trait Store {
+    async fn load(&self, key: &str) -> Option<String>;
+}
+
+pub struct MemStore;
+
+#[async_trait]
+impl Store for MemStore {
+    async fn load(&self, key: &str) -> Option<String> {
+        Some(key.to_string())
+    }
+}
+
+#[macro_export]
+macro_rules! make_getter {
+    ($name:ident) => {
+        pub fn $name() -> u32 {
+            42
+        }
+    };
+}
+
+make_getter!(answer);
+
+lazy_static::lazy_static! {
+    static ref TABLE: Vec<u32> = vec![1, 2, 3];
+}
+"#;
+
+    fn extract_symbols(source: &str) -> Vec<crate::schema::SymbolInfo> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary.symbols
+    }
+
+    #[test]
+    fn test_trait_impl_members_are_qualified() {
+        let symbols = extract_symbols(SHAPES);
+        let find = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("no symbol {name}"))
+        };
+
+        let area = find("<Square as Shape>::area");
+        assert_eq!((area.start_line, area.end_line), (20, 22));
+        assert_eq!(find("<Circle as Shape>::area").start_line, 28);
+        assert!(!symbols.iter().any(|s| s.name == "area"));
+
+        // The async_trait attribute wraps the same impl
+        let load = find("<MemStore as Store>::load");
+        assert!(load.is_async);
+        assert_eq!(load.start_line, 48);
+
+        // Inherent methods keep their bare names
+        assert_eq!(find("new").start_line, 34);
+
+        // Associated constants and types
+        assert_eq!(find("<Square as Shape>::SIDES").start_line, 18);
+        assert_eq!(find("SIDES").start_line, 4);
+        let unit = find("<Circle as Shape>::Unit");
+        assert_eq!((unit.kind, unit.start_line), (SymbolKind::TypeAlias, 27));
+        assert!(find("Unit").is_exported);
+    }
+
+    #[test]
+    fn test_item_macros_are_opaque_symbols() {
+        let symbols = extract_symbols(SHAPES);
+        let span = |name: &str| {
+            let symbol = symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("no symbol {name}"));
+            assert_eq!(symbol.kind, SymbolKind::Macro, "{name}");
+            (symbol.start_line, symbol.end_line, symbol.is_exported)
+        };
+
+        assert_eq!(span("make_getter"), (54, 60, true));
+        assert_eq!(span("make_getter!(answer)"), (62, 62, false));
+        assert_eq!(span("lazy_static!(TABLE)"), (64, 66, false));
+        // Expression macros aren't items
+        assert!(!symbols.iter().any(|s| s.name.starts_with("vec!")));
+    }
+
+    #[test]
+    fn test_impl_member_aliases() {
+        assert_eq!(
+            impl_member_aliases("<Square as Shape>::area"),
+            ["Square::area", "area"]
+        );
+        assert_eq!(
+            impl_member_aliases("<Wrapper<T> as From<T>>::from"),
+            ["Wrapper::from", "from"]
+        );
+        assert_eq!(
+            impl_member_aliases("<crate::shapes::Circle as Shape>::SIDES"),
+            ["Circle::SIDES", "SIDES"]
+        );
+        assert!(impl_member_aliases("area").is_empty());
+        assert!(impl_member_aliases("Vec<u8>").is_empty());
+    }
+
+    fn symbol_generics(source: &str) -> Vec<(String, Vec<String>)> {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract(&mut summary, source, &tree, ExtractionLevel::Full).unwrap();
+        summary
+            .symbols
+            .into_iter()
+            .map(|s| (s.name, s.generic_params))
+            .collect()
+    }
+
+    fn generics_of(symbols: &[(String, Vec<String>)], name: &str) -> Vec<String> {
+        symbols
+            .iter()
+            .find(|(n, _)| n == name)
+            .unwrap_or_else(|| panic!("no symbol {name}"))
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn test_generic_params_record_bounds() {
+        let symbols = symbol_generics(
+            "pub fn first<T: Clone + Send, U = u32, const N: usize>(items: [T; N]) -> T { items[0].clone() }\npub fn plain(x: u32) -> u32 { x }\n",
+        );
+        assert_eq!(
+            generics_of(&symbols, "first"),
+            ["T: Clone + Send", "U = u32", "const N: usize"]
+        );
+        assert!(generics_of(&symbols, "plain").is_empty());
+    }
+
+    #[test]
+    fn test_generic_params_fold_where_clause() {
+        let symbols = symbol_generics(
+            "pub fn merge<K, V: Default>(a: K, b: V) -> V\nwhere\n    K: std::hash::Hash + Eq,\n    V: Clone,\n    Vec<K>: Send,\n{\n    b\n}\n\npub struct Cache<K>\nwhere\n    K: Ord,\n{\n    keys: Vec<K>,\n}\n",
+        );
+        assert_eq!(
+            generics_of(&symbols, "merge"),
+            [
+                "K: std::hash::Hash + Eq",
+                "V: Default + Clone",
+                "Vec<K>: Send"
+            ]
+        );
+        assert_eq!(generics_of(&symbols, "Cache"), ["K: Ord"]);
+    }
+
+    #[test]
+    fn test_generic_params_lifetimes() {
+        let symbols = symbol_generics(
+            "pub fn longest<'a, 'b: 'a, T: 'a + ?Sized>(x: &'a T, y: &'b T) -> &'a T { x }\n",
+        );
+        assert_eq!(
+            generics_of(&symbols, "longest"),
+            ["'a", "'b: 'a", "T: 'a + ?Sized"]
+        );
+    }
+
+    #[test]
+    fn test_lifetime_params() {
+        let source = "pub fn longest<'a, 'b: 'a>(x: &'a str, y: &'b str) -> &'a str { let s: &'static str = \"\"; x }\n\nstruct Parser<'src> {\n    input: &'src str,\n}\n\nimpl<'src> Parser<'src> {\n    fn rest(&self) -> &'src str { self.input }\n\n    fn peek(&self) -> Option<&'_ str> { None }\n}\n\nfn name() -> &'static str { \"\" }\n";
+        let symbols = extract_symbols(source);
+        let lifetimes = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("no symbol {name}"))
+                .lifetime_params
+                .clone()
+        };
+
+        assert_eq!(lifetimes("longest"), ["'a", "'b"]);
+        // Declared on the impl, used in the signature
+        assert_eq!(lifetimes("rest"), ["'src"]);
+        // Elided and 'static lifetimes aren't named parameters
+        assert!(lifetimes("peek").is_empty());
+        assert!(lifetimes("name").is_empty());
+        // Only function signatures
+        assert!(lifetimes("Parser").is_empty());
+    }
+
+    #[test]
+    fn test_extract_use_records() {
+        let source = "use demo::billing::invoice::{total, Invoice as Inv, tax::rate};\nuse demo::billing::*;\nuse std::fmt;\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let mut summary = SemanticSummary::default();
+        extract_use_records(&mut summary, &tree.root_node(), source);
+
+        let imports: Vec<(&str, Vec<(&str, Option<&str>)>, bool)> = summary
+            .imports
+            .iter()
+            .map(|i| {
+                (
+                    i.source.as_str(),
+                    i.names
+                        .iter()
+                        .map(|n| (n.name.as_str(), n.alias.as_deref()))
+                        .collect(),
+                    i.is_namespace,
+                )
+            })
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                (
+                    "demo::billing::invoice",
+                    vec![("total", None), ("Invoice", Some("Inv"))],
+                    false
+                ),
+                ("demo::billing::invoice::tax", vec![("rate", None)], false),
+                ("demo::billing", vec![], true),
+                ("std", vec![("fmt", None)], false),
+            ]
+        );
+    }
 }
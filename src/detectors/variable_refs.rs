@@ -108,6 +108,7 @@ pub fn extract_variable_references(
             in_try: false,
             is_hook: false,
             is_io: false,
+            is_macro: false,
             ref_kind,
             location: Location::new(reference.line, 0),
         };
@@ -207,6 +208,7 @@ fn add_escape_local_symbols(summary: &mut SemanticSummary, defs: &[locals::Local
             is_escape_local: true,
             hash: None,
             arguments: Vec::new(),
+            param_types: Vec::new(),
             props: Vec::new(),
             return_type: None,
             calls: Vec::new(),
@@ -217,6 +219,22 @@ fn add_escape_local_symbols(summary: &mut SemanticSummary, defs: &[locals::Local
             framework_entry_point: FrameworkEntryPoint::None,
             is_async: false,
             base_classes: Vec::new(),
+            doc_comment: None,
+            feature_flags: Vec::new(),
+            generic_params: Vec::new(),
+            lifetime_params: Vec::new(),
+            implements: Vec::new(),
+            is_deprecated: false,
+            deprecation_message: None,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: Vec::new(),
+            embedded_queries: Vec::new(),
+            partial_of: None,
+            parent_symbol: None,
+            nested_id: None,
         });
     }
 }
@@ -0,0 +1,259 @@
+//! Warnings about changed code in diffs
+//!
+//! `analyze --diff` reports two kinds of regressions introduced by a diff:
+//!
+//! - calls gained to a symbol marked deprecated (see
+//!   [`crate::detectors::deprecation`]). Deprecated symbols are taken from the
+//!   index and from the changed files themselves, so a symbol deprecated in the
+//!   same diff counts, and one un-deprecated there does not.
+//! - I/O calls that were inside a try block at the base ref and no longer are
+//!   (see [`crate::detectors::facets`]).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::cache::CacheDir;
+use crate::git::{get_file_at_ref, ChangeType, ChangedFile};
+use crate::lang::Lang;
+use crate::parsing::parse_and_extract;
+use crate::schema::{Call, RefKind, SemanticSummary, SymbolInfo};
+
+/// A call added by a diff whose target is marked deprecated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedCall {
+    /// File containing the call (relative to the repo root)
+    pub file: String,
+    /// Line of the call (0 when unknown)
+    pub line: usize,
+    /// Symbol making the call
+    pub caller: String,
+    /// Deprecated symbol being called
+    pub callee: String,
+    /// Deprecation note of the callee, if any
+    pub message: Option<String>,
+}
+
+/// An I/O call a diff moved out of the try block that guarded it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoCallOutOfTry {
+    /// File containing the call (relative to the repo root)
+    pub file: String,
+    /// Line of the call (0 when unknown)
+    pub line: usize,
+    /// Symbol making the call
+    pub caller: String,
+    /// I/O call no longer guarded
+    pub callee: String,
+}
+
+/// Everything [`diff_warnings`] found in a diff
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffWarnings {
+    /// New calls into deprecated symbols
+    pub deprecated_calls: Vec<DeprecatedCall>,
+    /// I/O calls no longer inside a try block
+    pub io_out_of_try: Vec<IoCallOutOfTry>,
+}
+
+impl DiffWarnings {
+    /// Whether the diff raised no warnings
+    pub fn is_empty(&self) -> bool {
+        self.deprecated_calls.is_empty() && self.io_out_of_try.is_empty()
+    }
+}
+
+/// A changed file summarized at the target ref, and at the base ref if it
+/// existed there
+pub(crate) struct ChangedSummary<'a> {
+    pub(crate) changed: &'a ChangedFile,
+    pub(crate) target: SemanticSummary,
+    pub(crate) base: Option<SemanticSummary>,
+    pub(crate) target_source: String,
+    pub(crate) base_source: Option<String>,
+}
+
+impl ChangedSummary<'_> {
+    /// The base version of a symbol of the target summary
+    pub(crate) fn base_symbol(&self, symbol: &SymbolInfo) -> Option<&SymbolInfo> {
+        self.base
+            .as_ref()?
+            .symbols
+            .iter()
+            .find(|s| s.name == symbol.name)
+    }
+}
+
+/// Warnings about a set of changed files.
+///
+/// `base_ref` is read from git. `target_ref` is read from git as well, or from
+/// the working tree when it is `None`.
+pub fn diff_warnings(
+    repo_root: &Path,
+    base_ref: &str,
+    target_ref: Option<&str>,
+    changed_files: &[ChangedFile],
+) -> DiffWarnings {
+    let summaries = changed_summaries(repo_root, base_ref, target_ref, changed_files);
+    if summaries.is_empty() {
+        return DiffWarnings::default();
+    }
+
+    DiffWarnings {
+        deprecated_calls: deprecated_calls(repo_root, changed_files, &summaries),
+        io_out_of_try: io_out_of_try(&summaries),
+    }
+}
+
+/// Summaries of the changed files that still exist at the target and parse
+pub(crate) fn changed_summaries<'a>(
+    repo_root: &Path,
+    base_ref: &str,
+    target_ref: Option<&str>,
+    changed_files: &'a [ChangedFile],
+) -> Vec<ChangedSummary<'a>> {
+    changed_files
+        .iter()
+        .filter(|changed| changed.change_type != ChangeType::Deleted)
+        .filter_map(|changed| {
+            let source = match target_ref {
+                Some(target) => get_file_at_ref(&changed.path, target, Some(repo_root))
+                    .ok()
+                    .flatten(),
+                None => fs::read_to_string(repo_root.join(&changed.path)).ok(),
+            }?;
+            let target = summarize(repo_root, &changed.path, &source)?;
+            let old_path = changed.old_path.as_deref().unwrap_or(&changed.path);
+            let base_source = (changed.change_type != ChangeType::Added)
+                .then(|| get_file_at_ref(old_path, base_ref, Some(repo_root)).ok())
+                .flatten()
+                .flatten();
+            let base = base_source
+                .as_deref()
+                .and_then(|source| summarize(repo_root, old_path, source));
+            Some(ChangedSummary {
+                changed,
+                target,
+                base,
+                target_source: source,
+                base_source,
+            })
+        })
+        .collect()
+}
+
+/// Calls into deprecated symbols that the changed files add. Calls a symbol
+/// already made at the base ref are not reported again.
+fn deprecated_calls(
+    repo_root: &Path,
+    changed_files: &[ChangedFile],
+    summaries: &[ChangedSummary],
+) -> Vec<DeprecatedCall> {
+    // Changed files are authoritative over their (possibly stale) index entries
+    let changed_paths: HashSet<&str> = changed_files.iter().map(|c| c.path.as_str()).collect();
+    let mut deprecated: HashMap<String, Option<String>> = HashMap::new();
+    if let Ok(cache) = CacheDir::for_repo(repo_root) {
+        if cache.exists() {
+            for entry in cache.load_all_symbol_entries().unwrap_or_default() {
+                if entry.is_deprecated && !changed_paths.contains(entry.file.as_str()) {
+                    let message = (!entry.deprecation_message.is_empty())
+                        .then_some(entry.deprecation_message);
+                    deprecated.entry(entry.symbol).or_insert(message);
+                }
+            }
+        }
+    }
+    for summary in summaries {
+        for symbol in summary.target.symbols.iter().filter(|s| s.is_deprecated) {
+            deprecated.insert(symbol.name.clone(), symbol.deprecation_message.clone());
+        }
+    }
+    if deprecated.is_empty() {
+        return Vec::new();
+    }
+
+    let mut calls = Vec::new();
+    for summary in summaries {
+        // Deprecated code may keep calling deprecated code
+        for symbol in summary.target.symbols.iter().filter(|s| !s.is_deprecated) {
+            let existing: HashSet<&str> = summary
+                .base_symbol(symbol)
+                .iter()
+                .flat_map(|s| s.calls.iter().map(|c| c.name.as_str()))
+                .collect();
+            let mut reported = HashSet::new();
+            for call in &symbol.calls {
+                if call.ref_kind != RefKind::None
+                    || call.name == symbol.name
+                    || existing.contains(call.name.as_str())
+                    || !reported.insert(call.name.as_str())
+                {
+                    continue;
+                }
+                if let Some(message) = deprecated.get(&call.name) {
+                    calls.push(DeprecatedCall {
+                        file: summary.changed.path.clone(),
+                        line: call.location.line,
+                        caller: symbol.name.clone(),
+                        callee: call.name.clone(),
+                        message: message.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    calls
+}
+
+/// I/O calls made inside a try block at the base ref that the changed files
+/// now make outside of one. A call still guarded at another site is not
+/// reported.
+fn io_out_of_try(summaries: &[ChangedSummary]) -> Vec<IoCallOutOfTry> {
+    let mut calls = Vec::new();
+    for summary in summaries {
+        for symbol in &summary.target.symbols {
+            let Some(base) = summary.base_symbol(symbol) else {
+                continue;
+            };
+            let was_guarded: HashSet<&str> = symbol_io_calls(base)
+                .filter(|c| c.in_try)
+                .map(|c| c.name.as_str())
+                .collect();
+            let still_guarded: HashSet<&str> = symbol_io_calls(symbol)
+                .filter(|c| c.in_try)
+                .map(|c| c.name.as_str())
+                .collect();
+            let mut reported = HashSet::new();
+            for call in symbol_io_calls(symbol).filter(|c| !c.in_try) {
+                if was_guarded.contains(call.name.as_str())
+                    && !still_guarded.contains(call.name.as_str())
+                    && reported.insert(call.name.as_str())
+                {
+                    calls.push(IoCallOutOfTry {
+                        file: summary.changed.path.clone(),
+                        line: call.location.line,
+                        caller: symbol.name.clone(),
+                        callee: call.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+    calls
+}
+
+/// The I/O calls a symbol makes
+fn symbol_io_calls(symbol: &SymbolInfo) -> impl Iterator<Item = &Call> {
+    symbol
+        .calls
+        .iter()
+        .filter(|c| c.ref_kind == RefKind::None && c.is_io)
+}
+
+/// Semantic summary of `source`, parsed as the language of `path`
+pub(crate) fn summarize(repo_root: &Path, path: &str, source: &str) -> Option<SemanticSummary> {
+    let full_path = repo_root.join(path);
+    let lang = Lang::from_path(&full_path).ok()?;
+    parse_and_extract(&full_path, source, lang).ok()
+}
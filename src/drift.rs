@@ -17,6 +17,10 @@
 //! | < 30% of repo | Rebase overlay |
 //! | ≥ 30% of repo | Full rebuild |
 //!
+//! A branch layer whose branch has diverged from the base branch by more than
+//! [`DEFAULT_DIVERGENCE_THRESHOLD`] commits (ahead plus behind) is rebased
+//! rather than updated incrementally.
+//!
 //! # Layer-Specific Detection
 //!
 //! - **Base layer**: Compare indexed SHA vs current HEAD of base branch
@@ -28,9 +32,13 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
-use crate::git;
+use crate::git::{self, BranchDivergence};
 use crate::overlay::LayerKind;
 
+/// Commits (ahead plus behind) a branch may diverge from the base branch
+/// before its layer is rebased instead of updated incrementally
+pub const DEFAULT_DIVERGENCE_THRESHOLD: u32 = 50;
+
 // ============================================================================
 // Drift Status
 // ============================================================================
@@ -63,6 +71,14 @@ pub struct DriftStatus {
 
     /// Whether the merge-base has changed (indicates rebase/merge)
     pub merge_base_changed: bool,
+
+    /// For branch layers: how far the branch has diverged from the base branch
+    #[serde(default)]
+    pub divergence: Option<BranchDivergence>,
+
+    /// Whether `divergence` exceeds the detector's threshold
+    #[serde(default)]
+    pub divergence_exceeded: bool,
 }
 
 impl DriftStatus {
@@ -78,6 +94,8 @@ impl DriftStatus {
             indexed_merge_base: None,
             current_merge_base: None,
             merge_base_changed: false,
+            divergence: None,
+            divergence_exceeded: false,
         }
     }
 
@@ -104,6 +122,8 @@ impl DriftStatus {
             indexed_merge_base: None,
             current_merge_base: None,
             merge_base_changed: false,
+            divergence: None,
+            divergence_exceeded: false,
         }
     }
 
@@ -119,6 +139,8 @@ impl DriftStatus {
             indexed_merge_base: None,
             current_merge_base: None,
             merge_base_changed: false,
+            divergence: None,
+            divergence_exceeded: false,
         }
     }
 
@@ -136,6 +158,16 @@ impl DriftStatus {
         self
     }
 
+    /// Add branch divergence information (for branch layers)
+    #[must_use]
+    pub fn with_divergence(mut self, divergence: Option<BranchDivergence>, threshold: u32) -> Self {
+        self.divergence_exceeded = divergence
+            .as_ref()
+            .is_some_and(|d| d.total_commits() > threshold);
+        self.divergence = divergence;
+        self
+    }
+
     /// Get the recommended update strategy based on drift magnitude
     #[must_use]
     pub fn strategy(&self, total_repo_files: usize) -> UpdateStrategy {
@@ -153,6 +185,16 @@ impl DriftStatus {
             return UpdateStrategy::Rebase;
         }
 
+        // Too far from the base branch for file-by-file updates to be enough
+        if self.divergence_exceeded && !self.changed_files.is_empty() {
+            let thirty_percent = (total_repo_files as f64 * 0.30).ceil() as usize;
+            return if self.changed_files.len() < thirty_percent {
+                UpdateStrategy::Rebase
+            } else {
+                UpdateStrategy::FullRebuild
+            };
+        }
+
         let changed_count = self.changed_files.len();
 
         // Calculate thresholds
@@ -239,6 +281,9 @@ pub struct DriftDetector {
 
     /// Total number of tracked files in the repository (for percentage calculation)
     total_files: usize,
+
+    /// Commits a branch may diverge from the base branch before escalating
+    divergence_threshold: u32,
 }
 
 impl DriftDetector {
@@ -247,6 +292,7 @@ impl DriftDetector {
         Self {
             repo_root,
             total_files: 0,
+            divergence_threshold: DEFAULT_DIVERGENCE_THRESHOLD,
         }
     }
 
@@ -255,6 +301,7 @@ impl DriftDetector {
         Self {
             repo_root,
             total_files,
+            divergence_threshold: DEFAULT_DIVERGENCE_THRESHOLD,
         }
     }
 
@@ -263,6 +310,12 @@ impl DriftDetector {
         self.total_files = count;
     }
 
+    /// Set how many commits a branch may diverge from the base branch before
+    /// its layer is rebased instead of updated incrementally
+    pub fn set_divergence_threshold(&mut self, commits: u32) {
+        self.divergence_threshold = commits;
+    }
+
     /// Check drift for a specific layer
     ///
     /// # Arguments
@@ -290,6 +343,8 @@ impl DriftDetector {
                     indexed_merge_base: None,
                     current_merge_base: None,
                     merge_base_changed: false,
+                    divergence: None,
+                    divergence_exceeded: false,
                 })
             }
         }
@@ -346,6 +401,8 @@ impl DriftDetector {
         let base_branch = git::detect_base_branch(Some(&self.repo_root))?;
         let current_merge_base =
             git::get_merge_base("HEAD", &base_branch, Some(&self.repo_root)).ok();
+        let divergence =
+            git::get_branch_divergence("HEAD", &base_branch, Some(&self.repo_root)).ok();
 
         // Check if merge-base changed (indicates rebase)
         let merge_base_changed = match (stored_merge_base, &current_merge_base) {
@@ -358,7 +415,8 @@ impl DriftDetector {
         // Same SHA and merge-base = fresh
         if indexed_sha == current_sha && !merge_base_changed {
             return Ok(DriftStatus::fresh(indexed_sha.to_string(), current_sha)
-                .with_merge_base(stored_merge_base.map(String::from), current_merge_base));
+                .with_merge_base(stored_merge_base.map(String::from), current_merge_base)
+                .with_divergence(divergence, self.divergence_threshold));
         }
 
         // Get changed files
@@ -375,7 +433,8 @@ impl DriftDetector {
             changed_paths,
             self.total_files,
         )
-        .with_merge_base(stored_merge_base.map(String::from), current_merge_base))
+        .with_merge_base(stored_merge_base.map(String::from), current_merge_base)
+        .with_divergence(divergence, self.divergence_threshold))
     }
 
     /// Check drift for working layer
@@ -743,6 +802,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_branch_divergence_escalates_strategy() {
+        let dir = setup_git_repo();
+        let run = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .expect("Failed to run git");
+        };
+
+        run(&["checkout", "-b", "feature"]);
+        let indexed_sha = get_head_sha(&dir);
+        let merge_base = indexed_sha.clone();
+        for i in 0..3 {
+            fs::write(dir.path().join(format!("feature{}.rs", i)), "// feature").unwrap();
+            run(&["add", "."]);
+            run(&["commit", "-m", "Feature commit"]);
+        }
+
+        let mut detector = DriftDetector::with_file_count(dir.path().to_path_buf(), 100);
+        let drift = detector
+            .check_drift(LayerKind::Branch, Some(&indexed_sha), Some(&merge_base))
+            .unwrap();
+        let divergence = drift.divergence.clone().expect("branch divergence");
+        assert_eq!(divergence.commits_ahead, 3);
+        assert_eq!(divergence.commits_behind, 0);
+        assert_eq!(divergence.files_changed, 3);
+        assert_eq!(divergence.last_common_commit, merge_base);
+        assert!(!drift.divergence_exceeded);
+        assert!(matches!(
+            drift.strategy(100),
+            UpdateStrategy::Incremental(_)
+        ));
+
+        // Same changes, but the branch is now considered far from main
+        detector.set_divergence_threshold(2);
+        let drift = detector
+            .check_drift(LayerKind::Branch, Some(&indexed_sha), Some(&merge_base))
+            .unwrap();
+        assert!(drift.divergence_exceeded);
+        assert_eq!(drift.strategy(100), UpdateStrategy::Rebase);
+    }
+
     #[test]
     fn test_working_layer_checks_uncommitted() {
         let dir = setup_git_repo();
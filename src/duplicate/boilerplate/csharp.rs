@@ -33,11 +33,11 @@
 //!
 //! ## General C# (2 patterns)
 //! - **CSharpProperty**: Auto-property accessor (get; set;)
-//! - **CSharpRecord**: Record types (primary constructors)
+//! - **CSharpRecord**: Record types and their compiler-generated members
 
 use super::{BoilerplateCategory, PatternMatcher};
 use crate::lang::Lang;
-use crate::schema::SymbolInfo;
+use crate::schema::{branch_count, SymbolInfo, SymbolKind};
 
 /// All C# boilerplate patterns
 ///
@@ -158,16 +158,17 @@ pub static PATTERNS: &[PatternMatcher] = &[
     // ==========================================================================
     // General C# Patterns (most generic - check last)
     // ==========================================================================
+    // Records before properties: a record type would pass the property check
     PatternMatcher {
-        category: BoilerplateCategory::CSharpProperty,
+        category: BoilerplateCategory::CSharpRecord,
         languages: &[Lang::CSharp],
-        detector: is_csharp_property,
+        detector: is_csharp_record,
         enabled_by_default: true,
     },
     PatternMatcher {
-        category: BoilerplateCategory::CSharpRecord,
+        category: BoilerplateCategory::CSharpProperty,
         languages: &[Lang::CSharp],
-        detector: is_csharp_record,
+        detector: is_csharp_property,
         enabled_by_default: true,
     },
 ];
@@ -325,7 +326,7 @@ pub fn is_moq_setup(info: &SymbolInfo) -> bool {
 
     // At least 60% of calls should be Moq-related
     let ratio = moq_call_count as f32 / info.calls.len() as f32;
-    ratio >= 0.6 && info.control_flow.len() <= 1
+    ratio >= 0.6 && branch_count(&info.control_flow) <= 1
 }
 
 // =============================================================================
@@ -405,7 +406,7 @@ pub fn is_unity_serialized_field(info: &SymbolInfo) -> bool {
     }
 
     // Fallback: Must have no logic
-    if !info.control_flow.is_empty() || info.calls.len() > 2 {
+    if branch_count(&info.control_flow) > 0 || info.calls.len() > 2 {
         return false;
     }
 
@@ -474,7 +475,7 @@ pub fn is_unity_scriptable_object(info: &SymbolInfo) -> bool {
     // Check naming patterns for ScriptableObject types
     let name = &info.name;
     (name.ends_with("Data") || name.ends_with("Config") || name.ends_with("Settings"))
-        && info.control_flow.is_empty()
+        && branch_count(&info.control_flow) == 0
         && info.calls.is_empty()
 }
 
@@ -502,7 +503,7 @@ pub fn is_aspnet_controller(info: &SymbolInfo) -> bool {
 
     if has_http_attr {
         // With HTTP attribute, allow more complexity (still controller action)
-        return info.control_flow.len() <= 4 && info.calls.len() <= 10;
+        return branch_count(&info.control_flow) <= 4 && info.calls.len() <= 10;
     }
 
     // Fallback: Check for ActionResult return patterns
@@ -534,7 +535,7 @@ pub fn is_aspnet_controller(info: &SymbolInfo) -> bool {
     });
 
     if has_action_result {
-        return info.control_flow.len() <= 3 && info.calls.len() <= 8;
+        return branch_count(&info.control_flow) <= 3 && info.calls.len() <= 8;
     }
 
     // Check for HTTP action naming patterns
@@ -554,7 +555,7 @@ pub fn is_aspnet_controller(info: &SymbolInfo) -> bool {
 
     // Actions with HTTP naming should have moderate complexity
     if is_action_name {
-        return info.control_flow.len() <= 2 && info.calls.len() <= 6;
+        return branch_count(&info.control_flow) <= 2 && info.calls.len() <= 6;
     }
 
     false
@@ -647,7 +648,7 @@ pub fn is_aspnet_di(info: &SymbolInfo) -> bool {
         .count();
 
     let ratio = di_call_count as f32 / info.calls.len() as f32;
-    ratio >= 0.7 && info.control_flow.len() <= 1
+    ratio >= 0.7 && branch_count(&info.control_flow) <= 1
 }
 
 // =============================================================================
@@ -662,7 +663,7 @@ pub fn is_ef_dbcontext(info: &SymbolInfo) -> bool {
 /// EF DbSet: Property getter/setter with no logic, DbSet type
 pub fn is_ef_dbset(info: &SymbolInfo) -> bool {
     // DbSet properties are typically simple getter/setters
-    if !info.calls.is_empty() || !info.control_flow.is_empty() {
+    if !info.calls.is_empty() || branch_count(&info.control_flow) > 0 {
         return false;
     }
 
@@ -674,7 +675,7 @@ pub fn is_ef_dbset(info: &SymbolInfo) -> bool {
     let is_pascal_case = name.chars().next().map_or(false, |c| c.is_uppercase());
     let is_plural = name.ends_with('s') || name.ends_with("ies") || name.ends_with("es");
 
-    is_pascal_case && is_plural && info.calls.is_empty() && info.control_flow.is_empty()
+    is_pascal_case && is_plural && info.calls.is_empty() && branch_count(&info.control_flow) == 0
 }
 
 /// EF Fluent API: 60%+ calls are HasKey/HasOne/HasMany/Property/ToTable
@@ -806,12 +807,12 @@ pub fn is_linq_chain(info: &SymbolInfo) -> bool {
         .filter(|c| linq_methods.contains(&c.name.as_str()))
         .count();
 
-    linq_count >= 2 && info.control_flow.len() <= 1
+    linq_count >= 2 && branch_count(&info.control_flow) <= 1
 }
 
 /// LINQ Projection: Only Select + ToList/ToArray calls, no control flow
 pub fn is_linq_projection(info: &SymbolInfo) -> bool {
-    if !info.control_flow.is_empty() {
+    if branch_count(&info.control_flow) > 0 {
         return false;
     }
 
@@ -833,7 +834,7 @@ pub fn is_linq_projection(info: &SymbolInfo) -> bool {
 /// C# Property: No calls, no control flow, PascalCase name
 pub fn is_csharp_property(info: &SymbolInfo) -> bool {
     // Properties have no logic
-    if !info.calls.is_empty() || !info.control_flow.is_empty() {
+    if !info.calls.is_empty() || branch_count(&info.control_flow) > 0 {
         return false;
     }
 
@@ -847,8 +848,15 @@ pub fn is_csharp_property(info: &SymbolInfo) -> bool {
     is_property_name
 }
 
-/// C# Record: Equals/GetHashCode/ToString/Deconstruct with minimal logic
+/// C# Record: record types, and Equals/GetHashCode/ToString/Deconstruct with minimal logic
+///
+/// A record's equality, printing and deconstruction are generated from its
+/// members, so a record without logic of its own is boilerplate too.
 pub fn is_csharp_record(info: &SymbolInfo) -> bool {
+    if info.kind == SymbolKind::Type {
+        return branch_count(&info.control_flow) <= 2 && info.calls.len() <= 4;
+    }
+
     let record_methods = [
         "Equals",
         "GetHashCode",
@@ -865,7 +873,7 @@ pub fn is_csharp_record(info: &SymbolInfo) -> bool {
     }
 
     // Record-generated methods should be simple
-    info.control_flow.len() <= 2 && info.calls.len() <= 4
+    branch_count(&info.control_flow) <= 2 && info.calls.len() <= 4
 }
 
 #[cfg(test)]
@@ -891,6 +899,7 @@ mod tests {
                     in_try: false,
                     is_hook: false,
                     is_io: false,
+                    is_macro: false,
                     ref_kind: RefKind::None,
                     location: Location::default(),
                 })
@@ -924,6 +933,7 @@ mod tests {
                     in_try: false,
                     is_hook: false,
                     is_io: false,
+                    is_macro: false,
                     ref_kind: RefKind::None,
                     location: Location::default(),
                 })
@@ -1554,6 +1564,24 @@ mod tests {
         assert!(is_csharp_record(&symbol));
     }
 
+    #[test]
+    fn test_csharp_record_type() {
+        use crate::duplicate::boilerplate::classify_boilerplate_with_lang;
+
+        let symbol = SymbolInfo {
+            kind: SymbolKind::Type,
+            ..make_symbol("Person", vec![], 0)
+        };
+        assert!(is_csharp_record(&symbol));
+        assert_eq!(
+            classify_boilerplate_with_lang(&symbol, Some(Lang::CSharp), None),
+            Some(BoilerplateCategory::CSharpRecord)
+        );
+
+        // A class with the same shape is not a record
+        assert!(!is_csharp_record(&make_symbol("Person", vec![], 0)));
+    }
+
     #[test]
     fn test_csharp_record_too_complex() {
         let symbol = make_symbol("Equals", vec!["a", "b", "c", "d", "e"], 3);
@@ -22,7 +22,7 @@
 
 use super::{BoilerplateCategory, PatternMatcher};
 use crate::lang::Lang;
-use crate::schema::SymbolInfo;
+use crate::schema::{branch_count, SymbolInfo};
 
 /// All JavaScript/TypeScript boilerplate patterns
 pub static PATTERNS: &[PatternMatcher] = &[
@@ -169,7 +169,7 @@ pub fn is_react_query(info: &SymbolInfo) -> bool {
     }
 
     // Must have query calls and minimal other logic (query calls + 2 max)
-    info.calls.len() <= query_calls.len() + 2 && info.control_flow.len() <= 1
+    info.calls.len() <= query_calls.len() + 2 && branch_count(&info.control_flow) <= 1
 }
 
 /// React hook wrapper: custom hook with useState/useEffect
@@ -188,7 +188,7 @@ pub fn is_react_hook_wrapper(info: &SymbolInfo) -> bool {
     // Must have React hooks and minimal other logic
     !hook_calls.is_empty()
         && info.calls.len() <= hook_calls.len() + 3
-        && info.control_flow.len() <= 2
+        && branch_count(&info.control_flow) <= 2
 }
 
 /// Event handler: handle*/on* with minimal calls
@@ -204,7 +204,7 @@ pub fn is_event_handler(info: &SymbolInfo) -> bool {
     }
 
     // Minimal calls (2 or fewer)
-    info.calls.len() <= 2 && info.control_flow.len() <= 1
+    info.calls.len() <= 2 && branch_count(&info.control_flow) <= 1
 }
 
 /// Test setup: beforeEach, afterEach, setup, teardown
@@ -230,7 +230,7 @@ pub fn is_type_guard(info: &SymbolInfo) -> bool {
     }
 
     // Minimal logic: few control flow, few calls
-    info.control_flow.len() <= 1 && info.calls.len() <= 1
+    branch_count(&info.control_flow) <= 1 && info.calls.len() <= 1
 }
 
 /// API route: Express/Next.js route handlers
@@ -254,7 +254,7 @@ pub fn is_api_route(info: &SymbolInfo) -> bool {
         .collect();
 
     // Must have route calls and minimal control flow
-    !route_calls.is_empty() && info.control_flow.len() <= 2
+    !route_calls.is_empty() && branch_count(&info.control_flow) <= 2
 }
 
 /// Config/export: module.exports patterns
@@ -270,7 +270,7 @@ pub fn is_config_export(info: &SymbolInfo) -> bool {
     }
 
     // Minimal logic
-    info.calls.is_empty() && info.control_flow.is_empty()
+    info.calls.is_empty() && branch_count(&info.control_flow) == 0
 }
 
 /// Redux/RTK: createSlice, createAction, useSelector, useDispatch, selectors
@@ -295,7 +295,7 @@ pub fn is_redux_pattern(info: &SymbolInfo) -> bool {
 
     if !rtk_calls.is_empty() {
         // RTK setup with minimal additional logic
-        return info.calls.len() <= rtk_calls.len() + 2 && info.control_flow.len() <= 1;
+        return info.calls.len() <= rtk_calls.len() + 2 && branch_count(&info.control_flow) <= 1;
     }
 
     // Redux hooks: useSelector, useDispatch, useStore
@@ -307,13 +307,13 @@ pub fn is_redux_pattern(info: &SymbolInfo) -> bool {
 
     if !redux_hooks.is_empty() {
         // Hook usage with minimal logic
-        return info.calls.len() <= redux_hooks.len() + 2 && info.control_flow.len() <= 1;
+        return info.calls.len() <= redux_hooks.len() + 2 && branch_count(&info.control_flow) <= 1;
     }
 
     // Selector pattern: select* functions with minimal logic
     if info.name.starts_with("select") && info.name.len() > 6 {
         // Selectors typically just access state with minimal transformations
-        return info.calls.len() <= 2 && info.control_flow.len() <= 1;
+        return info.calls.len() <= 2 && branch_count(&info.control_flow) <= 1;
     }
 
     false
@@ -389,7 +389,7 @@ pub fn is_test_mock(info: &SymbolInfo) -> bool {
 
     if !mock_calls.is_empty() {
         // Has mock calls with minimal other logic
-        return info.calls.len() <= mock_calls.len() + 3 && info.control_flow.len() <= 2;
+        return info.calls.len() <= mock_calls.len() + 3 && branch_count(&info.control_flow) <= 2;
     }
 
     // Check function name patterns for mock factories
@@ -402,7 +402,7 @@ pub fn is_test_mock(info: &SymbolInfo) -> bool {
         || name_lower.ends_with("stub");
 
     // Mock factory with minimal logic
-    is_mock_name && info.calls.len() <= 3 && info.control_flow.len() <= 1
+    is_mock_name && info.calls.len() <= 3 && branch_count(&info.control_flow) <= 1
 }
 
 /// Next.js data fetching: getServerSideProps, getStaticProps, generateMetadata
@@ -444,7 +444,7 @@ pub fn is_react_wrapper(info: &SymbolInfo) -> bool {
     }
 
     // Wrapper with minimal additional logic (wrapper call + maybe 1-2 other calls like displayName)
-    info.calls.len() <= wrapper_calls.len() + 2 && info.control_flow.len() <= 1
+    info.calls.len() <= wrapper_calls.len() + 2 && branch_count(&info.control_flow) <= 1
 }
 
 /// Classic Redux reducer: switch on action.type pattern (pre-RTK)
@@ -462,7 +462,7 @@ pub fn is_classic_redux_reducer(info: &SymbolInfo) -> bool {
     // Classic reducers have substantial control flow (switch cases)
     // Each case in a switch typically gets counted as control flow
     // Minimum 2 cases (including default) to be a real reducer
-    if info.control_flow.len() < 2 {
+    if branch_count(&info.control_flow) < 2 {
         return false;
     }
 
@@ -547,7 +547,7 @@ pub fn is_api_wrapper(info: &SymbolInfo) -> bool {
 
     // Thin wrapper: HTTP call + minimal additional logic
     // Allow a few extra calls for headers, params, etc.
-    info.calls.len() <= http_calls.len() + 2 && info.control_flow.len() <= 1
+    info.calls.len() <= http_calls.len() + 2 && branch_count(&info.control_flow) <= 1
 }
 
 /// Context provider: Component wrapping children with Context.Provider
@@ -576,7 +576,7 @@ pub fn is_context_provider(info: &SymbolInfo) -> bool {
 
     // Provider with minimal logic - mostly just wrapping children
     // Allow state hooks and context creation, but not much else
-    info.calls.len() <= provider_calls.len() + 3 && info.control_flow.len() <= 2
+    info.calls.len() <= provider_calls.len() + 3 && branch_count(&info.control_flow) <= 2
 }
 
 /// Simple useContext hook: One-liner hooks that just call useContext
@@ -596,7 +596,7 @@ pub fn is_simple_context_hook(info: &SymbolInfo) -> bool {
     }
 
     // Very minimal: useContext + maybe one other call (like a getter)
-    info.calls.len() <= 2 && info.control_flow.is_empty()
+    info.calls.len() <= 2 && branch_count(&info.control_flow) == 0
 }
 
 /// HOC wrapper: Higher-order component patterns (withAuth, withRouter)
@@ -625,7 +625,7 @@ pub fn is_hoc_wrapper(info: &SymbolInfo) -> bool {
     }
 
     // HOC with minimal logic (hook calls + maybe forwardRef or memo)
-    info.calls.len() <= wrapper_calls.len() + 3 && info.control_flow.len() <= 1
+    info.calls.len() <= wrapper_calls.len() + 3 && branch_count(&info.control_flow) <= 1
 }
 
 /// Lazy component: React.lazy dynamic import wrappers
@@ -644,7 +644,7 @@ pub fn is_lazy_component(info: &SymbolInfo) -> bool {
     }
 
     // Lazy components are very minimal - just the lazy call and maybe import
-    info.calls.len() <= 2 && info.control_flow.is_empty()
+    info.calls.len() <= 2 && branch_count(&info.control_flow) == 0
 }
 
 /// Suspense/ErrorBoundary wrapper: Components that wrap children with Suspense or ErrorBoundary
@@ -686,7 +686,7 @@ pub fn is_suspense_boundary(info: &SymbolInfo) -> bool {
     }
 
     // Boundary wrapper with minimal logic
-    info.calls.len() <= boundary_calls.len() + 3 && info.control_flow.len() <= 2
+    info.calls.len() <= boundary_calls.len() + 3 && branch_count(&info.control_flow) <= 2
 }
 
 #[cfg(test)]
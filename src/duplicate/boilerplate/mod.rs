@@ -20,10 +20,12 @@
 
 pub mod csharp;
 pub mod javascript;
+pub mod php;
+pub mod python;
 pub mod rust;
 
 use crate::lang::Lang;
-use crate::schema::SymbolInfo;
+use crate::schema::{branch_count, SymbolInfo};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -111,12 +113,13 @@ pub enum BoilerplateCategory {
     RustSerde,
 
     // =========================================================================
-    // Python Patterns (TODO: Implement in python.rs)
+    // Python Patterns
     // =========================================================================
+    /// FastAPI route handlers (@app.get, @router.post)
+    FastAPIRoute,
     // TODO(SEM-XX): Python boilerplate detection - HIGH PRIORITY
     // - PytestFixture: pytest fixtures (@pytest.fixture)
     // - PythonDataclass: dataclass boilerplate (@dataclass)
-    // - FastAPIRoute: FastAPI route handlers (@app.get, @app.post)
     // - PydanticModel: Pydantic model definitions (BaseModel subclasses)
     // - DjangoView: Django view classes and functions
     // - FlaskRoute: Flask route handlers (@app.route)
@@ -227,14 +230,14 @@ pub enum BoilerplateCategory {
     // - SwiftAsync: async/await task tree patterns
 
     // =========================================================================
-    // PHP Patterns (TODO: Implement in php.rs) - MEDIUM PRIORITY
+    // PHP Patterns
     // =========================================================================
-    // TODO(SEM-XX): PHP boilerplate detection - MEDIUM PRIORITY (High ROI for Laravel)
-    // - LaravelController: Laravel controller methods
-    // - LaravelServiceProvider: Service provider patterns
-    // - LaravelMiddleware: Middleware handle() patterns
-    // - EloquentModel: Eloquent model boilerplate ($fillable, relations)
-    // - BladeComponent: Blade component patterns
+    /// Laravel middleware handle() that passes the request to $next
+    LaravelMiddleware,
+    /// Laravel controller actions with minimal logic
+    LaravelController,
+    /// Eloquent model relations, scopes and accessors
+    EloquentModel,
 
     // =========================================================================
     // Ruby Patterns (TODO: Implement in ruby.rs) - LOW PRIORITY
@@ -290,6 +293,8 @@ impl BoilerplateCategory {
             BoilerplateCategory::RustDrop => "Rust Drop implementation",
             BoilerplateCategory::RustTest => "Rust test function",
             BoilerplateCategory::RustSerde => "Rust serde helper",
+            // Python
+            BoilerplateCategory::FastAPIRoute => "FastAPI route handler",
             // C# patterns
             BoilerplateCategory::AspNetController => "ASP.NET Controller action method",
             BoilerplateCategory::AspNetMinimalApi => "ASP.NET Minimal API endpoint",
@@ -309,6 +314,10 @@ impl BoilerplateCategory {
             BoilerplateCategory::UnityScriptableObject => "Unity ScriptableObject",
             BoilerplateCategory::CSharpProperty => "C# auto-property",
             BoilerplateCategory::CSharpRecord => "C# record boilerplate",
+            // PHP patterns
+            BoilerplateCategory::LaravelMiddleware => "Laravel middleware handle()",
+            BoilerplateCategory::LaravelController => "Laravel controller action",
+            BoilerplateCategory::EloquentModel => "Eloquent model relation/scope/accessor",
             // Cross-language
             BoilerplateCategory::Custom => "Custom boilerplate pattern",
         }
@@ -351,6 +360,8 @@ impl BoilerplateCategory {
             | BoilerplateCategory::RustDrop
             | BoilerplateCategory::RustTest
             | BoilerplateCategory::RustSerde => Some(Lang::Rust),
+            // Python patterns
+            BoilerplateCategory::FastAPIRoute => Some(Lang::Python),
             // C# patterns
             BoilerplateCategory::AspNetController
             | BoilerplateCategory::AspNetMinimalApi
@@ -370,6 +381,10 @@ impl BoilerplateCategory {
             | BoilerplateCategory::UnityScriptableObject
             | BoilerplateCategory::CSharpProperty
             | BoilerplateCategory::CSharpRecord => Some(Lang::CSharp),
+            // PHP patterns
+            BoilerplateCategory::LaravelMiddleware
+            | BoilerplateCategory::LaravelController
+            | BoilerplateCategory::EloquentModel => Some(Lang::Php),
             // Cross-language
             BoilerplateCategory::Custom => None,
         }
@@ -399,10 +414,17 @@ pub struct PatternMatcher {
 }
 
 /// Get all registered patterns from all language modules
+///
+/// PHP patterns come first: they only match Laravel entry points and `$next`
+/// calls, which no other language produces, while name-based patterns such
+/// as Rust trait methods (`index`, `from`) would otherwise claim PHP methods
+/// when no language hint is given.
 pub fn all_patterns() -> impl Iterator<Item = &'static PatternMatcher> {
-    javascript::PATTERNS
+    php::PATTERNS
         .iter()
+        .chain(javascript::PATTERNS.iter())
         .chain(rust::PATTERNS.iter())
+        .chain(python::PATTERNS.iter())
         .chain(csharp::PATTERNS.iter())
 }
 
@@ -725,7 +747,7 @@ impl CustomBoilerplateRule {
 
         // Check max control flow
         if let Some(max) = self.max_control_flow {
-            if info.control_flow.len() > max {
+            if branch_count(&info.control_flow) > max {
                 return false;
             }
         }
@@ -914,6 +936,7 @@ mod tests {
                     in_try: false,
                     is_hook: false,
                     is_io: false,
+                    is_macro: false,
                     ref_kind: RefKind::None,
                     location: Location::default(),
                 })
@@ -947,6 +970,7 @@ mod tests {
                     in_try: false,
                     is_hook: false,
                     is_io: false,
+                    is_macro: false,
                     ref_kind: RefKind::None,
                     location: Location::default(),
                 })
@@ -0,0 +1,174 @@
+//! PHP boilerplate pattern detection
+//!
+//! This module contains detection functions for common PHP boilerplate patterns
+//! that should be excluded from duplicate detection.
+//!
+//! # Patterns Detected
+//!
+//! ## Laravel (3 patterns)
+//! - **LaravelMiddleware**: Middleware `handle()` methods that pass the request to `$next`
+//! - **LaravelController**: Controller actions that validate input and hand off to a model or service
+//! - **EloquentModel**: Relations, scopes and accessors on Eloquent models
+
+use super::{BoilerplateCategory, PatternMatcher};
+use crate::lang::Lang;
+use crate::schema::{branch_count, FrameworkEntryPoint, SymbolInfo, SymbolKind};
+
+/// All PHP boilerplate patterns
+///
+/// Order matters! Patterns are checked in order, so more specific patterns
+/// should come before more general ones.
+pub static PATTERNS: &[PatternMatcher] = &[
+    PatternMatcher {
+        category: BoilerplateCategory::LaravelMiddleware,
+        languages: &[Lang::Php],
+        detector: is_laravel_middleware,
+        enabled_by_default: true,
+    },
+    PatternMatcher {
+        category: BoilerplateCategory::LaravelController,
+        languages: &[Lang::Php],
+        detector: is_laravel_controller,
+        enabled_by_default: true,
+    },
+    PatternMatcher {
+        category: BoilerplateCategory::EloquentModel,
+        languages: &[Lang::Php],
+        detector: is_eloquent_model,
+        enabled_by_default: true,
+    },
+];
+
+// =============================================================================
+// Laravel Patterns
+// =============================================================================
+
+/// Laravel middleware: `handle($request, Closure $next)` that ends in `$next($request)`
+pub fn is_laravel_middleware(info: &SymbolInfo) -> bool {
+    if info.name != "handle" {
+        return false;
+    }
+
+    info.calls.iter().any(|c| c.name == "$next")
+}
+
+/// Laravel controller action: routed to by the framework and thin enough to
+/// only validate input and hand off to a model or service
+pub fn is_laravel_controller(info: &SymbolInfo) -> bool {
+    if info.framework_entry_point != FrameworkEntryPoint::LaravelController {
+        return false;
+    }
+
+    // Actions with real logic are worth deduplicating
+    branch_count(&info.control_flow) <= 3 && info.calls.len() <= 8
+}
+
+/// Eloquent model method: a relation, scope or accessor that wraps a single
+/// query builder or relation call
+pub fn is_eloquent_model(info: &SymbolInfo) -> bool {
+    if info.framework_entry_point != FrameworkEntryPoint::EloquentModel
+        || info.kind != SymbolKind::Function
+    {
+        return false;
+    }
+
+    branch_count(&info.control_flow) == 0 && info.calls.len() <= 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duplicate::boilerplate::tests::make_symbol;
+
+    fn with_entry_point(
+        name: &str,
+        calls: Vec<&str>,
+        control_flow: usize,
+        entry_point: FrameworkEntryPoint,
+    ) -> SymbolInfo {
+        SymbolInfo {
+            framework_entry_point: entry_point,
+            ..make_symbol(name, calls, control_flow)
+        }
+    }
+
+    #[test]
+    fn test_laravel_middleware_detection() {
+        assert!(is_laravel_middleware(&make_symbol(
+            "handle",
+            vec!["check", "$next"],
+            1
+        )));
+
+        // Short-circuits without passing the request on
+        assert!(!is_laravel_middleware(&make_symbol(
+            "handle",
+            vec!["abort"],
+            1
+        )));
+        // Express-style `next()` is not Laravel's `$next` closure
+        assert!(!is_laravel_middleware(&make_symbol(
+            "handle",
+            vec!["next"],
+            0
+        )));
+        assert!(!is_laravel_middleware(&make_symbol(
+            "process",
+            vec!["$next"],
+            0
+        )));
+    }
+
+    #[test]
+    fn test_laravel_controller_detection() {
+        let action = |calls, control_flow| {
+            with_entry_point(
+                "store",
+                calls,
+                control_flow,
+                FrameworkEntryPoint::LaravelController,
+            )
+        };
+        assert!(is_laravel_controller(&action(
+            vec!["validated", "create", "redirect"],
+            0
+        )));
+
+        // Too much logic to be boilerplate
+        assert!(!is_laravel_controller(&action(vec!["create"], 5)));
+
+        // Not marked as an action by the detector
+        assert!(!is_laravel_controller(&make_symbol(
+            "store",
+            vec!["create"],
+            0
+        )));
+    }
+
+    #[test]
+    fn test_eloquent_model_detection() {
+        assert!(is_eloquent_model(&with_entry_point(
+            "posts",
+            vec!["hasMany"],
+            0,
+            FrameworkEntryPoint::EloquentModel
+        )));
+
+        // The model class itself isn't a duplicate candidate
+        let mut class = with_entry_point("User", vec![], 0, FrameworkEntryPoint::EloquentModel);
+        class.kind = SymbolKind::Class;
+        assert!(!is_eloquent_model(&class));
+
+        assert!(!is_eloquent_model(&with_entry_point(
+            "scopeActive",
+            vec!["where"],
+            2,
+            FrameworkEntryPoint::EloquentModel
+        )));
+        assert!(!is_eloquent_model(&make_symbol(
+            "posts",
+            vec!["hasMany"],
+            0
+        )));
+    }
+}
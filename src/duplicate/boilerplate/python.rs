@@ -0,0 +1,78 @@
+//! Python boilerplate pattern detection
+//!
+//! This module contains detection functions for common Python boilerplate patterns
+//! that should be excluded from duplicate detection.
+//!
+//! # Patterns Detected
+//!
+//! ## FastAPI (1 pattern)
+//! - **FastAPIRoute**: Route handlers (@app.get, @router.post) that delegate to services
+
+use super::{BoilerplateCategory, PatternMatcher};
+use crate::detectors::python::frameworks::fastapi::is_route_decorator;
+use crate::lang::Lang;
+use crate::schema::{branch_count, FrameworkEntryPoint, SymbolInfo};
+
+/// All Python boilerplate patterns
+///
+/// Order matters! Patterns are checked in order, so more specific patterns
+/// should come before more general ones.
+pub static PATTERNS: &[PatternMatcher] = &[PatternMatcher {
+    category: BoilerplateCategory::FastAPIRoute,
+    languages: &[Lang::Python],
+    detector: is_fastapi_route,
+    enabled_by_default: true,
+}];
+
+// =============================================================================
+// FastAPI Patterns
+// =============================================================================
+
+/// FastAPI route handler: decorated with a route registration and thin enough
+/// to only validate input and hand off to a service
+pub fn is_fastapi_route(info: &SymbolInfo) -> bool {
+    if info.framework_entry_point != FrameworkEntryPoint::ApiRoute {
+        return false;
+    }
+    if !info.decorators.iter().any(|d| is_route_decorator(d)) {
+        return false;
+    }
+
+    // Handlers with real logic are worth deduplicating
+    branch_count(&info.control_flow) <= 3 && info.calls.len() <= 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duplicate::boilerplate::tests::make_symbol;
+
+    fn route(calls: Vec<&str>, control_flow: usize) -> SymbolInfo {
+        SymbolInfo {
+            decorators: vec!["router.get(\"/items/{item_id}\")".to_string()],
+            framework_entry_point: FrameworkEntryPoint::ApiRoute,
+            ..make_symbol("read_item", calls, control_flow)
+        }
+    }
+
+    #[test]
+    fn test_fastapi_route_detection() {
+        assert!(is_fastapi_route(&route(
+            vec!["get_item", "HTTPException"],
+            1
+        )));
+
+        // Too much logic to be boilerplate
+        assert!(!is_fastapi_route(&route(vec!["get_item"], 5)));
+
+        // Not marked as a route by the detector
+        let mut plain = route(vec!["get_item"], 0);
+        plain.framework_entry_point = FrameworkEntryPoint::None;
+        assert!(!is_fastapi_route(&plain));
+
+        // Other decorators don't count
+        let mut cached = route(vec!["get_item"], 0);
+        cached.decorators = vec!["lru_cache(maxsize=32)".to_string()];
+        assert!(!is_fastapi_route(&cached));
+    }
+}
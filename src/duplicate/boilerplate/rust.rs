@@ -21,7 +21,7 @@
 
 use super::{BoilerplateCategory, PatternMatcher};
 use crate::lang::Lang;
-use crate::schema::SymbolInfo;
+use crate::schema::{branch_count, SymbolInfo};
 
 /// All Rust boilerplate patterns
 ///
@@ -211,7 +211,7 @@ pub fn is_rust_trait_impl(info: &SymbolInfo) -> bool {
     }
 
     // Trait implementations should be simple
-    info.control_flow.len() <= 2 && info.calls.len() <= 4
+    branch_count(&info.control_flow) <= 2 && info.calls.len() <= 4
 }
 
 /// Rust builder pattern: with_* or set_* methods that return Self
@@ -222,7 +222,7 @@ pub fn is_rust_builder(info: &SymbolInfo) -> bool {
     if name.starts_with("with_") || name.starts_with("set_") || name == "builder" || name == "build"
     {
         // Should have minimal logic - typically just field assignment
-        return info.control_flow.len() <= 1 && info.calls.len() <= 3;
+        return branch_count(&info.control_flow) <= 1 && info.calls.len() <= 3;
     }
 
     false
@@ -250,7 +250,7 @@ pub fn is_rust_getter(info: &SymbolInfo) -> bool {
     }
 
     // Getters should have minimal logic
-    info.control_flow.len() <= 1 && info.calls.len() <= 2
+    branch_count(&info.control_flow) <= 1 && info.calls.len() <= 2
 }
 
 /// Rust setter: set_* methods (not builder pattern - no return)
@@ -260,7 +260,7 @@ pub fn is_rust_setter(info: &SymbolInfo) -> bool {
     }
 
     // Setters should have minimal logic - just assignment, maybe with simple validation
-    info.control_flow.len() <= 1 && info.calls.len() <= 2
+    branch_count(&info.control_flow) <= 1 && info.calls.len() <= 2
 }
 
 /// Rust constructor: new, default, from_*, try_from_*, with_*
@@ -282,7 +282,7 @@ pub fn is_rust_constructor(info: &SymbolInfo) -> bool {
         || name == "connect"
     {
         // Constructors can have moderate logic but shouldn't be too complex
-        return info.control_flow.len() <= 2 && info.calls.len() <= 4;
+        return branch_count(&info.control_flow) <= 2 && info.calls.len() <= 4;
     }
 
     false
@@ -301,7 +301,7 @@ pub fn is_rust_conversion(info: &SymbolInfo) -> bool {
         || name.starts_with("try_into_")
     {
         // Conversions should be simple
-        return info.control_flow.len() <= 2 && info.calls.len() <= 3;
+        return branch_count(&info.control_flow) <= 2 && info.calls.len() <= 3;
     }
 
     false
@@ -313,7 +313,7 @@ pub fn is_rust_derived(info: &SymbolInfo) -> bool {
     matches!(
         info.name.as_str(),
         "clone" | "default" | "eq" | "ne" | "hash" | "cmp" | "partial_cmp" | "fmt"
-    ) && info.control_flow.len() <= 1
+    ) && branch_count(&info.control_flow) <= 1
         && info.calls.len() <= 2
 }
 
@@ -325,7 +325,7 @@ pub fn is_rust_error_from(info: &SymbolInfo) -> bool {
     }
 
     // Error conversions are usually simple wrappers
-    info.control_flow.is_empty() && info.calls.len() <= 2
+    branch_count(&info.control_flow) == 0 && info.calls.len() <= 2
 }
 
 /// Rust iterator: next, into_iter, iter, iter_mut
@@ -355,18 +355,18 @@ pub fn is_rust_iterator(info: &SymbolInfo) -> bool {
 
     // Check name matches and complexity is reasonable
     iterator_methods.contains(&info.name.as_str())
-        && info.control_flow.len() <= 2
+        && branch_count(&info.control_flow) <= 2
         && info.calls.len() <= 4
 }
 
 /// Rust Deref: deref and deref_mut
 pub fn is_rust_deref(info: &SymbolInfo) -> bool {
-    matches!(info.name.as_str(), "deref" | "deref_mut") && info.control_flow.is_empty()
+    matches!(info.name.as_str(), "deref" | "deref_mut") && branch_count(&info.control_flow) == 0
 }
 
 /// Rust Drop: drop method
 pub fn is_rust_drop(info: &SymbolInfo) -> bool {
-    info.name == "drop" && info.control_flow.len() <= 1
+    info.name == "drop" && branch_count(&info.control_flow) <= 1
 }
 
 /// Rust serde helpers: serialize_*, deserialize_*, with_*
@@ -386,7 +386,7 @@ pub fn is_rust_serde(info: &SymbolInfo) -> bool {
 
     // Serde attribute helpers
     if name.starts_with("default_") || name.starts_with("skip_") || name.starts_with("rename_") {
-        return info.control_flow.is_empty() && info.calls.len() <= 1;
+        return branch_count(&info.control_flow) == 0 && info.calls.len() <= 1;
     }
 
     false
@@ -17,12 +17,14 @@
 //! - Total: <5ms for full repository scan
 
 pub mod boilerplate;
+pub mod report;
 
 use crate::lang::Lang;
 use crate::schema::{
     fnv1a_hash, Call, ControlFlowChange, ControlFlowKind, StateChange, SymbolInfo,
 };
 use crate::security::{CVEMatch, CVEPattern, PatternDatabase};
+use crate::utils::{is_word_char, split_scripts, ScriptClass};
 use boilerplate::{classify_boilerplate, BoilerplateCategory, BoilerplateConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -107,6 +109,14 @@ pub struct FunctionSignature {
     /// Parameter count (arguments + props)
     pub param_count: u8,
 
+    /// Declared return type, if annotated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+
+    /// Whether the signature names lifetimes (Rust `'a`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_lifetimes: bool,
+
     /// Whether this function has business logic (non-empty business_calls)
     pub has_business_logic: bool,
 
@@ -128,7 +138,7 @@ impl FunctionSignature {
         config: Option<&BoilerplateConfig>,
     ) -> Self {
         // 1. Tokenize name: "handleUserLogin" → ["handle", "user", "login"]
-        let name_tokens = tokenize_camel_snake(&info.name);
+        let name_tokens = tokenize_camel_snake(info.identity_name());
 
         // 2. Filter utility calls to get business calls
         let business_calls: Vec<String> = info
@@ -163,6 +173,8 @@ impl FunctionSignature {
             has_business_logic: !business_calls.is_empty(),
             business_calls,
             param_count: (info.arguments.len() + info.props.len()) as u8,
+            return_type: info.return_type.clone(),
+            has_lifetimes: !info.lifetime_params.is_empty(),
             boilerplate_category,
             line_count: info.end_line.saturating_sub(info.start_line) + 1,
         }
@@ -179,6 +191,20 @@ impl FunctionSignature {
             end_line: self.start_line + self.line_count.saturating_sub(1),
         }
     }
+
+    /// Strip formatting from the business calls and recompute the call fingerprint
+    ///
+    /// Signatures written by this version are already normalized; this repairs
+    /// ones loaded from older indexes, where a call split across lines kept its
+    /// line breaks and indentation.
+    pub fn normalize_whitespace(&mut self) {
+        for call in &mut self.business_calls {
+            *call = normalize_whitespace(call);
+        }
+        let mut sorted_calls = self.business_calls.clone();
+        sorted_calls.sort();
+        self.call_fingerprint = compute_set_fingerprint(&sorted_calls);
+    }
 }
 
 /// Kind of duplicate match
@@ -263,6 +289,32 @@ impl DuplicateMatch {
     }
 }
 
+/// Why a cluster member was picked as the likely original
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OriginReason {
+    /// Its oldest line predates every other member's
+    Oldest,
+    /// Tied on age; the longest member wins
+    Longest,
+}
+
+impl OriginReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OriginReason::Oldest => "oldest",
+            OriginReason::Longest => "longest",
+        }
+    }
+}
+
+/// Member of a divergent cluster that was most likely copied by the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LikelyOriginal {
+    pub symbol: SymbolRef,
+    pub reason: OriginReason,
+}
+
 /// A cluster of similar functions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DuplicateCluster {
@@ -272,6 +324,9 @@ pub struct DuplicateCluster {
     pub duplicates: Vec<DuplicateMatch>,
     /// Human-readable summary
     pub summary: String,
+    /// Copy-paste origin hint for divergent clusters (see `annotate_likely_original`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub likely_original: Option<LikelyOriginal>,
 }
 
 impl DuplicateCluster {
@@ -281,6 +336,74 @@ impl DuplicateCluster {
             primary,
             duplicates: Vec::new(),
             summary: String::new(),
+            likely_original: None,
+        }
+    }
+
+    /// Whether every duplicate only diverged from the primary (no exact/near copies)
+    pub fn is_divergent(&self) -> bool {
+        !self.duplicates.is_empty()
+            && self
+                .duplicates
+                .iter()
+                .all(|d| d.kind == DuplicateKind::Divergent)
+    }
+
+    /// Guess which member of a divergent cluster is the original
+    ///
+    /// `age_of` returns when a member's code was first written (e.g. the
+    /// author time of its oldest blamed line); the oldest member wins.
+    /// Members tied on age fall back to the longest, then to the primary
+    /// (already the longest/most documented pick). Members without an age are
+    /// treated as newest; if no member has one there's no hint.
+    pub fn annotate_likely_original<F>(&mut self, mut age_of: F)
+    where
+        F: FnMut(&SymbolRef) -> Option<i64>,
+    {
+        if !self.is_divergent() {
+            return;
+        }
+        let members: Vec<(&SymbolRef, Option<i64>)> = std::iter::once(&self.primary)
+            .chain(self.duplicates.iter().map(|d| &d.symbol))
+            .map(|symbol| (symbol, age_of(symbol)))
+            .collect();
+        let Some(oldest) = members.iter().filter_map(|(_, age)| *age).min() else {
+            return;
+        };
+
+        let tied: Vec<&SymbolRef> = members
+            .iter()
+            .filter(|(_, age)| *age == Some(oldest))
+            .map(|(symbol, _)| *symbol)
+            .collect();
+        let reason = if tied.len() == 1 {
+            OriginReason::Oldest
+        } else {
+            OriginReason::Longest
+        };
+        let line_count = |s: &SymbolRef| s.end_line.saturating_sub(s.start_line);
+        // First of the longest, so the primary wins a full tie
+        let original = tied
+            .iter()
+            .copied()
+            .reduce(|best, s| {
+                if line_count(s) > line_count(best) {
+                    s
+                } else {
+                    best
+                }
+            })
+            .cloned();
+
+        if let Some(symbol) = original {
+            self.summary = format!(
+                "{}; likely original: {} in {} ({})",
+                self.summary,
+                symbol.name,
+                symbol.file,
+                reason.as_str()
+            );
+            self.likely_original = Some(LikelyOriginal { symbol, reason });
         }
     }
 
@@ -405,10 +528,21 @@ impl DuplicateDetector {
 
     /// Find all duplicate clusters in a set of signatures
     pub fn find_all_clusters(&self, signatures: &[FunctionSignature]) -> Vec<DuplicateCluster> {
+        self.find_all_clusters_with_progress(signatures, |_, _| {})
+    }
+
+    /// [`Self::find_all_clusters`], calling `progress(done, total)` as each
+    /// signature is processed
+    pub fn find_all_clusters_with_progress(
+        &self,
+        signatures: &[FunctionSignature],
+        progress: impl Fn(usize, usize),
+    ) -> Vec<DuplicateCluster> {
         let mut processed: HashSet<String> = HashSet::new();
         let mut clusters: Vec<DuplicateCluster> = Vec::new();
 
-        for sig in signatures {
+        for (i, sig) in signatures.iter().enumerate() {
+            progress(i, signatures.len());
             if processed.contains(&sig.symbol_hash) {
                 continue;
             }
@@ -429,6 +563,7 @@ impl DuplicateDetector {
 
             processed.insert(sig.symbol_hash.clone());
         }
+        progress(signatures.len(), signatures.len());
 
         clusters
     }
@@ -498,7 +633,8 @@ impl DuplicateDetector {
                     remediation: pattern.remediation.clone(),
                     file: signature.file.clone(),
                     function: signature.name.clone(),
-                    line: 0, // Will be populated by caller if needed
+                    symbol_hash: Some(signature.symbol_hash.clone()),
+                    line: signature.start_line as u32,
                 });
             }
         }
@@ -618,6 +754,11 @@ impl DuplicateDetector {
     /// - Name similarity (0.20)
     /// - Control flow similarity (0.20)
     /// - State similarity (0.15)
+    ///
+    /// When both functions declare a return type, return type similarity
+    /// takes 0.10 of the total and the components above share the rest.
+    /// A function with explicit lifetimes and one without borrow differently,
+    /// so the pair scores 0.95 of that.
     fn compute_similarity(&self, a: &FunctionSignature, b: &FunctionSignature) -> f64 {
         // Call similarity (Jaccard)
         let call_sim = jaccard_similarity(&a.business_calls, &b.business_calls);
@@ -633,7 +774,19 @@ impl DuplicateDetector {
         let state_sim = fingerprint_similarity(a.state_fingerprint, b.state_fingerprint);
 
         // Weighted combination
-        call_sim * 0.45 + name_sim * 0.20 + control_sim * 0.20 + state_sim * 0.15
+        let similarity = call_sim * 0.45 + name_sim * 0.20 + control_sim * 0.20 + state_sim * 0.15;
+
+        let similarity =
+            match return_type_similarity(a.return_type.as_deref(), b.return_type.as_deref()) {
+                Some(return_sim) => similarity * 0.90 + return_sim * 0.10,
+                None => similarity,
+            };
+
+        if a.has_lifetimes != b.has_lifetimes {
+            similarity * 0.95
+        } else {
+            similarity
+        }
     }
 
     /// Compute specific differences between two functions
@@ -669,33 +822,68 @@ impl DuplicateDetector {
 // Fingerprint and Similarity Utilities
 // =============================================================================
 
+/// Similarity of two return type annotations, `None` unless both are known
+///
+/// Identical types (ignoring case and whitespace) score 1.0; others score the
+/// Jaccard similarity of the type names they mention, so `list[User]` and
+/// `list[Order]` are closer than `list[User]` and `str`.
+fn return_type_similarity(a: Option<&str>, b: Option<&str>) -> Option<f64> {
+    let normalize = |t: &str| -> String {
+        t.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let (a, b) = (normalize(a?), normalize(b?));
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    if a == b {
+        return Some(1.0);
+    }
+
+    let type_names = |t: &str| -> Vec<String> {
+        let mut names: Vec<String> = t
+            .split(|c: char| !is_word_char(c))
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+    Some(jaccard_similarity(&type_names(&a), &type_names(&b)))
+}
+
 /// Tokenize camelCase and snake_case names
 ///
 /// "handleUserLogin" → ["handle", "user", "login"]
 /// "handle_user_login" → ["handle", "user", "login"]
+/// "handle用户Login" → ["handle", "用户", "login"] (splits where the script changes)
 pub fn tokenize_camel_snake(name: &str) -> Vec<String> {
     let mut tokens = Vec::new();
-    let mut current = String::new();
 
-    for ch in name.chars() {
-        if ch == '_' || ch == '-' {
+    for part in name.split(|c: char| !is_word_char(c)) {
+        for (class, run) in split_scripts(part) {
+            if class != ScriptClass::Other {
+                tokens.push(run.to_string());
+                continue;
+            }
+
+            let mut current = String::new();
+            for ch in run.chars() {
+                if ch.is_uppercase() && !current.is_empty() {
+                    tokens.push(current.to_lowercase());
+                    current.clear();
+                }
+                current.push(ch);
+            }
             if !current.is_empty() {
                 tokens.push(current.to_lowercase());
-                current.clear();
             }
-        } else if ch.is_uppercase() && !current.is_empty() {
-            tokens.push(current.to_lowercase());
-            current.clear();
-            current.push(ch);
-        } else {
-            current.push(ch);
         }
     }
 
-    if !current.is_empty() {
-        tokens.push(current.to_lowercase());
-    }
-
     tokens
 }
 
@@ -733,13 +921,39 @@ fn is_utility_call(name: &str, object: Option<&str>) -> bool {
 }
 
 /// Format a call name for fingerprinting
+///
+/// The receiver is source text, so a chain split across lines
+/// (`svc\n    .cache\n    .store`) is normalized to `svc.cache.store`.
 fn format_call_name(call: &Call) -> String {
     match &call.object {
-        Some(obj) => format!("{}.{}", obj, call.name),
+        Some(obj) => format!("{}.{}", normalize_whitespace(obj), call.name),
         None => call.name.clone(),
     }
 }
 
+/// Remove purely-formatting characters from an expression
+///
+/// Drops all whitespace and backslash line continuations, so two copies of an
+/// expression that differ only in layout normalize to the same string.
+pub fn normalize_whitespace(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_whitespace() {
+            continue;
+        }
+        if ch == '\\'
+            && chars
+                .peek()
+                .is_some_and(|next| *next == '\n' || *next == '\r')
+        {
+            continue;
+        }
+        normalized.push(ch);
+    }
+    normalized
+}
+
 /// Compute a fingerprint from a sorted set of strings
 fn compute_set_fingerprint(items: &[String]) -> u64 {
     let combined = items.join("|");
@@ -758,6 +972,8 @@ fn compute_control_flow_fingerprint(control_flow: &[ControlFlowChange]) -> u64 {
             ControlFlowKind::Match => "M",
             ControlFlowKind::Try => "T",
             ControlFlowKind::Loop => "L",
+            ControlFlowKind::Await => "A",
+            ControlFlowKind::Yield => "Y",
         })
         .collect();
 
@@ -766,7 +982,10 @@ fn compute_control_flow_fingerprint(control_flow: &[ControlFlowChange]) -> u64 {
 
 /// Compute a fingerprint from state changes
 fn compute_state_fingerprint(state_changes: &[StateChange]) -> u64 {
-    let mut names: Vec<_> = state_changes.iter().map(|s| s.name.as_str()).collect();
+    let mut names: Vec<_> = state_changes
+        .iter()
+        .map(|s| normalize_whitespace(&s.name))
+        .collect();
     names.sort();
     let combined = names.join("|");
     fnv1a_hash(&combined)
@@ -805,6 +1024,85 @@ fn fingerprint_similarity(a: u64, b: u64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+
+    fn symbol(name: &str, file: &str, lines: usize) -> SymbolRef {
+        SymbolRef {
+            hash: format!("{file}:{name}"),
+            name: name.to_string(),
+            file: file.to_string(),
+            module: String::new(),
+            start_line: 1,
+            end_line: lines,
+        }
+    }
+
+    fn divergent_cluster() -> DuplicateCluster {
+        let mut cluster = DuplicateCluster::new(symbol("parse", "src/a.rs", 20));
+        cluster.add_duplicate(DuplicateMatch::new(
+            symbol("parse", "src/b.rs", 30),
+            0.85,
+            Vec::new(),
+        ));
+        cluster.add_duplicate(DuplicateMatch::new(
+            symbol("parse", "src/c.rs", 10),
+            0.82,
+            Vec::new(),
+        ));
+        cluster.generate_summary();
+        cluster
+    }
+
+    #[test]
+    fn test_likely_original_is_oldest() {
+        let mut cluster = divergent_cluster();
+        let ages: HashMap<&str, i64> = [("src/a.rs", 300), ("src/b.rs", 200), ("src/c.rs", 100)]
+            .into_iter()
+            .collect();
+        cluster.annotate_likely_original(|s| ages.get(s.file.as_str()).copied());
+
+        let original = cluster.likely_original.as_ref().unwrap();
+        assert_eq!(original.symbol.file, "src/c.rs");
+        assert_eq!(original.reason, OriginReason::Oldest);
+        assert!(
+            cluster
+                .summary
+                .ends_with("likely original: parse in src/c.rs (oldest)"),
+            "{}",
+            cluster.summary
+        );
+    }
+
+    #[test]
+    fn test_likely_original_tie_prefers_longest() {
+        let mut cluster = divergent_cluster();
+        // b and c tied as oldest; a never committed
+        let ages: HashMap<&str, i64> = [("src/b.rs", 100), ("src/c.rs", 100)].into_iter().collect();
+        cluster.annotate_likely_original(|s| ages.get(s.file.as_str()).copied());
+
+        let original = cluster.likely_original.as_ref().unwrap();
+        assert_eq!(original.symbol.file, "src/b.rs");
+        assert_eq!(original.reason, OriginReason::Longest);
+    }
+
+    #[test]
+    fn test_likely_original_only_for_divergent() {
+        let mut cluster = DuplicateCluster::new(symbol("parse", "src/a.rs", 20));
+        cluster.add_duplicate(DuplicateMatch::new(
+            symbol("parse", "src/b.rs", 20),
+            0.99,
+            Vec::new(),
+        ));
+        cluster.annotate_likely_original(|_| Some(1));
+        assert!(cluster.likely_original.is_none());
+
+        // No ages at all (not a git repo): no hint
+        let mut cluster = divergent_cluster();
+        let summary = cluster.summary.clone();
+        cluster.annotate_likely_original(|_| None);
+        assert!(cluster.likely_original.is_none());
+        assert_eq!(cluster.summary, summary);
+    }
 
     #[test]
     fn test_tokenize_camel_case() {
@@ -830,6 +1128,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tokenize_unicode_scripts() {
+        assert_eq!(
+            tokenize_camel_snake("handle用户Login"),
+            vec!["handle", "用户", "login"]
+        );
+        assert_eq!(
+            tokenize_camel_snake("party_🎉Time"),
+            vec!["party", "🎉", "time"]
+        );
+        assert_eq!(
+            tokenize_camel_snake("cafe\u{301}Menu"),
+            vec!["cafe\u{301}", "menu"]
+        );
+    }
+
     #[test]
     fn test_jaccard_identical() {
         let a = vec!["foo", "bar", "baz"];
@@ -867,4 +1181,156 @@ mod tests {
         assert!(!is_utility_call("fetchUser", None));
         assert!(!is_utility_call("validateInput", None));
     }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(
+            normalize_whitespace("svc\n        .user_service"),
+            "svc.user_service"
+        );
+        assert_eq!(normalize_whitespace("svc  .  cache"), "svc.cache");
+        assert_eq!(normalize_whitespace("svc.cache \\\n   "), "svc.cache");
+        // A backslash that isn't a line continuation is kept
+        assert_eq!(normalize_whitespace(r"re\d+"), r"re\d+");
+    }
+
+    /// `load_user_profile` with the receiver text as a given layout left it
+    fn formatted_profile_loader(name: &str, user_service: &str, cache: &str) -> SymbolInfo {
+        let call = |object: &str, name: &str| Call {
+            name: name.to_string(),
+            object: Some(object.to_string()),
+            ..Default::default()
+        };
+        SymbolInfo {
+            name: name.to_string(),
+            start_line: 1,
+            end_line: 12,
+            calls: vec![
+                call(user_service, "fetch_user"),
+                call(cache, "store"),
+                call("svc.audit_log", "record"),
+            ],
+            control_flow: vec![ControlFlowChange {
+                kind: ControlFlowKind::If,
+                ..Default::default()
+            }],
+            state_changes: vec![StateChange {
+                name: "svc.state.count".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reformatted_copy_is_exact() {
+        let original =
+            formatted_profile_loader("load_user_profile", "svc.user_service", "svc.cache");
+        let mut reformatted = formatted_profile_loader(
+            "load_user_profile",
+            "svc\n        .user_service",
+            "svc  .  cache \\\n       ",
+        );
+        reformatted.state_changes[0].name = "svc.state .count".to_string();
+
+        let a = FunctionSignature::from_symbol_info(&original, "a:1", "src/a.rs", "a", None);
+        let b = FunctionSignature::from_symbol_info(&reformatted, "b:1", "src/b.rs", "b", None);
+        assert_eq!(a.business_calls, b.business_calls);
+        assert_eq!(a.call_fingerprint, b.call_fingerprint);
+        assert_eq!(a.control_flow_fingerprint, b.control_flow_fingerprint);
+        assert_eq!(a.state_fingerprint, b.state_fingerprint);
+
+        let matches = DuplicateDetector::default().find_duplicates(&a, &[a.clone(), b]);
+        let copy = matches
+            .iter()
+            .find(|m| m.symbol.file == "src/b.rs")
+            .unwrap();
+        assert!(copy.similarity >= 0.98, "{}", copy.similarity);
+        assert_eq!(copy.kind, DuplicateKind::Exact);
+    }
+
+    #[test]
+    fn test_normalize_legacy_signature() {
+        let info = formatted_profile_loader("load_user_profile", "svc.user_service", "svc.cache");
+        let expected = FunctionSignature::from_symbol_info(&info, "a:1", "src/a.rs", "a", None);
+
+        // An index written before normalization kept the layout in the call names
+        let mut legacy = expected.clone();
+        legacy.business_calls[0] = "svc\n    .user_service.fetch_user".to_string();
+        legacy.call_fingerprint = 0;
+        legacy.normalize_whitespace();
+        assert_eq!(legacy.business_calls, expected.business_calls);
+        assert_eq!(legacy.call_fingerprint, expected.call_fingerprint);
+    }
+    #[test]
+    fn test_return_type_similarity() {
+        assert_eq!(return_type_similarity(Some("str"), None), None);
+        assert_eq!(
+            return_type_similarity(Some("Dict[str, int]"), Some("dict[str,int]")),
+            Some(1.0)
+        );
+        let related = return_type_similarity(Some("list[User]"), Some("list[Order]")).unwrap();
+        let unrelated = return_type_similarity(Some("list[User]"), Some("str")).unwrap();
+        assert!(related > unrelated, "{} vs {}", related, unrelated);
+    }
+
+    #[test]
+    fn test_different_return_types_reduce_similarity() {
+        let info = formatted_profile_loader("load_user_profile", "svc.user_service", "svc.cache");
+        let typed = |return_type: Option<&str>, hash: &str| {
+            let info = SymbolInfo {
+                return_type: return_type.map(str::to_string),
+                ..info.clone()
+            };
+            FunctionSignature::from_symbol_info(&info, hash, &format!("src/{}.py", hash), "m", None)
+        };
+        let detector = DuplicateDetector::default();
+
+        // Unannotated functions score as before
+        let plain = detector.compute_similarity(&typed(None, "a"), &typed(None, "b"));
+        assert_eq!(plain, 1.0);
+        let one_sided = detector.compute_similarity(&typed(Some("User"), "a"), &typed(None, "b"));
+        assert_eq!(one_sided, 1.0);
+
+        let same =
+            detector.compute_similarity(&typed(Some("User"), "a"), &typed(Some("User"), "b"));
+        assert_eq!(same, 1.0);
+        let different =
+            detector.compute_similarity(&typed(Some("User"), "a"), &typed(Some("None"), "b"));
+        assert!((different - 0.90).abs() < 1e-9, "{}", different);
+        assert_eq!(
+            DuplicateKind::from_similarity(different),
+            DuplicateKind::Near
+        );
+    }
+
+    #[test]
+    fn test_lifetime_mismatch_reduces_similarity() {
+        let info = formatted_profile_loader("load_user_profile", "svc.user_service", "svc.cache");
+        let with_lifetimes = |lifetimes: &[&str], hash: &str| {
+            let info = SymbolInfo {
+                lifetime_params: lifetimes.iter().map(|l| l.to_string()).collect(),
+                ..info.clone()
+            };
+            FunctionSignature::from_symbol_info(&info, hash, &format!("src/{}.rs", hash), "m", None)
+        };
+        let detector = DuplicateDetector::default();
+
+        let both = detector.compute_similarity(
+            &with_lifetimes(&["'a"], "a"),
+            &with_lifetimes(&["'a", "'b"], "b"),
+        );
+        assert_eq!(both, 1.0);
+        let neither =
+            detector.compute_similarity(&with_lifetimes(&[], "a"), &with_lifetimes(&[], "b"));
+        assert_eq!(neither, 1.0);
+
+        let one_sided =
+            detector.compute_similarity(&with_lifetimes(&["'a"], "a"), &with_lifetimes(&[], "b"));
+        assert!((one_sided - 0.95).abs() < 1e-9, "{}", one_sided);
+        assert_eq!(
+            DuplicateKind::from_similarity(one_sided),
+            DuplicateKind::Near
+        );
+    }
 }
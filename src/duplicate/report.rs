@@ -0,0 +1,272 @@
+//! Consolidation report for duplicate clusters
+//!
+//! Ranks duplicate clusters by how much code could be removed by
+//! consolidating them into a single implementation. Each cluster is scored
+//! from its duplicated line count, the number of modules it spans and its
+//! highest similarity, and gets a one-line suggested action.
+//!
+//! Boilerplate-classified members never contribute to the score, even when
+//! boilerplate was included in detection.
+
+use super::{DuplicateCluster, SymbolRef};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+/// Extra weight per additional module a cluster spans
+const MODULE_SPREAD_WEIGHT: f64 = 0.25;
+
+/// A duplicate cluster scored as a consolidation opportunity
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsolidationOpportunity {
+    /// The implementation to keep
+    pub primary: SymbolRef,
+    /// Module the primary lives in
+    pub primary_module: String,
+    /// Number of non-boilerplate copies, including the primary
+    pub copies: usize,
+    /// Lines that would be removed by keeping only the primary
+    pub duplicated_lines: usize,
+    /// Distinct modules containing a copy
+    pub modules: Vec<String>,
+    /// Lowest similarity to the primary among scored copies
+    pub min_similarity: f64,
+    /// Highest similarity to the primary among scored copies
+    pub max_similarity: f64,
+    /// Consolidation savings score (higher = more worth consolidating)
+    pub score: f64,
+}
+
+impl ConsolidationOpportunity {
+    /// Score a cluster, skipping members whose hash is in `boilerplate`
+    ///
+    /// Returns `None` when fewer than two non-boilerplate copies remain.
+    pub fn from_cluster<F>(
+        cluster: &DuplicateCluster,
+        boilerplate: &HashSet<String>,
+        module_of: F,
+    ) -> Option<Self>
+    where
+        F: Fn(&SymbolRef) -> String,
+    {
+        let duplicates: Vec<_> = cluster
+            .duplicates
+            .iter()
+            .filter(|d| !boilerplate.contains(&d.symbol.hash))
+            .collect();
+        let primary_scored = !boilerplate.contains(&cluster.primary.hash);
+
+        let mut members: Vec<&SymbolRef> = duplicates.iter().map(|d| &d.symbol).collect();
+        if primary_scored {
+            members.push(&cluster.primary);
+        }
+        if members.len() < 2 {
+            return None;
+        }
+
+        // Keep the primary when it counts, otherwise the longest scored copy
+        let kept_lines = if primary_scored {
+            line_count(&cluster.primary)
+        } else {
+            members.iter().map(|s| line_count(s)).max().unwrap_or(0)
+        };
+        let total_lines: usize = members.iter().map(|s| line_count(s)).sum();
+        let duplicated_lines = total_lines.saturating_sub(kept_lines);
+
+        let modules: Vec<String> = members
+            .iter()
+            .map(|s| module_of(s))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let max_similarity = duplicates
+            .iter()
+            .map(|d| d.similarity)
+            .fold(0.0_f64, f64::max);
+        let min_similarity = duplicates
+            .iter()
+            .map(|d| d.similarity)
+            .fold(f64::INFINITY, f64::min)
+            .min(max_similarity);
+
+        let score = savings_score(duplicated_lines, modules.len(), max_similarity);
+
+        Some(Self {
+            primary_module: module_of(&cluster.primary),
+            primary: cluster.primary.clone(),
+            copies: members.len(),
+            duplicated_lines,
+            modules,
+            min_similarity,
+            max_similarity,
+            score,
+        })
+    }
+
+    /// One-line suggested action for this opportunity
+    pub fn suggested_action(&self) -> String {
+        let location = if self.modules.len() <= 1 {
+            format!("in module {}", self.primary_module)
+        } else {
+            format!("shared by {} modules", self.modules.len())
+        };
+        format!(
+            "extract shared helper {} — {} copies, {} duplicated lines, {} similar",
+            location,
+            self.copies,
+            self.duplicated_lines,
+            self.similarity_range()
+        )
+    }
+
+    /// Similarity range as a percentage, e.g. "96–99%"
+    pub fn similarity_range(&self) -> String {
+        let min = (self.min_similarity * 100.0).round();
+        let max = (self.max_similarity * 100.0).round();
+        if min == max {
+            format!("{:.0}%", max)
+        } else {
+            format!("{:.0}–{:.0}%", min, max)
+        }
+    }
+}
+
+/// Score and rank clusters by consolidation savings, highest first
+///
+/// Clusters left with fewer than two non-boilerplate copies are dropped.
+pub fn rank_consolidation_opportunities<F>(
+    clusters: &[DuplicateCluster],
+    boilerplate: &HashSet<String>,
+    module_of: F,
+) -> Vec<ConsolidationOpportunity>
+where
+    F: Fn(&SymbolRef) -> String,
+{
+    let mut opportunities: Vec<_> = clusters
+        .iter()
+        .filter_map(|c| ConsolidationOpportunity::from_cluster(c, boilerplate, &module_of))
+        .collect();
+    opportunities.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.duplicated_lines.cmp(&a.duplicated_lines))
+            .then_with(|| a.primary.name.cmp(&b.primary.name))
+    });
+    opportunities
+}
+
+/// Savings score: duplicated lines weighted by similarity and module spread
+///
+/// Cross-module duplication scores higher because a shared helper removes
+/// copies that would otherwise drift independently.
+pub fn savings_score(duplicated_lines: usize, module_count: usize, max_similarity: f64) -> f64 {
+    let spread = 1.0 + MODULE_SPREAD_WEIGHT * module_count.saturating_sub(1) as f64;
+    duplicated_lines as f64 * max_similarity * spread
+}
+
+fn line_count(symbol: &SymbolRef) -> usize {
+    symbol.end_line.saturating_sub(symbol.start_line) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duplicate::DuplicateMatch;
+
+    fn sym(name: &str, module: &str, lines: usize) -> SymbolRef {
+        SymbolRef {
+            hash: format!("{}:{}", module, name),
+            name: name.to_string(),
+            file: format!("src/{}/{}.ts", module, name),
+            module: module.to_string(),
+            start_line: 10,
+            end_line: 10 + lines - 1,
+        }
+    }
+
+    fn cluster(primary: SymbolRef, dups: Vec<(SymbolRef, f64)>) -> DuplicateCluster {
+        let mut cluster = DuplicateCluster::new(primary);
+        for (symbol, similarity) in dups {
+            cluster.add_duplicate(DuplicateMatch::new(symbol, similarity, Vec::new()));
+        }
+        cluster
+    }
+
+    fn module_of(symbol: &SymbolRef) -> String {
+        symbol.module.clone()
+    }
+
+    #[test]
+    fn test_opportunity_metrics() {
+        let c = cluster(
+            sym("parse", "api", 20),
+            vec![
+                (sym("parseA", "api", 18), 0.96),
+                (sym("parseB", "web", 22), 0.99),
+            ],
+        );
+        let opp = ConsolidationOpportunity::from_cluster(&c, &HashSet::new(), module_of).unwrap();
+        assert_eq!(opp.copies, 3);
+        assert_eq!(opp.duplicated_lines, 40);
+        assert_eq!(opp.modules, vec!["api", "web"]);
+        assert!((opp.max_similarity - 0.99).abs() < 1e-9);
+        assert_eq!(opp.similarity_range(), "96–99%");
+        assert_eq!(
+            opp.suggested_action(),
+            "extract shared helper shared by 2 modules — 3 copies, 40 duplicated lines, 96–99% similar"
+        );
+    }
+
+    #[test]
+    fn test_ranking_order() {
+        // Small exact copy in one module
+        let small = cluster(sym("fmt", "util", 5), vec![(sym("fmt2", "util", 5), 1.0)]);
+        // Large near copies in one module
+        let large = cluster(
+            sym("render", "ui", 40),
+            vec![(sym("render2", "ui", 40), 0.92)],
+        );
+        // Same size as `large`, but spread over three modules
+        let spread = cluster(
+            sym("load", "a", 20),
+            vec![(sym("load", "b", 20), 0.92), (sym("load", "c", 20), 0.92)],
+        );
+
+        let ranked =
+            rank_consolidation_opportunities(&[small, large, spread], &HashSet::new(), module_of);
+        let names: Vec<_> = ranked.iter().map(|o| o.primary.name.as_str()).collect();
+        assert_eq!(names, vec!["load", "render", "fmt"]);
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[test]
+    fn test_boilerplate_members_ignored() {
+        let c = cluster(
+            sym("handler", "api", 30),
+            vec![
+                (sym("getter", "api", 100), 0.99),
+                (sym("handler2", "api", 30), 0.95),
+            ],
+        );
+        let boilerplate: HashSet<String> = ["api:getter".to_string()].into_iter().collect();
+
+        let opp = ConsolidationOpportunity::from_cluster(&c, &boilerplate, module_of).unwrap();
+        assert_eq!(opp.copies, 2);
+        assert_eq!(opp.duplicated_lines, 30);
+        assert!((opp.max_similarity - 0.95).abs() < 1e-9);
+        assert_eq!(
+            opp.suggested_action(),
+            "extract shared helper in module api — 2 copies, 30 duplicated lines, 95% similar"
+        );
+    }
+
+    #[test]
+    fn test_boilerplate_only_cluster_dropped() {
+        let c = cluster(sym("get", "api", 5), vec![(sym("set", "api", 5), 0.99)]);
+        let boilerplate: HashSet<String> = ["api:set".to_string()].into_iter().collect();
+
+        assert!(ConsolidationOpportunity::from_cluster(&c, &boilerplate, module_of).is_none());
+        assert!(rank_consolidation_opportunities(&[c], &boilerplate, module_of).is_empty());
+    }
+}
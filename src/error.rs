@@ -28,6 +28,14 @@ pub enum McpDiffError {
     #[error("Not a git repository")]
     NotGitRepo,
 
+    #[error(
+        "{message}: this is a shallow clone. Fetch the missing history with `{fetch_command}`"
+    )]
+    ShallowClone {
+        message: String,
+        fetch_command: String,
+    },
+
     #[error("Export error: {message}")]
     ExportError { message: String },
 
@@ -49,8 +57,55 @@ pub enum McpDiffError {
     #[error("Installation error: {message}")]
     InstallError { message: String },
 
+    #[error("Corrupt cache artifact {}: {message}", path.display())]
+    CacheCorrupt { path: PathBuf, message: String },
+
+    #[error(
+        "Incompatible cache artifact {}: schema {found}, expected {expected}",
+        path.display()
+    )]
+    CacheIncompatible {
+        path: PathBuf,
+        found: String,
+        expected: String,
+    },
+
+    /// An operation ran past its time budget
+    #[error("{operation} timed out after {elapsed_ms}ms")]
+    Timeout { operation: String, elapsed_ms: u64 },
+
+    /// Another process kept the index lock past the wait timeout
+    #[error(
+        "Index generation already in progress for {} (waited {waited_ms}ms); \
+         try again once it finishes",
+        repo.display()
+    )]
+    IndexLocked { repo: PathBuf, waited_ms: u64 },
+
+    /// `validate --fail-on` rules tripped; `report` is the command output
+    #[error("{count} --fail-on rule violation(s)")]
+    ValidationFailed { count: usize, report: String },
+
+    /// `benchmark --compare` found metrics worse than the baseline by more
+    /// than `--max-regression`; `report` is the command output
+    #[error("{count} benchmark metric(s) regressed more than {max_regression}% from the baseline")]
+    BenchmarkRegressed {
+        count: usize,
+        max_regression: f64,
+        report: String,
+    },
+
     #[error("{0}")]
     Generic(String),
+
+    /// Context added by [`McpDiffError::with_context`] to an error without a
+    /// message of its own
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<McpDiffError>,
+    },
 }
 
 impl From<Box<bincode::ErrorKind>> for McpDiffError {
@@ -63,11 +118,18 @@ impl McpDiffError {
     /// Convert error to appropriate exit code per spec:
     /// - 0: Success
     /// - 1: File not found / IO error
-    /// - 2: Unsupported language
+    /// - 2: Unsupported language / `validate --fail-on` rule violated /
+    ///   `benchmark --compare` regression
     /// - 3: Parse failure
     /// - 4: Internal semantic extraction failure
     /// - 5: Git error
     /// - 6: Export error
+    /// - 12: Corrupt or schema-incompatible cache
+    /// - 13: Operation timed out
+    /// - 14: Another process is writing the index
+    ///
+    /// Errors wrapped by [`McpDiffError::with_context`] keep the code of the
+    /// underlying error.
     pub fn exit_code(&self) -> ExitCode {
         match self {
             Self::FileNotFound { .. } => ExitCode::from(1),
@@ -77,6 +139,7 @@ impl McpDiffError {
             Self::QueryError { .. } => ExitCode::from(4),
             Self::GitError { .. } => ExitCode::from(5),
             Self::NotGitRepo => ExitCode::from(5),
+            Self::ShallowClone { .. } => ExitCode::from(5),
             Self::ExportError { .. } => ExitCode::from(6),
             Self::Io(_) => ExitCode::from(1),
             Self::IoError { .. } => ExitCode::from(1),
@@ -84,10 +147,154 @@ impl McpDiffError {
             Self::Serialization(_) => ExitCode::from(8),
             Self::ConfigError { .. } => ExitCode::from(10),
             Self::InstallError { .. } => ExitCode::from(11),
+            Self::CacheCorrupt { .. } => ExitCode::from(12),
+            Self::CacheIncompatible { .. } => ExitCode::from(12),
+            Self::Timeout { .. } => ExitCode::from(13),
+            Self::IndexLocked { .. } => ExitCode::from(14),
+            Self::ValidationFailed { .. } => ExitCode::from(2),
+            Self::BenchmarkRegressed { .. } => ExitCode::from(2),
             Self::Generic(_) => ExitCode::from(9),
+            Self::Context { source, .. } => source.exit_code(),
+        }
+    }
+
+    /// Prepend `ctx` to the error's message (`"<ctx>: <message>"`)
+    ///
+    /// Variants without a message field are wrapped in
+    /// [`McpDiffError::Context`], which keeps them reachable through
+    /// [`std::error::Error::source`].
+    pub fn with_context(self, ctx: impl Into<String>) -> Self {
+        let ctx = ctx.into();
+        let prefix = |message: String| format!("{}: {}", ctx, message);
+        match self {
+            Self::ParseFailure { message } => Self::ParseFailure {
+                message: prefix(message),
+            },
+            Self::ExtractionFailure { message } => Self::ExtractionFailure {
+                message: prefix(message),
+            },
+            Self::QueryError { message } => Self::QueryError {
+                message: prefix(message),
+            },
+            Self::GitError { message } => Self::GitError {
+                message: prefix(message),
+            },
+            Self::ShallowClone {
+                message,
+                fetch_command,
+            } => Self::ShallowClone {
+                message: prefix(message),
+                fetch_command,
+            },
+            Self::ExportError { message } => Self::ExportError {
+                message: prefix(message),
+            },
+            Self::IoError { path, message } => Self::IoError {
+                path,
+                message: prefix(message),
+            },
+            Self::Serialization(message) => Self::Serialization(prefix(message)),
+            Self::ConfigError { message } => Self::ConfigError {
+                message: prefix(message),
+            },
+            Self::InstallError { message } => Self::InstallError {
+                message: prefix(message),
+            },
+            Self::CacheCorrupt { path, message } => Self::CacheCorrupt {
+                path,
+                message: prefix(message),
+            },
+            Self::Generic(message) => Self::Generic(prefix(message)),
+            other => Self::Context {
+                context: ctx,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Whether this error means a cache artifact must be discarded and rebuilt
+    pub fn is_cache_damage(&self) -> bool {
+        match self {
+            Self::CacheCorrupt { .. } | Self::CacheIncompatible { .. } => true,
+            Self::Context { source, .. } => source.is_cache_damage(),
+            _ => false,
         }
     }
 }
 
 /// Result type alias for semfora-engine operations
 pub type Result<T> = std::result::Result<T, McpDiffError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_with_context_prepends_message() {
+        let err = McpDiffError::IoError {
+            path: PathBuf::from("/tmp/index"),
+            message: "permission denied".to_string(),
+        }
+        .with_context("Failed to write index");
+
+        assert!(matches!(
+            &err,
+            McpDiffError::IoError { message, .. }
+                if message == "Failed to write index: permission denied"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "IO error at /tmp/index: Failed to write index: permission denied"
+        );
+        assert_eq!(err.exit_code(), ExitCode::from(1));
+    }
+
+    #[test]
+    fn test_with_context_chains_sources() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let err = McpDiffError::from(io)
+            .with_context("Failed to read overview")
+            .with_context("Failed to access cache");
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to access cache: Failed to read overview: IO error: missing"
+        );
+        assert_eq!(err.exit_code(), ExitCode::from(1));
+
+        let chain: Vec<String> = std::iter::successors(err.source(), |&e| e.source())
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            chain,
+            [
+                "Failed to read overview: IO error: missing",
+                "IO error: missing",
+                "missing"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_context_keeps_cache_damage() {
+        let err = McpDiffError::CacheIncompatible {
+            path: PathBuf::from("meta.json"),
+            found: "1".to_string(),
+            expected: "2".to_string(),
+        }
+        .with_context("Failed to load index");
+        assert!(err.is_cache_damage());
+        assert_eq!(err.exit_code(), ExitCode::from(12));
+    }
+
+    #[test]
+    fn test_timeout_display() {
+        let err = McpDiffError::Timeout {
+            operation: "Index generation".to_string(),
+            elapsed_ms: 1500,
+        };
+        assert_eq!(err.to_string(), "Index generation timed out after 1500ms");
+        assert_eq!(err.exit_code(), ExitCode::from(13));
+    }
+}
@@ -19,46 +19,83 @@ use crate::lang::Lang;
 use crate::risk::calculate_risk;
 use crate::schema::{SemanticSummary, SymbolId};
 
+/// How much semantic detail to extract from each file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExtractionLevel {
+    /// Symbols, imports, state changes, control flow and calls
+    #[default]
+    Full,
+    /// Symbol names, kinds and signatures only - much faster, for first-pass indexing
+    Signatures,
+}
+
+impl ExtractionLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtractionLevel::Full => "full",
+            ExtractionLevel::Signatures => "signatures",
+        }
+    }
+}
+
 /// Extract semantic information from a parsed source file
 ///
 /// This is the main entry point for semantic extraction. It delegates to
 /// language-specific extractors based on the detected language.
 pub fn extract(file_path: &Path, source: &str, tree: &Tree, lang: Lang) -> Result<SemanticSummary> {
+    extract_with_level(file_path, source, tree, lang, ExtractionLevel::Full)
+}
+
+/// Extract semantic information at the given level of detail
+///
+/// With `ExtractionLevel::Signatures` the detectors skip control flow, call
+/// and state change extraction, producing lightweight summaries.
+pub fn extract_with_level(
+    file_path: &Path,
+    source: &str,
+    tree: &Tree,
+    lang: Lang,
+    level: ExtractionLevel,
+) -> Result<SemanticSummary> {
     let mut summary = SemanticSummary {
         file: file_path.display().to_string(),
         language: lang.name().to_string(),
+        is_generated: crate::generated::is_generated(file_path, source),
         ..Default::default()
     };
 
     // Dispatch to language family extractor
     // Vue SFCs need special handling - extract script section first
     if lang.is_vue_sfc() {
-        crate::detectors::javascript::extract_vue_sfc(&mut summary, source)?;
+        crate::detectors::javascript::extract_vue_sfc(&mut summary, source, level)?;
     } else {
         match lang.family() {
             crate::lang::LangFamily::JavaScript => {
-                crate::detectors::javascript::extract(&mut summary, source, tree, lang)?;
+                crate::detectors::javascript::extract(&mut summary, source, tree, lang, level)?;
             }
             crate::lang::LangFamily::Rust => {
-                crate::detectors::rust::extract(&mut summary, source, tree)?;
+                crate::detectors::rust::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Python => {
-                crate::detectors::python::extract(&mut summary, source, tree)?;
+                crate::detectors::python::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Go => {
-                crate::detectors::go::extract(&mut summary, source, tree)?;
+                crate::detectors::go::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Java => {
-                crate::detectors::java::extract(&mut summary, source, tree)?;
+                crate::detectors::java::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::CSharp => {
-                crate::detectors::csharp::extract(&mut summary, source, tree)?;
+                crate::detectors::csharp::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Kotlin => {
-                crate::detectors::kotlin::extract(&mut summary, source, tree)?;
+                crate::detectors::kotlin::extract(&mut summary, source, tree, level)?;
+            }
+            crate::lang::LangFamily::Php => {
+                crate::detectors::php::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::CFamily => {
-                crate::detectors::c_family::extract(&mut summary, source, tree)?;
+                crate::detectors::c_family::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Markup => {
                 crate::detectors::markup::extract(&mut summary, source, tree, lang)?;
@@ -67,10 +104,10 @@ pub fn extract(file_path: &Path, source: &str, tree: &Tree, lang: Lang) -> Resul
                 crate::detectors::config::extract(&mut summary, source, tree, lang)?;
             }
             crate::lang::LangFamily::Shell => {
-                crate::detectors::shell::extract(&mut summary, source, tree)?;
+                crate::detectors::shell::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Gradle => {
-                crate::detectors::gradle::extract(&mut summary, source, tree)?;
+                crate::detectors::gradle::extract(&mut summary, source, tree, level)?;
             }
             crate::lang::LangFamily::Hcl => {
                 crate::detectors::hcl::extract(&mut summary, source, tree)?;
@@ -78,9 +115,35 @@ pub fn extract(file_path: &Path, source: &str, tree: &Tree, lang: Lang) -> Resul
             crate::lang::LangFamily::Dockerfile => {
                 crate::detectors::dockerfile::extract(&mut summary, source, tree)?;
             }
+            crate::lang::LangFamily::Protobuf => {
+                crate::detectors::protobuf::extract(&mut summary, source, tree)?;
+            }
+            // Recognized so files are listed and searchable; no parser yet
+            crate::lang::LangFamily::GraphQL => {}
         }
     }
 
+    // Parent links and stable IDs for closures, callbacks and nested functions
+    crate::detectors::nested::link_nested_symbols(&mut summary);
+
+    // Feature flags gating each symbol and deprecation markers on it
+    // (both need symbol line ranges)
+    if !lang.is_vue_sfc() {
+        crate::detectors::feature_flags::extract(&mut summary, &tree.root_node(), source, lang);
+        crate::detectors::deprecation::extract(&mut summary, &tree.root_node(), source, lang);
+    }
+
+    // Async and error-handling facets (needs symbol calls and line ranges)
+    crate::detectors::facets::extract(&mut summary, &tree.root_node(), source, lang);
+
+    // SQL in string literals (needs symbol line ranges)
+    crate::detectors::embedded_sql::extract(&mut summary, &tree.root_node(), source, lang);
+
+    // Detectors without a lightweight path still extract everything
+    if level == ExtractionLevel::Signatures {
+        strip_to_signatures(&mut summary);
+    }
+
     // Reorder insertions: put state hooks last per spec
     reorder_insertions(&mut summary.insertions);
 
@@ -96,8 +159,8 @@ pub fn extract(file_path: &Path, source: &str, tree: &Tree, lang: Lang) -> Resul
     // Generate stable symbol ID for cross-commit tracking
     summary.symbol_id = SymbolId::from_summary(&summary);
 
-    // Add raw fallback if extraction was incomplete
-    if !summary.extraction_complete {
+    // Add raw fallback if extraction was incomplete (not for lightweight summaries)
+    if !summary.extraction_complete && level == ExtractionLevel::Full {
         // Adaptive fallback sizing: 10% of source, clamped between MIN and MAX
         let adaptive_len = (source.len() / 10).clamp(MIN_FALLBACK_LEN, MAX_FALLBACK_LEN);
 
@@ -133,6 +196,18 @@ pub fn extract(file_path: &Path, source: &str, tree: &Tree, lang: Lang) -> Resul
 // Utility functions
 // ============================================================================
 
+/// Drop everything but symbols, signatures and imports from a summary
+fn strip_to_signatures(summary: &mut SemanticSummary) {
+    summary.calls.clear();
+    summary.control_flow_changes.clear();
+    summary.state_changes.clear();
+    for symbol in &mut summary.symbols {
+        symbol.calls.clear();
+        symbol.control_flow.clear();
+        symbol.state_changes.clear();
+    }
+}
+
 /// Reorder insertions to put state hooks last (per plan.md spec)
 fn reorder_insertions(insertions: &mut Vec<String>) {
     // Separate state hook insertions from others
@@ -147,6 +222,7 @@ fn reorder_insertions(insertions: &mut Vec<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::SymbolKind;
     use std::path::PathBuf;
 
     fn parse_source(source: &str, lang: Lang) -> Tree {
@@ -270,6 +346,97 @@ def process_files(paths: List[str]) -> None:
         assert!(!summary.added_dependencies.is_empty());
     }
 
+    #[test]
+    fn test_extract_signatures_level_skips_calls_and_control_flow() {
+        let source = r#"
+use std::collections::HashMap;
+
+pub struct Cache {
+    entries: HashMap<String, String>,
+}
+
+pub fn lookup(cache: &Cache, key: &str) -> Option<String> {
+    if key.is_empty() {
+        return None;
+    }
+    cache.entries.get(key).cloned()
+}
+"#;
+
+        let tree = parse_source(source, Lang::Rust);
+        let path = PathBuf::from("cache.rs");
+
+        let summary = extract_with_level(
+            &path,
+            source,
+            &tree,
+            Lang::Rust,
+            ExtractionLevel::Signatures,
+        )
+        .unwrap();
+        let names: Vec<(&str, SymbolKind)> = summary
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind))
+            .collect();
+        assert!(names.contains(&("Cache", SymbolKind::Class)));
+        assert!(names.contains(&("lookup", SymbolKind::Function)));
+        assert!(!summary.added_dependencies.is_empty());
+        assert!(summary.calls.is_empty());
+        assert!(summary.control_flow_changes.is_empty());
+        assert!(summary.state_changes.is_empty());
+        assert!(summary
+            .symbols
+            .iter()
+            .all(|s| s.calls.is_empty() && s.control_flow.is_empty()));
+
+        // Full extraction of the same file still records calls and control flow
+        let full = extract(&path, source, &tree, Lang::Rust).unwrap();
+        assert_eq!(full.symbols.len(), summary.symbols.len());
+        let lookup = full.symbols.iter().find(|s| s.name == "lookup").unwrap();
+        assert!(!lookup.calls.is_empty());
+        assert!(!full.control_flow_changes.is_empty());
+    }
+
+    #[test]
+    fn test_extract_signatures_level_typescript() {
+        let source = r#"
+import { api } from './api';
+
+export async function fetchUser(id: string): Promise<User> {
+    try {
+        return await api.get(id);
+    } catch (e) {
+        return null;
+    }
+}
+"#;
+
+        let tree = parse_source(source, Lang::TypeScript);
+        let path = PathBuf::from("user.ts");
+
+        let summary = extract_with_level(
+            &path,
+            source,
+            &tree,
+            Lang::TypeScript,
+            ExtractionLevel::Signatures,
+        )
+        .unwrap();
+        assert_eq!(summary.symbol, Some("fetchUser".to_string()));
+        assert_eq!(summary.symbol_kind, Some(SymbolKind::Function));
+        assert!(summary
+            .symbols
+            .iter()
+            .all(|s| s.calls.is_empty() && s.control_flow.is_empty()));
+        assert!(summary.control_flow_changes.is_empty());
+        assert!(summary.raw_fallback.is_none());
+
+        let full = extract(&path, source, &tree, Lang::TypeScript).unwrap();
+        assert!(full.symbols.iter().any(|s| !s.calls.is_empty()));
+        assert!(full.symbols.iter().any(|s| !s.control_flow.is_empty()));
+    }
+
     #[test]
     fn test_truncate_to_char_boundary() {
         // ASCII - should work normally
@@ -0,0 +1,202 @@
+//! Generated-code detection
+//!
+//! Generated files (protobuf output, GraphQL codegen, OpenAPI clients) swamp
+//! duplicate clusters, complexity stats and search results, so files are
+//! flagged `is_generated` at index time and left out of quality metrics.
+//!
+//! A file is generated when, in order of precedence:
+//!
+//! 1. it matches a `generated.not_generated` glob: never generated
+//! 2. it matches a `generated.patterns` glob or a built-in file name pattern
+//!    ([`FILE_PATTERNS`], or a [`GENERATED_DIRS`] directory)
+//! 3. a comment in its first [`HEADER_LINES`] lines carries a header marker
+//!    ([`HEADER_MARKERS`] or a `generated.markers` entry)
+//!
+//! Extraction applies the built-in rules ([`is_generated`]); the index writer
+//! then applies the repository config ([`apply_settings`]).
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::duplicate::boilerplate::matches_glob;
+use crate::schema::SemanticSummary;
+use crate::settings::RepoSettings;
+
+/// Header comments that mark a file as generated
+pub const HEADER_MARKERS: &[&str] = &[
+    "Code generated by",
+    "@generated",
+    "DO NOT EDIT",
+    "<auto-generated",
+    "This file was automatically generated",
+    "This file is automatically generated",
+    "Autogenerated by",
+];
+
+/// File names of well-known code generator output
+pub const FILE_PATTERNS: &[&str] = &[
+    "*.pb.go",
+    "*.pb.cc",
+    "*.pb.h",
+    "*_pb2.py",
+    "*_pb2.pyi",
+    "*_pb2_grpc.py",
+    "*_pb.js",
+    "*_pb.d.ts",
+    "*_grpc_pb.js",
+    "*.g.cs",
+    "*.g.i.cs",
+    "*.designer.cs",
+    "*.Designer.cs",
+    "*.g.dart",
+    "*.freezed.dart",
+    "*.generated.*",
+];
+
+/// Directories whose files are all generated
+pub const GENERATED_DIRS: &[&str] = &["__generated__"];
+
+/// Leading lines searched for header markers
+pub const HEADER_LINES: usize = 20;
+
+/// Whether the built-in rules flag `path` with contents `source` as generated
+pub fn is_generated(path: &Path, source: &str) -> bool {
+    has_generated_name(path) || has_generated_header(source.lines(), HEADER_MARKERS)
+}
+
+/// Whether `path` is named like generator output or lies in a generated directory
+pub fn has_generated_name(path: &Path) -> bool {
+    let in_generated_dir = path.components().any(|c| {
+        GENERATED_DIRS
+            .iter()
+            .any(|dir| c.as_os_str() == std::ffi::OsStr::new(dir))
+    });
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    in_generated_dir || FILE_PATTERNS.iter().any(|p| matches_glob(p, &name))
+}
+
+/// Whether a comment among the first [`HEADER_LINES`] `lines` contains one
+/// of `markers`
+///
+/// Only comment lines count, so code or strings mentioning a marker don't.
+pub fn has_generated_header<S: AsRef<str>>(
+    lines: impl Iterator<Item = S>,
+    markers: &[impl AsRef<str>],
+) -> bool {
+    lines.take(HEADER_LINES).any(|line| {
+        let line = line.as_ref().trim_start();
+        is_comment_line(line) && markers.iter().any(|m| line.contains(m.as_ref()))
+    })
+}
+
+/// Whether a trimmed line starts a comment in one of the indexed languages
+fn is_comment_line(line: &str) -> bool {
+    ["//", "#", "/*", "*", "--", "<!--", ";", "\"\"\"", "'''"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// Apply the repository's `[generated]` config to a summary the built-in
+/// rules have already classified
+pub fn apply_settings(summary: &mut SemanticSummary, settings: &RepoSettings) {
+    let file = Path::new(&summary.file);
+    if settings.is_not_generated(file) {
+        summary.is_generated = false;
+    } else if !summary.is_generated {
+        summary.is_generated = settings.is_generated_path(file)
+            || (!settings.generated_markers.is_empty()
+                && header_has_marker(&settings.root.join(file), &settings.generated_markers));
+    }
+}
+
+/// Whether the file at `path` has one of `markers` in its header
+fn header_has_marker(path: &Path, markers: &[String]) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    let lines = BufReader::new(file).lines().map_while(|line| line.ok());
+    has_generated_header(lines, markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const PROTOBUF_PY: &str = r#"# -*- coding: utf-8 -*-
+# Generated by the protocol buffer compiler.  DO NOT EDIT!
+# source: users.proto
+"""Generated protocol buffer code."""
+from google.protobuf import descriptor as _descriptor
+"#;
+
+    const HAND_WRITTEN: &str = r#"// Helpers around the generated protobuf client.
+// Results are generated lazily and cached.
+export function userName(user) {
+  return user.name;
+}
+"#;
+
+    #[test]
+    fn test_protobuf_output_is_generated() {
+        assert!(is_generated(Path::new("api/users.py"), PROTOBUF_PY));
+        assert!(is_generated(Path::new("api/users_pb2.py"), "import os\n"));
+        assert!(is_generated(
+            Path::new("proto/users.pb.go"),
+            "package proto\n"
+        ));
+        assert!(is_generated(
+            Path::new("src/__generated__/schema.ts"),
+            "export type Q = {};\n"
+        ));
+    }
+
+    #[test]
+    fn test_hand_written_file_mentioning_generated_is_not() {
+        assert!(!is_generated(Path::new("src/users.js"), HAND_WRITTEN));
+        // A marker outside a comment doesn't count
+        assert!(!is_generated(
+            Path::new("src/banner.js"),
+            "const banner = \"DO NOT EDIT\";\n"
+        ));
+        // Nor does one past the header
+        let late = format!("{}// @generated\n", "let x = 1;\n".repeat(HEADER_LINES));
+        assert!(!is_generated(Path::new("src/late.js"), &late));
+    }
+
+    #[test]
+    fn test_repo_config_extends_and_overrides_detection() {
+        let repo = tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".semfora")).unwrap();
+        fs::write(
+            repo.path().join(".semfora/config.toml"),
+            "[generated]\npatterns = [\"src/client\"]\nmarkers = [\"openapi-generator\"]\nnot_generated = [\"legacy_pb2.py\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            repo.path().join("api.ts"),
+            "// Produced by openapi-generator 7.0\nexport class Api {}\n",
+        )
+        .unwrap();
+        let settings =
+            RepoSettings::load_with_global(repo.path(), &repo.path().join("none.toml")).unwrap();
+
+        let classify = |file: &str, builtin: bool| {
+            let mut summary = SemanticSummary {
+                file: file.to_string(),
+                is_generated: builtin,
+                ..Default::default()
+            };
+            apply_settings(&mut summary, &settings);
+            summary.is_generated
+        };
+        assert!(classify("src/client/users.ts", false));
+        assert!(classify("api.ts", false));
+        assert!(!classify("src/legacy_pb2.py", true));
+        assert!(!classify("src/users.ts", false));
+    }
+}
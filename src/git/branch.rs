@@ -4,6 +4,11 @@
 
 use std::path::Path;
 
+use serde::{Deserialize, Serialize};
+
+use super::shallow::{
+    deepen_command, fetch_ref_command, is_shallow_repo, missing_ref_error, ref_exists,
+};
 use super::{git_command, git_command_optional};
 use crate::error::Result;
 
@@ -19,35 +24,73 @@ pub fn get_current_branch(cwd: Option<&Path>) -> Result<String> {
     git_command(&["rev-parse", "--abbrev-ref", "HEAD"], cwd)
 }
 
+/// CI environment variables naming the target branch of a pull/merge request
+const CI_BASE_BRANCH_VARS: &[&str] = &[
+    "GITHUB_BASE_REF",
+    "CI_MERGE_REQUEST_TARGET_BRANCH_NAME",
+    "BITBUCKET_PR_DESTINATION_BRANCH",
+    "SYSTEM_PULLREQUEST_TARGETBRANCH",
+];
+
 /// Detect the base branch (main or master)
 ///
 /// Priority:
-/// 1. Check if 'main' branch exists
-/// 2. Check if 'master' branch exists
-/// 3. Check for origin/main or origin/master
-/// 4. Return error if neither found
+/// 1. The PR/MR target branch from CI environment variables
+///    (GITHUB_BASE_REF, CI_MERGE_REQUEST_TARGET_BRANCH_NAME, ...)
+/// 2. The default branch from origin/HEAD
+/// 3. Check if 'main' branch exists
+/// 4. Check if 'master' branch exists
+/// 5. Check for origin/main or origin/master
+/// 6. Return error if neither found
+///
+/// Local branches are preferred over their remote-tracking counterparts. In
+/// a shallow clone where the CI target branch wasn't fetched, this returns a
+/// `ShallowClone` error with the fetch command instead of guessing.
 pub fn detect_base_branch(cwd: Option<&Path>) -> Result<String> {
-    // Check local branches first
-    if branch_exists("main", cwd) {
-        return Ok("main".to_string());
-    }
+    detect_base_branch_with_env(cwd, |var| std::env::var(var).ok())
+}
 
-    if branch_exists("master", cwd) {
-        return Ok("master".to_string());
+fn detect_base_branch_with_env(
+    cwd: Option<&Path>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    // CI knows the real target branch
+    let ci_branch = CI_BASE_BRANCH_VARS.iter().find_map(|var| {
+        env(var)
+            .map(|v| v.trim().trim_start_matches("refs/heads/").to_string())
+            .filter(|v| !v.is_empty())
+    });
+    if let Some(branch) = ci_branch {
+        if let Some(found) = existing_branch(&branch, cwd) {
+            return Ok(found);
+        }
+        if is_shallow_repo(cwd) {
+            return Err(missing_ref_error(&format!("origin/{}", branch), cwd));
+        }
     }
 
-    // Check remote tracking branches
-    if remote_branch_exists("origin/main", cwd) {
-        return Ok("origin/main".to_string());
+    // The remote's default branch
+    if let Some(default) = get_origin_default_branch(cwd) {
+        let local = default.strip_prefix("origin/").unwrap_or(&default);
+        if branch_exists(local, cwd) {
+            return Ok(local.to_string());
+        }
+        if remote_branch_exists(&default, cwd) {
+            return Ok(default);
+        }
     }
 
-    if remote_branch_exists("origin/master", cwd) {
-        return Ok("origin/master".to_string());
+    for name in ["main", "master"] {
+        if let Some(found) = existing_branch(name, cwd) {
+            return Ok(found);
+        }
     }
 
-    // Try to get the default branch from origin
-    if let Some(default) = get_origin_default_branch(cwd) {
-        return Ok(default);
+    if is_shallow_repo(cwd) {
+        return Err(crate::error::McpDiffError::ShallowClone {
+            message: "Could not detect base branch (no main/master branch fetched)".to_string(),
+            fetch_command: fetch_ref_command("origin/main"),
+        });
     }
 
     Err(crate::error::McpDiffError::GitError {
@@ -57,6 +100,15 @@ pub fn detect_base_branch(cwd: Option<&Path>) -> Result<String> {
     })
 }
 
+/// A local branch named `name`, or else its origin remote-tracking branch
+fn existing_branch(name: &str, cwd: Option<&Path>) -> Option<String> {
+    if branch_exists(name, cwd) {
+        return Some(name.to_string());
+    }
+    let remote = format!("origin/{}", name);
+    remote_branch_exists(&remote, cwd).then_some(remote)
+}
+
 /// Check if a local branch exists
 fn branch_exists(name: &str, cwd: Option<&Path>) -> bool {
     git_command_optional(
@@ -98,8 +150,130 @@ fn get_origin_default_branch(cwd: Option<&Path>) -> Option<String> {
 /// Find the merge base between two refs (common ancestor)
 ///
 /// This is useful for finding where a branch diverged from the base branch.
+///
+/// In a shallow clone, a merge base outside the fetched history (or a ref
+/// that wasn't fetched) is reported as a `ShallowClone` error.
 pub fn get_merge_base(ref1: &str, ref2: &str, cwd: Option<&Path>) -> Result<String> {
-    git_command(&["merge-base", ref1, ref2], cwd)
+    match git_command(&["merge-base", ref1, ref2], cwd) {
+        Ok(sha) => Ok(sha),
+        Err(_) if is_shallow_repo(cwd) => {
+            if let Some(missing) = [ref1, ref2].into_iter().find(|r| !ref_exists(r, cwd)) {
+                return Err(missing_ref_error(missing, cwd));
+            }
+            Err(crate::error::McpDiffError::ShallowClone {
+                message: format!(
+                    "No merge-base between '{}' and '{}' in the fetched history",
+                    ref1, ref2
+                ),
+                fetch_command: deepen_command(),
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Where HEAD stands relative to the base branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseBranchStatus {
+    /// The detected base branch (see [`detect_base_branch`])
+    pub base_branch: String,
+    /// Commits on HEAD since it diverged from the base branch
+    pub commits_ahead: usize,
+}
+
+/// Get the base branch and how far HEAD is ahead of it
+///
+/// Fails when there's no base branch or merge base, which in a shallow clone
+/// is a `ShallowClone` error. Callers that only use this for context should
+/// omit it rather than fail.
+pub fn get_base_branch_status(cwd: Option<&Path>) -> Result<BaseBranchStatus> {
+    let base_branch = detect_base_branch(cwd)?;
+    let merge_base = get_merge_base(&base_branch, "HEAD", cwd)?;
+    let range = format!("{}..HEAD", merge_base);
+    let commits_ahead = git_command(&["rev-list", "--count", &range], cwd)?
+        .parse()
+        .unwrap_or(0);
+
+    Ok(BaseBranchStatus {
+        base_branch,
+        commits_ahead,
+    })
+}
+
+/// How far a branch has drifted from its base
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchDivergence {
+    /// Commits on the branch that the base doesn't have
+    pub commits_ahead: u32,
+    /// Commits on the base that the branch doesn't have
+    pub commits_behind: u32,
+    /// Files the branch changed since the merge base
+    pub files_changed: usize,
+    /// Lines the branch added since the merge base
+    pub lines_added: u32,
+    /// Lines the branch removed since the merge base
+    pub lines_removed: u32,
+    /// The merge base of the branch and its base
+    pub last_common_commit: String,
+}
+
+impl BranchDivergence {
+    /// Commits on either side since the merge base
+    pub fn total_commits(&self) -> u32 {
+        self.commits_ahead + self.commits_behind
+    }
+}
+
+/// Measure how far `branch` has diverged from `base`
+///
+/// Commit counts come from `git rev-list --count` on each side of the merge
+/// base, and file/line counts from `git diff --shortstat` between the merge
+/// base and the branch.
+pub fn get_branch_divergence(
+    branch: &str,
+    base: &str,
+    repo: Option<&Path>,
+) -> Result<BranchDivergence> {
+    let last_common_commit = get_merge_base(base, branch, repo)?;
+    let count = |range: String| -> Result<u32> {
+        Ok(git_command(&["rev-list", "--count", &range], repo)?
+            .parse()
+            .unwrap_or(0))
+    };
+    let commits_ahead = count(format!("{}..{}", last_common_commit, branch))?;
+    let commits_behind = count(format!("{}..{}", last_common_commit, base))?;
+    let shortstat = git_command(&["diff", "--shortstat", &last_common_commit, branch], repo)?;
+    let (files_changed, lines_added, lines_removed) = parse_shortstat(&shortstat);
+
+    Ok(BranchDivergence {
+        commits_ahead,
+        commits_behind,
+        files_changed,
+        lines_added,
+        lines_removed,
+        last_common_commit,
+    })
+}
+
+/// Parse `git diff --shortstat` output, e.g.
+/// ` 3 files changed, 10 insertions(+), 2 deletions(-)`, into
+/// (files, insertions, deletions). Missing parts count as zero.
+fn parse_shortstat(shortstat: &str) -> (usize, u32, u32) {
+    let mut stats = (0, 0, 0);
+    for part in shortstat.split(',') {
+        let mut words = part.split_whitespace();
+        let (Some(n), Some(label)) = (words.next(), words.next()) else {
+            continue;
+        };
+        if label.starts_with("file") {
+            stats.0 = n.parse().unwrap_or(0);
+        } else if label.starts_with("insertion") {
+            stats.1 = n.parse().unwrap_or(0);
+        } else if label.starts_with("deletion") {
+            stats.2 = n.parse().unwrap_or(0);
+        }
+    }
+    stats
 }
 
 /// Get the upstream branch for the current branch (if any)
@@ -119,6 +293,141 @@ pub fn get_remote_url(remote: Option<&str>, cwd: Option<&Path>) -> Option<String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::McpDiffError;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        git_command(args, Some(dir)).unwrap()
+    }
+
+    /// An origin with `main`, `release` and `feature` branches
+    fn origin_repo() -> TempDir {
+        let origin = TempDir::new().unwrap();
+        let dir = origin.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(dir.join(name), name).unwrap();
+            git(dir, &["add", "."]);
+            git(dir, &["commit", "-q", "-m", name]);
+        }
+        git(dir, &["branch", "release"]);
+        git(dir, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(dir.join("c.txt"), "c").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "c"]);
+        origin
+    }
+
+    fn clone(origin: &TempDir, args: &[&str]) -> TempDir {
+        let clone = TempDir::new().unwrap();
+        let url = format!("file://{}", origin.path().display());
+        let mut clone_args = vec!["clone", "-q"];
+        clone_args.extend_from_slice(args);
+        clone_args.extend_from_slice(&[url.as_str(), "."]);
+        git(clone.path(), &clone_args);
+        clone
+    }
+
+    #[test]
+    fn test_detect_base_branch_prefers_ci_target() {
+        let origin = origin_repo();
+        let dir = Some(origin.path());
+
+        let ci = |var: &str| (var == "GITHUB_BASE_REF").then(|| "release".to_string());
+        assert_eq!(detect_base_branch_with_env(dir, ci).unwrap(), "release");
+
+        let gitlab = |var: &str| {
+            (var == "CI_MERGE_REQUEST_TARGET_BRANCH_NAME").then(|| "refs/heads/release".to_string())
+        };
+        assert_eq!(detect_base_branch_with_env(dir, gitlab).unwrap(), "release");
+
+        assert_eq!(detect_base_branch_with_env(dir, |_| None).unwrap(), "main");
+    }
+
+    #[test]
+    fn test_detect_base_branch_uses_origin_head() {
+        let origin = origin_repo();
+        git(origin.path(), &["checkout", "-q", "release"]);
+        let clone = clone(&origin, &[]);
+
+        // origin/HEAD points at release, which wins over origin/main
+        let base = detect_base_branch_with_env(Some(clone.path()), |_| None).unwrap();
+        assert_eq!(base, "release");
+    }
+
+    #[test]
+    fn test_base_branch_in_shallow_clone() {
+        let origin = origin_repo();
+        git(origin.path(), &["checkout", "-q", "main"]);
+        let clone = clone(&origin, &["--depth", "1", "--branch", "feature"]);
+        let dir = Some(clone.path());
+
+        // The CI target branch wasn't fetched: report the fetch instead of guessing
+        let ci = |var: &str| (var == "GITHUB_BASE_REF").then(|| "release".to_string());
+        match detect_base_branch_with_env(dir, ci).unwrap_err() {
+            McpDiffError::ShallowClone { fetch_command, .. } => {
+                assert!(fetch_command.contains("origin release:refs/remotes/origin/release"));
+            }
+            other => panic!("Expected ShallowClone, got {:?}", other),
+        }
+
+        // The base branch is present but the merge base isn't
+        git(
+            clone.path(),
+            &[
+                "fetch",
+                "-q",
+                "--depth=1",
+                "origin",
+                "main:refs/remotes/origin/main",
+            ],
+        );
+        let err = get_base_branch_status(dir).unwrap_err();
+        assert!(
+            matches!(err, McpDiffError::ShallowClone { .. }),
+            "{:?}",
+            err
+        );
+
+        git(clone.path(), &["fetch", "-q", "--unshallow", "origin"]);
+        let status = get_base_branch_status(dir).unwrap();
+        assert_eq!(status.commits_ahead, 1);
+    }
+
+    #[test]
+    fn test_parse_shortstat() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 10 insertions(+), 2 deletions(-)"),
+            (3, 10, 2)
+        );
+        assert_eq!(parse_shortstat(" 1 file changed, 1 deletion(-)"), (1, 0, 1));
+        assert_eq!(parse_shortstat(""), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_get_branch_divergence() {
+        let origin = origin_repo();
+        let dir = origin.path();
+        let fork_point = git(dir, &["rev-parse", "main"]);
+        git(dir, &["checkout", "-q", "main"]);
+        std::fs::write(dir.join("d.txt"), "d").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "d"]);
+        git(dir, &["checkout", "-q", "feature"]);
+        std::fs::write(dir.join("a.txt"), "a\nchanged\n").unwrap();
+        git(dir, &["commit", "-q", "-am", "a"]);
+
+        let divergence = get_branch_divergence("feature", "main", Some(dir)).unwrap();
+        assert_eq!(divergence.commits_ahead, 2);
+        assert_eq!(divergence.commits_behind, 1);
+        assert_eq!(divergence.files_changed, 2);
+        assert_eq!(divergence.lines_added, 3);
+        assert_eq!(divergence.lines_removed, 1);
+        assert_eq!(divergence.last_common_commit, fork_point);
+        assert_eq!(divergence.total_commits(), 3);
+    }
 
     #[test]
     fn test_is_git_repo() {
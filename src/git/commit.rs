@@ -3,9 +3,10 @@
 #![allow(dead_code)]
 
 use std::path::Path;
+use std::process::Command;
 
 use super::git_command;
-use crate::error::Result;
+use crate::error::{McpDiffError, Result};
 
 /// Information about a commit
 #[derive(Debug, Clone)]
@@ -74,6 +75,81 @@ pub fn get_file_at_ref(
     Ok(result)
 }
 
+/// Get the file content from a stash entry (`stash@{index}`)
+///
+/// Returns None if the file doesn't exist in that stash. Errors if the
+/// stash entry doesn't exist or the file is binary.
+pub fn get_file_at_stash(
+    file_path: &str,
+    stash_index: usize,
+    cwd: Option<&Path>,
+) -> Result<Option<String>> {
+    let stash = format!("stash@{{{}}}", stash_index);
+    if super::git_command_optional(&["rev-parse", "--verify", "--quiet", &stash], cwd).is_none() {
+        return Err(McpDiffError::GitError {
+            message: format!("No stash entry {}", stash),
+        });
+    }
+
+    let spec = format!("{}:{}", stash, file_path);
+    if super::git_command_optional(&["cat-file", "-e", &spec], cwd).is_none() {
+        return Ok(None);
+    }
+
+    get_blob_text(&spec, cwd).map(Some)
+}
+
+/// Get the raw content of a blob
+///
+/// `spec` is anything git resolves to a blob: a blob SHA or `<ref>:<path>`
+/// (e.g. `stash@{0}:src/lib.rs`, `HEAD~2:Cargo.toml`).
+pub fn get_blob(spec: &str, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let object_type = git_command(&["cat-file", "-t", spec], cwd)?;
+    if object_type != "blob" {
+        return Err(McpDiffError::GitError {
+            message: format!("{} is a {}, not a blob", spec, object_type),
+        });
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["cat-file", "blob", spec]);
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd.output().map_err(|e| McpDiffError::GitError {
+        message: format!("Failed to execute git: {}", e),
+    })?;
+    if !output.status.success() {
+        return Err(McpDiffError::GitError {
+            message: format!(
+                "git cat-file blob {} failed: {}",
+                spec,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    Ok(output.stdout)
+}
+
+/// Get the content of a text blob (see [`get_blob`])
+///
+/// Binary blobs are rejected, using git's heuristic of a NUL byte in the
+/// first 8000 bytes, as are blobs that are not valid UTF-8.
+pub fn get_blob_text(spec: &str, cwd: Option<&Path>) -> Result<String> {
+    let content = get_blob(spec, cwd)?;
+    if content.iter().take(8000).any(|&b| b == 0) {
+        return Err(McpDiffError::GitError {
+            message: format!("{} is a binary blob", spec),
+        });
+    }
+
+    String::from_utf8(content).map_err(|_| McpDiffError::GitError {
+        message: format!("{} is a binary blob (not valid UTF-8)", spec),
+    })
+}
+
 /// Get commit count since base ref
 pub fn get_commit_count(base_ref: &str, cwd: Option<&Path>) -> Result<usize> {
     let output = git_command(
@@ -126,9 +202,239 @@ pub fn get_last_commit(cwd: Option<&Path>) -> Option<CommitInfo> {
     })
 }
 
+/// Author time (unix seconds) of the oldest surviving line in a line range
+///
+/// Uses `git blame`; lines not yet committed count as written now. Returns
+/// `None` when the file isn't tracked or the range is out of bounds.
+pub fn get_oldest_line_time(
+    path: &str,
+    start_line: usize,
+    end_line: usize,
+    cwd: Option<&Path>,
+) -> Option<i64> {
+    let range = format!("{},{}", start_line.max(1), end_line.max(start_line).max(1));
+    let output = git_command(&["blame", "--porcelain", "-L", &range, "--", path], cwd).ok()?;
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("author-time "))
+        .filter_map(|time| time.trim().parse::<i64>().ok())
+        .min()
+}
+
+/// Author of each line of a file, in order
+///
+/// One `git blame --line-porcelain` for the whole file. Lines not committed
+/// yet are attributed to `Not Committed Yet`, as git does. Returns `None`
+/// when the file isn't tracked.
+pub fn get_line_authors(path: &str, cwd: Option<&Path>) -> Option<Vec<String>> {
+    let output = git_command(&["blame", "--line-porcelain", "--", path], cwd).ok()?;
+    Some(
+        output
+            .lines()
+            .filter_map(|line| line.strip_prefix("author "))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// A commit that changed a line range, from [`get_line_range_history`]
+#[derive(Debug, Clone)]
+pub struct LineRangeCommit {
+    pub commit: CommitInfo,
+    /// Path of the file in this commit (differs from the current path when
+    /// the file was renamed later)
+    pub path: String,
+    /// The range first appeared in this commit
+    pub introduced: bool,
+}
+
+/// Commits that changed a line range, newest first
+///
+/// Uses `git log -L`, which follows the lines themselves: renaming the
+/// function on them, or moving the file, doesn't end the history. The range
+/// refers to the file at HEAD.
+pub fn get_line_range_history(
+    path: &str,
+    start_line: usize,
+    end_line: usize,
+    cwd: Option<&Path>,
+) -> Result<Vec<LineRangeCommit>> {
+    // Each commit starts with a record separator, then SHA|short|subject|author|date
+    let range = format!(
+        "{},{}:{}",
+        start_line.max(1),
+        end_line.max(start_line).max(1),
+        path
+    );
+    let output = git_command(&["log", "--format=%x1e%H|%h|%s|%an|%aI", "-L", &range], cwd)?;
+
+    let mut commits = Vec::new();
+    for record in output.split('\x1e') {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let parts: Vec<&str> = header.splitn(5, '|').collect();
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let mut introduced = false;
+        let mut file = path.to_string();
+        for line in lines {
+            if line == "--- /dev/null" {
+                introduced = true;
+            } else if let Some(name) = line.strip_prefix("+++ b/") {
+                file = name.to_string();
+                break;
+            }
+        }
+
+        commits.push(LineRangeCommit {
+            commit: CommitInfo {
+                sha: parts[0].to_string(),
+                short_sha: parts[1].to_string(),
+                subject: parts[2].to_string(),
+                author: parts[3].to_string(),
+                date: parts[4].to_string(),
+            },
+            path: file,
+            introduced,
+        });
+    }
+
+    Ok(commits)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        git_command(args, Some(dir)).unwrap()
+    }
+
+    /// A repo with one commit of `lib.ts` and a stashed change to it
+    fn repo_with_stash() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        fs::write(dir.join("lib.ts"), "export function original() {}\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "init"]);
+        fs::write(dir.join("lib.ts"), "export function stashed() {}\n").unwrap();
+        git(dir, &["stash", "-q"]);
+        temp
+    }
+
+    #[test]
+    fn test_get_file_at_stash() {
+        let temp = repo_with_stash();
+        let dir = Some(temp.path());
+
+        let stashed = get_file_at_stash("lib.ts", 0, dir).unwrap();
+        assert_eq!(stashed.as_deref(), Some("export function stashed() {}\n"));
+        assert_eq!(get_file_at_stash("missing.ts", 0, dir).unwrap(), None);
+        assert!(get_file_at_stash("lib.ts", 1, dir).is_err());
+    }
+
+    #[test]
+    fn test_get_blob_by_sha() {
+        let temp = repo_with_stash();
+        let dir = Some(temp.path());
+
+        let sha = git(temp.path(), &["rev-parse", "HEAD:lib.ts"]);
+        assert_eq!(
+            get_blob_text(&sha, dir).unwrap(),
+            "export function original() {}\n"
+        );
+
+        // A commit is not a blob
+        let err = get_blob("HEAD", dir).unwrap_err().to_string();
+        assert!(err.contains("not a blob"), "{}", err);
+    }
+
+    #[test]
+    fn test_get_oldest_line_time() {
+        let temp = repo_with_stash();
+        let dir = temp.path();
+        fs::write(dir.join("math.ts"), "export function add() {}\n").unwrap();
+        git(dir, &["add", "math.ts"]);
+        // Backdate the first version so blame has something older to find
+        Command::new("git")
+            .args(["commit", "-q", "-m", "old"])
+            .env("GIT_AUTHOR_DATE", "@1000000000 +0000")
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        fs::write(
+            dir.join("math.ts"),
+            "export function add() {}\nexport function sub() {}\n",
+        )
+        .unwrap();
+        git(dir, &["commit", "-q", "-am", "new"]);
+
+        let dir = Some(dir);
+        assert_eq!(get_oldest_line_time("math.ts", 1, 2, dir), Some(1000000000));
+        assert!(get_oldest_line_time("math.ts", 2, 2, dir).unwrap() > 1000000000);
+        assert_eq!(get_oldest_line_time("missing.ts", 1, 1, dir), None);
+    }
+
+    #[test]
+    fn test_get_line_range_history_follows_renames() {
+        let temp = repo_with_stash();
+        let dir = temp.path();
+        fs::write(
+            dir.join("lib.rs"),
+            "fn other() {}\n\nfn add() {\n    1\n}\n",
+        )
+        .unwrap();
+        git(dir, &["add", "lib.rs"]);
+        git(dir, &["commit", "-q", "-m", "add add"]);
+        fs::write(
+            dir.join("lib.rs"),
+            "fn other() { 2 }\n\nfn add() {\n    1\n}\n",
+        )
+        .unwrap();
+        git(dir, &["commit", "-q", "-am", "change other"]);
+        git(dir, &["mv", "lib.rs", "math.rs"]);
+        git(dir, &["commit", "-q", "-m", "move"]);
+        fs::write(
+            dir.join("math.rs"),
+            "fn other() { 2 }\n\nfn plus() {\n    1\n}\n",
+        )
+        .unwrap();
+        git(dir, &["commit", "-q", "-am", "rename add"]);
+
+        let history = get_line_range_history("math.rs", 3, 5, Some(dir)).unwrap();
+        let subjects: Vec<&str> = history.iter().map(|c| c.commit.subject.as_str()).collect();
+        assert_eq!(subjects, ["rename add", "add add"]);
+        assert_eq!(history[0].path, "math.rs");
+        assert_eq!(history[1].path, "lib.rs");
+        assert!(!history[0].introduced);
+        assert!(history[1].introduced);
+
+        assert!(get_line_range_history("missing.rs", 1, 1, Some(dir)).is_err());
+    }
+
+    #[test]
+    fn test_get_blob_text_rejects_binary() {
+        let temp = repo_with_stash();
+        let dir = temp.path();
+        fs::write(dir.join("image.bin"), [0x89, b'P', b'N', b'G', 0, 1, 2]).unwrap();
+        git(dir, &["add", "image.bin"]);
+        git(dir, &["commit", "-q", "-m", "binary"]);
+
+        assert_eq!(get_blob("HEAD:image.bin", Some(dir)).unwrap().len(), 7);
+        let err = get_blob_text("HEAD:image.bin", Some(dir))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("binary"), "{}", err);
+    }
 
     #[test]
     fn test_commit_info_fields() {
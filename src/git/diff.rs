@@ -22,6 +22,8 @@ pub enum ChangeType {
     Copied,
     /// Type changed (e.g., file to symlink)
     TypeChanged,
+    /// New file not yet tracked by git
+    Untracked,
 }
 
 impl ChangeType {
@@ -47,6 +49,7 @@ impl ChangeType {
             Self::Renamed => "renamed",
             Self::Copied => "copied",
             Self::TypeChanged => "type_changed",
+            Self::Untracked => "untracked",
         }
     }
 }
@@ -187,6 +190,18 @@ pub fn get_uncommitted_changes(base_ref: &str, cwd: Option<&Path>) -> Result<Vec
     parse_name_status_output(&output)
 }
 
+/// Get uncommitted changes compared to a base ref, including untracked files
+///
+/// Like [`get_uncommitted_changes`], plus every untracked, non-ignored file
+/// as [`ChangeType::Untracked`]. Sorted by path.
+pub fn get_working_tree_changes(base_ref: &str, cwd: Option<&Path>) -> Result<Vec<ChangedFile>> {
+    let mut files = get_uncommitted_changes(base_ref, cwd)?;
+    files.extend(get_untracked_changes(cwd)?);
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(files)
+}
+
 /// Get staged changes only (changes added to index, ready to commit)
 ///
 /// # Arguments
@@ -213,6 +228,108 @@ pub fn get_unstaged_changes(cwd: Option<&Path>) -> Result<Vec<ChangedFile>> {
     parse_name_status_output(&output)
 }
 
+/// Get changed line ranges per file, comparing the working tree to a base ref
+///
+/// Uses `git diff -U0 --relative`, so paths are relative to `cwd`. Ranges are
+/// 1-indexed and inclusive; a pure deletion is reported as the two lines
+/// surrounding the removed text. Deleted files are omitted.
+pub fn get_changed_line_ranges(
+    base_ref: &str,
+    cwd: Option<&Path>,
+) -> Result<Vec<(String, Vec<(usize, usize)>)>> {
+    let output = git_command(
+        &[
+            "diff",
+            "-U0",
+            "--relative",
+            "--no-color",
+            "--no-ext-diff",
+            base_ref,
+        ],
+        cwd,
+    )?;
+
+    Ok(parse_zero_context_diff(&output))
+}
+
+/// Get untracked files that are not ignored (relative to `cwd`)
+pub fn get_untracked_files(cwd: Option<&Path>) -> Result<Vec<String>> {
+    let output = git_command(&["ls-files", "--others", "--exclude-standard"], cwd)?;
+
+    Ok(output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Get untracked files that are not ignored, as changes
+///
+/// Unlike [`get_untracked_files`], this covers the whole repository whatever
+/// `cwd` is, with paths relative to the repo root like the other change
+/// lists. The in-place cache that repositories under the temp dir keep in
+/// `.semfora-cache/` is skipped.
+pub fn get_untracked_changes(cwd: Option<&Path>) -> Result<Vec<ChangedFile>> {
+    let output = git_command(
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--full-name",
+            "--",
+            ":/",
+            ":(top,exclude).semfora-cache",
+        ],
+        cwd,
+    )?;
+
+    Ok(output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|path| ChangedFile {
+            path: path.to_string(),
+            old_path: None,
+            change_type: ChangeType::Untracked,
+        })
+        .collect())
+}
+
+/// Parse `git diff -U0` output into new-side line ranges per file
+fn parse_zero_context_diff(output: &str) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut files: Vec<(String, Vec<(usize, usize)>)> = Vec::new();
+    // False while inside a deleted file, whose hunks have no new side
+    let mut in_file = false;
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            in_file = path != "/dev/null";
+            if in_file {
+                let path = path.strip_prefix("b/").unwrap_or(path);
+                files.push((path.to_string(), Vec::new()));
+            }
+        } else if in_file && line.starts_with("@@") {
+            // @@ -old_start[,old_count] +new_start[,new_count] @@
+            let Some(new_side) = line.split_whitespace().find(|p| p.starts_with('+')) else {
+                continue;
+            };
+            let mut parts = new_side[1..].split(',');
+            let start: usize = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            let count: usize = parts.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+
+            let range = if count == 0 {
+                (start.max(1), start + 1)
+            } else {
+                (start, start + count - 1)
+            };
+            if let Some((_, ranges)) = files.last_mut() {
+                ranges.push(range);
+            }
+        }
+    }
+
+    files
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,5 +377,75 @@ mod tests {
         assert_eq!(ChangeType::Added.as_str(), "added");
         assert_eq!(ChangeType::Modified.as_str(), "modified");
         assert_eq!(ChangeType::Deleted.as_str(), "deleted");
+        assert_eq!(ChangeType::Untracked.as_str(), "untracked");
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        git_command(args, Some(dir)).unwrap()
+    }
+
+    #[test]
+    fn test_working_tree_changes_cover_every_state() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/both.ts"), "export const a = 1;\n").unwrap();
+        std::fs::write(dir.join("src/gone.ts"), "export const b = 1;\n").unwrap();
+        std::fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "initial"]);
+
+        // Staged edit with further unstaged edits on top
+        std::fs::write(dir.join("src/both.ts"), "export const a = 2;\n").unwrap();
+        git(dir, &["add", "src/both.ts"]);
+        std::fs::write(dir.join("src/both.ts"), "export const a = 3;\n").unwrap();
+        // Staged new file, unstaged deletion, untracked and ignored files
+        std::fs::write(dir.join("src/staged.ts"), "export const c = 1;\n").unwrap();
+        git(dir, &["add", "src/staged.ts"]);
+        std::fs::remove_file(dir.join("src/gone.ts")).unwrap();
+        std::fs::write(dir.join("src/new.ts"), "export const d = 1;\n").unwrap();
+        std::fs::write(dir.join("debug.log"), "noise").unwrap();
+        std::fs::create_dir_all(dir.join(".semfora-cache")).unwrap();
+        std::fs::write(dir.join(".semfora-cache/meta.json"), "{}").unwrap();
+
+        // Paths stay repo-relative when run from a subdirectory
+        let files = get_working_tree_changes("HEAD", Some(&dir.join("src"))).unwrap();
+        let changes: Vec<_> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.change_type))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("src/both.ts", ChangeType::Modified),
+                ("src/gone.ts", ChangeType::Deleted),
+                ("src/new.ts", ChangeType::Untracked),
+                ("src/staged.ts", ChangeType::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_zero_context_diff() {
+        let output = "diff --git a/src/lib.rs b/src/lib.rs\n\
+--- a/src/lib.rs\n\
++++ b/src/lib.rs\n\
+@@ -10,2 +10,3 @@ fn main() {\n\
++    added();\n\
+@@ -40 +41 @@\n\
+-old\n\
++new\n\
+@@ -50,3 +51,0 @@\n\
+diff --git a/gone.rs b/gone.rs\n\
+--- a/gone.rs\n\
++++ /dev/null\n\
+@@ -1,5 +0,0 @@\n";
+        let files = parse_zero_context_diff(output);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "src/lib.rs");
+        assert_eq!(files[0].1, vec![(10, 12), (41, 41), (51, 52)]);
     }
 }
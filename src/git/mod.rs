@@ -6,17 +6,25 @@
 mod branch;
 mod commit;
 mod diff;
+mod shallow;
 
 pub use branch::{
-    detect_base_branch, get_current_branch, get_merge_base, get_remote_url, is_git_repo,
+    detect_base_branch, get_base_branch_status, get_branch_divergence, get_current_branch,
+    get_merge_base, get_remote_url, is_git_repo, BaseBranchStatus, BranchDivergence,
 };
 pub use commit::{
-    get_commits_since, get_file_at_ref, get_last_commit, get_parent_commit, get_repo_root,
-    CommitInfo,
+    get_blob, get_blob_text, get_commits_since, get_file_at_ref, get_file_at_stash,
+    get_last_commit, get_line_authors, get_line_range_history, get_oldest_line_time,
+    get_parent_commit, get_repo_root, CommitInfo, LineRangeCommit,
 };
 pub use diff::{
-    get_changed_files, get_commit_changed_files, get_staged_changes, get_uncommitted_changes,
-    get_unstaged_changes, ChangeType, ChangedFile,
+    get_changed_files, get_changed_line_ranges, get_commit_changed_files, get_staged_changes,
+    get_uncommitted_changes, get_unstaged_changes, get_untracked_changes, get_untracked_files,
+    get_working_tree_changes, ChangeType, ChangedFile,
+};
+pub use shallow::{
+    deepen_command, ensure_ref, fetch_ref_command, is_shallow_repo, merge_base_with_deepen,
+    ref_exists, DEEPEN_STEP,
 };
 
 use std::path::Path;
@@ -0,0 +1,208 @@
+//! Shallow clone detection and history deepening
+//!
+//! CI systems commonly check out with `--depth 1`, so base branches and
+//! merge-bases are often missing. These helpers turn those cases into a
+//! [`McpDiffError::ShallowClone`] naming the exact fetch needed, and can
+//! fetch the missing history on request.
+
+use std::path::Path;
+
+use super::{git_command, git_command_optional};
+use crate::error::{McpDiffError, Result};
+
+/// Commits fetched per step when deepening, and in suggested fetch commands
+pub const DEEPEN_STEP: usize = 50;
+
+/// Check if the repository is a shallow clone (has a `.git/shallow` file)
+pub fn is_shallow_repo(cwd: Option<&Path>) -> bool {
+    let Some(shallow) = git_command_optional(&["rev-parse", "--git-path", "shallow"], cwd) else {
+        return false;
+    };
+
+    // `--git-path` is relative to the working directory git ran in
+    match cwd {
+        Some(dir) => dir.join(&shallow).exists(),
+        None => Path::new(&shallow).exists(),
+    }
+}
+
+/// Check if a ref resolves to a commit
+pub fn ref_exists(ref_name: &str, cwd: Option<&Path>) -> bool {
+    git_command_optional(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", ref_name),
+        ],
+        cwd,
+    )
+    .is_some()
+}
+
+/// Command that fetches `ref_name` into a shallow clone
+pub fn fetch_ref_command(ref_name: &str) -> String {
+    match ref_name.strip_prefix("origin/") {
+        Some(branch) => format!(
+            "git fetch --depth={} origin {}:refs/remotes/origin/{}",
+            DEEPEN_STEP, branch, branch
+        ),
+        None => format!("git fetch --depth={} origin {}", DEEPEN_STEP, ref_name),
+    }
+}
+
+/// Command that extends the history of a shallow clone
+pub fn deepen_command() -> String {
+    format!("git fetch --deepen={}", DEEPEN_STEP)
+}
+
+/// Error for a ref that can't be resolved
+///
+/// In a shallow clone this is a [`McpDiffError::ShallowClone`] with the fetch
+/// that makes the ref available.
+pub fn missing_ref_error(ref_name: &str, cwd: Option<&Path>) -> McpDiffError {
+    if is_shallow_repo(cwd) {
+        McpDiffError::ShallowClone {
+            message: format!("Ref '{}' is not available", ref_name),
+            fetch_command: fetch_ref_command(ref_name),
+        }
+    } else {
+        McpDiffError::GitError {
+            message: format!("Unknown ref '{}'", ref_name),
+        }
+    }
+}
+
+/// Make sure `ref_name` resolves to a commit
+///
+/// With `deepen`, a ref missing from a shallow clone is fetched from origin
+/// (at most `deepen` commits deep) before giving up.
+pub fn ensure_ref(ref_name: &str, deepen: Option<usize>, cwd: Option<&Path>) -> Result<()> {
+    if ref_exists(ref_name, cwd) {
+        return Ok(());
+    }
+
+    if let Some(depth) = deepen.filter(|_| is_shallow_repo(cwd)) {
+        let branch = ref_name.strip_prefix("origin/").unwrap_or(ref_name);
+        let refspec = format!("{}:refs/remotes/origin/{}", branch, branch);
+        let depth_arg = format!("--depth={}", depth);
+        // A SHA or tag can't be mapped onto a remote-tracking branch
+        if git_command_optional(&["fetch", &depth_arg, "origin", &refspec], cwd).is_none() {
+            let _ = git_command_optional(&["fetch", &depth_arg, "origin", branch], cwd);
+        }
+        if ref_exists(ref_name, cwd) {
+            return Ok(());
+        }
+    }
+
+    Err(missing_ref_error(ref_name, cwd))
+}
+
+/// Find the merge base of two refs, deepening a shallow clone if needed
+///
+/// With `deepen`, history is fetched [`DEEPEN_STEP`] commits at a time, up to
+/// `deepen` commits, until a merge base is found.
+pub fn merge_base_with_deepen(
+    ref1: &str,
+    ref2: &str,
+    deepen: Option<usize>,
+    cwd: Option<&Path>,
+) -> Result<String> {
+    let mut result = super::get_merge_base(ref1, ref2, cwd);
+
+    if let Some(max_commits) = deepen {
+        let mut deepened = 0;
+        while matches!(result, Err(McpDiffError::ShallowClone { .. })) && deepened < max_commits {
+            let step = DEEPEN_STEP.min(max_commits - deepened);
+            git_command(&["fetch", &format!("--deepen={}", step), "origin"], cwd)?;
+            deepened += step;
+            result = super::get_merge_base(ref1, ref2, cwd);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        git_command(args, Some(dir)).unwrap()
+    }
+
+    fn commit_file(dir: &Path, name: &str, content: &str) {
+        fs::write(dir.join(name), content).unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", name]);
+    }
+
+    /// An origin with `main` and a `feature` branch three commits ahead,
+    /// plus a `--depth 1` clone of `feature`
+    fn shallow_clone() -> (TempDir, TempDir) {
+        let origin = TempDir::new().unwrap();
+        let dir = origin.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        commit_file(dir, "a.txt", "a");
+        commit_file(dir, "b.txt", "b");
+        git(dir, &["checkout", "-q", "-b", "feature"]);
+        for name in ["c.txt", "d.txt", "e.txt"] {
+            commit_file(dir, name, name);
+        }
+
+        let clone = TempDir::new().unwrap();
+        let url = format!("file://{}", dir.display());
+        git(
+            clone.path(),
+            &[
+                "clone", "-q", "--depth", "1", "--branch", "feature", &url, ".",
+            ],
+        );
+        (origin, clone)
+    }
+
+    #[test]
+    fn test_is_shallow_repo() {
+        let (origin, clone) = shallow_clone();
+        assert!(is_shallow_repo(Some(clone.path())));
+        assert!(!is_shallow_repo(Some(origin.path())));
+    }
+
+    #[test]
+    fn test_missing_base_ref_in_shallow_clone() {
+        let (_origin, clone) = shallow_clone();
+        let dir = Some(clone.path());
+
+        let err = ensure_ref("origin/main", None, dir).unwrap_err();
+        match err {
+            McpDiffError::ShallowClone { fetch_command, .. } => {
+                assert!(fetch_command.contains("origin main:refs/remotes/origin/main"));
+            }
+            other => panic!("Expected ShallowClone, got {:?}", other),
+        }
+
+        ensure_ref("origin/main", Some(10), dir).unwrap();
+        assert!(ref_exists("origin/main", dir));
+    }
+
+    #[test]
+    fn test_missing_merge_base_in_shallow_clone() {
+        let (_origin, clone) = shallow_clone();
+        let dir = Some(clone.path());
+        ensure_ref("origin/main", Some(1), dir).unwrap();
+
+        let err = merge_base_with_deepen("HEAD", "origin/main", None, dir).unwrap_err();
+        assert!(
+            matches!(err, McpDiffError::ShallowClone { ref fetch_command, .. } if fetch_command.contains("--deepen")),
+            "{:?}",
+            err
+        );
+
+        let base = merge_base_with_deepen("HEAD", "origin/main", Some(10), dir).unwrap();
+        assert_eq!(base, git(clone.path(), &["rev-parse", "origin/main"]));
+    }
+}
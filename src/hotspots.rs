@@ -0,0 +1,504 @@
+//! Repository hot spots: where the action happens
+//!
+//! Three rankings answer the onboarding question "what should I read first":
+//!
+//! - **largest**: symbols with the most lines
+//! - **most_called**: symbols with the most distinct callers (fan-in), counted
+//!   from the call graph's function-call edges
+//! - **most_changed**: files touched by the most of the last
+//!   [`DEFAULT_CHURN_COMMITS`] commits (configurable through
+//!   [`HOTSPOT_COMMITS_ENV`]), from a single `git log --name-only`
+//!
+//! The rankings are built at index time (`graphs/hotspots.json`) and keep the
+//! top [`STORED_HOTSPOTS`] of each list. Test files and variables are left
+//! out. Churn is skipped outside a git repository, leaving `most_changed`
+//! empty and `churn_window` unset.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::verify::{corrupt, read_artifact};
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::cli::SymbolScope;
+use crate::error::{McpDiffError, Result};
+use crate::git::{git_command, is_git_repo};
+use crate::module_usage::normalize_path;
+use crate::schema::{CallGraphEdge, RefKind, SCHEMA_VERSION};
+use crate::search::is_test_file;
+
+/// Entries kept per ranking in the persisted index
+pub const STORED_HOTSPOTS: usize = 25;
+
+/// Commits scanned for churn unless [`HOTSPOT_COMMITS_ENV`] says otherwise
+pub const DEFAULT_CHURN_COMMITS: usize = 500;
+
+/// Number of recent commits scanned for churn; `0` disables churn
+pub const HOTSPOT_COMMITS_ENV: &str = "SEMFORA_HOTSPOT_COMMITS";
+
+/// Persisted hot spot rankings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotspotIndex {
+    pub schema_version: String,
+    /// Symbols by line count
+    pub largest: Vec<SymbolHotspot>,
+    /// Symbols by number of distinct callers
+    pub most_called: Vec<SymbolHotspot>,
+    /// Files by number of commits touching them
+    #[serde(default)]
+    pub most_changed: Vec<FileHotspot>,
+    /// Commits scanned for `most_changed` (unset when churn was skipped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn_window: Option<usize>,
+}
+
+/// A symbol in a ranking
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolHotspot {
+    pub symbol: String,
+    pub hash: String,
+    pub kind: String,
+    pub module: String,
+    pub file: String,
+    pub risk: String,
+    /// Lines (`largest`) or callers (`most_called`)
+    pub value: usize,
+}
+
+/// A file in the churn ranking
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHotspot {
+    pub file: String,
+    pub module: String,
+    /// Highest risk among the file's symbols
+    pub risk: String,
+    /// Commits touching the file
+    pub value: usize,
+}
+
+impl HotspotIndex {
+    /// Rank the indexed symbols
+    ///
+    /// `call_graph` is the caller -> callees map from
+    /// [`CacheDir::load_call_graph`]. Churn is read from the git history of
+    /// `repo_root` when given, scanning the last `churn_commits` commits.
+    /// Generated code is ranked only when `include_generated` is set.
+    pub fn build(
+        entries: &[SymbolIndexEntry],
+        call_graph: &HashMap<String, Vec<String>>,
+        repo_root: Option<&Path>,
+        churn_commits: usize,
+        include_generated: bool,
+    ) -> Self {
+        let ranked: Vec<&SymbolIndexEntry> = entries
+            .iter()
+            .filter(|e| {
+                !e.is_escape_local
+                    && SymbolScope::Functions.matches_kind(&e.kind)
+                    && !is_test_file(&e.file)
+                    && (include_generated || !e.is_generated)
+            })
+            .collect();
+
+        let largest = top_symbols(ranked.iter().map(|e| (*e, line_count(&e.lines))));
+
+        let fan_in = caller_counts(call_graph);
+        let most_called = top_symbols(
+            ranked
+                .iter()
+                .map(|e| (*e, fan_in.get(e.hash.as_str()).copied().unwrap_or(0))),
+        );
+
+        let churn = repo_root
+            .filter(|_| churn_commits > 0)
+            .and_then(|root| commit_touches(root, churn_commits).map(|counts| (root, counts)));
+        let (most_changed, churn_window) = match churn {
+            Some((root, counts)) => (top_files(&ranked, root, &counts), Some(churn_commits)),
+            None => (Vec::new(), None),
+        };
+
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            largest,
+            most_called,
+            most_changed,
+            churn_window,
+        }
+    }
+
+    /// Keep the first `limit` entries of each ranking
+    pub fn truncated(mut self, limit: usize) -> Self {
+        self.largest.truncate(limit);
+        self.most_called.truncate(limit);
+        self.most_changed.truncate(limit);
+        self
+    }
+
+    /// Load the persisted rankings
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        let path = cache.hotspots_path();
+        if !path.exists() {
+            return Err(McpDiffError::FileNotFound {
+                path: "Hot spots not found. Run `semfora index generate --force` first."
+                    .to_string(),
+            });
+        }
+        let content = read_artifact(&path)?;
+        serde_json::from_str(&content).map_err(|e| corrupt(&path, e))
+    }
+
+    /// Persist the rankings, returning the bytes written
+    pub fn write(&self, cache: &CacheDir) -> Result<usize> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize hot spots: {}", e),
+        })?;
+        let path = cache.hotspots_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+        Ok(content.len())
+    }
+}
+
+/// The configured churn window
+pub fn churn_commits() -> usize {
+    parse_churn_commits(std::env::var(HOTSPOT_COMMITS_ENV).ok().as_deref())
+}
+
+fn parse_churn_commits(value: Option<&str>) -> usize {
+    let Some(value) = value.map(str::trim) else {
+        return DEFAULT_CHURN_COMMITS;
+    };
+    value.parse().unwrap_or_else(|_| {
+        tracing::warn!(
+            "Ignoring {}={:?}: expected a number of commits",
+            HOTSPOT_COMMITS_ENV,
+            value
+        );
+        DEFAULT_CHURN_COMMITS
+    })
+}
+
+/// Lines spanned by a `start-end` range
+fn line_count(lines: &str) -> usize {
+    let mut bounds = lines.split('-').map(|n| n.trim().parse::<usize>().ok());
+    match (bounds.next().flatten(), bounds.next().flatten()) {
+        (Some(start), Some(end)) if end >= start => end - start + 1,
+        (Some(_), _) => 1,
+        _ => 0,
+    }
+}
+
+/// Distinct callers of each symbol, counting function calls only
+fn caller_counts(call_graph: &HashMap<String, Vec<String>>) -> HashMap<String, usize> {
+    let mut callers: HashMap<String, HashSet<&str>> = HashMap::new();
+    for (caller, callees) in call_graph {
+        for callee in callees {
+            let edge = CallGraphEdge::decode(callee);
+            if edge.edge_kind != RefKind::None
+                || edge.callee.starts_with("ext:")
+                || edge.callee == *caller
+            {
+                continue;
+            }
+            callers.entry(edge.callee).or_default().insert(caller);
+        }
+    }
+    callers.into_iter().map(|(k, v)| (k, v.len())).collect()
+}
+
+/// Highest-valued symbols first, ties broken by file then name
+fn top_symbols<'a>(
+    values: impl Iterator<Item = (&'a SymbolIndexEntry, usize)>,
+) -> Vec<SymbolHotspot> {
+    let mut ranked: Vec<_> = values.filter(|(_, value)| *value > 0).collect();
+    ranked.sort_by(|(a, va), (b, vb)| {
+        vb.cmp(va)
+            .then_with(|| a.file.cmp(&b.file))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+    ranked.truncate(STORED_HOTSPOTS);
+    ranked
+        .into_iter()
+        .map(|(e, value)| SymbolHotspot {
+            symbol: e.symbol.clone(),
+            hash: e.hash.clone(),
+            kind: e.kind.clone(),
+            module: e.module.clone(),
+            file: e.file.clone(),
+            risk: e.risk.clone(),
+            value,
+        })
+        .collect()
+}
+
+/// Commits touching each file among the last `commits`, keyed by path
+/// relative to `repo_root`, or `None` outside a git repository
+fn commit_touches(repo_root: &Path, commits: usize) -> Option<HashMap<String, usize>> {
+    if !is_git_repo(Some(repo_root)) {
+        return None;
+    }
+    let limit = commits.to_string();
+    // `--relative` scopes the log to the indexed directory and reports paths
+    // relative to it, which may be below the work tree root
+    let log = git_command(
+        &[
+            "log",
+            "-n",
+            &limit,
+            "--relative",
+            "--name-only",
+            "--format=",
+        ],
+        Some(repo_root),
+    )
+    .ok()?;
+    Some(parse_name_only_log(&log))
+}
+
+/// Count file occurrences in `git log --name-only --format=` output
+///
+/// Each commit lists a file at most once, so occurrences are commits.
+fn parse_name_only_log(log: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for line in log.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        *counts.entry(line.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Most-touched indexed files first, ties broken by path
+fn top_files(
+    entries: &[&SymbolIndexEntry],
+    repo_root: &Path,
+    touches: &HashMap<String, usize>,
+) -> Vec<FileHotspot> {
+    // Index paths may be `./src/a.ts`, `src/a.ts` or absolute
+    let mut files: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+    for e in entries {
+        let (_, risk) = files.entry(&e.file).or_insert((&e.module, &e.risk));
+        if risk_rank(&e.risk) > risk_rank(risk) {
+            *risk = &e.risk;
+        }
+    }
+
+    let mut ranked: Vec<FileHotspot> = files
+        .into_iter()
+        .filter_map(|(file, (module, risk))| {
+            let path = Path::new(file);
+            let relative = path.strip_prefix(repo_root).unwrap_or(path);
+            let key = normalize_path(relative.to_string_lossy().trim_start_matches("./"));
+            let value = touches.get(&key).copied()?;
+            Some(FileHotspot {
+                file: file.to_string(),
+                module: module.to_string(),
+                risk: risk.to_string(),
+                value,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.file.cmp(&b.file)));
+    ranked.truncate(STORED_HOTSPOTS);
+    ranked
+}
+
+fn risk_rank(risk: &str) -> u8 {
+    match risk {
+        "high" => 2,
+        "medium" => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::FrameworkEntryPoint;
+
+    fn entry(symbol: &str, hash: &str, file: &str, lines: &str, risk: &str) -> SymbolIndexEntry {
+        SymbolIndexEntry {
+            symbol: symbol.to_string(),
+            hash: hash.to_string(),
+            semantic_hash: String::new(),
+            kind: "function".to_string(),
+            module: "billing".to_string(),
+            file: file.to_string(),
+            lines: lines.to_string(),
+            risk: risk.to_string(),
+            cognitive_complexity: 0,
+            max_nesting: 0,
+            is_escape_local: false,
+            framework_entry_point: FrameworkEntryPoint::None,
+            is_exported: true,
+            decorators: String::new(),
+            arity: 0,
+            is_async: false,
+            return_type: String::new(),
+            ext_package: String::new(),
+            base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
+        }
+    }
+
+    fn fixture() -> (Vec<SymbolIndexEntry>, HashMap<String, Vec<String>>) {
+        let mut counter = entry("counter", "h5", "src/billing/state.ts", "1-1", "low");
+        counter.kind = "variable".to_string();
+        let mut encode = entry("encode", "h6", "src/billing/billing_pb.js", "1-500", "high");
+        encode.is_generated = true;
+        let entries = vec![
+            entry("charge", "h1", "src/billing/charge.ts", "10-89", "high"),
+            entry("refund", "h2", "src/billing/refund.ts", "1-30", "medium"),
+            entry("format", "h3", "src/billing/format.ts", "5-9", "low"),
+            entry(
+                "testCharge",
+                "h4",
+                "src/billing/charge.test.ts",
+                "1-200",
+                "low",
+            ),
+            counter,
+            encode,
+        ];
+        let graph = HashMap::from([
+            (
+                "h1".to_string(),
+                vec!["\"h3\"".to_string(), "\"h5:read\"".to_string()],
+            ),
+            (
+                "h2".to_string(),
+                vec![
+                    "\"h3\"".to_string(),
+                    "\"h1\"".to_string(),
+                    "\"ext:stripe\"".to_string(),
+                ],
+            ),
+            (
+                "h4".to_string(),
+                vec![
+                    "\"h1\"".to_string(),
+                    "\"h3\"".to_string(),
+                    "\"h3\"".to_string(),
+                ],
+            ),
+            ("h5".to_string(), vec!["\"h5\"".to_string()]),
+        ]);
+        (entries, graph)
+    }
+
+    fn names(hotspots: &[SymbolHotspot]) -> Vec<(&str, usize)> {
+        hotspots
+            .iter()
+            .map(|h| (h.symbol.as_str(), h.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_largest_ranks_by_line_count() {
+        let (entries, graph) = fixture();
+        let index = HotspotIndex::build(&entries, &graph, None, 0, false);
+
+        // Test files, generated code and variables are left out
+        assert_eq!(
+            names(&index.largest),
+            vec![("charge", 80), ("refund", 30), ("format", 5)]
+        );
+        assert_eq!(index.largest[0].risk, "high");
+        assert_eq!(index.largest[0].file, "src/billing/charge.ts");
+    }
+
+    #[test]
+    fn test_generated_code_ranked_on_request() {
+        let (entries, graph) = fixture();
+        let index = HotspotIndex::build(&entries, &graph, None, 0, true);
+
+        assert_eq!(index.largest[0].symbol, "encode");
+        assert_eq!(index.largest.len(), 4);
+    }
+
+    #[test]
+    fn test_most_called_counts_distinct_callers() {
+        let (entries, graph) = fixture();
+        let index = HotspotIndex::build(&entries, &graph, None, 0, false);
+
+        // Reads, external calls and self-calls are not fan-in; callers from
+        // test files still count
+        assert_eq!(
+            names(&index.most_called),
+            vec![("format", 3), ("charge", 2)]
+        );
+    }
+
+    #[test]
+    fn test_churn_skipped_without_git() {
+        let (entries, graph) = fixture();
+        let dir = tempfile::TempDir::new().unwrap();
+        let index = HotspotIndex::build(&entries, &graph, Some(dir.path()), 100, false);
+
+        assert!(index.most_changed.is_empty());
+        assert_eq!(index.churn_window, None);
+    }
+
+    #[test]
+    fn test_top_files_matches_index_paths_to_log_paths() {
+        let (entries, _) = fixture();
+        let mut entries = entries;
+        entries[1].file = "/repo/src/billing/refund.ts".to_string();
+        entries[2].file = "./src/billing/format.ts".to_string();
+        let ranked: Vec<&SymbolIndexEntry> = entries.iter().collect();
+        let touches = parse_name_only_log(
+            "src/billing/format.ts\nsrc/billing/refund.ts\n\nsrc/billing/format.ts\nREADME.md\n",
+        );
+
+        let files = top_files(&ranked, Path::new("/repo"), &touches);
+        let got: Vec<(&str, usize)> = files.iter().map(|f| (f.file.as_str(), f.value)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("./src/billing/format.ts", 2),
+                ("/repo/src/billing/refund.ts", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_risk_is_highest_symbol_risk() {
+        let entries = [
+            entry("a", "h1", "src/a.ts", "1-2", "low"),
+            entry("b", "h2", "src/a.ts", "3-4", "high"),
+        ];
+        let ranked: Vec<&SymbolIndexEntry> = entries.iter().collect();
+        let touches = parse_name_only_log("src/a.ts\n");
+
+        let files = top_files(&ranked, Path::new("/repo"), &touches);
+        assert_eq!(files[0].risk, "high");
+    }
+
+    #[test]
+    fn test_parse_churn_commits() {
+        assert_eq!(parse_churn_commits(None), DEFAULT_CHURN_COMMITS);
+        assert_eq!(parse_churn_commits(Some(" 50 ")), 50);
+        assert_eq!(parse_churn_commits(Some("0")), 0);
+        assert_eq!(parse_churn_commits(Some("lots")), DEFAULT_CHURN_COMMITS);
+    }
+
+    #[test]
+    fn test_line_count() {
+        assert_eq!(line_count("10-19"), 10);
+        assert_eq!(line_count("7"), 1);
+        assert_eq!(line_count(""), 0);
+    }
+}
@@ -0,0 +1,368 @@
+//! Resumable checkpoints for time-boxed index generation
+//!
+//! When index generation is bounded by a deadline, every processed batch of
+//! files is persisted to the cache's `checkpoint/` directory so that a later
+//! run can pick up where the previous one stopped instead of starting over.
+//!
+//! # Crash Safety
+//!
+//! Each batch is written to a `.tmp` file and atomically renamed into place,
+//! so a batch is either fully recorded or not recorded at all. Leftover
+//! `.tmp` files from an interrupted write are ignored on load. Batches are
+//! replayed in order and keyed by file path, so a file recorded twice (e.g.
+//! reprocessed after it changed) is never counted twice, and a file whose
+//! mtime or size no longer matches its record is treated as unprocessed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::SourceFileInfo;
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+use crate::schema::{Call, Location, RefKind};
+use crate::SemanticSummary;
+
+/// Call fields that are skipped by the summary's serde representation.
+///
+/// They are needed to rebuild identical call graphs, so the checkpoint stores
+/// them alongside each summary.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CallExtras {
+    is_hook: bool,
+    is_io: bool,
+    ref_kind: RefKind,
+    location: Location,
+}
+
+impl CallExtras {
+    fn capture(call: &Call) -> Self {
+        Self {
+            is_hook: call.is_hook,
+            is_io: call.is_io,
+            ref_kind: call.ref_kind,
+            location: call.location.clone(),
+        }
+    }
+
+    fn restore(self, call: &mut Call) {
+        call.is_hook = self.is_hook;
+        call.is_io = self.is_io;
+        call.ref_kind = self.ref_kind;
+        call.location = self.location;
+    }
+}
+
+/// A checkpointed summary together with the fields serde skips
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointEntry {
+    summary: SemanticSummary,
+    extraction_complete: bool,
+    calls: Vec<CallExtras>,
+    symbol_calls: Vec<Vec<CallExtras>>,
+}
+
+impl CheckpointEntry {
+    fn capture(summary: &SemanticSummary) -> Self {
+        Self {
+            summary: summary.clone(),
+            extraction_complete: summary.extraction_complete,
+            calls: summary.calls.iter().map(CallExtras::capture).collect(),
+            symbol_calls: summary
+                .symbols
+                .iter()
+                .map(|s| s.calls.iter().map(CallExtras::capture).collect())
+                .collect(),
+        }
+    }
+
+    fn restore(self) -> SemanticSummary {
+        let mut summary = self.summary;
+        summary.extraction_complete = self.extraction_complete;
+        for (call, extras) in summary.calls.iter_mut().zip(self.calls) {
+            extras.restore(call);
+        }
+        for (symbol, calls) in summary.symbols.iter_mut().zip(self.symbol_calls) {
+            for (call, extras) in symbol.calls.iter_mut().zip(calls) {
+                extras.restore(call);
+            }
+        }
+        summary
+    }
+}
+
+/// One persisted batch of processed files
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointBatch {
+    /// Every file attempted in this batch, including ones that failed to parse
+    files: Vec<SourceFileInfo>,
+    /// Summaries for the files that parsed successfully
+    entries: Vec<CheckpointEntry>,
+}
+
+/// Files processed so far by an interrupted index generation
+pub struct IndexCheckpoint {
+    dir: PathBuf,
+    repo_root: PathBuf,
+    next_batch: usize,
+    /// Processed files keyed by path relative to the repo root
+    files: HashMap<String, SourceFileInfo>,
+    /// Summaries keyed by path relative to the repo root
+    summaries: HashMap<String, SemanticSummary>,
+}
+
+impl IndexCheckpoint {
+    /// Open the checkpoint in `dir`, replaying every completed batch.
+    pub fn open(dir: &Path, repo_root: &Path) -> Self {
+        let mut checkpoint = Self {
+            dir: dir.to_path_buf(),
+            repo_root: repo_root.to_path_buf(),
+            next_batch: 0,
+            files: HashMap::new(),
+            summaries: HashMap::new(),
+        };
+
+        let mut batches: Vec<(usize, PathBuf)> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let name = path.file_name()?.to_str()?;
+                let index = name
+                    .strip_prefix("batch-")?
+                    .strip_suffix(".json")?
+                    .parse()
+                    .ok()?;
+                Some((index, path))
+            })
+            .collect();
+        batches.sort();
+
+        for (index, path) in batches {
+            let Some(batch) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<CheckpointBatch>(&content).ok())
+            else {
+                continue;
+            };
+            checkpoint.apply(batch);
+            checkpoint.next_batch = index + 1;
+        }
+
+        checkpoint
+    }
+
+    /// Whether the checkpoint holds any processed files
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Number of distinct files recorded as processed
+    pub fn processed_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether `path` was processed and has not changed since
+    pub fn is_processed(&self, path: &Path) -> bool {
+        self.files
+            .get(&self.relative_key(path))
+            .is_some_and(|info| !info.is_stale(&self.repo_root))
+    }
+
+    /// Total source bytes of the recorded files
+    pub fn source_bytes(&self) -> usize {
+        self.files.values().map(|info| info.size as usize).sum()
+    }
+
+    /// Persist a processed batch of files and their summaries.
+    ///
+    /// `files` must list every file attempted in the batch so that files
+    /// which failed to parse are not retried on resume.
+    pub fn record_batch(&mut self, files: &[PathBuf], summaries: &[SemanticSummary]) -> Result<()> {
+        let batch = CheckpointBatch {
+            files: files
+                .iter()
+                .filter_map(|f| SourceFileInfo::from_path(f, &self.repo_root))
+                .collect(),
+            entries: summaries.iter().map(CheckpointEntry::capture).collect(),
+        };
+
+        let content =
+            serde_json::to_string(&batch).map_err(|e| McpDiffError::ExtractionFailure {
+                message: format!("Failed to serialize index checkpoint: {}", e),
+            })?;
+
+        fs::create_dir_all(&self.dir)?;
+        let name = format!("batch-{:06}.json", self.next_batch);
+        let temp = self.dir.join(format!("{}.tmp", name));
+        fs::write(&temp, content)?;
+        fs_utils::atomic_rename(&temp, &self.dir.join(name))?;
+
+        self.next_batch += 1;
+        self.apply(batch);
+        Ok(())
+    }
+
+    /// Summaries for the given files that are recorded and still fresh,
+    /// in the order of `files`.
+    pub fn summaries_for(&self, files: &[PathBuf]) -> Vec<SemanticSummary> {
+        files
+            .iter()
+            .filter(|f| self.is_processed(f))
+            .filter_map(|f| self.summaries.get(&self.relative_key(f)).cloned())
+            .collect()
+    }
+
+    /// Remove the checkpoint once generation has completed.
+    pub fn clear(&mut self) -> Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        self.next_batch = 0;
+        self.files.clear();
+        self.summaries.clear();
+        Ok(())
+    }
+
+    fn apply(&mut self, batch: CheckpointBatch) {
+        for info in batch.files {
+            // A file reprocessed in a later batch may have stopped parsing
+            self.summaries.remove(&info.path);
+            self.files.insert(info.path.clone(), info);
+        }
+        for entry in batch.entries {
+            let key = self.relative_key(Path::new(&entry.summary.file));
+            self.summaries.insert(key, entry.restore());
+        }
+    }
+
+    fn relative_key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.repo_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_file(root: &Path, name: &str, content: &str) -> PathBuf {
+        let path = root.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn summary_for(path: &Path) -> SemanticSummary {
+        let mut summary = SemanticSummary {
+            file: path.display().to_string(),
+            ..Default::default()
+        };
+        summary.calls.push(Call {
+            name: "helper".to_string(),
+            is_io: true,
+            ref_kind: RefKind::Read,
+            ..Default::default()
+        });
+        summary
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip_restores_skipped_fields() {
+        let repo = TempDir::new().unwrap();
+        let dir = repo.path().join("checkpoint");
+        let a = write_file(repo.path(), "a.rs", "fn a() {}");
+        let b = write_file(repo.path(), "b.rs", "fn b(");
+
+        let mut checkpoint = IndexCheckpoint::open(&dir, repo.path());
+        assert!(checkpoint.is_empty());
+        // b.rs "failed to parse": attempted but no summary
+        checkpoint
+            .record_batch(&[a.clone(), b.clone()], &[summary_for(&a)])
+            .unwrap();
+
+        let reopened = IndexCheckpoint::open(&dir, repo.path());
+        assert_eq!(reopened.processed_count(), 2);
+        assert!(reopened.is_processed(&a));
+        assert!(reopened.is_processed(&b));
+
+        let summaries = reopened.summaries_for(&[a, b]);
+        assert_eq!(summaries.len(), 1);
+        assert!(summaries[0].calls[0].is_io);
+        assert_eq!(summaries[0].calls[0].ref_kind, RefKind::Read);
+    }
+
+    #[test]
+    fn test_checkpoint_ignores_interrupted_batch_write() {
+        let repo = TempDir::new().unwrap();
+        let dir = repo.path().join("checkpoint");
+        let a = write_file(repo.path(), "a.rs", "fn a() {}");
+
+        let mut checkpoint = IndexCheckpoint::open(&dir, repo.path());
+        checkpoint
+            .record_batch(std::slice::from_ref(&a), &[summary_for(&a)])
+            .unwrap();
+
+        // Simulate a crash in the middle of writing the next batch
+        fs::write(dir.join("batch-000001.json.tmp"), "{\"files\":[{\"pa").unwrap();
+
+        let mut reopened = IndexCheckpoint::open(&dir, repo.path());
+        assert_eq!(reopened.processed_count(), 1);
+
+        // The next batch reuses the slot of the interrupted write
+        let b = write_file(repo.path(), "b.rs", "fn b() {}");
+        reopened
+            .record_batch(std::slice::from_ref(&b), &[summary_for(&b)])
+            .unwrap();
+        assert!(dir.join("batch-000001.json").exists());
+        assert_eq!(
+            IndexCheckpoint::open(&dir, repo.path()).processed_count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_does_not_double_count_reprocessed_files() {
+        let repo = TempDir::new().unwrap();
+        let dir = repo.path().join("checkpoint");
+        let a = write_file(repo.path(), "a.rs", "fn a() {}");
+
+        let mut checkpoint = IndexCheckpoint::open(&dir, repo.path());
+        checkpoint
+            .record_batch(std::slice::from_ref(&a), &[summary_for(&a)])
+            .unwrap();
+
+        // The file changes and is processed again in a later batch
+        write_file(repo.path(), "a.rs", "fn a() { changed(); }");
+        assert!(!checkpoint.is_processed(&a));
+        checkpoint
+            .record_batch(std::slice::from_ref(&a), &[summary_for(&a)])
+            .unwrap();
+
+        let reopened = IndexCheckpoint::open(&dir, repo.path());
+        assert_eq!(reopened.processed_count(), 1);
+        assert!(reopened.is_processed(&a));
+        assert_eq!(reopened.summaries_for(&[a]).len(), 1);
+    }
+
+    #[test]
+    fn test_checkpoint_clear() {
+        let repo = TempDir::new().unwrap();
+        let dir = repo.path().join("checkpoint");
+        let a = write_file(repo.path(), "a.rs", "fn a() {}");
+
+        let mut checkpoint = IndexCheckpoint::open(&dir, repo.path());
+        checkpoint
+            .record_batch(std::slice::from_ref(&a), &[summary_for(&a)])
+            .unwrap();
+        checkpoint.clear().unwrap();
+
+        assert!(!dir.exists());
+        assert!(IndexCheckpoint::open(&dir, repo.path()).is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides functions for recursively collecting source files
 //! from a directory, with filtering by extension and language support.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -92,6 +93,45 @@ pub fn should_skip_path(path: &Path) -> bool {
     }
 }
 
+/// File stems that mark a file as an entry point
+const ENTRY_POINT_STEMS: &[&str] = &[
+    "main", "index", "lib", "app", "server", "program", "__main__", "__init__",
+];
+
+/// Whether a file looks like an entry point (main.rs, index.ts, app.py, ...)
+fn is_entry_point(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|stem| ENTRY_POINT_STEMS.contains(&stem.to_ascii_lowercase().as_str()))
+}
+
+/// Order files so the most useful modules are indexed first.
+///
+/// Files are grouped by module (parent directory). Modules containing an
+/// entry point come first, then modules with more files; within a module,
+/// entry points lead. The sort is stable, so ties keep collection order.
+pub fn prioritize_files(files: &[PathBuf]) -> Vec<PathBuf> {
+    let mut module_sizes: HashMap<&Path, usize> = HashMap::new();
+    let mut module_entry: HashMap<&Path, bool> = HashMap::new();
+    for file in files {
+        let module = file.parent().unwrap_or(Path::new(""));
+        *module_sizes.entry(module).or_insert(0) += 1;
+        *module_entry.entry(module).or_insert(false) |= is_entry_point(file);
+    }
+
+    let mut ordered: Vec<&PathBuf> = files.iter().collect();
+    ordered.sort_by(|a, b| {
+        let ma = a.parent().unwrap_or(Path::new(""));
+        let mb = b.parent().unwrap_or(Path::new(""));
+        module_entry[mb]
+            .cmp(&module_entry[ma])
+            .then(module_sizes[mb].cmp(&module_sizes[ma]))
+            .then(ma.cmp(mb))
+            .then(is_entry_point(b).cmp(&is_entry_point(a)))
+    });
+    ordered.into_iter().cloned().collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +169,33 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_prioritize_files_entry_points_then_file_count() {
+        let files: Vec<PathBuf> = [
+            "repo/src/utils/a.rs",
+            "repo/src/big/x.rs",
+            "repo/src/big/y.rs",
+            "repo/src/big/z.rs",
+            "repo/src/helper.rs",
+            "repo/src/main.rs",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+
+        let ordered = prioritize_files(&files);
+        let names: Vec<&str> = ordered.iter().map(|p| p.to_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "repo/src/main.rs",
+                "repo/src/helper.rs",
+                "repo/src/big/x.rs",
+                "repo/src/big/y.rs",
+                "repo/src/big/z.rs",
+                "repo/src/utils/a.rs",
+            ]
+        );
+    }
 }
@@ -9,8 +9,8 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rayon::prelude::*;
 
-use crate::parsing::parse_and_extract;
-use crate::{Lang, SemanticSummary};
+use crate::parsing::parse_and_extract_with_level;
+use crate::{ExtractionLevel, Lang, SemanticSummary};
 
 /// Progress callback type for index generation.
 ///
@@ -69,6 +69,19 @@ pub fn analyze_files_parallel(
     files: &[PathBuf],
     progress: Option<IndexingProgressCallback>,
     verbose: bool,
+) -> IndexGenerationResult {
+    analyze_files_parallel_with_level(files, progress, verbose, ExtractionLevel::Full)
+}
+
+/// Analyze files in parallel at the given extraction level.
+///
+/// Same as [`analyze_files_parallel`], but `ExtractionLevel::Signatures`
+/// produces lightweight summaries (names, kinds, signatures) much faster.
+pub fn analyze_files_parallel_with_level(
+    files: &[PathBuf],
+    progress: Option<IndexingProgressCallback>,
+    verbose: bool,
+    level: ExtractionLevel,
 ) -> IndexGenerationResult {
     let total = files.len();
     let processed = AtomicUsize::new(0);
@@ -113,7 +126,7 @@ pub fn analyze_files_parallel(
             total_bytes.fetch_add(source.len(), Ordering::Relaxed);
 
             // Parse and extract semantic summary
-            match parse_and_extract(file_path, &source, lang) {
+            match parse_and_extract_with_level(file_path, &source, lang, level) {
                 Ok(summary) => Some(summary),
                 Err(e) => {
                     errors.fetch_add(1, Ordering::Relaxed);
@@ -8,6 +8,7 @@
 //! - **Parallel Processing**: Uses Rayon for multi-threaded file analysis
 //! - **Progress Reporting**: Optional callback for progress updates
 //! - **Error Handling**: Collects errors without stopping the entire operation
+//! - **Checkpointing**: Time-boxed generation can resume from processed batches
 //!
 //! # Example
 //!
@@ -20,11 +21,13 @@
 //! println!("Analyzed {} files, {} errors", result.summaries.len(), result.errors);
 //! ```
 
+mod checkpoint;
 mod files;
 mod generation;
 
-pub use files::{collect_files, collect_files_recursive, should_skip_path};
+pub use checkpoint::IndexCheckpoint;
+pub use files::{collect_files, collect_files_recursive, prioritize_files, should_skip_path};
 pub use generation::{
-    analyze_files_parallel, analyze_files_with_stats, IndexGenerationResult,
-    IndexingProgressCallback,
+    analyze_files_parallel, analyze_files_parallel_with_level, analyze_files_with_stats,
+    IndexGenerationResult, IndexingProgressCallback,
 };
@@ -7,6 +7,7 @@
 use crate::error::McpDiffError;
 use crate::fs_utils;
 use crate::installer::platform::{Platform, SemforaPaths};
+use crate::settings::{DuplicatesSettings, IndexSettings};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -29,6 +30,18 @@ pub struct SemforaConfig {
     /// Security pattern settings
     #[serde(default)]
     pub patterns: PatternConfig,
+
+    /// Semantic search settings
+    #[serde(default)]
+    pub search: SearchConfig,
+
+    /// Duplicate detection defaults (see [`crate::settings`])
+    #[serde(default, skip_serializing_if = "DuplicatesSettings::is_empty")]
+    pub duplicates: DuplicatesSettings,
+
+    /// Indexing defaults (see [`crate::settings`])
+    #[serde(default, skip_serializing_if = "IndexSettings::is_empty")]
+    pub index: IndexSettings,
 }
 
 /// Cache configuration
@@ -73,6 +86,22 @@ pub struct McpConfig {
     pub configured_clients: Vec<String>,
 }
 
+/// Semantic search configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SearchConfig {
+    /// Extra words to drop from BM25 terms
+    #[serde(default)]
+    pub stopwords_add: Vec<String>,
+
+    /// Built-in stopwords to keep as searchable terms
+    #[serde(default)]
+    pub stopwords_remove: Vec<String>,
+
+    /// Default number of search results (see [`crate::settings`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
 /// Security pattern configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternConfig {
@@ -161,6 +190,8 @@ impl SemforaConfig {
             ["logging", "level"] => Some(self.logging.level.clone()),
             ["patterns", "url"] => Some(self.patterns.url.clone()),
             ["mcp", "configured_clients"] => Some(self.mcp.configured_clients.join(", ")),
+            ["search", "stopwords_add"] => Some(self.search.stopwords_add.join(", ")),
+            ["search", "stopwords_remove"] => Some(self.search.stopwords_remove.join(", ")),
             _ => None,
         }
     }
@@ -190,6 +221,12 @@ impl SemforaConfig {
             ["patterns", "url"] => {
                 self.patterns.url = value.to_string();
             }
+            ["search", "stopwords_add"] => {
+                self.search.stopwords_add = split_word_list(value);
+            }
+            ["search", "stopwords_remove"] => {
+                self.search.stopwords_remove = split_word_list(value);
+            }
             _ => {
                 return Err(McpDiffError::ConfigError {
                     message: format!("Unknown configuration key: {}", key),
@@ -231,10 +268,29 @@ impl SemforaConfig {
         output.push_str("\n[patterns]\n");
         output.push_str(&format!("url = \"{}\"\n", self.patterns.url));
 
+        output.push_str("\n[search]\n");
+        output.push_str(&format!(
+            "stopwords_add = {:?}\n",
+            self.search.stopwords_add
+        ));
+        output.push_str(&format!(
+            "stopwords_remove = {:?}\n",
+            self.search.stopwords_remove
+        ));
+
         output
     }
 }
 
+/// Split a comma-separated `config set` value into words
+fn split_word_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
 /// Show current configuration
 pub fn show_config() -> Result<(), McpDiffError> {
     let config = SemforaConfig::load()?;
@@ -295,4 +351,47 @@ mod tests {
         let loaded = SemforaConfig::load_from(&config_path).unwrap();
         assert_eq!(loaded.logging.level, "debug");
     }
+
+    #[test]
+    fn test_search_stopwords_config() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "[search]\nstopwords_add = [\"handler\"]\nstopwords_remove = [\"get\"]\n",
+        )
+        .unwrap();
+
+        let mut config = SemforaConfig::load_from(&config_path).unwrap();
+        assert_eq!(config.search.stopwords_add, vec!["handler"]);
+        assert_eq!(config.search.stopwords_remove, vec!["get"]);
+
+        config.set("search.stopwords_add", "util, helper").unwrap();
+        assert_eq!(
+            config.get("search.stopwords_add"),
+            Some("util, helper".to_string())
+        );
+    }
+
+    #[test]
+    fn test_analysis_defaults_survive_save() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "[duplicates]\nthreshold = 0.8\n\n[index]\nexclude = [\"vendor\"]\n",
+        )
+        .unwrap();
+
+        let mut config = SemforaConfig::load_from(&config_path).unwrap();
+        config.set("logging.level", "debug").unwrap();
+        config.save_to(&config_path).unwrap();
+
+        let loaded = SemforaConfig::load_from(&config_path).unwrap();
+        assert_eq!(loaded.duplicates.threshold, Some(0.8));
+        assert_eq!(loaded.index.exclude, Some(vec!["vendor".to_string()]));
+        assert!(!fs::read_to_string(&config_path)
+            .unwrap()
+            .contains("include_boilerplate"));
+    }
 }
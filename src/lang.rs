@@ -22,6 +22,8 @@ pub enum Lang {
     /// C# (.NET)
     CSharp,
     Kotlin,
+    /// PHP (.php, .phtml)
+    Php,
     Html,
     Css,
     Scss,
@@ -39,6 +41,11 @@ pub enum Lang {
     Gradle,
     /// Dockerfile container definitions
     Dockerfile,
+    /// Protocol Buffers service contracts (.proto)
+    Protobuf,
+    /// GraphQL schemas and operations (.graphql, .gql); recognized, not
+    /// yet parsed
+    GraphQL,
 }
 
 impl Lang {
@@ -65,7 +72,8 @@ impl Lang {
     pub fn from_extension(ext: &str) -> Result<Self> {
         match ext.to_lowercase().as_str() {
             "ts" | "mts" | "cts" => Ok(Self::TypeScript),
-            "tsx" => Ok(Self::Tsx),
+            // MDX is Markdown with embedded JSX
+            "tsx" | "mdx" => Ok(Self::Tsx),
             "js" | "mjs" | "cjs" => Ok(Self::JavaScript),
             "jsx" => Ok(Self::Jsx),
             "rs" => Ok(Self::Rust),
@@ -76,6 +84,7 @@ impl Lang {
             "cpp" | "cc" | "cxx" | "hpp" | "hxx" | "hh" => Ok(Self::Cpp),
             "cs" => Ok(Self::CSharp),
             "kt" | "kts" => Ok(Self::Kotlin),
+            "php" | "phtml" => Ok(Self::Php),
             "html" | "htm" => Ok(Self::Html),
             "css" => Ok(Self::Css),
             "scss" | "sass" => Ok(Self::Scss),
@@ -89,6 +98,8 @@ impl Lang {
             "sh" | "bash" | "zsh" | "fish" => Ok(Self::Bash),
             "gradle" => Ok(Self::Gradle),
             "dockerfile" => Ok(Self::Dockerfile),
+            "proto" => Ok(Self::Protobuf),
+            "graphql" | "gql" => Ok(Self::GraphQL),
             _ => Err(McpDiffError::UnsupportedLanguage {
                 extension: ext.to_string(),
             }),
@@ -119,6 +130,7 @@ impl Lang {
             Self::Cpp => "cpp",
             Self::CSharp => "csharp",
             Self::Kotlin => "kotlin",
+            Self::Php => "php",
             Self::Html => "html",
             Self::Css => "css",
             Self::Scss => "scss",
@@ -132,6 +144,8 @@ impl Lang {
             Self::Bash => "bash",
             Self::Gradle => "gradle",
             Self::Dockerfile => "dockerfile",
+            Self::Protobuf => "protobuf",
+            Self::GraphQL => "graphql",
         }
     }
 
@@ -152,6 +166,8 @@ impl Lang {
             Self::Cpp => tree_sitter_cpp::LANGUAGE.into(),
             Self::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
             Self::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
+            // Includes the HTML around `<?php ... ?>` blocks (templates, .phtml)
+            Self::Php => tree_sitter_php::LANGUAGE_PHP.into(),
             Self::Html | Self::Vue => tree_sitter_html::LANGUAGE.into(),
             Self::Css => tree_sitter_css::language().into(),
             Self::Scss => tree_sitter_scss::language().into(),
@@ -167,6 +183,12 @@ impl Lang {
             // is updated to tree-sitter 0.25. The actual parsing is text-based in
             // detectors/dockerfile.rs
             Self::Dockerfile => tree_sitter_bash::LANGUAGE.into(),
+            // No protobuf grammar supports tree-sitter 0.25 yet; parsing is
+            // text-based in detectors/protobuf.rs
+            Self::Protobuf => tree_sitter_bash::LANGUAGE.into(),
+            // No GraphQL grammar supports tree-sitter 0.25 yet; files are
+            // recognized but nothing is extracted
+            Self::GraphQL => tree_sitter_bash::LANGUAGE.into(),
         }
     }
 
@@ -182,6 +204,7 @@ impl Lang {
             Self::Java => LangFamily::Java,
             Self::CSharp => LangFamily::CSharp,
             Self::Kotlin => LangFamily::Kotlin,
+            Self::Php => LangFamily::Php,
             Self::C | Self::Cpp => LangFamily::CFamily,
             Self::Html | Self::Css | Self::Scss | Self::Markdown => LangFamily::Markup,
             Self::Json | Self::Yaml | Self::Toml | Self::Xml => LangFamily::Config,
@@ -189,6 +212,8 @@ impl Lang {
             Self::Bash => LangFamily::Shell,
             Self::Gradle => LangFamily::Gradle,
             Self::Dockerfile => LangFamily::Dockerfile,
+            Self::Protobuf => LangFamily::Protobuf,
+            Self::GraphQL => LangFamily::GraphQL,
         }
     }
 
@@ -208,6 +233,7 @@ impl Lang {
                 | LangFamily::Java
                 | LangFamily::CSharp
                 | LangFamily::Kotlin
+                | LangFamily::Php
                 | LangFamily::CFamily
                 | LangFamily::Shell
                 | LangFamily::Gradle
@@ -218,7 +244,7 @@ impl Lang {
     pub fn extensions(&self) -> &'static [&'static str] {
         match self {
             Self::TypeScript => &["ts", "mts", "cts"],
-            Self::Tsx => &["tsx"],
+            Self::Tsx => &["tsx", "mdx"],
             Self::JavaScript => &["js", "mjs", "cjs"],
             Self::Jsx => &["jsx"],
             Self::Rust => &["rs"],
@@ -229,6 +255,7 @@ impl Lang {
             Self::Cpp => &["cpp", "cc", "cxx", "hpp", "hxx", "hh"],
             Self::CSharp => &["cs"],
             Self::Kotlin => &["kt", "kts"],
+            Self::Php => &["php", "phtml"],
             Self::Html => &["html", "htm"],
             Self::Css => &["css"],
             Self::Scss => &["scss", "sass"],
@@ -242,6 +269,8 @@ impl Lang {
             Self::Bash => &["sh", "bash", "zsh", "fish"],
             Self::Gradle => &["gradle"],
             Self::Dockerfile => &["dockerfile"],
+            Self::Protobuf => &["proto"],
+            Self::GraphQL => &["graphql", "gql"],
         }
     }
 
@@ -251,6 +280,35 @@ impl Lang {
     }
 }
 
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    /// Parse a language name (`rust`, `typescript`), a common alias
+    /// (`golang`, `shell`, `terraform`) or an extension (`rs`, `ts`)
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        let wanted = name.trim().trim_start_matches('.').to_lowercase();
+        match wanted.as_str() {
+            "typescript" => Ok(Self::TypeScript),
+            "javascript" => Ok(Self::JavaScript),
+            "rust" => Ok(Self::Rust),
+            "python" => Ok(Self::Python),
+            "golang" => Ok(Self::Go),
+            "c++" => Ok(Self::Cpp),
+            "csharp" | "c#" => Ok(Self::CSharp),
+            "kotlin" => Ok(Self::Kotlin),
+            "terraform" => Ok(Self::Hcl),
+            "shell" => Ok(Self::Bash),
+            "protobuf" => Ok(Self::Protobuf),
+            other => Self::from_extension(other).map_err(|_| {
+                format!(
+                    "unknown language `{}` (use a name like rust or typescript, or an extension like rs or ts)",
+                    name.trim()
+                )
+            }),
+        }
+    }
+}
+
 /// Language families for grouping similar extraction logic
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LangFamily {
@@ -268,6 +326,8 @@ pub enum LangFamily {
     CSharp,
     /// Kotlin
     Kotlin,
+    /// PHP
+    Php,
     /// C and C++
     CFamily,
     /// HTML, CSS, SCSS, Markdown
@@ -282,6 +342,10 @@ pub enum LangFamily {
     Gradle,
     /// Dockerfile/Containerfile
     Dockerfile,
+    /// Protocol Buffers
+    Protobuf,
+    /// GraphQL
+    GraphQL,
 }
 
 impl LangFamily {
@@ -295,6 +359,7 @@ impl LangFamily {
             Self::Java => "java",
             Self::CSharp => "csharp",
             Self::Kotlin => "kotlin",
+            Self::Php => "php",
             Self::CFamily => "c_family",
             Self::Markup => "markup",
             Self::Config => "config",
@@ -302,6 +367,8 @@ impl LangFamily {
             Self::Shell => "shell",
             Self::Gradle => "gradle",
             Self::Dockerfile => "dockerfile",
+            Self::Protobuf => "protobuf",
+            Self::GraphQL => "graphql",
         }
     }
 }
@@ -330,6 +397,55 @@ mod tests {
         assert_eq!(Lang::from_extension("toml").unwrap(), Lang::Toml);
         assert_eq!(Lang::from_extension("md").unwrap(), Lang::Markdown);
         assert_eq!(Lang::from_extension("vue").unwrap(), Lang::Vue);
+        assert_eq!(Lang::from_extension("proto").unwrap(), Lang::Protobuf);
+        assert_eq!(Lang::from_extension("php").unwrap(), Lang::Php);
+        assert_eq!(Lang::from_extension("phtml").unwrap(), Lang::Php);
+    }
+
+    #[test]
+    fn test_lang_from_str() {
+        for (name, lang) in [
+            ("rust", Lang::Rust),
+            ("RS", Lang::Rust),
+            ("typescript", Lang::TypeScript),
+            (".ts", Lang::TypeScript),
+            ("golang", Lang::Go),
+            ("go", Lang::Go),
+            ("terraform", Lang::Hcl),
+            ("shell", Lang::Bash),
+            ("markdown", Lang::Markdown),
+        ] {
+            assert_eq!(name.parse::<Lang>().unwrap(), lang, "{}", name);
+        }
+        let err = "cobol".parse::<Lang>().unwrap_err();
+        assert!(err.contains("unknown language `cobol`"), "{}", err);
+    }
+
+    #[test]
+    fn test_module_variants_mdx_and_graphql() {
+        for (ext, lang) in [
+            ("mts", Lang::TypeScript),
+            ("cts", Lang::TypeScript),
+            ("mjs", Lang::JavaScript),
+            ("cjs", Lang::JavaScript),
+            ("mdx", Lang::Tsx),
+            ("graphql", Lang::GraphQL),
+            ("GQL", Lang::GraphQL),
+        ] {
+            assert_eq!(Lang::from_extension(ext).unwrap(), lang, "{}", ext);
+        }
+        // Solidity and Svelte have no grammar
+        assert!(Lang::from_extension("sol").is_err());
+        assert!(Lang::from_extension("svelte").is_err());
+
+        // Every listed extension maps back to its language
+        for lang in [Lang::Tsx, Lang::GraphQL] {
+            for ext in lang.extensions() {
+                assert_eq!(Lang::from_extension(ext).unwrap(), lang);
+            }
+        }
+        assert_eq!(Lang::GraphQL.family().name(), "graphql");
+        assert!(!Lang::GraphQL.is_programming_language());
     }
 
     #[test]
@@ -367,6 +483,7 @@ mod tests {
         assert_eq!(Lang::Cpp.family(), LangFamily::CFamily);
         assert_eq!(Lang::Json.family(), LangFamily::Config);
         assert_eq!(Lang::Html.family(), LangFamily::Markup);
+        assert_eq!(Lang::Php.family(), LangFamily::Php);
     }
 
     #[test]
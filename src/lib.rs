@@ -94,60 +94,82 @@
 //! println!("{}", toon);
 //! ```
 
+pub mod ai_layer;
 pub mod analysis;
 pub mod benchmark;
 pub mod benchmark_builder;
 pub mod bm25;
 pub mod cache;
 pub mod cli;
+pub mod cli_schema;
 pub mod commands;
+pub mod components;
+pub mod contracts;
 pub mod detectors;
+pub mod diff_warnings;
 pub mod drift;
 pub mod duplicate;
 pub mod error;
 pub mod extract;
 pub mod fs_utils;
+pub mod generated;
 pub mod git;
+pub mod hotspots;
 pub mod indexing;
 pub mod installer;
 pub mod lang;
+pub mod line_symbols;
 pub mod lint;
 pub mod mcp_server;
 pub mod module_registry;
+pub mod module_usage;
 pub mod overlay;
+pub mod ownership;
 pub mod parsing;
 pub mod paths;
+pub mod profile;
+pub mod review;
 pub mod ripgrep;
 pub mod risk;
 pub mod schema;
 pub mod search;
 pub mod security;
+pub mod semantic_grep;
 pub mod server;
+pub mod settings;
 pub mod shard;
 pub mod socket_server;
 pub mod sqlite_export;
+pub mod stemmer;
+pub mod test_links;
 pub mod test_runner;
+pub mod test_selection;
+pub mod test_stubs;
 pub mod tokens;
 pub mod toon;
 pub mod trace;
 pub mod utils;
+pub mod workspace;
 
 // Re-export commonly used types
 pub use utils::{truncate_to_char_boundary, truncate_with_ellipsis};
 
 pub use cli::{Cli, OperationMode, OutputFormat};
 pub use error::{McpDiffError, Result};
-pub use extract::extract;
+pub use extract::{extract, extract_with_level, ExtractionLevel};
 pub use lang::{Lang, LangFamily};
 pub use risk::calculate_risk;
 pub use schema::{
-    Argument, Call, ControlFlowChange, ControlFlowKind, Import, ImportedName, JsxElement, Location,
-    ModuleGroup, Prop, RepoOverview, RepoStats, RiskLevel, SemanticDiff, SemanticSummary,
-    StateChange, SurfaceDelta, SymbolId, SymbolKind, SCHEMA_VERSION,
+    Argument, Call, ControlFlowChange, ControlFlowKind, HookCall, Import, ImportedName, JsxElement,
+    Location, ModuleGroup, Prop, RepoOverview, RepoStats, RiskLevel, RouteInfo, ScheduleEntry,
+    SemanticDiff, SemanticSummary, StateChange, SurfaceDelta, SymbolId, SymbolKind, SCHEMA_VERSION,
 };
 // Note: Call is included above for function call tracking
 pub use tokens::{format_analysis_compact, format_analysis_report, TokenAnalysis, TokenAnalyzer};
-pub use toon::{encode_toon, encode_toon_clean, encode_toon_directory, generate_repo_overview};
+pub use toon::{
+    decode_toon, encode_toon, encode_toon_clean, encode_toon_directory, generate_repo_overview,
+    ToonParseError,
+};
 
 // Re-export git module types
 pub use git::{
@@ -159,12 +181,15 @@ pub use git::{
 // Re-export cache module types
 pub use cache::{
     get_cache_base_dir, list_cached_repos, normalize_kind, prune_old_caches, CacheDir, CacheMeta,
-    IndexingStatus, LayeredIndexMeta, RipgrepSearchResult, SearchWithFallbackResult,
+    CacheRepoInfo, IndexingStatus, LayeredIndexMeta, RipgrepSearchResult, SearchWithFallbackResult,
     SourceFileInfo, SymbolIndexEntry,
 };
 
 // Re-export shard module types
-pub use shard::{compute_optimal_names_public, extract_module_name, ShardStats, ShardWriter};
+pub use shard::{
+    compute_optimal_names_public, compute_shard_names, extract_module_name, ShardNamingStrategy,
+    ShardStats, ShardWriter,
+};
 
 // Re-export benchmark types
 pub use benchmark::{
@@ -174,8 +199,8 @@ pub use benchmark::{
 
 // Re-export overlay types (Phase 2.5 - SEM-44)
 pub use overlay::{
-    compute_content_hash, compute_symbol_hash, FileMove, LayerKind, LayerMeta, LayeredIndex,
-    LayeredIndexStats, Overlay, SymbolState,
+    compute_content_hash, compute_symbol_hash, CompactionStats, FileMove, LayerKind, LayerMeta,
+    LayeredIndex, LayeredIndexStats, Overlay, SymbolPatch, SymbolState,
 };
 
 // Re-export layered query types (Phase 2.5 - SEM-53)
@@ -198,9 +223,9 @@ pub use test_runner::{
 
 // Re-export static analysis types
 pub use analysis::{
-    analyze_call_graph, analyze_module, analyze_repo,
-    format_analysis_report as format_static_analysis_report, CallGraphAnalysis, ModuleMetrics,
-    RepoAnalysis, SymbolComplexity,
+    add_duplicate_debt, analyze_call_graph, analyze_coupling, analyze_module, analyze_repo,
+    format_analysis_report as format_static_analysis_report, CallGraphAnalysis, CouplingKind,
+    CouplingReport, DebtLines, ModuleMetrics, RepoAnalysis, SymbolComplexity, TechDebtReport,
 };
 
 // Re-export server types (SEM-98, SEM-99, SEM-101, SEM-102, SEM-104)
@@ -210,13 +235,16 @@ pub use server::{
 };
 
 // Re-export BM25 semantic search types (Phase 3)
-pub use bm25::{extract_terms_from_symbol, tokenize, Bm25Document, Bm25Index, Bm25SearchResult};
+pub use bm25::{
+    extract_terms_from_symbol, tokenize, Bm25Document, Bm25Index, Bm25SearchResult, TopicCluster,
+};
 
 // Re-export duplicate detection types
 pub use duplicate::{
     boilerplate::{BoilerplateCategory, BoilerplateConfig, CustomBoilerplateRule},
+    report::{rank_consolidation_opportunities, ConsolidationOpportunity},
     Difference, DuplicateCluster, DuplicateDetector, DuplicateKind, DuplicateMatch,
-    FunctionSignature, SymbolRef,
+    FunctionSignature, LikelyOriginal, OriginReason, SymbolRef,
 };
 
 // Re-export SQLite export types (call graph visualization)
@@ -244,7 +272,10 @@ pub use indexing::{
 };
 
 // Re-export parsing utilities (CLI/MCP unification - DEDUP-103)
-pub use parsing::{parse_and_extract, parse_and_extract_with_options};
+pub use parsing::{
+    extract_from_bytes, parse_and_extract, parse_and_extract_with_level,
+    parse_and_extract_with_options,
+};
 
 // Re-export lint types (unified multi-language linting)
 pub use lint::{
@@ -0,0 +1,370 @@
+//! Line-precise mapping from file locations to symbols
+//!
+//! Lint issues, CVE matches and test failures point at a file and a line, but
+//! follow-up calls (`get_symbol`, `find_duplicates`) want a symbol hash.
+//! [`LineSymbolIndex`] keeps the index's symbol ranges per file, sorted by
+//! start line, and answers with the innermost symbol containing a line. Lines
+//! outside every symbol (file headers, imports) map to none.
+//!
+//! Line ranges are only trusted while the file is unchanged: the content hash
+//! of every indexed file is recorded at index time (`file_hashes.json`). A
+//! file whose content no longer matches maps to no symbol, with
+//! `stale_mapping` set. Files without a recorded hash (indexes built before
+//! the hashes were) resolve as they are.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::error::{McpDiffError, Result};
+use crate::schema::{fnv1a_hash, SCHEMA_VERSION};
+use crate::test_selection::parse_lines;
+use crate::workspace::repo_relative;
+
+/// Content hashes of the indexed files, keyed by repo-relative path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileHashes {
+    pub schema_version: String,
+    pub files: BTreeMap<String, String>,
+}
+
+impl FileHashes {
+    /// Hash the current content of `files`; unreadable files are left out
+    pub fn build<'a>(files: impl IntoIterator<Item = &'a str>, repo_root: &Path) -> Self {
+        let files = files
+            .into_iter()
+            .filter_map(|file| {
+                let key = repo_relative(repo_root, file);
+                let hash = current_hash(repo_root, &key)?;
+                Some((key, hash))
+            })
+            .collect();
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            files,
+        }
+    }
+
+    /// Load the recorded hashes (empty when missing or unreadable)
+    pub fn load(cache: &CacheDir) -> Self {
+        fs::read_to_string(cache.file_hashes_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the hashes, returning the bytes written
+    pub fn write(&self, cache: &CacheDir) -> Result<usize> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize file hashes: {}", e),
+        })?;
+        fs::create_dir_all(&cache.root)?;
+        fs::write(cache.file_hashes_path(), &content)?;
+        Ok(content.len())
+    }
+}
+
+/// Content hash of a file, in the format recorded by [`FileHashes`]
+pub fn content_hash(content: &str) -> String {
+    format!("{:016x}", fnv1a_hash(content))
+}
+
+fn current_hash(repo_root: &Path, relative: &str) -> Option<String> {
+    let content = fs::read_to_string(repo_root.join(relative)).ok()?;
+    Some(content_hash(&content))
+}
+
+/// The symbol a reported location belongs to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMapping {
+    /// Hash of the innermost symbol containing the line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_hash: Option<String>,
+    /// Name of that symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// The file changed since it was indexed, so its line ranges can't be trusted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale_mapping: bool,
+}
+
+impl SymbolMapping {
+    fn resolved(entry: &SymbolIndexEntry) -> Self {
+        Self {
+            symbol_hash: Some(entry.hash.clone()),
+            symbol: Some(entry.symbol.clone()),
+            stale_mapping: false,
+        }
+    }
+
+    /// Name of the symbol for display, `-` when the location maps to none
+    pub fn display_name(&self) -> &str {
+        self.symbol.as_deref().unwrap_or("-")
+    }
+
+    /// Add `symbol`, `symbol_hash` and `stale_mapping` to a JSON output object
+    pub fn insert_into(&self, value: &mut serde_json::Value) {
+        value["symbol"] = self.display_name().into();
+        value["symbol_hash"] = self.symbol_hash.clone().into();
+        value["stale_mapping"] = self.stale_mapping.into();
+    }
+}
+
+impl fmt::Display for SymbolMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.symbol, &self.symbol_hash) {
+            (Some(name), Some(hash)) => write!(f, "symbol: {} ({})", name, hash),
+            _ if self.stale_mapping => write!(f, "symbol: - (stale mapping)"),
+            _ => write!(f, "symbol: -"),
+        }
+    }
+}
+
+/// A symbol's line range
+#[derive(Debug, Clone)]
+struct SymbolRange {
+    start: usize,
+    end: usize,
+    entry: SymbolIndexEntry,
+}
+
+/// Symbol ranges of every indexed file
+#[derive(Debug, Clone, Default)]
+pub struct LineSymbolIndex {
+    repo_root: PathBuf,
+    /// Ranges by repo-relative file, sorted by start line, longest first
+    files: HashMap<String, Vec<SymbolRange>>,
+    hashes: FileHashes,
+    /// Whether each checked file still matches its recorded hash
+    fresh: HashMap<String, bool>,
+}
+
+impl LineSymbolIndex {
+    /// Load the symbol ranges and file hashes of an index
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        Ok(Self::from_entries(
+            cache.load_all_symbol_entries()?,
+            &cache.repo_root,
+            FileHashes::load(cache),
+        ))
+    }
+
+    /// Load the index of `repo`, if it has one
+    pub fn for_repo(repo: &Path) -> Option<Self> {
+        let cache = CacheDir::for_repo(repo).ok()?;
+        if !cache.exists() {
+            return None;
+        }
+        Self::load(&cache).ok()
+    }
+
+    /// Build from symbol index entries
+    pub fn from_entries(
+        entries: Vec<SymbolIndexEntry>,
+        repo_root: &Path,
+        hashes: FileHashes,
+    ) -> Self {
+        let mut files: HashMap<String, Vec<SymbolRange>> = HashMap::new();
+        // Escape locals live inside the function that owns them
+        for entry in entries.into_iter().filter(|e| !e.is_escape_local) {
+            let (start, end) = parse_lines(&entry.lines);
+            if start == 0 {
+                continue;
+            }
+            files
+                .entry(repo_relative(repo_root, &entry.file))
+                .or_default()
+                .push(SymbolRange { start, end, entry });
+        }
+        for ranges in files.values_mut() {
+            ranges.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        }
+        Self {
+            repo_root: repo_root.to_path_buf(),
+            files,
+            hashes,
+            fresh: HashMap::new(),
+        }
+    }
+
+    /// Innermost symbol containing `line`, trusting the indexed ranges
+    pub fn lookup(&self, file: &str, line: usize) -> Option<&SymbolIndexEntry> {
+        let ranges = self.files.get(&repo_relative(&self.repo_root, file))?;
+        // Containing ranges nest, so the one starting last is the innermost
+        let candidates = ranges.partition_point(|r| r.start <= line);
+        ranges[..candidates]
+            .iter()
+            .rev()
+            .find(|r| r.end >= line)
+            .map(|r| &r.entry)
+    }
+
+    /// The symbol containing `line`, unless the file changed since indexing
+    pub fn resolve(&mut self, file: &str, line: usize) -> SymbolMapping {
+        let key = repo_relative(&self.repo_root, file);
+        if !self.is_fresh(&key) {
+            return SymbolMapping {
+                stale_mapping: true,
+                ..SymbolMapping::default()
+            };
+        }
+        self.lookup(&key, line)
+            .map(SymbolMapping::resolved)
+            .unwrap_or_default()
+    }
+
+    fn is_fresh(&mut self, key: &str) -> bool {
+        let Some(recorded) = self.hashes.files.get(key) else {
+            return true;
+        };
+        if let Some(&fresh) = self.fresh.get(key) {
+            return fresh;
+        }
+        let fresh = current_hash(&self.repo_root, key).as_deref() == Some(recorded.as_str());
+        self.fresh.insert(key.to_string(), fresh);
+        fresh
+    }
+}
+
+/// The innermost indexed symbol containing `line` of `file`
+///
+/// `None` when the repository has no index, the line is outside every symbol,
+/// or the file changed since it was indexed. Use [`LineSymbolIndex`] to map
+/// many locations, or to tell a stale mapping apart.
+pub fn resolve_line_to_symbol(repo: &Path, file: &str, line: usize) -> Option<SymbolIndexEntry> {
+    let mut index = LineSymbolIndex::for_repo(repo)?;
+    if index.resolve(file, line).stale_mapping {
+        return None;
+    }
+    index.lookup(file, line).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::FrameworkEntryPoint;
+
+    fn entry(symbol: &str, hash: &str, file: &str, lines: &str) -> SymbolIndexEntry {
+        SymbolIndexEntry {
+            symbol: symbol.to_string(),
+            hash: hash.to_string(),
+            semantic_hash: String::new(),
+            kind: "function".to_string(),
+            module: "orders".to_string(),
+            file: file.to_string(),
+            lines: lines.to_string(),
+            risk: "low".to_string(),
+            cognitive_complexity: 0,
+            max_nesting: 0,
+            is_escape_local: false,
+            framework_entry_point: FrameworkEntryPoint::None,
+            is_exported: false,
+            decorators: String::new(),
+            arity: 0,
+            is_async: false,
+            return_type: String::new(),
+            ext_package: String::new(),
+            base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
+        }
+    }
+
+    fn fixture() -> Vec<SymbolIndexEntry> {
+        let mut local = entry("total", "h9", "src/orders.ts", "6-6");
+        local.is_escape_local = true;
+        vec![
+            entry("OrderService", "h1", "src/orders.ts", "3-30"),
+            entry("place", "h2", "src/orders.ts", "5-12"),
+            entry("validate", "h3", "/repo/src/orders.ts", "7-9"),
+            entry("cancel", "h4", "./src/orders.ts", "14-20"),
+            local,
+        ]
+    }
+
+    #[test]
+    fn test_nested_symbols_resolve_to_innermost() {
+        let index =
+            LineSymbolIndex::from_entries(fixture(), Path::new("/repo"), FileHashes::default());
+        let name = |line| {
+            index
+                .lookup("src/orders.ts", line)
+                .map(|e| e.symbol.as_str())
+        };
+
+        assert_eq!(name(1), None);
+        assert_eq!(name(3), Some("OrderService"));
+        assert_eq!(name(6), Some("place"));
+        assert_eq!(name(8), Some("validate"));
+        assert_eq!(name(11), Some("place"));
+        assert_eq!(name(13), Some("OrderService"));
+        assert_eq!(name(20), Some("cancel"));
+        assert_eq!(name(31), None);
+        assert_eq!(
+            index
+                .lookup("/repo/src/orders.ts", 15)
+                .map(|e| e.hash.as_str()),
+            Some("h4")
+        );
+        assert!(index.lookup("src/other.ts", 5).is_none());
+    }
+
+    #[test]
+    fn test_stale_file_is_not_resolved() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/orders.ts"), "class OrderService {}\n").unwrap();
+        fs::write(root.join("src/users.ts"), "function load() {}\n").unwrap();
+        let hashes = FileHashes::build(["src/orders.ts", "src/users.ts"], root);
+
+        let mut entries = fixture();
+        entries.push(entry("load", "h5", "src/users.ts", "1-1"));
+        let mut index = LineSymbolIndex::from_entries(entries, root, hashes);
+        assert_eq!(
+            index.resolve("src/users.ts", 1).symbol_hash.as_deref(),
+            Some("h5")
+        );
+
+        // Lines shifted after indexing
+        fs::write(
+            root.join("src/orders.ts"),
+            "// header\nclass OrderService {}\n",
+        )
+        .unwrap();
+        let mut index = LineSymbolIndex::from_entries(
+            fixture(),
+            root,
+            FileHashes::build(["src/orders.ts"], root),
+        );
+        fs::write(
+            root.join("src/orders.ts"),
+            "// moved\n\nclass OrderService {}\n",
+        )
+        .unwrap();
+        let mapping = index.resolve("src/orders.ts", 8);
+        assert!(mapping.stale_mapping);
+        assert_eq!(mapping.symbol_hash, None);
+        assert_eq!(mapping.display_name(), "-");
+
+        // Files without a recorded hash are trusted
+        let mut index = LineSymbolIndex::from_entries(fixture(), root, FileHashes::default());
+        let mapping = index.resolve("src/orders.ts", 6);
+        assert!(!mapping.stale_mapping);
+        assert_eq!(mapping.symbol.as_deref(), Some("place"));
+    }
+}
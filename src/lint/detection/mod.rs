@@ -51,7 +51,8 @@ mod yaml;
 
 use std::path::Path;
 
-use crate::lint::types::DetectedLinter;
+use crate::lang::Lang;
+use crate::lint::types::{lint_language, DetectedLinter};
 use crate::lint::version::{has_markdown_files, has_shell_files, has_terraform_files};
 
 // Re-export per-language detection functions
@@ -75,93 +76,116 @@ pub use yaml::detect_yaml_linters;
 
 /// Detect available linters for a project directory
 pub fn detect_linters(dir: &Path) -> Vec<DetectedLinter> {
+    detect_linters_for(dir, None)
+}
+
+/// Detect available linters, skipping languages outside `languages`
+///
+/// Detection probes config files and tool versions, so scoping it saves the
+/// probes for languages that won't be linted.
+pub fn detect_linters_for(dir: &Path, languages: Option<&[Lang]>) -> Vec<DetectedLinter> {
+    let wants = |language: &str| {
+        languages.is_none_or(|langs| langs.iter().any(|l| lint_language(*l) == language))
+    };
     let mut detected = Vec::new();
 
     // Detect Rust linters
-    if dir.join("Cargo.toml").exists() {
+    if wants("rust") && dir.join("Cargo.toml").exists() {
         detected.extend(detect_rust_linters(dir));
     }
 
     // Detect JavaScript/TypeScript linters
-    if dir.join("package.json").exists() {
+    if wants("javascript") && dir.join("package.json").exists() {
         detected.extend(detect_js_linters(dir));
     }
 
     // Detect Python linters
-    if dir.join("pyproject.toml").exists()
-        || dir.join("setup.py").exists()
-        || dir.join("requirements.txt").exists()
+    if wants("python")
+        && (dir.join("pyproject.toml").exists()
+            || dir.join("setup.py").exists()
+            || dir.join("requirements.txt").exists())
     {
         detected.extend(detect_python_linters(dir));
     }
 
     // Detect Go linters
-    if dir.join("go.mod").exists() {
+    if wants("go") && dir.join("go.mod").exists() {
         detected.extend(detect_go_linters(dir));
     }
 
     // Detect Java linters
-    if dir.join("pom.xml").exists()
-        || dir.join("build.gradle").exists()
-        || dir.join("build.gradle.kts").exists()
+    if wants("java")
+        && (dir.join("pom.xml").exists()
+            || dir.join("build.gradle").exists()
+            || dir.join("build.gradle.kts").exists())
     {
         detected.extend(detect_java_linters(dir));
     }
 
     // Detect Kotlin linters
-    if dir.join("build.gradle.kts").exists() || has_kotlin_sources(dir) {
+    if wants("kotlin") && (dir.join("build.gradle.kts").exists() || has_kotlin_sources(dir)) {
         detected.extend(detect_kotlin_linters(dir));
     }
 
     // Detect C/C++ linters
-    if has_cpp_sources(dir) || has_cpp_build_system(dir) {
+    if wants("cpp") && (has_cpp_sources(dir) || has_cpp_build_system(dir)) {
         detected.extend(detect_cpp_linters(dir));
     }
 
     // Detect C#/.NET linters
-    if has_dotnet_project(dir) {
+    if wants("csharp") && has_dotnet_project(dir) {
         detected.extend(detect_csharp_linters(dir));
     }
 
     // Detect HTML linters (check for HTML files or package.json with html linter deps)
-    if has_html_files(dir) || dir.join("package.json").exists() {
+    if wants("html") && (has_html_files(dir) || dir.join("package.json").exists()) {
         detected.extend(detect_html_linters(dir));
     }
 
     // Detect CSS/SCSS/SASS linters (check for CSS files or package.json with stylelint)
-    if has_css_files(dir) || dir.join("package.json").exists() {
+    if wants("css") && (has_css_files(dir) || dir.join("package.json").exists()) {
         detected.extend(detect_css_linters(dir));
     }
 
     // Detect JSON linters (check for package.json with jsonlint dependency)
-    if dir.join("package.json").exists() {
+    if wants("json") && dir.join("package.json").exists() {
         detected.extend(detect_json_linters(dir));
     }
 
     // Detect YAML linters (check for yamllint config)
-    detected.extend(detect_yaml_linters(dir));
+    if wants("yaml") {
+        detected.extend(detect_yaml_linters(dir));
+    }
 
     // Detect TOML linters (check for taplo config or Cargo.toml)
-    detected.extend(detect_toml_linters(dir));
+    if wants("toml") {
+        detected.extend(detect_toml_linters(dir));
+    }
 
     // Detect XML linters (check for XML files)
-    detected.extend(detect_xml_linters(dir));
+    if wants("xml") {
+        detected.extend(detect_xml_linters(dir));
+    }
 
     // Detect Terraform linters (check for .tf files)
-    if has_terraform_files(dir) {
+    if wants("terraform") && has_terraform_files(dir) {
         detected.extend(detect_terraform_linters(dir));
     }
 
     // Detect Shell linters (check for .sh files)
-    if has_shell_files(dir) {
+    if wants("shell") && has_shell_files(dir) {
         detected.extend(detect_shell_linters(dir));
     }
 
     // Detect Markdown linters (check for .md files or config)
-    if has_markdown_files(dir) || dir.join("package.json").exists() {
+    if wants("markdown") && (has_markdown_files(dir) || dir.join("package.json").exists()) {
         detected.extend(detect_markdown_linters(dir));
     }
 
+    if languages.is_some() {
+        // Detectors for one language can report tools shared with another
+        detected.retain(|d| wants(d.linter.language()));
+    }
     detected
 }
 
@@ -64,14 +64,15 @@ mod version;
 
 // Re-export types for public API
 pub use types::{
-    ConfigHash, DetectedLinter, LintCache, LintCapabilities, LintCategory, LintCommand, LintIssue,
-    LintRecommendation, LintResults, LintRunOptions, LintSeverity, Linter, SingleLinterResult,
+    lint_language, ConfigHash, DetectedLinter, LintCache, LintCapabilities, LintCategory,
+    LintCommand, LintIssue, LintRecommendation, LintResults, LintRunOptions, LintSeverity, Linter,
+    SingleLinterResult,
 };
 
 // Re-export core functions
 pub use cache::{collect_config_hashes, get_recommendations};
-pub use detection::detect_linters;
-pub use runner::{run_lint, run_single_linter};
+pub use detection::{detect_linters, detect_linters_for};
+pub use runner::{run_lint, run_lint_fix, run_single_linter};
 pub use version::{
     get_biome_version, get_black_version, get_checkstyle_version, get_clang_tidy_version,
     get_clippy_version, get_cppcheck_version, get_cpplint_version, get_detekt_version,
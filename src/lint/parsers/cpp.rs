@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse clang-tidy output
@@ -160,6 +161,7 @@ fn parse_clang_diagnostic_line(line: &str, dir: &Path, linter: Linter) -> Option
         message: message.to_string(),
         linter,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -234,6 +236,7 @@ fn parse_gcc_style_diagnostic(line: &str, dir: &Path, linter: Linter) -> Option<
         message: message.to_string(),
         linter,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -322,6 +325,7 @@ fn parse_cpplint_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message: message.to_string(),
         linter: Linter::Cpplint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse dotnet format output
@@ -143,6 +144,7 @@ fn parse_msbuild_diagnostic_line(line: &str, dir: &Path, linter: Linter) -> Opti
         message,
         linter,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
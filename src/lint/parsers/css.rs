@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse Stylelint JSON output
@@ -93,6 +94,7 @@ fn parse_stylelint_warning(
         message,
         linter: Linter::Stylelint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
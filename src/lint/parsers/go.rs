@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse golangci-lint JSON output
@@ -57,6 +58,7 @@ pub fn parse_golangci_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                     message: message.to_string(),
                     linter: Linter::GolangciLint,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         }
@@ -93,6 +95,7 @@ pub fn parse_gofmt_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
             message: "File needs formatting".to_string(),
             linter: Linter::Gofmt,
             fix: Some("Run 'gofmt -w' to fix".to_string()),
+            symbol: SymbolMapping::default(),
         });
     }
 
@@ -136,6 +139,7 @@ pub fn parse_govet_output(stderr: &str, dir: &Path) -> Vec<LintIssue> {
                 message: message.to_string(),
                 linter: Linter::GoVet,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
     }
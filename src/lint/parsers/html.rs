@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse HTMLHint JSON output
@@ -86,6 +87,7 @@ fn parse_htmlhint_issue(issue: &serde_json::Value, file: &str, dir: &Path) -> Op
         message,
         linter: Linter::HtmlHint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -183,6 +185,7 @@ fn parse_html_validate_message(
         message,
         linter: Linter::HtmlValidate,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
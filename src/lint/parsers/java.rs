@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse Checkstyle XML output
@@ -72,6 +73,7 @@ pub fn parse_checkstyle_output(stdout: &str, stderr: &str, dir: &Path) -> Vec<Li
                 message: html_decode(&message),
                 linter: Linter::Checkstyle,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
     }
@@ -131,6 +133,7 @@ pub fn parse_checkstyle_output(stdout: &str, stderr: &str, dir: &Path) -> Vec<Li
                             message: msg.to_string(),
                             linter: Linter::Checkstyle,
                             fix: None,
+                            symbol: SymbolMapping::default(),
                         });
                     }
                 }
@@ -234,6 +237,7 @@ pub fn parse_spotbugs_output(stdout: &str, stderr: &str, dir: &Path) -> Vec<Lint
                     message,
                     linter: Linter::SpotBugs,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         } else if line.starts_with("</BugInstance") {
@@ -313,6 +317,7 @@ pub fn parse_pmd_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                             message: message.to_string(),
                             linter: Linter::Pmd,
                             fix: None,
+                            symbol: SymbolMapping::default(),
                         });
                     }
                 }
@@ -347,6 +352,7 @@ pub fn parse_pmd_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                     message: message.to_string(),
                     linter: Linter::Pmd,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         }
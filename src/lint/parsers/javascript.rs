@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse ESLint JSON output
@@ -60,6 +61,7 @@ pub fn parse_eslint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                         message: message.to_string(),
                         linter: Linter::ESLint,
                         fix,
+                        symbol: SymbolMapping::default(),
                     });
                 }
             }
@@ -97,6 +99,7 @@ pub fn parse_prettier_output(stdout: &str, _stderr: &str, dir: &Path) -> Vec<Lin
             message: "File needs formatting".to_string(),
             linter: Linter::Prettier,
             fix: Some("Run 'npx prettier --write' to fix".to_string()),
+            symbol: SymbolMapping::default(),
         });
     }
 
@@ -149,6 +152,7 @@ pub fn parse_biome_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                             message: rest.trim_start_matches(':').trim().to_string(),
                             linter: Linter::Biome,
                             fix: None,
+                            symbol: SymbolMapping::default(),
                         });
                     }
                 }
@@ -202,6 +206,7 @@ pub fn parse_tsc_output(stdout: &str, _stderr: &str, dir: &Path) -> Vec<LintIssu
                     message: message.to_string(),
                     linter: Linter::Tsc,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         }
@@ -270,6 +275,7 @@ pub fn parse_oxlint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                         message: message.to_string(),
                         linter: Linter::Oxlint,
                         fix,
+                        symbol: SymbolMapping::default(),
                     });
                 }
             }
@@ -316,6 +322,7 @@ pub fn parse_oxlint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                     message: message.to_string(),
                     linter: Linter::Oxlint,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         }
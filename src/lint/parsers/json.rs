@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse jsonlint output
@@ -78,6 +79,7 @@ fn parse_jsonlint_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message,
         linter: Linter::JsonLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse detekt JSON output
@@ -61,6 +62,7 @@ pub fn parse_detekt_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                         message: message.to_string(),
                         linter: Linter::Detekt,
                         fix: None,
+                        symbol: SymbolMapping::default(),
                     });
                 }
             }
@@ -98,6 +100,7 @@ pub fn parse_detekt_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                     message: message.to_string(),
                     linter: Linter::Detekt,
                     fix: None,
+                    symbol: SymbolMapping::default(),
                 });
             }
         }
@@ -144,6 +147,7 @@ pub fn parse_ktlint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                         message: message.to_string(),
                         linter: Linter::Ktlint,
                         fix: Some("Run 'ktlint -F' to fix".to_string()),
+                        symbol: SymbolMapping::default(),
                     });
                 }
             }
@@ -197,6 +201,7 @@ pub fn parse_ktlint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: msg.to_string(),
                 linter: Linter::Ktlint,
                 fix: Some("Run 'ktlint -F' to fix".to_string()),
+                symbol: SymbolMapping::default(),
             });
         }
     }
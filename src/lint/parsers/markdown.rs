@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse markdownlint JSON output
@@ -109,6 +110,7 @@ fn parse_markdownlint_json_item(item: &serde_json::Value, dir: &Path) -> Option<
         message,
         linter: Linter::MarkdownLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -165,6 +167,7 @@ fn parse_markdownlint_text_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message,
         linter: Linter::MarkdownLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
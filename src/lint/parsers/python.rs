@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse Ruff JSON output
@@ -46,6 +47,7 @@ pub fn parse_ruff_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: message.to_string(),
                 linter: Linter::Ruff,
                 fix,
+                symbol: SymbolMapping::default(),
             });
         }
     }
@@ -78,6 +80,7 @@ pub fn parse_black_output(stdout: &str, _stderr: &str, dir: &Path) -> Vec<LintIs
                 message: "File needs formatting".to_string(),
                 linter: Linter::Black,
                 fix: Some("Run 'black' to fix".to_string()),
+                symbol: SymbolMapping::default(),
             });
         }
     }
@@ -128,6 +131,7 @@ pub fn parse_mypy_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: message.to_string(),
                 linter: Linter::Mypy,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
         return issues;
@@ -174,6 +178,7 @@ pub fn parse_mypy_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: message.to_string(),
                 linter: Linter::Mypy,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
     }
@@ -252,6 +257,7 @@ pub fn parse_pylint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: message.to_string(),
                 linter: Linter::Pylint,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
         return issues;
@@ -317,6 +323,7 @@ pub fn parse_pylint_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message,
                 linter: Linter::Pylint,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
     }
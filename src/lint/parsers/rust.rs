@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse clippy JSON output (cargo clippy --message-format=json)
@@ -89,6 +90,7 @@ pub fn parse_clippy_output(stdout: &str, _stderr: &str, dir: &Path) -> Vec<LintI
                             message: text.to_string(),
                             linter: Linter::Clippy,
                             fix: suggested_replacement.map(|s| s.to_string()),
+                            symbol: SymbolMapping::default(),
                         });
 
                         break; // Only process first primary span
@@ -102,13 +104,20 @@ pub fn parse_clippy_output(stdout: &str, _stderr: &str, dir: &Path) -> Vec<LintI
 }
 
 /// Parse rustfmt output (cargo fmt --check)
-pub fn parse_rustfmt_output(_stdout: &str, stderr: &str, dir: &Path) -> Vec<LintIssue> {
+///
+/// `cargo fmt --check` prints one `Diff in <file>:<line>:` header per hunk
+/// on stdout (older releases: `Diff in <file> at line <line>:`).
+pub fn parse_rustfmt_output(stdout: &str, stderr: &str, dir: &Path) -> Vec<LintIssue> {
     let mut issues = Vec::new();
 
-    // rustfmt outputs "Diff in <file>" messages
-    for line in stderr.lines() {
-        if line.starts_with("Diff in ") {
-            let file = line.trim_start_matches("Diff in ").trim();
+    for line in stdout.lines().chain(stderr.lines()) {
+        if let Some(header) = line.strip_prefix("Diff in ") {
+            let header = header.trim().trim_end_matches(':');
+            let (file, line) = header
+                .rsplit_once(" at line ")
+                .or_else(|| header.rsplit_once(':'))
+                .and_then(|(file, line)| Some((file, line.parse::<usize>().ok()?)))
+                .unwrap_or((header, 1));
             let file_path = PathBuf::from(file);
             let relative_file = file_path
                 .strip_prefix(dir)
@@ -117,7 +126,7 @@ pub fn parse_rustfmt_output(_stdout: &str, stderr: &str, dir: &Path) -> Vec<Lint
 
             issues.push(LintIssue {
                 file: relative_file,
-                line: 1,
+                line,
                 column: None,
                 end_line: None,
                 end_column: None,
@@ -126,9 +135,28 @@ pub fn parse_rustfmt_output(_stdout: &str, stderr: &str, dir: &Path) -> Vec<Lint
                 message: "File needs formatting".to_string(),
                 linter: Linter::Rustfmt,
                 fix: Some("Run 'cargo fmt' to fix".to_string()),
+                symbol: SymbolMapping::default(),
             });
         }
     }
 
     issues
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rustfmt_check_output() {
+        let stdout = "Diff in /repo/src/lib.rs:1:\n-pub fn add(a:i32)->i32{a}\n+pub fn add(a: i32) -> i32 {\nDiff in /repo/src/main.rs at line 12:\n";
+
+        let issues = parse_rustfmt_output(stdout, "", Path::new("/repo"));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].file, "src/lib.rs");
+        assert_eq!(issues[0].line, 1);
+        assert_eq!(issues[1].file, "src/main.rs");
+        assert_eq!(issues[1].line, 12);
+        assert_eq!(issues[1].rule, "formatting");
+    }
+}
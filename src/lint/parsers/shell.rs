@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse ShellCheck JSON output
@@ -86,6 +87,7 @@ fn parse_shellcheck_item(item: &serde_json::Value, dir: &Path) -> Option<LintIss
         message,
         linter: Linter::ShellCheck,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -146,6 +148,7 @@ pub fn parse_shfmt_output(stdout: &str, stderr: &str, dir: &Path) -> Vec<LintIss
             message: "File needs formatting".to_string(),
             linter: Linter::Shfmt,
             fix: None,
+            symbol: SymbolMapping::default(),
         });
     }
 
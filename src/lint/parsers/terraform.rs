@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse TFLint JSON output
@@ -92,6 +93,7 @@ fn parse_tflint_issue(issue: &serde_json::Value, dir: &Path) -> Option<LintIssue
         message,
         linter: Linter::TfLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -192,6 +194,7 @@ fn parse_terraform_diagnostic(
         message,
         linter,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -241,6 +244,7 @@ pub fn parse_terraform_fmt_output(stdout: &str, dir: &Path) -> Vec<LintIssue> {
                 message: "File needs formatting".to_string(),
                 linter: Linter::TerraformFmt,
                 fix: None,
+                symbol: SymbolMapping::default(),
             });
         }
     }
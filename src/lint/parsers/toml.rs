@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse taplo JSON output
@@ -84,6 +85,7 @@ fn parse_taplo_error(error: &serde_json::Value, dir: &Path) -> Option<LintIssue>
         message,
         linter: Linter::Taplo,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -137,6 +139,7 @@ fn parse_taplo_text_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message,
         linter: Linter::Taplo,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
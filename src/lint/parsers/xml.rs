@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse xmllint output
@@ -94,6 +95,7 @@ fn parse_xmllint_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message,
         linter: Linter::XmlLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
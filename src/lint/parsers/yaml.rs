@@ -2,6 +2,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::line_symbols::SymbolMapping;
 use crate::lint::types::{LintIssue, LintSeverity, Linter};
 
 /// Parse yamllint parsable output
@@ -84,6 +85,7 @@ fn parse_yamllint_line(line: &str, dir: &Path) -> Option<LintIssue> {
         message,
         linter: Linter::YamlLint,
         fix: None,
+        symbol: SymbolMapping::default(),
     })
 }
 
@@ -10,7 +10,7 @@ use rayon::prelude::*;
 
 use crate::error::{McpDiffError, Result};
 use crate::lint::cache::collect_config_hashes;
-use crate::lint::detection::detect_linters;
+use crate::lint::detection::{detect_linters, detect_linters_for};
 use crate::lint::parsers::parse_linter_output;
 use crate::lint::types::{
     DetectedLinter, LintCache, LintResults, LintRunOptions, LintSeverity, SingleLinterResult,
@@ -23,6 +23,9 @@ pub fn run_lint(dir: &Path, options: &LintRunOptions) -> Result<LintResults> {
     // Try to load cached linter detection
     let (mut detected, _used_cache) = if let Some(cache) = LintCache::load(dir) {
         (cache.detected_linters, true)
+    } else if let Some(ref languages) = options.languages {
+        // Scoped detection would leave the cache incomplete, so skip saving it
+        (detect_linters_for(dir, Some(languages)), false)
     } else {
         // Detect available linters and save to cache
         let linters = detect_linters(dir);
@@ -42,6 +45,14 @@ pub fn run_lint(dir: &Path, options: &LintRunOptions) -> Result<LintResults> {
     if let Some(ref target_linter) = options.linter {
         detected.retain(|d| d.linter == *target_linter);
     }
+    if let Some(ref linters) = options.linters {
+        detected.retain(|d| linters.contains(&d.linter));
+    }
+
+    // Filter to the requested languages
+    if let Some(ref languages) = options.languages {
+        detected.retain(|d| languages.iter().any(|lang| d.linter.covers(*lang)));
+    }
 
     // Filter to only available linters
     detected.retain(|d| d.available);
@@ -55,23 +66,17 @@ pub fn run_lint(dir: &Path, options: &LintRunOptions) -> Result<LintResults> {
             duration_ms: start.elapsed().as_millis() as u64,
             linters: Vec::new(),
             issues: Vec::new(),
+            fixed_count: 0,
         });
     }
 
-    // Run linters in parallel using rayon
-    let dir_owned = dir.to_path_buf();
-    let options_clone = options.clone();
-
-    let parallel_results: Vec<Result<SingleLinterResult>> = detected
-        .par_iter()
-        .map(|linter| run_single_linter(linter, &dir_owned, &options_clone))
-        .collect();
+    let results = run_detected_linters(&detected, dir, options);
 
     // Collect results, handling any errors
     let mut linter_results = Vec::new();
     let mut all_issues = Vec::new();
 
-    for result in parallel_results {
+    for result in results {
         match result {
             Ok(r) => {
                 all_issues.extend(r.issues.clone());
@@ -123,30 +128,92 @@ pub fn run_lint(dir: &Path, options: &LintRunOptions) -> Result<LintResults> {
         warning_count,
         files_with_issues: files_with_issues.len(),
         duration_ms: start.elapsed().as_millis() as u64,
+        fixed_count: linter_results.iter().map(|r| r.fixed_count).sum(),
         linters: linter_results,
         issues: all_issues,
     })
 }
 
-/// Run a single linter and parse its output
+/// Run each detected linter, in check or fix mode depending on `options`
+///
+/// Checks run in parallel. Fixers run one after another so they never
+/// rewrite the same file at once, and each linter's `fixed_count` only
+/// reflects its own fixes.
+fn run_detected_linters(
+    detected: &[DetectedLinter],
+    dir: &Path,
+    options: &LintRunOptions,
+) -> Vec<Result<SingleLinterResult>> {
+    if options.fix {
+        detected
+            .iter()
+            .map(|linter| run_lint_fix(linter, dir, options))
+            .collect()
+    } else {
+        detected
+            .par_iter()
+            .map(|linter| run_single_linter(linter, dir, options))
+            .collect()
+    }
+}
+
+/// Run a single linter in check mode and parse its output
 pub fn run_single_linter(
+    linter: &DetectedLinter,
+    dir: &Path,
+    _options: &LintRunOptions,
+) -> Result<SingleLinterResult> {
+    run_linter_command(linter, dir, &linter.run_command.args)
+}
+
+/// Run a single linter in fix mode, counting what it fixed
+///
+/// The check command runs before and after the linter's fix command
+/// (`--fix` for ESLint and Ruff, `--write` for Prettier, in place for Black
+/// and rustfmt): issues gone afterwards were auto-fixed, and the ones left
+/// need manual intervention. A dry run changes nothing and counts the issues
+/// the fix command would resolve: every issue of a formatter, and those
+/// carrying a suggested fix otherwise. Linters without a fix mode only check.
+pub fn run_lint_fix(
     linter: &DetectedLinter,
     dir: &Path,
     options: &LintRunOptions,
 ) -> Result<SingleLinterResult> {
     let start = std::time::Instant::now();
-
-    // Choose args based on fix mode
-    let args = if options.fix && !options.dry_run {
-        linter
-            .run_command
-            .fix_args
-            .as_ref()
-            .unwrap_or(&linter.run_command.args)
-    } else {
-        &linter.run_command.args
+    let before = run_single_linter(linter, dir, options)?;
+    let Some(fix_args) = linter.run_command.fix_args.as_ref() else {
+        return Ok(before);
     };
 
+    if options.dry_run {
+        let fixed_count = before
+            .issues
+            .iter()
+            .filter(|issue| linter.capabilities.can_format || issue.fix.is_some())
+            .count();
+        return Ok(SingleLinterResult {
+            fixed_count,
+            ..before
+        });
+    }
+
+    run_linter_command(linter, dir, fix_args)?;
+    let after = run_single_linter(linter, dir, options)?;
+    Ok(SingleLinterResult {
+        fixed_count: before.issues.len().saturating_sub(after.issues.len()),
+        duration_ms: start.elapsed().as_millis() as u64,
+        ..after
+    })
+}
+
+/// Run a linter with the given arguments and parse its output
+fn run_linter_command(
+    linter: &DetectedLinter,
+    dir: &Path,
+    args: &[String],
+) -> Result<SingleLinterResult> {
+    let start = std::time::Instant::now();
+
     // Build and execute command
     let mut cmd = Command::new(&linter.run_command.program);
     cmd.args(args);
@@ -191,5 +258,62 @@ pub fn run_single_linter(
         stdout,
         stderr,
         exit_code,
+        fixed_count: 0,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::types::{LintCapabilities, LintCommand, Linter};
+
+    /// A fake linter reporting one issue per line of `app.yaml`, whose fix
+    /// keeps only the last `keep` lines
+    fn line_fixer(keep: usize) -> DetectedLinter {
+        let sh = |script: String| vec!["-c".to_string(), script];
+        DetectedLinter {
+            linter: Linter::YamlLint,
+            config_path: None,
+            version: None,
+            available: true,
+            run_command: LintCommand::new(
+                "sh",
+                sh(
+                    "grep -n x app.yaml | sed 's/:.*/:1: [warning] x (x)/; s/^/app.yaml:/'"
+                        .to_string(),
+                ),
+            )
+            .with_fix_args(sh(format!(
+                "sleep 0.2; tail -n {} app.yaml > app.tmp && mv app.tmp app.yaml",
+                keep
+            ))),
+            capabilities: LintCapabilities {
+                can_fix: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_fixers_run_one_at_a_time() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("app.yaml"), "x\nx\nx\nx\nx\n").unwrap();
+
+        let options = LintRunOptions {
+            fix: true,
+            ..Default::default()
+        };
+        let results = run_detected_linters(&[line_fixer(3), line_fixer(2)], dir.path(), &options);
+        let fixed: Vec<_> = results
+            .into_iter()
+            .map(|r| r.unwrap().fixed_count)
+            .collect();
+
+        assert_eq!(fixed, [2, 1]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("app.yaml")).unwrap(),
+            "x\nx\n"
+        );
+    }
+}
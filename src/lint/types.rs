@@ -12,6 +12,9 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::lang::Lang;
+use crate::line_symbols::SymbolMapping;
+
 // ============================================================================
 // Core Types
 // ============================================================================
@@ -203,6 +206,23 @@ impl Linter {
             Linter::Unknown => "unknown",
         }
     }
+
+    /// Whether this linter checks source in `lang`
+    pub fn covers(&self, lang: Lang) -> bool {
+        self.language() == lint_language(lang)
+    }
+}
+
+/// The [`Linter::language`] whose linters check `lang`
+pub fn lint_language(lang: Lang) -> &'static str {
+    match lang {
+        Lang::TypeScript | Lang::Tsx | Lang::JavaScript | Lang::Jsx | Lang::Vue => "javascript",
+        Lang::C | Lang::Cpp => "cpp",
+        Lang::Css | Lang::Scss => "css",
+        Lang::Hcl => "terraform",
+        Lang::Bash => "shell",
+        other => other.name(),
+    }
 }
 
 impl std::str::FromStr for Linter {
@@ -427,6 +447,10 @@ pub struct LintIssue {
 
     /// Suggested fix (if available)
     pub fix: Option<String>,
+
+    /// Symbol containing the issue (filled in from the index, see [`crate::line_symbols`])
+    #[serde(default, flatten)]
+    pub symbol: SymbolMapping,
 }
 
 // ============================================================================
@@ -462,6 +486,10 @@ pub struct SingleLinterResult {
 
     /// Exit code
     pub exit_code: Option<i32>,
+
+    /// Issues the fix run resolved (in a dry run, the ones it would resolve)
+    #[serde(default)]
+    pub fixed_count: usize,
 }
 
 impl Default for SingleLinterResult {
@@ -476,6 +504,7 @@ impl Default for SingleLinterResult {
             stdout: String::new(),
             stderr: String::new(),
             exit_code: None,
+            fixed_count: 0,
         }
     }
 }
@@ -503,6 +532,11 @@ pub struct LintResults {
 
     /// All issues combined and sorted by file/line
     pub issues: Vec<LintIssue>,
+
+    /// Issues fixed across all linters (fix mode only); `issues` holds the
+    /// ones left for manual intervention
+    #[serde(default)]
+    pub fixed_count: usize,
 }
 
 impl Default for LintResults {
@@ -515,6 +549,7 @@ impl Default for LintResults {
             duration_ms: 0,
             linters: Vec::new(),
             issues: Vec::new(),
+            fixed_count: 0,
         }
     }
 }
@@ -529,6 +564,9 @@ pub struct LintRunOptions {
     /// Only run this specific linter
     pub linter: Option<Linter>,
 
+    /// Only run linters in this set
+    pub linters: Option<Vec<Linter>>,
+
     /// Filter to specific file or directory
     pub path_filter: Option<PathBuf>,
 
@@ -549,6 +587,9 @@ pub struct LintRunOptions {
 
     /// Only apply safe fixes
     pub safe_only: bool,
+
+    /// Only detect and run linters for these languages
+    pub languages: Option<Vec<Lang>>,
 }
 
 // ============================================================================
@@ -5,16 +5,18 @@
 
 use std::process::ExitCode;
 
-use semfora_engine::analyze_repo_tokens;
-use semfora_engine::cli::{Cli, Commands, ConfigOperation};
+use semfora_engine::cli::{BenchmarkArgs, Cli, Commands, ConfigOperation};
 use semfora_engine::commands::{
-    run_analyze, run_cache, run_commit, run_index, run_lint, run_query, run_search, run_serve,
-    run_test, run_trace, run_validate, CommandContext,
+    completions, run_analyze, run_cache, run_commit, run_completions, run_config_effective,
+    run_index, run_lint, run_query, run_search, run_serve, run_test, run_trace, run_validate,
+    CommandContext,
 };
 use semfora_engine::installer::{
     self, agents::AgentScope, print_available_clients, ConfigArgs, SetupArgs, UninstallArgs,
 };
 use semfora_engine::trace;
+use semfora_engine::McpDiffError;
+use semfora_engine::{analyze_repo_tokens, RepoTokenMetrics};
 
 fn main() -> ExitCode {
     match run() {
@@ -32,23 +34,47 @@ fn main() -> ExitCode {
 }
 
 fn run() -> semfora_engine::Result<String> {
+    // The completion script calls back into the binary for its candidates
+    completions::complete_from_env();
     let cli = Cli::parse_args();
 
     // Create shared context for command handlers
-    let ctx = CommandContext::from_cli(cli.format, cli.verbose, cli.progress);
+    let ctx = CommandContext::from_cli(cli.format, cli.verbose, cli.show_progress())
+        .with_paths(cli.paths)
+        .with_progress_format(cli.progress_format)
+        .with_settings(cli.settings.clone());
 
     // Dispatch to appropriate command handler
     match cli.command {
         // ============================================
         // Core Analysis Commands
         // ============================================
-        Commands::Analyze(args) => run_analyze(&ctx, &args),
+        Commands::Analyze(args) => {
+            let ctx = ctx.with_output(args.output.clone());
+            run_analyze(&ctx, &args).and_then(|output| ctx.emit(output))
+        }
 
-        Commands::Search(args) => run_search(&args, &ctx),
+        Commands::Search(args) => {
+            let ctx = ctx.with_output(args.output.clone());
+            run_search(&args, &ctx).and_then(|output| ctx.emit(output))
+        }
 
         Commands::Query(args) => run_query(&args, &ctx),
 
-        Commands::Validate(args) => run_validate(&args, &ctx),
+        Commands::Validate(args) => {
+            let ctx = ctx.with_output(args.output.clone());
+            match run_validate(&args, &ctx) {
+                // A tripped --fail-on rule still produces the full report
+                Err(McpDiffError::ValidationFailed { count, report }) => {
+                    print!("{}", ctx.emit(report)?);
+                    Err(McpDiffError::ValidationFailed {
+                        count,
+                        report: String::new(),
+                    })
+                }
+                result => result.and_then(|output| ctx.emit(output)),
+            }
+        }
 
         // ============================================
         // Index & Cache Management
@@ -116,7 +142,11 @@ fn run() -> semfora_engine::Result<String> {
 
         Commands::Config(args) => {
             let config_args = match &args.operation {
-                ConfigOperation::Show => ConfigArgs {
+                ConfigOperation::Show {
+                    effective: true,
+                    path,
+                } => return run_config_effective(path.as_deref(), &ctx),
+                ConfigOperation::Show { .. } => ConfigArgs {
                     command: "show".to_string(),
                     key: None,
                     value: None,
@@ -144,9 +174,27 @@ fn run() -> semfora_engine::Result<String> {
             let dir_path = args.path.clone().unwrap_or_else(|| {
                 std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
             });
-            run_benchmark(&dir_path)
+            let ctx = ctx.with_output(args.output.clone());
+            match run_benchmark(&dir_path, &args) {
+                // A regression still produces the full report
+                Err(McpDiffError::BenchmarkRegressed {
+                    count,
+                    max_regression,
+                    report,
+                }) => {
+                    print!("{}", ctx.emit(report)?);
+                    Err(McpDiffError::BenchmarkRegressed {
+                        count,
+                        max_regression,
+                        report: String::new(),
+                    })
+                }
+                result => result.and_then(|output| ctx.emit(output)),
+            }
         }
 
+        Commands::Completions(args) => run_completions(&args),
+
         // ============================================
         // MCP Server Mode
         // ============================================
@@ -181,8 +229,20 @@ fn run() -> semfora_engine::Result<String> {
 }
 
 /// Run token efficiency benchmark
-fn run_benchmark(dir_path: &std::path::Path) -> semfora_engine::Result<String> {
+fn run_benchmark(
+    dir_path: &std::path::Path,
+    args: &BenchmarkArgs,
+) -> semfora_engine::Result<String> {
+    // Read the baseline first so a bad path fails before the analysis
+    let baseline = args
+        .compare
+        .as_deref()
+        .map(RepoTokenMetrics::load)
+        .transpose()?;
     let metrics = analyze_repo_tokens(dir_path)?;
+    if let Some(path) = &args.save {
+        metrics.save(path)?;
+    }
 
     let mut output = String::new();
     output.push_str("═══════════════════════════════════════════════════════\n");
@@ -224,5 +284,21 @@ fn run_benchmark(dir_path: &std::path::Path) -> semfora_engine::Result<String> {
         metrics.total_token_savings * 100.0
     ));
 
-    Ok(output)
+    let Some(baseline) = baseline else {
+        return Ok(output);
+    };
+    let comparison = metrics.compare(&baseline, args.max_regression);
+    output.push('\n');
+    output.push_str(&comparison.report());
+
+    let regressions = comparison.regressions();
+    if regressions.is_empty() {
+        Ok(output)
+    } else {
+        Err(McpDiffError::BenchmarkRegressed {
+            count: regressions.len(),
+            max_regression: args.max_regression,
+            report: output,
+        })
+    }
 }
@@ -23,7 +23,12 @@ pub fn toon_header(type_name: &str) -> String {
     format!("_type: {}\nversion: {}\n", type_name, VERSION)
 }
 
+use crate::ai_layer::AiLayer;
+use crate::contracts::BreakingChange;
+use crate::diff_warnings::{DeprecatedCall, DiffWarnings, IoCallOutOfTry};
 use crate::parsing::parse_and_extract;
+use crate::schema::{SemanticDiff, SurfaceDelta};
+use crate::test_stubs::TestStubs;
 use crate::{encode_toon, CacheDir, Lang, SymbolIndexEntry};
 
 // ============================================================================
@@ -32,11 +37,20 @@ use crate::{encode_toon, CacheDir, Lang, SymbolIndexEntry};
 
 /// Format diff output with pagination support - TOON format
 /// Returns paginated file analysis with semantic summaries
+///
+/// `breaking` lists breaking contract changes and `warnings` the new calls
+/// into deprecated symbols and I/O calls moved out of try blocks across the
+/// whole diff; both are shown on every page. Deleted files show their summary at `diff_base`, the commit the diff
+/// starts from.
+#[allow(clippy::too_many_arguments)]
 pub fn format_diff_output_paginated(
     working_dir: &Path,
     base_ref: &str,
     target_ref: &str,
+    diff_base: &str,
     changed_files: &[crate::git::ChangedFile],
+    breaking: &[BreakingChange],
+    warnings: &DiffWarnings,
     offset: usize,
     limit: usize,
 ) -> String {
@@ -73,6 +87,8 @@ pub fn format_diff_output_paginated(
         .map(|(k, v)| format!("{}={}", k, v))
         .collect();
     output.push_str(&format!("changes: {}\n", type_summary.join(", ")));
+    output.push_str(&format_breaking_changes(breaking));
+    output.push_str(&format_diff_warnings(warnings));
 
     if page_files.is_empty() {
         if total_files == 0 {
@@ -100,6 +116,12 @@ pub fn format_diff_output_paginated(
 
         if changed_file.change_type == crate::git::ChangeType::Deleted {
             output.push_str("    (deleted)\n");
+            if let Some(summary) = summary_at_ref(working_dir, &changed_file.path, diff_base) {
+                output.push_str("    base:\n");
+                for line in encode_toon(&summary).lines() {
+                    output.push_str(&format!("      {}\n", line));
+                }
+            }
             continue;
         }
 
@@ -136,6 +158,18 @@ pub fn format_diff_output_paginated(
     output
 }
 
+/// Semantic summary of `path` as of `git_ref`, if it existed and parses
+fn summary_at_ref(
+    working_dir: &Path,
+    path: &str,
+    git_ref: &str,
+) -> Option<crate::schema::SemanticSummary> {
+    let full_path = working_dir.join(path);
+    let lang = Lang::from_path(&full_path).ok()?;
+    let source = crate::git::get_file_at_ref(path, git_ref, Some(working_dir)).ok()??;
+    parse_and_extract(&full_path, &source, lang).ok()
+}
+
 /// Format diff summary only - compact overview without per-file details
 /// Returns aggregate statistics for large diffs
 pub fn format_diff_summary(
@@ -143,6 +177,8 @@ pub fn format_diff_summary(
     base_ref: &str,
     target_ref: &str,
     changed_files: &[crate::git::ChangedFile],
+    breaking: &[BreakingChange],
+    warnings: &DiffWarnings,
 ) -> String {
     use std::collections::HashMap;
 
@@ -256,6 +292,8 @@ pub fn format_diff_summary(
         "risk_estimate: high={}, medium={}, low={}\n",
         high_risk, medium_risk, low_risk
     ));
+    output.push_str(&format_breaking_changes(breaking));
+    output.push_str(&format_diff_warnings(warnings));
 
     // Hint for getting details
     output.push_str("\n_hint: Use limit/offset params to paginate file details, or omit summary_only for full analysis.\n");
@@ -263,6 +301,186 @@ pub fn format_diff_summary(
     output
 }
 
+/// Format staged AI edits as a diff against the working tree - TOON format
+///
+/// Mirrors `format_diff_output_paginated`, adding the typed surface deltas of
+/// each staged file relative to its working tree version.
+pub fn format_ai_diff(
+    working_dir: &Path,
+    layer: &AiLayer,
+    offset: usize,
+    limit: usize,
+    summary_only: bool,
+) -> String {
+    let staged: Vec<_> = layer.files().collect();
+    let total_files = staged.len();
+    let page_files: Vec<_> = staged.iter().skip(offset).take(limit).collect();
+
+    let mut output = toon_header("analyze_diff");
+    output.push_str("base: \"WORKING\"\n");
+    output.push_str("target: \"AI\"\n");
+    output.push_str(&format!("total_files: {}\n", total_files));
+
+    if total_files == 0 {
+        output.push_str("\n_note: No staged AI edits. Use stage_ai_edit first.\n");
+        return output;
+    }
+
+    output.push_str(&format!("showing: {}\n", page_files.len()));
+    output.push_str(&format!("offset: {}\n", offset));
+    output.push_str(&format!("limit: {}\n", limit));
+    if offset + page_files.len() < total_files {
+        output.push_str(&format!(
+            "next_offset: {} (use offset={} for next page)\n",
+            offset + page_files.len(),
+            offset + limit
+        ));
+    }
+
+    output.push_str(&format!("\nfiles[{}]:\n", page_files.len()));
+    for file in page_files {
+        let full_path = working_dir.join(&file.path);
+        let before = fs::read_to_string(&full_path).ok().and_then(|source| {
+            let lang = Lang::from_path(&full_path).ok()?;
+            parse_and_extract(Path::new(&file.index_file), &source, lang).ok()
+        });
+        let change_type = if full_path.exists() {
+            "modified"
+        } else {
+            "added"
+        };
+        output.push_str(&format!("  {} [{}]\n", file.path, change_type));
+
+        let diff = SemanticDiff::from_summaries(before.as_ref(), &file.summary);
+        output.push_str(&format!(
+            "    risk: {} -> {}\n",
+            diff.risk_before.as_str(),
+            diff.risk_after.as_str()
+        ));
+        output.push_str(&format!("    deltas[{}]:\n", diff.deltas.len()));
+        for delta in &diff.deltas {
+            output.push_str(&format!("      {}\n", format_surface_delta(delta)));
+        }
+
+        if !summary_only {
+            for line in encode_toon(&file.summary).lines() {
+                output.push_str(&format!("    {}\n", line));
+            }
+        }
+    }
+
+    output
+}
+
+/// Format the semantic diff between two arbitrary files - TOON format
+pub fn format_file_diff(file_a: &str, file_b: &str, diff: &SemanticDiff) -> String {
+    let mut output = toon_header("file_diff");
+    output.push_str(&format!("file_a: \"{}\"\n", file_a));
+    output.push_str(&format!("file_b: \"{}\"\n", file_b));
+    output.push_str(&format!(
+        "risk: {} -> {}\n",
+        diff.risk_before.as_str(),
+        diff.risk_after.as_str()
+    ));
+    output.push_str(&format!("deltas[{}]:\n", diff.deltas.len()));
+    for delta in &diff.deltas {
+        output.push_str(&format!("  {}\n", format_surface_delta(delta)));
+    }
+    if diff.deltas.is_empty() {
+        output.push_str("\n_note: No semantic differences between the two files.\n");
+    }
+    output
+}
+
+/// One-line form of a surface delta: `type key=value ...`
+pub fn format_surface_delta(delta: &SurfaceDelta) -> String {
+    let value = serde_json::to_value(delta).unwrap_or_default();
+    let Some(fields) = value.as_object() else {
+        return String::new();
+    };
+    let mut line = fields
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+    for (key, value) in fields.iter().filter(|(key, _)| *key != "type") {
+        match value.as_str() {
+            Some(text) => line.push_str(&format!(" {}={}", key, text)),
+            None => line.push_str(&format!(" {}={}", key, value)),
+        }
+    }
+    line
+}
+
+/// Format the breaking contract changes section (empty when there are none)
+pub fn format_breaking_changes(breaking: &[BreakingChange]) -> String {
+    if breaking.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("breaking_changes[{}]:\n", breaking.len());
+    for change in breaking {
+        output.push_str(&format!(
+            "  {}: {} - {}\n",
+            change.file, change.element, change.reason
+        ));
+    }
+    output
+}
+
+/// Format diff warnings - TOON format (empty when none)
+pub fn format_diff_warnings(warnings: &DiffWarnings) -> String {
+    let mut output = format_deprecated_calls(&warnings.deprecated_calls);
+    output.push_str(&format_io_out_of_try(&warnings.io_out_of_try));
+    output
+}
+
+/// Format new calls into deprecated symbols - TOON format (empty when none)
+pub fn format_deprecated_calls(calls: &[DeprecatedCall]) -> String {
+    if calls.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("deprecated_calls[{}]:\n", calls.len());
+    for call in calls {
+        let location = if call.line > 0 {
+            format!("{}:{}", call.file, call.line)
+        } else {
+            call.file.clone()
+        };
+        output.push_str(&format!(
+            "  {}: {} -> {}",
+            location, call.caller, call.callee
+        ));
+        if let Some(message) = &call.message {
+            output.push_str(&format!(" - {}", message));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Format I/O calls moved out of try blocks - TOON format (empty when none)
+pub fn format_io_out_of_try(calls: &[IoCallOutOfTry]) -> String {
+    if calls.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("io_out_of_try[{}]:\n", calls.len());
+    for call in calls {
+        let location = if call.line > 0 {
+            format!("{}:{}", call.file, call.line)
+        } else {
+            call.file.clone()
+        };
+        output.push_str(&format!(
+            "  {}: {} -> {}\n",
+            location, call.caller, call.callee
+        ));
+    }
+    output
+}
+
 // ============================================================================
 // Language Support
 // ============================================================================
@@ -270,8 +488,8 @@ pub fn format_diff_summary(
 /// Get the list of supported languages as a formatted string
 pub(super) fn get_supported_languages() -> String {
     let languages = vec![
-        ("TypeScript", ".ts"),
-        ("TSX", ".tsx"),
+        ("TypeScript", ".ts, .mts, .cts"),
+        ("TSX", ".tsx, .mdx"),
         ("JavaScript", ".js, .mjs, .cjs"),
         ("JSX", ".jsx"),
         ("Rust", ".rs"),
@@ -282,6 +500,7 @@ pub(super) fn get_supported_languages() -> String {
         ("C", ".c, .h"),
         ("C++", ".cpp, .cc, .cxx, .hpp, .hxx, .hh"),
         ("Kotlin", ".kt, .kts"),
+        ("PHP", ".php, .phtml"),
         ("HTML", ".html, .htm"),
         ("CSS", ".css"),
         ("SCSS", ".scss, .sass"),
@@ -294,6 +513,8 @@ pub(super) fn get_supported_languages() -> String {
         ("Vue", ".vue"),
         ("Bash/Shell", ".sh, .bash, .zsh, .fish"),
         ("Gradle", ".gradle"),
+        ("Protocol Buffers", ".proto"),
+        ("GraphQL (recognized, not parsed)", ".graphql, .gql"),
     ];
 
     let mut output = String::from("Supported Languages:\n\n");
@@ -337,6 +558,36 @@ pub(super) fn format_module_symbols(
     output
 }
 
+// ============================================================================
+// Test Stub Formatting
+// ============================================================================
+
+/// Format generated test skeletons as compact TOON, with the source last
+pub(super) fn format_test_stubs(stubs: &TestStubs) -> String {
+    let mut output = toon_header("test_stubs");
+    output.push_str(&format!("symbol: {}\n", stubs.symbol));
+    output.push_str(&format!("hash: {}\n", stubs.hash));
+    output.push_str(&format!("file: {}\n", stubs.file));
+    output.push_str(&format!("framework: {}\n", stubs.framework.as_str()));
+    output.push_str(&format!("test_file: {}\n", stubs.test_file));
+    output.push_str(&format!(
+        "cases[{}]{{name,line,covers}}:\n",
+        stubs.cases.len()
+    ));
+    for case in &stubs.cases {
+        let name = if case.id.is_empty() {
+            "happy_path"
+        } else {
+            &case.id
+        };
+        let line = case.line.map(|l| l.to_string()).unwrap_or_default();
+        output.push_str(&format!("  {},{},\"{}\"\n", name, line, case.description));
+    }
+    output.push_str("\n__source__:\n");
+    output.push_str(&stubs.source);
+    output
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -386,7 +637,17 @@ mod tests {
     #[test]
     fn test_format_diff_output_paginated_empty() {
         let temp = tempfile::tempdir().unwrap();
-        let output = format_diff_output_paginated(temp.path(), "main", "HEAD", &[], 0, 20);
+        let output = format_diff_output_paginated(
+            temp.path(),
+            "main",
+            "HEAD",
+            "main",
+            &[],
+            &[],
+            &DiffWarnings::default(),
+            0,
+            20,
+        );
 
         assert!(output.contains("_type: analyze_diff"));
         assert!(output.contains("base: \"main\""));
@@ -411,7 +672,17 @@ mod tests {
             crate::git::ChangeType::Modified,
         )];
 
-        let output = format_diff_output_paginated(temp.path(), "main", "HEAD", &files, 0, 20);
+        let output = format_diff_output_paginated(
+            temp.path(),
+            "main",
+            "HEAD",
+            "main",
+            &files,
+            &[],
+            &DiffWarnings::default(),
+            0,
+            20,
+        );
 
         assert!(output.contains("_type: analyze_diff"));
         assert!(output.contains("total_files: 1"));
@@ -436,7 +707,17 @@ mod tests {
             .map(|i| make_changed_file(&format!("src/file{}.ts", i), crate::git::ChangeType::Added))
             .collect();
 
-        let output = format_diff_output_paginated(temp.path(), "main", "HEAD", &files, 0, 2);
+        let output = format_diff_output_paginated(
+            temp.path(),
+            "main",
+            "HEAD",
+            "main",
+            &files,
+            &[],
+            &DiffWarnings::default(),
+            0,
+            2,
+        );
 
         assert!(output.contains("total_files: 5"));
         assert!(output.contains("showing: 2"));
@@ -457,7 +738,17 @@ mod tests {
             crate::git::ChangeType::Deleted,
         )];
 
-        let output = format_diff_output_paginated(temp.path(), "main", "HEAD", &files, 0, 20);
+        let output = format_diff_output_paginated(
+            temp.path(),
+            "main",
+            "HEAD",
+            "main",
+            &files,
+            &[],
+            &DiffWarnings::default(),
+            0,
+            20,
+        );
 
         assert!(output.contains("src/deleted.ts [deleted]"));
         assert!(output.contains("(deleted)"));
@@ -470,7 +761,14 @@ mod tests {
     #[test]
     fn test_format_diff_summary_empty() {
         let temp = tempfile::tempdir().unwrap();
-        let output = format_diff_summary(temp.path(), "main", "HEAD", &[]);
+        let output = format_diff_summary(
+            temp.path(),
+            "main",
+            "HEAD",
+            &[],
+            &[],
+            &DiffWarnings::default(),
+        );
 
         assert!(output.contains("_type: analyze_diff_summary"));
         assert!(output.contains("total_files: 0"));
@@ -490,7 +788,14 @@ mod tests {
             make_changed_file("src/lib.rs", crate::git::ChangeType::Modified),
         ];
 
-        let output = format_diff_summary(temp.path(), "main", "HEAD", &files);
+        let output = format_diff_summary(
+            temp.path(),
+            "main",
+            "HEAD",
+            &files,
+            &[],
+            &DiffWarnings::default(),
+        );
 
         assert!(output.contains("_type: analyze_diff_summary"));
         assert!(output.contains("total_files: 3"));
@@ -509,11 +814,55 @@ mod tests {
             make_changed_file("src/utils/format.ts", crate::git::ChangeType::Modified),
         ];
 
-        let output = format_diff_summary(temp.path(), "main", "HEAD", &files);
+        let output = format_diff_summary(
+            temp.path(),
+            "main",
+            "HEAD",
+            &files,
+            &[],
+            &DiffWarnings::default(),
+        );
 
         assert!(output.contains("risk_estimate:"));
         assert!(output.contains("high=1"));
         assert!(output.contains("medium=1"));
         assert!(output.contains("low=1"));
     }
+
+    // ========================================================================
+    // format_file_diff Tests
+    // ========================================================================
+
+    #[test]
+    fn test_format_file_diff_lists_deltas() {
+        let diff = SemanticDiff {
+            file: "b.ts".to_string(),
+            deltas: vec![
+                SurfaceDelta::DependencyAdded {
+                    name: "axios".to_string(),
+                },
+                SurfaceDelta::NetworkIntroduced,
+            ],
+            risk_before: crate::RiskLevel::Low,
+            risk_after: crate::RiskLevel::High,
+            ..Default::default()
+        };
+
+        let output = format_file_diff("a.ts", "b.ts", &diff);
+
+        assert!(output.starts_with("_type: file_diff\n"));
+        assert!(output.contains("file_a: \"a.ts\""));
+        assert!(output.contains("risk: low -> high"));
+        assert!(
+            output.contains("deltas[2]:\n  dependency_added name=axios\n  network_introduced\n")
+        );
+        assert!(!output.contains("_note"));
+    }
+
+    #[test]
+    fn test_format_file_diff_identical() {
+        let output = format_file_diff("a.ts", "b.ts", &SemanticDiff::default());
+        assert!(output.contains("deltas[0]:"));
+        assert!(output.contains("No semantic differences"));
+    }
 }
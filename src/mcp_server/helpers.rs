@@ -5,20 +5,35 @@
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use super::formatting::toon_header;
 use crate::cache::{
-    load_function_signatures as cache_load_function_signatures, split_respecting_quotes,
+    generation, load_function_signatures as cache_load_function_signatures, migrate,
+    split_respecting_quotes, verify, Generation,
 };
+use crate::commands::ProgressSink;
 use crate::duplicate::DuplicateDetector;
+use crate::error::McpDiffError;
 use crate::indexing::{
+    analyze_files_parallel as indexing_analyze_files_parallel,
     analyze_files_with_stats as indexing_analyze_files_with_stats,
-    collect_files as indexing_collect_files, should_skip_path as indexing_should_skip_path,
+    collect_files as indexing_collect_files, prioritize_files as indexing_prioritize_files,
+    should_skip_path as indexing_should_skip_path, IndexCheckpoint,
+};
+use crate::module_usage::ModuleUsageIndex;
+use crate::overlay::{LayerKind, LayeredIndex, SymbolPatch, SymbolState};
+use crate::ownership::{OwnershipIndex, SymbolAuthor};
+use crate::profile::{Phase, PhaseTimer};
+use crate::schema::{RiskLevel, SymbolInfo, SymbolKind};
+use crate::settings::RepoSettings;
+use crate::{
+    extract_module_name, CacheDir, CacheMeta, IndexingStatus, Lang, SemanticSummary, ShardWriter,
+    SymbolIndexEntry,
 };
-use crate::{extract_module_name, CacheDir, Lang, SemanticSummary, ShardWriter, SymbolIndexEntry};
 
 // ============================================================================
 // Staleness Info Struct
@@ -54,8 +69,13 @@ pub struct IndexGenerationResult {
     pub symbols_written: usize,
     /// Compression percentage achieved
     pub compression_pct: f64,
+    /// Set when generation hit its deadline and the index is partial
+    pub indexing_status: Option<IndexingStatus>,
 }
 
+/// Files analyzed per checkpointed batch during time-boxed generation
+const INDEX_BATCH_SIZE: usize = 256;
+
 // ============================================================================
 // Cache Staleness Detection
 // ============================================================================
@@ -191,19 +211,62 @@ fn collect_source_files_recursive(
 ///
 /// This is the core indexing logic used by both `generate_index` (explicit)
 /// and `ensure_index` (auto-generation). Returns statistics about what was generated.
+/// Runs to completion; see [`generate_index_with_deadline`] for the time-boxed variant.
 pub fn generate_index_internal(
     dir_path: &Path,
     max_depth: usize,
     extensions: &[String],
 ) -> Result<IndexGenerationResult, String> {
-    let start = std::time::Instant::now();
+    generate_index_with_deadline(dir_path, max_depth, extensions, None)
+}
 
-    // Create shard writer
-    let mut shard_writer = ShardWriter::new(dir_path)
-        .map_err(|e| format!("Failed to initialize shard writer: {}", e))?;
+/// Generate the index, stopping early once `deadline` has passed.
+///
+/// Files are analyzed in batches, highest-priority modules first (entry
+/// points, then by file count). With a deadline every batch is checkpointed;
+/// if the deadline passes before all files are processed, the files analyzed
+/// so far are written as a usable partial index and the cache meta records an
+/// `IndexingStatus::partial`. Any later call resumes from the checkpoint, and
+/// once complete the index is identical to an uninterrupted run.
+pub fn generate_index_with_deadline(
+    dir_path: &Path,
+    max_depth: usize,
+    extensions: &[String],
+    deadline: Option<Instant>,
+) -> Result<IndexGenerationResult, String> {
+    generate_index_in_batches(
+        dir_path,
+        max_depth,
+        extensions,
+        deadline,
+        INDEX_BATCH_SIZE,
+        None,
+    )
+}
 
-    // Collect files
-    let files = indexing_collect_files(dir_path, max_depth, extensions);
+fn generate_index_in_batches(
+    dir_path: &Path,
+    max_depth: usize,
+    extensions: &[String],
+    deadline: Option<Instant>,
+    batch_size: usize,
+    progress: Option<&ProgressSink>,
+) -> Result<IndexGenerationResult, String> {
+    let report = |phase: &str, current: usize, total: usize| {
+        if let Some(progress) = progress {
+            progress.report(phase, current, total);
+        }
+    };
+    let start = Instant::now();
+
+    let cache =
+        CacheDir::for_repo(dir_path).map_err(|e| format!("Failed to access cache: {}", e))?;
+    let _lock = cache.lock_index().map_err(|e| e.to_string())?;
+
+    // Collect files, leaving out paths the layered config excludes
+    let mut files = indexing_collect_files(dir_path, max_depth, extensions);
+    let settings = RepoSettings::load_or_default(dir_path);
+    files.retain(|file| !settings.is_excluded(file));
 
     if files.is_empty() {
         return Ok(IndexGenerationResult {
@@ -212,23 +275,134 @@ pub fn generate_index_internal(
             modules_written: 0,
             symbols_written: 0,
             compression_pct: 0.0,
+            indexing_status: None,
         });
     }
 
-    // Analyze files
-    let (summaries, total_bytes) = indexing_analyze_files_with_stats(&files);
+    let mut checkpoint = IndexCheckpoint::open(&cache.checkpoint_dir(), dir_path);
+    let resuming = !checkpoint.is_empty();
+    // Without a deadline or earlier progress the run always completes, so
+    // checkpointing would only add overhead
+    let use_checkpoint = deadline.is_some() || resuming;
+
+    let pending: Vec<PathBuf> = if use_checkpoint {
+        indexing_prioritize_files(&files)
+            .into_iter()
+            .filter(|f| !checkpoint.is_processed(f))
+            .collect()
+    } else {
+        files.clone()
+    };
+
+    // Analyze files batch by batch; always make progress on at least one batch
+    let mut summaries = Vec::new();
+    let mut total_bytes = 0;
+    let mut timed_out = false;
+    let mut analyzed = 0;
+    report("Indexing files", 0, pending.len());
+    let mut batches = pending.chunks(batch_size.max(1)).peekable();
+    while let Some(batch) = batches.next() {
+        let result = indexing_analyze_files_parallel(batch, None, false);
+        analyzed += batch.len();
+        report("Indexing files", analyzed, pending.len());
+        if use_checkpoint {
+            checkpoint
+                .record_batch(batch, &result.summaries)
+                .map_err(|e| format!("Failed to write index checkpoint: {}", e))?;
+        } else {
+            total_bytes += result.total_bytes;
+            summaries.extend(result.summaries);
+        }
+
+        if batches.peek().is_some() && deadline.is_some_and(|d| Instant::now() >= d) {
+            timed_out = true;
+            break;
+        }
+    }
+
+    if use_checkpoint {
+        // Natural collection order keeps the output identical to an uninterrupted run
+        summaries = checkpoint.summaries_for(&files);
+        total_bytes = checkpoint.source_bytes();
+    }
+
+    let indexing_status = if timed_out {
+        let mut ready = BTreeSet::new();
+        let mut pending_modules = BTreeSet::new();
+        let mut files_indexed = 0;
+        for file in &files {
+            let module = extract_module_name(&file.display().to_string());
+            if checkpoint.is_processed(file) {
+                files_indexed += 1;
+                ready.insert(module);
+            } else {
+                pending_modules.insert(module);
+            }
+        }
+        ready.retain(|m| !pending_modules.contains(m));
+        Some(IndexingStatus::partial(
+            files_indexed,
+            files.len(),
+            ready.into_iter().collect(),
+            pending_modules.into_iter().collect(),
+        ))
+    } else {
+        None
+    };
+
+    // A previous partial write may have left shards that a fuller index names
+    // differently, so start from empty shard directories
+    if resuming || cache.partial_status().is_some() {
+        let _ = fs::remove_dir_all(cache.modules_dir());
+        let _ = fs::remove_dir_all(cache.symbols_dir());
+    }
+    // Stage markers from an earlier write would skip stages of this one
+    let _ = fs::remove_file(cache.root.join("progress.json"));
+
+    // Create shard writer
+    let mut shard_writer = ShardWriter::new(dir_path)
+        .map_err(|e| format!("Failed to initialize shard writer: {}", e))?;
+    if let Some(ref status) = indexing_status {
+        shard_writer.set_progress(status.clone());
+    }
 
     // Add summaries to shard writer
-    shard_writer.add_summaries(summaries.clone());
+    let files_analyzed = summaries.len();
+    shard_writer.add_summaries(summaries);
 
     // Write all shards
     let dir_str = dir_path.display().to_string();
-    let stats = shard_writer
-        .write_all(&dir_str)
-        .map_err(|e| format!("Failed to write shards: {}", e))?;
+    let stats = match progress {
+        Some(progress) => {
+            let progress = progress.clone();
+            shard_writer.write_all_with_progress(
+                &dir_str,
+                Some(Arc::new(move |step, current, total| {
+                    progress.report(step, current, total)
+                })),
+            )
+        }
+        None => shard_writer.write_all(&dir_str),
+    }
+    .map_err(|e| format!("Failed to write shards: {}", e))?;
+
+    // The shard writer recorded the repo fingerprint; keep it
+    let meta = CacheMeta {
+        indexing_status: indexing_status.clone(),
+        ..cache
+            .load_meta()
+            .unwrap_or_else(|| CacheMeta::new(Vec::new()))
+    };
+    cache
+        .save_meta(&meta)
+        .map_err(|e| format!("Failed to save cache meta: {}", e))?;
 
-    // Set the indexed SHA and status hash for staleness tracking
-    if let Ok(cache) = CacheDir::for_repo(dir_path) {
+    if indexing_status.is_none() {
+        checkpoint
+            .clear()
+            .map_err(|e| format!("Failed to clear index checkpoint: {}", e))?;
+
+        // Set the indexed SHA and status hash for staleness tracking
         if let Ok(sha) = crate::git::git_command(&["rev-parse", "HEAD"], Some(dir_path)) {
             let _ = cache.set_indexed_sha(&sha);
         }
@@ -247,10 +421,11 @@ pub fn generate_index_internal(
 
     Ok(IndexGenerationResult {
         duration_ms: start.elapsed().as_millis() as u64,
-        files_analyzed: summaries.len(),
+        files_analyzed,
         modules_written: stats.modules_written,
         symbols_written: stats.symbols_written,
         compression_pct: compression,
+        indexing_status,
     })
 }
 
@@ -276,7 +451,12 @@ pub struct PartialReindexResult {
 /// 2. Groups them by module
 /// 3. For each affected module, loads existing summaries, removes old entries
 ///    for changed files, adds new summaries, and rewrites the module shard
-/// 4. Updates the indexed SHA to current HEAD
+/// 4. Replaces the changed files' symbol shards and symbol index entries
+/// 5. Updates the indexed SHA to current HEAD
+///
+/// Rewritten shards and the symbol index are written side by side as the
+/// next index generation and published together once complete (see
+/// [`generation`]), so concurrent queries never see a mix of old and new.
 ///
 /// This is much faster than a full reindex for small changes (<50 files).
 pub fn partial_reindex(
@@ -284,11 +464,13 @@ pub fn partial_reindex(
     changed_files: &[PathBuf],
 ) -> Result<PartialReindexResult, String> {
     let start = std::time::Instant::now();
+    let _lock = cache.lock_index().map_err(|e| e.to_string())?;
 
-    // Filter to only valid source files
+    // Filter to only valid source files the layered config doesn't exclude
+    let settings = RepoSettings::load_or_default(&cache.repo_root);
     let valid_files: Vec<PathBuf> = changed_files
         .iter()
-        .filter(|f| f.exists() && Lang::from_path(f).is_ok())
+        .filter(|f| f.exists() && Lang::from_path(f).is_ok() && !settings.is_excluded(f))
         .cloned()
         .collect();
 
@@ -305,7 +487,10 @@ pub fn partial_reindex(
     }
 
     // Analyze only the changed files (parallel)
-    let (new_summaries, _) = indexing_analyze_files_with_stats(&valid_files);
+    let (mut new_summaries, _) = indexing_analyze_files_with_stats(&valid_files);
+    for summary in &mut new_summaries {
+        crate::generated::apply_settings(summary, &settings);
+    }
 
     // Build file-to-module mapping from existing cache for consistent module names
     // This ensures partial reindex uses the same module names as the full index
@@ -361,6 +546,9 @@ pub fn partial_reindex(
 
     let mut modules_updated = 0;
 
+    // Queries keep reading the current generation until this one is published
+    let mut next = generation::current(&cache.root).unwrap_or_default().next();
+
     // Update each affected module
     for (module_name, new_module_summaries) in &new_by_module {
         // Load existing summaries for this module
@@ -377,16 +565,14 @@ pub fn partial_reindex(
 
         // Skip writing empty modules (all files deleted)
         if updated.is_empty() {
-            // Delete the module file
-            let module_path = cache.module_path(module_name);
-            let _ = fs::remove_file(module_path);
+            next.remove_module(module_name);
             modules_updated += 1;
             continue;
         }
 
         // Encode and write the updated module shard
         let toon = crate::shard::encode_module_shard(module_name, &updated, &cache.repo_root);
-        let module_path = cache.module_path(module_name);
+        let module_path = cache.modules_dir().join(next.replace_module(module_name));
 
         if let Err(e) = fs::write(&module_path, toon) {
             return Err(format!("Failed to write module {}: {}", module_name, e));
@@ -395,10 +581,45 @@ pub fn partial_reindex(
         modules_updated += 1;
     }
 
-    // Update symbol shards for changed files
-    // For simplicity, we'll delete old symbol files and create new ones
+    // Write symbol shards for changed files
     // (Symbol files are keyed by hash which includes file path)
-    update_symbol_shards(cache, &new_summaries)?;
+    update_symbol_shards(cache, &mut next, &new_summaries)?;
+
+    // Replace the changed files' symbol index entries
+    if cache.has_symbol_index() {
+        let mut entries = cache
+            .load_all_symbol_entries()
+            .map_err(|e| format!("Failed to load symbol index: {}", e))?;
+        let replaced: HashSet<&str> = changed_files.iter().filter_map(|p| p.to_str()).collect();
+        entries.retain(|entry| !replaced.contains(entry.file.as_str()));
+        for summary in &new_summaries {
+            let module = file_to_module
+                .get(&summary.file)
+                .cloned()
+                .unwrap_or_else(|| extract_module_name(&summary.file));
+            entries.extend(crate::shard::symbol_index_entries(summary, &module));
+        }
+
+        next.replace_symbol_index();
+        generation::with_generation(&cache.root, &next, || cache.write_symbol_index(&entries))
+            .map_err(|e| format!("Failed to write symbol index: {}", e))?;
+    }
+
+    generation::publish(cache, next)
+        .map_err(|e| format!("Failed to publish index generation: {}", e))?;
+
+    // Patch the module usage summary (a missing one is rebuilt by the next full index)
+    if let Ok(mut module_usage) = ModuleUsageIndex::load(cache) {
+        let removed: Vec<String> = deleted_files
+            .iter()
+            .filter_map(|p| p.to_str())
+            .map(String::from)
+            .collect();
+        module_usage.update(&new_summaries, &removed, &file_to_module);
+        module_usage
+            .write(cache)
+            .map_err(|e| format!("Failed to write module usage: {}", e))?;
+    }
 
     // Update the indexed SHA
     if let Ok(sha) = crate::git::git_command(&["rev-parse", "HEAD"], Some(&cache.repo_root)) {
@@ -427,8 +648,12 @@ pub fn partial_reindex(
     })
 }
 
-/// Update symbol shards for the given summaries
-fn update_symbol_shards(cache: &CacheDir, summaries: &[SemanticSummary]) -> Result<(), String> {
+/// Write symbol shards for the given summaries as part of `next`
+fn update_symbol_shards(
+    cache: &CacheDir,
+    next: &mut Generation,
+    summaries: &[SemanticSummary],
+) -> Result<(), String> {
     use crate::schema::SymbolId;
 
     for summary in summaries {
@@ -440,7 +665,9 @@ fn update_symbol_shards(cache: &CacheDir, summaries: &[SemanticSummary]) -> Resu
                 let symbol_id = symbol_info.to_symbol_id(&namespace, &summary.file);
                 let toon =
                     crate::shard::encode_symbol_shard_from_info(summary, symbol_info, &symbol_id);
-                let path = cache.symbol_path(&symbol_id.hash);
+                let path = cache
+                    .symbols_dir()
+                    .join(next.replace_symbol(&symbol_id.hash));
 
                 if let Err(e) = fs::write(&path, toon) {
                     return Err(format!("Failed to write symbol {}: {}", symbol_id.hash, e));
@@ -449,7 +676,9 @@ fn update_symbol_shards(cache: &CacheDir, summaries: &[SemanticSummary]) -> Resu
         } else if let Some(symbol_id) = SymbolId::from_summary(summary) {
             // Fallback to primary symbol
             let toon = crate::shard::encode_symbol_shard(summary);
-            let path = cache.symbol_path(&symbol_id.hash);
+            let path = cache
+                .symbols_dir()
+                .join(next.replace_symbol(&symbol_id.hash));
 
             if let Err(e) = fs::write(&path, toon) {
                 return Err(format!("Failed to write symbol {}: {}", symbol_id.hash, e));
@@ -477,6 +706,19 @@ pub struct FreshnessResult {
     pub files_updated: usize,
     /// Time taken for refresh in milliseconds
     pub duration_ms: u64,
+    /// Set when the index only partially covers the repository
+    pub indexing_status: Option<IndexingStatus>,
+    /// Set when an unreadable cache was quarantined before regenerating
+    pub recovery: Option<CacheRecovery>,
+}
+
+/// An unreadable cache that was moved aside and regenerated
+#[derive(Debug, Clone)]
+pub struct CacheRecovery {
+    /// Why the cache could not be used
+    pub reason: String,
+    /// Where the old cache was moved
+    pub quarantined_to: PathBuf,
 }
 
 /// Type of index refresh performed
@@ -503,6 +745,30 @@ impl RefreshType {
 /// Default threshold for partial vs full reindex
 const DEFAULT_MAX_STALE_FILES: usize = 50;
 
+/// Seconds [`ensure_fresh_index`] may spend generating an index before it
+/// fails with [`McpDiffError::Timeout`]
+pub const INDEX_TIMEOUT_ENV: &str = "SEMFORA_INDEX_TIMEOUT_SECS";
+
+/// The configured index generation budget, if any
+fn index_timeout() -> Option<Duration> {
+    parse_index_timeout(std::env::var(INDEX_TIMEOUT_ENV).ok().as_deref())
+}
+
+fn parse_index_timeout(value: Option<&str>) -> Option<Duration> {
+    let value = value?.trim();
+    match value.parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => {
+            tracing::warn!(
+                "Ignoring {}={:?}: expected a positive number of seconds",
+                INDEX_TIMEOUT_ENV,
+                value
+            );
+            None
+        }
+    }
+}
+
 /// Ensure the index is fresh before executing a query.
 ///
 /// This function is called at the start of query tools to transparently
@@ -511,29 +777,151 @@ const DEFAULT_MAX_STALE_FILES: usize = 50;
 /// 2. If stale with few changes (<= max_stale_files): partial reindex
 /// 3. If stale with many changes (> max_stale_files): full reindex
 /// 4. If no index exists: full index generation
+/// 5. If the index is partial: resume generation from its checkpoint
+///
+/// A cache whose repository fingerprint (see
+/// [`crate::cache::compute_repo_fingerprint`]) no longer matches the
+/// repository at `repo_path` is cleared and regenerated as in step 4.
+///
+/// An index written by an older schema version is migrated first; if the
+/// migration has to discard it, it is regenerated as in step 4. A cache with
+/// corrupt artifacts is quarantined (see [`verify::quarantine_cache`]) and
+/// regenerated the same way; `recovery` then records where it was moved.
+///
+/// With a `deadline`, generation in steps 4 and 5 stops once it passes and the
+/// result carries the partial `indexing_status` (see
+/// [`generate_index_with_deadline`]).
+///
+/// Generation in steps 3-5 is also bounded by `SEMFORA_INDEX_TIMEOUT_SECS`
+/// (see [`INDEX_TIMEOUT_ENV`]). Unlike `deadline`, running out of that budget
+/// is an error: [`McpDiffError::Timeout`]. The work done so far is
+/// checkpointed, so the next call resumes it.
 ///
 /// The decision of what to reindex is made entirely by the engine based on
 /// git status and file changes - the LLM does not influence this decision.
 pub fn ensure_fresh_index(
     repo_path: &Path,
     max_stale_files: Option<usize>,
-) -> Result<FreshnessResult, String> {
+    deadline: Option<Instant>,
+) -> crate::Result<FreshnessResult> {
+    ensure_fresh_index_within(repo_path, max_stale_files, deadline, index_timeout(), None)
+}
+
+/// [`ensure_fresh_index`], reporting the progress of index generation
+pub fn ensure_fresh_index_with_progress(
+    repo_path: &Path,
+    max_stale_files: Option<usize>,
+    deadline: Option<Instant>,
+    progress: Option<&ProgressSink>,
+) -> crate::Result<FreshnessResult> {
+    let _timer = PhaseTimer::start(Phase::FilesystemIo);
+    ensure_fresh_index_within(
+        repo_path,
+        max_stale_files,
+        deadline,
+        index_timeout(),
+        progress,
+    )
+}
+
+fn ensure_fresh_index_within(
+    repo_path: &Path,
+    max_stale_files: Option<usize>,
+    deadline: Option<Instant>,
+    timeout: Option<Duration>,
+    progress: Option<&ProgressSink>,
+) -> crate::Result<FreshnessResult> {
     let start = std::time::Instant::now();
     let threshold = max_stale_files.unwrap_or(DEFAULT_MAX_STALE_FILES);
+    let timeout_deadline = timeout.map(|budget| start + budget);
+
+    // Generation that stopped early because the timeout ran out, rather than
+    // because `deadline` passed, is an error
+    let check_timeout = |result: &IndexGenerationResult| -> crate::Result<()> {
+        match timeout {
+            Some(budget) if result.indexing_status.is_some() && start.elapsed() >= budget => {
+                Err(McpDiffError::Timeout {
+                    operation: format!("Index generation for {}", repo_path.display()),
+                    elapsed_ms: start.elapsed().as_millis() as u64,
+                })
+            }
+            _ => Ok(()),
+        }
+    };
 
     // Get or create cache directory
     let cache =
-        CacheDir::for_repo(repo_path).map_err(|e| format!("Failed to access cache: {}", e))?;
+        CacheDir::for_repo(repo_path).map_err(|e| e.with_context("Failed to access cache"))?;
+
+    // Hold the index lock throughout, since clearing, migrating and
+    // reindexing all rewrite the cache
+    let _lock = cache.lock_index()?;
+
+    // A cache left at this path by a different repository is rebuilt from
+    // scratch, without that repository's manifests
+    if cache.exists() && !cache.fingerprint_matches() {
+        tracing::info!(
+            "[CACHE] Repository at {} changed identity; rebuilding index",
+            cache.repo_root.display()
+        );
+        cache
+            .purge()
+            .map_err(|e| e.with_context("Failed to clear cache"))?;
+    }
+
+    // An index built under different index settings in the layered config
+    // covers the wrong files, so it is regenerated below
+    let settings = RepoSettings::load_or_default(repo_path);
+    if cache.exists() && !cache.settings_match() {
+        tracing::info!(
+            "[CACHE] Index settings for {} changed; rebuilding index",
+            cache.repo_root.display()
+        );
+        cache
+            .clear()
+            .map_err(|e| e.with_context("Failed to clear cache"))?;
+    }
+
+    // Upgrade an index written by an older schema instead of serving it
+    if migrate::needs_migration(&cache) {
+        migrate::migrate_cache(&cache).map_err(|e| e.with_context("Failed to migrate index"))?;
+    }
+
+    // Move an unreadable cache aside rather than serving it
+    let recovery = match verify::check_essentials(&cache) {
+        Err(e) if e.is_cache_damage() => {
+            let quarantined_to = verify::quarantine_cache(&cache)
+                .map_err(|e| e.with_context("Failed to quarantine corrupt cache"))?;
+            Some(CacheRecovery {
+                reason: e.to_string(),
+                quarantined_to,
+            })
+        }
+        _ => None,
+    };
 
-    // Check if index exists at all
+    // Check if index exists at all (or was left partial by a time-boxed run)
     let overview_path = cache.repo_overview_path();
-    if !overview_path.exists() {
-        // No index exists - do full generation
-        let result = generate_index_internal(repo_path, 10, &[])?;
+    if !overview_path.exists() || cache.partial_status().is_some() {
+        // No complete index - generate, resuming from any checkpoint
+        let deadline = match (deadline, timeout_deadline) {
+            (Some(d), Some(t)) => Some(d.min(t)),
+            (d, t) => d.or(t),
+        };
+        let result = generate_index_in_batches(
+            repo_path,
+            settings.max_depth,
+            &settings.extensions,
+            deadline,
+            INDEX_BATCH_SIZE,
+            progress,
+        )
+        .map_err(McpDiffError::Generic)?;
+        check_timeout(&result)?;
 
         // Re-get cache after generation (it may have been created)
         let cache = CacheDir::for_repo(repo_path)
-            .map_err(|e| format!("Failed to access cache after generation: {}", e))?;
+            .map_err(|e| e.with_context("Failed to access cache after generation"))?;
 
         return Ok(FreshnessResult {
             cache,
@@ -541,9 +929,19 @@ pub fn ensure_fresh_index(
             refresh_type: RefreshType::Full,
             files_updated: result.files_analyzed,
             duration_ms: start.elapsed().as_millis() as u64,
+            indexing_status: result.indexing_status,
+            recovery,
         });
     }
 
+    // Search terms from an older tokenizer no longer match queries; only the
+    // BM25 index depends on it, so rebuild that from the symbol index
+    if cache.bm25_needs_rebuild() {
+        tracing::info!("[CACHE] Tokenizer changed; rebuilding BM25 search index");
+        crate::shard::rebuild_bm25_index(&cache)
+            .map_err(|e| e.with_context("Failed to rebuild BM25 index"))?;
+    }
+
     // Index exists - check staleness
     let staleness = cache.quick_staleness_check();
 
@@ -555,6 +953,8 @@ pub fn ensure_fresh_index(
             refresh_type: RefreshType::None,
             files_updated: 0,
             duration_ms: start.elapsed().as_millis() as u64,
+            indexing_status: None,
+            recovery: None,
         });
     }
 
@@ -563,7 +963,8 @@ pub fn ensure_fresh_index(
 
     if changed_count <= threshold && changed_count > 0 {
         // Partial reindex - only update changed files
-        let result = partial_reindex(&cache, &staleness.changed_files)?;
+        let result =
+            partial_reindex(&cache, &staleness.changed_files).map_err(McpDiffError::Generic)?;
 
         return Ok(FreshnessResult {
             cache,
@@ -571,11 +972,22 @@ pub fn ensure_fresh_index(
             refresh_type: RefreshType::Partial,
             files_updated: result.files_reindexed,
             duration_ms: start.elapsed().as_millis() as u64,
+            indexing_status: None,
+            recovery: None,
         });
     }
 
     // Too many changes or can't determine - full reindex
-    let result = generate_index_internal(repo_path, 10, &[])?;
+    let result = generate_index_in_batches(
+        repo_path,
+        settings.max_depth,
+        &settings.extensions,
+        timeout_deadline,
+        INDEX_BATCH_SIZE,
+        progress,
+    )
+    .map_err(McpDiffError::Generic)?;
+    check_timeout(&result)?;
 
     // Update the indexed SHA after full reindex
     if let Ok(sha) = crate::git::git_command(&["rev-parse", "HEAD"], Some(&cache.repo_root)) {
@@ -588,11 +1000,38 @@ pub fn ensure_fresh_index(
         refresh_type: RefreshType::Full,
         files_updated: result.files_analyzed,
         duration_ms: start.elapsed().as_millis() as u64,
+        indexing_status: None,
+        recovery: None,
     })
 }
 
 /// Format a freshness note for inclusion in query responses
 pub fn format_freshness_note(result: &FreshnessResult) -> Option<String> {
+    let note = refresh_note(result);
+    let Some(ref recovery) = result.recovery else {
+        return note;
+    };
+    let recovered = format!(
+        "🩹 Cache was unreadable ({}); moved to {} and regenerated",
+        recovery.reason,
+        recovery.quarantined_to.display()
+    );
+    Some(match note {
+        Some(note) => format!("{}\n{}", recovered, note),
+        None => recovered,
+    })
+}
+
+fn refresh_note(result: &FreshnessResult) -> Option<String> {
+    if let Some(ref status) = result.indexing_status {
+        return Some(format!(
+            "⏳ Partial index ({} files in {}ms)\n{}",
+            result.files_updated,
+            result.duration_ms,
+            status.coverage_note()
+        ));
+    }
+
     if !result.refreshed {
         return None;
     }
@@ -610,6 +1049,29 @@ pub fn format_freshness_note(result: &FreshnessResult) -> Option<String> {
     }
 }
 
+/// Run a query against a single index generation
+///
+/// Pins the repository's current generation on this thread for the duration
+/// of `f`, so a partial reindex finishing mid-query can't mix old and new
+/// shards into one answer (see [`generation`]). `f` must not await.
+pub fn with_pinned_index<R>(repo_path: &Path, f: impl FnOnce() -> R) -> R {
+    let _pin = CacheDir::for_repo(repo_path)
+        .ok()
+        .map(|cache| cache.pin_generation());
+    f()
+}
+
+/// Prefix tool output with an `index_coverage` note when the index is partial
+pub fn with_index_coverage(repo_path: &Path, output: String) -> String {
+    match CacheDir::for_repo(repo_path)
+        .ok()
+        .and_then(|cache| cache.partial_status())
+    {
+        Some(status) => format!("{}\n\n{}", status.coverage_note(), output),
+        None => output,
+    }
+}
+
 // ============================================================================
 // Symbol Validation Helpers
 // ============================================================================
@@ -665,6 +1127,22 @@ pub struct SymbolValidationResult {
     pub high_risk_callers: Vec<String>,
     /// Actionable suggestions
     pub suggestions: Vec<String>,
+    /// CODEOWNERS owners of the symbol's file
+    pub owners: Vec<String>,
+    /// Top git blame author (indexes built with `--with-blame`)
+    pub author: Option<SymbolAuthor>,
+    /// Whether the symbol is declared async
+    pub is_async: bool,
+    /// `await` expressions in the body
+    pub awaits_count: usize,
+    /// I/O calls made outside any try block
+    pub io_calls_outside_try: usize,
+    /// Whether a catch/except block swallows errors
+    pub has_empty_catch: bool,
+    /// Whether a catch/except block throws
+    pub rethrows: bool,
+    /// Places the body can panic or throw
+    pub panic_points: usize,
 }
 
 /// Find a symbol by hash in the index
@@ -906,6 +1384,8 @@ pub fn validate_single_symbol(
     // Generate suggestions
     let suggestions = generate_validation_suggestions(&complexity_concerns, &duplicates, &callers);
 
+    let ownership = OwnershipIndex::load(cache).unwrap_or_default();
+
     SymbolValidationResult {
         symbol: entry.symbol.clone(),
         file: entry.file.clone(),
@@ -920,6 +1400,14 @@ pub fn validate_single_symbol(
         callers,
         high_risk_callers,
         suggestions,
+        owners: ownership.owners_of(&entry.file).to_vec(),
+        author: ownership.authors.get(&entry.hash).cloned(),
+        is_async: entry.is_async,
+        awaits_count: entry.awaits_count,
+        io_calls_outside_try: entry.io_calls_outside_try,
+        has_empty_catch: entry.has_empty_catch,
+        rethrows: entry.rethrows,
+        panic_points: entry.panic_points,
     }
 }
 
@@ -931,12 +1419,29 @@ pub fn format_validation_result(result: &SymbolValidationResult) -> String {
     output.push_str(&format!("lines: {}\n", result.lines));
     output.push_str(&format!("kind: {}\n", result.kind));
     output.push_str(&format!("hash: {}\n", result.hash));
+    if !result.owners.is_empty() {
+        output.push_str(&format!("owners: {}\n", result.owners.join(", ")));
+    }
+    if let Some(author) = &result.author {
+        output.push_str(&format!("author: {}\n", author.describe()));
+    }
 
     output.push_str("\ncomplexity:\n");
     output.push_str(&format!("  cognitive: {}\n", result.cognitive_complexity));
     output.push_str(&format!("  max_nesting: {}\n", result.max_nesting));
     output.push_str(&format!("  risk: {}\n", result.risk));
 
+    output.push_str("\nerror_handling:\n");
+    output.push_str(&format!("  async: {}\n", result.is_async));
+    output.push_str(&format!("  awaits: {}\n", result.awaits_count));
+    output.push_str(&format!(
+        "  io_calls_outside_try: {}\n",
+        result.io_calls_outside_try
+    ));
+    output.push_str(&format!("  empty_catch: {}\n", result.has_empty_catch));
+    output.push_str(&format!("  rethrows: {}\n", result.rethrows));
+    output.push_str(&format!("  panic_points: {}\n", result.panic_points));
+
     output.push_str("\nduplicates:\n");
     if result.duplicates.is_empty() {
         output.push_str("  (none found above threshold)\n");
@@ -982,6 +1487,179 @@ pub fn format_validation_result(result: &SymbolValidationResult) -> String {
     output
 }
 
+/// Everything needed to refactor a symbol safely
+///
+/// Built fresh on every `start_refactoring_session` call; nothing is kept
+/// between calls.
+#[derive(Debug, Clone)]
+pub struct RefactoringContext {
+    /// Complexity, duplicates and callers of the symbol
+    pub validation: SymbolValidationResult,
+    /// Module containing the symbol
+    pub module: String,
+    /// Whether the symbol is public API
+    pub is_exported: bool,
+    /// Framework that invokes the symbol, if any (e.g. "Express route handler")
+    pub framework_entry_point: Option<String>,
+    /// Test files exercising the symbol, strongest evidence first
+    pub tested_by: Vec<String>,
+    /// Ordered steps for the refactoring
+    pub checklist: Vec<String>,
+}
+
+/// Gather the refactoring context for a symbol
+pub fn build_refactoring_context(
+    cache: &CacheDir,
+    entry: &SymbolIndexEntry,
+    duplicate_threshold: f64,
+) -> RefactoringContext {
+    let validation = validate_single_symbol(cache, entry, duplicate_threshold);
+    let tested_by: Vec<String> = crate::test_links::TestLinkIndex::load(cache)
+        .ok()
+        .and_then(|links| links.tested_by.get(&entry.hash).cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|file_ref| file_ref.file)
+        .collect();
+    let checklist = refactoring_checklist(entry, &validation, &tested_by);
+
+    RefactoringContext {
+        module: entry.module.clone(),
+        is_exported: entry.is_exported,
+        framework_entry_point: entry
+            .framework_entry_point
+            .is_entry_point()
+            .then(|| entry.framework_entry_point.description().to_string()),
+        tested_by,
+        checklist,
+        validation,
+    }
+}
+
+/// Ordered steps for refactoring a symbol without breaking its users
+pub fn refactoring_checklist(
+    entry: &SymbolIndexEntry,
+    validation: &SymbolValidationResult,
+    tested_by: &[String],
+) -> Vec<String> {
+    let mut steps = Vec::new();
+
+    if tested_by.is_empty() {
+        steps.push(format!(
+            "No tests are linked to {} - add a test pinning its current behavior first",
+            entry.symbol
+        ));
+    } else {
+        steps.push(format!(
+            "Run the tests covering {} before changing it: {}",
+            entry.symbol,
+            tested_by.join(", ")
+        ));
+    }
+
+    if !validation.duplicates.is_empty() {
+        let names: Vec<&str> = validation
+            .duplicates
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        steps.push(format!(
+            "Consolidate with its {} near-duplicate(s) ({}) or apply the same change to each",
+            names.len(),
+            names.join(", ")
+        ));
+    }
+
+    if entry.cognitive_complexity > 10 || entry.max_nesting > 4 {
+        steps.push(format!(
+            "Reduce complexity (cognitive {}, nesting {}) by extracting helpers and using guard clauses",
+            entry.cognitive_complexity, entry.max_nesting
+        ));
+    }
+
+    if !validation.callers.is_empty() {
+        let high_risk = if validation.high_risk_callers.is_empty() {
+            String::new()
+        } else {
+            format!(" (high risk: {})", validation.high_risk_callers.join(", "))
+        };
+        steps.push(format!(
+            "Keep the signature compatible or update all {} direct caller(s){}",
+            validation.callers.len(),
+            high_risk
+        ));
+    } else if entry.framework_entry_point.is_entry_point() {
+        steps.push(format!(
+            "Called by the framework ({}), not by indexed code - keep its registration and signature",
+            entry.framework_entry_point.description()
+        ));
+    } else if entry.is_exported {
+        steps.push(
+            "Exported with no indexed callers - check external consumers before changing its signature"
+                .to_string(),
+        );
+    }
+
+    steps.push("Change in small steps, re-running the tests after each".to_string());
+    steps.push(
+        "Re-index and validate the new symbol to confirm complexity and duplicates improved"
+            .to_string(),
+    );
+
+    steps
+}
+
+/// Format a refactoring context to TOON format
+pub fn format_refactoring_context(context: &RefactoringContext) -> String {
+    let result = &context.validation;
+    let mut output = toon_header("refactoring_context");
+    output.push_str(&format!("symbol: {}\n", result.symbol));
+    output.push_str(&format!("hash: {}\n", result.hash));
+    output.push_str(&format!("kind: {}\n", result.kind));
+    output.push_str(&format!("file: {}\n", result.file));
+    output.push_str(&format!("lines: {}\n", result.lines));
+    output.push_str(&format!("module: {}\n", context.module));
+    output.push_str(&format!("exported: {}\n", context.is_exported));
+    if let Some(ref fep) = context.framework_entry_point {
+        output.push_str(&format!("framework_entry_point: {}\n", fep));
+    }
+
+    output.push_str("\ncomplexity:\n");
+    output.push_str(&format!("  cognitive: {}\n", result.cognitive_complexity));
+    output.push_str(&format!("  max_nesting: {}\n", result.max_nesting));
+    output.push_str(&format!("  risk: {}\n", result.risk));
+
+    output.push_str(&format!("\ncallers[{}]:\n", result.callers.len()));
+    for caller in &result.callers {
+        output.push_str(&format!(
+            "  - {} ({}) risk={}\n",
+            caller.name, caller.hash, caller.risk
+        ));
+    }
+
+    output.push_str(&format!("\nduplicates[{}]:\n", result.duplicates.len()));
+    for dup in &result.duplicates {
+        output.push_str(&format!(
+            "  - {} ({}) [{:.0}%]\n",
+            dup.name,
+            dup.file,
+            dup.similarity * 100.0
+        ));
+    }
+
+    output.push_str(&format!("\ntested_by[{}]:\n", context.tested_by.len()));
+    for file in &context.tested_by {
+        output.push_str(&format!("  - {}\n", file));
+    }
+
+    output.push_str(&format!("\nchecklist[{}]:\n", context.checklist.len()));
+    for (i, step) in context.checklist.iter().enumerate() {
+        output.push_str(&format!("  {}. {}\n", i + 1, step));
+    }
+
+    output
+}
+
 /// Batch validate multiple symbols and return aggregated results
 pub fn validate_symbols_batch(
     cache: &CacheDir,
@@ -1019,6 +1697,11 @@ pub fn format_batch_validation_results(
         .filter(|r| !r.duplicates.is_empty())
         .collect();
     let high_impact: Vec<_> = results.iter().filter(|r| r.callers.len() > 10).collect();
+    let io_outside_try = results
+        .iter()
+        .filter(|r| r.io_calls_outside_try > 0)
+        .count();
+    let empty_catch = results.iter().filter(|r| r.has_empty_catch).count();
 
     output.push_str("\nsummary:\n");
     output.push_str(&format!(
@@ -1041,6 +1724,14 @@ pub fn format_batch_validation_results(
         "  high_impact: {} (>10 callers)\n",
         high_impact.len()
     ));
+    output.push_str(&format!(
+        "  io_outside_try: {} (I/O calls outside try blocks)\n",
+        io_outside_try
+    ));
+    output.push_str(&format!(
+        "  empty_catch: {} (catch blocks that swallow errors)\n",
+        empty_catch
+    ));
 
     // List symbols needing attention (high complexity first)
     if !high_complexity.is_empty() {
@@ -1101,20 +1792,43 @@ pub fn format_batch_validation_results(
         }
     }
 
+    // Symbols per owner, so a module audit shows which teams are involved
+    if results.iter().any(|r| !r.owners.is_empty()) {
+        let mut by_owner: BTreeMap<&str, usize> = BTreeMap::new();
+        for r in results {
+            if r.owners.is_empty() {
+                *by_owner.entry("(unowned)").or_insert(0) += 1;
+            }
+            for owner in &r.owners {
+                *by_owner.entry(owner).or_insert(0) += 1;
+            }
+        }
+        output.push_str("\nowners:\n");
+        for (owner, count) in by_owner {
+            output.push_str(&format!("  {}: {} symbols\n", owner, count));
+        }
+    }
+
     // All symbols table (compact)
     output.push_str(&format!(
-        "\nall_symbols[{}]{{name,cc,nest,dups,callers,risk}}:\n",
+        "\nall_symbols[{}]{{name,cc,nest,dups,callers,risk,async,awaits,io_outside_try,empty_catch,rethrows}}:\n",
         results.len()
     ));
+    let flag = |set: bool| if set { "Y" } else { "_" };
     for r in results.iter().take(50) {
         output.push_str(&format!(
-            "  {},{},{},{},{},{}\n",
+            "  {},{},{},{},{},{},{},{},{},{},{}\n",
             r.symbol,
             r.cognitive_complexity,
             r.max_nesting,
             r.duplicates.len(),
             r.callers.len(),
-            r.risk
+            r.risk,
+            flag(r.is_async),
+            r.awaits_count,
+            r.io_calls_outside_try,
+            flag(r.has_empty_catch),
+            flag(r.rethrows)
         ));
     }
     if results.len() > 50 {
@@ -1124,6 +1838,110 @@ pub fn format_batch_validation_results(
     output
 }
 
+// ============================================================================
+// Index Patching Helpers
+// ============================================================================
+
+/// Result of applying symbol patches to the layered index
+#[derive(Debug, Clone, Default)]
+pub struct PatchOutcome {
+    /// Patched symbols as (hash, name, layer the symbol was resolved from)
+    pub applied: Vec<(String, String, LayerKind)>,
+    /// Hashes not found in the layered index or the symbol index
+    pub not_found: Vec<String>,
+}
+
+/// Apply patches to the working layer of `index`
+///
+/// Symbols missing from the layered index (e.g. when layers were never
+/// built) are seeded from the cached symbol index before patching.
+pub fn apply_symbol_patches(
+    index: &mut LayeredIndex,
+    cache: &CacheDir,
+    patches: &[SymbolPatch],
+) -> PatchOutcome {
+    let mut outcome = PatchOutcome::default();
+    let mut entries: Option<HashMap<String, SymbolIndexEntry>> = None;
+
+    for patch in patches {
+        let hash = &patch.symbol_hash;
+        let mut layer = index.apply_patch(patch);
+
+        if layer.is_none() {
+            let entries = entries.get_or_insert_with(|| {
+                cache
+                    .load_all_symbol_entries()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|e| (e.hash.clone(), e))
+                    .collect()
+            });
+            if let Some(entry) = entries.get(hash) {
+                index.working.upsert(
+                    hash.clone(),
+                    SymbolState::active_at(
+                        symbol_info_from_entry(entry),
+                        PathBuf::from(&entry.file),
+                    ),
+                );
+                layer = index.apply_patch(patch).map(|_| LayerKind::Base);
+            }
+        }
+
+        match (layer, index.resolve_symbol(hash)) {
+            (Some(layer), Some(symbol)) => {
+                outcome
+                    .applied
+                    .push((hash.clone(), symbol.name.clone(), layer))
+            }
+            _ => outcome.not_found.push(hash.clone()),
+        }
+    }
+
+    outcome
+}
+
+/// Rebuild a `SymbolInfo` from its symbol index entry
+fn symbol_info_from_entry(entry: &SymbolIndexEntry) -> SymbolInfo {
+    let (start_line, end_line) = entry
+        .lines
+        .split_once('-')
+        .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)))
+        .unwrap_or((0, 0));
+
+    SymbolInfo {
+        name: entry.symbol.clone(),
+        kind: SymbolKind::from_str(&entry.kind),
+        start_line,
+        end_line,
+        is_exported: entry.is_exported,
+        hash: Some(entry.hash.clone()),
+        return_type: (!entry.return_type.is_empty()).then(|| entry.return_type.clone()),
+        behavioral_risk: RiskLevel::from_str(&entry.risk),
+        is_async: entry.is_async,
+        base_classes: entry
+            .base_classes
+            .split(',')
+            .filter(|b| !b.is_empty())
+            .map(String::from)
+            .collect(),
+        feature_flags: entry
+            .feature_flags
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .map(String::from)
+            .collect(),
+        is_deprecated: entry.is_deprecated,
+        deprecation_message: (!entry.deprecation_message.is_empty())
+            .then(|| entry.deprecation_message.clone()),
+        awaits_count: entry.awaits_count,
+        io_calls_outside_try: entry.io_calls_outside_try,
+        has_empty_catch: entry.has_empty_catch,
+        rethrows: entry.rethrows,
+        ..Default::default()
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1157,9 +1975,63 @@ mod tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         }
     }
 
+    #[test]
+    fn test_apply_symbol_patches_seeds_from_symbol_index() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = CacheDir {
+            root: temp.path().join("cache"),
+            repo_root: temp.path().to_path_buf(),
+            repo_hash: "test".to_string(),
+        };
+        fs::create_dir_all(&cache.root).unwrap();
+        let entry = make_entry(1, 1);
+        fs::write(
+            cache.symbol_index_path(),
+            format!("{}\n", serde_json::to_string(&entry).unwrap()),
+        )
+        .unwrap();
+
+        let patches = vec![
+            SymbolPatch {
+                symbol_hash: "abc123".to_string(),
+                extra_params: vec!["limit: usize".to_string()],
+                ..Default::default()
+            },
+            SymbolPatch {
+                symbol_hash: "missing".to_string(),
+                ..Default::default()
+            },
+        ];
+        let mut index = LayeredIndex::new();
+        let outcome = apply_symbol_patches(&mut index, &cache, &patches);
+
+        assert_eq!(
+            outcome.applied,
+            vec![("abc123".to_string(), "test_fn".to_string(), LayerKind::Base)]
+        );
+        assert_eq!(outcome.not_found, vec!["missing".to_string()]);
+
+        let symbol = index.working.get("abc123").unwrap().as_symbol().unwrap();
+        assert_eq!(symbol.kind, SymbolKind::Function);
+        assert_eq!((symbol.start_line, symbol.end_line), (1, 10));
+        assert_eq!(symbol.arguments[0].arg_type.as_deref(), Some("usize"));
+    }
+
     #[test]
     fn test_assess_complexity_low() {
         let entry = make_entry(5, 2);
@@ -1255,6 +2127,8 @@ mod tests {
             refresh_type: RefreshType::None,
             files_updated: 0,
             duration_ms: 0,
+            indexing_status: None,
+            recovery: None,
         };
         assert!(format_freshness_note(&result).is_none());
     }
@@ -1267,6 +2141,8 @@ mod tests {
             refresh_type: RefreshType::Partial,
             files_updated: 5,
             duration_ms: 123,
+            indexing_status: None,
+            recovery: None,
         };
         let note = format_freshness_note(&result).unwrap();
         assert!(note.contains("5 files"));
@@ -1282,6 +2158,8 @@ mod tests {
             refresh_type: RefreshType::Full,
             files_updated: 100,
             duration_ms: 500,
+            indexing_status: None,
+            recovery: None,
         };
         let note = format_freshness_note(&result).unwrap();
         assert!(note.contains("100 files"));
@@ -1289,6 +2167,21 @@ mod tests {
         assert!(note.contains("🔄"));
     }
 
+    #[test]
+    fn test_format_freshness_note_partial_index() {
+        let result = FreshnessResult {
+            cache: CacheDir::for_repo(&std::env::temp_dir()).unwrap(),
+            refreshed: true,
+            refresh_type: RefreshType::Full,
+            files_updated: 62,
+            duration_ms: 30000,
+            indexing_status: Some(IndexingStatus::partial(62, 100, vec![], vec![])),
+            recovery: None,
+        };
+        let note = format_freshness_note(&result).unwrap();
+        assert!(note.contains("index_coverage: 62% (62/100 files indexed"));
+    }
+
     // ========================================================================
     // Validation Result Formatting Tests
     // ========================================================================
@@ -1309,6 +2202,14 @@ mod tests {
             callers: vec![],
             high_risk_callers: vec![],
             suggestions: vec![],
+            owners: vec![],
+            author: None,
+            is_async: false,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
         };
 
         let output = format_validation_result(&result);
@@ -1339,6 +2240,14 @@ mod tests {
             callers: vec![],
             high_risk_callers: vec![],
             suggestions: vec![],
+            owners: vec![],
+            author: None,
+            is_async: false,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
         };
 
         let output = format_validation_result(&result);
@@ -1373,6 +2282,14 @@ mod tests {
             ],
             high_risk_callers: vec!["caller_two".to_string()],
             suggestions: vec![],
+            owners: vec![],
+            author: None,
+            is_async: false,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
         };
 
         let output = format_validation_result(&result);
@@ -1413,6 +2330,14 @@ mod tests {
                 callers: vec![],
                 high_risk_callers: vec![],
                 suggestions: vec![],
+                owners: vec!["@org/core".to_string()],
+                author: None,
+                is_async: false,
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: 0,
             },
             SymbolValidationResult {
                 symbol: "simple_fn".to_string(),
@@ -1428,6 +2353,14 @@ mod tests {
                 callers: vec![],
                 high_risk_callers: vec![],
                 suggestions: vec![],
+                owners: vec![],
+                author: None,
+                is_async: false,
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: 0,
             },
         ];
 
@@ -1436,6 +2369,7 @@ mod tests {
         assert!(output.contains("high_complexity: 1"));
         assert!(output.contains("deep_nesting: 1"));
         assert!(output.contains("complex_fn"));
+        assert!(output.contains("owners:\n  (unowned): 1 symbols\n  @org/core: 1 symbols\n"));
     }
 
     // ========================================================================
@@ -1464,6 +2398,7 @@ mod tests {
             modules_written: 10,
             symbols_written: 500,
             compression_pct: 75.5,
+            indexing_status: None,
         };
         assert_eq!(result.duration_ms, 500);
         assert_eq!(result.files_analyzed, 100);
@@ -1481,4 +2416,451 @@ mod tests {
         assert_eq!(result.modules_updated, 2);
         assert_eq!(result.duration_ms, 100);
     }
+
+    // ========================================================================
+    // Time-boxed Generation Tests
+    // ========================================================================
+
+    fn write_sample_repo(root: &Path) {
+        let files = [
+            ("src/main.rs", "mod api;\nfn main() { api::serve(); }\n"),
+            (
+                "src/api/mod.rs",
+                "pub fn serve() { crate::db::connect(); }\n",
+            ),
+            (
+                "src/api/routes.rs",
+                "pub fn routes() -> Vec<String> { Vec::new() }\n",
+            ),
+            (
+                "src/api/auth.rs",
+                "pub fn login(user: &str) -> bool { !user.is_empty() }\n",
+            ),
+            (
+                "src/db/mod.rs",
+                "pub fn connect() { pool(); }\nfn pool() {}\n",
+            ),
+            (
+                "src/db/query.rs",
+                "pub fn select(table: &str) -> String { table.into() }\n",
+            ),
+            (
+                "src/util.rs",
+                "pub fn clamp(v: i32) -> i32 { if v > 10 { 10 } else { v } }\n",
+            ),
+        ];
+        for (path, content) in files {
+            let full = root.join(path);
+            fs::create_dir_all(full.parent().unwrap()).unwrap();
+            fs::write(full, content).unwrap();
+        }
+    }
+
+    /// Every generated index file with its lines sorted (shard writers emit
+    /// HashMap-ordered sections) and generation timestamps (and the
+    /// timestamp-named manifests) dropped
+    fn index_snapshot(repo: &Path) -> std::collections::BTreeMap<String, String> {
+        fn walk(dir: &Path, root: &Path, out: &mut std::collections::BTreeMap<String, String>) {
+            for entry in fs::read_dir(dir).unwrap().flatten() {
+                let path = entry.path();
+                let rel = path.strip_prefix(root).unwrap().display().to_string();
+                if ["meta.json", "progress.json", "checkpoint", "manifests"].contains(&rel.as_str())
+                    || rel.ends_with(".db")
+                {
+                    continue;
+                }
+                if path.is_dir() {
+                    walk(&path, root, out);
+                } else if let Ok(content) = fs::read_to_string(&path) {
+                    let mut lines: Vec<&str> = content
+                        .lines()
+                        .filter(|line| !line.contains("generated_at:"))
+                        .collect();
+                    lines.sort_unstable();
+                    out.insert(rel, lines.join("\n"));
+                }
+            }
+        }
+
+        let cache = CacheDir::for_repo(repo).unwrap();
+        let mut out = std::collections::BTreeMap::new();
+        walk(&cache.root, &cache.root, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_interrupted_generation_resumes_to_identical_index() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+
+        // Uninterrupted reference run
+        let full = generate_index_in_batches(repo.path(), 10, &[], None, 2, None).unwrap();
+        assert!(full.indexing_status.is_none());
+        let expected = index_snapshot(repo.path());
+        assert!(!expected.is_empty());
+        fs::remove_dir_all(CacheDir::for_repo(repo.path()).unwrap().root).unwrap();
+
+        // A deadline that has already passed stops after a single batch
+        let first =
+            generate_index_in_batches(repo.path(), 10, &[], Some(Instant::now()), 2, None).unwrap();
+        let status = first.indexing_status.expect("index should be partial");
+        assert_eq!((status.files_indexed, status.files_total), (2, 7));
+        assert!(!status.modules_pending.is_empty());
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        assert_eq!(cache.partial_status().unwrap().files_indexed, 2);
+        assert!(cache.repo_overview_path().exists());
+
+        // Crash while writing the next batch: the torn write must be ignored
+        fs::write(
+            cache.checkpoint_dir().join("batch-000001.json.tmp"),
+            "{\"files\":[",
+        )
+        .unwrap();
+        let second =
+            generate_index_in_batches(repo.path(), 10, &[], Some(Instant::now()), 2, None).unwrap();
+        assert_eq!(second.indexing_status.unwrap().files_indexed, 4);
+
+        // Resume to completion
+        let resumed = generate_index_in_batches(repo.path(), 10, &[], None, 2, None).unwrap();
+        assert!(resumed.indexing_status.is_none());
+        assert_eq!(resumed.files_analyzed, full.files_analyzed);
+        assert!(cache.partial_status().is_none());
+        assert!(!cache.checkpoint_dir().exists());
+        assert_eq!(index_snapshot(repo.path()), expected);
+    }
+
+    #[test]
+    fn test_queries_see_one_generation_during_partial_reindex() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+        ensure_fresh_index(repo.path(), None, None).unwrap();
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+
+        let done = Arc::new(AtomicBool::new(false));
+        let checks = Arc::new(AtomicUsize::new(0));
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let repo_path = repo.path().to_path_buf();
+                let (done, checks) = (Arc::clone(&done), Arc::clone(&checks));
+                std::thread::spawn(move || {
+                    while !done.load(Ordering::SeqCst) {
+                        with_pinned_index(&repo_path, || {
+                            let cache = CacheDir::for_repo(&repo_path).unwrap();
+                            let generation = cache.generation_id();
+                            let entries = cache.load_all_symbol_entries().unwrap();
+                            assert!(!entries.is_empty());
+                            for entry in &entries {
+                                assert!(
+                                    cache.symbol_path(&entry.hash).exists(),
+                                    "{} unresolvable in generation {}",
+                                    entry.hash,
+                                    generation
+                                );
+                                assert!(
+                                    cache.module_path(&entry.module).exists(),
+                                    "module {} unresolvable in generation {}",
+                                    entry.module,
+                                    generation
+                                );
+                            }
+                            assert_eq!(cache.generation_id(), generation);
+                        });
+                        checks.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect();
+
+        // Keep changing the repository: rename a function, add and remove a file
+        let query = cache.repo_root.join("src/db/query.rs");
+        let extra = cache.repo_root.join("src/db/extra.rs");
+        for round in 0..20 {
+            fs::write(
+                &query,
+                format!("pub fn select_{round}(table: &str) -> String {{ table.into() }}\n"),
+            )
+            .unwrap();
+            if round % 2 == 0 {
+                fs::write(&extra, format!("pub fn extra_{round}() {{}}\n")).unwrap();
+            } else {
+                fs::remove_file(&extra).unwrap();
+            }
+            partial_reindex(&cache, &[query.clone(), extra.clone()]).unwrap();
+        }
+        // Let every reader finish at least one request against the final index
+        let target = checks.load(Ordering::SeqCst) + readers.len();
+        while checks.load(Ordering::SeqCst) < target && !readers.iter().any(|r| r.is_finished()) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        done.store(true, Ordering::SeqCst);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(cache.generation_id(), 20);
+        let names: Vec<String> = cache
+            .load_all_symbol_entries()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.symbol)
+            .collect();
+        assert!(names.contains(&"select_19".to_string()));
+        assert!(!names
+            .iter()
+            .any(|n| n == "select" || n.starts_with("extra_")));
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_partial_then_resume() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+
+        let partial = ensure_fresh_index(repo.path(), None, Some(Instant::now())).unwrap();
+        // Every file fits in one batch, so even an expired deadline completes
+        assert!(partial.indexing_status.is_none());
+        assert_eq!(partial.refresh_type, RefreshType::Full);
+
+        // Leave the index partial, as a time-boxed run on a larger repo would
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        fs::remove_dir_all(&cache.root).unwrap();
+        generate_index_in_batches(repo.path(), 10, &[], Some(Instant::now()), 3, None).unwrap();
+
+        let note = with_index_coverage(repo.path(), "output".to_string());
+        assert!(note.starts_with("index_coverage: 42% (3/7 files indexed"));
+        assert!(note.ends_with("\n\noutput"));
+
+        // The next call resumes instead of treating the index as fresh
+        let resumed = ensure_fresh_index(repo.path(), None, None).unwrap();
+        assert!(resumed.refreshed);
+        assert!(resumed.indexing_status.is_none());
+        assert_eq!(
+            with_index_coverage(repo.path(), "output".to_string()),
+            "output"
+        );
+    }
+
+    #[test]
+    fn test_parse_index_timeout() {
+        assert_eq!(parse_index_timeout(None), None);
+        assert_eq!(
+            parse_index_timeout(Some(" 90 ")),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(parse_index_timeout(Some("0")), None);
+        assert_eq!(parse_index_timeout(Some("1.5")), None);
+        assert_eq!(parse_index_timeout(Some("soon")), None);
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_timeout_only_fails_unfinished_runs() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+
+        // Every file fits in one batch, so generation finishes despite the budget
+        let result =
+            ensure_fresh_index_within(repo.path(), None, None, Some(Duration::from_nanos(1)), None)
+                .unwrap();
+        assert!(result.indexing_status.is_none());
+        assert_eq!(result.refresh_type, RefreshType::Full);
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_quarantines_corrupt_cache() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+        ensure_fresh_index(repo.path(), None, None).unwrap();
+
+        // Cut the symbol index short, as a crash mid-write would
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        let index = fs::read(cache.symbol_index_path()).unwrap();
+        fs::write(cache.symbol_index_path(), &index[..index.len() - 20]).unwrap();
+
+        let result = ensure_fresh_index(repo.path(), None, None).unwrap();
+        assert_eq!(result.refresh_type, RefreshType::Full);
+        let recovery = result.recovery.clone().expect("cache should be recovered");
+        assert!(recovery.quarantined_to.join("symbol_index.jsonl").exists());
+        assert!(format_freshness_note(&result)
+            .unwrap()
+            .starts_with("🩹 Cache was unreadable"));
+
+        assert!(verify::check_essentials(&result.cache).is_ok());
+        assert!(!result.cache.load_all_symbol_entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_refactoring_checklist_flags_untested_complex_exported_symbol() {
+        let mut entry = make_entry(18, 5);
+        entry.is_exported = true;
+        let validation = SymbolValidationResult {
+            symbol: entry.symbol.clone(),
+            file: entry.file.clone(),
+            lines: entry.lines.clone(),
+            kind: entry.kind.clone(),
+            hash: entry.hash.clone(),
+            cognitive_complexity: entry.cognitive_complexity,
+            max_nesting: entry.max_nesting,
+            risk: entry.risk.clone(),
+            complexity_concerns: assess_complexity(&entry),
+            duplicates: Vec::new(),
+            callers: Vec::new(),
+            high_risk_callers: Vec::new(),
+            suggestions: Vec::new(),
+            owners: Vec::new(),
+            author: None,
+            is_async: false,
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+        };
+
+        let steps = refactoring_checklist(&entry, &validation, &[]);
+        assert!(steps[0].starts_with("No tests are linked to test_fn"));
+        assert!(steps[1].contains("cognitive 18, nesting 5"));
+        assert!(steps[2].starts_with("Exported with no indexed callers"));
+        assert!(steps.last().unwrap().starts_with("Re-index and validate"));
+    }
+
+    #[test]
+    fn test_build_refactoring_context_lists_callers_and_duplicates() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let body = "const total = items.reduce((sum, item) => sum + item.price * item.qty, 0);\n  \
+                    if (total > 100) { return total * 0.9; }\n  return total;";
+        fs::create_dir_all(repo.path().join("src")).unwrap();
+        fs::write(
+            repo.path().join("src/pricing.ts"),
+            format!(
+                "export function priceOrder(items: any[]) {{\n  {body}\n}}\n\n\
+                 export function priceCart(items: any[]) {{\n  {body}\n}}\n"
+            ),
+        )
+        .unwrap();
+        fs::write(
+            repo.path().join("src/checkout.ts"),
+            "import { priceOrder } from './pricing';\n\n\
+             export function checkout(items: any[]) {\n  return priceOrder(items);\n}\n",
+        )
+        .unwrap();
+        let cache = ensure_fresh_index(repo.path(), None, None).unwrap().cache;
+
+        let entry = cache
+            .load_all_symbol_entries()
+            .unwrap()
+            .into_iter()
+            .find(|e| e.symbol == "priceOrder")
+            .expect("priceOrder should be indexed");
+        let context = build_refactoring_context(&cache, &entry, 0.8);
+
+        assert!(context.is_exported);
+        assert!(context.tested_by.is_empty());
+        assert!(context
+            .validation
+            .callers
+            .iter()
+            .any(|c| c.name == "checkout"));
+        assert!(context
+            .validation
+            .duplicates
+            .iter()
+            .any(|d| d.name == "priceCart"));
+        assert!(context
+            .checklist
+            .iter()
+            .any(|step| step.starts_with("Keep the signature compatible")));
+
+        let output = format_refactoring_context(&context);
+        assert!(output.contains("symbol: priceOrder"));
+        assert!(output.contains("  - checkout ("));
+        assert!(output.contains("checklist["));
+    }
+
+    /// Commit everything in `dir` as the root commit of a fresh git repository
+    fn git_init_and_commit(dir: &Path, message: &str) {
+        fs::write(dir.join(".gitignore"), ".semfora-cache/\n").unwrap();
+        for args in [
+            &["init", "-q", "-b", "main"][..],
+            &["config", "user.email", "test@example.com"],
+            &["config", "user.name", "Test User"],
+            &["add", "-A"],
+            &["commit", "-q", "-m", message],
+        ] {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .status()
+                .expect("Failed to run git");
+            assert!(status.success(), "git {:?} failed", args);
+        }
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_reuses_cache_with_matching_fingerprint() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+        git_init_and_commit(repo.path(), "initial");
+        ensure_fresh_index(repo.path(), None, None).unwrap();
+
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        assert!(cache.load_meta().unwrap().repo_fingerprint.is_some());
+        assert!(cache.fingerprint_matches());
+
+        let marker = cache.root.join("marker");
+        fs::write(&marker, "kept").unwrap();
+
+        let result = ensure_fresh_index(repo.path(), None, None).unwrap();
+        assert_eq!(result.refresh_type, RefreshType::None);
+        assert!(marker.exists());
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_rebuilds_cache_with_mismatched_fingerprint() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+        git_init_and_commit(repo.path(), "first project");
+        ensure_fresh_index(repo.path(), None, None).unwrap();
+
+        // A different repository is checked out at the same path
+        fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        git_init_and_commit(repo.path(), "second project");
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        let marker = cache.root.join("marker");
+        fs::write(&marker, "stale").unwrap();
+        assert!(!cache.fingerprint_matches());
+
+        let result = ensure_fresh_index(repo.path(), None, None).unwrap();
+        assert_eq!(result.refresh_type, RefreshType::Full);
+        assert!(!marker.exists());
+        assert!(result.cache.fingerprint_matches());
+        assert!(!result.cache.load_all_symbol_entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ensure_fresh_index_rebuilds_bm25_for_new_tokenizer() {
+        let repo = tempfile::TempDir::new().unwrap();
+        write_sample_repo(repo.path());
+        git_init_and_commit(repo.path(), "initial");
+        ensure_fresh_index(repo.path(), None, None).unwrap();
+
+        // Terms written by an older tokenizer that no longer match queries
+        let cache = CacheDir::for_repo(repo.path()).unwrap();
+        let conn = rusqlite::Connection::open(cache.bm25_index_path()).unwrap();
+        conn.execute_batch("DELETE FROM bm25_terms; UPDATE bm25_meta SET tokenizer_version = NULL")
+            .unwrap();
+        drop(conn);
+        assert!(cache.bm25_needs_rebuild());
+        let marker = cache.root.join("marker");
+        fs::write(&marker, "kept").unwrap();
+
+        let result = ensure_fresh_index(repo.path(), None, None).unwrap();
+        assert_eq!(result.refresh_type, RefreshType::None);
+        assert!(marker.exists(), "only the BM25 index should be rebuilt");
+        assert!(!result.cache.bm25_needs_rebuild());
+
+        let results =
+            crate::bm25::search_sqlite(&result.cache.bm25_index_path(), "connections", 10).unwrap();
+        assert!(results.iter().any(|r| r.symbol == "connect"));
+    }
 }
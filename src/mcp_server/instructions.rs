@@ -99,10 +99,15 @@ TOTAL: ~6,000 tokens
   - `mode: "symbols"`: exact name match only
   - `mode: "semantic"`: BM25 conceptual only
   - `mode: "raw"`: regex for comments/strings
+- **semantic_grep**: Symbols by behavior - `calls:fetchUser`, `mutates:session`, `imports:axios`, `returns:Promise<User>`
 - **get_symbol**: Symbol details by hash (supports batch with `hashes: [...]`)
 - **get_source**: Surgical source code read (by hash or file+lines)
 - **get_file**: File symbols OR module symbols (mutually exclusive params)
 - **get_callers**: Who calls this function? (impact analysis)
+- **get_module_usages**: Who imports this module? `unused: true` lists modules nothing imports
+- **get_api_surface**: What does this module export? Functions with parameters, constants, optionally types
+- **get_components**: React render tree under a component, or which components call a hook
+- **symbol_history**: Commits that changed a symbol, across renames (why/when did this change?)
 
 ### Repository Analysis
 - **get_context**: Quick git/project context (~200 tokens) - USE FIRST
@@ -110,6 +115,7 @@ TOTAL: ~6,000 tokens
 - **get_languages**: List supported programming languages
 - **analyze**: Unified analysis - auto-detects file, directory, or module
 - **analyze_diff**: Semantic diff between branches/commits
+- **analyze_snippet**: Duplicates, CVE matches and complexity for unsaved code
 - **get_callgraph**: Call graph with optional `export: "sqlite"` for visualization
 
 ### Quality & Duplicates
@@ -119,6 +125,7 @@ TOTAL: ~6,000 tokens
   - `module`: All symbols in module (use names from `get_overview`)
   - ⚠️ No project-wide scan - must specify scope
 - **find_duplicates**: Codebase scan (default) or single symbol check (`symbol_hash`)
+- **start_refactoring_session**: Callers, duplicates, risk, tests and a step checklist for one symbol (`symbol_hash`)
 
 ### Security
 - **security**: CVE vulnerability scanning
@@ -137,9 +144,12 @@ TOTAL: ~6,000 tokens
 - **test**: Unified test runner
   - Default: Run tests with auto-detected framework
   - `detect_only: true`: Only detect framework, don't run
+- **generate_test_stubs**: Skeleton tests for a symbol hash (happy path + one per branch/loop/try)
 
 ### Server & Commit
 - **server_status**: Server mode info with optional `include_layers: true`
+- **patch_index**: Add LSP/IDE-derived kinds, calls, typed params or docs to symbols by hash
+- **stage_ai_edit**: Stage proposed file content; search/get_symbol/get_callers answer as if applied. Review with `analyze_diff(base_ref: "WORKING", target_ref: "AI")`, drop with **clear_ai_edits**
 - **prep_commit**: Gather commit info (never commits, just prepares)
 
 ## AVOID These Patterns
@@ -30,7 +30,8 @@ get_context:200 | get_overview:1-2k | search:500-1k | validate:1-2k | get_caller
 
 ## Tools
 Start: get_context, get_overview
-Search: search, get_file, get_symbol, get_source
-Analysis: analyze, analyze_diff, get_callers, get_callgraph
-Quality: validate, find_duplicates
-Ops: index, test, lint, prep_commit"#;
+Search: search, semantic_grep, get_file, get_symbol, get_source
+Analysis: analyze, analyze_diff, get_callers, get_module_usages, get_api_surface, get_components, symbol_history, get_callgraph
+Quality: validate, find_duplicates, start_refactoring_session
+Ops: index, test, generate_test_stubs, lint, prep_commit
+Staged edits: stage_ai_edit, list_ai_edits, clear_ai_edits"#;
@@ -104,11 +104,17 @@ Results from tools include symbol hashes. USE THEM:
 - **get_file**: Symbols in file (use `file_path`) OR module (use `module`). Mutually exclusive.
 - **get_symbol**: Full semantic details by hash. Supports batch with `hashes: [...]` (max 20).
 - **get_source**: Code extraction by hash or file+lines. Use for final edits.
+- **semantic_grep**: Symbols by behavior, not text: `calls:axios.get`, `mutates:session`, `imports:axios`, `returns:Promise<User>`.
 
 ### Analysis
 - **analyze**: Unified analysis - auto-detects file, directory, or module scope.
 - **analyze_diff**: Semantic diff between refs. Use `target_ref: "WORKING"` for uncommitted.
+- **analyze_snippet**: Check a function before writing it: duplicates already in the index, CVE matches, complexity.
 - **get_callers**: Who calls this function? **USE BEFORE modifying or recommending changes.**
+- **get_module_usages**: Which modules import a module (names, counts, barrels). Use before deleting or moving a module; `unused: true` lists deletion candidates.
+- **get_api_surface**: A module's exported functions (parameters, return types), constants and, with `include_types: true`, types. Answers "what can I call from this module?" without reading its files.
+- **get_components**: React render tree under a component (`root`), or the components calling a hook (`hook: "useContext(ThemeContext)"`), with file, line and risk per component.
+- **symbol_history**: Commits that changed a symbol, newest first, following function and file renames. Use to find when and why code changed.
 - **get_callgraph**: Dependency graph. Use `summary_only: true` for ~300 tokens vs ~2-6k full.
 
 ### Quality
@@ -118,13 +124,17 @@ Results from tools include symbol hashes. USE THEM:
   - `module`: All symbols in module (use names from `get_overview`)
   - No project-wide scan - must specify scope
 - **find_duplicates**: Duplicate detection. Default: full codebase scan. Or pass `symbol_hash` for single check.
+- **start_refactoring_session**: Everything needed before refactoring a symbol (by hash): callers, near-duplicates, risk, complexity, covering tests and an ordered checklist. Stateless - call again after each change.
 
 ### Operations
 - **index**: Smart refresh (auto-triggered by other tools). Use `force: true` to regenerate.
 - **test**: Run tests with auto-detected framework. Use `detect_only: true` to just detect.
+- **generate_test_stubs**: Skeleton tests for a symbol (by hash) in the detected framework: happy path plus one test per branch/loop/try. Returns source only; nothing is written.
 - **lint**: Run linters with auto-detection. Supports Rust (clippy, rustfmt), JS/TS (ESLint, Prettier, Biome, TSC), Python (ruff, black, mypy), Go (golangci-lint, gofmt, go vet). Use `detect_only: true` to just detect, `mode: "fix"` to auto-fix.
 - **prep_commit**: Gather commit context for writing commit messages. Never commits.
 - **server_status**: Diagnostic info with optional `include_layers: true`.
+- **patch_index**: Enrich symbols (by hash) with LSP/IDE kinds, calls, typed params or doc comments. Goes to the working layer; no re-index.
+- **stage_ai_edit**: Stage proposed content for a file without writing it. Until `clear_ai_edits`, search/get_symbol/get_callers see the staged symbols (removed ones are hidden) and `analyze_diff(base_ref: "WORKING", target_ref: "AI")` diffs them. `list_ai_edits` shows what is staged. Memory only; never cached.
 
 ## AVOID These Patterns
 
@@ -15,7 +15,14 @@ pub(super) const MCP_INSTRUCTIONS: &str = r#"MCP Semantic Diff - Code Analysis f
 | "Analyze this file" | analyze(path) | On-demand parsing |
 | "Review changes/PR" | analyze_diff(base_ref) | Independent of index |
 | "What calls this?" | search → get_callers(hash) | Direct to impact |
+| "Who calls/mutates/returns X?" | semantic_grep("calls:X") | No hash needed |
+| "Can I delete/move this module?" | get_module_usages(module) | Module-level impact |
+| "What can I call from this module?" | get_overview → get_api_surface(module) | Exports with signatures |
+| "What does this React component render?" | get_components(root) / get_components(hook) | Render tree, hook users |
+| "When/why did this change?" | search → symbol_history(hash) | Follows renames |
+| "What would my edit break?" | stage_ai_edit → get_callers / analyze_diff(WORKING, AI) | Impact before writing |
 | "Check quality" | get_overview → validate(module) | Need module names first |
+| "Refactor this function" | search → start_refactoring_session(hash) | Callers, duplicates, checklist |
 
 ## Large File Strategy (>500 lines)
 
@@ -107,10 +114,11 @@ pub(super) const MCP_INSTRUCTIONS: &str = r#"MCP Semantic Diff - Code Analysis f
 ## Tools Quick Reference
 
 **Start:** get_context, get_overview
-**Search:** search (hybrid default), get_file, get_symbol, get_source
-**Analysis:** analyze, analyze_diff, get_callers, get_callgraph
-**Quality:** validate (requires scope!), find_duplicates
-**Ops:** index, test, lint, prep_commit
+**Search:** search (hybrid default), semantic_grep, get_file, get_symbol, get_source
+**Analysis:** analyze, analyze_diff, get_callers, get_module_usages, get_api_surface, get_components, symbol_history, get_callgraph
+**Quality:** validate (requires scope!), find_duplicates, start_refactoring_session
+**Ops:** index, test, generate_test_stubs, lint, prep_commit
+**Staged edits:** stage_ai_edit, list_ai_edits, clear_ai_edits
 
 ## AVOID
 
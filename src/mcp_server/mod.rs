@@ -6,6 +6,7 @@
 
 pub mod formatting;
 pub mod helpers;
+mod progress;
 mod types;
 
 // Instruction variants for A/B testing - change import to switch:
@@ -13,43 +14,61 @@ mod types;
 // mod instructions_complete;  // Full documentation (~4000 tokens)
 mod instructions_fast; // Decision tree focused (~2000 tokens) - DEFAULT
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
     model::*,
-    tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler,
+    service::RequestContext,
+    tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler,
 };
 use tokio::sync::Mutex;
 
 use crate::{
+    ai_layer::AiLayer,
     // CLI types for MCP->CLI handler consolidation
     cli::{
         AnalyzeArgs, CommitArgs, IndexArgs, IndexOperation, LintArgs, LintOperation, OutputFormat,
-        SearchArgs, SymbolScope, TestArgs, ValidateArgs,
+        PathStyle, ProgressFormat, SearchArgs, SymbolScope, TestArgs, ValidateArgs,
     },
     commands::{
-        run_analyze, run_commit, run_duplicates, run_file_symbols, run_get_callers,
-        run_get_callgraph, run_get_source, run_get_symbol, run_index, run_lint, run_overview,
-        run_search, run_test, run_validate, CommandContext,
+        run_analyze, run_analyze_snippet, run_api_surface, run_commit, run_component_tree,
+        run_duplicates, run_file_diff, run_file_symbols, run_get_callers, run_get_callgraph,
+        run_get_source, run_get_symbol, run_hook_usage, run_index, run_lint, run_lint_results,
+        run_module_usages, run_overview, run_query_imports, run_search, run_semantic_grep,
+        run_symbol_history, run_test, run_topics, run_unused_modules, run_validate, CommandContext,
+        ProgressSink,
     },
+    lang::Lang,
+    lint::{LintRunOptions, LintSeverity, Linter},
+    overlay::{LayerKind, LayeredIndex, Overlay, AI_ENTRY_MAX_AGE_SECS, AI_PRUNE_THRESHOLD},
+    profile::{Phase, PhaseTimes, Profiling},
     server::ServerState,
-    test_runner::{self},
+    settings::RepoSettings,
+    test_runner::{self, TestFramework},
+    test_stubs::generate_test_stubs,
     utils::truncate_to_char_boundary,
     CacheDir,
+    ExtractionLevel,
 };
 
 // Re-export types for external use
-use formatting::{format_module_symbols, get_supported_languages, toon_header};
+use formatting::{
+    format_ai_diff, format_module_symbols, format_test_stubs, get_supported_languages, toon_header,
+};
 use helpers::{
-    check_cache_staleness_detailed, ensure_fresh_index, format_freshness_note,
-    generate_index_internal, FreshnessResult,
+    apply_symbol_patches, check_cache_staleness_detailed, ensure_fresh_index_with_progress,
+    format_freshness_note, generate_index_internal, generate_index_with_deadline,
+    with_index_coverage, with_pinned_index, FreshnessResult, RefreshType,
 };
 pub use types::*;
 // Match this to the active module above:
 use instructions_fast::MCP_INSTRUCTIONS;
+use progress::ProgressNotifier;
 
 // ============================================================================
 // MCP Server Implementation
@@ -64,8 +83,25 @@ pub struct McpDiffServer {
     tool_router: ToolRouter<McpDiffServer>,
     /// Optional persistent server state for live layer updates
     server_state: Option<Arc<ServerState>>,
+    /// Repos whose partial index is being completed in the background
+    background_indexing: Arc<std::sync::Mutex<HashSet<PathBuf>>>,
+    /// Staged AI edits per repository root (never persisted)
+    ai_layers: Arc<std::sync::Mutex<HashMap<PathBuf, Arc<AiLayer>>>>,
 }
 
+/// How long a tool call may spend generating a missing index before it
+/// answers from a partial one (most MCP clients time out after ~60s)
+const ENSURE_INDEX_TIME_BUDGET: Duration = Duration::from_secs(30);
+
+/// Set to `1` to enable the `benchmark_query` tool
+pub const ALLOW_BENCHMARK_ENV: &str = "SEMFORA_ALLOW_BENCHMARK";
+
+/// Calls `benchmark_query` times when no iteration count is given
+const DEFAULT_BENCHMARK_ITERATIONS: u32 = 5;
+
+/// Most calls a single `benchmark_query` makes
+const MAX_BENCHMARK_ITERATIONS: u32 = 100;
+
 impl Default for McpDiffServer {
     fn default() -> Self {
         Self::new()
@@ -81,6 +117,8 @@ impl McpDiffServer {
             working_dir: Arc::new(Mutex::new(working_dir)),
             tool_router: Self::tool_router(),
             server_state: None,
+            background_indexing: Arc::default(),
+            ai_layers: Arc::default(),
         }
     }
 
@@ -90,6 +128,8 @@ impl McpDiffServer {
             working_dir: Arc::new(Mutex::new(working_dir)),
             tool_router: Self::tool_router(),
             server_state: None,
+            background_indexing: Arc::default(),
+            ai_layers: Arc::default(),
         }
     }
 
@@ -101,6 +141,8 @@ impl McpDiffServer {
             working_dir: Arc::new(Mutex::new(working_dir)),
             tool_router: Self::tool_router(),
             server_state: Some(server_state),
+            background_indexing: Arc::default(),
+            ai_layers: Arc::default(),
         }
     }
 
@@ -137,9 +179,150 @@ impl McpDiffServer {
     /// - Stale index with few changes: partial reindex
     /// - Stale index with many changes: full regeneration
     ///
+    /// Generation is time-boxed: if it can't finish within the time budget a
+    /// partial index is returned. With persistent server state generation then
+    /// continues in the background; otherwise the next call resumes it.
+    ///
     /// Returns FreshnessResult containing the cache and refresh status.
     async fn ensure_index(&self, repo_path: &Path) -> Result<FreshnessResult, String> {
-        ensure_fresh_index(repo_path, None)
+        self.ensure_index_with_progress(repo_path, None).await
+    }
+
+    /// [`Self::ensure_index`], reporting the progress of index generation
+    async fn ensure_index_with_progress(
+        &self,
+        repo_path: &Path,
+        progress: Option<ProgressSink>,
+    ) -> Result<FreshnessResult, String> {
+        let in_background = self
+            .background_indexing
+            .lock()
+            .map(|repos| repos.contains(repo_path))
+            .unwrap_or(false);
+        if in_background {
+            // Serve the partial index while the background job completes it
+            let cache = CacheDir::for_repo(repo_path)
+                .map_err(|e| format!("Failed to access cache: {}", e))?;
+            let indexing_status = cache.partial_status();
+            return Ok(FreshnessResult {
+                cache,
+                refreshed: false,
+                refresh_type: RefreshType::None,
+                files_updated: 0,
+                duration_ms: 0,
+                indexing_status,
+                recovery: None,
+            });
+        }
+
+        let deadline = Instant::now() + ENSURE_INDEX_TIME_BUDGET;
+        let result =
+            ensure_fresh_index_with_progress(repo_path, None, Some(deadline), progress.as_ref())
+                .map_err(|e| e.to_string())?;
+        if result.indexing_status.is_some() && self.has_server_state() {
+            self.spawn_background_indexing(repo_path);
+        }
+        self.prune_stale_ai_layer(repo_path);
+        Ok(result)
+    }
+
+    /// Finish a partial index on a background thread (persistent mode)
+    fn spawn_background_indexing(&self, repo_path: &Path) {
+        let Ok(mut repos) = self.background_indexing.lock() else {
+            return;
+        };
+        if !repos.insert(repo_path.to_path_buf()) {
+            return;
+        }
+
+        let repos = Arc::clone(&self.background_indexing);
+        let repo_path = repo_path.to_path_buf();
+        std::thread::spawn(move || {
+            let settings = RepoSettings::load_or_default(&repo_path);
+            if let Err(e) = generate_index_with_deadline(
+                &repo_path,
+                settings.max_depth,
+                &settings.extensions,
+                None,
+            ) {
+                tracing::warn!("Background indexing failed: {}", e);
+            }
+            if let Ok(mut repos) = repos.lock() {
+                repos.remove(&repo_path);
+            }
+        });
+    }
+
+    /// Key of a repository's AI layer
+    fn ai_layer_key(repo_path: &Path) -> PathBuf {
+        CacheDir::for_repo(repo_path)
+            .map(|cache| cache.repo_root)
+            .unwrap_or_else(|_| repo_path.to_path_buf())
+    }
+
+    /// Staged AI edits a query should see
+    ///
+    /// The layer is applied when requested, by default only in persistent mode.
+    fn ai_layer_for(&self, repo_path: &Path, include: Option<bool>) -> Option<Arc<AiLayer>> {
+        if !include.unwrap_or(self.has_server_state()) {
+            return None;
+        }
+        let layers = self.ai_layers.lock().ok()?;
+        layers
+            .get(&Self::ai_layer_key(repo_path))
+            .filter(|layer| !layer.is_empty())
+            .cloned()
+    }
+
+    /// Apply a change to a repository's AI layer
+    ///
+    /// In persistent mode the resulting overlay also becomes the AI layer of
+    /// the live index, so `server_status` reports its symbols.
+    fn update_ai_layer<R>(&self, repo_path: &Path, f: impl FnOnce(&mut AiLayer) -> R) -> R {
+        let key = Self::ai_layer_key(repo_path);
+        let mut layers = self
+            .ai_layers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let layer = Arc::make_mut(layers.entry(key.clone()).or_default());
+        let result = f(layer);
+
+        if let Some(state) = &self.server_state {
+            if Self::ai_layer_key(state.repo_root()) == key {
+                state.write(|index| index.ai = layer.overlay().clone());
+            }
+        }
+        result
+    }
+
+    /// Drop stale staged edits once a repository's AI layer holds more than
+    /// [`AI_PRUNE_THRESHOLD`] symbols (see [`AiLayer::prune_stale`])
+    fn prune_stale_ai_layer(&self, repo_path: &Path) {
+        let oversized = self
+            .ai_layers
+            .lock()
+            .ok()
+            .and_then(|layers| {
+                layers
+                    .get(&Self::ai_layer_key(repo_path))
+                    .map(|layer| layer.overlay().active_count() > AI_PRUNE_THRESHOLD)
+            })
+            .unwrap_or(false);
+        if oversized {
+            let pruned =
+                self.update_ai_layer(repo_path, |layer| layer.prune_stale(AI_ENTRY_MAX_AGE_SECS));
+            tracing::info!("[AI] Pruned {} stale staged edits", pruned.len());
+        }
+
+        // Symbols placed in the live index's AI layer directly age out too
+        if let Some(state) = &self.server_state {
+            state.write(|index| {
+                if index.ai.active_count() > AI_PRUNE_THRESHOLD {
+                    let pruned = index.prune_stale_ai_entries(AI_ENTRY_MAX_AGE_SECS);
+                    tracing::info!("[AI] Pruned {} stale AI layer symbols", pruned);
+                }
+            });
+        }
     }
 
     // ========================================================================
@@ -153,7 +336,9 @@ impl McpDiffServer {
         &self,
         Parameters(request): Parameters<GetContextRequest>,
     ) -> Result<CallToolResult, McpError> {
-        use crate::git::{get_current_branch, get_last_commit, get_remote_url};
+        use crate::git::{
+            get_base_branch_status, get_current_branch, get_last_commit, get_remote_url,
+        };
 
         let repo_path = match &request.path {
             Some(p) => self.resolve_path(p).await,
@@ -177,13 +362,29 @@ impl McpDiffServer {
         let remote = get_remote_url(None, Some(&repo_path)).unwrap_or_else(|| "none".to_string());
         output.push_str(&format!("remote: \"{}\"\n", remote));
 
+        // Base branch position (omitted when unknown, e.g. in a shallow clone)
+        match get_base_branch_status(Some(&repo_path)) {
+            Ok(status) => {
+                output.push_str(&format!("base_branch: \"{}\"\n", status.base_branch));
+                output.push_str(&format!("commits_ahead: {}\n", status.commits_ahead));
+            }
+            Err(crate::error::McpDiffError::ShallowClone { fetch_command, .. }) => {
+                output.push_str("shallow_clone: true\n");
+                output.push_str(&format!(
+                    "_shallow_note: \"base branch history not fetched; run `{}`\"\n",
+                    fetch_command
+                ));
+            }
+            Err(_) => {}
+        }
+
         // Last commit info (DEDUP-104: uses shared git module)
         if let Some(commit) = get_last_commit(Some(&repo_path)) {
             output.push_str("last_commit:\n");
             output.push_str(&format!("  hash: \"{}\"\n", commit.short_sha));
             // Truncate message to 60 chars
             let msg = if commit.subject.len() > 60 {
-                format!("{}...", &commit.subject[..57])
+                format!("{}...", truncate_to_char_boundary(&commit.subject, 57))
             } else {
                 commit.subject.clone()
             };
@@ -249,12 +450,103 @@ impl McpDiffServer {
     // ========================================================================
 
     #[tool(
-        description = "Unified analysis: auto-detects file, directory, or module. For files: extracts semantic info. For directories: returns overview with module grouping. For modules: returns detailed semantic info from index."
+        description = "Unified analysis: auto-detects file, directory, or module. For files: extracts semantic info. For directories: returns overview with module grouping. For modules: returns detailed semantic info from index. With mode='coupling': lists tightly-coupled module pairs. With mode='debt': scores technical debt with its trend."
     )]
     async fn analyze(
         &self,
         Parameters(request): Parameters<AnalyzeRequest>,
     ) -> Result<CallToolResult, McpError> {
+        // Coupling and debt modes: repo-wide reports from the index
+        match request.mode.as_deref() {
+            None => {}
+            Some(mode @ ("coupling" | "debt")) => {
+                let repo_path = match &request.path {
+                    Some(p) => self.resolve_path(p).await,
+                    None => self.get_working_dir().await,
+                };
+                if let Err(e) = self.ensure_index(&repo_path).await {
+                    return Ok(CallToolResult::error(vec![Content::text(e)]));
+                }
+
+                let args = AnalyzeArgs {
+                    path: Some(repo_path.clone()),
+                    diff: None,
+                    uncommitted: false,
+                    commit: None,
+                    all_commits: false,
+                    base: None,
+                    max_depth: 10,
+                    extensions: Vec::new(),
+                    allow_tests: false,
+                    summary_only: false,
+                    start_line: None,
+                    end_line: None,
+                    output_mode: "full".to_string(),
+                    target_ref: None,
+                    package: None,
+                    no_merge_base: false,
+                    auto_deepen: None,
+                    limit: None,
+                    offset: None,
+                    shard: false,
+                    incremental: false,
+                    mirror: false,
+                    mirror_ext: "toon".to_string(),
+                    output_dir: None,
+                    analyze_tokens: None,
+                    compare_compact: false,
+                    token_budget: None,
+                    print_ast: false,
+                    blob_ref: None,
+                    coupling: mode == "coupling",
+                    debt_score: mode == "debt",
+                    topics: None,
+                    routes: false,
+                    file_a: None,
+                    file_b: None,
+                    stdin: false,
+                    lang: None,
+                    select: None,
+                    output: None,
+                };
+                let ctx = CommandContext {
+                    format: match request.format.as_deref() {
+                        Some("json") => OutputFormat::Json,
+                        _ => OutputFormat::Toon,
+                    },
+                    verbose: false,
+                    progress: false,
+                    output: None,
+                    ai_layer: None,
+                    paths: PathStyle::Relative,
+                    progress_format: ProgressFormat::Text,
+                    progress_sink: None,
+                    settings: None,
+                };
+
+                return match run_analyze(&ctx, &args) {
+                    Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                        with_index_coverage(&repo_path, output),
+                    )])),
+                    Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                        "{} analysis failed: {}",
+                        if mode == "coupling" {
+                            "Coupling"
+                        } else {
+                            "Debt"
+                        },
+                        e
+                    ))])),
+                };
+            }
+            Some(other) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Unknown mode '{}'. Supported modes: coupling, debt",
+                    other
+                ))]))
+            }
+        }
+
         // Module mode: get module from index
         if let Some(module_name) = &request.module {
             let repo_path = match &request.path {
@@ -279,7 +571,9 @@ impl McpDiffServer {
             }
 
             return match fs::read_to_string(&module_path) {
-                Ok(content) => Ok(CallToolResult::success(vec![Content::text(content)])),
+                Ok(content) => Ok(CallToolResult::success(vec![Content::text(
+                    with_index_coverage(&repo_path, content),
+                )])),
                 Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                     "Failed to read module: {}",
                     e
@@ -298,6 +592,10 @@ impl McpDiffServer {
         };
 
         let resolved_path = self.resolve_path(path).await;
+        let settings = match load_repo_settings(&resolved_path) {
+            Ok(settings) => settings,
+            Err(result) => return Ok(result),
+        };
 
         // Build CLI args from MCP request
         let args = AnalyzeArgs {
@@ -307,8 +605,11 @@ impl McpDiffServer {
             commit: None,
             all_commits: false,
             base: None,
-            max_depth: request.max_depth.unwrap_or(10),
-            extensions: request.extensions.clone().unwrap_or_default(),
+            max_depth: request.max_depth.unwrap_or(settings.max_depth),
+            extensions: request
+                .extensions
+                .clone()
+                .unwrap_or_else(|| settings.extensions.clone()),
             allow_tests: false,
             summary_only: request.summary_only.unwrap_or(false),
             start_line: request.start_line,
@@ -318,13 +619,31 @@ impl McpDiffServer {
                 .clone()
                 .unwrap_or_else(|| "full".to_string()),
             target_ref: None,
+            package: None,
+            no_merge_base: false,
+            auto_deepen: None,
             limit: None,
             offset: None,
             shard: false,
             incremental: false,
+            mirror: false,
+            mirror_ext: "toon".to_string(),
+            output_dir: None,
             analyze_tokens: None,
             compare_compact: false,
+            token_budget: None,
             print_ast: false,
+            blob_ref: None,
+            coupling: false,
+            debt_score: false,
+            topics: None,
+            routes: false,
+            file_a: None,
+            file_b: None,
+            stdin: false,
+            lang: None,
+            select: None,
+            output: None,
         };
 
         // Select output format based on MCP request
@@ -337,6 +656,12 @@ impl McpDiffServer {
             format,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: Some(settings),
         };
 
         // Call CLI handler
@@ -350,7 +675,7 @@ impl McpDiffServer {
     }
 
     #[tool(
-        description = "**Use for code reviews** - analyzes changes between git branches or commits semantically. Shows new/modified symbols, changed dependencies, and risk assessment for each file. Use target_ref='WORKING' to review uncommitted changes before committing. Supports pagination (limit/offset) for large diffs and summary_only mode for quick overview."
+        description = "**Use for code reviews** - analyzes changes between git branches or commits semantically. Shows new/modified symbols, changed dependencies, and risk assessment for each file. Use target_ref='WORKING' to review uncommitted changes before committing, or target_ref='AI' to review edits staged with stage_ai_edit against the working tree (typed surface deltas per file). Supports pagination (limit/offset) for large diffs and summary_only mode for quick overview."
     )]
     async fn analyze_diff(
         &self,
@@ -362,6 +687,23 @@ impl McpDiffServer {
             None => self.get_working_dir().await,
         };
 
+        // Staged AI edits are diffed against the working tree, without git
+        let target_ref = request.target_ref.as_deref().unwrap_or("HEAD");
+        if target_ref.eq_ignore_ascii_case("AI") {
+            let layer = self
+                .ai_layer_for(&working_dir, Some(true))
+                .unwrap_or_default();
+            return Ok(CallToolResult::success(vec![Content::text(
+                format_ai_diff(
+                    &working_dir,
+                    &layer,
+                    request.offset.unwrap_or(0),
+                    request.limit.unwrap_or(20).min(100),
+                    request.summary_only.unwrap_or(false),
+                ),
+            )]));
+        }
+
         // Validate git repo before delegating
         if !crate::git::is_git_repo(Some(&working_dir)) {
             return Ok(CallToolResult::error(vec![Content::text(
@@ -385,19 +727,43 @@ impl McpDiffServer {
             end_line: None,
             output_mode: "full".to_string(),
             target_ref: request.target_ref.clone(),
+            package: None,
+            no_merge_base: !request.use_merge_base.unwrap_or(true),
+            auto_deepen: request.auto_deepen,
             limit: request.limit,
             offset: request.offset,
             shard: false,
             incremental: false,
+            mirror: false,
+            mirror_ext: "toon".to_string(),
+            output_dir: None,
             analyze_tokens: None,
             compare_compact: false,
+            token_budget: None,
             print_ast: false,
+            blob_ref: None,
+            coupling: false,
+            debt_score: false,
+            topics: None,
+            routes: false,
+            file_a: None,
+            file_b: None,
+            stdin: false,
+            lang: None,
+            select: None,
+            output: None,
         };
 
         let ctx = CommandContext {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
         // Delegate to CLI handler
@@ -410,6 +776,74 @@ impl McpDiffServer {
         }
     }
 
+    #[tool(
+        description = "Analyze a code snippet (e.g. a function you are writing) without indexing it. Returns per-symbol complexity and risk, near-duplicates already in the repository's index, and known CVE pattern matches. The snippet is never written to the cache."
+    )]
+    async fn analyze_snippet(
+        &self,
+        Parameters(request): Parameters<AnalyzeSnippetRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        match run_analyze_snippet(&ctx, &request.content, &request.lang, &repo_path) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Snippet analysis failed: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Semantically compare two files directly, without git: e.g. a file against its generated counterpart, two candidate implementations, or the same file from two repositories. Returns typed surface deltas (symbols, dependencies, state, control flow, arity) and the risk change from file_a to file_b."
+    )]
+    async fn diff_two_files(
+        &self,
+        Parameters(request): Parameters<DiffTwoFilesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let base_dir = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+        let file_a = base_dir.join(&request.file_a);
+        let file_b = base_dir.join(&request.file_b);
+
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        match run_file_diff(&ctx, &file_a, &file_b) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "File diff failed: {}",
+                e
+            ))])),
+        }
+    }
+
     #[tool(
         description = "Get all programming languages supported by semfora-engine for semantic analysis"
     )]
@@ -426,11 +860,12 @@ impl McpDiffServer {
     // ========================================================================
 
     #[tool(
-        description = "Get the repository overview from a pre-built sharded index. Returns a compact summary with framework detection, module list, risk breakdown, and entry points. Use this to understand a codebase before diving into specific modules. Use max_modules param to control module listing (default 30, set 0 to exclude, high number for all)."
+        description = "Get the repository overview from a pre-built sharded index. Returns a compact summary with framework detection, module list, risk breakdown, and entry points. Use this to understand a codebase before diving into specific modules. Use max_modules param to control module listing (default 30, set 0 to exclude, high number for all). Use max_tokens to guarantee the overview fits a token budget. Set topics=N to append N module topic clusters."
     )]
     async fn get_overview(
         &self,
         Parameters(request): Parameters<GetOverviewRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let repo_path = match &request.path {
             Some(p) => self.resolve_path(p).await,
@@ -443,7 +878,14 @@ impl McpDiffServer {
         let include_git_context = request.include_git_context.unwrap_or(true);
 
         // Ensure index exists and is fresh (auto-generates or refreshes if needed)
-        let freshness = match self.ensure_index(&repo_path).await {
+        let notifier = ProgressNotifier::for_request(&context);
+        let freshness = self
+            .ensure_index_with_progress(&repo_path, notifier.as_ref().map(|n| n.sink()))
+            .await;
+        if let Some(notifier) = notifier {
+            notifier.finish().await;
+        }
+        let freshness = match freshness {
             Ok(r) => r,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
         };
@@ -462,18 +904,46 @@ impl McpDiffServer {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
-        match run_overview(
-            Some(&repo_path),
-            include_modules,
-            max_modules,
-            exclude_test_dirs,
-            include_git_context,
-            &ctx,
-        ) {
+        let topics_block = match request.topics.filter(|&n| n > 0) {
+            Some(num_topics) => match run_topics(&ctx, &repo_path, num_topics) {
+                Ok(block) => format!("\n---\n{}", block),
+                Err(e) => format!("\n---\ntopics: unavailable ({})\n", e),
+            },
+            None => String::new(),
+        };
+
+        // The freshness note and topics block count against the token budget too
+        let max_tokens = request.max_tokens.map(|budget| {
+            let counter = crate::tokens::TokenAnalyzer::new();
+            budget
+                .saturating_sub(counter.count_tokens(&output))
+                .saturating_sub(counter.count_tokens(&topics_block))
+        });
+
+        match with_pinned_index(&repo_path, || {
+            run_overview(
+                Some(&repo_path),
+                include_modules,
+                max_modules,
+                exclude_test_dirs,
+                include_git_context,
+                max_tokens,
+                request.include_hotspots.unwrap_or(false),
+                request.min_lines,
+                &ctx,
+            )
+        }) {
             Ok(overview_output) => {
                 output.push_str(&overview_output);
+                output.push_str(&topics_block);
                 Ok(CallToolResult::success(vec![Content::text(output)]))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
@@ -522,18 +992,28 @@ impl McpDiffServer {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: self.ai_layer_for(&repo_path, request.include_ai_layer),
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
-        match run_get_symbol(
-            Some(&repo_path),
-            hash_str.as_deref(),
-            request.file.as_deref(),
-            request.line,
-            include_source,
-            context,
-            &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        match with_pinned_index(&repo_path, || {
+            run_get_symbol(
+                Some(&repo_path),
+                hash_str.as_deref(),
+                request.file.as_deref(),
+                request.line,
+                include_source,
+                context,
+                &ctx,
+            )
+        }) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to get symbol: {}",
                 e
@@ -547,6 +1027,7 @@ impl McpDiffServer {
     async fn get_callgraph(
         &self,
         Parameters(request): Parameters<GetCallgraphRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         // DEDUP-306: Delegate to CLI run_get_callgraph handler
 
@@ -572,24 +1053,43 @@ impl McpDiffServer {
         let stats_only = request.summary_only.unwrap_or(false);
         let include_escape_refs = request.include_escape_refs.unwrap_or(false);
 
+        // Only a SQLite export reports progress
+        let notifier = ProgressNotifier::for_request(&context).filter(|_| export_arg.is_some());
         let ctx = CommandContext {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: notifier.as_ref().map(|n| n.sink()),
+            settings: None,
         };
 
-        match run_get_callgraph(
-            Some(&repo_path),
-            request.module.as_deref(),
-            request.symbol.as_deref(),
-            export_arg.as_deref(),
-            stats_only,
-            limit,
-            offset,
-            include_escape_refs,
-            &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        let result = with_pinned_index(&repo_path, || {
+            run_get_callgraph(
+                Some(&repo_path),
+                request.module.as_deref(),
+                request.exclude_modules.as_deref().unwrap_or_default(),
+                request.symbol.as_deref(),
+                request.package.as_deref(),
+                export_arg.as_deref(),
+                stats_only,
+                limit,
+                offset,
+                include_escape_refs,
+                &ctx,
+            )
+        });
+        if let Some(notifier) = notifier {
+            notifier.finish().await;
+        }
+
+        match result {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to get call graph: {}",
                 e
@@ -639,18 +1139,28 @@ impl McpDiffServer {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
-        match run_get_source(
-            Some(&repo_path),
-            file_str.as_deref(),
-            request.start_line,
-            request.end_line,
-            hash_str.as_deref(),
-            context,
-            &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        match with_pinned_index(&repo_path, || {
+            run_get_source(
+                Some(&repo_path),
+                file_str.as_deref(),
+                request.start_line,
+                request.end_line,
+                hash_str.as_deref(),
+                context,
+                &ctx,
+            )
+        }) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to get source: {}",
                 e
@@ -663,7 +1173,7 @@ impl McpDiffServer {
     // ========================================================================
 
     #[tool(
-        description = "Unified search - runs BOTH symbol and semantic search by default (hybrid mode). Returns symbol matches AND conceptually related code in one call. Use mode='symbols' for exact name match only, mode='semantic' for BM25 conceptual search, or mode='raw' for regex patterns in comments/strings. Variables are hidden by default; use symbol_scope='variables' or 'both' to include."
+        description = "Unified search - runs BOTH symbol and semantic search by default (hybrid mode). Returns symbol matches AND conceptually related code in one call. Use mode='symbols' for exact name match only, mode='semantic' for BM25 conceptual search, or mode='raw' for regex patterns in comments/strings. When a hybrid search for text that isn't a symbol name finds nothing, literal text matches are added as raw_fallback (no_fallback=true to disable). Variables are hidden by default; use symbol_scope='variables' or 'both' to include."
     )]
     async fn search(
         &self,
@@ -674,6 +1184,16 @@ impl McpDiffServer {
             Some(p) => self.resolve_path(p).await,
             None => self.get_working_dir().await,
         };
+        let facets = match request
+            .facets
+            .iter()
+            .flatten()
+            .map(|f| f.parse())
+            .collect::<Result<Vec<crate::search::FacetFilter>, String>>()
+        {
+            Ok(facets) => facets,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
 
         // Change to repo directory for the CLI handler
         let original_dir = std::env::current_dir().ok();
@@ -697,6 +1217,16 @@ impl McpDiffServer {
             }
         }
 
+        let settings = match load_repo_settings(&repo_path) {
+            Ok(settings) => settings,
+            Err(result) => {
+                if let Some(ref dir) = original_dir {
+                    let _ = std::env::set_current_dir(dir);
+                }
+                return Ok(result);
+            }
+        };
+
         // Build SearchArgs from the request
         let args = SearchArgs {
             query: request.query.clone(),
@@ -705,21 +1235,36 @@ impl McpDiffServer {
             raw: mode == "raw",
             kind: request.kind.clone(),
             module: request.module.clone(),
+            exclude_modules: request.exclude_modules.clone().unwrap_or_default(),
             risk: request.risk.clone(),
+            in_file: request.in_file.clone(),
+            package: request.package.clone(),
+            path_globs: request.path_glob.clone().unwrap_or_default(),
+            flag: request.flag.clone(),
+            deprecated: request.deprecated.unwrap_or(false),
+            panics: request.panics.unwrap_or(false),
+            sql: request.sql.clone(),
+            facets,
             include_source: request.include_source.unwrap_or(false),
-            limit: request.limit.unwrap_or(20),
+            limit: request.limit.unwrap_or(settings.search_limit),
             file_types: request.file_types.as_ref().map(|v| v.join(",")),
             case_sensitive: !request.case_insensitive.unwrap_or(true),
             merge_threshold: request.merge_threshold.unwrap_or(3),
             symbol_scope: SymbolScope::from_optional(request.symbol_scope.as_deref()),
             include_escape_refs: request.include_escape_refs.unwrap_or(false),
+            no_fallback: request.no_fallback.unwrap_or(false),
+            fallback_min: request.fallback_min.unwrap_or(1),
+            fallback_limit: request.fallback_limit.unwrap_or(10),
+            output: None,
         };
 
         // Create command context (TOON format for MCP)
-        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false);
+        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false)
+            .with_ai_layer(self.ai_layer_for(&repo_path, request.include_ai_layer))
+            .with_settings(Some(settings));
 
         // Call the CLI handler
-        let result = run_search(&args, &ctx);
+        let result = with_pinned_index(&repo_path, || run_search(&args, &ctx));
 
         // Restore original directory
         if let Some(ref dir) = original_dir {
@@ -727,7 +1272,9 @@ impl McpDiffServer {
         }
 
         match result {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Search failed: {}",
                 e
@@ -735,6 +1282,44 @@ impl McpDiffServer {
         }
     }
 
+    #[tool(
+        description = "Semantic grep - finds symbols by behavior instead of text: 'calls:fetchUser' (or 'calls:axios.get'), 'mutates:session', 'imports:axios', 'returns:Promise<User>'. Searches the semantic index, not source; use search mode='raw' for text."
+    )]
+    async fn semantic_grep(
+        &self,
+        Parameters(request): Parameters<SemanticGrepRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let limit = request.limit.unwrap_or(50).min(200);
+        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false);
+
+        match with_pinned_index(&repo_path, || {
+            run_semantic_grep(
+                &repo_path,
+                &request.pattern,
+                request.kind.as_deref(),
+                limit,
+                &ctx,
+            )
+        }) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Semantic grep failed: {}",
+                e
+            ))])),
+        }
+    }
+
     /// Unified validate handler - auto-detects scope based on parameters.
     /// Scope priority: symbol_hash > file_path+line > file_path > module
     #[tool(
@@ -750,35 +1335,59 @@ impl McpDiffServer {
             None => self.get_working_dir().await,
         };
 
+        let settings = match load_repo_settings(&repo_path) {
+            Ok(settings) => settings,
+            Err(result) => return Ok(result),
+        };
+
         // Build CLI args from MCP request (DEDUP-304)
         let args = ValidateArgs {
             target: None,
-            path: Some(repo_path),
+            path: Some(repo_path.clone()),
             symbol_hash: request.symbol_hash.clone(),
             file_path: request.file_path.clone(),
             line: request.line,
             module: request.module.clone(),
+            exclude_modules: request.exclude_modules.clone().unwrap_or_default(),
+            package: None,
             include_source: request.include_source.unwrap_or(false),
             duplicates: false,
-            threshold: request.duplicate_threshold.unwrap_or(0.85),
-            include_boilerplate: false,
+            cycles: false,
+            threshold: request
+                .duplicate_threshold
+                .unwrap_or(settings.duplicate_threshold),
+            include_boilerplate: settings.include_boilerplate,
+            include_generated: request.include_generated.unwrap_or(false),
+            normalize_whitespace: false,
             kind: request.kind.clone(),
             symbol_scope: SymbolScope::from_optional(request.symbol_scope.as_deref()),
             limit: request.limit.unwrap_or(100),
             offset: 0,
             min_lines: 3,
             sort_by: "similarity".to_string(),
+            report: false,
+            fail_on: Vec::new(),
+            base: None,
+            output: None,
         };
 
         let ctx = CommandContext {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: Some(settings),
         };
 
         // Delegate to CLI handler
         match run_validate(&args, &ctx) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Validation failed: {}",
                 e
@@ -786,20 +1395,81 @@ impl McpDiffServer {
         }
     }
 
+    #[tool(
+        description = "Start refactoring a symbol: returns its callers, near-duplicates, risk, complexity, covering tests and an ordered checklist of steps. Stateless - call again after each change to get a fresh context."
+    )]
+    async fn start_refactoring_session(
+        &self,
+        Parameters(request): Parameters<StartRefactoringSessionRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let cache = match CacheDir::for_repo(&repo_path) {
+            Ok(c) => c,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to open cache: {}",
+                    e
+                ))]))
+            }
+        };
+
+        let entry = match helpers::find_symbol_by_hash(&cache, &request.symbol_hash) {
+            Ok(entry) => entry,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let context = helpers::build_refactoring_context(&cache, &entry, 0.85);
+        Ok(CallToolResult::success(vec![Content::text(
+            with_index_coverage(&repo_path, helpers::format_refactoring_context(&context)),
+        )]))
+    }
+
     /// Unified index handler - smart refresh by default.
     /// Checks freshness first, only regenerates if stale. Use force=true to always rebuild.
     #[tool(
-        description = "Unified index management - smart refresh by default (checks freshness, rebuilds only if stale). Use force=true to always regenerate. Returns index status and statistics."
+        description = "Unified index management - smart refresh by default (checks freshness, rebuilds only if stale). Use force=true to always regenerate, or compact=true to fold committed overlay layers and drop stale tombstones. Returns index status and statistics."
     )]
     async fn index(
         &self,
         Parameters(request): Parameters<IndexRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         let repo_path = match &request.path {
             Some(p) => self.resolve_path(p).await,
             None => self.get_working_dir().await,
         };
 
+        // A live server compacts its in-memory layers (and persists them);
+        // without one, the cached layers are compacted below
+        if let Some(state) = self
+            .server_state
+            .as_ref()
+            .filter(|s| request.compact == Some(true) && s.repo_root() == &repo_path)
+        {
+            if !crate::server::is_worktree_clean(&repo_path) {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Index operation failed: working tree has uncommitted changes; commit or stash them before compacting",
+                )]));
+            }
+            return match state.compact(crate::server::compaction_target(&repo_path)) {
+                Ok(stats) => Ok(CallToolResult::success(vec![Content::text(
+                    crate::commands::index::format_compaction(&stats, OutputFormat::Toon),
+                )])),
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Index operation failed: {}",
+                    e
+                ))])),
+            };
+        }
+
         // Save original dir and change to repo path
         let original_dir = std::env::current_dir().ok();
         if let Err(e) = std::env::set_current_dir(&repo_path) {
@@ -810,19 +1480,41 @@ impl McpDiffServer {
             ))]));
         }
 
+        let settings = match load_repo_settings(&repo_path) {
+            Ok(settings) => settings,
+            Err(result) => {
+                if let Some(ref dir) = original_dir {
+                    let _ = std::env::set_current_dir(dir);
+                }
+                return Ok(result);
+            }
+        };
+
         let force = request.force.unwrap_or(false);
         let max_age = request.max_age.unwrap_or(3600);
 
         // Build IndexArgs for CLI handler
-        let args = if force {
+        let args = if request.compact.unwrap_or(false) {
+            IndexArgs {
+                operation: IndexOperation::Compact {
+                    path: Some(repo_path.clone()),
+                },
+            }
+        } else if force {
             // Force regeneration
             IndexArgs {
                 operation: IndexOperation::Generate {
                     path: Some(repo_path.clone()),
                     force: true,
                     incremental: false,
-                    max_depth: request.max_depth.unwrap_or(10),
-                    extensions: request.extensions.clone().unwrap_or_default(),
+                    max_depth: request.max_depth.unwrap_or(settings.max_depth),
+                    extensions: request
+                        .extensions
+                        .clone()
+                        .unwrap_or_else(|| settings.extensions.clone()),
+                    level: ExtractionLevel::Full,
+                    shard_naming: None,
+                    with_blame: false,
                 },
             }
         } else {
@@ -835,8 +1527,14 @@ impl McpDiffServer {
             }
         };
 
-        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false);
+        let notifier = ProgressNotifier::for_request(&context);
+        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false)
+            .with_progress_sink(notifier.as_ref().map(|n| n.sink()))
+            .with_settings(Some(settings));
         let result = run_index(&args, &ctx);
+        if let Some(notifier) = notifier {
+            notifier.finish().await;
+        }
 
         // Restore original directory
         if let Some(ref dir) = original_dir {
@@ -854,7 +1552,7 @@ impl McpDiffServer {
 
     /// Unified test handler - runs tests by default, use detect_only=true to only detect frameworks.
     #[tool(
-        description = "Unified test runner - runs tests by default (auto-detects framework). Use detect_only=true to only detect available test frameworks without running."
+        description = "Unified test runner - runs tests by default (auto-detects framework). Use detect_only=true to only detect available test frameworks without running. Use changed=\"HEAD\" to run only tests that transitively call changed code."
     )]
     async fn test(
         &self,
@@ -883,6 +1581,7 @@ impl McpDiffServer {
             filter: request.filter.clone(),
             test_verbose: request.verbose.unwrap_or(false),
             timeout: request.timeout.unwrap_or(300),
+            changed: request.changed.clone(),
         };
 
         let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false);
@@ -902,13 +1601,58 @@ impl McpDiffServer {
         }
     }
 
+    // ========================================================================
+    // Test Generation
+    // ========================================================================
+
+    #[tool(
+        description = "Generate skeleton tests for a symbol in the project's test framework (cargo, pytest, jest/vitest, go): one test for the happy path plus one per branch, loop or error path in the symbol's control flow. Returns the test source and the conventional test file; nothing is written to disk."
+    )]
+    async fn generate_test_stubs(
+        &self,
+        Parameters(request): Parameters<GenerateTestStubsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let framework = match request.framework.as_deref() {
+            Some(name) => match TestFramework::from_name(name) {
+                Some(framework) => Some(framework),
+                None => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Unknown test framework: '{}'. Supported: pytest, cargo, npm, vitest, jest, go",
+                    name
+                ))]))
+                }
+            },
+            None => None,
+        };
+
+        let cache = match self.ensure_index(&repo_path).await {
+            Ok(fresh) => fresh.cache,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        match generate_test_stubs(&cache, &request.symbol_hash, framework) {
+            Ok(stubs) => Ok(CallToolResult::success(vec![Content::text(
+                format_test_stubs(&stubs),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to generate test stubs: {}",
+                e
+            ))])),
+        }
+    }
+
     // ========================================================================
     // Lint Handler
     // ========================================================================
 
     /// Unified lint handler - scans for issues by default.
     #[tool(
-        description = "Unified linter - auto-detects and runs linters. Use detect_only=true to list available linters. Use mode=\"fix\" to auto-fix.\n\n**26 linters across 16 languages:**\n• Core: Rust (clippy/rustfmt), JS/TS (ESLint/Biome/Prettier/TSC), Python (ruff/black/mypy), Go (golangci-lint/gofmt)\n• JVM: Java (Checkstyle/PMD/SpotBugs), Kotlin (detekt/ktlint)\n• Systems: C/C++ (clang-tidy/cppcheck), C# (dotnet-format/Roslyn)\n• Web: HTML (HTMLHint), CSS (Stylelint)\n• Config: JSON/YAML/TOML/XML linters\n• Infra: Terraform (tflint), Shell (shellcheck/shfmt), Markdown\n\n**When to use:** Run after editing code, before commits, or to audit code quality. Typically once per editing session."
+        description = "Unified linter - auto-detects and runs linters. Scans and fixes return lint_results: errors, issues fixed per linter and the 5 most frequent rules; pass include_warnings=true to list warnings too. Use detect_only=true to list available linters. Use mode=\"fix\" to auto-fix.\n\n**26 linters across 16 languages:**\n• Core: Rust (clippy/rustfmt), JS/TS (ESLint/Biome/Prettier/TSC), Python (ruff/black/mypy), Go (golangci-lint/gofmt)\n• JVM: Java (Checkstyle/PMD/SpotBugs), Kotlin (detekt/ktlint)\n• Systems: C/C++ (clang-tidy/cppcheck), C# (dotnet-format/Roslyn)\n• Web: HTML (HTMLHint), CSS (Stylelint)\n• Config: JSON/YAML/TOML/XML linters\n• Infra: Terraform (tflint), Shell (shellcheck/shfmt), Markdown\n\n**When to use:** Run after editing code, before commits, or to audit code quality. Typically once per editing session."
     )]
     async fn lint(
         &self,
@@ -919,6 +1663,36 @@ impl McpDiffServer {
             None => self.get_working_dir().await,
         };
 
+        let languages = match request
+            .languages
+            .iter()
+            .flatten()
+            .map(|name| name.parse::<Lang>())
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(languages) => languages,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let linters = match request
+            .linter
+            .iter()
+            .chain(request.linters.iter().flatten())
+            .map(|name| {
+                name.parse::<Linter>()
+                    .map_err(|_| format!("Unknown linter: {}", name))
+            })
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(linters) => linters,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let format = match request.format.as_deref() {
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Toon,
+        };
+
         // Save original dir and change to project path
         let original_dir = std::env::current_dir().ok();
         if let Err(e) = std::env::set_current_dir(&project_path) {
@@ -929,19 +1703,59 @@ impl McpDiffServer {
             ))]));
         }
 
-        // Determine which operation to run based on request
-        let operation = if request.detect_only.unwrap_or(false) {
-            LintOperation::Detect {
-                path: Some(project_path.clone()),
+        let mode = match request.fix {
+            Some(true) => Some("fix"),
+            _ => request.mode.as_deref(),
+        };
+        let detect_only = request.detect_only.unwrap_or(false);
+
+        // Scans and fixes report errors, warnings, fixes and top rules
+        if !detect_only && !matches!(mode, Some("typecheck" | "recommend" | "detect")) {
+            let fix = mode == Some("fix");
+            let options = LintRunOptions {
+                linters: (!linters.is_empty()).then_some(linters),
+                severity_filter: request
+                    .severity_filter
+                    .iter()
+                    .flatten()
+                    .find_map(|s| s.parse::<LintSeverity>().ok()),
+                limit: Some(request.limit.unwrap_or(100)),
+                fixable_only: request.fixable_only.unwrap_or(false),
+                fix,
+                dry_run: fix && request.dry_run.unwrap_or(false),
+                safe_only: request.safe_only.unwrap_or(false),
+                languages: (!languages.is_empty()).then_some(languages),
+                ..LintRunOptions::default()
+            };
+            let ctx = CommandContext::from_cli(format, false, false);
+            let result = run_lint_results(
+                &project_path,
+                &options,
+                request.include_warnings.unwrap_or(false),
+                &ctx,
+            );
+
+            if let Some(ref dir) = original_dir {
+                let _ = std::env::set_current_dir(dir);
             }
-        } else {
-            match request.mode.as_deref() {
-                Some("fix") => LintOperation::Fix {
-                    path: Some(project_path.clone()),
-                    linter: request.linter.clone(),
-                    dry_run: request.dry_run.unwrap_or(false),
-                    safe_only: request.safe_only.unwrap_or(false),
-                },
+
+            return match result {
+                Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Lint operation failed: {}",
+                    e
+                ))])),
+            };
+        }
+
+        // Determine which operation to run based on request
+        let operation = if detect_only {
+            LintOperation::Detect {
+                path: Some(project_path.clone()),
+                languages: languages.clone(),
+            }
+        } else {
+            match mode {
                 Some("typecheck") => LintOperation::Typecheck {
                     path: Some(project_path.clone()),
                     checker: request.linter.clone(),
@@ -950,22 +1764,18 @@ impl McpDiffServer {
                 Some("recommend") => LintOperation::Recommend {
                     path: Some(project_path.clone()),
                 },
-                Some("detect") => LintOperation::Detect {
-                    path: Some(project_path.clone()),
-                },
-                _ => LintOperation::Scan {
+                _ => LintOperation::Detect {
                     path: Some(project_path.clone()),
-                    linter: request.linter.clone(),
-                    severity: request.severity_filter.clone(),
-                    limit: request.limit.unwrap_or(100),
-                    file: None,
-                    fixable_only: request.fixable_only.unwrap_or(false),
+                    languages,
                 },
             }
         };
 
-        let args = LintArgs { operation };
-        let ctx = CommandContext::from_cli(OutputFormat::Toon, false, false);
+        let args = LintArgs {
+            operation: Some(operation),
+            fix: false,
+        };
+        let ctx = CommandContext::from_cli(format, false, false);
         let result = run_lint(&args, &ctx);
 
         // Restore original directory
@@ -1000,6 +1810,11 @@ impl McpDiffServer {
         Parameters(request): Parameters<ServerStatusRequest>,
     ) -> Result<CallToolResult, McpError> {
         let include_layers = request.include_layers.unwrap_or(false);
+        let working_dir = self.get_working_dir().await;
+        let (ai_files, ai_symbols) = self
+            .ai_layer_for(&working_dir, Some(true))
+            .map(|layer| (layer.files().count(), layer.overlay().active_count()))
+            .unwrap_or_default();
         let mut output = toon_header("server_status");
         output.push_str(&format!(
             "persistent_mode: {}\n",
@@ -1018,6 +1833,7 @@ impl McpDiffServer {
             output.push_str(&format!("  branch_symbols: {}\n", stats.branch_symbols));
             output.push_str(&format!("  working_symbols: {}\n", stats.working_symbols));
             output.push_str(&format!("  ai_symbols: {}\n", stats.ai_symbols));
+            output.push_str(&format!("  ai_staged_files: {}\n", ai_files));
 
             // Include detailed layer status if requested
             if include_layers {
@@ -1042,6 +1858,9 @@ impl McpDiffServer {
             output.push_str("  - file_watcher: disabled\n");
             output.push_str("  - git_poller: disabled\n");
             output.push_str("  - thread_safe: n/a\n");
+            output.push_str("\nindex_stats:\n");
+            output.push_str(&format!("  ai_symbols: {}\n", ai_symbols));
+            output.push_str(&format!("  ai_staged_files: {}\n", ai_files));
             output.push_str("\nhint: Start with --persistent to enable live layer updates\n");
 
             if include_layers {
@@ -1052,6 +1871,289 @@ impl McpDiffServer {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    #[tool(
+        description = "Profile a slow query: calls another tool N times (default 5) with the given params and reports min/max/median/p95 latency plus the time spent in index lookup, BM25 search, filesystem I/O and formatting. Only available when the server runs with SEMFORA_ALLOW_BENCHMARK=1."
+    )]
+    async fn benchmark_query(
+        &self,
+        Parameters(request): Parameters<BenchmarkQueryRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if std::env::var(ALLOW_BENCHMARK_ENV).as_deref() != Ok("1") {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "benchmark_query is disabled; set {}=1 when starting the server to enable it",
+                ALLOW_BENCHMARK_ENV
+            ))]));
+        }
+        if request.tool == "benchmark_query" || !self.tool_router.has_route(&request.tool) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Cannot benchmark unknown tool: {}",
+                request.tool
+            ))]));
+        }
+        let arguments = match request.params {
+            None => serde_json::Map::new(),
+            Some(serde_json::Value::Object(arguments)) => arguments,
+            Some(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "params must be an object of tool arguments",
+                )]))
+            }
+        };
+        let iterations = request
+            .iterations
+            .unwrap_or(DEFAULT_BENCHMARK_ITERATIONS)
+            .clamp(1, MAX_BENCHMARK_ITERATIONS);
+
+        let Some(profiling) = Profiling::start() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Another benchmark is already running",
+            )]));
+        };
+
+        // The timed calls don't report progress against this call's token
+        let mut call_context = context;
+        call_context.meta = Meta::default();
+
+        let mut samples = Vec::with_capacity(iterations as usize);
+        for _ in 0..iterations {
+            let call = CallToolRequestParam {
+                name: request.tool.clone().into(),
+                arguments: Some(arguments.clone()),
+            };
+            let started = Instant::now();
+            let result = self
+                .tool_router
+                .call(ToolCallContext::new(self, call, call_context.clone()))
+                .await?;
+            samples.push((started.elapsed(), profiling.take()));
+            if result.is_error == Some(true) {
+                return Ok(result);
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            format_benchmark_results(&request.tool, &samples),
+        )]))
+    }
+
+    // ========================================================================
+    // Index Patching
+    // ========================================================================
+
+    #[tool(
+        description = "Enrich indexed symbols with information from a language server or IDE (resolved kinds, calls, typed parameters, doc comments). Patches go to the working layer without re-indexing from source, and are replaced when the file is re-indexed."
+    )]
+    async fn patch_index(
+        &self,
+        Parameters(request): Parameters<PatchIndexRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let cache = match CacheDir::for_repo(&repo_path) {
+            Ok(c) => c,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to open cache: {}",
+                    e
+                ))]))
+            }
+        };
+
+        // Persistent mode patches the live index; otherwise the cached
+        // working layer is loaded, patched and saved back
+        let outcome = if let Some(state) = &self.server_state {
+            state.write(|index| apply_symbol_patches(index, &cache, &request.patches))
+        } else {
+            let loaded = cache.load_layered_index().and_then(|index| match index {
+                Some(index) => Ok(index),
+                None => Ok(LayeredIndex {
+                    working: cache
+                        .load_layer(LayerKind::Working)?
+                        .unwrap_or_else(|| Overlay::new(LayerKind::Working)),
+                    ..LayeredIndex::new()
+                }),
+            });
+            let mut index = match loaded {
+                Ok(index) => index,
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Failed to load index layers: {}",
+                        e
+                    ))]))
+                }
+            };
+
+            let outcome = apply_symbol_patches(&mut index, &cache, &request.patches);
+            if !outcome.applied.is_empty() {
+                if let Err(e) = cache.save_layer(&index.working) {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Failed to save working layer: {}",
+                        e
+                    ))]));
+                }
+            }
+            outcome
+        };
+
+        let mut output = toon_header("patch_index");
+        output.push_str("layer: working\n");
+        output.push_str(&format!(
+            "persistent_mode: {}\n",
+            self.server_state.is_some()
+        ));
+        output.push_str(&format!("applied: {}\n", outcome.applied.len()));
+        if !outcome.applied.is_empty() {
+            output.push_str(&format!(
+                "patched[{}]{{hash,symbol,from_layer}}:\n",
+                outcome.applied.len()
+            ));
+            for (hash, name, layer) in &outcome.applied {
+                output.push_str(&format!("  {},{},{}\n", hash, name, layer));
+            }
+        }
+        if !outcome.not_found.is_empty() {
+            output.push_str(&format!(
+                "not_found[{}]: {}\n",
+                outcome.not_found.len(),
+                outcome.not_found.join(",")
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    // ========================================================================
+    // AI Layer (staged edits)
+    // ========================================================================
+
+    #[tool(
+        description = "Stage proposed content for a file in the AI layer before writing it to disk. The content is parsed and its symbols shadow the indexed ones, so search, get_symbol and get_callers (include_ai_layer, default true in persistent mode) answer as if the edit were applied, and analyze_diff with target_ref='AI' reviews it. Staged content is kept in memory only."
+    )]
+    async fn stage_ai_edit(
+        &self,
+        Parameters(request): Parameters<StageAiEditRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let cache = match CacheDir::for_repo(&repo_path) {
+            Ok(c) => c,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to open cache: {}",
+                    e
+                ))]))
+            }
+        };
+
+        let staged = self.update_ai_layer(&repo_path, |layer| {
+            layer
+                .stage(&cache, &request.file_path, request.content)
+                .map(|outcome| (outcome, layer.files().count()))
+        });
+        let (outcome, staged_files) = match staged {
+            Ok(staged) => staged,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to stage edit: {}",
+                    e
+                ))]))
+            }
+        };
+
+        let mut output = toon_header("stage_ai_edit");
+        output.push_str(&format!("file: {}\n", outcome.path));
+        output.push_str(&format!("symbols: {}\n", outcome.symbols));
+        output.push_str(&format!("added: {}\n", outcome.added));
+        output.push_str(&format!("modified: {}\n", outcome.modified));
+        output.push_str(&format!("removed: {}\n", outcome.removed));
+        output.push_str(&format!("staged_files: {}\n", staged_files));
+        if !self.has_server_state() {
+            output.push_str(
+                "hint: Pass include_ai_layer=true to search/get_symbol/get_callers to see staged symbols\n",
+            );
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(
+        description = "Drop edits staged with stage_ai_edit: one file (file_path), all of them, or (max_age_secs) those staged longer ago whose file was never written."
+    )]
+    async fn clear_ai_edits(
+        &self,
+        Parameters(request): Parameters<ClearAiEditsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let repo_root = Self::ai_layer_key(&repo_path);
+        let cleared = self.update_ai_layer(&repo_path, |layer| match request.max_age_secs {
+            Some(max_age) => layer.prune_stale(max_age),
+            None => layer.clear(&repo_root, request.file_path.as_deref()),
+        });
+
+        let mut output = toon_header("clear_ai_edits");
+        output.push_str(&format!("cleared: {}\n", cleared.len()));
+        if !cleared.is_empty() {
+            output.push_str(&format!(
+                "files[{}]: {}\n",
+                cleared.len(),
+                cleared.join(",")
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "List edits staged with stage_ai_edit, with their symbol counts.")]
+    async fn list_ai_edits(
+        &self,
+        Parameters(request): Parameters<ListAiEditsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        let layer = self
+            .ai_layer_for(&repo_path, Some(true))
+            .unwrap_or_default();
+        let files: Vec<_> = layer.files().collect();
+
+        let mut output = toon_header("list_ai_edits");
+        output.push_str(&format!("count: {}\n", files.len()));
+        if files.is_empty() {
+            output.push_str("edits: (none)\n");
+        } else {
+            output.push_str(&format!(
+                "edits[{}]{{file,module,symbols,added,removed,lines}}:\n",
+                files.len()
+            ));
+            for file in files {
+                output.push_str(&format!(
+                    "  {},{},{},{},{},{}\n",
+                    file.path,
+                    file.module,
+                    file.hashes.len(),
+                    file.added,
+                    file.removed.len(),
+                    file.content.lines().count()
+                ));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     // ========================================================================
     // Duplicate Detection Tools
     // ========================================================================
@@ -1064,6 +2166,7 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
     async fn find_duplicates(
         &self,
         Parameters(request): Parameters<FindDuplicatesRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         // DEDUP-307: Delegate to CLI run_duplicates handler
 
@@ -1072,32 +2175,54 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
             None => self.get_working_dir().await,
         };
 
-        let threshold = request.threshold.unwrap_or(0.90);
-        let exclude_boilerplate = request.exclude_boilerplate.unwrap_or(true);
+        let settings = match load_repo_settings(&repo_path) {
+            Ok(settings) => settings,
+            Err(result) => return Ok(result),
+        };
+        let threshold = request.threshold.unwrap_or(settings.duplicate_threshold);
+        let exclude_boilerplate = request
+            .exclude_boilerplate
+            .unwrap_or(!settings.include_boilerplate);
         let min_lines = request.min_lines.unwrap_or(3) as usize;
         let limit = request.limit.unwrap_or(50).min(200) as usize;
         let offset = request.offset.unwrap_or(0) as usize;
         let sort_by = request.sort_by.as_deref().unwrap_or("similarity");
 
+        let notifier = ProgressNotifier::for_request(&context);
         let ctx = CommandContext {
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: notifier.as_ref().map(|n| n.sink()),
+            settings: Some(settings),
         };
 
-        match run_duplicates(
+        let result = run_duplicates(
             Some(&repo_path),
             request.symbol_hash.as_deref(),
             threshold,
             request.module.as_deref(),
+            request.exclude_modules.as_deref().unwrap_or_default(),
             exclude_boilerplate,
+            request.include_generated.unwrap_or(false),
             min_lines,
             sort_by,
             limit,
             offset,
             &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        );
+        if let Some(notifier) = notifier {
+            notifier.finish().await;
+        }
+
+        match result {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to find duplicates: {}",
                 e
@@ -1110,7 +2235,7 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
     // ========================================================================
 
     #[tool(
-        description = "Prepare information for writing a commit message. Gathers git context, analyzes staged and unstaged changes semantically, and returns a compact summary with optional complexity metrics. **Use before committing** to understand what you're about to commit. This tool NEVER commits - it only provides information."
+        description = "Prepare information for writing a commit message. Gathers git context, analyzes staged, unstaged and untracked changes semantically (in separate sections, with per-section symbol counts), and returns a compact summary with optional complexity metrics. **Use before committing** to understand what you're about to commit. This tool NEVER commits - it only provides information."
     )]
     async fn prep_commit(
         &self,
@@ -1131,7 +2256,12 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
                     let staleness = check_cache_staleness_detailed(&cache, 3600);
                     if staleness.is_stale {
                         // Silently refresh the index
-                        let _ = generate_index_internal(&repo_path, 10, &[]);
+                        let settings = RepoSettings::load_or_default(&repo_path);
+                        let _ = generate_index_internal(
+                            &repo_path,
+                            settings.max_depth,
+                            &settings.extensions,
+                        );
                     }
                 }
             }
@@ -1152,6 +2282,12 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
         // Delegate to CLI handler
@@ -1228,7 +2364,9 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
                 .filter(|entry| include_escape_refs || !entry.is_escape_local)
                 .collect();
             let output = format_module_symbols(module, &results, &cache);
-            return Ok(CallToolResult::success(vec![Content::text(output)]));
+            return Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )]));
         }
 
         // File mode: delegate to CLI run_file_symbols handler
@@ -1243,20 +2381,39 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
-        match run_file_symbols(
-            Some(&repo_path),
-            file_path,
-            include_source,
-            request.kind.as_deref(),
-            request.risk.as_deref(),
-            context,
-            symbol_scope,
-            include_escape_refs,
-            &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        let result = with_pinned_index(&repo_path, || {
+            run_file_symbols(
+                Some(&repo_path),
+                file_path,
+                include_source,
+                request.kind.as_deref(),
+                request.risk.as_deref(),
+                context,
+                symbol_scope,
+                include_escape_refs,
+                &ctx,
+            )
+            .and_then(|mut output| {
+                if request.include_imports.unwrap_or(false) {
+                    let cache = CacheDir::for_repo(&repo_path)?;
+                    output.push('\n');
+                    output.push_str(&run_query_imports(file_path, &cache, &ctx)?);
+                }
+                Ok(output)
+            })
+        });
+        match result {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to get file symbols: {}",
                 e
@@ -1287,24 +2444,286 @@ Output is token-optimized: duplicates are grouped by module with counts and simi
             format: OutputFormat::Toon,
             verbose: false,
             progress: false,
+            output: None,
+            ai_layer: self.ai_layer_for(&repo_path, request.include_ai_layer),
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
         };
 
         // Delegate to CLI handler
-        match run_get_callers(
-            Some(&repo_path),
-            &request.symbol_hash,
-            depth,
-            include_source,
-            limit,
-            &ctx,
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+        match with_pinned_index(&repo_path, || {
+            run_get_callers(
+                Some(&repo_path),
+                &request.symbol_hash,
+                depth,
+                include_source,
+                limit,
+                &ctx,
+            )
+        }) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to get callers: {}",
                 e
             ))])),
         }
     }
+
+    #[tool(
+        description = "**Use before deleting or moving a module.** Lists every module importing from it, with the imported names and statement counts (re-exports through barrel files are followed and counted once). With unused=true, lists modules nothing imports that contain no entry points, tagged high/low confidence."
+    )]
+    async fn get_module_usages(
+        &self,
+        Parameters(request): Parameters<GetModuleUsagesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let limit = request.limit.unwrap_or(50).min(200);
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        let result = match (&request.module, request.unused.unwrap_or(false)) {
+            (Some(module), false) => run_module_usages(Some(&repo_path), module, limit, &ctx),
+            (None, true) => run_unused_modules(
+                Some(&repo_path),
+                limit,
+                request.include_generated.unwrap_or(false),
+                &ctx,
+            ),
+            _ => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Error: Provide either module or unused=true.",
+                )]))
+            }
+        };
+
+        match result {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get module usages: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "**What can I call from this module?** Lists a module's exported functions (with parameters and return types when known), constants and, with include_types=true, types with their members. Private symbols are only counted."
+    )]
+    async fn get_api_surface(
+        &self,
+        Parameters(request): Parameters<GetApiSurfaceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        match with_pinned_index(&repo_path, || {
+            run_api_surface(
+                Some(&repo_path),
+                &request.module,
+                request.include_types.unwrap_or(false),
+                &ctx,
+            )
+        }) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get API surface: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "**What does this React component render / who uses this hook?** With `root`, the render tree under a component (each child resolved from JSX to its definition, with file, line and risk; cycles and depth cut-offs are marked). With `hook`, the components calling it, e.g. `useContext(ThemeContext)`."
+    )]
+    async fn get_components(
+        &self,
+        Parameters(request): Parameters<GetComponentsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        let result = match (&request.hook, &request.root) {
+            (Some(hook), _) => run_hook_usage(Some(&repo_path), hook, &ctx),
+            (None, Some(root)) => {
+                run_component_tree(Some(&repo_path), root, request.depth.unwrap_or(5), &ctx)
+            }
+            (None, None) => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Provide either `root` (render tree) or `hook` (hook usage)",
+                )]))
+            }
+        };
+        match result {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(
+                with_index_coverage(&repo_path, output),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get components: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Commit history of a symbol: every commit that changed its lines, newest first, with date, author, subject and the file path at the time. Follows the lines through function and file renames, so it reaches back to the commit that added the symbol."
+    )]
+    async fn symbol_history(
+        &self,
+        Parameters(request): Parameters<SymbolHistoryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let repo_path = match &request.path {
+            Some(p) => self.resolve_path(p).await,
+            None => self.get_working_dir().await,
+        };
+
+        if let Err(e) = self.ensure_index(&repo_path).await {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let limit = request.limit.unwrap_or(20).min(100);
+        let ctx = CommandContext {
+            format: OutputFormat::Toon,
+            verbose: false,
+            progress: false,
+            output: None,
+            ai_layer: None,
+            paths: PathStyle::Relative,
+            progress_format: ProgressFormat::Text,
+            progress_sink: None,
+            settings: None,
+        };
+
+        match run_symbol_history(Some(&repo_path), &request.symbol_hash, limit, &ctx) {
+            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get symbol history: {}",
+                e
+            ))])),
+        }
+    }
+}
+
+/// Load the layered config for a tool's repository, or the error result to
+/// return when it is invalid
+fn load_repo_settings(path: &Path) -> Result<Arc<RepoSettings>, CallToolResult> {
+    RepoSettings::load(path).map(Arc::new).map_err(|e| {
+        CallToolResult::error(vec![Content::text(format!("Invalid configuration: {}", e))])
+    })
+}
+
+/// Format `benchmark_query` timings as compact TOON output
+///
+/// Each sample is a call's wall time and the phase times recorded during it;
+/// time outside the profiled phases is reported as `other`.
+fn format_benchmark_results(tool: &str, samples: &[(Duration, PhaseTimes)]) -> String {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mut sorted: Vec<Duration> = samples.iter().map(|(wall, _)| *wall).collect();
+    sorted.sort();
+    let n = sorted.len();
+    let median = if n % 2 == 0 {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+    } else {
+        sorted[n / 2]
+    };
+    let p95 = sorted[(n * 95).div_ceil(100) - 1];
+
+    let mut output = toon_header("benchmark_results");
+    output.push_str(&format!("tool: {}\n", tool));
+    output.push_str(&format!("iterations: {}\n", n));
+    output.push_str(&format!("min_ms: {:.2}\n", ms(sorted[0])));
+    output.push_str(&format!("max_ms: {:.2}\n", ms(sorted[n - 1])));
+    output.push_str(&format!("median_ms: {:.2}\n", ms(median)));
+    output.push_str(&format!("p95_ms: {:.2}\n", ms(p95)));
+
+    let wall: Duration = sorted.iter().sum();
+    let mut breakdown: Vec<(&str, Duration)> = Phase::ALL
+        .iter()
+        .map(|&phase| {
+            let total = samples.iter().map(|(_, times)| times.get(phase)).sum();
+            (phase.as_str(), total)
+        })
+        .collect();
+    let profiled: Duration = samples.iter().map(|(_, times)| times.total()).sum();
+    breakdown.push(("other", wall.saturating_sub(profiled)));
+
+    output.push_str(&format!(
+        "breakdown[{}]{{phase,mean_ms,percent}}:\n",
+        breakdown.len()
+    ));
+    for (phase, total) in breakdown {
+        let percent = if wall.is_zero() {
+            0.0
+        } else {
+            total.as_secs_f64() / wall.as_secs_f64() * 100.0
+        };
+        output.push_str(&format!(
+            "  {},{:.2},{:.1}\n",
+            phase,
+            ms(total) / n as f64,
+            percent
+        ));
+    }
+    output
 }
 
 /// Format test results as compact TOON output
@@ -1332,6 +2751,7 @@ fn format_test_results(results: &test_runner::TestResults) -> String {
             }
             if let Some(line) = failure.line {
                 output.push_str(&format!("    line: {}\n", line));
+                output.push_str(&format!("    {}\n", failure.symbol));
             }
             if !failure.message.is_empty() {
                 // Truncate long messages
@@ -1387,10 +2807,317 @@ impl ServerHandler for McpDiffServer {
 mod tests {
     use super::*;
 
+    use rmcp::ServiceExt;
+    use serde_json::{json, Value};
+    use tokio::io::{
+        AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf,
+    };
+
     #[test]
     fn test_server_creation() {
         let server = McpDiffServer::new();
         let info = server.get_info();
         assert_eq!(info.server_info.name, "semfora-engine");
     }
+
+    /// Minimal JSON-RPC client speaking to the server over an in-memory pipe
+    struct MockClient {
+        lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+        writer: WriteHalf<DuplexStream>,
+        next_id: u64,
+    }
+
+    impl MockClient {
+        async fn connect(server: McpDiffServer) -> Self {
+            let (client_io, server_io) = tokio::io::duplex(1 << 20);
+            tokio::spawn(async move {
+                let service = server.serve(tokio::io::split(server_io)).await.unwrap();
+                let _ = service.waiting().await;
+            });
+            let (reader, writer) = tokio::io::split(client_io);
+            let mut client = Self {
+                lines: BufReader::new(reader).lines(),
+                writer,
+                next_id: 1,
+            };
+            client
+                .request(
+                    "initialize",
+                    json!({
+                        "protocolVersion": "2024-11-05",
+                        "capabilities": {},
+                        "clientInfo": {"name": "mock", "version": "0"}
+                    }),
+                )
+                .await;
+            client
+                .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+                .await;
+            client
+        }
+
+        async fn send(&mut self, message: Value) {
+            let line = format!("{}\n", message);
+            self.writer.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        /// Send a request; returns its result and the notifications before it
+        async fn request(&mut self, method: &str, params: Value) -> (Value, Vec<Value>) {
+            let id = self.next_id;
+            self.next_id += 1;
+            self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+                .await;
+
+            let mut notifications = Vec::new();
+            while let Some(line) = self.lines.next_line().await.unwrap() {
+                let message: Value = serde_json::from_str(&line).unwrap();
+                if message["id"] == id {
+                    return (message["result"].clone(), notifications);
+                }
+                notifications.push(message);
+            }
+            panic!("server closed the connection");
+        }
+
+        /// Call a tool; returns its text and the progress notifications
+        async fn call_tool(
+            &mut self,
+            name: &str,
+            arguments: Value,
+            progress_token: Option<&str>,
+        ) -> (String, Vec<Value>) {
+            let mut params = json!({"name": name, "arguments": arguments});
+            if let Some(token) = progress_token {
+                params["_meta"] = json!({"progressToken": token});
+            }
+            let (result, notifications) = self.request("tools/call", params).await;
+            let progress = notifications
+                .into_iter()
+                .filter(|n| n["method"] == "notifications/progress")
+                .map(|n| n["params"].clone())
+                .collect();
+            let text = result["content"][0]["text"].as_str().unwrap().to_string();
+            (text, progress)
+        }
+    }
+
+    fn assert_progress_in_order(progress: &[Value], token: &str, phases: &[&str]) {
+        assert!(!progress.is_empty(), "no progress notifications");
+        let values: Vec<f64> = progress
+            .iter()
+            .map(|p| p["progress"].as_f64().unwrap())
+            .collect();
+        assert!(
+            values.windows(2).all(|w| w[0] <= w[1]),
+            "progress went backwards: {:?}",
+            values
+        );
+        assert!(progress.iter().all(|p| p["progressToken"] == token));
+
+        // Each phase's updates are contiguous, in the expected order
+        let mut seen: Vec<String> = Vec::new();
+        for p in progress {
+            let message = p["message"].as_str().unwrap();
+            let phase = message.split(':').next().unwrap().to_string();
+            if seen.last() != Some(&phase) {
+                assert!(!seen.contains(&phase), "phase {} resumed", phase);
+                seen.push(phase);
+            }
+        }
+        let expected: Vec<&str> = seen
+            .iter()
+            .map(String::as_str)
+            .filter(|phase| phases.contains(phase))
+            .collect();
+        assert_eq!(expected, phases, "phases: {:?}", seen);
+        assert!(progress.last().unwrap()["message"]
+            .as_str()
+            .unwrap()
+            .ends_with("(100%)"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_progress_notifications_precede_tool_result() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let body = "const total = items.reduce((sum, item) => sum + item.price * item.quantity, 0);
+    const tax = total * 0.2;
+    if (tax > 100) { console.log('high tax'); }
+    return total + tax;";
+        for (file, name) in [("src/cart.ts", "cartTotal"), ("src/order.ts", "orderTotal")] {
+            let path = repo.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(
+                path,
+                format!(
+                    "export function {}(items: any[]) {{\n    {}\n}}\n",
+                    name, body
+                ),
+            )
+            .unwrap();
+        }
+        let path = repo.path().to_string_lossy().to_string();
+        let mut client =
+            MockClient::connect(McpDiffServer::with_working_dir(repo.path().to_path_buf())).await;
+
+        // Generating the index behind get_overview reports its phases
+        let (_, progress) = client
+            .call_tool("get_overview", json!({"path": path}), Some("overview"))
+            .await;
+        assert_progress_in_order(&progress, "overview", &["Indexing files"]);
+
+        // A duplicate scan reports the same result with or without progress
+        let arguments = json!({"path": path, "min_lines": 1});
+        let (plain, none) = client
+            .call_tool("find_duplicates", arguments.clone(), None)
+            .await;
+        assert!(none.is_empty());
+        let (with_progress, progress) = client
+            .call_tool("find_duplicates", arguments, Some("dups"))
+            .await;
+        assert_eq!(with_progress, plain);
+        assert_progress_in_order(
+            &progress,
+            "dups",
+            &["Loading signatures", "Finding duplicates"],
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_repo_config_supplies_tool_defaults() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let body = "const total = items.reduce((sum, item) => sum + item.price * item.quantity, 0);
+    const tax = total * 0.2;
+    if (tax > 100) { console.log('high tax'); }
+    return total + tax;";
+        for (file, name) in [
+            ("src/cart.ts", "cartTotal"),
+            ("src/order.ts", "orderTotal"),
+            ("src/generated/client.ts", "generatedTotal"),
+        ] {
+            let path = repo.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(
+                path,
+                format!(
+                    "export function {}(items: any[]) {{\n    {}\n}}\n",
+                    name, body
+                ),
+            )
+            .unwrap();
+        }
+        let config = repo.path().join(".semfora/config.toml");
+        fs::create_dir_all(config.parent().unwrap()).unwrap();
+        fs::write(
+            &config,
+            "[duplicates]\nthreshold = 0.77\n\n[index]\nexclude = [\"src/generated\"]\n",
+        )
+        .unwrap();
+        let path = repo.path().to_string_lossy().to_string();
+        let mut client =
+            MockClient::connect(McpDiffServer::with_working_dir(repo.path().to_path_buf())).await;
+
+        client
+            .call_tool("get_overview", json!({"path": path}), None)
+            .await;
+        let (output, _) = client
+            .call_tool("find_duplicates", json!({"path": path}), None)
+            .await;
+        assert!(output.contains("threshold: 77%"), "{}", output);
+        assert!(output.contains("cartTotal"), "{}", output);
+        assert!(!output.contains("generatedTotal"), "{}", output);
+
+        // Dropping the exclusion invalidates the index built without those files
+        fs::write(&config, "[duplicates]\nthreshold = 0.77\n").unwrap();
+        client
+            .call_tool("get_overview", json!({"path": path}), None)
+            .await;
+        let (output, _) = client
+            .call_tool("find_duplicates", json!({"path": path}), None)
+            .await;
+        assert!(output.contains("generatedTotal"), "{}", output);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_lint_reports_lint_results() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let path = repo.path().to_string_lossy().to_string();
+        let mut client =
+            MockClient::connect(McpDiffServer::with_working_dir(repo.path().to_path_buf())).await;
+
+        let (output, _) = client
+            .call_tool("lint", json!({"path": path, "format": "json"}), None)
+            .await;
+        let report: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(report["_type"], "lint_results");
+        assert_eq!(report["mode"], "scan");
+        assert_eq!(report["error_count"], 0);
+        assert!(report["top_rules"].as_array().unwrap().is_empty());
+        assert!(report.get("warnings").is_none());
+
+        let (output, _) = client
+            .call_tool("lint", json!({"path": path, "linters": ["nope"]}), None)
+            .await;
+        assert_eq!(output, "Unknown linter: nope");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_benchmark_query_reports_latency_breakdown() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let path = repo.path().join("src/cart.ts");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "export function cartTotal(items: any[]) {\n    return items.length;\n}\n",
+        )
+        .unwrap();
+        let path = repo.path().to_string_lossy().to_string();
+        let mut client =
+            MockClient::connect(McpDiffServer::with_working_dir(repo.path().to_path_buf())).await;
+        let arguments = json!({
+            "tool": "search",
+            "params": {"path": path, "query": "cartTotal"},
+            "iterations": 3
+        });
+
+        std::env::remove_var(ALLOW_BENCHMARK_ENV);
+        let (output, _) = client
+            .call_tool("benchmark_query", arguments.clone(), None)
+            .await;
+        assert!(output.contains("SEMFORA_ALLOW_BENCHMARK=1"), "{}", output);
+
+        std::env::set_var(ALLOW_BENCHMARK_ENV, "1");
+        let (output, _) = client.call_tool("benchmark_query", arguments, None).await;
+        assert!(
+            output.starts_with("_type: benchmark_results\n"),
+            "{}",
+            output
+        );
+        assert!(output.contains("tool: search\n"), "{}", output);
+        assert!(output.contains("iterations: 3\n"), "{}", output);
+        for field in ["min_ms", "max_ms", "median_ms", "p95_ms"] {
+            assert!(output.contains(&format!("\n{}: ", field)), "{}", output);
+        }
+        let breakdown: Vec<&str> = output
+            .lines()
+            .skip_while(|line| !line.starts_with("breakdown[5]{phase,mean_ms,percent}:"))
+            .skip(1)
+            .map(|line| line.trim().split(',').next().unwrap())
+            .collect();
+        assert_eq!(
+            breakdown,
+            vec![
+                "index_lookup",
+                "bm25_search",
+                "filesystem_io",
+                "formatting",
+                "other"
+            ]
+        );
+
+        let (output, _) = client
+            .call_tool("benchmark_query", json!({"tool": "benchmark_query"}), None)
+            .await;
+        assert_eq!(output, "Cannot benchmark unknown tool: benchmark_query");
+        std::env::remove_var(ALLOW_BENCHMARK_ENV);
+    }
 }
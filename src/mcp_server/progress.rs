@@ -0,0 +1,193 @@
+//! MCP progress notifications for long-running tool calls
+//!
+//! A client asks for progress by sending a `progressToken` in the `_meta` of
+//! its `tools/call` request. [`ProgressNotifier`] turns the engine's
+//! `(phase, current, total)` progress updates into `notifications/progress`
+//! for that token. Updates are throttled to four per second, except that the
+//! first and last update of each phase are always sent.
+//!
+//! `progress` counts completed phases plus the fraction of the current one, so
+//! it only ever increases; the message names the phase and its percentage.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::{RequestContext, RoleServer};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::commands::ProgressSink;
+
+/// Shortest gap between two updates within a phase
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Sends progress notifications for one tool call
+pub(crate) struct ProgressNotifier {
+    sink: ProgressSink,
+    sender: Arc<Mutex<Option<UnboundedSender<ProgressNotificationParam>>>>,
+    forwarder: JoinHandle<()>,
+}
+
+impl ProgressNotifier {
+    /// Notifier for a tool call, if the client supplied a progress token
+    pub(crate) fn for_request(context: &RequestContext<RoleServer>) -> Option<Self> {
+        let token = context.meta.get_progress_token()?;
+
+        // Tool handlers report from blocking code; a single task sends the
+        // notifications, in order
+        let (tx, mut rx) = unbounded_channel::<ProgressNotificationParam>();
+        let peer = context.peer.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(param) = rx.recv().await {
+                if peer.notify_progress(param).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let sender = Arc::new(Mutex::new(Some(tx)));
+        let throttle = Mutex::new(Throttle::new(token));
+        let sink_sender = Arc::clone(&sender);
+        let sink = ProgressSink::new(move |phase, current, total| {
+            let Some(param) = throttle
+                .lock()
+                .ok()
+                .and_then(|mut t| t.update(phase, current, total, Instant::now()))
+            else {
+                return;
+            };
+            if let Some(tx) = sink_sender.lock().ok().as_ref().and_then(|s| s.as_ref()) {
+                let _ = tx.send(param);
+            }
+        });
+
+        Some(Self {
+            sink,
+            sender,
+            forwarder,
+        })
+    }
+
+    /// Sink to pass to the command handling the call
+    pub(crate) fn sink(&self) -> ProgressSink {
+        self.sink.clone()
+    }
+
+    /// Wait until every update reported so far has been sent
+    ///
+    /// Call before returning the tool result, so no notification follows it.
+    /// Later updates are dropped.
+    pub(crate) async fn finish(self) {
+        if let Ok(mut sender) = self.sender.lock() {
+            sender.take();
+        }
+        let _ = self.forwarder.await;
+    }
+}
+
+/// Decides which updates become notifications
+#[derive(Debug)]
+struct Throttle {
+    token: ProgressToken,
+    phase: String,
+    /// Phases started so far
+    phases: usize,
+    last_current: usize,
+    last_sent: Option<Instant>,
+}
+
+impl Throttle {
+    fn new(token: ProgressToken) -> Self {
+        Self {
+            token,
+            phase: String::new(),
+            phases: 0,
+            last_current: 0,
+            last_sent: None,
+        }
+    }
+
+    fn update(
+        &mut self,
+        phase: &str,
+        current: usize,
+        total: usize,
+        now: Instant,
+    ) -> Option<ProgressNotificationParam> {
+        let new_phase = self.phase != phase;
+        if new_phase {
+            self.phase = phase.to_string();
+            self.phases += 1;
+        } else if current <= self.last_current {
+            // Parallel workers can report out of order
+            return None;
+        }
+        self.last_current = current;
+
+        let phase_done = current >= total;
+        let too_soon = self
+            .last_sent
+            .is_some_and(|sent| now.duration_since(sent) < MIN_INTERVAL);
+        if !new_phase && !phase_done && too_soon {
+            return None;
+        }
+        self.last_sent = Some(now);
+
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            (current as f64 / total as f64).min(1.0)
+        };
+        Some(ProgressNotificationParam {
+            progress_token: self.token.clone(),
+            progress: (self.phases - 1) as f64 + fraction,
+            total: None,
+            message: Some(format!(
+                "{}: {}/{} ({:.0}%)",
+                phase,
+                current,
+                total,
+                fraction * 100.0
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    #[test]
+    fn test_throttle_keeps_phase_transitions_and_completions() {
+        let mut throttle = Throttle::new(ProgressToken(NumberOrString::Number(1)));
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let sent: Vec<String> = [
+            ("Indexing files", 0, 10, 0),
+            ("Indexing files", 3, 10, 10),   // too soon
+            ("Indexing files", 2, 10, 300),  // out of order
+            ("Indexing files", 5, 10, 300),  // interval passed
+            ("Indexing files", 10, 10, 310), // phase done
+            ("Writing shards", 0, 4, 320),   // new phase
+            ("Writing shards", 4, 4, 330),
+        ]
+        .into_iter()
+        .filter_map(|(phase, current, total, ms)| throttle.update(phase, current, total, at(ms)))
+        .map(|p| format!("{} {}", p.progress, p.message.unwrap()))
+        .collect();
+
+        assert_eq!(
+            sent,
+            vec![
+                "0 Indexing files: 0/10 (0%)",
+                "0.5 Indexing files: 5/10 (50%)",
+                "1 Indexing files: 10/10 (100%)",
+                "1 Writing shards: 0/4 (0%)",
+                "2 Writing shards: 4/4 (100%)",
+            ]
+        );
+    }
+}
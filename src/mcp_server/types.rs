@@ -64,6 +64,13 @@ pub struct AnalyzeRequest {
         description = "Output mode: 'full' (default - complete TOON), 'summary' (overview only), 'symbols_only' (just symbol list with line ranges)"
     )]
     pub output_mode: Option<String>,
+
+    /// Analysis mode: "coupling" reports tightly-coupled module pairs from the index,
+    /// "debt" the technical debt score
+    #[schemars(
+        description = "Analysis mode: 'coupling' reports module pairs that call into each other (from the index, sorted by coupling score); 'debt' scores technical debt (duplicates, complexity, coupling, untested code) with its trend. Omit for file/directory/module analysis."
+    )]
+    pub mode: Option<String>,
 }
 
 /// Request to analyze git diff
@@ -79,7 +86,7 @@ pub struct AnalyzeDiffRequest {
     /// The target branch or commit. Use "WORKING" to compare against uncommitted changes in the working tree.
     /// Defaults to "HEAD" for committed changes.
     #[schemars(
-        description = "Target branch or commit (defaults to 'HEAD'). Use 'WORKING' to analyze uncommitted changes vs base_ref."
+        description = "Target branch or commit (defaults to 'HEAD'). Use 'WORKING' to analyze uncommitted changes vs base_ref, or 'AI' to review staged AI edits (stage_ai_edit) against the working tree."
     )]
     pub target_ref: Option<String>,
 
@@ -102,6 +109,60 @@ pub struct AnalyzeDiffRequest {
         description = "Return only summary statistics (file counts, risk breakdown, top modules) without per-file details. Use for large diffs to get overview first."
     )]
     pub summary_only: Option<bool>,
+
+    /// Diff from the merge-base of base_ref and target (default: true)
+    #[schemars(
+        description = "Diff from the merge-base of base_ref and the target so only changes unique to the target branch appear, like a PR diff (default: true). Set false to diff directly against base_ref."
+    )]
+    pub use_merge_base: Option<bool>,
+
+    /// In a shallow clone, fetch up to this many commits of missing history
+    #[schemars(
+        description = "In a shallow clone (e.g. CI checkouts with --depth 1), fetch up to this many commits when base_ref or the merge-base is missing. Without it, a shallow clone returns an error naming the git fetch command to run."
+    )]
+    pub auto_deepen: Option<usize>,
+}
+
+/// Request to analyze a code snippet without indexing it
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AnalyzeSnippetRequest {
+    /// Source code to analyze (e.g. a single function)
+    #[schemars(
+        description = "Source code to analyze, typically a single function being written or reviewed. Never written to the index."
+    )]
+    pub content: String,
+
+    /// Language of the snippet
+    #[schemars(
+        description = "Language name or extension of the snippet (e.g. 'ts', 'typescript', 'py', 'rust')"
+    )]
+    pub lang: String,
+
+    /// Repository whose index is used for duplicate detection
+    #[schemars(
+        description = "Repository whose index is searched for duplicates (defaults to current directory). Duplicate detection is skipped when it has no index."
+    )]
+    pub path: Option<String>,
+}
+
+/// Request to semantically diff two files directly
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DiffTwoFilesRequest {
+    /// The "before" file
+    #[schemars(description = "Path to the first (\"before\") file, relative to path or absolute")]
+    pub file_a: String,
+
+    /// The "after" file
+    #[schemars(
+        description = "Path to the second (\"after\") file, relative to path or absolute. May be in another repository or language."
+    )]
+    pub file_b: String,
+
+    /// Directory relative file paths are resolved against
+    #[schemars(
+        description = "Directory that relative file_a/file_b are resolved against (defaults to current directory)"
+    )]
+    pub path: Option<String>,
 }
 
 /// Request to get supported languages
@@ -148,6 +209,30 @@ pub struct GetOverviewRequest {
         description = "Include git context (branch, last commit) in output (default: true)"
     )]
     pub include_git_context: Option<bool>,
+
+    /// Token budget for the overview
+    #[schemars(
+        description = "Trim the overview to fit this many tokens (e.g. 4000 for small context windows). Entry-point and high-risk modules are kept longest."
+    )]
+    pub max_tokens: Option<usize>,
+
+    /// Append a topics block clustering modules into this many topics
+    #[schemars(
+        description = "Append a 'topics' block grouping modules into this many topics by shared vocabulary (e.g. 5). Each topic has a label of its top terms, its modules and a cohesion score."
+    )]
+    pub topics: Option<usize>,
+
+    /// Append the largest, most-called and most-changed code (default: false)
+    #[schemars(
+        description = "Append a 'hotspots' block: the largest symbols, the most-called symbols (fan-in) and the most-changed files in recent git history, with module, file, risk and metric value (default: false)"
+    )]
+    pub include_hotspots: Option<bool>,
+
+    /// Tally the risk breakdown over symbols of at least this many lines
+    #[schemars(
+        description = "Count only symbols spanning at least this many lines in the risk breakdown (symbols instead of files), so tiny functions don't skew it"
+    )]
+    pub min_lines: Option<usize>,
 }
 
 /// Request to get symbol(s) from sharded index - supports single, batch, and file+line modes
@@ -188,6 +273,12 @@ pub struct GetSymbolRequest {
         description = "Context lines before/after symbol source (batch mode only, default: 3)"
     )]
     pub context: Option<usize>,
+
+    /// Answer as if staged AI edits were applied
+    #[schemars(
+        description = "Include staged AI edits (stage_ai_edit): staged symbols shadow indexed ones (default: true in persistent mode, false otherwise)"
+    )]
+    pub include_ai_layer: Option<bool>,
 }
 
 /// Request to generate/regenerate sharded index
@@ -224,10 +315,22 @@ pub struct GetCallgraphRequest {
     #[schemars(description = "Filter to edges involving symbols in this module")]
     pub module: Option<String>,
 
+    /// Leave out calls from/to symbols in these modules
+    #[schemars(
+        description = "Leave out edges whose caller or callee is in one of these modules or their submodules"
+    )]
+    pub exclude_modules: Option<Vec<String>>,
+
     /// Filter to calls from/to this specific symbol
     #[schemars(description = "Filter to edges from or to this symbol (by name or hash)")]
     pub symbol: Option<String>,
 
+    /// Filter to calls made from one workspace package
+    #[schemars(
+        description = "Filter to edges whose caller is in this monorepo package (name or directory)"
+    )]
+    pub package: Option<String>,
+
     /// Maximum edges to return (default: 500, max: 2000)
     #[schemars(description = "Maximum edges to return (default: 500, max: 2000)")]
     pub limit: Option<u32>,
@@ -329,6 +432,10 @@ pub struct SearchRequest {
     #[schemars(description = "Filter results to a specific module")]
     pub module: Option<String>,
 
+    /// Leave out results in these modules
+    #[schemars(description = "Leave out results in these modules and their submodules")]
+    pub exclude_modules: Option<Vec<String>>,
+
     /// Filter by symbol kind (fn, struct, component, enum, trait, etc.)
     #[schemars(description = "Filter by symbol kind (fn, struct, component, enum, trait, etc.)")]
     pub kind: Option<String>,
@@ -345,6 +452,54 @@ pub struct SearchRequest {
     )]
     pub risk: Option<String>,
 
+    /// Restrict results to one file
+    #[schemars(
+        description = "Only return results from this file (path relative to the repository root)"
+    )]
+    pub in_file: Option<String>,
+
+    /// Restrict results to one workspace package
+    #[schemars(
+        description = "Only return results from this monorepo package (name from get_overview's packages section, or its directory)"
+    )]
+    pub package: Option<String>,
+
+    /// Restrict results to paths matching these globs
+    #[schemars(
+        description = "Only return results from files matching these globs, relative to the repository root, e.g. ['src/payments/**'] or ['!vendor/**']. '**' spans directories; a '!' prefix excludes. Applies in every mode."
+    )]
+    pub path_glob: Option<Vec<String>>,
+
+    /// List symbols gated by this feature flag (query then filters by name)
+    #[schemars(
+        description = "List symbols gated by this feature flag, e.g. a Rust cfg feature or a JS flag SDK key. The query, if non-empty, filters by symbol name."
+    )]
+    pub flag: Option<String>,
+
+    /// List symbols marked deprecated (query then filters by name)
+    #[schemars(
+        description = "List symbols marked deprecated (Rust #[deprecated], Java @Deprecated, JSDoc @deprecated, Python DeprecationWarning) with their deprecation notes. The query, if non-empty, filters by symbol name."
+    )]
+    pub deprecated: Option<bool>,
+
+    /// List symbols that can panic or throw (query then filters by name)
+    #[schemars(
+        description = "List symbols that can panic or throw (Rust .unwrap()/.expect()/panic!, JS/Java/C# throw, Python raise) with each panic point's line, kind and whether a try block catches it. The query, if non-empty, filters by symbol name."
+    )]
+    pub panics: Option<bool>,
+
+    /// List symbols with embedded SQL touching this table (query then filters by name)
+    #[schemars(
+        description = "List symbols whose SQL string literals (SELECT/INSERT/UPDATE/DELETE/CREATE) touch this table, matched case-insensitively, with each query's line, text and tables. The query, if non-empty, filters by symbol name."
+    )]
+    pub sql: Option<String>,
+
+    /// Facet filters, e.g. ["is_async", "io_calls_outside_try>0"]
+    #[schemars(
+        description = "Facet filters, all of which must match, e.g. ['is_async', 'io_calls_outside_try>0'] for async code doing I/O outside try blocks, or ['has_empty_catch'] for code that swallows errors. Facets: is_async, awaits_count, io_calls_outside_try, has_empty_catch, rethrows, panic_points; operators =, !=, >, >=, <, <= (a bare name means > 0). The query, if non-empty, filters by symbol name."
+    )]
+    pub facets: Option<Vec<String>>,
+
     /// Maximum results to return (default: 20)
     #[schemars(description = "Maximum results to return (default: 20)")]
     pub limit: Option<usize>,
@@ -373,6 +528,51 @@ pub struct SearchRequest {
     /// Include local variables that escape their scope (default: false)
     #[schemars(description = "Include local variables that escape their scope (default: false)")]
     pub include_escape_refs: Option<bool>,
+
+    // --- Hybrid mode fallback ---
+    /// Skip the plain-text fallback when the index finds nothing
+    #[schemars(
+        description = "Disable the automatic plain-text fallback: by default, a hybrid search whose query isn't a symbol name and that finds fewer than fallback_min indexed hits also returns literal text matches tagged source: raw_fallback (default: false)"
+    )]
+    pub no_fallback: Option<bool>,
+
+    /// Indexed hits below which the fallback runs (default: 1)
+    #[schemars(
+        description = "Run the plain-text fallback when symbol + related hits are below this (default: 1, i.e. only on zero hits)"
+    )]
+    pub fallback_min: Option<usize>,
+
+    /// Maximum fallback matches (default: 10)
+    #[schemars(description = "Maximum plain-text fallback matches returned (default: 10)")]
+    pub fallback_limit: Option<usize>,
+
+    /// Answer as if staged AI edits were applied
+    #[schemars(
+        description = "Include staged AI edits (stage_ai_edit): staged symbols shadow indexed ones (default: true in persistent mode, false otherwise)"
+    )]
+    pub include_ai_layer: Option<bool>,
+}
+
+/// Semantic grep request - finds symbols by what they call, mutate, import or return
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SemanticGrepRequest {
+    /// Pattern of the form `<field>:<value>`
+    #[schemars(
+        description = "Pattern '<field>:<value>': 'calls:fetchUser' (or 'calls:axios.get'), 'mutates:session', 'imports:axios', 'returns:Promise<User>'"
+    )]
+    pub pattern: String,
+
+    /// Filter by symbol kind (fn, struct, component, enum, trait, etc.)
+    #[schemars(description = "Filter by symbol kind (fn, struct, component, enum, trait, etc.)")]
+    pub kind: Option<String>,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Maximum results to return (default: 50)
+    #[schemars(description = "Maximum results to return (default: 50)")]
+    pub limit: Option<usize>,
 }
 
 /// Unified validate request - auto-detects scope based on provided parameters.
@@ -405,6 +605,16 @@ pub struct ValidateRequest {
     )]
     pub module: Option<String>,
 
+    /// Leave out symbols in these modules (file/module scope)
+    #[schemars(
+        description = "Leave out symbols in these modules and their submodules - only for file/module scope"
+    )]
+    pub exclude_modules: Option<Vec<String>>,
+
+    /// Include generated code (default: false)
+    #[schemars(description = "Include generated code such as protobuf output (default: false)")]
+    pub include_generated: Option<bool>,
+
     /// Repository path (defaults to current directory)
     #[schemars(description = "Path to the repository root (defaults to current directory)")]
     pub path: Option<String>,
@@ -465,6 +675,12 @@ pub struct IndexRequest {
         description = "Maximum cache age in seconds for smart refresh (default: 3600 = 1 hour)"
     )]
     pub max_age: Option<u64>,
+
+    /// Compact overlay layers instead of indexing (default: false)
+    #[schemars(
+        description = "Fold committed overlay layers into the branch/base layer and drop stale tombstones instead of indexing. Requires a clean working tree; returns before/after layer stats (default: false)"
+    )]
+    pub compact: Option<bool>,
 }
 
 /// Unified test request - runs tests by default, use detect_only=true to only detect frameworks.
@@ -500,6 +716,30 @@ pub struct TestRequest {
     /// Maximum time to run tests in seconds (default: 300)
     #[schemars(description = "Maximum time to run tests in seconds (default: 300)")]
     pub timeout: Option<u64>,
+
+    /// Only run tests that reach code changed since this ref
+    #[schemars(
+        description = "Only run tests that transitively call code changed since this ref (e.g. 'HEAD' for uncommitted changes, 'main' for the branch). Falls back to all tests if coverage can't be determined."
+    )]
+    pub changed: Option<String>,
+}
+
+/// Request to generate skeleton tests for a symbol
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GenerateTestStubsRequest {
+    /// Symbol hash from search or get_symbol
+    #[schemars(description = "Symbol hash from search or get_symbol")]
+    pub symbol_hash: String,
+
+    /// Test framework to generate for
+    #[schemars(
+        description = "Test framework to generate for (cargo, pytest, jest, vitest, npm, go). Defaults to the detected framework if it fits the symbol's language, else the language's default."
+    )]
+    pub framework: Option<String>,
+
+    /// Path to the repository (defaults to current directory)
+    #[schemars(description = "Path to the repository (defaults to current directory)")]
+    pub path: Option<String>,
 }
 
 // ============================================================================
@@ -533,6 +773,22 @@ pub struct LintRequest {
     )]
     pub linter: Option<String>,
 
+    /// Only run these linters
+    #[schemars(
+        description = "Only run these linters (e.g., ['clippy', 'rustfmt']). Combined with 'linter'; auto-detects if neither is given."
+    )]
+    pub linters: Option<Vec<String>>,
+
+    /// List warnings, info and hints as well as errors (default: false)
+    #[schemars(
+        description = "List warnings, info and hints as well as errors (default: false = errors only, with a count of the rest)"
+    )]
+    pub include_warnings: Option<bool>,
+
+    /// Output format: "toon" (default) or "json"
+    #[schemars(description = "Output format: 'toon' (default) or 'json'")]
+    pub format: Option<String>,
+
     /// Filter issues by severity (error, warning, info, hint)
     #[schemars(
         description = "Filter by severity levels (e.g., ['error', 'warning']). Shows all by default."
@@ -547,6 +803,12 @@ pub struct LintRequest {
     #[schemars(description = "Only show fixable issues (default: false)")]
     pub fixable_only: Option<bool>,
 
+    /// Apply automatic fixes (same as mode "fix")
+    #[schemars(
+        description = "Apply automatic fixes, then report how many issues were fixed and which remain for manual intervention (same as mode 'fix'; default: false)"
+    )]
+    pub fix: Option<bool>,
+
     /// Dry run for fix mode - show what would be fixed without changing files
     #[schemars(
         description = "Dry run - show what would be fixed without changing files (default: false)"
@@ -556,6 +818,12 @@ pub struct LintRequest {
     /// Only apply safe fixes in fix mode (default: false)
     #[schemars(description = "Only apply safe auto-fixes (default: false)")]
     pub safe_only: Option<bool>,
+
+    /// Only detect and run linters for these languages
+    #[schemars(
+        description = "Only detect and run linters for these languages, by name or extension (e.g., ['rust', 'go']). All languages by default."
+    )]
+    pub languages: Option<Vec<String>>,
 }
 
 // ============================================================================
@@ -655,6 +923,12 @@ pub struct GetFileRequest {
     #[schemars(description = "Include local variables that escape their scope (default: false)")]
     pub include_escape_refs: Option<bool>,
 
+    /// Append the file's imports (file mode only)
+    #[schemars(
+        description = "Append the names the file imports and where they come from (file mode only, default: false)"
+    )]
+    pub include_imports: Option<bool>,
+
     /// Repository path
     #[schemars(description = "Path to the repository root (defaults to current directory)")]
     pub path: Option<String>,
@@ -769,6 +1043,90 @@ pub struct ServerStatusRequest {
     pub include_layers: Option<bool>,
 }
 
+/// Request to time repeated calls of another tool
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct BenchmarkQueryRequest {
+    /// Name of the tool to benchmark
+    #[schemars(description = "Name of the tool to benchmark (e.g., 'search', 'get_symbol')")]
+    pub tool: String,
+
+    /// Arguments passed to the tool on every call
+    #[schemars(description = "Arguments passed to the tool on every call (default: {})")]
+    pub params: Option<serde_json::Value>,
+
+    /// Number of timed calls (default: 5, max: 100)
+    #[schemars(description = "Number of timed calls (default: 5, max: 100)")]
+    pub iterations: Option<u32>,
+}
+
+// ============================================================================
+// AI Layer Request Types
+// ============================================================================
+
+/// Stage proposed file content in the AI layer
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StageAiEditRequest {
+    /// Path to the repository (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// File the content is proposed for
+    #[schemars(
+        description = "File the content is proposed for, relative to the repository root (the file may not exist yet)"
+    )]
+    pub file_path: String,
+
+    /// Proposed full file content
+    #[schemars(
+        description = "Proposed full file content. Replaces any content staged earlier for the file. Never written to disk or the index."
+    )]
+    pub content: String,
+}
+
+/// Drop staged AI edits
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ClearAiEditsRequest {
+    /// Path to the repository (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Only unstage this file
+    #[schemars(description = "Only unstage this file (default: clear all staged edits)")]
+    pub file_path: Option<String>,
+
+    /// Only unstage edits older than this whose file doesn't exist on disk
+    #[schemars(
+        description = "Only unstage edits staged more than this many seconds ago whose file doesn't exist on disk (file_path is then ignored)"
+    )]
+    pub max_age_secs: Option<u64>,
+}
+
+/// List staged AI edits
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListAiEditsRequest {
+    /// Path to the repository (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+}
+
+// ============================================================================
+// Index Patch Request Type
+// ============================================================================
+
+/// Request to enrich indexed symbols with external (LSP/IDE) information
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PatchIndexRequest {
+    /// Path to the repository (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Patches to apply
+    #[schemars(
+        description = "Symbol patches: symbol_hash plus any of kind, extra_calls ('name' or 'object.name'), extra_params ('name' or 'name: Type'), doc_comment"
+    )]
+    pub patches: Vec<crate::overlay::SymbolPatch>,
+}
+
 // ============================================================================
 // Duplicate Detection Request Types
 // ============================================================================
@@ -796,6 +1154,14 @@ pub struct FindDuplicatesRequest {
     #[schemars(description = "Filter to specific module")]
     pub module: Option<String>,
 
+    /// Leave out functions in these modules (only for codebase scan)
+    #[schemars(description = "Leave out functions in these modules and their submodules")]
+    pub exclude_modules: Option<Vec<String>>,
+
+    /// Include generated code in the scan (default: false)
+    #[schemars(description = "Include generated code such as protobuf output (default: false)")]
+    pub include_generated: Option<bool>,
+
     /// Repository path (defaults to current directory)
     #[schemars(description = "Repository path")]
     pub path: Option<String>,
@@ -812,9 +1178,9 @@ pub struct FindDuplicatesRequest {
     #[schemars(description = "Skip first N clusters for pagination (default: 0)")]
     pub offset: Option<u32>,
 
-    /// Sort clusters by: "similarity" (default), "size", or "count" (only for codebase scan)
+    /// Sort clusters by: "similarity" (default), "size", "count", or "savings" (only for codebase scan)
     #[schemars(
-        description = "Sort by: 'similarity' (highest first), 'size' (largest functions), 'count' (most duplicates)"
+        description = "Sort by: 'similarity' (highest first), 'size' (largest functions), 'count' (most duplicates), 'savings' (estimated consolidation savings, with a suggested action per cluster)"
     )]
     pub sort_by: Option<String>,
 }
@@ -897,6 +1263,101 @@ pub struct GetCallersRequest {
     /// Include source snippets for callers (default: false)
     #[schemars(description = "Include source code snippets for each caller (default: false)")]
     pub include_source: Option<bool>,
+
+    /// Answer as if staged AI edits were applied
+    #[schemars(
+        description = "Include staged AI edits (stage_ai_edit): staged symbols shadow indexed ones (default: true in persistent mode, false otherwise)"
+    )]
+    pub include_ai_layer: Option<bool>,
+}
+
+/// Module-level impact: which modules import a module, or which modules
+/// nothing imports
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetModuleUsagesRequest {
+    /// Module to find importers for
+    #[schemars(
+        description = "Module name (e.g. 'legacy.payments') or directory (e.g. 'src/legacy/payments'). Omit with unused=true."
+    )]
+    pub module: Option<String>,
+
+    /// List unused modules instead
+    #[schemars(
+        description = "List modules with no inbound imports and no entry points (deletion candidates) instead of importers"
+    )]
+    pub unused: Option<bool>,
+
+    /// With unused, also list generated modules
+    #[schemars(
+        description = "With unused=true, also list modules made up of generated code (default: false)"
+    )]
+    pub include_generated: Option<bool>,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Maximum modules to return (default: 50)
+    #[schemars(description = "Maximum modules to return (default: 50)")]
+    pub limit: Option<usize>,
+}
+
+/// Public API of a module
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetApiSurfaceRequest {
+    /// Module to list the exports of
+    #[schemars(description = "Module name (from get_overview)")]
+    pub module: String,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Also list exported types (default: false)
+    #[schemars(
+        description = "Also list exported classes, structs, interfaces and other types with their members (default: false)"
+    )]
+    pub include_types: Option<bool>,
+}
+
+/// React render tree or hook usage
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetComponentsRequest {
+    /// Component at the root of the render tree
+    #[schemars(description = "Component name or symbol hash at the root of the render tree")]
+    pub root: Option<String>,
+
+    /// Hook to list the callers of instead
+    #[schemars(
+        description = "List components calling this hook instead of a tree: `useTheme`, or `useContext(ThemeContext)` to match the first argument too"
+    )]
+    pub hook: Option<String>,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Maximum render depth (default: 5)
+    #[schemars(description = "Maximum render depth below the root (default: 5)")]
+    pub depth: Option<usize>,
+}
+
+/// Commit history of a symbol
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SymbolHistoryRequest {
+    /// Symbol hash to trace
+    #[schemars(
+        description = "Symbol hash to show the history of (from search or get_file_symbols)"
+    )]
+    pub symbol_hash: String,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+
+    /// Maximum commits to return (default: 20, max: 100)
+    #[schemars(description = "Maximum commits to return, newest first (default: 20, max: 100)")]
+    pub limit: Option<usize>,
 }
 
 // ============================================================================
@@ -931,6 +1392,18 @@ pub struct ValidateSymbolRequest {
     pub include_source: Option<bool>,
 }
 
+/// Request for the context needed to refactor a symbol
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StartRefactoringSessionRequest {
+    /// Symbol hash to refactor
+    #[schemars(description = "Symbol hash to refactor (from search or get_file_symbols)")]
+    pub symbol_hash: String,
+
+    /// Repository path (defaults to current directory)
+    #[schemars(description = "Path to the repository root (defaults to current directory)")]
+    pub path: Option<String>,
+}
+
 // ============================================================================
 // BM25 Semantic Search Types (Phase 3)
 // ============================================================================
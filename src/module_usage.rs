@@ -0,0 +1,744 @@
+//! Module-level import usage
+//!
+//! Answers two questions before deleting or moving a module:
+//!
+//! - **Who imports it?** [`ModuleUsageIndex::usages`] lists every other module
+//!   importing anything from it, with the imported names and statement counts.
+//! - **Does anything import it?** [`ModuleUsageIndex::unused_modules`] lists
+//!   modules with no inbound imports and no entry points (deletion candidates).
+//!
+//! The data comes from a per-file import summary written at index time
+//! (`graphs/module_usage.json`) and patched on partial reindex. Import
+//! specifiers are stored unresolved and resolved against the indexed files
+//! when queried, so files added by a partial reindex become import targets
+//! without rebuilding the summary.
+//!
+//! Re-exports are followed: a module importing `charge` from a barrel that
+//! re-exports it from `legacy.payments` uses `legacy.payments` as well. Each
+//! import statement is attributed once, however many barrels it passes through.
+//!
+//! Two files importing each other form an import cycle
+//! ([`ModuleUsageIndex::import_cycles`]). Dynamic `import()` calls, the usual
+//! way to break a cycle, don't count.
+//!
+//! Only JavaScript/TypeScript files record imports today; modules in other
+//! languages are never reported as unused or cyclic.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::ops::Bound;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::verify::{corrupt, read_artifact};
+use crate::cache::CacheDir;
+use crate::error::{McpDiffError, Result};
+use crate::lang::{Lang, LangFamily};
+use crate::schema::{
+    FrameworkEntryPoint, ImportKind, ImportedName, RiskLevel, SemanticSummary, SCHEMA_VERSION,
+};
+use crate::search::is_test_file;
+use crate::shard::extract_module_name;
+
+/// Extensions tried when resolving an extensionless specifier
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts", "vue"];
+
+/// Persisted per-file import summary
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleUsageIndex {
+    pub schema_version: String,
+    /// Every indexed file, keyed by path
+    pub files: BTreeMap<String, FileImports>,
+}
+
+/// Imports recorded for one file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileImports {
+    /// Module the file belongs to
+    pub module: String,
+    /// Framework entry point, binary, test or config file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub entry_point: bool,
+    /// The file's language records imports (see module docs)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tracks_imports: bool,
+    /// Generated code (see [`crate::generated`])
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub generated: bool,
+    /// Repo-local (relative) imports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<ImportRecord>,
+}
+
+/// A relative import statement
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportRecord {
+    /// Specifier as written (`./payments`); a trailing `*` is a dynamic prefix
+    pub source: String,
+    /// Imported (or re-exported) names; empty for namespace and side-effect imports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<ImportedName>,
+    pub kind: ImportKind,
+}
+
+/// A module importing from the queried module
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModuleImporter {
+    pub module: String,
+    /// Names imported from the queried module (`*` for namespace imports)
+    pub names: BTreeSet<String>,
+    /// Import statements (each counted once)
+    pub count: usize,
+    /// Importing files
+    pub files: BTreeSet<String>,
+    /// Barrel modules the imports go through (empty for direct imports)
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub via: BTreeSet<String>,
+    /// Only reached through dynamic `import()`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dynamic_only: bool,
+}
+
+/// A module nothing imports
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedModule {
+    pub module: String,
+    pub files: usize,
+    /// `high` when nothing references the module, `low` when only dynamic
+    /// `import()` calls (or template prefixes) may reach it
+    pub confidence: &'static str,
+    pub reason: String,
+}
+
+impl FileImports {
+    /// Summarize a file's imports
+    pub fn from_summary(summary: &SemanticSummary, module: String) -> Self {
+        let tracks_imports = Lang::from_path(Path::new(&summary.file))
+            .is_ok_and(|lang| lang.family() == LangFamily::JavaScript);
+        let imports = summary
+            .imports
+            .iter()
+            .filter(|import| tracks_imports && import.source.starts_with('.'))
+            .map(|import| ImportRecord {
+                source: import.source.clone(),
+                names: if import.is_namespace {
+                    Vec::new()
+                } else {
+                    import.names.clone()
+                },
+                kind: import.kind,
+            })
+            .collect();
+
+        Self {
+            module,
+            entry_point: is_entry_point(summary),
+            tracks_imports,
+            generated: summary.is_generated,
+            imports,
+        }
+    }
+}
+
+/// Whether nothing is expected to import the file
+fn is_entry_point(summary: &SemanticSummary) -> bool {
+    if summary.framework_entry_point != FrameworkEntryPoint::None
+        || summary
+            .symbols
+            .iter()
+            .any(|s| s.framework_entry_point != FrameworkEntryPoint::None)
+        || is_test_file(&summary.file)
+    {
+        return true;
+    }
+
+    let path = Path::new(&summary.file);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let stem = name.split('.').next().unwrap_or("");
+    summary.file.contains("/bin/")
+        || matches!(stem, "main" | "cli" | "__main__")
+        || name.contains(".config.")
+        || name.ends_with(".d.ts")
+}
+
+impl ModuleUsageIndex {
+    /// Build the summary for a freshly indexed repository
+    pub fn build(summaries: &[SemanticSummary], file_to_module: &HashMap<String, String>) -> Self {
+        let mut index = Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            files: BTreeMap::new(),
+        };
+        index.update(summaries, &[], file_to_module);
+        index
+    }
+
+    /// Replace the entries of reindexed files and drop deleted ones
+    pub fn update(
+        &mut self,
+        summaries: &[SemanticSummary],
+        removed: &[String],
+        file_to_module: &HashMap<String, String>,
+    ) {
+        // Keys are normalized so `./src/a.ts` (repo indexed as `.`) resolves
+        // against specifiers joined onto the importer's directory
+        for file in removed {
+            self.files.remove(&normalize_path(file));
+        }
+        for summary in summaries {
+            let module = file_to_module
+                .get(&summary.file)
+                .cloned()
+                .unwrap_or_else(|| extract_module_name(&summary.file));
+            self.files.insert(
+                normalize_path(&summary.file),
+                FileImports::from_summary(summary, module),
+            );
+        }
+    }
+
+    /// Load the persisted summary
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        let path = cache.module_usage_path();
+        if !path.exists() {
+            return Err(McpDiffError::FileNotFound {
+                path: "Module usage summary not found. Run `semfora index generate --force` first."
+                    .to_string(),
+            });
+        }
+        let content = read_artifact(&path)?;
+        serde_json::from_str(&content).map_err(|e| corrupt(&path, e))
+    }
+
+    /// Persist the summary, returning the bytes written
+    pub fn write(&self, cache: &CacheDir) -> Result<usize> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize module usage: {}", e),
+        })?;
+        let path = cache.module_usage_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+        Ok(content.len())
+    }
+
+    /// Names of all indexed modules
+    pub fn modules(&self) -> BTreeSet<&str> {
+        self.files.values().map(|f| f.module.as_str()).collect()
+    }
+
+    /// Every module importing from `module`, most imports first
+    pub fn usages(&self, module: &str) -> Vec<ModuleImporter> {
+        let edges = self.resolve_edges();
+        let mut importers_of: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (idx, edge) in edges.iter().enumerate() {
+            for target in &edge.targets {
+                importers_of.entry(target).or_default().push(idx);
+            }
+        }
+
+        // Walk backwards from the module's files through re-exports. `names`
+        // is the set of names a reached file exports from the module (None = all).
+        struct Reach<'a> {
+            file: &'a str,
+            names: Option<BTreeSet<String>>,
+            via: Option<&'a str>,
+        }
+        let mut queue: VecDeque<Reach> = self
+            .files
+            .iter()
+            .filter(|(_, f)| f.module == module)
+            .map(|(file, _)| Reach {
+                file: file.as_str(),
+                names: None,
+                via: None,
+            })
+            .collect();
+        let mut visited: HashSet<&str> = queue.iter().map(|r| r.file).collect();
+
+        // (importing file, statement) -> (names, via, dynamic)
+        let mut hits: BTreeMap<(&str, usize), (BTreeSet<String>, Option<&str>, bool)> =
+            BTreeMap::new();
+
+        while let Some(reach) = queue.pop_front() {
+            for &idx in importers_of.get(reach.file).into_iter().flatten() {
+                let edge = &edges[idx];
+                let Some(names) = imported_names(edge.record, reach.names.as_ref()) else {
+                    continue;
+                };
+                let from_module = self.files[edge.from].module.as_str();
+
+                if from_module != module {
+                    let hit = hits.entry((edge.from, edge.statement)).or_insert_with(|| {
+                        (
+                            BTreeSet::new(),
+                            reach.via,
+                            edge.record.kind == ImportKind::Dynamic,
+                        )
+                    });
+                    hit.0.extend(names.iter().cloned());
+                }
+
+                if edge.record.kind == ImportKind::ReExport && visited.insert(edge.from) {
+                    let via = if from_module == module {
+                        reach.via
+                    } else {
+                        reach.via.or(Some(from_module))
+                    };
+                    queue.push_back(Reach {
+                        file: edge.from,
+                        names: reexported_names(edge.record, reach.names.as_ref()),
+                        via,
+                    });
+                }
+            }
+        }
+
+        let mut by_module: BTreeMap<&str, ModuleImporter> = BTreeMap::new();
+        for ((file, _), (names, via, dynamic)) in hits {
+            let importer_module = self.files[file].module.as_str();
+            let importer = by_module
+                .entry(importer_module)
+                .or_insert_with(|| ModuleImporter {
+                    module: importer_module.to_string(),
+                    dynamic_only: true,
+                    ..Default::default()
+                });
+            importer.names.extend(names);
+            importer.count += 1;
+            importer.files.insert(file.to_string());
+            importer.via.extend(via.map(String::from));
+            importer.dynamic_only &= dynamic;
+        }
+
+        let mut importers: Vec<ModuleImporter> = by_module.into_values().collect();
+        importers.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.module.cmp(&b.module)));
+        importers
+    }
+
+    /// Modules with no inbound imports that contain no entry points
+    ///
+    /// Only modules whose files all record imports are considered. Modules
+    /// made up of generated code are left out unless `include_generated`.
+    pub fn unused_modules(&self, include_generated: bool) -> Vec<UnusedModule> {
+        #[derive(Default)]
+        struct ModuleState {
+            files: usize,
+            candidate: bool,
+            generated: bool,
+            static_refs: usize,
+            dynamic_refs: usize,
+        }
+
+        let mut modules: BTreeMap<&str, ModuleState> = BTreeMap::new();
+        for file in self.files.values() {
+            let state = modules.entry(file.module.as_str()).or_insert(ModuleState {
+                candidate: true,
+                generated: true,
+                ..Default::default()
+            });
+            state.files += 1;
+            state.candidate &= file.tracks_imports && !file.entry_point;
+            state.generated &= file.generated;
+        }
+
+        for edge in self.resolve_edges() {
+            let from_module = self.files[edge.from].module.as_str();
+            let targets: BTreeSet<&str> = edge
+                .targets
+                .iter()
+                .map(|t| self.files[*t].module.as_str())
+                .filter(|m| *m != from_module)
+                .collect();
+            for target in targets {
+                let Some(state) = modules.get_mut(target) else {
+                    continue;
+                };
+                if edge.record.kind == ImportKind::Dynamic {
+                    state.dynamic_refs += 1;
+                } else {
+                    state.static_refs += 1;
+                }
+            }
+        }
+
+        modules
+            .into_iter()
+            .filter(|(_, s)| s.candidate && s.static_refs == 0)
+            .filter(|(_, s)| include_generated || !s.generated)
+            .map(|(module, s)| {
+                let (confidence, reason) = if s.dynamic_refs > 0 {
+                    (
+                        "low",
+                        format!("referenced only via {} dynamic import(s)", s.dynamic_refs),
+                    )
+                } else {
+                    ("high", "no inbound imports".to_string())
+                };
+                UnusedModule {
+                    module: module.to_string(),
+                    files: s.files,
+                    confidence,
+                    reason,
+                }
+            })
+            .collect()
+    }
+
+    /// Pairs of files importing each other, each pair once and sorted
+    pub fn import_cycles(&self) -> Vec<(String, String)> {
+        let targets = self.static_targets();
+        let mut cycles = Vec::new();
+        for (from, tos) in &targets {
+            for to in tos {
+                if from < to && targets.get(to).is_some_and(|back| back.contains(from)) {
+                    cycles.push((from.to_string(), to.to_string()));
+                }
+            }
+        }
+        cycles
+    }
+
+    /// Files `file` imports that import it back
+    pub fn cyclic_imports_of(&self, file: &str) -> Vec<String> {
+        let file = normalize_path(file);
+        self.import_cycles()
+            .into_iter()
+            .filter_map(|(a, b)| {
+                if a == file {
+                    Some(b)
+                } else if b == file {
+                    Some(a)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Files each file imports, leaving out dynamic imports
+    fn static_targets(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut targets: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for edge in self.resolve_edges() {
+            if edge.record.kind == ImportKind::Dynamic {
+                continue;
+            }
+            let from = edge.from;
+            targets
+                .entry(from)
+                .or_default()
+                .extend(edge.targets.into_iter().filter(|t| *t != from));
+        }
+        targets
+    }
+
+    /// Resolve every import statement to the indexed files it refers to
+    fn resolve_edges(&self) -> Vec<Edge<'_>> {
+        let mut edges = Vec::new();
+        for (file, imports) in &self.files {
+            for (statement, record) in imports.imports.iter().enumerate() {
+                let targets = self.resolve(file, &record.source);
+                if !targets.is_empty() {
+                    edges.push(Edge {
+                        from: file,
+                        statement,
+                        record,
+                        targets,
+                    });
+                }
+            }
+        }
+        edges
+    }
+
+    /// Indexed files a relative specifier refers to
+    fn resolve(&self, importer: &str, specifier: &str) -> Vec<&str> {
+        resolve_specifier(&self.files, importer, specifier)
+    }
+}
+
+/// Record the import cycles each summary takes part in
+///
+/// Needs the summaries of every indexed file, since a cycle is only visible
+/// once the imported file's own imports are known. A file in a cycle is at
+/// least medium risk.
+pub fn mark_cyclic_imports(summaries: &mut [SemanticSummary]) {
+    let index = ModuleUsageIndex::build(summaries, &HashMap::new());
+    let mut cyclic: HashMap<String, Vec<String>> = HashMap::new();
+    for (a, b) in index.import_cycles() {
+        cyclic.entry(a.clone()).or_default().push(b.clone());
+        cyclic.entry(b).or_default().push(a);
+    }
+    for summary in summaries {
+        summary.cyclic_imports = cyclic
+            .remove(&normalize_path(&summary.file))
+            .unwrap_or_default();
+        if !summary.cyclic_imports.is_empty() && summary.behavioral_risk == RiskLevel::Low {
+            summary.behavioral_risk = RiskLevel::Medium;
+        }
+    }
+}
+
+/// Files among `files` (keyed by normalized path) a relative JavaScript
+/// specifier imported by `importer` refers to
+pub(crate) fn resolve_specifier<'a, V>(
+    files: &'a BTreeMap<String, V>,
+    importer: &str,
+    specifier: &str,
+) -> Vec<&'a str> {
+    let dir = importer.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+    let joined = normalize_path(&format!("{}/{}", dir, specifier));
+
+    // Dynamic import with a template: every file under the literal prefix
+    if let Some(prefix) = joined.strip_suffix('*') {
+        return files
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .map(|(file, _)| file.as_str())
+            .take_while(|file| file.starts_with(prefix))
+            .filter(|file| *file != importer)
+            .collect();
+    }
+
+    let mut candidates = vec![joined.clone()];
+    // TypeScript ESM imports name the emitted `.js` file
+    if let Some(stem) = joined.strip_suffix(".js") {
+        candidates.push(format!("{}.ts", stem));
+        candidates.push(format!("{}.tsx", stem));
+    }
+    for ext in RESOLVE_EXTENSIONS {
+        candidates.push(format!("{}.{}", joined, ext));
+    }
+    for ext in RESOLVE_EXTENSIONS {
+        candidates.push(format!("{}/index.{}", joined, ext));
+    }
+
+    candidates
+        .iter()
+        .find_map(|candidate| files.get_key_value(candidate.as_str()))
+        .map(|(file, _)| vec![file.as_str()])
+        .unwrap_or_default()
+}
+
+/// An import statement resolved to indexed files
+struct Edge<'a> {
+    from: &'a str,
+    statement: usize,
+    record: &'a ImportRecord,
+    targets: Vec<&'a str>,
+}
+
+/// Names a statement pulls from a file exporting `available` (None = all)
+///
+/// Returns `None` when the statement imports nothing the file exports from
+/// the queried module (e.g. a barrel's unrelated re-exports).
+fn imported_names(
+    record: &ImportRecord,
+    available: Option<&BTreeSet<String>>,
+) -> Option<BTreeSet<String>> {
+    if record.names.is_empty() {
+        return Some(
+            available
+                .cloned()
+                .unwrap_or_else(|| ["*".to_string()].into()),
+        );
+    }
+    let names: BTreeSet<String> = record
+        .names
+        .iter()
+        .map(|n| n.name.clone())
+        .filter(|name| available.is_none_or(|a| a.contains(name)))
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// Names a re-export makes available from the queried module (None = all)
+fn reexported_names(
+    record: &ImportRecord,
+    available: Option<&BTreeSet<String>>,
+) -> Option<BTreeSet<String>> {
+    if record.names.is_empty() {
+        return available.cloned();
+    }
+    Some(
+        record
+            .names
+            .iter()
+            .filter(|n| available.is_none_or(|a| a.contains(&n.name)))
+            .map(|n| n.alias.clone().unwrap_or_else(|| n.name.clone()))
+            .collect(),
+    )
+}
+
+/// Collapse `.` and `..` segments of a `/`-separated path
+pub(crate) fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "." => {}
+            ".." => {
+                if parts.last().is_some_and(|p| !p.is_empty() && *p != "..") {
+                    parts.pop();
+                } else {
+                    parts.push(part);
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(module: &str, imports: Vec<ImportRecord>) -> FileImports {
+        FileImports {
+            module: module.to_string(),
+            entry_point: false,
+            tracks_imports: true,
+            generated: false,
+            imports,
+        }
+    }
+
+    fn import(source: &str, names: &[&str], kind: ImportKind) -> ImportRecord {
+        ImportRecord {
+            source: source.to_string(),
+            names: names
+                .iter()
+                .map(|n| ImportedName {
+                    name: n.to_string(),
+                    alias: None,
+                })
+                .collect(),
+            kind,
+        }
+    }
+
+    fn index(files: Vec<(&str, FileImports)>) -> ModuleUsageIndex {
+        ModuleUsageIndex {
+            schema_version: SCHEMA_VERSION.to_string(),
+            files: files
+                .into_iter()
+                .map(|(path, f)| (path.to_string(), f))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("/r/src/app/../lib/./x"), "/r/src/lib/x");
+        assert_eq!(normalize_path("/r/a/b/../../c"), "/r/c");
+    }
+
+    #[test]
+    fn test_resolve_extensions_and_index() {
+        let idx = index(vec![
+            ("/r/src/a.ts", file("src", vec![])),
+            ("/r/src/lib/index.ts", file("lib", vec![])),
+            ("/r/src/util.ts", file("src", vec![])),
+        ]);
+        assert_eq!(
+            idx.resolve("/r/src/a.ts", "./lib"),
+            vec!["/r/src/lib/index.ts"]
+        );
+        assert_eq!(
+            idx.resolve("/r/src/a.ts", "./util.js"),
+            vec!["/r/src/util.ts"]
+        );
+        assert!(idx.resolve("/r/src/a.ts", "./missing").is_empty());
+    }
+
+    #[test]
+    fn test_reexport_filters_names() {
+        // The barrel re-exports only `charge` from payments; `format` comes
+        // from elsewhere and must not attribute the import to payments.
+        let idx = index(vec![
+            ("/r/src/payments/charge.ts", file("payments", vec![])),
+            (
+                "/r/src/barrel/index.ts",
+                file(
+                    "barrel",
+                    vec![
+                        import("../payments/charge", &["charge"], ImportKind::ReExport),
+                        import("../fmt", &["format"], ImportKind::ReExport),
+                    ],
+                ),
+            ),
+            ("/r/src/fmt.ts", file("src", vec![])),
+            (
+                "/r/src/ui/view.ts",
+                file(
+                    "ui",
+                    vec![import("../barrel", &["format"], ImportKind::Static)],
+                ),
+            ),
+        ]);
+        let usages = idx.usages("payments");
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].module, "barrel");
+    }
+
+    #[test]
+    fn test_import_cycles_skip_dynamic_imports() {
+        let idx = index(vec![
+            (
+                "/r/src/a.ts",
+                file("src", vec![import("./b", &["b"], ImportKind::Static)]),
+            ),
+            (
+                "/r/src/b.ts",
+                file(
+                    "src",
+                    vec![
+                        import("./a", &["a"], ImportKind::Static),
+                        import("./c", &[], ImportKind::Dynamic),
+                    ],
+                ),
+            ),
+            (
+                "/r/src/c.ts",
+                file("src", vec![import("./b", &["b"], ImportKind::Static)]),
+            ),
+        ]);
+        assert_eq!(
+            idx.import_cycles(),
+            vec![("/r/src/a.ts".to_string(), "/r/src/b.ts".to_string())]
+        );
+        assert_eq!(idx.cyclic_imports_of("/r/src/b.ts"), vec!["/r/src/a.ts"]);
+        assert!(idx.cyclic_imports_of("/r/src/c.ts").is_empty());
+    }
+
+    #[test]
+    fn test_unused_dynamic_is_low_confidence() {
+        let idx = index(vec![
+            (
+                "/r/src/app/main.ts",
+                FileImports {
+                    entry_point: true,
+                    ..file("app", vec![import("../pages/*", &[], ImportKind::Dynamic)])
+                },
+            ),
+            ("/r/src/pages/home.ts", file("pages", vec![])),
+            ("/r/src/orphan/x.ts", file("orphan", vec![])),
+            (
+                "/r/src/proto/users_pb.js",
+                FileImports {
+                    generated: true,
+                    ..file("proto", vec![])
+                },
+            ),
+        ]);
+        let unused = idx.unused_modules(false);
+        let modules: Vec<(&str, &str)> = unused
+            .iter()
+            .map(|u| (u.module.as_str(), u.confidence))
+            .collect();
+        assert_eq!(modules, vec![("orphan", "high"), ("pages", "low")]);
+
+        let with_generated = idx.unused_modules(true);
+        assert!(with_generated.iter().any(|u| u.module == "proto"));
+    }
+}
@@ -26,12 +26,12 @@
 //! First match wins. A `Deleted` marker stops the search and returns None.
 
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
-use crate::schema::{fnv1a_hash, SymbolInfo};
+use crate::schema::{fnv1a_hash, Argument, Call, SymbolInfo, SymbolKind};
 
 // ============================================================================
 // Layer Types
@@ -112,6 +112,9 @@ pub enum SymbolState {
         /// Hash of the base content this was derived from (for conflict detection)
         #[serde(default, skip_serializing_if = "Option::is_none")]
         base_content_hash: Option<String>,
+        /// When the symbol entered this layer (Unix timestamp, 0 if unknown)
+        #[serde(default)]
+        created_at: u64,
     },
     /// Symbol was deleted in this layer
     Deleted {
@@ -129,6 +132,7 @@ impl SymbolState {
             symbol,
             file_path: None,
             base_content_hash: None,
+            created_at: unix_now(),
         }
     }
 
@@ -138,6 +142,7 @@ impl SymbolState {
             symbol,
             file_path: Some(file_path),
             base_content_hash: None,
+            created_at: unix_now(),
         }
     }
 
@@ -147,6 +152,7 @@ impl SymbolState {
             symbol,
             file_path: None,
             base_content_hash: Some(base_content_hash),
+            created_at: unix_now(),
         }
     }
 
@@ -160,21 +166,26 @@ impl SymbolState {
             symbol,
             file_path: Some(file_path),
             base_content_hash: Some(base_content_hash),
+            created_at: unix_now(),
         }
     }
 
     /// Create a deleted symbol state
     pub fn deleted(original_hash: String) -> Self {
-        let deleted_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
         Self::Deleted {
             original_hash,
-            deleted_at,
+            deleted_at: unix_now(),
         }
     }
 
+    /// Set when an active symbol entered its layer
+    pub fn with_created_at(mut self, timestamp: u64) -> Self {
+        if let Self::Active { created_at, .. } = &mut self {
+            *created_at = timestamp;
+        }
+        self
+    }
+
     /// Check if this is an active state
     pub fn is_active(&self) -> bool {
         matches!(self, Self::Active { .. })
@@ -210,6 +221,22 @@ impl SymbolState {
             Self::Deleted { .. } => None,
         }
     }
+
+    /// When an active symbol entered its layer (Unix timestamp)
+    pub fn created_at(&self) -> Option<u64> {
+        match self {
+            Self::Active { created_at, .. } => Some(*created_at),
+            Self::Deleted { .. } => None,
+        }
+    }
+}
+
+/// Current Unix timestamp in seconds
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 // ============================================================================
@@ -492,6 +519,26 @@ impl Overlay {
             .unwrap_or_default()
     }
 
+    /// Remove a symbol or tombstone from this layer entirely
+    ///
+    /// Unlike `delete`, no tombstone is left behind, so lower layers become
+    /// visible again for this hash.
+    pub fn remove(&mut self, hash: &str) -> Option<SymbolState> {
+        let state = self.symbols.remove(hash)?;
+        if let Some(path) = state.file_path() {
+            if let Some(hashes) = self.symbols_by_file.get_mut(path) {
+                hashes.retain(|h| h != hash);
+                if hashes.is_empty() {
+                    self.symbols_by_file.remove(path);
+                }
+            }
+        }
+        self.deleted.remove(hash);
+        self.meta.touch();
+        self.update_counts();
+        Some(state)
+    }
+
     /// Update metadata counts
     fn update_counts(&mut self) {
         self.meta.symbol_count = self.active_count();
@@ -526,23 +573,8 @@ impl Overlay {
 /// - file_hash (8 chars): Hash of file path for uniqueness
 /// - semantic_hash (16 chars): Hash of namespace:name:kind:arity for move detection
 pub fn compute_symbol_hash(symbol: &SymbolInfo, file_path: &str) -> String {
-    // Semantic hash (for move detection and duplicate finding)
     let namespace = crate::schema::SymbolId::namespace_from_path(file_path);
-    let semantic_input = format!(
-        "{}:{}:{}:{}",
-        namespace,
-        symbol.name,
-        symbol.kind.as_str(),
-        symbol.arguments.len() + symbol.props.len()
-    );
-    let semantic_hash = format!("{:016x}", fnv1a_hash(&semantic_input));
-
-    // File hash (for uniqueness across different files)
-    // Truncate to 32 bits (8 hex chars) for compactness
-    let file_hash = format!("{:08x}", fnv1a_hash(file_path) as u32);
-
-    // Combined hash: file_hash:semantic_hash
-    format!("{}:{}", file_hash, semantic_hash)
+    symbol.to_symbol_id(&namespace, file_path).hash
 }
 
 /// Extract the semantic hash from a full two-part hash
@@ -681,6 +713,30 @@ impl LayeredIndex {
         result
     }
 
+    /// Hashes of the active symbols in `path` as seen from layer `top`
+    ///
+    /// Considers `top` and every layer below it; a symbol is visible unless a
+    /// layer between its own and `top` overrides or deletes it.
+    pub fn visible_file_hashes(&self, path: &PathBuf, top: LayerKind) -> HashSet<String> {
+        let mut result = HashSet::new();
+        for kind in LayerKind::all_descending() {
+            if kind > top {
+                continue;
+            }
+            let layer = self.layer(kind);
+            for hash in layer.symbols_by_file.get(path).into_iter().flatten() {
+                let shadowed = LayerKind::all_descending()
+                    .into_iter()
+                    .filter(|k| *k > kind && *k <= top)
+                    .any(|k| self.layer(k).get(hash).is_some());
+                if !shadowed && layer.get(hash).is_some_and(|s| s.is_active()) {
+                    result.insert(hash.clone());
+                }
+            }
+        }
+        result
+    }
+
     /// Resolve a file path through all move histories
     pub fn resolve_path(&self, path: &PathBuf) -> PathBuf {
         let mut current = path.clone();
@@ -718,7 +774,7 @@ impl LayeredIndex {
 }
 
 /// Statistics about a layered index
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 pub struct LayeredIndexStats {
     /// Number of active symbols in base layer
     pub base_symbols: usize,
@@ -734,6 +790,199 @@ pub struct LayeredIndexStats {
     pub total_moves: usize,
 }
 
+// ============================================================================
+// AI Layer Hygiene
+// ============================================================================
+
+/// Active AI layer symbols above which index refreshes prune stale ones
+pub const AI_PRUNE_THRESHOLD: usize = 1000;
+
+/// Age after which an AI layer symbol whose file was never written is stale
+pub const AI_ENTRY_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// Whether an AI layer entry for `file`, created at `created_at`, is stale at
+/// `now`: older than `max_age_secs`, and its file isn't in the working tree
+///
+/// An entry without a file is never stale, since there is nothing to check.
+pub fn is_stale_ai_entry(
+    created_at: u64,
+    file: Option<&Path>,
+    max_age_secs: u64,
+    now: u64,
+) -> bool {
+    now.saturating_sub(created_at) > max_age_secs && file.is_some_and(|f| !f.exists())
+}
+
+impl LayeredIndex {
+    /// Remove AI layer symbols older than `max_age_secs` whose file no longer
+    /// exists in the working tree
+    ///
+    /// Generated code an assistant proposed but never wrote (or later
+    /// replaced) would otherwise shadow the real symbols forever. Tombstones
+    /// are kept. Returns the number of symbols removed.
+    pub fn prune_stale_ai_entries(&mut self, max_age_secs: u64) -> usize {
+        let now = unix_now();
+        let stale: Vec<String> = self
+            .ai
+            .symbols
+            .iter()
+            .filter(|(_, state)| {
+                state.created_at().is_some_and(|created_at| {
+                    is_stale_ai_entry(
+                        created_at,
+                        state.file_path().map(PathBuf::as_path),
+                        max_age_secs,
+                        now,
+                    )
+                })
+            })
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        for hash in &stale {
+            self.ai.remove(hash);
+        }
+        stale.len()
+    }
+}
+
+// ============================================================================
+// Compaction
+// ============================================================================
+
+/// Result of folding upper layers into a lower one
+#[derive(Debug, Clone, Serialize)]
+pub struct CompactionStats {
+    /// Layer the upper layers were folded into
+    pub target: LayerKind,
+    /// Index statistics before compaction
+    pub before: LayeredIndexStats,
+    /// Index statistics after compaction
+    pub after: LayeredIndexStats,
+    /// Active symbol states moved into the target layer
+    pub symbols_merged: usize,
+    /// Tombstones removed because nothing below them is active any more
+    pub tombstones_dropped: usize,
+    /// File moves discarded because the base layer already reflects them
+    pub moves_folded: usize,
+    /// Files whose symbols changed in the target layer
+    pub affected_files: Vec<PathBuf>,
+}
+
+impl CompactionStats {
+    /// Whether compaction changed anything
+    pub fn is_noop(&self) -> bool {
+        self.symbols_merged == 0
+            && self.tombstones_dropped == 0
+            && self.moves_folded == 0
+            && self.before == self.after
+    }
+}
+
+impl LayeredIndex {
+    /// Fold the layers between `target` and the AI layer into `target`
+    ///
+    /// Call this once the working tree is clean and HEAD contains every
+    /// change recorded in those layers: `Branch` folds the working layer into
+    /// the branch layer, `Base` folds both into the base layer. Active states
+    /// overwrite the target's, deletions remove the symbol from the target,
+    /// and a tombstone survives only while a lower layer still has the symbol
+    /// active. Afterwards tombstones that no longer shadow anything are
+    /// dropped from every persistent layer. Folding into `Base` also discards
+    /// move history, since base symbols are keyed by their current paths.
+    ///
+    /// Layer metadata (indexed SHAs) is left alone; the AI layer is never
+    /// touched. Any other target is a no-op.
+    pub fn compact(&mut self, target: LayerKind) -> CompactionStats {
+        let before = self.stats();
+        let mut stats = CompactionStats {
+            target,
+            after: before.clone(),
+            before,
+            symbols_merged: 0,
+            tombstones_dropped: 0,
+            moves_folded: 0,
+            affected_files: Vec::new(),
+        };
+        if !matches!(target, LayerKind::Base | LayerKind::Branch) {
+            return stats;
+        }
+
+        let mut affected = std::collections::BTreeSet::new();
+        // Branch first so working-layer states win
+        for source_kind in [LayerKind::Branch, LayerKind::Working] {
+            if source_kind <= target {
+                continue;
+            }
+            let meta = self.layer(source_kind).meta.clone();
+            let source = std::mem::replace(self.layer_mut(source_kind), Overlay::new(source_kind));
+            let emptied = self.layer_mut(source_kind);
+            emptied.meta = meta;
+            emptied.meta.touch();
+            emptied.update_counts();
+
+            for (hash, state) in source.symbols {
+                affected.extend(state.file_path().cloned());
+                if let Some(old) = self.layer(target).get(&hash) {
+                    affected.extend(old.file_path().cloned());
+                }
+
+                if state.is_deleted() {
+                    self.layer_mut(target).remove(&hash);
+                    if self.active_below(target, &hash) {
+                        self.layer_mut(target).delete(&hash);
+                    } else {
+                        stats.tombstones_dropped += 1;
+                    }
+                } else {
+                    self.layer_mut(target).upsert(hash, state);
+                    stats.symbols_merged += 1;
+                }
+            }
+
+            if target == LayerKind::Base {
+                stats.moves_folded += source.moves.len();
+            } else {
+                let layer = self.layer_mut(target);
+                layer.moves.extend(source.moves);
+                layer.update_counts();
+            }
+        }
+
+        for kind in [LayerKind::Base, LayerKind::Branch, LayerKind::Working] {
+            let stale: Vec<String> = self
+                .layer(kind)
+                .deleted
+                .iter()
+                .filter(|hash| !self.active_below(kind, hash))
+                .cloned()
+                .collect();
+            for hash in stale {
+                self.layer_mut(kind).remove(&hash);
+                stats.tombstones_dropped += 1;
+            }
+        }
+
+        if target == LayerKind::Base && !self.base.moves.is_empty() {
+            stats.moves_folded += self.base.moves.len();
+            self.base.moves.clear();
+            self.base.update_counts();
+        }
+
+        stats.after = self.stats();
+        stats.affected_files = affected.into_iter().collect();
+        stats
+    }
+
+    /// Whether the nearest layer below `kind` that knows `hash` has it active
+    fn active_below(&self, kind: LayerKind, hash: &str) -> bool {
+        LayerKind::all_descending()
+            .into_iter()
+            .filter(|k| *k < kind)
+            .find_map(|k| self.layer(k).get(hash))
+            .is_some_and(|state| state.is_active())
+    }
+}
+
 // ============================================================================
 // Layered Query Resolution (SEM-53)
 // ============================================================================
@@ -1102,6 +1351,113 @@ impl LayeredIndex {
     }
 }
 
+// ============================================================================
+// External Symbol Patches
+// ============================================================================
+
+/// Enrichment for an indexed symbol from an external source (LSP, IDE)
+///
+/// Tree-sitter extraction can't resolve types or dynamic calls. Patches
+/// let an editor integration add what its language server knows, without
+/// regenerating the index from source.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SymbolPatch {
+    /// Hash of the symbol to patch
+    pub symbol_hash: String,
+
+    /// Corrected symbol kind (e.g. "method", "class", "typealias")
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub kind: Option<SymbolKind>,
+
+    /// Calls to add, as `name` or `object.name`
+    #[serde(default)]
+    pub extra_calls: Vec<String>,
+
+    /// Parameters to add or annotate, as `name` or `name: Type`
+    #[serde(default)]
+    pub extra_params: Vec<String>,
+
+    /// Documentation comment for the symbol
+    #[serde(default)]
+    pub doc_comment: Option<String>,
+}
+
+impl SymbolPatch {
+    /// Apply this patch to a symbol
+    ///
+    /// Calls already present are skipped. A parameter that already exists
+    /// takes the patch's type annotation; new parameters are appended.
+    pub fn apply(&self, symbol: &mut SymbolInfo) {
+        if let Some(kind) = self.kind {
+            symbol.kind = kind;
+        }
+
+        for call in &self.extra_calls {
+            let (object, name) = match call.rsplit_once('.') {
+                Some((object, name)) => (Some(object.to_string()), name.to_string()),
+                None => (None, call.clone()),
+            };
+            if !symbol
+                .calls
+                .iter()
+                .any(|c| c.name == name && c.object == object)
+            {
+                symbol.calls.push(Call {
+                    name,
+                    object,
+                    ..Default::default()
+                });
+            }
+        }
+
+        for param in &self.extra_params {
+            let (name, arg_type) = match param.split_once(':') {
+                Some((name, ty)) => (name.trim(), Some(ty.trim().to_string())),
+                None => (param.trim(), None),
+            };
+            match symbol.arguments.iter_mut().find(|a| a.name == name) {
+                Some(existing) => {
+                    if arg_type.is_some() {
+                        existing.arg_type = arg_type;
+                    }
+                }
+                None => symbol.arguments.push(Argument {
+                    name: name.to_string(),
+                    arg_type,
+                    default_value: None,
+                }),
+            }
+        }
+
+        if let Some(doc) = &self.doc_comment {
+            symbol.doc_comment = Some(doc.clone());
+        }
+    }
+}
+
+impl LayeredIndex {
+    /// Apply a patch to the working layer
+    ///
+    /// The symbol is resolved across all layers, patched, and written to the
+    /// working layer at its original file path. Returns the layer the symbol
+    /// was resolved from, or None if it isn't in the index.
+    pub fn apply_patch(&mut self, patch: &SymbolPatch) -> Option<LayerKind> {
+        let resolved = self.resolve_symbol_with_layer(&patch.symbol_hash)?;
+        let mut symbol = resolved.symbol;
+        patch.apply(&mut symbol);
+
+        let state = match resolved.file_path {
+            Some(path) => SymbolState::active_at(symbol, path),
+            None => SymbolState::active(symbol),
+        };
+        self.working.upsert(patch.symbol_hash.clone(), state);
+        self.working.meta.touch();
+
+        Some(resolved.layer)
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1109,7 +1465,7 @@ impl LayeredIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::{Argument, RiskLevel, SymbolKind};
+    use crate::schema::RiskLevel;
 
     // ------------------------------------------------------------------------
     // LayerKind Tests
@@ -1381,6 +1737,79 @@ mod tests {
         assert_eq!(resolved.unwrap().name, "base_fn");
     }
 
+    #[test]
+    fn test_symbol_patch_apply() {
+        let mut symbol = make_test_symbol("handler");
+        symbol.arguments.push(Argument {
+            name: "req".to_string(),
+            arg_type: None,
+            default_value: None,
+        });
+        symbol.calls.push(Call {
+            name: "send".to_string(),
+            object: Some("res".to_string()),
+            ..Default::default()
+        });
+
+        let patch = SymbolPatch {
+            symbol_hash: "h".to_string(),
+            kind: Some(SymbolKind::Method),
+            extra_calls: vec!["res.send".to_string(), "db.query".to_string()],
+            extra_params: vec!["req: Request".to_string(), "next".to_string()],
+            doc_comment: Some("Handles requests".to_string()),
+        };
+        patch.apply(&mut symbol);
+
+        assert_eq!(symbol.kind, SymbolKind::Method);
+        assert_eq!(symbol.calls.len(), 2);
+        assert_eq!(symbol.calls[1].name, "query");
+        assert_eq!(symbol.calls[1].object.as_deref(), Some("db"));
+        assert_eq!(symbol.arguments.len(), 2);
+        assert_eq!(symbol.arguments[0].arg_type.as_deref(), Some("Request"));
+        assert_eq!(symbol.arguments[1].name, "next");
+        assert_eq!(symbol.doc_comment.as_deref(), Some("Handles requests"));
+    }
+
+    #[test]
+    fn test_layered_index_apply_patch_writes_working_layer() {
+        let mut index = LayeredIndex::new();
+        let hash = "hash_base".to_string();
+        index.base.upsert(
+            hash.clone(),
+            SymbolState::active_at(make_test_symbol("base_fn"), PathBuf::from("src/a.ts")),
+        );
+
+        let patch = SymbolPatch {
+            symbol_hash: hash.clone(),
+            doc_comment: Some("docs".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(index.apply_patch(&patch), Some(LayerKind::Base));
+
+        // Base is untouched; the patched copy shadows it from the working layer
+        assert!(index
+            .base
+            .get(&hash)
+            .unwrap()
+            .as_symbol()
+            .unwrap()
+            .doc_comment
+            .is_none());
+        let working = index.working.get(&hash).unwrap();
+        assert_eq!(working.file_path(), Some(&PathBuf::from("src/a.ts")));
+        assert_eq!(
+            index.resolve_symbol(&hash).unwrap().doc_comment.as_deref(),
+            Some("docs")
+        );
+
+        let missing = SymbolPatch {
+            symbol_hash: "unknown".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(index.apply_patch(&missing), None);
+        assert!(index.working.get("unknown").is_none());
+    }
+
     #[test]
     fn test_layered_index_ai_shadows_base() {
         let mut index = LayeredIndex::new();
@@ -1525,6 +1954,37 @@ mod tests {
         assert!(index.working.is_empty());
     }
 
+    #[test]
+    fn test_prune_stale_ai_entries() {
+        let mut index = LayeredIndex::new();
+        let written = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let unwritten = PathBuf::from("/nonexistent/generated.ts");
+        let stage = |index: &mut LayeredIndex, hash: &str, path: &PathBuf, created_at| {
+            index.ai.upsert(
+                hash.to_string(),
+                SymbolState::active_at(make_test_symbol(hash), path.clone())
+                    .with_created_at(created_at),
+            );
+        };
+        stage(&mut index, "old_unwritten", &unwritten, 0);
+        stage(&mut index, "old_written", &written, 0);
+        stage(&mut index, "new_unwritten", &unwritten, unix_now());
+        index.ai.delete("tombstone");
+        index.base.upsert(
+            "old_unwritten".to_string(),
+            SymbolState::active(make_test_symbol("base")),
+        );
+
+        assert_eq!(index.prune_stale_ai_entries(3600), 1);
+        assert!(index.ai.get("old_unwritten").is_none());
+        assert!(index.ai.get("old_written").is_some());
+        assert!(index.ai.get("new_unwritten").is_some());
+        assert!(index.ai.is_deleted("tombstone"));
+        // The base symbol is no longer shadowed
+        assert_eq!(index.resolve_symbol("old_unwritten").unwrap().name, "base");
+        assert_eq!(index.ai.get_file_symbols(&unwritten).len(), 1);
+    }
+
     #[test]
     fn test_layered_index_stats() {
         let mut index = LayeredIndex::new();
@@ -2843,4 +3303,133 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].symbol.name, "validateUser");
     }
+
+    fn file_state(name: &str, file: &str) -> SymbolState {
+        SymbolState::active_at(make_test_symbol(name), PathBuf::from(file))
+    }
+
+    /// Base layer as a fresh full index of the given (hash, name, file) symbols
+    fn fresh_index(symbols: &[(&str, &str, &str)]) -> LayeredIndex {
+        let mut index = LayeredIndex::new();
+        for (hash, name, file) in symbols {
+            index.base.upsert(hash.to_string(), file_state(name, file));
+        }
+        index
+    }
+
+    #[test]
+    fn test_compact_into_base_matches_fresh_index() {
+        let mut index = fresh_index(&[
+            ("h_parse", "parse", "a.rs"),
+            ("h_render", "render", "a.rs"),
+            ("h_load", "load", "b.rs"),
+        ]);
+
+        // An earlier commit on the branch added a helper and moved a file
+        index
+            .branch
+            .upsert("h_helper".to_string(), file_state("helper", "a.rs"));
+        index
+            .branch
+            .record_move(PathBuf::from("old.rs"), PathBuf::from("b.rs"));
+
+        // Edits: render changes, load is deleted, save is added
+        index.working.delete("h_render");
+        index
+            .working
+            .upsert("h_render2".to_string(), file_state("render", "a.rs"));
+        index.working.delete("h_load");
+        index
+            .working
+            .upsert("h_save".to_string(), file_state("save", "b.rs"));
+
+        // ...then committed, so HEAD is what a fresh index would see
+        let stats = index.compact(LayerKind::Base);
+        let fresh = fresh_index(&[
+            ("h_parse", "parse", "a.rs"),
+            ("h_render2", "render", "a.rs"),
+            ("h_helper", "helper", "a.rs"),
+            ("h_save", "save", "b.rs"),
+        ]);
+
+        assert_eq!(index.base.symbols, fresh.base.symbols);
+        assert!(index.branch.is_empty());
+        assert!(index.working.is_empty());
+        assert_eq!(index.stats(), fresh.stats());
+        assert_eq!(index.all_symbol_hashes(), fresh.all_symbol_hashes());
+        assert_eq!(
+            index.base.get_file_symbols(&PathBuf::from("b.rs")).len(),
+            fresh.base.get_file_symbols(&PathBuf::from("b.rs")).len()
+        );
+
+        assert_eq!(stats.symbols_merged, 3);
+        assert_eq!(stats.tombstones_dropped, 2);
+        assert_eq!(stats.moves_folded, 1);
+        assert_eq!(stats.before.total_deleted, 2);
+        assert_eq!(stats.after, fresh.stats());
+        assert_eq!(
+            stats.affected_files,
+            vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_compact_into_branch_keeps_base() {
+        let mut index = fresh_index(&[("h_parse", "parse", "a.rs"), ("h_load", "load", "b.rs")]);
+        index
+            .branch
+            .upsert("h_helper".to_string(), file_state("helper", "a.rs"));
+        index.working.delete("h_load");
+        index.working.delete("h_helper");
+        index
+            .working
+            .upsert("h_save".to_string(), file_state("save", "b.rs"));
+        index.ai.delete("h_parse");
+
+        let visible = index.all_symbol_hashes();
+        let stats = index.compact(LayerKind::Branch);
+
+        // Base is untouched; its deleted symbol stays shadowed by a tombstone
+        assert_eq!(index.base.active_count(), 2);
+        assert!(index.branch.is_deleted("h_load"));
+        assert!(index.branch.get("h_helper").is_none());
+        assert!(index.working.is_empty());
+        assert!(index.ai.is_deleted("h_parse"));
+        assert_eq!(index.all_symbol_hashes(), visible);
+        assert_eq!(stats.symbols_merged, 1);
+        assert_eq!(stats.tombstones_dropped, 1);
+
+        // Nothing left to fold
+        assert!(index.compact(LayerKind::Branch).is_noop());
+        assert!(index.compact(LayerKind::Working).is_noop());
+    }
+
+    #[test]
+    fn test_compact_drops_orphaned_tombstones() {
+        let mut index = fresh_index(&[("h_parse", "parse", "a.rs")]);
+        // Deletions already reflected below: nothing to shadow
+        index.base.delete("h_gone");
+        index.branch.delete("h_never_existed");
+        index.branch.delete("h_parse");
+
+        let stats = index.compact(LayerKind::Branch);
+        assert_eq!(stats.tombstones_dropped, 2);
+        assert_eq!(index.stats().total_deleted, 1);
+        assert!(index.branch.is_deleted("h_parse"));
+    }
+
+    #[test]
+    fn test_overlay_remove() {
+        let mut overlay = Overlay::new(LayerKind::Working);
+        overlay.upsert("h1".to_string(), file_state("parse", "a.rs"));
+        overlay.delete("h2");
+
+        assert!(overlay.remove("h1").is_some());
+        assert!(overlay.remove("h2").is_some());
+        assert!(overlay.remove("h3").is_none());
+        assert!(overlay.is_empty());
+        assert!(overlay.symbols_by_file.is_empty());
+        assert_eq!(overlay.meta.symbol_count, 0);
+        assert_eq!(overlay.meta.deleted_count, 0);
+    }
 }
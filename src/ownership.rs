@@ -0,0 +1,619 @@
+//! Code ownership: who owns a file and who wrote a symbol
+//!
+//! Two sources answer "who owns this function":
+//!
+//! - **CODEOWNERS** (`.github/`, the repository root, `docs/` or `.gitlab/`,
+//!   first found wins): path patterns mapped to owners, in GitHub or GitLab
+//!   syntax. The last matching rule wins; a matching rule without owners, or
+//!   a `!` negation, leaves the file unowned. GitLab `[Section]` headers are
+//!   matched independently and their owners combined, with a section's
+//!   default owners standing in for rules that list none.
+//! - **git blame** (`index generate --with-blame`): the author of most of a
+//!   symbol's committed lines, from one `git blame --line-porcelain` per file.
+//!   Results are cached by file content hash in `blame_cache.json`, which
+//!   survives [`CacheDir::clear`]; files with uncommitted lines aren't cached.
+//!
+//! Ownership is built at index time (`graphs/ownership.json`). Paths are
+//! matched relative to the indexed directory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::verify::{corrupt, read_artifact};
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::error::{McpDiffError, Result};
+use crate::git::{get_line_authors, is_git_repo};
+use crate::module_usage::normalize_path;
+use crate::schema::{fnv1a_hash, SCHEMA_VERSION};
+use crate::test_selection::parse_lines;
+
+/// Where CODEOWNERS is looked for, in order
+pub const CODEOWNERS_LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// Author git blame reports for lines that aren't committed yet
+const UNCOMMITTED_AUTHOR: &str = "Not Committed Yet";
+
+/// Parsed CODEOWNERS rules
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<OwnerRule>,
+}
+
+#[derive(Debug, Clone)]
+struct OwnerRule {
+    /// Lowercased GitLab section name (`None` before the first section)
+    section: Option<String>,
+    matcher: Regex,
+    owners: Vec<String>,
+    negated: bool,
+}
+
+impl CodeOwners {
+    /// Load the repository's CODEOWNERS, with its path relative to `repo_root`
+    pub fn find(repo_root: &Path) -> Option<(String, Self)> {
+        CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let content = fs::read_to_string(repo_root.join(location)).ok()?;
+            Some((location.to_string(), Self::parse(&content)))
+        })
+    }
+
+    /// Parse CODEOWNERS content, skipping lines that aren't valid rules
+    pub fn parse(content: &str) -> Self {
+        let mut rules = Vec::new();
+        let mut section: Option<String> = None;
+        let mut section_owners: Vec<String> = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((name, owners)) = parse_section_header(line) {
+                section = Some(name.to_lowercase());
+                section_owners = owners;
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else {
+                continue;
+            };
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+            let mut owners = owner_tokens(tokens);
+            if owners.is_empty() && !negated {
+                owners = section_owners.clone();
+            }
+            if let Some(matcher) = pattern_regex(pattern) {
+                rules.push(OwnerRule {
+                    section: section.clone(),
+                    matcher,
+                    owners,
+                    negated,
+                });
+            }
+        }
+
+        Self { rules }
+    }
+
+    /// Whether the file has no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Owners of a path relative to the repository root (empty when unowned)
+    pub fn owners_of(&self, path: &str) -> Vec<String> {
+        let path = normalize_path(path.trim_start_matches("./"));
+        let path = path.trim_start_matches('/');
+
+        // Last matching rule of each section, sections in file order
+        let mut last: Vec<&OwnerRule> = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.matcher.is_match(path)) {
+            match last.iter_mut().find(|r| r.section == rule.section) {
+                Some(slot) => *slot = rule,
+                None => last.push(rule),
+            }
+        }
+
+        let mut owners: Vec<String> = Vec::new();
+        for rule in last.into_iter().filter(|r| !r.negated) {
+            for owner in &rule.owners {
+                if !owners.contains(owner) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+        owners
+    }
+}
+
+/// `[Section]`, `^[Optional]` or `[Section][2]`, with optional default owners
+fn parse_section_header(line: &str) -> Option<(&str, Vec<String>)> {
+    let rest = line.strip_prefix('^').unwrap_or(line).strip_prefix('[')?;
+    let (name, rest) = rest.split_once(']')?;
+    let rest = match rest.strip_prefix('[') {
+        Some(approvals) => approvals.split_once(']')?.1,
+        None => rest,
+    };
+    Some((name.trim(), owner_tokens(rest.split_whitespace())))
+}
+
+/// Owners up to a trailing `# comment`
+fn owner_tokens<'a>(tokens: impl Iterator<Item = &'a str>) -> Vec<String> {
+    tokens
+        .take_while(|t| !t.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Regex for a gitignore-style CODEOWNERS pattern
+///
+/// `*` and `?` stay within a path segment, `**` crosses segments. Patterns
+/// with a leading or inner `/` are anchored at the root, others match at any
+/// depth. A match on a directory covers everything below it, except that a
+/// trailing `/*` only covers the directory's direct children.
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let body = pattern.trim_start_matches('/').trim_end_matches('/');
+    if body.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let chars: Vec<char> = body.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                regex.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex.push_str(if body.ends_with("/*") {
+        "$"
+    } else {
+        "(?:/.*)?$"
+    });
+    Regex::new(&regex).ok()
+}
+
+/// The author of most of a symbol's committed lines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolAuthor {
+    pub author: String,
+    /// Lines last changed by `author`
+    pub lines: usize,
+    /// Committed lines in the symbol
+    pub total: usize,
+}
+
+impl SymbolAuthor {
+    /// `author (lines/total lines)`
+    pub fn describe(&self) -> String {
+        format!("{} ({}/{} lines)", self.author, self.lines, self.total)
+    }
+}
+
+/// Persisted ownership
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnershipIndex {
+    pub schema_version: String,
+    /// CODEOWNERS file the owners came from, relative to the repository root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codeowners: Option<String>,
+    /// Indexed file -> its owners; unowned files are left out
+    pub files: BTreeMap<String, Vec<String>>,
+    /// Whether blame authors were computed (`index generate --with-blame`)
+    #[serde(default)]
+    pub blame: bool,
+    /// Symbol hash -> top blame author
+    #[serde(default)]
+    pub authors: BTreeMap<String, SymbolAuthor>,
+}
+
+impl OwnershipIndex {
+    /// Attribute the indexed files, and with a blame cache their symbols
+    ///
+    /// `blame_cache` is consulted and updated in place; pass `None` to skip
+    /// blame. Blame is also skipped outside a git repository.
+    pub fn build(
+        entries: &[SymbolIndexEntry],
+        repo_root: &Path,
+        blame_cache: Option<&mut BlameCache>,
+    ) -> Self {
+        let mut index = Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            ..Default::default()
+        };
+
+        if let Some((location, codeowners)) = CodeOwners::find(repo_root) {
+            index.codeowners = Some(location);
+            for entry in entries {
+                if index.files.contains_key(&entry.file) {
+                    continue;
+                }
+                let owners = codeowners.owners_of(&relative_path(&entry.file, repo_root));
+                if !owners.is_empty() {
+                    index.files.insert(entry.file.clone(), owners);
+                }
+            }
+        }
+
+        if let Some(blame_cache) = blame_cache.filter(|_| is_git_repo(Some(repo_root))) {
+            index.blame = true;
+            index.authors = blame_authors(entries, repo_root, blame_cache);
+        }
+
+        index
+    }
+
+    /// Owners of an indexed file (empty when unowned)
+    ///
+    /// `file` is the index path, or the same file relative to or below it
+    /// (display paths may differ from index paths).
+    pub fn owners_of(&self, file: &str) -> &[String] {
+        if let Some(owners) = self.files.get(file) {
+            return owners;
+        }
+        let file = file.trim_start_matches("./");
+        self.files
+            .iter()
+            .find(|(indexed, _)| {
+                let indexed = indexed.trim_start_matches("./");
+                indexed == file
+                    || indexed.ends_with(&format!("/{}", file))
+                    || file.ends_with(&format!("/{}", indexed))
+            })
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+
+    /// Load the persisted ownership
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        let path = cache.ownership_path();
+        if !path.exists() {
+            return Err(McpDiffError::FileNotFound {
+                path: "Ownership not found. Run `semfora index generate --force` first."
+                    .to_string(),
+            });
+        }
+        let content = read_artifact(&path)?;
+        serde_json::from_str(&content).map_err(|e| corrupt(&path, e))
+    }
+
+    /// Persist the ownership, returning the bytes written
+    pub fn write(&self, cache: &CacheDir) -> Result<usize> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize ownership: {}", e),
+        })?;
+        let path = cache.ownership_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+        Ok(content.len())
+    }
+}
+
+/// Blamed line authors by file, valid while the file content is unchanged
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlameCache {
+    pub files: BTreeMap<String, CachedBlame>,
+}
+
+/// Line authors of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedBlame {
+    /// Hash of the file content the blame was computed for
+    pub content_hash: String,
+    /// Line authors in order, run-length encoded as `(author, lines)`
+    pub runs: Vec<(String, usize)>,
+}
+
+impl BlameCache {
+    /// Load the cache (empty when missing or unreadable)
+    pub fn load(cache: &CacheDir) -> Self {
+        fs::read_to_string(cache.blame_cache_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the cache
+    pub fn write(&self, cache: &CacheDir) -> Result<()> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize blame cache: {}", e),
+        })?;
+        fs::create_dir_all(&cache.root)?;
+        fs::write(cache.blame_cache_path(), content)?;
+        Ok(())
+    }
+}
+
+/// Top author of each symbol, blaming each file once
+fn blame_authors(
+    entries: &[SymbolIndexEntry],
+    repo_root: &Path,
+    blame_cache: &mut BlameCache,
+) -> BTreeMap<String, SymbolAuthor> {
+    let mut by_file: BTreeMap<&str, Vec<&SymbolIndexEntry>> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| !e.is_escape_local) {
+        by_file.entry(&entry.file).or_default().push(entry);
+    }
+
+    // Files whose content changed since they were last blamed
+    let hashes: HashMap<&str, String> = by_file
+        .keys()
+        .filter_map(|file| {
+            let content =
+                fs::read_to_string(repo_root.join(relative_path(file, repo_root))).ok()?;
+            Some((*file, format!("{:016x}", fnv1a_hash(&content))))
+        })
+        .collect();
+    let stale: Vec<&str> = hashes
+        .iter()
+        .filter(|(file, hash)| {
+            blame_cache
+                .files
+                .get(**file)
+                .map_or(true, |cached| cached.content_hash.as_str() != hash.as_str())
+        })
+        .map(|(file, _)| *file)
+        .collect();
+
+    let blamed: Vec<(&str, Option<Vec<String>>)> = stale
+        .par_iter()
+        .map(|file| {
+            let authors = get_line_authors(&relative_path(file, repo_root), Some(repo_root));
+            (*file, authors)
+        })
+        .collect();
+
+    let mut fresh: HashMap<&str, Vec<String>> = HashMap::new();
+    for (file, authors) in blamed {
+        let Some(authors) = authors else {
+            blame_cache.files.remove(file);
+            continue;
+        };
+        if authors.iter().all(|a| a != UNCOMMITTED_AUTHOR) {
+            blame_cache.files.insert(
+                file.to_string(),
+                CachedBlame {
+                    content_hash: hashes[&file].clone(),
+                    runs: encode_runs(&authors),
+                },
+            );
+        } else {
+            blame_cache.files.remove(file);
+        }
+        fresh.insert(file, authors);
+    }
+
+    let mut authors = BTreeMap::new();
+    for (file, symbols) in by_file {
+        let line_authors = match fresh.remove(file) {
+            Some(line_authors) => line_authors,
+            None if hashes.contains_key(file) => match blame_cache.files.get(file) {
+                Some(cached) => decode_runs(&cached.runs),
+                None => continue,
+            },
+            None => continue,
+        };
+        for entry in symbols {
+            if let Some(author) = top_author(&line_authors, &entry.lines) {
+                authors.insert(entry.hash.clone(), author);
+            }
+        }
+    }
+    authors
+}
+
+/// The author of most committed lines in a `start-end` range, ties going to
+/// the alphabetically first
+fn top_author(line_authors: &[String], lines: &str) -> Option<SymbolAuthor> {
+    let (start, end) = parse_lines(lines);
+    let range = line_authors.get(start.max(1) - 1..end.min(line_authors.len()))?;
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for author in range.iter().filter(|a| *a != UNCOMMITTED_AUTHOR) {
+        *counts.entry(author).or_insert(0) += 1;
+    }
+    let total = counts.values().sum();
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(author, lines)| SymbolAuthor {
+            author: author.to_string(),
+            lines,
+            total,
+        })
+}
+
+fn encode_runs(authors: &[String]) -> Vec<(String, usize)> {
+    let mut runs: Vec<(String, usize)> = Vec::new();
+    for author in authors {
+        match runs.last_mut() {
+            Some((last, count)) if last == author => *count += 1,
+            _ => runs.push((author.clone(), 1)),
+        }
+    }
+    runs
+}
+
+fn decode_runs(runs: &[(String, usize)]) -> Vec<String> {
+    runs.iter()
+        .flat_map(|(author, count)| std::iter::repeat_n(author.clone(), *count))
+        .collect()
+}
+
+/// Index path relative to `repo_root`, `/`-separated
+fn relative_path(file: &str, repo_root: &Path) -> String {
+    let path = Path::new(file);
+    let relative = path.strip_prefix(repo_root).unwrap_or(path);
+    normalize_path(relative.to_string_lossy().trim_start_matches("./"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*                       @org/core
+*.md                    @org/docs
+/src/                   @org/backend
+/src/payments/          @org/payments @alice
+/src/payments/legacy/
+docs/*                  @org/docs-root
+assets/**/icons         @org/design
+!/src/generated/
+";
+
+    fn owners(codeowners: &CodeOwners, path: &str) -> Vec<String> {
+        codeowners.owners_of(path)
+    }
+
+    #[test]
+    fn test_last_match_wins_for_nested_patterns() {
+        let codeowners = CodeOwners::parse(CODEOWNERS);
+        assert_eq!(owners(&codeowners, "src/api/user.ts"), vec!["@org/backend"]);
+        assert_eq!(
+            owners(&codeowners, "src/payments/charge.ts"),
+            vec!["@org/payments", "@alice"]
+        );
+        assert_eq!(owners(&codeowners, "./src/payments/refund.ts").len(), 2);
+        assert_eq!(owners(&codeowners, "build.rs"), vec!["@org/core"]);
+        // `/src/` comes after `*.md`, so it wins
+        assert_eq!(owners(&codeowners, "src/README.md"), vec!["@org/backend"]);
+        assert_eq!(owners(&codeowners, "README.md"), vec!["@org/docs"]);
+    }
+
+    #[test]
+    fn test_rule_without_owners_and_negation_leave_file_unowned() {
+        let codeowners = CodeOwners::parse(CODEOWNERS);
+        assert!(owners(&codeowners, "src/payments/legacy/old.ts").is_empty());
+        assert!(owners(&codeowners, "src/generated/api.ts").is_empty());
+        assert!(CodeOwners::parse("/src/ @team\n")
+            .owners_of("lib/a.ts")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_single_star_stays_in_directory_double_star_crosses() {
+        let codeowners = CodeOwners::parse(CODEOWNERS);
+        assert_eq!(
+            owners(&codeowners, "docs/intro.txt"),
+            vec!["@org/docs-root"]
+        );
+        assert_eq!(
+            owners(&codeowners, "docs/guides/setup.txt"),
+            vec!["@org/core"]
+        );
+        assert_eq!(
+            owners(&codeowners, "assets/icons/a.svg"),
+            vec!["@org/design"]
+        );
+        assert_eq!(
+            owners(&codeowners, "assets/ui/dark/icons/b.svg"),
+            vec!["@org/design"]
+        );
+        assert_eq!(owners(&codeowners, "assets/ui/logo.svg"), vec!["@org/core"]);
+
+        let deep = CodeOwners::parse("/lib/** @deep\n/lib/*.c @shallow\n");
+        assert_eq!(deep.owners_of("lib/x/y.c"), vec!["@deep"]);
+        assert_eq!(deep.owners_of("lib/y.c"), vec!["@shallow"]);
+        assert_eq!(deep.owners_of("src/lib/y.c"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_at_any_depth() {
+        let codeowners = CodeOwners::parse("apps/ @apps\n*.go @gophers # trailing comment\n");
+        assert_eq!(codeowners.owners_of("apps/web/main.ts"), vec!["@apps"]);
+        assert_eq!(codeowners.owners_of("services/apps/a.ts"), vec!["@apps"]);
+        assert_eq!(codeowners.owners_of("cmd/server/main.go"), vec!["@gophers"]);
+        assert!(codeowners.owners_of("main.ts").is_empty());
+    }
+
+    #[test]
+    fn test_gitlab_sections_combine_owners() {
+        let codeowners = CodeOwners::parse(
+            "\
+[Backend] @backend-team
+/src/
+/src/vendor/ @vendor-team
+
+^[Security][2] @security
+/src/auth/
+!/src/auth/fixtures/
+
+[backend]
+*.sql @dba
+",
+        );
+        assert_eq!(codeowners.owners_of("src/api.ts"), vec!["@backend-team"]);
+        assert_eq!(
+            codeowners.owners_of("src/vendor/x.ts"),
+            vec!["@vendor-team"]
+        );
+        assert_eq!(
+            codeowners.owners_of("src/auth/login.ts"),
+            vec!["@backend-team", "@security"]
+        );
+        assert_eq!(
+            codeowners.owners_of("src/auth/fixtures/users.ts"),
+            vec!["@backend-team"]
+        );
+        // Same-named sections are one section: `*.sql` is its last match
+        assert_eq!(codeowners.owners_of("src/schema.sql"), vec!["@dba"]);
+    }
+
+    #[test]
+    fn test_top_author_counts_committed_lines() {
+        let authors: Vec<String> = ["ana", "ana", "bo", "bo", "bo", UNCOMMITTED_AUTHOR, "cy"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+
+        let top = top_author(&authors, "1-6").unwrap();
+        assert_eq!(
+            top,
+            SymbolAuthor {
+                author: "bo".to_string(),
+                lines: 3,
+                total: 5
+            }
+        );
+        assert_eq!(top_author(&authors, "2-3").unwrap().author, "ana");
+        assert_eq!(top_author(&authors, "6-6"), None);
+        assert_eq!(top_author(&authors, "9-12"), None);
+
+        assert_eq!(decode_runs(&encode_runs(&authors)), authors);
+        assert_eq!(encode_runs(&authors).len(), 4);
+    }
+}
@@ -17,11 +17,14 @@
 //!
 //! let summary = parse_and_extract(path, source, lang)?;
 //! ```
+//!
+//! Content that isn't on disk can be analyzed with [`extract_from_bytes`],
+//! which takes a label instead of a path.
 
 use std::path::Path;
 
 use crate::error::McpDiffError;
-use crate::extract::extract;
+use crate::extract::{extract_with_level, ExtractionLevel};
 use crate::lang::Lang;
 use crate::SemanticSummary;
 
@@ -71,6 +74,59 @@ pub fn parse_and_extract_with_options(
     source: &str,
     lang: Lang,
     print_ast: bool,
+) -> Result<SemanticSummary, McpDiffError> {
+    parse_and_extract_at_level(file_path, source, lang, print_ast, ExtractionLevel::Full)
+}
+
+/// Parse source code and extract a semantic summary at the given level of detail.
+///
+/// `ExtractionLevel::Signatures` skips control flow, call and state change
+/// extraction for fast first-pass indexing.
+///
+/// # Errors
+///
+/// Same as [`parse_and_extract`].
+pub fn parse_and_extract_with_level(
+    file_path: &Path,
+    source: &str,
+    lang: Lang,
+    level: ExtractionLevel,
+) -> Result<SemanticSummary, McpDiffError> {
+    parse_and_extract_at_level(file_path, source, lang, false, level)
+}
+
+/// Extract a semantic summary from source held in memory.
+///
+/// For embedders analyzing content that isn't on disk: `name` labels the
+/// summary in place of a file path and is never read, and `lang` is used as
+/// given rather than detected from `name`.
+///
+/// # Errors
+///
+/// Returns `McpDiffError::ParseFailure` if `source` is not valid UTF-8,
+/// naming the byte offset of the first invalid sequence, and otherwise the
+/// same errors as [`parse_and_extract`].
+pub fn extract_from_bytes(
+    name: &str,
+    source: &[u8],
+    lang: Lang,
+) -> Result<SemanticSummary, McpDiffError> {
+    let source = std::str::from_utf8(source).map_err(|e| McpDiffError::ParseFailure {
+        message: format!(
+            "{} is not valid UTF-8 (invalid byte at offset {})",
+            name,
+            e.valid_up_to()
+        ),
+    })?;
+    parse_and_extract(Path::new(name), source, lang)
+}
+
+fn parse_and_extract_at_level(
+    file_path: &Path,
+    source: &str,
+    lang: Lang,
+    print_ast: bool,
+    level: ExtractionLevel,
 ) -> Result<SemanticSummary, McpDiffError> {
     let mut parser = tree_sitter::Parser::new();
     parser
@@ -95,7 +151,7 @@ pub fn parse_and_extract_with_options(
         eprintln!("=================");
     }
 
-    extract(file_path, source, &tree, lang)
+    extract_with_level(file_path, source, &tree, lang, level)
 }
 
 #[cfg(test)]
@@ -144,6 +200,34 @@ mod tests {
         assert!(summary.symbol.is_some() || !summary.symbols.is_empty());
     }
 
+    #[test]
+    fn test_extract_from_bytes() {
+        let source = b"export function greet(name: string) { return name; }";
+
+        let summary = extract_from_bytes("<buffer>", source, Lang::TypeScript).unwrap();
+        assert_eq!(summary.file, "<buffer>");
+        assert!(summary.symbols.iter().any(|s| s.name == "greet"));
+
+        // The name carries no extension; the language is taken as given
+        let summary =
+            extract_from_bytes("snippet", b"def run():\n    pass\n", Lang::Python).unwrap();
+        assert!(summary.symbols.iter().any(|s| s.name == "run"));
+    }
+
+    #[test]
+    fn test_extract_from_bytes_invalid_utf8() {
+        let source = b"fn main() { let s = \"\xff\xfe\"; }";
+
+        match extract_from_bytes("blob.rs", source, Lang::Rust).unwrap_err() {
+            McpDiffError::ParseFailure { message } => {
+                assert!(message.contains("blob.rs"), "{}", message);
+                assert!(message.contains("not valid UTF-8"), "{}", message);
+                assert!(message.contains("offset 21"), "{}", message);
+            }
+            other => panic!("Expected ParseFailure, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_invalid_syntax() {
         // Tree-sitter is lenient with syntax errors, but we can test the flow
@@ -0,0 +1,146 @@
+//! Phase timing for profiling tool calls
+//!
+//! The MCP `benchmark_query` tool measures where a call spends its time. Code
+//! on the query paths opens a [`PhaseTimer`] for the phase it belongs to;
+//! while a [`Profiling`] session is active the elapsed time is added to a
+//! process-wide counter for that phase. Outside a session a timer costs one
+//! atomic load.
+//!
+//! A timer opened inside another one on the same thread records nothing, so
+//! a symbol index load during a freshness check isn't counted twice.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Part of a tool call that is timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Loading and filtering the symbol index
+    IndexLookup,
+    /// Scoring against the BM25 index
+    Bm25Search,
+    /// Freshness checks, source reads and raw text scans
+    FilesystemIo,
+    /// Encoding the response
+    Formatting,
+}
+
+impl Phase {
+    /// All phases, in report order
+    pub const ALL: [Phase; 4] = [
+        Phase::IndexLookup,
+        Phase::Bm25Search,
+        Phase::FilesystemIo,
+        Phase::Formatting,
+    ];
+
+    /// Name used in benchmark output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::IndexLookup => "index_lookup",
+            Phase::Bm25Search => "bm25_search",
+            Phase::FilesystemIo => "filesystem_io",
+            Phase::Formatting => "formatting",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Whether a profiling session is active
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Nanoseconds recorded per phase in the active session
+static PHASE_NANOS: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+thread_local! {
+    /// Whether a timer is already running on this thread
+    static TIMING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Records the time until it is dropped against a phase
+#[must_use = "the phase is timed until the timer is dropped"]
+pub struct PhaseTimer {
+    running: Option<(Phase, Instant)>,
+}
+
+impl PhaseTimer {
+    /// Start timing `phase`
+    pub fn start(phase: Phase) -> Self {
+        if !ENABLED.load(Ordering::Relaxed) || TIMING.with(|t| t.replace(true)) {
+            return Self { running: None };
+        }
+        Self {
+            running: Some((phase, Instant::now())),
+        }
+    }
+}
+
+impl Drop for PhaseTimer {
+    fn drop(&mut self) {
+        if let Some((phase, started)) = self.running.take() {
+            let nanos = started.elapsed().as_nanos() as u64;
+            PHASE_NANOS[phase.index()].fetch_add(nanos, Ordering::Relaxed);
+            TIMING.with(|t| t.set(false));
+        }
+    }
+}
+
+/// Time spent in each phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimes([Duration; 4]);
+
+impl PhaseTimes {
+    /// Time recorded for one phase
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.0[phase.index()]
+    }
+
+    /// Time recorded across all phases
+    pub fn total(&self) -> Duration {
+        self.0.iter().sum()
+    }
+}
+
+/// An active profiling session
+///
+/// Only one session runs at a time; the counters are process-wide, so calls
+/// made concurrently by other clients are included in its times.
+pub struct Profiling(());
+
+impl Profiling {
+    /// Start a session, or `None` if one is already active
+    pub fn start() -> Option<Self> {
+        ENABLED
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        for nanos in &PHASE_NANOS {
+            nanos.store(0, Ordering::Relaxed);
+        }
+        Some(Self(()))
+    }
+
+    /// Times recorded since the session started or the last call
+    pub fn take(&self) -> PhaseTimes {
+        let mut times = PhaseTimes::default();
+        for phase in Phase::ALL {
+            let nanos = PHASE_NANOS[phase.index()].swap(0, Ordering::Relaxed);
+            times.0[phase.index()] = Duration::from_nanos(nanos);
+        }
+        times
+    }
+}
+
+impl Drop for Profiling {
+    fn drop(&mut self) {
+        ENABLED.store(false, Ordering::Release);
+    }
+}
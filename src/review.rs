@@ -0,0 +1,734 @@
+//! Review bundles for code review bots
+//!
+//! `analyze --diff --format review-json` condenses a diff into one JSON
+//! document a review bot can turn into inline comments: a summary block
+//! (risk distribution, breaking changes, new escape hatches and TODOs) and a
+//! flat list of findings anchored to new-side lines.
+//!
+//! Findings only concern symbols the diff adds or edits, and only problems the
+//! diff introduces: a symbol that was already high risk, or already duplicated
+//! the same code, is not reported again. The list is deduplicated, capped at
+//! [`MAX_FINDINGS_PER_FILE`] per file and sorted by path and line, so the same
+//! diff always yields the same bundle. A bundle larger than
+//! [`MAX_BUNDLE_BYTES`] drops its lowest-severity findings first.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::analysis::calculate_cognitive_complexity;
+use crate::cache::{load_function_signatures, CacheDir};
+use crate::contracts::BreakingChange;
+use crate::diff_warnings::{changed_summaries, ChangedSummary};
+use crate::duplicate::{DuplicateDetector, FunctionSignature};
+use crate::git::ChangedFile;
+use crate::schema::{RiskLevel, SymbolId, SymbolInfo, SymbolKind};
+use crate::security::patterns::embedded::load_embedded_patterns;
+use crate::security::{self, PatternDatabase};
+
+/// Findings kept per file; the rest are summarized in an overflow note
+pub const MAX_FINDINGS_PER_FILE: usize = 10;
+
+/// Serialized size the bundle is trimmed to
+pub const MAX_BUNDLE_BYTES: usize = 64 * 1024;
+
+/// Cognitive complexity above which an increase is a regression (the
+/// "complex" rating used by `analyze`)
+pub const COMPLEXITY_THRESHOLD: usize = 10;
+
+/// Minimum similarity for a CVE pattern match (as for `analyze --stdin`)
+const CVE_THRESHOLD: f32 = 0.75;
+
+/// Comment markers counted as TODOs
+const TODO_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// Constructs that switch off a type checker, linter or safety check
+const ESCAPE_HATCHES: &[&str] = &[
+    "as any",
+    ": any",
+    "@ts-ignore",
+    "@ts-expect-error",
+    "@ts-nocheck",
+    "eslint-disable",
+    "# type: ignore",
+    "# noqa",
+    "# pylint: disable",
+    "//nolint",
+    "#[allow(",
+    "unsafe {",
+    "@SuppressWarnings",
+    "#pragma warning disable",
+    "@SuppressLint",
+];
+
+/// How much a finding needs a reviewer's attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// What a finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCategory {
+    /// A contract (protobuf, OpenAPI) change that breaks existing clients
+    BreakingChange,
+    /// A symbol that became high risk
+    HighRiskSymbol,
+    /// A symbol that now duplicates indexed code
+    NewDuplicate,
+    /// A symbol that now matches a known vulnerability pattern
+    SecurityMatch,
+    /// A symbol whose cognitive complexity rose past [`COMPLEXITY_THRESHOLD`]
+    ComplexityRegression,
+}
+
+/// One review comment
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct ReviewFinding {
+    /// File (relative to the repo root)
+    pub path: String,
+    /// New-side line, or `None` for file-level findings
+    pub line: Option<usize>,
+    pub severity: ReviewSeverity,
+    pub category: FindingCategory,
+    pub message: String,
+    /// Hash of the symbol, for `get_symbol`
+    pub symbol_hash: Option<String>,
+}
+
+/// Risk levels of the symbols a diff adds or edits
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RiskDistribution {
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+/// Diff-wide counts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReviewSummary {
+    pub files_changed: usize,
+    pub risk_distribution: RiskDistribution,
+    pub breaking_changes: usize,
+    /// Lines added with a type checker, linter or safety escape hatch
+    pub new_escape_hatches: usize,
+    /// Lines added with a TODO/FIXME/HACK/XXX marker
+    pub new_todos: usize,
+    /// Findings in the bundle
+    pub findings: usize,
+    /// Findings left out by the per-file cap or the size ceiling
+    pub findings_omitted: usize,
+}
+
+/// Everything a review bot needs about a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewBundle {
+    #[serde(rename = "_type")]
+    pub kind: &'static str,
+    pub base: String,
+    pub target: String,
+    pub summary: ReviewSummary,
+    pub findings: Vec<ReviewFinding>,
+    /// What was left out, and why
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+impl ReviewBundle {
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Review bundle for a set of changed files
+///
+/// `target_ref` is read from git, or from the working tree when it is `None`.
+/// Duplicates are looked up in the repository's index, if it has one.
+pub fn review_bundle(
+    repo_root: &Path,
+    base: &str,
+    target: &str,
+    base_ref: &str,
+    target_ref: Option<&str>,
+    changed_files: &[ChangedFile],
+    breaking: &[BreakingChange],
+) -> ReviewBundle {
+    let summaries = changed_summaries(repo_root, base_ref, target_ref, changed_files);
+    let indexed = CacheDir::for_repo(repo_root)
+        .ok()
+        .filter(|c| c.exists())
+        .and_then(|c| load_function_signatures(&c).ok())
+        .unwrap_or_default();
+    let mut bundle = build_review(
+        repo_root,
+        &summaries,
+        breaking,
+        &indexed,
+        &load_embedded_patterns(),
+        MAX_BUNDLE_BYTES,
+    );
+    bundle.base = base.to_string();
+    bundle.target = target.to_string();
+    bundle.summary.files_changed = changed_files.len();
+    bundle
+}
+
+/// Build a bundle from already summarized files, trimmed to `max_bytes`
+pub(crate) fn build_review(
+    repo_root: &Path,
+    summaries: &[ChangedSummary],
+    breaking: &[BreakingChange],
+    indexed: &[FunctionSignature],
+    patterns: &PatternDatabase,
+    max_bytes: usize,
+) -> ReviewBundle {
+    let mut summary = ReviewSummary {
+        files_changed: summaries.len(),
+        breaking_changes: breaking.len(),
+        ..Default::default()
+    };
+    let mut findings: Vec<ReviewFinding> = breaking
+        .iter()
+        .map(|b| ReviewFinding {
+            path: b.file.clone(),
+            line: None,
+            severity: ReviewSeverity::High,
+            category: FindingCategory::BreakingChange,
+            message: format!("{}: {}", b.element, b.reason),
+            symbol_hash: None,
+        })
+        .collect();
+
+    let detector = DuplicateDetector::default();
+    for changed in summaries {
+        let added = added_lines(&changed.target_source, changed.base_source.as_deref());
+        summary.new_escape_hatches += added.iter().filter(|l| is_escape_hatch(l)).count();
+        summary.new_todos += added.iter().filter(|l| has_todo(l)).count();
+
+        // Signatures elsewhere in the repo; the indexed copy of this file is stale
+        let others: Vec<FunctionSignature> = indexed
+            .iter()
+            .filter(|sig| relative_path(repo_root, &sig.file) != changed.changed.path)
+            .cloned()
+            .collect();
+        let reviewer = SymbolReviewer {
+            repo_root,
+            changed,
+            others: &others,
+            detector: &detector,
+            patterns,
+        };
+
+        for sym in &changed.target.symbols {
+            if sym.kind == SymbolKind::Variable || !reviewer.is_edited(sym) {
+                continue;
+            }
+            match sym.calculate_risk() {
+                RiskLevel::High => summary.risk_distribution.high += 1,
+                RiskLevel::Medium => summary.risk_distribution.medium += 1,
+                RiskLevel::Low => summary.risk_distribution.low += 1,
+            }
+            reviewer.review(sym, &mut findings);
+        }
+    }
+
+    let (findings, notes, omitted) = cap_findings(findings, max_bytes);
+    summary.findings = findings.len();
+    summary.findings_omitted = omitted;
+    ReviewBundle {
+        kind: "review_bundle",
+        base: String::new(),
+        target: String::new(),
+        summary,
+        findings,
+        notes,
+    }
+}
+
+/// Reviews the symbols of one changed file against their base versions
+struct SymbolReviewer<'a> {
+    repo_root: &'a Path,
+    changed: &'a ChangedSummary<'a>,
+    others: &'a [FunctionSignature],
+    detector: &'a DuplicateDetector,
+    patterns: &'a PatternDatabase,
+}
+
+impl SymbolReviewer<'_> {
+    /// Whether the diff adds `sym` or edits its body
+    fn is_edited(&self, sym: &SymbolInfo) -> bool {
+        let (Some(base), Some(base_source)) = (
+            self.changed.base_symbol(sym),
+            self.changed.base_source.as_deref(),
+        ) else {
+            return true;
+        };
+        symbol_lines(&self.changed.target_source, sym) != symbol_lines(base_source, base)
+    }
+
+    /// Add the findings `sym` introduces
+    fn review(&self, sym: &SymbolInfo, findings: &mut Vec<ReviewFinding>) {
+        let base = self.changed.base_symbol(sym);
+        let file = &self.changed.target.file;
+        let hash = sym
+            .to_symbol_id(&SymbolId::namespace_from_path(file), file)
+            .hash;
+        let finding = |severity, category, message| ReviewFinding {
+            path: self.changed.changed.path.clone(),
+            line: Some(sym.start_line),
+            severity,
+            category,
+            message,
+            symbol_hash: Some(hash.clone()),
+        };
+
+        if sym.calculate_risk() == RiskLevel::High {
+            match base.map(SymbolInfo::calculate_risk) {
+                None => findings.push(finding(
+                    ReviewSeverity::Medium,
+                    FindingCategory::HighRiskSymbol,
+                    format!("new high-risk symbol `{}`", sym.name),
+                )),
+                Some(RiskLevel::High) => {}
+                Some(was) => findings.push(finding(
+                    ReviewSeverity::Medium,
+                    FindingCategory::HighRiskSymbol,
+                    format!("`{}` became high risk (was {})", sym.name, was.as_str()),
+                )),
+            }
+        }
+
+        if !matches!(sym.kind, SymbolKind::Function | SymbolKind::Method) {
+            return;
+        }
+
+        let complexity = calculate_cognitive_complexity(&sym.control_flow);
+        let base_complexity = base.map_or(0, |b| calculate_cognitive_complexity(&b.control_flow));
+        if complexity > COMPLEXITY_THRESHOLD && complexity > base_complexity {
+            let severity = if complexity > 15 {
+                ReviewSeverity::Medium
+            } else {
+                ReviewSeverity::Low
+            };
+            findings.push(finding(
+                severity,
+                FindingCategory::ComplexityRegression,
+                format!(
+                    "`{}` cognitive complexity {} -> {}",
+                    sym.name, base_complexity, complexity
+                ),
+            ));
+        }
+
+        let signature = self.signature(sym, &hash);
+        let base_signature = base.map(|b| self.signature(b, &hash));
+
+        let known: HashSet<String> = base_signature
+            .as_ref()
+            .map(|b| {
+                self.detector
+                    .find_duplicates(b, self.others)
+                    .into_iter()
+                    .map(|d| d.symbol.hash)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let duplicate = self
+            .detector
+            .find_duplicates(&signature, self.others)
+            .into_iter()
+            .filter(|d| !known.contains(&d.symbol.hash))
+            .max_by(|a, b| {
+                a.similarity
+                    .partial_cmp(&b.similarity)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| b.symbol.hash.cmp(&a.symbol.hash))
+            });
+        if let Some(d) = duplicate {
+            findings.push(finding(
+                ReviewSeverity::Low,
+                FindingCategory::NewDuplicate,
+                format!(
+                    "`{}` is {:.0}% similar to {}:{}",
+                    sym.name,
+                    d.similarity * 100.0,
+                    relative_path(self.repo_root, &d.symbol.file),
+                    d.symbol.name
+                ),
+            ));
+        }
+
+        let known: HashSet<String> = base_signature
+            .as_ref()
+            .map(|b| {
+                self.detector
+                    .match_cve_patterns(b, self.patterns, CVE_THRESHOLD)
+                    .into_iter()
+                    .map(|m| m.cve_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for m in self
+            .detector
+            .match_cve_patterns(&signature, self.patterns, CVE_THRESHOLD)
+            .into_iter()
+            .filter(|m| !known.contains(&m.cve_id))
+        {
+            let severity = match m.severity {
+                security::Severity::Critical | security::Severity::High => ReviewSeverity::High,
+                security::Severity::Medium => ReviewSeverity::Medium,
+                security::Severity::Low | security::Severity::None => ReviewSeverity::Low,
+            };
+            findings.push(finding(
+                severity,
+                FindingCategory::SecurityMatch,
+                format!(
+                    "`{}` matches {} ({}, {:.0}% similar): {}",
+                    sym.name,
+                    m.cve_id,
+                    m.severity,
+                    m.similarity * 100.0,
+                    m.description
+                ),
+            ));
+        }
+    }
+
+    fn signature(&self, sym: &SymbolInfo, hash: &str) -> FunctionSignature {
+        FunctionSignature::from_symbol_info(sym, hash, &self.changed.target.file, "", None)
+    }
+}
+
+/// Deduplicate, apply the per-file cap and the size ceiling, and sort.
+/// Returns the kept findings, the notes about dropped ones and their count.
+fn cap_findings(
+    findings: Vec<ReviewFinding>,
+    max_bytes: usize,
+) -> (Vec<ReviewFinding>, Vec<String>, usize) {
+    let mut by_file: BTreeMap<String, Vec<ReviewFinding>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for finding in findings {
+        if seen.insert(finding.clone()) {
+            by_file
+                .entry(finding.path.clone())
+                .or_default()
+                .push(finding);
+        }
+    }
+
+    let mut notes = Vec::new();
+    let mut omitted = 0;
+    let mut kept = Vec::new();
+    for (path, mut file_findings) in by_file {
+        file_findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| order(a, b)));
+        if file_findings.len() > MAX_FINDINGS_PER_FILE {
+            let overflow = file_findings.len() - MAX_FINDINGS_PER_FILE;
+            file_findings.truncate(MAX_FINDINGS_PER_FILE);
+            omitted += overflow;
+            notes.push(format!(
+                "{}: {} more findings omitted (limit {} per file)",
+                path, overflow, MAX_FINDINGS_PER_FILE
+            ));
+        }
+        kept.extend(file_findings);
+    }
+    kept.sort_by(order);
+
+    // Drop the lowest-severity findings, last first, until the bundle fits.
+    // 1 KiB is left for the summary and notes.
+    let sizes: Vec<usize> = kept
+        .iter()
+        .map(|f| serde_json::to_string_pretty(f).map_or(0, |s| s.len()) + 6)
+        .collect();
+    let mut total: usize = sizes.iter().sum::<usize>() + 1024;
+    let mut drop_order: Vec<usize> = (0..kept.len()).collect();
+    drop_order.sort_by(|&a, &b| kept[a].severity.cmp(&kept[b].severity).then(b.cmp(&a)));
+    let mut dropped = HashSet::new();
+    for i in drop_order {
+        if total <= max_bytes {
+            break;
+        }
+        total -= sizes[i];
+        dropped.insert(i);
+    }
+    if !dropped.is_empty() {
+        omitted += dropped.len();
+        notes.push(format!(
+            "{} lowest-severity findings dropped to keep the bundle under {} bytes",
+            dropped.len(),
+            max_bytes
+        ));
+        kept = kept
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !dropped.contains(i))
+            .map(|(_, f)| f)
+            .collect();
+    }
+    (kept, notes, omitted)
+}
+
+/// Stable finding order: path, line, category, message
+fn order(a: &ReviewFinding, b: &ReviewFinding) -> std::cmp::Ordering {
+    (&a.path, a.line, a.category, &a.message).cmp(&(&b.path, b.line, b.category, &b.message))
+}
+
+/// Trimmed lines of `target` that `base` doesn't have (as many times)
+fn added_lines<'s>(target: &'s str, base: Option<&str>) -> Vec<&'s str> {
+    let mut base_counts: HashMap<&str, usize> = HashMap::new();
+    for line in base.unwrap_or_default().lines() {
+        *base_counts.entry(line.trim()).or_default() += 1;
+    }
+    target
+        .lines()
+        .map(str::trim)
+        .filter(|line| match base_counts.get_mut(line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+fn is_escape_hatch(line: &str) -> bool {
+    ESCAPE_HATCHES.iter().any(|hatch| line.contains(hatch))
+}
+
+/// Whether `line` has a TODO marker as a whole word
+fn has_todo(line: &str) -> bool {
+    line.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| TODO_MARKERS.contains(&word))
+}
+
+/// Source lines of a symbol
+fn symbol_lines<'s>(source: &'s str, sym: &SymbolInfo) -> Vec<&'s str> {
+    source
+        .lines()
+        .skip(sym.start_line.saturating_sub(1))
+        .take(sym.end_line.saturating_sub(sym.start_line) + 1)
+        .collect()
+}
+
+/// `file` relative to the repo root, with `/` separators
+fn relative_path(repo_root: &Path, file: &str) -> String {
+    Path::new(file)
+        .strip_prefix(repo_root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff_warnings::summarize;
+    use crate::git::ChangeType;
+    use crate::lang::Lang;
+    use crate::security::CVEPattern;
+
+    const BASE: &str = r#"export function parseConfig(raw) {
+  return JSON.parse(raw);
+}
+"#;
+
+    const TARGET: &str = r#"export function parseConfig(raw) {
+  // TODO: validate the schema
+  const config = JSON.parse(raw) as any;
+  if (config.a) {
+    if (config.b) {
+      if (config.c) {
+        if (config.d) {
+          for (const x of config.items) {
+            if (x) { return x; }
+          }
+        }
+      }
+    }
+  }
+  return config;
+}
+
+export async function syncUsers(db, api) {
+  const users = await api.fetchUsers();
+  await db.saveUsers(users);
+  await api.notifyAdmins(users);
+  await db.recordAudit(users);
+  return users;
+}
+"#;
+
+    const OTHER: &str = r#"export async function syncAccounts(db, api) {
+  const users = await api.fetchUsers();
+  await db.saveUsers(users);
+  await api.notifyAdmins(users);
+  await db.recordAudit(users);
+  return users;
+}
+"#;
+
+    #[test]
+    fn test_review_bundle_snapshot() {
+        let root = Path::new("/repo");
+        let changed = ChangedFile {
+            path: "src/config.ts".to_string(),
+            old_path: None,
+            change_type: ChangeType::Modified,
+        };
+        let target = summarize(root, &changed.path, TARGET).unwrap();
+        let base = summarize(root, &changed.path, BASE).unwrap();
+        let summaries = vec![ChangedSummary {
+            changed: &changed,
+            target,
+            base: Some(base),
+            target_source: TARGET.to_string(),
+            base_source: Some(BASE.to_string()),
+        }];
+
+        let other = summarize(root, "src/accounts.ts", OTHER).unwrap();
+        let indexed: Vec<FunctionSignature> = other
+            .symbols
+            .iter()
+            .map(|s| FunctionSignature::from_symbol_info(s, "accounts:sync", &other.file, "", None))
+            .collect();
+
+        // A pattern cut from the synced function itself, so it surely matches
+        let sync = summaries[0]
+            .target
+            .symbols
+            .iter()
+            .find(|s| s.name == "syncUsers")
+            .unwrap();
+        let sig =
+            FunctionSignature::from_symbol_info(sync, "h", &summaries[0].target.file, "", None);
+        let mut patterns = PatternDatabase::new();
+        patterns.add_pattern(
+            CVEPattern::new("CVE-2099-0001", vec!["CWE-862".to_string()], 1)
+                .with_fingerprints(
+                    sig.call_fingerprint,
+                    sig.control_flow_fingerprint,
+                    sig.state_fingerprint,
+                )
+                .with_vulnerable_calls(sig.business_calls.clone())
+                .with_cvss(8.1)
+                .with_languages(vec![Lang::TypeScript])
+                .with_description("Unauthorized bulk sync"),
+        );
+
+        let breaking = vec![BreakingChange {
+            file: "proto/users.proto".to_string(),
+            element: "User.email".to_string(),
+            reason: "field removed (#2)".to_string(),
+        }];
+
+        let bundle = build_review(
+            root,
+            &summaries,
+            &breaking,
+            &indexed,
+            &patterns,
+            MAX_BUNDLE_BYTES,
+        );
+        let json: serde_json::Value = serde_json::from_str(&bundle.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "_type": "review_bundle",
+                "base": "",
+                "target": "",
+                "summary": {
+                    "files_changed": 1,
+                    "risk_distribution": { "high": 1, "medium": 1, "low": 0 },
+                    "breaking_changes": 1,
+                    "new_escape_hatches": 1,
+                    "new_todos": 1,
+                    "findings": 5,
+                    "findings_omitted": 0
+                },
+                "findings": [
+                    {
+                        "path": "proto/users.proto",
+                        "line": null,
+                        "severity": "high",
+                        "category": "breaking_change",
+                        "message": "User.email: field removed (#2)",
+                        "symbol_hash": null
+                    },
+                    {
+                        "path": "src/config.ts",
+                        "line": 1,
+                        "severity": "medium",
+                        "category": "complexity_regression",
+                        "message": "`parseConfig` cognitive complexity 0 -> 16",
+                        "symbol_hash": "c51d72e1:84d6daf19ac62942"
+                    },
+                    {
+                        "path": "src/config.ts",
+                        "line": 18,
+                        "severity": "medium",
+                        "category": "high_risk_symbol",
+                        "message": "new high-risk symbol `syncUsers`",
+                        "symbol_hash": "c51d72e1:e48edbfd4725d567"
+                    },
+                    {
+                        "path": "src/config.ts",
+                        "line": 18,
+                        "severity": "low",
+                        "category": "new_duplicate",
+                        "message": "`syncUsers` is 87% similar to src/accounts.ts:syncAccounts",
+                        "symbol_hash": "c51d72e1:e48edbfd4725d567"
+                    },
+                    {
+                        "path": "src/config.ts",
+                        "line": 18,
+                        "severity": "high",
+                        "category": "security_match",
+                        "message": "`syncUsers` matches CVE-2099-0001 (HIGH, 100% similar): Unauthorized bulk sync",
+                        "symbol_hash": "c51d72e1:e48edbfd4725d567"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_cap_findings_limits_per_file_and_size() {
+        let finding = |path: &str, line, severity| ReviewFinding {
+            path: path.to_string(),
+            line: Some(line),
+            severity,
+            category: FindingCategory::HighRiskSymbol,
+            message: format!("finding {}", line),
+            symbol_hash: None,
+        };
+        let mut findings: Vec<ReviewFinding> = (1..=12)
+            .map(|line| finding("b.ts", line, ReviewSeverity::Low))
+            .collect();
+        findings.push(finding("b.ts", 20, ReviewSeverity::High));
+        findings.push(finding("a.ts", 1, ReviewSeverity::Medium));
+        findings.push(finding("a.ts", 1, ReviewSeverity::Medium));
+
+        let (kept, notes, omitted) = cap_findings(findings.clone(), MAX_BUNDLE_BYTES);
+        assert_eq!(kept.len(), 1 + MAX_FINDINGS_PER_FILE);
+        assert_eq!(kept[0].path, "a.ts");
+        assert!(kept.iter().any(|f| f.line == Some(20)));
+        assert_eq!(omitted, 3);
+        assert_eq!(notes, ["b.ts: 3 more findings omitted (limit 10 per file)"]);
+
+        // A tight ceiling drops low-severity findings, never the high one
+        let (kept, notes, _) = cap_findings(findings, 1024 + 400);
+        assert!(kept.iter().any(|f| f.severity == ReviewSeverity::High));
+        assert!(kept.iter().any(|f| f.severity == ReviewSeverity::Medium));
+        assert!(kept.len() < 1 + MAX_FINDINGS_PER_FILE);
+        assert!(notes
+            .last()
+            .unwrap()
+            .contains("lowest-severity findings dropped"));
+    }
+}
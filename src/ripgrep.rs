@@ -41,10 +41,12 @@ use grep_matcher::Matcher;
 use grep_regex::RegexMatcher;
 use grep_searcher::sinks::UTF8;
 use grep_searcher::{BinaryDetection, SearcherBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{McpDiffError, Result};
+use crate::profile::{Phase, PhaseTimer};
 
 // ============================================================================
 // Core Types
@@ -152,6 +154,12 @@ pub struct SearchOptions {
 
     /// File type filters (e.g., "rs", "ts")
     pub file_types: Vec<String>,
+
+    /// Path globs in ripgrep's `--glob` syntax (e.g., "src/**", "!vendor/**")
+    pub globs: Vec<String>,
+
+    /// Directory the globs are relative to (default: the search root)
+    pub glob_root: Option<PathBuf>,
 }
 
 impl SearchOptions {
@@ -164,6 +172,8 @@ impl SearchOptions {
             merge_threshold: 3, // Default: merge blocks within 3 lines
             case_insensitive: false,
             file_types: Vec::new(),
+            globs: Vec::new(),
+            glob_root: None,
         }
     }
 
@@ -196,6 +206,16 @@ impl SearchOptions {
         self.file_types = types;
         self
     }
+
+    /// Set path globs, relative to `root`
+    ///
+    /// A file must match one of the globs without a `!` prefix, if there are
+    /// any, and none of the `!` ones.
+    pub fn with_globs(mut self, root: impl Into<PathBuf>, globs: Vec<String>) -> Self {
+        self.glob_root = Some(root.into());
+        self.globs = globs;
+        self
+    }
 }
 
 impl Default for SearchOptions {
@@ -224,6 +244,8 @@ impl RipgrepSearcher {
     ///
     /// Returns a list of matches, respecting .gitignore and other options.
     pub fn search(&self, root: &Path, options: &SearchOptions) -> Result<Vec<SearchMatch>> {
+        let _timer = PhaseTimer::start(Phase::FilesystemIo);
+
         // Build regex matcher
         let matcher = self.build_matcher(options)?;
 
@@ -231,8 +253,9 @@ impl RipgrepSearcher {
         let matches = Arc::new(Mutex::new(Vec::new()));
         let limit = options.limit;
 
-        // Build file walker
+        // Build file walker; it skips excluded paths itself
         let walker = self.build_walker(root, options)?;
+        let included = self.build_overrides(root, options, false)?;
 
         // Search each file
         for result in walker {
@@ -248,6 +271,11 @@ impl RipgrepSearcher {
 
             let path = entry.path();
 
+            // Check path globs
+            if included.matched(path, false).is_ignore() {
+                continue;
+            }
+
             // Check file type filters
             if !options.file_types.is_empty() {
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
@@ -351,9 +379,33 @@ impl RipgrepSearcher {
         // Include hidden files in search (hidden=true means "process hidden files")
         builder.hidden(true);
 
+        // Only `!` globs go to the walker: an override whitelist would also
+        // pull in gitignored files, so inclusion is checked per file instead
+        builder.overrides(self.build_overrides(root, options, true)?);
+
         Ok(builder.build())
     }
 
+    /// Build an override matcher from the `!` globs (`negated`) or the others
+    fn build_overrides(
+        &self,
+        root: &Path,
+        options: &SearchOptions,
+        negated: bool,
+    ) -> Result<Override> {
+        let mut builder = OverrideBuilder::new(options.glob_root.as_deref().unwrap_or(root));
+        for glob in &options.globs {
+            if glob.starts_with('!') == negated {
+                builder.add(glob).map_err(|e| McpDiffError::QueryError {
+                    message: format!("Invalid path glob '{}': {}", glob, e),
+                })?;
+            }
+        }
+        builder.build().map_err(|e| McpDiffError::QueryError {
+            message: format!("Invalid path glob: {}", e),
+        })
+    }
+
     /// Search a single file
     fn search_file(
         &self,
@@ -824,6 +876,36 @@ fn beta() {}
         assert!(matches[0].file.to_string_lossy().ends_with(".rs"));
     }
 
+    #[test]
+    fn test_search_with_path_globs() {
+        let dir = TempDir::new().unwrap();
+        for sub in ["src/api", "src/vendor", "lib"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("mod.rs"), "fn needle() {}").unwrap();
+        }
+
+        let searcher = RipgrepSearcher::new();
+        let files = |globs: &[&str]| {
+            let options = SearchOptions::new("needle")
+                .with_globs(dir.path(), globs.iter().map(|g| g.to_string()).collect());
+            let mut files: Vec<String> = searcher
+                .search(dir.path(), &options)
+                .unwrap()
+                .iter()
+                .map(|m| {
+                    let relative = m.file.strip_prefix(dir.path()).unwrap();
+                    relative.to_string_lossy().replace('\\', "/")
+                })
+                .collect();
+            files.sort();
+            files
+        };
+
+        assert_eq!(files(&["src/**"]), ["src/api/mod.rs", "src/vendor/mod.rs"]);
+        assert_eq!(files(&["!src/vendor/**"]), ["lib/mod.rs", "src/api/mod.rs"]);
+        assert_eq!(files(&["src/**", "!**/vendor/**"]), ["src/api/mod.rs"]);
+    }
+
     #[test]
     fn test_search_respects_gitignore_disabled() {
         let dir = setup_test_dir();
@@ -1,14 +1,19 @@
 //! Behavioral risk calculation
 
-use crate::schema::{RiskLevel, SemanticSummary};
+use crate::schema::{control_flow_weight, panic_weight, RiskLevel, SemanticSummary};
 
 /// Calculate behavioral risk level from a semantic summary
 ///
 /// Risk scoring (tuned for practical use):
 /// - +1 per new import (capped at 3)
 /// - +1 per state variable
+/// - +1 more when the file has async functions and any state mutation isn't
+///   atomic (lock-guarded or an atomic type)
 /// - +1 for presence of complex control flow (if/match/for), +1 if > 5, +1 if > 15
+///   (each `await` counts as half a construct, each `yield` as one)
 /// - +2 for I/O or network calls
+/// - +1 per panic/throw point outside a try block, +0.5 per one inside
+///   (capped at 2)
 /// - +3 for public API changes
 /// - +3 for persistence operations
 pub fn calculate_risk(summary: &SemanticSummary) -> RiskLevel {
@@ -20,16 +25,22 @@ pub fn calculate_risk(summary: &SemanticSummary) -> RiskLevel {
     // +1 per state variable
     score += summary.state_changes.len();
 
+    // Unsynchronized mutations alongside async code can interleave across awaits
+    let has_async = summary.symbols.iter().any(|s| s.is_async);
+    if has_async && summary.state_changes.iter().any(|s| !s.is_atomic) {
+        score += 1;
+    }
+
     // Control flow: graduated scoring instead of +2 per item
     // This prevents normal Rust files with many if/match from being "high risk"
-    let cf_count = summary.control_flow_changes.len();
-    if cf_count > 0 {
+    let cf_weight = control_flow_weight(&summary.control_flow_changes);
+    if cf_weight > 0.0 {
         score += 1; // Base: has control flow
     }
-    if cf_count > 5 {
+    if cf_weight > 5.0 {
         score += 1; // Moderate complexity
     }
-    if cf_count > 15 {
+    if cf_weight > 15.0 {
         score += 1; // High complexity
     }
 
@@ -46,6 +57,9 @@ pub fn calculate_risk(summary: &SemanticSummary) -> RiskLevel {
         }
     }
 
+    // Places the file's symbols can panic or throw
+    score += panic_weight(summary.symbols.iter().flat_map(|s| &s.panic_points));
+
     // +3 for public API changes
     if summary.public_surface_changed {
         score += 3;
@@ -70,7 +84,10 @@ pub fn calculate_risk(summary: &SemanticSummary) -> RiskLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::{ControlFlowChange, ControlFlowKind, Location, StateChange};
+    use crate::schema::{
+        ControlFlowChange, ControlFlowKind, Location, PanicKind, PanicPoint, StateChange,
+        SymbolInfo,
+    };
 
     #[test]
     fn test_low_risk() {
@@ -89,12 +106,37 @@ mod tests {
                 name: "open".to_string(),
                 state_type: "boolean".to_string(),
                 initializer: "false".to_string(),
+                is_atomic: false,
             }],
             ..Default::default()
         };
         assert_eq!(calculate_risk(&summary), RiskLevel::Medium);
     }
 
+    #[test]
+    fn test_async_non_atomic_state_scores_higher() {
+        let state = |name: &str, is_atomic: bool| StateChange {
+            name: name.to_string(),
+            state_type: "_".to_string(),
+            initializer: String::new(),
+            is_atomic,
+        };
+        let summary_with = |is_atomic: bool| SemanticSummary {
+            symbols: vec![crate::schema::SymbolInfo {
+                name: "handle".to_string(),
+                is_async: true,
+                ..Default::default()
+            }],
+            added_dependencies: vec!["tokio".to_string()],
+            state_changes: vec![state("hits", is_atomic), state("total", is_atomic)],
+            ..Default::default()
+        };
+        // 1 import + 2 state variables + 1 unsynchronized async mutation = 4
+        assert_eq!(calculate_risk(&summary_with(false)), RiskLevel::High);
+        // 1 import + 2 state variables = 3
+        assert_eq!(calculate_risk(&summary_with(true)), RiskLevel::Medium);
+    }
+
     #[test]
     fn test_high_risk_control_flow() {
         // High risk now requires more substantial changes
@@ -131,4 +173,64 @@ mod tests {
         // 2 network + 3 public = 5 = high
         assert_eq!(calculate_risk(&summary), RiskLevel::High);
     }
+
+    #[test]
+    fn test_await_weighs_half() {
+        let control_flow = |kind: ControlFlowKind, count: usize| {
+            (0..count)
+                .map(|_| ControlFlowChange {
+                    kind,
+                    location: Location::default(),
+                    nesting_depth: 0,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 10 awaits weigh 5.0: not above the "moderate" threshold
+        let awaits = SemanticSummary {
+            control_flow_changes: control_flow(ControlFlowKind::Await, 10),
+            ..Default::default()
+        };
+        // 6 yields weigh 6.0: above it
+        let yields = SemanticSummary {
+            control_flow_changes: control_flow(ControlFlowKind::Yield, 6),
+            ..Default::default()
+        };
+        assert_eq!(calculate_risk(&awaits), RiskLevel::Low);
+        assert_eq!(calculate_risk(&yields), RiskLevel::Medium);
+    }
+
+    #[test]
+    fn test_panic_points_raise_risk() {
+        let point = |kind: PanicKind, in_try: bool| PanicPoint {
+            location: Location::new(3, 4),
+            kind,
+            in_try,
+        };
+        let summary = |points: Vec<PanicPoint>| SemanticSummary {
+            symbols: vec![SymbolInfo {
+                name: "load".to_string(),
+                panic_points: points,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // One caught throw weighs half a point
+        assert_eq!(
+            calculate_risk(&summary(vec![point(PanicKind::Throw, true)])),
+            RiskLevel::Low
+        );
+        // Two uncaught unwraps push the file to medium
+        assert_eq!(
+            calculate_risk(&summary(vec![
+                point(PanicKind::Unwrap, false),
+                point(PanicKind::Unwrap, false),
+            ])),
+            RiskLevel::Medium
+        );
+        // The contribution is capped, so a pile of panics alone stays medium
+        let many = (0..20).map(|_| point(PanicKind::Panic, false)).collect();
+        assert_eq!(calculate_risk(&summary(many)), RiskLevel::Medium);
+    }
 }
@@ -88,6 +88,11 @@ pub struct SymbolInfo {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub arguments: Vec<Argument>,
 
+    /// Type annotation of each argument, parallel to `arguments`
+    /// (`None` for an untyped argument)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub param_types: Vec<Option<String>>,
+
     /// Component props (for components)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub props: Vec<Prop>,
@@ -129,21 +134,147 @@ pub struct SymbolInfo {
     /// Comma-separated names, e.g. "BaseModel,Serializable"
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_classes: Vec<String>,
+
+    /// Documentation comment supplied by an external source (LSP/IDE patches)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_comment: Option<String>,
+
+    /// Feature flags gating this symbol
+    /// Examples: Rust `#[cfg(feature = "beta")]`, JS `flags.isEnabled('beta')`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_flags: Vec<String>,
+
+    /// Generic parameters with their bounds, `where` predicates folded in
+    /// Examples: Rust `'a`, `T: Clone + Send`, `const N: usize`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generic_params: Vec<String>,
+
+    /// Named lifetimes in the signature, in order of first use (`'static`
+    /// and the elided `'_` aren't counted)
+    /// Examples: Rust `'a`, `'b` for `fn pick<'a, 'b>(x: &'a str, y: &'b str) -> &'a str`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lifetime_params: Vec<String>,
+
+    /// Interfaces this type satisfies through its method set
+    /// Examples: Go `Handler` for a type with a matching `ServeHTTP` method
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub implements: Vec<String>,
+
+    /// Whether this symbol is marked deprecated
+    /// Examples: Rust `#[deprecated]`, Java `@Deprecated`, JSDoc `@deprecated`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_deprecated: bool,
+
+    /// Deprecation note, if the marker carries one (e.g. "use bar instead")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation_message: Option<String>,
+
+    /// Number of `await` expressions in this symbol's body
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub awaits_count: usize,
+
+    /// Number of I/O calls made outside any try block
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub io_calls_outside_try: usize,
+
+    /// Whether a catch/except block swallows errors (no calls or state changes)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_empty_catch: bool,
+
+    /// Whether a catch/except block throws (rethrows or wraps the error)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub rethrows: bool,
+
+    /// Places the body can panic or throw (`.unwrap()`, `panic!`, `throw`, `raise`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub panic_points: Vec<PanicPoint>,
+
+    /// SQL statements embedded in string literals, with the tables they touch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedded_queries: Vec<EmbeddedQuery>,
+
+    /// Qualified name of the partial type this symbol is one part of
+    /// Examples: C# `partial class OrderService` in namespace `Shop` -> `Shop.OrderService`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial_of: Option<String>,
+
+    /// Name of the function this closure, callback or nested function is defined in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_symbol: Option<String>,
+
+    /// Position of this symbol under its parent chain, folded into its hash
+    /// (see [`crate::detectors::nested`])
+    /// Examples: `handler:function:2/<anon>:function:1#0`, `<anon>:function:2#1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nested_id: Option<String>,
+}
+
+/// Synthetic name given to an anonymous closure or callback starting at `line`
+pub fn anonymous_symbol_name(line: usize) -> String {
+    format!("<anon@L{}>", line)
 }
 
 impl SymbolInfo {
+    /// Tables touched by the embedded queries, deduplicated and comma-separated
+    /// as stored in the symbol index
+    pub fn embedded_sql_tables(&self) -> String {
+        let mut tables: Vec<&str> = Vec::new();
+        for table in self.embedded_queries.iter().flat_map(|q| &q.tables) {
+            if !tables.iter().any(|t| t.eq_ignore_ascii_case(table)) {
+                tables.push(table);
+            }
+        }
+        tables.join(",")
+    }
+
+    /// Whether this is an anonymous closure or callback (see [`anonymous_symbol_name`])
+    pub fn is_anonymous(&self) -> bool {
+        self.name.starts_with("<anon@L")
+    }
+
+    /// Name used for identity: anonymous symbols drop their line so that
+    /// edits above them don't change their hash
+    pub fn identity_name(&self) -> &str {
+        if self.is_anonymous() {
+            "<anon>"
+        } else {
+            &self.name
+        }
+    }
+
+    /// `name:kind:arity` key of this symbol, the last segment of a nested ID
+    pub fn identity_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.identity_name(),
+            self.kind.as_str(),
+            self.arguments.len() + self.props.len()
+        )
+    }
+
     /// Create a SymbolId for this symbol given a namespace and file path
+    ///
+    /// Parts of a partial type share their namespace, name, kind and arity,
+    /// so their file is folded into the semantic hash to keep them apart.
+    /// Nested symbols fold in their position under the parent chain instead.
     pub fn to_symbol_id(&self, namespace: &str, file_path: &str) -> SymbolId {
         let arity = self.arguments.len() + self.props.len();
-        SymbolId::new(namespace, &self.name, self.kind, arity, file_path)
+        let id = SymbolId::new(namespace, self.identity_name(), self.kind, arity, file_path);
+        if let Some(nested_id) = &self.nested_id {
+            id.with_semantic_suffix(nested_id)
+        } else if self.partial_of.is_some() {
+            id.with_semantic_suffix(file_path)
+        } else {
+            id
+        }
     }
 
     /// Calculate behavioral risk from calls and control flow
     pub fn calculate_risk(&self) -> RiskLevel {
         let mut score = 0;
 
-        // Control flow complexity
-        score += self.control_flow.len().min(3);
+        // Control flow complexity (awaits weigh half)
+        score += (control_flow_weight(&self.control_flow).ceil() as usize).min(3);
 
         // I/O operations
         for call in &self.calls {
@@ -159,6 +290,19 @@ impl SymbolInfo {
             }
         }
 
+        // Unsynchronized state mutation across await points
+        if self.is_async && self.state_changes.iter().any(|s| !s.is_atomic) {
+            score += 1;
+        }
+
+        // Panics and uncaught throws
+        score += panic_weight(&self.panic_points);
+
+        // Explicit lifetimes tie the borrows in the signature together
+        if !self.lifetime_params.is_empty() {
+            score += 1;
+        }
+
         RiskLevel::from_score(score)
     }
 }
@@ -197,6 +341,22 @@ impl SymbolId {
         }
     }
 
+    /// Recompute the semantic hash with `suffix` appended to its input
+    fn with_semantic_suffix(mut self, suffix: &str) -> Self {
+        let semantic_input = format!(
+            "{}:{}:{}:{}:{}",
+            self.namespace,
+            self.symbol,
+            self.kind.as_str(),
+            self.arity,
+            suffix
+        );
+        self.semantic_hash = format!("{:016x}", fnv1a_hash(&semantic_input));
+        let file_hash = self.hash.split(':').next().unwrap_or_default().to_string();
+        self.hash = format!("{}:{}", file_hash, self.semantic_hash);
+        self
+    }
+
     /// Create a SymbolId without file path (for backward compatibility)
     /// NOTE: This produces only a semantic hash, not the two-part format.
     /// Prefer `new()` with file_path when possible.
@@ -316,6 +476,13 @@ pub enum SurfaceDelta {
     SymbolAdded { name: String, kind: SymbolKind },
     /// Symbol removed
     SymbolRemoved { name: String, kind: SymbolKind },
+    /// Generic parameters or their bounds changed; an added bound breaks
+    /// callers whose types don't satisfy it
+    GenericBoundsChanged {
+        symbol: String,
+        before: Vec<String>,
+        after: Vec<String>,
+    },
 }
 
 /// Semantic diff between two versions of a file
@@ -482,6 +649,21 @@ impl SemanticDiff {
                     }
                 }
 
+                // Generic bound changes on symbols present in both versions
+                for after_sym in &after.symbols {
+                    if let Some(before_sym) =
+                        before.symbols.iter().find(|s| s.name == after_sym.name)
+                    {
+                        if before_sym.generic_params != after_sym.generic_params {
+                            deltas.push(SurfaceDelta::GenericBoundsChanged {
+                                symbol: after_sym.name.clone(),
+                                before: before_sym.generic_params.clone(),
+                                after: after_sym.generic_params.clone(),
+                            });
+                        }
+                    }
+                }
+
                 // Check for new persistence/network
                 let before_has_persistence = before.insertions.iter().any(|i| {
                     let l = i.to_lowercase();
@@ -562,6 +744,39 @@ pub struct RepoOverview {
 
     /// Total statistics
     pub stats: RepoStats,
+
+    /// Most recent technical debt score (0-100, lower is better), recorded by
+    /// `analyze --debt-score`; computing it needs the finished index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tech_debt_score: Option<f64>,
+
+    /// Per-package stats of a monorepo workspace (empty outside workspaces)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageStats>,
+}
+
+/// Size and risk of one workspace package
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PackageStats {
+    /// Package namespace (see [`crate::workspace::WorkspacePackage::name`])
+    pub name: String,
+
+    /// Package directory relative to the repo root
+    pub path: String,
+
+    /// Files analyzed
+    pub files: usize,
+
+    /// Symbols extracted
+    pub symbols: usize,
+
+    /// Lines of code in the analyzed files
+    pub loc: usize,
+
+    /// Files per risk level
+    pub high_risk: usize,
+    pub medium_risk: usize,
+    pub low_risk: usize,
 }
 
 /// A group of related files (by directory or purpose)
@@ -608,6 +823,25 @@ pub struct RepoStats {
 
     /// Total React components
     pub components: usize,
+
+    /// Files flagged as generated code
+    #[serde(default)]
+    pub generated_files: usize,
+
+    /// Lines of code in generated files
+    #[serde(default)]
+    pub generated_lines: usize,
+}
+
+impl RepoStats {
+    /// Share of the repository's lines that are generated, as a percentage
+    pub fn generated_loc_pct(&self) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            self.generated_lines as f64 * 100.0 / self.total_lines as f64
+        }
+    }
 }
 
 /// Complete semantic summary of a file
@@ -633,7 +867,7 @@ pub struct SemanticSummary {
     ///
     /// This captures every exported symbol, solving the "single symbol per file"
     /// limitation. Each SymbolInfo contains full semantic data for that symbol.
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub symbols: Vec<SymbolInfo>,
 
     /// Start line of the primary symbol (1-indexed)
@@ -660,9 +894,14 @@ pub struct SemanticSummary {
     pub added_dependencies: Vec<String>,
 
     /// Local file imports (for data flow tracking)
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub local_imports: Vec<String>,
 
+    /// Indexed files this file imports that import it back (see
+    /// [`crate::module_usage::mark_cyclic_imports`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cyclic_imports: Vec<String>,
+
     /// Mapping of imported symbol names to their source packages
     /// e.g., {"useState": "react", "ChevronDown": "lucide-react"}
     /// Used for tracking which external package each external call comes from
@@ -693,9 +932,53 @@ pub struct SemanticSummary {
     #[serde(default, skip_serializing_if = "FrameworkEntryPoint::is_none")]
     pub framework_entry_point: FrameworkEntryPoint,
 
+    /// HTTP routes declared in this file (Express, Next.js, NestJS, FastAPI)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<RouteInfo>,
+
+    /// Scheduled tasks declared in this file (Spring `@Scheduled`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<ScheduleEntry>,
+
+    /// Whether the file is generated code (see [`crate::generated`])
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_generated: bool,
+
     /// Whether extraction was complete
     #[serde(skip)]
     pub extraction_complete: bool,
+
+    /// Import statements (with imported names) for module usage tracking
+    #[serde(skip)]
+    pub imports: Vec<Import>,
+}
+
+impl SemanticSummary {
+    /// The symbol `symbol` is nested in, i.e. the innermost symbol containing
+    /// it that carries its `parent_symbol` name
+    pub fn parent_of(&self, symbol: &SymbolInfo) -> Option<&SymbolInfo> {
+        let parent_name = symbol.parent_symbol.as_deref()?;
+        self.symbols
+            .iter()
+            .filter(|s| {
+                s.name == parent_name
+                    && s.start_line <= symbol.start_line
+                    && s.end_line >= symbol.end_line
+                    && (s.start_line, s.end_line) != (symbol.start_line, symbol.end_line)
+            })
+            .min_by_key(|s| s.end_line - s.start_line)
+    }
+
+    /// Hash of the symbol `symbol` is nested in, or an empty string
+    pub fn parent_hash(&self, symbol: &SymbolInfo) -> String {
+        self.parent_of(symbol)
+            .map(|parent| {
+                parent
+                    .to_symbol_id(&SymbolId::namespace_from_path(&self.file), &self.file)
+                    .hash
+            })
+            .unwrap_or_default()
+    }
 }
 
 /// Kind of symbol being analyzed
@@ -725,6 +1008,20 @@ pub enum SymbolKind {
     TypeAlias,
     /// Variable declaration (module-level constant, static, or class field)
     Variable,
+    /// API contract element (protobuf message/enum/service, OpenAPI path/schema)
+    Contract,
+    /// RPC method or API operation (protobuf rpc, OpenAPI operation)
+    Rpc,
+    /// Macro definition or item-position invocation (Rust `macro_rules!`)
+    Macro,
+    /// Persistent data model (JPA `@Entity`)
+    Entity,
+    /// Data access component (Spring `@Repository`)
+    Repository,
+    /// Config file key, named by its dotted path (`database.url`)
+    ConfigKey,
+    /// Data type defined by its members (C# `record`)
+    Type,
 }
 
 impl SymbolKind {
@@ -742,6 +1039,13 @@ impl SymbolKind {
             Self::Module => "module",
             Self::TypeAlias => "type_alias",
             Self::Variable => "variable",
+            Self::Contract => "contract",
+            Self::Rpc => "rpc",
+            Self::Macro => "macro",
+            Self::Entity => "entity",
+            Self::Repository => "repository",
+            Self::ConfigKey => "config_key",
+            Self::Type => "type",
         }
     }
 
@@ -757,8 +1061,15 @@ impl SymbolKind {
             "struct" => Self::Struct,
             "enum" => Self::Enum,
             "module" | "mod" => Self::Module,
-            "type_alias" | "type" => Self::TypeAlias,
+            "type_alias" | "typealias" => Self::TypeAlias,
+            "type" | "record" => Self::Type,
             "variable" | "var" | "const" | "static" | "field" => Self::Variable,
+            "contract" | "message" => Self::Contract,
+            "rpc" | "operation" => Self::Rpc,
+            "macro" => Self::Macro,
+            "entity" => Self::Entity,
+            "repository" => Self::Repository,
+            "config_key" | "configkey" | "key" => Self::ConfigKey,
             _ => Self::Function, // Default fallback
         }
     }
@@ -825,6 +1136,26 @@ pub enum FrameworkEntryPoint {
     /// Redux selector function
     ReduxSelector,
 
+    // === Python Entry Points ===
+    /// HTTP route handler registered with a decorator (FastAPI `@app.get`)
+    ApiRoute,
+
+    // === Spring Entry Points ===
+    /// Spring MVC request handler (`@GetMapping`, `@RequestMapping`, ...)
+    SpringRoute,
+    /// Spring `@Scheduled` task
+    SpringScheduled,
+
+    // === Laravel Entry Points ===
+    /// Laravel controller action
+    LaravelController,
+    /// Laravel route closure (`Route::get('/users', function () {...})`)
+    LaravelRoute,
+    /// Eloquent model, or a relation, scope or accessor Eloquent calls by name
+    EloquentModel,
+    /// Artisan command handler (`handle` on a `Command`, `Artisan::command` closure)
+    ArtisanCommand,
+
     // === Generic Entry Points ===
     /// CLI entry point (main function)
     CliMain,
@@ -869,6 +1200,13 @@ impl FrameworkEntryPoint {
             Self::ReduxSlice => "Redux Toolkit slice",
             Self::ReduxThunk => "Redux async thunk",
             Self::ReduxSelector => "Redux selector function",
+            Self::ApiRoute => "API route handler",
+            Self::SpringRoute => "Spring request handler",
+            Self::SpringScheduled => "Spring scheduled task",
+            Self::LaravelController => "Laravel controller action",
+            Self::LaravelRoute => "Laravel route closure",
+            Self::EloquentModel => "Eloquent model",
+            Self::ArtisanCommand => "Artisan command handler",
             Self::CliMain => "CLI main entry point",
             Self::TestFunction => "test function",
             Self::PackageExport => "package export",
@@ -920,6 +1258,11 @@ pub struct StateChange {
 
     /// Initializer expression
     pub initializer: String,
+
+    /// Mutation is synchronized: made under a lock (`Mutex::lock()`,
+    /// `RwLock::write()`, Java `synchronized`) or through an atomic type
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_atomic: bool,
 }
 
 /// Control flow change
@@ -937,6 +1280,29 @@ pub struct ControlFlowChange {
     pub nesting_depth: usize,
 }
 
+/// Weighted control flow count used for risk scoring
+pub fn control_flow_weight(control_flow: &[ControlFlowChange]) -> f64 {
+    control_flow.iter().map(|cf| cf.kind.risk_weight()).sum()
+}
+
+/// Risk points for panic/throw points: 1 per point outside a try block and
+/// half for one a try block catches, capped at 2
+pub fn panic_weight<'a>(points: impl IntoIterator<Item = &'a PanicPoint>) -> usize {
+    let weight: f64 = points
+        .into_iter()
+        .map(|p| if p.in_try { 0.5 } else { 1.0 })
+        .sum();
+    (weight.ceil() as usize).min(2)
+}
+
+/// Number of branches and loops, excluding await/yield suspension points
+pub fn branch_count(control_flow: &[ControlFlowChange]) -> usize {
+    control_flow
+        .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
+        .count()
+}
+
 fn is_zero(n: &usize) -> bool {
     *n == 0
 }
@@ -960,6 +1326,10 @@ pub enum ControlFlowKind {
     Try,
     /// Infinite loop (Rust)
     Loop,
+    /// Await on a promise/future (suspension point)
+    Await,
+    /// Generator yield (suspension point)
+    Yield,
 }
 
 impl ControlFlowKind {
@@ -973,6 +1343,24 @@ impl ControlFlowKind {
             Self::Match => "match",
             Self::Try => "try",
             Self::Loop => "loop",
+            Self::Await => "await",
+            Self::Yield => "yield",
+        }
+    }
+
+    /// Whether this is a suspension point rather than a branch or loop
+    ///
+    /// Suspension points don't add nesting and are left out of cognitive
+    /// complexity, but still count toward risk.
+    pub fn is_suspension_point(&self) -> bool {
+        matches!(self, Self::Await | Self::Yield)
+    }
+
+    /// Weight of this construct in risk scoring (an `if` weighs 1.0)
+    pub fn risk_weight(&self) -> f64 {
+        match self {
+            Self::Await => 0.5,
+            _ => 1.0,
         }
     }
 
@@ -986,11 +1374,124 @@ impl ControlFlowKind {
             "match" => Self::Match,
             "try" => Self::Try,
             "loop" => Self::Loop,
+            "await" => Self::Await,
+            "yield" => Self::Yield,
             _ => Self::If, // Default fallback
         }
     }
 }
 
+/// An HTTP route registered with a web framework
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteInfo {
+    /// HTTP method in upper case (`GET`, `POST`, ...), or `ALL` when the
+    /// route accepts any method
+    pub method: String,
+
+    /// URL path, including any controller or router prefix
+    pub path: String,
+
+    /// Function or method handling the route; `None` for inline handlers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
+
+    /// Line of the route declaration (1-indexed)
+    pub line: usize,
+
+    /// Framework declaring the route (`express`, `nextjs`, `nestjs`, `fastapi`)
+    pub framework: String,
+}
+
+impl RouteInfo {
+    /// Create a route; `method` is upper-cased
+    pub fn new(
+        framework: &str,
+        method: &str,
+        path: impl Into<String>,
+        handler: Option<String>,
+        line: usize,
+    ) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            path: path.into(),
+            handler,
+            line,
+            framework: framework.to_string(),
+        }
+    }
+}
+
+/// A task run on a schedule by a framework
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Method run by the scheduler
+    pub handler: String,
+
+    /// Schedule as written, e.g. `cron = "0 0 * * * *"` or `fixedRate = 5000`
+    pub trigger: String,
+
+    /// Line of the schedule declaration (1-indexed)
+    pub line: usize,
+}
+
+/// How a symbol can panic or throw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PanicKind {
+    /// Rust `.unwrap()`
+    Unwrap,
+    /// Rust `.expect(..)`
+    Expect,
+    /// Rust `panic!`, `unreachable!`, `todo!` or `unimplemented!`
+    Panic,
+    /// `throw` (JavaScript/TypeScript, Java, C#)
+    Throw,
+    /// Python `raise`
+    Raise,
+}
+
+impl PanicKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PanicKind::Unwrap => "unwrap",
+            PanicKind::Expect => "expect",
+            PanicKind::Panic => "panic",
+            PanicKind::Throw => "throw",
+            PanicKind::Raise => "raise",
+        }
+    }
+}
+
+/// A place a symbol can panic or throw
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicPoint {
+    /// Where the panic or throw is
+    #[serde(flatten)]
+    pub location: Location,
+
+    /// What panics or throws
+    pub kind: PanicKind,
+
+    /// Whether it is inside a try block (caught, so lower risk)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_try: bool,
+}
+
+/// A SQL statement found in a string literal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddedQuery {
+    /// Where the string literal starts
+    #[serde(flatten)]
+    pub location: Location,
+
+    /// The statement, whitespace collapsed and truncated for display
+    pub query: String,
+
+    /// Tables the statement reads or writes, as written (heuristic)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<String>,
+}
+
 /// Source code location
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
@@ -1066,6 +1567,19 @@ pub struct JsxElement {
     pub location: Location,
 }
 
+/// React hook call, e.g. `useContext(ThemeContext)`
+#[derive(Debug, Clone, Default)]
+pub struct HookCall {
+    /// Hook name (`useContext`, `useTheme`)
+    pub name: String,
+
+    /// First argument when it is an identifier or member path (`ThemeContext`)
+    pub argument: Option<String>,
+
+    /// Source location
+    pub location: Location,
+}
+
 /// Kind of reference for variable tracking
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub enum RefKind {
@@ -1203,11 +1717,11 @@ pub struct Call {
     pub object: Option<String>,
 
     /// Whether this call is awaited
-    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub is_awaited: bool,
 
     /// Whether this call is inside a try block
-    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub in_try: bool,
 
     /// Whether this is a React hook
@@ -1218,6 +1732,11 @@ pub struct Call {
     #[serde(skip)]
     pub is_io: bool,
 
+    /// Whether this is a macro or code-generation directive rather than a
+    /// runtime call (e.g. Go `//go:generate stringer`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_macro: bool,
+
     /// Kind of variable reference (None = function call, Read/Write/ReadWrite = variable)
     #[serde(skip)]
     pub ref_kind: RefKind,
@@ -1275,15 +1794,32 @@ pub struct Import {
 
     /// Whether this is a namespace import
     pub is_namespace: bool,
+
+    /// How the module is pulled in (import, re-export, dynamic import)
+    pub kind: ImportKind,
+}
+
+/// How an import statement pulls in its module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// `import ... from`, `require(...)`
+    #[default]
+    Static,
+    /// `export ... from` (barrel files)
+    ReExport,
+    /// `import(...)`; a source ending in `*` is a template prefix
+    Dynamic,
 }
 
 /// Individual imported name
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ImportedName {
     /// Original name
     pub name: String,
 
     /// Alias (if renamed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alias: Option<String>,
 }
 
@@ -1301,6 +1837,35 @@ mod tests {
         assert_eq!(RiskLevel::from_score(10), RiskLevel::High);
     }
 
+    #[test]
+    fn test_semantic_diff_generic_bounds_changed() {
+        let summary = |params: &[&str]| SemanticSummary {
+            file: "src/lib.rs".to_string(),
+            symbols: vec![SymbolInfo {
+                name: "first".to_string(),
+                kind: SymbolKind::Function,
+                generic_params: params.iter().map(|p| p.to_string()).collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let before = summary(&["T"]);
+        let after = summary(&["T: Clone"]);
+        let diff = SemanticDiff::from_summaries(Some(&before), &after);
+        assert_eq!(
+            diff.deltas,
+            vec![SurfaceDelta::GenericBoundsChanged {
+                symbol: "first".to_string(),
+                before: vec!["T".to_string()],
+                after: vec!["T: Clone".to_string()],
+            }]
+        );
+
+        let unchanged = SemanticDiff::from_summaries(Some(&after), &after);
+        assert!(unchanged.deltas.is_empty());
+    }
+
     #[test]
     fn test_call_is_hook() {
         assert!(Call::check_is_hook("useState"));
@@ -1332,6 +1897,8 @@ mod tests {
         assert_eq!(ControlFlowKind::If.as_str(), "if");
         assert_eq!(ControlFlowKind::For.as_str(), "for");
         assert_eq!(ControlFlowKind::Match.as_str(), "match");
+        assert_eq!(ControlFlowKind::Await.as_str(), "await");
+        assert_eq!(ControlFlowKind::from_str("yield"), ControlFlowKind::Yield);
     }
 
     #[test]
@@ -1537,6 +2104,7 @@ mod tests {
                 name: "open".to_string(),
                 state_type: "boolean".to_string(),
                 initializer: "false".to_string(),
+                is_atomic: false,
             }],
             behavioral_risk: RiskLevel::Medium,
             ..Default::default()
@@ -1576,6 +2144,7 @@ mod tests {
                 name: "open".to_string(),
                 state_type: "boolean".to_string(),
                 initializer: "false".to_string(),
+                is_atomic: false,
             }],
             behavioral_risk: RiskLevel::Low,
             ..Default::default()
@@ -1592,11 +2161,13 @@ mod tests {
                     name: "open".to_string(),
                     state_type: "boolean".to_string(),
                     initializer: "false".to_string(),
+                    is_atomic: false,
                 },
                 StateChange {
                     name: "count".to_string(),
                     state_type: "number".to_string(),
                     initializer: "0".to_string(),
+                    is_atomic: false,
                 },
             ],
             control_flow_changes: vec![ControlFlowChange {
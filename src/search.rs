@@ -1,10 +1,15 @@
 //! Search hints for filtering queries
 //!
 //! This module provides filtering capabilities for symbol and file searches
-//! based on extension, directory, file pattern, and programming language.
+//! based on extension, directory, file pattern, and programming language,
+//! plus per-symbol facet filters (`search --facet`).
+
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
+use crate::cache::SymbolIndexEntry;
+
 /// Search hints for filtering queries
 ///
 /// Supports filtering by extension, directory, file pattern, and language.
@@ -145,8 +150,8 @@ pub fn lang_from_extension(path: &str) -> Option<String> {
         "py" | "pyi" | "pyw" => "python",
         // JavaScript
         "js" | "mjs" | "cjs" | "jsx" => "javascript",
-        // TypeScript
-        "ts" | "mts" | "cts" | "tsx" => "typescript",
+        // TypeScript (MDX embeds JSX, parsed as TSX)
+        "ts" | "mts" | "cts" | "tsx" | "mdx" => "typescript",
         // Go
         "go" => "go",
         // Java
@@ -162,7 +167,7 @@ pub fn lang_from_extension(path: &str) -> Option<String> {
         // Ruby
         "rb" => "ruby",
         // PHP
-        "php" => "php",
+        "php" | "phtml" => "php",
         // Swift
         "swift" => "swift",
         // Scala
@@ -189,6 +194,12 @@ pub fn lang_from_extension(path: &str) -> Option<String> {
         "vue" => "vue",
         // Svelte
         "svelte" => "svelte",
+        // GraphQL
+        "graphql" | "gql" => "graphql",
+        // Protocol Buffers
+        "proto" => "protobuf",
+        // Solidity
+        "sol" => "solidity",
         _ => return None,
     };
 
@@ -293,6 +304,133 @@ pub fn is_test_file(path: &str) -> bool {
     false
 }
 
+// ============================================================================
+// Facet Filters
+// ============================================================================
+
+/// Facets a symbol can be filtered on with `search --facet`
+pub const FACET_NAMES: &[&str] = &[
+    "is_async",
+    "awaits_count",
+    "io_calls_outside_try",
+    "has_empty_catch",
+    "rethrows",
+    "panic_points",
+];
+
+/// Comparison in a facet filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FacetOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// A filter on one symbol facet, e.g. `io_calls_outside_try>0` or `has_empty_catch`
+///
+/// Boolean facets compare as 0/1 and accept `true`/`false`; a bare facet name
+/// means "is set" (`>0`).
+///
+/// # Example
+///
+/// ```
+/// use semfora_engine::search::FacetFilter;
+///
+/// let filter: FacetFilter = "io_calls_outside_try>0".parse().unwrap();
+/// assert_eq!(filter.facet, "io_calls_outside_try");
+/// assert!("unknown>1".parse::<FacetFilter>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetFilter {
+    /// Facet name (one of [`FACET_NAMES`])
+    pub facet: String,
+    pub op: FacetOp,
+    pub value: usize,
+}
+
+impl FacetFilter {
+    /// Whether `entry` passes this filter
+    pub fn matches(&self, entry: &SymbolIndexEntry) -> bool {
+        let actual = match self.facet.as_str() {
+            "is_async" => entry.is_async as usize,
+            "awaits_count" => entry.awaits_count,
+            "io_calls_outside_try" => entry.io_calls_outside_try,
+            "has_empty_catch" => entry.has_empty_catch as usize,
+            "rethrows" => entry.rethrows as usize,
+            "panic_points" => entry.panic_points,
+            _ => return false,
+        };
+        match self.op {
+            FacetOp::Eq => actual == self.value,
+            FacetOp::Ne => actual != self.value,
+            FacetOp::Gt => actual > self.value,
+            FacetOp::Ge => actual >= self.value,
+            FacetOp::Lt => actual < self.value,
+            FacetOp::Le => actual <= self.value,
+        }
+    }
+}
+
+impl std::fmt::Display for FacetFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self.op {
+            FacetOp::Eq => "=",
+            FacetOp::Ne => "!=",
+            FacetOp::Gt => ">",
+            FacetOp::Ge => ">=",
+            FacetOp::Lt => "<",
+            FacetOp::Le => "<=",
+        };
+        write!(f, "{}{}{}", self.facet, op, self.value)
+    }
+}
+
+impl FromStr for FacetFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Two-character operators first so `>=` isn't read as `>`
+        let ops = [
+            (">=", FacetOp::Ge),
+            ("<=", FacetOp::Le),
+            ("!=", FacetOp::Ne),
+            ("=", FacetOp::Eq),
+            (">", FacetOp::Gt),
+            ("<", FacetOp::Lt),
+        ];
+        let (facet, op, value) = ops
+            .iter()
+            .find_map(|(token, op)| {
+                let (facet, value) = s.split_once(token)?;
+                Some((facet.trim(), *op, value.trim()))
+            })
+            .unwrap_or((s.trim(), FacetOp::Gt, "0"));
+
+        if !FACET_NAMES.contains(&facet) {
+            return Err(format!(
+                "unknown facet '{}' (expected one of: {})",
+                facet,
+                FACET_NAMES.join(", ")
+            ));
+        }
+        let value = match value {
+            "true" => 1,
+            "false" => 0,
+            n => n
+                .parse()
+                .map_err(|_| format!("invalid value '{}' for facet '{}'", n, facet))?,
+        };
+        Ok(Self {
+            facet: facet.to_string(),
+            op,
+            value,
+        })
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -418,6 +556,30 @@ mod tests {
         assert_eq!(lang_from_extension("foo.unknown"), None);
     }
 
+    #[test]
+    fn test_lang_from_extension_additional() {
+        let cases = [
+            ("src/index.mts", "typescript"),
+            ("src/index.cts", "typescript"),
+            ("src/index.mjs", "javascript"),
+            ("src/index.cjs", "javascript"),
+            ("docs/intro.mdx", "typescript"),
+            ("schema/user.graphql", "graphql"),
+            ("schema/user.gql", "graphql"),
+            ("proto/user.proto", "protobuf"),
+            ("contracts/Token.sol", "solidity"),
+            ("src/App.svelte", "svelte"),
+            ("SRC/APP.GQL", "graphql"),
+        ];
+        for (path, lang) in cases {
+            assert_eq!(lang_from_extension(path).as_deref(), Some(lang), "{}", path);
+        }
+        assert_eq!(lang_from_extension("Makefile"), None);
+        assert!(SearchHints::new()
+            .with_lang("graphql")
+            .matches("schema/user.gql"));
+    }
+
     // ========================================================================
     // Test file detection tests
     // ========================================================================
@@ -515,4 +677,26 @@ mod tests {
         assert!(is_test_file("test_main.c"));
         assert!(is_test_file("parser_test.cc"));
     }
+
+    #[test]
+    fn test_facet_filter_parse() {
+        let filter: FacetFilter = "io_calls_outside_try>0".parse().unwrap();
+        assert_eq!(filter.op, FacetOp::Gt);
+        assert_eq!(filter.value, 0);
+
+        let filter: FacetFilter = "awaits_count >= 2".parse().unwrap();
+        assert_eq!(filter.facet, "awaits_count");
+        assert_eq!(filter.op, FacetOp::Ge);
+        assert_eq!(filter.value, 2);
+
+        let filter: FacetFilter = "has_empty_catch=false".parse().unwrap();
+        assert_eq!((filter.op, filter.value), (FacetOp::Eq, 0));
+
+        // A bare name means "is set"
+        let filter: FacetFilter = "rethrows".parse().unwrap();
+        assert_eq!((filter.op, filter.value), (FacetOp::Gt, 0));
+
+        assert!("cognitive>1".parse::<FacetFilter>().is_err());
+        assert!("awaits_count>many".parse::<FacetFilter>().is_err());
+    }
 }
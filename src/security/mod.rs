@@ -329,6 +329,19 @@ impl PatternDatabase {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
         bincode::deserialize(bytes)
     }
+
+    /// Load the database embedded in the binary at build time
+    ///
+    /// That is the database compiled by `semfora-security-compiler` when the
+    /// build set `SECURITY_PATTERNS_PATH`, and otherwise one built from the
+    /// manually curated patterns, so scanning works out of the box.
+    pub fn load_embedded() -> crate::error::Result<Self> {
+        let bytes = patterns::embedded::EMBEDDED_PATTERNS;
+        if bytes.is_empty() {
+            return Ok(Self::from_patterns(patterns::manual::all_patterns()));
+        }
+        Ok(Self::from_bytes(bytes)?)
+    }
 }
 
 /// A CVE pattern match result
@@ -358,6 +371,10 @@ pub struct CVEMatch {
     /// Function name that matched
     pub function: String,
 
+    /// Hash of the matched function, for `get_symbol` and `find_duplicates`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_hash: Option<String>,
+
     /// Line number in the file
     pub line: u32,
 }
@@ -5,19 +5,22 @@
 //!
 //! Runtime updates are supported via HTTP fetch from a pattern server.
 //! Set `SEMFORA_PATTERN_URL` environment variable to enable automatic updates.
+//! Updated databases are installed on disk (see [`super::store`]) and
+//! preferred over the embedded one.
 
 use crate::error::McpDiffError;
+use crate::security::patterns::store::{FetchOutcome, PatternStore, UpdateStatus};
 use crate::security::PatternDatabase;
 use once_cell::sync::Lazy;
+use std::path::Path;
 use std::sync::RwLock;
 
-/// Embedded pattern database bytes (set by build.rs if available)
-/// If not present, an empty database is used
-#[cfg(feature = "embedded-patterns")]
-static EMBEDDED_PATTERNS: &[u8] = include_bytes!(env!("SECURITY_PATTERNS_PATH"));
-
-#[cfg(not(feature = "embedded-patterns"))]
-static EMBEDDED_PATTERNS: &[u8] = &[];
+/// Compiled pattern database bytes written by build.rs
+///
+/// Empty unless the build set `SECURITY_PATTERNS_PATH`; the manually curated
+/// patterns are used then.
+pub(crate) static EMBEDDED_PATTERNS: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/embedded_patterns.bin"));
 
 /// Cached pattern database
 static PATTERN_DB: Lazy<RwLock<Option<PatternDatabase>>> = Lazy::new(|| RwLock::new(None));
@@ -43,9 +46,11 @@ pub struct PatternUpdateResult {
     pub message: String,
 }
 
-/// Load the embedded pattern database
+/// Load the pattern database
 ///
-/// Returns an empty database if no patterns are embedded.
+/// Prefers the database installed by `security update`, then the embedded
+/// one (see [`PatternDatabase::load_embedded`]); returns an empty database
+/// if the embedded one can't be read.
 /// Caches the result for subsequent calls.
 pub fn load_embedded_patterns() -> PatternDatabase {
     // Check cache first
@@ -56,12 +61,15 @@ pub fn load_embedded_patterns() -> PatternDatabase {
         }
     }
 
-    // Load from embedded bytes
-    let db = if EMBEDDED_PATTERNS.is_empty() {
-        tracing::info!("No embedded security patterns, using empty database");
-        PatternDatabase::new()
+    // Load the installed database, then the embedded bytes
+    let db = if let Some(db) = PatternStore::open().load() {
+        tracing::info!(
+            "Loaded {} security patterns from installed database",
+            db.len()
+        );
+        db
     } else {
-        match PatternDatabase::from_bytes(EMBEDDED_PATTERNS) {
+        match PatternDatabase::load_embedded() {
             Ok(db) => {
                 tracing::info!(
                     "Loaded {} security patterns from embedded database",
@@ -92,7 +100,9 @@ pub fn load_patterns_from_file(path: &std::path::Path) -> crate::error::Result<P
     Ok(db)
 }
 
-/// Check if embedded patterns are available
+/// Check if a compiled pattern database is embedded
+///
+/// Without one, the manually curated patterns are loaded instead.
 pub fn has_embedded_patterns() -> bool {
     !EMBEDDED_PATTERNS.is_empty()
 }
@@ -122,17 +132,20 @@ pub fn get_pattern_url() -> Option<String> {
 
 /// Fetch pattern updates from a URL
 ///
-/// This function fetches the pattern database from the specified URL,
-/// validates it, and atomically swaps it with the current database.
+/// Downloads, verifies and installs the database via
+/// [`PatternStore::fetch`], then swaps it in. The previous database is kept
+/// for `security rollback`.
 ///
 /// # Arguments
 /// * `url` - Optional URL to fetch from. If None, uses SEMFORA_PATTERN_URL env var or default.
-/// * `force` - If true, updates even if versions match
+/// * `checksum` - Expected SHA-256 of the database (hex); fetched from `<url>.sha256` when None
+/// * `force` - If true, checks and updates even if the checksum matches the installed database
 ///
 /// # Returns
 /// `PatternUpdateResult` describing what happened
 pub async fn fetch_pattern_updates(
     url: Option<&str>,
+    checksum: Option<&str>,
     force: bool,
 ) -> Result<PatternUpdateResult, McpDiffError> {
     let fetch_url = url
@@ -143,52 +156,27 @@ pub async fn fetch_pattern_updates(
     tracing::info!("Fetching security patterns from: {}", fetch_url);
 
     // Get current version for comparison
-    let previous_version = current_patterns_version();
-
-    // Fetch the pattern database
-    let client = reqwest::Client::builder()
-        .user_agent("semfora-engine/0.1.0")
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| McpDiffError::Generic(format!("Failed to create HTTP client: {}", e)))?;
-
-    let response = client
-        .get(&fetch_url)
-        .send()
-        .await
-        .map_err(|e| McpDiffError::Generic(format!("Failed to fetch patterns: {}", e)))?;
-
-    if !response.status().is_success() {
-        return Err(McpDiffError::Generic(format!(
-            "Pattern server returned HTTP {}: {}",
-            response.status(),
-            fetch_url
-        )));
-    }
-
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| McpDiffError::Generic(format!("Failed to read response: {}", e)))?;
-
-    // Deserialize and validate
-    let new_db = PatternDatabase::from_bytes(&bytes)
-        .map_err(|e| McpDiffError::Generic(format!("Invalid pattern database: {}", e)))?;
+    let current = load_embedded_patterns();
+    let previous_version = Some(current.version.clone());
 
+    let new_db = match PatternStore::open()
+        .fetch(&fetch_url, checksum, force)
+        .await?
+    {
+        FetchOutcome::Installed(db) => db,
+        FetchOutcome::Skipped(message) => {
+            return Ok(PatternUpdateResult {
+                updated: false,
+                previous_version,
+                current_version: current.version.clone(),
+                pattern_count: current.len(),
+                message,
+            });
+        }
+    };
     let new_version = new_db.version.clone();
     let pattern_count = new_db.len();
 
-    // Check if update is needed
-    if !force && previous_version.as_ref() == Some(&new_version) {
-        return Ok(PatternUpdateResult {
-            updated: false,
-            previous_version: previous_version.clone(),
-            current_version: new_version,
-            pattern_count,
-            message: "Patterns already up to date".to_string(),
-        });
-    }
-
     // Atomic swap of the pattern database
     {
         let mut cache = PATTERN_DB.write().unwrap();
@@ -213,15 +201,16 @@ pub async fn fetch_pattern_updates(
 
 /// Update patterns from a local file
 ///
-/// Useful for offline environments or testing.
+/// Useful for offline environments or testing. The file is verified like a
+/// download (against `checksum` when given) and installed, keeping the
+/// previous database for rollback.
 pub fn update_patterns_from_file(
-    path: &std::path::Path,
+    path: &Path,
+    checksum: Option<&str>,
 ) -> Result<PatternUpdateResult, McpDiffError> {
-    let previous_version = current_patterns_version();
-
-    let bytes = std::fs::read(path)?;
-    let new_db = PatternDatabase::from_bytes(&bytes)?;
+    let previous_version = Some(load_embedded_patterns().version);
 
+    let new_db = PatternStore::open().import(path, checksum)?;
     let new_version = new_db.version.clone();
     let pattern_count = new_db.len();
 
@@ -245,6 +234,35 @@ pub fn update_patterns_from_file(
     })
 }
 
+/// Restore the database replaced by the last update
+pub fn rollback_patterns() -> Result<PatternUpdateResult, McpDiffError> {
+    let previous_version = Some(load_embedded_patterns().version);
+    let store = PatternStore::open();
+    let status = store.rollback()?;
+    let db = store.load().ok_or_else(|| {
+        McpDiffError::Generic("Restored pattern database could not be read".to_string())
+    })?;
+    let current_version = db.version.clone();
+    let pattern_count = db.len();
+
+    {
+        let mut cache = PATTERN_DB.write().unwrap();
+        *cache = Some(db);
+    }
+
+    Ok(PatternUpdateResult {
+        updated: true,
+        previous_version,
+        current_version,
+        pattern_count,
+        message: format!(
+            "Rolled back to {} patterns from {}",
+            pattern_count,
+            status.current.map(|c| c.source).unwrap_or_default()
+        ),
+    })
+}
+
 /// Update patterns from raw bytes
 ///
 /// Used when patterns are received through other means (e.g., embedded in CI artifacts).
@@ -272,6 +290,8 @@ pub fn update_patterns_from_bytes(bytes: &[u8]) -> Result<PatternUpdateResult, M
 
 /// Get pattern database statistics
 pub fn pattern_stats() -> PatternStats {
+    let store = PatternStore::open();
+    let installed = store.database_path().exists();
     let cache = PATTERN_DB.read().unwrap();
     match cache.as_ref() {
         Some(db) => PatternStats {
@@ -281,11 +301,15 @@ pub fn pattern_stats() -> PatternStats {
             pattern_count: db.len(),
             cwe_count: db.cwe_index.len(),
             language_count: db.lang_index.len(),
-            source: if has_embedded_patterns() {
+            source: if installed {
+                PatternSource::Installed
+            } else if has_embedded_patterns() {
                 PatternSource::Embedded
             } else {
                 PatternSource::Runtime
             },
+            update: store.status().current,
+            rollback_available: store.previous_path().exists(),
         },
         None => PatternStats {
             loaded: false,
@@ -295,6 +319,8 @@ pub fn pattern_stats() -> PatternStats {
             cwe_count: 0,
             language_count: 0,
             source: PatternSource::None,
+            update: None,
+            rollback_available: false,
         },
     }
 }
@@ -309,6 +335,10 @@ pub struct PatternStats {
     pub cwe_count: usize,
     pub language_count: usize,
     pub source: PatternSource,
+    /// Where the installed database came from, if one is installed
+    pub update: Option<UpdateStatus>,
+    /// Whether `security rollback` has a previous database to restore
+    pub rollback_available: bool,
 }
 
 /// Source of the pattern database
@@ -318,6 +348,8 @@ pub enum PatternSource {
     None,
     /// Patterns embedded at build time
     Embedded,
+    /// Database installed by `security update`
+    Installed,
     /// Patterns loaded at runtime (file or HTTP)
     Runtime,
 }
@@ -334,6 +366,20 @@ mod tests {
         assert!(db.len() >= 0);
     }
 
+    #[test]
+    fn test_load_embedded_has_manual_patterns() {
+        let db = PatternDatabase::load_embedded().expect("embedded patterns load");
+        assert!(!db.is_empty());
+        assert!(!db.patterns_for_cwe("CWE-89").is_empty());
+        if !has_embedded_patterns() {
+            assert_eq!(db.len(), crate::security::patterns::all_patterns().len());
+        }
+        assert_eq!(
+            env!("SEMFORA_PATTERNS_SIZE"),
+            EMBEDDED_PATTERNS.len().to_string()
+        );
+    }
+
     #[test]
     fn test_has_embedded_patterns() {
         // Should work regardless of feature flag
@@ -3,15 +3,17 @@
 //! This module provides:
 //! - Embedded pattern database (loaded from binary at runtime)
 //! - Manually curated patterns for high-profile CVEs
-//! - Runtime pattern updates via HTTP fetch
+//! - Runtime pattern updates via HTTP fetch, installed on disk with rollback
 
 pub mod embedded;
 pub mod manual;
+pub mod store;
 
 pub use embedded::{
     current_patterns_version, embedded_patterns_version, fetch_pattern_updates,
-    has_embedded_patterns, load_embedded_patterns, pattern_stats, update_patterns_from_bytes,
-    update_patterns_from_file, PatternSource, PatternStats, PatternUpdateResult,
-    DEFAULT_PATTERN_URL, PATTERN_URL_ENV,
+    has_embedded_patterns, load_embedded_patterns, pattern_stats, rollback_patterns,
+    update_patterns_from_bytes, update_patterns_from_file, PatternSource, PatternStats,
+    PatternUpdateResult, DEFAULT_PATTERN_URL, PATTERN_URL_ENV,
 };
 pub use manual::all_patterns;
+pub use store::{FetchOutcome, PatternStore, StoreStatus, UpdateStatus, MIN_UPDATE_INTERVAL_SECS};
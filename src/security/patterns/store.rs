@@ -0,0 +1,731 @@
+//! On-disk pattern database installed by `security update`
+//!
+//! Downloaded databases live in the user cache (`<cache>/security/`), shared
+//! by every repository:
+//! - `security_patterns.bin` - the active database, preferred over the
+//!   embedded one
+//! - `security_patterns.prev.bin` - the database it replaced, restored by
+//!   `security rollback`
+//! - `security_patterns.part` - an interrupted download, resumed by the next
+//!   update
+//! - `update_status.json` - where the active and previous databases came from
+//!
+//! A database is only moved into place after its checksum and contents were
+//! verified, so a failed update never replaces the active one.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{McpDiffError, Result};
+use crate::fs_utils;
+use crate::security::PatternDatabase;
+
+/// Minimum time between checks of the same pattern server, unless forced
+pub const MIN_UPDATE_INTERVAL_SECS: i64 = 3600;
+
+/// Provenance of an installed pattern database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateStatus {
+    /// URL or file the database was installed from
+    pub source: String,
+    /// SHA-256 of the database file (lowercase hex)
+    pub checksum: String,
+    /// Database version
+    pub version: String,
+    /// Number of patterns in the database
+    pub pattern_count: usize,
+    /// When the database was fetched (RFC 3339)
+    pub fetched_at: String,
+}
+
+/// Status of the active database and the rollback target
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<UpdateStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<UpdateStatus>,
+    /// Server last checked for updates, and when (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_check: Option<(String, String)>,
+}
+
+impl StoreStatus {
+    /// Seconds since `url` was last checked for updates
+    pub fn last_checked(&self, url: &str) -> Option<i64> {
+        let (checked_url, at) = self.last_check.as_ref()?;
+        if checked_url != url {
+            return None;
+        }
+        let at = chrono::DateTime::parse_from_rfc3339(at).ok()?;
+        Some((chrono::Utc::now() - at.with_timezone(&chrono::Utc)).num_seconds())
+    }
+}
+
+/// Result of [`PatternStore::fetch`]
+#[derive(Debug)]
+pub enum FetchOutcome {
+    /// A verified database was downloaded and installed
+    Installed(PatternDatabase),
+    /// Nothing was downloaded, for the given reason
+    Skipped(String),
+}
+
+/// Directory holding the installed pattern database
+#[derive(Debug, Clone)]
+pub struct PatternStore {
+    dir: PathBuf,
+}
+
+impl PatternStore {
+    /// Store under the user cache directory
+    pub fn open() -> Self {
+        Self::at(fs_utils::get_cache_base_dir().join("security"))
+    }
+
+    /// Store in a specific directory
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Active database
+    pub fn database_path(&self) -> PathBuf {
+        self.dir.join("security_patterns.bin")
+    }
+
+    /// Database replaced by the last install, restored by [`Self::rollback`]
+    pub fn previous_path(&self) -> PathBuf {
+        self.dir.join("security_patterns.prev.bin")
+    }
+
+    /// Partial download kept for resuming
+    pub fn partial_path(&self) -> PathBuf {
+        self.dir.join("security_patterns.part")
+    }
+
+    fn status_path(&self) -> PathBuf {
+        self.dir.join("update_status.json")
+    }
+
+    /// Status of the installed databases (default when nothing was installed)
+    pub fn status(&self) -> StoreStatus {
+        fs::read_to_string(self.status_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// The active database, if one is installed and readable
+    pub fn load(&self) -> Option<PatternDatabase> {
+        let bytes = fs::read(self.database_path()).ok()?;
+        match PatternDatabase::from_bytes(&bytes) {
+            Ok(db) => Some(db),
+            Err(e) => {
+                tracing::error!(
+                    "Ignoring unreadable pattern database {}: {}",
+                    self.database_path().display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Move a verified database file into place
+    ///
+    /// The active database becomes the rollback target. `file` is renamed,
+    /// so it must be on the same filesystem (e.g. [`Self::partial_path`]).
+    pub fn install(&self, file: &Path, status: UpdateStatus) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut store_status = self.status();
+        if self.database_path().exists() {
+            fs_utils::atomic_rename(&self.database_path(), &self.previous_path())?;
+            store_status.previous = store_status.current.take();
+        }
+        fs_utils::atomic_rename(file, &self.database_path())?;
+        store_status.current = Some(status);
+        self.write_status(&store_status)
+    }
+
+    /// Download, verify and install the database published at `url`
+    ///
+    /// The download must match a SHA-256 checksum, given as `checksum` or
+    /// published beside the database as `<url>.sha256`. It goes to
+    /// [`Self::partial_path`] first; an interrupted download is resumed with
+    /// an HTTP `Range` request on the next fetch, and a download failing
+    /// verification is discarded without touching the active database. The
+    /// same server is checked at most once per [`MIN_UPDATE_INTERVAL_SECS`],
+    /// and a database matching the installed checksum isn't downloaded
+    /// again, unless `force`.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        checksum: Option<&str>,
+        force: bool,
+    ) -> Result<FetchOutcome> {
+        let status = self.status();
+        if !force {
+            if let Some(elapsed) = status
+                .last_checked(url)
+                .filter(|elapsed| *elapsed < MIN_UPDATE_INTERVAL_SECS)
+            {
+                return Ok(FetchOutcome::Skipped(format!(
+                    "Checked {} minutes ago; use --force to check again",
+                    elapsed / 60
+                )));
+            }
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("semfora-engine/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .read_timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| McpDiffError::Generic(format!("Failed to create HTTP client: {}", e)))?;
+
+        let expected = match checksum {
+            Some(checksum) => expect_checksum(checksum)?,
+            None => fetch_checksum(&client, url).await?,
+        };
+
+        // Nothing to download when the installed database already matches
+        let up_to_date = status.current.is_some_and(|c| c.checksum == expected);
+        if up_to_date && !force {
+            self.record_check(url)?;
+            return Ok(FetchOutcome::Skipped(
+                "Patterns already up to date".to_string(),
+            ));
+        }
+
+        let partial = self.partial_path();
+        fs::create_dir_all(&self.dir)?;
+        download_resumable(&client, url, &partial).await?;
+
+        let db = match verify(&partial, &expected) {
+            Ok(db) => db,
+            Err(e) => {
+                // A corrupted partial file can't be resumed into a valid one
+                let _ = fs::remove_file(&partial);
+                return Err(e);
+            }
+        };
+        self.install(
+            &partial,
+            UpdateStatus {
+                source: url.to_string(),
+                checksum: expected,
+                version: db.version.clone(),
+                pattern_count: db.len(),
+                fetched_at: chrono::Utc::now().to_rfc3339(),
+            },
+        )?;
+        self.record_check(url)?;
+        Ok(FetchOutcome::Installed(db))
+    }
+
+    /// Verify and install a database file from disk
+    ///
+    /// The file is checked against `checksum` when given and copied, so the
+    /// original stays where it is.
+    pub fn import(&self, path: &Path, checksum: Option<&str>) -> Result<PatternDatabase> {
+        let actual = sha256_file(path)?;
+        let expected = match checksum {
+            Some(checksum) => expect_checksum(checksum)?,
+            None => actual.clone(),
+        };
+        let db = verify(path, &expected)?;
+
+        fs::create_dir_all(&self.dir)?;
+        let staged = self.dir.join("security_patterns.import");
+        fs::copy(path, &staged)?;
+        self.install(
+            &staged,
+            UpdateStatus {
+                source: path.display().to_string(),
+                checksum: actual,
+                version: db.version.clone(),
+                pattern_count: db.len(),
+                fetched_at: chrono::Utc::now().to_rfc3339(),
+            },
+        )?;
+        Ok(db)
+    }
+
+    /// Remember that `url` was checked for updates just now
+    pub fn record_check(&self, url: &str) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut status = self.status();
+        status.last_check = Some((url.to_string(), chrono::Utc::now().to_rfc3339()));
+        self.write_status(&status)
+    }
+
+    /// Swap the active database with the one it replaced
+    ///
+    /// Rolling back twice restores the newer database.
+    pub fn rollback(&self) -> Result<StoreStatus> {
+        if !self.previous_path().exists() {
+            return Err(McpDiffError::Generic(
+                "No previous pattern database to roll back to".to_string(),
+            ));
+        }
+        let swap = self.dir.join("security_patterns.swap");
+        fs_utils::atomic_rename(&self.previous_path(), &swap)?;
+        if self.database_path().exists() {
+            fs_utils::atomic_rename(&self.database_path(), &self.previous_path())?;
+        }
+        fs_utils::atomic_rename(&swap, &self.database_path())?;
+
+        let mut status = self.status();
+        std::mem::swap(&mut status.current, &mut status.previous);
+        self.write_status(&status)?;
+        Ok(status)
+    }
+
+    fn write_status(&self, status: &StoreStatus) -> Result<()> {
+        let content = serde_json::to_string_pretty(status)
+            .map_err(|e| McpDiffError::Serialization(e.to_string()))?;
+        let temp = self.dir.join("update_status.json.tmp");
+        fs::write(&temp, content)?;
+        fs_utils::atomic_rename(&temp, &self.status_path())?;
+        Ok(())
+    }
+}
+
+/// Check a downloaded database against its checksum and this engine version
+fn verify(path: &Path, expected: &str) -> Result<PatternDatabase> {
+    let actual = sha256_file(path)?;
+    if actual != expected {
+        return Err(McpDiffError::Generic(format!(
+            "Checksum mismatch for {}: expected {}, got {}; the current patterns were kept",
+            path.display(),
+            expected,
+            actual
+        )));
+    }
+    let db = PatternDatabase::from_bytes(&fs::read(path)?)
+        .map_err(|e| McpDiffError::Generic(format!("Invalid pattern database: {}", e)))?;
+    if !is_compatible_version(&db.version) {
+        return Err(McpDiffError::Generic(format!(
+            "Pattern database version {} is incompatible with semfora-engine {}",
+            db.version,
+            env!("CARGO_PKG_VERSION")
+        )));
+    }
+    Ok(db)
+}
+
+/// Parse a user-supplied checksum
+fn expect_checksum(checksum: &str) -> Result<String> {
+    parse_checksum(checksum)
+        .ok_or_else(|| McpDiffError::Generic(format!("Invalid SHA-256 checksum: {}", checksum)))
+}
+
+/// Fetch the checksum published at `<url>.sha256`
+async fn fetch_checksum(client: &reqwest::Client, url: &str) -> Result<String> {
+    let checksum_url = format!("{}.sha256", url);
+    let response = client.get(&checksum_url).send().await.map_err(|e| {
+        McpDiffError::Generic(format!("Failed to fetch checksum {}: {}", checksum_url, e))
+    })?;
+    check_status(&response, &checksum_url)?;
+    let text = response
+        .text()
+        .await
+        .map_err(|e| McpDiffError::Generic(format!("Failed to read checksum: {}", e)))?;
+    parse_checksum(&text).ok_or_else(|| {
+        McpDiffError::Generic(format!(
+            "{} is not a SHA-256 checksum; pass --checksum to verify the download",
+            checksum_url
+        ))
+    })
+}
+
+/// Download `url` into `partial`, resuming from what's already there
+async fn download_resumable(client: &reqwest::Client, url: &str, partial: &Path) -> Result<()> {
+    let offset = fs::metadata(partial).map(|m| m.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if offset > 0 {
+        tracing::info!("Resuming pattern download at byte {}", offset);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| McpDiffError::Generic(format!("Failed to fetch patterns: {}", e)))?;
+
+    // The partial file already holds the whole database
+    if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+    check_status(&response, url)?;
+
+    // A server ignoring the range sends the whole file again
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(partial)?;
+
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => file.write_all(&chunk)?,
+            Ok(None) => break,
+            Err(e) => {
+                file.flush()?;
+                return Err(McpDiffError::Generic(format!(
+                    "Pattern download interrupted ({}); run the update again to resume",
+                    e
+                )));
+            }
+        }
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Turn error statuses (including rate limiting) into errors
+fn check_status(response: &reqwest::Response, url: &str) -> Result<()> {
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| format!("; retry after {}s", v))
+            .unwrap_or_default();
+        return Err(McpDiffError::Generic(format!(
+            "Pattern server is rate limiting requests{}: {}",
+            retry_after, url
+        )));
+    }
+    if !status.is_success() {
+        return Err(McpDiffError::Generic(format!(
+            "Pattern server returned HTTP {}: {}",
+            status, url
+        )));
+    }
+    Ok(())
+}
+
+/// SHA-256 of a file as lowercase hex
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Checksum from a `.sha256` file or `--checksum` value
+///
+/// Accepts a bare hex digest or `sha256sum` output (`<digest>  <file>`).
+pub fn parse_checksum(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?.to_ascii_lowercase();
+    let digest = digest.strip_prefix("sha256:").unwrap_or(&digest);
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_string())
+}
+
+/// Whether this engine can use a database of the given version
+///
+/// The database format changes with the engine's major version (minor
+/// version before 1.0), so those have to match.
+pub fn is_compatible_version(version: &str) -> bool {
+    fn release(version: &str) -> Option<(u64, u64)> {
+        let core = version.split(['-', '+']).next()?;
+        let mut parts = core.split('.');
+        Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+    }
+    let (Some(engine), Some(db)) = (release(env!("CARGO_PKG_VERSION")), release(version)) else {
+        return false;
+    };
+    match engine.0 {
+        0 => db == engine,
+        major => db.0 == major,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    fn status(checksum: &str) -> UpdateStatus {
+        UpdateStatus {
+            checksum: checksum.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_install_and_rollback() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = PatternStore::at(temp.path().join("security"));
+        assert!(store.load().is_none());
+        assert!(store.rollback().is_err());
+
+        let install = |generated_at: &str, checksum: &str| {
+            let mut db = PatternDatabase::new();
+            db.generated_at = generated_at.to_string();
+            let file = temp.path().join("download");
+            fs::write(&file, db.to_bytes().unwrap()).unwrap();
+            store.install(&file, status(checksum)).unwrap();
+        };
+        install("first", "aaa");
+        install("second", "bbb");
+        assert_eq!(store.load().unwrap().generated_at, "second");
+        assert_eq!(store.status().current.unwrap().checksum, "bbb");
+
+        let rolled_back = store.rollback().unwrap();
+        assert_eq!(rolled_back.current.unwrap().checksum, "aaa");
+        assert_eq!(rolled_back.previous.unwrap().checksum, "bbb");
+        assert_eq!(store.load().unwrap().generated_at, "first");
+
+        // Rolling back again restores the newer database
+        store.rollback().unwrap();
+        assert_eq!(store.load().unwrap().generated_at, "second");
+    }
+
+    /// Response settings of [`PatternServer`]
+    #[derive(Default)]
+    struct Served {
+        body: Vec<u8>,
+        checksum: String,
+        /// Close the connection after sending this many bytes of the database
+        truncate_at: Option<usize>,
+    }
+
+    /// Local HTTP server publishing `/patterns.bin` and `/patterns.bin.sha256`
+    struct PatternServer {
+        url: String,
+        served: Arc<Mutex<Served>>,
+        /// Path and `Range` header of every request
+        requests: Arc<Mutex<Vec<(String, Option<String>)>>>,
+    }
+
+    impl PatternServer {
+        fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/patterns.bin", listener.local_addr().unwrap());
+            let served = Arc::new(Mutex::new(Served::default()));
+            let requests = Arc::new(Mutex::new(Vec::new()));
+
+            let (thread_served, thread_requests) = (served.clone(), requests.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { continue };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    let _ = reader.read_line(&mut request_line);
+                    let path = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string();
+                    let mut range = None;
+                    loop {
+                        let mut header = String::new();
+                        if reader.read_line(&mut header).unwrap_or(0) == 0 || header == "\r\n" {
+                            break;
+                        }
+                        if let Some((name, value)) = header.split_once(':') {
+                            if name.eq_ignore_ascii_case("range") {
+                                range = Some(value.trim().to_string());
+                            }
+                        }
+                    }
+                    thread_requests
+                        .lock()
+                        .unwrap()
+                        .push((path.clone(), range.clone()));
+
+                    let served = thread_served.lock().unwrap();
+                    let start = range
+                        .as_deref()
+                        .and_then(|r| r.strip_prefix("bytes="))
+                        .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+                    let (status, body) = match start {
+                        _ if path.ends_with(".sha256") => {
+                            ("200 OK", served.checksum.as_bytes().to_vec())
+                        }
+                        Some(start) if start >= served.body.len() => {
+                            ("416 Range Not Satisfiable", Vec::new())
+                        }
+                        Some(start) => ("206 Partial Content", served.body[start..].to_vec()),
+                        None => ("200 OK", served.body.clone()),
+                    };
+                    let sent = match served.truncate_at {
+                        Some(limit) if !path.ends_with(".sha256") => limit.min(body.len()),
+                        _ => body.len(),
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        body.len()
+                    );
+                    let _ = stream.write_all(&body[..sent]);
+                }
+            });
+
+            Self {
+                url,
+                served,
+                requests,
+            }
+        }
+
+        /// Serve a database identified by its `generated_at`; returns its checksum
+        fn publish(&self, generated_at: &str) -> String {
+            let mut db = PatternDatabase::new();
+            db.generated_at = generated_at.to_string();
+            let body = db.to_bytes().unwrap();
+            let checksum = format!("{:x}", Sha256::digest(&body));
+            *self.served.lock().unwrap() = Served {
+                body,
+                checksum: checksum.clone(),
+                truncate_at: None,
+            };
+            checksum
+        }
+
+        fn request_count(&self) -> usize {
+            self.requests.lock().unwrap().len()
+        }
+
+        /// `Range` headers of the database requests
+        fn database_ranges(&self) -> Vec<Option<String>> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(path, _)| path.ends_with(".bin"))
+                .map(|(_, range)| range.clone())
+                .collect()
+        }
+    }
+
+    fn installed(outcome: FetchOutcome) -> PatternDatabase {
+        match outcome {
+            FetchOutcome::Installed(db) => db,
+            FetchOutcome::Skipped(reason) => panic!("fetch skipped: {}", reason),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_installs_verified_database() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = PatternStore::at(temp.path());
+        let server = PatternServer::start();
+        let checksum = server.publish("first");
+
+        let db = installed(store.fetch(&server.url, None, false).await.unwrap());
+        assert_eq!(db.generated_at, "first");
+        assert_eq!(store.load().unwrap().generated_at, "first");
+        let current = store.status().current.unwrap();
+        assert_eq!(current.checksum, checksum);
+        assert_eq!(current.source, server.url);
+        assert!(!store.partial_path().exists());
+
+        // Checking again within the interval doesn't contact the server
+        let requests = server.request_count();
+        let outcome = store.fetch(&server.url, None, false).await.unwrap();
+        assert!(matches!(outcome, FetchOutcome::Skipped(_)));
+        assert_eq!(server.request_count(), requests);
+
+        // Forcing re-downloads even an unchanged database
+        installed(store.fetch(&server.url, None, true).await.unwrap());
+        assert_eq!(server.request_count(), requests + 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checksum_mismatch_keeps_current() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = PatternStore::at(temp.path());
+        let server = PatternServer::start();
+        let checksum = server.publish("first");
+        installed(store.fetch(&server.url, None, false).await.unwrap());
+
+        server.publish("second");
+        server.served.lock().unwrap().checksum = "0".repeat(64);
+        let err = store.fetch(&server.url, None, true).await.unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+
+        // An explicit checksum is used instead of the published one
+        let err = store
+            .fetch(&server.url, Some(&checksum), true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+
+        assert_eq!(store.load().unwrap().generated_at, "first");
+        assert_eq!(store.status().current.unwrap().checksum, checksum);
+        assert!(!store.partial_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_resumes_truncated_download() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = PatternStore::at(temp.path());
+        let server = PatternServer::start();
+        let old = server.publish("first");
+        installed(store.fetch(&server.url, None, false).await.unwrap());
+
+        let checksum = server.publish("second");
+        let size = server.served.lock().unwrap().body.len();
+        server.served.lock().unwrap().truncate_at = Some(size / 2);
+        let err = store.fetch(&server.url, None, true).await.unwrap_err();
+        assert!(err.to_string().contains("interrupted"), "{}", err);
+        assert_eq!(store.load().unwrap().generated_at, "first");
+        assert_eq!(store.status().current.unwrap().checksum, old);
+
+        // The retry only asks for the missing bytes
+        let received = fs::metadata(store.partial_path()).unwrap().len();
+        server.served.lock().unwrap().truncate_at = None;
+        let db = installed(store.fetch(&server.url, None, true).await.unwrap());
+        assert_eq!(db.generated_at, "second");
+        assert_eq!(
+            server.database_ranges().last().unwrap().as_deref(),
+            Some(format!("bytes={}-", received).as_str())
+        );
+        assert_eq!(store.status().current.unwrap().checksum, checksum);
+        assert_eq!(store.status().previous.unwrap().checksum, old);
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let digest = "a".repeat(64);
+        assert_eq!(parse_checksum(&digest), Some(digest.clone()));
+        assert_eq!(
+            parse_checksum(&format!(
+                "{}  security_patterns.bin\n",
+                digest.to_uppercase()
+            )),
+            Some(digest.clone())
+        );
+        assert_eq!(parse_checksum(&format!("sha256:{}", digest)), Some(digest));
+        assert_eq!(parse_checksum("abc"), None);
+        assert_eq!(parse_checksum("<html>not found</html>"), None);
+    }
+
+    #[test]
+    fn test_is_compatible_version() {
+        assert!(is_compatible_version(env!("CARGO_PKG_VERSION")));
+        assert!(!is_compatible_version("99.0.0"));
+        assert!(!is_compatible_version("garbage"));
+    }
+}
@@ -0,0 +1,358 @@
+//! Semantic grep: find symbols by what they do, not how their source is spelled
+//!
+//! A pattern is `<field>:<value>` and is answered from the symbol shards
+//! written at index time, never from source text:
+//!
+//! - `calls:fetchUser` - symbols calling `fetchUser`; `calls:axios.get`
+//!   also requires the receiver
+//! - `mutates:session` - symbols whose state changes assign `session` or one
+//!   of its fields (only languages that attribute state changes to symbols
+//!   record them)
+//! - `imports:axios` - symbols in files depending on `axios`
+//! - `returns:Promise<User>` - symbols whose declared return type matches,
+//!   ignoring whitespace and the `->`/`:` annotation prefix
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::commands::toon_parser::read_cached_toon;
+use crate::error::Result;
+
+const FIELDS: &str = "calls, mutates, imports, returns";
+
+/// A parsed semantic grep pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SemanticPattern {
+    /// Calls a function, optionally on a receiver (`object.name`)
+    Calls {
+        object: Option<String>,
+        name: String,
+    },
+    /// Assigns a variable or one of its fields
+    Mutates(String),
+    /// Lives in a file depending on a package or module
+    Imports(String),
+    /// Declares this return type
+    Returns(String),
+}
+
+impl FromStr for SemanticPattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> std::result::Result<Self, Self::Err> {
+        let pattern = pattern.trim();
+        let Some((field, value)) = pattern.split_once(':') else {
+            return Err(format!(
+                "`{}` has no field; expected `<field>:<value>` (fields: {})",
+                pattern, FIELDS
+            ));
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("`{}` has no value after `{}:`", pattern, field));
+        }
+
+        match field.trim() {
+            "calls" => Ok(match value.rsplit_once('.') {
+                Some((object, name)) if !object.is_empty() && !name.is_empty() => {
+                    SemanticPattern::Calls {
+                        object: Some(object.to_string()),
+                        name: name.to_string(),
+                    }
+                }
+                _ => SemanticPattern::Calls {
+                    object: None,
+                    name: value.to_string(),
+                },
+            }),
+            "mutates" => Ok(SemanticPattern::Mutates(value.to_string())),
+            "imports" => Ok(SemanticPattern::Imports(value.to_string())),
+            "returns" => Ok(SemanticPattern::Returns(normalize_type(value))),
+            other => Err(format!(
+                "unknown field `{}` in `{}`; expected one of: {}",
+                other, pattern, FIELDS
+            )),
+        }
+    }
+}
+
+impl SemanticPattern {
+    /// The item of a parsed symbol shard that satisfies the pattern, if any
+    pub fn find_in_shard(&self, shard: &Value) -> Option<String> {
+        match self {
+            SemanticPattern::Calls { object, name } => {
+                shard_rows(shard, "calls").find_map(|call| {
+                    let call_name = call.get("name").and_then(Value::as_str)?;
+                    let call_object = call.get("obj").and_then(Value::as_str);
+                    if call_name != name {
+                        return None;
+                    }
+                    match (object, call_object) {
+                        (None, None) => Some(call_name.to_string()),
+                        (None, Some(obj)) => Some(format!("{}.{}", obj, call_name)),
+                        (Some(wanted), Some(obj)) if path_matches(obj, wanted, '.') => {
+                            Some(format!("{}.{}", obj, call_name))
+                        }
+                        _ => None,
+                    }
+                })
+            }
+            SemanticPattern::Mutates(target) => shard_strings(shard, "state_changes")
+                .find(|name| {
+                    name == target
+                        || name
+                            .strip_prefix(target.as_str())
+                            .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+                })
+                .map(String::from),
+            SemanticPattern::Imports(module) => shard_strings(shard, "added_dependencies")
+                .find(|dep| {
+                    dep == module
+                        || dep
+                            .strip_prefix(module.as_str())
+                            .is_some_and(|rest| rest.starts_with('/') || rest.starts_with("::"))
+                })
+                .map(String::from),
+            SemanticPattern::Returns(wanted) => shard
+                .get("return_type")
+                .and_then(Value::as_str)
+                .filter(|ret| normalize_type(ret) == *wanted)
+                .map(String::from),
+        }
+    }
+}
+
+/// A symbol matching a semantic grep pattern
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticGrepMatch {
+    pub symbol: String,
+    pub kind: String,
+    pub module: String,
+    pub file: String,
+    pub lines: String,
+    pub hash: String,
+    /// The call, state change, dependency or return type that matched
+    #[serde(rename = "match")]
+    pub matched: String,
+}
+
+/// Search indexed symbols for `pattern`
+///
+/// `keep` narrows the candidates before their shards are read. Matches are
+/// ordered by file, then line.
+pub fn semantic_grep(
+    cache: &CacheDir,
+    pattern: &SemanticPattern,
+    keep: impl Fn(&SymbolIndexEntry) -> bool,
+) -> Result<Vec<SemanticGrepMatch>> {
+    let mut entries = cache.load_all_symbol_entries()?;
+    entries.retain(|e| keep(e));
+    entries.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then_with(|| line_start(&a.lines).cmp(&line_start(&b.lines)))
+    });
+
+    // Dependencies are file-level; read one shard per file
+    let mut file_imports: HashMap<String, Option<String>> = HashMap::new();
+
+    let mut matches = Vec::new();
+    for entry in entries {
+        let matched = match pattern {
+            SemanticPattern::Imports(_) => file_imports
+                .entry(entry.file.clone())
+                .or_insert_with(|| {
+                    read_shard(cache, &entry).and_then(|s| pattern.find_in_shard(&s))
+                })
+                .clone(),
+            _ => read_shard(cache, &entry).and_then(|s| pattern.find_in_shard(&s)),
+        };
+        if let Some(matched) = matched {
+            matches.push(SemanticGrepMatch {
+                symbol: entry.symbol,
+                kind: entry.kind,
+                module: entry.module,
+                file: entry.file,
+                lines: entry.lines,
+                hash: entry.hash,
+                matched,
+            });
+        }
+    }
+
+    Ok(matches)
+}
+
+fn read_shard(cache: &CacheDir, entry: &SymbolIndexEntry) -> Option<Value> {
+    read_cached_toon(&cache.symbol_path(&entry.hash)).ok()
+}
+
+fn shard_rows<'a>(shard: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    shard
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn shard_strings<'a>(shard: &'a Value, key: &str) -> impl Iterator<Item = &'a str> {
+    shard_rows(shard, key).filter_map(Value::as_str)
+}
+
+/// Whether `path` is `wanted` or ends with it at a `sep` boundary
+/// (`this.api` matches `api`)
+fn path_matches(path: &str, wanted: &str, sep: char) -> bool {
+    path == wanted
+        || path
+            .strip_suffix(wanted)
+            .is_some_and(|rest| rest.ends_with(sep))
+}
+
+/// Return type without whitespace or its `->` / `:` annotation prefix
+fn normalize_type(ty: &str) -> String {
+    let ty = ty.trim();
+    let ty = ty
+        .strip_prefix("->")
+        .or_else(|| ty.strip_prefix(':'))
+        .unwrap_or(ty);
+    ty.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn line_start(lines: &str) -> usize {
+    lines
+        .split('-')
+        .next()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::toon_parser::parse_toon_to_json;
+    use crate::schema::{Call, SemanticSummary, StateChange, SymbolId, SymbolInfo};
+    use crate::shard::encode_symbol_shard_from_info;
+
+    fn parse(pattern: &str) -> SemanticPattern {
+        pattern.parse().unwrap()
+    }
+
+    /// Round-trip a symbol through the on-disk shard format
+    fn shard(symbol: SymbolInfo, dependencies: &[&str]) -> Value {
+        let summary = SemanticSummary {
+            file: "src/users.ts".to_string(),
+            language: "typescript".to_string(),
+            added_dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let id = SymbolId {
+            hash: "abc:def".to_string(),
+            ..Default::default()
+        };
+        parse_toon_to_json(&encode_symbol_shard_from_info(&summary, &symbol, &id))
+    }
+
+    fn load_user() -> SymbolInfo {
+        SymbolInfo {
+            name: "loadUser".to_string(),
+            start_line: 3,
+            end_line: 9,
+            return_type: Some("Promise<User>".to_string()),
+            calls: vec![
+                Call {
+                    name: "get".to_string(),
+                    object: Some("this.http".to_string()),
+                    is_awaited: true,
+                    ..Default::default()
+                },
+                Call {
+                    name: "fetchUser".to_string(),
+                    ..Default::default()
+                },
+            ],
+            state_changes: vec![StateChange {
+                name: "session.user".to_string(),
+                state_type: "_".to_string(),
+                initializer: "user".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_patterns() {
+        assert_eq!(
+            parse("calls:fetchUser"),
+            SemanticPattern::Calls {
+                object: None,
+                name: "fetchUser".to_string()
+            }
+        );
+        assert_eq!(
+            parse("calls:axios.get"),
+            SemanticPattern::Calls {
+                object: Some("axios".to_string()),
+                name: "get".to_string()
+            }
+        );
+        assert_eq!(
+            parse("returns: -> Result<User, Error>"),
+            SemanticPattern::Returns("Result<User,Error>".to_string())
+        );
+        assert_eq!(
+            parse("imports:axios"),
+            SemanticPattern::Imports("axios".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_patterns() {
+        let cases = [
+            ("fetchUser", "has no field"),
+            ("calls:", "has no value"),
+            ("throws:Error", "unknown field `throws`"),
+        ];
+        for (pattern, expected) in cases {
+            let err = pattern.parse::<SemanticPattern>().expect_err(pattern);
+            assert!(err.contains(expected), "{pattern}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_find_in_shard() {
+        let shard = shard(load_user(), &["axios", "@app/session"]);
+        let find = |pattern: &str| parse(pattern).find_in_shard(&shard);
+
+        assert_eq!(find("calls:fetchUser").as_deref(), Some("fetchUser"));
+        assert_eq!(find("calls:get").as_deref(), Some("this.http.get"));
+        assert_eq!(find("calls:http.get").as_deref(), Some("this.http.get"));
+        assert_eq!(find("calls:axios.get"), None);
+        assert_eq!(find("calls:fetch"), None);
+
+        assert_eq!(find("mutates:session").as_deref(), Some("session.user"));
+        assert_eq!(
+            find("mutates:session.user").as_deref(),
+            Some("session.user")
+        );
+        assert_eq!(find("mutates:sess"), None);
+
+        assert_eq!(find("imports:axios").as_deref(), Some("axios"));
+        assert_eq!(find("imports:@app").as_deref(), Some("@app/session"));
+        assert_eq!(find("imports:axi"), None);
+
+        assert_eq!(
+            find("returns:Promise<User>").as_deref(),
+            Some("Promise<User>")
+        );
+        assert_eq!(
+            find("returns:Promise< User >").as_deref(),
+            Some("Promise<User>")
+        );
+        assert_eq!(find("returns:User"), None);
+    }
+}
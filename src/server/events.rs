@@ -17,12 +17,13 @@
 //! - `layer_stale` - A layer became stale and needs update
 //! - `server_status` - Server status changed (started, stopped)
 //! - `indexing_progress` - Progress during full rebuild
+//! - `layers_compacted` - Committed layers were folded into a lower layer
 
 use serde::Serialize;
 use std::io::{self, Write};
 
 use super::sync::LayerUpdateStats;
-use crate::overlay::LayerKind;
+use crate::overlay::{CompactionStats, LayerKind, LayeredIndexStats};
 
 /// Event emitter for sending JSON events to stdout
 pub struct EventEmitter {
@@ -203,6 +204,43 @@ impl EngineEvent for IndexingProgressEvent {
     }
 }
 
+/// Event emitted when committed layers were folded into a lower layer
+#[derive(Debug, Clone, Serialize)]
+pub struct LayersCompactedEvent {
+    /// Layer the upper layers were folded into
+    pub target: String,
+    /// Index statistics before compaction
+    pub before: LayeredIndexStats,
+    /// Index statistics after compaction
+    pub after: LayeredIndexStats,
+    /// Number of symbol states merged into the target
+    pub symbols_merged: usize,
+    /// Number of tombstones dropped
+    pub tombstones_dropped: usize,
+    /// Timestamp
+    pub timestamp: String,
+}
+
+impl EngineEvent for LayersCompactedEvent {
+    fn event_type() -> &'static str {
+        "layers_compacted"
+    }
+}
+
+impl LayersCompactedEvent {
+    /// Create from compaction stats
+    pub fn from_stats(stats: &CompactionStats) -> Self {
+        Self {
+            target: format!("{:?}", stats.target),
+            before: stats.before.clone(),
+            after: stats.after.clone(),
+            symbols_merged: stats.symbols_merged,
+            tombstones_dropped: stats.tombstones_dropped,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
 /// Event emitted when potential duplicate functions are detected
 #[derive(Debug, Clone, Serialize)]
 pub struct DuplicateDetectedEvent {
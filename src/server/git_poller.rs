@@ -7,14 +7,17 @@
 //!
 //! - Base layer: every 5s (origin/main changes after fetch/pull)
 //! - Branch layer: every 1s (local commits, rebases, checkouts)
+//! - Compaction: every 5min (fold committed layers once HEAD advanced)
 //!
 //! # Detection Methods
 //!
 //! - Base layer stale: origin/main HEAD != indexed SHA
 //! - Branch layer stale: HEAD != indexed SHA
 //! - Rebase detected: merge-base change between base and HEAD
+//! - Compaction due: HEAD advanced since the last compaction and the
+//!   working tree is clean
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -38,6 +41,8 @@ pub struct PollerConfig {
     pub branch_interval: Duration,
     /// Whether to auto-update on changes
     pub auto_update: bool,
+    /// Layer compaction check interval (default: 5min, None disables)
+    pub compact_interval: Option<Duration>,
 }
 
 impl Default for PollerConfig {
@@ -46,6 +51,7 @@ impl Default for PollerConfig {
             base_interval: Duration::from_secs(5),
             branch_interval: Duration::from_secs(1),
             auto_update: true,
+            compact_interval: Some(Duration::from_secs(300)),
         }
     }
 }
@@ -102,6 +108,19 @@ impl GitState {
         }
     }
 
+    /// Layer that committed changes fold into
+    ///
+    /// On the base branch (or when HEAD is already contained in it) that is
+    /// the base layer; on a feature branch it is the branch layer.
+    fn compaction_target(&self) -> LayerKind {
+        let on_base_branch = matches!(self.branch_name.as_deref(), Some("main" | "master"));
+        if on_base_branch || (self.head_sha.is_some() && self.head_sha == self.merge_base) {
+            LayerKind::Base
+        } else {
+            LayerKind::Branch
+        }
+    }
+
     /// Check if a rebase occurred
     fn is_rebase(&self, indexed_merge_base: &Option<String>) -> bool {
         match (&self.merge_base, indexed_merge_base) {
@@ -153,9 +172,10 @@ impl GitPoller {
 
     /// Start polling for git state changes
     ///
-    /// This spawns two background threads:
+    /// This spawns up to three background threads:
     /// 1. Base layer poller (every 5s)
     /// 2. Branch layer poller (every 1s)
+    /// 3. Layer compaction (every 5min, unless disabled)
     pub fn start(&self, state: Arc<ServerState>) -> Result<PollerHandle> {
         if self.running.swap(true, Ordering::SeqCst) {
             // Already running
@@ -245,6 +265,47 @@ impl GitPoller {
             }
         });
 
+        // Spawn compaction task: fold committed layers once HEAD advanced
+        if let Some(compact_interval) = config.compact_interval {
+            let compact_running = Arc::clone(&running);
+            let compact_state = Arc::clone(&state);
+            let compact_root = repo_root.clone();
+            let mut compacted_head = last_state.lock().head_sha.clone();
+
+            thread::spawn(move || {
+                while compact_running.load(Ordering::SeqCst) {
+                    thread::sleep(compact_interval);
+
+                    let current = GitState::read(&compact_root);
+                    if current.head_sha.is_none()
+                        || current.head_sha == compacted_head
+                        || !is_worktree_clean(&compact_root)
+                    {
+                        continue;
+                    }
+
+                    match compact_state.compact(current.compaction_target()) {
+                        Ok(stats) => {
+                            if !stats.is_noop() {
+                                tracing::info!(
+                                    "Compacted layers into {}: {} symbols merged, {} tombstones dropped",
+                                    stats.target,
+                                    stats.symbols_merged,
+                                    stats.tombstones_dropped
+                                );
+                                let event = super::events::LayersCompactedEvent::from_stats(&stats);
+                                super::events::emit_event(&event);
+                            }
+                            compacted_head = current.head_sha;
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to compact layers: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+
         // Spawn base layer poller (less frequent)
         let base_running = Arc::clone(&running);
         let base_state = Arc::clone(&state);
@@ -351,6 +412,22 @@ impl Drop for PollerHandle {
 
 use std::process::Command;
 
+/// Layer that committed changes in `repo_root` should be compacted into
+pub fn compaction_target(repo_root: &Path) -> LayerKind {
+    GitState::read(&repo_root.to_path_buf()).compaction_target()
+}
+
+/// Whether the working tree has no staged, unstaged or untracked changes
+///
+/// Only then does HEAD contain everything the working layer recorded.
+pub fn is_worktree_clean(repo_root: &Path) -> bool {
+    Command::new("git")
+        .current_dir(repo_root)
+        .args(["status", "--porcelain"])
+        .output()
+        .is_ok_and(|o| o.status.success() && o.stdout.iter().all(u8::is_ascii_whitespace))
+}
+
 /// Get SHA for a specific ref (branch, tag, etc.)
 fn get_ref_sha(repo_root: &PathBuf, ref_name: &str) -> crate::error::Result<String> {
     let output = Command::new("git")
@@ -386,6 +463,7 @@ mod tests {
         assert_eq!(config.base_interval, Duration::from_secs(5));
         assert_eq!(config.branch_interval, Duration::from_secs(1));
         assert!(config.auto_update);
+        assert_eq!(config.compact_interval, Some(Duration::from_secs(300)));
     }
 
     #[test]
@@ -426,6 +504,25 @@ mod tests {
         assert!(state.is_rebase(&Some("old_merge_base".to_string())));
     }
 
+    #[test]
+    fn test_compaction_target() {
+        let mut state = GitState {
+            head_sha: Some("abc123".to_string()),
+            base_sha: Some("def456".to_string()),
+            branch_name: Some("feature".to_string()),
+            merge_base: Some("def456".to_string()),
+        };
+        assert_eq!(state.compaction_target(), LayerKind::Branch);
+
+        // HEAD already contained in the base branch
+        state.merge_base = Some("abc123".to_string());
+        assert_eq!(state.compaction_target(), LayerKind::Base);
+
+        state.merge_base = None;
+        state.branch_name = Some("main".to_string());
+        assert_eq!(state.compaction_target(), LayerKind::Base);
+    }
+
     #[test]
     fn test_poller_creation() {
         let poller = GitPoller::new(PathBuf::from("/tmp/test"));
@@ -52,9 +52,9 @@ pub mod watcher;
 pub use ast_cache::{AstCache, AstCacheStats, ParseResult};
 pub use events::{
     emit_event, init_event_emitter, IndexingProgressEvent, LayerStaleEvent, LayerUpdatedEvent,
-    ServerStatusEvent,
+    LayersCompactedEvent, ServerStatusEvent,
 };
-pub use git_poller::GitPoller;
+pub use git_poller::{compaction_target, is_worktree_clean, GitPoller, PollerConfig};
 pub use state::{LayerStatus, ServerState, ServerStatus};
 pub use sync::{LayerSynchronizer, LayerUpdateStats};
-pub use watcher::FileWatcher;
+pub use watcher::{FileWatcher, WatcherConfig};
@@ -27,7 +27,8 @@ use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::cache::CacheDir;
 use crate::drift::{DriftDetector, DriftStatus, UpdateStrategy};
-use crate::overlay::{LayerKind, LayeredIndex, LayeredIndexStats, SymbolState};
+use crate::error::Result;
+use crate::overlay::{CompactionStats, LayerKind, LayeredIndex, LayeredIndexStats, SymbolState};
 use crate::schema::SymbolInfo;
 
 /// Status of a specific layer
@@ -322,6 +323,29 @@ impl ServerState {
         });
     }
 
+    /// Fold committed layers into `target` (see `LayeredIndex::compact`)
+    ///
+    /// The merge happens under a single write lock, so readers see the index
+    /// either entirely before or entirely after compaction. The result is
+    /// then persisted outside the lock with the cache's atomic layer writes,
+    /// and the graphs are regenerated if any file's symbols changed.
+    pub fn compact(&self, target: LayerKind) -> Result<CompactionStats> {
+        let (stats, snapshot) = self.write(|index| {
+            let stats = index.compact(target);
+            let snapshot = (!stats.is_noop()).then(|| index.clone());
+            (stats, snapshot)
+        });
+
+        let cache = self.cache_dir.lock().clone();
+        if let (Some(snapshot), Some(cache)) = (snapshot, cache) {
+            cache.save_layered_index(&snapshot)?;
+            if !stats.affected_files.is_empty() && cache.has_symbol_index() {
+                cache.regenerate_graphs()?;
+            }
+        }
+        Ok(stats)
+    }
+
     // ========================================================================
     // Status Operations
     // ========================================================================
@@ -478,6 +502,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compact_persists_layers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = CacheDir {
+            root: temp_dir.path().join("cache"),
+            repo_root: temp_dir.path().to_path_buf(),
+            repo_hash: "test_hash".to_string(),
+        };
+        let state = ServerState::with_cache_dir(temp_dir.path().to_path_buf(), cache.clone());
+
+        let symbol = |name: &str| SymbolInfo {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        state.upsert_symbol(
+            LayerKind::Base,
+            "h_old".to_string(),
+            SymbolState::active_at(symbol("old"), PathBuf::from("a.rs")),
+        );
+        state.write(|index| index.working.delete("h_old"));
+        state.upsert_symbol(
+            LayerKind::Working,
+            "h_new".to_string(),
+            SymbolState::active_at(symbol("new"), PathBuf::from("a.rs")),
+        );
+
+        let stats = state.compact(LayerKind::Base).unwrap();
+        assert_eq!(stats.before.working_symbols, 1);
+        assert_eq!(stats.after.base_symbols, 1);
+        assert_eq!(stats.after.total_deleted, 0);
+        assert_eq!(state.stats(), stats.after);
+        assert_eq!(state.status().layer(LayerKind::Working).symbol_count, 0);
+
+        let loaded = cache.load_layered_index().unwrap().unwrap();
+        assert_eq!(loaded.stats(), stats.after);
+        assert!(loaded.base.get("h_new").is_some());
+
+        // Nothing left to fold
+        assert!(state.compact(LayerKind::Base).unwrap().is_noop());
+    }
+
     #[test]
     fn test_write_blocks_reads() {
         let state = Arc::new(ServerState::new(PathBuf::from("/tmp/test")));
@@ -38,7 +38,7 @@ use crate::duplicate::{DuplicateDetector, DuplicateKind, FunctionSignature};
 use crate::error::Result;
 use crate::extract::extract;
 use crate::lang::Lang;
-use crate::overlay::{LayerKind, SymbolState};
+use crate::overlay::{LayerKind, LayeredIndex, SymbolState};
 use crate::schema::SymbolInfo;
 use crate::shard::extract_module_name;
 
@@ -94,6 +94,62 @@ pub struct RebaseResult {
     pub discarded: usize,
 }
 
+/// A parsed file waiting to be applied to a layer
+struct StagedFile {
+    /// Path relative to the repository root
+    file_path: PathBuf,
+    full_path: PathBuf,
+    /// Symbols with their hashes and index entries; `None` for a deleted file
+    symbols: Option<Vec<(SymbolInfo, String, crate::cache::SymbolIndexEntry)>>,
+    /// Hashes visible for the file before the update
+    existing_hashes: HashSet<String>,
+    /// Symbols the layer did not have yet
+    new_symbols_for_check: Vec<(SymbolInfo, String)>,
+    /// Index entries for the disk cache, filled in by [`StagedFile::apply`]
+    index_entries: Vec<crate::cache::SymbolIndexEntry>,
+    stats: LayerUpdateStats,
+}
+
+impl StagedFile {
+    /// Update the overlay with this file's symbols
+    fn apply(&mut self, index: &mut LayeredIndex, layer: LayerKind) {
+        let Some(symbols) = self.symbols.as_mut() else {
+            // File was deleted - mark all its symbols as deleted
+            let hashes = index.visible_file_hashes(&self.file_path, layer);
+            let overlay = index.layer_mut(layer);
+            for hash in hashes {
+                overlay.delete(&hash);
+                self.stats.symbols_removed += 1;
+            }
+            return;
+        };
+
+        let overlay = index.layer_mut(layer);
+        let mut new_hashes = HashSet::new();
+        for (symbol, hash, entry) in symbols.drain(..) {
+            new_hashes.insert(hash.clone());
+            self.index_entries.push(entry);
+
+            let is_new = !self.existing_hashes.contains(&hash);
+            let symbol_state = SymbolState::active_at(symbol, self.file_path.clone());
+
+            if is_new {
+                self.stats.symbols_added += 1;
+            } else {
+                self.stats.symbols_modified += 1;
+            }
+
+            overlay.upsert(hash, symbol_state);
+        }
+
+        // Mark removed symbols as deleted
+        for hash in self.existing_hashes.difference(&new_hashes) {
+            overlay.delete(hash);
+            self.stats.symbols_removed += 1;
+        }
+    }
+}
+
 /// Layer synchronization engine
 ///
 /// Handles incremental updates, rebases, and full rebuilds based on
@@ -186,7 +242,9 @@ impl LayerSynchronizer {
     ///
     /// This is the fastest update path for small changes (< 10 files).
     /// It parses only the changed files and updates the symbol index.
-    /// After all files are processed, regenerates all graphs (call, import, module).
+    /// The whole batch is applied under one write lock, so queries see none
+    /// or all of it. After all files are processed, regenerates all graphs
+    /// (call, import, module).
     pub fn incremental_update(
         &self,
         state: &ServerState,
@@ -199,8 +257,19 @@ impl LayerSynchronizer {
             ..Default::default()
         };
 
+        // Parse every file before applying any, then apply the batch in one write
+        let mut staged = Vec::new();
         for file_path in changed_files {
-            let file_stats = self.update_single_file(state, layer, file_path)?;
+            staged.extend(self.stage_file(state, layer, file_path)?);
+        }
+        state.write(|index| {
+            for file in &mut staged {
+                file.apply(index, layer);
+            }
+        });
+
+        for file in staged {
+            let file_stats = self.finish_file(file);
             stats.symbols_added += file_stats.symbols_added;
             stats.symbols_removed += file_stats.symbols_removed;
             stats.symbols_modified += file_stats.symbols_modified;
@@ -234,36 +303,46 @@ impl LayerSynchronizer {
         Ok(stats)
     }
 
-    /// Update a single file in a layer
+    /// Parse a single file for a layer update
     ///
     /// This is the core incremental update operation:
     /// 1. Read file contents
     /// 2. Parse with tree-sitter (using incremental parsing if cached)
     /// 3. Extract symbols
     /// 4. Compare with existing symbols
-    /// 5. Update overlay
-    /// 6. Update disk cache (if cache_dir is set)
-    fn update_single_file(
+    ///
+    /// The result is applied to the overlay by [`StagedFile::apply`] and
+    /// written to the disk cache by [`Self::finish_file`]. Returns `None`
+    /// for unsupported files.
+    fn stage_file(
         &self,
         state: &ServerState,
         layer: LayerKind,
         file_path: &PathBuf,
-    ) -> Result<LayerUpdateStats> {
+    ) -> Result<Option<StagedFile>> {
         let mut stats = LayerUpdateStats::default();
         let full_path = self.repo_root.join(file_path);
 
         // Check if file exists
         if !full_path.exists() {
-            // File was deleted - mark all its symbols as deleted
+            // File was deleted - all its symbols are marked deleted on apply
             // Also remove from AST cache
             self.ast_cache.remove(&full_path);
-            return self.mark_file_deleted(state, layer, file_path);
+            return Ok(Some(StagedFile {
+                file_path: file_path.clone(),
+                full_path,
+                symbols: None,
+                existing_hashes: HashSet::new(),
+                new_symbols_for_check: Vec::new(),
+                index_entries: Vec::new(),
+                stats,
+            }));
         }
 
         // Determine language
         let lang = match Lang::from_path(&full_path) {
             Ok(l) => l,
-            Err(_) => return Ok(stats), // Skip unsupported files
+            Err(_) => return Ok(None), // Skip unsupported files
         };
 
         // Read file contents
@@ -306,22 +385,10 @@ impl LayerSynchronizer {
         // Extract symbols
         let summary = extract(&full_path, &source, &tree, lang)?;
 
-        // Get existing symbol hashes for this file
-        let existing_hashes: HashSet<String> = state.read(|index| {
-            let overlay = index.layer(layer);
-            // Get hashes from symbols_by_file directly
-            overlay
-                .symbols_by_file
-                .get(file_path)
-                .cloned()
-                .unwrap_or_default()
-                .into_iter()
-                .collect()
-        });
-
-        // Compute new symbol hashes and build index entries for cache
-        let mut new_hashes = HashSet::new();
-        let mut index_entries = Vec::new();
+        // Get existing symbol hashes for this file, including those this
+        // layer inherits from the layers below it
+        let existing_hashes: HashSet<String> =
+            state.read(|index| index.visible_file_hashes(file_path, layer));
 
         // Extract module name from file path
         let module_name = file_path
@@ -333,10 +400,22 @@ impl LayerSynchronizer {
 
         // Pre-compute hashes and index entries before consuming symbols
         // NOTE: Always use full_path (absolute) for hash computation - this is the canonical rule
+        let parent_hashes: Vec<String> = summary
+            .symbols
+            .iter()
+            .map(|symbol| {
+                summary
+                    .parent_of(symbol)
+                    .map_or_else(String::new, |parent| {
+                        crate::overlay::compute_symbol_hash(parent, &full_path.to_string_lossy())
+                    })
+            })
+            .collect();
         let symbols_with_hashes: Vec<_> = summary
             .symbols
             .into_iter()
-            .map(|symbol| {
+            .zip(parent_hashes)
+            .map(|(symbol, parent)| {
                 let hash =
                     crate::overlay::compute_symbol_hash(&symbol, &full_path.to_string_lossy());
 
@@ -361,6 +440,18 @@ impl LayerSynchronizer {
                     return_type: symbol.return_type.clone().unwrap_or_default(),
                     ext_package: String::new(),
                     base_classes: symbol.base_classes.join(","),
+                    feature_flags: symbol.feature_flags.join(","),
+                    is_deprecated: symbol.is_deprecated,
+                    deprecation_message: symbol.deprecation_message.clone().unwrap_or_default(),
+                    awaits_count: symbol.awaits_count,
+                    io_calls_outside_try: symbol.io_calls_outside_try,
+                    has_empty_catch: symbol.has_empty_catch,
+                    rethrows: symbol.rethrows,
+                    panic_points: symbol.panic_points.len(),
+                    sql_tables: symbol.embedded_sql_tables(),
+                    partial_of: symbol.partial_of.clone().unwrap_or_default(),
+                    parent,
+                    is_generated: summary.is_generated,
                 };
 
                 (symbol, hash, entry)
@@ -374,87 +465,48 @@ impl LayerSynchronizer {
             .map(|(symbol, hash, _)| (symbol.clone(), hash.clone()))
             .collect();
 
-        // Update overlay with new symbols
-        state.write(|index| {
-            let overlay = index.layer_mut(layer);
-
-            for (symbol, hash, entry) in symbols_with_hashes {
-                new_hashes.insert(hash.clone());
-                index_entries.push(entry);
-
-                let is_new = !existing_hashes.contains(&hash);
-                let symbol_state = SymbolState::active(symbol);
-
-                if is_new {
-                    stats.symbols_added += 1;
-                } else {
-                    stats.symbols_modified += 1;
-                }
-
-                overlay.upsert(hash, symbol_state);
-            }
+        Ok(Some(StagedFile {
+            file_path: file_path.clone(),
+            full_path,
+            symbols: Some(symbols_with_hashes),
+            existing_hashes,
+            new_symbols_for_check,
+            index_entries: Vec::new(),
+            stats,
+        }))
+    }
 
-            // Mark removed symbols as deleted
-            for hash in existing_hashes.difference(&new_hashes) {
-                overlay.delete(hash);
-                stats.symbols_removed += 1;
-            }
-        });
+    /// Check an applied file for duplicates and update the disk cache
+    fn finish_file(&self, file: StagedFile) -> LayerUpdateStats {
+        let full_path = file.full_path.to_string_lossy();
 
         // Check for duplicates in newly added symbols (non-blocking)
-        if !new_symbols_for_check.is_empty() {
-            self.check_and_emit_duplicates(&new_symbols_for_check, &full_path.to_string_lossy());
+        if !file.new_symbols_for_check.is_empty() {
+            self.check_and_emit_duplicates(&file.new_symbols_for_check, &full_path);
         }
 
         // Update disk cache if available
-        if let Some(ref cache_dir) = self.cache_dir {
-            if let Err(e) =
-                cache_dir.update_symbol_index_for_file(&full_path.to_string_lossy(), index_entries)
-            {
+        if let (Some(cache_dir), Some(_)) = (&self.cache_dir, &file.symbols) {
+            let symbol_count = file.index_entries.len();
+            if let Err(e) = cache_dir.update_symbol_index_for_file(&full_path, file.index_entries) {
                 tracing::warn!(
                     "[SYNC] Failed to update disk cache for {:?}: {}",
-                    file_path,
+                    file.file_path,
                     e
                 );
             } else {
                 tracing::info!(
                     "[SYNC] Updated disk cache for {:?}: {} symbols",
-                    file_path,
-                    new_hashes.len()
+                    file.file_path,
+                    symbol_count
                 );
             }
         }
 
-        stats.files_processed = 1;
-        Ok(stats)
-    }
-
-    /// Mark all symbols from a deleted file as deleted
-    fn mark_file_deleted(
-        &self,
-        state: &ServerState,
-        layer: LayerKind,
-        file_path: &PathBuf,
-    ) -> Result<LayerUpdateStats> {
-        let mut stats = LayerUpdateStats::default();
-
-        state.write(|index| {
-            let overlay = index.layer_mut(layer);
-            // Get hashes from symbols_by_file directly
-            let hashes = overlay
-                .symbols_by_file
-                .get(file_path)
-                .cloned()
-                .unwrap_or_default();
-
-            for hash in hashes {
-                overlay.delete(&hash);
-                stats.symbols_removed += 1;
-            }
-        });
-
-        stats.files_processed = 1;
-        Ok(stats)
+        LayerUpdateStats {
+            files_processed: 1,
+            ..file.stats
+        }
     }
 
     /// Rebase layer - reconcile overlay with new base
@@ -699,6 +751,74 @@ impl LayerSynchronizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::process::Command;
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("git should run");
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    /// Base layer built from scratch over `files`
+    fn fresh_index(root: &std::path::Path, files: &[PathBuf]) -> ServerState {
+        let state = ServerState::new(root.to_path_buf());
+        LayerSynchronizer::new(root.to_path_buf())
+            .incremental_update(&state, LayerKind::Base, files)
+            .unwrap();
+        state
+    }
+
+    #[test]
+    fn test_edit_commit_compact_matches_fresh_index() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-b", "main"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        git(root, &["config", "user.name", "Test User"]);
+
+        let files = [PathBuf::from("src/lib.rs"), PathBuf::from("src/util.rs")];
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "pub fn parse(s: &str) -> u32 { s.len() as u32 }\npub fn render() {}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/util.rs"), "pub fn helper() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-m", "initial"]);
+
+        let state = fresh_index(root, &files);
+        let synchronizer = LayerSynchronizer::new(root.to_path_buf());
+
+        // Edit: change parse's signature, drop render, add format
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "pub fn parse(s: &str, radix: u32) -> u32 { radix }\npub fn format() {}\n",
+        )
+        .unwrap();
+        synchronizer
+            .incremental_update(&state, LayerKind::Working, &files[..1])
+            .unwrap();
+        assert!(state.stats().working_symbols > 0);
+        assert!(state.stats().total_deleted > 0);
+
+        git(root, &["commit", "-am", "edit"]);
+        let stats = state.compact(LayerKind::Base).unwrap();
+        assert_eq!(stats.affected_files, vec![files[0].clone()]);
+
+        let fresh = fresh_index(root, &files);
+        state.read(|index| {
+            fresh.read(|expected| {
+                assert_eq!(index.base.symbols, expected.base.symbols);
+                assert_eq!(index.stats(), expected.stats());
+                assert_eq!(index.all_symbol_hashes(), expected.all_symbol_hashes());
+                assert!(index.working.is_empty());
+            })
+        });
+    }
 
     #[test]
     fn test_layer_update_stats_fresh() {
@@ -8,24 +8,28 @@
 //! - Recursive directory watching
 //! - .gitignore pattern respect
 //! - Debounced events (100ms window to batch rapid changes)
+//! - Batched updates: a burst of changes (e.g. a branch switch touching
+//!   thousands of files) is applied as a single incremental update
 //! - Automatic Working layer updates within 500ms of file save
 //!
 //! # Architecture
 //!
 //! ```text
-//! ┌─────────────┐     ┌─────────────┐     ┌─────────────────┐
-//! │   notify    │────>│  debouncer  │────>│ LayerSynchronizer│
-//! │   watcher   │     │  (100ms)    │     │   (Working)      │
-//! └─────────────┘     └─────────────┘     └─────────────────┘
+//! ┌─────────────┐     ┌─────────────┐     ┌─────────────┐     ┌─────────────────┐
+//! │   notify    │────>│  debouncer  │────>│ ChangeBatch │────>│ LayerSynchronizer│
+//! │   watcher   │     │  (100ms)    │     │ (quiet/max) │     │   (Working)      │
+//! └─────────────┘     └─────────────┘     └─────────────┘     └─────────────────┘
 //! ```
 
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use notify::RecursiveMode;
-use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind};
 use parking_lot::Mutex;
 
 use crate::drift::UpdateStrategy;
@@ -39,7 +43,14 @@ use super::sync::LayerSynchronizer;
 #[derive(Debug, Clone)]
 pub struct WatcherConfig {
     /// Debounce duration (default: 100ms)
+    ///
+    /// Changes are batched until no new event arrives for this long.
     pub debounce_duration: Duration,
+    /// Longest a batch waits for the events to go quiet (default: 2s)
+    ///
+    /// Bounds update latency while files keep changing, e.g. during a
+    /// long-running checkout or a build writing into the tree.
+    pub max_batch_delay: Duration,
     /// Whether to respect .gitignore patterns
     pub respect_gitignore: bool,
     /// File extensions to watch (empty = all supported)
@@ -50,6 +61,7 @@ impl Default for WatcherConfig {
     fn default() -> Self {
         Self {
             debounce_duration: Duration::from_millis(100),
+            max_batch_delay: Duration::from_secs(2),
             respect_gitignore: true,
             extensions: vec![],
         }
@@ -121,6 +133,7 @@ impl FileWatcher {
 
         let repo_root = self.repo_root.clone();
         let debounce_duration = self.config.debounce_duration;
+        let max_batch_delay = self.config.max_batch_delay;
         let running = Arc::clone(&self.running);
         let pending = Arc::clone(&self.pending_changes);
 
@@ -154,120 +167,136 @@ impl FileWatcher {
                 None => LayerSynchronizer::new(repo_root.clone()),
             };
 
-            // Track recently processed files to avoid re-processing
-            // Key: file path, Value: last processed time
-            let mut recently_processed: std::collections::HashMap<PathBuf, std::time::Instant> =
-                std::collections::HashMap::new();
-            let cooldown_duration = Duration::from_secs(3); // 3 second cooldown per file
-
-            while handle_running.load(Ordering::SeqCst) {
-                // Clean up old entries from recently_processed (older than 2x cooldown)
-                let now = std::time::Instant::now();
-                recently_processed.retain(|_, processed_at| {
-                    now.duration_since(*processed_at) < cooldown_duration * 2
-                });
-
-                // Receive events with timeout
-                match rx.recv_timeout(Duration::from_millis(100)) {
-                    Ok(Ok(events)) => {
-                        tracing::debug!("[WATCHER] Received {} raw events", events.len());
-
-                        // Collect changed file paths, filtering out recently processed
-                        let mut changed_files = Vec::new();
-                        let now = std::time::Instant::now();
-
-                        for event in events {
-                            tracing::debug!(
-                                "[WATCHER] Event kind: {:?}, path: {:?}",
-                                event.kind,
-                                event.path
+            Self::process_events(
+                &rx,
+                &repo_root,
+                &handle_running,
+                ChangeBatch::new(debounce_duration, max_batch_delay),
+                |changed_files| {
+                    // Add to pending changes
+                    pending.lock().extend(changed_files.clone());
+
+                    // Trigger incremental update
+                    let strategy = UpdateStrategy::Incremental(changed_files);
+                    match synchronizer.update_layer(&state, LayerKind::Working, strategy) {
+                        Ok(stats) => {
+                            tracing::info!("[WATCHER] Layer updated successfully, emitting event");
+                            // Emit event for CLI
+                            let event = super::events::LayerUpdatedEvent::from_stats(
+                                LayerKind::Working,
+                                &stats,
                             );
-                            if matches!(event.kind, DebouncedEventKind::Any) {
-                                // Filter out ignored paths
-                                let path = event.path;
-                                if Self::should_watch_path(&path, &repo_root) {
-                                    // Convert to relative path
-                                    if let Ok(rel_path) = path.strip_prefix(&repo_root) {
-                                        // Check if file is in cooldown period
-                                        if let Some(processed_at) = recently_processed.get(rel_path)
-                                        {
-                                            if now.duration_since(*processed_at) < cooldown_duration
-                                            {
-                                                tracing::debug!(
-                                                    "[WATCHER] Skipping {:?} (in cooldown)",
-                                                    rel_path
-                                                );
-                                                continue;
-                                            }
-                                        }
-                                        tracing::debug!("[WATCHER] Accepted file: {:?}", rel_path);
-                                        changed_files.push(rel_path.to_path_buf());
-                                    }
-                                } else {
-                                    tracing::debug!("[WATCHER] Filtered out: {:?}", path);
-                                }
-                            }
+                            super::events::emit_event(&event);
+                            tracing::info!("[WATCHER] Event emitted");
                         }
+                        Err(e) => {
+                            tracing::error!("[WATCHER] Failed to update working layer: {}", e);
+                        }
+                    }
+                },
+            );
 
-                        if !changed_files.is_empty() {
-                            tracing::info!(
-                                "[WATCHER] Processing {} changed files: {:?}",
-                                changed_files.len(),
-                                changed_files
-                            );
+            // Keep debouncer alive until thread exits
+            drop(debouncer);
+        });
 
-                            // Mark files as recently processed BEFORE processing
-                            // This prevents re-processing during the cooldown
-                            let now = std::time::Instant::now();
-                            for file in &changed_files {
-                                recently_processed.insert(file.clone(), now);
-                            }
+        Ok(WatcherHandle { running })
+    }
 
-                            // Add to pending changes
-                            pending.lock().extend(changed_files.clone());
-
-                            // Trigger incremental update
-                            let strategy = UpdateStrategy::Incremental(changed_files.clone());
-                            match synchronizer.update_layer(&state, LayerKind::Working, strategy) {
-                                Ok(stats) => {
-                                    tracing::info!(
-                                        "[WATCHER] Layer updated successfully, emitting event"
-                                    );
-                                    // Emit event for CLI
-                                    let event = super::events::LayerUpdatedEvent::from_stats(
-                                        LayerKind::Working,
-                                        &stats,
-                                    );
-                                    super::events::emit_event(&event);
-                                    tracing::info!("[WATCHER] Event emitted");
-                                }
-                                Err(e) => {
-                                    tracing::error!(
-                                        "[WATCHER] Failed to update working layer: {}",
-                                        e
-                                    );
+    /// Batch debounced events from `rx` and hand each finished batch of
+    /// changed files (relative to `repo_root`) to `apply`
+    ///
+    /// Runs until `running` is cleared or the channel closes.
+    fn process_events(
+        rx: &Receiver<DebounceEventResult>,
+        repo_root: &PathBuf,
+        running: &AtomicBool,
+        mut batch: ChangeBatch,
+        mut apply: impl FnMut(Vec<PathBuf>),
+    ) {
+        // Track recently processed files to avoid re-processing
+        // Key: file path, Value: last processed time
+        let mut recently_processed: std::collections::HashMap<PathBuf, std::time::Instant> =
+            std::collections::HashMap::new();
+        let cooldown_duration = Duration::from_secs(3); // 3 second cooldown per file
+
+        while running.load(Ordering::SeqCst) {
+            // Clean up old entries from recently_processed (older than 2x cooldown)
+            let now = std::time::Instant::now();
+            recently_processed.retain(|_, processed_at| {
+                now.duration_since(*processed_at) < cooldown_duration * 2
+            });
+
+            // Receive events with timeout
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(Ok(events)) => {
+                    tracing::debug!("[WATCHER] Received {} raw events", events.len());
+
+                    // Collect changed file paths, filtering out recently processed
+                    let now = std::time::Instant::now();
+
+                    for event in events {
+                        tracing::debug!(
+                            "[WATCHER] Event kind: {:?}, path: {:?}",
+                            event.kind,
+                            event.path
+                        );
+                        if matches!(event.kind, DebouncedEventKind::Any) {
+                            // Filter out ignored paths
+                            let path = event.path;
+                            if Self::should_watch_path(&path, repo_root) {
+                                // Convert to relative path
+                                if let Ok(rel_path) = path.strip_prefix(repo_root) {
+                                    // Check if file is in cooldown period
+                                    if let Some(processed_at) = recently_processed.get(rel_path) {
+                                        if now.duration_since(*processed_at) < cooldown_duration {
+                                            tracing::debug!(
+                                                "[WATCHER] Skipping {:?} (in cooldown)",
+                                                rel_path
+                                            );
+                                            continue;
+                                        }
+                                    }
+                                    tracing::debug!("[WATCHER] Accepted file: {:?}", rel_path);
+                                    batch.push(rel_path.to_path_buf(), now);
                                 }
+                            } else {
+                                tracing::debug!("[WATCHER] Filtered out: {:?}", path);
                             }
                         }
                     }
-                    Ok(Err(e)) => {
-                        tracing::error!("Watcher error: {:?}", e);
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                        // No events, continue loop
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                        // Channel closed, exit
-                        break;
-                    }
+                }
+                Ok(Err(e)) => {
+                    tracing::error!("Watcher error: {:?}", e);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // No events, continue loop
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    // Channel closed, exit
+                    break;
                 }
             }
 
-            // Keep debouncer alive until thread exits
-            drop(debouncer);
-        });
+            // Apply the batch as one update once the burst is over
+            if batch.is_ready(std::time::Instant::now()) {
+                let changed_files = batch.take();
+                tracing::info!(
+                    "[WATCHER] Processing {} changed files: {:?}",
+                    changed_files.len(),
+                    changed_files
+                );
+
+                // Mark files as recently processed BEFORE processing
+                // This prevents re-processing during the cooldown
+                let now = std::time::Instant::now();
+                for file in &changed_files {
+                    recently_processed.insert(file.clone(), now);
+                }
 
-        Ok(WatcherHandle { running })
+                apply(changed_files);
+            }
+        }
     }
 
     /// Stop watching for file changes
@@ -331,6 +360,61 @@ impl FileWatcher {
     }
 }
 
+/// Changed files collected across debounced event batches
+///
+/// A batch is ready once no change has arrived for the debounce window, or
+/// once its first change has waited `max_delay`, so a burst of events turns
+/// into a single incremental update with the full changed set.
+struct ChangeBatch {
+    /// Quiet period after the last change
+    debounce: Duration,
+    /// Longest the first change may wait
+    max_delay: Duration,
+    /// Changed files, deduplicated
+    files: BTreeSet<PathBuf>,
+    /// When the first change of the batch arrived
+    first_at: Option<Instant>,
+    /// When the most recent change arrived
+    last_at: Option<Instant>,
+}
+
+impl ChangeBatch {
+    fn new(debounce: Duration, max_delay: Duration) -> Self {
+        Self {
+            debounce,
+            max_delay,
+            files: BTreeSet::new(),
+            first_at: None,
+            last_at: None,
+        }
+    }
+
+    /// Record a changed file seen at `now`
+    fn push(&mut self, file: PathBuf, now: Instant) {
+        self.files.insert(file);
+        self.first_at.get_or_insert(now);
+        self.last_at = Some(now);
+    }
+
+    /// Whether the batch should be applied at `now`
+    fn is_ready(&self, now: Instant) -> bool {
+        match (self.first_at, self.last_at) {
+            (Some(first), Some(last)) => {
+                now.duration_since(last) >= self.debounce
+                    || now.duration_since(first) >= self.max_delay
+            }
+            _ => false,
+        }
+    }
+
+    /// Take the changed files, sorted, and start a new batch
+    fn take(&mut self) -> Vec<PathBuf> {
+        self.first_at = None;
+        self.last_at = None;
+        std::mem::take(&mut self.files).into_iter().collect()
+    }
+}
+
 /// Handle for controlling a running watcher
 pub struct WatcherHandle {
     running: Arc<AtomicBool>,
@@ -366,6 +450,7 @@ mod tests {
     fn test_watcher_config_default() {
         let config = WatcherConfig::default();
         assert_eq!(config.debounce_duration, Duration::from_millis(100));
+        assert_eq!(config.max_batch_delay, Duration::from_secs(2));
         assert!(config.respect_gitignore);
         assert!(config.extensions.is_empty());
     }
@@ -414,6 +499,91 @@ mod tests {
         // (but files inside need to be source files)
     }
 
+    #[test]
+    fn test_change_batch_coalesces_burst() {
+        let mut batch = ChangeBatch::new(Duration::from_millis(100), Duration::from_secs(2));
+        let start = Instant::now();
+        assert!(!batch.is_ready(start));
+
+        // A checkout touching many files, some more than once, 2ms apart
+        let mut expected = BTreeSet::new();
+        for i in 0..500 {
+            let at = start + Duration::from_millis(i * 2);
+            let file = PathBuf::from(format!("src/file{}.rs", i % 400));
+            expected.insert(file.clone());
+            batch.push(file, at);
+            assert!(!batch.is_ready(at + Duration::from_millis(10)));
+        }
+
+        let quiet = start + Duration::from_millis(998 + 100);
+        assert!(batch.is_ready(quiet));
+        let files = batch.take();
+        assert_eq!(files.len(), 400);
+        assert_eq!(files, expected.into_iter().collect::<Vec<_>>());
+
+        // Nothing left for a second update
+        assert!(!batch.is_ready(quiet + Duration::from_secs(10)));
+        assert!(batch.take().is_empty());
+    }
+
+    #[test]
+    fn test_change_batch_max_delay() {
+        let mut batch = ChangeBatch::new(Duration::from_millis(100), Duration::from_millis(500));
+        let start = Instant::now();
+
+        // Events that never go quiet still flush after the max delay
+        for i in 0..10 {
+            let at = start + Duration::from_millis(i * 50);
+            batch.push(PathBuf::from(format!("src/{}.ts", i)), at);
+            assert_eq!(batch.is_ready(at), i * 50 >= 500);
+        }
+        assert!(batch.is_ready(start + Duration::from_millis(500)));
+        assert_eq!(batch.take().len(), 10);
+    }
+
+    #[test]
+    fn test_event_burst_applies_one_update() {
+        use notify_debouncer_mini::DebouncedEvent;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (updates_tx, updates) = std::sync::mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let loop_running = Arc::clone(&running);
+        let worker = std::thread::spawn(move || {
+            FileWatcher::process_events(
+                &rx,
+                &PathBuf::from("/repo"),
+                &loop_running,
+                ChangeBatch::new(Duration::from_millis(200), Duration::from_secs(5)),
+                |files| updates_tx.send(files).unwrap(),
+            );
+        });
+
+        // Several debounced event sets in quick succession, with repeats and
+        // paths the watcher ignores
+        let event = |path: &str| DebouncedEvent::new(PathBuf::from(path), DebouncedEventKind::Any);
+        for i in 0..5 {
+            let events = vec![
+                event(&format!("/repo/src/file{}.rs", i)),
+                event("/repo/src/lib.rs"),
+                event("/repo/node_modules/pkg/index.js"),
+            ];
+            tx.send(Ok(events)).unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let files = updates.recv_timeout(Duration::from_secs(5)).unwrap();
+        let expected: Vec<_> = ["file0", "file1", "file2", "file3", "file4", "lib"]
+            .iter()
+            .map(|name| PathBuf::from(format!("src/{}.rs", name)))
+            .collect();
+        assert_eq!(files, expected);
+        assert!(updates.recv_timeout(Duration::from_millis(500)).is_err());
+
+        running.store(false, Ordering::SeqCst);
+        worker.join().unwrap();
+    }
+
     #[test]
     fn test_watcher_creation() {
         let watcher = FileWatcher::new(PathBuf::from("/tmp/test"));
@@ -0,0 +1,571 @@
+//! Analysis defaults layered from config files
+//!
+//! Defaults for duplicate detection, search and indexing come from, in
+//! increasing precedence:
+//!
+//! 1. built-in defaults
+//! 2. the global config, `~/.config/semfora/config.toml`
+//! 3. the repository config, `.semfora/config.toml` in the repository (or the
+//!    nearest directory above the analyzed path that has one)
+//! 4. the flags of a CLI invocation or the parameters of an MCP call
+//!
+//! The first three are merged into a [`RepoSettings`]; flags and parameters
+//! are applied by the command handling the call. Settings that shape the
+//! index are fingerprinted, so an index built under other settings is
+//! rebuilt rather than served.
+//!
+//! ```toml
+//! [duplicates]
+//! threshold = 0.85
+//! include_boilerplate = false
+//!
+//! [search]
+//! limit = 30
+//!
+//! [index]
+//! extensions = ["ts", "tsx"]
+//! max_depth = 12
+//! exclude = ["src/generated", "**/*.pb.go"]
+//!
+//! [generated]
+//! patterns = ["src/api/client", "*.gen.ts"]
+//! markers = ["Generated by openapi-generator"]
+//! not_generated = ["src/legacy_pb2.py"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::duplicate::boilerplate::matches_glob;
+use crate::error::{McpDiffError, Result};
+use crate::installer::platform::{Platform, SemforaPaths};
+use crate::schema::fnv1a_hash;
+use crate::workspace::repo_relative;
+
+/// Repository config file, relative to the repository root
+pub const REPO_CONFIG_FILE: &str = ".semfora/config.toml";
+
+/// `[duplicates]` section of a config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DuplicatesSettings {
+    /// Similarity threshold for duplicate detection (0.0-1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+
+    /// Count boilerplate (getters, trivial wrappers) as duplicates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_boilerplate: Option<bool>,
+}
+
+impl DuplicatesSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[search]` keys read as settings (the global `[search]` section also
+/// holds stopwords, see [`crate::installer::config::SearchConfig`])
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct SearchSettings {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// `[index]` section of a config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexSettings {
+    /// File extensions to index (empty = every supported language)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+
+    /// Maximum directory depth to index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+
+    /// Repo-relative directories or globs left out of the index and results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+}
+
+impl IndexSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[generated]` section of a config file (see [`crate::generated`])
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedSettings {
+    /// Extra directories or globs whose files are generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patterns: Option<Vec<String>>,
+
+    /// Extra header comments that mark a file as generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markers: Option<Vec<String>>,
+
+    /// Directories or globs never treated as generated (fixes misdetections)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_generated: Option<Vec<String>>,
+}
+
+impl GeneratedSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The settings sections of one config file; other sections are ignored
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SettingsLayer {
+    #[serde(default)]
+    duplicates: DuplicatesSettings,
+    #[serde(default)]
+    search: SearchSettings,
+    #[serde(default)]
+    index: IndexSettings,
+    #[serde(default)]
+    generated: GeneratedSettings,
+}
+
+impl SettingsLayer {
+    /// Read a config file; a missing file is an empty layer
+    fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| McpDiffError::IoError {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        toml::from_str(&content).map_err(|e| McpDiffError::ConfigError {
+            message: format!("Failed to parse {}: {}", path.display(), e),
+        })
+    }
+}
+
+/// Config layer a setting's value came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingSource {
+    #[default]
+    Default,
+    Global,
+    Repo,
+}
+
+impl fmt::Display for SettingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SettingSource::Default => "default",
+            SettingSource::Global => "global",
+            SettingSource::Repo => "repo",
+        })
+    }
+}
+
+/// Analysis defaults for one repository, merged from its config layers
+#[derive(Debug, Clone)]
+pub struct RepoSettings {
+    /// Similarity threshold for duplicate detection
+    pub duplicate_threshold: f64,
+    /// Count boilerplate as duplicates
+    pub include_boilerplate: bool,
+    /// Results returned by a search
+    pub search_limit: usize,
+    /// File extensions to index and analyze (empty = all supported)
+    pub extensions: Vec<String>,
+    /// Maximum directory depth to index and analyze
+    pub max_depth: usize,
+    /// Repo-relative directories or globs left out of the index and results
+    pub exclude: Vec<String>,
+    /// Directories or globs whose files are generated, besides the built-in
+    /// file name patterns
+    pub generated_patterns: Vec<String>,
+    /// Header comments marking a file generated, besides the built-in ones
+    pub generated_markers: Vec<String>,
+    /// Directories or globs never treated as generated
+    pub not_generated: Vec<String>,
+    /// Directory the repository config belongs to; excluded paths are
+    /// relative to it
+    pub root: PathBuf,
+    /// Global config file, when it exists
+    pub global_file: Option<PathBuf>,
+    /// Repository config file, when one was found
+    pub repo_file: Option<PathBuf>,
+    sources: BTreeMap<&'static str, SettingSource>,
+}
+
+impl Default for RepoSettings {
+    fn default() -> Self {
+        Self {
+            duplicate_threshold: 0.90,
+            include_boilerplate: false,
+            search_limit: 20,
+            extensions: Vec::new(),
+            max_depth: 10,
+            exclude: Vec::new(),
+            generated_patterns: Vec::new(),
+            generated_markers: Vec::new(),
+            not_generated: Vec::new(),
+            root: PathBuf::new(),
+            global_file: None,
+            repo_file: None,
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+impl RepoSettings {
+    /// Keys of every setting, in display order
+    pub const KEYS: &'static [&'static str] = &[
+        "duplicates.threshold",
+        "duplicates.include_boilerplate",
+        "search.limit",
+        "index.extensions",
+        "index.max_depth",
+        "index.exclude",
+        "generated.patterns",
+        "generated.markers",
+        "generated.not_generated",
+    ];
+
+    /// Settings for the repository at (or containing) `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let global = SemforaPaths::for_platform(&Platform::detect()).config_file;
+        Self::load_with_global(path, &global)
+    }
+
+    /// Like [`RepoSettings::load`], falling back to the defaults when a
+    /// config file cannot be read
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring config: {}", e);
+            Self {
+                root: Self::start_dir(path),
+                ..Self::default()
+            }
+        })
+    }
+
+    /// Settings for `path`, with the global config read from `global`
+    pub fn load_with_global(path: &Path, global: &Path) -> Result<Self> {
+        let start = Self::start_dir(path);
+        let repo_file = start
+            .ancestors()
+            .map(|dir| dir.join(REPO_CONFIG_FILE))
+            .find(|file| file.is_file());
+
+        let mut settings = Self {
+            root: match &repo_file {
+                Some(file) => file
+                    .parent()
+                    .and_then(Path::parent)
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| start.clone()),
+                None => start,
+            },
+            global_file: global.is_file().then(|| global.to_path_buf()),
+            repo_file: repo_file.clone(),
+            ..Self::default()
+        };
+        settings.merge(SettingsLayer::read(global)?, SettingSource::Global);
+        if let Some(file) = repo_file {
+            settings.merge(SettingsLayer::read(&file)?, SettingSource::Repo);
+        }
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Canonical directory to look for the repository config from
+    fn start_dir(path: &Path) -> PathBuf {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if path.is_file() {
+            path.parent().map(Path::to_path_buf).unwrap_or(path)
+        } else {
+            path
+        }
+    }
+
+    fn merge(&mut self, layer: SettingsLayer, source: SettingSource) {
+        if let Some(threshold) = layer.duplicates.threshold {
+            self.duplicate_threshold = threshold;
+            self.sources.insert("duplicates.threshold", source);
+        }
+        if let Some(include) = layer.duplicates.include_boilerplate {
+            self.include_boilerplate = include;
+            self.sources
+                .insert("duplicates.include_boilerplate", source);
+        }
+        if let Some(limit) = layer.search.limit {
+            self.search_limit = limit;
+            self.sources.insert("search.limit", source);
+        }
+        if let Some(extensions) = layer.index.extensions {
+            self.extensions = extensions
+                .into_iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect();
+            self.sources.insert("index.extensions", source);
+        }
+        if let Some(max_depth) = layer.index.max_depth {
+            self.max_depth = max_depth;
+            self.sources.insert("index.max_depth", source);
+        }
+        if let Some(exclude) = layer.index.exclude {
+            self.exclude = exclude;
+            self.sources.insert("index.exclude", source);
+        }
+        if let Some(patterns) = layer.generated.patterns {
+            self.generated_patterns = patterns;
+            self.sources.insert("generated.patterns", source);
+        }
+        if let Some(markers) = layer.generated.markers {
+            self.generated_markers = markers;
+            self.sources.insert("generated.markers", source);
+        }
+        if let Some(not_generated) = layer.generated.not_generated {
+            self.not_generated = not_generated;
+            self.sources.insert("generated.not_generated", source);
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.duplicate_threshold) {
+            return Err(McpDiffError::ConfigError {
+                message: format!(
+                    "duplicates.threshold must be between 0.0 and 1.0 (got {})",
+                    self.duplicate_threshold
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Layer the value of `key` came from
+    pub fn source(&self, key: &str) -> SettingSource {
+        self.sources.get(key).copied().unwrap_or_default()
+    }
+
+    /// Value of `key` as JSON
+    pub fn value(&self, key: &str) -> serde_json::Value {
+        match key {
+            "duplicates.threshold" => serde_json::json!(self.duplicate_threshold),
+            "duplicates.include_boilerplate" => serde_json::json!(self.include_boilerplate),
+            "search.limit" => serde_json::json!(self.search_limit),
+            "index.extensions" => serde_json::json!(self.extensions),
+            "index.max_depth" => serde_json::json!(self.max_depth),
+            "index.exclude" => serde_json::json!(self.exclude),
+            "generated.patterns" => serde_json::json!(self.generated_patterns),
+            "generated.markers" => serde_json::json!(self.generated_markers),
+            "generated.not_generated" => serde_json::json!(self.not_generated),
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    /// Whether `file` (repo-relative or absolute) is under an `index.exclude`
+    /// directory or matches an `index.exclude` glob
+    pub fn is_excluded(&self, file: impl AsRef<Path>) -> bool {
+        if self.exclude.is_empty() {
+            return false;
+        }
+        // The root is canonical, so absolute paths are too before comparing
+        let file = file.as_ref();
+        let file = match file.canonicalize() {
+            Ok(canonical) if file.is_absolute() => canonical,
+            _ => file.to_path_buf(),
+        };
+        let relative = repo_relative(&self.root, &file.to_string_lossy());
+        self.exclude.iter().any(|pattern| {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            matches_glob(pattern, &relative) || matches_glob(&format!("{}/**", pattern), &relative)
+        })
+    }
+
+    /// Whether `file` matches a `generated.patterns` directory or glob
+    pub fn is_generated_path(&self, file: impl AsRef<Path>) -> bool {
+        self.matches_any(&self.generated_patterns, file.as_ref())
+    }
+
+    /// Whether `file` matches a `generated.not_generated` directory or glob
+    pub fn is_not_generated(&self, file: impl AsRef<Path>) -> bool {
+        self.matches_any(&self.not_generated, file.as_ref())
+    }
+
+    /// Whether `file` is under one of the `patterns` directories or matches
+    /// one of its globs; a pattern without `/` may match the file name alone
+    fn matches_any(&self, patterns: &[String], file: &Path) -> bool {
+        if patterns.is_empty() {
+            return false;
+        }
+        let relative = repo_relative(&self.root, &file.to_string_lossy());
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        patterns.iter().any(|pattern| {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            matches_glob(pattern, &relative)
+                || matches_glob(&format!("{}/**", pattern), &relative)
+                || (!pattern.contains('/') && matches_glob(pattern, &name))
+        })
+    }
+
+    /// Whether an indexed file with `ext` should be collected
+    pub fn includes_extension(&self, ext: &str) -> bool {
+        self.extensions.is_empty() || self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+    }
+
+    /// Fingerprint of the settings that shape the index
+    ///
+    /// Stored with the index; an index whose fingerprint differs from the
+    /// current settings' is rebuilt.
+    pub fn index_fingerprint(&self) -> String {
+        let input = format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.extensions.join(","),
+            self.max_depth,
+            self.exclude.join(","),
+            self.generated_patterns.join(","),
+            self.generated_markers.join(","),
+            self.not_generated.join(",")
+        );
+        format!("{:016x}", fnv1a_hash(&input))
+    }
+
+    /// Merged settings with the source of each value, as JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        let settings: serde_json::Map<String, serde_json::Value> = Self::KEYS
+            .iter()
+            .map(|key| {
+                (
+                    key.to_string(),
+                    serde_json::json!({
+                        "value": self.value(key),
+                        "source": self.source(key),
+                    }),
+                )
+            })
+            .collect();
+        serde_json::json!({
+            "_type": "effective_config",
+            "global_config": self.global_file.as_ref().map(|p| p.display().to_string()),
+            "repo_config": self.repo_file.as_ref().map(|p| p.display().to_string()),
+            "settings": settings,
+        })
+    }
+
+    /// Merged settings as TOML, each value commented with its source
+    pub fn display(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!(
+            "# global: {}\n",
+            self.global_file
+                .as_ref()
+                .map_or("(none)".to_string(), |p| p.display().to_string())
+        ));
+        output.push_str(&format!(
+            "# repo:   {}\n",
+            self.repo_file
+                .as_ref()
+                .map_or("(none)".to_string(), |p| p.display().to_string())
+        ));
+
+        let mut section = "";
+        for key in Self::KEYS {
+            let (table, name) = key.split_once('.').unwrap_or(("", key));
+            if table != section {
+                output.push_str(&format!("\n[{}]\n", table));
+                section = table;
+            }
+            let line = format!("{} = {}", name, self.value(key));
+            output.push_str(&format!("{:<40} # {}\n", line, self.source(key)));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_repo_config_overrides_global() {
+        let home = tempdir().unwrap();
+        let global = home.path().join("config.toml");
+        fs::write(
+            &global,
+            "[search]\nlimit = 50\nstopwords_add = [\"handler\"]\n\n[duplicates]\nthreshold = 0.8\n",
+        )
+        .unwrap();
+
+        let repo = tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".semfora")).unwrap();
+        fs::create_dir_all(repo.path().join("src/api")).unwrap();
+        fs::write(
+            repo.path().join(REPO_CONFIG_FILE),
+            "[duplicates]\nthreshold = 0.75\n\n[index]\nexclude = [\"src/generated\", \"**/*.pb.go\"]\n",
+        )
+        .unwrap();
+
+        // Found from a subdirectory too
+        let settings =
+            RepoSettings::load_with_global(&repo.path().join("src/api"), &global).unwrap();
+        assert_eq!(settings.duplicate_threshold, 0.75);
+        assert_eq!(settings.source("duplicates.threshold"), SettingSource::Repo);
+        assert_eq!(settings.search_limit, 50);
+        assert_eq!(settings.source("search.limit"), SettingSource::Global);
+        assert_eq!(settings.max_depth, 10);
+        assert_eq!(settings.source("index.max_depth"), SettingSource::Default);
+
+        assert!(settings.is_excluded("src/generated/client.ts"));
+        assert!(settings.is_excluded(
+            &settings
+                .root
+                .join("src/generated/deep/a.ts")
+                .display()
+                .to_string()
+        ));
+        assert!(settings.is_excluded("./proto/api.pb.go"));
+        assert!(!settings.is_excluded("src/generated_docs/a.ts"));
+        assert!(!settings.is_excluded("src/api/users.ts"));
+    }
+
+    #[test]
+    fn test_index_fingerprint_tracks_index_settings() {
+        let dir = tempdir().unwrap();
+        let global = dir.path().join("missing.toml");
+        let defaults = RepoSettings::load_with_global(dir.path(), &global).unwrap();
+
+        fs::create_dir_all(dir.path().join(".semfora")).unwrap();
+        let config = dir.path().join(REPO_CONFIG_FILE);
+        fs::write(&config, "[search]\nlimit = 5\n").unwrap();
+        let search_only = RepoSettings::load_with_global(dir.path(), &global).unwrap();
+        assert_eq!(
+            defaults.index_fingerprint(),
+            search_only.index_fingerprint()
+        );
+
+        fs::write(&config, "[index]\nexclude = [\"vendor\"]\n").unwrap();
+        let excluded = RepoSettings::load_with_global(dir.path(), &global).unwrap();
+        assert_ne!(defaults.index_fingerprint(), excluded.index_fingerprint());
+    }
+
+    #[test]
+    fn test_invalid_threshold_rejected() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".semfora")).unwrap();
+        fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "[duplicates]\nthreshold = 85\n",
+        )
+        .unwrap();
+        let global = dir.path().join("missing.toml");
+        assert!(RepoSettings::load_with_global(dir.path(), &global).is_err());
+    }
+}
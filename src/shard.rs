@@ -16,17 +16,30 @@ use std::sync::Arc;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::analysis::{calculate_cognitive_complexity, max_nesting_depth};
+use crate::analysis::{
+    calculate_cognitive_complexity, max_nesting_depth, resolve_python_call, PythonImportTarget,
+    PythonPackageIndex,
+};
 use crate::bm25::{extract_terms_from_file_path, Bm25Document};
-use crate::cache::{CacheDir, IndexingStatus, SourceFileInfo};
+use crate::cache::manifest::manifest_history;
+use crate::cache::{
+    sanitize_filename, CacheDir, IndexManifest, IndexingStatus, SourceFileInfo, SymbolIndexEntry,
+};
 use crate::duplicate::FunctionSignature;
 use crate::error::Result;
+use crate::hotspots::{churn_commits, HotspotIndex};
+use crate::line_symbols::FileHashes;
 use crate::module_registry::ModuleRegistrySqlite;
+use crate::module_usage::{mark_cyclic_imports, ModuleUsageIndex};
+use crate::ownership::{BlameCache, OwnershipIndex};
 use crate::schema::{
-    CallGraphEdge, RefKind, RepoOverview, RiskLevel, SemanticSummary, SymbolId, SymbolInfo,
-    SymbolKind, SCHEMA_VERSION,
+    fnv1a_hash, CallGraphEdge, PackageStats, RefKind, RepoOverview, RepoStats, RiskLevel,
+    SemanticSummary, SymbolId, SymbolInfo, SymbolKind, SCHEMA_VERSION,
 };
+use crate::settings::RepoSettings;
+use crate::test_links::TestLinkIndex;
 use crate::toon::{encode_toon, generate_repo_overview_with_modules, is_meaningful_call};
+use crate::workspace::{repo_relative, Workspace};
 use rusqlite::Connection;
 
 /// Package version from Cargo.toml
@@ -75,6 +88,44 @@ pub struct ModuleRegistry {
 
     /// Current global strip depth applied to all modules
     strip_depth: usize,
+
+    /// Number of names that had to be deduplicated
+    collision_count: usize,
+}
+
+/// How module (and module shard) names are derived from full module paths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShardNamingStrategy {
+    /// Shortest dotted path that stays unique (`game.player`)
+    #[default]
+    ModulePath,
+    /// FNV-1a hash of the full module path (`3f1c0e9a7b2d4c61`)
+    ContentHash,
+    /// First 8 characters of the shortened path plus 8 hash characters
+    /// of the full path (`game.pla-7b2d4c61`)
+    Hybrid,
+}
+
+impl ShardNamingStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShardNamingStrategy::ModulePath => "module-path",
+            ShardNamingStrategy::ContentHash => "content-hash",
+            ShardNamingStrategy::Hybrid => "hybrid",
+        }
+    }
+}
+
+/// Module names computed for a set of full module paths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardNames {
+    /// Names in the same order as the input paths
+    pub names: Vec<String>,
+    /// Strip depth of the shortened module paths
+    pub strip_depth: usize,
+    /// Number of names that had to be deduplicated
+    pub collision_count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -95,20 +146,64 @@ impl ModuleRegistry {
             full_to_short: HashMap::new(),
             short_to_full: HashMap::new(),
             strip_depth: 0,
+            collision_count: 0,
         }
     }
 
     /// Build a registry from a list of full module paths
     pub fn from_full_paths(full_paths: &[String]) -> Self {
-        let (short_names, strip_depth) = compute_optimal_names(full_paths);
+        Self::with_strategy(full_paths, ShardNamingStrategy::ModulePath)
+    }
+
+    /// Build a registry for workspace module paths (`pkg/src.components`).
+    ///
+    /// Names are shortened within each package and keep the `pkg/` prefix, so
+    /// the `src` of one package never competes with another's. Paths without
+    /// a package prefix are named among themselves.
+    pub fn with_strategy_namespaced(full_paths: &[String], strategy: ShardNamingStrategy) -> Self {
+        let mut groups: HashMap<Option<&str>, Vec<String>> = HashMap::new();
+        for full in full_paths {
+            match full.split_once('/') {
+                Some((package, inner)) => groups
+                    .entry(Some(package))
+                    .or_default()
+                    .push(inner.to_string()),
+                None => groups.entry(None).or_default().push(full.clone()),
+            }
+        }
+
+        let mut registry = Self::new();
+        for (package, inner_paths) in groups {
+            let group = Self::with_strategy(&inner_paths, strategy);
+            registry.strip_depth = registry.strip_depth.max(group.strip_depth);
+            registry.collision_count += group.collision_count;
+            for (inner, short) in group.full_to_short {
+                let (full, short) = match package {
+                    Some(package) => (
+                        format!("{}/{}", package, inner),
+                        format!("{}/{}", package, short),
+                    ),
+                    None => (inner, short),
+                };
+                registry.short_to_full.insert(short.clone(), full.clone());
+                registry.full_to_short.insert(full, short);
+            }
+        }
+        registry
+    }
+
+    /// Build a registry naming modules with `strategy`
+    pub fn with_strategy(full_paths: &[String], strategy: ShardNamingStrategy) -> Self {
+        let shard_names = compute_shard_names(full_paths, strategy);
 
         let mut registry = Self {
             full_to_short: HashMap::new(),
             short_to_full: HashMap::new(),
-            strip_depth,
+            strip_depth: shard_names.strip_depth,
+            collision_count: shard_names.collision_count,
         };
 
-        for (full, short) in full_paths.iter().zip(short_names.iter()) {
+        for (full, short) in full_paths.iter().zip(shard_names.names.iter()) {
             registry.full_to_short.insert(full.clone(), short.clone());
             registry.short_to_full.insert(short.clone(), full.clone());
         }
@@ -133,6 +228,11 @@ impl ModuleRegistry {
         self.strip_depth
     }
 
+    /// Number of names that had to be deduplicated
+    pub fn collision_count(&self) -> usize {
+        self.collision_count
+    }
+
     /// Check if a shortened name already exists (would cause conflict)
     #[allow(dead_code)]
     pub fn has_conflict(&self, short_name: &str) -> bool {
@@ -248,6 +348,55 @@ fn compute_optimal_names(full_paths: &[String]) -> (Vec<String>, usize) {
     (result, strip_depth)
 }
 
+/// Compute module names for `full_paths` with the given strategy.
+///
+/// Names are unique as shard file names: two names that would map to the
+/// same file (after sanitizing, compared case-insensitively for
+/// case-insensitive file systems) are deduplicated by suffixing `_2`, `_3`,
+/// ... to the later path in sorted order, and counted in `collision_count`.
+pub fn compute_shard_names(full_paths: &[String], strategy: ShardNamingStrategy) -> ShardNames {
+    let (short_names, strip_depth) = compute_optimal_names(full_paths);
+
+    let mut names: Vec<String> = full_paths
+        .iter()
+        .zip(short_names)
+        .map(|(full, short)| match strategy {
+            ShardNamingStrategy::ModulePath => short,
+            ShardNamingStrategy::ContentHash => format!("{:016x}", fnv1a_hash(full)),
+            ShardNamingStrategy::Hybrid => {
+                let prefix: String = short.chars().take(8).collect();
+                format!("{}-{:08x}", prefix, fnv1a_hash(full) as u32)
+            }
+        })
+        .collect();
+
+    // Deduplicate in sorted path order so suffixes don't depend on input order
+    let mut order: Vec<usize> = (0..full_paths.len()).collect();
+    order.sort_by(|&a, &b| full_paths[a].cmp(&full_paths[b]));
+
+    let file_key = |name: &str| sanitize_filename(name).to_lowercase();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut collision_count = 0;
+    for i in order {
+        if taken.insert(file_key(&names[i])) {
+            continue;
+        }
+        collision_count += 1;
+        let base = names[i].clone();
+        let mut n = 2;
+        while !taken.insert(file_key(&format!("{}_{}", base, n))) {
+            n += 1;
+        }
+        names[i] = format!("{}_{}", base, n);
+    }
+
+    ShardNames {
+        names,
+        strip_depth,
+        collision_count,
+    }
+}
+
 /// Public wrapper for `compute_optimal_names` - exposed for integration testing.
 ///
 /// This function is used by integration tests to verify the module naming algorithm
@@ -350,6 +499,18 @@ pub struct ShardWriter {
 
     /// Module name registry (computed at write time)
     module_registry: Option<ModuleRegistry>,
+
+    /// How module shard names are derived
+    naming: ShardNamingStrategy,
+
+    /// Attribute symbols to their top git blame author
+    with_blame: bool,
+
+    /// Monorepo packages; their modules are namespaced by package name
+    workspace: Option<Workspace>,
+
+    /// Repository config; its `[generated]` rules classify incoming summaries
+    settings: RepoSettings,
 }
 
 pub type ShardProgressCallback = Arc<dyn Fn(&str, usize, usize) + Send + Sync>;
@@ -365,6 +526,10 @@ impl ShardWriter {
             .trim_end_matches('/')
             .to_string();
 
+        let naming = cache.shard_naming();
+        let workspace = Workspace::detect(repo_path);
+        let settings = RepoSettings::load_or_default(repo_path);
+
         Ok(Self {
             cache,
             repo_root,
@@ -373,6 +538,10 @@ impl ShardWriter {
             overview: None,
             progress: IndexingStatus::default(),
             module_registry: None,
+            naming,
+            with_blame: false,
+            workspace,
+            settings,
         })
     }
 
@@ -381,6 +550,9 @@ impl ShardWriter {
     pub fn with_cache(cache: CacheDir) -> Result<Self> {
         cache.init()?;
 
+        let naming = cache.shard_naming();
+        let settings = RepoSettings::load_or_default(&cache.repo_root);
+
         Ok(Self {
             cache,
             repo_root: String::new(), // Will use extract_module_name fallback
@@ -389,27 +561,57 @@ impl ShardWriter {
             overview: None,
             progress: IndexingStatus::default(),
             module_registry: None,
+            naming,
+            with_blame: false,
+            workspace: None,
+            settings,
         })
     }
 
+    /// Record the indexing status shown in the repo overview (partial indexes)
+    pub fn set_progress(&mut self, progress: IndexingStatus) {
+        self.progress = progress;
+    }
+
+    /// Choose how module shard names are derived (default: the strategy the
+    /// existing index was written with, else module path)
+    pub fn set_naming_strategy(&mut self, naming: ShardNamingStrategy) {
+        self.naming = naming;
+    }
+
+    /// Attribute each symbol to the author of most of its lines (one
+    /// `git blame` per file, cached across runs)
+    pub fn set_with_blame(&mut self, with_blame: bool) {
+        self.with_blame = with_blame;
+    }
+
     /// Add summaries to be sharded
-    pub fn add_summaries(&mut self, summaries: Vec<SemanticSummary>) {
+    pub fn add_summaries(&mut self, mut summaries: Vec<SemanticSummary>) {
+        for summary in &mut summaries {
+            crate::generated::apply_settings(summary, &self.settings);
+        }
+        self.all_summaries.extend(summaries);
+
+        // Import cycles span files, so they are marked across every summary
+        // added so far and the module groups rebuilt from the marked copies
+        mark_cyclic_imports(&mut self.all_summaries);
+
         // Organize by full module path (relative to repo root)
-        for summary in &summaries {
-            let module_name = self.compute_module_path(&summary.file);
-            self.modules
-                .entry(module_name)
-                .or_insert_with(Vec::new)
+        let mut modules: HashMap<String, Vec<SemanticSummary>> = HashMap::new();
+        for summary in &self.all_summaries {
+            modules
+                .entry(self.compute_module_path(&summary.file))
+                .or_default()
                 .push(summary.clone());
         }
-
-        self.all_summaries.extend(summaries);
+        self.modules = modules;
     }
 
     /// Compute the full module path for a file (relative to repo root).
     ///
     /// This returns the raw dotted path WITHOUT hardcoded marker stripping.
     /// The conflict-aware algorithm will determine optimal stripping at write time.
+    /// Files of a workspace package get the package prefix (`web/src.components`).
     fn compute_module_path(&self, file_path: &str) -> String {
         // Strip repo root prefix if present
         let relative = if !self.repo_root.is_empty() && file_path.starts_with(&self.repo_root) {
@@ -418,46 +620,26 @@ impl ShardWriter {
             file_path
         };
 
-        let path = std::path::Path::new(relative);
-
-        // Get parent directory (module path is based on directory structure)
-        let parent = match path.parent() {
-            Some(p) if !p.as_os_str().is_empty() => p,
-            _ => {
-                // File in root - use filename without extension
-                let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("root");
-
-                // Skip generic names
-                if matches!(stem, "index" | "mod" | "lib" | "main" | "__init__") {
-                    return "root".to_string();
-                }
-                return stem.to_string();
-            }
-        };
-
-        // Convert path components to dotted notation
-        let components: Vec<&str> = parent
-            .components()
-            .filter_map(|c| match c {
-                std::path::Component::Normal(s) => s.to_str(),
-                _ => None,
-            })
-            .collect();
-
-        if components.is_empty() {
-            return "root".to_string();
+        if let Some(package) = self.workspace.as_ref().and_then(|w| w.package_of(relative)) {
+            let inner = &relative[package.path.len() + 1..];
+            return format!("{}/{}", package.name, dotted_module_path(inner));
         }
 
-        components.join(".")
+        dotted_module_path(relative)
     }
 
     /// Compute the module registry with optimal names.
     ///
     /// This builds a registry that maps full module paths to optimally
-    /// shortened names using conflict-aware stripping.
+    /// shortened names using conflict-aware stripping, or to the names of
+    /// the chosen [`ShardNamingStrategy`].
     fn compute_module_registry(&mut self) {
         let full_paths: Vec<String> = self.modules.keys().cloned().collect();
-        self.module_registry = Some(ModuleRegistry::from_full_paths(&full_paths));
+        self.module_registry = Some(if self.workspace.is_some() {
+            ModuleRegistry::with_strategy_namespaced(&full_paths, self.naming)
+        } else {
+            ModuleRegistry::with_strategy(&full_paths, self.naming)
+        });
     }
 
     /// Persist the module registry to SQLite for incremental indexing support.
@@ -535,11 +717,20 @@ impl ShardWriter {
         progress: Option<ShardProgressCallback>,
     ) -> Result<ShardStats> {
         let mut stats = ShardStats::default();
+
+        // Shards are written under their plain names, which the generations
+        // of earlier partial reindexes would otherwise shadow
+        crate::cache::generation::reset(&self.cache)?;
+
         let mut progress_state = self.load_progress_state();
 
         // Compute optimal module names using conflict-aware stripping
         emit_progress(&progress, "Module registry", 0, 1);
         self.compute_module_registry();
+        stats.collision_count = self
+            .module_registry
+            .as_ref()
+            .map_or(0, ModuleRegistry::collision_count);
 
         // Persist registry to SQLite (Phase 2 - enables incremental indexing)
         emit_progress(&progress, "Module registry", 1, 1);
@@ -582,6 +773,8 @@ impl ShardWriter {
                 self.cache.call_graph_path(),
                 self.cache.import_graph_path(),
                 self.cache.module_graph_path(),
+                self.cache.module_usage_path(),
+                self.cache.test_links_path(),
             ],
             &progress_state,
         ) {
@@ -592,15 +785,35 @@ impl ShardWriter {
         // Write symbol index (query-driven API v1)
         if !self.stage_completed(
             "symbol_index",
-            &[self.cache.symbol_index_path()],
+            &[
+                self.cache.symbol_index_path(),
+                self.cache.file_hashes_path(),
+            ],
             &progress_state,
         ) {
             emit_progress(&progress, "Symbol index", 0, 1);
             self.write_symbol_index(&mut stats)?;
+            self.write_file_hashes(&mut stats)?;
             emit_progress(&progress, "Symbol index", 1, 1);
             self.mark_stage_completed("symbol_index", &mut progress_state)?;
         }
 
+        // Rank hot spots (needs the call graph and symbol index)
+        if !self.stage_completed("hotspots", &[self.cache.hotspots_path()], &progress_state) {
+            emit_progress(&progress, "Hot spots", 0, 1);
+            self.write_hotspots(&mut stats)?;
+            emit_progress(&progress, "Hot spots", 1, 1);
+            self.mark_stage_completed("hotspots", &mut progress_state)?;
+        }
+
+        // Attribute files to CODEOWNERS owners and symbols to blame authors
+        if !self.stage_completed("ownership", &[self.cache.ownership_path()], &progress_state) {
+            emit_progress(&progress, "Ownership", 0, 1);
+            self.write_ownership(&mut stats)?;
+            emit_progress(&progress, "Ownership", 1, 1);
+            self.mark_stage_completed("ownership", &mut progress_state)?;
+        }
+
         // Write function signature index (duplicate detection)
         if !self.stage_completed(
             "signature_index",
@@ -625,19 +838,60 @@ impl ShardWriter {
             self.mark_stage_completed("bm25_index", &mut progress_state)?;
         }
 
+        // A resumed run may have written the symbol index in an earlier pass
+        let symbol_count = if stats.index_entries > 0 {
+            stats.index_entries
+        } else {
+            self.cache
+                .load_all_symbol_entries()
+                .map(|entries| entries.len())
+                .unwrap_or(0)
+        };
+
+        // Lets a later open tell whether a different repo took over this path,
+        // and later regenerations keep the naming strategy
+        self.cache
+            .record_index_identity(self.naming, symbol_count)?;
+
+        // Record this generation so `cache diff` can compare it with the next;
+        // a partial index is not a generation yet
+        if !self.progress.is_partial() {
+            self.write_manifest()?;
+        }
+
         Ok(stats)
     }
 
+    /// Write the manifest of the index just generated
+    fn write_manifest(&self) -> Result<()> {
+        let entries = self.cache.load_all_symbol_entries().unwrap_or_default();
+        let files: Vec<String> = self
+            .all_summaries
+            .iter()
+            .map(|summary| summary.file.clone())
+            .collect();
+        let manifest = IndexManifest::build(&self.cache.repo_root, &entries, &files);
+        self.cache.save_manifest(&manifest, manifest_history())?;
+        Ok(())
+    }
+
     /// Write the repository overview
     fn write_repo_overview(&mut self, dir_path: &str, stats: &mut ShardStats) -> Result<()> {
         // Build file-to-module mapping for consistent naming with module shards
         let file_to_module = self.build_file_to_module_map();
 
-        let overview = generate_repo_overview_with_modules(
+        let mut overview = generate_repo_overview_with_modules(
             &self.all_summaries,
             dir_path,
             Some(&file_to_module),
         );
+        overview.tech_debt_score = self
+            .cache
+            .load_debt_history()
+            .last()
+            .map(|entry| entry.overall_score);
+        overview.packages = self.package_stats();
+        self.count_lines(&mut overview.stats);
         self.overview = Some(overview.clone());
 
         // Create TOON output with metadata
@@ -654,6 +908,60 @@ impl ShardWriter {
         Ok(())
     }
 
+    /// Files, symbols, lines and file risk of each workspace package
+    fn package_stats(&self) -> Vec<PackageStats> {
+        let Some(ref workspace) = self.workspace else {
+            return Vec::new();
+        };
+        let repo_root = Path::new(&self.repo_root);
+
+        let mut stats: Vec<PackageStats> = workspace
+            .packages
+            .iter()
+            .map(|p| PackageStats {
+                name: p.name.clone(),
+                path: p.path.clone(),
+                ..Default::default()
+            })
+            .collect();
+        for summary in &self.all_summaries {
+            let relative = repo_relative(repo_root, &summary.file);
+            let Some(package) = workspace.package_of(&relative) else {
+                continue;
+            };
+            let Some(entry) = stats.iter_mut().find(|s| s.path == package.path) else {
+                continue;
+            };
+            entry.files += 1;
+            entry.symbols += summary.symbols.len();
+            entry.loc += fs::read_to_string(repo_root.join(&relative))
+                .map(|source| source.lines().count())
+                .unwrap_or(0);
+            match summary.behavioral_risk {
+                RiskLevel::High => entry.high_risk += 1,
+                RiskLevel::Medium => entry.medium_risk += 1,
+                RiskLevel::Low => entry.low_risk += 1,
+            }
+        }
+        stats
+    }
+
+    /// Count total and generated lines of code across the indexed files
+    fn count_lines(&self, stats: &mut RepoStats) {
+        let repo_root = Path::new(&self.repo_root);
+        for summary in &self.all_summaries {
+            let relative = repo_relative(repo_root, &summary.file);
+            let loc = fs::read_to_string(repo_root.join(&relative))
+                .map(|source| source.lines().count())
+                .unwrap_or(0);
+            stats.total_lines += loc;
+            if summary.is_generated {
+                stats.generated_files += 1;
+                stats.generated_lines += loc;
+            }
+        }
+    }
+
     fn progress_path(&self) -> std::path::PathBuf {
         self.cache.root.join("progress.json")
     }
@@ -804,7 +1112,8 @@ impl ShardWriter {
     ) -> Result<()> {
         // Build and write call graph
         emit_progress(progress, "Call graph", 0, self.all_summaries.len());
-        let call_graph = build_call_graph(&self.all_summaries, progress);
+        let call_graph =
+            build_call_graph(&self.all_summaries, Some(&self.cache.repo_root), progress);
         let graph_bytes = write_call_graph(&self.cache.call_graph_path(), &call_graph)?;
         stats.graph_bytes += graph_bytes;
         emit_progress(
@@ -833,7 +1142,61 @@ impl ShardWriter {
         stats.graph_bytes += module_graph_toon.len();
         emit_progress(progress, "Module graph", 1, 1);
 
-        stats.files_written += 3;
+        // Build and write the import summary behind module usage queries
+        emit_progress(progress, "Module usage", 0, 1);
+        let module_usage = ModuleUsageIndex::build(&self.all_summaries, &file_to_module);
+        stats.graph_bytes += module_usage.write(&self.cache)?;
+        emit_progress(progress, "Module usage", 1, 1);
+
+        // Link tests to the production code they exercise
+        emit_progress(progress, "Test links", 0, 1);
+        let test_links = TestLinkIndex::build(&self.all_summaries, Some(&self.cache.repo_root));
+        stats.graph_bytes += test_links.write(&self.cache)?;
+        emit_progress(progress, "Test links", 1, 1);
+
+        stats.files_written += 5;
+        Ok(())
+    }
+
+    /// Rank the largest, most-called and most-changed code
+    fn write_hotspots(&self, stats: &mut ShardStats) -> Result<()> {
+        let hotspots = HotspotIndex::build(
+            &self.cache.load_all_symbol_entries()?,
+            &self.cache.load_call_graph()?,
+            Some(&self.cache.repo_root),
+            churn_commits(),
+            false,
+        );
+        stats.graph_bytes += hotspots.write(&self.cache)?;
+        stats.files_written += 1;
+        Ok(())
+    }
+
+    /// Attribute the indexed files and symbols to their owners
+    fn write_ownership(&self, stats: &mut ShardStats) -> Result<()> {
+        let mut blame_cache = self.with_blame.then(|| BlameCache::load(&self.cache));
+        let ownership = OwnershipIndex::build(
+            &self.cache.load_all_symbol_entries()?,
+            &self.cache.repo_root,
+            blame_cache.as_mut(),
+        );
+        if let Some(blame_cache) = &blame_cache {
+            blame_cache.write(&self.cache)?;
+        }
+        stats.graph_bytes += ownership.write(&self.cache)?;
+        stats.files_written += 1;
+        Ok(())
+    }
+
+    /// Record the content hash of every indexed file, so line-to-symbol
+    /// mapping can tell when a file's line ranges went stale
+    fn write_file_hashes(&self, stats: &mut ShardStats) -> Result<()> {
+        let hashes = FileHashes::build(
+            self.all_summaries.iter().map(|s| s.file.as_str()),
+            &self.cache.repo_root,
+        );
+        stats.graph_bytes += hashes.write(&self.cache)?;
+        stats.files_written += 1;
         Ok(())
     }
 
@@ -841,118 +1204,21 @@ impl ShardWriter {
     ///
     /// Now writes entries for ALL symbols in summary.symbols, not just the primary one.
     fn write_symbol_index(&self, stats: &mut ShardStats) -> Result<()> {
-        use crate::cache::SymbolIndexEntry;
-
         let path = self.cache.symbol_index_path();
         let mut file = fs::File::create(&path)?;
+        let mut entries = Vec::new();
 
         // Build file-to-module mapping for proper module names from registry
         let file_to_module = self.build_file_to_module_map();
 
         for summary in &self.all_summaries {
-            let namespace = SymbolId::namespace_from_path(&summary.file);
             // Get the optimal module name from registry, fallback to extraction
             let module_name = file_to_module
                 .get(&summary.file)
                 .cloned()
                 .unwrap_or_else(|| extract_module_name(&summary.file));
 
-            // If we have symbols in the new multi-symbol format, use those
-            if !summary.symbols.is_empty() {
-                for symbol_info in &summary.symbols {
-                    let symbol_id = symbol_info.to_symbol_id(&namespace, &summary.file);
-
-                    // Calculate cognitive complexity from control flow
-                    // If symbol has its own control_flow, use that
-                    // Otherwise, filter summary's control_flow_changes by symbol's line range
-                    let (cc, nest) = if !symbol_info.control_flow.is_empty() {
-                        (
-                            calculate_cognitive_complexity(&symbol_info.control_flow),
-                            max_nesting_depth(&symbol_info.control_flow),
-                        )
-                    } else {
-                        // Filter file-level control flow by symbol's line range
-                        let symbol_cf: Vec<_> = summary
-                            .control_flow_changes
-                            .iter()
-                            .filter(|cf| {
-                                cf.location.line >= symbol_info.start_line
-                                    && cf.location.line <= symbol_info.end_line
-                            })
-                            .cloned()
-                            .collect();
-                        (
-                            calculate_cognitive_complexity(&symbol_cf),
-                            max_nesting_depth(&symbol_cf),
-                        )
-                    };
-
-                    let entry = SymbolIndexEntry {
-                        symbol: symbol_info.name.clone(),
-                        hash: symbol_id.hash.clone(),
-                        semantic_hash: symbol_id.semantic_hash.clone(),
-                        kind: format!("{:?}", symbol_info.kind).to_lowercase(),
-                        module: module_name.clone(),
-                        file: summary.file.clone(),
-                        lines: format!("{}-{}", symbol_info.start_line, symbol_info.end_line),
-                        risk: format!("{:?}", symbol_info.behavioral_risk).to_lowercase(),
-                        cognitive_complexity: cc,
-                        max_nesting: nest,
-                        is_escape_local: symbol_info.is_escape_local,
-                        framework_entry_point: symbol_info.framework_entry_point,
-                        is_exported: symbol_info.is_exported,
-                        decorators: symbol_info.decorators.join(","),
-                        arity: symbol_info.arguments.len() + symbol_info.props.len(),
-                        is_async: symbol_info.is_async,
-                        return_type: symbol_info.return_type.clone().unwrap_or_default(),
-                        ext_package: String::new(),
-                        base_classes: symbol_info.base_classes.join(","),
-                    };
-
-                    // Write as JSONL (one JSON object per line)
-                    let json = serde_json::to_string(&entry).map_err(|e| {
-                        crate::McpDiffError::ExtractionFailure {
-                            message: format!("Failed to serialize symbol index entry: {}", e),
-                        }
-                    })?;
-                    writeln!(file, "{}", json)?;
-
-                    stats.index_entries += 1;
-                }
-            } else if let Some(ref symbol_id) = summary.symbol_id {
-                // Fallback to old single-symbol format - use summary's control flow
-                let cc = calculate_cognitive_complexity(&summary.control_flow_changes);
-                let nest = max_nesting_depth(&summary.control_flow_changes);
-
-                let entry = SymbolIndexEntry {
-                    symbol: summary.symbol.clone().unwrap_or_default(),
-                    hash: symbol_id.hash.clone(),
-                    semantic_hash: symbol_id.semantic_hash.clone(),
-                    kind: summary
-                        .symbol_kind
-                        .map(|k| format!("{:?}", k).to_lowercase())
-                        .unwrap_or_else(|| "unknown".to_string()),
-                    module: module_name,
-                    file: summary.file.clone(),
-                    lines: match (summary.start_line, summary.end_line) {
-                        (Some(s), Some(e)) => format!("{}-{}", s, e),
-                        (Some(s), None) => format!("{}", s),
-                        _ => String::new(),
-                    },
-                    risk: format!("{:?}", summary.behavioral_risk).to_lowercase(),
-                    cognitive_complexity: cc,
-                    max_nesting: nest,
-                    is_escape_local: false,
-                    framework_entry_point: summary.framework_entry_point,
-                    is_exported: false,
-                    decorators: String::new(),
-                    arity: summary.arguments.len() + summary.props.len(),
-                    is_async: false,
-                    return_type: summary.return_type.clone().unwrap_or_default(),
-                    ext_package: String::new(),
-                    base_classes: String::new(),
-                };
-
+            for entry in symbol_index_entries(summary, &module_name) {
                 // Write as JSONL (one JSON object per line)
                 let json = serde_json::to_string(&entry).map_err(|e| {
                     crate::McpDiffError::ExtractionFailure {
@@ -960,10 +1226,18 @@ impl ShardWriter {
                     }
                 })?;
                 writeln!(file, "{}", json)?;
+                entries.push(entry);
 
                 stats.index_entries += 1;
             }
         }
+        drop(file);
+
+        // Binary copy for fast cold-start loads, tied to the text just written
+        if let Some(fingerprint) = crate::cache::symbol_bin::TextFingerprint::of(&self.cache) {
+            crate::cache::symbol_bin::write(&self.cache, &entries, fingerprint)?;
+            stats.files_written += 1;
+        }
 
         stats.index_bytes = fs::metadata(&path)?.len() as usize;
         stats.files_written += 1;
@@ -1032,6 +1306,7 @@ impl ShardWriter {
                         is_default_export: false,
                         hash: Some(symbol_id.hash.clone()),
                         arguments: summary.arguments.clone(),
+                        param_types: Vec::new(),
                         props: summary.props.clone(),
                         return_type: summary.return_type.clone(),
                         calls: summary.calls.clone(),
@@ -1043,6 +1318,22 @@ impl ShardWriter {
                         framework_entry_point: summary.framework_entry_point,
                         is_async: false,
                         base_classes: Vec::new(),
+                        doc_comment: None,
+                        feature_flags: Vec::new(),
+                        generic_params: Vec::new(),
+                        lifetime_params: Vec::new(),
+                        implements: Vec::new(),
+                        is_deprecated: false,
+                        deprecation_message: None,
+                        awaits_count: 0,
+                        io_calls_outside_try: 0,
+                        has_empty_catch: false,
+                        rethrows: false,
+                        panic_points: Vec::new(),
+                        embedded_queries: Vec::new(),
+                        partial_of: None,
+                        parent_symbol: None,
+                        nested_id: None,
                     };
 
                     let signature = FunctionSignature::from_symbol_info(
@@ -1079,8 +1370,6 @@ impl ShardWriter {
         stats: &mut ShardStats,
         progress: &Option<ShardProgressCallback>,
     ) -> Result<()> {
-        let entries = AtomicUsize::new(0);
-        let total_length = AtomicUsize::new(0);
         let total_docs: usize = self
             .all_summaries
             .iter()
@@ -1123,13 +1412,12 @@ impl ShardWriter {
                         let kind_str = format!("{:?}", symbol_info.kind).to_lowercase();
 
                         // Extract searchable terms from this symbol
-                        let mut terms = Vec::new();
-                        terms.extend(crate::bm25::tokenize(&symbol_info.name));
-                        terms.extend(file_terms.iter().cloned());
-                        terms.push(kind_str.clone());
-                        terms.extend(module_terms.iter().cloned());
-                        let mut seen = std::collections::HashSet::new();
-                        terms.retain(|t| seen.insert(t.clone()));
+                        let terms = bm25_symbol_terms(
+                            &symbol_info.name,
+                            &file_terms,
+                            &kind_str,
+                            &module_terms,
+                        );
 
                         let doc = Bm25Document {
                             hash: symbol_id.hash,
@@ -1151,15 +1439,12 @@ impl ShardWriter {
                         .map(|k| format!("{:?}", k).to_lowercase())
                         .unwrap_or_else(|| "unknown".to_string());
 
-                    let mut terms = Vec::new();
-                    terms.extend(crate::bm25::tokenize(
+                    let terms = bm25_symbol_terms(
                         summary.symbol.as_deref().unwrap_or(""),
-                    ));
-                    terms.extend(file_terms.iter().cloned());
-                    terms.push(kind_str.clone());
-                    terms.extend(module_terms.iter().cloned());
-                    let mut seen = std::collections::HashSet::new();
-                    terms.retain(|t| seen.insert(t.clone()));
+                        &file_terms,
+                        &kind_str,
+                        &module_terms,
+                    );
 
                     let doc = Bm25Document {
                         hash: symbol_id.hash.clone(),
@@ -1185,93 +1470,8 @@ impl ShardWriter {
         let docs: Vec<(Bm25Document, Vec<String>)> =
             docs_by_summary.into_iter().flatten().collect();
 
-        let db_path = self.cache.bm25_index_path();
-        let mut conn =
-            Connection::open(&db_path).map_err(|e| crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to open BM25 sqlite: {}", e),
-            })?;
-        crate::bm25::init_bm25_sqlite(&conn).map_err(|e| {
-            crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to init BM25 sqlite: {}", e),
-            }
-        })?;
-        crate::bm25::clear_bm25_sqlite(&conn).map_err(|e| {
-            crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to clear BM25 sqlite: {}", e),
-            }
-        })?;
-        let tx = conn
-            .transaction()
-            .map_err(|e| crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to start BM25 sqlite transaction: {}", e),
-            })?;
-        {
-            let mut insert_doc = tx.prepare(
-                "INSERT OR IGNORE INTO bm25_documents (doc_id, symbol, file, lines, kind, module, risk, doc_length) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            ).map_err(|e| {
-                crate::McpDiffError::ExtractionFailure {
-                    message: format!("Failed to prepare BM25 doc insert: {}", e),
-                }
-            })?;
-            let mut insert_term = tx
-                .prepare("INSERT OR IGNORE INTO bm25_terms (term, doc_id, tf) VALUES (?, ?, ?)")
-                .map_err(|e| crate::McpDiffError::ExtractionFailure {
-                    message: format!("Failed to prepare BM25 term insert: {}", e),
-                })?;
-
-            for (doc, terms) in docs {
-                let doc_length = terms.len() as u32;
-                let doc_id = doc.hash.clone();
-                let inserted = insert_doc
-                    .execute(rusqlite::params![
-                        doc_id,
-                        doc.symbol,
-                        doc.file,
-                        doc.lines,
-                        doc.kind,
-                        doc.module,
-                        doc.risk,
-                        doc_length as i64
-                    ])
-                    .map_err(|e| crate::McpDiffError::ExtractionFailure {
-                        message: format!("Failed to insert BM25 doc: {}", e),
-                    })?;
-                if inserted > 0 {
-                    for term in terms {
-                        insert_term
-                            .execute(rusqlite::params![term, doc_id, 1i64])
-                            .map_err(|e| crate::McpDiffError::ExtractionFailure {
-                                message: format!("Failed to insert BM25 term: {}", e),
-                            })?;
-                    }
-                }
-                total_length.fetch_add(doc_length as usize, Ordering::Relaxed);
-                let current = entries.fetch_add(1, Ordering::Relaxed) + 1;
-                if current % 1000 == 0 || current == total_docs {
-                    emit_progress(progress, "BM25 index", current, total_docs.max(1));
-                }
-            }
-        }
-
-        emit_progress(progress, "BM25 index", total_docs, total_docs.max(1));
-        stats.bm25_entries = entries.load(Ordering::Relaxed);
-
-        let total_docs_u32 = stats.bm25_entries as u32;
-        let avg_doc_length = if total_docs_u32 > 0 {
-            total_length.load(Ordering::Relaxed) as f64 / total_docs_u32 as f64
-        } else {
-            0.0
-        };
-
-        tx.commit()
-            .map_err(|e| crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to commit BM25 sqlite: {}", e),
-            })?;
-        crate::bm25::write_bm25_meta(&conn, total_docs_u32, avg_doc_length).map_err(|e| {
-            crate::McpDiffError::ExtractionFailure {
-                message: format!("Failed to write BM25 meta: {}", e),
-            }
-        })?;
+        stats.bm25_entries =
+            write_bm25_sqlite(&self.cache.bm25_index_path(), docs, total_docs, progress)?;
 
         let path = self.cache.bm25_index_path();
         stats.bm25_bytes = fs::metadata(&path).map(|m| m.len() as usize).unwrap_or(0);
@@ -1331,6 +1531,9 @@ pub struct ShardStats {
 
     /// Bytes written for BM25 index
     pub bm25_bytes: usize,
+
+    /// Number of module names that had to be deduplicated
+    pub collision_count: usize,
 }
 
 impl ShardStats {
@@ -1350,6 +1553,151 @@ impl ShardStats {
 // Encoding Functions
 // ============================================================================
 
+/// Searchable terms for one symbol's BM25 document
+fn bm25_symbol_terms(
+    name: &str,
+    file_terms: &[String],
+    kind: &str,
+    module_terms: &[String],
+) -> Vec<String> {
+    let mut terms = crate::bm25::tokenize(name);
+    terms.extend(file_terms.iter().cloned());
+    terms.extend(crate::bm25::tokenize(kind));
+    terms.extend(module_terms.iter().cloned());
+    let mut seen = HashSet::new();
+    terms.retain(|t| seen.insert(t.clone()));
+    terms
+}
+
+/// Replace the BM25 tables in `db_path` with `docs`
+///
+/// Returns the number of documents written.
+fn write_bm25_sqlite(
+    db_path: &Path,
+    docs: Vec<(Bm25Document, Vec<String>)>,
+    total_docs: usize,
+    progress: &Option<ShardProgressCallback>,
+) -> Result<usize> {
+    let mut entries = 0usize;
+    let mut total_length = 0usize;
+    let mut conn =
+        Connection::open(db_path).map_err(|e| crate::McpDiffError::ExtractionFailure {
+            message: format!("Failed to open BM25 sqlite: {}", e),
+        })?;
+    crate::bm25::init_bm25_sqlite(&conn).map_err(|e| crate::McpDiffError::ExtractionFailure {
+        message: format!("Failed to init BM25 sqlite: {}", e),
+    })?;
+    crate::bm25::clear_bm25_sqlite(&conn).map_err(|e| crate::McpDiffError::ExtractionFailure {
+        message: format!("Failed to clear BM25 sqlite: {}", e),
+    })?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| crate::McpDiffError::ExtractionFailure {
+            message: format!("Failed to start BM25 sqlite transaction: {}", e),
+        })?;
+    {
+        let mut insert_doc = tx.prepare(
+            "INSERT OR IGNORE INTO bm25_documents (doc_id, symbol, file, lines, kind, module, risk, doc_length) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        ).map_err(|e| {
+            crate::McpDiffError::ExtractionFailure {
+                message: format!("Failed to prepare BM25 doc insert: {}", e),
+            }
+        })?;
+        let mut insert_term = tx
+            .prepare("INSERT OR IGNORE INTO bm25_terms (term, doc_id, tf) VALUES (?, ?, ?)")
+            .map_err(|e| crate::McpDiffError::ExtractionFailure {
+                message: format!("Failed to prepare BM25 term insert: {}", e),
+            })?;
+
+        for (doc, terms) in docs {
+            let doc_length = terms.len() as u32;
+            let doc_id = doc.hash.clone();
+            let inserted = insert_doc
+                .execute(rusqlite::params![
+                    doc_id,
+                    doc.symbol,
+                    doc.file,
+                    doc.lines,
+                    doc.kind,
+                    doc.module,
+                    doc.risk,
+                    doc_length as i64
+                ])
+                .map_err(|e| crate::McpDiffError::ExtractionFailure {
+                    message: format!("Failed to insert BM25 doc: {}", e),
+                })?;
+            if inserted > 0 {
+                for term in terms {
+                    insert_term
+                        .execute(rusqlite::params![term, doc_id, 1i64])
+                        .map_err(|e| crate::McpDiffError::ExtractionFailure {
+                            message: format!("Failed to insert BM25 term: {}", e),
+                        })?;
+                }
+            }
+            total_length += doc_length as usize;
+            entries += 1;
+            if entries % 1000 == 0 || entries == total_docs {
+                emit_progress(progress, "BM25 index", entries, total_docs.max(1));
+            }
+        }
+    }
+
+    emit_progress(progress, "BM25 index", total_docs, total_docs.max(1));
+
+    let total_docs_u32 = entries as u32;
+    let avg_doc_length = if total_docs_u32 > 0 {
+        total_length as f64 / total_docs_u32 as f64
+    } else {
+        0.0
+    };
+
+    tx.commit()
+        .map_err(|e| crate::McpDiffError::ExtractionFailure {
+            message: format!("Failed to commit BM25 sqlite: {}", e),
+        })?;
+    crate::bm25::write_bm25_meta(&conn, total_docs_u32, avg_doc_length).map_err(|e| {
+        crate::McpDiffError::ExtractionFailure {
+            message: format!("Failed to write BM25 meta: {}", e),
+        }
+    })?;
+
+    Ok(entries)
+}
+
+/// Rebuild only the BM25 index, from the symbol index already on disk
+///
+/// Used when the tokenizer changed but the rest of the cache is current.
+/// Returns the number of documents indexed.
+pub fn rebuild_bm25_index(cache: &CacheDir) -> Result<usize> {
+    let _lock = cache.lock_index()?;
+    let docs: Vec<(Bm25Document, Vec<String>)> = cache
+        .load_all_symbol_entries()?
+        .into_iter()
+        .map(|entry| {
+            let terms = bm25_symbol_terms(
+                &entry.symbol,
+                &extract_terms_from_file_path(&entry.file),
+                &entry.kind,
+                &crate::bm25::tokenize(&entry.module),
+            );
+            let doc = Bm25Document {
+                hash: entry.hash,
+                symbol: entry.symbol,
+                file: entry.file,
+                lines: entry.lines,
+                kind: entry.kind,
+                module: entry.module,
+                risk: entry.risk,
+                doc_length: 0,
+            };
+            (doc, terms)
+        })
+        .collect();
+    let total_docs = docs.len();
+    write_bm25_sqlite(&cache.bm25_index_path(), docs, total_docs, &None)
+}
+
 /// Encode repository overview with metadata
 fn encode_repo_overview_with_meta(overview: &RepoOverview, progress: &IndexingStatus) -> String {
     let mut lines = Vec::new();
@@ -1403,6 +1751,33 @@ fn encode_repo_overview_with_meta(overview: &RepoOverview, progress: &IndexingSt
         overview.stats.high_risk, overview.stats.medium_risk, overview.stats.low_risk
     ));
 
+    if overview.stats.generated_files > 0 {
+        lines.push(format!(
+            "generated: \"files:{},lines:{},loc_pct:{:.1}\"",
+            overview.stats.generated_files,
+            overview.stats.generated_lines,
+            overview.stats.generated_loc_pct()
+        ));
+    }
+
+    if let Some(score) = overview.tech_debt_score {
+        lines.push(format!("tech_debt_score: {}", score));
+    }
+
+    // Workspace packages
+    if !overview.packages.is_empty() {
+        lines.push(format!(
+            "packages[{}]{{name,path,files,symbols,loc,high,medium,low}}:",
+            overview.packages.len()
+        ));
+        for p in &overview.packages {
+            lines.push(format!(
+                "  {},{},{},{},{},{},{},{}",
+                p.name, p.path, p.files, p.symbols, p.loc, p.high_risk, p.medium_risk, p.low_risk
+            ));
+        }
+    }
+
     // Entry points
     if !overview.entry_points.is_empty() {
         let entries: Vec<String> = overview
@@ -1574,10 +1949,20 @@ pub(crate) fn encode_symbol_shard_from_info(
         symbol_info.start_line, symbol_info.end_line
     ));
 
+    // Closure, callback or nested function: the function it is defined in
+    let parent = summary.parent_hash(symbol_info);
+    if !parent.is_empty() {
+        lines.push(format!("parent: \"{}\"", parent));
+    }
+
     if symbol_info.is_exported {
         lines.push(format!("public_surface_changed: true"));
     }
 
+    if summary.is_generated {
+        lines.push("generated: true".to_string());
+    }
+
     lines.push(format!(
         "behavioral_risk: {}",
         symbol_info.behavioral_risk.as_str()
@@ -1620,6 +2005,33 @@ pub(crate) fn encode_symbol_shard_from_info(
         lines.push(format!("return_type: \"{}\"", ret));
     }
 
+    // Generic parameters and their bounds
+    if !symbol_info.generic_params.is_empty() {
+        lines.push(format!(
+            "generic_params[{}]: {}",
+            symbol_info.generic_params.len(),
+            symbol_info.generic_params.join(",")
+        ));
+    }
+
+    // Named lifetimes in the signature
+    if !symbol_info.lifetime_params.is_empty() {
+        lines.push(format!(
+            "lifetimes[{}]: {}",
+            symbol_info.lifetime_params.len(),
+            symbol_info.lifetime_params.join(",")
+        ));
+    }
+
+    // Interfaces satisfied by the type's methods
+    if !symbol_info.implements.is_empty() {
+        lines.push(format!(
+            "implements[{}]: {}",
+            symbol_info.implements.len(),
+            symbol_info.implements.join(",")
+        ));
+    }
+
     // Control flow
     if !symbol_info.control_flow.is_empty() {
         let cf: Vec<String> = symbol_info
@@ -1630,6 +2042,21 @@ pub(crate) fn encode_symbol_shard_from_info(
         lines.push(format!("control_flow[{}]: {}", cf.len(), cf.join(",")));
     }
 
+    // State changes attributed to the symbol (names only, for semantic grep)
+    if !symbol_info.state_changes.is_empty() {
+        let mut names: Vec<&str> = Vec::new();
+        for state in &symbol_info.state_changes {
+            if !names.contains(&state.name.as_str()) {
+                names.push(&state.name);
+            }
+        }
+        lines.push(format!(
+            "state_changes[{}]: {}",
+            names.len(),
+            names.join(",")
+        ));
+    }
+
     // Calls - use symbol-level calls (symbol_info.calls) which are correctly attributed
     // during extraction via find_containing_symbol_by_line
     if !symbol_info.calls.is_empty() {
@@ -1694,6 +2121,139 @@ pub(crate) fn encode_symbol_shard_from_info(
     lines.join("\n")
 }
 
+/// Symbol index entries for one file's summary, in `module_name`
+///
+/// Covers every symbol in `summary.symbols`, or the primary symbol for
+/// summaries in the old single-symbol format.
+pub(crate) fn symbol_index_entries(
+    summary: &SemanticSummary,
+    module_name: &str,
+) -> Vec<SymbolIndexEntry> {
+    let namespace = SymbolId::namespace_from_path(&summary.file);
+
+    // If we have symbols in the new multi-symbol format, use those
+    if !summary.symbols.is_empty() {
+        return summary
+            .symbols
+            .iter()
+            .map(|symbol_info| {
+                let symbol_id = symbol_info.to_symbol_id(&namespace, &summary.file);
+
+                // Calculate cognitive complexity from control flow
+                // If symbol has its own control_flow, use that
+                // Otherwise, filter summary's control_flow_changes by symbol's line range
+                let (cc, nest) = if !symbol_info.control_flow.is_empty() {
+                    (
+                        calculate_cognitive_complexity(&symbol_info.control_flow),
+                        max_nesting_depth(&symbol_info.control_flow),
+                    )
+                } else {
+                    // Filter file-level control flow by symbol's line range
+                    let symbol_cf: Vec<_> = summary
+                        .control_flow_changes
+                        .iter()
+                        .filter(|cf| {
+                            cf.location.line >= symbol_info.start_line
+                                && cf.location.line <= symbol_info.end_line
+                        })
+                        .cloned()
+                        .collect();
+                    (
+                        calculate_cognitive_complexity(&symbol_cf),
+                        max_nesting_depth(&symbol_cf),
+                    )
+                };
+
+                SymbolIndexEntry {
+                    symbol: symbol_info.name.clone(),
+                    hash: symbol_id.hash.clone(),
+                    semantic_hash: symbol_id.semantic_hash.clone(),
+                    kind: format!("{:?}", symbol_info.kind).to_lowercase(),
+                    module: module_name.to_string(),
+                    file: summary.file.clone(),
+                    lines: format!("{}-{}", symbol_info.start_line, symbol_info.end_line),
+                    risk: format!("{:?}", symbol_info.behavioral_risk).to_lowercase(),
+                    cognitive_complexity: cc,
+                    max_nesting: nest,
+                    is_escape_local: symbol_info.is_escape_local,
+                    framework_entry_point: symbol_info.framework_entry_point,
+                    is_exported: symbol_info.is_exported,
+                    decorators: symbol_info.decorators.join(","),
+                    arity: symbol_info.arguments.len() + symbol_info.props.len(),
+                    is_async: symbol_info.is_async,
+                    return_type: symbol_info.return_type.clone().unwrap_or_default(),
+                    ext_package: String::new(),
+                    base_classes: symbol_info.base_classes.join(","),
+                    feature_flags: symbol_info.feature_flags.join(","),
+                    is_deprecated: symbol_info.is_deprecated,
+                    deprecation_message: symbol_info
+                        .deprecation_message
+                        .clone()
+                        .unwrap_or_default(),
+                    awaits_count: symbol_info.awaits_count,
+                    io_calls_outside_try: symbol_info.io_calls_outside_try,
+                    has_empty_catch: symbol_info.has_empty_catch,
+                    rethrows: symbol_info.rethrows,
+                    panic_points: symbol_info.panic_points.len(),
+                    sql_tables: symbol_info.embedded_sql_tables(),
+                    partial_of: symbol_info.partial_of.clone().unwrap_or_default(),
+                    parent: summary.parent_hash(symbol_info),
+                    is_generated: summary.is_generated,
+                }
+            })
+            .collect();
+    }
+
+    let Some(ref symbol_id) = summary.symbol_id else {
+        return Vec::new();
+    };
+
+    // Fallback to old single-symbol format - use summary's control flow
+    let cc = calculate_cognitive_complexity(&summary.control_flow_changes);
+    let nest = max_nesting_depth(&summary.control_flow_changes);
+
+    vec![SymbolIndexEntry {
+        symbol: summary.symbol.clone().unwrap_or_default(),
+        hash: symbol_id.hash.clone(),
+        semantic_hash: symbol_id.semantic_hash.clone(),
+        kind: summary
+            .symbol_kind
+            .map(|k| format!("{:?}", k).to_lowercase())
+            .unwrap_or_else(|| "unknown".to_string()),
+        module: module_name.to_string(),
+        file: summary.file.clone(),
+        lines: match (summary.start_line, summary.end_line) {
+            (Some(s), Some(e)) => format!("{}-{}", s, e),
+            (Some(s), None) => format!("{}", s),
+            _ => String::new(),
+        },
+        risk: format!("{:?}", summary.behavioral_risk).to_lowercase(),
+        cognitive_complexity: cc,
+        max_nesting: nest,
+        is_escape_local: false,
+        framework_entry_point: summary.framework_entry_point,
+        is_exported: false,
+        decorators: String::new(),
+        arity: summary.arguments.len() + summary.props.len(),
+        is_async: false,
+        return_type: summary.return_type.clone().unwrap_or_default(),
+        ext_package: String::new(),
+        base_classes: String::new(),
+        feature_flags: String::new(),
+        is_deprecated: false,
+        deprecation_message: String::new(),
+        awaits_count: 0,
+        io_calls_outside_try: 0,
+        has_empty_catch: false,
+        rethrows: false,
+        panic_points: 0,
+        sql_tables: String::new(),
+        partial_of: String::new(),
+        parent: String::new(),
+        is_generated: summary.is_generated,
+    }]
+}
+
 /// Build a lookup map from symbol name to their SymbolIds
 /// Returns: name -> Vec<(hash, namespace)> for disambiguation
 fn build_symbol_lookup(summaries: &[SemanticSummary]) -> HashMap<String, Vec<(String, String)>> {
@@ -1711,6 +2271,12 @@ fn build_symbol_lookup(summaries: &[SemanticSummary]) -> HashMap<String, Vec<(St
         for symbol in &summary.symbols {
             let hash = crate::overlay::compute_symbol_hash(symbol, &summary.file);
             let namespace = SymbolId::namespace_from_path(&summary.file);
+            for alias in crate::detectors::rust::impl_member_aliases(&symbol.name) {
+                lookup
+                    .entry(alias)
+                    .or_default()
+                    .push((hash.clone(), namespace.clone()));
+            }
             lookup
                 .entry(symbol.name.clone())
                 .or_default()
@@ -1727,16 +2293,64 @@ fn build_symbol_lookup(summaries: &[SemanticSummary]) -> HashMap<String, Vec<(St
     lookup
 }
 
+/// Resolve a `Type::method` call to a method defined alongside `Type`
+///
+/// Inherent methods keep their bare names, so `Square::new` can't be looked up
+/// directly; it resolves to the only `new` in the file that defines `Square`
+/// (`Self::new`: in the calling file), or to the only `new` anywhere. Two
+/// candidates are ambiguous, and calls on types outside the repo (`Vec::new`)
+/// don't resolve.
+pub(crate) fn resolve_type_path_call<'a>(
+    call_name: &str,
+    same_file_prefix: &str,
+    hashes_of: impl Fn(&str) -> Vec<&'a str>,
+) -> Option<&'a str> {
+    let (path, method) = call_name.rsplit_once("::")?;
+    let type_name = path.rsplit("::").next().unwrap_or(path);
+    let file_of = |hash: &str| hash.split(':').next().unwrap_or_default().to_string();
+    let type_files: Vec<String> = if type_name == "Self" {
+        vec![file_of(same_file_prefix)]
+    } else {
+        hashes_of(type_name).into_iter().map(file_of).collect()
+    };
+    if type_files.is_empty() || method.is_empty() {
+        return None;
+    }
+    let methods = hashes_of(method);
+    let beside_type: Vec<&str> = methods
+        .iter()
+        .copied()
+        .filter(|hash| type_files.contains(&file_of(hash)))
+        .collect();
+    match (beside_type.as_slice(), methods.as_slice()) {
+        ([hash], _) | ([], [hash]) => Some(hash),
+        _ => None,
+    }
+}
+
 /// Resolve a call name to a symbol hash if possible
 /// Returns the hash if uniquely resolved, or the original name if ambiguous/external
 /// When multiple symbols have the same name, prefers same-file matches (local scope)
 /// For external calls, includes package name if available from import_sources
+/// Python calls through a repo-local import prefer the imported file
 fn resolve_call_to_hash(
     call_name: &str,
     lookup: &HashMap<String, Vec<(String, String)>>,
     same_file_prefix: &str,
     import_sources: &HashMap<String, String>,
+    python_targets: Option<&HashMap<String, PythonImportTarget>>,
 ) -> String {
+    if let Some((name, file)) = python_targets.and_then(|t| resolve_python_call(t, call_name)) {
+        let file_prefix = format!("{:08x}:", crate::schema::fnv1a_hash(file) as u32);
+        if let Some((hash, _)) = lookup.get(name).and_then(|matches| {
+            matches
+                .iter()
+                .find(|(hash, _)| hash.starts_with(&file_prefix))
+        }) {
+            return hash.clone();
+        }
+    }
+
     // Helper to find best match from a list, preferring same-file matches
     let find_best_match = |matches: &[(String, String)]| -> Option<String> {
         if matches.is_empty() {
@@ -1761,6 +2375,17 @@ fn resolve_call_to_hash(
         }
     }
 
+    // `Type::method` resolves to a method in the file defining `Type`
+    let hashes_of = |name: &str| {
+        lookup
+            .get(name)
+            .map(|matches| matches.iter().map(|(hash, _)| hash.as_str()).collect())
+            .unwrap_or_default()
+    };
+    if let Some(resolved) = resolve_type_path_call(call_name, same_file_prefix, hashes_of) {
+        return resolved.to_string();
+    }
+
     // If call has an object (e.g., "playerEntity.GetGoldAmount"), try just the method name
     // This handles cases where we can't infer the type of the object variable
     if let Some(dot_pos) = call_name.rfind('.') {
@@ -1807,6 +2432,7 @@ fn resolve_call_to_hash(
 /// Returns edges with edge_kind to distinguish calls from variable reads/writes
 fn build_call_graph(
     summaries: &[SemanticSummary],
+    repo_root: Option<&Path>,
     progress: &Option<ShardProgressCallback>,
 ) -> HashMap<String, Vec<CallGraphEdge>> {
     use crate::overlay::compute_symbol_hash;
@@ -1816,6 +2442,19 @@ fn build_call_graph(
     // Build lookup for resolving call names to hashes (must be done before parallel phase)
     let symbol_lookup = build_symbol_lookup(summaries);
 
+    // Repo-local names bound by each Python file's imports
+    let python_packages = PythonPackageIndex::from_summaries(summaries, repo_root);
+    let python_imports: HashMap<&str, HashMap<String, PythonImportTarget>> =
+        if python_packages.is_empty() {
+            HashMap::new()
+        } else {
+            summaries
+                .iter()
+                .map(|s| (s.file.as_str(), python_packages.import_targets(s)))
+                .filter(|(_, targets)| !targets.is_empty())
+                .collect()
+        };
+
     // Progress tracking
     let processed = AtomicUsize::new(0);
 
@@ -1850,6 +2489,7 @@ fn build_call_graph(
                 } else {
                     format!("{}:", caller_file_hash)
                 };
+                let python_targets = python_imports.get(summary.file.as_str());
 
                 // Process each symbol in the file
                 for symbol in &summary.symbols {
@@ -1869,6 +2509,7 @@ fn build_call_graph(
                                 &symbol_lookup,
                                 &same_file_prefix,
                                 &summary.import_sources,
+                                python_targets,
                             )
                         } else {
                             resolve_call_to_hash(
@@ -1876,6 +2517,7 @@ fn build_call_graph(
                                 &symbol_lookup,
                                 &same_file_prefix,
                                 &summary.import_sources,
+                                python_targets,
                             )
                         };
                         let edge = CallGraphEdge::new(resolved, c.ref_kind);
@@ -1897,6 +2539,7 @@ fn build_call_graph(
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 );
                                 let edge = CallGraphEdge::call(resolved);
                                 if seen.insert((edge.callee.clone(), edge.edge_kind)) {
@@ -1906,6 +2549,14 @@ fn build_call_graph(
                         }
                     }
 
+                    // Nested symbols are reached through the function defining them
+                    if let Some(parent) = summary.parent_of(symbol) {
+                        local_graph
+                            .entry(compute_symbol_hash(parent, &summary.file))
+                            .or_default()
+                            .push(CallGraphEdge::call(hash.clone()));
+                    }
+
                     if !edges.is_empty() {
                         local_graph.entry(hash).or_default().extend(edges);
                     }
@@ -1927,6 +2578,7 @@ fn build_call_graph(
                                 &symbol_lookup,
                                 &same_file_prefix,
                                 &summary.import_sources,
+                                python_targets,
                             )
                         } else {
                             resolve_call_to_hash(
@@ -1934,6 +2586,7 @@ fn build_call_graph(
                                 &symbol_lookup,
                                 &same_file_prefix,
                                 &summary.import_sources,
+                                python_targets,
                             )
                         };
                         let edge = CallGraphEdge::new(resolved, c.ref_kind);
@@ -1952,6 +2605,7 @@ fn build_call_graph(
                                     &symbol_lookup,
                                     &same_file_prefix,
                                     &summary.import_sources,
+                                    python_targets,
                                 );
                                 let edge = CallGraphEdge::call(resolved);
                                 if seen.insert((edge.callee.clone(), edge.edge_kind)) {
@@ -1970,6 +2624,7 @@ fn build_call_graph(
                                 &symbol_lookup,
                                 &same_file_prefix,
                                 &summary.import_sources,
+                                python_targets,
                             );
                             let edge = CallGraphEdge::call(resolved);
                             if seen.insert((edge.callee.clone(), edge.edge_kind)) {
@@ -2227,6 +2882,42 @@ fn encode_module_graph(graph: &HashMap<String, Vec<String>>) -> String {
     lines.join("\n")
 }
 
+/// Dotted module path of a file from its directory (`src/game/player.rs` is
+/// `src.game`); files at the top level use their stem, or `root`.
+fn dotted_module_path(relative: &str) -> String {
+    let path = std::path::Path::new(relative);
+
+    // Get parent directory (module path is based on directory structure)
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => {
+            // File in root - use filename without extension
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("root");
+
+            // Skip generic names
+            if matches!(stem, "index" | "mod" | "lib" | "main" | "__init__") {
+                return "root".to_string();
+            }
+            return stem.to_string();
+        }
+    };
+
+    // Convert path components to dotted notation
+    let components: Vec<&str> = parent
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect();
+
+    if components.is_empty() {
+        return "root".to_string();
+    }
+
+    components.join(".")
+}
+
 /// Extract module/namespace from file path.
 ///
 /// Returns the path-based namespace (directory structure after src/).
@@ -2708,6 +3399,69 @@ mod tests {
         assert_eq!(result, vec!["users", "auth", "helpers"]);
     }
 
+    #[test]
+    fn test_compute_shard_names_hybrid() {
+        let paths = vec!["src.game.player".to_string(), "src.map.player".to_string()];
+        let shard_names = compute_shard_names(&paths, ShardNamingStrategy::Hybrid);
+
+        assert_eq!(
+            shard_names.names[0],
+            format!("game.pla-{:08x}", fnv1a_hash("src.game.player") as u32)
+        );
+        assert!(shard_names.names[1].starts_with("map.play-"));
+        assert_eq!(shard_names.collision_count, 0);
+
+        let hashed = compute_shard_names(&paths, ShardNamingStrategy::ContentHash);
+        assert_eq!(
+            hashed.names[0],
+            format!("{:016x}", fnv1a_hash("src.game.player"))
+        );
+    }
+
+    #[test]
+    fn test_compute_shard_names_dedupes_file_name_collisions() {
+        // Distinct module paths whose shard files would collide: spaces are
+        // sanitized to '_' and case-insensitive file systems fold case
+        let paths = vec![
+            "src.my_app".to_string(),
+            "src.my app".to_string(),
+            "src.My_App".to_string(),
+        ];
+        let shard_names = compute_shard_names(&paths, ShardNamingStrategy::ModulePath);
+
+        assert_eq!(shard_names.collision_count, 2);
+        assert_eq!(
+            shard_names.names,
+            vec![
+                "my_app_3".to_string(),
+                "my app_2".to_string(),
+                "My_App".to_string()
+            ]
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_shard_names_are_unique(
+            paths in proptest::collection::hash_set("[a-zA-Z_ .-]{1,6}(\\.[a-zA-Z_ -]{1,6}){0,3}", 1..40),
+            strategy in proptest::sample::select(vec![
+                ShardNamingStrategy::ModulePath,
+                ShardNamingStrategy::ContentHash,
+                ShardNamingStrategy::Hybrid,
+            ]),
+        ) {
+            let paths: Vec<String> = paths.into_iter().collect();
+            let shard_names = compute_shard_names(&paths, strategy);
+
+            let files: HashSet<String> = shard_names
+                .names
+                .iter()
+                .map(|n| sanitize_filename(n).to_lowercase())
+                .collect();
+            proptest::prop_assert_eq!(files.len(), paths.len());
+        }
+    }
+
     #[test]
     fn test_module_registry_basic() {
         // Use paths that will have conflicts to test partial stripping
@@ -2996,7 +3750,7 @@ mod tests {
     #[test]
     fn test_build_call_graph_empty() {
         let summaries: Vec<SemanticSummary> = vec![];
-        let graph = build_call_graph(&summaries, &None);
+        let graph = build_call_graph(&summaries, None, &None);
         assert!(
             graph.is_empty(),
             "Empty summaries should produce empty graph"
@@ -3022,7 +3776,7 @@ mod tests {
             ..Default::default()
         }];
 
-        let graph = build_call_graph(&summaries, &None);
+        let graph = build_call_graph(&summaries, None, &None);
         // No calls means no edges in the graph
         assert!(
             graph.is_empty() || graph.values().all(|v| v.is_empty()),
@@ -3071,11 +3825,198 @@ mod tests {
             },
         ];
 
-        let graph = build_call_graph(&summaries, &None);
+        let graph = build_call_graph(&summaries, None, &None);
         // Should have at least one entry for main calling helper
         assert!(!graph.is_empty(), "Should produce a call graph with edges");
     }
 
+    #[test]
+    fn test_build_call_graph_python_relative_import() {
+        use crate::overlay::compute_symbol_hash;
+        use crate::schema::{Call, Import, ImportedName, SymbolInfo, SymbolKind};
+
+        let function = |name: &str, calls: Vec<Call>| SymbolInfo {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            start_line: 1,
+            end_line: 3,
+            calls,
+            ..Default::default()
+        };
+        let module = |file: &str, symbols: Vec<SymbolInfo>, imports: Vec<Import>| SemanticSummary {
+            file: file.to_string(),
+            language: "python".to_string(),
+            symbols,
+            imports,
+            ..Default::default()
+        };
+        let call = |object: Option<&str>, name: &str| Call {
+            name: name.to_string(),
+            object: object.map(String::from),
+            ..Default::default()
+        };
+
+        // Two packages both define `helper`; each service imports its own
+        let summaries = vec![
+            module("pkg/__init__.py", vec![], vec![]),
+            module("pkg/a/__init__.py", vec![], vec![]),
+            module("pkg/a/utils.py", vec![function("helper", vec![])], vec![]),
+            module("pkg/b/__init__.py", vec![], vec![]),
+            module("pkg/b/utils.py", vec![function("helper", vec![])], vec![]),
+            module(
+                "pkg/b/service.py",
+                vec![
+                    function("direct", vec![call(None, "h")]),
+                    function("qualified", vec![call(Some("utils"), "helper")]),
+                ],
+                vec![
+                    Import {
+                        source: ".utils".to_string(),
+                        names: vec![ImportedName {
+                            name: "helper".to_string(),
+                            alias: Some("h".to_string()),
+                        }],
+                        ..Default::default()
+                    },
+                    Import {
+                        source: ".".to_string(),
+                        names: vec![ImportedName {
+                            name: "utils".to_string(),
+                            alias: None,
+                        }],
+                        ..Default::default()
+                    },
+                ],
+            ),
+        ];
+
+        let graph = build_call_graph(&summaries, None, &None);
+        let helper_b = compute_symbol_hash(&function("helper", vec![]), "pkg/b/utils.py");
+        for caller in ["direct", "qualified"] {
+            let caller_hash = compute_symbol_hash(
+                summaries[5]
+                    .symbols
+                    .iter()
+                    .find(|s| s.name == caller)
+                    .unwrap(),
+                "pkg/b/service.py",
+            );
+            let callees: Vec<&str> = graph[&caller_hash]
+                .iter()
+                .map(|e| e.callee.as_str())
+                .collect();
+            assert_eq!(callees, vec![helper_b.as_str()], "{caller}");
+        }
+    }
+
+    #[test]
+    fn test_build_call_graph_rust_impl_methods() {
+        use crate::overlay::compute_symbol_hash;
+        use std::path::Path;
+
+        let parse = |file: &str, source: &str| {
+            crate::parsing::parse_and_extract(Path::new(file), source, crate::lang::Lang::Rust)
+                .unwrap()
+        };
+        let summaries = vec![
+            parse(
+                "src/shapes.rs",
+                r#"
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square {
+    side: f64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Square {
+    pub fn new(side: f64) -> Self {
+        Square { side }
+    }
+}
+
+#[async_trait]
+pub trait Store {
+    async fn load(&self, key: &str) -> Option<String>;
+}
+
+pub struct MemStore;
+
+#[async_trait]
+impl Store for MemStore {
+    async fn load(&self, key: &str) -> Option<String> {
+        None
+    }
+}
+"#,
+            ),
+            parse(
+                "src/circle.rs",
+                r#"
+pub struct Circle {
+    radius: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        self.radius * self.radius
+    }
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Circle { radius }
+    }
+}
+"#,
+            ),
+            parse(
+                "src/main.rs",
+                r#"
+pub async fn run(store: &MemStore) -> f64 {
+    let square = Square::new(2.0);
+    let circle = Circle::new(1.0);
+    let names: Vec<String> = Vec::new();
+    store.load("key").await;
+    Circle::area(&circle) + square.side
+}
+"#,
+            ),
+        ];
+
+        let hash_of = |file: usize, name: &str| {
+            let summary = &summaries[file];
+            let symbol = summary
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap_or_else(|| panic!("no symbol {name}"));
+            compute_symbol_hash(symbol, &summary.file)
+        };
+        let graph = build_call_graph(&summaries, None, &None);
+        let callees: Vec<&str> = graph[&hash_of(2, "run")]
+            .iter()
+            .map(|e| e.callee.as_str())
+            .collect();
+
+        for expected in [
+            hash_of(0, "new"),
+            hash_of(1, "new"),
+            hash_of(0, "<MemStore as Store>::load"),
+            hash_of(1, "<Circle as Shape>::area"),
+        ] {
+            assert!(callees.contains(&expected.as_str()), "{callees:?}");
+        }
+        assert!(callees.contains(&"ext:Vec::new"), "{callees:?}");
+    }
+
     // ========================================================================
     // ShardStats tests
     // ========================================================================
@@ -59,6 +59,21 @@ pub enum ExportPhase {
     Finalizing,
 }
 
+impl ExportPhase {
+    /// Stable snake_case name, as used in progress events
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportPhase::CreatingSchema => "creating_schema",
+            ExportPhase::InsertingNodes => "inserting_nodes",
+            ExportPhase::InsertingEdges => "inserting_edges",
+            ExportPhase::ComputingModuleEdges => "computing_module_edges",
+            ExportPhase::UpdatingCounts => "updating_counts",
+            ExportPhase::CreatingIndexes => "creating_indexes",
+            ExportPhase::Finalizing => "finalizing",
+        }
+    }
+}
+
 /// SQLite exporter for call graph data
 pub struct SqliteExporter {
     batch_size: usize,
@@ -0,0 +1,296 @@
+//! Porter stemmer for English search terms
+//!
+//! Reduces inflected words to a common stem so that `authenticate`,
+//! `authenticated` and `authentication` all index as `authent`. This is the
+//! original algorithm from M.F. Porter, "An algorithm for suffix stripping"
+//! (1980). Stems are not always words; they only need to agree between index
+//! and query time.
+//!
+//! Only lowercase ASCII words are stemmed. Anything else (identifiers with
+//! digits, non-Latin scripts, words of one or two letters) is returned as is.
+
+/// Reduce `word` to its stem
+pub fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut w = word.as_bytes().to_vec();
+    step1a(&mut w);
+    step1b(&mut w);
+    step1c(&mut w);
+    step2(&mut w);
+    step3(&mut w);
+    step4(&mut w);
+    step5(&mut w);
+
+    // Only ASCII letters were ever written
+    String::from_utf8(w).unwrap_or_else(|_| word.to_string())
+}
+
+/// Whether `w[i]` is a consonant: not a vowel, and `y` only after a vowel
+fn is_consonant(w: &[u8], i: usize) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => false,
+        b'y' => i == 0 || !is_consonant(w, i - 1),
+        _ => true,
+    }
+}
+
+/// Porter's measure m: the number of vowel-consonant sequences in `w`
+fn measure(w: &[u8]) -> usize {
+    let n = w.len();
+    let mut i = 0;
+    while i < n && is_consonant(w, i) {
+        i += 1;
+    }
+    let mut m = 0;
+    loop {
+        while i < n && !is_consonant(w, i) {
+            i += 1;
+        }
+        if i >= n {
+            return m;
+        }
+        while i < n && is_consonant(w, i) {
+            i += 1;
+        }
+        m += 1;
+    }
+}
+
+fn has_vowel(w: &[u8]) -> bool {
+    (0..w.len()).any(|i| !is_consonant(w, i))
+}
+
+fn ends_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 2 && w[n - 1] == w[n - 2] && is_consonant(w, n - 1)
+}
+
+/// Consonant-vowel-consonant ending, where the last consonant is not w, x or y
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 3
+        && is_consonant(w, n - 3)
+        && !is_consonant(w, n - 2)
+        && is_consonant(w, n - 1)
+        && !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+/// Length of the stem left once `suffix` is removed, if `w` ends with it
+fn stem_len(w: &[u8], suffix: &str) -> Option<usize> {
+    w.ends_with(suffix.as_bytes())
+        .then(|| w.len() - suffix.len())
+}
+
+fn replace_suffix(w: &mut Vec<u8>, stem_len: usize, replacement: &str) {
+    w.truncate(stem_len);
+    w.extend_from_slice(replacement.as_bytes());
+}
+
+/// Apply the rule for the longest suffix in `rules` that `w` ends with, if
+/// the remaining stem's measure exceeds `min_measure`
+fn apply_rules(w: &mut Vec<u8>, rules: &[(&str, &str)], min_measure: usize) {
+    for (suffix, replacement) in rules {
+        if let Some(len) = stem_len(w, suffix) {
+            if measure(&w[..len]) > min_measure {
+                replace_suffix(w, len, replacement);
+            }
+            return;
+        }
+    }
+}
+
+/// Plurals: `caresses` → `caress`, `ponies` → `poni`, `cats` → `cat`
+fn step1a(w: &mut Vec<u8>) {
+    if let Some(len) = stem_len(w, "sses") {
+        replace_suffix(w, len, "ss");
+    } else if let Some(len) = stem_len(w, "ies") {
+        replace_suffix(w, len, "i");
+    } else if w.ends_with(b"s") && !w.ends_with(b"ss") {
+        w.pop();
+    }
+}
+
+/// Past tenses and gerunds: `agreed` → `agree`, `hopping` → `hop`
+fn step1b(w: &mut Vec<u8>) {
+    if let Some(len) = stem_len(w, "eed") {
+        if measure(&w[..len]) > 0 {
+            replace_suffix(w, len, "ee");
+        }
+        return;
+    }
+
+    let stripped = ["ed", "ing"]
+        .iter()
+        .find_map(|suffix| stem_len(w, suffix))
+        .filter(|&len| has_vowel(&w[..len]));
+    let Some(len) = stripped else {
+        return;
+    };
+    w.truncate(len);
+
+    if w.ends_with(b"at") || w.ends_with(b"bl") || w.ends_with(b"iz") {
+        w.push(b'e');
+    } else if ends_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+/// Terminal y: `happy` → `happi`
+fn step1c(w: &mut [u8]) {
+    let n = w.len();
+    if w.ends_with(b"y") && has_vowel(&w[..n - 1]) {
+        w[n - 1] = b'i';
+    }
+}
+
+/// Double suffixes: `relational` → `relate`, `generalization` → `generalize`
+fn step2(w: &mut Vec<u8>) {
+    const RULES: &[(&str, &str)] = &[
+        ("ational", "ate"),
+        ("tional", "tion"),
+        ("enci", "ence"),
+        ("anci", "ance"),
+        ("izer", "ize"),
+        ("abli", "able"),
+        ("alli", "al"),
+        ("entli", "ent"),
+        ("eli", "e"),
+        ("ousli", "ous"),
+        ("ization", "ize"),
+        ("ation", "ate"),
+        ("ator", "ate"),
+        ("alism", "al"),
+        ("iveness", "ive"),
+        ("fulness", "ful"),
+        ("ousness", "ous"),
+        ("aliti", "al"),
+        ("iviti", "ive"),
+        ("biliti", "ble"),
+    ];
+    apply_rules(w, &longest_first(RULES), 0);
+}
+
+/// `-ic-`, `-full`, `-ness` and friends: `triplicate` → `triplic`
+fn step3(w: &mut Vec<u8>) {
+    const RULES: &[(&str, &str)] = &[
+        ("icate", "ic"),
+        ("ative", ""),
+        ("alize", "al"),
+        ("iciti", "ic"),
+        ("ical", "ic"),
+        ("ful", ""),
+        ("ness", ""),
+    ];
+    apply_rules(w, &longest_first(RULES), 0);
+}
+
+/// Remaining suffixes on long stems: `adjustment` → `adjust`
+fn step4(w: &mut Vec<u8>) {
+    const SUFFIXES: &[&str] = &[
+        "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+        "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+    ];
+    let mut suffixes = SUFFIXES.to_vec();
+    suffixes.sort_by_key(|s| std::cmp::Reverse(s.len()));
+
+    for suffix in suffixes {
+        if let Some(len) = stem_len(w, suffix) {
+            let ion_ok = suffix != "ion" || (len > 0 && matches!(w[len - 1], b's' | b't'));
+            if ion_ok && measure(&w[..len]) > 1 {
+                w.truncate(len);
+            }
+            return;
+        }
+    }
+}
+
+/// Final e and double l: `probate` → `probat`, `controll` → `control`
+fn step5(w: &mut Vec<u8>) {
+    if let Some(len) = stem_len(w, "e") {
+        let m = measure(&w[..len]);
+        if m > 1 || (m == 1 && !ends_cvc(&w[..len])) {
+            w.truncate(len);
+        }
+    }
+    if measure(w) > 1 && ends_double_consonant(w) && w.ends_with(b"l") {
+        w.pop();
+    }
+}
+
+/// Rules ordered so the longest matching suffix is found first
+fn longest_first<'a>(rules: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+    let mut rules = rules.to_vec();
+    rules.sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_stems(cases: &[(&str, &str)]) {
+        for (word, expected) in cases {
+            assert_eq!(stem(word), *expected, "stem({:?})", word);
+        }
+    }
+
+    #[test]
+    fn test_step1_plurals_and_tenses() {
+        assert_stems(&[
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("feed", "feed"),
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("sing", "sing"),
+            ("conflated", "conflat"),
+            ("hopping", "hop"),
+            ("falling", "fall"),
+            ("filing", "file"),
+            ("happy", "happi"),
+        ]);
+    }
+
+    #[test]
+    fn test_derivational_suffixes() {
+        assert_stems(&[
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("generalization", "gener"),
+            ("triplicate", "triplic"),
+            ("adjustment", "adjust"),
+            ("adoption", "adopt"),
+            ("controlling", "control"),
+        ]);
+    }
+
+    #[test]
+    fn test_word_family_shares_a_stem() {
+        for word in [
+            "authenticate",
+            "authenticated",
+            "authenticates",
+            "authenticating",
+            "authentication",
+        ] {
+            assert_eq!(stem(word), "authent", "stem({:?})", word);
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_and_short_words_unchanged() {
+        assert_stems(&[
+            ("is", "is"),
+            ("utf8", "utf8"),
+            ("café", "café"),
+            ("用户", "用户"),
+        ]);
+    }
+}
@@ -0,0 +1,767 @@
+//! Test-to-production association
+//!
+//! Links production code and the tests exercising it, in both directions:
+//! [`TestLinkIndex::tested_by`] lists the test files covering a production
+//! symbol, [`TestLinkIndex::tests_symbols`] the production symbols a test
+//! calls. The links are built at index time (`graphs/test_links.json`) from
+//! three kinds of evidence, reported as a [`LinkConfidence`] tier:
+//!
+//! - **convention**: the test file is named after the source file
+//!   (`invoice.test.ts`, `invoice_test.go`, `tests/test_invoice.py`), or the
+//!   tests live in a Rust `#[cfg(test)]` module inside it
+//! - **import**: the test file imports the source file
+//! - **call**: a test body calls a symbol of the source file
+//!
+//! Partial reindexes leave the links untouched; the next full index rebuilds
+//! them.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::PythonPackageIndex;
+use crate::cache::verify::{corrupt, read_artifact};
+use crate::cache::CacheDir;
+use crate::error::{McpDiffError, Result};
+use crate::lang::{Lang, LangFamily};
+use crate::module_usage::{normalize_path, resolve_specifier};
+use crate::overlay::compute_symbol_hash;
+use crate::schema::{Call, FrameworkEntryPoint, SemanticSummary, SymbolInfo, SCHEMA_VERSION};
+use crate::search::is_test_file;
+
+/// Strength of the evidence linking a test to production code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkConfidence {
+    /// File naming convention only
+    Convention,
+    /// The test file imports the production file
+    Import,
+    /// A test calls the production symbol
+    Call,
+}
+
+impl LinkConfidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Convention => "convention",
+            Self::Import => "import",
+            Self::Call => "call",
+        }
+    }
+}
+
+/// A file on the other side of a link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRef {
+    pub file: String,
+    pub confidence: LinkConfidence,
+}
+
+/// A production symbol exercised by a test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolLink {
+    pub hash: String,
+    pub name: String,
+    pub file: String,
+    pub confidence: LinkConfidence,
+}
+
+/// Production symbols called by one test
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestTargets {
+    /// Test function name (the file stem for module-level test code)
+    pub name: String,
+    pub file: String,
+    pub symbols: Vec<SymbolLink>,
+}
+
+/// Persisted test association
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestLinkIndex {
+    pub schema_version: String,
+    /// Production file -> test files covering it, strongest evidence first
+    pub files: BTreeMap<String, Vec<FileRef>>,
+    /// Production symbol hash -> test files exercising it, strongest first
+    pub tested_by: BTreeMap<String, Vec<FileRef>>,
+    /// Test symbol hash -> production symbols it calls. Module-level test
+    /// code (`describe`/`it` callbacks) is keyed by the test file.
+    pub tests_symbols: BTreeMap<String, TestTargets>,
+}
+
+/// A production symbol candidate for call matching
+struct ProductionSymbol<'a> {
+    hash: String,
+    name: &'a str,
+    file: &'a str,
+}
+
+/// Test code found in one file
+struct TestSource<'a> {
+    summary: &'a SemanticSummary,
+    /// `(key, name, calls)` per test; module-level code is keyed by the file
+    units: Vec<(String, String, Vec<&'a Call>)>,
+    /// Tests live inside the production file (Rust `#[cfg(test)]`)
+    inline: bool,
+}
+
+impl TestLinkIndex {
+    /// Associate the tests among `summaries` with the production code
+    pub fn build(summaries: &[SemanticSummary], repo_root: Option<&Path>) -> Self {
+        let mut index = Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            ..Default::default()
+        };
+
+        let by_key: BTreeMap<String, &SemanticSummary> = summaries
+            .iter()
+            .map(|s| (normalize_path(&s.file), s))
+            .collect();
+        let python = PythonPackageIndex::from_summaries(summaries, repo_root);
+
+        let mut production: Vec<&SemanticSummary> = Vec::new();
+        let mut tests: Vec<TestSource> = Vec::new();
+        for summary in summaries {
+            if !is_programming_file(&summary.file) {
+                continue;
+            }
+            if is_test_file(&summary.file) {
+                tests.push(test_source(summary, false));
+                continue;
+            }
+            production.push(summary);
+            let inline = test_source(summary, true);
+            if !inline.units.is_empty() {
+                tests.push(inline);
+            }
+        }
+
+        let mut symbols_by_name: HashMap<&str, Vec<ProductionSymbol>> = HashMap::new();
+        let mut symbols_by_file: HashMap<&str, Vec<String>> = HashMap::new();
+        for summary in &production {
+            for symbol in summary.symbols.iter().filter(|s| !is_test_function(s)) {
+                let hash = compute_symbol_hash(symbol, &summary.file);
+                symbols_by_file
+                    .entry(summary.file.as_str())
+                    .or_default()
+                    .push(hash.clone());
+                symbols_by_name
+                    .entry(symbol.name.as_str())
+                    .or_default()
+                    .push(ProductionSymbol {
+                        hash,
+                        name: &symbol.name,
+                        file: &summary.file,
+                    });
+            }
+        }
+
+        // (production file, test file) -> convention/import evidence
+        let mut evidence: BTreeMap<(&str, &str), LinkConfidence> = BTreeMap::new();
+        // (production file, test file) pairs confirmed by a call
+        let mut call_pairs: BTreeSet<(&str, &str)> = BTreeSet::new();
+        // (production symbol, test file) pairs confirmed by a call
+        let mut called: BTreeSet<(&str, &str)> = BTreeSet::new();
+
+        for test in &tests {
+            let test_file = test.summary.file.as_str();
+            let mut linked: BTreeMap<&str, LinkConfidence> = BTreeMap::new();
+            if test.inline {
+                linked.insert(test_file, LinkConfidence::Convention);
+            } else {
+                for file in convention_matches(test_file, &production) {
+                    linked.insert(file, LinkConfidence::Convention);
+                }
+                for file in imported_files(test.summary, &by_key, &python) {
+                    if !is_test_file(file) {
+                        linked.insert(file, LinkConfidence::Import);
+                    }
+                }
+            }
+            for (file, confidence) in &linked {
+                evidence.insert((file, test_file), *confidence);
+            }
+
+            for (key, name, calls) in &test.units {
+                let mut targets = TestTargets {
+                    name: name.clone(),
+                    file: test_file.to_string(),
+                    symbols: Vec::new(),
+                };
+                for call in calls {
+                    let Some(symbol) = match_call(call, &symbols_by_name, &linked) else {
+                        continue;
+                    };
+                    if targets.symbols.iter().any(|s| s.hash == symbol.hash) {
+                        continue;
+                    }
+                    called.insert((symbol.hash.as_str(), test_file));
+                    call_pairs.insert((symbol.file, test_file));
+                    targets.symbols.push(SymbolLink {
+                        hash: symbol.hash.clone(),
+                        name: symbol.name.to_string(),
+                        file: symbol.file.to_string(),
+                        confidence: LinkConfidence::Call,
+                    });
+                }
+                if !targets.symbols.is_empty() {
+                    index.tests_symbols.insert(key.clone(), targets);
+                }
+            }
+        }
+
+        let pairs: BTreeSet<(&str, &str)> = evidence
+            .keys()
+            .copied()
+            .chain(call_pairs.iter().copied())
+            .collect();
+        for (production_file, test_file) in pairs {
+            let file_evidence = evidence.get(&(production_file, test_file)).copied();
+            let confidence = if call_pairs.contains(&(production_file, test_file)) {
+                LinkConfidence::Call
+            } else {
+                file_evidence.unwrap_or(LinkConfidence::Convention)
+            };
+            index
+                .files
+                .entry(production_file.to_string())
+                .or_default()
+                .push(FileRef {
+                    file: test_file.to_string(),
+                    confidence,
+                });
+
+            // Symbols the tests don't call keep the file's naming/import evidence
+            for hash in symbols_by_file.get(production_file).into_iter().flatten() {
+                let confidence = if called.contains(&(hash.as_str(), test_file)) {
+                    LinkConfidence::Call
+                } else {
+                    match file_evidence {
+                        Some(confidence) => confidence,
+                        None => continue,
+                    }
+                };
+                index
+                    .tested_by
+                    .entry(hash.clone())
+                    .or_default()
+                    .push(FileRef {
+                        file: test_file.to_string(),
+                        confidence,
+                    });
+            }
+        }
+
+        for refs in index.files.values_mut().chain(index.tested_by.values_mut()) {
+            sort_refs(refs);
+        }
+        index
+    }
+
+    /// Load the persisted links
+    pub fn load(cache: &CacheDir) -> Result<Self> {
+        let path = cache.test_links_path();
+        if !path.exists() {
+            return Err(McpDiffError::FileNotFound {
+                path: "Test links not found. Run `semfora index generate --force` first."
+                    .to_string(),
+            });
+        }
+        let content = read_artifact(&path)?;
+        serde_json::from_str(&content).map_err(|e| corrupt(&path, e))
+    }
+
+    /// Persist the links, returning the bytes written
+    pub fn write(&self, cache: &CacheDir) -> Result<usize> {
+        let content = serde_json::to_string(self).map_err(|e| McpDiffError::ExtractionFailure {
+            message: format!("Failed to serialize test links: {}", e),
+        })?;
+        let path = cache.test_links_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+        Ok(content.len())
+    }
+
+    /// Test files covering a production file (matched by path suffix)
+    pub fn tests_for_file(&self, file: &str) -> Option<(&str, &[FileRef])> {
+        self.files
+            .iter()
+            .find(|(production, _)| same_file(production, file))
+            .map(|(production, refs)| (production.as_str(), refs.as_slice()))
+    }
+
+    /// Whether `file` is a test file with recorded links
+    pub fn is_test_file(&self, file: &str) -> bool {
+        self.files
+            .values()
+            .flatten()
+            .any(|r| same_file(&r.file, file))
+    }
+
+    /// Production files a test file covers, strongest evidence first
+    pub fn sources_for_test_file(&self, test_file: &str) -> Vec<FileRef> {
+        let mut refs: Vec<FileRef> = self
+            .files
+            .iter()
+            .filter_map(|(production, tests)| {
+                tests
+                    .iter()
+                    .find(|r| same_file(&r.file, test_file))
+                    .map(|r| FileRef {
+                        file: production.clone(),
+                        confidence: r.confidence,
+                    })
+            })
+            .collect();
+        sort_refs(&mut refs);
+        refs
+    }
+
+    /// Production symbols called by any test in a test file
+    pub fn symbols_for_test_file(&self, test_file: &str) -> Vec<SymbolLink> {
+        let mut symbols: Vec<SymbolLink> = Vec::new();
+        for targets in self
+            .tests_symbols
+            .values()
+            .filter(|t| same_file(&t.file, test_file))
+        {
+            for symbol in &targets.symbols {
+                if !symbols.iter().any(|s| s.hash == symbol.hash) {
+                    symbols.push(symbol.clone());
+                }
+            }
+        }
+        symbols
+    }
+
+    /// Production symbols a failing test most likely exercises
+    ///
+    /// Matches the test by its last name segment (`tests::test_total`,
+    /// `tests/test_x.py::test_total`, `TestTotal/case`), falling back to
+    /// everything the failing test's file calls.
+    pub fn probable_symbols(&self, test_name: &str, test_file: Option<&str>) -> Vec<SymbolLink> {
+        let leaf = test_name.rsplit("::").next().unwrap_or(test_name);
+        let leaf = leaf.split('/').next().unwrap_or(leaf).trim();
+
+        let by_name: Vec<&TestTargets> = self
+            .tests_symbols
+            .values()
+            .filter(|t| t.name == leaf)
+            .filter(|t| test_file.is_none_or(|file| same_file(&t.file, file)))
+            .collect();
+        if let [targets] = by_name.as_slice() {
+            return targets.symbols.clone();
+        }
+        test_file
+            .map(|file| self.symbols_for_test_file(file))
+            .unwrap_or_default()
+    }
+}
+
+/// Tests in a file: every function of a test file plus its module-level
+/// code, or only the `#[test]`-style functions of a production file
+fn test_source(summary: &SemanticSummary, inline: bool) -> TestSource<'_> {
+    let mut units: Vec<(String, String, Vec<&Call>)> = summary
+        .symbols
+        .iter()
+        .filter(|s| {
+            if inline {
+                is_test_function(s)
+            } else {
+                matches!(s.kind.as_str(), "function" | "method")
+            }
+        })
+        .map(|s| {
+            (
+                compute_symbol_hash(s, &summary.file),
+                s.name.clone(),
+                s.calls.iter().collect(),
+            )
+        })
+        .collect();
+    if !inline && !summary.calls.is_empty() {
+        units.push((
+            summary.file.clone(),
+            file_stem(&summary.file).to_string(),
+            summary.calls.iter().collect(),
+        ));
+    }
+    TestSource {
+        summary,
+        units,
+        inline,
+    }
+}
+
+/// Test function by attribute or framework marker (`#[test]`, `#[tokio::test]`)
+fn is_test_function(symbol: &SymbolInfo) -> bool {
+    symbol.framework_entry_point == FrameworkEntryPoint::TestFunction
+        || symbol.decorators.iter().any(|d| {
+            d.trim_start_matches("#[")
+                .trim_end_matches(']')
+                .ends_with("test")
+        })
+}
+
+/// Resolve a test's call to a production symbol
+///
+/// Symbols in files the test is already linked to win; otherwise the name
+/// must be unique among production symbols.
+fn match_call<'a, 'b>(
+    call: &Call,
+    symbols_by_name: &'b HashMap<&str, Vec<ProductionSymbol<'a>>>,
+    linked: &BTreeMap<&str, LinkConfidence>,
+) -> Option<&'b ProductionSymbol<'a>> {
+    let candidates = symbols_by_name.get(call.name.as_str())?;
+    candidates
+        .iter()
+        .find(|s| linked.contains_key(s.file))
+        .or(match candidates.as_slice() {
+            [only] => Some(only),
+            _ => None,
+        })
+}
+
+/// Production files named after a test file, closest directory first
+fn convention_matches<'a>(test_file: &str, production: &[&'a SemanticSummary]) -> Vec<&'a str> {
+    let Some(subject) = test_subject(test_file) else {
+        return Vec::new();
+    };
+    let family = Lang::from_path(Path::new(test_file))
+        .map(|l| l.family())
+        .ok();
+
+    let candidates: Vec<(&str, usize)> = production
+        .iter()
+        .map(|s| s.file.as_str())
+        .filter(|file| Lang::from_path(Path::new(file)).map(|l| l.family()).ok() == family)
+        .filter(|file| source_subject(file) == subject)
+        .map(|file| (file, shared_dirs(test_file, file)))
+        .collect();
+    let best = candidates.iter().map(|(_, shared)| *shared).max();
+    candidates
+        .into_iter()
+        .filter(|(_, shared)| Some(*shared) == best)
+        .map(|(file, _)| file)
+        .collect()
+}
+
+/// Normalized name of the unit a test file is about (`invoice.test.ts`,
+/// `test_invoice.py`, `invoice_test.go`, `InvoiceTest.java` -> `invoice`)
+fn test_subject(test_file: &str) -> Option<String> {
+    let stem = file_stem(test_file);
+    let stem = stem.strip_prefix("test_").unwrap_or(stem);
+    let stem = ["_tests", "_test", "_spec", "Tests", "Test", "Spec"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .unwrap_or(stem);
+    if matches!(
+        stem,
+        "" | "test" | "tests" | "index" | "mod" | "__init__" | "lib" | "main"
+    ) {
+        return None;
+    }
+    Some(normalize_name(stem))
+}
+
+/// Normalized name of a production file (`mod.rs`/`index.ts` use their directory)
+fn source_subject(file: &str) -> String {
+    let stem = file_stem(file);
+    if matches!(stem, "mod" | "index" | "__init__") {
+        let dir = file.rsplit('/').nth(1).unwrap_or(stem);
+        return normalize_name(dir);
+    }
+    normalize_name(stem)
+}
+
+/// File name up to the first dot (`invoice.test.ts` -> `invoice`)
+fn file_stem(file: &str) -> &str {
+    let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+    name.split('.').next().unwrap_or(name)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Directory names two files share, ignoring layout directories
+fn shared_dirs(a: &str, b: &str) -> usize {
+    let dirs = |file: &str| -> BTreeSet<String> {
+        let parts: Vec<&str> = file.split('/').collect();
+        parts[..parts.len().saturating_sub(1)]
+            .iter()
+            .filter(|d| {
+                !matches!(
+                    **d,
+                    "" | "." | ".." | "src" | "lib" | "tests" | "test" | "__tests__"
+                )
+            })
+            .map(|d| d.to_string())
+            .collect()
+    };
+    dirs(a).intersection(&dirs(b)).count()
+}
+
+/// Repo files a test file imports
+fn imported_files<'a>(
+    summary: &SemanticSummary,
+    by_key: &'a BTreeMap<String, &'a SemanticSummary>,
+    python: &'a PythonPackageIndex,
+) -> Vec<&'a str> {
+    let key = normalize_path(&summary.file);
+    let family = Lang::from_path(Path::new(&summary.file)).map(|l| l.family());
+    let files: Vec<&str> = match family {
+        Ok(LangFamily::JavaScript) => summary
+            .imports
+            .iter()
+            .filter(|i| i.source.starts_with('.'))
+            .flat_map(|i| resolve_specifier(by_key, &key, &i.source))
+            .collect(),
+        Ok(LangFamily::Python) => python
+            .import_targets(summary)
+            .into_values()
+            .filter_map(|target| {
+                by_key
+                    .get(&normalize_path(&target.file))
+                    .map(|s| s.file.as_str())
+            })
+            .collect(),
+        Ok(LangFamily::Rust) => rust_imported_files(&key, summary, by_key),
+        _ => Vec::new(),
+    };
+    files
+        .into_iter()
+        .map(|file| by_key.get(file).map_or(file, |s| s.file.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Crate files an integration test (`<crate>/tests/*.rs`) imports through
+/// `use <crate>::path::to::item`
+fn rust_imported_files<'a>(
+    key: &str,
+    summary: &SemanticSummary,
+    by_key: &'a BTreeMap<String, &'a SemanticSummary>,
+) -> Vec<&'a str> {
+    let crate_dir = match key.rsplit_once("tests/") {
+        Some((dir, _)) => dir,
+        None => return Vec::new(),
+    };
+    let src = format!("{}src", crate_dir);
+    let lookup = |path: &str| by_key.get(path).map(|s| s.file.as_str());
+
+    let mut files = Vec::new();
+    for import in &summary.imports {
+        let segments: Vec<&str> = import.source.split("::").collect();
+        if matches!(
+            segments[0],
+            "std" | "core" | "alloc" | "crate" | "self" | "super"
+        ) {
+            continue;
+        }
+        let names = import.names.iter().map(|n| n.name.as_str());
+        let paths: Vec<Vec<&str>> = if import.names.is_empty() {
+            vec![segments[1..].to_vec()]
+        } else {
+            names
+                .map(|name| {
+                    let mut path = segments[1..].to_vec();
+                    path.push(name);
+                    path
+                })
+                .collect()
+        };
+        for path in paths {
+            // The longest prefix naming a module file wins; items of the
+            // crate root live in lib.rs
+            let found = (1..=path.len()).rev().find_map(|len| {
+                let module = path[..len].join("/");
+                lookup(&format!("{}/{}.rs", src, module))
+                    .or_else(|| lookup(&format!("{}/{}/mod.rs", src, module)))
+            });
+            if let Some(file) = found.or_else(|| lookup(&format!("{}/lib.rs", src))) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+fn is_programming_file(file: &str) -> bool {
+    Lang::from_path(Path::new(file)).is_ok_and(|lang| lang.is_programming_language())
+}
+
+/// Paths equal after normalization, or one is a suffix of the other
+fn same_file(a: &str, b: &str) -> bool {
+    let a = normalize_path(a.trim_start_matches("./"));
+    let b = normalize_path(b.trim_start_matches("./"));
+    a == b || a.ends_with(&format!("/{}", b)) || b.ends_with(&format!("/{}", a))
+}
+
+fn sort_refs(refs: &mut [FileRef]) {
+    refs.sort_by(|a, b| {
+        b.confidence
+            .cmp(&a.confidence)
+            .then_with(|| a.file.cmp(&b.file))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summarize(path: &str, source: &str) -> SemanticSummary {
+        let lang = Lang::from_path(Path::new(path)).unwrap();
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&lang.tree_sitter_language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        crate::extract::extract(Path::new(path), source, &tree, lang).unwrap()
+    }
+
+    fn refs(refs: &[FileRef]) -> Vec<(&str, LinkConfidence)> {
+        refs.iter()
+            .map(|r| (r.file.as_str(), r.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn test_subject_names() {
+        assert_eq!(
+            test_subject("src/invoice.test.ts").as_deref(),
+            Some("invoice")
+        );
+        assert_eq!(
+            test_subject("tests/test_invoice.py").as_deref(),
+            Some("invoice")
+        );
+        assert_eq!(
+            test_subject("pkg/invoice_test.go").as_deref(),
+            Some("invoice")
+        );
+        assert_eq!(
+            test_subject("src/test/java/InvoiceTest.java").as_deref(),
+            Some("invoice")
+        );
+        assert_eq!(test_subject("tests/mod.rs"), None);
+        assert_eq!(source_subject("src/billing/mod.rs"), "billing");
+        assert_eq!(source_subject("src/line_item.rs"), "lineitem");
+    }
+
+    #[test]
+    fn test_typescript_links_both_directions() {
+        let summaries = vec![
+            summarize(
+                "src/billing/invoice.ts",
+                "export function computeTotal(items: number[]): number {\n  return items.length;\n}\n\nexport function formatInvoice(total: number): string {\n  return String(total);\n}\n",
+            ),
+            summarize(
+                "src/billing/invoice.test.ts",
+                "import { computeTotal } from './invoice';\n\ndescribe('computeTotal', () => {\n  it('sums', () => {\n    expect(computeTotal([1])).toBe(1);\n  });\n});\n",
+            ),
+            summarize(
+                "src/billing/receipt.ts",
+                "export function printReceipt() {\n  return 1;\n}\n",
+            ),
+        ];
+        let index = TestLinkIndex::build(&summaries, None);
+
+        let (file, tests) = index.tests_for_file("src/billing/invoice.ts").unwrap();
+        assert_eq!(file, "src/billing/invoice.ts");
+        assert_eq!(
+            refs(tests),
+            vec![("src/billing/invoice.test.ts", LinkConfidence::Call)]
+        );
+        assert!(index.tests_for_file("src/billing/receipt.ts").is_none());
+
+        let hash = |name: &str| {
+            let symbol = summaries[0]
+                .symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap();
+            compute_symbol_hash(symbol, &summaries[0].file)
+        };
+        assert_eq!(
+            refs(&index.tested_by[&hash("computeTotal")]),
+            vec![("src/billing/invoice.test.ts", LinkConfidence::Call)]
+        );
+        // Imported file, but never called
+        assert_eq!(
+            refs(&index.tested_by[&hash("formatInvoice")]),
+            vec![("src/billing/invoice.test.ts", LinkConfidence::Import)]
+        );
+
+        let symbols = index.symbols_for_test_file("src/billing/invoice.test.ts");
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "computeTotal");
+        assert_eq!(
+            index.sources_for_test_file("invoice.test.ts"),
+            vec![FileRef {
+                file: "src/billing/invoice.ts".to_string(),
+                confidence: LinkConfidence::Call,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rust_inline_and_integration_tests() {
+        let summaries = vec![
+            summarize(
+                "demo/src/lib.rs",
+                "pub mod billing;\n\npub fn version() -> u32 {\n    1\n}\n",
+            ),
+            summarize(
+                "demo/src/billing.rs",
+                "pub fn total(x: u32) -> u32 {\n    x + 1\n}\n\npub fn tax(x: u32) -> u32 {\n    x / 10\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_tax() {\n        let got = tax(100);\n        assert_eq!(got, 10);\n    }\n}\n",
+            ),
+            summarize(
+                "demo/tests/billing_test.rs",
+                "use demo::billing::total;\n\n#[test]\nfn adds_one() {\n    let got = total(2);\n    assert_eq!(got, 3);\n}\n",
+            ),
+        ];
+        let index = TestLinkIndex::build(&summaries, None);
+
+        let (_, tests) = index.tests_for_file("demo/src/billing.rs").unwrap();
+        assert_eq!(
+            refs(tests),
+            vec![
+                ("demo/src/billing.rs", LinkConfidence::Call),
+                ("demo/tests/billing_test.rs", LinkConfidence::Call),
+            ]
+        );
+
+        let targets: Vec<(&str, &str)> = index
+            .tests_symbols
+            .values()
+            .flat_map(|t| {
+                t.symbols
+                    .iter()
+                    .map(move |s| (t.name.as_str(), s.name.as_str()))
+            })
+            .collect();
+        assert!(targets.contains(&("test_tax", "tax")), "{targets:?}");
+        assert!(targets.contains(&("adds_one", "total")), "{targets:?}");
+
+        // Test functions are not production symbols
+        let test_tax = summaries[1]
+            .symbols
+            .iter()
+            .find(|s| s.name == "test_tax")
+            .unwrap();
+        assert!(!index
+            .tested_by
+            .contains_key(&compute_symbol_hash(test_tax, "demo/src/billing.rs")));
+
+        let probable = index.probable_symbols("tests::test_tax", None);
+        assert_eq!(probable.len(), 1);
+        assert_eq!(probable[0].name, "tax");
+    }
+}
@@ -16,6 +16,8 @@ use std::time::Instant;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
+use crate::line_symbols::SymbolMapping;
+use crate::test_links::SymbolLink;
 use crate::McpDiffError;
 
 // ============================================================================
@@ -47,6 +49,19 @@ impl TestFramework {
             TestFramework::Unknown => "unknown",
         }
     }
+
+    /// Parse a framework name as accepted by `--framework` (also "rust", "python", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "pytest" | "python" => Some(TestFramework::Pytest),
+            "cargo" | "rust" => Some(TestFramework::Cargo),
+            "npm" | "node" => Some(TestFramework::Npm),
+            "vitest" => Some(TestFramework::Vitest),
+            "jest" => Some(TestFramework::Jest),
+            "go" | "golang" => Some(TestFramework::Go),
+            _ => None,
+        }
+    }
 }
 
 /// Result of running tests
@@ -121,6 +136,14 @@ pub struct TestFailure {
 
     /// Stack trace or additional context
     pub traceback: Option<String>,
+
+    /// Production symbols the test most likely exercises (see [`crate::test_links`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub production_symbols: Vec<SymbolLink>,
+
+    /// Symbol containing the failing line (see [`crate::line_symbols`])
+    #[serde(default, flatten)]
+    pub symbol: SymbolMapping,
 }
 
 /// Options for running tests
@@ -499,6 +522,8 @@ fn parse_pytest_output(stdout: &str, _stderr: &str) -> TestResults {
                 line: None,
                 message: String::new(),
                 traceback: None,
+                production_symbols: Vec::new(),
+                symbol: SymbolMapping::default(),
             });
         } else if line.starts_with("E ") {
             // Error message line
@@ -569,6 +594,8 @@ fn parse_cargo_test_output(stdout: &str, stderr: &str) -> TestResults {
                     line: None,
                     message: failure_message.trim().to_string(),
                     traceback: None,
+                    production_symbols: Vec::new(),
+                    symbol: SymbolMapping::default(),
                 });
             }
 
@@ -593,6 +620,8 @@ fn parse_cargo_test_output(stdout: &str, stderr: &str) -> TestResults {
             line: None,
             message: failure_message.trim().to_string(),
             traceback: None,
+            production_symbols: Vec::new(),
+            symbol: SymbolMapping::default(),
         });
     }
 
@@ -664,6 +693,8 @@ fn parse_go_test_output(stdout: &str, stderr: &str) -> TestResults {
                 line: None,
                 message: String::new(),
                 traceback: None,
+                production_symbols: Vec::new(),
+                symbol: SymbolMapping::default(),
             });
         } else if line.starts_with("--- SKIP:") {
             results.skipped += 1;
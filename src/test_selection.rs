@@ -0,0 +1,360 @@
+//! Diff-aware test selection.
+//!
+//! Maps the lines changed since a base ref onto indexed symbols, then walks
+//! the call graph backwards from those symbols to every test function that
+//! reaches them, directly or through any number of intermediate calls.
+//! Coverage that cannot be determined is reported as an error so callers can
+//! fall back to running the whole suite.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+use crate::cache::{CacheDir, SymbolIndexEntry};
+use crate::error::{McpDiffError, Result};
+use crate::git::{
+    get_changed_line_ranges, get_uncommitted_changes, get_untracked_files, ChangeType,
+};
+use crate::lang::Lang;
+use crate::schema::{CallGraphEdge, FrameworkEntryPoint};
+use crate::search::is_test_file;
+use crate::test_runner::TestFramework;
+
+/// A test function selected because it reaches changed code
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SelectedTest {
+    /// Symbol hash of the test function
+    pub hash: String,
+    /// Test function name
+    pub name: String,
+    /// File containing the test
+    pub file: String,
+    /// Call distance to the nearest changed symbol (0 = the test itself changed)
+    pub depth: usize,
+}
+
+/// Tests selected for the changes since a base ref
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TestSelection {
+    /// Ref the working tree was compared against
+    pub base: String,
+    /// Files with changes (including untracked files)
+    pub changed_files: Vec<String>,
+    /// Names of indexed symbols touched by the changes
+    pub changed_symbols: Vec<String>,
+    /// Tests reaching at least one changed symbol, nearest first
+    pub tests: Vec<SelectedTest>,
+}
+
+/// Select the tests that transitively call symbols changed since `base_ref`
+///
+/// Returns an error when coverage can't be determined (no index or call
+/// graph, deleted files, or changed source files the index doesn't know).
+pub fn select_changed_tests(project_dir: &Path, base_ref: &str) -> Result<TestSelection> {
+    let cache = CacheDir::for_repo(project_dir)?;
+    if !cache.exists() {
+        return Err(McpDiffError::FileNotFound {
+            path: "No index found. Run `semfora index generate` first.".to_string(),
+        });
+    }
+    let call_graph = cache.load_call_graph()?;
+    if call_graph.is_empty() {
+        return Err(McpDiffError::FileNotFound {
+            path: "Call graph not found or empty. Run `semfora index generate` first.".to_string(),
+        });
+    }
+
+    let cwd = Some(cache.repo_root.as_path());
+    if let Some(deleted) = get_uncommitted_changes(base_ref, cwd)?
+        .into_iter()
+        .find(|f| f.change_type == ChangeType::Deleted && is_source_file(&f.path))
+    {
+        return Err(McpDiffError::QueryError {
+            message: format!(
+                "{} was deleted; its callers are no longer indexed",
+                deleted.path
+            ),
+        });
+    }
+
+    let mut changed_ranges = get_changed_line_ranges(base_ref, cwd)?;
+    for path in get_untracked_files(cwd)? {
+        // Untracked files are new in their entirety
+        changed_ranges.push((path, vec![(1, usize::MAX)]));
+    }
+
+    let entries = cache.load_all_symbol_entries()?;
+    let mut by_file: HashMap<String, Vec<&SymbolIndexEntry>> = HashMap::new();
+    for entry in &entries {
+        by_file
+            .entry(relative_to(&cache.repo_root, &entry.file))
+            .or_default()
+            .push(entry);
+    }
+
+    let mut changed: HashSet<String> = HashSet::new();
+    let mut changed_symbols: BTreeSet<String> = BTreeSet::new();
+    for (path, ranges) in &changed_ranges {
+        let Some(symbols) = by_file.get(path) else {
+            if is_source_file(path) {
+                return Err(McpDiffError::QueryError {
+                    message: format!("{} has changes but no indexed symbols", path),
+                });
+            }
+            continue;
+        };
+
+        let mut touched: Vec<&SymbolIndexEntry> = symbols
+            .iter()
+            .copied()
+            .filter(|entry| {
+                let (start, end) = parse_lines(&entry.lines);
+                ranges.iter().any(|&(lo, hi)| lo <= end && start <= hi)
+            })
+            .collect();
+        if touched.is_empty() {
+            // Changes outside any symbol (imports, top-level constants) may
+            // affect everything in the file
+            touched = symbols.clone();
+        }
+        for entry in touched {
+            changed.insert(entry.hash.clone());
+            changed_symbols.insert(entry.symbol.clone());
+        }
+    }
+
+    let entry_by_hash: HashMap<&str, &SymbolIndexEntry> =
+        entries.iter().map(|e| (e.hash.as_str(), e)).collect();
+    let is_test = |hash: &str| {
+        entry_by_hash
+            .get(hash)
+            .is_some_and(|entry| is_test_symbol(entry))
+    };
+
+    let tests = find_covering_tests(&call_graph, &changed, is_test)
+        .into_iter()
+        .filter_map(|(hash, depth)| {
+            let entry = entry_by_hash.get(hash.as_str())?;
+            Some(SelectedTest {
+                name: entry.symbol.clone(),
+                file: relative_to(&cache.repo_root, &entry.file),
+                hash,
+                depth,
+            })
+        })
+        .collect();
+
+    Ok(TestSelection {
+        base: base_ref.to_string(),
+        changed_files: changed_ranges.into_iter().map(|(path, _)| path).collect(),
+        changed_symbols: changed_symbols.into_iter().collect(),
+        tests,
+    })
+}
+
+/// Walk the call graph backwards from `changed` and collect reachable tests
+///
+/// Returns `(hash, depth)` pairs ordered by depth, then hash. Every edge kind
+/// is followed so tests reaching changed code through callbacks or references
+/// are not missed.
+pub fn find_covering_tests<F>(
+    call_graph: &HashMap<String, Vec<String>>,
+    changed: &HashSet<String>,
+    is_test: F,
+) -> Vec<(String, usize)>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut reverse: HashMap<String, Vec<&str>> = HashMap::new();
+    for (caller, callees) in call_graph {
+        for callee in callees {
+            let edge = CallGraphEdge::decode(callee);
+            if edge.callee.starts_with("ext:") {
+                continue;
+            }
+            reverse
+                .entry(edge.callee)
+                .or_default()
+                .push(caller.as_str());
+        }
+    }
+
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+    let mut roots: Vec<&String> = changed.iter().collect();
+    roots.sort();
+    for hash in roots {
+        visited.insert(hash.clone());
+        queue.push_back((hash.clone(), 0));
+    }
+
+    let mut tests = Vec::new();
+    while let Some((current, depth)) = queue.pop_front() {
+        if is_test(&current) {
+            tests.push((current.clone(), depth));
+        }
+        if let Some(callers) = reverse.get(&current) {
+            for caller in callers {
+                if visited.insert(caller.to_string()) {
+                    queue.push_back((caller.to_string(), depth + 1));
+                }
+            }
+        }
+    }
+
+    tests.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    tests
+}
+
+/// Build the runner filter for the selected tests
+///
+/// Returns `(filter, extra_args)` for [`crate::test_runner::TestRunOptions`].
+/// JavaScript runners select by test file rather than function name.
+pub fn filter_for_framework(
+    framework: TestFramework,
+    tests: &[SelectedTest],
+) -> (Option<String>, Vec<String>) {
+    let names: Vec<&str> = tests
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    match framework {
+        TestFramework::Pytest => (Some(names.join(" or ")), Vec::new()),
+        // libtest accepts several substring filters after `--`
+        TestFramework::Cargo => (None, names.iter().map(|n| n.to_string()).collect()),
+        TestFramework::Go => (Some(format!("^({})$", names.join("|"))), Vec::new()),
+        TestFramework::Npm | TestFramework::Vitest | TestFramework::Jest => {
+            let files: BTreeSet<String> = tests.iter().map(|t| t.file.clone()).collect();
+            (None, files.into_iter().collect())
+        }
+        TestFramework::Unknown => (None, Vec::new()),
+    }
+}
+
+/// Whether an indexed symbol is a test function
+fn is_test_symbol(entry: &SymbolIndexEntry) -> bool {
+    if entry.framework_entry_point == FrameworkEntryPoint::TestFunction {
+        return true;
+    }
+    if !matches!(entry.kind.as_str(), "fn" | "function" | "method") {
+        return false;
+    }
+    let decorators = entry.decorators.to_lowercase();
+    decorators.contains("test")
+        || is_test_file(&entry.file)
+        || entry.symbol.starts_with("test_")
+        || (entry.symbol.starts_with("Test") && entry.file.ends_with("_test.go"))
+}
+
+/// Index paths may be absolute; git reports them relative to the repo root
+pub(crate) fn relative_to(root: &Path, file: &str) -> String {
+    Path::new(file)
+        .strip_prefix(root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file.to_string())
+}
+
+fn is_source_file(path: &str) -> bool {
+    Lang::from_path(Path::new(path)).is_ok_and(|lang| lang.is_programming_language())
+}
+
+/// Parse an index line range like "45-89"
+pub(crate) fn parse_lines(lines: &str) -> (usize, usize) {
+    let mut parts = lines.split('-');
+    let start = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let end = parts.next().and_then(|s| s.parse().ok()).unwrap_or(start);
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        edges
+            .iter()
+            .map(|(caller, callees)| {
+                (
+                    caller.to_string(),
+                    callees.iter().map(|c| c.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn changed(hashes: &[&str]) -> HashSet<String> {
+        hashes.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn test_selects_transitive_callers() {
+        // test_checkout -> checkout -> apply_discount -> round_price (changed)
+        let g = graph(&[
+            ("test_checkout", &["checkout"]),
+            ("checkout", &["apply_discount"]),
+            ("apply_discount", &["round_price"]),
+            ("test_round", &["round_price"]),
+            ("test_unrelated", &["format_name"]),
+        ]);
+        let selected =
+            find_covering_tests(&g, &changed(&["round_price"]), |h| h.starts_with("test_"));
+        assert_eq!(
+            selected,
+            vec![
+                ("test_round".to_string(), 1),
+                ("test_checkout".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_test_selects_itself() {
+        let g = graph(&[("test_a", &["helper"])]);
+        let selected = find_covering_tests(&g, &changed(&["test_a"]), |h| h.starts_with("test_"));
+        assert_eq!(selected, vec![("test_a".to_string(), 0)]);
+    }
+
+    #[test]
+    fn test_cycles_and_external_edges() {
+        let g = graph(&[
+            ("a", &["b", "ext:lodash.map"]),
+            ("b", &["a"]),
+            ("test_b", &["b"]),
+            ("test_ext", &["ext:lodash.map"]),
+        ]);
+        let selected = find_covering_tests(&g, &changed(&["a"]), |h| h.starts_with("test_"));
+        assert_eq!(selected, vec![("test_b".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_filter_for_framework() {
+        let tests = vec![
+            SelectedTest {
+                hash: "1".to_string(),
+                name: "test_round".to_string(),
+                file: "tests/test_price.py".to_string(),
+                depth: 1,
+            },
+            SelectedTest {
+                hash: "2".to_string(),
+                name: "test_checkout".to_string(),
+                file: "tests/test_cart.py".to_string(),
+                depth: 3,
+            },
+        ];
+        assert_eq!(
+            filter_for_framework(TestFramework::Pytest, &tests),
+            (Some("test_checkout or test_round".to_string()), vec![])
+        );
+        assert_eq!(
+            filter_for_framework(TestFramework::Go, &tests).0,
+            Some("^(test_checkout|test_round)$".to_string())
+        );
+        assert_eq!(
+            filter_for_framework(TestFramework::Jest, &tests).1,
+            vec!["tests/test_cart.py", "tests/test_price.py"]
+        );
+    }
+}
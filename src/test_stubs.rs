@@ -0,0 +1,529 @@
+//! Test skeleton generation
+//!
+//! Builds a skeleton test for an indexed symbol in the project's test
+//! framework: one test for the happy path plus one per branch, loop or
+//! error path recorded in the symbol's control flow. The skeletons contain
+//! no assertions; they name the cases and quote the branch they exercise so
+//! the bodies can be filled in.
+
+use std::path::Path;
+
+use crate::cache::CacheDir;
+use crate::error::{McpDiffError, Result};
+use crate::lang::{Lang, LangFamily};
+use crate::overlay::compute_symbol_hash;
+use crate::parsing::parse_and_extract;
+use crate::schema::{ControlFlowKind, SemanticSummary, SymbolInfo};
+use crate::test_runner::{detect_framework, TestFramework};
+
+/// One test to write for a symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    /// Identifier suffix, e.g. `if_1` (empty for the happy path)
+    pub id: String,
+    /// What the test should cover
+    pub description: String,
+    /// Line of the branch the case exercises
+    pub line: Option<usize>,
+    /// Source text of that line
+    pub code: Option<String>,
+}
+
+/// Generated skeleton tests for one symbol
+#[derive(Debug, Clone)]
+pub struct TestStubs {
+    pub symbol: String,
+    pub hash: String,
+    /// Repository-relative file of the symbol
+    pub file: String,
+    pub framework: TestFramework,
+    /// Where the tests conventionally live for this framework
+    pub test_file: String,
+    pub cases: Vec<TestCase>,
+    /// Generated test source
+    pub source: String,
+}
+
+/// Generate skeleton tests for the indexed symbol `symbol_hash`.
+///
+/// Without an explicit `framework`, the one detected at the repository root
+/// is used if it fits the symbol's language, else the language's default.
+pub fn generate_test_stubs(
+    cache: &CacheDir,
+    symbol_hash: &str,
+    framework: Option<TestFramework>,
+) -> Result<TestStubs> {
+    let entry = cache
+        .load_all_symbol_entries()?
+        .into_iter()
+        .find(|e| e.hash == symbol_hash)
+        .ok_or_else(|| McpDiffError::QueryError {
+            message: format!("Symbol not found: {}", symbol_hash),
+        })?;
+
+    let path = cache.repo_root.join(&entry.file);
+    let source = std::fs::read_to_string(&path).map_err(|_| McpDiffError::FileNotFound {
+        path: path.display().to_string(),
+    })?;
+    let lang = Lang::from_path(&path)?;
+    let summary = parse_and_extract(Path::new(&entry.file), &source, lang)?;
+
+    let start_line = entry
+        .lines
+        .split('-')
+        .next()
+        .and_then(|l| l.parse::<usize>().ok());
+    let symbol = summary
+        .symbols
+        .iter()
+        .find(|s| compute_symbol_hash(s, &summary.file) == symbol_hash)
+        .or_else(|| {
+            // Hashes drift when the file changed since indexing
+            summary
+                .symbols
+                .iter()
+                .find(|s| s.name == entry.symbol && Some(s.start_line) == start_line)
+        })
+        .ok_or_else(|| McpDiffError::QueryError {
+            message: format!(
+                "Symbol {} is no longer in {}; re-index and retry",
+                entry.symbol, entry.file
+            ),
+        })?;
+
+    let framework = match framework {
+        Some(framework) => framework,
+        None => {
+            let detected = detect_framework(&cache.repo_root);
+            if fits_language(detected, lang) {
+                detected
+            } else {
+                default_framework(lang).ok_or_else(|| McpDiffError::UnsupportedLanguage {
+                    extension: format!("{} (no test stub template)", lang.name()),
+                })?
+            }
+        }
+    };
+
+    let file = path
+        .strip_prefix(&cache.repo_root)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .to_string();
+    let cases = infer_test_cases(symbol, &summary, &source);
+
+    Ok(TestStubs {
+        symbol: symbol.name.clone(),
+        hash: symbol_hash.to_string(),
+        test_file: test_file_for(&file, framework),
+        source: render_stubs(symbol, &file, &cases, framework),
+        file,
+        framework,
+        cases,
+    })
+}
+
+/// Test cases for a symbol: the happy path, then one per branch, loop or
+/// error path in source order. Await/yield points are not branches and get
+/// no case.
+///
+/// Extractors that only record control flow per file are handled by taking
+/// the file's control flow within the symbol's lines.
+pub fn infer_test_cases(
+    symbol: &SymbolInfo,
+    summary: &SemanticSummary,
+    source: &str,
+) -> Vec<TestCase> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut cases = vec![TestCase {
+        id: String::new(),
+        description: "happy path".to_string(),
+        line: None,
+        code: None,
+    }];
+
+    let control_flow = if symbol.control_flow.is_empty() {
+        &summary.control_flow_changes
+    } else {
+        &symbol.control_flow
+    };
+    let mut flow: Vec<_> = control_flow
+        .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
+        .filter(|cf| (symbol.start_line..=symbol.end_line).contains(&cf.location.line))
+        .collect();
+    flow.sort_by_key(|cf| cf.location.line);
+
+    let mut counts = std::collections::HashMap::new();
+    for cf in flow {
+        let count = counts.entry(cf.kind.as_str()).or_insert(0);
+        *count += 1;
+        let line = cf.location.line;
+        let description = match cf.kind {
+            ControlFlowKind::If => format!("takes the `if` branch at line {}", line),
+            ControlFlowKind::Match | ControlFlowKind::Switch => {
+                format!(
+                    "covers each arm of the `{}` at line {}",
+                    cf.kind.as_str(),
+                    line
+                )
+            }
+            ControlFlowKind::Try => format!("handles an error in the `try` at line {}", line),
+            ControlFlowKind::For | ControlFlowKind::While | ControlFlowKind::Loop => format!(
+                "runs the `{}` loop at line {} zero, one and many times",
+                cf.kind.as_str(),
+                line
+            ),
+            ControlFlowKind::Await | ControlFlowKind::Yield => continue,
+        };
+        cases.push(TestCase {
+            id: format!("{}_{}", cf.kind.as_str(), count),
+            description,
+            line: Some(line),
+            code: line
+                .checked_sub(1)
+                .and_then(|i| lines.get(i))
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty()),
+        });
+    }
+
+    cases
+}
+
+/// Render the skeleton tests for `symbol` (defined in `file`)
+pub fn render_stubs(
+    symbol: &SymbolInfo,
+    file: &str,
+    cases: &[TestCase],
+    framework: TestFramework,
+) -> String {
+    match framework {
+        TestFramework::Cargo => render_rust(symbol, cases),
+        TestFramework::Pytest => render_pytest(symbol, file, cases),
+        TestFramework::Go => render_go(symbol, cases),
+        TestFramework::Jest
+        | TestFramework::Vitest
+        | TestFramework::Npm
+        | TestFramework::Unknown => render_js(symbol, file, cases, framework),
+    }
+}
+
+/// Conventional location of the tests for `file`
+pub fn test_file_for(file: &str, framework: TestFramework) -> String {
+    let path = Path::new(file);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("module");
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let joined = |name: String| dir.join(name).to_string_lossy().to_string();
+
+    match framework {
+        // Unit tests live next to the code in `#[cfg(test)] mod tests`
+        TestFramework::Cargo => file.to_string(),
+        TestFramework::Pytest => Path::new("tests")
+            .join(format!("test_{}.py", stem))
+            .to_string_lossy()
+            .to_string(),
+        TestFramework::Go => joined(format!("{}_test.go", stem)),
+        _ => {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("js");
+            joined(format!("{}.test.{}", stem, ext))
+        }
+    }
+}
+
+fn fits_language(framework: TestFramework, lang: Lang) -> bool {
+    match framework {
+        TestFramework::Cargo => lang.family() == LangFamily::Rust,
+        TestFramework::Pytest => lang.family() == LangFamily::Python,
+        TestFramework::Go => lang.family() == LangFamily::Go,
+        TestFramework::Jest | TestFramework::Vitest | TestFramework::Npm => {
+            lang.family() == LangFamily::JavaScript
+        }
+        TestFramework::Unknown => false,
+    }
+}
+
+fn default_framework(lang: Lang) -> Option<TestFramework> {
+    match lang.family() {
+        LangFamily::Rust => Some(TestFramework::Cargo),
+        LangFamily::Python => Some(TestFramework::Pytest),
+        LangFamily::Go => Some(TestFramework::Go),
+        LangFamily::JavaScript => Some(TestFramework::Jest),
+        _ => None,
+    }
+}
+
+/// Comment lines describing a case, without the comment marker
+fn case_notes(case: &TestCase) -> Vec<String> {
+    let mut notes = vec![case.description.clone()];
+    if let Some(code) = &case.code {
+        notes.push(code.clone());
+    }
+    notes
+}
+
+fn render_rust(symbol: &SymbolInfo, cases: &[TestCase]) -> String {
+    let base = format!("test_{}", to_snake_case(&symbol.name));
+    let mut out = String::from("#[cfg(test)]\nmod tests {\n    use super::*;\n");
+    for case in cases {
+        let name = if case.id.is_empty() {
+            base.clone()
+        } else {
+            format!("{}_{}", base, case.id)
+        };
+        out.push_str(&format!("\n    #[test]\n    fn {}() {{\n", name));
+        for note in case_notes(case) {
+            out.push_str(&format!("        // {}\n", note));
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_pytest(symbol: &SymbolInfo, file: &str, cases: &[TestCase]) -> String {
+    let module = file
+        .trim_end_matches(".py")
+        .trim_start_matches("./")
+        .replace(['/', '\\'], ".");
+    let base = format!("test_{}", to_snake_case(&symbol.name));
+    let mut out = format!("from {} import {}\n", module, symbol.name);
+    for case in cases {
+        let name = if case.id.is_empty() {
+            base.clone()
+        } else {
+            format!("{}_{}", base, case.id)
+        };
+        out.push_str(&format!("\n\ndef {}():\n", name));
+        for note in case_notes(case) {
+            out.push_str(&format!("    # {}\n", note));
+        }
+        out.push_str("    pass\n");
+    }
+    out
+}
+
+fn render_go(symbol: &SymbolInfo, cases: &[TestCase]) -> String {
+    let mut name = symbol.name.clone();
+    if let Some(first) = name.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    let mut out = format!("func Test{}(t *testing.T) {{\n", name);
+    for (i, case) in cases.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let label = case.id.replace('_', " ");
+        let label = if label.is_empty() {
+            "happy path"
+        } else {
+            &label
+        };
+        out.push_str(&format!("\tt.Run(\"{}\", func(t *testing.T) {{\n", label));
+        for note in case_notes(case) {
+            out.push_str(&format!("\t\t// {}\n", note));
+        }
+        out.push_str("\t})\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_js(
+    symbol: &SymbolInfo,
+    file: &str,
+    cases: &[TestCase],
+    framework: TestFramework,
+) -> String {
+    let mut out = String::new();
+    if framework == TestFramework::Vitest {
+        out.push_str("import { describe, it } from 'vitest';\n");
+    }
+    if symbol.is_exported {
+        let stem = Path::new(file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("module");
+        let binding = if symbol.is_default_export {
+            symbol.name.clone()
+        } else {
+            format!("{{ {} }}", symbol.name)
+        };
+        out.push_str(&format!("import {} from './{}';\n", binding, stem));
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    let arrow = if symbol.is_async {
+        "async () =>"
+    } else {
+        "() =>"
+    };
+    out.push_str(&format!("describe('{}', () => {{\n", symbol.name));
+    for (i, case) in cases.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let title = if case.id.is_empty() {
+            format!("should {}", symbol.name)
+        } else {
+            format!("should {} when it {}", symbol.name, case.description)
+        };
+        out.push_str(&format!(
+            "  it('{}', {} {{\n",
+            title.replace('\'', "\\'"),
+            arrow
+        ));
+        let notes = if case.id.is_empty() {
+            case_notes(case)
+        } else {
+            case.code.iter().cloned().collect()
+        };
+        for note in notes {
+            out.push_str(&format!("    // {}\n", note));
+        }
+        out.push_str("  });\n");
+    }
+    out.push_str("});\n");
+    out
+}
+
+/// `chargeCard` / `ChargeCard` -> `charge_card`, `HTTPServer` -> `http_server`
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summarize(source: &str, lang: Lang, path: &str) -> SemanticSummary {
+        parse_and_extract(Path::new(path), source, lang).unwrap()
+    }
+
+    const CHARGE_TS: &str = r#"export function chargeCard(amount: number, card: Card) {
+  if (amount <= 0) {
+    throw new Error("invalid amount");
+  }
+  for (const fee of card.fees) {
+    amount += fee;
+  }
+  return card.charge(amount);
+}
+"#;
+
+    #[test]
+    fn test_infer_cases_from_control_flow() {
+        let summary = summarize(CHARGE_TS, Lang::TypeScript, "src/billing.ts");
+        let symbol = &summary.symbols[0];
+        let cases = infer_test_cases(symbol, &summary, CHARGE_TS);
+        let ids: Vec<&str> = cases.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["", "if_1", "for_1"]);
+        assert_eq!(cases[1].line, Some(2));
+        assert_eq!(cases[1].code.as_deref(), Some("if (amount <= 0) {"));
+    }
+
+    #[test]
+    fn test_render_jest_stub() {
+        let summary = summarize(CHARGE_TS, Lang::TypeScript, "src/billing.ts");
+        let symbol = &summary.symbols[0];
+        let cases = infer_test_cases(symbol, &summary, CHARGE_TS);
+        let stub = render_stubs(symbol, "src/billing.ts", &cases, TestFramework::Jest);
+        assert!(stub.starts_with("import { chargeCard } from './billing';\n"));
+        assert!(stub.contains("  it('should chargeCard', () => {\n"));
+        assert!(stub.contains(
+            "  it('should chargeCard when it takes the `if` branch at line 2', () => {\n    // if (amount <= 0) {\n"
+        ));
+        assert_eq!(
+            test_file_for("src/billing.ts", TestFramework::Jest),
+            "src/billing.test.ts"
+        );
+    }
+
+    #[test]
+    fn test_render_rust_and_pytest_stubs() {
+        let rust = "pub fn parse_port(s: &str) -> u16 {\n    match s.parse() {\n        Ok(p) => p,\n        Err(_) => 80,\n    }\n}\n";
+        let summary = summarize(rust, Lang::Rust, "src/net.rs");
+        let symbol = &summary.symbols[0];
+        let cases = infer_test_cases(symbol, &summary, rust);
+        let stub = render_stubs(symbol, "src/net.rs", &cases, TestFramework::Cargo);
+        assert!(stub.contains("    #[test]\n    fn test_parse_port() {\n"));
+        assert!(stub.contains("    fn test_parse_port_match_1() {\n        // covers each arm of the `match` at line 2\n        // match s.parse() {\n"));
+
+        let python =
+            "def load_config(path):\n    if not path:\n        return {}\n    return read(path)\n";
+        let summary = summarize(python, Lang::Python, "app/config.py");
+        let symbol = &summary.symbols[0];
+        let cases = infer_test_cases(symbol, &summary, python);
+        let stub = render_stubs(symbol, "app/config.py", &cases, TestFramework::Pytest);
+        assert!(stub.starts_with("from app.config import load_config\n"));
+        assert!(stub.contains("\n\ndef test_load_config():\n    # happy path\n    pass\n"));
+        assert!(stub.contains("\n\ndef test_load_config_if_1():\n"));
+        assert_eq!(
+            test_file_for("app/config.py", TestFramework::Pytest),
+            "tests/test_config.py"
+        );
+    }
+
+    #[test]
+    fn test_generate_for_indexed_symbol() {
+        use crate::mcp_server::helpers::generate_index_internal;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/billing.ts"), CHARGE_TS).unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"devDependencies": {"vitest": "^1.0.0"}}"#,
+        )
+        .unwrap();
+        generate_index_internal(dir.path(), 10, &[]).unwrap();
+
+        let cache = CacheDir::for_repo(dir.path()).unwrap();
+        let hash = cache
+            .load_all_symbol_entries()
+            .unwrap()
+            .into_iter()
+            .find(|e| e.symbol == "chargeCard")
+            .unwrap()
+            .hash;
+
+        let stubs = generate_test_stubs(&cache, &hash, None).unwrap();
+        assert_eq!(stubs.framework, TestFramework::Vitest);
+        assert_eq!(stubs.file, "src/billing.ts");
+        assert_eq!(stubs.test_file, "src/billing.test.ts");
+        assert_eq!(stubs.cases.len(), 3);
+        assert!(stubs.source.starts_with(
+            "import { describe, it } from 'vitest';\nimport { chargeCard } from './billing';\n"
+        ));
+
+        // An explicit framework wins over detection
+        let stubs = generate_test_stubs(&cache, &hash, Some(TestFramework::Jest)).unwrap();
+        assert!(stubs.source.starts_with("import { chargeCard }"));
+
+        assert!(generate_test_stubs(&cache, "missing:hash", None).is_err());
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("chargeCard"), "charge_card");
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("parse_port"), "parse_port");
+    }
+}
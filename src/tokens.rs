@@ -8,6 +8,11 @@
 
 use std::collections::HashMap;
 
+use once_cell::sync::Lazy;
+
+use crate::analysis::calculate_cognitive_complexity;
+use crate::schema::{RiskLevel, SemanticSummary, SymbolInfo};
+
 /// Token analysis result comparing different formats
 #[derive(Debug, Clone, Default)]
 pub struct TokenAnalysis {
@@ -487,9 +492,78 @@ pub fn format_analysis_report(analysis: &TokenAnalysis, include_compact: bool) -
     report
 }
 
-/// Format a compact single-line summary
-pub fn format_analysis_compact(analysis: &TokenAnalysis, include_compact: bool) -> String {
-    if include_compact {
+/// Estimate the tokens a report section will cost
+///
+/// Uses the same BPE-style estimate as [`TokenAnalyzer::count_tokens`], so
+/// budgets line up with the token counts reported for TOON output.
+pub fn estimate_section_tokens(section: &str) -> usize {
+    static ANALYZER: Lazy<TokenAnalyzer> = Lazy::new(TokenAnalyzer::new);
+    ANALYZER.count_tokens(section)
+}
+
+/// Format a compact token report for one analyzed file
+///
+/// The report is built from sections in priority order:
+///
+/// 1. the summary header (token counts and file overview), always included
+/// 2. high-risk symbols
+/// 3. all symbols, most complex first
+/// 4. imports
+///
+/// With a `token_budget`, sections are added only while the report still
+/// fits; the first one that doesn't stops the report, and an `omitted:` line
+/// names the dropped sections when there is room for it. Without a budget
+/// every section is included.
+pub fn format_analysis_compact(
+    analysis: &TokenAnalysis,
+    include_compact: bool,
+    summary: &SemanticSummary,
+    token_budget: Option<usize>,
+) -> String {
+    let mut report = compact_header(analysis, include_compact, summary);
+    let mut used = estimate_section_tokens(&report);
+
+    let sections = [
+        ("high_risk", high_risk_section(summary)),
+        ("symbols", symbols_section(summary)),
+        ("imports", imports_section(summary)),
+    ];
+
+    let mut omitted = Vec::new();
+    for (name, section) in sections {
+        let Some(section) = section else {
+            continue;
+        };
+        if !omitted.is_empty() {
+            omitted.push(name);
+            continue;
+        }
+        let cost = estimate_section_tokens(&section);
+        if token_budget.is_some_and(|budget| used + cost > budget) {
+            omitted.push(name);
+            continue;
+        }
+        report.push_str(&section);
+        used += cost;
+    }
+
+    if let (Some(budget), false) = (token_budget, omitted.is_empty()) {
+        let note = format!("omitted: {} (token budget {})\n", omitted.join(","), budget);
+        if used + estimate_section_tokens(&note) <= budget {
+            report.push_str(&note);
+        }
+    }
+
+    report
+}
+
+/// Token counts plus a one-line overview of the file
+fn compact_header(
+    analysis: &TokenAnalysis,
+    include_compact: bool,
+    summary: &SemanticSummary,
+) -> String {
+    let tokens = if include_compact {
         format!(
             "tokens: json_pretty={} json_compact={} toon={} | saved_vs_pretty={} ({:.1}%) saved_vs_compact={} ({:.1}%)",
             analysis.json_tokens,
@@ -508,7 +582,69 @@ pub fn format_analysis_compact(analysis: &TokenAnalysis, include_compact: bool)
             analysis.token_savings,
             analysis.reduction_percent
         )
+    };
+    let high_risk = summary
+        .symbols
+        .iter()
+        .filter(|s| s.behavioral_risk == RiskLevel::High)
+        .count();
+    format!(
+        "{}\nfile: {} ({}) symbols={} high_risk={} imports={}\n",
+        tokens,
+        summary.file,
+        summary.language,
+        summary.symbols.len(),
+        high_risk,
+        summary.added_dependencies.len()
+    )
+}
+
+fn high_risk_section(summary: &SemanticSummary) -> Option<String> {
+    let names: Vec<String> = summary
+        .symbols
+        .iter()
+        .filter(|s| s.behavioral_risk == RiskLevel::High)
+        .map(|s| format!("{}:{}-{}", s.name, s.start_line, s.end_line))
+        .collect();
+    if names.is_empty() {
+        return None;
+    }
+    Some(format!("high_risk[{}]: {}\n", names.len(), names.join(",")))
+}
+
+fn symbols_section(summary: &SemanticSummary) -> Option<String> {
+    if summary.symbols.is_empty() {
+        return None;
     }
+    let mut symbols: Vec<(&SymbolInfo, usize)> = summary
+        .symbols
+        .iter()
+        .map(|s| (s, calculate_cognitive_complexity(&s.control_flow)))
+        .collect();
+    symbols.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.start_line.cmp(&b.0.start_line)));
+
+    let mut section = format!("symbols[{}]{{name,kind,risk,cc}}:\n", symbols.len());
+    for (symbol, cc) in symbols {
+        section.push_str(&format!(
+            "  {},{},{},{}\n",
+            symbol.name,
+            symbol.kind.as_str(),
+            symbol.behavioral_risk.as_str(),
+            cc
+        ));
+    }
+    Some(section)
+}
+
+fn imports_section(summary: &SemanticSummary) -> Option<String> {
+    if summary.added_dependencies.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "imports[{}]: {}\n",
+        summary.added_dependencies.len(),
+        summary.added_dependencies.join(",")
+    ))
 }
 
 #[cfg(test)]
@@ -621,4 +757,80 @@ mod tests {
         assert!(report.contains("vs JSON (pretty):"));
         assert!(report.contains("vs JSON (compact):"));
     }
+
+    fn sample_summary() -> SemanticSummary {
+        let symbol = |name: &str, risk: RiskLevel, branches: usize, line: usize| SymbolInfo {
+            name: name.to_string(),
+            kind: crate::schema::SymbolKind::Function,
+            start_line: line,
+            end_line: line + 9,
+            behavioral_risk: risk,
+            control_flow: vec![crate::schema::ControlFlowChange::default(); branches],
+            ..Default::default()
+        };
+        SemanticSummary {
+            file: "src/checkout.ts".to_string(),
+            language: "typescript".to_string(),
+            symbols: vec![
+                symbol("formatPrice", RiskLevel::Low, 0, 1),
+                symbol("chargeCard", RiskLevel::High, 4, 11),
+                symbol("applyDiscount", RiskLevel::Medium, 2, 21),
+            ],
+            added_dependencies: (0..30).map(|i| format!("package-number-{}", i)).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_compact_without_budget_includes_every_section() {
+        let report =
+            format_analysis_compact(&TokenAnalysis::default(), false, &sample_summary(), None);
+
+        assert!(report.starts_with("tokens: json=0 toon=0"));
+        assert!(
+            report.contains("file: src/checkout.ts (typescript) symbols=3 high_risk=1 imports=30")
+        );
+        assert!(report.contains("high_risk[1]: chargeCard:11-20"));
+        assert!(report.contains("imports[30]: package-number-0,"));
+        assert!(!report.contains("omitted:"));
+
+        // Most complex first
+        let charge = report.find("  chargeCard,function,high,4").unwrap();
+        let discount = report.find("  applyDiscount,function,medium,2").unwrap();
+        let price = report.find("  formatPrice,function,low,0").unwrap();
+        assert!(charge < discount && discount < price, "{}", report);
+    }
+
+    #[test]
+    fn test_format_compact_drops_low_priority_sections_to_fit_budget() {
+        let summary = sample_summary();
+        let analysis = TokenAnalysis::default();
+        let full = format_analysis_compact(&analysis, false, &summary, None);
+        let budget = estimate_section_tokens(&full) - 20;
+
+        let report = format_analysis_compact(&analysis, false, &summary, Some(budget));
+        assert!(estimate_section_tokens(&report) <= budget, "{}", report);
+        assert!(report.contains("high_risk[1]:"));
+        assert!(report.contains("symbols[3]"));
+        assert!(!report.contains("imports[30]"));
+        assert!(report.contains(&format!("omitted: imports (token budget {})", budget)));
+    }
+
+    #[test]
+    fn test_format_compact_keeps_header_under_tiny_budget() {
+        let report =
+            format_analysis_compact(&TokenAnalysis::default(), false, &sample_summary(), Some(1));
+        assert!(report.starts_with("tokens: "));
+        assert!(report.contains("file: src/checkout.ts"));
+        assert!(!report.contains("high_risk[") && !report.contains("symbols["));
+    }
+
+    #[test]
+    fn test_estimate_section_tokens_matches_analyzer() {
+        let section = "symbols[2]{name,kind,risk,cc}:\n  main,function,low,0\n";
+        assert_eq!(
+            estimate_section_tokens(section),
+            TokenAnalyzer::new().count_tokens(section)
+        );
+    }
 }
@@ -6,15 +6,21 @@
 //! - Strings quoted only if necessary
 //! - Field headers emitted once per array
 //! - Stable field ordering enforced
+//!
+//! [`decode_toon`] reads the output of [`encode_toon`] back into a
+//! [`SemanticSummary`]. The encoding is lossy (call locations, import
+//! details, ...), so decoding restores what the TOON carries and
+//! `encode -> decode -> encode` reproduces the same TOON.
 
 use std::collections::{HashMap, HashSet};
 
-use rtoon::encode_default;
+use rtoon::{encode_default, is_literal_like, needs_quoting, Delimiter};
 use serde_json::{json, Map, Value};
 
 use crate::analysis::{calculate_cognitive_complexity, max_nesting_depth};
 use crate::schema::{
-    FrameworkEntryPoint, ModuleGroup, RepoOverview, RepoStats, RiskLevel, SemanticSummary,
+    Argument, Call, ControlFlowChange, ControlFlowKind, FrameworkEntryPoint, ModuleGroup, Prop,
+    RepoOverview, RepoStats, RiskLevel, RouteInfo, ScheduleEntry, SemanticSummary, StateChange,
     SymbolKind,
 };
 use crate::shard::extract_module_name;
@@ -986,17 +992,28 @@ pub fn encode_toon_clean(summary: &SemanticSummary) -> String {
         obj.insert("imports_from".to_string(), json!(summary.local_imports));
     }
 
+    // Files importing this one back (only if non-empty)
+    if !summary.cyclic_imports.is_empty() {
+        obj.insert("cycles".to_string(), json!(summary.cyclic_imports));
+    }
+
     // State changes
     if !summary.state_changes.is_empty() {
+        let any_atomic = summary.state_changes.iter().any(|s| s.is_atomic);
         let state_objs: Vec<Value> = summary
             .state_changes
             .iter()
             .map(|s| {
-                json!({
+                let mut state = json!({
                     "name": s.name,
                     "type": s.state_type,
                     "init": s.initializer
-                })
+                });
+                // Column only when something is atomic, keeping the table uniform
+                if any_atomic {
+                    state["atomic"] = json!(s.is_atomic);
+                }
+                state
             })
             .collect();
         obj.insert("state".to_string(), Value::Array(state_objs));
@@ -1125,15 +1142,21 @@ pub fn encode_toon(summary: &SemanticSummary) -> String {
 
     // State changes - convert to uniform array of objects for tabular format
     if !summary.state_changes.is_empty() {
+        let any_atomic = summary.state_changes.iter().any(|s| s.is_atomic);
         let state_objs: Vec<Value> = summary
             .state_changes
             .iter()
             .map(|s| {
-                json!({
+                let mut state = json!({
                     "name": s.name,
                     "type": s.state_type,
                     "initializer": s.initializer
-                })
+                });
+                // Column only when something is atomic, keeping the table uniform
+                if any_atomic {
+                    state["atomic"] = json!(s.is_atomic);
+                }
+                state
             })
             .collect();
         obj.insert("state_changes".to_string(), Value::Array(state_objs));
@@ -1172,6 +1195,37 @@ pub fn encode_toon(summary: &SemanticSummary) -> String {
         obj.insert("props".to_string(), Value::Array(prop_objs));
     }
 
+    // HTTP routes declared in the file
+    if !summary.routes.is_empty() {
+        let route_objs: Vec<Value> = summary
+            .routes
+            .iter()
+            .map(|r| {
+                json!({
+                    "method": r.method,
+                    "path": r.path,
+                    "handler": r.handler.as_deref().unwrap_or("_")
+                })
+            })
+            .collect();
+        obj.insert("routes".to_string(), Value::Array(route_objs));
+    }
+
+    // Scheduled tasks declared in the file
+    if !summary.schedules.is_empty() {
+        let schedule_objs: Vec<Value> = summary
+            .schedules
+            .iter()
+            .map(|s| {
+                json!({
+                    "handler": s.handler,
+                    "trigger": s.trigger
+                })
+            })
+            .collect();
+        obj.insert("schedules".to_string(), Value::Array(schedule_objs));
+    }
+
     // Control flow changes - just extract the kinds
     if !summary.control_flow_changes.is_empty() {
         let kinds: Vec<&str> = summary
@@ -1244,7 +1298,7 @@ fn build_deduplicated_calls(calls: &[crate::schema::Call]) -> Vec<Value> {
 
     // Convert to sorted vec for deterministic output
     let mut unique_calls: Vec<_> = call_counts.into_iter().collect();
-    unique_calls.sort_by(|a, b| b.1.cmp(&a.1).then(a.0 .0.cmp(&b.0 .0))); // Sort by count desc, then name
+    unique_calls.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0))); // Sort by count desc, then name
 
     unique_calls
         .into_iter()
@@ -1265,6 +1319,651 @@ fn risk_to_string(risk: RiskLevel) -> &'static str {
     risk.as_str()
 }
 
+// ============================================================================
+// Decoding
+// ============================================================================
+
+/// Error decoding TOON, positioned at a 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("TOON parse error at line {line}, column {column}: {message}")]
+pub struct ToonParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ToonParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+/// A decoded field value
+enum ToonField {
+    /// `key: value`
+    Scalar(String),
+    /// `key[N]: a,b,c`
+    List(Vec<String>),
+    /// `key[N]{f1,f2}:` followed by N indented rows, each with its line number
+    Table {
+        fields: Vec<String>,
+        rows: Vec<(usize, Vec<String>)>,
+    },
+}
+
+/// Decoder state between lines
+enum DecodeState {
+    /// Expecting a top-level field
+    Fields,
+    /// Reading the rows of a tabular array
+    Rows {
+        key: String,
+        line: usize,
+        fields: Vec<String>,
+        expected: usize,
+        rows: Vec<(usize, Vec<String>)>,
+    },
+}
+
+/// Line-by-line TOON decoder for semantic summaries
+struct ToonDecoder {
+    summary: SemanticSummary,
+    state: DecodeState,
+    cognitive_complexity: usize,
+    max_nesting_depth: usize,
+}
+
+/// Decode a semantic summary encoded by [`encode_toon`]
+///
+/// Unknown fields, malformed values and tables with missing rows are errors,
+/// reported with the line and column where they occur.
+pub fn decode_toon(input: &str) -> Result<SemanticSummary, ToonParseError> {
+    let mut decoder = ToonDecoder {
+        summary: SemanticSummary::default(),
+        state: DecodeState::Fields,
+        cognitive_complexity: 0,
+        max_nesting_depth: 0,
+    };
+    let mut last_line = 0;
+    for (index, line) in input.lines().enumerate() {
+        last_line = index + 1;
+        decoder.line(last_line, line)?;
+    }
+    decoder.finish(last_line + 1)
+}
+
+impl ToonDecoder {
+    fn line(&mut self, number: usize, text: &str) -> Result<(), ToonParseError> {
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+        match &mut self.state {
+            DecodeState::Rows {
+                key,
+                fields,
+                expected,
+                rows,
+                ..
+            } => {
+                let Some(row) = text.strip_prefix("  ") else {
+                    return Err(ToonParseError::new(
+                        number,
+                        1,
+                        format!("expected {} more rows of `{}`", *expected - rows.len(), key),
+                    ));
+                };
+                let values = split_values(row, fields.len());
+                if values.len() != fields.len() {
+                    return Err(ToonParseError::new(
+                        number,
+                        3,
+                        format!("expected {} values, found {}", fields.len(), values.len()),
+                    ));
+                }
+                rows.push((number, values));
+                if rows.len() == *expected {
+                    self.end_table()?;
+                }
+                Ok(())
+            }
+            DecodeState::Fields => self.field(number, text),
+        }
+    }
+
+    /// Parse a top-level `key...:` line
+    fn field(&mut self, number: usize, text: &str) -> Result<(), ToonParseError> {
+        if text.starts_with(char::is_whitespace) {
+            return Err(ToonParseError::new(number, 1, "unexpected indentation"));
+        }
+        let key_end = text.find([':', '[']).ok_or_else(|| {
+            ToonParseError::new(number, text.chars().count() + 1, "expected `:` after key")
+        })?;
+        let key = &text[..key_end];
+        if key.is_empty() {
+            return Err(ToonParseError::new(number, 1, "missing key"));
+        }
+
+        if text[key_end..].starts_with(':') {
+            let value = text[key_end + 1..]
+                .strip_prefix(' ')
+                .unwrap_or(&text[key_end + 1..]);
+            let column = column_of(text, text.len() - value.len());
+            let mut values = split_values(value, 1);
+            if values.len() != 1 {
+                return Err(ToonParseError::new(
+                    number,
+                    column,
+                    "expected a single value",
+                ));
+            }
+            return self.apply(key, ToonField::Scalar(values.remove(0)), number, 1);
+        }
+
+        // `key[N]...`
+        let rest = &text[key_end + 1..];
+        let close = rest
+            .find(']')
+            .ok_or_else(|| ToonParseError::new(number, column_of(text, key_end), "unclosed `[`"))?;
+        let count: usize = rest[..close].parse().map_err(|_| {
+            ToonParseError::new(
+                number,
+                column_of(text, key_end + 1),
+                format!("invalid array length `{}`", &rest[..close]),
+            )
+        })?;
+        let after = key_end + 1 + close + 1;
+
+        if text[after..].starts_with('{') {
+            let close = text[after..].find('}').ok_or_else(|| {
+                ToonParseError::new(number, column_of(text, after), "unclosed `{`")
+            })?;
+            let fields: Vec<String> = text[after + 1..after + close]
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .collect();
+            let colon = after + close + 1;
+            if text[colon..] != *":" {
+                return Err(ToonParseError::new(
+                    number,
+                    column_of(text, colon),
+                    "expected `:` ending the table header",
+                ));
+            }
+            self.state = DecodeState::Rows {
+                key: key.to_string(),
+                line: number,
+                fields,
+                expected: count,
+                rows: Vec::new(),
+            };
+            if count == 0 {
+                self.end_table()?;
+            }
+            return Ok(());
+        }
+
+        let Some(values) = text[after..].strip_prefix(':') else {
+            return Err(ToonParseError::new(
+                number,
+                column_of(text, after),
+                "expected `:` after array length",
+            ));
+        };
+        let values = values.strip_prefix(' ').unwrap_or(values);
+        let column = column_of(text, text.len() - values.len());
+        let values = if values.is_empty() {
+            Vec::new()
+        } else {
+            split_values(values, count)
+        };
+        if values.len() != count {
+            return Err(ToonParseError::new(
+                number,
+                column,
+                format!("expected {} values, found {}", count, values.len()),
+            ));
+        }
+        self.apply(key, ToonField::List(values), number, 1)
+    }
+
+    /// Apply the table whose rows were just read
+    fn end_table(&mut self) -> Result<(), ToonParseError> {
+        if let DecodeState::Rows {
+            key,
+            line,
+            fields,
+            rows,
+            ..
+        } = std::mem::replace(&mut self.state, DecodeState::Fields)
+        {
+            self.apply(&key, ToonField::Table { fields, rows }, line, 1)?;
+        }
+        Ok(())
+    }
+
+    /// Store a decoded field in the summary
+    fn apply(
+        &mut self,
+        key: &str,
+        field: ToonField,
+        line: usize,
+        column: usize,
+    ) -> Result<(), ToonParseError> {
+        let err = |message: String| ToonParseError::new(line, column, message);
+        let summary = &mut self.summary;
+        match (key, field) {
+            // The schema version is the crate's, not the input's
+            ("schema_version", ToonField::Scalar(_)) => {}
+            ("file", ToonField::Scalar(v)) => summary.file = v,
+            ("language", ToonField::Scalar(v)) => summary.language = v,
+            ("symbol_id", ToonField::Scalar(v)) => {
+                summary.symbol_id.get_or_insert_with(Default::default).hash = v
+            }
+            ("symbol_namespace", ToonField::Scalar(v)) => {
+                summary
+                    .symbol_id
+                    .get_or_insert_with(Default::default)
+                    .namespace = v
+            }
+            ("symbol", ToonField::Scalar(v)) => summary.symbol = Some(v),
+            ("symbol_kind", ToonField::Scalar(v)) => {
+                let kind = SymbolKind::from_str(&v);
+                if kind.as_str() != v {
+                    return Err(err(format!("unknown symbol kind `{}`", v)));
+                }
+                summary.symbol_kind = Some(kind);
+            }
+            ("lines", ToonField::Scalar(v)) => {
+                let range = v
+                    .split_once('-')
+                    .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)));
+                let Some((start, end)) = range else {
+                    return Err(err(format!("invalid line range `{}`", v)));
+                };
+                summary.start_line = Some(start);
+                summary.end_line = Some(end);
+            }
+            ("return_type", ToonField::Scalar(v)) => summary.return_type = Some(v),
+            ("public_surface_changed", ToonField::Scalar(v)) => {
+                summary.public_surface_changed = parse_bool(&v)
+                    .ok_or_else(|| err(format!("expected `true` or `false`, found `{}`", v)))?
+            }
+            ("behavioral_risk", ToonField::Scalar(v)) => {
+                let risk = RiskLevel::from_str(&v);
+                if risk.as_str() != v {
+                    return Err(err(format!("unknown risk level `{}`", v)));
+                }
+                summary.behavioral_risk = risk;
+            }
+            ("cognitive_complexity", ToonField::Scalar(v)) => {
+                self.cognitive_complexity = parse_count(&v).map_err(err)?
+            }
+            ("max_nesting_depth", ToonField::Scalar(v)) => {
+                self.max_nesting_depth = parse_count(&v).map_err(err)?
+            }
+            ("raw_source", ToonField::Scalar(v)) => summary.raw_fallback = Some(v),
+            ("insertions", ToonField::List(values)) => summary.insertions = values,
+            ("added_dependencies", ToonField::List(values)) => summary.added_dependencies = values,
+            ("control_flow", ToonField::List(values)) => {
+                for v in values {
+                    let kind = ControlFlowKind::from_str(&v);
+                    if kind.as_str() != v {
+                        return Err(err(format!("unknown control flow kind `{}`", v)));
+                    }
+                    summary.control_flow_changes.push(ControlFlowChange {
+                        kind,
+                        ..Default::default()
+                    });
+                }
+            }
+            ("state_changes", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["name", "type", "initializer"])?;
+                for (row_line, row) in rows {
+                    let is_atomic = match table.get(&row, "atomic") {
+                        Some(v) => parse_bool(v).ok_or_else(|| {
+                            ToonParseError::new(
+                                row_line,
+                                3,
+                                format!("expected `true` or `false`, found `{}`", v),
+                            )
+                        })?,
+                        None => false,
+                    };
+                    summary.state_changes.push(StateChange {
+                        name: table.value(&row, "name"),
+                        state_type: table.value(&row, "type"),
+                        initializer: table.value(&row, "initializer"),
+                        is_atomic,
+                    });
+                }
+            }
+            ("arguments", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["name", "type", "default"])?;
+                for (_, row) in rows {
+                    summary.arguments.push(Argument {
+                        name: table.value(&row, "name"),
+                        arg_type: table.optional(&row, "type"),
+                        default_value: table.optional(&row, "default"),
+                    });
+                }
+            }
+            ("props", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["name", "type", "default", "required"])?;
+                for (row_line, row) in rows {
+                    let required = table.value(&row, "required");
+                    summary.props.push(Prop {
+                        name: table.value(&row, "name"),
+                        prop_type: table.optional(&row, "type"),
+                        default_value: table.optional(&row, "default"),
+                        required: parse_bool(&required).ok_or_else(|| {
+                            ToonParseError::new(
+                                row_line,
+                                3,
+                                format!("expected `true` or `false`, found `{}`", required),
+                            )
+                        })?,
+                    });
+                }
+            }
+            ("routes", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["method", "path", "handler"])?;
+                for (_, row) in rows {
+                    summary.routes.push(RouteInfo {
+                        method: table.value(&row, "method"),
+                        path: table.value(&row, "path"),
+                        handler: table.optional(&row, "handler"),
+                        ..Default::default()
+                    });
+                }
+            }
+            ("schedules", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["handler", "trigger"])?;
+                for (_, row) in rows {
+                    summary.schedules.push(ScheduleEntry {
+                        handler: table.value(&row, "handler"),
+                        trigger: table.value(&row, "trigger"),
+                        line: 0,
+                    });
+                }
+            }
+            ("calls", ToonField::Table { fields, rows }) => {
+                let table = Table::new(&fields, line, &["name", "obj", "await", "try", "count"])?;
+                for (row_line, row) in rows {
+                    let flag = |column: &str| match table.value(&row, column).as_str() {
+                        "Y" => Ok(true),
+                        "_" => Ok(false),
+                        other => Err(ToonParseError::new(
+                            row_line,
+                            3,
+                            format!("expected `Y` or `_` for `{}`, found `{}`", column, other),
+                        )),
+                    };
+                    let call = Call {
+                        name: table.value(&row, "name"),
+                        object: table.optional(&row, "obj"),
+                        is_awaited: flag("await")?,
+                        in_try: flag("try")?,
+                        ..Default::default()
+                    };
+                    let count = match table.optional(&row, "count") {
+                        Some(count) => parse_count(&count)
+                            .map_err(|message| ToonParseError::new(row_line, 3, message))?,
+                        None => 1,
+                    };
+                    summary
+                        .calls
+                        .extend(std::iter::repeat_n(call, count.max(1)));
+                }
+            }
+            (
+                "schema_version"
+                | "file"
+                | "language"
+                | "symbol_id"
+                | "symbol_namespace"
+                | "symbol"
+                | "symbol_kind"
+                | "lines"
+                | "return_type"
+                | "public_surface_changed"
+                | "behavioral_risk"
+                | "cognitive_complexity"
+                | "max_nesting_depth"
+                | "raw_source",
+                _,
+            ) => return Err(err(format!("`{}` must be a single value", key))),
+            ("insertions" | "added_dependencies" | "control_flow", _) => {
+                return Err(err(format!("`{}` must be a list", key)))
+            }
+            ("state_changes" | "arguments" | "props" | "routes" | "schedules" | "calls", _) => {
+                return Err(err(format!("`{}` must be a table", key)))
+            }
+            _ => return Err(err(format!("unknown field `{}`", key))),
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, end_line: usize) -> Result<SemanticSummary, ToonParseError> {
+        if let DecodeState::Rows {
+            key,
+            expected,
+            rows,
+            ..
+        } = &self.state
+        {
+            return Err(ToonParseError::new(
+                end_line,
+                1,
+                format!("expected {} more rows of `{}`", expected - rows.len(), key),
+            ));
+        }
+        restore_nesting(
+            &mut self.summary.control_flow_changes,
+            self.cognitive_complexity,
+            self.max_nesting_depth,
+        );
+        Ok(self.summary)
+    }
+}
+
+/// Column layout of a tabular array
+struct Table<'a> {
+    fields: &'a [String],
+}
+
+impl<'a> Table<'a> {
+    /// Check that the header has the `required` columns
+    fn new(fields: &'a [String], line: usize, required: &[&str]) -> Result<Self, ToonParseError> {
+        match required.iter().find(|r| !fields.iter().any(|f| f == *r)) {
+            Some(missing) => Err(ToonParseError::new(
+                line,
+                1,
+                format!("table is missing the `{}` column", missing),
+            )),
+            None => Ok(Self { fields }),
+        }
+    }
+
+    fn get<'r>(&self, row: &'r [String], column: &str) -> Option<&'r str> {
+        let index = self.fields.iter().position(|f| f == column)?;
+        row.get(index).map(String::as_str)
+    }
+
+    fn value(&self, row: &[String], column: &str) -> String {
+        self.get(row, column).unwrap_or_default().to_string()
+    }
+
+    /// A column value, `None` for the `_` placeholder
+    fn optional(&self, row: &[String], column: &str) -> Option<String> {
+        self.get(row, column)
+            .filter(|v| *v != "_")
+            .map(str::to_string)
+    }
+}
+
+/// Split delimited values, unquoting and unescaping quoted ones
+///
+/// rtoon only quotes values that need it for other reasons (delimiters,
+/// surrounding spaces, ...) and otherwise leaves `"` and `\` as they are, so
+/// a value that doesn't parse as a complete quoted string is taken verbatim.
+/// That leaves some lines ambiguous (`",_,","` is either `"`, `_` and `,` or
+/// `,_,` and `"`): the first split into `expected` values wins, preferring
+/// quoted readings and splits rtoon could have written. Without one the line
+/// is split greedily.
+fn split_values(text: &str, expected: usize) -> Vec<String> {
+    let mut values = Vec::new();
+    if split_exactly(text, expected, true, &mut values, &mut HashSet::new())
+        || split_exactly(text, expected, false, &mut values, &mut HashSet::new())
+    {
+        return values;
+    }
+
+    values.clear();
+    let mut rest = text;
+    loop {
+        let (value, remainder) = value_readings(rest, false).swap_remove(0);
+        values.push(value);
+        match remainder {
+            Some(remainder) => rest = remainder,
+            None => return values,
+        }
+    }
+}
+
+/// Split `text` into exactly `count` values appended to `values`
+///
+/// A quoted value can also be read verbatim up to the same `,`, so `failed`
+/// remembers the (remaining length, count) states that can't be split to keep
+/// mismatched rows from backtracking exponentially.
+fn split_exactly(
+    text: &str,
+    count: usize,
+    strict: bool,
+    values: &mut Vec<String>,
+    failed: &mut HashSet<(usize, usize)>,
+) -> bool {
+    if count == 0 || failed.contains(&(text.len(), count)) {
+        return false;
+    }
+    for (value, remainder) in value_readings(text, strict) {
+        values.push(value);
+        let complete = match remainder {
+            None => count == 1,
+            Some(remainder) => split_exactly(remainder, count - 1, strict, values, failed),
+        };
+        if complete {
+            return true;
+        }
+        values.pop();
+    }
+    failed.insert((text.len(), count));
+    false
+}
+
+/// The ways to read the value at the start of `text`, quoted first, each with
+/// the text after the following `,` (`None` at the end of the line)
+///
+/// Strict readings are those rtoon writes: no space around delimiters, and
+/// no verbatim value that rtoon would have quoted or that is itself a quoted
+/// string.
+fn value_readings(text: &str, strict: bool) -> Vec<(String, Option<&str>)> {
+    let text = if strict {
+        text
+    } else {
+        text.trim_start_matches(' ')
+    };
+    let mut readings: Vec<(String, Option<&str>)> = parse_quoted(text).into_iter().collect();
+    let (value, remainder) = match text.split_once(',') {
+        Some((value, remainder)) => (value, Some(remainder)),
+        None => (text, None),
+    };
+    if !strict {
+        readings.push((value.trim_end().to_string(), remainder));
+    } else if (is_literal_like(value) || !needs_quoting(value, Delimiter::Comma))
+        && !matches!(parse_quoted(value), Some((_, None)))
+    {
+        readings.push((value.to_string(), remainder));
+    }
+    readings
+}
+
+/// Parse a quoted value at the start of `text`, returning it and the text
+/// after the following `,` (`None` at the end of the line)
+fn parse_quoted(text: &str) -> Option<(String, Option<&str>)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+    let end = loop {
+        match chars.next()? {
+            (i, '"') => break i + 2,
+            (_, '\\') => value.push(match chars.next()?.1 {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '"' => '"',
+                '\\' => '\\',
+                _ => return None,
+            }),
+            (_, c) => value.push(c),
+        }
+    };
+    let after = text[end..].trim_start_matches(' ');
+    if after.is_empty() {
+        Some((value, None))
+    } else {
+        after
+            .strip_prefix(',')
+            .map(|remainder| (value, Some(remainder)))
+    }
+}
+
+/// 1-based column of byte offset `offset` in `text`
+fn column_of(text: &str, offset: usize) -> usize {
+    text[..offset].chars().count() + 1
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_count(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, found `{}`", value))
+}
+
+/// Give decoded control flow nesting depths that reproduce the encoded
+/// cognitive complexity and maximum nesting depth
+///
+/// TOON only carries the control flow kinds and these two totals, so the
+/// deepest construct comes first and the remaining depth is spread over the
+/// rest.
+fn restore_nesting(
+    control_flow: &mut [ControlFlowChange],
+    cognitive_complexity: usize,
+    max_nesting_depth: usize,
+) {
+    let structural = control_flow
+        .iter()
+        .filter(|cf| !cf.kind.is_suspension_point())
+        .count();
+    let mut remaining = cognitive_complexity.saturating_sub(structural);
+    for cf in control_flow
+        .iter_mut()
+        .filter(|cf| !cf.kind.is_suspension_point())
+    {
+        cf.nesting_depth = remaining.min(max_nesting_depth);
+        remaining -= cf.nesting_depth;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1329,6 +2028,7 @@ mod tests {
                 name: "open".to_string(),
                 state_type: "boolean".to_string(),
                 initializer: "false".to_string(),
+                is_atomic: false,
             }],
             ..Default::default()
         };
@@ -1341,6 +2041,30 @@ mod tests {
         assert!(toon.contains("false"));
     }
 
+    #[test]
+    fn test_state_changes_atomic_column() {
+        let state = |name: &str, is_atomic: bool| StateChange {
+            name: name.to_string(),
+            state_type: "_".to_string(),
+            initializer: "x".to_string(),
+            is_atomic,
+        };
+        let mut summary = SemanticSummary {
+            file: "counter.rs".to_string(),
+            language: "rust".to_string(),
+            state_changes: vec![state("hits", true), state("total", false)],
+            ..Default::default()
+        };
+
+        let toon = encode_toon(&summary);
+        assert!(toon.contains("atomic"), "{toon}");
+        assert!(toon.contains("{name,type,initializer,atomic}"), "{toon}");
+        assert!(toon.contains("hits,_,x,true"), "{toon}");
+
+        summary.state_changes = vec![state("total", false)];
+        assert!(!encode_toon(&summary).contains("atomic"));
+    }
+
     #[test]
     fn test_dependencies_inline() {
         let summary = SemanticSummary {
@@ -1798,4 +2522,239 @@ mod tests {
         );
         assert!(fw.contains("+"), "Should combine frameworks: {}", fw);
     }
+
+    /// Strings TOON must quote or escape. A bare `_` is the encoder's
+    /// placeholder for a missing value, and rtoon writes a value such as
+    /// `"a"` unquoted, so neither can round-trip.
+    fn arb_text() -> impl proptest::strategy::Strategy<Value = String> {
+        use proptest::strategy::Strategy;
+
+        proptest::string::string_regex("[a-zA-Z0-9_ ,:\"\\\\#{}\\[\\]\\-]{0,10}|true|false|42|-1.5")
+            .unwrap()
+            .prop_filter("placeholder", |s| s != "_")
+            .prop_filter("ambiguous quotes", |s| {
+                !(s.starts_with('"') && s[1..].contains('"'))
+            })
+    }
+
+    fn arb_summary() -> impl proptest::strategy::Strategy<Value = SemanticSummary> {
+        use proptest::collection::vec;
+        use proptest::option::of;
+        use proptest::prelude::*;
+        use proptest::sample::select;
+
+        let kinds = select(vec![
+            ControlFlowKind::If,
+            ControlFlowKind::For,
+            ControlFlowKind::While,
+            ControlFlowKind::Match,
+            ControlFlowKind::Try,
+            ControlFlowKind::Await,
+        ]);
+        let header = (
+            arb_text(),
+            arb_text(),
+            of((arb_text(), arb_text())),
+            of(arb_text()),
+            of(select(vec![
+                SymbolKind::Function,
+                SymbolKind::Class,
+                SymbolKind::Component,
+            ])),
+            of((1usize..500, 1usize..500)),
+            of(arb_text()),
+            any::<bool>(),
+            select(vec![RiskLevel::Low, RiskLevel::Medium, RiskLevel::High]),
+        );
+        let body = (
+            vec(arb_text(), 0..3),
+            vec(arb_text(), 0..3),
+            vec((arb_text(), arb_text(), arb_text(), any::<bool>()), 0..3),
+            vec((arb_text(), of(arb_text()), of(arb_text())), 0..3),
+            vec((arb_text(), of(arb_text()), any::<bool>()), 0..3),
+            vec((arb_text(), arb_text(), of(arb_text())), 0..3),
+            vec((arb_text(), arb_text()), 0..3),
+            vec((kinds, 0usize..4), 0..5),
+            vec(
+                (arb_text(), of(arb_text()), any::<bool>(), any::<bool>()),
+                0..6,
+            ),
+        );
+        (header, body).prop_map(|(header, body)| {
+            let (file, language, id, symbol, symbol_kind, lines, return_type, public, risk) =
+                header;
+            let (insertions, deps, states, args, props, routes, schedules, flow, calls) = body;
+            SemanticSummary {
+                file,
+                language,
+                symbol_id: id.map(|(hash, namespace)| crate::schema::SymbolId {
+                    hash,
+                    namespace,
+                    ..Default::default()
+                }),
+                symbol,
+                symbol_kind,
+                start_line: lines.map(|l| l.0),
+                end_line: lines.map(|l| l.1),
+                return_type,
+                public_surface_changed: public,
+                behavioral_risk: risk,
+                insertions,
+                added_dependencies: deps,
+                state_changes: states
+                    .into_iter()
+                    .map(|(name, state_type, initializer, is_atomic)| StateChange {
+                        name,
+                        state_type,
+                        initializer,
+                        is_atomic,
+                    })
+                    .collect(),
+                arguments: args
+                    .into_iter()
+                    .map(|(name, arg_type, default_value)| Argument {
+                        name,
+                        arg_type,
+                        default_value,
+                    })
+                    .collect(),
+                props: props
+                    .into_iter()
+                    .map(|(name, prop_type, required)| Prop {
+                        name,
+                        prop_type,
+                        default_value: None,
+                        required,
+                    })
+                    .collect(),
+                routes: routes
+                    .into_iter()
+                    .map(|(method, path, handler)| RouteInfo {
+                        method,
+                        path,
+                        handler,
+                        ..Default::default()
+                    })
+                    .collect(),
+                schedules: schedules
+                    .into_iter()
+                    .map(|(handler, trigger)| ScheduleEntry {
+                        handler,
+                        trigger,
+                        line: 0,
+                    })
+                    .collect(),
+                control_flow_changes: flow
+                    .into_iter()
+                    .map(|(kind, nesting_depth)| ControlFlowChange {
+                        kind,
+                        nesting_depth,
+                        ..Default::default()
+                    })
+                    .collect(),
+                calls: calls
+                    .into_iter()
+                    .map(|(name, object, is_awaited, in_try)| Call {
+                        name,
+                        object,
+                        is_awaited,
+                        in_try,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        })
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_decode_round_trips_encoded_toon(summary in arb_summary()) {
+            let toon = encode_toon(&summary);
+            let decoded = decode_toon(&toon)
+                .map_err(|e| proptest::test_runner::TestCaseError::fail(format!("{}\n{}", e, toon)))?;
+            proptest::prop_assert_eq!(encode_toon(&decoded), toon);
+        }
+    }
+
+    #[test]
+    fn test_decode_toon_fields() {
+        let toon = "schema_version: \"1.0\"\n\
+                    file: src/api.ts\n\
+                    language: ts\n\
+                    symbol: fetchUser\n\
+                    symbol_kind: function\n\
+                    lines: 3-12\n\
+                    public_surface_changed: true\n\
+                    behavioral_risk: medium\n\
+                    cognitive_complexity: 3\n\
+                    max_nesting_depth: 1\n\
+                    arguments[1]{name,type,default}:\n  id,string,_\n\
+                    control_flow[3]: if,await,for\n\
+                    calls[2]{name,obj,await,try,count}:\n  get,http,Y,_,\"2\"\n  \"a, b\",_,_,Y,_\n";
+
+        let summary = decode_toon(toon).unwrap();
+
+        assert_eq!(summary.file, "src/api.ts");
+        assert_eq!(summary.symbol.as_deref(), Some("fetchUser"));
+        assert_eq!(summary.symbol_kind, Some(SymbolKind::Function));
+        assert_eq!((summary.start_line, summary.end_line), (Some(3), Some(12)));
+        assert!(summary.public_surface_changed);
+        assert_eq!(summary.behavioral_risk, RiskLevel::Medium);
+        assert_eq!(summary.arguments[0].arg_type.as_deref(), Some("string"));
+        assert_eq!(summary.arguments[0].default_value, None);
+        assert_eq!(
+            calculate_cognitive_complexity(&summary.control_flow_changes),
+            3
+        );
+        assert_eq!(max_nesting_depth(&summary.control_flow_changes), 1);
+        assert_eq!(summary.calls.len(), 3);
+        assert_eq!(summary.calls[0].object.as_deref(), Some("http"));
+        assert!(summary.calls[0].is_awaited);
+        assert_eq!(summary.calls[2].name, "a, b");
+        assert!(summary.calls[2].in_try);
+    }
+
+    #[test]
+    fn test_decode_toon_error_positions() {
+        let err = |input: &str| {
+            let e = decode_toon(input).unwrap_err();
+            (e.line, e.column)
+        };
+
+        assert_eq!(err("file: a.rs\nbogus: 1\n"), (2, 1));
+        assert_eq!(err("file: a.rs\nsymbol[x]: a\n"), (2, 8));
+        assert_eq!(err("insertions[2]: a\n"), (1, 16));
+        assert_eq!(
+            err("file: a.rs\ncalls[2]{name,obj,await,try,count}:\n  f,_,_,_,_\n"),
+            (4, 1)
+        );
+        assert_eq!(
+            err("calls[1]{name,obj,await,try,count}:\n  f,_,maybe,_,_\n"),
+            (2, 3)
+        );
+        assert_eq!(err("lines: 3-x\n"), (1, 1));
+
+        let message = decode_toon("behavioral_risk: extreme")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            message,
+            "TOON parse error at line 1, column 1: unknown risk level `extreme`"
+        );
+    }
+
+    #[test]
+    fn test_decode_toon_mismatched_quoted_values() {
+        // Every `"a"` reads both quoted and verbatim, which used to backtrack
+        // through 2^40 splits before reporting the mismatch
+        let values = vec!["\"a\""; 40].join(",");
+        let e = decode_toon(&format!("insertions[41]: {}\n", values)).unwrap_err();
+        assert_eq!((e.line, e.column), (1, 17));
+
+        let fields = (0..41).map(|i| format!("f{}", i)).collect::<Vec<_>>();
+        let input = format!("calls[1]{{{}}}:\n  {}\n", fields.join(","), values);
+        let e = decode_toon(&input).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 3));
+    }
 }
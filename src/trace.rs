@@ -495,6 +495,71 @@ fn symbol_from_json(sym: &serde_json::Value, module_name: &str) -> Option<Symbol
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string(),
+        feature_flags: sym
+            .get("feature_flags")
+            .or_else(|| sym.get("ff"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        is_deprecated: sym
+            .get("is_deprecated")
+            .or_else(|| sym.get("dep"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        deprecation_message: sym
+            .get("deprecation_message")
+            .or_else(|| sym.get("depm"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        awaits_count: sym
+            .get("awaits_count")
+            .or_else(|| sym.get("aw"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        io_calls_outside_try: sym
+            .get("io_calls_outside_try")
+            .or_else(|| sym.get("iot"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        has_empty_catch: sym
+            .get("has_empty_catch")
+            .or_else(|| sym.get("ec"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        rethrows: sym
+            .get("rethrows")
+            .or_else(|| sym.get("rth"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        panic_points: sym
+            .get("panic_points")
+            .or_else(|| sym.get("pp"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize,
+        sql_tables: sym
+            .get("sql_tables")
+            .or_else(|| sym.get("sqt"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        partial_of: sym
+            .get("partial_of")
+            .or_else(|| sym.get("pof"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        parent: sym
+            .get("parent")
+            .or_else(|| sym.get("par"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        is_generated: sym
+            .get("is_generated")
+            .or_else(|| sym.get("gen"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
     })
 }
 
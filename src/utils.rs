@@ -61,6 +61,134 @@ pub fn truncate_with_ellipsis(s: &str, max_bytes: usize) -> String {
     }
 }
 
+/// Coarse script of a character, for splitting identifiers that mix scripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptClass {
+    /// Han, kana and Hangul, which are written without word separators
+    Cjk,
+    /// Emoji pictographs and symbols
+    Emoji,
+    /// Everything else (Latin, Cyrillic, digits, ...)
+    Other,
+}
+
+/// Whether `c` can be part of an identifier-like word.
+///
+/// Alphanumerics plus the characters `char::is_alphanumeric` rejects but
+/// which never separate words: combining marks (`e` + U+0301), emoji, and
+/// the joiners and variation selectors that build emoji sequences.
+pub fn is_word_char(c: char) -> bool {
+    if c.is_ascii() {
+        return c.is_ascii_alphanumeric();
+    }
+    c.is_alphanumeric() || is_combining_mark(c) || is_emoji(c) || is_joiner(c)
+}
+
+/// Script class of `c`, or `None` for combining marks and joiners, which
+/// belong to whatever run they follow
+pub fn script_class(c: char) -> Option<ScriptClass> {
+    if is_combining_mark(c) || is_joiner(c) {
+        None
+    } else if is_cjk(c) {
+        Some(ScriptClass::Cjk)
+    } else if is_emoji(c) {
+        Some(ScriptClass::Emoji)
+    } else {
+        Some(ScriptClass::Other)
+    }
+}
+
+/// Split a word into runs of a single script class.
+///
+/// ```
+/// use semfora_engine::utils::{split_scripts, ScriptClass};
+///
+/// assert_eq!(
+///     split_scripts("get用户🎉"),
+///     vec![
+///         (ScriptClass::Other, "get"),
+///         (ScriptClass::Cjk, "用户"),
+///         (ScriptClass::Emoji, "🎉"),
+///     ]
+/// );
+/// ```
+pub fn split_scripts(word: &str) -> Vec<(ScriptClass, &str)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut class = None;
+
+    for (i, c) in word.char_indices() {
+        let Some(c_class) = script_class(c) else {
+            continue;
+        };
+        match class {
+            Some(current) if current != c_class => {
+                runs.push((current, &word[start..i]));
+                start = i;
+                class = Some(c_class);
+            }
+            None => class = Some(c_class),
+            _ => {}
+        }
+    }
+    if start < word.len() {
+        runs.push((class.unwrap_or(ScriptClass::Other), &word[start..]));
+    }
+    runs
+}
+
+/// Han ideographs, kana and Hangul
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{1100}'..='\u{11FF}' // Hangul Jamo
+        | '\u{3130}'..='\u{318F}' // Hangul Compatibility Jamo
+        | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B-F, Compatibility Supplement
+    ) && c.is_alphanumeric()
+}
+
+/// Emoji pictographs and the symbol blocks emoji are drawn from
+pub fn is_emoji(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}' // Pictographs, emoticons, transport, flags, ...
+        | '\u{2600}'..='\u{27BF}' // Miscellaneous Symbols, Dingbats
+        | '\u{2B00}'..='\u{2BFF}' // Arrows and stars (⭐)
+    )
+}
+
+/// Combining marks in the common blocks; these attach to the preceding
+/// character and never start a word
+fn is_combining_mark(c: char) -> bool {
+    match c {
+        '\u{0300}'..='\u{036F}' // Combining Diacritical Marks
+        | '\u{0483}'..='\u{0489}' // Cyrillic
+        | '\u{0591}'..='\u{05BD}' // Hebrew points
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}' // Arabic
+        | '\u{0E31}'
+        | '\u{0E34}'..='\u{0E3A}'
+        | '\u{0E47}'..='\u{0E4E}' // Thai
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}' // Extended and supplementary marks, for symbols
+        | '\u{3099}'..='\u{309A}' // Kana voicing marks
+        | '\u{FE20}'..='\u{FE2F}' => true,
+        // Indic vowel signs and viramas (the dandas are punctuation)
+        '\u{0900}'..='\u{0DFF}' => !c.is_alphanumeric() && !matches!(c, '\u{0964}' | '\u{0965}'),
+        _ => false,
+    }
+}
+
+/// Zero-width joiners and variation selectors used inside emoji sequences
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\u{200C}' | '\u{200D}' | '\u{FE00}'..='\u{FE0F}')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +282,58 @@ mod tests {
         let formatted = truncate_with_ellipsis(s, 57);
         assert!(formatted.ends_with("..."));
     }
+
+    #[test]
+    fn test_truncate_emoji_cjk_and_combining_at_display_limits() {
+        // The 57-byte commit subject trim and the 200-byte failure message trim
+        let emoji = "🎉".repeat(20); // 4 bytes each; byte 57 is inside the 15th
+        assert_eq!(truncate_to_char_boundary(&emoji, 57), "🎉".repeat(14));
+
+        let cjk = "获取用户".repeat(20); // 3 bytes each; byte 200 is inside a char
+        let trimmed = truncate_with_ellipsis(&cjk, 200);
+        assert_eq!(trimmed, format!("{}...", &cjk[..198]));
+
+        // 'e' + U+0301 (2 bytes): a cut between them drops only the mark
+        let combining = format!("{}cafe\u{301}", "x".repeat(54));
+        assert_eq!(
+            truncate_to_char_boundary(&combining, 59),
+            format!("{}cafe", "x".repeat(54))
+        );
+    }
+
+    #[test]
+    fn test_is_word_char() {
+        assert!(is_word_char('a'));
+        assert!(is_word_char('用'));
+        assert!(is_word_char('🎉'));
+        assert!(is_word_char('\u{301}'));
+        assert!(is_word_char('\u{200D}'));
+        assert!(is_word_char('\u{94D}')); // Devanagari virama
+        assert!(!is_word_char('_'));
+        assert!(!is_word_char(' '));
+        assert!(!is_word_char('、'));
+        assert!(!is_word_char('\u{964}')); // Devanagari danda
+    }
+
+    #[test]
+    fn test_split_scripts() {
+        assert_eq!(
+            split_scripts("handle用户Login"),
+            vec![
+                (ScriptClass::Other, "handle"),
+                (ScriptClass::Cjk, "用户"),
+                (ScriptClass::Other, "Login"),
+            ]
+        );
+        // Combining marks and emoji joiners stay inside their run
+        assert_eq!(
+            split_scripts("cafe\u{301}👨\u{200D}💻"),
+            vec![
+                (ScriptClass::Other, "cafe\u{301}"),
+                (ScriptClass::Emoji, "👨\u{200D}💻"),
+            ]
+        );
+        assert_eq!(split_scripts("हिन्दी"), vec![(ScriptClass::Other, "हिन्दी")]);
+        assert!(split_scripts("").is_empty());
+    }
 }
@@ -0,0 +1,529 @@
+//! Monorepo workspace detection
+//!
+//! A repository is a workspace when its root declares member packages in
+//! `pnpm-workspace.yaml`, the `workspaces` field of `package.json`, the
+//! `[workspace] members` of `Cargo.toml` or the `use` directives of `go.work`.
+//! Indexing namespaces the modules of each package by the package name
+//! (`web/components`), the repo overview lists per-package stats, and
+//! `--package` scopes queries to one package. Repositories without any of
+//! these manifests are indexed exactly as before.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::duplicate::boilerplate::matches_glob;
+use crate::error::{McpDiffError, Result};
+
+/// Directories never searched for workspace members
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+/// Deepest directory searched for workspace members
+const MAX_MEMBER_DEPTH: usize = 6;
+
+/// Manifest that declares a package as a workspace member
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceKind {
+    /// `pnpm-workspace.yaml`
+    Pnpm,
+    /// `workspaces` in `package.json` (npm, yarn, bun)
+    Npm,
+    /// `[workspace] members` in `Cargo.toml`
+    Cargo,
+    /// `use` directives in `go.work`
+    Go,
+}
+
+impl WorkspaceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkspaceKind::Pnpm => "pnpm",
+            WorkspaceKind::Npm => "npm",
+            WorkspaceKind::Cargo => "cargo",
+            WorkspaceKind::Go => "go",
+        }
+    }
+
+    /// Manifest every member package directory contains
+    fn member_manifest(&self) -> &'static str {
+        match self {
+            WorkspaceKind::Pnpm | WorkspaceKind::Npm => "package.json",
+            WorkspaceKind::Cargo => "Cargo.toml",
+            WorkspaceKind::Go => "go.mod",
+        }
+    }
+}
+
+/// A member package of a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    /// Module namespace of the package: its declared name without an npm
+    /// scope, or its directory when that name is taken
+    pub name: String,
+    /// Name declared in the package manifest (`@acme/web`, `api-server`)
+    pub declared_name: String,
+    /// Package directory relative to the repo root, `/`-separated
+    pub path: String,
+    /// Manifest that declared the package
+    pub kind: WorkspaceKind,
+}
+
+impl WorkspacePackage {
+    /// Whether a repo-relative file path lies inside this package
+    pub fn contains(&self, relative: &str) -> bool {
+        relative
+            .strip_prefix(self.path.as_str())
+            .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Whether `name` refers to this package (namespace, declared name or path)
+    pub fn is_named(&self, name: &str) -> bool {
+        let name = name.trim_end_matches('/');
+        self.name == name || self.declared_name == name || self.path == name
+    }
+}
+
+/// The member packages of a monorepo
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workspace {
+    /// Packages sorted by path
+    pub packages: Vec<WorkspacePackage>,
+}
+
+impl Workspace {
+    /// Detect the workspace rooted at `repo_root`, if its manifests declare
+    /// any member packages
+    pub fn detect(repo_root: &Path) -> Option<Self> {
+        let declared: Vec<(WorkspaceKind, MemberPatterns)> = [
+            (WorkspaceKind::Pnpm, pnpm_patterns(repo_root)),
+            (WorkspaceKind::Npm, npm_patterns(repo_root)),
+            (WorkspaceKind::Cargo, cargo_patterns(repo_root)),
+            (WorkspaceKind::Go, go_patterns(repo_root)),
+        ]
+        .into_iter()
+        .filter_map(|(kind, patterns)| Some((kind, patterns?)))
+        .filter(|(_, patterns)| !patterns.include.is_empty())
+        .collect();
+        if declared.is_empty() {
+            return None;
+        }
+
+        let mut dirs = Vec::new();
+        collect_dirs(repo_root, "", 0, &mut dirs);
+
+        let mut seen_paths = HashSet::new();
+        let mut packages = Vec::new();
+        for (kind, patterns) in &declared {
+            for dir in &dirs {
+                if !patterns.matches(dir)
+                    || !repo_root.join(dir).join(kind.member_manifest()).is_file()
+                    || !seen_paths.insert(dir.clone())
+                {
+                    continue;
+                }
+                let declared_name = declared_name(&repo_root.join(dir), *kind)
+                    .unwrap_or_else(|| dir_name(dir).to_string());
+                packages.push(WorkspacePackage {
+                    name: String::new(),
+                    declared_name,
+                    path: dir.clone(),
+                    kind: *kind,
+                });
+            }
+        }
+        if packages.is_empty() {
+            return None;
+        }
+
+        packages.sort_by(|a, b| a.path.cmp(&b.path));
+        assign_namespaces(&mut packages);
+        Some(Self { packages })
+    }
+
+    /// The package containing a repo-relative file path (innermost first)
+    pub fn package_of(&self, relative: &str) -> Option<&WorkspacePackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.contains(relative))
+            .max_by_key(|p| p.path.len())
+    }
+
+    /// The package `name` refers to (see [`WorkspacePackage::is_named`])
+    pub fn find(&self, name: &str) -> Option<&WorkspacePackage> {
+        self.packages.iter().find(|p| p.is_named(name))
+    }
+}
+
+/// Resolve a `--package` argument for the repository at `repo_root`
+pub fn resolve_package(repo_root: &Path, name: &str) -> Result<WorkspacePackage> {
+    let Some(workspace) = Workspace::detect(repo_root) else {
+        return Err(McpDiffError::QueryError {
+            message: format!(
+                "--package {}: no workspace found (pnpm-workspace.yaml, package.json workspaces, Cargo workspace or go.work)",
+                name
+            ),
+        });
+    };
+    workspace
+        .find(name)
+        .cloned()
+        .ok_or_else(|| McpDiffError::QueryError {
+            message: format!(
+                "Unknown package '{}' (packages: {})",
+                name,
+                workspace
+                    .packages
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+}
+
+/// A file path relative to `repo_root`, `/`-separated
+pub fn repo_relative(repo_root: &Path, file: &str) -> String {
+    let root = repo_root.to_string_lossy();
+    let relative = file
+        .strip_prefix(root.trim_end_matches('/'))
+        .map(|rest| rest.trim_start_matches('/'))
+        .unwrap_or(file);
+    relative
+        .trim_start_matches("./")
+        .replace(std::path::MAIN_SEPARATOR, "/")
+}
+
+/// Member globs declared by one manifest
+#[derive(Debug, Default)]
+struct MemberPatterns {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl MemberPatterns {
+    fn push(&mut self, pattern: &str) {
+        let pattern = pattern.trim().trim_matches(|c| c == '"' || c == '\'');
+        match pattern.strip_prefix('!') {
+            Some(excluded) => self.exclude.push(normalize_pattern(excluded)),
+            None if !pattern.is_empty() => self.include.push(normalize_pattern(pattern)),
+            None => {}
+        }
+    }
+
+    fn matches(&self, dir: &str) -> bool {
+        self.include.iter().any(|p| matches_glob(p, dir))
+            && !self.exclude.iter().any(|p| matches_glob(p, dir))
+    }
+}
+
+fn normalize_pattern(pattern: &str) -> String {
+    pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// `packages:` list of `pnpm-workspace.yaml`
+fn pnpm_patterns(repo_root: &Path) -> Option<MemberPatterns> {
+    let content = fs::read_to_string(repo_root.join("pnpm-workspace.yaml")).ok()?;
+    let mut patterns = MemberPatterns::default();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                patterns.push(item.split(" #").next().unwrap_or(item));
+            }
+        }
+    }
+    Some(patterns)
+}
+
+/// `workspaces` of `package.json`: an array, or `{ "packages": [...] }`
+fn npm_patterns(repo_root: &Path) -> Option<MemberPatterns> {
+    let content = fs::read_to_string(repo_root.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let workspaces = json.get("workspaces")?;
+    let list = workspaces
+        .as_array()
+        .or_else(|| workspaces.get("packages")?.as_array())?;
+    let mut patterns = MemberPatterns::default();
+    for pattern in list.iter().filter_map(|p| p.as_str()) {
+        patterns.push(pattern);
+    }
+    Some(patterns)
+}
+
+/// `[workspace] members` and `exclude` of `Cargo.toml`
+fn cargo_patterns(repo_root: &Path) -> Option<MemberPatterns> {
+    let content = fs::read_to_string(repo_root.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    let workspace = manifest.get("workspace")?;
+    let strings = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(normalize_pattern))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Some(MemberPatterns {
+        include: strings("members"),
+        exclude: strings("exclude"),
+    })
+}
+
+/// `use` directives of `go.work`, single-line or in a `use ( ... )` block
+fn go_patterns(repo_root: &Path) -> Option<MemberPatterns> {
+    let content = fs::read_to_string(repo_root.join("go.work")).ok()?;
+    let mut patterns = MemberPatterns::default();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                patterns.push(line);
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                patterns.push(rest);
+            }
+        }
+    }
+    // The root module itself is not a member package
+    patterns.include.retain(|p| !p.is_empty() && p != ".");
+    Some(patterns)
+}
+
+/// Repo-relative directories below `relative`, skipping hidden, vendored and
+/// build output directories
+fn collect_dirs(repo_root: &Path, relative: &str, depth: usize, dirs: &mut Vec<String>) {
+    if depth >= MAX_MEMBER_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(repo_root.join(relative)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        let path = if relative.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        collect_dirs(repo_root, &path, depth + 1, dirs);
+        dirs.push(path);
+    }
+}
+
+/// Package name declared in a member's manifest
+fn declared_name(dir: &Path, kind: WorkspaceKind) -> Option<String> {
+    let content = fs::read_to_string(dir.join(kind.member_manifest())).ok()?;
+    let name = match kind {
+        WorkspaceKind::Pnpm | WorkspaceKind::Npm => {
+            let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+            json.get("name")?.as_str()?.to_string()
+        }
+        WorkspaceKind::Cargo => {
+            let manifest: toml::Value = toml::from_str(&content).ok()?;
+            manifest.get("package")?.get("name")?.as_str()?.to_string()
+        }
+        WorkspaceKind::Go => {
+            let module = content
+                .lines()
+                .find_map(|l| l.trim().strip_prefix("module "))?;
+            module.trim().trim_matches('"').to_string()
+        }
+    };
+    (!name.is_empty()).then_some(name)
+}
+
+/// Give each package a unique namespace: its declared name without an npm
+/// scope or Go module path, falling back to its directory on a clash
+fn assign_namespaces(packages: &mut [WorkspacePackage]) {
+    let short = |p: &WorkspacePackage| match p.kind {
+        WorkspaceKind::Go => dir_name(&p.declared_name).to_string(),
+        _ => p
+            .declared_name
+            .rsplit_once('/')
+            .map_or(p.declared_name.clone(), |(_, name)| name.to_string()),
+    };
+    let shorts: Vec<String> = packages.iter().map(short).collect();
+    for (i, package) in packages.iter_mut().enumerate() {
+        let taken = shorts.iter().filter(|s| **s == shorts[i]).count() > 1;
+        package.name = if taken {
+            package.path.replace('/', ".")
+        } else {
+            shorts[i].clone()
+        };
+    }
+}
+
+/// Last component of a `/`-separated path
+fn dir_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn names(workspace: &Workspace) -> Vec<(&str, &str)> {
+        workspace
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.path.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_pnpm_and_cargo_workspace() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  - \"packages/**\"\n  - '!**/fixtures/**'\n",
+        );
+        write(root, "apps/web/package.json", r#"{"name": "@acme/web"}"#);
+        write(root, "packages/ui/package.json", r#"{"name": "@acme/ui"}"#);
+        write(
+            root,
+            "packages/ui/fixtures/demo/package.json",
+            r#"{"name": "demo"}"#,
+        );
+        write(root, "apps/web/node_modules/react/package.json", "{}");
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n",
+        );
+        write(
+            root,
+            "crates/engine/Cargo.toml",
+            "[package]\nname = \"acme-engine\"\n",
+        );
+        write(
+            root,
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n",
+        );
+
+        let workspace = Workspace::detect(root).expect("workspace");
+        assert_eq!(
+            names(&workspace),
+            vec![
+                ("web", "apps/web"),
+                ("acme-engine", "crates/engine"),
+                ("ui", "packages/ui"),
+            ]
+        );
+
+        let web = workspace.package_of("apps/web/src/App.tsx").unwrap();
+        assert_eq!(web.declared_name, "@acme/web");
+        assert!(workspace.package_of("apps/website/index.ts").is_none());
+        assert!(workspace.find("@acme/ui").is_some());
+        assert!(workspace.find("crates/engine").is_some());
+    }
+
+    #[test]
+    fn test_detect_npm_and_go_workspaces() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "package.json",
+            r#"{"name": "root", "workspaces": {"packages": ["services/*"]}}"#,
+        );
+        write(root, "services/api/package.json", r#"{"name": "api"}"#);
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./tools/gen // codegen\n\t.\n)\nuse ./tools/lint\n",
+        );
+        write(root, "tools/gen/go.mod", "module github.com/acme/gen\n");
+        write(root, "tools/lint/go.mod", "module github.com/acme/lint\n");
+
+        let workspace = Workspace::detect(root).expect("workspace");
+        assert_eq!(
+            names(&workspace),
+            vec![
+                ("api", "services/api"),
+                ("gen", "tools/gen"),
+                ("lint", "tools/lint"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_workspace() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "package.json", r#"{"name": "app"}"#);
+        write(
+            temp.path(),
+            "Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        );
+        assert!(Workspace::detect(temp.path()).is_none());
+        assert!(resolve_package(temp.path(), "app").is_err());
+    }
+
+    #[test]
+    fn test_clashing_names_fall_back_to_path() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'packages/*/*'\n",
+        );
+        write(
+            root,
+            "packages/a/utils/package.json",
+            r#"{"name": "@a/utils"}"#,
+        );
+        write(
+            root,
+            "packages/b/utils/package.json",
+            r#"{"name": "@b/utils"}"#,
+        );
+
+        let workspace = Workspace::detect(root).expect("workspace");
+        assert_eq!(
+            names(&workspace),
+            vec![
+                ("packages.a.utils", "packages/a/utils"),
+                ("packages.b.utils", "packages/b/utils"),
+            ]
+        );
+    }
+}
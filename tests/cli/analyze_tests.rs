@@ -3,7 +3,19 @@
 //! The analyze command operations:
 //! - `analyze <path>` - Analyze a single file or directory
 //! - `analyze --diff <ref>` - Analyze git diff against a reference
+//!   (`--format review-json` for review bots)
 //! - `analyze --uncommitted` - Analyze uncommitted changes
+//! - `analyze --coupling` - Report tightly-coupled module pairs
+//! - `analyze --debt-score` - Score technical debt, with its trend
+//! - `analyze --topics <n>` - Cluster modules into topics by vocabulary
+//! - `analyze --ref <spec>` - Analyze a git blob (stash, ref:path, blob SHA)
+//! - `analyze --stdin --lang <lang>` - Analyze a snippet piped on stdin
+//! - `analyze --output <path>` - Write the result to a file instead of stdout
+//! - `analyze --select <pointer>` - Print one subtree of the JSON output
+//! - `analyze --mirror` - Write each file's TOON summary next to it
+//!
+//! `benchmark --save`/`--compare` (token efficiency regressions) is covered
+//! here too.
 //!
 //! Note: Directory analysis outputs TOON format regardless of -f flag
 
@@ -396,6 +408,737 @@ fn test_analyze_diff_non_git_repo() {
     assert!(has_error, "Expected git-related error: {}", combined);
 }
 
+/// Build a diverged history:
+///
+/// ```text
+/// base:     A --- B        (B adds src/base_only.ts)
+///            \
+/// feature:    C            (C adds src/feature.ts)
+/// ```
+///
+/// Returns the name of the base branch. HEAD is left on `feature`.
+fn setup_diverged_branches(repo: &TestRepo) -> String {
+    repo.init_git();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.commit("A: initial");
+
+    let branch_output = std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .unwrap();
+    let base_branch = String::from_utf8_lossy(&branch_output.stdout)
+        .trim()
+        .to_string();
+
+    std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["branch", "feature"])
+        .output()
+        .unwrap();
+
+    repo.add_ts_function("src/base_only.ts", "baseOnly", "return 'base';");
+    repo.commit("B: base-only change");
+
+    std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["checkout", "feature"])
+        .output()
+        .unwrap();
+
+    repo.add_ts_function("src/feature.ts", "featureFunction", "return 'feature';");
+    repo.commit("C: feature change");
+
+    base_branch
+}
+
+#[test]
+fn test_analyze_diff_merge_base_excludes_base_only_changes() {
+    let repo = TestRepo::new();
+    let base_branch = setup_diverged_branches(&repo);
+
+    let output = repo.run_cli_success(&["analyze", "--diff", &base_branch]);
+
+    assert!(
+        output.contains("feature.ts"),
+        "Expected feature-branch change in diff: {}",
+        output
+    );
+    assert!(
+        !output.contains("base_only.ts"),
+        "Base-only change should be excluded by merge-base scoping: {}",
+        output
+    );
+    assert!(output.contains("total_files: 1"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_no_merge_base_includes_base_only_changes() {
+    let repo = TestRepo::new();
+    let base_branch = setup_diverged_branches(&repo);
+
+    let output = repo.run_cli_success(&["analyze", "--diff", &base_branch, "--no-merge-base"]);
+
+    assert!(output.contains("feature.ts"), "{}", output);
+    assert!(
+        output.contains("base_only.ts"),
+        "Direct diff should report the base-only file: {}",
+        output
+    );
+}
+
+#[test]
+fn test_analyze_diff_merge_base_working_target() {
+    let repo = TestRepo::new();
+    let base_branch = setup_diverged_branches(&repo);
+
+    // Uncommitted change on top of the feature branch
+    repo.add_ts_function("src/wip.ts", "wip", "return 'wip';");
+    std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["add", "src/wip.ts"])
+        .output()
+        .unwrap();
+
+    let output =
+        repo.run_cli_success(&["analyze", "--diff", &base_branch, "--target-ref", "WORKING"]);
+
+    assert!(output.contains("feature.ts"), "{}", output);
+    assert!(output.contains("wip.ts"), "{}", output);
+    assert!(
+        !output.contains("base_only.ts"),
+        "Base-only change should be excluded for WORKING target: {}",
+        output
+    );
+}
+
+/// A repo with every kind of uncommitted change: a staged edit with further
+/// unstaged edits (`both.ts`), a staged new file, an unstaged deletion and an
+/// untracked file
+fn setup_mixed_working_tree(repo: &TestRepo) {
+    repo.init_git();
+    repo.add_file(".gitignore", ".semfora-cache/\n*.log\n");
+    repo.add_ts_function("src/both.ts", "original", "return 1;")
+        .add_ts_function("src/gone.ts", "goneFn", "return 2;");
+    repo.commit("Initial");
+
+    repo.add_ts_function("src/both.ts", "stagedEdit", "return 1;")
+        .git(&["add", "src/both.ts"])
+        .add_ts_function("src/both.ts", "furtherEdit", "return 1;");
+    repo.add_ts_function("src/staged.ts", "stagedNew", "return 3;")
+        .git(&["add", "src/staged.ts"]);
+    std::fs::remove_file(repo.path().join("src/gone.ts")).unwrap();
+    repo.add_ts_function("src/fresh.ts", "freshFn", "return 4;")
+        .add_file("debug.log", "ignored");
+}
+
+#[test]
+fn test_analyze_uncommitted_includes_untracked_and_deleted() {
+    let repo = TestRepo::new();
+    setup_mixed_working_tree(&repo);
+
+    let output = repo.run_cli_success(&["analyze", "--uncommitted"]);
+
+    // New files, staged or not, are analyzed alongside the edited one
+    assert!(output.contains("files: 3"), "{}", output);
+    for module in ["fresh,", "staged,", "both,"] {
+        assert!(output.contains(module), "{}", output);
+    }
+    // Deleted files are removals described by their committed content
+    let removed = output
+        .split("removed[1]:")
+        .nth(1)
+        .unwrap_or_else(|| panic!("missing removed section: {}", output));
+    assert!(removed.contains("src/gone.ts"), "{}", output);
+    assert!(removed.contains("goneFn"), "{}", output);
+    assert!(!output.contains("debug.log"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_working_includes_untracked_and_deleted() {
+    let repo = TestRepo::new();
+    setup_mixed_working_tree(&repo);
+
+    let output = repo.run_cli_success(&["analyze", "--diff", "HEAD", "--target-ref", "WORKING"]);
+
+    assert!(output.contains("total_files: 4"), "{}", output);
+    assert!(
+        output.contains("changes: added=1, deleted=1, modified=1, untracked=1"),
+        "{}",
+        output
+    );
+    assert!(output.contains("src/fresh.ts [untracked]"), "{}", output);
+    assert!(output.contains("freshFn"), "{}", output);
+    assert!(output.contains("src/staged.ts [added]"), "{}", output);
+    assert!(output.contains("src/both.ts [modified]"), "{}", output);
+    assert!(output.contains("furtherEdit"), "{}", output);
+
+    // The deletion carries the base summary and nothing from the working tree
+    let deleted = output
+        .split("src/gone.ts [deleted]")
+        .nth(1)
+        .unwrap_or_else(|| panic!("missing deleted file: {}", output));
+    assert!(deleted.contains("(deleted)"), "{}", output);
+    assert!(deleted.contains("base:"), "{}", output);
+    assert!(deleted.contains("goneFn"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_merge_base_after_rebase() {
+    let repo = TestRepo::new();
+    let base_branch = setup_diverged_branches(&repo);
+
+    // Rebase feature onto base: base-only commit is now part of feature's history
+    let rebase = std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["rebase", &base_branch])
+        .output()
+        .unwrap();
+    assert!(rebase.status.success());
+
+    let output = repo.run_cli_success(&["analyze", "--diff", &base_branch]);
+
+    assert!(output.contains("feature.ts"), "{}", output);
+    assert!(!output.contains("base_only.ts"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_shallow_clone_missing_base() {
+    let origin = TestRepo::new();
+    let base_branch = setup_diverged_branches(&origin);
+    let clone = origin.shallow_clone("feature");
+    let base_ref = format!("origin/{}", base_branch);
+
+    let (_stdout, stderr) = clone.run_cli_failure(&["analyze", "--diff", &base_ref]);
+
+    assert_contains(&stderr, "shallow clone", false, "shallow clone error");
+    assert_contains(
+        &stderr,
+        &format!("git fetch --depth=50 origin {}", base_branch),
+        true,
+        "fetch command",
+    );
+}
+
+#[test]
+fn test_analyze_diff_shallow_clone_auto_deepen() {
+    let origin = TestRepo::new();
+    let base_branch = setup_diverged_branches(&origin);
+    let clone = origin.shallow_clone("feature");
+    let base_ref = format!("origin/{}", base_branch);
+
+    let output = clone.run_cli_success(&["analyze", "--diff", &base_ref, "--auto-deepen"]);
+
+    assert!(output.contains("feature.ts"), "{}", output);
+    assert!(
+        !output.contains("base_only.ts"),
+        "Deepened history should restore merge-base scoping: {}",
+        output
+    );
+}
+
+#[test]
+fn test_analyze_diff_warns_on_new_deprecated_calls() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(
+        "src/format.ts",
+        r#"/**
+ * @deprecated Use formatDateTime instead.
+ */
+export function formatDate(d: Date): string {
+  return d.toISOString();
+}
+
+export function formatDateTime(d: Date): string {
+  return d.toISOString();
+}
+"#,
+    );
+    repo.add_file(
+        "src/checkout.ts",
+        "export function checkout(d: Date) {\n  return formatDateTime(d);\n}\n",
+    );
+    repo.commit("Initial commit");
+    repo.generate_index().unwrap();
+
+    repo.add_file(
+        "src/checkout.ts",
+        "export function checkout(d: Date) {\n  return formatDate(d);\n}\n",
+    );
+    repo.commit("Use the old formatter");
+
+    let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1"]);
+    assert!(output.contains("deprecated_calls[1]:"), "{}", output);
+    assert!(
+        output.contains("checkout -> formatDate - Use formatDateTime instead."),
+        "{}",
+        output
+    );
+
+    // A call the symbol already made is not reported again
+    repo.add_file(
+        "src/checkout.ts",
+        "export function checkout(d: Date) {\n  const s = formatDate(d);\n  return s.trim();\n}\n",
+    );
+    repo.commit("Tweak checkout");
+    let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1"]);
+    assert!(!output.contains("deprecated_calls"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_warns_on_io_moved_out_of_try() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(
+        "src/users.ts",
+        r#"export async function saveUser(user: User) {
+  try {
+    await fetch('/users', { method: 'POST' });
+  } catch (e) {
+    console.error(e);
+  }
+}
+"#,
+    );
+    repo.commit("Initial commit");
+
+    repo.add_file(
+        "src/users.ts",
+        r#"export async function saveUser(user: User) {
+  await fetch('/users', { method: 'POST' });
+}
+"#,
+    );
+    repo.commit("Drop the error handling");
+
+    let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1"]);
+    assert!(output.contains("io_out_of_try[1]:"), "{}", output);
+    assert!(output.contains("saveUser -> fetch"), "{}", output);
+}
+
+#[test]
+fn test_analyze_diff_review_json() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(
+        "src/config.ts",
+        "export function parseConfig(raw) {\n  return JSON.parse(raw);\n}\n",
+    );
+    repo.commit("Initial commit");
+
+    repo.add_file(
+        "src/config.ts",
+        r#"export function parseConfig(raw) {
+  // TODO: validate the schema
+  const config = JSON.parse(raw) as any;
+  if (config.a) {
+    if (config.b) {
+      if (config.c) {
+        if (config.d) {
+          for (const x of config.items) {
+            if (x) { return x; }
+          }
+        }
+      }
+    }
+  }
+  return config;
+}
+"#,
+    );
+    repo.commit("Deeper config parsing");
+
+    let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1", "-f", "review-json"]);
+    let json = assert_valid_json(&output, "review bundle");
+    assert_eq!(json["_type"], "review_bundle");
+    assert_eq!(json["summary"]["new_todos"], 1, "{}", output);
+    assert_eq!(json["summary"]["new_escape_hatches"], 1, "{}", output);
+    let finding = &json["findings"][0];
+    assert_eq!(finding["category"], "complexity_regression", "{}", output);
+    assert_eq!(finding["path"], "src/config.ts");
+    assert_eq!(finding["line"], 1);
+    assert!(finding["symbol_hash"].is_string(), "{}", output);
+}
+
+#[test]
+fn test_review_json_requires_diff() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+
+    let (_, stderr) = repo.run_cli_failure(&["analyze", "src/main.ts", "-f", "review-json"]);
+    assert_contains(
+        &stderr,
+        "only supported by `analyze --diff`",
+        true,
+        "review-json outside analyze --diff",
+    );
+}
+
+// ============================================================================
+// ANALYZE BLOB TESTS (requires git repo)
+// ============================================================================
+
+#[test]
+fn test_analyze_ref_stashed_file() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_ts_function("src/lib.ts", "committed", "return 1;");
+    repo.commit("initial");
+    repo.add_ts_function("src/lib.ts", "experimental", "return 2;");
+    repo.stash();
+
+    let output = repo.run_cli_success(&["analyze", "--ref", "stash@{0}:src/lib.ts", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze stashed file");
+    assert_symbol_exists(&json, "experimental");
+    assert_eq!(json["file"], "src/lib.ts");
+
+    // A ref without a path takes the file from PATH
+    let output =
+        repo.run_cli_success(&["analyze", "src/lib.ts", "--ref", "stash@{0}", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze stash with path");
+    assert_symbol_exists(&json, "experimental");
+
+    // The working tree still has the committed version
+    let output = repo.run_cli_success(&["analyze", "src/lib.ts", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze working tree");
+    assert_symbol_exists(&json, "committed");
+}
+
+#[test]
+fn test_analyze_ref_blob_sha() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_ts_function("src/lib.ts", "fromBlob", "return 1;");
+    repo.commit("initial");
+
+    let sha = std::process::Command::new("git")
+        .current_dir(repo.path())
+        .args(["rev-parse", "HEAD:src/lib.ts"])
+        .output()
+        .expect("Failed to run git");
+    let sha = String::from_utf8_lossy(&sha.stdout).trim().to_string();
+
+    // The path only names the blob, it isn't read
+    let output = repo.run_cli_success(&["analyze", "renamed.ts", "--ref", &sha, "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze blob sha");
+    assert_symbol_exists(&json, "fromBlob");
+
+    // Without a path the language can't be detected
+    let (_stdout, stderr) = repo.run_cli_failure(&["analyze", "--ref", &sha]);
+    assert_contains(
+        &stderr,
+        "pass the file path",
+        false,
+        "analyze blob sha without path",
+    );
+}
+
+#[test]
+fn test_analyze_ref_binary_blob_errors() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    std::fs::write(repo.path().join("data.ts"), [b'a', 0, b'b', 0xff]).unwrap();
+    repo.commit("binary");
+
+    let (_stdout, stderr) = repo.run_cli_failure(&["analyze", "--ref", "HEAD:data.ts"]);
+    assert_contains(&stderr, "binary blob", false, "analyze binary blob");
+}
+
+// ============================================================================
+// ANALYZE COUPLING TESTS (requires index)
+// ============================================================================
+
+fn add_coupled_modules(repo: &TestRepo) {
+    repo.add_file(
+        "src/api/handlers.ts",
+        r#"import { findUser, saveUser } from '../db/store';
+
+export function getUser(id: string) {
+    return findUser(id);
+}
+
+export function audit(message: string) {
+    return message;
+}
+"#,
+    );
+    repo.add_file(
+        "src/db/store.ts",
+        r#"import { audit } from '../api/handlers';
+
+export function findUser(id: string) {
+    audit(id);
+    return { id };
+}
+"#,
+    );
+    repo.add_file(
+        "src/ui/page.ts",
+        r#"import { getUser } from '../api/handlers';
+
+export function render() {
+    return getUser('1');
+}
+"#,
+    );
+}
+
+#[test]
+fn test_analyze_coupling_reports_module_pairs() {
+    let repo = TestRepo::new();
+    add_coupled_modules(&repo);
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["analyze", "--coupling", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze coupling");
+
+    assert_eq!(json["_type"], "module_coupling");
+    let pairs = json["pairs"].as_array().expect("pairs array");
+    assert_eq!(pairs.len(), 2, "Expected api<->db and api<-ui: {}", output);
+
+    // The bidirectional pair only calls each other, so it ranks first
+    assert_eq!(pairs[0]["module_a"], "api");
+    assert_eq!(pairs[0]["module_b"], "db");
+    assert_eq!(pairs[0]["kind"], "bidirectional");
+    assert_eq!(pairs[1]["module_b"], "ui");
+    assert_eq!(pairs[1]["kind"], "afferent_only");
+    assert!(
+        pairs[0]["coupling_score"].as_f64() >= pairs[1]["coupling_score"].as_f64(),
+        "Pairs should be sorted by score: {}",
+        output
+    );
+}
+
+#[test]
+fn test_analyze_coupling_requires_index() {
+    let repo = TestRepo::new();
+    add_coupled_modules(&repo);
+
+    let (_stdout, stderr) = repo.run_cli_failure(&["analyze", "--coupling"]);
+    assert_contains(
+        &stderr,
+        "index generate",
+        false,
+        "analyze coupling without index",
+    );
+}
+
+// ============================================================================
+// ANALYZE DEBT SCORE TESTS (requires index)
+// ============================================================================
+
+/// Two copies of one function, plus a deeply nested one; no tests
+fn add_indebted_code(repo: &TestRepo) {
+    let profile = r#"pub fn load_user_profile(svc: &Service, id: u32) -> Profile {
+    let user = svc.user_service.fetch_user(id);
+    svc.cache.store(id, &user);
+    svc.audit_log.record(id);
+    user
+}
+"#;
+    repo.add_file("src/a.rs", profile);
+    repo.add_file("src/b.rs", profile);
+    repo.add_file(
+        "src/rules.rs",
+        r#"pub fn classify(items: &[u32], limit: u32) -> u32 {
+    let mut score = 0;
+    for item in items {
+        if *item > limit {
+            for step in 0..*item {
+                if step % 2 == 0 {
+                    if step > limit {
+                        score += 1;
+                    } else if step == limit {
+                        score += 2;
+                    }
+                }
+            }
+        }
+    }
+    score
+}
+"#,
+    );
+}
+
+#[test]
+fn test_analyze_debt_score_components() {
+    let repo = TestRepo::new();
+    add_indebted_code(&repo);
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["analyze", "--debt-score", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze debt score");
+
+    assert_eq!(json["_type"], "tech_debt");
+    let score = |key: &str| {
+        json[key]
+            .as_f64()
+            .unwrap_or_else(|| panic!("{key}: {json}"))
+    };
+    assert!(score("duplicate_debt") > 0.0, "{json}");
+    assert!(score("complexity_debt") > 0.0, "{json}");
+    // Nothing is tested
+    assert_eq!(score("test_coverage_debt"), 100.0, "{json}");
+    let overall = score("overall_score");
+    assert!(overall > 0.0 && overall <= 100.0, "{json}");
+    let affected = json["affected_loc"].as_u64().unwrap() as f64;
+    assert_eq!(score("remediation_hours"), affected * 0.5, "{json}");
+    // First score: nothing to compare with
+    assert!(json.get("trend").is_none(), "{json}");
+}
+
+#[test]
+fn test_analyze_debt_score_trend_across_generations() {
+    let repo = TestRepo::new();
+    add_indebted_code(&repo);
+    repo.generate_index().unwrap();
+    let first = assert_valid_json(
+        &repo.run_cli_success(&["analyze", "--debt-score", "-f", "json"]),
+        "first debt score",
+    );
+    // Re-running against the same index doesn't make a trend
+    let again = assert_valid_json(
+        &repo.run_cli_success(&["analyze", "--debt-score", "-f", "json"]),
+        "repeated debt score",
+    );
+    assert!(again.get("trend").is_none(), "{again}");
+
+    // Remove the duplicate and reindex
+    std::fs::remove_file(repo.path().join("src/b.rs")).unwrap();
+    repo.run_cli_success(&["index", "generate", "--force"]);
+    let second = assert_valid_json(
+        &repo.run_cli_success(&["analyze", "--debt-score", "-f", "json"]),
+        "second debt score",
+    );
+
+    let trend = &second["trend"];
+    assert_eq!(trend["previous_score"], first["overall_score"], "{second}");
+    assert_eq!(trend["direction"], "improving", "{second}");
+    assert!(trend["change"].as_f64().unwrap() < 0.0, "{second}");
+    assert_eq!(second["duplicate_debt"].as_f64(), Some(0.0), "{second}");
+
+    // The overview of the next index carries the latest score
+    repo.run_cli_success(&["index", "generate", "--force"]);
+    let overview = assert_valid_json(
+        &repo.run_cli_success(&["query", "overview", "-f", "json"]),
+        "overview",
+    );
+    assert_eq!(
+        overview["tech_debt_score"], second["overall_score"],
+        "{overview}"
+    );
+}
+
+#[test]
+fn test_analyze_debt_score_requires_index() {
+    let repo = TestRepo::new();
+    add_indebted_code(&repo);
+
+    let (_stdout, stderr) = repo.run_cli_failure(&["analyze", "--debt-score"]);
+    assert_contains(&stderr, "index generate", false, "debt score without index");
+}
+
+// ============================================================================
+// ANALYZE STDIN SNIPPET TESTS
+// ============================================================================
+
+const FORMAT_DATE_SRC: &str = r#"export function formatDate(date: Date): string {
+    const year = date.getFullYear();
+    const month = padNumber(date.getMonth() + 1);
+    if (!year) {
+        throw new Error('invalid date');
+    }
+    return joinParts([year, month]);
+}
+
+export function parseDate(value: string): Date {
+    return new Date(Date.parse(value));
+}
+"#;
+
+#[test]
+fn test_analyze_stdin_reports_duplicates_per_symbol() {
+    let repo = TestRepo::new();
+    repo.add_file("src/utils/formatDate.ts", FORMAT_DATE_SRC);
+    repo.generate_index().unwrap();
+
+    let output = repo
+        .run_cli_with_stdin(
+            &["analyze", "--stdin", "--lang", "ts", "-f", "json"],
+            FORMAT_DATE_SRC,
+        )
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json = assert_valid_json(&String::from_utf8_lossy(&output.stdout), "stdin snippet");
+
+    assert_eq!(json["_type"], "snippet_analysis");
+    assert_eq!(json["index_available"], true);
+    let symbols = json["symbols"].as_array().expect("symbols array");
+    let names: Vec<&str> = symbols.iter().filter_map(|s| s["name"].as_str()).collect();
+    assert!(names.contains(&"formatDate") && names.contains(&"parseDate"));
+
+    let format_date = symbols.iter().find(|s| s["name"] == "formatDate").unwrap();
+    assert!(format_date["risk"].is_string());
+    let finding = format_date["duplicates"][0]["finding"]
+        .as_str()
+        .unwrap_or_default();
+    assert_eq!(
+        finding,
+        "100% similar to src/utils/formatDate.ts:formatDate"
+    );
+
+    // The snippet must not leak into the index
+    let search = repo.run_cli_success(&["search", "formatDate", "--symbols", "-f", "json"]);
+    assert!(
+        !search.contains("<stdin>"),
+        "Snippet was indexed: {}",
+        search
+    );
+}
+
+#[test]
+fn test_analyze_stdin_without_index() {
+    let repo = TestRepo::new();
+
+    let output = repo
+        .run_cli_with_stdin(
+            &["analyze", "--stdin", "--lang", "python"],
+            "def add(a, b):\n    return a + b\n",
+        )
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_contains(&stdout, "SNIPPET ANALYSIS", true, "stdin text banner");
+    assert_contains(&stdout, "duplicates: skipped", true, "no index note");
+}
+
+#[test]
+fn test_analyze_stdin_unsupported_language() {
+    let repo = TestRepo::new();
+
+    let output = repo
+        .run_cli_with_stdin(&["analyze", "--stdin", "--lang", "cobol"], "DISPLAY 'HI'.")
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_contains(
+        &stderr,
+        "supported: typescript (ts)",
+        true,
+        "supported list",
+    );
+}
+
 // ============================================================================
 // FORMAT CONSISTENCY TESTS
 // ============================================================================
@@ -486,3 +1229,466 @@ fn test_analyze_special_characters_in_path() {
 
     assert_symbol_exists(&json, "specialPath");
 }
+
+// ============================================================================
+// ANALYZE TOPICS TESTS
+// ============================================================================
+
+fn add_topic_modules(repo: &TestRepo) {
+    repo.add_file(
+        "src/auth/session.ts",
+        r#"export function loginUser(token: string) { return token; }
+export function logoutUser(token: string) { return token; }
+export function refreshToken(token: string) { return token; }
+"#,
+    );
+    repo.add_file(
+        "src/accounts/tokens.ts",
+        r#"export function revokeToken(token: string) { return token; }
+export function validateToken(token: string) { return token; }
+"#,
+    );
+    repo.add_file(
+        "src/db/queries.ts",
+        r#"export function runQuery(sql: string) { return sql; }
+export function buildQuery(sql: string) { return sql; }
+export function migrateSchema(sql: string) { return sql; }
+"#,
+    );
+    repo.add_file(
+        "src/storage/tables.ts",
+        r#"export function createTable(sql: string) { return sql; }
+export function dropTable(sql: string) { return sql; }
+export function queryTable(sql: string) { return sql; }
+"#,
+    );
+}
+
+#[test]
+fn test_analyze_topics_clusters_modules() {
+    let repo = TestRepo::new();
+    add_topic_modules(&repo);
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["analyze", "--topics", "2", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze topics");
+
+    assert_eq!(json["_type"], "module_topics");
+    let topics = json["topics"].as_array().expect("topics array");
+    assert_eq!(topics.len(), 2, "Expected two topics: {}", output);
+
+    let modules_of = |module: &str| {
+        topics
+            .iter()
+            .find(|t| {
+                t["modules"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|m| m.as_str().unwrap().ends_with(module))
+            })
+            .map(|t| t["id"].as_u64().unwrap())
+    };
+    assert_eq!(modules_of("auth"), modules_of("accounts"), "{}", output);
+    assert_eq!(modules_of("db"), modules_of("storage"), "{}", output);
+    assert_ne!(modules_of("auth"), modules_of("db"), "{}", output);
+
+    for topic in topics {
+        assert!(!topic["label"].as_str().unwrap().is_empty());
+        assert!(topic["cohesion_score"].as_f64().unwrap() > 0.0);
+    }
+}
+
+#[test]
+fn test_analyze_topics_requires_index() {
+    let repo = TestRepo::new();
+    add_topic_modules(&repo);
+
+    let (_stdout, stderr) = repo.run_cli_failure(&["analyze", "--topics", "3"]);
+    assert_contains(
+        &stderr,
+        "index generate",
+        false,
+        "analyze topics without index",
+    );
+}
+
+// ============================================================================
+// ANALYZE ROUTES TESTS
+// ============================================================================
+
+#[test]
+fn test_analyze_routes_manifest() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/server.js",
+        r#"const express = require('express');
+const app = express();
+
+app.get('/health', healthCheck);
+app.post('/orders', requireAuth, createOrder);
+
+app.listen(3000);
+"#,
+    );
+    repo.add_file(
+        "src/users.controller.ts",
+        r#"import { Controller, Get, Delete } from '@nestjs/common';
+
+@Controller('users')
+export class UsersController {
+    @Get(':id')
+    findOne() {
+        return null;
+    }
+
+    @Delete(':id')
+    remove() {
+        return null;
+    }
+}
+"#,
+    );
+    repo.add_ts_function("src/util.ts", "helper", "return 1;");
+
+    let output = repo.run_cli_success(&["analyze", "--routes", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze --routes");
+    assert_eq!(json["_type"], "routes");
+    assert_eq!(json["total"], 4, "{}", output);
+
+    let routes: Vec<(&str, &str, &str, &str)> = json["routes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["method"].as_str().unwrap(),
+                r["path"].as_str().unwrap(),
+                r["handler"].as_str().unwrap(),
+                r["file"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        routes,
+        vec![
+            ("GET", "/health", "healthCheck", "src/server.js"),
+            ("POST", "/orders", "createOrder", "src/server.js"),
+            (
+                "GET",
+                "/users/:id",
+                "UsersController.findOne",
+                "src/users.controller.ts"
+            ),
+            (
+                "DELETE",
+                "/users/:id",
+                "UsersController.remove",
+                "src/users.controller.ts"
+            ),
+        ]
+    );
+
+    let text = repo.run_cli_success(&["analyze", "--routes", "src/server.js", "-f", "text"]);
+    assert!(text.contains("routes: 2"), "{}", text);
+    assert!(text.contains("createOrder"), "{}", text);
+}
+
+// ============================================================================
+// ANALYZE FILE DIFF TESTS
+// ============================================================================
+
+#[test]
+fn test_analyze_file_a_file_b() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/load.ts", "load", "return 1;");
+    repo.add_file(
+        "gen/load.ts",
+        r#"import axios from 'axios';
+
+export function fetchData() {
+    return axios.get('/api');
+}
+"#,
+    );
+
+    let output = repo.run_cli_success(&[
+        "analyze",
+        "--file-a",
+        "src/load.ts",
+        "--file-b",
+        "gen/load.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "analyze --file-a --file-b");
+    assert_eq!(json["_type"], "file_diff");
+    let deltas: Vec<String> = json["deltas"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| format!("{} {}", d["type"].as_str().unwrap(), d["name"]))
+        .collect();
+    assert_eq!(
+        deltas,
+        vec![
+            "symbol_removed \"load\"",
+            "symbol_added \"fetchData\"",
+            "dependency_added \"axios\"",
+        ]
+    );
+
+    let toon = repo.run_cli_success(&[
+        "analyze",
+        "--file-a",
+        "src/load.ts",
+        "--file-b",
+        "src/load.ts",
+        "-f",
+        "toon",
+    ]);
+    assert!(toon.contains("deltas[0]:"), "{}", toon);
+
+    let (_, stderr) = repo.run_cli_failure(&[
+        "analyze",
+        "--file-a",
+        "src/load.ts",
+        "--file-b",
+        "missing.ts",
+    ]);
+    assert!(stderr.contains("missing.ts"), "{}", stderr);
+}
+
+// ============================================================================
+// ANALYZE SELECT TESTS
+// ============================================================================
+
+#[test]
+fn test_analyze_select_json_pointer() {
+    let repo = TestRepo::new();
+    repo.add_ts_module("src/service.ts", "Auth");
+
+    let full = repo.run_cli_success(&["analyze", "src/service.ts", "-f", "json"]);
+    let json = assert_valid_json(&full, "analyze full");
+
+    let output = repo.run_cli_success(&[
+        "analyze",
+        "src/service.ts",
+        "-f",
+        "json",
+        "--select",
+        "/symbols/0/name",
+    ]);
+    let selected = assert_valid_json(&output, "analyze --select");
+    assert_eq!(selected, json["symbols"][0]["name"]);
+
+    // Subtrees come out as JSON too
+    let output = repo.run_cli_success(&[
+        "analyze",
+        "src/service.ts",
+        "-f",
+        "json",
+        "--json-pointer",
+        "/symbols/0",
+    ]);
+    assert_eq!(
+        assert_valid_json(&output, "analyze --json-pointer"),
+        json["symbols"][0]
+    );
+}
+
+#[test]
+fn test_analyze_select_invalid_pointer() {
+    let repo = TestRepo::new();
+    repo.add_ts_module("src/service.ts", "Auth");
+
+    let (_, stderr) = repo.run_cli_failure(&[
+        "analyze",
+        "src/service.ts",
+        "-f",
+        "json",
+        "--select",
+        "/symbols/999/name",
+    ]);
+    assert!(
+        stderr.contains("`/symbols/999/name` does not resolve"),
+        "{}",
+        stderr
+    );
+
+    let (_, stderr) = repo.run_cli_failure(&[
+        "analyze",
+        "src/service.ts",
+        "-f",
+        "json",
+        "--select",
+        "symbols",
+    ]);
+    assert!(
+        stderr.contains("must be empty or start with `/`"),
+        "{}",
+        stderr
+    );
+
+    let (_, stderr) = repo.run_cli_failure(&[
+        "analyze",
+        "src/service.ts",
+        "-f",
+        "toon",
+        "--select",
+        "/symbols",
+    ]);
+    assert!(
+        stderr.contains("--select requires --format json"),
+        "{}",
+        stderr
+    );
+}
+
+// ============================================================================
+// ANALYZE OUTPUT FILE TESTS
+// ============================================================================
+
+#[test]
+fn test_analyze_output_writes_file_not_stdout() {
+    let repo = TestRepo::new();
+    repo.add_ts_module("src/service.ts", "Auth");
+
+    let expected = repo.run_cli_success(&["analyze", "src/service.ts", "-f", "json"]);
+
+    let output = repo
+        .run_cli(&[
+            "analyze",
+            "src/service.ts",
+            "-f",
+            "json",
+            "--output",
+            "reports/analysis.json",
+        ])
+        .expect("Failed to run CLI");
+    assert!(
+        output.status.success(),
+        "analyze --output failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        output.stdout.is_empty(),
+        "stdout should be empty with --output, got: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let written = std::fs::read_to_string(repo.path().join("reports/analysis.json"))
+        .expect("report file should be written");
+    assert_eq!(written, expected);
+    assert!(
+        !repo.path().join("reports/.analysis.json.tmp").exists(),
+        "temporary file should be renamed into place"
+    );
+}
+
+#[test]
+fn test_analyze_output_not_written_on_failure() {
+    let repo = TestRepo::new();
+
+    let (_stdout, _stderr) =
+        repo.run_cli_failure(&["analyze", "missing.ts", "--output", "analysis.toon"]);
+    assert!(!repo.path().join("analysis.toon").exists());
+}
+
+// ============================================================================
+// ANALYZE MIRROR TESTS
+// ============================================================================
+
+/// TOON summary of a repo file, as `encode_toon` renders it
+fn expected_toon(repo: &TestRepo, file: &str) -> String {
+    let path = std::path::Path::new(file);
+    let source = std::fs::read_to_string(repo.path().join(file)).unwrap();
+    let lang = semfora_engine::Lang::from_path(path).unwrap();
+    let summary = semfora_engine::parsing::parse_and_extract(path, &source, lang).unwrap();
+    semfora_engine::encode_toon(&summary)
+}
+
+#[test]
+fn test_analyze_mirror_writes_sibling_files() {
+    let repo = TestRepo::new();
+    repo.add_ts_module("src/service.ts", "Auth");
+    repo.add_rs_module("src/cache/lib.rs", "Cache");
+
+    let output = repo.run_cli_success(&["analyze", "src", "--mirror", "-f", "json"]);
+    let json = assert_valid_json(&output, "analyze mirror");
+    assert_eq!(json["_type"], "analyze_mirror");
+    assert_eq!(json["files_written"], 2);
+
+    for file in ["src/service.ts", "src/cache/lib.rs"] {
+        let mirror = repo.path().join(format!("{}.toon", file));
+        let written = std::fs::read_to_string(&mirror)
+            .unwrap_or_else(|_| panic!("{} should be mirrored", file));
+        assert_eq!(written, expected_toon(&repo, file), "{}", file);
+    }
+    assert!(!repo.path().join("src/.service.ts.toon.tmp").exists());
+
+    // A single file mirrors next to itself, with a custom extension
+    repo.run_cli_success(&[
+        "analyze",
+        "src/service.ts",
+        "--mirror",
+        "--mirror-ext",
+        "sem",
+    ]);
+    assert!(repo.path().join("src/service.ts.sem").exists());
+}
+
+#[test]
+fn test_analyze_mirror_into_output_dir_preserves_structure() {
+    let repo = TestRepo::new();
+    repo.add_ts_module("src/api/service.ts", "Auth");
+    repo.add_py_module("src/app.py", "Database");
+
+    repo.run_cli_success(&["analyze", "src", "--mirror", "--output-dir", "docs/toon"]);
+
+    for (file, mirror) in [
+        ("src/api/service.ts", "docs/toon/api/service.ts.toon"),
+        ("src/app.py", "docs/toon/app.py.toon"),
+    ] {
+        let written = std::fs::read_to_string(repo.path().join(mirror))
+            .unwrap_or_else(|_| panic!("{} should be written", mirror));
+        assert_eq!(written, expected_toon(&repo, file), "{}", file);
+        assert!(!repo.path().join(format!("{}.toon", file)).exists());
+    }
+}
+
+// ============================================================================
+// BENCHMARK COMPARE TESTS
+// ============================================================================
+
+#[test]
+fn test_benchmark_compare_fails_on_regression() {
+    let repo = TestRepo::new();
+    repo.add_ts_function(
+        "src/cart.ts",
+        "cartTotal",
+        "const total = items.reduce((sum, item) => sum + item.price, 0);\n    return total;",
+    );
+
+    repo.run_cli_success(&["benchmark", ".", "--save", "bench/baseline.json"]);
+    let baseline_path = repo.path().join("bench/baseline.json");
+    let baseline = std::fs::read_to_string(&baseline_path).expect("baseline saved");
+    assert_valid_json(&baseline, "saved benchmark metrics");
+
+    // Comparing an unchanged tree against its own baseline passes
+    let output = repo.run_cli_success(&["benchmark", ".", "--compare", "bench/baseline.json"]);
+    assert_contains(&output, "Result: PASS", false, "unchanged metrics");
+
+    // A baseline with far better compression trips the threshold
+    let mut json: serde_json::Value = serde_json::from_str(&baseline).unwrap();
+    json["total_compression"] = serde_json::json!(0.999);
+    std::fs::write(&baseline_path, json.to_string()).unwrap();
+
+    let output = repo
+        .run_cli(&["benchmark", ".", "--compare", "bench/baseline.json"])
+        .expect("Failed to run CLI");
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_contains(&stdout, "REGRESSED", false, "regressed metric flagged");
+    assert_contains(&stdout, "Result: FAIL", false, "failed comparison");
+}
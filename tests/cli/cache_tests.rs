@@ -2,8 +2,13 @@
 //!
 //! The cache command manages the semantic index cache:
 //! - `cache info` - Show cache information
+//! - `cache list` - List cached repositories with size, age and symbol count
 //! - `cache clear` - Clear the cache for the current directory
 //! - `cache prune --days N` - Prune caches older than N days
+//! - `cache migrate` - Upgrade the index to the current schema version
+//! - `cache verify` - Report the status of every cached artifact
+//! - `cache repair` - Rebuild only the broken artifacts
+//! - `cache diff` - Compare two index generations by their manifests
 
 #![allow(unused_imports)]
 
@@ -82,6 +87,70 @@ fn test_cache_info_text_format() {
     assert!(!output.is_empty(), "Should produce text output");
 }
 
+// ============================================================================
+// CACHE LIST TESTS
+// ============================================================================
+
+#[test]
+fn test_index_records_list_metadata() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;")
+        .add_ts_function("src/util.ts", "helper", "return 2;");
+    repo.generate_index().unwrap();
+
+    let cache = cache_for(&repo);
+    let meta = cache.load_meta().expect("meta.json");
+    let indexed = cache.load_all_symbol_entries().unwrap().len();
+
+    assert_eq!(meta.symbol_count, Some(indexed));
+    assert_eq!(meta.repo_path.as_deref(), Some(cache.repo_root.as_path()));
+}
+
+#[test]
+fn test_cache_list_sort_options() {
+    let repo = TestRepo::new();
+
+    for sort_by in ["size", "age", "symbols"] {
+        let output = repo.run_cli_success(&["cache", "list", "--sort-by", sort_by, "-f", "json"]);
+        let json = assert_valid_json(&output, "cache list json");
+        assert_eq!(json["_type"], "cache_list");
+        assert_eq!(json["sort_by"], sort_by);
+        assert!(json["repos"].is_array(), "{}", output);
+    }
+
+    let (_, stderr) = repo.run_cli_failure(&["cache", "list", "--sort-by", "name"]);
+    assert_contains(&stderr, "name", true, "invalid sort key");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_cache_list_skips_security_pattern_store() {
+    let repo = TestRepo::new();
+    let cache_home = tempfile::TempDir::new().unwrap();
+    let base = cache_home.path().join("semfora");
+    std::fs::create_dir_all(base.join("security")).unwrap();
+    std::fs::write(base.join("security/security_patterns.bin"), b"patterns").unwrap();
+    std::fs::create_dir_all(base.join("0123456789abcdef")).unwrap();
+
+    for subcommand in ["list", "info"] {
+        let output = repo
+            .cli_command(&["cache", subcommand, "-f", "json"])
+            .env("XDG_CACHE_HOME", cache_home.path())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json = assert_valid_json(&stdout, "cache json");
+
+        let hashes: Vec<_> = json["repos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["repo_hash"].as_str().or(r["hash"].as_str()).unwrap())
+            .collect();
+        assert_eq!(hashes, ["0123456789abcdef"], "{}", stdout);
+    }
+}
+
 // ============================================================================
 // CACHE CLEAR TESTS
 // ============================================================================
@@ -168,6 +237,255 @@ fn test_cache_prune_text_output() {
     assert!(result.is_ok(), "Prune should complete");
 }
 
+// ============================================================================
+// CACHE MIGRATE TESTS
+// ============================================================================
+
+/// Rewrite the schema version recorded in the repo's index
+fn set_stored_schema_version(repo: &TestRepo, version: &str) {
+    let cache = semfora_engine::cache::CacheDir::for_repo(repo.path()).unwrap();
+    cache.set_stored_schema_version(version).unwrap();
+}
+
+#[test]
+fn test_cache_migrate_current_index_is_noop() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["cache", "migrate", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache migrate json");
+
+    assert_eq!(json["_type"], "cache_migrate");
+    assert_eq!(json["from_version"], semfora_engine::SCHEMA_VERSION);
+    assert_eq!(json["rebuild_required"], false);
+    assert_eq!(json["applied"].as_array().map(|a| a.len()), Some(0));
+}
+
+#[test]
+fn test_cache_migrate_rebuilds_outdated_index() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.generate_index().unwrap();
+    set_stored_schema_version(&repo, "2.0");
+
+    let output = repo.run_cli_success(&["cache", "migrate", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache migrate json");
+
+    assert_eq!(json["from_version"], "2.0");
+    assert_eq!(json["applied"][0], "2.1");
+    assert_eq!(json["rebuilt"], true);
+
+    // The regenerated index is stamped with the current version
+    let output = repo.run_cli_success(&["cache", "migrate", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache migrate json");
+    assert_eq!(json["from_version"], semfora_engine::SCHEMA_VERSION);
+}
+
+#[test]
+fn test_cache_migrate_no_rebuild() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.generate_index().unwrap();
+    set_stored_schema_version(&repo, "2.0");
+
+    let output = repo.run_cli_success(&["cache", "migrate", "--no-rebuild"]);
+    assert_contains(
+        &output,
+        "semfora index generate",
+        true,
+        "cache migrate --no-rebuild",
+    );
+
+    let output = repo.run_cli_success(&["cache", "migrate", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache migrate json");
+    assert!(
+        json["from_version"].is_null(),
+        "Index should be gone: {}",
+        output
+    );
+}
+
+// ============================================================================
+// CACHE VERIFY / REPAIR TESTS
+// ============================================================================
+
+fn cache_for(repo: &TestRepo) -> semfora_engine::cache::CacheDir {
+    semfora_engine::cache::CacheDir::for_repo(repo.path()).unwrap()
+}
+
+#[test]
+fn test_cache_verify_healthy_index() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["cache", "verify", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache verify json");
+
+    assert_eq!(json["_type"], "cache_verify");
+    assert_eq!(json["healthy"], true, "{}", output);
+    assert_eq!(json["broken"], 0);
+    let artifacts = json["artifacts"].as_array().unwrap();
+    assert!(artifacts.iter().any(|a| a["path"] == "symbol_index.jsonl"));
+    assert!(artifacts.iter().all(|a| a["state"] == "ok"));
+}
+
+#[test]
+fn test_cache_repair_corrupt_shards() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return helper();");
+    repo.add_ts_function("src/util.ts", "helper", "return 1;");
+    repo.generate_index().unwrap();
+
+    // Garbage bytes in a module shard and a search index that isn't a database
+    let cache = cache_for(&repo);
+    let module = std::fs::read_dir(cache.modules_dir())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    std::fs::write(&module, [0x5f, 0x74, 0xff, 0xfe, 0x00, 0x9c]).unwrap();
+    std::fs::write(cache.index_db_path(), b"not a database").unwrap();
+
+    let output = repo.run_cli_success(&["cache", "verify", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache verify json");
+    assert_eq!(json["healthy"], false);
+    let corrupt: Vec<&str> = json["artifacts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["state"] == "corrupt")
+        .map(|a| a["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(corrupt.len(), 2, "{}", output);
+    assert!(corrupt.contains(&"index.sqlite"));
+
+    // Readers report the damage instead of panicking
+    let (_, stderr) = repo.run_cli_failure(&["search", "helper", "--related"]);
+    assert_contains(&stderr, "Corrupt cache artifact", true, "semantic search");
+
+    let output = repo.run_cli_success(&["cache", "repair", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache repair json");
+    assert_eq!(json["repaired"].as_array().unwrap().len(), 2);
+    assert!(json["files_indexed"].as_u64().unwrap() > 0);
+    assert_eq!(json["remaining"].as_array().unwrap().len(), 0, "{}", output);
+
+    let output = repo.run_cli_success(&["cache", "verify", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache verify json");
+    assert_eq!(json["healthy"], true, "{}", output);
+    repo.run_cli_success(&["search", "helper", "--related"]);
+}
+
+#[test]
+fn test_cache_repair_graphs_without_reindex() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return helper();");
+    repo.add_ts_function("src/util.ts", "helper", "return 1;");
+    repo.generate_index().unwrap();
+
+    // Cut the call graph off mid-list
+    let cache = cache_for(&repo);
+    let graph = std::fs::read_to_string(cache.call_graph_path()).unwrap();
+    let cut = graph.rfind(']').unwrap();
+    std::fs::write(cache.call_graph_path(), &graph[..cut]).unwrap();
+
+    let output = repo.run_cli_success(&["cache", "verify"]);
+    assert_contains(&output, "corrupt graphs/call_graph.toon", true, "verify");
+
+    let output = repo.run_cli_success(&["cache", "repair", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache repair json");
+    assert!(json["files_indexed"].is_null(), "{}", output);
+    assert!(json["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|a| a == "regenerated graphs"));
+    assert_eq!(json["remaining"].as_array().unwrap().len(), 0, "{}", output);
+}
+
+#[test]
+fn test_cache_repair_healthy_is_noop() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["cache", "repair"]);
+    assert_contains(&output, "nothing to repair", true, "cache repair");
+}
+
+// ============================================================================
+// CACHE DIFF TESTS
+// ============================================================================
+
+#[test]
+fn test_cache_diff_names_mutated_file_and_symbols() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return helper(1);");
+    repo.add_file(
+        "src/util.ts",
+        r#"export function helper() {
+    return 1;
+}
+
+export function legacy() {
+    return 2;
+}
+"#,
+    );
+    repo.add_ts_function("lib/other.ts", "other", "return 3;");
+    repo.generate_index().unwrap();
+
+    let (_, stderr) = repo.run_cli_failure(&["cache", "diff", "--previous"]);
+    assert_contains(&stderr, "No earlier index generation", true, "cache diff");
+
+    // Change helper's arity, drop legacy, add modern
+    repo.add_file(
+        "src/util.ts",
+        r#"export function helper(value: number) {
+    return value;
+}
+
+export function modern() {
+    return 2;
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["cache", "diff", "--previous", "-f", "json"]);
+    let json = assert_valid_json(&output, "cache diff json");
+    assert_eq!(json["_type"], "cache_diff");
+    assert_eq!(json["identical"], false);
+    assert_eq!(json["files_changed"], serde_json::json!(["src/util.ts"]));
+    assert_eq!(json["files_added"], serde_json::json!([]));
+    assert_eq!(json["files_removed"], serde_json::json!([]));
+
+    let modules = json["modules"].as_array().unwrap();
+    assert_eq!(modules.len(), 1, "{}", output);
+    assert_eq!(
+        modules[0]["added"],
+        serde_json::json!(["src/util.ts::modern"])
+    );
+    assert_eq!(
+        modules[0]["removed"],
+        serde_json::json!(["src/util.ts::legacy"])
+    );
+    assert_eq!(
+        modules[0]["rehashed"],
+        serde_json::json!(["src/util.ts::helper"])
+    );
+    assert_eq!(json["totals"]["symbols"]["delta"], 0);
+
+    // Explicit manifests: a generation compared with itself is identical
+    let manifests = cache_for(&repo).list_manifests();
+    assert_eq!(manifests.len(), 2);
+    let current = manifests[1].to_string_lossy().to_string();
+    let output = repo.run_cli_success(&["cache", "diff", &current, &current]);
+    assert_contains(&output, "No changes", true, "cache diff");
+}
+
 // ============================================================================
 // FORMAT CONSISTENCY TESTS
 // ============================================================================
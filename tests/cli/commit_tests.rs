@@ -1,7 +1,7 @@
 //! Tests for the `commit` CLI command
 //!
 //! The commit command prepares information for writing commit messages:
-//! - `commit` - Show staged and unstaged changes with semantic analysis
+//! - `commit` - Show staged, unstaged and untracked changes with semantic analysis
 //! - `commit --staged` - Only show staged changes
 //! - `commit --metrics` - Include complexity metrics
 //! - `commit --all-metrics` - Include all detailed metrics
@@ -114,6 +114,76 @@ fn test_commit_prep_json_format() {
     );
 }
 
+#[test]
+fn test_commit_prep_separates_untracked_files() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(".gitignore", ".semfora-cache/\n");
+    repo.add_ts_function("src/main.ts", "main", "return 1;")
+        .add_ts_function("src/gone.ts", "goneFn", "return 2;");
+    repo.commit("Initial commit");
+
+    // Staged edit with further unstaged edits, unstaged deletion, new files
+    repo.add_ts_function("src/main.ts", "stagedEdit", "return 1;")
+        .git(&["add", "src/main.ts"])
+        .add_ts_function("src/main.ts", "furtherEdit", "return 1;");
+    repo.add_ts_function("src/staged.ts", "stagedNew", "return 3;")
+        .git(&["add", "src/staged.ts"]);
+    std::fs::remove_file(repo.path().join("src/gone.ts")).unwrap();
+    repo.add_ts_function("src/fresh.ts", "freshFn", "return 4;");
+
+    let output = repo.run_cli_success(&["commit", "-f", "json", "--no-auto-refresh"]);
+    let json = assert_valid_json(&output, "commit prep with untracked files");
+
+    let paths = |section: &str| -> Vec<(String, String)> {
+        json[section]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                (
+                    f["path"].as_str().unwrap().to_string(),
+                    f["change_type"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    };
+    let pair = |path: &str, change: &str| (path.to_string(), change.to_string());
+    assert_eq!(
+        paths("staged_changes"),
+        vec![
+            pair("src/main.ts", "Modified"),
+            pair("src/staged.ts", "Added")
+        ]
+    );
+    assert_eq!(
+        paths("unstaged_changes"),
+        vec![
+            pair("src/gone.ts", "Deleted"),
+            pair("src/main.ts", "Modified")
+        ]
+    );
+    assert_eq!(
+        paths("untracked_changes"),
+        vec![pair("src/fresh.ts", "Untracked")]
+    );
+
+    let summary = &json["summary"];
+    assert_eq!(summary["staged_symbols"], 2, "{}", output);
+    assert_eq!(summary["unstaged_symbols"], 1, "{}", output);
+    assert_eq!(summary["untracked_files"], 1, "{}", output);
+    assert_eq!(summary["untracked_symbols"], 1, "{}", output);
+    assert_eq!(json["untracked_changes"][0]["insertions"], 3, "{}", output);
+
+    // Text output gets its own section; --staged leaves untracked files out
+    let text = repo.run_cli_success(&["commit", "-f", "text", "--no-auto-refresh"]);
+    assert!(text.contains("untracked_changes[1]:"), "{}", text);
+    assert!(text.contains("src/fresh.ts [Untracked]"), "{}", text);
+    assert!(text.contains("untracked_symbols: 1"), "{}", text);
+    let staged = repo.run_cli_success(&["commit", "--staged", "-f", "text", "--no-auto-refresh"]);
+    assert!(staged.contains("untracked_changes: (none)"), "{}", staged);
+}
+
 // ============================================================================
 // COMMIT PREP STAGED ONLY TESTS
 // ============================================================================
@@ -367,3 +437,34 @@ fn test_commit_prep_deleted_file() {
         output
     );
 }
+
+#[test]
+fn test_commit_prep_shallow_clone_omits_base_branch() {
+    let origin = TestRepo::new();
+    origin.init_git();
+    origin.add_ts_function("src/main.ts", "main", "return 1;");
+    origin.commit("Initial commit");
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .current_dir(origin.path())
+            .args(args)
+            .output()
+            .unwrap();
+    };
+    git(&["checkout", "-b", "feature"]);
+    origin.add_ts_function("src/feature.ts", "feature", "return 2;");
+    origin.commit("Feature commit");
+    git(&["checkout", "-"]);
+
+    let clone = origin.shallow_clone("feature");
+    clone.add_ts_function("src/feature.ts", "featureUpdated", "return 3;");
+
+    let output = clone.run_cli_success(&["commit", "-f", "json", "--no-auto-refresh"]);
+    let json = assert_valid_json(&output, "commit prep in shallow clone");
+
+    let git_context = &json["git_context"];
+    assert_eq!(git_context["branch"], "feature");
+    assert_eq!(git_context["shallow_clone"], true, "{}", output);
+    assert!(git_context.get("base_branch").is_none(), "{}", output);
+    assert_eq!(json["summary"]["unstaged_files"], 1, "{}", output);
+}
@@ -4,6 +4,9 @@
 //! - `index generate [PATH]` - Generate/refresh the index
 //! - `index check` - Check if index is fresh
 //! - `index export [PATH]` - Export index to SQLite
+//! - `index compact [PATH]` - Fold committed overlay layers
+//!
+//! `--progress-format json` progress events are covered here too.
 
 #![allow(unused_imports)]
 
@@ -106,6 +109,87 @@ fn test_index_generate_with_max_depth() {
     );
 }
 
+#[test]
+fn test_index_generate_signatures_level() {
+    let repo = TestRepo::new();
+    repo.with_complex_callgraph();
+
+    let output =
+        repo.run_cli_success(&["index", "generate", "--level", "signatures", "-f", "json"]);
+    let json = assert_valid_json(&output, "index generate --level signatures");
+    assert_eq!(json["level"], "signatures");
+    assert!(json["symbols"].as_u64().unwrap_or(0) > 0, "{}", output);
+
+    // Signature-only summaries carry no call edges
+    let output = repo
+        .run_cli(&["query", "callgraph", "--stats-only", "-f", "json"])
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !output.status.success() || stdout.contains("\"total_call_edges\": 0"),
+        "Signatures level should not record call edges: {}",
+        stdout
+    );
+
+    // A later full pass enriches the same cache
+    let output = repo.run_cli_success(&["index", "generate", "--level", "full", "-f", "json"]);
+    let json = assert_valid_json(&output, "index generate --level full");
+    assert_eq!(json["level"], "full");
+
+    let output = repo.run_cli_success(&["query", "callgraph", "--stats-only", "-f", "json"]);
+    let json = assert_valid_json(&output, "query callgraph");
+    assert!(
+        json["total_call_edges"].as_u64().unwrap_or(0) > 0,
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_index_generate_shard_naming_hybrid() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/api/users.ts", "getUser", "return 1;")
+        .add_ts_function("src/db/users.ts", "loadUser", "return 2;");
+
+    let output = repo.run_cli_success(&[
+        "index",
+        "generate",
+        "--shard-naming",
+        "hybrid",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "index generate --shard-naming hybrid");
+    assert_eq!(json["shard_naming"], "hybrid");
+    assert_eq!(json["shard_name_collisions"], 0);
+
+    // Shards are named <path prefix>-<8 hash chars>
+    let cache_root = std::fs::read_dir(repo.path().join(".semfora-cache"))
+        .unwrap()
+        .flatten()
+        .find(|e| e.path().join("modules").is_dir())
+        .expect("cache directory")
+        .path();
+    let mut shards: Vec<String> = std::fs::read_dir(cache_root.join("modules"))
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    shards.sort();
+    assert_eq!(shards.len(), 2, "{:?}", shards);
+    for shard in &shards {
+        let stem = shard.trim_end_matches(".toon");
+        let (_, hash) = stem.rsplit_once('-').expect("hybrid shard name");
+        assert_eq!(hash.len(), 8, "{}", shard);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()), "{}", shard);
+    }
+
+    // Regenerating without the flag keeps the strategy
+    let output = repo.run_cli_success(&["index", "generate", "--force", "-f", "json"]);
+    let json = assert_valid_json(&output, "index generate --force");
+    assert_eq!(json["shard_naming"], "hybrid");
+}
+
 #[test]
 fn test_index_generate_text_format() {
     let repo = TestRepo::new();
@@ -257,6 +341,247 @@ fn test_index_export_no_index() {
     assert!(result.is_ok() || result.is_err());
 }
 
+// ============================================================================
+// PROGRESS EVENT TESTS
+// ============================================================================
+
+/// Parse the newline-delimited JSON progress events written to stderr
+fn progress_events(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("progress event should be JSON"))
+        .collect()
+}
+
+/// Assert counts never go backwards within a phase and no phase ends past its total
+fn assert_monotonic(events: &[serde_json::Value]) {
+    let mut last: Option<(&str, u64)> = None;
+    for event in events {
+        assert_eq!(event["event"], "progress", "unexpected event: {}", event);
+        let phase = event["phase"].as_str().unwrap();
+        let current = event["current"].as_u64().unwrap();
+        let total = event["total"].as_u64().unwrap();
+        assert!(
+            total == 0 || current <= total,
+            "count past total: {}",
+            event
+        );
+        assert!(event["percent"].as_f64().unwrap() <= 100.0);
+        if let Some((last_phase, last_current)) = last {
+            if last_phase == phase {
+                assert!(
+                    current >= last_current,
+                    "{} went from {} to {}",
+                    phase,
+                    last_current,
+                    current
+                );
+            }
+        }
+        last = Some((phase, current));
+    }
+}
+
+#[test]
+fn test_index_generate_emits_json_progress_events_in_order() {
+    let repo = TestRepo::new();
+    for i in 0..60 {
+        repo.add_ts_function(
+            &format!("src/mod{}.ts", i),
+            &format!("fn{}", i),
+            "return 1;",
+        );
+    }
+
+    let output = repo
+        .run_cli(&["index", "generate", "--progress-format", "json"])
+        .unwrap();
+    assert!(output.status.success());
+
+    let events = progress_events(&output.stderr);
+    assert!(!events.is_empty(), "expected progress events on stderr");
+    assert_monotonic(&events);
+
+    let phases: Vec<&str> = events
+        .iter()
+        .map(|e| e["phase"].as_str().unwrap())
+        .collect();
+    let collecting = phases
+        .iter()
+        .position(|p| *p == "Collecting files")
+        .unwrap();
+    let indexing = phases.iter().position(|p| *p == "Indexing files").unwrap();
+    assert!(collecting < indexing, "phases out of order: {:?}", phases);
+
+    let last_indexing = events
+        .iter()
+        .rfind(|e| e["phase"] == "Indexing files")
+        .unwrap();
+    assert_eq!(last_indexing["current"], 60);
+    assert_eq!(last_indexing["total"], 60);
+    assert_eq!(last_indexing["percent"], 100.0);
+
+    // stdout still carries only the command result
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("\"event\""));
+}
+
+#[test]
+fn test_index_export_emits_json_progress_events() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return helper();")
+        .add_ts_function("src/utils.ts", "helper", "return 2;");
+    repo.generate_index().unwrap();
+
+    let export_path = repo.path().join("export.sqlite");
+    let output = repo
+        .run_cli(&[
+            "index",
+            "export",
+            export_path.to_str().unwrap(),
+            "--progress-format",
+            "json",
+        ])
+        .unwrap();
+    assert!(output.status.success());
+
+    let events = progress_events(&output.stderr);
+    assert_monotonic(&events);
+    let phases: Vec<&str> = events
+        .iter()
+        .map(|e| e["phase"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases.first(), Some(&"creating_schema"));
+    assert_eq!(phases.last(), Some(&"finalizing"));
+}
+
+#[test]
+fn test_no_progress_overrides_progress_format() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+
+    let output = repo
+        .run_cli(&[
+            "index",
+            "generate",
+            "--progress-format",
+            "json",
+            "--no-progress",
+        ])
+        .unwrap();
+    assert!(output.status.success());
+    assert!(progress_events(&output.stderr).is_empty());
+}
+
+// ============================================================================
+// INDEX COMPACT TESTS
+// ============================================================================
+
+#[test]
+fn test_index_compact_folds_cached_layers() {
+    use semfora_engine::schema::SymbolInfo;
+    use semfora_engine::{LayerKind, LayeredIndex, SymbolState};
+
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    // Temp repos keep their cache in-tree; it must not make the tree dirty
+    repo.add_file(".gitignore", ".semfora-cache/\n");
+    repo.commit("initial");
+
+    let output = repo.run_cli_success(&["index", "compact", "-f", "text"]);
+    assert!(output.contains("No cached layers"), "{}", output);
+
+    // Layers as a server would persist them after an edit that is now committed
+    let symbol = |name: &str| SymbolInfo {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let mut index = LayeredIndex::new();
+    index
+        .base
+        .upsert("h_old".to_string(), SymbolState::active(symbol("old")));
+    index.working.delete("h_old");
+    index
+        .working
+        .upsert("h_new".to_string(), SymbolState::active(symbol("main")));
+    let cache = semfora_engine::cache::CacheDir::for_repo(repo.path()).unwrap();
+    cache.save_layered_index(&index).unwrap();
+
+    let output = repo.run_cli_success(&["index", "compact", "-f", "json"]);
+    let json = assert_valid_json(&output, "index compact");
+    assert_eq!(json["_type"], "index_compact");
+    assert_eq!(json["target"], "base");
+    assert_eq!(json["before"]["working_symbols"], 1);
+    assert_eq!(json["before"]["total_deleted"], 1);
+    assert_eq!(json["after"]["base_symbols"], 1);
+    assert_eq!(json["after"]["working_symbols"], 0);
+    assert_eq!(json["after"]["total_deleted"], 0);
+
+    let loaded = cache.load_layered_index().unwrap().unwrap();
+    assert!(loaded.layer(LayerKind::Base).get("h_new").is_some());
+    assert!(loaded.layer(LayerKind::Working).is_empty());
+}
+
+#[test]
+fn test_index_compact_requires_clean_tree() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    repo.commit("initial");
+    repo.add_ts_function("src/extra.ts", "extra", "return 2;");
+
+    let (_, stderr) = repo.run_cli_failure(&["index", "compact"]);
+    assert!(stderr.contains("uncommitted changes"), "{}", stderr);
+}
+
+#[test]
+fn test_index_generate_waits_for_lock() {
+    use std::process::Stdio;
+
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/main.ts", "main", "return 1;");
+    let cache = semfora_engine::cache::CacheDir::for_repo(repo.path()).unwrap();
+
+    // Another indexer holds the lock
+    let lock = cache.lock_index_within(Duration::ZERO).unwrap();
+
+    // With no wait allowed, a second indexer fails without writing
+    let output = repo
+        .cli_command(&["index", "generate", "--force"])
+        .env("SEMFORA_INDEX_LOCK_TIMEOUT_SECS", "0")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(14), "{}", stderr);
+    assert!(stderr.contains("in progress"), "{}", stderr);
+    assert!(!cache.symbol_index_path().exists());
+
+    // Otherwise it blocks until the holder finishes, then indexes
+    let mut waiter = repo
+        .cli_command(&["index", "generate", "--force"])
+        .env("SEMFORA_INDEX_LOCK_TIMEOUT_SECS", "60")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    thread::sleep(Duration::from_millis(500));
+    assert!(
+        waiter.try_wait().unwrap().is_none(),
+        "second indexer should wait for the lock"
+    );
+    assert!(!cache.symbol_index_path().exists());
+
+    drop(lock);
+    let output = waiter.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(cache.symbol_index_path().exists());
+}
+
 // ============================================================================
 // INDEX MAX AGE TESTS
 // ============================================================================
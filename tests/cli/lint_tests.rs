@@ -370,6 +370,52 @@ edition = "2021"
     assert!(json.is_object(), "Should return valid JSON object");
 }
 
+// ============================================================================
+// LINT FIX TESTS
+// ============================================================================
+
+#[test]
+fn test_lint_fix_counts_fixed_issues() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "Cargo.toml",
+        r#"[package]
+name = "test"
+version = "0.1.0"
+edition = "2021"
+"#,
+    );
+    repo.add_file(
+        "src/lib.rs",
+        "pub fn add(a:i32,b:i32)->i32{a+b}\n\npub fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\npub fn mul(a:i32,b:i32)->i32{a*b}\n",
+    );
+
+    let output = repo.run_cli_success(&[
+        "lint",
+        "fix",
+        "--linter",
+        "rustfmt",
+        "--dry-run",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "lint fix dry run");
+    assert_eq!(json["mode"], "dry-run");
+    assert_eq!(json["fixed"], 2, "{output}");
+    assert_eq!(json["manual"], 0, "{output}");
+
+    let output = repo.run_cli_success(&["lint", "fix", "--linter", "rustfmt", "-f", "json"]);
+    let json = assert_valid_json(&output, "lint fix");
+    assert_eq!(json["fixed"], 2, "{output}");
+    assert_eq!(json["manual"], 0, "{output}");
+    assert_eq!(json["remaining_issues"], serde_json::json!([]));
+    let source = std::fs::read_to_string(repo.path().join("src/lib.rs")).unwrap();
+    assert!(
+        source.contains("pub fn add(a: i32, b: i32) -> i32 {"),
+        "{source}"
+    );
+}
+
 // ============================================================================
 // LINT RECOMMEND TESTS
 // ============================================================================
@@ -487,3 +533,71 @@ edition = "2021"
 
     assert!(json.is_object(), "Should return valid JSON");
 }
+
+// ============================================================================
+// LANGUAGE FILTER TESTS
+// ============================================================================
+
+fn mixed_rust_js_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "Cargo.toml",
+        r#"[package]
+name = "test"
+version = "0.1.0"
+edition = "2021"
+"#,
+    );
+    repo.add_file("src/lib.rs", "pub fn rust_fn() {}");
+    repo.add_file("package.json", r#"{"name": "test", "version": "1.0.0"}"#);
+    repo.add_file(".eslintrc.json", r#"{"env": {"node": true}}"#);
+    repo.add_file("src/index.js", "console.log('hello');");
+    repo
+}
+
+#[test]
+fn test_lint_detect_lang_filter() {
+    let repo = mixed_rust_js_repo();
+
+    let output = repo.run_cli_success(&["lint", "detect", "--lang", "rust", "-f", "json"]);
+    let json = assert_valid_json(&output, "lint detect --lang rust");
+
+    let linters: Vec<&str> = json["linters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["linter"].as_str().unwrap())
+        .collect();
+    assert!(linters.contains(&"clippy"), "{linters:?}");
+    assert!(
+        linters.iter().all(|l| ["clippy", "rustfmt"].contains(l)),
+        "Only Rust linters should be selected: {linters:?}"
+    );
+}
+
+#[test]
+fn test_lint_scan_lang_filter_runs_only_rust_linters() {
+    let repo = mixed_rust_js_repo();
+
+    let output = repo.run_cli_success(&["lint", "scan", "--lang", "rust", "-f", "json"]);
+    let json = assert_valid_json(&output, "lint scan --lang rust");
+
+    for linter in json["linters"].as_array().unwrap() {
+        let name = linter["linter"].as_str().unwrap();
+        assert!(
+            ["clippy", "rustfmt"].contains(&name),
+            "Only Rust linters should run: {output}"
+        );
+    }
+    for issue in json["issues"].as_array().unwrap() {
+        assert!(["clippy", "rustfmt"].contains(&issue["linter"].as_str().unwrap()));
+    }
+}
+
+#[test]
+fn test_lint_lang_rejects_unknown_language() {
+    let repo = mixed_rust_js_repo();
+
+    let (_, stderr) = repo.run_cli_failure(&["lint", "detect", "--lang", "rust,cobol"]);
+    assert!(stderr.contains("unknown language `cobol`"), "{stderr}");
+}
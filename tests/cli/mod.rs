@@ -9,6 +9,7 @@ pub mod cache_tests;
 pub mod commit_tests;
 pub mod index_tests;
 pub mod lint_tests;
+pub mod paths_tests;
 pub mod query_tests;
 pub mod search_tests;
 // Security tests disabled - command hidden from CLI (kept in src/commands/security.rs for future use)
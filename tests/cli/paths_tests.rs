@@ -0,0 +1,140 @@
+//! Tests for the global `--paths` option
+//!
+//! File paths in output are relative to the repo root by default, so the
+//! same repo renders identically wherever it is checked out. `--paths
+//! absolute` restores machine-specific paths.
+
+use crate::common::{assert_valid_json, TestRepo};
+use serde_json::Value;
+
+/// Source of a function copied between modules
+const TOTALS: &str = r#"import { db } from "../db";
+
+export async function loadTotals(id: string) {
+    const rows = await db.query(id);
+    const total = rows.reduce((sum, r) => sum + r.amount, 0);
+    console.log(total);
+    await db.save(id, total);
+    return total;
+}
+"#;
+
+/// A repo with searchable and duplicated functions, indexed by absolute path
+fn checkout() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file("src/billing/totals.ts", TOTALS)
+        .add_file("src/orders/totals.ts", TOTALS)
+        .add_ts_function("src/api/users.ts", "fetchUsers", "return users;");
+    let root = repo.path_str();
+    repo.run_cli_success(&["index", "generate", &root]);
+    repo
+}
+
+/// Every file path reported in `json`, sorted
+fn file_fields(json: &Value) -> Vec<String> {
+    let mut files = Vec::new();
+    collect_file_fields(json, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_file_fields(json: &Value, files: &mut Vec<String>) {
+    match json {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    // Index entries abbreviate `file` to `f`
+                    Value::String(s) if matches!(key.as_str(), "file" | "primary_file" | "f") => {
+                        files.push(s.clone())
+                    }
+                    _ => collect_file_fields(value, files),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_file_fields(item, files);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Run `args` as JSON in both checkouts and return each side's file paths
+fn file_fields_in_both(a: &TestRepo, b: &TestRepo, args: &[&str]) -> (Vec<String>, Vec<String>) {
+    let run = |repo: &TestRepo| {
+        let output = repo.run_cli_success(args);
+        file_fields(&assert_valid_json(&output, &args.join(" ")))
+    };
+    (run(a), run(b))
+}
+
+#[test]
+fn test_relative_paths_identical_across_checkouts() {
+    let a = checkout();
+    let b = checkout();
+    assert_ne!(a.path(), b.path());
+
+    let commands: [&[&str]; 3] = [
+        &["search", "loadTotals", "--symbols", "-f", "json"],
+        &["search", "fetch users", "--related", "-f", "json"],
+        &["validate", "--duplicates", "-f", "json"],
+    ];
+    for args in commands {
+        let (files_a, files_b) = file_fields_in_both(&a, &b, args);
+        assert!(!files_a.is_empty(), "{:?} reported no files", args);
+        assert_eq!(files_a, files_b, "{:?} differs between checkouts", args);
+        for file in &files_a {
+            assert!(
+                file.starts_with("src/"),
+                "{:?} should report repo-relative paths, got {}",
+                args,
+                file
+            );
+        }
+    }
+}
+
+#[test]
+fn test_analyze_file_relative_to_repo_root() {
+    let a = checkout();
+    let b = checkout();
+
+    let analyze = |repo: &TestRepo| {
+        let file = repo.path().join("src/api/users.ts");
+        let output = repo.run_cli_success(&["analyze", &file.to_string_lossy(), "-f", "json"]);
+        assert_valid_json(&output, "analyze file")["file"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    assert_eq!(analyze(&a), "src/api/users.ts");
+    assert_eq!(analyze(&a), analyze(&b));
+}
+
+#[test]
+fn test_paths_absolute_option() {
+    let repo = checkout();
+    let root = repo.path().canonicalize().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "fetchUsers",
+        "--symbols",
+        "--paths",
+        "absolute",
+        "-f",
+        "json",
+    ]);
+    let files = file_fields(&assert_valid_json(&output, "absolute search"));
+
+    assert!(!files.is_empty(), "search reported no files: {}", output);
+    for file in files {
+        assert_eq!(
+            std::path::Path::new(&file).canonicalize().unwrap(),
+            root.join("src/api/users.ts"),
+            "expected an absolute path under the repo root"
+        );
+    }
+}
@@ -8,6 +8,12 @@
 //! - `query callers <hash>` - Get symbol callers
 //! - `query callgraph` - Get call graph
 //! - `query file <path>` - Get file symbols (--source for code)
+//! - `query module-usages <module>` - Get modules importing a module (--unused for orphans)
+//! - `query imports --file <path>` - Get a file's imports
+//! - `query exports --module <name>` - Get a module's exported symbols
+//! - `query tests-for <hash|file>` - Get tests covering code, or code a test exercises
+//! - `query hotspots` - Get the largest, most-called and most-changed code
+//! - `query owners [target]` - Get CODEOWNERS coverage and unowned files
 //! - `query languages` - List supported languages
 //!
 //! Note: Some query outputs may return TOON format even with -f json
@@ -15,8 +21,8 @@
 #![allow(unused_imports)]
 
 use crate::common::{
-    assert_contains, assert_symbol_exists, assert_valid_json, assert_valid_toon,
-    extract_symbol_hashes, extract_symbol_names, TestRepo,
+    assert_contains, assert_not_contains, assert_symbol_exists, assert_valid_json,
+    assert_valid_toon, extract_symbol_hashes, extract_symbol_names, TestRepo,
 };
 
 // ============================================================================
@@ -40,6 +46,24 @@ fn test_query_overview_basic() {
     );
 }
 
+#[test]
+fn test_query_overview_reports_generated_loc() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/proto/users.py",
+        "# Generated by the protocol buffer compiler.  DO NOT EDIT!\n# source: users.proto\nfrom google.protobuf import descriptor as _descriptor\n\ndef encode(user):\n    return user\n",
+    )
+    .add_ts_function("src/api/users.ts", "getUsers", "return [];");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "overview", "-f", "json"]);
+    let json = assert_valid_json(&output, "overview with generated code");
+    assert_eq!(json["generated"]["files"], 1, "{}", output);
+    assert_eq!(json["generated"]["lines"], 6, "{}", output);
+    let pct = json["generated"]["loc_pct"].as_f64().expect("loc_pct");
+    assert!(pct > 0.0 && pct < 100.0, "{}", output);
+}
+
 #[test]
 fn test_query_overview_with_modules() {
     let repo = TestRepo::new();
@@ -105,6 +129,131 @@ fn test_query_overview_toon_format() {
     assert_valid_toon(&output, "query overview toon");
 }
 
+#[test]
+fn test_query_overview_max_tokens() {
+    let repo = TestRepo::new();
+    for i in 0..60 {
+        repo.add_ts_function(
+            &format!("src/feature{}/handlers.ts", i),
+            &format!("handle{}", i),
+            "return 1;",
+        );
+    }
+    repo.add_ts_function("src/app/index.ts", "start", "return 1;");
+
+    repo.generate_index().unwrap();
+
+    let args = [
+        "query",
+        "overview",
+        "--modules",
+        "--max-modules",
+        "100",
+        "-f",
+        "toon",
+    ];
+    let full = repo.run_cli_success(&args);
+    let analyzer = semfora_engine::TokenAnalyzer::new();
+    assert!(analyzer.count_tokens(&full) > 300, "{}", full);
+
+    let mut budget_args = args.to_vec();
+    budget_args.extend(["--max-tokens", "300"]);
+    let output = repo.run_cli_success(&budget_args);
+
+    assert!(analyzer.count_tokens(&output) <= 300, "{}", output);
+    assert_contains(&output, "entry_points[", true, "entry points kept");
+    assert_contains(&output, "src/app/index.ts", true, "entry point kept");
+    assert_contains(&output, "  app,", true, "entry-point module kept");
+    assert_contains(&output, "budget:", true, "trimmed module note");
+}
+
+#[test]
+fn test_query_overview_min_lines_risk_breakdown() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/math.ts",
+        r#"export function identity(x: number) {
+  return x; }
+
+export function clampAll(values: number[], min: number, max: number) {
+  const result: number[] = [];
+  for (const v of values) {
+    if (v < min) {
+      result.push(min);
+    } else if (v > max) {
+      result.push(max);
+    } else {
+      result.push(v);
+    }
+  }
+  return result;
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let breakdown_total = |min_lines: &str| -> u64 {
+        let output =
+            repo.run_cli_success(&["query", "overview", "--min-lines", min_lines, "-f", "json"]);
+        let json = assert_valid_json(&output, "overview with --min-lines");
+        assert_eq!(
+            json["risk_min_lines"].as_u64(),
+            min_lines.parse().ok(),
+            "{}",
+            output
+        );
+        let breakdown = json["risk_breakdown"]
+            .as_object()
+            .unwrap_or_else(|| panic!("risk_breakdown missing: {}", output));
+        breakdown.values().filter_map(|v| v.as_u64()).sum()
+    };
+
+    // Both functions count with no real threshold; the 2-line one drops at 5
+    assert_eq!(breakdown_total("1"), 2);
+    assert_eq!(breakdown_total("5"), 1);
+
+    let output = repo.run_cli_success(&["query", "overview", "--min-lines", "5", "-f", "toon"]);
+    assert_contains(&output, "risk_min_lines: 5", true, "threshold noted");
+}
+
+#[test]
+fn test_query_overview_workspace_packages() {
+    let repo = TestRepo::new();
+    repo.add_file("pnpm-workspace.yaml", "packages:\n  - \"packages/*\"\n");
+    repo.add_file("packages/web/package.json", r#"{"name": "@acme/web"}"#);
+    repo.add_file("packages/api/package.json", r#"{"name": "@acme/api"}"#);
+    repo.add_ts_function("packages/web/src/page.ts", "renderPage", "return '';");
+    repo.add_ts_function("packages/api/src/server.ts", "handleRequest", "return 1;");
+    repo.add_ts_function("packages/api/src/routes.ts", "listRoutes", "return [];");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "overview", "-f", "json"]);
+    let json = assert_valid_json(&output, "workspace overview");
+    let packages = json["packages"]
+        .as_array()
+        .unwrap_or_else(|| panic!("packages missing: {}", output));
+    let names: Vec<&str> = packages.iter().filter_map(|p| p["name"].as_str()).collect();
+    assert_eq!(names, ["api", "web"], "{}", output);
+    assert_eq!(packages[0]["path"], "packages/api", "{}", output);
+    assert_eq!(packages[0]["files"], 3, "{}", output);
+    assert_eq!(packages[1]["files"], 2, "{}", output);
+
+    // Each package's src/ is its own module rather than one merged `src`
+    let output = repo.run_cli_success(&["search", "handleRequest", "--symbols", "-f", "json"]);
+    assert_contains(&output, "\"m\": \"api/src\"", true, "namespaced module");
+}
+
+#[test]
+fn test_query_overview_without_workspace_has_no_packages() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/index.ts", "main", "return 0;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "overview", "-f", "json"]);
+    let json = assert_valid_json(&output, "plain overview");
+    assert!(json.get("packages").is_none(), "{}", output);
+}
+
 // ============================================================================
 // QUERY MODULE TESTS
 // ============================================================================
@@ -363,6 +512,80 @@ export function level0() { return level1(); }
     }
 }
 
+#[test]
+fn test_query_callers_aggregates_partial_class_parts() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/Shop/Cart.cs",
+        r#"
+namespace Shop;
+
+public partial class Cart
+{
+    public int Total() => 0;
+}
+"#,
+    );
+    repo.add_file(
+        "src/Shop/Cart.Audit.cs",
+        r#"
+namespace Shop;
+
+public partial class Cart
+{
+    public void Audit() => Log();
+}
+"#,
+    );
+    repo.add_file(
+        "src/Shop/Checkout.cs",
+        r#"
+namespace Shop;
+
+public class Checkout
+{
+    public void Run()
+    {
+        var cart = new Cart();
+    }
+}
+"#,
+    );
+
+    repo.generate_index().unwrap();
+
+    let search_output = repo.run_cli_success(&["search", "Cart", "-f", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&search_output).unwrap();
+    let parts: Vec<String> = json["symbol_matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|m| m["symbol"] == "Cart" && m["kind"] == "class")
+        .map(|m| m["hash"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        parts.len(),
+        2,
+        "Each part is its own symbol: {}",
+        search_output
+    );
+    assert_ne!(parts[0], parts[1]);
+
+    // Whichever part `new Cart()` resolved to, both report the caller
+    for hash in &parts {
+        let output = repo.run_cli_success(&["query", "callers", hash, "-f", "json"]);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let callers: Vec<_> = json["callers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(callers, vec!["Run"], "callers of {}: {}", hash, output);
+        assert_eq!(json["parts"].as_array().unwrap().len(), 2);
+    }
+}
+
 // ============================================================================
 // QUERY CALLGRAPH TESTS
 // ============================================================================
@@ -408,6 +631,47 @@ export function helper() { return "help"; }
     assert!(!output.is_empty(), "Should show filtered callgraph");
 }
 
+#[test]
+fn test_query_callgraph_exclude_module() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/api/handlers.ts",
+        r#"
+import { helper } from '../utils';
+import { trace } from '../generated/trace';
+export function apiHandler() { trace(); return helper(); }
+"#,
+    )
+    .add_file(
+        "src/utils/index.ts",
+        r#"
+export function helper() { return "help"; }
+"#,
+    )
+    .add_file(
+        "src/generated/trace.ts",
+        r#"
+import { helper } from '../utils';
+export function trace() { return helper(); }
+"#,
+    );
+
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "callgraph",
+        "--exclude-module",
+        "generated",
+        "-f",
+        "json",
+    ]);
+    assert_valid_json(&output, "callgraph with excluded module");
+    assert_contains(&output, "apiHandler", true, "caller outside module kept");
+    assert_contains(&output, "helper", true, "callee outside module kept");
+    assert_not_contains(&output, "trace", "excluded module's symbols dropped");
+}
+
 #[test]
 fn test_query_callgraph_summary() {
     let repo = TestRepo::new();
@@ -449,6 +713,75 @@ fn test_query_file_basic() {
     );
 }
 
+#[test]
+fn test_query_file_nested_function() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/tasks.py",
+        r#"from functools import wraps
+
+
+def retrying(fn):
+    @wraps(fn)
+    def wrapper(*args, **kwargs):
+        for attempt in range(3):
+            try:
+                return fn(*args, **kwargs)
+            except IOError:
+                log_retry(attempt)
+        raise RuntimeError("gave up")
+
+    return wrapper
+
+
+def log_retry(attempt):
+    print(attempt)
+"#,
+    );
+
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "file", "src/tasks.py", "-f", "json"]);
+    let json = assert_valid_json(&output, "query file nested");
+    let symbols = json["symbols"].as_array().expect("symbols array");
+    let outer = symbols.iter().find(|s| s["name"] == "retrying").unwrap();
+    let inner = symbols.iter().find(|s| s["name"] == "wrapper").unwrap();
+    assert_eq!(inner["parent"], outer["hash"]);
+    assert_eq!(inner["parent_name"], "retrying");
+
+    // A line in the nested function resolves to it, and its calls are its own
+    let output = repo.run_cli_success(&[
+        "query",
+        "symbol",
+        "--file",
+        "src/tasks.py",
+        "--line",
+        "11",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "query symbol nested");
+    assert_eq!(json["s"], "wrapper");
+    assert_eq!(json["parent_name"], "retrying");
+
+    let log_retry = symbols.iter().find(|s| s["name"] == "log_retry").unwrap();
+    let output = repo.run_cli_success(&[
+        "query",
+        "callers",
+        log_retry["hash"].as_str().unwrap(),
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "callers of log_retry");
+    let callers: Vec<&str> = json["callers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["name"].as_str())
+        .collect();
+    assert_eq!(callers, vec!["wrapper"]);
+}
+
 #[test]
 fn test_query_file_with_source() {
     let repo = TestRepo::new();
@@ -519,6 +852,882 @@ fn test_query_languages_text_format() {
     assert!(!output.is_empty(), "Should have language list");
 }
 
+// ============================================================================
+// QUERY MODULE-USAGES TESTS
+// ============================================================================
+
+/// Repo where `app` imports `legacy.payments` through two barrel files
+fn barrel_chain_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/legacy/payments/charge.ts",
+        "export function charge(amount: number) { return amount; }\n",
+    )
+    .add_file(
+        "src/legacy/payments/refund.ts",
+        "export function refund(amount: number) { return -amount; }\n",
+    )
+    .add_file(
+        "src/legacy/payments/index.ts",
+        "export { charge } from './charge';\nexport * from './refund';\n",
+    )
+    .add_file("src/legacy/index.ts", "export * from './payments';\n")
+    .add_file(
+        "src/app/checkout.ts",
+        "import { charge, refund } from '../legacy';\n\nexport function checkout() { return charge(1) + refund(1); }\n",
+    )
+    .add_file(
+        "src/orphan/helpers.ts",
+        "export function unusedHelper() { return 1; }\n",
+    );
+    repo.generate_index().unwrap();
+    repo
+}
+
+#[test]
+fn test_query_module_usages_barrel_chain() {
+    let repo = barrel_chain_repo();
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "module-usages",
+        "src/legacy/payments",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "module-usages");
+    let importers = json["importers"].as_array().expect("importers array");
+
+    // Both names come through one import statement: counted once, not per barrel
+    let app = importers
+        .iter()
+        .find(|i| i["module"].as_str().is_some_and(|m| m.ends_with("app")))
+        .unwrap_or_else(|| panic!("app importer missing: {output}"));
+    assert_eq!(app["count"], 1, "app import double counted: {output}");
+    let names: Vec<&str> = app["names"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|n| n.as_str())
+        .collect();
+    assert_eq!(names, vec!["charge", "refund"]);
+    assert!(
+        app["via"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|v| v.as_str().is_some_and(|v| v.ends_with("legacy"))),
+        "barrel not reported in via: {output}"
+    );
+    assert_eq!(json["import_count"], 2, "unexpected counts: {output}");
+}
+
+#[test]
+fn test_query_module_usages_unused() {
+    let repo = barrel_chain_repo();
+
+    let output = repo.run_cli_success(&["query", "module-usages", "--unused", "-f", "json"]);
+    let json = assert_valid_json(&output, "module-usages --unused");
+    let modules: Vec<&str> = json["modules"]
+        .as_array()
+        .expect("modules array")
+        .iter()
+        .filter_map(|m| m["module"].as_str())
+        .collect();
+
+    assert!(
+        modules.iter().any(|m| m.ends_with("orphan")),
+        "orphan module not reported: {output}"
+    );
+    assert!(
+        !modules.iter().any(|m| m.contains("legacy")),
+        "imported module reported as unused: {output}"
+    );
+}
+
+// ============================================================================
+// QUERY API-SURFACE TESTS
+// ============================================================================
+
+#[test]
+fn test_query_api_surface() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/users/service.ts",
+        r#"export function getUser(id: string, includeDeleted: boolean) {
+  return lookup(id, includeDeleted);
+}
+
+function lookup(id: string, deleted: boolean) {
+  return null;
+}
+
+export class UserStore {
+  save(user: string) {
+    return user;
+  }
+}
+"#,
+    );
+    repo.add_file("src/users/limits.rs", "pub const MAX_USERS: usize = 100;\n");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "getUser", "--symbols", "-f", "json"]);
+    let json = assert_valid_json(&output, "module lookup");
+    let module = json["results"][0]["m"]
+        .as_str()
+        .unwrap_or_else(|| panic!("module missing: {}", output))
+        .to_string();
+
+    let output = repo.run_cli_success(&["query", "api-surface", &module, "-f", "json"]);
+    let json = assert_valid_json(&output, "api surface");
+    assert_eq!(json["_type"], "api_surface");
+    let functions = json["functions"].as_array().unwrap();
+    assert_eq!(functions.len(), 1, "private lookup excluded: {}", output);
+    assert_eq!(functions[0]["name"], "getUser");
+    assert_eq!(
+        functions[0]["params"],
+        serde_json::json!(["id: string", "includeDeleted: boolean"]),
+        "{}",
+        output
+    );
+    assert_eq!(json["constants"][0]["name"], "MAX_USERS", "{}", output);
+    assert_eq!(json["types_omitted"], 1, "{}", output);
+    assert!(json.get("types").is_none(), "{}", output);
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "api-surface",
+        &module,
+        "--include-types",
+        "-f",
+        "toon",
+    ]);
+    assert_contains(&output, "_type: api_surface", true, "toon header");
+    assert_contains(
+        &output,
+        "getUser,\"id: string, includeDeleted: boolean\"",
+        true,
+        "function row",
+    );
+    assert_contains(&output, "types[1]", true, "types listed");
+    assert_contains(&output, "UserStore,class", true, "type row");
+
+    let output = repo
+        .run_cli(&["query", "api-surface", "no.such.module"])
+        .unwrap();
+    assert!(!output.status.success(), "unknown module should fail");
+}
+
+// ============================================================================
+// QUERY IMPORTS / EXPORTS TESTS
+// ============================================================================
+
+#[test]
+fn test_query_imports_and_exports() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/auth/index.ts",
+        r#"import { useState } from "react";
+import { hashPassword } from "./crypto";
+
+export function login(user: string, password: string) {
+  const [token, setToken] = useState("");
+  setToken(hashPassword(password));
+  return token;
+}
+
+function internal() {
+  return 1;
+}
+"#,
+    );
+    repo.add_file(
+        "src/auth/crypto.ts",
+        "export function hashPassword(p: string) {\n  return p;\n}\n",
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "imports",
+        "--file",
+        "src/auth/index.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "query imports");
+    assert_eq!(json["_type"], "file_imports");
+    assert_eq!(
+        json["imports"],
+        serde_json::json!([
+            {"name": "useState", "source": "react"},
+            {"name": "hashPassword", "source": "./crypto"},
+        ]),
+        "{}",
+        output
+    );
+
+    let output = repo.run_cli_success(&["query", "exports", "--module", "auth", "-f", "json"]);
+    let json = assert_valid_json(&output, "query exports");
+    assert_eq!(json["_type"], "module_exports");
+    let names: Vec<&str> = json["exports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["hashPassword", "login"], "{}", output);
+
+    let output = repo.run_cli_success(&["query", "exports", "--module", "auth", "-f", "toon"]);
+    assert_contains(
+        &output,
+        "exports[2]{name,kind,hash,file,lines}",
+        true,
+        "toon rows",
+    );
+    assert_not_contains(&output, "internal", "unexported symbol");
+}
+
+// ============================================================================
+// QUERY COMPONENTS TESTS
+// ============================================================================
+
+/// AppShell renders Header and Sidebar; Header renders NavLink. Header and
+/// Sidebar both call the custom `useTheme` hook.
+fn react_fixture() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/hooks/useTheme.ts",
+        r#"import { useContext } from 'react';
+import { ThemeContext } from '../theme';
+
+export function useTheme() {
+  return useContext(ThemeContext);
+}
+"#,
+    )
+    .add_file(
+        "src/components/AppShell.tsx",
+        r#"import { Header } from './Header';
+import { Sidebar } from './Sidebar';
+
+export function AppShell() {
+  return (
+    <div>
+      <Header />
+      <Sidebar />
+    </div>
+  );
+}
+"#,
+    )
+    .add_file(
+        "src/components/Header.tsx",
+        r#"import { useTheme } from '../hooks/useTheme';
+import { NavLink } from './NavLink';
+
+export function Header() {
+  const theme = useTheme();
+  return (
+    <header className={theme}>
+      <NavLink href="/" />
+    </header>
+  );
+}
+"#,
+    )
+    .add_file(
+        "src/components/Sidebar.tsx",
+        r#"import { useContext } from 'react';
+import { ThemeContext } from '../theme';
+import { useTheme } from '../hooks/useTheme';
+
+export function Sidebar() {
+  const theme = useTheme();
+  const user = useContext(ThemeContext);
+  return <aside className={theme}>{user}</aside>;
+}
+"#,
+    )
+    .add_file(
+        "src/components/NavLink.tsx",
+        r#"export function NavLink({ href }: { href: string }) {
+  return <a href={href}>home</a>;
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+    repo
+}
+
+#[test]
+fn test_query_components_tree() {
+    let repo = react_fixture();
+
+    let output = repo.run_cli_success(&["query", "components", "AppShell", "-f", "json"]);
+    let json = assert_valid_json(&output, "component tree");
+    assert_eq!(json["_type"], "component_tree");
+    assert_eq!(json["nodes"], 4, "{}", output);
+    let tree = &json["tree"];
+    assert_eq!(tree["name"], "AppShell");
+    assert_eq!(tree["file"], "src/components/AppShell.tsx");
+    assert_eq!(tree["line"], 4);
+    let children: Vec<&str> = tree["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(children, vec!["Header", "Sidebar"], "{}", output);
+    assert_eq!(tree["children"][0]["children"][0]["name"], "NavLink");
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "components",
+        "AppShell",
+        "--depth",
+        "1",
+        "-f",
+        "toon",
+    ]);
+    assert_contains(
+        &output,
+        "nodes[3]{depth,name,file,line,risk,note}",
+        true,
+        "toon rows",
+    );
+    assert_contains(
+        &output,
+        "1,Header,src/components/Header.tsx,4,low,truncated",
+        true,
+        "depth limit marked",
+    );
+    assert_not_contains(&output, "NavLink", "below the depth limit");
+}
+
+#[test]
+fn test_query_components_hook_users() {
+    let repo = react_fixture();
+
+    let output = repo.run_cli_success(&["query", "components", "--hook", "useTheme", "-f", "json"]);
+    let json = assert_valid_json(&output, "hook usage");
+    assert_eq!(json["_type"], "hook_usage");
+    let users: Vec<&str> = json["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(users, vec!["Header", "Sidebar"], "{}", output);
+    assert_eq!(json["users"][0]["calls"][0]["line"], 5, "{}", output);
+
+    let output = repo.run_cli_success(&[
+        "query",
+        "components",
+        "--hook",
+        "useContext(ThemeContext)",
+        "-f",
+        "toon",
+    ]);
+    assert_contains(
+        &output,
+        "Sidebar,ThemeContext,src/components/Sidebar.tsx,7,low",
+        true,
+        "context argument",
+    );
+    assert_contains(&output, "components: 1 | calls: 1", true, "only components");
+}
+
+#[test]
+fn test_query_components_without_react() {
+    let repo = TestRepo::new();
+    repo.add_file("src/lib.rs", "pub fn render() -> u32 {\n    1\n}\n");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "components", "App"]);
+    assert_contains(
+        &output,
+        "no React components indexed",
+        true,
+        "non-React repo",
+    );
+}
+
+// ============================================================================
+// QUERY TESTS-FOR TESTS
+// ============================================================================
+
+/// TypeScript repo with a colocated `*.test.ts` file
+fn ts_test_links_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/billing/invoice.ts",
+        "export function total(items: number[]) {\n  return items.reduce((a, b) => a + b, 0);\n}\n",
+    )
+    .add_file(
+        "src/billing/invoice.test.ts",
+        "import { total } from './invoice';\n\ndescribe('total', () => {\n  it('sums items', () => {\n    expect(total([1, 2])).toBe(3);\n  });\n});\n",
+    )
+    .add_file(
+        "src/billing/format.ts",
+        "export function formatAmount(n: number) { return `$${n}`; }\n",
+    );
+    repo.generate_index().unwrap();
+    repo
+}
+
+/// Rust crate with an inline `#[cfg(test)]` module and an integration test
+fn rust_test_links_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .add_file("src/lib.rs", "pub mod billing;\n")
+    .add_file(
+        "src/billing.rs",
+        "pub fn total(n: u32) -> u32 {\n    n * 2\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_total() {\n        let got = total(2);\n        assert_eq!(got, 4);\n    }\n}\n",
+    )
+    .add_file(
+        "tests/billing_test.rs",
+        "use demo::billing::total;\n\n#[test]\nfn total_doubles() {\n    let got = total(3);\n    assert_eq!(got, 6);\n}\n",
+    );
+    repo.generate_index().unwrap();
+    repo
+}
+
+/// Hash of the first symbol named `name` in `file`
+fn symbol_hash(repo: &TestRepo, file: &str, name: &str) -> String {
+    let output = repo.run_cli_success(&["query", "file", file, "-f", "json"]);
+    let json = assert_valid_json(&output, "query file");
+    json["symbols"]
+        .as_array()
+        .expect("symbols array")
+        .iter()
+        .find(|s| s["name"] == name)
+        .and_then(|s| s["hash"].as_str())
+        .unwrap_or_else(|| panic!("{name} missing from {file}: {output}"))
+        .to_string()
+}
+
+fn test_files(json: &serde_json::Value) -> Vec<&str> {
+    json["tests"]
+        .as_array()
+        .expect("tests array")
+        .iter()
+        .filter_map(|t| t["file"].as_str())
+        .collect()
+}
+
+#[test]
+fn test_query_tests_for_typescript() {
+    let repo = ts_test_links_repo();
+
+    // Production file -> test file
+    let output =
+        repo.run_cli_success(&["query", "tests-for", "src/billing/invoice.ts", "-f", "json"]);
+    let json = assert_valid_json(&output, "tests-for production file");
+    assert_eq!(json["role"], "production");
+    let tests = json["tests"].as_array().unwrap();
+    assert!(
+        tests.iter().any(|t| t["file"]
+            .as_str()
+            .is_some_and(|f| f.ends_with("invoice.test.ts"))
+            && t["confidence"] == "call"),
+        "invoice.test.ts not linked by call: {output}"
+    );
+
+    // Production symbol -> test file
+    let hash = symbol_hash(&repo, "src/billing/invoice.ts", "total");
+    let output = repo.run_cli_success(&["query", "tests-for", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "tests-for symbol");
+    assert!(
+        test_files(&json)
+            .iter()
+            .any(|f| f.ends_with("invoice.test.ts")),
+        "total not linked to its test: {output}"
+    );
+
+    // Untested file
+    let output =
+        repo.run_cli_success(&["query", "tests-for", "src/billing/format.ts", "-f", "json"]);
+    let json = assert_valid_json(&output, "tests-for untested file");
+    assert!(
+        test_files(&json).is_empty(),
+        "format.ts has no tests: {output}"
+    );
+
+    // Test file -> production symbols
+    let output = repo.run_cli_success(&[
+        "query",
+        "tests-for",
+        "src/billing/invoice.test.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "tests-for test file");
+    assert_eq!(json["role"], "test");
+    assert!(
+        json["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["name"] == "total" && s["hash"] == hash.as_str()),
+        "test file does not exercise total: {output}"
+    );
+}
+
+#[test]
+fn test_query_tests_for_rust() {
+    let repo = rust_test_links_repo();
+
+    // Production symbol -> inline test module and integration test
+    let hash = symbol_hash(&repo, "src/billing.rs", "total");
+    let output = repo.run_cli_success(&["query", "tests-for", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "tests-for rust symbol");
+    let files = test_files(&json);
+    assert!(
+        files.iter().any(|f| f.ends_with("tests/billing_test.rs")),
+        "integration test not linked: {output}"
+    );
+    assert!(
+        files.iter().any(|f| f.ends_with("src/billing.rs")),
+        "inline test module not linked: {output}"
+    );
+
+    // Test symbol -> production symbol
+    let test_hash = symbol_hash(&repo, "tests/billing_test.rs", "total_doubles");
+    let output = repo.run_cli_success(&["query", "tests-for", &test_hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "tests-for rust test");
+    assert_eq!(json["role"], "test");
+    assert!(
+        json["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["hash"] == hash.as_str()),
+        "total_doubles does not exercise total: {output}"
+    );
+
+    // get_symbol carries the links too
+    let output = repo.run_cli_success(&["query", "symbol", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "query symbol");
+    assert!(
+        json["tested_by"].as_array().is_some_and(|t| !t.is_empty()),
+        "tested_by missing from symbol output: {output}"
+    );
+}
+
+// ============================================================================
+// QUERY HOTSPOTS TESTS
+// ============================================================================
+
+/// `charge.ts` with the given fee rate
+fn charge_source(fee: &str) -> String {
+    format!(
+        r#"import {{ formatAmount }} from './format';
+
+export function charge(amount: number, card: string) {{
+  if (amount <= 0) {{
+    throw new Error('invalid amount');
+  }}
+  const fee = amount * {fee};
+  const total = amount + fee;
+  console.log(formatAmount(total));
+  return {{ card, total }};
+}}
+"#
+    )
+}
+
+/// `refund.ts` with the given local name for the reversed charge
+fn refund_source(local: &str) -> String {
+    format!(
+        r#"import {{ formatAmount }} from './format';
+import {{ charge }} from './charge';
+
+export function refund(amount: number, card: string) {{
+  const {local} = charge(-amount, card);
+  return formatAmount({local}.total);
+}}
+"#
+    )
+}
+
+/// TypeScript repo where `charge` is largest, `formatAmount` most called and
+/// `charge.ts` most changed
+fn hotspots_repo(with_git: bool) -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/billing/format.ts",
+        "export function formatAmount(n: number) {\n  return `$${n}`;\n}\n",
+    )
+    .add_file("src/billing/charge.ts", &charge_source("0.03"))
+    .add_file("src/billing/refund.ts", &refund_source("reversal"))
+    .add_file(
+        "src/reports/summary.ts",
+        r#"import { formatAmount } from '../billing/format';
+
+export function summarize(totals: number[]) {
+  return formatAmount(totals.reduce((a, b) => a + b, 0));
+}
+"#,
+    );
+    if with_git {
+        repo.init_git().commit("initial");
+        repo.add_file("src/billing/charge.ts", &charge_source("0.029"))
+            .commit("lower fee");
+        repo.add_file("src/billing/charge.ts", &charge_source("0.025"))
+            .add_file("src/billing/refund.ts", &refund_source("reversed"))
+            .commit("rename reversal");
+    }
+    repo.generate_index().unwrap();
+    repo
+}
+
+/// `(name, value)` pairs of a ranking
+fn ranking<'a>(json: &'a serde_json::Value, key: &str, name: &str) -> Vec<(&'a str, u64)> {
+    json[key]
+        .as_array()
+        .unwrap_or_else(|| panic!("{key} missing: {json}"))
+        .iter()
+        .map(|e| (e[name].as_str().unwrap(), e["value"].as_u64().unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_query_hotspots_rankings() {
+    let repo = hotspots_repo(true);
+
+    let output = repo.run_cli_success(&["query", "hotspots", "-f", "json"]);
+    let json = assert_valid_json(&output, "query hotspots");
+    assert_eq!(json["_type"], "hotspots");
+
+    assert_eq!(
+        ranking(&json, "largest", "symbol"),
+        vec![
+            ("charge", 9),
+            ("refund", 4),
+            ("formatAmount", 3),
+            ("summarize", 3)
+        ]
+    );
+    assert_eq!(
+        ranking(&json, "most_called", "symbol"),
+        vec![("formatAmount", 3), ("charge", 1)]
+    );
+    assert_eq!(
+        ranking(&json, "most_changed", "file"),
+        vec![
+            ("src/billing/charge.ts", 3),
+            ("src/billing/refund.ts", 2),
+            ("src/billing/format.ts", 1),
+            ("src/reports/summary.ts", 1)
+        ]
+    );
+    assert_eq!(json["churn_window"], 500);
+
+    let largest = &json["largest"][0];
+    assert_eq!(largest["module"], "billing");
+    assert_eq!(largest["file"], "src/billing/charge.ts");
+    assert!(largest["risk"].is_string(), "risk missing: {largest}");
+
+    // --limit trims every ranking
+    let output = repo.run_cli_success(&["query", "hotspots", "--limit", "1", "-f", "json"]);
+    let json = assert_valid_json(&output, "query hotspots --limit");
+    assert_eq!(ranking(&json, "largest", "symbol"), vec![("charge", 9)]);
+    assert_eq!(ranking(&json, "most_changed", "file").len(), 1);
+
+    let output = repo.run_cli_success(&["query", "hotspots"]);
+    assert_contains(&output, "MOST CALLED", true, "text hotspots");
+}
+
+#[test]
+fn test_query_hotspots_without_git() {
+    let repo = hotspots_repo(false);
+
+    let output = repo.run_cli_success(&["query", "hotspots", "-f", "json"]);
+    let json = assert_valid_json(&output, "query hotspots without git");
+    assert_eq!(ranking(&json, "largest", "symbol")[0], ("charge", 9));
+    assert!(ranking(&json, "most_changed", "file").is_empty());
+    assert!(json["churn_window"].is_null());
+}
+
+#[test]
+fn test_query_overview_hotspots_opt_in() {
+    let repo = hotspots_repo(false);
+
+    let output = repo.run_cli_success(&["query", "overview", "-f", "json"]);
+    let json = assert_valid_json(&output, "overview");
+    assert!(json.get("hotspots").is_none(), "hotspots are opt-in");
+
+    let output = repo.run_cli_success(&["query", "overview", "--hotspots", "-f", "json"]);
+    let json = assert_valid_json(&output, "overview --hotspots");
+    assert_eq!(
+        ranking(&json["hotspots"], "most_called", "symbol")[0],
+        ("formatAmount", 3)
+    );
+
+    let output = repo.run_cli_success(&["query", "overview", "--hotspots", "-f", "toon"]);
+    assert_contains(&output, "hotspots:", true, "toon overview --hotspots");
+}
+
+#[test]
+fn test_query_symbol_history() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(
+        "src/pricing.ts",
+        "export function other() {\n  return 0;\n}\n\nexport function total(a: number) {\n  return a;\n}\n",
+    );
+    repo.commit("add total");
+    repo.add_file(
+        "src/pricing.ts",
+        "export function other() {\n  return 1;\n}\n\nexport function total(a: number) {\n  return a;\n}\n",
+    );
+    repo.commit("change other");
+    repo.add_file(
+        "src/pricing.ts",
+        "export function other() {\n  return 1;\n}\n\nexport function total(a: number) {\n  return a * 2;\n}\n",
+    );
+    repo.commit("double total");
+    repo.generate_index().unwrap();
+
+    let hash = symbol_hash(&repo, "src/pricing.ts", "total");
+    let output = repo.run_cli_success(&["query", "symbol-history", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "query symbol-history");
+    assert_eq!(json["_type"], "symbol_history");
+    assert_eq!(json["symbol"], "total");
+
+    // Both commits touching `total` appear, newest first; the one that only
+    // changed `other` doesn't
+    let subjects: Vec<&str> = json["commits"]
+        .as_array()
+        .expect("commits array")
+        .iter()
+        .filter_map(|c| c["subject"].as_str())
+        .collect();
+    assert_eq!(subjects, ["double total", "add total"]);
+    assert_eq!(json["commits"][0]["change"], "modified");
+    assert_eq!(json["commits"][1]["change"], "added");
+    assert_eq!(json["commits"][1]["file"], "src/pricing.ts");
+
+    let output = repo.run_cli_success(&["query", "symbol-history", &hash, "--limit", "1"]);
+    assert_contains(&output, "double total", true, "text symbol-history");
+    assert!(!output.contains("add total"), "--limit ignored: {output}");
+
+    let (_, stderr) = repo.run_cli_failure(&["query", "symbol-history", "deadbeef"]);
+    assert_contains(&stderr, "not found", false, "unknown hash");
+}
+
+// ============================================================================
+// QUERY OWNERS TESTS
+// ============================================================================
+
+/// Repo with a CODEOWNERS file owning `src/` and `src/payments/`, leaving
+/// `scripts/` unowned
+fn owners_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        ".github/CODEOWNERS",
+        "/src/ @org/backend\n/src/payments/ @org/payments @alice\n",
+    )
+    .add_ts_function("src/api/user.ts", "getUser", "return { id: 1 };")
+    .add_ts_function("src/payments/charge.ts", "charge", "return 1;")
+    .add_ts_function("src/payments/refund.ts", "refund", "return -1;")
+    .add_ts_function("scripts/build.ts", "build", "return 0;");
+    repo
+}
+
+#[test]
+fn test_query_owners_coverage_and_unowned_files() {
+    let repo = owners_repo();
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["query", "owners", "-f", "json"]);
+    let json = assert_valid_json(&output, "query owners");
+    assert_eq!(json["_type"], "owners");
+    assert_eq!(json["codeowners"], ".github/CODEOWNERS");
+    assert_eq!(json["files"], 4);
+    assert_eq!(json["owned_files"], 3);
+    assert_eq!(json["coverage"], 75.0);
+    assert_eq!(
+        json["unowned_files"],
+        serde_json::json!(["scripts/build.ts"])
+    );
+    let owners: Vec<(&str, u64)> = json["owners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| (o["owner"].as_str().unwrap(), o["files"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        owners,
+        vec![("@alice", 2), ("@org/payments", 2), ("@org/backend", 1)]
+    );
+    assert!(json.get("authors").is_none(), "blame is opt-in");
+
+    // A directory target narrows the summary
+    let output = repo.run_cli_success(&["query", "owners", "src/payments", "-f", "json"]);
+    let json = assert_valid_json(&output, "query owners src/payments");
+    assert_eq!(json["files"], 2);
+    assert_eq!(json["coverage"], 100.0);
+
+    let output = repo.run_cli_success(&["query", "owners"]);
+    assert_contains(&output, "UNOWNED FILES (1)", true, "text owners");
+}
+
+#[test]
+fn test_symbol_and_file_queries_show_owners() {
+    let repo = owners_repo();
+    repo.generate_index().unwrap();
+
+    let hash = symbol_hash(&repo, "src/payments/charge.ts", "charge");
+    let output = repo.run_cli_success(&["query", "symbol", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "query symbol");
+    assert_eq!(
+        json["owners"],
+        serde_json::json!(["@org/payments", "@alice"])
+    );
+
+    let output = repo.run_cli_success(&["query", "file", "scripts/build.ts", "-f", "json"]);
+    let json = assert_valid_json(&output, "query file");
+    assert!(json.get("owners").is_none(), "unowned file: {json}");
+
+    let output = repo.run_cli_success(&["query", "file", "src/api/user.ts", "-f", "toon"]);
+    assert_contains(
+        &output,
+        "owners: \"@org/backend\"",
+        true,
+        "toon file owners",
+    );
+}
+
+#[test]
+fn test_index_with_blame_attributes_symbols_to_authors() {
+    let repo = owners_repo();
+    repo.init_git().commit("initial");
+    repo.add_file(
+        "src/payments/charge.ts",
+        "export function charge() {\n  const fee = 2;\n  return fee;\n}\n",
+    )
+    .git(&["add", "-A"])
+    .git(&["-c", "user.name=Bo Other", "commit", "-m", "rewrite charge"]);
+    repo.run_cli_success(&["index", "generate", "--with-blame"]);
+
+    let hash = symbol_hash(&repo, "src/payments/charge.ts", "charge");
+    let output = repo.run_cli_success(&["query", "symbol", &hash, "-f", "json"]);
+    let json = assert_valid_json(&output, "query symbol with blame");
+    assert_eq!(json["author"]["author"], "Bo Other");
+    assert_eq!(json["author"]["total"], 4);
+
+    let output = repo.run_cli_success(&["query", "owners", "-f", "json"]);
+    let json = assert_valid_json(&output, "query owners with blame");
+    assert_eq!(json["authors"][0]["author"], "Test User");
+    assert_eq!(json["authors"][0]["symbols"], 3);
+}
+
 // ============================================================================
 // FORMAT CONSISTENCY TESTS
 // ============================================================================
@@ -5,13 +5,20 @@
 //! - `--symbols` or `-s`: Exact symbol name matching
 //! - `--related` or `-r`: BM25 conceptual search
 //! - `--raw`: Regex patterns in comments/strings
+//! - `--flag`: Symbols gated by a feature flag
+//! - `--deprecated`: Symbols marked deprecated
+//! - `--panics`: Symbols that can panic or throw
+//!
+//! `--in-file` confines any mode to a single file, `--package` to one
+//! workspace package, `--path-glob` to matching paths; `--exclude-module`
+//! leaves modules out.
 
 #![allow(unused_imports)]
 #![allow(unused_variables)]
 
 use crate::common::{
-    assert_contains, assert_symbol_exists, assert_valid_json, assert_valid_toon,
-    extract_symbol_names, TestRepo,
+    assert_contains, assert_not_contains, assert_symbol_exists, assert_valid_json,
+    assert_valid_toon, extract_symbol_names, TestRepo,
 };
 
 // ============================================================================
@@ -278,6 +285,775 @@ const errorHandler = () => {};
     );
 }
 
+// ============================================================================
+// RAW FALLBACK (hybrid mode)
+// ============================================================================
+
+fn repo_with_comment_only_text() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/db.ts",
+        r#"// retry budget exceeded when the pool drains
+export function connectDatabase(url: string) {
+    return open(url);
+}
+"#,
+    );
+    repo
+}
+
+#[test]
+fn test_search_fallback_skipped_on_index_hit() {
+    let repo = repo_with_comment_only_text();
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "connectDatabase", "-f", "json"]);
+    let json = assert_valid_json(&output, "indexed hit");
+    assert!(json["symbol_count"].as_u64().unwrap() > 0, "{output}");
+    assert!(json.get("raw_fallback").is_none(), "{output}");
+}
+
+#[test]
+fn test_search_fallback_fires_on_zero_hits() {
+    let repo = repo_with_comment_only_text();
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "retry budget", "-f", "json"]);
+    let json = assert_valid_json(&output, "raw fallback");
+    assert_eq!(json["symbol_count"], 0, "{output}");
+    assert_eq!(json["related_count"], 0, "{output}");
+    assert_eq!(json["raw_fallback_count"], 1, "{output}");
+    let hit = &json["raw_fallback"][0];
+    assert_eq!(hit["source"], "raw_fallback");
+    assert_eq!(hit["file"], "src/db.ts");
+    assert_eq!(hit["line"], 1);
+
+    // The fallback has its own cap
+    let capped = repo.run_cli_success(&[
+        "search",
+        "retry budget",
+        "--fallback-limit",
+        "0",
+        "-f",
+        "json",
+    ]);
+    let capped = assert_valid_json(&capped, "capped fallback");
+    assert_eq!(capped["raw_fallback_count"], 0, "{capped}");
+}
+
+#[test]
+fn test_search_fallback_disabled() {
+    let repo = repo_with_comment_only_text();
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "retry budget", "--no-fallback", "-f", "json"]);
+    let json = assert_valid_json(&output, "fallback disabled");
+    assert!(json.get("raw_fallback").is_none(), "{output}");
+}
+
+#[test]
+fn test_search_fallback_skips_symbol_like_query() {
+    let repo = repo_with_comment_only_text();
+    repo.generate_index().unwrap();
+
+    // A missing symbol is reported as missing, not grepped for
+    let output = repo.run_cli_success(&["search", "missingHandler", "-f", "json"]);
+    let json = assert_valid_json(&output, "symbol-like query");
+    assert!(json.get("raw_fallback").is_none(), "{output}");
+}
+
+// ============================================================================
+// FEATURE FLAG SEARCH (--flag)
+// ============================================================================
+
+/// Symbol names from a flag search result (index entries use short keys)
+fn flag_result_names(json: &serde_json::Value) -> Vec<String> {
+    json["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| r["s"].as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_search_flag_lists_gated_symbols() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/lib.rs",
+        r#"#[cfg(feature = "beta")]
+pub fn beta_exporter() -> u32 {
+    1
+}
+
+pub fn stable_exporter() -> u32 {
+    2
+}
+"#,
+    );
+    repo.add_file(
+        "src/checkout.js",
+        r#"export function renderCheckout(flags) {
+  if (flags.isEnabled('beta')) {
+    return 'new';
+  }
+  return 'old';
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "--flag", "beta", "-f", "json"]);
+    let symbols = flag_result_names(&assert_valid_json(&output, "flag search"));
+
+    assert!(symbols.contains(&"beta_exporter".to_string()), "{}", output);
+    assert!(
+        symbols.contains(&"renderCheckout".to_string()),
+        "{}",
+        output
+    );
+    assert!(
+        !symbols.contains(&"stable_exporter".to_string()),
+        "{}",
+        output
+    );
+
+    // The query narrows results by name
+    let output = repo.run_cli_success(&["search", "checkout", "--flag", "beta", "-f", "json"]);
+    let symbols = flag_result_names(&assert_valid_json(&output, "flag search"));
+    assert_eq!(symbols, vec!["renderCheckout".to_string()], "{}", output);
+}
+
+// ============================================================================
+// DEPRECATED SYMBOL SEARCH (--deprecated)
+// ============================================================================
+
+#[test]
+fn test_search_deprecated_lists_marked_symbols() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/lib.rs",
+        r#"#[deprecated(since = "0.4.0", note = "use save_v2 instead")]
+pub fn save() -> u32 {
+    1
+}
+
+pub fn save_v2() -> u32 {
+    2
+}
+"#,
+    );
+    repo.add_file(
+        "src/format.js",
+        r#"/**
+ * @deprecated Use formatDateTime instead.
+ */
+export function formatDate(d) {
+  return d.toISOString();
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "--deprecated", "-f", "json"]);
+    let json = assert_valid_json(&output, "deprecated search");
+    let results = json["results"].as_array().expect("results array");
+    let note_of = |name: &str| {
+        results
+            .iter()
+            .find(|r| r["s"] == name)
+            .map(|r| (r["dep"].as_bool(), r["depm"].as_str().map(String::from)))
+    };
+
+    assert_eq!(
+        note_of("save"),
+        Some((Some(true), Some("use save_v2 instead".to_string()))),
+        "{}",
+        output
+    );
+    assert_eq!(
+        note_of("formatDate"),
+        Some((Some(true), Some("Use formatDateTime instead.".to_string()))),
+        "{}",
+        output
+    );
+    assert_eq!(note_of("save_v2"), None, "{}", output);
+
+    // Text output shows the note under each symbol
+    let output = repo.run_cli_success(&["search", "save", "--deprecated"]);
+    assert!(output.contains("deprecated[1]:"), "{}", output);
+    assert!(output.contains("note: use save_v2 instead"), "{}", output);
+}
+
+// ============================================================================
+// PANIC SEARCH (--panics)
+// ============================================================================
+
+#[test]
+fn test_search_panics_lists_panic_points() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/lib.rs",
+        r#"pub fn load(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+pub fn load_or_default(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+"#,
+    );
+    repo.add_file(
+        "src/parse.js",
+        r#"export function parseSafe(text) {
+  try {
+    if (!text) throw new Error("empty");
+    return JSON.parse(text);
+  } catch (e) {
+    return null;
+  }
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "--panics", "-f", "json"]);
+    let json = assert_valid_json(&output, "panic search");
+    let results = json["results"].as_array().expect("results array");
+    let points_of = |name: &str| {
+        results
+            .iter()
+            .find(|r| r["s"] == name)
+            .map(|r| r["points"].as_array().cloned().unwrap_or_default())
+    };
+
+    let load = points_of("load").expect("load listed");
+    assert_eq!(load.len(), 1, "{}", output);
+    assert_eq!(load[0]["line"], 2);
+    assert_eq!(load[0]["kind"], "unwrap");
+
+    let parse = points_of("parseSafe").expect("parseSafe listed");
+    assert_eq!(parse[0]["kind"], "throw");
+    assert_eq!(parse[0]["in_try"], true);
+
+    assert!(points_of("load_or_default").is_none(), "{}", output);
+
+    // Text output lists each point under its symbol
+    let output = repo.run_cli_success(&["search", "parse", "--panics"]);
+    assert!(output.contains("panics[1]:"), "{}", output);
+    assert!(output.contains("line 3: throw (in try)"), "{}", output);
+
+    // The count is also a facet
+    let output = repo.run_cli_success(&["search", "--facet", "panic_points>0", "-f", "json"]);
+    let json = assert_valid_json(&output, "facet search");
+    assert_eq!(json["count"], 2, "{}", output);
+}
+
+// ============================================================================
+// EMBEDDED SQL SEARCH (--sql)
+// ============================================================================
+
+#[test]
+fn test_search_sql_lists_queries_touching_table() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/users.ts",
+        r#"export async function findActiveUsers(db: Db) {
+  return db.query("SELECT * FROM users WHERE active = 1");
+}
+
+export async function countOrders(db: Db) {
+  return db.query(`
+    SELECT count(*) FROM orders
+    JOIN Users ON users.id = orders.user_id
+  `);
+}
+
+export function heading(): string {
+  return "Select a user from the list";
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "--sql", "users", "-f", "json"]);
+    let json = assert_valid_json(&output, "sql search");
+    assert_eq!(json["table"], "users");
+    let results = json["results"].as_array().expect("results array");
+    let names: Vec<_> = results.iter().map(|r| r["s"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["findActiveUsers", "countOrders"], "{}", output);
+
+    let queries = results[0]["queries"].as_array().expect("queries");
+    assert_eq!(queries[0]["query"], "SELECT * FROM users WHERE active = 1");
+    assert_eq!(queries[0]["tables"], serde_json::json!(["users"]));
+    assert_eq!(queries[0]["line"], 2);
+
+    // Only the table asked for
+    let output = repo.run_cli_success(&["search", "--sql", "orders"]);
+    assert!(output.contains("results[1]:"), "{}", output);
+    assert!(output.contains("countOrders"), "{}", output);
+    assert!(
+        output.contains("line 6: SELECT count(*) FROM orders"),
+        "{}",
+        output
+    );
+}
+
+// ============================================================================
+// FACET SEARCH (--facet)
+// ============================================================================
+
+#[test]
+fn test_search_facet_filters_async_and_error_handling() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/users.ts",
+        r#"export async function loadUser(id: string) {
+  const res = await fetch(`/users/${id}`);
+  return await res.json();
+}
+
+export async function saveUser(user: User) {
+  try {
+    await fetch('/users', { method: 'POST' });
+  } catch (e) {
+  }
+}
+
+export function userName(user: User) {
+  return user.name;
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let names = |args: &[&str]| -> Vec<String> {
+        let output = repo.run_cli_success(args);
+        let json = assert_valid_json(&output, "facet search");
+        let mut names: Vec<String> = json["results"]
+            .as_array()
+            .expect("results array")
+            .iter()
+            .filter_map(|r| r["s"].as_str().map(String::from))
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(&[
+            "search",
+            "--facet",
+            "is_async",
+            "--facet",
+            "io_calls_outside_try>0",
+            "-f",
+            "json"
+        ]),
+        vec!["loadUser"]
+    );
+    assert_eq!(
+        names(&["search", "--facet", "has_empty_catch", "-f", "json"]),
+        vec!["saveUser"]
+    );
+    assert_eq!(
+        names(&["search", "--facet", "awaits_count>=1", "-f", "json"]),
+        vec!["loadUser", "saveUser"]
+    );
+
+    // Unknown facets are rejected
+    let output = repo.run_cli(&["search", "--facet", "is_slow"]).unwrap();
+    assert!(!output.status.success());
+}
+
+// ============================================================================
+// FILE SCOPE (--in-file)
+// ============================================================================
+
+/// `(symbol, file)` pairs from a symbol search result
+fn symbol_result_files(json: &serde_json::Value) -> Vec<(String, String)> {
+    json["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|r| Some((r["s"].as_str()?.into(), r["f"].as_str()?.into())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_search_in_file_confines_results() {
+    let repo = TestRepo::new();
+    // More matches elsewhere than the default limit of 20
+    for i in 0..25 {
+        repo.add_ts_function(
+            &format!("src/jobs/job{}.ts", i),
+            &format!("doSomething{}", i),
+            "return 1;",
+        );
+    }
+    repo.add_file(
+        "src/zz/foo.ts",
+        r#"export function doSomething() {
+  return 1;
+}
+
+export function doSomethingElse() {
+  return 2;
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "doSomething",
+        "--symbols",
+        "--in-file",
+        "src/zz/foo.ts",
+        "-f",
+        "json",
+    ]);
+    let mut results = symbol_result_files(&assert_valid_json(&output, "in-file search"));
+    results.sort();
+    assert_eq!(
+        results,
+        vec![
+            ("doSomething".to_string(), "src/zz/foo.ts".to_string()),
+            ("doSomethingElse".to_string(), "src/zz/foo.ts".to_string()),
+        ],
+        "{}",
+        output
+    );
+
+    // Intersects with the other filters
+    let output = repo.run_cli_success(&[
+        "search",
+        "doSomething",
+        "--symbols",
+        "--in-file",
+        "src/zz/foo.ts",
+        "--module",
+        "jobs",
+        "-f",
+        "json",
+    ]);
+    let results = symbol_result_files(&assert_valid_json(&output, "in-file search"));
+    assert!(results.is_empty(), "{}", output);
+
+    // Hybrid search is scoped too
+    let output = repo.run_cli_success(&[
+        "search",
+        "doSomethingElse",
+        "--in-file",
+        "src/zz/foo.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "in-file hybrid search");
+    for section in ["symbol_matches", "related_code"] {
+        for entry in json[section].as_array().into_iter().flatten() {
+            assert_eq!(entry["file"], "src/zz/foo.ts", "{}", output);
+        }
+    }
+}
+
+#[test]
+fn test_search_in_file_empty_when_symbol_elsewhere() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/api/users.ts", "fetchUsers", "return [];")
+        .add_ts_function("src/foo.ts", "doSomething", "return 1;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "fetchUsers",
+        "--symbols",
+        "--in-file",
+        "src/foo.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "in-file search");
+    assert_eq!(json["count"], 0, "{}", output);
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "fetchUsers",
+        "--raw",
+        "--in-file",
+        "src/foo.ts",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "in-file raw search");
+    assert_eq!(json["count"], 0, "{}", output);
+}
+
+// ============================================================================
+// WORKSPACE PACKAGE (--package)
+// ============================================================================
+
+#[test]
+fn test_search_package_scope() {
+    let repo = TestRepo::new();
+    repo.add_file("pnpm-workspace.yaml", "packages:\n  - \"packages/*\"\n");
+    repo.add_file("packages/web/package.json", r#"{"name": "@acme/web"}"#);
+    repo.add_file("packages/api/package.json", r#"{"name": "@acme/api"}"#);
+    repo.add_ts_function("packages/web/src/users.ts", "fetchUsers", "return [];");
+    repo.add_ts_function(
+        "packages/api/src/users.ts",
+        "fetchUsersFromDb",
+        "return [];",
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "fetchUsers",
+        "--symbols",
+        "--package",
+        "api",
+        "-f",
+        "json",
+    ]);
+    assert_contains(&output, "fetchUsersFromDb", true, "api symbol kept");
+    assert_not_contains(&output, "packages/web/", "web files dropped");
+
+    // The declared (scoped) name and the directory work too
+    for name in ["@acme/web", "packages/web"] {
+        let output = repo.run_cli_success(&[
+            "search",
+            "fetchUsers",
+            "--symbols",
+            "--package",
+            name,
+            "-f",
+            "json",
+        ]);
+        assert_contains(&output, "packages/web/src/users.ts", true, name);
+        assert_not_contains(&output, "fetchUsersFromDb", name);
+    }
+
+    let output = repo
+        .run_cli(&["search", "fetchUsers", "--package", "mobile"])
+        .unwrap();
+    assert!(!output.status.success(), "unknown package should fail");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_contains(
+        &stderr,
+        "packages: api, web",
+        true,
+        "available packages listed",
+    );
+}
+
+// ============================================================================
+// PATH SCOPE (--path-glob)
+// ============================================================================
+
+#[test]
+fn test_search_path_glob_scopes_symbols() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/payments/charge.ts", "processCharge", "return 1;")
+        .add_ts_function("src/billing/charge.ts", "processCharge", "return 2;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "processCharge",
+        "--symbols",
+        "--path-glob",
+        "src/payments/**",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "path-glob search");
+    assert_eq!(
+        symbol_result_files(&json),
+        vec![(
+            "processCharge".to_string(),
+            "src/payments/charge.ts".to_string()
+        )],
+        "{}",
+        output
+    );
+    assert_eq!(json["path_scope"][0], "src/payments/**", "{}", output);
+
+    // The text header echoes the scope too
+    let output = repo.run_cli_success(&[
+        "search",
+        "processCharge",
+        "--symbols",
+        "--path-glob",
+        "src/payments/**",
+    ]);
+    assert_contains(&output, "path_scope: src/payments/**", true, "scope echoed");
+    assert_not_contains(&output, "src/billing/", "billing dropped");
+}
+
+#[test]
+fn test_search_path_glob_negation_in_raw_mode() {
+    let repo = TestRepo::new();
+    repo.add_ts_function(
+        "src/app.ts",
+        "renderApp",
+        "// TODO: theme support\nreturn 1;",
+    )
+    .add_ts_function(
+        "vendor/lib/widget.ts",
+        "renderWidget",
+        "// TODO: upstream fix\nreturn 2;",
+    );
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "TODO",
+        "--raw",
+        "--path-glob",
+        "!vendor/**",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "negated path-glob raw search");
+    assert_eq!(json["count"], 1, "{}", output);
+    assert_contains(&output, "src/app.ts", true, "app match kept");
+    assert_not_contains(&output, "vendor/lib/widget.ts", "vendored path excluded");
+}
+
+// ============================================================================
+// GENERATED CODE
+// ============================================================================
+
+#[test]
+fn test_search_tags_generated_hits() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/api/users_pb.js",
+        "export function encodeUser(user) { return JSON.stringify(user); }\n",
+    )
+    .add_file(
+        "src/api/users.js",
+        "// Wraps the generated protobuf encoder.\nexport function encodeUserName(user) { return user.name; }\n",
+    );
+    repo.generate_index().unwrap();
+
+    // Generated hits are kept, not dropped
+    let output = repo.run_cli_success(&["search", "encodeUser", "--symbols"]);
+    assert_contains(&output, "users_pb.js", true, "generated hit kept");
+    let tagged: Vec<&str> = output
+        .lines()
+        .filter(|l| l.contains("[generated]"))
+        .collect();
+    assert_eq!(
+        tagged.len(),
+        1,
+        "only the generated hit is tagged: {}",
+        output
+    );
+    assert!(tagged[0].contains("users_pb.js"), "{}", output);
+
+    let output = repo.run_cli_success(&["search", "encodeUser", "--symbols", "-f", "json"]);
+    let json = assert_valid_json(&output, "symbol search with generated hit");
+    let results = json["results"].as_array().expect("results");
+    for result in results {
+        let generated = result.to_string().contains("users_pb.js");
+        assert_eq!(result.get("gen").is_some(), generated, "{}", result);
+    }
+}
+
+// ============================================================================
+// MODULE EXCLUSION (--exclude-module)
+// ============================================================================
+
+#[test]
+fn test_search_exclude_module() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/api/users.ts", "loadUsers", "return [];")
+        .add_ts_function("src/generated/users.ts", "loadUsersStub", "return [];")
+        .add_ts_function("src/generated/deep/users.ts", "loadUsersDeep", "return [];");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "search",
+        "loadUsers",
+        "--symbols",
+        "--exclude-module",
+        "generated",
+        "-f",
+        "json",
+    ]);
+    assert_valid_json(&output, "search with excluded module");
+    assert_contains(&output, "src/api/users.ts", true, "api symbol kept");
+    assert_not_contains(&output, "loadUsersStub", "excluded module dropped");
+    assert_not_contains(&output, "loadUsersDeep", "excluded submodule dropped");
+
+    // Repeatable, and applies to the semantic mode too
+    let output = repo.run_cli_success(&[
+        "search",
+        "users",
+        "--related",
+        "--exclude-module",
+        "generated",
+        "--exclude-module",
+        "api",
+        "-f",
+        "json",
+    ]);
+    assert_valid_json(&output, "related search with excluded modules");
+    for name in ["loadUsers", "loadUsersStub", "loadUsersDeep"] {
+        assert_not_contains(&output, name, name);
+    }
+}
+
+// ============================================================================
+// CONFIG KEYS
+// ============================================================================
+
+#[test]
+fn test_search_config_key_by_dotted_path() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "config/app.yaml",
+        "app:\n  name: demo\ndatabase:\n  pool:\n    max: 5\n  url: postgres://localhost/app\n",
+    )
+    .add_file(
+        "config/settings.toml",
+        "[server]\nport = 8080\n\n[database]\nurl = \"postgres://localhost/app\"\n",
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "database.url", "-f", "json"]);
+    let json = assert_valid_json(&output, "config key search");
+    let mut locations: Vec<(String, String)> = json["symbol_matches"]
+        .as_array()
+        .unwrap_or_else(|| panic!("Expected symbol matches: {}", output))
+        .iter()
+        .filter(|r| r["symbol"] == "database.url")
+        .map(|r| {
+            (
+                r["file"].as_str().unwrap().to_string(),
+                r["lines"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    locations.sort();
+    assert_eq!(
+        locations,
+        vec![
+            ("config/app.yaml".to_string(), "6-6".to_string()),
+            ("config/settings.toml".to_string(), "5-5".to_string()),
+        ],
+        "{}",
+        output
+    );
+}
+
 // ============================================================================
 // LIMIT TESTS
 // ============================================================================
@@ -5,6 +5,8 @@
 //! - `setup --list-clients` - List available MCP clients
 //! - `setup --dry-run` - Show what would be done without making changes
 //! - `setup --non-interactive --clients <CLIENTS>` - Non-interactive setup
+//! - `completions <SHELL>` - Shell completion scripts
+//! - `--json-help` - Machine-readable command schema
 
 #![allow(unused_imports)]
 
@@ -332,3 +334,87 @@ fn test_config_show_output() {
         output
     );
 }
+
+#[test]
+fn test_config_show_effective_reports_sources() {
+    let repo = TestRepo::new();
+    repo.add_file(".semfora/config.toml", "[duplicates]\nthreshold = 0.8\n");
+
+    let output = repo.run_cli_success(&["config", "show", "--effective", ".", "-f", "json"]);
+    let json = assert_valid_json(&output, "config show --effective");
+    let threshold = &json["settings"]["duplicates.threshold"];
+    assert_eq!(threshold["value"], 0.8);
+    assert_eq!(threshold["source"], "repo");
+    assert_eq!(json["settings"]["search.limit"]["source"], "default");
+}
+
+#[test]
+fn test_config_rejects_invalid_repo_config() {
+    let repo = TestRepo::new();
+    repo.add_file(".semfora/config.toml", "[duplicates]\nthreshold = 3.0\n");
+
+    let (_, stderr) = repo.run_cli_failure(&["validate", "--duplicates"]);
+    assert!(stderr.contains("threshold"), "stderr: {}", stderr);
+}
+
+// ============================================================================
+// COMPLETIONS / JSON HELP TESTS
+// ============================================================================
+
+#[test]
+fn test_json_help() {
+    let repo = TestRepo::new();
+
+    let output = repo.run_cli_success(&["--json-help"]);
+    let json = assert_valid_json(&output, "json help");
+    let commands: Vec<&str> = json["subcommands"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert!(commands.contains(&"query"), "{:?}", commands);
+    assert!(commands.contains(&"completions"), "{:?}", commands);
+    assert_eq!(
+        output,
+        repo.run_cli_success(&["--json-help"]),
+        "stable output"
+    );
+}
+
+#[test]
+fn test_completions_script() {
+    let repo = TestRepo::new();
+
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = repo.run_cli_success(&["completions", shell]);
+        assert!(
+            output.contains("SEMFORA_COMPLETE"),
+            "{} script calls back into the binary: {}",
+            shell,
+            output
+        );
+    }
+}
+
+#[test]
+fn test_completions_complete_indexed_modules() {
+    let repo = TestRepo::new();
+    let complete = |repo: &TestRepo| {
+        let output = repo
+            .cli_command(&["--", "semfora-engine", "query", "exports", "--module", ""])
+            .env("SEMFORA_COMPLETE", "fish")
+            .output()
+            .expect("Failed to run CLI");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    // No index: nothing to offer, and no error
+    assert_eq!(complete(&repo), "");
+
+    repo.add_file("src/auth/login.ts", "export function login() {}\n");
+    repo.generate_index().unwrap();
+    let output = complete(&repo);
+    assert!(output.lines().any(|m| m.contains("auth")), "{}", output);
+}
@@ -5,6 +5,7 @@
 //! - `test --detect` - Only detect test framework
 //! - `test --framework <FRAMEWORK>` - Force specific framework
 //! - `test <FILTER>` - Run tests matching filter pattern
+//! - `test --changed [REF]` - Run only tests reaching changed code
 
 use crate::common::{assert_valid_json, TestRepo};
 
@@ -326,6 +327,84 @@ fn test_detect_toon_format() {
     repo.run_cli_success(&["test", "--detect", "-f", "toon"]);
 }
 
+// ============================================================================
+// DIFF-AWARE SELECTION TESTS
+// ============================================================================
+
+/// Python project where `test_checkout` reaches `round_price` through two calls
+fn setup_checkout_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/price.py",
+        "def round_price(value):\n    return round(value, 2)\n",
+    )
+    .add_file(
+        "src/cart.py",
+        r#"from src.price import round_price
+
+
+def apply_discount(total, pct):
+    discounted = total * (1 - pct)
+    return round_price(discounted)
+
+
+def checkout(items):
+    total = sum(items)
+    return apply_discount(total, 0.1)
+"#,
+    )
+    .add_file(
+        "src/names.py",
+        "def format_name(first, last):\n    return first + ' ' + last\n",
+    )
+    .add_file(
+        "tests/test_cart.py",
+        "from src.cart import checkout\n\n\ndef test_checkout():\n    assert checkout([10, 20]) == 27.0\n",
+    )
+    .add_file(
+        "tests/test_names.py",
+        "from src.names import format_name\n\n\ndef test_format_name():\n    assert format_name('a', 'b') == 'a b'\n",
+    );
+    repo.init_git().commit("initial");
+    repo.generate_index().unwrap();
+    repo
+}
+
+#[test]
+fn test_changed_selects_transitive_callers() {
+    let repo = setup_checkout_repo();
+    repo.add_file(
+        "src/price.py",
+        "def round_price(value):\n    return round(value + 0.0, 2)\n",
+    );
+
+    let output =
+        repo.run_cli_success(&["test", "--changed", "--framework", "pytest", "-f", "json"]);
+    let json = assert_valid_json(&output, "test --changed");
+
+    let selection = &json["selection"];
+    assert_eq!(selection["mode"], "changed", "Selection: {}", selection);
+    let selected: Vec<&str> = selection["selected"]
+        .as_array()
+        .expect("selected should be an array")
+        .iter()
+        .filter_map(|t| t.as_str())
+        .collect();
+    assert_eq!(selected, vec!["test_checkout"]);
+}
+
+#[test]
+fn test_changed_without_covering_tests() {
+    let repo = setup_checkout_repo();
+    repo.add_file("README.md", "# Checkout\n");
+
+    let output = repo.run_cli_success(&["test", "--changed", "-f", "json"]);
+    let json = assert_valid_json(&output, "test --changed no tests");
+
+    assert_eq!(json["_type"], "test_selection");
+    assert_eq!(json["selected"], 0);
+}
+
 // ============================================================================
 // EDGE CASES
 // ============================================================================
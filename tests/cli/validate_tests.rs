@@ -3,13 +3,15 @@
 //! The validate command checks code quality:
 //! - `validate <TARGET>` - Validates file/module/hash (auto-detected)
 //! - `validate --duplicates` - Find duplicate code patterns
+//! - `validate --cycles` - List files that import each other
 //!
 //! TARGET can be a file path, module name, or symbol hash
 
 #![allow(unused_imports)]
 
 use crate::common::{
-    assert_contains, assert_valid_json, assert_valid_toon, extract_symbol_hashes, TestRepo,
+    assert_contains, assert_not_contains, assert_valid_json, assert_valid_toon,
+    extract_symbol_hashes, TestRepo,
 };
 
 // ============================================================================
@@ -321,6 +323,387 @@ fn test_validate_duplicates_with_limit() {
     }
 }
 
+#[test]
+fn test_validate_duplicates_inline_route_handlers() {
+    let repo = TestRepo::new();
+    let handler = r#"async (req, res) => {
+  const user = await db.users.create(req.body);
+  await audit.record('create', user.id);
+  if (!user) {
+    return res.status(400).json({ error: 'invalid' });
+  }
+  res.json(user);
+}"#;
+    repo.add_file(
+        "src/routes.js",
+        &format!(
+            "const express = require('express');\nconst app = express();\n\n\
+             app.post('/users', {handler});\n\n\
+             app.post('/accounts', {handler});\n\n\
+             module.exports = app;\n"
+        ),
+    );
+
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--duplicates", "-f", "json"]);
+    let json = assert_valid_json(&output, "duplicate route handlers");
+    let clusters = json["cluster_details"]
+        .as_array()
+        .expect("cluster_details should be an array");
+    let cluster = clusters
+        .iter()
+        .find(|c| c["primary"] == "<anon@L4>")
+        .unwrap_or_else(|| panic!("First handler should head a cluster: {}", output));
+    assert_eq!(cluster["duplicates"][0]["name"], "<anon@L13>");
+    assert_eq!(cluster["duplicates"][0]["kind"], "Exact");
+}
+
+#[test]
+fn test_validate_duplicates_report() {
+    let repo = TestRepo::new();
+    let body = r#"
+    const user = fetchUser(id);
+    if (!user) {
+        throw new Error('missing');
+    }
+    auditLog(user);
+    return saveUser(user);
+"#;
+    for module in ["auth", "users", "orders"] {
+        repo.add_ts_function(&format!("src/{}/sync.ts", module), "syncUser", body);
+    }
+
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--duplicates", "--report", "-f", "json"]);
+    let json = assert_valid_json(&output, "duplicates report");
+    assert_eq!(json["_type"], "duplicate_consolidation_report");
+
+    let details = json["opportunity_details"]
+        .as_array()
+        .expect("opportunity_details should be an array");
+    assert!(
+        !details.is_empty(),
+        "Should report consolidation opportunities"
+    );
+    for detail in details {
+        let action = detail["action"].as_str().unwrap_or_default();
+        assert!(
+            action.starts_with("extract shared helper"),
+            "Each opportunity should suggest an action: {}",
+            action
+        );
+    }
+    let scores: Vec<f64> = details
+        .iter()
+        .map(|d| d["score"].as_f64().unwrap_or_default())
+        .collect();
+    assert!(
+        scores.windows(2).all(|w| w[0] >= w[1]),
+        "Opportunities should be sorted by savings: {:?}",
+        scores
+    );
+
+    let toon = repo.run_cli_success(&[
+        "validate",
+        "--duplicates",
+        "--sort-by",
+        "savings",
+        "-f",
+        "toon",
+    ]);
+    assert_contains(&toon, "sort: savings", true, "savings sort header");
+    assert_contains(
+        &toon,
+        "action: extract shared helper",
+        true,
+        "savings action",
+    );
+}
+
+#[test]
+fn test_validate_duplicates_cross_package() {
+    let repo = TestRepo::new();
+    let body = r#"(amount: number, currency: string) {
+  const rounded = Math.round(amount * 100) / 100;
+  const parts = rounded.toFixed(2).split(".");
+  const whole = parts[0].replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+  return `${currency} ${whole}.${parts[1]}`;
+}
+"#;
+    repo.add_file("pnpm-workspace.yaml", "packages:\n  - \"packages/*\"\n");
+    repo.add_file("packages/web/package.json", r#"{"name": "@acme/web"}"#);
+    repo.add_file("packages/api/package.json", r#"{"name": "@acme/api"}"#);
+    repo.add_file("packages/admin/package.json", r#"{"name": "@acme/admin"}"#);
+    repo.add_file(
+        "packages/web/src/fmt.ts",
+        &format!("export function formatPrice{}", body),
+    );
+    repo.add_file(
+        "packages/api/src/fmt.ts",
+        &format!("export function formatAmount{}", body),
+    );
+    repo.add_ts_function("packages/admin/src/index.ts", "boot", "return 0;");
+    repo.generate_index().unwrap();
+
+    let output =
+        repo.run_cli_success(&["validate", "--duplicates", "--package", "api", "-f", "json"]);
+    let json = assert_valid_json(&output, "package duplicates");
+    assert_eq!(json["clusters"], 1, "{}", output);
+    let cluster = &json["cluster_details"][0];
+    assert_eq!(cluster["cross_package"], true, "{}", output);
+    let mut packages: Vec<&str> = cluster["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|p| p.as_str())
+        .collect();
+    packages.sort();
+    assert_eq!(packages, ["api", "web"], "{}", output);
+
+    // A package with no part in the cluster sees none
+    let output = repo.run_cli_success(&[
+        "validate",
+        "--duplicates",
+        "--package",
+        "admin",
+        "-f",
+        "json",
+    ]);
+    let json = assert_valid_json(&output, "unrelated package duplicates");
+    assert_eq!(json["clusters"], 0, "{}", output);
+}
+
+#[test]
+fn test_validate_duplicates_csv() {
+    let repo = TestRepo::new();
+    let body = r#"
+export function NAME(items: number[], taxRate: number) {
+    let subtotal = 0;
+    for (const item of items) {
+        subtotal += item;
+    }
+    const tax = subtotal * taxRate;
+    console.log("invoice", subtotal, tax);
+    return subtotal + tax;
+}
+"#;
+    repo.add_file(
+        "src/billing, legacy.ts",
+        &body.replace("NAME", "computeInvoiceTotal"),
+    )
+    .add_file("src/orders.ts", &body.replace("NAME", "computeOrderTotal"));
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--duplicates", "-f", "csv"]);
+    let mut lines = output.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "primary_hash,primary_name,primary_file,duplicate_hash,duplicate_name,duplicate_file,similarity,kind"
+        ),
+        "{}",
+        output
+    );
+
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), 1, "{}", output);
+    // The file name containing a comma is quoted so the row keeps 8 columns
+    let row = rows[0];
+    assert_contains(row, "\"src/billing, legacy.ts\"", true, "quoted file");
+    assert_contains(
+        row,
+        "computeInvoiceTotal",
+        true,
+        "primary or duplicate name",
+    );
+    assert_contains(row, "computeOrderTotal", true, "primary or duplicate name");
+    assert_eq!(
+        row.replace("\"src/billing, legacy.ts\"", "")
+            .split(',')
+            .count(),
+        8
+    );
+}
+
+#[test]
+fn test_csv_format_rejected_outside_duplicates() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/a.ts", "alpha", "return 1;");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli(&["query", "overview", "-f", "csv"]).unwrap();
+    assert!(!output.status.success());
+    assert_contains(
+        &String::from_utf8_lossy(&output.stderr),
+        "only supported by `validate --duplicates`",
+        true,
+        "csv rejection",
+    );
+}
+
+#[test]
+fn test_validate_duplicates_exclude_module() {
+    let repo = TestRepo::new();
+    let body = r#"(amount: number, currency: string) {
+  const rounded = Math.round(amount * 100) / 100;
+  const parts = rounded.toFixed(2).split(".");
+  const whole = parts[0].replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+  return `${currency} ${whole}.${parts[1]}`;
+}
+"#;
+    for (file, name) in [
+        ("src/web/fmt.ts", "formatPrice"),
+        ("src/api/fmt.ts", "formatAmount"),
+        ("src/generated/fmt.ts", "formatGenerated"),
+    ] {
+        repo.add_file(file, &format!("export function {}{}", name, body));
+    }
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "validate",
+        "--duplicates",
+        "--exclude-module",
+        "generated",
+        "-f",
+        "json",
+    ]);
+    assert_valid_json(&output, "duplicates with excluded module");
+    assert_contains(&output, "formatPrice", true, "web copy kept");
+    assert_contains(&output, "formatAmount", true, "api copy kept");
+    assert_not_contains(&output, "formatGenerated", "excluded module dropped");
+}
+
+#[test]
+fn test_validate_module_exclude_submodule() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/api/users.ts", "getUsers", "return [];")
+        .add_ts_function("src/api/generated/users.ts", "getUsersStub", "return [];");
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&[
+        "validate",
+        "--file-path",
+        "users.ts",
+        "--exclude-module",
+        "api.generated",
+    ]);
+    assert_contains(&output, "getUsers", true, "parent module kept");
+    assert_not_contains(&output, "getUsersStub", "excluded submodule dropped");
+}
+
+// ============================================================================
+// GENERATED CODE (--include-generated)
+// ============================================================================
+
+const PROTOBUF_HEADER: &str =
+    "// Code generated by protoc-gen-ts_proto. DO NOT EDIT.\n// source: cart.proto\n";
+
+#[test]
+fn test_validate_duplicates_skips_generated_code() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/cart.ts", "cartTotal", TOTAL_BODY)
+        .add_file(
+            "src/order.ts",
+            &format!(
+                "// Mirrors the generated protobuf client; kept by hand.\nexport function orderTotal(items: any[]) {{\n    {}\n}}\n",
+                TOTAL_BODY
+            ),
+        )
+        .add_file(
+            "src/proto/cart.ts",
+            &format!(
+                "{}export function protoTotal(items: any[]) {{\n    {}\n}}\n",
+                PROTOBUF_HEADER, TOTAL_BODY
+            ),
+        );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--duplicates", "-f", "json"]);
+    assert_valid_json(&output, "duplicates without generated code");
+    assert_contains(&output, "cartTotal", true, "hand-written copy kept");
+    assert_contains(
+        &output,
+        "orderTotal",
+        true,
+        "comment mentioning generated code",
+    );
+    assert_not_contains(&output, "protoTotal", "generated copy dropped");
+
+    let output = repo.run_cli_success(&[
+        "validate",
+        "--duplicates",
+        "--include-generated",
+        "-f",
+        "json",
+    ]);
+    assert_contains(&output, "protoTotal", true, "--include-generated");
+}
+
+#[test]
+fn test_validate_generated_config_patterns() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/cart.ts", "cartTotal", TOTAL_BODY)
+        .add_ts_function("src/order.ts", "orderTotal", TOTAL_BODY)
+        .add_ts_function("src/client/api.ts", "clientTotal", TOTAL_BODY)
+        .add_file(
+            "src/legacy/cart.ts",
+            &format!(
+                "{}export function legacyTotal(items: any[]) {{\n    {}\n}}\n",
+                PROTOBUF_HEADER, TOTAL_BODY
+            ),
+        )
+        .add_file(
+            ".semfora/config.toml",
+            "[generated]\npatterns = [\"src/client\"]\nnot_generated = [\"src/legacy\"]\n",
+        );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--duplicates", "-f", "json"]);
+    assert_not_contains(&output, "clientTotal", "configured generated path");
+    assert_contains(&output, "legacyTotal", true, "not_generated override");
+}
+
+// ============================================================================
+// IMPORT CYCLES (--cycles)
+// ============================================================================
+
+#[test]
+fn test_validate_import_cycles() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/orders.ts",
+        "import { notify } from './notify';\nexport function placeOrder(id: string) {\n  return notify(id);\n}\n",
+    );
+    repo.add_file(
+        "src/notify.ts",
+        "import { placeOrder } from './orders';\nexport function notify(id: string) {\n  return placeOrder(id);\n}\n",
+    );
+    repo.add_file(
+        "src/report.ts",
+        "import { notify } from './notify';\nconst lazy = () => import('./orders');\nexport function report(id: string) {\n  return notify(id);\n}\n",
+    );
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["validate", "--cycles", "-f", "json"]);
+    let json = assert_valid_json(&output, "import cycles");
+    assert_eq!(json["count"], 1, "{}", output);
+    assert_eq!(json["cycles"][0]["a"], "src/notify.ts", "{}", output);
+    assert_eq!(json["cycles"][0]["b"], "src/orders.ts", "{}", output);
+
+    let output = repo.run_cli_success(&["validate", "--file-path", "src/orders.ts"]);
+    assert_contains(
+        &output,
+        "import cycle: src/notify.ts <-> src/orders.ts",
+        true,
+        "file validation warns about the cycle",
+    );
+    let output = repo.run_cli_success(&["validate", "--file-path", "src/report.ts"]);
+    assert!(!output.contains("import cycle"), "{}", output);
+}
+
 // ============================================================================
 // FORMAT TESTS
 // ============================================================================
@@ -725,6 +1108,53 @@ fn test_validate_duplicates_short_vs_long_functions() {
     );
 }
 
+#[test]
+fn test_validate_duplicates_reformatted_copy_is_exact() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/a.rs",
+        r#"pub fn load_user_profile(svc: &Service, id: u32) -> Profile {
+    let user = svc.user_service.fetch_user(id);
+    svc.cache.store(id, &user);
+    svc.audit_log.record(id);
+    user
+}
+"#,
+    );
+    // Same function after a formatter split the call chains
+    repo.add_file(
+        "src/b.rs",
+        r#"pub fn load_user_profile(svc: &Service, id: u32) -> Profile {
+    let user = svc
+        .user_service
+        .fetch_user(id);
+    svc  .  cache
+        .store(id, &user);
+    svc.audit_log
+       .record( id );
+    user
+}
+"#,
+    );
+    repo.generate_index().unwrap();
+
+    for extra in [None, Some("--normalize-whitespace")] {
+        let mut args = vec!["validate", "--duplicates", "-f", "json"];
+        args.extend(extra);
+        let output = repo.run_cli_success(&args);
+        let json = assert_valid_json(&output, "reformatted duplicates");
+
+        let clusters = json["cluster_details"].as_array().unwrap();
+        assert_eq!(clusters.len(), 1, "{json}");
+        let duplicate = &clusters[0]["duplicates"][0];
+        assert!(
+            duplicate["similarity"].as_f64().unwrap() >= 0.98,
+            "{duplicate}"
+        );
+        assert_eq!(duplicate["kind"], "Exact", "{duplicate}");
+    }
+}
+
 // ============================================================================
 // THRESHOLD EDGE CASES
 // ============================================================================
@@ -782,3 +1212,220 @@ export function process(x: number) { return x * 2; }
     let result = repo.run_cli(&["validate", "--duplicates", "--kind", "fn", "-f", "json"]);
     assert!(result.is_ok(), "Kind filter should work");
 }
+
+// ============================================================================
+// FAIL-ON RULE TESTS
+// ============================================================================
+
+const BRANCHY_FUNCTION: &str = r#"
+export function classify(x: number): string {
+    if (x > 0) {
+        if (x > 10) {
+            for (let i = 0; i < x; i++) {
+                if (i % 2 === 0) {
+                    console.log(i);
+                }
+            }
+            return "large";
+        }
+        return "small";
+    }
+    return "none";
+}
+
+export function identity(x: number): number {
+    return x;
+}
+"#;
+
+/// Section of a text report after the `violations[N]:` header
+fn violations_section(stdout: &str) -> &str {
+    let start = stdout
+        .find("violations[")
+        .expect("report should end with a violations section");
+    &stdout[start..]
+}
+
+#[test]
+fn test_validate_fail_on_complexity_exit_code() {
+    let repo = TestRepo::new();
+    repo.add_file("src/classify.ts", BRANCHY_FUNCTION);
+    repo.generate_index().unwrap();
+
+    let output = repo
+        .run_cli(&[
+            "validate",
+            "--file-path",
+            "src/classify.ts",
+            "--fail-on",
+            "complexity:cyclomatic>3",
+        ])
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let violations = violations_section(&stdout);
+    assert!(violations.starts_with("violations[1]:"), "{}", violations);
+    assert!(violations.contains("complexity:cyclomatic>3 classify"));
+    assert!(!violations.contains("identity"));
+
+    // Nothing trips a generous threshold
+    let stdout = repo.run_cli_success(&[
+        "validate",
+        "--file-path",
+        "src/classify.ts",
+        "--fail-on",
+        "complexity:cyclomatic>100",
+        "--fail-on",
+        "impact:callers>50",
+    ]);
+    assert!(violations_section(&stdout).starts_with("violations[0]:"));
+}
+
+#[test]
+fn test_validate_fail_on_json_violations() {
+    let repo = TestRepo::new();
+    repo.add_file("src/classify.ts", BRANCHY_FUNCTION);
+    repo.generate_index().unwrap();
+
+    let output = repo
+        .run_cli(&[
+            "validate",
+            "src/classify.ts",
+            "--fail-on",
+            "complexity:nesting>=2",
+            "-f",
+            "json",
+        ])
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = assert_valid_json(&stdout, "validate --fail-on json");
+
+    assert_eq!(json["fail_on"][0], "complexity:nesting>=2");
+    let violations = json["violations"].as_array().expect("violations array");
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0]["rule"], "complexity:nesting>=2");
+    assert_eq!(violations[0]["symbol"], "classify");
+    assert_eq!(violations[0]["file"], "src/classify.ts");
+    assert!(violations[0]["value"].as_u64().unwrap() >= 2);
+}
+
+#[test]
+fn test_validate_fail_on_new_duplicates_since_base() {
+    let repo = TestRepo::new();
+    repo.init_git();
+    repo.add_file(".gitignore", ".semfora-cache/\n");
+    let legacy = |name: &str| {
+        format!(
+            "export function {}(order: Order) {{\n    const total = sumLines(order.lines);\n    applyDiscount(order, total);\n    return persistOrder(order);\n}}\n",
+            name
+        )
+    };
+    repo.add_file("src/legacy/a.ts", &legacy("saveOrderA"));
+    repo.add_file("src/legacy/b.ts", &legacy("saveOrderB"));
+    repo.add_file(
+        "src/users.ts",
+        "export function loadUser(id: string) {\n    const row = db.query(id);\n    audit.record(row);\n    return toUser(row);\n}\n",
+    );
+    repo.commit("base");
+
+    repo.add_file(
+        "src/admins.ts",
+        "export function loadAdmin(id: string) {\n    const row = db.query(id);\n    audit.record(row);\n    return toUser(row);\n}\n",
+    );
+    repo.commit("feature");
+    repo.generate_index().unwrap();
+
+    let output = repo
+        .run_cli(&[
+            "validate",
+            "--fail-on",
+            "duplicates:new",
+            "--base",
+            "HEAD~1",
+            "--min-lines",
+            "1",
+            "--threshold",
+            "0.8",
+        ])
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let violations = violations_section(&stdout);
+    assert!(violations.starts_with("violations[1]:"), "{}", violations);
+    assert!(violations.contains("duplicates:new loadAdmin"));
+    assert!(violations.contains("loadUser"));
+    // Duplicates that already existed on the base don't count
+    assert!(!violations.contains("saveOrder"));
+
+    // Against the feature commit itself nothing is new
+    let stdout = repo.run_cli_success(&[
+        "validate",
+        "--fail-on",
+        "duplicates:new",
+        "--base",
+        "HEAD",
+        "--min-lines",
+        "1",
+        "--threshold",
+        "0.8",
+    ]);
+    assert!(violations_section(&stdout).starts_with("violations[0]:"));
+}
+
+#[test]
+fn test_validate_fail_on_malformed_rule() {
+    let repo = TestRepo::new();
+    repo.add_file("src/classify.ts", BRANCHY_FUNCTION);
+    repo.generate_index().unwrap();
+
+    let (_, stderr) = repo.run_cli_failure(&["validate", "--fail-on", "complexity:halstead>3"]);
+    assert!(
+        stderr.contains("unknown complexity metric `halstead`"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+// ============================================================================
+// REPO CONFIG TESTS
+// ============================================================================
+
+const TOTAL_BODY: &str =
+    "const total = items.reduce((sum, item) => sum + item.price * item.quantity, 0);
+    const tax = total * 0.2;
+    if (tax > 100) { console.log('high tax'); }
+    return total + tax;";
+
+#[test]
+fn test_validate_duplicates_honors_repo_config() {
+    let repo = TestRepo::new();
+    repo.add_ts_function("src/cart.ts", "cartTotal", TOTAL_BODY)
+        .add_ts_function("src/order.ts", "orderTotal", TOTAL_BODY)
+        .add_ts_function("src/generated/client.ts", "generatedTotal", TOTAL_BODY)
+        .add_file(
+            ".semfora/config.toml",
+            "[duplicates]\nthreshold = 0.77\n\n[index]\nexclude = [\"src/generated\"]\n",
+        );
+    repo.generate_index().unwrap();
+
+    // No --threshold: the repo config's threshold applies
+    let output = repo.run_cli_success(&["validate", "--duplicates"]);
+    assert_contains(&output, "threshold: 77%", false, "repo config threshold");
+    assert_contains(
+        &output,
+        "cartTotal",
+        true,
+        "duplicates outside excluded paths",
+    );
+    assert_not_contains(&output, "generatedTotal", "excluded path");
+
+    // An explicit flag still wins
+    let output = repo.run_cli_success(&["validate", "--duplicates", "--threshold", "0.95"]);
+    assert_contains(&output, "threshold: 95%", false, "flag overrides config");
+}
@@ -55,6 +55,37 @@ impl TestRepo {
             .output()
     }
 
+    /// Run semfora-engine CLI command with `input` piped to stdin
+    pub fn run_cli_with_stdin(&self, args: &[&str], input: &str) -> std::io::Result<Output> {
+        use std::io::Write;
+
+        let binary = PathBuf::from(env!("CARGO_BIN_EXE_semfora-engine"));
+        let mut child = Command::new(&binary)
+            .current_dir(self.path())
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(input.as_bytes())?;
+        child.wait_with_output()
+    }
+
+    /// Build a semfora-engine CLI command in the repo without running it
+    ///
+    /// For tests that need to set environment variables or run the CLI in
+    /// the background.
+    pub fn cli_command(&self, args: &[&str]) -> Command {
+        let binary = PathBuf::from(env!("CARGO_BIN_EXE_semfora-engine"));
+        let mut command = Command::new(&binary);
+        command.current_dir(self.path()).args(args);
+        command
+    }
+
     /// Run CLI and expect success, return stdout
     pub fn run_cli_success(&self, args: &[&str]) -> String {
         let output = self.run_cli(args).expect("Failed to run CLI");
@@ -121,6 +152,43 @@ impl TestRepo {
         self
     }
 
+    /// Run a git command in the repo
+    pub fn git(&self, args: &[&str]) -> &Self {
+        Command::new("git")
+            .current_dir(self.path())
+            .args(args)
+            .output()
+            .expect("Failed to run git");
+        self
+    }
+
+    /// Stash uncommitted changes
+    pub fn stash(&self) -> &Self {
+        Command::new("git")
+            .current_dir(self.path())
+            .args(["stash"])
+            .output()
+            .expect("Failed to git stash");
+        self
+    }
+
+    /// Clone this repository with `--depth 1`, checking out `branch`
+    pub fn shallow_clone(&self, branch: &str) -> TestRepo {
+        let clone = TestRepo::new();
+        let url = format!("file://{}", self.path_str());
+        let output = Command::new("git")
+            .current_dir(clone.path())
+            .args(["clone", "--depth", "1", "--branch", branch, &url, "."])
+            .output()
+            .expect("Failed to git clone");
+        assert!(
+            output.status.success(),
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        clone
+    }
+
     // ========================================================================
     // PRE-BUILT REPO STRUCTURES
     // ========================================================================
@@ -267,6 +267,67 @@ fn test_unicode_path() {
     assert!(result.is_ok(), "Should handle Unicode in path");
 }
 
+#[test]
+fn test_cjk_identifiers_are_searchable() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/users.py",
+        r#"
+def 获取用户信息(用户名):
+    return 用户名
+
+def 删除订单(订单号):
+    return 订单号
+"#,
+    );
+
+    let output = repo.run_cli_success(&["analyze", "src/users.py", "-f", "json"]);
+    assert!(
+        output.contains("获取用户信息"),
+        "CJK symbol not extracted: {output}"
+    );
+
+    repo.generate_index().unwrap();
+
+    // A two-character query matches inside the longer identifier
+    let output = repo.run_cli_success(&["search", "用户", "--related", "-f", "json"]);
+    let json = assert_valid_json(&output, "CJK search");
+    let symbols: Vec<&str> = json["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|r| r["symbol"].as_str())
+        .collect();
+    assert_eq!(symbols, ["获取用户信息"]);
+}
+
+#[test]
+fn test_emoji_and_combining_identifiers_are_searchable() {
+    let repo = TestRepo::new();
+    repo.add_file(
+        "src/party.js",
+        "export function launch🚀Rocket(count) {\n  return count + 1;\n}\n\nexport function cafe\u{301}Menu() {\n  return [];\n}\n",
+    );
+
+    let output = repo.run_cli_success(&["analyze", "src/party.js", "-f", "json"]);
+    assert!(
+        output.contains("launch🚀Rocket"),
+        "emoji symbol not extracted: {output}"
+    );
+    assert!(output.contains("cafe\u{301}Menu"));
+
+    repo.generate_index().unwrap();
+
+    let output = repo.run_cli_success(&["search", "🚀", "--related", "-f", "json"]);
+    assert!(output.contains("launch🚀Rocket"), "emoji search: {output}");
+
+    let output = repo.run_cli_success(&["search", "cafe\u{301}", "--related", "-f", "json"]);
+    assert!(
+        output.contains("cafe\u{301}Menu"),
+        "combining search: {output}"
+    );
+}
+
 // ============================================================================
 // VERY LONG FILE TESTS
 // ============================================================================
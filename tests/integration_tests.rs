@@ -562,6 +562,8 @@ mod duplicate_detection {
             has_business_logic: true,
             business_calls: vec!["db.query".to_string()],
             param_count: 1,
+            return_type: None,
+            has_lifetimes: false,
             boilerplate_category: None,
             line_count: 10,
         }
@@ -581,6 +583,8 @@ mod duplicate_detection {
             has_business_logic: true,
             business_calls: vec!["stripe.charge".to_string()],
             param_count: 3,
+            return_type: None,
+            has_lifetimes: false,
             boilerplate_category: None,
             line_count: 25,
         }
@@ -1,7 +1,8 @@
 //! Configuration file format integration tests
 //!
 //! Tests for JSON, YAML, TOML, and XML - structured data formats
-//! commonly used for configuration and data exchange.
+//! commonly used for configuration and data exchange - plus the protobuf
+//! and OpenAPI contracts built on top of them.
 
 #![allow(unused_imports)]
 #![allow(clippy::duplicate_mod)]
@@ -794,3 +795,189 @@ mod xml_tests {
         assert!(output.unwrap().status.success(), "Should handle SVG XML");
     }
 }
+
+// =============================================================================
+// CONTRACT TESTS (PROTOBUF, OPENAPI)
+// =============================================================================
+
+mod contract_tests {
+    use super::*;
+
+    const USER_PROTO: &str = r#"syntax = "proto3";
+package users.v1;
+
+message User {
+  string id = 1;
+  string email = 2;
+  repeated string roles = 3;
+}
+
+message GetUserRequest {
+  string id = 1;
+}
+
+service UserService {
+  rpc GetUser(GetUserRequest) returns (User);
+}
+"#;
+
+    const USERS_OPENAPI: &str = r#"openapi: 3.0.3
+info:
+  title: Users API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    get:
+      operationId: fetchUser
+      responses:
+        '200':
+          description: The user
+        '404':
+          description: Not found
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: string
+        email:
+          type: string
+"#;
+
+    const CLIENT_TS: &str = r#"export async function loadProfile(id: string) {
+  return client.getUser({ id });
+}
+
+export async function loadViaRest(id: string) {
+  return api.fetchUser(id);
+}
+"#;
+
+    fn contract_repo() -> TestRepo {
+        let repo = TestRepo::new();
+        repo.add_file("proto/user.proto", USER_PROTO)
+            .add_file("api/openapi.yaml", USERS_OPENAPI)
+            .add_file("src/client.ts", CLIENT_TS);
+        repo
+    }
+
+    fn symbol_kinds(json: &serde_json::Value) -> Vec<(String, String)> {
+        json["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| {
+                (
+                    s["name"].as_str().unwrap().to_string(),
+                    s["kind"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_proto_contract_extraction() {
+        let repo = contract_repo();
+
+        let output = repo.run_cli_success(&["analyze", "proto/user.proto", "-f", "json"]);
+        let json = assert_valid_json(&output, "analyze proto");
+        let kinds = symbol_kinds(&json);
+        assert!(kinds.contains(&("User".to_string(), "contract".to_string())));
+        assert!(kinds.contains(&("UserService".to_string(), "contract".to_string())));
+        assert!(kinds.contains(&("GetUser".to_string(), "rpc".to_string())));
+
+        let user = json["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["name"] == "User")
+            .unwrap();
+        assert_eq!(user["arguments"][1]["name"], "email");
+        assert_eq!(user["arguments"][1]["arg_type"], "string");
+        assert_eq!(user["arguments"][1]["default_value"], "2");
+    }
+
+    #[test]
+    fn test_openapi_contract_extraction() {
+        let repo = contract_repo();
+
+        let output = repo.run_cli_success(&["analyze", "api/openapi.yaml", "-f", "json"]);
+        let json = assert_valid_json(&output, "analyze openapi");
+        let kinds = symbol_kinds(&json);
+        assert!(kinds.contains(&("/users/{id}".to_string(), "contract".to_string())));
+        assert!(kinds.contains(&("fetchUser".to_string(), "rpc".to_string())));
+        assert!(kinds.contains(&("User".to_string(), "contract".to_string())));
+    }
+
+    #[test]
+    fn test_contract_symbols_are_searchable() {
+        let repo = contract_repo();
+        repo.generate_index().unwrap();
+
+        let output = repo.run_cli_success(&["search", "GetUser", "--symbols"]);
+        assert!(output.contains("user.proto"), "{}", output);
+    }
+
+    #[test]
+    fn test_contract_usages_query() {
+        let repo = contract_repo();
+        repo.generate_index().unwrap();
+
+        let output = repo.run_cli_success(&["query", "contract-usages", "GetUser", "-f", "json"]);
+        let json = assert_valid_json(&output, "contract-usages rpc");
+        let element = &json["elements"][0];
+        assert_eq!(element["name"], "UserService.GetUser");
+        assert_eq!(element["consumers"][0]["symbol"], "loadProfile");
+
+        let output =
+            repo.run_cli_success(&["query", "contract-usages", "/users/{id}", "-f", "json"]);
+        let json = assert_valid_json(&output, "contract-usages path");
+        assert_eq!(json["elements"][0]["kind"], "path");
+        assert_eq!(json["elements"][0]["consumers"][0]["symbol"], "loadViaRest");
+    }
+
+    #[test]
+    fn test_contract_breaking_changes_in_diff() {
+        let repo = contract_repo();
+        repo.init_git();
+        repo.commit("initial contracts");
+
+        let broken = USER_PROTO
+            .replace("  string email = 2;\n", "")
+            .replace("roles = 3;", "roles = 4;");
+        repo.add_file("proto/user.proto", &broken);
+        repo.add_file(
+            "api/openapi.yaml",
+            &USERS_OPENAPI.replace("        '404':\n          description: Not found\n", ""),
+        );
+        repo.commit("break contracts");
+
+        let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1"]);
+        assert!(output.contains("breaking_changes[3]:"), "{}", output);
+        assert!(
+            output.contains("User.email - field removed (#2)"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("User.roles - field renumbered (3 -> 4)"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("GET /users/{id} - response 404 removed"),
+            "{}",
+            output
+        );
+
+        // Additive changes are not breaking
+        repo.add_file(
+            "proto/user.proto",
+            &broken.replace("roles = 4;", "roles = 4;\n  string name = 5;"),
+        );
+        repo.commit("extend contract");
+        let output = repo.run_cli_success(&["analyze", "--diff", "HEAD~1"]);
+        assert!(!output.contains("breaking_changes"), "{}", output);
+    }
+}
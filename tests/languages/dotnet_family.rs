@@ -117,6 +117,57 @@ public record Config
         assert_symbol_exists(&json, "Config");
     }
 
+    #[test]
+    fn test_csharp_record_kind_and_props() {
+        let repo = TestRepo::new();
+        repo.add_file(
+            "src/Example/Records.cs",
+            r#"namespace Example;
+
+// Positional record
+public record Person(string Name, int Age = 0);
+
+// Nominal record
+public record Address
+{
+    public required string Street { get; init; }
+    public string City { get; init; } = "Springfield";
+    public string Label => $"{Street}, {City}";
+}
+"#,
+        );
+
+        let output = repo.run_cli_success(&["analyze", "src/Example/Records.cs", "-f", "json"]);
+        let json = assert_valid_json(&output, "C# record kinds");
+        let symbols = json["symbols"].as_array().expect("symbols array");
+        let symbol = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s["name"] == name)
+                .unwrap_or_else(|| panic!("{} not found: {}", name, output))
+        };
+        let prop_names = |name: &str| -> Vec<String> {
+            symbol(name)["props"]
+                .as_array()
+                .map(|props| {
+                    props
+                        .iter()
+                        .map(|p| p["name"].as_str().unwrap().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        assert_eq!(symbol("Person")["kind"], "type");
+        assert_eq!(prop_names("Person"), vec!["Name", "Age"]);
+        assert_eq!(symbol("Person")["props"][1]["default_value"], "0");
+
+        assert_eq!(symbol("Address")["kind"], "type");
+        assert_eq!(prop_names("Address"), vec!["Street", "City"]);
+        assert_eq!(symbol("Address")["props"][0]["required"], true);
+        assert_eq!(symbol("Address")["props"][1]["required"], false);
+    }
+
     #[test]
     fn test_csharp_struct_extraction() {
         let repo = TestRepo::new();
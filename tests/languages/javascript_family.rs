@@ -861,6 +861,36 @@ export default Button;
         assert_symbol_exists(&json, "Button");
     }
 
+    #[test]
+    fn test_anonymous_default_export_named_from_file() {
+        let repo = TestRepo::new();
+        repo.add_file(
+            "src/pages/homepage.tsx",
+            r#"
+export default () => {
+    return <main>Welcome</main>;
+};
+"#,
+        );
+        repo.add_file(
+            "src/pages/user-profile.tsx",
+            r#"
+export default function ({ userId }: { userId: string }) {
+    return <section>{userId}</section>;
+}
+"#,
+        );
+        repo.generate_index().expect("Index failed");
+
+        let output = repo.run_cli_success(&["search", "Homepage", "-f", "json"]);
+        let json = assert_valid_json(&output, "search anonymous arrow default export");
+        assert_symbol_exists(&json, "Homepage");
+
+        let output = repo.run_cli_success(&["search", "UserProfile", "-f", "json"]);
+        let json = assert_valid_json(&output, "search anonymous function default export");
+        assert_symbol_exists(&json, "UserProfile");
+    }
+
     #[test]
     fn test_usestate_hook() {
         let repo = TestRepo::new();
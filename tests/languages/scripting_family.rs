@@ -1,6 +1,6 @@
 //! Scripting language family integration tests
 //!
-//! Tests for Python and Bash - dynamic scripting languages with
+//! Tests for Python, Bash and PHP - dynamic scripting languages with
 //! specific visibility and module conventions.
 
 #[path = "../common/mod.rs"]
@@ -1499,3 +1499,136 @@ main "$@"
         assert_symbol_exists(&json, "parse_args");
     }
 }
+
+// =============================================================================
+// PHP TESTS
+// =============================================================================
+
+mod php_tests {
+    use super::*;
+
+    /// Laravel app with a two-action controller and an Eloquent model
+    fn laravel_repo() -> TestRepo {
+        let repo = TestRepo::new();
+        repo.add_file(
+            "app/Http/Controllers/UserController.php",
+            r#"<?php
+
+namespace App\Http\Controllers;
+
+use App\Models\User;
+use Illuminate\Http\Request;
+
+class UserController extends Controller
+{
+    public function index()
+    {
+        return view('users.index', ['users' => User::all()]);
+    }
+
+    public function store(Request $request)
+    {
+        try {
+            $user = User::create($request->validated());
+        } catch (\Exception $e) {
+            report($e);
+        }
+        return redirect()->route('users.show', $user);
+    }
+
+    private function authorizeAdmin()
+    {
+        return match (auth()->user()->role) {
+            'admin' => true,
+            default => false,
+        };
+    }
+}
+"#,
+        );
+        repo.add_file(
+            "app/Models/User.php",
+            r#"<?php
+
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Model;
+
+class User extends Model
+{
+    protected $fillable = ['name', 'email'];
+
+    public function posts()
+    {
+        return $this->hasMany(Post::class);
+    }
+}
+"#,
+        );
+        repo.generate_index().unwrap();
+        repo
+    }
+
+    fn entry_point<'a>(json: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+        json["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["name"] == name)
+            .unwrap_or_else(|| panic!("symbol {} not found: {}", name, json))
+            .get("framework_entry_point")
+            .and_then(|e| e.as_str())
+    }
+
+    #[test]
+    fn test_php_laravel_controller_extraction() {
+        let repo = laravel_repo();
+
+        let output = repo.run_cli_success(&[
+            "analyze",
+            "app/Http/Controllers/UserController.php",
+            "-f",
+            "json",
+        ]);
+        let json = assert_valid_json(&output, "PHP controller extraction");
+
+        assert_symbol_exists(&json, "UserController");
+        assert_symbol_exists(&json, "index");
+        assert_symbol_exists(&json, "store");
+        assert_symbol_exported(&json, "store");
+        assert_symbol_private(&json, "authorizeAdmin");
+        assert_eq!(entry_point(&json, "index"), Some("laravel_controller"));
+        assert_eq!(entry_point(&json, "store"), Some("laravel_controller"));
+        assert_eq!(entry_point(&json, "authorizeAdmin"), None);
+        assert_contains(&output, "App\\\\Models\\\\User", true, "use import");
+    }
+
+    #[test]
+    fn test_php_eloquent_model_extraction() {
+        let repo = laravel_repo();
+
+        let output = repo.run_cli_success(&["analyze", "app/Models/User.php", "-f", "json"]);
+        let json = assert_valid_json(&output, "PHP model extraction");
+
+        assert_symbol_exists(&json, "User");
+        assert_symbol_exists(&json, "posts");
+        assert_eq!(entry_point(&json, "User"), Some("eloquent_model"));
+        assert_eq!(entry_point(&json, "posts"), Some("eloquent_model"));
+    }
+
+    #[test]
+    fn test_php_symbols_are_indexed_and_searchable() {
+        let repo = laravel_repo();
+
+        let output = repo.run_cli_success(&["search", "store", "-f", "json"]);
+        assert_contains(
+            &output,
+            "app/Http/Controllers/UserController.php",
+            true,
+            "PHP symbol search",
+        );
+
+        let languages = repo.run_cli_success(&["query", "languages"]);
+        assert_contains(&languages, "php, phtml", true, "supported languages");
+    }
+}
@@ -27,6 +27,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         assert_eq!(entry.symbol, "test_function");
@@ -58,6 +70,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         assert!(!entry.is_exported);
@@ -87,6 +111,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(), // Large parameter count
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         assert_eq!(entry.arity, 12);
@@ -138,6 +174,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
             };
 
             assert_eq!(entry.decorators, decorator);
@@ -167,6 +215,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         // Verify all decorators are preserved
@@ -228,6 +288,18 @@ mod schema_enrichment_tests {
                 return_type: String::new(),
                 ext_package: String::new(),
                 base_classes: String::new(),
+                feature_flags: String::new(),
+                is_deprecated: false,
+                deprecation_message: String::new(),
+                awaits_count: 0,
+                io_calls_outside_try: 0,
+                has_empty_catch: false,
+                rethrows: false,
+                panic_points: 0,
+                sql_tables: String::new(),
+                partial_of: String::new(),
+                parent: String::new(),
+                is_generated: false,
             };
 
             assert_eq!(entry.arity, case.expected_arity);
@@ -256,6 +328,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         let private = SymbolIndexEntry {
@@ -278,6 +362,18 @@ mod schema_enrichment_tests {
             return_type: String::new(),
             ext_package: String::new(),
             base_classes: String::new(),
+            feature_flags: String::new(),
+            is_deprecated: false,
+            deprecation_message: String::new(),
+            awaits_count: 0,
+            io_calls_outside_try: 0,
+            has_empty_catch: false,
+            rethrows: false,
+            panic_points: 0,
+            sql_tables: String::new(),
+            partial_of: String::new(),
+            parent: String::new(),
+            is_generated: false,
         };
 
         assert!(exported.is_exported);